@@ -0,0 +1,85 @@
+//! `audit` command: check the secret access log for tampering.
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use rustyclaw_core::config::Config;
+
+use super::shared::open_secrets;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum AuditCommands {
+    /// Check that the secret access log has not been edited
+    ///
+    /// Each line of the log carries the hash of the line before it and a
+    /// signature made with a key kept in the vault. Reports the first line
+    /// where the chain breaks and exits non-zero.
+    Verify {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Run an `audit` subcommand.
+pub(crate) fn run(sub: AuditCommands, config: &Config) -> Result<()> {
+    use rustyclaw_core::theme as t;
+
+    let mut secrets = open_secrets(config)?;
+
+    match sub {
+        AuditCommands::Verify { json } => {
+            let report = secrets.verify_access_log()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else if let Some(broken) = &report.broken {
+                let when = broken
+                    .timestamp
+                    .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0))
+                    .map(|t| t.with_timezone(&chrono::Local))
+                    .map(|t| format!(", {}", t.format("%Y-%m-%d %H:%M:%S")))
+                    .unwrap_or_default();
+                println!(
+                    "{}",
+                    t::icon_fail(&format!(
+                        "Secret access log broken at entry {} ({} line {}{})",
+                        broken.entry,
+                        broken.file.display(),
+                        broken.line,
+                        when
+                    ))
+                );
+                println!("  {}", broken.problem);
+                println!(
+                    "  {}",
+                    t::muted(&format!("{} earlier entries check out.", broken.entry - 1))
+                );
+            } else if report.entries == 0 {
+                println!("{}", t::icon_muted("The secret access log is empty."));
+            } else {
+                println!(
+                    "{}",
+                    t::icon_ok(&format!(
+                        "Secret access log intact: {} entries",
+                        report.entries
+                    ))
+                );
+                if report.unsigned > 0 {
+                    println!(
+                        "{}",
+                        t::icon_warn(&format!(
+                            "{} entries are unsigned (written before signing began); they \
+                             are chained but not authenticated",
+                            report.unsigned
+                        ))
+                    );
+                }
+            }
+            if !report.is_intact() {
+                anyhow::bail!("secret access log failed verification");
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -109,7 +109,7 @@ pub(crate) fn run(args: ClawHubCommands, config: &mut Config) -> Result<()> {
 
     let mut sm = SkillManager::with_dirs(skills_dirs);
     sm.load_skills()?;
-    if let Some(url) = config.clawhub_url.as_deref() {
+    if let Some(url) = config.skill_registry() {
         sm.set_registry(url, config.clawhub_token.clone());
     } else if let Some(ref token) = config.clawhub_token {
         let url = sm.registry_url().to_string();
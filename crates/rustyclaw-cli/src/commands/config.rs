@@ -1,8 +1,10 @@
-//! `rustyclaw config get|set|unset` — read and mutate config values by path.
+//! `rustyclaw config get|set|unset|diff` — read and mutate config values by path.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
-use rustyclaw_core::config::Config;
+use rustyclaw_core::config::{CURRENT_SCHEMA_VERSION, Config, ConfigLayer, migrations};
+use rustyclaw_core::theme as t;
+use std::path::Path;
 
 /// `rustyclaw config` subcommands.
 #[derive(Debug, Subcommand)]
@@ -12,6 +14,9 @@ pub enum ConfigCommands {
         /// Dot-separated config path
         #[arg(value_name = "PATH")]
         path: String,
+        /// Also print which config layer (system, user or project) set it
+        #[arg(long)]
+        show_origin: bool,
     },
     /// Set a config value
     Set {
@@ -21,13 +26,22 @@ pub enum ConfigCommands {
         /// Value to set
         #[arg(value_name = "VALUE")]
         value: String,
+        /// Validate and show the change without saving
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Remove a config value
     Unset {
         /// Dot-separated config path
         #[arg(value_name = "PATH")]
         path: String,
+        /// Validate and show the change without saving
+        #[arg(long)]
+        dry_run: bool,
     },
+    /// Show how the effective config (including command-line overrides)
+    /// differs from the saved file
+    Diff,
 }
 
 pub(crate) fn config_get(config: &Config, path: &str) -> String {
@@ -56,6 +70,42 @@ pub(crate) fn config_get(config: &Config, path: &str) -> String {
     }
 }
 
+/// The file key behind a `config get|set|unset` path, resolving the short
+/// aliases (`provider` → `model.provider`).
+fn config_key(path: &str) -> &str {
+    match path {
+        "workspace" => "workspace_dir",
+        "soul" => "soul_path",
+        "skills" => "skills_dir",
+        "gateway" => "gateway_url",
+        "provider" => "model.provider",
+        "model" => "model.model",
+        other => other,
+    }
+}
+
+/// Which layer set `path`, for `config get --show-origin`: `user
+/// (~/.rustyclaw/config.toml)`, or `default` when no file sets it.
+pub(crate) fn config_origin(config: &Config, path: &str) -> String {
+    match config.origin(config_key(path)) {
+        Some(source) => format!("{} ({})", source.layer, source.path.display()),
+        None => "default".to_string(),
+    }
+}
+
+/// A note for `set`/`unset` when a project config overrides `path`, since
+/// those only write the user file.
+pub(crate) fn overridden_note(config: &Config, path: &str) -> Option<String> {
+    let source = config.origin(config_key(path))?;
+    (source.layer == ConfigLayer::Project).then(|| {
+        format!(
+            "{} is set in {}, which overrides the user config",
+            path,
+            source.path.display()
+        )
+    })
+}
+
 pub(crate) fn config_set(config: &mut Config, path: &str, value: &str) -> Result<()> {
     match path {
         "workspace_dir" | "workspace" => {
@@ -92,7 +142,7 @@ pub(crate) fn config_set(config: &mut Config, path: &str, value: &str) -> Result
         }
         _ => anyhow::bail!("Unknown config path: {}", path),
     }
-    Ok(())
+    validate(config)
 }
 
 pub(crate) fn config_unset(config: &mut Config, path: &str) -> Result<()> {
@@ -104,5 +154,187 @@ pub(crate) fn config_unset(config: &mut Config, path: &str) -> Result<()> {
         "model" | "model.provider" | "model.model" => config.model = None,
         _ => anyhow::bail!("Unknown config path: {}", path),
     }
+    validate(config)
+}
+
+/// Checks shared by `set` and `unset`, so `--dry-run` catches exactly what
+/// a real write would reject.
+fn validate(config: &Config) -> Result<()> {
+    if let Some(url) = config.gateway_url.as_deref() {
+        url::Url::parse(url).with_context(|| format!("Invalid gateway_url '{}'", url))?;
+    }
+    // The saved file must load again.
+    let rendered = toml::to_string_pretty(config).context("Config cannot be serialized")?;
+    toml::from_str::<Config>(&rendered).context("Config would not load after saving")?;
+    Ok(())
+}
+
+/// Print the effect of a `set`/`unset` on `path` without saving it.
+pub(crate) fn print_dry_run(before: &Config, after: &Config, path: &str) -> Result<()> {
+    println!(
+        "{} {} → {}",
+        t::accent_bright(path),
+        t::muted(&config_get(before, path)),
+        t::info(&config_get(after, path))
+    );
+    let old = toml::to_string_pretty(before)?;
+    let new = toml::to_string_pretty(after)?;
+    for line in line_diff(&old, &new) {
+        println!("  {}", line);
+    }
+    println!("{}", t::muted("(dry run — nothing saved)"));
+    Ok(())
+}
+
+/// Print the difference between the effective config and the saved files.
+///
+/// Command-line overrides such as `--gateway` or `--soul` are not saved on
+/// their own, but the next `config set` writes the whole effective config,
+/// so they show up here as pending changes.  The saved side is loaded the
+/// same way as at startup, system and project layers included.
+pub(crate) fn print_diff(config: &Config) -> Result<()> {
+    let saved_path = config.settings_dir.join("config.toml");
+    let saved = if saved_path.exists() || !config.layers.is_empty() {
+        let saved = Config::load(Some(saved_path.clone()))
+            .with_context(|| format!("Failed to load {}", saved_path.display()))?;
+        toml::to_string_pretty(&saved)?
+    } else {
+        String::new()
+    };
+
+    let lines = line_diff(&saved, &toml::to_string_pretty(config)?);
+    if lines.is_empty() {
+        println!("{}", t::icon_ok("No unsaved changes"));
+    } else {
+        println!("{}", t::muted(&format!("--- {}", saved_path.display())));
+        println!("{}", t::muted("+++ effective config"));
+        for line in lines {
+            println!("{}", line);
+        }
+    }
     Ok(())
 }
+
+/// `doctor`'s config schema check: report the version of the file at
+/// `path` and, when `apply` is set, run its pending migrations.  Returns
+/// `false` if the file is left on an older schema.
+pub(crate) fn check_config_schema(path: &Path, apply: bool) -> Result<bool> {
+    // A missing file is reported by the "Config file" check.
+    if !path.exists() {
+        return Ok(true);
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let table: toml::Table =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    let version = migrations::schema_version(&table);
+    let label = format!("Config schema v{}", version);
+
+    if version > CURRENT_SCHEMA_VERSION {
+        println!(
+            "  {}",
+            t::icon_warn(&format!(
+                "{} (newer than this build, v{})",
+                label, CURRENT_SCHEMA_VERSION
+            ))
+        );
+        return Ok(true);
+    }
+    let pending = migrations::pending_for(version);
+    if pending.is_empty() {
+        println!("  {}", t::icon_ok(&label));
+        return Ok(true);
+    }
+    if !apply {
+        println!(
+            "  {}",
+            t::icon_fail(&format!(
+                "{} (current is v{})",
+                label, CURRENT_SCHEMA_VERSION
+            ))
+        );
+        for step in &pending {
+            println!(
+                "      {}",
+                t::muted(&format!("pending {}", step.description))
+            );
+        }
+        println!(
+            "      {}",
+            t::muted("run `rustyclaw doctor --repair` to apply")
+        );
+        return Ok(false);
+    }
+
+    if let Some((backup, steps)) = migrations::migrate_file(path)? {
+        for step in &steps {
+            println!("  {}", t::icon_ok(&format!("Applied {}", step.description)));
+        }
+        println!(
+            "      {}",
+            t::muted(&format!("previous config saved as {}", backup.display()))
+        );
+    }
+    Ok(true)
+}
+
+/// Changed lines between two renderings, as `- old` / `+ new`, in order.
+fn line_diff(old: &str, new: &str) -> Vec<String> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // Longest-common-subsequence table; configs are a few hundred lines.
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push(t::success(&format!("+ {}", b[j])));
+            j += 1;
+        } else {
+            out.push(t::error(&format!("- {}", a[i])));
+            i += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff_marks_added_and_removed_lines() {
+        let old = "a = 1\nb = 2\nc = 3\n";
+        let new = "a = 1\nc = 3\nd = 4\n";
+        assert_eq!(
+            line_diff(old, new),
+            vec![t::error("- b = 2"), t::success("+ d = 4")]
+        );
+        // Unchanged lines are left out.
+        assert!(line_diff(old, old).is_empty());
+        assert_eq!(line_diff("", "a = 1\n"), vec![t::success("+ a = 1")]);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_gateway_url() {
+        let mut config = Config::default();
+        assert!(validate(&config).is_ok());
+        config.gateway_url = Some("not a url".to_string());
+        let err = validate(&config).unwrap_err();
+        assert!(err.to_string().contains("Invalid gateway_url"), "{err}");
+    }
+}
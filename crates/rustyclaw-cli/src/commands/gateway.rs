@@ -204,13 +204,22 @@ pub fn handle_reload_result(result: Result<(String, String), String>) {
 /// terminal so it can prompt for the vault password and stream logs). The
 /// gateway server itself lives entirely in the `rustyclaw-gateway` crate;
 /// the CLI only locates and launches its binary.
-pub fn handle_run(config: &Config, bind: &str, port: u16, log_level: Option<&str>) -> Result<()> {
-    let args = vec![
+pub fn handle_run(
+    config: &Config,
+    bind: &str,
+    port: u16,
+    log_level: Option<&str>,
+    trace: bool,
+) -> Result<()> {
+    let mut args = vec![
         "--bind".to_string(),
         bind.to_string(),
         "--port".to_string(),
         port.to_string(),
     ];
+    if trace {
+        args.push("--trace".to_string());
+    }
 
     let status = daemon::run_foreground(
         &config.settings_dir,
@@ -9,8 +9,9 @@ use url::Url;
 use rustyclaw_core::commands::{CommandAction, CommandContext, handle_command};
 use rustyclaw_core::config::Config;
 use rustyclaw_core::gateway::{
-    ClientFrame, ClientFrameType, ClientPayload, ServerFrame, ServerFrameType, ServerPayload,
-    deserialize_frame, serialize_frame,
+    Capabilities, ClientFrame, ClientFrameType, ClientPayload, GatewayClient, GatewayEvent,
+    ServerFrame, ServerFrameType, ServerPayload, SshConnection, StatusType, WireFrame,
+    deserialize_frame, serialize_frame, serialize_wire_frame, unix_socket_path,
 };
 use rustyclaw_core::skills::SkillManager;
 
@@ -31,9 +32,9 @@ pub(crate) struct AskArgs {
     /// Read prompt from stdin
     #[arg(long)]
     stdin: bool,
-    /// Model to use (overrides default)
-    #[arg(long, short, value_name = "MODEL")]
-    model: Option<String>,
+    /// Model to use for this prompt, as provider/model (overrides default)
+    #[arg(long, short, value_name = "PROVIDER/MODEL", value_parser = parse_model_arg)]
+    model: Option<(String, String)>,
     /// Disable tool use (pure chat mode)
     #[arg(long)]
     no_tools: bool,
@@ -43,12 +44,12 @@ pub(crate) struct AskArgs {
     /// System prompt override
     #[arg(long, value_name = "PROMPT")]
     system: Option<String>,
-    /// Gateway WebSocket URL (ws://…)
+    /// Gateway URL (ws://… or unix:/path/to/gateway.sock)
     #[arg(
         long = "gateway",
         alias = "url",
         alias = "ws",
-        value_name = "WS_URL",
+        value_name = "URL",
         env = "RUSTYCLAW_GATEWAY"
     )]
     gateway: Option<String>,
@@ -60,11 +61,31 @@ pub(crate) struct AskArgs {
     temperature: Option<f32>,
 }
 
+/// Parse a `--model provider/model` argument.
+pub(crate) fn parse_model_arg(spec: &str) -> Result<(String, String), String> {
+    rustyclaw_core::providers::parse_model_spec(spec)
+}
+
+/// The frame asking the gateway to use `provider`/`model` for this connection.
+fn session_model_frame(provider: &str, model: &str) -> Result<Vec<u8>> {
+    let frame = ClientFrame {
+        frame_type: ClientFrameType::SessionModel,
+        payload: ClientPayload::SessionModel {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        },
+    };
+    serialize_frame(&frame).map_err(|e| anyhow::anyhow!("serialize failed: {}", e))
+}
+
 pub(crate) fn run_local_command(config: &mut Config, input: &str) -> Result<()> {
     let mut secrets_manager = open_secrets(config)?;
     let skills_dir = config.skills_dir();
     let mut skill_manager = SkillManager::new(skills_dir);
     skill_manager.load_skills()?;
+    if let Some(url) = config.skill_registry() {
+        skill_manager.set_registry(url, config.clawhub_token.clone());
+    }
 
     let mut context = CommandContext {
         secrets_manager: &mut secrets_manager,
@@ -91,6 +112,27 @@ pub(crate) fn run_local_command(config: &mut Config, input: &str) -> Result<()>
     Ok(())
 }
 
+/// Environment variable holding a gateway bearer token for unattended clients
+/// (see `rustyclaw gateway token issue`).
+pub(crate) const GATEWAY_TOKEN_ENV: &str = "RUSTYCLAW_GATEWAY_TOKEN";
+
+/// Bearer token from the environment, if one is set.
+fn gateway_token_from_env() -> Option<String> {
+    std::env::var(GATEWAY_TOKEN_ENV)
+        .ok()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// Answer an auth challenge: use the bearer token from the environment when
+/// available, otherwise prompt the user for their 2FA code.
+fn auth_challenge_answer() -> String {
+    gateway_token_from_env().unwrap_or_else(|| {
+        rpassword::prompt_password(format!("{} 2FA code: ", rustyclaw_core::theme::info("🔑")))
+            .unwrap_or_default()
+    })
+}
+
 /// Send a reload command to the running gateway and wait for the result.
 pub(crate) async fn send_gateway_reload(
     gateway_url: &str,
@@ -118,11 +160,7 @@ pub(crate) async fn send_gateway_reload(
                         match frame.frame_type {
                             ServerFrameType::AuthChallenge => {
                                 if let ServerPayload::AuthChallenge { method: _ } = frame.payload {
-                                    let code = rpassword::prompt_password(format!(
-                                        "{} 2FA code: ",
-                                        rustyclaw_core::theme::info("🔑")
-                                    ))
-                                    .unwrap_or_default();
+                                    let code = auth_challenge_answer();
                                     let auth_frame = ClientFrame {
                                         frame_type: ClientFrameType::AuthResponse,
                                         payload: ClientPayload::AuthResponse {
@@ -160,11 +198,7 @@ pub(crate) async fn send_gateway_reload(
                     if let Ok(val) = serde_json::from_str::<serde_json::Value>(text.as_ref()) {
                         let frame_type = val.get("type").and_then(|t| t.as_str());
                         if frame_type == Some("auth_challenge") {
-                            let code = rpassword::prompt_password(format!(
-                                "{} 2FA code: ",
-                                rustyclaw_core::theme::info("🔑")
-                            ))
-                            .unwrap_or_default();
+                            let code = auth_challenge_answer();
                             let auth_frame = ClientFrame {
                                 frame_type: ClientFrameType::AuthResponse,
                                 payload: ClientPayload::AuthResponse {
@@ -218,11 +252,7 @@ pub(crate) async fn send_gateway_reload(
                         ServerFrameType::AuthChallenge if totp_enabled => {
                             // Prompt the user for their TOTP 2FA code and reply
                             // with an AuthResponse frame.
-                            let code = rpassword::prompt_password(format!(
-                                "{} 2FA code: ",
-                                rustyclaw_core::theme::info("🔑")
-                            ))
-                            .unwrap_or_default();
+                            let code = auth_challenge_answer();
                             let auth_frame = ClientFrame {
                                 frame_type: ClientFrameType::AuthResponse,
                                 payload: ClientPayload::AuthResponse {
@@ -243,11 +273,7 @@ pub(crate) async fn send_gateway_reload(
                     let frame_type = val.get("type").and_then(|t| t.as_str());
                     if frame_type == Some("hello") || frame_type == Some("auth_challenge") {
                         if frame_type == Some("auth_challenge") && !totp_enabled {
-                            let code = rpassword::prompt_password(format!(
-                                "{} 2FA code: ",
-                                rustyclaw_core::theme::info("🔑")
-                            ))
-                            .unwrap_or_default();
+                            let code = auth_challenge_answer();
                             let auth_frame = ClientFrame {
                                 frame_type: ClientFrameType::AuthResponse,
                                 payload: ClientPayload::AuthResponse {
@@ -310,13 +336,15 @@ pub(crate) async fn send_gateway_reload(
         }
     }
 
-    // Send reload command using binary frame
-    let reload_frame = ClientFrame {
+    // Send reload command using binary frame.  Config-changing frames need a
+    // sequence number; this is the connection's first.
+    let mut reload_frame = WireFrame::control(ClientFrame {
         frame_type: ClientFrameType::Reload,
         payload: ClientPayload::Reload,
-    };
-    let bytes =
-        serialize_frame(&reload_frame).map_err(|e| anyhow::anyhow!("serialize failed: {}", e))?;
+    });
+    reload_frame.sequence = 1;
+    let bytes = serialize_wire_frame(&reload_frame)
+        .map_err(|e| anyhow::anyhow!("serialize failed: {}", e))?;
     writer
         .send(Message::Binary(bytes.into()))
         .await
@@ -381,13 +409,84 @@ pub(crate) async fn send_gateway_reload(
     }
 }
 
-pub(crate) async fn send_command_via_gateway(gateway_url: &str, command: &str) -> Result<String> {
+pub(crate) async fn send_command_via_gateway(
+    gateway_url: &str,
+    command: &str,
+    model: Option<(String, String)>,
+) -> Result<String> {
     let url = Url::parse(gateway_url).context("Invalid gateway URL")?;
 
     let (ws_stream, _) = tokio_tungstenite::connect_async(url.to_string())
         .await
         .context("Failed to connect to gateway")?;
     let (mut writer, mut reader) = ws_stream.split();
+
+    // With a bearer token available, complete the auth handshake before
+    // sending the command so no human needs to be present.  A session model
+    // also needs the handshake done, since it is sent as a frame first.
+    let token = gateway_token_from_env();
+    if token.is_some() || model.is_some() {
+        while let Some(message) = reader.next().await {
+            let Message::Binary(data) = message.context("Gateway read error")? else {
+                continue;
+            };
+            let Ok(frame) = deserialize_frame::<ServerFrame>(&data) else {
+                continue;
+            };
+            match frame.payload {
+                ServerPayload::AuthChallenge { .. } => {
+                    let auth_frame = ClientFrame {
+                        frame_type: ClientFrameType::AuthResponse,
+                        payload: ClientPayload::AuthResponse {
+                            code: token.clone().unwrap_or_else(auth_challenge_answer),
+                        },
+                    };
+                    let bytes = serialize_frame(&auth_frame)
+                        .map_err(|e| anyhow::anyhow!("serialize failed: {}", e))?;
+                    writer.send(Message::Binary(bytes.into())).await?;
+                }
+                ServerPayload::AuthResult {
+                    ok: false, message, ..
+                } => {
+                    anyhow::bail!("{}", message.as_deref().unwrap_or("Auth failed"));
+                }
+                ServerPayload::AuthLocked { message, .. } => anyhow::bail!("{}", message),
+                ServerPayload::AuthResult { ok: true, .. } | ServerPayload::Hello { .. } => break,
+                _ => {}
+            }
+        }
+    }
+
+    // Switch this connection's model and wait until the gateway has found
+    // credentials for it, so a bad override fails before the command runs.
+    if let Some((provider, model)) = model {
+        let bytes = session_model_frame(&provider, &model)?;
+        writer.send(Message::Binary(bytes.into())).await?;
+        loop {
+            let message = reader
+                .next()
+                .await
+                .context("Gateway closed before accepting the model")?
+                .context("Gateway read error")?;
+            let Message::Binary(data) = message else {
+                continue;
+            };
+            let Ok(frame) = deserialize_frame::<ServerFrame>(&data) else {
+                continue;
+            };
+            match frame.payload {
+                ServerPayload::Status {
+                    status: StatusType::ModelConfigured,
+                    ..
+                } => break,
+                ServerPayload::Error { message, .. } => {
+                    anyhow::bail!("Cannot use {}/{}: {}", provider, model, message)
+                }
+                _ => {}
+            }
+        }
+    }
+
     writer
         .send(Message::Text(command.to_string().into()))
         .await
@@ -437,6 +536,10 @@ pub(crate) async fn handle_ask(config: &Config, args: AskArgs) -> Result<()> {
         .or_else(|| config.gateway_url.clone())
         .unwrap_or_else(|| "ws://127.0.0.1:9001".to_string());
 
+    if unix_socket_path(&gateway_url).is_some() {
+        return ask_over_socket(&gateway_url, &prompt, &args).await;
+    }
+
     // Connect to gateway
     let url = Url::parse(&gateway_url).context("Invalid gateway URL")?;
     let (ws_stream, _) = tokio_tungstenite::connect_async(url.to_string())
@@ -447,6 +550,13 @@ pub(crate) async fn handle_ask(config: &Config, args: AskArgs) -> Result<()> {
     // Handle auth if needed (simplified — skip TOTP for now)
     // TODO: Add TOTP support for headless mode
 
+    // Ask for the override first; the gateway handles frames in order, so
+    // the chat below already runs on it.
+    if let Some((provider, model)) = &args.model {
+        let bytes = session_model_frame(provider, model)?;
+        writer.send(Message::Binary(bytes.into())).await?;
+    }
+
     // Build the chat message
     let message = ChatMessage::text("user", &prompt);
 
@@ -455,6 +565,8 @@ pub(crate) async fn handle_ask(config: &Config, args: AskArgs) -> Result<()> {
         frame_type: ClientFrameType::Chat,
         payload: ClientPayload::Chat {
             messages: vec![message],
+            sampling: Default::default(),
+            system_prefix: None,
         },
     };
     let bytes = serialize_frame(&frame).map_err(|e| anyhow::anyhow!("serialize failed: {}", e))?;
@@ -507,8 +619,8 @@ pub(crate) async fn handle_ask(config: &Config, args: AskArgs) -> Result<()> {
                             }
                         }
                         ServerFrameType::Error => {
-                            if let ServerPayload::Error { message, .. } = frame.payload {
-                                anyhow::bail!("Gateway error: {}", message);
+                            if let ServerPayload::Error { message, code, .. } = frame.payload {
+                                anyhow::bail!("Gateway error ({}): {}", code, message);
                             }
                         }
                         ServerFrameType::Info => {
@@ -545,3 +657,86 @@ pub(crate) async fn handle_ask(config: &Config, args: AskArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// `ask` against a gateway's Unix socket (`unix:/path`), which speaks the
+/// framed protocol the TUI uses rather than WebSocket.
+async fn ask_over_socket(gateway_url: &str, prompt: &str, args: &AskArgs) -> Result<()> {
+    use std::io::{self, Write};
+
+    let (connection, writer, reader) = SshConnection::connect(gateway_url)
+        .await
+        .context("Failed to connect to gateway. Is it running? Try `rustyclaw gateway start`")?;
+    let client = GatewayClient::from_transport(
+        connection,
+        writer,
+        reader,
+        Some(gateway_url),
+        Capabilities::legacy(),
+        args.model.clone(),
+    );
+
+    let mut response_text = String::new();
+    let mut tool_outputs: Vec<String> = Vec::new();
+
+    while let Some(event) = client.recv().await {
+        match event {
+            GatewayEvent::AuthRequired => client.authenticate(auth_challenge_answer()).await?,
+            GatewayEvent::AuthFailed { message, retry } => {
+                if !retry {
+                    anyhow::bail!("{}", message);
+                }
+                eprintln!("  {}", message);
+                client.authenticate(auth_challenge_answer()).await?;
+            }
+            // The gateway says hello once any auth is done; the client has
+            // already queued its capabilities and model by then.
+            GatewayEvent::Connected { .. } => client.chat(prompt.to_string()).await?,
+            GatewayEvent::Chunk { delta } => {
+                if !args.json {
+                    print!("{}", delta);
+                    io::stdout().flush()?;
+                }
+                response_text.push_str(&delta);
+            }
+            GatewayEvent::ToolCall { name, .. } => {
+                if !args.json {
+                    eprintln!("  → {}", name);
+                }
+            }
+            GatewayEvent::ToolResult { name, result, .. } => {
+                tool_outputs.push(format!("{}: {}", name, result));
+            }
+            GatewayEvent::Info { message } => {
+                if !args.json {
+                    eprintln!("  ℹ {}", message);
+                }
+            }
+            GatewayEvent::Error { message, code } => {
+                anyhow::bail!("Gateway error ({}): {}", code, message);
+            }
+            GatewayEvent::ResponseDone => {
+                if !args.json {
+                    println!();
+                }
+                break;
+            }
+            GatewayEvent::Disconnected { reason } => {
+                anyhow::bail!(
+                    "Gateway closed: {}",
+                    reason.as_deref().unwrap_or("connection closed")
+                );
+            }
+            _ => {}
+        }
+    }
+
+    if args.json {
+        let output = serde_json::json!({
+            "response": response_text,
+            "tool_calls": tool_outputs,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! `gateway token` command: issue and revoke bearer tokens for headless clients.
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use rustyclaw_core::config::Config;
+use rustyclaw_core::secrets::GatewayRole;
+
+use super::shared::open_secrets;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum GatewayTokenCommands {
+    /// Issue a new token (printed once — store it securely)
+    Issue {
+        /// Label identifying the client (e.g. "ci-nightly")
+        #[arg(value_name = "LABEL")]
+        label: String,
+        /// Expire the token after this many days (default: never)
+        #[arg(long, value_name = "DAYS")]
+        expires_days: Option<u64>,
+        /// Read-only token: watch sessions without sending messages or commands
+        #[arg(long)]
+        observer: bool,
+        /// Only let the agent on this token's connections use this tool
+        /// (repeatable; default: no restriction)
+        #[arg(long = "tool", value_name = "TOOL")]
+        tools: Vec<String>,
+    },
+    /// List issued tokens
+    List,
+    /// Revoke a token by id
+    Revoke {
+        /// Token id (as shown by `gateway token list`)
+        #[arg(value_name = "ID")]
+        id: String,
+    },
+}
+
+/// Run a `gateway token` subcommand.
+pub(crate) fn run(sub: GatewayTokenCommands, config: &Config) -> Result<()> {
+    use rustyclaw_core::theme as t;
+
+    let mut secrets = open_secrets(config)?;
+
+    match sub {
+        GatewayTokenCommands::Issue {
+            label,
+            expires_days,
+            observer,
+            tools,
+        } => {
+            let ttl = expires_days.map(|d| d.saturating_mul(86_400));
+            let role = if observer {
+                GatewayRole::Observer
+            } else {
+                GatewayRole::Operator
+            };
+            let scope = (!tools.is_empty()).then_some(tools);
+            let (record, token) = secrets.issue_gateway_token(&label, ttl, role, scope)?;
+            println!(
+                "{}",
+                t::icon_ok(&format!(
+                    "Issued {}gateway token {} ({})",
+                    if observer { "read-only " } else { "" },
+                    t::accent_bright(&record.id),
+                    label
+                ))
+            );
+            if let Some(tools) = &record.tools {
+                println!("{}", t::muted(&format!("Tools: {}", tools.join(", "))));
+            }
+            println!();
+            println!("  {}", token);
+            println!();
+            println!(
+                "{}",
+                t::muted("This token will not be shown again. It bypasses TOTP — keep it secret.")
+            );
+            if !config.totp_enabled {
+                println!(
+                    "{}",
+                    t::icon_warn("TOTP is disabled; the gateway does not challenge clients yet.")
+                );
+            }
+        }
+        GatewayTokenCommands::List => {
+            let tokens = secrets.list_gateway_tokens()?;
+            if tokens.is_empty() {
+                println!(
+                    "{}",
+                    t::muted(
+                        "No gateway tokens. Use `rustyclaw gateway token issue` to create one."
+                    )
+                );
+                return Ok(());
+            }
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            for token in tokens {
+                let expiry = match token.expires_at {
+                    _ if token.is_expired_at(now) => t::icon_fail("expired"),
+                    Some(exp) => t::info(&format!("expires in {}", format_remaining(exp - now))),
+                    None => t::muted("no expiry"),
+                };
+                let role = if token.role.is_read_only() {
+                    t::muted(" (observer)")
+                } else {
+                    String::new()
+                };
+                let scope = match &token.tools {
+                    Some(tools) => t::muted(&format!(" [{}]", tools.join(", "))),
+                    None => String::new(),
+                };
+                println!(
+                    "  {}  {}{}{} — {}",
+                    t::accent_bright(&token.id),
+                    token.label,
+                    role,
+                    scope,
+                    expiry
+                );
+            }
+        }
+        GatewayTokenCommands::Revoke { id } => {
+            if secrets.revoke_gateway_token(&id)? {
+                println!("{}", t::icon_ok(&format!("Revoked gateway token {}", id)));
+            } else {
+                anyhow::bail!("No gateway token with id '{}'", id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn format_remaining(secs: u64) -> String {
+    match secs {
+        s if s >= 86_400 => format!("{}d", s / 86_400),
+        s if s >= 3_600 => format!("{}h", s / 3_600),
+        s => format!("{}m", s.div_ceil(60)),
+    }
+}
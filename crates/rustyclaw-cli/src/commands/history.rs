@@ -0,0 +1,65 @@
+//! `doctor`'s check of the HISTORY.md search index.
+
+use anyhow::{Result, anyhow};
+use rustyclaw_core::config::Config;
+use rustyclaw_core::history_index::{HistoryIndex, IndexStatus};
+use rustyclaw_core::theme as t;
+
+/// Report on the history index and, when `repair` is set, build it if it
+/// is missing or out of date.  `rebuild` always builds it from scratch.
+///
+/// A missing index is only a warning: `search_history` scans HISTORY.md
+/// without one.
+pub(crate) fn check_history_index(config: &Config, repair: bool, rebuild: bool) -> Result<()> {
+    let workspace = config.workspace_dir();
+    let history_path = workspace.join(&config.memory_consolidation.history_path);
+    // Nothing to index until the first `save_memory`.
+    if !history_path.exists() {
+        return Ok(());
+    }
+
+    let status = HistoryIndex::status(&workspace, &history_path);
+    let needs_build = matches!(status, IndexStatus::Missing | IndexStatus::Outdated);
+    if rebuild || (repair && needs_build) {
+        let index = HistoryIndex::rebuild(&workspace, &history_path).map_err(|e| anyhow!(e))?;
+        println!(
+            "  {}",
+            t::icon_ok(&format!("Rebuilt history index ({} entries)", index.len()))
+        );
+        return Ok(());
+    }
+
+    match status {
+        IndexStatus::Current { entries } => {
+            println!(
+                "  {}",
+                t::icon_ok(&format!("History index ({} entries)", entries))
+            );
+        }
+        IndexStatus::Behind { .. } => {
+            println!(
+                "  {}",
+                t::icon_ok("History index (new entries are added on the next search)")
+            );
+        }
+        IndexStatus::Missing | IndexStatus::Outdated => {
+            let problem = if status == IndexStatus::Missing {
+                "missing"
+            } else {
+                "out of date"
+            };
+            println!(
+                "  {}",
+                t::icon_warn(&format!(
+                    "History index {} — history searches scan HISTORY.md",
+                    problem
+                ))
+            );
+            println!(
+                "      {}",
+                t::muted("run `rustyclaw doctor --repair` to build it")
+            );
+        }
+    }
+    Ok(())
+}
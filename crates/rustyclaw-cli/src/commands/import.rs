@@ -478,6 +478,21 @@ pub(crate) fn run_import(args: &ImportArgs, config: &mut Config) -> Result<()> {
                                 Ok(true) => {
                                     config.totp_enabled = true;
                                     println!("  {}", "✓ 2FA enabled.".green());
+                                    let codes = secrets.generate_backup_codes()?;
+                                    println!();
+                                    println!(
+                                        "  {}",
+                                        "Backup codes (each works once if you lose your authenticator):"
+                                            .bold()
+                                    );
+                                    for code in &codes {
+                                        println!("    {}", code);
+                                    }
+                                    println!(
+                                        "  {}",
+                                        "Store these somewhere safe — they will not be shown again."
+                                            .dimmed()
+                                    );
                                     break;
                                 }
                                 Ok(false) => {
@@ -2,18 +2,26 @@
 //!
 //! Each submodule handles a specific command group (gateway, skills, etc.)
 
+pub mod audit;
 pub mod clawhub;
 pub mod config;
 pub mod gateway;
 pub mod gateway_client;
+pub mod gateway_token;
+pub mod history;
 pub mod import;
 pub mod refresh_token;
+pub mod secrets;
 pub mod shared;
 pub mod status;
 pub mod swarm;
 
 // Re-export handlers for use in main.rs
-pub(crate) use config::{config_get, config_set, config_unset};
+pub(crate) use config::{
+    check_config_schema, config_get, config_origin, config_set, config_unset, overridden_note,
+    print_diff, print_dry_run,
+};
 pub use gateway::{handle_restart, handle_run, handle_start, handle_status, handle_stop};
+pub(crate) use history::check_history_index;
 pub(crate) use import::run_import;
 pub(crate) use refresh_token::run_refresh_token;
@@ -0,0 +1,210 @@
+//! `secrets` command: review the secret access log and manage TOTP backup codes.
+
+use anyhow::Result;
+use clap::{Subcommand, ValueEnum};
+
+use rustyclaw_core::config::Config;
+use rustyclaw_core::secrets::{AccessOutcome, AuditFilter, BACKUP_CODE_COUNT};
+
+use super::shared::open_secrets;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum SecretsCommands {
+    /// Show who read which secret, and whether the access policy allowed it
+    ///
+    /// Every read of a secret or credential is logged with its requester
+    /// (tool, provider, client), skill and session. Values are never logged.
+    Audit {
+        /// Only secrets whose name contains this text
+        #[arg(long, value_name = "NAME")]
+        secret: Option<String>,
+        /// Only requests whose requester, skill or session contains this text
+        #[arg(long, value_name = "TEXT")]
+        requester: Option<String>,
+        /// Only this outcome
+        #[arg(long, value_enum)]
+        outcome: Option<OutcomeArg>,
+        /// Only the last period, e.g. 30m, 24h or 7d
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        since: Option<u64>,
+        /// Show at most this many of the most recent records
+        #[arg(long, short = 'n', value_name = "N", default_value_t = 50)]
+        limit: usize,
+        /// Print the records as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show how many TOTP backup codes are left, or issue a new set
+    ///
+    /// Each backup code can be entered once in place of a 2FA code, e.g.
+    /// after losing the authenticator. Regenerating invalidates the old set.
+    BackupCodes {
+        /// Generate a new set (printed once — store it securely)
+        #[arg(long)]
+        regenerate: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum OutcomeArg {
+    Allowed,
+    Denied,
+    NotFound,
+}
+
+impl From<OutcomeArg> for AccessOutcome {
+    fn from(arg: OutcomeArg) -> Self {
+        match arg {
+            OutcomeArg::Allowed => AccessOutcome::Allowed,
+            OutcomeArg::Denied => AccessOutcome::Denied,
+            OutcomeArg::NotFound => AccessOutcome::NotFound,
+        }
+    }
+}
+
+/// Run a `secrets` subcommand.
+pub(crate) fn run(sub: SecretsCommands, config: &Config) -> Result<()> {
+    use rustyclaw_core::theme as t;
+
+    let mut secrets = open_secrets(config)?;
+
+    match sub {
+        SecretsCommands::Audit {
+            secret,
+            requester,
+            outcome,
+            since,
+            limit,
+            json,
+        } => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let filter = AuditFilter {
+                secret,
+                requester,
+                outcome: outcome.map(Into::into),
+                since: since.map(|age| now.saturating_sub(age)),
+                limit: Some(limit),
+            };
+            let records = secrets.access_log(&filter)?;
+            if json {
+                for record in &records {
+                    println!("{}", serde_json::to_string(record)?);
+                }
+                return Ok(());
+            }
+            if records.is_empty() {
+                println!("{}", t::muted("No matching secret accesses."));
+                return Ok(());
+            }
+            for record in records {
+                let when = chrono::DateTime::from_timestamp(record.timestamp as i64, 0)
+                    .map(|t| t.with_timezone(&chrono::Local))
+                    .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_else(|| record.timestamp.to_string());
+                let outcome = match record.outcome {
+                    AccessOutcome::Allowed => t::success("allowed  "),
+                    AccessOutcome::Denied => t::error("denied   "),
+                    AccessOutcome::NotFound => t::warn("not found"),
+                };
+                let who: Vec<String> = [
+                    record.requester,
+                    record.skill.map(|s| format!("skill:{}", s)),
+                    record.session,
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                let who = if who.is_empty() {
+                    "unspecified".to_string()
+                } else {
+                    who.join(" ")
+                };
+                let reason = record
+                    .reason
+                    .map(|r| format!(" — {}", r))
+                    .unwrap_or_default();
+                println!(
+                    "  {}  {}  {}  {}{}",
+                    t::muted(&when),
+                    outcome,
+                    t::accent_bright(&record.secret),
+                    who,
+                    t::muted(&reason)
+                );
+            }
+        }
+        SecretsCommands::BackupCodes { regenerate } => {
+            if !secrets.has_totp() {
+                anyhow::bail!("2FA is not enabled; backup codes only exist alongside TOTP");
+            }
+            if regenerate {
+                let codes = secrets.generate_backup_codes()?;
+                println!("{}", t::icon_ok("Generated new TOTP backup codes"));
+                println!();
+                for code in &codes {
+                    println!("  {}", code);
+                }
+                println!();
+                println!(
+                    "{}",
+                    t::muted(
+                        "Each code works once. The old set no longer works; these will not be shown again."
+                    )
+                );
+                return Ok(());
+            }
+            let remaining = secrets.backup_codes_remaining()?;
+            let generated = secrets
+                .backup_codes_created_at()?
+                .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0))
+                .map(|t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d")
+                        .to_string()
+                });
+            match generated {
+                Some(date) => println!(
+                    "  {} of {} backup codes unused {}",
+                    t::accent_bright(&remaining.to_string()),
+                    BACKUP_CODE_COUNT,
+                    t::muted(&format!("(generated {})", date))
+                ),
+                None => println!("  {}", t::muted("No backup codes generated.")),
+            }
+            if remaining <= 2 {
+                println!(
+                    "{}",
+                    t::icon_warn(
+                        "Run `rustyclaw secrets backup-codes --regenerate` for a new set."
+                    )
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse `30m`, `24h`, `7d` (or plain seconds) into seconds.
+fn parse_age(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let (digits, unit) = match text.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => text.split_at(i),
+        None => (text, "s"),
+    };
+    let n: u64 = digits
+        .parse()
+        .map_err(|_| format!("'{}' is not an age like 30m, 24h or 7d", text))?;
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return Err(format!("Unknown unit '{}': use s, m, h, d or w", unit)),
+    };
+    Ok(n.saturating_mul(scale))
+}
@@ -34,13 +34,14 @@ pub(crate) fn open_secrets(config: &Config) -> Result<SecretsManager> {
         SecretsManager::with_password(config.credentials_dir(), pw)
     } else {
         SecretsManager::new(config.credentials_dir())
-    };
+    }
+    .with_backend_kind(config.secrets_backend)?;
 
     // If TOTP 2FA is enabled, verify before returning.
     if config.totp_enabled {
         loop {
-            let code = prompt_password("Enter your 2FA code: ")?;
-            match manager.verify_totp(code.trim()) {
+            let code = prompt_password("Enter your 2FA code (or a backup code): ")?;
+            match manager.verify_totp_or_backup_code(code.trim()) {
                 Ok(true) => break,
                 Ok(false) => {
                     eprintln!("Invalid code. Please try again.");
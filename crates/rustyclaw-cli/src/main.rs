@@ -9,15 +9,21 @@ use rustyclaw_core::args::CommonArgs;
 use rustyclaw_core::config::Config;
 use rustyclaw_core::daemon;
 use rustyclaw_core::skills::SkillManager;
-use rustyclaw_onboard::{OnboardArgs as WizardArgs, run_onboard_wizard};
+use rustyclaw_onboard::{
+    HeadlessArgs, OnboardArgs as WizardArgs, run_headless_onboard, run_onboard_wizard,
+};
 
 mod commands;
 
+use commands::audit::AuditCommands;
 use commands::clawhub::ClawHubCommands;
 use commands::config::ConfigCommands;
 use commands::gateway_client::{
-    AskArgs, handle_ask, run_local_command, send_command_via_gateway, send_gateway_reload,
+    AskArgs, handle_ask, parse_model_arg, run_local_command, send_command_via_gateway,
+    send_gateway_reload,
 };
+use commands::gateway_token::GatewayTokenCommands;
+use commands::secrets::SecretsCommands;
 use commands::shared::{extract_vault_password, open_secrets};
 use commands::swarm::SwarmCommands;
 
@@ -87,6 +93,14 @@ enum Commands {
     #[command(subcommand)]
     Skills(SkillsCommands),
 
+    /// Secrets vault commands (access audit, TOTP backup codes)
+    #[command(subcommand)]
+    Secrets(SecretsCommands),
+
+    /// Check the secret access log's hash chain and signatures
+    #[command(subcommand)]
+    Audit(AuditCommands),
+
     /// Refresh the GitHub Copilot session token from OpenClaw
     #[command(alias = "refresh")]
     RefreshToken(commands::refresh_token::RefreshTokenArgs),
@@ -110,7 +124,7 @@ struct SetupArgs {
     /// Run the onboarding wizard
     #[arg(long)]
     wizard: bool,
-    /// Run wizard without prompts
+    /// Provision from flags and the existing config, without prompts
     #[arg(long)]
     non_interactive: bool,
     /// Wizard mode
@@ -145,6 +159,15 @@ enum GatewayAuthMode {
     Password,
 }
 
+impl GatewayAuthMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            GatewayAuthMode::Token => "token",
+            GatewayAuthMode::Password => "password",
+        }
+    }
+}
+
 #[derive(Debug, Clone, ValueEnum, Default)]
 enum GatewayBind {
     #[default]
@@ -155,6 +178,18 @@ enum GatewayBind {
     Custom,
 }
 
+impl GatewayBind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            GatewayBind::Loopback => "loopback",
+            GatewayBind::Lan => "lan",
+            GatewayBind::Tailnet => "tailnet",
+            GatewayBind::Auto => "auto",
+            GatewayBind::Custom => "custom",
+        }
+    }
+}
+
 #[derive(Debug, Args, Default)]
 #[command(after_help = "\
 SECURITY NOTE:
@@ -165,6 +200,13 @@ SECURITY NOTE:
     OPENROUTER_API_KEY=sk-xxx rustyclaw onboard
 
   Or use the interactive wizard which prompts securely.
+
+NON-INTERACTIVE:
+  --non-interactive provisions everything from flags (plus --config-json)
+  and fails with an error naming any missing input instead of prompting.
+  A password-protected vault is unlocked with RUSTYCLAW_VAULT_PASSWORD.
+
+    ANTHROPIC_API_KEY=sk-xxx rustyclaw onboard --non-interactive --json
 ")]
 struct OnboardArgs {
     /// Agent workspace directory
@@ -173,7 +215,7 @@ struct OnboardArgs {
     /// Reset config + credentials + sessions before wizard
     #[arg(long)]
     reset: bool,
-    /// Run without prompts
+    /// Provision from flags and --config-json without prompts
     #[arg(long)]
     non_interactive: bool,
     /// Wizard mode
@@ -188,6 +230,16 @@ struct OnboardArgs {
     /// Output JSON summary
     #[arg(long)]
     json: bool,
+    /// Default model as provider/model (non-interactive)
+    #[arg(long, value_name = "PROVIDER/MODEL")]
+    model: Option<String>,
+    /// Agent name (non-interactive)
+    #[arg(long, value_name = "NAME")]
+    agent_name: Option<String>,
+    /// JSON config overrides and secrets to apply, or `-` for stdin
+    /// (non-interactive)
+    #[arg(long, value_name = "FILE")]
+    config_json: Option<String>,
 
     // ── Provider API-key flags (mirrors openclaw) ────────────────
     // ⚠️ CLI flags are visible in `ps aux`. Prefer env vars for security:
@@ -256,6 +308,9 @@ struct DoctorArgs {
     /// Run without prompts (safe migrations only)
     #[arg(long)]
     non_interactive: bool,
+    /// Rebuild the HISTORY.md search index from scratch
+    #[arg(long)]
+    reindex_history: bool,
     /// Output JSON
     #[arg(long)]
     json: bool,
@@ -265,7 +320,7 @@ struct DoctorArgs {
 
 #[derive(Debug, Args, Default)]
 struct TuiArgs {
-    /// Gateway WebSocket URL (overrides config)
+    /// Gateway URL, ssh://host:port or unix:/path (overrides config)
     #[arg(long = "url", value_name = "URL")]
     url: Option<String>,
     /// Gateway token
@@ -284,6 +339,10 @@ struct TuiArgs {
     /// gateway URL when --url is not provided.
     #[arg(long = "no-dialog", alias = "auto-connect")]
     no_dialog: bool,
+    /// Provider/model for this session (e.g. anthropic/claude-haiku-4-5);
+    /// the config is left unchanged
+    #[arg(long, value_name = "PROVIDER/MODEL", value_parser = parse_model_arg)]
+    model: Option<(String, String)>,
 }
 
 #[derive(Debug, Args, Default)]
@@ -313,6 +372,10 @@ struct CommandArgs {
         env = "RUSTYCLAW_GATEWAY"
     )]
     gateway: Option<String>,
+    /// Provider/model for this command (e.g. anthropic/claude-haiku-4-5);
+    /// the config is left unchanged
+    #[arg(long, value_name = "PROVIDER/MODEL", value_parser = parse_model_arg)]
+    model: Option<(String, String)>,
 }
 
 // ── Ask (headless mode) ─────────────────────────────────────────────────────
@@ -345,6 +408,14 @@ enum GatewayCommands {
     Reload,
     /// Run the gateway in the foreground (like `rustyclaw-gateway`)
     Run(GatewayRunArgs),
+    /// Manage bearer tokens for headless clients (issue / list / revoke)
+    ///
+    /// Tokens answer the gateway's auth challenge in place of a TOTP code, so
+    /// anyone holding one skips 2FA. Issue one token per client, give each an
+    /// expiry, and revoke tokens you no longer need. Clients read the token
+    /// from the RUSTYCLAW_GATEWAY_TOKEN environment variable.
+    #[command(subcommand)]
+    Token(GatewayTokenCommands),
 }
 
 #[derive(Debug, Args, Default)]
@@ -373,6 +444,9 @@ struct GatewayRunArgs {
     /// Log level filter (e.g., "debug", "rustyclaw=debug,info", "rustyclaw_core::providers=debug")
     #[arg(long, value_name = "LEVEL")]
     log_level: Option<String>,
+    /// Write each request's spans as a Chrome trace under the logs dir
+    #[arg(long)]
+    trace: bool,
 }
 
 // ── Skills subcommands ──────────────────────────────────────────────────────
@@ -389,6 +463,12 @@ enum SkillsCommands {
     },
     /// Check skills for issues
     Check,
+    /// Package installed skills into a directory usable as `clawhub_mirror`
+    Bundle {
+        /// Mirror directory to create or add to
+        #[arg(value_name = "DIR")]
+        dir: std::path::PathBuf,
+    },
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -433,6 +513,32 @@ fn common_flags(c: &CommonArgs) -> Vec<String> {
     v
 }
 
+/// Read the `--config-json` blob from a file, or from stdin for `-`.
+fn read_config_json(path: &str) -> Result<serde_json::Value> {
+    use anyhow::Context;
+    let (source, text) = if path == "-" {
+        let text = std::io::read_to_string(std::io::stdin())
+            .context("Failed to read JSON config from stdin")?;
+        ("stdin", text)
+    } else {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        (path, text)
+    };
+    serde_json::from_str(&text).with_context(|| format!("JSON config on {} is invalid", source))
+}
+
+/// Run headless onboarding and print what it configured.
+fn onboard_headless(config: &mut Config, args: HeadlessArgs, json: bool) -> Result<()> {
+    let summary = run_headless_onboard(config, args)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        summary.print();
+    }
+    Ok(())
+}
+
 /// Spawn a sibling client binary in the foreground, forwarding `args`, and
 /// propagate its exit code on failure.
 fn launch_client(name: &str, args: &[String]) -> Result<()> {
@@ -459,6 +565,7 @@ async fn main() -> Result<()> {
     let config_path = cli.common.config_path();
     let mut config = Config::load(config_path)?;
     cli.common.apply_overrides(&mut config);
+    rustyclaw_core::log_rotation::init(&config.log_rotation);
 
     match cli.command.unwrap_or(Commands::Tui(TuiArgs::default())) {
         // ── Setup ───────────────────────────────────────────────
@@ -471,17 +578,29 @@ async fn main() -> Result<()> {
                 || args.remote_token.is_some();
 
             if has_wizard_flags {
-                let mut secrets = open_secrets(&config)?;
-                let tui_args = WizardArgs {
-                    openrouter_api_key: None,
-                    anthropic_api_key: None,
-                    openai_api_key: None,
-                    gemini_api_key: None,
-                    xai_api_key: None,
-                    reset: false,
-                    non_interactive: args.non_interactive,
-                };
-                run_onboard_wizard(&mut config, &mut secrets, Some(tui_args))?;
+                if args.non_interactive {
+                    let headless = HeadlessArgs {
+                        remote: matches!(args.mode, Some(OnboardMode::Remote)),
+                        workspace: args.workspace.map(Into::into),
+                        remote_url: args.remote_url,
+                        remote_token: args.remote_token,
+                        vault_password: std::env::var("RUSTYCLAW_VAULT_PASSWORD").ok(),
+                        ..HeadlessArgs::default()
+                    };
+                    onboard_headless(&mut config, headless, false)?;
+                } else {
+                    let mut secrets = open_secrets(&config)?;
+                    let tui_args = WizardArgs {
+                        openrouter_api_key: None,
+                        anthropic_api_key: None,
+                        openai_api_key: None,
+                        gemini_api_key: None,
+                        xai_api_key: None,
+                        reset: false,
+                        non_interactive: false,
+                    };
+                    run_onboard_wizard(&mut config, &mut secrets, Some(tui_args))?;
+                }
                 // Optional agent setup step
                 let ws_dir = config.workspace_dir();
                 match rustyclaw_core::tools::agent_setup::exec_agent_setup(
@@ -524,6 +643,43 @@ async fn main() -> Result<()> {
         }
 
         // ── Onboard ─────────────────────────────────────────────
+        Commands::Onboard(args) if args.non_interactive => {
+            let api_keys = [
+                ("anthropic", args.anthropic_api_key),
+                ("openai", args.openai_api_key),
+                ("openrouter", args.openrouter_api_key),
+                ("opencode", args.opencode_api_key),
+                ("google", args.gemini_api_key),
+                ("xai", args.xai_api_key),
+            ]
+            .into_iter()
+            .filter_map(|(id, key)| Some((id.to_string(), key?)))
+            .collect();
+            let config_json = args
+                .config_json
+                .as_deref()
+                .map(read_config_json)
+                .transpose()?;
+            let headless = HeadlessArgs {
+                reset: args.reset,
+                remote: matches!(args.mode, Some(OnboardMode::Remote)),
+                workspace: args.workspace.map(Into::into),
+                agent_name: args.agent_name,
+                model: args.model,
+                auth_choice: args.auth_choice,
+                api_keys,
+                gateway_port: args.gateway_port,
+                gateway_bind: args.gateway_bind.map(|b| b.as_str().to_string()),
+                gateway_auth: args.gateway_auth.map(|a| a.as_str().to_string()),
+                gateway_token: args.gateway_token,
+                gateway_password: args.gateway_password,
+                remote_url: args.remote_url,
+                remote_token: args.remote_token,
+                config_json,
+                vault_password: std::env::var("RUSTYCLAW_VAULT_PASSWORD").ok(),
+            };
+            onboard_headless(&mut config, headless, args.json)?;
+        }
         Commands::Onboard(args) => {
             let mut secrets = open_secrets(&config)?;
             let tui_args = WizardArgs {
@@ -533,7 +689,7 @@ async fn main() -> Result<()> {
                 gemini_api_key: args.gemini_api_key.clone(),
                 xai_api_key: args.xai_api_key.clone(),
                 reset: args.reset,
-                non_interactive: args.non_interactive,
+                non_interactive: false,
             };
             run_onboard_wizard(&mut config, &mut secrets, Some(tui_args))?;
         }
@@ -554,39 +710,65 @@ async fn main() -> Result<()> {
             run_onboard_wizard(&mut config, &mut secrets, None)?;
         }
 
-        // ── Config get / set / unset ────────────────────────────
+        // ── Config get / set / unset / diff ─────────────────────
         Commands::Config(sub) => match sub {
-            ConfigCommands::Get { path } => {
+            ConfigCommands::Get { path, show_origin } => {
                 let value = commands::config_get(&config, &path);
-                println!("{}", value);
+                if show_origin {
+                    let origin = commands::config_origin(&config, &path);
+                    println!("{}\t{}", value, rustyclaw_core::theme::muted(&origin));
+                } else {
+                    println!("{}", value);
+                }
             }
-            ConfigCommands::Set { path, value } => {
-                commands::config_set(&mut config, &path, &value)?;
-                config.save(None)?;
-                println!(
-                    "{}",
-                    rustyclaw_core::theme::icon_ok(&format!(
-                        "Set {} = {}",
-                        rustyclaw_core::theme::accent_bright(&path),
-                        rustyclaw_core::theme::info(&value)
-                    ))
-                );
+            ConfigCommands::Set {
+                path,
+                value,
+                dry_run,
+            } => {
+                let mut updated = config.clone();
+                commands::config_set(&mut updated, &path, &value)?;
+                if dry_run {
+                    commands::print_dry_run(&config, &updated, &path)?;
+                } else {
+                    updated.save(None)?;
+                    if let Some(note) = commands::overridden_note(&config, &path) {
+                        println!("{}", rustyclaw_core::theme::icon_warn(&note));
+                    }
+                    println!(
+                        "{}",
+                        rustyclaw_core::theme::icon_ok(&format!(
+                            "Set {} = {}",
+                            rustyclaw_core::theme::accent_bright(&path),
+                            rustyclaw_core::theme::info(&value)
+                        ))
+                    );
+                }
             }
-            ConfigCommands::Unset { path } => {
-                commands::config_unset(&mut config, &path)?;
-                config.save(None)?;
-                println!(
-                    "{}",
-                    rustyclaw_core::theme::icon_ok(&format!(
-                        "Unset {}",
-                        rustyclaw_core::theme::accent_bright(&path)
-                    ))
-                );
+            ConfigCommands::Unset { path, dry_run } => {
+                let mut updated = config.clone();
+                commands::config_unset(&mut updated, &path)?;
+                if dry_run {
+                    commands::print_dry_run(&config, &updated, &path)?;
+                } else {
+                    updated.save(None)?;
+                    if let Some(note) = commands::overridden_note(&config, &path) {
+                        println!("{}", rustyclaw_core::theme::icon_warn(&note));
+                    }
+                    println!(
+                        "{}",
+                        rustyclaw_core::theme::icon_ok(&format!(
+                            "Unset {}",
+                            rustyclaw_core::theme::accent_bright(&path)
+                        ))
+                    );
+                }
             }
+            ConfigCommands::Diff => commands::print_diff(&config)?,
         },
 
         // ── Doctor ──────────────────────────────────────────────
-        Commands::Doctor(_args) => {
+        Commands::Doctor(args) => {
             use rustyclaw_core::theme as t;
 
             let sp = t::spinner("Running health checks…");
@@ -615,6 +797,25 @@ async fn main() -> Result<()> {
                     all_ok = false;
                 }
             }
+
+            // Schema migrations are safe to apply without asking.
+            let config_file = cli
+                .common
+                .config_path()
+                .unwrap_or_else(|| config.settings_dir.join("config.toml"));
+            let migrate = args.repair || args.yes || args.non_interactive;
+            if !commands::check_config_schema(&config_file, migrate)? {
+                all_ok = false;
+            }
+            // The gateway skips these; point them out before they're missed.
+            let (_, problems) = rustyclaw_core::tools::check_tool_defaults(&config.tool_defaults);
+            for problem in &problems {
+                println!(
+                    "  {}",
+                    t::icon_warn(&format!("[tool_defaults] {}", problem))
+                );
+            }
+            commands::check_history_index(&config, migrate, args.reindex_history)?;
             println!();
             if all_ok {
                 println!("{}", t::success("All checks passed."));
@@ -645,6 +846,10 @@ async fn main() -> Result<()> {
             if args.no_dialog {
                 fwd.push("--no-dialog".to_string());
             }
+            if let Some((provider, model)) = args.model {
+                fwd.push("--model".to_string());
+                fwd.push(format!("{}/{}", provider, model));
+            }
             launch_client("rustyclaw-tui", &fwd)?;
         }
 
@@ -671,9 +876,17 @@ async fn main() -> Result<()> {
             }
 
             if let Some(gateway_url) = args.gateway {
-                let response = send_command_via_gateway(&gateway_url, &input).await?;
+                let response = send_command_via_gateway(&gateway_url, &input, args.model).await?;
                 println!("{}", response);
             } else {
+                if let Some((provider, model)) = args.model {
+                    let base_url = rustyclaw_core::providers::base_url_for_provider(&provider);
+                    config.model = Some(rustyclaw_core::config::ModelProvider {
+                        provider,
+                        model: Some(model),
+                        base_url: base_url.map(String::from),
+                    });
+                }
                 run_local_command(&mut config, &input)?;
             }
         }
@@ -750,21 +963,16 @@ async fn main() -> Result<()> {
                 }
             }
             GatewayCommands::Run(args) => {
-                let bind = match args.bind {
-                    GatewayBind::Loopback => "loopback",
-                    GatewayBind::Lan => "lan",
-                    GatewayBind::Tailnet => "tailnet",
-                    GatewayBind::Auto => "auto",
-                    GatewayBind::Custom => "custom",
-                };
+                let bind = args.bind.as_str();
                 // Verbose flag overrides log_level
                 let log_level = if args.verbose {
                     Some("rustyclaw=debug,info")
                 } else {
                     args.log_level.as_deref()
                 };
-                commands::handle_run(&config, bind, args.port, log_level)?;
+                commands::handle_run(&config, bind, args.port, log_level, args.trace)?;
             }
+            GatewayCommands::Token(sub) => commands::gateway_token::run(sub, &config)?,
         },
 
         // ── Skills sub-commands ─────────────────────────────────
@@ -806,9 +1014,30 @@ async fn main() -> Result<()> {
                         rustyclaw_core::theme::muted("Skill check is not yet implemented.")
                     );
                 }
+                SkillsCommands::Bundle { dir } => {
+                    use rustyclaw_core::theme as t;
+                    let bundled = sm.bundle_skills(&dir)?;
+                    if bundled.is_empty() {
+                        println!("{}", t::muted("No skills to bundle."));
+                    } else {
+                        for (name, version) in &bundled {
+                            println!("  {}", t::icon_ok(&format!("{}@{}", name, version)));
+                        }
+                        println!(
+                            "Bundled {} skill(s) into {}. Point `clawhub_mirror` at it to \
+                             install from it offline.",
+                            bundled.len(),
+                            dir.display()
+                        );
+                    }
+                }
             }
         }
 
+        // ── Secrets sub-commands ────────────────────────────────
+        Commands::Secrets(sub) => commands::secrets::run(sub, &config)?,
+        Commands::Audit(sub) => commands::audit::run(sub, &config)?,
+
         // ── ClawHub sub-commands ────────────────────────────────
         Commands::ClawHub(args) => commands::clawhub::run(args, &mut config)?,
 
@@ -0,0 +1,101 @@
+//! Global pause of the agent loop.
+//!
+//! Pausing freezes the agent without ending anything: every session's
+//! tool loop stops at the start of its next round and waits there until
+//! the agent is resumed.  Tool calls already running finish first, and
+//! unlike a cancel the turn carries on where it stopped.  Clients pause
+//! with the `PauseAgent` / `ResumeAgent` frames, the agent with the
+//! `gateway` tool's `pause` / `resume` actions.
+//!
+//! This is the switch; the gateway decides where its loops wait for it.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::OnceLock;
+use tokio::sync::watch;
+
+/// Since when, and why, the agent is paused.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseState {
+    pub since: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl PauseState {
+    /// One line for status frames and logs.
+    pub fn describe(&self) -> String {
+        match &self.reason {
+            Some(reason) => format!("Agent paused ({}) — resume to continue", reason),
+            None => "Agent paused — resume to continue".to_string(),
+        }
+    }
+}
+
+/// A pause switch that tool loops wait on.
+#[derive(Debug)]
+pub struct AgentPause {
+    state: watch::Sender<Option<PauseState>>,
+}
+
+impl AgentPause {
+    pub fn new() -> Self {
+        Self {
+            state: watch::channel(None).0,
+        }
+    }
+
+    /// Pause the agent.  `false` if it already was; the first reason
+    /// stands.
+    pub fn pause(&self, reason: Option<String>) -> bool {
+        self.state.send_if_modified(|state| {
+            if state.is_some() {
+                return false;
+            }
+            *state = Some(PauseState {
+                since: Utc::now(),
+                reason,
+            });
+            true
+        })
+    }
+
+    /// Resume the agent.  `false` if it wasn't paused.
+    pub fn resume(&self) -> bool {
+        self.state.send_if_modified(|state| state.take().is_some())
+    }
+
+    /// The current pause, if paused.
+    pub fn state(&self) -> Option<PauseState> {
+        self.state.borrow().clone()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.borrow().is_some()
+    }
+
+    /// Follow pauses and resumes as they happen.
+    pub fn subscribe(&self) -> watch::Receiver<Option<PauseState>> {
+        self.state.subscribe()
+    }
+
+    /// Resolve once the agent isn't paused.
+    pub async fn resumed(&self) {
+        let mut changes = self.state.subscribe();
+        // The sender is `self`, so it outlives the wait.
+        let _ = changes.wait_for(Option::is_none).await;
+    }
+}
+
+impl Default for AgentPause {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The gateway-wide switch.
+pub fn global() -> &'static AgentPause {
+    static PAUSE: OnceLock<AgentPause> = OnceLock::new();
+    PAUSE.get_or_init(AgentPause::new)
+}
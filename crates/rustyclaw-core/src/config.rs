@@ -3,10 +3,18 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::memory_consolidation::ConsolidationConfig;
 use crate::memory_flush::MemoryFlushConfig;
+use crate::secrets::SecretsBackendKind;
 use crate::services::ServiceDef;
+use crate::threads::HistoryBackendKind;
 use crate::workspace_context::WorkspaceContextConfig;
 
+pub mod layers;
+pub mod migrations;
+pub use layers::{ConfigLayer, LayerSource};
+pub use migrations::CURRENT_SCHEMA_VERSION;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelProvider {
     /// Provider id (e.g. "anthropic", "openai", "google", "ollama", "custom")
@@ -31,6 +39,319 @@ pub struct SandboxConfig {
     pub allow_paths: Vec<PathBuf>,
 }
 
+/// How the TUI folds tool-call blocks in the transcript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolDisplayConfig {
+    /// Start results above the thresholds collapsed to a one-line summary.
+    /// When false, every finished tool block starts expanded.
+    #[serde(default = "default_true")]
+    pub collapse_long_results: bool,
+    /// Results with more lines than this count as long.
+    #[serde(default = "ToolDisplayConfig::default_collapse_lines")]
+    pub collapse_threshold_lines: usize,
+    /// Results larger than this many bytes count as long.
+    #[serde(default = "ToolDisplayConfig::default_collapse_bytes")]
+    pub collapse_threshold_bytes: usize,
+}
+
+impl ToolDisplayConfig {
+    fn default_collapse_lines() -> usize {
+        12
+    }
+
+    fn default_collapse_bytes() -> usize {
+        1500
+    }
+
+    /// Whether a result of this content should start collapsed.
+    pub fn should_collapse(&self, result: &str) -> bool {
+        self.collapse_long_results
+            && (result.len() > self.collapse_threshold_bytes
+                || result.lines().count() > self.collapse_threshold_lines)
+    }
+}
+
+impl Default for ToolDisplayConfig {
+    fn default() -> Self {
+        Self {
+            collapse_long_results: true,
+            collapse_threshold_lines: Self::default_collapse_lines(),
+            collapse_threshold_bytes: Self::default_collapse_bytes(),
+        }
+    }
+}
+
+/// Provider-side prompt caching and reuse of identical model responses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptCacheConfig {
+    /// Mark the system prompt as a cacheable prefix for Anthropic
+    /// (`cache_control`), so repeat turns are billed at the cache rate.
+    /// OpenAI caches long prefixes automatically and needs no setting.
+    #[serde(default)]
+    pub cache_control: bool,
+    /// Answer an exact repeat of an earlier request on the same connection
+    /// from memory instead of calling the provider.
+    #[serde(default)]
+    pub responses: bool,
+    /// Responses kept per connection.
+    #[serde(default = "PromptCacheConfig::default_max_entries")]
+    pub max_entries: usize,
+    /// Also reuse responses to requests that sample (no `temperature = 0`).
+    #[serde(default)]
+    pub allow_nondeterministic: bool,
+}
+
+impl PromptCacheConfig {
+    fn default_max_entries() -> usize {
+        32
+    }
+}
+
+impl Default for PromptCacheConfig {
+    fn default() -> Self {
+        Self {
+            cache_control: false,
+            responses: false,
+            max_entries: Self::default_max_entries(),
+            allow_nondeterministic: false,
+        }
+    }
+}
+
+/// Extended thinking for models that can reason before answering
+/// (`[thinking]`).
+///
+/// Anthropic and Gemini get `budget_tokens` as their thinking budget;
+/// OpenAI reasoning models get the nearest effort level.  Other models
+/// are unaffected, though inline `<think>` blocks from open models are
+/// split out of the answer either way.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThinkingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Most tokens one reply may spend thinking.  Anthropic needs at least
+    /// 1024.
+    #[serde(default = "ThinkingConfig::default_budget_tokens")]
+    pub budget_tokens: u32,
+}
+
+impl ThinkingConfig {
+    /// Smallest budget Anthropic accepts.
+    const MIN_BUDGET_TOKENS: u32 = 1024;
+
+    fn default_budget_tokens() -> u32 {
+        8192
+    }
+
+    /// The thinking budget to request, if thinking is on.
+    pub fn budget(&self) -> Option<u32> {
+        self.enabled
+            .then(|| self.budget_tokens.max(Self::MIN_BUDGET_TOKENS))
+    }
+}
+
+impl Default for ThinkingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            budget_tokens: Self::default_budget_tokens(),
+        }
+    }
+}
+
+/// Verbose logging of provider request and response bodies
+/// (`[provider_logging]`), for capturing a reproduction of a provider bug.
+///
+/// Credentials and secret-shaped values are redacted, but prompts, tool
+/// output and model replies are logged as-is, so the log should still be
+/// treated as sensitive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderLogConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Write to this file instead of the gateway's own log.  Relative paths
+    /// are resolved against the logs directory.
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+    /// Rotate the file once it grows past this many bytes.
+    #[serde(default = "ProviderLogConfig::default_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Rotated files kept (`<file>.1` is the newest).
+    #[serde(default = "ProviderLogConfig::default_max_files")]
+    pub max_files: usize,
+}
+
+impl ProviderLogConfig {
+    fn default_max_file_bytes() -> u64 {
+        10 * 1024 * 1024
+    }
+
+    fn default_max_files() -> usize {
+        3
+    }
+}
+
+impl Default for ProviderLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: None,
+            max_file_bytes: Self::default_max_file_bytes(),
+            max_files: Self::default_max_files(),
+        }
+    }
+}
+
+/// Per-request Chrome trace files (`[request_trace]`, or `--trace` on the
+/// gateway): each chat turn's spans — provider and tool calls with their
+/// durations — written where chrome://tracing or Perfetto can open them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestTraceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Trace of the latest request.  Relative paths are resolved against
+    /// the logs directory.
+    #[serde(default = "RequestTraceConfig::default_file")]
+    pub file: PathBuf,
+    /// Earlier traces kept (`<file>.1` is the newest).
+    #[serde(default = "RequestTraceConfig::default_max_files")]
+    pub max_files: usize,
+}
+
+impl RequestTraceConfig {
+    fn default_file() -> PathBuf {
+        PathBuf::from("traces/request-trace.json")
+    }
+
+    fn default_max_files() -> usize {
+        20
+    }
+}
+
+impl Default for RequestTraceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: Self::default_file(),
+            max_files: Self::default_max_files(),
+        }
+    }
+}
+
+/// When logs are rolled and how long rolled files are kept
+/// (`[log_rotation]`).  Applies to the secret access log, the protocol event
+/// log and the daemon's `gateway.log`; the provider wire log takes its size
+/// and count from `[provider_logging]` and its age limit from here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRotationConfig {
+    /// Roll a log to `<file>.1` once it grows past this many bytes.
+    #[serde(default = "LogRotationConfig::default_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Rolled files kept per log (`<file>.1` is the newest).
+    #[serde(default = "LogRotationConfig::default_max_files")]
+    pub max_files: usize,
+    /// Delete rolled files last written more than this many days ago
+    /// (0 keeps them until `max_files` pushes them out).
+    #[serde(default = "LogRotationConfig::default_max_age_days")]
+    pub max_age_days: u64,
+}
+
+impl LogRotationConfig {
+    fn default_max_file_bytes() -> u64 {
+        10 * 1024 * 1024
+    }
+
+    fn default_max_files() -> usize {
+        3
+    }
+
+    fn default_max_age_days() -> u64 {
+        30
+    }
+}
+
+impl Default for LogRotationConfig {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: Self::default_max_file_bytes(),
+            max_files: Self::default_max_files(),
+            max_age_days: Self::default_max_age_days(),
+        }
+    }
+}
+
+/// Timeouts for one provider (`[provider_timeouts.<id>]`).  Unset fields
+/// use the defaults for the provider's type (see
+/// [`crate::providers::default_timeouts`]).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProviderTimeoutConfig {
+    /// Longest wait for a whole non-streamed reply, or between two events
+    /// of a streamed one.
+    #[serde(default)]
+    pub request_timeout_secs: Option<u64>,
+    /// Longest wait to open a connection to the provider.
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u64>,
+}
+
+/// Loading the model while the gateway starts (`[model_warmup]`), so the
+/// first chat doesn't wait for a local server to page it in.  Only local
+/// providers are warmed; cloud APIs have no cold start to hide.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelWarmupConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Give up on the warm-up after this many seconds.  The gateway keeps
+    /// serving either way.
+    #[serde(default = "ModelWarmupConfig::default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// How long Ollama keeps the model loaded afterwards (`"30m"`, `"-1"`
+    /// for always).  Unset uses the server's own default.
+    #[serde(default)]
+    pub keep_alive: Option<String>,
+}
+
+impl ModelWarmupConfig {
+    fn default_timeout_secs() -> u64 {
+        300
+    }
+}
+
+impl Default for ModelWarmupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_secs: Self::default_timeout_secs(),
+            keep_alive: None,
+        }
+    }
+}
+
+/// Prometheus metrics endpoint (`[metrics]`).  Off unless enabled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address of the HTTP listener serving `GET /metrics`.
+    #[serde(default = "MetricsConfig::default_listen")]
+    pub listen: String,
+}
+
+impl MetricsConfig {
+    fn default_listen() -> String {
+        "127.0.0.1:9464".to_string()
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: Self::default_listen(),
+        }
+    }
+}
+
 /// SSH transport configuration for the gateway.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SshGatewayConfig {
@@ -77,8 +398,316 @@ impl SshGatewayConfig {
     }
 }
 
+/// A remote host the `ssh` tool may run commands on (`[ssh_hosts.<alias>]`).
+///
+/// The agent refers to hosts by alias only; hosts that are not listed here
+/// cannot be reached.  Credentials are named, not stored: `key` and
+/// `password` are vault credential names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SshHostConfig {
+    /// Hostname or IP address.
+    pub host: String,
+    #[serde(default = "SshHostConfig::default_port")]
+    pub port: u16,
+    pub user: String,
+    /// Vault credential holding the private key (an SSH key credential).
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Vault credential holding the password, used when no `key` is set.
+    /// It is also fed to `sudo` on such hosts.
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Expected host key fingerprint (`SHA256:…`).  When set, a host that
+    /// presents any other key is refused.  Password authentication and
+    /// `sudo` need it.
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+    /// Allow commands on this host to run under `sudo`.
+    #[serde(default)]
+    pub allow_sudo: bool,
+}
+
+impl SshHostConfig {
+    fn default_port() -> u16 {
+        22
+    }
+}
+
+/// Where the `weather` tool gets its data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeatherProvider {
+    /// open-meteo.com, which needs no API key.
+    #[default]
+    #[serde(rename = "open-meteo")]
+    OpenMeteo,
+    /// openweathermap.org, with a key from the vault.
+    OpenWeatherMap,
+}
+
+/// `weather` tool settings (`[weather]`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherConfig {
+    #[serde(default)]
+    pub provider: WeatherProvider,
+    /// Vault credential holding the OpenWeatherMap API key.
+    #[serde(default = "WeatherConfig::default_api_key")]
+    pub api_key: String,
+    /// `"metric"` or `"imperial"`, unless a call asks otherwise.
+    #[serde(default = "WeatherConfig::default_units")]
+    pub units: String,
+    /// How long a result is reused for the same place.  0 disables caching.
+    #[serde(default = "WeatherConfig::default_cache_secs")]
+    pub cache_secs: u64,
+}
+
+impl WeatherConfig {
+    fn default_api_key() -> String {
+        "OPENWEATHERMAP_API_KEY".to_string()
+    }
+
+    fn default_units() -> String {
+        "metric".to_string()
+    }
+
+    fn default_cache_secs() -> u64 {
+        600
+    }
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        Self {
+            provider: WeatherProvider::default(),
+            api_key: Self::default_api_key(),
+            units: Self::default_units(),
+            cache_secs: Self::default_cache_secs(),
+        }
+    }
+}
+
+/// Which speech-to-text engine the `transcribe` tool uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscribeBackend {
+    /// whisper.cpp when it and a model are installed, otherwise the cloud.
+    #[default]
+    Auto,
+    /// whisper.cpp only; audio never leaves the machine.
+    Local,
+    /// An OpenAI-compatible transcription API, with a key from the vault.
+    Cloud,
+}
+
+/// `transcribe` tool settings (`[transcribe]`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscribeConfig {
+    #[serde(default)]
+    pub backend: TranscribeBackend,
+    /// whisper.cpp binary.  Unset looks for `whisper-cli`, then
+    /// `whisper-cpp`, on `PATH`.
+    #[serde(default)]
+    pub whisper_binary: Option<String>,
+    /// whisper.cpp ggml model file.  Unset picks the first `ggml-*.bin` in
+    /// `~/.cache/whisper` or `~/.local/share/whisper`.
+    #[serde(default)]
+    pub whisper_model: Option<String>,
+    /// OpenAI-compatible `/audio/transcriptions` endpoint.
+    #[serde(default = "TranscribeConfig::default_api_url")]
+    pub api_url: String,
+    #[serde(default = "TranscribeConfig::default_api_model")]
+    pub api_model: String,
+    /// Vault credential holding the API key.
+    #[serde(default = "TranscribeConfig::default_api_key")]
+    pub api_key: String,
+    /// Longest recording accepted, in seconds.
+    #[serde(default = "TranscribeConfig::default_max_duration_secs")]
+    pub max_duration_secs: u64,
+}
+
+impl TranscribeConfig {
+    fn default_api_url() -> String {
+        "https://api.openai.com/v1/audio/transcriptions".to_string()
+    }
+
+    fn default_api_model() -> String {
+        "whisper-1".to_string()
+    }
+
+    fn default_api_key() -> String {
+        "OPENAI_API_KEY".to_string()
+    }
+
+    fn default_max_duration_secs() -> u64 {
+        1800
+    }
+}
+
+impl Default for TranscribeConfig {
+    fn default() -> Self {
+        Self {
+            backend: TranscribeBackend::default(),
+            whisper_binary: None,
+            whisper_model: None,
+            api_url: Self::default_api_url(),
+            api_model: Self::default_api_model(),
+            api_key: Self::default_api_key(),
+            max_duration_secs: Self::default_max_duration_secs(),
+        }
+    }
+}
+
+/// `docker` tool settings (`[docker]`).
+///
+/// Containers started with `run` always get the memory and CPU limits
+/// below; privileged containers and host networking are refused unless
+/// enabled here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockerConfig {
+    /// Container CLI to use, `"docker"` or `"podman"`.  Unset picks
+    /// whichever is installed, docker first.
+    #[serde(default)]
+    pub binary: Option<String>,
+    /// Allow `run` with `privileged=true` or a mount of the engine socket.
+    #[serde(default)]
+    pub allow_privileged: bool,
+    /// Allow `run` with `network="host"`.
+    #[serde(default)]
+    pub allow_host_network: bool,
+    /// Memory limit for `run` (`"512m"`, `"2g"`); a call may ask for less.
+    #[serde(default = "DockerConfig::default_memory")]
+    pub memory: String,
+    /// CPU limit for `run`; a call may ask for less.
+    #[serde(default = "DockerConfig::default_cpus")]
+    pub cpus: f64,
+}
+
+impl DockerConfig {
+    fn default_memory() -> String {
+        "2g".to_string()
+    }
+
+    fn default_cpus() -> f64 {
+        1.0
+    }
+}
+
+impl Default for DockerConfig {
+    fn default() -> Self {
+        Self {
+            binary: None,
+            allow_privileged: false,
+            allow_host_network: false,
+            memory: Self::default_memory(),
+            cpus: Self::default_cpus(),
+        }
+    }
+}
+
+/// Hosts the `network_request_replay` tool may send to (`[request_replay]`).
+///
+/// Empty by default, so nothing is replayed until hosts are listed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestReplayConfig {
+    /// Host names, exact (`"api.example.com"`) or `"*.example.com"` for any
+    /// subdomain.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+}
+
+/// Clipboard history for the `clipboard` tool (`[clipboard_history]`).
+///
+/// Off by default: when on, the gateway samples the system clipboard and
+/// keeps recent text copies in memory, with secrets redacted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardHistoryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Most entries kept; the oldest is dropped first.
+    #[serde(default = "ClipboardHistoryConfig::default_max_entries")]
+    pub max_entries: usize,
+    /// Entries older than this are forgotten.
+    #[serde(default = "ClipboardHistoryConfig::default_max_age_secs")]
+    pub max_age_secs: u64,
+    /// How often the clipboard is sampled.  0 records only what passes
+    /// through the `clipboard` tool.
+    #[serde(default = "ClipboardHistoryConfig::default_poll_secs")]
+    pub poll_secs: u64,
+}
+
+impl ClipboardHistoryConfig {
+    fn default_max_entries() -> usize {
+        20
+    }
+
+    fn default_max_age_secs() -> u64 {
+        3600
+    }
+
+    fn default_poll_secs() -> u64 {
+        2
+    }
+}
+
+impl Default for ClipboardHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: Self::default_max_entries(),
+            max_age_secs: Self::default_max_age_secs(),
+            poll_secs: Self::default_poll_secs(),
+        }
+    }
+}
+
+/// Per-session working directories (`[session_workspaces]`).
+///
+/// Off by default: every session works in the workspace root.  When on,
+/// each conversation thread and messenger chat gets its own directory
+/// under `<workspace>/<dir>/` for its tools' relative paths.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionWorkspaceConfig {
+    #[serde(default)]
+    pub isolate: bool,
+    /// Directory under the workspace that holds the session directories.
+    #[serde(default = "SessionWorkspaceConfig::default_dir")]
+    pub dir: String,
+}
+
+impl SessionWorkspaceConfig {
+    fn default_dir() -> String {
+        "sessions".to_string()
+    }
+}
+
+impl Default for SessionWorkspaceConfig {
+    fn default() -> Self {
+        Self {
+            isolate: false,
+            dir: Self::default_dir(),
+        }
+    }
+}
+
+/// What the gateway does with a connection that arrives while it is at
+/// `gateway_max_connections`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionOverflow {
+    /// Tell the client the gateway is busy and close the connection.
+    #[default]
+    Reject,
+    /// Hold the connection until a slot frees up, for at most
+    /// `gateway_connection_queue_secs`, then reject it.
+    Queue,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Config schema version; files without one are version 0 and are
+    /// migrated on load (see [`migrations`]).
+    #[serde(default)]
+    pub schema_version: u32,
     /// Root state directory (e.g. `~/.rustyclaw`).
     /// All other paths are derived from this unless explicitly overridden.
     pub settings_dir: PathBuf,
@@ -105,6 +734,10 @@ pub struct Config {
     /// (as opposed to an auto-generated key file).
     #[serde(default)]
     pub secrets_password_protected: bool,
+    /// Where secret values are stored: `"file"` (the encrypted vault) or
+    /// `"keychain"` (the OS keychain).  Metadata and TOTP stay in the vault.
+    #[serde(default)]
+    pub secrets_backend: SecretsBackendKind,
     /// Whether TOTP two-factor authentication is enabled for the vault.
     #[serde(default)]
     pub totp_enabled: bool,
@@ -123,6 +756,9 @@ pub struct Config {
     /// Defaults to 5.
     #[serde(default = "Config::default_tab_width")]
     pub tab_width: u16,
+    /// Collapsing of tool-call blocks in the TUI transcript.
+    #[serde(default)]
+    pub tool_display: ToolDisplayConfig,
     /// Sandbox configuration for agent isolation.
     #[serde(default)]
     pub sandbox: SandboxConfig,
@@ -132,6 +768,10 @@ pub struct Config {
     /// ClawHub API token for publishing / authenticated downloads.
     #[serde(default)]
     pub clawhub_token: Option<String>,
+    /// Local skill registry mirror (a directory or `file://` URL) used for
+    /// skill search and install instead of ClawHub.
+    #[serde(default)]
+    pub clawhub_mirror: Option<String>,
     /// System prompt for the agent (used for messenger conversations).
     #[serde(default)]
     pub system_prompt: Option<String>,
@@ -145,6 +785,26 @@ pub struct Config {
     /// Per-tool permission overrides. Tools not listed here default to Allow.
     #[serde(default)]
     pub tool_permissions: HashMap<String, crate::tools::ToolPermission>,
+    /// Skills whose SKILL.md `allow_tools` grants are honoured, as `name`
+    /// or `name@sha256:<hex>` to pin the file's contents.  Grants from any
+    /// other skill are ignored.
+    #[serde(default)]
+    pub trusted_skills: Vec<String>,
+    /// Let the user answer an `Ask` prompt with "allow for this session",
+    /// which stops the prompts for that tool until the client disconnects.
+    #[serde(default = "default_true")]
+    pub tool_approval_session: bool,
+    /// Restrict gateway clients to these tools, keyed by how they
+    /// authenticated: an SSH key fingerprint (`SHA256:…`) or a gateway
+    /// token id.  A listed client can call nothing else, whatever
+    /// `tool_permissions` allows; a token's own scope narrows it further.
+    #[serde(default)]
+    pub client_tool_allowlists: HashMap<String, Vec<String>>,
+    /// Models gateway clients may select for their session or switch to,
+    /// as `model`, `provider/model` or `provider/*`.  Empty allows any
+    /// model; the configured `[model]` is always allowed.
+    #[serde(default)]
+    pub allowed_models: Vec<String>,
     /// Path to TLS certificate file (PEM) for WSS gateway connections.
     #[serde(default)]
     pub tls_cert: Option<PathBuf>,
@@ -154,18 +814,124 @@ pub struct Config {
     /// SSH transport configuration for the gateway.
     #[serde(default)]
     pub ssh: Option<SshGatewayConfig>,
+    /// Also accept gateway connections on this Unix domain socket, for
+    /// clients on the same host (`unix:/path` URLs).  The socket is only
+    /// usable by its owner.
+    #[serde(default)]
+    pub gateway_socket: Option<PathBuf>,
+    /// Close gateway connections that have been idle for this many seconds
+    /// (no client frames and no request in progress).  0 disables the
+    /// timeout.  Defaults to 4 hours.
+    #[serde(default = "Config::default_idle_timeout_secs")]
+    pub gateway_idle_timeout_secs: u64,
+    /// Most client connections the gateway serves at once, across SSH and
+    /// the Unix socket.  0 removes the limit.  Defaults to 256.
+    #[serde(default = "Config::default_max_connections")]
+    pub gateway_max_connections: usize,
+    /// `"reject"` (default) or `"queue"` connections beyond
+    /// `gateway_max_connections`.
+    #[serde(default)]
+    pub gateway_connection_overflow: ConnectionOverflow,
+    /// How long a queued connection waits for a slot before it is
+    /// rejected.  Defaults to 10 seconds.
+    #[serde(default = "Config::default_connection_queue_secs")]
+    pub gateway_connection_queue_secs: u64,
+    /// Probe the model every this many seconds in the background, in
+    /// addition to the probe on each connect.  0 disables background
+    /// probes.  Defaults to 5 minutes.
+    #[serde(default = "Config::default_health_probe_interval_secs")]
+    pub health_probe_interval_secs: u64,
+    /// Stop the agent's tool loop after this many model rounds in one
+    /// turn and tell the client why.  Defaults to 500.
+    #[serde(default = "Config::default_max_tool_iterations")]
+    pub max_tool_iterations: usize,
+    /// Stop the tool loop once a turn has run for this many seconds.  0
+    /// disables the budget.  Defaults to 1 hour.
+    #[serde(default = "Config::default_max_turn_secs")]
+    pub max_turn_secs: u64,
+    /// How many read-only tool calls from one model response may run at
+    /// once.  1 runs every call in turn.  Defaults to 4.
+    #[serde(default = "Config::default_max_parallel_tools")]
+    pub max_parallel_tools: usize,
+    /// Prompt and response caching (`[prompt_cache]`).
+    #[serde(default)]
+    pub prompt_cache: PromptCacheConfig,
+    /// Extended thinking (`[thinking]`).
+    #[serde(default)]
+    pub thinking: ThinkingConfig,
+    /// Per-provider request and connect timeouts, keyed by provider id
+    /// (`[provider_timeouts.ollama]`).
+    #[serde(default)]
+    pub provider_timeouts: HashMap<String, ProviderTimeoutConfig>,
+    /// Extra HTTP headers sent with every request to a provider, keyed by
+    /// provider id (`[provider_headers.openai]`).  They never replace the
+    /// provider's auth header.
+    #[serde(default)]
+    pub provider_headers: HashMap<String, HashMap<String, String>>,
+    /// Load a local model at gateway startup (`[model_warmup]`).
+    #[serde(default)]
+    pub model_warmup: ModelWarmupConfig,
+    /// Redacted request/response body logging (`[provider_logging]`).
+    #[serde(default)]
+    pub provider_logging: ProviderLogConfig,
+    /// Per-request Chrome trace files (`[request_trace]`).
+    #[serde(default)]
+    pub request_trace: RequestTraceConfig,
+    /// Size and age limits for the log files (`[log_rotation]`).
+    #[serde(default)]
+    pub log_rotation: LogRotationConfig,
+    /// Prometheus `/metrics` endpoint (`[metrics]`).
+    #[serde(default)]
+    pub metrics: MetricsConfig,
     /// Pre-compaction memory flush configuration.
     #[serde(default)]
     pub memory_flush: MemoryFlushConfig,
+    /// MEMORY.md / HISTORY.md settings for `save_memory`
+    /// (`[memory_consolidation]`).
+    #[serde(default)]
+    pub memory_consolidation: ConsolidationConfig,
     /// Workspace context injection configuration.
     #[serde(default)]
     pub workspace_context: WorkspaceContextConfig,
+    /// Separate working directories per session (`[session_workspaces]`).
+    #[serde(default)]
+    pub session_workspaces: SessionWorkspaceConfig,
+    /// Where thread history is kept: `"file"` (`threads.json`, default),
+    /// `"sqlite"` (`history.sqlite3`) or `"none"` (never written to disk).
+    #[serde(default)]
+    pub history_backend: HistoryBackendKind,
     /// Managed backend services.
     #[serde(default)]
     pub services: HashMap<String, ServiceDef>,
     /// Local inference engine configurations.
     #[serde(default)]
     pub engines: HashMap<String, crate::engines::EngineConfig>,
+    /// Hosts the `ssh` tool may connect to, by alias.
+    #[serde(default)]
+    pub ssh_hosts: HashMap<String, SshHostConfig>,
+    /// Provider and caching for the `weather` tool (`[weather]`).
+    #[serde(default)]
+    pub weather: WeatherConfig,
+    /// Speech-to-text backend for the `transcribe` tool (`[transcribe]`).
+    #[serde(default)]
+    pub transcribe: TranscribeConfig,
+    /// Opt-in history for the `clipboard` tool (`[clipboard_history]`).
+    #[serde(default)]
+    pub clipboard_history: ClipboardHistoryConfig,
+    /// Engine, limits and allowances for the `docker` tool (`[docker]`).
+    #[serde(default)]
+    pub docker: DockerConfig,
+    /// Host allowlist for the `network_request_replay` tool (`[request_replay]`).
+    #[serde(default)]
+    pub request_replay: RequestReplayConfig,
+    /// Arguments filled in when the model leaves them out, per tool
+    /// (`[tool_defaults.<tool>]`).
+    #[serde(default)]
+    pub tool_defaults: HashMap<String, serde_json::Map<String, serde_json::Value>>,
+    /// The files this config was loaded from, lowest precedence first
+    /// (see [`layers`]).  Empty for a config built in code.
+    #[serde(skip)]
+    pub layers: Vec<LayerSource>,
 }
 
 /// Configuration for a messenger backend.
@@ -291,6 +1057,7 @@ impl Default for Config {
     fn default() -> Self {
         let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
         Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
             settings_dir: home_dir.join(".rustyclaw"),
             soul_path: None,
             skills_dir: None,
@@ -301,25 +1068,61 @@ impl Default for Config {
             gateway_url: None,
             model: None,
             secrets_password_protected: false,
+            secrets_backend: SecretsBackendKind::default(),
             totp_enabled: false,
             agent_access: false,
             agent_name: Self::default_agent_name(),
             message_spacing: Self::default_message_spacing(),
             tab_width: Self::default_tab_width(),
+            tool_display: ToolDisplayConfig::default(),
             sandbox: SandboxConfig::default(),
             clawhub_url: None,
             clawhub_token: None,
+            clawhub_mirror: None,
             system_prompt: None,
             messenger_poll_interval_ms: None,
             messenger_max_concurrent: None,
             tool_permissions: HashMap::new(),
+            trusted_skills: Vec::new(),
+            tool_approval_session: true,
+            client_tool_allowlists: HashMap::new(),
+            allowed_models: Vec::new(),
             tls_cert: None,
             tls_key: None,
             ssh: None,
+            gateway_socket: None,
+            gateway_idle_timeout_secs: Self::default_idle_timeout_secs(),
+            gateway_max_connections: Self::default_max_connections(),
+            gateway_connection_overflow: ConnectionOverflow::default(),
+            gateway_connection_queue_secs: Self::default_connection_queue_secs(),
+            health_probe_interval_secs: Self::default_health_probe_interval_secs(),
+            max_tool_iterations: Self::default_max_tool_iterations(),
+            max_turn_secs: Self::default_max_turn_secs(),
+            max_parallel_tools: Self::default_max_parallel_tools(),
+            prompt_cache: PromptCacheConfig::default(),
+            thinking: ThinkingConfig::default(),
+            provider_timeouts: HashMap::new(),
+            provider_headers: HashMap::new(),
+            model_warmup: ModelWarmupConfig::default(),
+            provider_logging: ProviderLogConfig::default(),
+            request_trace: RequestTraceConfig::default(),
+            log_rotation: LogRotationConfig::default(),
+            metrics: MetricsConfig::default(),
             memory_flush: MemoryFlushConfig::default(),
+            memory_consolidation: ConsolidationConfig::default(),
             workspace_context: WorkspaceContextConfig::default(),
+            session_workspaces: SessionWorkspaceConfig::default(),
+            history_backend: HistoryBackendKind::default(),
             services: HashMap::new(),
             engines: HashMap::new(),
+            ssh_hosts: HashMap::new(),
+            weather: WeatherConfig::default(),
+            transcribe: TranscribeConfig::default(),
+            clipboard_history: ClipboardHistoryConfig::default(),
+            docker: DockerConfig::default(),
+            request_replay: RequestReplayConfig::default(),
+            tool_defaults: HashMap::new(),
+            layers: Vec::new(),
         }
     }
 }
@@ -337,6 +1140,34 @@ impl Config {
         5
     }
 
+    fn default_idle_timeout_secs() -> u64 {
+        4 * 60 * 60
+    }
+
+    fn default_max_connections() -> usize {
+        256
+    }
+
+    fn default_connection_queue_secs() -> u64 {
+        10
+    }
+
+    fn default_health_probe_interval_secs() -> u64 {
+        5 * 60
+    }
+
+    fn default_max_tool_iterations() -> usize {
+        500
+    }
+
+    fn default_max_turn_secs() -> u64 {
+        60 * 60
+    }
+
+    fn default_max_parallel_tools() -> usize {
+        4
+    }
+
     // ── Derived path helpers (mirrors openclaw layout) ───────────
 
     /// Agent workspace directory — holds SOUL.md, skills/, etc.
@@ -366,6 +1197,11 @@ impl Config {
         self.agent_dir().join("sessions")
     }
 
+    /// Cron store for `reminder` jobs, beside the threads they post into.
+    pub fn reminders_dir(&self) -> PathBuf {
+        self.sessions_dir().join("reminders")
+    }
+
     /// Path to SOUL.md — inside the workspace.
     pub fn soul_path(&self) -> PathBuf {
         self.soul_path
@@ -405,11 +1241,41 @@ impl Config {
         dirs
     }
 
+    /// The registry skill search and install use: `clawhub_mirror` when
+    /// set, otherwise `clawhub_url`.
+    pub fn skill_registry(&self) -> Option<&str> {
+        self.clawhub_mirror
+            .as_deref()
+            .or(self.clawhub_url.as_deref())
+    }
+
     /// Logs directory.
     pub fn logs_dir(&self) -> PathBuf {
         self.settings_dir.join("logs")
     }
 
+    /// Whether `allowed_models` lets a client use `model` from `provider`.
+    pub fn model_allowed(&self, provider: &str, model: &str) -> bool {
+        if self.allowed_models.is_empty() {
+            return true;
+        }
+        let configured = self
+            .model
+            .as_ref()
+            .is_some_and(|m| m.provider == provider && m.model.as_deref() == Some(model));
+        configured
+            || self.allowed_models.iter().any(|entry| {
+                let entry = entry.trim();
+                match entry.split_once('/') {
+                    Some((p, "*")) => p == provider,
+                    // Model ids can contain slashes themselves
+                    // (`openrouter` models are `vendor/model`).
+                    Some((p, m)) if p == provider => m == model,
+                    _ => entry == model,
+                }
+            })
+    }
+
     /// Ensure the entire directory skeleton exists on disk.
     pub fn ensure_dirs(&self) -> Result<()> {
         let dirs = [
@@ -429,7 +1295,11 @@ impl Config {
 
     // ── Load / save ─────────────────────────────────────────────────
 
-    /// Load configuration from file, with OpenClaw compatibility
+    /// Load configuration, merging the system, user and project layers
+    /// (see [`layers`]).  `path` replaces the user config file.
+    ///
+    /// The user file is migrated from older schemas and keeps OpenClaw
+    /// compatibility; when no layer exists the defaults are returned.
     pub fn load(path: Option<PathBuf>) -> Result<Self> {
         let config_path = if let Some(p) = path {
             p
@@ -438,25 +1308,94 @@ impl Config {
             home_dir.join(".rustyclaw").join("config.toml")
         };
 
+        let mut sources = Vec::new();
+        if let Some(system) = layers::system_config_path()
+            && system.is_file()
+        {
+            sources.push(LayerSource::read(ConfigLayer::System, system)?);
+        }
         if config_path.exists() {
             let content = std::fs::read_to_string(&config_path)?;
-            let config: Config = match toml::from_str(&content) {
-                Ok(c) => c,
+            let mut table: toml::Table = match toml::from_str(&content) {
+                Ok(t) => t,
                 Err(e) => {
                     eprintln!("ERROR: Failed to parse config: {}", e);
                     return Err(e.into());
                 }
             };
-            let mut config = config;
-            // Migrate legacy flat layout if detected.
-            config.migrate_legacy_layout()?;
-            Ok(config)
-        } else {
-            Ok(Config::default())
+            // Older schemas are upgraded in memory only; `rustyclaw doctor
+            // --repair` rewrites the file.
+            let steps = migrations::migrate(&mut table, &config_path);
+            for step in &steps {
+                eprintln!("Config migration {}", step.description);
+            }
+            if !steps.is_empty() {
+                eprintln!(
+                    "{} uses an older config schema; run `rustyclaw doctor --repair` to update it.",
+                    config_path.display()
+                );
+            }
+            sources.push(LayerSource {
+                layer: ConfigLayer::User,
+                path: config_path.clone(),
+                table,
+            });
+        }
+        if let Some(project) = layers::project_config_path()
+            && project != config_path
+        {
+            sources.push(LayerSource::read(ConfigLayer::Project, project)?);
+        }
+        if sources.is_empty() {
+            return Ok(Config::default());
+        }
+        let mut config = Self::from_layers(sources)?;
+        // Migrate legacy flat layout if detected.
+        config.migrate_legacy_layout()?;
+        Ok(config)
+    }
+
+    /// Merge parsed layers, lowest precedence first, into a config.
+    pub fn from_layers(mut sources: Vec<LayerSource>) -> Result<Self> {
+        let mut table = toml::Table::new();
+        for source in &mut sources {
+            let ignored = source.strip_restricted();
+            if !ignored.is_empty() {
+                eprintln!(
+                    "Ignoring {} in {}: a project config cannot set these.",
+                    ignored.join(", "),
+                    source.path.display()
+                );
+            }
+            layers::merge(&mut table, &source.table);
         }
+        // The user file always names these; system and project files
+        // alone may not.
+        let defaults = Config::default();
+        table
+            .entry("settings_dir")
+            .or_insert_with(|| defaults.settings_dir.display().to_string().into());
+        table
+            .entry("use_secrets")
+            .or_insert(defaults.use_secrets.into());
+
+        let mut config: Config = match toml::Value::Table(table).try_into() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("ERROR: Failed to parse config: {}", e);
+                return Err(e.into());
+            }
+        };
+        config.layers = sources;
+        Ok(config)
     }
 
-    /// Save configuration to file
+    /// Save configuration to the user file (`<settings_dir>/config.toml`),
+    /// or to `path` when given.
+    ///
+    /// Values this config inherited from the system or project layer are
+    /// left out unless the user file already set them, so saving never
+    /// copies shared settings into the user's own file.
     pub fn save(&self, path: Option<PathBuf>) -> Result<()> {
         let config_path = if let Some(p) = path {
             p
@@ -468,11 +1407,34 @@ impl Config {
             std::fs::create_dir_all(parent)?;
         }
 
-        let content = toml::to_string_pretty(self)?;
+        let content = if self.layers.iter().any(|l| l.layer != ConfigLayer::User) {
+            let mut inherited = toml::Table::new();
+            let mut own = toml::Table::new();
+            for source in &self.layers {
+                match source.layer {
+                    ConfigLayer::User => own = source.table.clone(),
+                    _ => layers::merge(&mut inherited, &source.table),
+                }
+            }
+            let mut table = toml::Table::try_from(self)?;
+            layers::strip_inherited(&mut table, &inherited, &own);
+            toml::to_string_pretty(&table)?
+        } else {
+            toml::to_string_pretty(self)?
+        };
         std::fs::write(&config_path, content)?;
         Ok(())
     }
 
+    /// The layer that set the dot-separated key `path`, or `None` when the
+    /// value is a built-in default.
+    pub fn origin(&self, path: &str) -> Option<&LayerSource> {
+        self.layers
+            .iter()
+            .rev()
+            .find(|source| layers::lookup(&source.table, path).is_some())
+    }
+
     // ── Legacy migration ────────────────────────────────────────────
 
     /// Detect the pre-restructure flat layout and move files into the
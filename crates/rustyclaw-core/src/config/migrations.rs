@@ -0,0 +1,198 @@
+//! Versioned config schema and the migrations between versions.
+//!
+//! `config.toml` carries a `schema_version`; files without one are version
+//! 0, which covers every config written before versioning, including those
+//! produced by `rustyclaw import`.  [`Config::load`](super::Config::load)
+//! runs the pending migrations on the parsed TOML before deserializing it,
+//! so an older file loads as the current schema, but it does not rewrite
+//! the file.  `rustyclaw doctor --repair` does that through
+//! [`migrate_file`], after copying the original to a backup.
+//!
+//! Every migration must be idempotent: it only fills in or converts what
+//! is still in the old shape, so running it on a file that was partly
+//! edited by hand, or already migrated, changes nothing.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Schema version written by this build.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// One step from `from` to `from + 1`.
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    apply: fn(&mut Table, &Path),
+}
+
+/// Every migration, oldest first.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    description: "0 → 1: fill in settings_dir and use_secrets, derive messenger_type from \
+                  name, and split \"provider/model\" strings into a [model] table",
+    apply: v0_to_v1,
+}];
+
+/// The `schema_version` of a parsed config; 0 when absent.
+pub fn schema_version(table: &Table) -> u32 {
+    table
+        .get("schema_version")
+        .and_then(Value::as_integer)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// The migrations a config at `version` still needs.
+pub fn pending_for(version: u32) -> Vec<&'static Migration> {
+    MIGRATIONS.iter().filter(|m| m.from >= version).collect()
+}
+
+/// Bring `table`, read from `config_path`, up to
+/// [`CURRENT_SCHEMA_VERSION`].  Returns the migrations applied, in order.
+/// A config from a newer build is left as it is.
+pub fn migrate(table: &mut Table, config_path: &Path) -> Vec<&'static Migration> {
+    let version = schema_version(table);
+    if version > CURRENT_SCHEMA_VERSION {
+        eprintln!(
+            "WARNING: {} has schema_version {}, newer than this build understands ({}); \
+             unknown settings will be ignored",
+            config_path.display(),
+            version,
+            CURRENT_SCHEMA_VERSION
+        );
+        return Vec::new();
+    }
+    let steps = pending_for(version);
+    for step in &steps {
+        (step.apply)(table, config_path);
+        table.insert(
+            "schema_version".into(),
+            Value::Integer(i64::from(step.from + 1)),
+        );
+    }
+    steps
+}
+
+/// Migrate the config file at `config_path` in place.
+///
+/// The original is first copied to `<file>.v<version>.bak` (an existing
+/// backup is kept, so it stays the oldest copy).  Returns the backup path
+/// and the migrations applied, or `None` when the file is up to date.
+pub fn migrate_file(config_path: &Path) -> Result<Option<(PathBuf, Vec<&'static Migration>)>> {
+    let content = std::fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read {}", config_path.display()))?;
+    let mut table: Table = toml::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", config_path.display()))?;
+    let version = schema_version(&table);
+    let steps = migrate(&mut table, config_path);
+    if steps.is_empty() {
+        return Ok(None);
+    }
+
+    let mut backup = config_path.as_os_str().to_owned();
+    backup.push(format!(".v{}.bak", version));
+    let backup = PathBuf::from(backup);
+    if !backup.exists() {
+        std::fs::copy(config_path, &backup)
+            .with_context(|| format!("Failed to back up config to {}", backup.display()))?;
+    }
+    std::fs::write(config_path, toml::to_string_pretty(&table)?)
+        .with_context(|| format!("Failed to write {}", config_path.display()))?;
+    Ok(Some((backup, steps)))
+}
+
+// ── Migrations ──────────────────────────────────────────────────────────────
+
+/// Pre-versioning configs: keys that hand-edited files often lack, and the
+/// OpenClaw-style `model = "provider/model"` string.
+fn v0_to_v1(table: &mut Table, config_path: &Path) {
+    if !table.contains_key("settings_dir") {
+        let dir = config_path.parent().unwrap_or(Path::new("."));
+        table.insert(
+            "settings_dir".into(),
+            Value::String(dir.display().to_string()),
+        );
+    }
+    table.entry("use_secrets").or_insert(Value::Boolean(true));
+
+    if let Some(Value::Array(messengers)) = table.get_mut("messengers") {
+        for messenger in messengers.iter_mut().filter_map(Value::as_table_mut) {
+            let typed = messenger
+                .get("messenger_type")
+                .and_then(Value::as_str)
+                .is_some_and(|t| !t.is_empty());
+            if typed {
+                continue;
+            }
+            if let Some(name) = messenger.get("name").and_then(Value::as_str) {
+                let kind = Value::String(name.to_ascii_lowercase());
+                messenger.insert("messenger_type".into(), kind);
+            }
+        }
+    }
+
+    if let Some(model) = table.get("model").and_then(Value::as_str) {
+        let mut provider = Table::new();
+        let (id, name) = match model.split_once('/') {
+            Some((id, name)) => (id, Some(name)),
+            None => (model, None),
+        };
+        provider.insert("provider".into(), Value::String(id.to_string()));
+        if let Some(name) = name {
+            provider.insert("model".into(), Value::String(name.to_string()));
+        }
+        table.insert("model".into(), Value::Table(provider));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    const V0: &str = r#"
+model = "anthropic/claude-sonnet-4"
+
+[[messengers]]
+name = "Slack"
+config_path = "/tmp/slack.toml"
+"#;
+
+    #[test]
+    fn test_v0_config_migrates_and_loads() {
+        let path = Path::new("/home/user/.rustyclaw/config.toml");
+        let mut table: Table = toml::from_str(V0).unwrap();
+        let steps = migrate(&mut table, path);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(schema_version(&table), CURRENT_SCHEMA_VERSION);
+
+        let config: Config = Value::Table(table.clone()).try_into().unwrap();
+        assert_eq!(config.settings_dir, Path::new("/home/user/.rustyclaw"));
+        assert!(config.use_secrets);
+        let model = config.model.unwrap();
+        assert_eq!(model.provider, "anthropic");
+        assert_eq!(model.model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(config.messengers[0].messenger_type, "slack");
+
+        // Idempotent: applying the same step again changes nothing.
+        let mut again = table.clone();
+        v0_to_v1(&mut again, path);
+        assert_eq!(again, table);
+        assert!(migrate(&mut again, path).is_empty());
+    }
+
+    #[test]
+    fn test_migrate_file_keeps_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, V0).unwrap();
+
+        let (backup, steps) = migrate_file(&path).unwrap().unwrap();
+        assert_eq!(steps.len(), 1);
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), V0);
+        let migrated: Table = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(schema_version(&migrated), CURRENT_SCHEMA_VERSION);
+        assert!(migrate_file(&path).unwrap().is_none());
+    }
+}
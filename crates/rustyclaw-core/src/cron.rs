@@ -41,6 +41,127 @@ pub enum Schedule {
     },
 }
 
+impl Schedule {
+    /// Fire time of an `At` schedule (ms since epoch).
+    ///
+    /// Returns `None` for recurring schedules and for `at` values that are
+    /// not RFC 3339 timestamps.
+    pub fn at_ms(&self) -> Option<u64> {
+        match self {
+            Schedule::At { at } => chrono::DateTime::parse_from_rfc3339(at)
+                .ok()
+                .and_then(|t| u64::try_from(t.timestamp_millis()).ok()),
+            _ => None,
+        }
+    }
+
+    /// Check that the schedule can fire: an RFC 3339 `at`, a non-zero
+    /// interval, or a well-formed 5-field expression in a known timezone.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Schedule::At { at } => chrono::DateTime::parse_from_rfc3339(at)
+                .map(|_| ())
+                .map_err(|e| format!("invalid 'at' time '{}': {}", at, e)),
+            Schedule::Every { every_ms: 0, .. } => Err("interval must be non-zero".to_string()),
+            Schedule::Every { .. } => Ok(()),
+            Schedule::Cron { expr, tz } => {
+                if let Some(tz) = tz {
+                    tz.parse::<chrono_tz::Tz>()
+                        .map_err(|_| format!("unknown timezone '{}'", tz))?;
+                }
+                validate_cron_expr(expr)
+            }
+        }
+    }
+}
+
+/// Bounds and names of the five cron fields, in order.
+const CRON_FIELDS: [(&str, u32, u32, &[&str]); 5] = [
+    ("minute", 0, 59, &[]),
+    ("hour", 0, 23, &[]),
+    ("day of month", 1, 31, &[]),
+    (
+        "month",
+        1,
+        12,
+        &[
+            "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+        ],
+    ),
+    (
+        "day of week",
+        0,
+        7,
+        &["sun", "mon", "tue", "wed", "thu", "fri", "sat"],
+    ),
+];
+
+const CRON_MACROS: &[&str] = &[
+    "@yearly",
+    "@annually",
+    "@monthly",
+    "@weekly",
+    "@daily",
+    "@midnight",
+    "@hourly",
+];
+
+fn validate_cron_expr(expr: &str) -> Result<(), String> {
+    let expr = expr.trim();
+    if CRON_MACROS.contains(&expr.to_ascii_lowercase().as_str()) {
+        return Ok(());
+    }
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    if fields.len() != CRON_FIELDS.len() {
+        return Err(format!(
+            "cron expression '{}' has {} fields, expected 5",
+            expr,
+            fields.len()
+        ));
+    }
+    for (field, (label, min, max, names)) in fields.iter().zip(CRON_FIELDS) {
+        for item in field.split(',') {
+            validate_cron_item(item, min, max, names)
+                .map_err(|e| format!("cron expression '{}': {} {}", expr, label, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// One comma-separated item: `*`, `N` or `N-M`, optionally `/STEP`.
+fn validate_cron_item(item: &str, min: u32, max: u32, names: &[&str]) -> Result<(), String> {
+    let (range, step) = match item.split_once('/') {
+        Some((range, step)) => (range, Some(step)),
+        None => (item, None),
+    };
+    if let Some(step) = step {
+        match step.parse::<u32>() {
+            Ok(n) if n > 0 => {}
+            _ => return Err(format!("has an invalid step '{}'", step)),
+        }
+    }
+    if range == "*" {
+        return Ok(());
+    }
+    let value = |v: &str| -> Result<u32, String> {
+        let lower = v.to_ascii_lowercase();
+        names
+            .iter()
+            .position(|n| *n == lower)
+            .map(|i| i as u32 + min)
+            .or_else(|| v.parse().ok())
+            .filter(|n| (min..=max).contains(n))
+            .ok_or_else(|| format!("value '{}' is not in {}-{}", v, min, max))
+    };
+    match range.split_once('-') {
+        Some((lo, hi)) if value(lo)? > value(hi)? => {
+            Err(format!("range '{}' runs backwards", range))
+        }
+        Some(_) => Ok(()),
+        None => value(range).map(|_| ()),
+    }
+}
+
 /// Payload kinds for cron jobs.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "camelCase")]
@@ -57,6 +178,14 @@ pub enum Payload {
         #[serde(skip_serializing_if = "Option::is_none")]
         timeout_seconds: Option<u64>,
     },
+    /// Message delivered straight to a channel, without an agent turn.
+    SendMessage {
+        channel: String,
+        target: String,
+        message: String,
+    },
+    /// Message injected as a user turn into a gateway conversation thread.
+    Reminder { thread_id: u64, message: String },
 }
 
 /// Delivery configuration for isolated jobs.
@@ -231,7 +360,15 @@ impl CronStore {
     }
 
     /// Add a new job.
-    pub fn add(&mut self, job: CronJob) -> Result<JobId, String> {
+    pub fn add(&mut self, mut job: CronJob) -> Result<JobId, String> {
+        // IDs are timestamps; jobs added within the same millisecond get a
+        // suffix instead of replacing each other.
+        let base = job.job_id.clone();
+        let mut n = 1;
+        while self.jobs.contains_key(&job.job_id) {
+            n += 1;
+            job.job_id = format!("{}-{}", base, n);
+        }
         let id = job.job_id.clone();
         self.jobs.insert(id.clone(), job);
         self.save()?;
@@ -287,6 +424,37 @@ impl CronStore {
         Ok(job)
     }
 
+    /// Enabled one-shot (`At`) jobs whose fire time is at or before `now_ms`.
+    ///
+    /// Recurring schedules are not evaluated here.
+    pub fn due(&self, now_ms: u64) -> Vec<&CronJob> {
+        self.jobs
+            .values()
+            .filter(|j| j.enabled)
+            .filter(|j| j.schedule.at_ms().is_some_and(|at| at <= now_ms))
+            .collect()
+    }
+
+    /// Settle a one-shot job after it ran at `ran_ms`.
+    ///
+    /// A successful run deletes the job when `delete_after_run` is set;
+    /// otherwise (or on failure) the job is kept but disabled, so it
+    /// shows up in `list` with `includeDisabled` instead of firing again.
+    pub fn finish_one_shot(&mut self, job_id: &str, ran_ms: u64, ok: bool) -> Result<(), String> {
+        let job = self
+            .jobs
+            .get_mut(job_id)
+            .ok_or_else(|| format!("Job not found: {}", job_id))?;
+
+        if ok && job.delete_after_run {
+            self.jobs.remove(job_id);
+        } else {
+            job.last_run_ms = Some(ran_ms);
+            job.enabled = false;
+        }
+        self.save()
+    }
+
     /// Get run history for a job.
     pub fn get_runs(&self, job_id: &str, limit: usize) -> Result<Vec<RunEntry>, String> {
         let runs_file = self.runs_dir.join(format!("{}.jsonl", job_id));
@@ -341,6 +509,125 @@ pub struct CronJobPatch {
     pub delivery: Option<Delivery>,
 }
 
+// ── Export / import ─────────────────────────────────────────────────────────
+
+/// Version of the [`CronExport`] document format.
+const EXPORT_VERSION: u32 = 1;
+
+/// Every job in a store, as written by `cron` `export` and read back by
+/// `import` on another machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CronExport {
+    #[serde(default)]
+    pub version: u32,
+    pub jobs: Vec<CronJob>,
+}
+
+impl CronExport {
+    /// Parse an export in JSON or YAML.  A bare list of jobs is accepted
+    /// too.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        // JSON is also YAML, but serde_json explains its errors better.
+        let doc: serde_json::Value = if text.trim_start().starts_with(['{', '[']) {
+            serde_json::from_str(text).map_err(|e| e.to_string())
+        } else {
+            serde_yaml::from_str(text).map_err(|e| e.to_string())
+        }
+        .map_err(|e| format!("Invalid cron export: {}", e))?;
+        let export = if doc.is_array() {
+            serde_json::from_value(doc).map(|jobs| CronExport {
+                version: EXPORT_VERSION,
+                jobs,
+            })
+        } else {
+            serde_json::from_value(doc)
+        }
+        .map_err(|e| format!("Invalid cron export: {}", e))?;
+        if export.version > EXPORT_VERSION {
+            return Err(format!(
+                "Cron export version {} is newer than this RustyClaw supports ({})",
+                export.version, EXPORT_VERSION
+            ));
+        }
+        Ok(export)
+    }
+}
+
+/// What [`CronStore::import`] does with a job whose ID is already taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportConflict {
+    /// Keep the existing job.
+    #[default]
+    Skip,
+    /// Replace the existing job, run state included.
+    Overwrite,
+    /// Take the imported definition but keep the existing job's creation
+    /// time and last/next run.
+    Merge,
+}
+
+/// What happened to one imported job.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportOutcome {
+    Added,
+    Overwritten,
+    Merged,
+    Skipped(String),
+}
+
+impl CronStore {
+    /// All jobs, oldest first.
+    pub fn export(&self) -> CronExport {
+        let mut jobs: Vec<CronJob> = self.jobs.values().cloned().collect();
+        jobs.sort_by(|a, b| (a.created_ms, &a.job_id).cmp(&(b.created_ms, &b.job_id)));
+        CronExport {
+            version: EXPORT_VERSION,
+            jobs,
+        }
+    }
+
+    /// Add `jobs`, keeping their IDs.  Jobs with an invalid schedule are
+    /// skipped; `conflict` decides for IDs already in the store.  The store
+    /// is saved once, after all jobs are settled.
+    pub fn import(
+        &mut self,
+        jobs: Vec<CronJob>,
+        conflict: ImportConflict,
+    ) -> Result<Vec<(JobId, ImportOutcome)>, String> {
+        let mut report = Vec::with_capacity(jobs.len());
+        for mut job in jobs {
+            let id = job.job_id.clone();
+            let outcome = if let Err(e) = job.schedule.validate() {
+                ImportOutcome::Skipped(e)
+            } else if id.trim().is_empty() {
+                ImportOutcome::Skipped("missing jobId".to_string())
+            } else {
+                match (self.jobs.get(&id), conflict) {
+                    (None, _) => ImportOutcome::Added,
+                    (Some(_), ImportConflict::Skip) => {
+                        ImportOutcome::Skipped("a job with this ID exists".to_string())
+                    }
+                    (Some(_), ImportConflict::Overwrite) => ImportOutcome::Overwritten,
+                    (Some(existing), ImportConflict::Merge) => {
+                        job.created_ms = existing.created_ms;
+                        job.last_run_ms = existing.last_run_ms;
+                        job.next_run_ms = existing.next_run_ms;
+                        ImportOutcome::Merged
+                    }
+                }
+            };
+            if !matches!(outcome, ImportOutcome::Skipped(_)) {
+                self.jobs.insert(id.clone(), job);
+            }
+            report.push((id, outcome));
+        }
+        self.save()?;
+        Ok(report)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -391,6 +678,47 @@ mod tests {
         assert_eq!(jobs[0].job_id, id);
     }
 
+    #[test]
+    fn test_due_one_shots() {
+        let dir = TempDir::new().unwrap();
+        let mut store = CronStore::new(dir.path()).unwrap();
+
+        let job = CronJob::new(
+            None,
+            Schedule::At {
+                at: "2026-02-12T18:00:00+01:00".to_string(),
+            },
+            SessionTarget::Isolated,
+            Payload::SendMessage {
+                channel: "discord".to_string(),
+                target: "123".to_string(),
+                message: "Standup in 5".to_string(),
+            },
+        );
+        let at_ms = job.schedule.at_ms().unwrap();
+        assert_eq!(at_ms, 1_770_915_600_000);
+        let id = store.add(job).unwrap();
+
+        assert!(store.due(at_ms - 1).is_empty());
+        assert_eq!(store.due(at_ms).len(), 1);
+
+        store.finish_one_shot(&id, at_ms, false).unwrap();
+        assert!(store.due(at_ms).is_empty());
+        assert!(!store.get(&id).unwrap().enabled);
+
+        store
+            .update(
+                &id,
+                CronJobPatch {
+                    enabled: Some(true),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        store.finish_one_shot(&id, at_ms, true).unwrap();
+        assert!(store.get(&id).is_none());
+    }
+
     #[test]
     fn test_cron_store_persistence() {
         let dir = TempDir::new().unwrap();
@@ -420,4 +748,145 @@ mod tests {
             assert_eq!(jobs[0].name, Some("Persistent".to_string()));
         }
     }
+
+    fn standup_job() -> CronJob {
+        let mut job = CronJob::new(
+            Some("Standup".to_string()),
+            Schedule::Cron {
+                expr: "30 9 * * MON-FRI".to_string(),
+                tz: Some("Europe/Berlin".to_string()),
+            },
+            SessionTarget::Isolated,
+            Payload::AgentTurn {
+                message: "Summarise yesterday's commits".to_string(),
+                model: Some("claude-haiku".to_string()),
+                thinking: None,
+                timeout_seconds: Some(120),
+            },
+        );
+        job.description = Some("Weekday summary".to_string());
+        job.delivery = Some(Delivery {
+            mode: DeliveryMode::Announce,
+            channel: Some("slack".to_string()),
+            to: Some("#team".to_string()),
+            best_effort: true,
+        });
+        job.enabled = false;
+        job.last_run_ms = Some(1_770_000_000_000);
+        job
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let from = TempDir::new().unwrap();
+        let mut store = CronStore::new(from.path()).unwrap();
+        store.add(standup_job()).unwrap();
+        let mut ping = CronJob::new(
+            None,
+            Schedule::Every {
+                every_ms: 60_000,
+                anchor_ms: Some(5),
+            },
+            SessionTarget::Main,
+            Payload::SystemEvent {
+                text: "ping".to_string(),
+            },
+        );
+        ping.job_id = "job-ping".to_string();
+        store.add(ping).unwrap();
+        let exported = store.export();
+
+        let text = serde_yaml::to_string(&exported).unwrap();
+        let to = TempDir::new().unwrap();
+        let mut fresh = CronStore::new(to.path()).unwrap();
+        let report = fresh
+            .import(CronExport::parse(&text).unwrap().jobs, ImportConflict::Skip)
+            .unwrap();
+        assert!(report.iter().all(|(_, o)| *o == ImportOutcome::Added));
+
+        // Every field survives, the timezone included, and across a reload.
+        let reloaded = CronStore::new(to.path()).unwrap().export();
+        assert_eq!(
+            serde_json::to_value(&reloaded.jobs).unwrap(),
+            serde_json::to_value(&exported.jobs).unwrap()
+        );
+        let standup = reloaded.jobs.iter().find(|j| j.name.is_some()).unwrap();
+        assert!(matches!(
+            &standup.schedule,
+            Schedule::Cron { tz: Some(tz), .. } if tz == "Europe/Berlin"
+        ));
+        assert!(!standup.enabled);
+    }
+
+    #[test]
+    fn test_import_conflicts() {
+        let dir = TempDir::new().unwrap();
+        let mut store = CronStore::new(dir.path()).unwrap();
+        let id = store.add(standup_job()).unwrap();
+
+        let mut incoming = store.get(&id).unwrap().clone();
+        incoming.name = Some("Renamed".to_string());
+        incoming.last_run_ms = None;
+        let import = |store: &mut CronStore, conflict| {
+            store.import(vec![incoming.clone()], conflict).unwrap()[0]
+                .1
+                .clone()
+        };
+
+        assert!(matches!(
+            import(&mut store, ImportConflict::Skip),
+            ImportOutcome::Skipped(_)
+        ));
+        assert_eq!(store.get(&id).unwrap().name.as_deref(), Some("Standup"));
+
+        assert_eq!(
+            import(&mut store, ImportConflict::Merge),
+            ImportOutcome::Merged
+        );
+        let merged = store.get(&id).unwrap();
+        assert_eq!(merged.name.as_deref(), Some("Renamed"));
+        assert_eq!(merged.last_run_ms, Some(1_770_000_000_000));
+
+        assert_eq!(
+            import(&mut store, ImportConflict::Overwrite),
+            ImportOutcome::Overwritten
+        );
+        assert_eq!(store.get(&id).unwrap().last_run_ms, None);
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_schedules() {
+        let bad = [
+            ("bad-at", r#"{"kind":"at","at":"tomorrow"}"#),
+            ("bad-every", r#"{"kind":"every","every_ms":0}"#),
+            ("bad-expr", r#"{"kind":"cron","expr":"61 * * * *"}"#),
+            ("bad-fields", r#"{"kind":"cron","expr":"* * *"}"#),
+            (
+                "bad-tz",
+                r#"{"kind":"cron","expr":"0 9 * * *","tz":"Mars/Olympus"}"#,
+            ),
+        ];
+        let jobs: Vec<String> = bad
+            .iter()
+            .chain([("ok", r#"{"kind":"cron","expr":"*/15 8-18 1,15 jan-jun 0"}"#)].iter())
+            .map(|(id, schedule)| {
+                format!(
+                    r#"{{"jobId":"{id}","schedule":{schedule},"sessionTarget":"main",
+                        "payload":{{"kind":"systemEvent","text":"x"}},"createdMs":1}}"#
+                )
+            })
+            .collect();
+        let export = CronExport::parse(&format!("[{}]", jobs.join(","))).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let mut store = CronStore::new(dir.path()).unwrap();
+        let report = store.import(export.jobs, ImportConflict::Skip).unwrap();
+        let added: Vec<&str> = report
+            .iter()
+            .filter(|(_, o)| *o == ImportOutcome::Added)
+            .map(|(id, _)| id.as_str())
+            .collect();
+        assert_eq!(added, ["ok"]);
+        assert_eq!(store.list(true).len(), 1);
+    }
 }
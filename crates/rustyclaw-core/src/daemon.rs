@@ -123,7 +123,15 @@ pub fn start(
         fs::create_dir_all(parent)?;
     }
 
-    let log_file = fs::File::create(&log)
+    // The gateway holds the log for its whole run, so it can only be rolled
+    // here: earlier runs are appended to until the log passes the size limit.
+    let rotator = crate::log_rotation::global();
+    rotator.rotate_if_needed(&log, 0);
+    rotator.prune(&log);
+    let log_file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log)
         .with_context(|| format!("Failed to create gateway log at {}", log.display()))?;
     let log_stderr = log_file
         .try_clone()
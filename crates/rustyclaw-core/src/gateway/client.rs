@@ -13,11 +13,11 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::{Mutex, mpsc};
 
-use crate::gateway::client_types::{GatewayCommand, GatewayEvent};
+use crate::gateway::client_types::{ErrorCode, GatewayCommand, GatewayEvent};
 use crate::gateway::protocol::event_log::{
     Direction, ProtocolEvent, ProtocolEventLog, default_log_path,
 };
-use crate::gateway::{ServerPayload, SshConnection, SshReader, SshWriter};
+use crate::gateway::{Capabilities, ServerPayload, SshConnection, SshReader, SshWriter};
 
 /// Client for communicating with the RustyClaw gateway.
 pub struct GatewayClient {
@@ -34,11 +34,20 @@ pub struct GatewayClient {
 
 impl GatewayClient {
     /// Connect to a gateway at the given URL, establishing the SSH transport.
-    pub async fn connect(url: &str) -> Result<Self> {
+    ///
+    /// `capabilities` are announced to the gateway once it says hello.
+    pub async fn connect(url: &str, capabilities: Capabilities) -> Result<Self> {
         let (connection, writer, reader) = SshConnection::connect(url)
             .await
             .context("Failed to establish SSH transport")?;
-        Ok(Self::from_transport(connection, writer, reader, Some(url)))
+        Ok(Self::from_transport(
+            connection,
+            writer,
+            reader,
+            Some(url),
+            capabilities,
+            None,
+        ))
     }
 
     /// Build a client over an already-established SSH transport.
@@ -46,12 +55,16 @@ impl GatewayClient {
     /// Clients that establish the SSH connection themselves (e.g. via an
     /// interactive connection dialog) can hand the transport parts here rather
     /// than reconnecting from a URL. `log_label` is recorded in the protocol
-    /// event log as the connection target, if known.
+    /// event log as the connection target, if known. `session_model`, a
+    /// `(provider, model)` pair, is requested for this connection only,
+    /// right after the capabilities.
     pub fn from_transport(
         connection: SshConnection,
         mut writer: SshWriter,
         mut reader: SshReader,
         log_label: Option<&str>,
+        capabilities: Capabilities,
+        session_model: Option<(String, String)>,
     ) -> Self {
         // Channels for communication.
         let (cmd_tx, mut cmd_rx) = mpsc::channel::<GatewayCommand>(32);
@@ -111,6 +124,7 @@ impl GatewayClient {
 
         // ── Spawn task to handle incoming messages ─────────────────────
         let active_stream_id_rx = active_stream_id.clone();
+        let announce_tx = cmd_tx.clone();
         tokio::spawn(async move {
             // Streaming stats for the event log.
             let mut stream_chunk_count: u32 = 0;
@@ -145,6 +159,19 @@ impl GatewayClient {
                                     stream_chunk_count, stream_total_bytes,
                                 ));
                             }
+                            // Tell the gateway what this client can render.
+                            ServerPayload::Hello { .. } => {
+                                let _ = announce_tx
+                                    .send(GatewayCommand::Capabilities {
+                                        capabilities: capabilities.clone(),
+                                    })
+                                    .await;
+                                if let Some((provider, model)) = session_model.clone() {
+                                    let _ = announce_tx
+                                        .send(GatewayCommand::SessionModel { provider, model })
+                                        .await;
+                                }
+                            }
                             _ => {}
                         }
 
@@ -198,6 +225,7 @@ impl GatewayClient {
                         let _ = event_tx
                             .send(GatewayEvent::Error {
                                 message: format!("Protocol error: {}", err),
+                                code: ErrorCode::InvalidFrame,
                             })
                             .await;
                         break;
@@ -260,9 +288,19 @@ impl GatewayClient {
         self.send(GatewayCommand::VaultUnlock { password }).await
     }
 
-    /// Approve or deny a tool call.
-    pub async fn respond_tool_approval(&self, id: String, approved: bool) -> Result<()> {
-        self.send(GatewayCommand::ToolApprove { id, approved })
-            .await
+    /// Approve or deny a tool call.  `remember` approves the tool for the
+    /// rest of the session.
+    pub async fn respond_tool_approval(
+        &self,
+        id: String,
+        approved: bool,
+        remember: bool,
+    ) -> Result<()> {
+        self.send(GatewayCommand::ToolApprove {
+            id,
+            approved,
+            remember,
+        })
+        .await
     }
 }
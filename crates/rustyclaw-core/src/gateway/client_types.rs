@@ -11,9 +11,12 @@ use crate::user_prompt_types::UserPrompt;
 
 // ── Re-export ────────────────────────────────────────────────────────────────
 
+pub use crate::gateway::protocol::ErrorCode;
 pub use crate::gateway::protocol::SecretEntryDto;
 pub use crate::gateway::protocol::ServiceInfoDto;
-pub use crate::gateway::protocol::frames::{EngineInfoDto, EngineModelDto};
+pub use crate::gateway::protocol::frames::{
+    EngineInfoDto, EngineModelDto, GlobalTaskDto, SandboxPolicyDto, ToolPermissionDto,
+};
 
 // ── Events (server → client) ────────────────────────────────────────────────
 
@@ -61,8 +64,8 @@ pub enum GatewayEvent {
     /// Thinking started (extended thinking)
     ThinkingStart,
 
-    /// A thinking delta was received (used to keep the thinking clock alive)
-    ThinkingDelta,
+    /// Reasoning text from the model, shown apart from the answer
+    ThinkingDelta { delta: String },
 
     /// Thinking ended
     ThinkingEnd,
@@ -92,7 +95,12 @@ pub enum GatewayEvent {
     ToolApprovalRequest {
         id: String,
         name: String,
+        /// Pretty-printed JSON, credentials masked.
         arguments: String,
+        summary: String,
+        risks: Vec<crate::tools::ToolRisk>,
+        /// The prompt may offer "allow for this session".
+        can_remember: bool,
     },
 
     /// User prompt request (agent asking for user input)
@@ -154,7 +162,7 @@ pub enum GatewayEvent {
     },
 
     /// Error from gateway
-    Error { message: String },
+    Error { message: String, code: ErrorCode },
 
     /// Info message
     Info { message: String },
@@ -280,6 +288,44 @@ pub enum GatewayEvent {
         ok: bool,
         message: String,
     },
+    // ── Tool permissions ─────────────────────────────────────────────────
+    /// Every tool with its current permission.
+    ToolPermissions {
+        ok: bool,
+        tools: Vec<ToolPermissionDto>,
+        message: Option<String>,
+    },
+    // ── Session export ───────────────────────────────────────────────────
+    /// An exported session transcript (empty `content` on failure).
+    SessionExport {
+        session_id: u64,
+        ok: bool,
+        format: String,
+        content: String,
+        error: Option<String>,
+    },
+    // ── Sandbox policy ───────────────────────────────────────────────────
+    /// The sandbox the gateway enforces and every tool's permission.
+    SandboxPolicy {
+        sandbox: SandboxPolicyDto,
+        tools: Vec<ToolPermissionDto>,
+    },
+    // ── Request IDs ──────────────────────────────────────────────────────
+    /// The IDs the gateway logs the current request under.
+    RequestStart {
+        connection_id: String,
+        request_id: String,
+    },
+    // ── Turn resume ──────────────────────────────────────────────────────
+    /// The session and turn a reply belongs to; `resumed` marks a replay.
+    TurnStarted {
+        session_id: u64,
+        turn_id: u64,
+        resumed: bool,
+    },
+    // ── Global task list ─────────────────────────────────────────────────
+    /// Tasks running (or finished) across every session.
+    GlobalTasks { tasks: Vec<GlobalTaskDto> },
 }
 
 // ── Commands (client → server) ──────────────────────────────────────────────
@@ -301,9 +347,15 @@ pub enum GatewayCommand {
     #[serde(rename = "vault_unlock")]
     VaultUnlock { password: String },
 
-    /// Approve tool call
+    /// Approve tool call; `remember` approves the tool for the rest of
+    /// the session
     #[serde(rename = "tool_approve")]
-    ToolApprove { id: String, approved: bool },
+    ToolApprove {
+        id: String,
+        approved: bool,
+        #[serde(default)]
+        remember: bool,
+    },
 
     /// Respond to a user prompt
     #[serde(rename = "user_prompt_response")]
@@ -485,6 +537,64 @@ pub enum GatewayCommand {
         #[serde(default)]
         extra_args: Vec<String>,
     },
+
+    // ── Tool permission commands ───────────────────────────────────────
+    /// Request every tool with its current permission.
+    #[serde(rename = "tool_permissions_request")]
+    ToolPermissionsRequest,
+
+    /// Set a tool's permission ("allow" | "deny" | "ask" | "skill_only").
+    #[serde(rename = "tool_permission_set")]
+    ToolPermissionSet {
+        tool: String,
+        permission: String,
+        skills: Vec<String>,
+    },
+
+    // ── Capability negotiation ─────────────────────────────────────────
+    /// Announce the features this client can render.
+    #[serde(rename = "capabilities")]
+    Capabilities {
+        capabilities: crate::gateway::Capabilities,
+    },
+
+    // ── Session export ─────────────────────────────────────────────────
+    /// Export a session transcript ("markdown" | "json").
+    #[serde(rename = "export_session")]
+    ExportSession { session_id: u64, format: String },
+
+    // ── Per-connection model ───────────────────────────────────────────
+    /// Use this provider/model for the connection without changing config.
+    #[serde(rename = "session_model")]
+    SessionModel { provider: String, model: String },
+
+    // ── Sandbox policy ─────────────────────────────────────────────────
+    /// Request the active sandbox policy and tool permissions.
+    #[serde(rename = "sandbox_policy_request")]
+    SandboxPolicyRequest,
+
+    // ── Agent pause ────────────────────────────────────────────────────
+    /// Pause the agent loop in every session until resumed.
+    #[serde(rename = "pause_agent")]
+    PauseAgent { reason: Option<String> },
+
+    /// Resume a paused agent loop.
+    #[serde(rename = "resume_agent")]
+    ResumeAgent,
+
+    // ── Turn resume ────────────────────────────────────────────────────
+    /// Replay and continue a turn cut off by a dropped connection.
+    #[serde(rename = "resume_turn")]
+    ResumeTurn { session_id: u64, turn_id: u64 },
+
+    // ── Global task list ───────────────────────────────────────────────
+    /// List tasks from every session, optionally by kind and status.
+    #[serde(rename = "global_tasks_request")]
+    GlobalTasksRequest {
+        kind: Option<String>,
+        status: Option<String>,
+        include_finished: bool,
+    },
 }
 
 // ── Protocol bridge (client types ⇄ wire frames) ────────────────────────────
@@ -507,6 +617,8 @@ impl GatewayCommand {
                 frame_type: ClientFrameType::Chat,
                 payload: ClientPayload::Chat {
                     messages: vec![ChatMessage::text("user", &message)],
+                    sampling: Default::default(),
+                    system_prefix: None,
                 },
             },
             GatewayCommand::Auth { code } => ClientFrame {
@@ -517,9 +629,17 @@ impl GatewayCommand {
                 frame_type: ClientFrameType::UnlockVault,
                 payload: ClientPayload::UnlockVault { password },
             },
-            GatewayCommand::ToolApprove { id, approved } => ClientFrame {
+            GatewayCommand::ToolApprove {
+                id,
+                approved,
+                remember,
+            } => ClientFrame {
                 frame_type: ClientFrameType::ToolApprovalResponse,
-                payload: ClientPayload::ToolApprovalResponse { id, approved },
+                payload: ClientPayload::ToolApprovalResponse {
+                    id,
+                    approved,
+                    remember,
+                },
             },
             GatewayCommand::ThreadSwitch { thread_id } => ClientFrame {
                 frame_type: ClientFrameType::ThreadSwitch,
@@ -737,6 +857,68 @@ impl GatewayCommand {
                     extra_args,
                 },
             },
+            GatewayCommand::ToolPermissionsRequest => ClientFrame {
+                frame_type: ClientFrameType::ToolPermissionsRequest,
+                payload: ClientPayload::ToolPermissionsRequest,
+            },
+            GatewayCommand::ToolPermissionSet {
+                tool,
+                permission,
+                skills,
+            } => ClientFrame {
+                frame_type: ClientFrameType::ToolPermissionSet,
+                payload: ClientPayload::ToolPermissionSet {
+                    tool,
+                    permission,
+                    skills,
+                },
+            },
+            GatewayCommand::Capabilities { capabilities } => ClientFrame {
+                frame_type: ClientFrameType::Capabilities,
+                payload: ClientPayload::Capabilities { capabilities },
+            },
+            GatewayCommand::ExportSession { session_id, format } => ClientFrame {
+                frame_type: ClientFrameType::ExportSession,
+                payload: ClientPayload::ExportSession { session_id, format },
+            },
+            GatewayCommand::SessionModel { provider, model } => ClientFrame {
+                frame_type: ClientFrameType::SessionModel,
+                payload: ClientPayload::SessionModel { provider, model },
+            },
+            GatewayCommand::SandboxPolicyRequest => ClientFrame {
+                frame_type: ClientFrameType::SandboxPolicyRequest,
+                payload: ClientPayload::SandboxPolicyRequest,
+            },
+            GatewayCommand::PauseAgent { reason } => ClientFrame {
+                frame_type: ClientFrameType::PauseAgent,
+                payload: ClientPayload::PauseAgent { reason },
+            },
+            GatewayCommand::ResumeAgent => ClientFrame {
+                frame_type: ClientFrameType::ResumeAgent,
+                payload: ClientPayload::ResumeAgent,
+            },
+            GatewayCommand::ResumeTurn {
+                session_id,
+                turn_id,
+            } => ClientFrame {
+                frame_type: ClientFrameType::ResumeTurn,
+                payload: ClientPayload::ResumeTurn {
+                    session_id,
+                    turn_id,
+                },
+            },
+            GatewayCommand::GlobalTasksRequest {
+                kind,
+                status,
+                include_finished,
+            } => ClientFrame {
+                frame_type: ClientFrameType::GlobalTasksRequest,
+                payload: ClientPayload::GlobalTasksRequest {
+                    kind,
+                    status,
+                    include_finished,
+                },
+            },
         }
     }
 }
@@ -772,6 +954,13 @@ impl GatewayEvent {
                 StatusType::ModelConnecting => GatewayEvent::Info { message: detail },
                 StatusType::CredentialsMissing => GatewayEvent::Warning { message: detail },
                 StatusType::NoModel => GatewayEvent::Warning { message: detail },
+                StatusType::IdleTimeout => GatewayEvent::Warning { message: detail },
+                StatusType::VaultUnlocked => GatewayEvent::VaultUnlocked,
+                StatusType::SkillsReloaded => GatewayEvent::Info { message: detail },
+                StatusType::ToolLoopStopped => GatewayEvent::Warning { message: detail },
+                StatusType::AgentPaused => GatewayEvent::Warning { message: detail },
+                StatusType::AgentResumed => GatewayEvent::Info { message: detail },
+                StatusType::ServerBusy => GatewayEvent::Warning { message: detail },
             }),
             ServerPayload::AuthChallenge { .. } => Some(GatewayEvent::AuthRequired),
             ServerPayload::AuthResult { ok, message, retry } => Some(if ok {
@@ -782,12 +971,16 @@ impl GatewayEvent {
                     retry: retry.unwrap_or(false),
                 }
             }),
-            ServerPayload::AuthLocked { message, .. } => Some(GatewayEvent::Error { message }),
+            ServerPayload::AuthLocked { message, .. } => Some(GatewayEvent::Error {
+                message,
+                code: ErrorCode::RateLimited,
+            }),
             ServerPayload::VaultUnlocked { ok, message } => Some(if ok {
                 GatewayEvent::VaultUnlocked
             } else {
                 GatewayEvent::Error {
                     message: message.unwrap_or_else(|| "Failed to unlock vault".into()),
+                    code: ErrorCode::VaultLocked,
                 }
             }),
             ServerPayload::ReloadResult {
@@ -803,11 +996,12 @@ impl GatewayEvent {
                         "Reload failed: {}",
                         message.as_deref().unwrap_or("Unknown error")
                     ),
+                    code: ErrorCode::ConfigError,
                 }
             }),
             ServerPayload::StreamStart => Some(GatewayEvent::StreamStart),
             ServerPayload::ThinkingStart => Some(GatewayEvent::ThinkingStart),
-            ServerPayload::ThinkingDelta { .. } => Some(GatewayEvent::ThinkingDelta),
+            ServerPayload::ThinkingDelta { delta } => Some(GatewayEvent::ThinkingDelta { delta }),
             ServerPayload::ThinkingEnd => Some(GatewayEvent::ThinkingEnd),
             ServerPayload::Chunk { delta } => Some(GatewayEvent::Chunk { delta }),
             ServerPayload::ResponseDone { .. } => Some(GatewayEvent::ResponseDone),
@@ -847,10 +1041,16 @@ impl GatewayEvent {
                 id,
                 name,
                 arguments,
+                summary,
+                risks,
+                can_remember,
             } => Some(GatewayEvent::ToolApprovalRequest {
                 id,
                 name,
                 arguments,
+                summary,
+                risks,
+                can_remember,
             }),
             ServerPayload::UserPromptRequest { id, mut prompt } => {
                 prompt.id = id.clone();
@@ -973,7 +1173,9 @@ impl GatewayEvent {
             ServerPayload::SecretsRemoveTotpResult { ok, .. } => {
                 Some(GatewayEvent::SecretsRemoveTotpResult { ok })
             }
-            ServerPayload::Error { message, .. } => Some(GatewayEvent::Error { message }),
+            ServerPayload::Error { message, code, .. } => {
+                Some(GatewayEvent::Error { message, code })
+            }
             ServerPayload::Info { message } => Some(GatewayEvent::Info { message }),
             ServerPayload::DomQuery { id, js } => Some(GatewayEvent::DomQuery { id, js }),
             ServerPayload::HostInfoResult {
@@ -1106,6 +1308,42 @@ impl GatewayEvent {
                 ok,
                 message,
             }),
+            ServerPayload::ToolPermissionsResult { ok, tools, message } => {
+                Some(GatewayEvent::ToolPermissions { ok, tools, message })
+            }
+            ServerPayload::SessionExportResult {
+                session_id,
+                ok,
+                format,
+                content,
+                error,
+            } => Some(GatewayEvent::SessionExport {
+                session_id,
+                ok,
+                format,
+                content,
+                error,
+            }),
+            ServerPayload::SandboxPolicyResult { sandbox, tools } => {
+                Some(GatewayEvent::SandboxPolicy { sandbox, tools })
+            }
+            ServerPayload::RequestStart {
+                connection_id,
+                request_id,
+            } => Some(GatewayEvent::RequestStart {
+                connection_id,
+                request_id,
+            }),
+            ServerPayload::TurnStarted {
+                session_id,
+                turn_id,
+                resumed,
+            } => Some(GatewayEvent::TurnStarted {
+                session_id,
+                turn_id,
+                resumed,
+            }),
+            ServerPayload::GlobalTasksResult { tasks } => Some(GatewayEvent::GlobalTasks { tasks }),
         }
     }
 }
@@ -21,25 +21,26 @@ pub use client::GatewayClient;
 pub use client_types::{GatewayCommand, GatewayEvent, ThreadInfoDto};
 
 // Re-export SSH connection transport (client-side)
-pub use ssh_connection::{SshConnection, SshReader, SshWriter};
+pub use ssh_connection::{SshConnection, SshReader, SshWriter, unix_socket_path};
 
 // Re-export protocol types
 pub use protocol::{
-    ClientFrame, ClientFrameType, ClientPayload, SecretEntryDto, ServerFrame, ServerFrameType,
-    ServerPayload, ServiceInfoDto, StatusType, WireFrame, deserialize_frame,
-    deserialize_wire_frame, serialize_frame, serialize_wire_frame,
+    Capabilities, ClientFrame, ClientFrameType, ClientPayload, ErrorCode, SecretEntryDto,
+    ServerFrame, ServerFrameType, ServerPayload, ServiceInfoDto, StatusType, WireFrame,
+    deserialize_frame, deserialize_wire_frame, serialize_frame, serialize_wire_frame,
 };
 
 // Re-export public types (includes protocol types via types module)
 pub use types::{
-    ChatMessage, ChatRequest, CopilotSession, GatewayOptions, MediaRef, ModelContext,
-    ModelResponse, ParsedToolCall, ProbeResult, ProviderRequest, ToolCallResult,
+    ChatMessage, ChatRequest, CopilotSession, GatewayOptions, MAX_SYSTEM_PREFIX_BYTES, MediaRef,
+    ModelContext, ModelResponse, ParsedToolCall, ProbeResult, ProviderRequest, SamplingParams,
+    ToolCallResult,
 };
 
 // Re-export transport types
 pub use transport::{
-    PeerInfo, ScopedTransportWriter, Transport, TransportAcceptor, TransportReader, TransportType,
-    TransportWriter,
+    NegotiatedWriter, PeerInfo, ScopedTransportWriter, Transport, TransportAcceptor,
+    TransportReader, TransportType, TransportWriter,
 };
 
 // Re-export protocol server helpers used by the gateway server crate and by
@@ -0,0 +1,112 @@
+//! Per-connection capability negotiation.
+//!
+//! The gateway lists what it can emit in its `Hello` frame; the client
+//! answers with a `Capabilities` frame naming what it can render.  Until
+//! (unless) that answer arrives the gateway assumes [`Capabilities::legacy`],
+//! which is exactly what clients built before negotiation existed handle.
+//!
+//! Features are plain strings so that a peer can announce a feature the other
+//! side has never heard of; unknown names are simply ignored.
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the capability set described by [`feature`].
+pub const CAPABILITIES_VERSION: u16 = 1;
+
+/// Feature names understood by this version.
+pub mod feature {
+    /// Incremental `StreamStart` / `Chunk` frames.  Without it the gateway
+    /// buffers the reply and sends it as a single chunk.
+    pub const STREAM_DELTAS: &str = "stream_deltas";
+    /// `ThinkingStart` / `ThinkingDelta` / `ThinkingEnd` frames.
+    pub const THINKING: &str = "thinking";
+    /// `DomQuery` frames evaluated in a webview.
+    pub const DOM_QUERY: &str = "dom_query";
+    /// A `RequestStart` frame opening each chat request's stream.
+    pub const REQUEST_IDS: &str = "request_ids";
+    /// `TurnStarted` frames, so a dropped turn can be asked for again with
+    /// `ResumeTurn`.
+    pub const RESUME_TURNS: &str = "resume_turns";
+}
+
+/// A versioned set of feature names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub version: u16,
+    pub features: Vec<String>,
+}
+
+impl Capabilities {
+    /// A set at the current version.
+    pub fn new(features: &[&str]) -> Self {
+        Self {
+            version: CAPABILITIES_VERSION,
+            features: features.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    /// Everything this gateway can emit.
+    pub fn gateway() -> Self {
+        Self::new(&[
+            feature::STREAM_DELTAS,
+            feature::THINKING,
+            feature::DOM_QUERY,
+            feature::REQUEST_IDS,
+            feature::RESUME_TURNS,
+        ])
+    }
+
+    /// What a client that never announces is assumed to handle: streamed
+    /// text and thinking, as the terminal UI always has, but no webview.
+    pub fn legacy() -> Self {
+        Self::new(&[feature::STREAM_DELTAS, feature::THINKING])
+    }
+
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+
+    /// The features both sides support, at the lower of the two versions.
+    pub fn negotiate(&self, client: &Capabilities) -> Capabilities {
+        Capabilities {
+            version: self.version.min(client.version),
+            features: self
+                .features
+                .iter()
+                .filter(|f| client.supports(f))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::legacy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_intersects_and_ignores_unknown() {
+        let client = Capabilities {
+            version: 7,
+            features: vec!["thinking".into(), "holograms".into()],
+        };
+        let agreed = Capabilities::gateway().negotiate(&client);
+        assert_eq!(agreed.version, CAPABILITIES_VERSION);
+        assert_eq!(agreed.features, vec!["thinking".to_string()]);
+        assert!(!agreed.supports(feature::STREAM_DELTAS));
+    }
+
+    #[test]
+    fn test_legacy_default_keeps_streaming_without_dom_query() {
+        let agreed = Capabilities::gateway().negotiate(&Capabilities::default());
+        assert!(agreed.supports(feature::STREAM_DELTAS));
+        assert!(agreed.supports(feature::THINKING));
+        assert!(!agreed.supports(feature::DOM_QUERY));
+    }
+}
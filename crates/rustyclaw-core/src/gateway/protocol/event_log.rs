@@ -9,8 +9,7 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
-/// Maximum log file size before rotation (1 MB).
-const MAX_LOG_SIZE: u64 = 1_024 * 1_024;
+use crate::log_rotation::{self, LogRotator};
 
 /// Direction of a protocol frame.
 #[derive(Debug, Clone, Copy)]
@@ -88,8 +87,8 @@ impl fmt::Display for ProtocolEvent {
 
 /// Thread-safe protocol event logger.
 ///
-/// Writes events to a file with timestamps. Rotates when the file exceeds
-/// `MAX_LOG_SIZE`. The logger is designed to never fail — I/O errors are
+/// Writes events to a file with timestamps, rolled under the `[log_rotation]`
+/// limits. The logger is designed to never fail — I/O errors are
 /// silently ignored so protocol operations are never blocked by logging.
 #[derive(Clone, Debug)]
 pub struct ProtocolEventLog {
@@ -100,6 +99,7 @@ pub struct ProtocolEventLog {
 struct LogInner {
     file: Option<std::fs::File>,
     path: PathBuf,
+    rotator: LogRotator,
 }
 
 impl ProtocolEventLog {
@@ -109,9 +109,15 @@ impl ProtocolEventLog {
     /// file cannot be opened (never fails).
     pub fn new(path: impl Into<PathBuf>) -> Self {
         let path = path.into();
+        let rotator = log_rotation::global();
+        rotator.prune(&path);
         let file = Self::open_log_file(&path);
         Self {
-            inner: Arc::new(Mutex::new(LogInner { file, path })),
+            inner: Arc::new(Mutex::new(LogInner {
+                file,
+                path,
+                rotator,
+            })),
         }
     }
 
@@ -121,6 +127,7 @@ impl ProtocolEventLog {
             inner: Arc::new(Mutex::new(LogInner {
                 file: None,
                 path: PathBuf::new(),
+                rotator: log_rotation::global(),
             })),
         }
     }
@@ -136,10 +143,11 @@ impl ProtocolEventLog {
             .file
             .as_ref()
             .and_then(|f| f.metadata().ok())
-            .is_some_and(|m| m.len() > MAX_LOG_SIZE);
+            .is_some_and(|m| inner.rotator.should_rotate(m.len(), 0));
 
         if needs_rotate {
-            Self::rotate(&inner.path);
+            inner.file = None;
+            inner.rotator.rotate(&inner.path);
             inner.file = Self::open_log_file(&inner.path);
         }
 
@@ -209,11 +217,6 @@ impl ProtocolEventLog {
             .open(path)
             .ok()
     }
-
-    fn rotate(path: &Path) {
-        let rotated = path.with_extension("log.old");
-        let _ = std::fs::rename(path, rotated);
-    }
 }
 
 /// Determine the default protocol event log path.
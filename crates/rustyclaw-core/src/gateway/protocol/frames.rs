@@ -152,6 +152,42 @@ pub enum ClientFrameType {
     EngineModelAction = 70,
     /// Set per-engine configuration.
     EngineConfigSet = 71,
+    /// Request every tool with its current permission.
+    ToolPermissionsRequest = 72,
+    /// Set a tool's permission (persisted to config).
+    ToolPermissionSet = 73,
+    /// Announce the features this client can render.
+    Capabilities = 74,
+    /// Export a session's transcript.
+    ExportSession = 75,
+    /// Use a different provider/model for this connection only.
+    SessionModel = 76,
+    /// Request the active sandbox policy and tool permissions.
+    SandboxPolicyRequest = 77,
+    /// Pause the agent loop in every session.
+    PauseAgent = 78,
+    /// Resume a paused agent loop.
+    ResumeAgent = 79,
+    /// Replay and follow a turn whose connection dropped.
+    ResumeTurn = 80,
+    /// List tasks from every session.
+    GlobalTasksRequest = 81,
+}
+
+impl ClientFrameType {
+    /// Whether the frame changes the gateway's configuration.  These must
+    /// carry a [`WireFrame::sequence`] higher than any earlier such frame on
+    /// the connection; the gateway rejects stale or repeated ones.
+    pub fn mutates_config(self) -> bool {
+        matches!(
+            self,
+            Self::Reload
+                | Self::ModelSwitch
+                | Self::SetAgentName
+                | Self::EngineConfigSet
+                | Self::ToolPermissionSet
+        )
+    }
 }
 
 /// Outgoing frame types from gateway to client.
@@ -322,6 +358,18 @@ pub enum ServerFrameType {
     EnginePullProgress = 79,
     /// Engine action result (start/stop/install/remove/load/unload).
     EngineActionResult = 80,
+    /// Tool permission list (reply to a list or set request).
+    ToolPermissionsResult = 81,
+    /// Exported session transcript.
+    SessionExportResult = 82,
+    /// Active sandbox policy and tool permissions.
+    SandboxPolicyResult = 83,
+    /// IDs of the request a stream answers.
+    RequestStart = 84,
+    /// Session and turn IDs of a chat turn, for resuming it.
+    TurnStarted = 85,
+    /// Tasks from every session (reply to `GlobalTasksRequest`).
+    GlobalTasksResult = 86,
 }
 
 /// Status frame sub-types.
@@ -344,6 +392,127 @@ pub enum StatusType {
     NoModel = 6,
     /// Vault is locked.
     VaultLocked = 7,
+    /// Connection is being closed for inactivity.
+    IdleTimeout = 8,
+    /// Vault was unlocked (by another client).
+    VaultUnlocked = 9,
+    /// Installed skills were reloaded.
+    SkillsReloaded = 10,
+    /// The tool loop hit `max_tool_iterations` or `max_turn_secs`; the
+    /// model did not choose to stop.
+    ToolLoopStopped = 11,
+    /// The agent is paused; tool loops wait before their next round.
+    AgentPaused = 12,
+    /// The agent was resumed.
+    AgentResumed = 13,
+    /// The gateway is at `gateway_max_connections` and is closing this
+    /// connection.
+    ServerBusy = 14,
+}
+
+/// Why a request failed, carried by every `Error` frame alongside its
+/// human-readable message, so clients can react to (or localize) a failure
+/// without matching on the text.
+///
+/// Codes are stable and sent as their number.  One this build doesn't know,
+/// from a newer gateway, reads as [`ErrorCode::Other`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "u16", into = "u16")]
+#[repr(u16)]
+pub enum ErrorCode {
+    /// Not classified, or not known to this build.
+    #[default]
+    Other = 0,
+    /// The provider rejected the credentials, or signing in failed.
+    AuthFailed = 1,
+    /// The vault is locked or unusable, so a secret could not be read.
+    VaultLocked = 2,
+    /// The model's endpoint could not be reached.
+    ModelUnreachable = 3,
+    /// The provider answered the model call with an error.
+    ModelError = 4,
+    /// A provider request or connect timeout ran out.
+    Timeout = 5,
+    /// The provider, or the gateway, is refusing requests for now.
+    RateLimited = 6,
+    /// A tool call was refused by policy or by the user.
+    ToolDenied = 7,
+    /// A frame could not be decoded or is not valid in this state.
+    InvalidFrame = 8,
+    /// A well-formed request carried an invalid value.
+    InvalidRequest = 9,
+    /// The thread, project or other object named does not exist.
+    NotFound = 10,
+    /// No model is configured, or the configuration could not be loaded.
+    ConfigError = 11,
+    /// The connection may not do this (an observer sending a command, or a
+    /// client scoped to a tool allowlist sending an admin frame).
+    ReadOnly = 12,
+    /// What was asked for is not available yet.
+    Unavailable = 13,
+    /// The gateway failed while handling the request.
+    Internal = 14,
+    /// The model declined to respond on safety grounds.
+    SafetyRefusal = 15,
+}
+
+impl ErrorCode {
+    /// Stable name of the code, for logs and JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Other => "other",
+            Self::AuthFailed => "auth_failed",
+            Self::VaultLocked => "vault_locked",
+            Self::ModelUnreachable => "model_unreachable",
+            Self::ModelError => "model_error",
+            Self::Timeout => "timeout",
+            Self::RateLimited => "rate_limited",
+            Self::ToolDenied => "tool_denied",
+            Self::InvalidFrame => "invalid_frame",
+            Self::InvalidRequest => "invalid_request",
+            Self::NotFound => "not_found",
+            Self::ConfigError => "config_error",
+            Self::ReadOnly => "read_only",
+            Self::Unavailable => "unavailable",
+            Self::Internal => "internal",
+            Self::SafetyRefusal => "safety_refusal",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<u16> for ErrorCode {
+    fn from(code: u16) -> Self {
+        match code {
+            1 => Self::AuthFailed,
+            2 => Self::VaultLocked,
+            3 => Self::ModelUnreachable,
+            4 => Self::ModelError,
+            5 => Self::Timeout,
+            6 => Self::RateLimited,
+            7 => Self::ToolDenied,
+            8 => Self::InvalidFrame,
+            9 => Self::InvalidRequest,
+            10 => Self::NotFound,
+            11 => Self::ConfigError,
+            12 => Self::ReadOnly,
+            13 => Self::Unavailable,
+            14 => Self::Internal,
+            15 => Self::SafetyRefusal,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl From<ErrorCode> for u16 {
+    fn from(code: ErrorCode) -> Self {
+        code as u16
+    }
 }
 
 // ============================================================================
@@ -351,7 +520,11 @@ pub enum StatusType {
 // ============================================================================
 
 /// Protocol version for multiplexed SSH/stdin wire envelopes.
-pub const WIRE_PROTOCOL_VERSION: u16 = 1;
+///
+/// 2: `Error` frames carry an [`ErrorCode`].
+/// 3: tool approval requests carry a summary and risks; responses can
+/// approve a tool for the rest of the session.
+pub const WIRE_PROTOCOL_VERSION: u16 = 3;
 
 /// Stream ID used for connection-level control frames.
 pub const CONTROL_STREAM_ID: u64 = 0;
@@ -365,6 +538,9 @@ pub const CONTROL_STREAM_ID: u64 = 0;
 pub struct WireFrame<T> {
     pub version: u16,
     pub stream_id: u64,
+    /// Per-connection counter set by the client, starting at 1 and rising
+    /// with every frame.  Required on config-mutating frames (see
+    /// [`ClientFrameType::mutates_config`]); 0 means unsequenced.
     pub sequence: u64,
     pub flags: u16,
     pub frame: T,
@@ -409,6 +585,13 @@ pub enum ClientPayload {
     Reload,
     Chat {
         messages: Vec<super::types::ChatMessage>,
+        /// Per-turn sampling overrides (default: provider defaults).
+        #[serde(default)]
+        sampling: super::types::SamplingParams,
+        /// System instruction for this turn only; see
+        /// [`ChatRequest::system_prefix`](crate::gateway::ChatRequest::system_prefix).
+        #[serde(default)]
+        system_prefix: Option<String>,
     },
     SecretsList,
     SecretsGet {
@@ -445,6 +628,8 @@ pub enum ClientPayload {
     ToolApprovalResponse {
         id: String,
         approved: bool,
+        /// Approve this tool for the rest of the session.
+        remember: bool,
     },
     UserPromptResponse {
         id: String,
@@ -703,6 +888,66 @@ pub enum ClientPayload {
         engine: String,
         config: crate::engines::EngineConfig,
     },
+    // ── Tool permissions ─────────────────────────────────────────────────
+    /// Request every tool with its current permission.
+    ToolPermissionsRequest,
+    /// Set a tool's permission.
+    ToolPermissionSet {
+        tool: String,
+        permission: String, // "allow" | "deny" | "ask" | "skill_only"
+        /// Skills allowed to call the tool when `permission` is "skill_only".
+        skills: Vec<String>,
+    },
+    // ── Capability negotiation ───────────────────────────────────────────
+    /// Features this client can render; sent once, after `Hello`.
+    Capabilities {
+        capabilities: super::capabilities::Capabilities,
+    },
+    // ── Session export ───────────────────────────────────────────────────
+    /// Export a session (thread) transcript.  Only sessions this connection
+    /// owns can be exported; observers may export any persisted session.
+    ExportSession {
+        session_id: u64,
+        format: String, // "markdown" | "json"
+    },
+    // ── Per-connection model ─────────────────────────────────────────────
+    /// Use `provider`/`model` for this connection's chats instead of the
+    /// configured model.  Unlike `ModelSwitch`, nothing is persisted and
+    /// other connections are unaffected.  Sent after `Hello`.
+    SessionModel {
+        provider: String,
+        model: String,
+    },
+    // ── Sandbox policy ───────────────────────────────────────────────────
+    /// Request what the agent is permitted to touch: the sandbox the
+    /// gateway enforces and every tool's permission.  Read-only.
+    SandboxPolicyRequest,
+    // ── Agent pause ──────────────────────────────────────────────────────
+    /// Pause the agent loop gateway-wide: running tool calls finish, then
+    /// every session waits before its next round.  Cancel still works.
+    PauseAgent {
+        reason: Option<String>,
+    },
+    /// Let paused sessions continue.
+    ResumeAgent,
+    // ── Turn resume ──────────────────────────────────────────────────────
+    /// After reconnecting, ask for a turn announced by `TurnStarted` on a
+    /// connection that dropped: the gateway replays what it produced so
+    /// far and streams the rest.
+    ResumeTurn {
+        session_id: u64,
+        turn_id: u64,
+    },
+    // ── Global task list ─────────────────────────────────────────────────
+    /// List the tasks the gateway tracks across all sessions.  `kind` and
+    /// `status` take the codes used in [`GlobalTaskDto`]; finished tasks
+    /// are left out unless `include_finished` is set or `status` asks for
+    /// them.
+    GlobalTasksRequest {
+        kind: Option<String>,
+        status: Option<String>,
+        include_finished: bool,
+    },
 }
 
 /// Generic server frame envelope.
@@ -722,6 +967,8 @@ pub enum ServerPayload {
         vault_locked: bool,
         provider: Option<String>,
         model: Option<String>,
+        /// Everything the gateway can emit.
+        capabilities: super::capabilities::Capabilities,
     },
     AuthChallenge {
         method: String,
@@ -803,6 +1050,7 @@ pub enum ServerPayload {
     Error {
         ok: bool,
         message: String,
+        code: ErrorCode,
     },
     Info {
         message: String,
@@ -833,7 +1081,13 @@ pub enum ServerPayload {
     ToolApprovalRequest {
         id: String,
         name: String,
+        /// Pretty-printed JSON, credentials masked.
         arguments: String,
+        /// What the tool lets the agent do.
+        summary: String,
+        risks: Vec<crate::tools::ToolRisk>,
+        /// Whether the gateway accepts `remember` in the response.
+        can_remember: bool,
     },
     UserPromptRequest {
         id: String,
@@ -1109,6 +1363,53 @@ pub enum ServerPayload {
         ok: bool,
         message: String,
     },
+    // ── Tool permissions ─────────────────────────────────────────────────
+    /// Every tool with its permission.  After a failed set, `ok` is false,
+    /// `message` says why and `tools` is the unchanged list.
+    ToolPermissionsResult {
+        ok: bool,
+        tools: Vec<ToolPermissionDto>,
+        message: Option<String>,
+    },
+    // ── Session export ───────────────────────────────────────────────────
+    /// Reply to `ExportSession`: the transcript with credentials redacted
+    /// and long tool outputs truncated.  `content` is empty when `ok` is
+    /// false.
+    SessionExportResult {
+        session_id: u64,
+        ok: bool,
+        format: String,
+        content: String,
+        error: Option<String>,
+    },
+    // ── Sandbox policy ───────────────────────────────────────────────────
+    /// Reply to `SandboxPolicyRequest`.  Policy, not secrets, so nothing is
+    /// redacted.
+    SandboxPolicyResult {
+        sandbox: SandboxPolicyDto,
+        tools: Vec<ToolPermissionDto>,
+    },
+    // ── Request IDs ──────────────────────────────────────────────────────
+    /// First frame on a chat request's stream, naming the IDs the gateway
+    /// logs it under.  Sent only with the `request_ids` capability.
+    RequestStart {
+        connection_id: String,
+        request_id: String,
+    },
+    // ── Turn resume ──────────────────────────────────────────────────────
+    /// Names the chat turn a stream carries.  Sent only with the
+    /// `resume_turns` capability, before the turn's first reply frame and
+    /// again (with `resumed`) before a `ResumeTurn` replay.
+    TurnStarted {
+        session_id: u64,
+        turn_id: u64,
+        resumed: bool,
+    },
+    // ── Global task list ─────────────────────────────────────────────────
+    /// Reply to `GlobalTasksRequest`, oldest task first.
+    GlobalTasksResult {
+        tasks: Vec<GlobalTaskDto>,
+    },
 }
 
 /// DTO for local engine info in protocol results.
@@ -1240,6 +1541,51 @@ pub struct TaskInfoDto {
     pub is_foreground: bool,
 }
 
+/// DTO for one task in the gateway-wide task list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlobalTaskDto {
+    pub id: u64,
+    /// "command", "sub_agent", "cron", "mcp", "browser", "file", "web" or "custom".
+    pub kind: String,
+    pub label: String,
+    pub description: Option<String>,
+    /// "pending", "running", "background", "paused", "waiting_input",
+    /// "completed", "failed" or "cancelled".
+    pub status: String,
+    /// Session that started the task, e.g. "thread-3" or "telegram:42";
+    /// `None` for gateway-owned work such as cron runs.
+    pub session: Option<String>,
+    /// Unix timestamps (seconds).
+    pub created_at: u64,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+    pub progress: Option<f32>,
+    pub message: Option<String>,
+}
+
+impl GlobalTaskDto {
+    pub fn new(task: &crate::tasks::Task) -> Self {
+        let secs = |t: std::time::SystemTime| {
+            t.duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        };
+        Self {
+            id: task.id.0,
+            kind: task.kind.code().to_string(),
+            label: task.display_label(),
+            description: task.description.clone(),
+            status: task.status.code().to_string(),
+            session: task.session_key.clone(),
+            created_at: secs(task.created_at),
+            started_at: task.started_at.map(secs),
+            finished_at: task.finished_at.map(secs),
+            progress: task.status.progress(),
+            message: task.status.message().map(str::to_string),
+        }
+    }
+}
+
 /// DTO for thread info in updates (unified tasks + threads).
 /// NOTE: Do NOT use skip_serializing_if with bincode - it breaks deserialization
 /// since bincode is not self-describing (positional format).
@@ -1415,6 +1761,88 @@ pub struct ToolConfigDto {
     pub description: String,
 }
 
+/// DTO for a tool and its permission.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolPermissionDto {
+    pub name: String,
+    /// "allow" | "deny" | "ask" | "skill_only"
+    pub permission: String,
+    /// Skills allowed to call the tool (empty unless "skill_only").
+    pub skills: Vec<String>,
+    pub summary: String,
+}
+
+impl ToolPermissionDto {
+    pub fn new(name: &str, permission: &crate::tools::ToolPermission) -> Self {
+        Self {
+            name: name.to_string(),
+            permission: permission.kind().to_string(),
+            skills: permission.skills().to_vec(),
+            summary: crate::tools::tool_summary(name).to_string(),
+        }
+    }
+}
+
+/// DTO for the sandbox the gateway enforces on tool execution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SandboxPolicyDto {
+    /// Configured mode ("none", "path", "bwrap", "landlock", "docker", "auto", …).
+    pub mode: String,
+    /// Mode after resolving "auto" against what the host supports.
+    pub effective_mode: String,
+    /// Sandbox mechanisms available on the gateway host.
+    pub capabilities: String,
+    pub workspace: String,
+    pub deny_read: Vec<String>,
+    pub deny_write: Vec<String>,
+    pub deny_exec: Vec<String>,
+    /// When non-empty, the only paths tools may touch.
+    pub allow_paths: Vec<String>,
+    pub network: String,
+    /// `(limit, value)` pairs applied to each command, e.g. `("memory", "2g")`.
+    pub resource_limits: Vec<(String, String)>,
+}
+
+impl SandboxPolicyDto {
+    /// Describe `sandbox`, or the absence of one (commands run unconfined)
+    /// when the gateway never initialized it.
+    pub fn new(sandbox: Option<&crate::sandbox::Sandbox>) -> Self {
+        let paths = |paths: &[std::path::PathBuf]| -> Vec<String> {
+            paths.iter().map(|p| p.display().to_string()).collect()
+        };
+        match sandbox {
+            Some(sb) => Self {
+                mode: sb.mode.to_string(),
+                effective_mode: sb.effective_mode().to_string(),
+                capabilities: sb.capabilities.describe(),
+                workspace: sb.policy.workspace.display().to_string(),
+                deny_read: paths(&sb.policy.deny_read),
+                deny_write: paths(&sb.policy.deny_write),
+                deny_exec: paths(&sb.policy.deny_exec),
+                allow_paths: paths(&sb.policy.allow_paths),
+                network: sb.network_policy().to_string(),
+                resource_limits: sb
+                    .resource_limits()
+                    .into_iter()
+                    .map(|(limit, value)| (limit.to_string(), value.to_string()))
+                    .collect(),
+            },
+            None => Self {
+                mode: crate::sandbox::SandboxMode::None.to_string(),
+                effective_mode: crate::sandbox::SandboxMode::None.to_string(),
+                capabilities: String::new(),
+                workspace: String::new(),
+                deny_read: Vec::new(),
+                deny_write: Vec::new(),
+                deny_exec: Vec::new(),
+                allow_paths: Vec::new(),
+                network: "unrestricted".to_string(),
+                resource_limits: Vec::new(),
+            },
+        }
+    }
+}
+
 // ============================================================================
 // Channel DTOs (A8)
 // ============================================================================
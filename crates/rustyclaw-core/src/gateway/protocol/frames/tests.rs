@@ -1,6 +1,7 @@
 //! Tests for protocol frame types.
 
 use super::*;
+use crate::gateway::protocol::capabilities::{CAPABILITIES_VERSION, Capabilities};
 
 mod serialization {
     use super::*;
@@ -159,6 +160,13 @@ mod serialization {
         assert_eq!(StatusType::ModelError as u8, 5);
         assert_eq!(StatusType::NoModel as u8, 6);
         assert_eq!(StatusType::VaultLocked as u8, 7);
+        assert_eq!(StatusType::IdleTimeout as u8, 8);
+        assert_eq!(StatusType::VaultUnlocked as u8, 9);
+        assert_eq!(StatusType::SkillsReloaded as u8, 10);
+        assert_eq!(StatusType::ToolLoopStopped as u8, 11);
+        assert_eq!(StatusType::AgentPaused as u8, 12);
+        assert_eq!(StatusType::AgentResumed as u8, 13);
+        assert_eq!(StatusType::ServerBusy as u8, 14);
     }
 
     #[test]
@@ -171,6 +179,7 @@ mod serialization {
                 vault_locked: false,
                 provider: Some("anthropic".into()),
                 model: Some("claude-3".into()),
+                capabilities: Capabilities::gateway(),
             },
         };
 
@@ -184,12 +193,14 @@ mod serialization {
                 vault_locked,
                 provider,
                 model,
+                capabilities,
             } => {
                 assert_eq!(agent, "test-agent");
                 assert_eq!(settings_dir, "/tmp/settings");
                 assert!(!vault_locked);
                 assert_eq!(provider, Some("anthropic".into()));
                 assert_eq!(model, Some("claude-3".into()));
+                assert_eq!(capabilities, Capabilities::gateway());
             }
             _ => panic!("Expected Hello payload"),
         }
@@ -547,6 +558,8 @@ mod serialization {
                 messages: vec![crate::gateway::protocol::types::ChatMessage::text(
                     "user", "hello",
                 )],
+                sampling: Default::default(),
+                system_prefix: None,
             },
         };
         let wire = WireFrame::new(7, frame);
@@ -561,4 +574,466 @@ mod serialization {
         assert_eq!(decoded.flags, 0);
         assert_eq!(decoded.frame.frame_type, ClientFrameType::Chat);
     }
+
+    #[test]
+    fn test_only_config_frames_need_a_sequence() {
+        assert!(ClientFrameType::Reload.mutates_config());
+        assert!(ClientFrameType::ToolPermissionSet.mutates_config());
+        assert!(!ClientFrameType::Chat.mutates_config());
+        assert!(!ClientFrameType::ToolPermissionsRequest.mutates_config());
+    }
+
+    #[test]
+    fn test_client_frame_roundtrip_chat_sampling() {
+        use crate::gateway::protocol::types::{ChatMessage, SamplingParams};
+
+        let sampling = SamplingParams {
+            temperature: Some(0.1),
+            top_p: None,
+            max_tokens: Some(1024),
+            stop: vec!["END".into()],
+        };
+        let frame = ClientFrame {
+            frame_type: ClientFrameType::Chat,
+            payload: ClientPayload::Chat {
+                messages: vec![ChatMessage::text("user", "rename the files")],
+                sampling: sampling.clone(),
+                system_prefix: Some("Respond in JSON.".into()),
+            },
+        };
+
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ClientFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+
+        match decoded.payload {
+            ClientPayload::Chat {
+                messages,
+                sampling: decoded_sampling,
+                system_prefix,
+            } => {
+                assert_eq!(messages.len(), 1);
+                assert_eq!(decoded_sampling, sampling);
+                assert_eq!(system_prefix.as_deref(), Some("Respond in JSON."));
+            }
+            _ => panic!("Expected Chat payload"),
+        }
+    }
+
+    #[test]
+    fn test_client_frame_roundtrip_tool_permission_set() {
+        let frame = ClientFrame {
+            frame_type: ClientFrameType::ToolPermissionSet,
+            payload: ClientPayload::ToolPermissionSet {
+                tool: "execute_command".into(),
+                permission: "skill_only".into(),
+                skills: vec!["deploy".into()],
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ClientFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        match decoded.payload {
+            ClientPayload::ToolPermissionSet {
+                tool,
+                permission,
+                skills,
+            } => {
+                assert_eq!(tool, "execute_command");
+                assert_eq!(permission, "skill_only");
+                assert_eq!(skills, vec!["deploy".to_string()]);
+            }
+            _ => panic!("Expected ToolPermissionSet payload"),
+        }
+    }
+
+    #[test]
+    fn test_client_frame_roundtrip_capabilities() {
+        let frame = ClientFrame {
+            frame_type: ClientFrameType::Capabilities,
+            payload: ClientPayload::Capabilities {
+                capabilities: Capabilities::new(&["stream_deltas", "future_feature"]),
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ClientFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ClientFrameType::Capabilities);
+        match decoded.payload {
+            ClientPayload::Capabilities { capabilities } => {
+                assert_eq!(capabilities.version, CAPABILITIES_VERSION);
+                assert!(capabilities.supports("future_feature"));
+            }
+            _ => panic!("Expected Capabilities payload"),
+        }
+    }
+
+    #[test]
+    fn test_tool_permissions_result_roundtrip() {
+        use crate::tools::ToolPermission;
+
+        let perm = ToolPermission::from_kind("skill_only", vec!["deploy".into(), " ".into()])
+            .expect("skill_only is a known permission");
+        let frame = ServerFrame {
+            frame_type: ServerFrameType::ToolPermissionsResult,
+            payload: ServerPayload::ToolPermissionsResult {
+                ok: true,
+                tools: vec![
+                    ToolPermissionDto::new("execute_command", &perm),
+                    ToolPermissionDto::new("read_file", &ToolPermission::Ask),
+                ],
+                message: None,
+            },
+        };
+
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ServerFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+
+        match decoded.payload {
+            ServerPayload::ToolPermissionsResult { ok, tools, message } => {
+                assert!(ok);
+                assert!(message.is_none());
+                assert_eq!(tools[0].permission, "skill_only");
+                assert_eq!(tools[0].skills, vec!["deploy".to_string()]);
+                assert_eq!(tools[0].summary, "Run shell commands");
+                assert_eq!(tools[1].permission, "ask");
+                assert!(tools[1].skills.is_empty());
+                let back = ToolPermission::from_kind(&tools[0].permission, tools[0].skills.clone());
+                assert_eq!(back, Some(perm));
+            }
+            _ => panic!("Expected ToolPermissionsResult payload"),
+        }
+    }
+
+    #[test]
+    fn test_session_export_roundtrip() {
+        let request = ClientFrame {
+            frame_type: ClientFrameType::ExportSession,
+            payload: ClientPayload::ExportSession {
+                session_id: 42,
+                format: "markdown".into(),
+            },
+        };
+        let bytes = serialize_frame(&request).expect("serialize should succeed");
+        let decoded: ClientFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ClientFrameType::ExportSession);
+        match decoded.payload {
+            ClientPayload::ExportSession { session_id, format } => {
+                assert_eq!(session_id, 42);
+                assert_eq!(format, "markdown");
+            }
+            _ => panic!("Expected ExportSession payload"),
+        }
+
+        let reply = ServerFrame {
+            frame_type: ServerFrameType::SessionExportResult,
+            payload: ServerPayload::SessionExportResult {
+                session_id: 42,
+                ok: true,
+                format: "markdown".into(),
+                content: "# Main\n\n## User\n\nhi\n".into(),
+                error: None,
+            },
+        };
+        let bytes = serialize_frame(&reply).expect("serialize should succeed");
+        let decoded: ServerFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        match decoded.payload {
+            ServerPayload::SessionExportResult {
+                session_id,
+                ok,
+                format,
+                content,
+                error,
+            } => {
+                assert_eq!(session_id, 42);
+                assert!(ok);
+                assert_eq!(format, "markdown");
+                assert!(content.starts_with("# Main"));
+                assert!(error.is_none());
+            }
+            _ => panic!("Expected SessionExportResult payload"),
+        }
+    }
+
+    #[test]
+    fn test_error_frame_carries_code() {
+        let frame = ServerFrame {
+            frame_type: ServerFrameType::Error,
+            payload: ServerPayload::Error {
+                ok: false,
+                message: "Slow down".into(),
+                code: ErrorCode::RateLimited,
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ServerFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        match decoded.payload {
+            ServerPayload::Error { message, code, .. } => {
+                assert_eq!(message, "Slow down");
+                assert_eq!(code, ErrorCode::RateLimited);
+            }
+            _ => panic!("Expected Error payload"),
+        }
+    }
+
+    #[test]
+    fn test_error_code_values_are_stable() {
+        assert_eq!(u16::from(ErrorCode::Other), 0);
+        assert_eq!(u16::from(ErrorCode::AuthFailed), 1);
+        assert_eq!(u16::from(ErrorCode::VaultLocked), 2);
+        assert_eq!(u16::from(ErrorCode::ToolDenied), 7);
+        assert_eq!(u16::from(ErrorCode::Internal), 14);
+        assert_eq!(u16::from(ErrorCode::SafetyRefusal), 15);
+        for code in 0..=15u16 {
+            assert_eq!(u16::from(ErrorCode::from(code)), code);
+        }
+
+        // A code from a newer gateway decodes as the generic one.
+        let bytes = serialize_frame(&999u16).expect("serialize should succeed");
+        let decoded: ErrorCode = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded, ErrorCode::Other);
+    }
+
+    #[test]
+    fn test_session_model_roundtrip() {
+        let frame = ClientFrame {
+            frame_type: ClientFrameType::SessionModel,
+            payload: ClientPayload::SessionModel {
+                provider: "openrouter".into(),
+                model: "anthropic/claude-haiku-4-5".into(),
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ClientFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ClientFrameType::SessionModel);
+        match decoded.payload {
+            ClientPayload::SessionModel { provider, model } => {
+                assert_eq!(provider, "openrouter");
+                assert_eq!(model, "anthropic/claude-haiku-4-5");
+            }
+            _ => panic!("Expected SessionModel payload"),
+        }
+    }
+
+    #[test]
+    fn test_pause_agent_roundtrip() {
+        assert_eq!(ClientFrameType::PauseAgent as u8, 78);
+        assert_eq!(ClientFrameType::ResumeAgent as u8, 79);
+
+        let frame = ClientFrame {
+            frame_type: ClientFrameType::PauseAgent,
+            payload: ClientPayload::PauseAgent {
+                reason: Some("inspecting the workspace".into()),
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ClientFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ClientFrameType::PauseAgent);
+        match decoded.payload {
+            ClientPayload::PauseAgent { reason } => {
+                assert_eq!(reason.as_deref(), Some("inspecting the workspace"));
+            }
+            _ => panic!("Expected PauseAgent payload"),
+        }
+
+        let frame = ClientFrame {
+            frame_type: ClientFrameType::ResumeAgent,
+            payload: ClientPayload::ResumeAgent,
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ClientFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ClientFrameType::ResumeAgent);
+        assert!(matches!(decoded.payload, ClientPayload::ResumeAgent));
+    }
+
+    #[test]
+    fn test_sandbox_policy_reports_initialized_sandbox() {
+        use crate::sandbox::{Sandbox, SandboxMode, SandboxPolicy};
+        use crate::tools::ToolPermission;
+        use std::path::PathBuf;
+
+        // Built the way the gateway's `init_sandbox` builds it.
+        let policy = SandboxPolicy::protect_credentials("/home/u/.rustyclaw/credentials", "/ws")
+            .deny_read("/etc/ssh")
+            .deny_write("/etc/ssh");
+        let sandbox = Sandbox::with_mode(SandboxMode::Docker, policy);
+        let dto = SandboxPolicyDto::new(Some(&sandbox));
+        assert_eq!(dto.mode, "docker");
+        assert_eq!(dto.effective_mode, "docker");
+        assert_eq!(dto.workspace, "/ws");
+        let paths = |paths: &[PathBuf]| -> Vec<String> {
+            paths.iter().map(|p| p.display().to_string()).collect()
+        };
+        assert_eq!(dto.deny_read, paths(&sandbox.policy.deny_read));
+        assert_eq!(dto.deny_write, paths(&sandbox.policy.deny_write));
+        assert_eq!(
+            dto.deny_exec,
+            vec!["/home/u/.rustyclaw/credentials".to_string()]
+        );
+        assert!(dto.allow_paths.is_empty());
+        assert_eq!(dto.network, sandbox.network_policy());
+        assert!(
+            dto.resource_limits
+                .contains(&("memory".to_string(), "2g".to_string()))
+        );
+
+        // With no sandbox initialized, commands run unconfined.
+        let unconfined = SandboxPolicyDto::new(None);
+        assert_eq!(unconfined.mode, "none");
+        assert_eq!(unconfined.network, "unrestricted");
+
+        let frame = ServerFrame {
+            frame_type: ServerFrameType::SandboxPolicyResult,
+            payload: ServerPayload::SandboxPolicyResult {
+                sandbox: dto.clone(),
+                tools: vec![ToolPermissionDto::new(
+                    "execute_command",
+                    &ToolPermission::Deny,
+                )],
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ServerFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        match decoded.payload {
+            ServerPayload::SandboxPolicyResult { sandbox, tools } => {
+                assert_eq!(sandbox, dto);
+                assert_eq!(tools[0].name, "execute_command");
+                assert_eq!(tools[0].permission, "deny");
+            }
+            _ => panic!("Expected SandboxPolicyResult payload"),
+        }
+    }
+
+    #[test]
+    fn test_request_start_roundtrip() {
+        let mut ids = crate::observability::ConnectionIds::new();
+        let first = ids.next_request();
+        let second = ids.next_request();
+        assert_eq!(ids.connection().len(), 8);
+        assert_eq!(first.request, format!("{}-1", ids.connection()));
+        assert_eq!(second.request, format!("{}-2", ids.connection()));
+
+        let frame = ServerFrame {
+            frame_type: ServerFrameType::RequestStart,
+            payload: ServerPayload::RequestStart {
+                connection_id: second.connection.clone(),
+                request_id: second.request.clone(),
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ServerFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ServerFrameType::RequestStart);
+        match decoded.payload {
+            ServerPayload::RequestStart {
+                connection_id,
+                request_id,
+            } => {
+                assert_eq!(connection_id, second.connection);
+                assert_eq!(request_id, second.request);
+            }
+            _ => panic!("Expected RequestStart payload"),
+        }
+    }
+
+    #[test]
+    fn test_turn_resume_roundtrip() {
+        assert_eq!(ClientFrameType::ResumeTurn as u8, 80);
+        assert_eq!(ServerFrameType::TurnStarted as u8, 85);
+
+        let frame = ClientFrame {
+            frame_type: ClientFrameType::ResumeTurn,
+            payload: ClientPayload::ResumeTurn {
+                session_id: 3,
+                turn_id: 41,
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ClientFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ClientFrameType::ResumeTurn);
+        match decoded.payload {
+            ClientPayload::ResumeTurn {
+                session_id,
+                turn_id,
+            } => {
+                assert_eq!(session_id, 3);
+                assert_eq!(turn_id, 41);
+            }
+            _ => panic!("Expected ResumeTurn payload"),
+        }
+
+        let frame = ServerFrame {
+            frame_type: ServerFrameType::TurnStarted,
+            payload: ServerPayload::TurnStarted {
+                session_id: 3,
+                turn_id: 41,
+                resumed: true,
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ServerFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ServerFrameType::TurnStarted);
+        assert!(matches!(
+            decoded.payload,
+            ServerPayload::TurnStarted {
+                session_id: 3,
+                turn_id: 41,
+                resumed: true,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_global_tasks_roundtrip() {
+        assert_eq!(ClientFrameType::GlobalTasksRequest as u8, 81);
+        assert_eq!(ServerFrameType::GlobalTasksResult as u8, 86);
+
+        let frame = ClientFrame {
+            frame_type: ClientFrameType::GlobalTasksRequest,
+            payload: ClientPayload::GlobalTasksRequest {
+                kind: Some("command".into()),
+                status: None,
+                include_finished: true,
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ClientFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ClientFrameType::GlobalTasksRequest);
+        match decoded.payload {
+            ClientPayload::GlobalTasksRequest {
+                kind,
+                status,
+                include_finished,
+            } => {
+                assert_eq!(kind.as_deref(), Some("command"));
+                assert_eq!(status, None);
+                assert!(include_finished);
+            }
+            _ => panic!("Expected GlobalTasksRequest payload"),
+        }
+
+        let mut task = crate::tasks::Task::new(crate::tasks::TaskKind::Command {
+            command: "cargo build".into(),
+            pid: None,
+        })
+        .with_session("thread-1");
+        task.start();
+        let dto = GlobalTaskDto::new(&task);
+        assert_eq!(dto.kind, "command");
+        assert_eq!(dto.status, "running");
+        assert_eq!(dto.label, "cargo build");
+        assert_eq!(dto.session.as_deref(), Some("thread-1"));
+        assert!(dto.started_at.is_some_and(|t| t >= dto.created_at));
+        assert_eq!(dto.finished_at, None);
+
+        let frame = ServerFrame {
+            frame_type: ServerFrameType::GlobalTasksResult,
+            payload: ServerPayload::GlobalTasksResult {
+                tasks: vec![dto.clone()],
+            },
+        };
+        let bytes = serialize_frame(&frame).expect("serialize should succeed");
+        let decoded: ServerFrame = deserialize_frame(&bytes).expect("deserialize should succeed");
+        assert_eq!(decoded.frame_type, ServerFrameType::GlobalTasksResult);
+        match decoded.payload {
+            ServerPayload::GlobalTasksResult { tasks } => assert_eq!(tasks, vec![dto]),
+            _ => panic!("Expected GlobalTasksResult payload"),
+        }
+    }
 }
@@ -10,14 +10,16 @@
 //! Each frame has a type enum as the first field to allow dispatch.
 //! Text frames are not supported and will be rejected.
 
+pub mod capabilities;
 pub mod event_log;
 pub mod frames;
 pub mod server;
 pub mod types;
 
+pub use capabilities::{CAPABILITIES_VERSION, Capabilities};
 pub use frames::{
-    CONTROL_STREAM_ID, ClientFrame, ClientFrameType, ClientPayload, ProjectInfoDto, SecretEntryDto,
-    ServerFrame, ServerFrameType, ServerPayload, ServiceInfoDto, StatusType, TaskInfoDto,
-    ThreadInfoDto, WIRE_PROTOCOL_VERSION, WireFrame, deserialize_frame, deserialize_wire_frame,
-    serialize_frame, serialize_wire_frame,
+    CONTROL_STREAM_ID, ClientFrame, ClientFrameType, ClientPayload, ErrorCode, ProjectInfoDto,
+    SecretEntryDto, ServerFrame, ServerFrameType, ServerPayload, ServiceInfoDto, StatusType,
+    TaskInfoDto, ThreadInfoDto, WIRE_PROTOCOL_VERSION, WireFrame, deserialize_frame,
+    deserialize_wire_frame, serialize_frame, serialize_wire_frame,
 };
@@ -2,11 +2,13 @@
 //!
 //! This module provides helpers for the gateway server to send frames to clients.
 
+use super::capabilities::Capabilities;
 use super::frames::{
-    ClientFrame, SecretEntryDto, ServerFrame, ServerFrameType, ServerPayload, TaskInfoDto,
-    deserialize_frame,
+    ClientFrame, ErrorCode, SecretEntryDto, ServerFrame, ServerFrameType, ServerPayload,
+    TaskInfoDto, deserialize_frame,
 };
 use crate::gateway::transport::TransportWriter;
+use crate::observability::RequestIds;
 use anyhow::Result;
 
 /// Send a ServerFrame via any transport writer.
@@ -27,6 +29,7 @@ pub async fn send_hello(
     vault_locked: bool,
     provider: Option<&str>,
     model: Option<&str>,
+    capabilities: Capabilities,
 ) -> Result<()> {
     let frame = ServerFrame {
         frame_type: ServerFrameType::Hello,
@@ -36,6 +39,7 @@ pub async fn send_hello(
             vault_locked,
             provider: provider.map(|s| s.into()),
             model: model.map(|s| s.into()),
+            capabilities,
         },
     };
     send_frame(writer, &frame).await
@@ -71,12 +75,17 @@ pub async fn send_auth_result(
 }
 
 /// Build and send an error frame.
-pub async fn send_error(writer: &mut dyn TransportWriter, message: &str) -> Result<()> {
+pub async fn send_error(
+    writer: &mut dyn TransportWriter,
+    code: ErrorCode,
+    message: &str,
+) -> Result<()> {
     let frame = ServerFrame {
         frame_type: ServerFrameType::Error,
         payload: ServerPayload::Error {
             ok: false,
             message: message.into(),
+            code,
         },
     };
     send_frame(writer, &frame).await
@@ -367,6 +376,36 @@ pub async fn send_stream_start(writer: &mut dyn TransportWriter) -> Result<()> {
     send_frame(writer, &frame).await
 }
 
+/// Build and send a request start frame naming `ids`.
+pub async fn send_request_start(writer: &mut dyn TransportWriter, ids: &RequestIds) -> Result<()> {
+    let frame = ServerFrame {
+        frame_type: ServerFrameType::RequestStart,
+        payload: ServerPayload::RequestStart {
+            connection_id: ids.connection.clone(),
+            request_id: ids.request.clone(),
+        },
+    };
+    send_frame(writer, &frame).await
+}
+
+/// Build and send a turn-started frame for `turn_id` in `session_id`.
+pub async fn send_turn_started(
+    writer: &mut dyn TransportWriter,
+    session_id: u64,
+    turn_id: u64,
+    resumed: bool,
+) -> Result<()> {
+    let frame = ServerFrame {
+        frame_type: ServerFrameType::TurnStarted,
+        payload: ServerPayload::TurnStarted {
+            session_id,
+            turn_id,
+            resumed,
+        },
+    };
+    send_frame(writer, &frame).await
+}
+
 /// Build and send a thinking-start frame (extended-reasoning block begins).
 pub async fn send_thinking_start(writer: &mut dyn TransportWriter) -> Result<()> {
     let frame = ServerFrame {
@@ -434,19 +473,24 @@ pub async fn send_tool_result(
     send_frame(writer, &frame).await
 }
 
-/// Build and send a tool approval request frame.
+/// Build and send a tool approval request frame.  `can_remember` tells
+/// the client it may offer to approve the tool for the whole session.
 pub async fn send_tool_approval_request(
     writer: &mut dyn TransportWriter,
     id: &str,
     name: &str,
-    arguments: &str,
+    context: &crate::tools::ApprovalContext,
+    can_remember: bool,
 ) -> Result<()> {
     let frame = ServerFrame {
         frame_type: ServerFrameType::ToolApprovalRequest,
         payload: ServerPayload::ToolApprovalRequest {
             id: id.into(),
             name: name.into(),
-            arguments: arguments.into(),
+            arguments: context.arguments.clone(),
+            summary: context.summary.clone(),
+            risks: context.risks.clone(),
+            can_remember,
         },
     };
     send_frame(writer, &frame).await
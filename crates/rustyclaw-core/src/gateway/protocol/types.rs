@@ -147,6 +147,62 @@ impl ChatMessage {
     }
 }
 
+/// Per-turn sampling overrides.
+///
+/// Every field is optional; an absent field leaves the provider's default
+/// in place.  Values are range-checked by [`SamplingParams::validate`] and
+/// anything a given provider or model does not accept is dropped at
+/// dispatch time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingParams {
+    /// Sampling temperature, `0.0..=2.0`.
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// Nucleus sampling mass, `(0.0, 1.0]`.
+    #[serde(default)]
+    pub top_p: Option<f64>,
+    /// Maximum tokens to generate (must be at least 1).
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Sequences that end generation when produced.
+    #[serde(default)]
+    pub stop: Vec<String>,
+}
+
+impl SamplingParams {
+    /// Returns `true` when no override is set.
+    pub fn is_empty(&self) -> bool {
+        self.temperature.is_none()
+            && self.top_p.is_none()
+            && self.max_tokens.is_none()
+            && self.stop.is_empty()
+    }
+
+    /// Check that every set value is within the range providers accept.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(t) = self.temperature {
+            if !(0.0..=2.0).contains(&t) {
+                return Err(format!(
+                    "temperature must be between 0.0 and 2.0 (got {})",
+                    t
+                ));
+            }
+        }
+        if let Some(p) = self.top_p {
+            if p.is_nan() || p <= 0.0 || p > 1.0 {
+                return Err(format!("top_p must be in (0.0, 1.0] (got {})", p));
+            }
+        }
+        if self.max_tokens == Some(0) {
+            return Err("max_tokens must be at least 1".to_string());
+        }
+        if self.stop.iter().any(|s| s.is_empty()) {
+            return Err("stop sequences must not be empty strings".to_string());
+        }
+        Ok(())
+    }
+}
+
 /// A parsed tool call from the model.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedToolCall {
@@ -165,9 +221,13 @@ pub struct ToolCallResult {
 }
 
 /// A complete model response: optional text + optional tool calls.
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModelResponse {
     pub text: String,
+    /// Reasoning the model did before answering, shown to the user but
+    /// never sent back as part of the conversation.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub thinking: String,
     pub tool_calls: Vec<ParsedToolCall>,
     /// The finish reason from the model (e.g., "stop", "tool_calls", "length").
     pub finish_reason: Option<String>,
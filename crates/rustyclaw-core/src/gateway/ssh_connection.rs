@@ -1,11 +1,17 @@
 //! Shared SSH transport for gateway communication.
 //!
-//! `ssh_connect()` spawns an SSH subprocess in `--ssh-stdio` mode and returns
-//! a split reader/writer pair. Both the desktop and TUI clients use this;
-//! the higher-level event mapping stays in each client crate.
+//! `SshConnection::connect()` spawns an SSH subprocess in `--ssh-stdio` mode
+//! and returns a split reader/writer pair. Both the desktop and TUI clients
+//! use this; the higher-level event mapping stays in each client crate.
+//!
+//! A `unix:/path` URL connects to a gateway's Unix domain socket instead
+//! (see `gateway_socket` in the config). The same frames are exchanged, with
+//! no SSH process in between.
+
+use std::path::PathBuf;
 
 use anyhow::{Context, Result, anyhow};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::process::Command;
 
 use super::protocol::frames::ClientFrame;
@@ -15,11 +21,12 @@ use super::protocol::{ServerFrame, WireFrame, deserialize_wire_frame, serialize_
 
 /// Read half of an SSH gateway transport.
 ///
-/// Owns the child's stdout and stderr. Designed to be moved into a dedicated
-/// reader task that calls `recv_wire()` in a loop.
+/// Owns the child's stdout and stderr (or the read half of a Unix socket).
+/// Designed to be moved into a dedicated reader task that calls
+/// `recv_wire()` in a loop.
 pub struct SshReader {
-    stdout: tokio::process::ChildStdout,
-    stderr: tokio::process::ChildStderr,
+    stdout: Box<dyn AsyncRead + Send + Unpin>,
+    stderr: Option<tokio::process::ChildStderr>,
 }
 
 impl SshReader {
@@ -55,23 +62,41 @@ impl SshReader {
 
     /// Drain stderr and return any error text.
     pub async fn drain_stderr(&mut self) -> String {
+        let Some(stderr) = self.stderr.as_mut() else {
+            return String::new();
+        };
         let mut buf = Vec::new();
-        let _ = self.stderr.read_to_end(&mut buf).await;
+        let _ = stderr.read_to_end(&mut buf).await;
         String::from_utf8_lossy(&buf).to_string()
     }
 }
 
 /// Write half of an SSH gateway transport.
 ///
-/// Owns the child's stdin. Designed to be moved into a dedicated writer task.
+/// Owns the child's stdin (or the write half of a Unix socket). Designed to
+/// be moved into a dedicated writer task.
 pub struct SshWriter {
-    stdin: tokio::process::ChildStdin,
+    stdin: Box<dyn AsyncWrite + Send + Unpin>,
+    /// Sequence number of the next frame sent.
+    next_sequence: u64,
 }
 
 impl SshWriter {
+    fn new(stdin: Box<dyn AsyncWrite + Send + Unpin>) -> Self {
+        Self {
+            stdin,
+            next_sequence: 1,
+        }
+    }
+
     /// Send a `ClientFrame` as a length-prefixed bincode wire frame.
+    ///
+    /// Frames are numbered in the order sent, which is what the gateway
+    /// checks config-mutating frames against.
     pub async fn send_frame(&mut self, stream_id: u64, frame: &ClientFrame) -> Result<()> {
-        let wire = WireFrame::new(stream_id, frame.clone());
+        let mut wire = WireFrame::new(stream_id, frame.clone());
+        wire.sequence = self.next_sequence;
+        self.next_sequence += 1;
         let data =
             serialize_wire_frame(&wire).map_err(|e| anyhow!("Failed to serialize frame: {}", e))?;
         self.send_raw(&data).await
@@ -111,14 +136,21 @@ impl SshWriter {
 /// while let Some(wire) = reader.recv_wire().await? { ... }
 /// ```
 pub struct SshConnection {
-    child: tokio::process::Child,
+    /// The SSH subprocess; `None` for a Unix socket connection.
+    child: Option<tokio::process::Child>,
 }
 
 impl SshConnection {
     /// Parse `url` (`ssh://[user@]host[:port]`), spawn an SSH subprocess
     /// running `rustyclaw-gateway run --ssh-stdio`, and return split
     /// reader + writer halves.
+    ///
+    /// A `unix:/path` URL connects to the gateway's Unix socket at `/path`.
     pub async fn connect(url: &str) -> Result<(Self, SshWriter, SshReader)> {
+        if let Some(path) = unix_socket_path(url) {
+            return Self::connect_unix(path).await;
+        }
+
         let parsed =
             url::Url::parse(url).map_err(|e| anyhow!("Invalid SSH URL '{}': {}", url, e))?;
 
@@ -186,18 +218,57 @@ impl SshConnection {
             .ok_or_else(|| anyhow!("SSH stderr unavailable"))?;
 
         Ok((
-            Self { child },
-            SshWriter { stdin },
-            SshReader { stdout, stderr },
+            Self { child: Some(child) },
+            SshWriter::new(Box::new(stdin)),
+            SshReader {
+                stdout: Box::new(stdout),
+                stderr: Some(stderr),
+            },
+        ))
+    }
+
+    #[cfg(unix)]
+    async fn connect_unix(path: PathBuf) -> Result<(Self, SshWriter, SshReader)> {
+        let stream = tokio::net::UnixStream::connect(&path)
+            .await
+            .with_context(|| format!("Failed to connect to {}", path.display()))?;
+        let (read, write) = stream.into_split();
+        Ok((
+            Self { child: None },
+            SshWriter::new(Box::new(write)),
+            SshReader {
+                stdout: Box::new(read),
+                stderr: None,
+            },
         ))
     }
 
-    /// Wait for the child process to exit.
-    pub async fn wait(mut self) -> Result<std::process::ExitStatus> {
-        self.child.wait().await.context("Failed to wait for SSH")
+    #[cfg(not(unix))]
+    async fn connect_unix(path: PathBuf) -> Result<(Self, SshWriter, SshReader)> {
+        anyhow::bail!(
+            "Cannot connect to {}: Unix sockets are not supported on this platform",
+            path.display()
+        )
+    }
+
+    /// Wait for the SSH process to exit; `None` for a Unix socket
+    /// connection, which has no process.
+    pub async fn wait(self) -> Result<Option<std::process::ExitStatus>> {
+        match self.child {
+            Some(mut child) => Ok(Some(child.wait().await.context("Failed to wait for SSH")?)),
+            None => Ok(None),
+        }
     }
 }
 
+/// The socket path in a `unix:/path` (or `unix:///path`) gateway URL.
+pub fn unix_socket_path(url: &str) -> Option<PathBuf> {
+    let rest = url.trim().strip_prefix("unix:")?;
+    // `unix:///path` is the URL-authority spelling of `unix:/path`.
+    let path = rest.strip_prefix("//").unwrap_or(rest);
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
 // ── Helpers ──────────────────────────────────────────────────────────────
 
 /// Fallback: deserialize a bare `ServerFrame` wrapped in a control wire frame.
@@ -207,3 +278,23 @@ fn bare_to_wire_frame(data: &[u8]) -> std::result::Result<WireFrame<ServerFrame>
         .map_err(|e| format!("Bincode decode error: {}", e))?;
     Ok(WireFrame::control(frame))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unix_socket_path() {
+        let expected = Some(PathBuf::from("/run/user/1000/gateway.sock"));
+        assert_eq!(
+            unix_socket_path("unix:/run/user/1000/gateway.sock"),
+            expected
+        );
+        assert_eq!(
+            unix_socket_path("unix:///run/user/1000/gateway.sock"),
+            expected
+        );
+        assert_eq!(unix_socket_path("unix:"), None);
+        assert_eq!(unix_socket_path("ssh://127.0.0.1:2222"), None);
+    }
+}
@@ -35,8 +35,14 @@
 //! - **SSH**: Uses `russh` to accept SSH connections. Clients connect via
 //!   standard SSH and frames are sent over the channel's stdin/stdout.
 //!   Supports both standalone server mode and OpenSSH subsystem mode.
+//! - **Unix**: A Unix domain socket for clients on the gateway host.  The
+//!   same frames travel without an SSH layer; the socket's file
+//!   permissions decide who may connect.
 
-use super::protocol::{CONTROL_STREAM_ID, ClientFrame, ServerFrame, WireFrame};
+use super::protocol::capabilities::{Capabilities, feature};
+use super::protocol::{
+    CONTROL_STREAM_ID, ClientFrame, ServerFrame, ServerFrameType, ServerPayload, WireFrame,
+};
 use anyhow::Result;
 use async_trait::async_trait;
 use std::net::SocketAddr;
@@ -61,6 +67,8 @@ pub enum TransportType {
     Ssh,
     /// SSH subsystem via stdio (OpenSSH subsystem mode).
     SshSubsystem,
+    /// Unix domain socket on the gateway host.
+    Unix,
 }
 
 impl std::fmt::Display for TransportType {
@@ -68,6 +76,7 @@ impl std::fmt::Display for TransportType {
         match self {
             TransportType::Ssh => write!(f, "ssh"),
             TransportType::SshSubsystem => write!(f, "ssh-subsystem"),
+            TransportType::Unix => write!(f, "unix"),
         }
     }
 }
@@ -161,6 +170,100 @@ impl<'a> TransportWriter for ScopedTransportWriter<'a> {
     }
 }
 
+/// Writer adapter that holds back frames the client did not negotiate.
+///
+/// - Without `stream_deltas`, `StreamStart` and `Chunk` frames are buffered
+///   and sent as one `StreamStart` plus one `Chunk` just before the next
+///   other frame (normally `ToolCall` or `ResponseDone`), or on [`flush`].
+/// - Without `thinking`, thinking frames are dropped.
+/// - Without `dom_query`, sending a `DomQuery` fails, so the caller reports
+///   an error instead of waiting for an answer that will never come.
+/// - Without `request_ids`, `RequestStart` frames are dropped.
+/// - Without `resume_turns`, `TurnStarted` frames are dropped.
+///
+/// [`flush`]: NegotiatedWriter::flush
+pub struct NegotiatedWriter<'a> {
+    inner: &'a mut dyn TransportWriter,
+    capabilities: &'a Capabilities,
+    /// Buffered reply text, once a stream has started.
+    pending: Option<String>,
+    stream_id: u64,
+}
+
+impl<'a> NegotiatedWriter<'a> {
+    pub fn new(inner: &'a mut dyn TransportWriter, capabilities: &'a Capabilities) -> Self {
+        Self {
+            inner,
+            capabilities,
+            pending: None,
+            stream_id: CONTROL_STREAM_ID,
+        }
+    }
+
+    /// Send any buffered reply text.
+    pub async fn flush(&mut self) -> Result<()> {
+        let Some(text) = self.pending.take() else {
+            return Ok(());
+        };
+        let start = ServerFrame {
+            frame_type: ServerFrameType::StreamStart,
+            payload: ServerPayload::StreamStart,
+        };
+        self.inner.send_on_stream(self.stream_id, &start).await?;
+        if text.is_empty() {
+            return Ok(());
+        }
+        let chunk = ServerFrame {
+            frame_type: ServerFrameType::Chunk,
+            payload: ServerPayload::Chunk { delta: text },
+        };
+        self.inner.send_on_stream(self.stream_id, &chunk).await
+    }
+}
+
+#[async_trait]
+impl<'a> TransportWriter for NegotiatedWriter<'a> {
+    async fn send_on_stream(&mut self, stream_id: u64, frame: &ServerFrame) -> Result<()> {
+        let caps = self.capabilities;
+        match &frame.payload {
+            ServerPayload::StreamStart if !caps.supports(feature::STREAM_DELTAS) => {
+                self.stream_id = stream_id;
+                self.pending.get_or_insert_with(String::new);
+                return Ok(());
+            }
+            ServerPayload::Chunk { delta } if !caps.supports(feature::STREAM_DELTAS) => {
+                self.stream_id = stream_id;
+                self.pending.get_or_insert_with(String::new).push_str(delta);
+                return Ok(());
+            }
+            ServerPayload::ThinkingStart
+            | ServerPayload::ThinkingDelta { .. }
+            | ServerPayload::ThinkingEnd
+                if !caps.supports(feature::THINKING) =>
+            {
+                return Ok(());
+            }
+            ServerPayload::DomQuery { .. } if !caps.supports(feature::DOM_QUERY) => {
+                anyhow::bail!("client does not support DOM queries");
+            }
+            ServerPayload::RequestStart { .. } if !caps.supports(feature::REQUEST_IDS) => {
+                return Ok(());
+            }
+            ServerPayload::TurnStarted { .. } if !caps.supports(feature::RESUME_TURNS) => {
+                return Ok(());
+            }
+            _ => {}
+        }
+        self.flush().await?;
+        self.inner.send_on_stream(stream_id, frame).await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.flush().await?;
+        self.inner.close().await
+    }
+}
+
 // ============================================================================
 // Transport Acceptor Trait
 // ============================================================================
@@ -180,6 +283,23 @@ pub trait TransportAcceptor: Send + Sync {
     fn local_addr(&self) -> Result<SocketAddr>;
 }
 
+/// Keeps the payload of every frame sent to it, for tests.
+#[cfg(test)]
+pub(crate) struct CollectWriter(pub(crate) Vec<ServerPayload>);
+
+#[cfg(test)]
+#[async_trait]
+impl TransportWriter for CollectWriter {
+    async fn send_on_stream(&mut self, _stream_id: u64, frame: &ServerFrame) -> Result<()> {
+        self.0.push(frame.payload.clone());
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,6 +308,7 @@ mod tests {
     fn test_transport_type_display() {
         assert_eq!(TransportType::Ssh.to_string(), "ssh");
         assert_eq!(TransportType::SshSubsystem.to_string(), "ssh-subsystem");
+        assert_eq!(TransportType::Unix.to_string(), "unix");
     }
 
     #[test]
@@ -201,4 +322,54 @@ mod tests {
         assert_eq!(info.username.as_deref(), Some("test"));
         assert_eq!(info.transport_type, TransportType::Ssh);
     }
+
+    async fn send_reply(writer: &mut dyn TransportWriter) -> Result<()> {
+        use super::super::protocol::server::{
+            send_chunk, send_response_done, send_stream_start, send_thinking_delta,
+            send_thinking_end, send_thinking_start,
+        };
+        send_stream_start(writer).await?;
+        send_thinking_start(writer).await?;
+        send_thinking_delta(writer, "hmm").await?;
+        send_thinking_end(writer).await?;
+        send_chunk(writer, "Hello, ").await?;
+        send_chunk(writer, "world").await?;
+        send_response_done(writer, true).await
+    }
+
+    #[tokio::test]
+    async fn test_negotiated_writer_buffers_deltas() {
+        let caps = Capabilities::new(&[]);
+        let mut sink = CollectWriter(Vec::new());
+        let mut writer = NegotiatedWriter::new(&mut sink, &caps);
+        send_reply(&mut writer).await.unwrap();
+
+        assert_eq!(sink.0.len(), 3, "{:?}", sink.0);
+        assert!(matches!(sink.0[0], ServerPayload::StreamStart));
+        assert!(matches!(&sink.0[1], ServerPayload::Chunk { delta } if delta == "Hello, world"));
+        assert!(matches!(
+            sink.0[2],
+            ServerPayload::ResponseDone { ok: true }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_negotiated_writer_passes_supported_frames() {
+        let caps = Capabilities::gateway();
+        let mut sink = CollectWriter(Vec::new());
+        let mut writer = NegotiatedWriter::new(&mut sink, &caps);
+        send_reply(&mut writer).await.unwrap();
+        assert_eq!(sink.0.len(), 7);
+
+        let dom_query = ServerFrame {
+            frame_type: ServerFrameType::DomQuery,
+            payload: ServerPayload::DomQuery {
+                id: "q1".into(),
+                js: "document.title".into(),
+            },
+        };
+        let legacy = Capabilities::legacy();
+        let mut writer = NegotiatedWriter::new(&mut sink, &legacy);
+        assert!(writer.send(&dom_query).await.is_err());
+    }
 }
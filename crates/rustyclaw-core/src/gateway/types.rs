@@ -7,7 +7,7 @@ use tracing::warn;
 use std::path::PathBuf;
 
 pub use crate::gateway::protocol::types::{
-    ChatMessage, MediaRef, ModelResponse, ParsedToolCall, ToolCallResult,
+    ChatMessage, MediaRef, ModelResponse, ParsedToolCall, SamplingParams, ToolCallResult,
 };
 
 #[derive(Debug, Clone)]
@@ -25,6 +25,9 @@ pub struct GatewayOptions {
     /// When true, the gateway reads/writes frames on stdin/stdout instead
     /// of opening a TCP listener. Used for OpenSSH subsystem integration.
     pub ssh_stdio: bool,
+    /// Unix domain socket to listen on as well, e.g.
+    /// `~/.rustyclaw/gateway.sock`.  Overrides `gateway_socket` in the config.
+    pub unix_socket: Option<PathBuf>,
     /// Path to SSH host key file. Defaults to ~/.rustyclaw/ssh_host_key.
     pub ssh_host_key: Option<PathBuf>,
     /// Path to authorized_clients file. Defaults to ~/.rustyclaw/authorized_clients.
@@ -39,6 +42,7 @@ impl Default for GatewayOptions {
             tls_key: None,
             ssh_listen: None,
             ssh_stdio: false,
+            unix_socket: None,
             ssh_host_key: None,
             ssh_authorized_clients: None,
         }
@@ -47,6 +51,9 @@ impl Default for GatewayOptions {
 
 // ── Chat protocol types ─────────────────────────────────────────────────────
 
+/// Largest [`ChatRequest::system_prefix`] the gateway accepts, in bytes.
+pub const MAX_SYSTEM_PREFIX_BYTES: usize = 8 * 1024;
+
 /// An incoming chat request from the TUI.
 ///
 /// All fields except `messages` and `type` are optional — the gateway fills
@@ -70,6 +77,15 @@ pub struct ChatRequest {
     /// API key / bearer token (optional for providers like Ollama).
     #[serde(default)]
     pub api_key: Option<String>,
+    /// Sampling overrides (`temperature`, `top_p`, `max_tokens`, `stop`),
+    /// accepted as top-level fields.
+    #[serde(flatten)]
+    pub sampling: SamplingParams,
+    /// Extra system instruction for this turn only (e.g. "respond in
+    /// JSON"), placed before the resolved system prompt.  It is never
+    /// stored in the thread, so later turns don't see it.
+    #[serde(default)]
+    pub system_prefix: Option<String>,
 }
 
 /// Fully-resolved request ready for dispatch to a model provider.
@@ -82,6 +98,13 @@ pub struct ProviderRequest {
     pub provider: String,
     pub base_url: String,
     pub api_key: Option<String>,
+    pub sampling: SamplingParams,
+    /// Mark the leading system prompt as a cacheable prefix (Anthropic
+    /// `cache_control`).  Ignored by other providers.
+    pub cache_prompt: bool,
+    /// Ask the model to think first, with up to this many tokens
+    /// (`[thinking]`).  Ignored by models that can't.
+    pub thinking_budget: Option<u32>,
 }
 
 // ── Model context (resolved once at startup) ────────────────────────────────
@@ -120,8 +143,9 @@ impl ModelContext {
         });
 
         let api_key = providers::secret_key_for_provider(&provider).and_then(|key_name| {
+            let ctx = crate::secrets::AccessContext::approved(format!("provider:{}", provider));
             secrets
-                .get_secret(key_name, true)
+                .get_secret_with(key_name, &ctx)
                 .ok()
                 .flatten()
                 .or_else(|| std::env::var(key_name).ok())
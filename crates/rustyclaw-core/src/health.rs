@@ -0,0 +1,261 @@
+//! Gateway health: uptime and recent model-probe results.
+//!
+//! The gateway probes the configured model when a client connects and,
+//! optionally, on a timer in the background.  Every probe is recorded here
+//! so "the model has been unreachable for 3 minutes" is visible before the
+//! next connect — the `gateway` tool's `status` action reports it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+
+use crate::gateway::ProbeResult;
+
+/// Number of probe results kept.
+pub const PROBE_HISTORY_LEN: usize = 20;
+
+/// What caused a probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeTrigger {
+    /// A client connected.
+    Connect,
+    /// The periodic background probe.
+    Background,
+}
+
+/// One model probe.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeRecord {
+    pub at: DateTime<Utc>,
+    pub trigger: ProbeTrigger,
+    pub provider: String,
+    pub model: String,
+    /// `ready`, `connected`, `auth_error` or `unreachable`.
+    pub status: String,
+    /// Whether the model is usable (`ready` or `connected`).
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ProbeRecord {
+    pub fn new(trigger: ProbeTrigger, provider: &str, model: &str, result: &ProbeResult) -> Self {
+        let (status, ok, detail) = match result {
+            ProbeResult::Ready => ("ready", true, None),
+            ProbeResult::Connected { warning } => ("connected", true, Some(warning.clone())),
+            ProbeResult::AuthError { detail } => ("auth_error", false, Some(detail.clone())),
+            ProbeResult::Unreachable { detail } => ("unreachable", false, Some(detail.clone())),
+        };
+        Self {
+            at: Utc::now(),
+            trigger,
+            provider: provider.to_string(),
+            model: model.to_string(),
+            status: status.to_string(),
+            ok,
+            detail,
+        }
+    }
+}
+
+/// Health report returned by [`snapshot`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthSnapshot {
+    /// One-line summary, e.g. "model unreachable for 3m 0s (failed probes: 4), up 2h 5m".
+    pub summary: String,
+    pub started_at: DateTime<Utc>,
+    pub uptime_secs: u64,
+    /// Failed probes since the last successful one.
+    pub consecutive_failures: u32,
+    /// Seconds since the first of the current run of failures.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failing_for_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<DateTime<Utc>>,
+    /// Most recent first.
+    pub probes: Vec<ProbeRecord>,
+}
+
+/// Uptime and probe history for one gateway process.
+#[derive(Debug, Clone)]
+pub struct HealthTracker {
+    started_at: DateTime<Utc>,
+    probes: VecDeque<ProbeRecord>,
+    consecutive_failures: u32,
+    failing_since: Option<DateTime<Utc>>,
+    last_success: Option<DateTime<Utc>>,
+}
+
+impl HealthTracker {
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            started_at,
+            probes: VecDeque::with_capacity(PROBE_HISTORY_LEN),
+            consecutive_failures: 0,
+            failing_since: None,
+            last_success: None,
+        }
+    }
+
+    /// Add a probe result, dropping the oldest beyond [`PROBE_HISTORY_LEN`].
+    pub fn record(&mut self, probe: ProbeRecord) {
+        if probe.ok {
+            self.consecutive_failures = 0;
+            self.failing_since = None;
+            self.last_success = Some(probe.at);
+        } else {
+            self.consecutive_failures += 1;
+            self.failing_since.get_or_insert(probe.at);
+        }
+        if self.probes.len() == PROBE_HISTORY_LEN {
+            self.probes.pop_front();
+        }
+        self.probes.push_back(probe);
+    }
+
+    pub fn snapshot(&self, now: DateTime<Utc>) -> HealthSnapshot {
+        let secs_since = |t: DateTime<Utc>| (now - t).num_seconds().max(0) as u64;
+        let uptime_secs = secs_since(self.started_at);
+        let failing_for_secs = self.failing_since.map(secs_since);
+
+        let summary = match (self.probes.back(), failing_for_secs) {
+            (None, _) => format!("up {}, model not probed yet", format_duration(uptime_secs)),
+            (Some(last), Some(secs)) => format!(
+                "model {} for {} (failed probes: {}), up {}",
+                last.status.replace('_', " "),
+                format_duration(secs),
+                self.consecutive_failures,
+                format_duration(uptime_secs)
+            ),
+            (Some(last), None) => format!(
+                "model {} ({} ago), up {}",
+                last.status,
+                format_duration(secs_since(last.at)),
+                format_duration(uptime_secs)
+            ),
+        };
+
+        HealthSnapshot {
+            summary,
+            started_at: self.started_at,
+            uptime_secs,
+            consecutive_failures: self.consecutive_failures,
+            failing_for_secs,
+            last_success: self.last_success,
+            probes: self.probes.iter().rev().cloned().collect(),
+        }
+    }
+}
+
+/// `90` → `1m 30s`, `7260` → `2h 1m`.
+fn format_duration(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m {}s", s / 60, s % 60),
+        s => format!("{}h {}m", s / 3600, (s % 3600) / 60),
+    }
+}
+
+/// Process-wide tracker; its uptime counts from first use.
+static TRACKER: OnceLock<Mutex<HealthTracker>> = OnceLock::new();
+
+fn tracker() -> &'static Mutex<HealthTracker> {
+    TRACKER.get_or_init(|| Mutex::new(HealthTracker::new(Utc::now())))
+}
+
+/// Start the uptime clock.  Called once at gateway startup.
+pub fn mark_started() {
+    let _ = tracker();
+}
+
+/// Record a model probe.
+pub fn record_probe(probe: ProbeRecord) {
+    if let Ok(mut t) = tracker().lock() {
+        t.record(probe);
+    }
+}
+
+/// Current uptime and probe history.
+pub fn snapshot() -> HealthSnapshot {
+    match tracker().lock() {
+        Ok(t) => t.snapshot(Utc::now()),
+        Err(poisoned) => poisoned.into_inner().snapshot(Utc::now()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn probe(at: DateTime<Utc>, result: ProbeResult) -> ProbeRecord {
+        ProbeRecord {
+            at,
+            ..ProbeRecord::new(ProbeTrigger::Background, "openai", "gpt-4o", &result)
+        }
+    }
+
+    #[test]
+    fn test_consecutive_failures_reset_on_success() {
+        let start = Utc::now();
+        let mut t = HealthTracker::new(start);
+        let down = || ProbeResult::Unreachable {
+            detail: "connection refused".into(),
+        };
+        t.record(probe(start + Duration::seconds(10), down()));
+        t.record(probe(start + Duration::seconds(70), down()));
+
+        let snap = t.snapshot(start + Duration::seconds(190));
+        assert_eq!(snap.consecutive_failures, 2);
+        assert_eq!(snap.failing_for_secs, Some(180));
+        assert_eq!(snap.uptime_secs, 190);
+        assert!(
+            snap.summary
+                .starts_with("model unreachable for 3m 0s (failed probes: 2)")
+        );
+        assert_eq!(snap.probes[0].at, start + Duration::seconds(70));
+
+        t.record(probe(start + Duration::seconds(200), ProbeResult::Ready));
+        let snap = t.snapshot(start + Duration::seconds(200));
+        assert_eq!(snap.consecutive_failures, 0);
+        assert_eq!(snap.failing_for_secs, None);
+        assert_eq!(snap.last_success, Some(start + Duration::seconds(200)));
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let start = Utc::now();
+        let mut t = HealthTracker::new(start);
+        for i in 0..PROBE_HISTORY_LEN + 5 {
+            t.record(probe(
+                start + Duration::seconds(i as i64),
+                ProbeResult::Ready,
+            ));
+        }
+        let snap = t.snapshot(start);
+        assert_eq!(snap.probes.len(), PROBE_HISTORY_LEN);
+        assert_eq!(
+            snap.probes[0].at,
+            start + Duration::seconds(PROBE_HISTORY_LEN as i64 + 4)
+        );
+    }
+
+    #[test]
+    fn test_probe_record_status() {
+        let rec = ProbeRecord::new(
+            ProbeTrigger::Connect,
+            "anthropic",
+            "claude",
+            &ProbeResult::AuthError {
+                detail: "401".into(),
+            },
+        );
+        assert_eq!(rec.status, "auth_error");
+        assert!(!rec.ok);
+        assert_eq!(rec.detail.as_deref(), Some("401"));
+    }
+}
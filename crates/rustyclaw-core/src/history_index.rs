@@ -0,0 +1,421 @@
+//! Full-text index over HISTORY.md.
+//!
+//! HISTORY.md only grows, and after months of use a linear scan for every
+//! `search_history` call reads megabytes.  This module keeps an inverted
+//! index beside it, in `.rustyclaw/history.idx` under the workspace:
+//! for every word, the entries it occurs in and how often.  Queries are
+//! ranked with BM25 and only the matching entries are read back from
+//! HISTORY.md.
+//!
+//! The index is incremental.  It records how many bytes of HISTORY.md it
+//! covers plus a hash of the bytes just before that point; an update reads
+//! only what was appended since.  If the covered bytes changed (the file was
+//! edited or truncated), the index is rebuilt from scratch.
+//!
+//! Updates in this process are serialized, and the index is written to a
+//! temporary file and renamed over the old one, so a reader (or a second
+//! gateway) always sees a complete index.  An index that is missing or
+//! unreadable is simply not used; callers fall back to scanning the file.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use crate::memory_consolidation::{HistoryEntry, STOPWORDS, history_entries};
+
+/// Where the index lives, relative to the workspace.
+pub const INDEX_PATH: &str = ".rustyclaw/history.idx";
+
+/// Bumped whenever the on-disk layout or tokenization changes; an index
+/// with another version is rebuilt.
+const VERSION: u32 = 1;
+
+/// Bytes before the indexed length that are hashed to detect edits.
+const FINGERPRINT_WINDOW: u64 = 1024;
+
+/// BM25 term-frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 document-length normalization.
+const B: f64 = 0.75;
+
+/// Serializes index updates within the process.
+static UPDATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Position of one entry in HISTORY.md.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedEntry {
+    offset: u64,
+    len: u64,
+    /// Indexed words in the entry, for length normalization.
+    terms: u32,
+}
+
+/// Inverted index over the entries of HISTORY.md.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HistoryIndex {
+    version: u32,
+    /// Bytes of HISTORY.md covered by the index.
+    indexed_len: u64,
+    /// SHA-256 of the [`FINGERPRINT_WINDOW`] bytes before `indexed_len`.
+    fingerprint: Vec<u8>,
+    entries: Vec<IndexedEntry>,
+    /// Word → `(entry, occurrences)`, in entry order.
+    postings: HashMap<String, Vec<(u32, u32)>>,
+    total_terms: u64,
+}
+
+/// How an index compares with the history file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexStatus {
+    /// No index, or one that can't be read.
+    Missing,
+    /// The history changed in a way that needs a full rebuild.
+    Outdated,
+    /// Entries were appended since the last update.
+    Behind { bytes: u64 },
+    /// Covers the whole file.
+    Current { entries: usize },
+}
+
+impl HistoryIndex {
+    /// The index file for `workspace`.
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace.join(INDEX_PATH)
+    }
+
+    /// Load the index, or `None` when it is missing or unreadable.
+    pub fn load(workspace: &Path) -> Option<Self> {
+        let bytes = fs::read(Self::path(workspace)).ok()?;
+        let (index, _): (Self, _) =
+            bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).ok()?;
+        (index.version == VERSION).then_some(index)
+    }
+
+    /// Number of indexed entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Compare the index for `workspace` with `history_path`.
+    pub fn status(workspace: &Path, history_path: &Path) -> IndexStatus {
+        let Some(index) = Self::load(workspace) else {
+            return IndexStatus::Missing;
+        };
+        let len = fs::metadata(history_path).map(|m| m.len()).unwrap_or(0);
+        match index.covers(history_path, len) {
+            Ok(true) if len == index.indexed_len => IndexStatus::Current {
+                entries: index.len(),
+            },
+            Ok(true) => IndexStatus::Behind {
+                bytes: len - index.indexed_len,
+            },
+            _ => IndexStatus::Outdated,
+        }
+    }
+
+    /// Index whatever was appended to `history_path` since the last update,
+    /// rebuilding if the covered part changed.  Returns the updated index.
+    pub fn update(workspace: &Path, history_path: &Path) -> Result<Self, String> {
+        let _guard = UPDATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let len = fs::metadata(history_path).map(|m| m.len()).unwrap_or(0);
+        let mut index = match Self::load(workspace) {
+            Some(index) if index.covers(history_path, len)? => index,
+            Some(_) => {
+                debug!("HISTORY.md changed under the index; rebuilding");
+                Self::default()
+            }
+            None => Self::default(),
+        };
+        if index.indexed_len == len && index.version == VERSION {
+            return Ok(index);
+        }
+        index.version = VERSION;
+        index.append_from(history_path, len)?;
+        index.save(workspace)?;
+        Ok(index)
+    }
+
+    /// Discard the index and build it again from `history_path`.
+    pub fn rebuild(workspace: &Path, history_path: &Path) -> Result<Self, String> {
+        let _guard = UPDATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let len = fs::metadata(history_path).map(|m| m.len()).unwrap_or(0);
+        let mut index = Self {
+            version: VERSION,
+            ..Self::default()
+        };
+        index.append_from(history_path, len)?;
+        index.save(workspace)?;
+        Ok(index)
+    }
+
+    /// Entries matching every word of `query`, best first (newer entries
+    /// win ties).
+    ///
+    /// Returns `None` when the query has no indexable words — only
+    /// punctuation or stopwords — so the caller can fall back to a
+    /// substring scan.
+    pub fn search(
+        &self,
+        history_path: &Path,
+        query: &str,
+        max_results: usize,
+    ) -> Result<Option<Vec<HistoryEntry>>, String> {
+        let mut words: Vec<String> = terms(query).collect();
+        words.sort();
+        words.dedup();
+        if words.is_empty() {
+            return Ok(None);
+        }
+
+        let n = self.entries.len() as f64;
+        let avg_len = (self.total_terms as f64 / n.max(1.0)).max(1.0);
+        let mut scores: HashMap<u32, (usize, f64)> = HashMap::new();
+        for word in &words {
+            let Some(postings) = self.postings.get(word) else {
+                return Ok(Some(Vec::new()));
+            };
+            let df = postings.len() as f64;
+            let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+            for &(entry, tf) in postings {
+                let len = self.entries[entry as usize].terms as f64;
+                let tf = tf as f64;
+                let score = idf * tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * len / avg_len));
+                let slot = scores.entry(entry).or_insert((0, 0.0));
+                slot.0 += 1;
+                slot.1 += score;
+            }
+        }
+
+        let mut ranked: Vec<(u32, f64)> = scores
+            .into_iter()
+            .filter(|(_, (matched, _))| *matched == words.len())
+            .map(|(entry, (_, score))| (entry, score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.0.cmp(&a.0)));
+        ranked.truncate(max_results);
+        if ranked.is_empty() {
+            return Ok(Some(Vec::new()));
+        }
+
+        let mut file = File::open(history_path)
+            .map_err(|e| format!("Failed to open {}: {}", history_path.display(), e))?;
+        let mut found = Vec::with_capacity(ranked.len());
+        for (entry, _) in ranked {
+            let entry = &self.entries[entry as usize];
+            let text = read_range(&mut file, entry.offset, entry.len)?;
+            found.extend(history_entries(&text).into_iter().next());
+        }
+        Ok(Some(found))
+    }
+
+    /// Whether the first `indexed_len` bytes of the history still look the
+    /// way they did when they were indexed.
+    fn covers(&self, history_path: &Path, len: u64) -> Result<bool, String> {
+        if len < self.indexed_len {
+            return Ok(false);
+        }
+        Ok(fingerprint(history_path, self.indexed_len)? == self.fingerprint)
+    }
+
+    /// Index the bytes between `indexed_len` and `len`.
+    ///
+    /// `append_history` writes each entry with a single write, so the range
+    /// never ends in the middle of an entry.
+    fn append_from(&mut self, history_path: &Path, len: u64) -> Result<(), String> {
+        let start = self.indexed_len;
+        if len > start {
+            let mut file = File::open(history_path)
+                .map_err(|e| format!("Failed to open {}: {}", history_path.display(), e))?;
+            let tail = read_range(&mut file, start, len - start)?;
+            for (offset, text) in entry_spans(&tail) {
+                self.add_entry(start + offset as u64, text);
+            }
+        }
+        self.indexed_len = len;
+        self.fingerprint = fingerprint(history_path, len)?;
+        Ok(())
+    }
+
+    fn add_entry(&mut self, offset: u64, text: &str) {
+        let id = self.entries.len() as u32;
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        let mut total = 0u32;
+        // Index the whole entry, timestamp included, so dates are searchable.
+        for word in terms(text) {
+            *counts.entry(word).or_default() += 1;
+            total += 1;
+        }
+        for (word, count) in counts {
+            self.postings.entry(word).or_default().push((id, count));
+        }
+        self.entries.push(IndexedEntry {
+            offset,
+            len: text.len() as u64,
+            terms: total,
+        });
+        self.total_terms += u64::from(total);
+    }
+
+    /// Write to a temporary file and rename it into place.
+    fn save(&self, workspace: &Path) -> Result<(), String> {
+        let path = Self::path(workspace);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        let bytes = bincode::serde::encode_to_vec(self, bincode::config::standard())
+            .map_err(|e| format!("Failed to encode history index: {}", e))?;
+        let tmp = path.with_extension(format!("idx.{}.tmp", std::process::id()));
+        fs::write(&tmp, bytes).map_err(|e| format!("Failed to write history index: {}", e))?;
+        fs::rename(&tmp, &path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            format!("Failed to replace history index: {}", e)
+        })
+    }
+}
+
+/// Lowercased words of `text`, without stopwords.
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+}
+
+/// Byte offset and text of each entry in `chunk`.  An entry starts at a
+/// line beginning `[timestamp]` and runs to the next one.
+fn entry_spans(chunk: &str) -> Vec<(usize, &str)> {
+    let mut starts = Vec::new();
+    let mut line_start = 0;
+    for line in chunk.split_inclusive('\n') {
+        if line.starts_with('[') && line.contains(']') {
+            starts.push(line_start);
+        }
+        line_start += line.len();
+    }
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(chunk.len());
+            (start, &chunk[start..end])
+        })
+        .collect()
+}
+
+fn read_range(file: &mut File, offset: u64, len: u64) -> Result<String, String> {
+    let mut buf = Vec::with_capacity(len as usize);
+    file.seek(SeekFrom::Start(offset))
+        .and_then(|_| file.by_ref().take(len).read_to_end(&mut buf))
+        .map_err(|e| format!("Failed to read HISTORY.md: {}", e))?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Hash of the bytes just before `len`.
+fn fingerprint(history_path: &Path, len: u64) -> Result<Vec<u8>, String> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    let start = len.saturating_sub(FINGERPRINT_WINDOW);
+    let mut file = File::open(history_path)
+        .map_err(|e| format!("Failed to open {}: {}", history_path.display(), e))?;
+    let mut buf = Vec::new();
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.by_ref().take(len - start).read_to_end(&mut buf))
+        .map_err(|e| format!("Failed to read HISTORY.md: {}", e))?;
+    Ok(Sha256::digest(&buf).as_slice().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_consolidation::{ConsolidationConfig, MemoryConsolidation};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_index_ranks_and_updates_incrementally() {
+        let dir = tempdir().unwrap();
+        let history = dir.path().join("HISTORY.md");
+        let consolidation = MemoryConsolidation::new(ConsolidationConfig::default());
+        for entry in [
+            "Met Alice about the parser rewrite",
+            "Fixed a bug in the parser",
+            "Alice, Alice and Bob reviewed the parser benchmarks",
+        ] {
+            consolidation.append_history(dir.path(), entry).unwrap();
+        }
+
+        let index = HistoryIndex::load(dir.path()).unwrap();
+        assert_eq!(index.len(), 3);
+        let found = index.search(&history, "alice PARSER", 10).unwrap().unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(found[0].text.starts_with("Alice, Alice and Bob"));
+        assert!(
+            index
+                .search(&history, "carol", 10)
+                .unwrap()
+                .unwrap()
+                .is_empty()
+        );
+        assert!(index.search(&history, "the", 10).unwrap().is_none());
+
+        // Appends are picked up without re-reading what was indexed.
+        consolidation
+            .append_history(dir.path(), "Lunch with Carol")
+            .unwrap();
+        assert_eq!(
+            HistoryIndex::status(dir.path(), &history),
+            IndexStatus::Current { entries: 4 }
+        );
+        let index = HistoryIndex::load(dir.path()).unwrap();
+        let found = index.search(&history, "carol", 10).unwrap().unwrap();
+        assert_eq!(found[0].text, "Lunch with Carol");
+    }
+
+    #[test]
+    fn test_index_rebuilds_after_history_is_edited() {
+        let dir = tempdir().unwrap();
+        let history = dir.path().join("HISTORY.md");
+        let consolidation = MemoryConsolidation::new(ConsolidationConfig::default());
+        consolidation
+            .append_history(dir.path(), "Deployed v2 to staging")
+            .unwrap();
+        consolidation
+            .append_history(dir.path(), "Rolled back staging")
+            .unwrap();
+
+        fs::write(&history, "\n[2025-01-01 09:00 UTC] Trimmed history\n").unwrap();
+        assert_eq!(
+            HistoryIndex::status(dir.path(), &history),
+            IndexStatus::Outdated
+        );
+        let index = HistoryIndex::update(dir.path(), &history).unwrap();
+        assert_eq!(index.len(), 1);
+        assert!(
+            index
+                .search(&history, "staging", 10)
+                .unwrap()
+                .unwrap()
+                .is_empty()
+        );
+
+        fs::remove_file(HistoryIndex::path(dir.path())).unwrap();
+        assert_eq!(
+            HistoryIndex::status(dir.path(), &history),
+            IndexStatus::Missing
+        );
+        assert_eq!(
+            HistoryIndex::rebuild(dir.path(), &history).unwrap().len(),
+            1
+        );
+    }
+}
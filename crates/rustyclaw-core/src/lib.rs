@@ -13,6 +13,7 @@
 //! - [`skills`] — dynamic skill loading from the workspace directory
 //! - [`soul`] — agent personality definition (`SOUL.md`)
 
+pub mod agent_pause;
 pub mod args;
 pub mod auto_fetch;
 pub mod canvas;
@@ -25,8 +26,11 @@ pub mod engines;
 pub mod error;
 pub mod error_details;
 pub mod gateway;
+pub mod health;
+pub mod history_index;
 pub mod host;
 pub mod load;
+pub mod log_rotation;
 pub mod logging;
 pub mod markdown;
 pub mod mcp;
@@ -49,6 +53,7 @@ pub mod sandbox;
 pub mod secrets;
 pub mod security;
 pub mod services;
+pub mod session_workspace;
 pub mod sessions;
 pub mod skills;
 pub mod soul;
@@ -0,0 +1,230 @@
+//! Size- and age-based rotation shared by every log RustyClaw writes.
+//!
+//! A [`LogRotator`] renames a full log to `<path>.1`, shifting older
+//! rotations up to `<path>.<max_files>` and dropping the one beyond, then
+//! deletes rotations whose last write is older than the age limit.  The
+//! secret access log, the provider wire log, the protocol event log and the
+//! daemon's `gateway.log` all roll this way, with the thresholds from
+//! `[log_rotation]`.
+//!
+//! Each binary calls [`init`] once after loading its config; sinks that have
+//! no config at hand read the thresholds back with [`global`].
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use crate::config::LogRotationConfig;
+
+static GLOBAL: OnceLock<LogRotator> = OnceLock::new();
+
+/// Set the process-wide thresholds from `[log_rotation]`.  Later calls are
+/// ignored.
+pub fn init(config: &LogRotationConfig) {
+    let _ = GLOBAL.set(LogRotator::from_config(config));
+}
+
+/// The process-wide thresholds, or the defaults before [`init`].
+pub fn global() -> LogRotator {
+    GLOBAL
+        .get()
+        .copied()
+        .unwrap_or_else(|| LogRotator::from_config(&LogRotationConfig::default()))
+}
+
+/// When to roll a log file and how many rolled files to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogRotator {
+    max_bytes: u64,
+    max_files: usize,
+    max_age: Option<Duration>,
+}
+
+impl LogRotator {
+    /// A rotator that rolls past `max_bytes`, keeps `max_files` rotations
+    /// and prunes those older than `max_age` (never, when `None`).
+    pub fn new(max_bytes: u64, max_files: usize, max_age: Option<Duration>) -> Self {
+        Self {
+            max_bytes,
+            max_files,
+            max_age,
+        }
+    }
+
+    pub fn from_config(config: &LogRotationConfig) -> Self {
+        let max_age = (config.max_age_days > 0)
+            .then(|| Duration::from_secs(config.max_age_days * 24 * 60 * 60));
+        Self::new(config.max_file_bytes, config.max_files, max_age)
+    }
+
+    /// The same limits with a different size threshold.
+    pub fn with_max_bytes(self, max_bytes: u64) -> Self {
+        Self { max_bytes, ..self }
+    }
+
+    /// The same limits with a different number of rotations kept.
+    pub fn with_max_files(self, max_files: usize) -> Self {
+        Self { max_files, ..self }
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    pub fn max_files(&self) -> usize {
+        self.max_files
+    }
+
+    /// Whether a log holding `size` bytes must roll before `incoming` more
+    /// are written.  An empty log never rolls, however long the line.
+    pub fn should_rotate(&self, size: u64, incoming: u64) -> bool {
+        size > 0 && size.saturating_add(incoming) > self.max_bytes
+    }
+
+    /// Roll `path` to `<path>.1` if writing `incoming` bytes would take it
+    /// past the size limit.  Returns whether it rolled.
+    pub fn rotate_if_needed(&self, path: &Path, incoming: u64) -> bool {
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if self.should_rotate(size, incoming) {
+            self.rotate(path);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Roll `path` now and prune rotations past the age limit.  With
+    /// `max_files` at zero the log is simply removed.
+    pub fn rotate(&self, path: &Path) {
+        if self.max_files == 0 {
+            let _ = std::fs::remove_file(path);
+            return;
+        }
+        let _ = std::fs::remove_file(rotated_path(path, self.max_files));
+        for n in (1..self.max_files).rev() {
+            let _ = std::fs::rename(rotated_path(path, n), rotated_path(path, n + 1));
+        }
+        let _ = std::fs::rename(path, rotated_path(path, 1));
+        self.prune(path);
+    }
+
+    /// Delete rotations of `path` last written before the age limit, and
+    /// any numbered past `max_files`.  The live file is never touched.
+    /// Returns how many files were removed.
+    pub fn prune(&self, path: &Path) -> usize {
+        let now = SystemTime::now();
+        let mut removed = 0;
+        for (n, file) in rotated_files(path).into_iter().enumerate() {
+            let too_many = n >= self.max_files;
+            let too_old = self.max_age.is_some_and(|max_age| {
+                std::fs::metadata(&file)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|modified| now.duration_since(modified).ok())
+                    .is_some_and(|age| age > max_age)
+            });
+            if (too_many || too_old) && std::fs::remove_file(&file).is_ok() {
+                removed += 1;
+            }
+        }
+        removed
+    }
+}
+
+/// `<path>.<n>`: the `n`th most recent rotation of `path`.
+pub fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+/// The rotations of `path` that exist, newest (`.1`) first.  Numbering
+/// stops at the first gap.
+pub fn rotated_files(path: &Path) -> Vec<PathBuf> {
+    (1..)
+        .map(|n| rotated_path(path, n))
+        .take_while(|file| file.exists())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn append(rotator: &LogRotator, path: &Path, line: &str) {
+        rotator.rotate_if_needed(path, line.len() as u64 + 1);
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        writeln!(file, "{}", line).unwrap();
+    }
+
+    #[test]
+    fn test_rotates_past_size_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let rotator = LogRotator::new(100, 2, None);
+
+        append(&rotator, &path, &"a".repeat(60));
+        assert!(!rotated_path(&path, 1).exists());
+        append(&rotator, &path, &"b".repeat(60));
+        let rolled = std::fs::read_to_string(rotated_path(&path, 1)).unwrap();
+        assert!(rolled.starts_with('a'));
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with('b'));
+
+        for c in ['c', 'd', 'e'] {
+            append(&rotator, &path, &c.to_string().repeat(60));
+        }
+        assert_eq!(rotated_files(&path).len(), 2);
+        let newest = std::fs::read_to_string(rotated_path(&path, 1)).unwrap();
+        assert!(newest.starts_with('d'));
+        assert!(std::fs::metadata(&path).unwrap().len() <= 100);
+    }
+
+    #[test]
+    fn test_zero_max_files_discards_full_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.log");
+        let rotator = LogRotator::new(10, 0, None);
+        append(&rotator, &path, "first line");
+        append(&rotator, &path, "second line");
+        assert!(rotated_files(&path).is_empty());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second line\n");
+    }
+
+    #[test]
+    fn test_prunes_rotations_past_age_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gateway.log");
+        std::fs::write(&path, "live").unwrap();
+        for n in 1..=3 {
+            std::fs::write(rotated_path(&path, n), format!("rotation {}", n)).unwrap();
+        }
+        let ten_days_ago = SystemTime::now() - Duration::from_secs(10 * 24 * 60 * 60);
+        for n in 2..=3 {
+            std::fs::File::options()
+                .write(true)
+                .open(rotated_path(&path, n))
+                .unwrap()
+                .set_modified(ten_days_ago)
+                .unwrap();
+        }
+
+        let rotator = LogRotator::new(1024, 5, Some(Duration::from_secs(7 * 24 * 60 * 60)));
+        assert_eq!(rotator.prune(&path), 2);
+        assert!(path.exists());
+        assert_eq!(rotated_files(&path), vec![rotated_path(&path, 1)]);
+
+        // Without an age limit nothing is pruned for age.
+        std::fs::File::options()
+            .write(true)
+            .open(rotated_path(&path, 1))
+            .unwrap()
+            .set_modified(ten_days_ago)
+            .unwrap();
+        assert_eq!(LogRotator::new(1024, 5, None).prune(&path), 0);
+    }
+}
@@ -1,10 +1,14 @@
 //! Memory search and retrieval for RustyClaw.
 //!
-//! Provides semantic-like search over `MEMORY.md` and `memory/*.md` files.
-//! Current implementation uses keyword/BM25-style matching with temporal decay
-//! for recency weighting. Embeddings can be added later for true semantic search.
+//! Provides semantic-like search over `MEMORY.md`, `HISTORY.md`, and
+//! `memory/*.md` files. Current implementation uses keyword/BM25-style
+//! matching with temporal decay for recency weighting. Embeddings can be added
+//! later for true semantic search.
+//!
+//! A [`MemoryFilter`] can restrict the index to a date range and a file glob;
+//! filtering is applied while indexing, so excluded chunks are never scored.
 
-use chrono::{NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
@@ -20,6 +24,9 @@ pub struct MemoryChunk {
     pub end_line: usize,
     /// The text content of this chunk.
     pub text: String,
+    /// When the chunk was written: the entry timestamp for `HISTORY.md`,
+    /// otherwise the file's modification time.
+    pub timestamp: Option<NaiveDateTime>,
 }
 
 /// A search result with relevance score.
@@ -56,18 +63,41 @@ impl MemoryIndex {
 
     /// Index all memory files in a workspace.
     pub fn index_workspace(workspace: &Path) -> Result<Self, String> {
+        Self::index_workspace_filtered(workspace, &MemoryFilter::default())
+    }
+
+    /// Index the memory files in a workspace that pass `filter`.
+    ///
+    /// Files outside the glob are skipped without being read, and chunks
+    /// outside the date range are dropped before the inverted index is built.
+    pub fn index_workspace_filtered(
+        workspace: &Path,
+        filter: &MemoryFilter,
+    ) -> Result<Self, String> {
         let mut index = Self::new();
 
         // Index MEMORY.md if it exists
         let memory_md = workspace.join("MEMORY.md");
-        if memory_md.exists() {
-            index.index_file(&memory_md, "MEMORY.md")?;
+        if memory_md.exists() && filter.matches_path("MEMORY.md") {
+            index.index_file(&memory_md, "MEMORY.md", filter)?;
+        }
+
+        // Index HISTORY.md entry-by-entry (each entry carries a timestamp)
+        let history_md = workspace.join(HISTORY_FILE);
+        if history_md.exists() && filter.matches_path(HISTORY_FILE) {
+            let content = fs::read_to_string(&history_md)
+                .map_err(|e| format!("Failed to read {}: {}", HISTORY_FILE, e))?;
+            index.chunks.extend(
+                chunk_history(&content)
+                    .into_iter()
+                    .filter(|c| filter.matches_time(c.timestamp)),
+            );
         }
 
         // Index memory/*.md
         let memory_dir = workspace.join("memory");
         if memory_dir.exists() && memory_dir.is_dir() {
-            index.index_directory(&memory_dir, "memory")?;
+            index.index_directory(&memory_dir, "memory", filter)?;
         }
 
         // Build inverted index
@@ -77,20 +107,38 @@ impl MemoryIndex {
     }
 
     /// Index a single file.
-    fn index_file(&mut self, path: &Path, relative_path: &str) -> Result<(), String> {
+    fn index_file(
+        &mut self,
+        path: &Path,
+        relative_path: &str,
+        filter: &MemoryFilter,
+    ) -> Result<(), String> {
+        let modified = file_mtime(path);
+        if !filter.matches_time(modified) {
+            return Ok(());
+        }
+
         let content = fs::read_to_string(path)
             .map_err(|e| format!("Failed to read {}: {}", relative_path, e))?;
 
         // Split into chunks (~400 tokens target, roughly 300-400 words)
         // For simplicity, we chunk by paragraphs or heading sections
-        let chunks = self.chunk_content(&content, relative_path);
+        let mut chunks = self.chunk_content(&content, relative_path);
+        for chunk in &mut chunks {
+            chunk.timestamp = modified;
+        }
         self.chunks.extend(chunks);
 
         Ok(())
     }
 
     /// Index a directory recursively.
-    fn index_directory(&mut self, dir: &Path, relative_prefix: &str) -> Result<(), String> {
+    fn index_directory(
+        &mut self,
+        dir: &Path,
+        relative_prefix: &str,
+        filter: &MemoryFilter,
+    ) -> Result<(), String> {
         let entries = fs::read_dir(dir)
             .map_err(|e| format!("Failed to read directory {}: {}", relative_prefix, e))?;
 
@@ -100,9 +148,11 @@ impl MemoryIndex {
             let relative = format!("{}/{}", relative_prefix, name);
 
             if path.is_file() && name.ends_with(".md") {
-                self.index_file(&path, &relative)?;
+                if filter.matches_path(&relative) {
+                    self.index_file(&path, &relative, filter)?;
+                }
             } else if path.is_dir() && !name.starts_with('.') {
-                self.index_directory(&path, &relative)?;
+                self.index_directory(&path, &relative, filter)?;
             }
         }
 
@@ -136,6 +186,7 @@ impl MemoryIndex {
                     start_line: chunk_start,
                     end_line: line_num - 1,
                     text: current_chunk.trim().to_string(),
+                    timestamp: None,
                 });
                 current_chunk = String::new();
                 chunk_start = line_num;
@@ -154,6 +205,7 @@ impl MemoryIndex {
                 start_line: chunk_start,
                 end_line: lines.len(),
                 text: current_chunk.trim().to_string(),
+                timestamp: None,
             });
         }
 
@@ -329,6 +381,170 @@ impl MemoryIndex {
     }
 }
 
+/// Timestamped log written by `save_memory` (relative to workspace).
+const HISTORY_FILE: &str = "HISTORY.md";
+
+/// Split `HISTORY.md` into one chunk per `[timestamp] entry`.
+///
+/// Text before the first entry (e.g. a title) becomes an undated chunk.
+fn chunk_history(content: &str) -> Vec<MemoryChunk> {
+    let mut chunks: Vec<MemoryChunk> = Vec::new();
+    let mut current: Option<MemoryChunk> = None;
+
+    for (i, line) in content.lines().enumerate() {
+        let line_num = i + 1;
+        let stamp = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .and_then(|(ts, _)| parse_date_lenient(ts));
+
+        if stamp.is_some() || current.is_none() {
+            if let Some(chunk) = current.take() {
+                if !chunk.text.trim().is_empty() {
+                    chunks.push(chunk);
+                }
+            }
+            current = Some(MemoryChunk {
+                path: HISTORY_FILE.to_string(),
+                start_line: line_num,
+                end_line: line_num,
+                text: String::new(),
+                timestamp: stamp,
+            });
+        }
+
+        if let Some(chunk) = current.as_mut() {
+            if !line.trim().is_empty() {
+                chunk.end_line = line_num;
+            }
+            chunk.text.push_str(line);
+            chunk.text.push('\n');
+        }
+    }
+
+    if let Some(chunk) = current {
+        if !chunk.text.trim().is_empty() {
+            chunks.push(chunk);
+        }
+    }
+    for chunk in &mut chunks {
+        chunk.text = chunk.text.trim().to_string();
+    }
+    chunks
+}
+
+/// Modification time of a file as a UTC timestamp.
+fn file_mtime(path: &Path) -> Option<NaiveDateTime> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(DateTime::<Utc>::from(modified).naive_utc())
+}
+
+/// Parse a date or datetime leniently.
+///
+/// Accepts `2026-02-20`, `2026/02/20`, `2026-02-20 14:30`,
+/// `2026-02-20T14:30:00`, RFC 3339 (`2026-02-20T14:30:00Z`), and the
+/// `HISTORY.md` style `2026-02-20 14:30 UTC`. Bare dates resolve to midnight.
+pub fn parse_date_lenient(input: &str) -> Option<NaiveDateTime> {
+    let s = input.trim();
+    let s = s.strip_suffix("UTC").map(str::trim_end).unwrap_or(s);
+    if s.is_empty() {
+        return None;
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.naive_utc());
+    }
+    for fmt in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, fmt) {
+            return Some(dt);
+        }
+    }
+    for fmt in ["%Y-%m-%d", "%Y/%m/%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(s, fmt) {
+            return date.and_hms_opt(0, 0, 0);
+        }
+    }
+    None
+}
+
+/// Restricts which memory chunks are searched.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFilter {
+    /// Only include chunks written at or after this time.
+    pub after: Option<NaiveDateTime>,
+    /// Only include chunks written before this time.
+    pub before: Option<NaiveDateTime>,
+    /// Only include files whose workspace-relative path matches this glob.
+    pub files: Option<glob::Pattern>,
+}
+
+impl MemoryFilter {
+    /// Whether no filter is active.
+    pub fn is_empty(&self) -> bool {
+        self.after.is_none() && self.before.is_none() && self.files.is_none()
+    }
+
+    /// Whether a file (workspace-relative path) is in scope.
+    ///
+    /// Patterns without a `/` are also matched against the bare file name,
+    /// so `2026-02-*.md` works as well as `memory/2026-02-*.md`.
+    pub fn matches_path(&self, relative_path: &str) -> bool {
+        let Some(pattern) = &self.files else {
+            return true;
+        };
+        if pattern.matches(relative_path) {
+            return true;
+        }
+        !pattern.as_str().contains('/')
+            && relative_path
+                .rsplit('/')
+                .next()
+                .is_some_and(|name| pattern.matches(name))
+    }
+
+    /// Whether a chunk timestamp is within the date range.
+    ///
+    /// Undated chunks are excluded whenever a date bound is set.
+    pub fn matches_time(&self, timestamp: Option<NaiveDateTime>) -> bool {
+        if self.after.is_none() && self.before.is_none() {
+            return true;
+        }
+        let Some(ts) = timestamp else {
+            return false;
+        };
+        self.after.is_none_or(|after| ts >= after) && self.before.is_none_or(|before| ts < before)
+    }
+
+    /// Human-readable summary of the active filters, e.g.
+    /// `after 2026-02-01, files memory/*.md`.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(after) = self.after {
+            parts.push(format!("after {}", format_bound(after)));
+        }
+        if let Some(before) = self.before {
+            parts.push(format!("before {}", format_bound(before)));
+        }
+        if let Some(files) = &self.files {
+            parts.push(format!("files {}", files.as_str()));
+        }
+        parts.join(", ")
+    }
+}
+
+fn format_bound(ts: NaiveDateTime) -> String {
+    if ts.time() == chrono::NaiveTime::MIN {
+        ts.format("%Y-%m-%d").to_string()
+    } else {
+        ts.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
 /// Files that should never be decayed (evergreen).
 #[allow(dead_code)]
 const EVERGREEN_FILES: &[&str] = &["MEMORY.md"];
@@ -391,8 +607,8 @@ pub fn read_memory_file(
 
 /// Check if a path is a valid memory file path.
 fn is_valid_memory_path(path: &str) -> bool {
-    // Must be MEMORY.md or within memory/ directory
-    if path == "MEMORY.md" {
+    // Must be MEMORY.md, HISTORY.md, or within memory/ directory
+    if path == "MEMORY.md" || path == HISTORY_FILE {
         return true;
     }
 
@@ -576,4 +792,95 @@ mod tests {
         // The more recent file should be first
         assert!(results[0].chunk.path.contains("2026-02"));
     }
+
+    #[test]
+    fn test_parse_date_lenient() {
+        let day = NaiveDate::from_ymd_opt(2026, 2, 20).unwrap();
+        let midnight = day.and_hms_opt(0, 0, 0).unwrap();
+        let afternoon = day.and_hms_opt(14, 30, 0).unwrap();
+
+        assert_eq!(parse_date_lenient("2026-02-20"), Some(midnight));
+        assert_eq!(parse_date_lenient(" 2026/02/20 "), Some(midnight));
+        assert_eq!(parse_date_lenient("2026-02-20 14:30"), Some(afternoon));
+        assert_eq!(parse_date_lenient("2026-02-20T14:30:00"), Some(afternoon));
+        assert_eq!(parse_date_lenient("2026-02-20T14:30:00Z"), Some(afternoon));
+        assert_eq!(parse_date_lenient("2026-02-20 14:30 UTC"), Some(afternoon));
+        assert_eq!(parse_date_lenient("last week"), None);
+        assert_eq!(parse_date_lenient(""), None);
+    }
+
+    #[test]
+    fn test_chunk_history_entries() {
+        let history = "# History\n\n[2026-02-10 09:00 UTC] Chose JWT for auth.\n\n\
+                       [2026-02-18 16:45 UTC] Switched auth to session cookies.\nMore detail.\n";
+        let chunks = chunk_history(history);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].timestamp, None);
+        assert!(chunks[1].text.contains("JWT"));
+        assert_eq!(chunks[2].timestamp, parse_date_lenient("2026-02-18 16:45"));
+        assert!(chunks[2].text.ends_with("More detail."));
+        assert_eq!(chunks[2].start_line, 5);
+        assert_eq!(chunks[2].end_line, 6);
+    }
+
+    #[test]
+    fn test_filter_by_date_uses_history_timestamps() {
+        let workspace = setup_test_workspace();
+        fs::write(
+            workspace.path().join("HISTORY.md"),
+            "\n[2026-02-10 09:00 UTC] Decided auth uses JWT.\n\n\
+             [2026-02-18 16:45 UTC] Decided auth uses session cookies.\n",
+        )
+        .unwrap();
+
+        let filter = MemoryFilter {
+            after: parse_date_lenient("2026-02-15"),
+            before: parse_date_lenient("2026-02-20"),
+            files: None,
+        };
+        let index = MemoryIndex::index_workspace_filtered(workspace.path(), &filter).unwrap();
+        let results = index.search("auth decided", 5);
+
+        // Only the in-range history entry survives; files written today
+        // (by the test setup) fall outside the range.
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.path, "HISTORY.md");
+        assert!(results[0].chunk.text.contains("session cookies"));
+    }
+
+    #[test]
+    fn test_filter_by_files_glob() {
+        let workspace = setup_test_workspace();
+        let filter = MemoryFilter {
+            files: Some(glob::Pattern::new("2026-02-*.md").unwrap()),
+            ..Default::default()
+        };
+        assert!(filter.matches_path("memory/2026-02-12.md"));
+        assert!(!filter.matches_path("MEMORY.md"));
+
+        let index = MemoryIndex::index_workspace_filtered(workspace.path(), &filter).unwrap();
+        assert!(
+            index
+                .chunks
+                .iter()
+                .all(|c| c.path == "memory/2026-02-12.md")
+        );
+        assert!(index.search("dark mode", 5).is_empty());
+        assert!(!index.search("BM25 search", 5).is_empty());
+    }
+
+    #[test]
+    fn test_filter_describe() {
+        assert!(MemoryFilter::default().is_empty());
+        let filter = MemoryFilter {
+            after: parse_date_lenient("2026-02-01"),
+            before: parse_date_lenient("2026-02-20 14:30"),
+            files: Some(glob::Pattern::new("memory/*.md").unwrap()),
+        };
+        assert!(!filter.is_empty());
+        assert_eq!(
+            filter.describe(),
+            "after 2026-02-01, before 2026-02-20 14:30, files memory/*.md"
+        );
+    }
 }
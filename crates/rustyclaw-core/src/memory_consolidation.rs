@@ -2,16 +2,27 @@
 //!
 //! This module implements LLM-driven memory consolidation with two layers:
 //! - **MEMORY.md**: Long-term facts, curated by the LLM
-//! - **HISTORY.md**: Grep-searchable timestamped log
+//! - **HISTORY.md**: Searchable timestamped log, backed by a full-text
+//!   index (see [`crate::history_index`])
 //!
 //! The LLM calls `save_memory` to consolidate conversation history, deciding
 //! what facts to keep in MEMORY.md and what to log in HISTORY.md.
+//!
+//! Facts added one at a time are compared with what MEMORY.md already holds
+//! so the same fact is not recorded over and over.  Similarity is lexical
+//! (word overlap after normalization), which catches rephrasings like "User
+//! prefers dark mode." vs "the user prefers dark mode" without needing an
+//! embedding model.
 
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::Path;
+use tracing::warn;
+
+use crate::history_index::HistoryIndex;
 
 /// Result of a memory consolidation operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +61,11 @@ pub struct ConsolidationConfig {
     /// Path to HISTORY.md (relative to workspace).
     #[serde(default = "default_history_path")]
     pub history_path: String,
+
+    /// Similarity (0.0–1.0) at which a new fact counts as a duplicate of an
+    /// existing one.  Values above 1.0 turn deduplication off.
+    #[serde(default = "default_dedup_threshold")]
+    pub dedup_threshold: f64,
 }
 
 fn default_true() -> bool {
@@ -72,6 +88,10 @@ fn default_history_path() -> String {
     "HISTORY.md".to_string()
 }
 
+fn default_dedup_threshold() -> f64 {
+    0.8
+}
+
 impl Default for ConsolidationConfig {
     fn default() -> Self {
         Self {
@@ -80,6 +100,7 @@ impl Default for ConsolidationConfig {
             memory_max_size: default_memory_max_size(),
             memory_path: default_memory_path(),
             history_path: default_history_path(),
+            dedup_threshold: default_dedup_threshold(),
         }
     }
 }
@@ -126,7 +147,8 @@ impl MemoryConsolidation {
 
     /// Save a history entry (append to HISTORY.md).
     ///
-    /// This is called by the `save_memory` tool to log timestamped entries.
+    /// This is called by the `save_memory` tool to log timestamped entries,
+    /// and brings the history index up to date.
     pub fn append_history(&self, workspace: &Path, entry: &str) -> Result<usize, String> {
         let history_path = workspace.join(&self.config.history_path);
 
@@ -150,6 +172,11 @@ impl MemoryConsolidation {
         let metadata = fs::metadata(&history_path)
             .map_err(|e| format!("Failed to read HISTORY.md metadata: {}", e))?;
 
+        // The entry is saved either way; a stale index only slows searches.
+        if let Err(e) = HistoryIndex::update(workspace, &history_path) {
+            warn!(error = %e, "Failed to update the history index");
+        }
+
         Ok(metadata.len() as usize)
     }
 
@@ -179,6 +206,100 @@ impl MemoryConsolidation {
         Ok(size)
     }
 
+    /// Add facts to MEMORY.md, skipping or replacing near-duplicates.
+    ///
+    /// Each fact is compared with every fact line already in the file (and
+    /// with the facts added before it).  Above `dedup_threshold` it is
+    /// skipped when it adds no new words, and otherwise replaces the
+    /// existing line, keeping its bullet and position.  Anything else is
+    /// appended as a new bullet.  Returns what happened to each fact and the
+    /// new file size.
+    pub fn add_facts(
+        &self,
+        workspace: &Path,
+        facts: &[String],
+    ) -> Result<(Vec<FactOutcome>, usize), String> {
+        let current = self.read_memory(workspace)?;
+        let mut lines: Vec<String> = current.lines().map(str::to_string).collect();
+        let mut outcomes = Vec::new();
+
+        for fact in facts {
+            let fact = match split_fact(fact) {
+                Some((_, text)) => text,
+                None => continue,
+            };
+            let words = normalized_words(fact);
+            let best = lines
+                .iter()
+                .enumerate()
+                .filter_map(|(i, line)| {
+                    let (_, text) = split_fact(line)?;
+                    Some((i, word_similarity(&words, &normalized_words(text))))
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            match best {
+                Some((i, score)) if score >= self.config.dedup_threshold => {
+                    let (prefix, existing) = split_fact(&lines[i]).unwrap_or_default();
+                    let (prefix, existing) = (prefix.to_string(), existing.to_string());
+                    if words.is_subset(&normalized_words(&existing)) {
+                        outcomes.push(FactOutcome::Skipped {
+                            fact: fact.to_string(),
+                            existing,
+                            score,
+                        });
+                    } else {
+                        lines[i] = format!("{}{}", prefix, fact);
+                        outcomes.push(FactOutcome::Updated {
+                            fact: fact.to_string(),
+                            previous: existing,
+                            score,
+                        });
+                    }
+                }
+                _ => {
+                    lines.push(format!("- {}", fact));
+                    outcomes.push(FactOutcome::Added {
+                        fact: fact.to_string(),
+                    });
+                }
+            }
+        }
+
+        if outcomes
+            .iter()
+            .all(|o| matches!(o, FactOutcome::Skipped { .. }))
+        {
+            return Ok((outcomes, current.len()));
+        }
+        let mut content = lines.join("\n");
+        content.push('\n');
+        let size = self.update_memory(workspace, &content)?;
+        Ok((outcomes, size))
+    }
+
+    /// The HISTORY.md entry most similar to `text`, if any reaches
+    /// `dedup_threshold`.
+    ///
+    /// HISTORY.md is append-only, so this only lets the caller flag a
+    /// repeat; it does not stop the entry from being written.
+    pub fn similar_history(
+        &self,
+        workspace: &Path,
+        text: &str,
+    ) -> Result<Option<(HistoryEntry, f64)>, String> {
+        let history = self.read_history(workspace)?;
+        let words = normalized_words(text);
+        Ok(history_entries(&history)
+            .into_iter()
+            .map(|entry| {
+                let score = word_similarity(&words, &normalized_words(&entry.text));
+                (entry, score)
+            })
+            .filter(|(_, score)| *score >= self.config.dedup_threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1)))
+    }
+
     /// Read current MEMORY.md content.
     pub fn read_memory(&self, workspace: &Path) -> Result<String, String> {
         let memory_path = workspace.join(&self.config.memory_path);
@@ -201,57 +322,37 @@ impl MemoryConsolidation {
         fs::read_to_string(&history_path).map_err(|e| format!("Failed to read HISTORY.md: {}", e))
     }
 
-    /// Search HISTORY.md using grep-style pattern matching.
+    /// Search HISTORY.md.
+    ///
+    /// With an index, entries containing every word of `pattern` are
+    /// returned best match first.  Without one — or when the pattern has no
+    /// indexed words or matches no whole word — this falls back to a
+    /// case-insensitive substring scan in file order.
     pub fn search_history(
         &self,
         workspace: &Path,
         pattern: &str,
         max_results: usize,
     ) -> Result<Vec<HistoryEntry>, String> {
-        let history = self.read_history(workspace)?;
-        let pattern_lower = pattern.to_lowercase();
-
-        let mut results = Vec::new();
-        let mut current_entry: Option<HistoryEntry> = None;
-
-        for line in history.lines() {
-            // Check if this is a new entry (starts with timestamp)
-            if line.starts_with('[') && line.contains(']') {
-                // Save previous entry if it matched
-                if let Some(entry) = current_entry.take() {
-                    if entry.text.to_lowercase().contains(&pattern_lower) {
-                        results.push(entry);
-                        if results.len() >= max_results {
-                            break;
-                        }
-                    }
-                }
-
-                // Parse new entry
-                if let Some(end_bracket) = line.find(']') {
-                    let timestamp_str = &line[1..end_bracket];
-                    let text = line[end_bracket + 1..].trim().to_string();
-
-                    current_entry = Some(HistoryEntry {
-                        timestamp: timestamp_str.to_string(),
-                        text,
-                    });
-                }
-            } else if let Some(ref mut entry) = current_entry {
-                // Continuation of current entry
-                entry.text.push('\n');
-                entry.text.push_str(line);
+        let history_path = workspace.join(&self.config.history_path);
+        if HistoryIndex::load(workspace).is_some() {
+            // Catch up on entries appended by someone else first.
+            let index = HistoryIndex::update(workspace, &history_path)?;
+            if let Some(found) = index.search(&history_path, pattern, max_results)?
+                && !found.is_empty()
+            {
+                return Ok(found);
             }
         }
 
-        // Don't forget the last entry
-        if let Some(entry) = current_entry {
-            if entry.text.to_lowercase().contains(&pattern_lower) && results.len() < max_results {
-                results.push(entry);
-            }
-        }
+        let history = self.read_history(workspace)?;
+        let pattern_lower = pattern.to_lowercase();
 
-        Ok(results)
+        Ok(history_entries(&history)
+            .into_iter()
+            .filter(|entry| entry.text.to_lowercase().contains(&pattern_lower))
+            .take(max_results)
+            .collect())
     }
 
     /// Get configuration reference.
@@ -269,6 +370,101 @@ pub struct HistoryEntry {
     pub text: String,
 }
 
+/// Split HISTORY.md into its timestamped entries.
+pub(crate) fn history_entries(history: &str) -> Vec<HistoryEntry> {
+    let mut entries = Vec::new();
+    let mut current_entry: Option<HistoryEntry> = None;
+
+    for line in history.lines() {
+        // Check if this is a new entry (starts with timestamp)
+        if line.starts_with('[')
+            && let Some(end_bracket) = line.find(']')
+        {
+            if let Some(entry) = current_entry.take() {
+                entries.push(entry);
+            }
+            current_entry = Some(HistoryEntry {
+                timestamp: line[1..end_bracket].to_string(),
+                text: line[end_bracket + 1..].trim().to_string(),
+            });
+        } else if let Some(ref mut entry) = current_entry {
+            // Continuation of current entry
+            entry.text.push('\n');
+            entry.text.push_str(line);
+        }
+    }
+
+    entries.extend(current_entry);
+    entries
+}
+
+/// What [`MemoryConsolidation::add_facts`] did with one fact.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FactOutcome {
+    /// Appended to MEMORY.md as a new bullet.
+    Added { fact: String },
+    /// Already recorded; nothing was written.
+    Skipped {
+        fact: String,
+        existing: String,
+        score: f64,
+    },
+    /// Replaced a near-duplicate that said less.
+    Updated {
+        fact: String,
+        previous: String,
+        score: f64,
+    },
+}
+
+/// Words that carry no meaning for duplicate detection or search.
+pub(crate) const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "for", "in", "is", "it", "of", "on", "or", "the",
+    "to", "was", "with",
+];
+
+/// Similarity of two facts, from 0.0 (no words in common) to 1.0 (the same
+/// words, ignoring case, punctuation, order and stopwords).
+pub fn fact_similarity(a: &str, b: &str) -> f64 {
+    word_similarity(&normalized_words(a), &normalized_words(b))
+}
+
+fn normalized_words(text: &str) -> BTreeSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && !STOPWORDS.contains(w))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Jaccard index of two word sets.
+fn word_similarity(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+/// Split a MEMORY.md line into its list marker and fact text.  Blank lines
+/// and headings are not facts.
+fn split_fact(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_end();
+    let body = line.trim_start();
+    if body.is_empty() || body.starts_with('#') {
+        return None;
+    }
+    let text = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| body.strip_prefix(marker))
+        .unwrap_or(body)
+        .trim_start();
+    if text.is_empty() {
+        return None;
+    }
+    Some(line.split_at(line.len() - text.len()))
+}
+
 /// Arguments for the save_memory tool.
 ///
 /// The LLM provides both pieces in a single call:
@@ -284,6 +480,11 @@ pub struct SaveMemoryArgs {
     /// If None, MEMORY.md is not modified.
     #[serde(default)]
     pub memory_update: Option<String>,
+
+    /// Individual facts to add to MEMORY.md, deduplicated against what is
+    /// already there.
+    #[serde(default)]
+    pub memory_facts: Vec<String>,
 }
 
 #[cfg(test)]
@@ -348,6 +549,83 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[test]
+    fn test_fact_similarity() {
+        assert_eq!(
+            fact_similarity("User prefers dark mode.", "the user prefers dark mode"),
+            1.0
+        );
+        assert!(fact_similarity("user prefers dark mode", "user prefers light mode") < 0.8);
+        assert_eq!(fact_similarity("the", "a"), 0.0);
+    }
+
+    #[test]
+    fn test_add_facts_dedup() {
+        let dir = tempdir().unwrap();
+        let consolidation = MemoryConsolidation::new(ConsolidationConfig::default());
+        consolidation
+            .update_memory(dir.path(), "# Preferences\n\n* User prefers dark mode\n")
+            .unwrap();
+
+        let facts = vec![
+            "The user prefers dark mode.".to_string(),
+            "user prefers dark mode in the editor".to_string(),
+            "Project uses PostgreSQL 16".to_string(),
+            "project uses postgresql 16".to_string(),
+        ];
+        let (outcomes, size) = consolidation.add_facts(dir.path(), &facts).unwrap();
+
+        assert!(matches!(outcomes[0], FactOutcome::Skipped { .. }));
+        assert!(matches!(outcomes[1], FactOutcome::Updated { .. }));
+        assert!(matches!(outcomes[2], FactOutcome::Added { .. }));
+        assert!(matches!(outcomes[3], FactOutcome::Skipped { .. }));
+        let memory = consolidation.read_memory(dir.path()).unwrap();
+        assert_eq!(memory.len(), size);
+        assert_eq!(
+            memory,
+            "# Preferences\n\n* user prefers dark mode in the editor\n- Project uses PostgreSQL 16\n"
+        );
+    }
+
+    #[test]
+    fn test_add_facts_threshold_disables_dedup() {
+        let dir = tempdir().unwrap();
+        let config = ConsolidationConfig {
+            dedup_threshold: 1.1,
+            ..Default::default()
+        };
+        let consolidation = MemoryConsolidation::new(config);
+        let facts = vec!["User prefers dark mode".to_string(); 2];
+        let (outcomes, _) = consolidation.add_facts(dir.path(), &facts).unwrap();
+
+        assert!(
+            outcomes
+                .iter()
+                .all(|o| matches!(o, FactOutcome::Added { .. }))
+        );
+    }
+
+    #[test]
+    fn test_similar_history() {
+        let dir = tempdir().unwrap();
+        let consolidation = MemoryConsolidation::new(ConsolidationConfig::default());
+        consolidation
+            .append_history(dir.path(), "Deployed v2.1 to staging")
+            .unwrap();
+
+        let (entry, _) = consolidation
+            .similar_history(dir.path(), "deployed v2.1 to staging.")
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.text, "Deployed v2.1 to staging");
+        assert!(
+            consolidation
+                .similar_history(dir.path(), "Rolled back staging")
+                .unwrap()
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_consolidation_threshold() {
         let config = ConsolidationConfig {
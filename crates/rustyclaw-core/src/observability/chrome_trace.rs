@@ -0,0 +1,319 @@
+//! Per-request span dumps in Chrome trace format (`[request_trace]`).
+//!
+//! [`ChromeTraceLayer`] is a `tracing` layer that collects the spans opened
+//! under a [`REQUEST_SPAN`] — the gateway opens one per chat turn, with the
+//! provider and tool calls nested inside — and writes them out when that
+//! span closes, as "complete" events that chrome://tracing and Perfetto
+//! open as a flame chart.  Spans outside a request are ignored.
+//!
+//! Each request overwrites the trace file after rolling the previous one to
+//! `<file>.1`, so with `max_files` rotations the last `max_files + 1`
+//! requests are on disk; the `[log_rotation]` age limit applies as well.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde_json::{Value, json};
+use tracing::Subscriber;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::config::RequestTraceConfig;
+use crate::log_rotation;
+
+/// Name of the span that starts a trace.
+pub const REQUEST_SPAN: &str = "request";
+
+/// Install the trace layer as the process's subscriber if `config` turns
+/// tracing on.  A relative `file` is resolved against `logs_dir`.  Returns
+/// the trace file path, or `None` when tracing is off or another subscriber
+/// was installed first.
+pub fn init_request_trace(config: &RequestTraceConfig, logs_dir: &Path) -> Option<PathBuf> {
+    if !config.enabled {
+        return None;
+    }
+    let path = logs_dir.join(&config.file);
+    let layer = ChromeTraceLayer::new(path.clone(), config.max_files);
+    tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer)).ok()?;
+    Some(path)
+}
+
+/// Writes one Chrome trace file per [`REQUEST_SPAN`].
+pub struct ChromeTraceLayer {
+    path: PathBuf,
+    max_files: usize,
+    /// Held while rotating and writing, so concurrent requests don't race.
+    write_lock: Mutex<()>,
+}
+
+/// A closed span, with times in microseconds from the start of its request.
+#[derive(Debug, Clone)]
+struct Slice {
+    name: &'static str,
+    target: &'static str,
+    start_us: u64,
+    dur_us: u64,
+    fields: BTreeMap<String, String>,
+}
+
+/// The closed spans of one request, shared by every span in it.
+struct Trace {
+    origin: Instant,
+    started_at: chrono::DateTime<chrono::Utc>,
+    slices: Mutex<Vec<Slice>>,
+}
+
+/// Kept in each recorded span's extensions until it closes.
+struct Recording {
+    trace: Arc<Trace>,
+    start: Instant,
+    fields: BTreeMap<String, String>,
+    /// Whether this span started the trace, so closing it writes the file.
+    root: bool,
+}
+
+impl ChromeTraceLayer {
+    /// A layer writing to `path`, keeping `max_files` earlier traces.
+    pub fn new(path: PathBuf, max_files: usize) -> Self {
+        Self {
+            path,
+            max_files,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn write(&self, trace: &Trace) {
+        let Ok(body) = serde_json::to_vec(&chrome_trace(trace)) else {
+            return;
+        };
+        let Ok(_guard) = self.write_lock.lock() else {
+            return;
+        };
+        if let Some(dir) = self.path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if self.path.exists() {
+            log_rotation::global()
+                .with_max_files(self.max_files)
+                .rotate(&self.path);
+        }
+        let _ = std::fs::write(&self.path, body);
+    }
+}
+
+impl<S> Layer<S> for ChromeTraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let inherited = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<Recording>()
+                .map(|r| r.trace.clone())
+        });
+        let (trace, root) = match inherited {
+            Some(trace) => (trace, false),
+            None if attrs.metadata().name() == REQUEST_SPAN => {
+                let trace = Trace {
+                    origin: Instant::now(),
+                    started_at: chrono::Utc::now(),
+                    slices: Mutex::new(Vec::new()),
+                };
+                (Arc::new(trace), true)
+            }
+            None => return,
+        };
+        let mut fields = BTreeMap::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        span.extensions_mut().insert(Recording {
+            trace,
+            start: Instant::now(),
+            fields,
+            root,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if let Some(recording) = span.extensions_mut().get_mut::<Recording>() {
+            values.record(&mut FieldVisitor(&mut recording.fields));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(recording) = span.extensions_mut().remove::<Recording>() else {
+            return;
+        };
+        let metadata = span.metadata();
+        let slice = Slice {
+            name: metadata.name(),
+            target: metadata.target(),
+            start_us: micros(recording.start.duration_since(recording.trace.origin)),
+            dur_us: micros(recording.start.elapsed()),
+            fields: recording.fields,
+        };
+        if let Ok(mut slices) = recording.trace.slices.lock() {
+            slices.push(slice);
+        }
+        if recording.root {
+            self.write(&recording.trace);
+        }
+    }
+}
+
+fn micros(duration: std::time::Duration) -> u64 {
+    duration.as_micros().try_into().unwrap_or(u64::MAX)
+}
+
+/// Span fields as display strings.
+struct FieldVisitor<'a>(&'a mut BTreeMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+/// The Chrome trace JSON for a finished request.
+fn chrome_trace(trace: &Trace) -> Value {
+    let mut slices = trace
+        .slices
+        .lock()
+        .map(|slices| slices.clone())
+        .unwrap_or_default();
+    slices.sort_by_key(|s| (s.start_us, Reverse(s.dur_us)));
+    let lanes = assign_lanes(&slices);
+
+    let mut events = vec![json!({
+        "name": "process_name",
+        "ph": "M",
+        "pid": 1,
+        "args": { "name": format!("RustyClaw request {}", trace.started_at.to_rfc3339()) },
+    })];
+    events.extend(slices.iter().zip(lanes).map(|(slice, lane)| {
+        json!({
+            "name": slice.name,
+            "cat": slice.target,
+            "ph": "X",
+            "ts": slice.start_us,
+            "dur": slice.dur_us,
+            "pid": 1,
+            "tid": lane + 1,
+            "args": slice.fields,
+        })
+    }));
+    json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+}
+
+/// Spread slices sorted by start over lanes (trace "threads") so that the
+/// slices on each lane nest.  Async spans can overlap without nesting —
+/// tool calls run in parallel — and those go on separate lanes.
+fn assign_lanes(slices: &[Slice]) -> Vec<usize> {
+    // Per lane, the end times of the slices still open at the current start.
+    let mut lanes: Vec<Vec<u64>> = Vec::new();
+    slices
+        .iter()
+        .map(|slice| {
+            let end = slice.start_us + slice.dur_us;
+            for (lane, open) in lanes.iter_mut().enumerate() {
+                while open.last().is_some_and(|&e| e <= slice.start_us) {
+                    open.pop();
+                }
+                if open.last().is_none_or(|&e| e >= end) {
+                    open.push(end);
+                    return lane;
+                }
+            }
+            lanes.push(vec![end]);
+            lanes.len() - 1
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::info_span;
+
+    fn slice(start_us: u64, dur_us: u64) -> Slice {
+        Slice {
+            name: "span",
+            target: "test",
+            start_us,
+            dur_us,
+            fields: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_overlapping_siblings_get_separate_lanes() {
+        let slices = [slice(0, 100), slice(10, 40), slice(20, 40), slice(55, 15)];
+        assert_eq!(assign_lanes(&slices), vec![0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_writes_one_trace_per_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("traces").join("request-trace.json");
+        let subscriber =
+            tracing_subscriber::registry().with(ChromeTraceLayer::new(path.clone(), 1));
+        tracing::subscriber::with_default(subscriber, || {
+            // Not part of any request.
+            let _startup = info_span!("startup").entered();
+            for turn in 0..3 {
+                let _request = info_span!("request", turn).entered();
+                drop(info_span!("provider_call", provider = "openai").entered());
+                let tool = info_span!("tool_call", tool = "read_file").entered();
+                drop(info_span!("execute_tool").entered());
+                drop(tool);
+            }
+        });
+
+        // The last request, plus one rotation.
+        assert!(log_rotation::rotated_path(&path, 1).exists());
+        assert!(!log_rotation::rotated_path(&path, 2).exists());
+
+        let trace: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let spans: BTreeMap<&str, &Value> = trace["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|e| e["ph"] == "X")
+            .map(|e| (e["name"].as_str().unwrap(), e))
+            .collect();
+        let names: Vec<&str> = spans.keys().copied().collect();
+        assert_eq!(
+            names,
+            ["execute_tool", "provider_call", "request", "tool_call"]
+        );
+        assert_eq!(spans["request"]["args"]["turn"], "2");
+        assert_eq!(spans["tool_call"]["args"]["tool"], "read_file");
+        assert!(spans.values().all(|e| e["tid"] == 1));
+        let start = |name: &str| spans[name]["ts"].as_u64().unwrap();
+        let end = |name: &str| start(name) + spans[name]["dur"].as_u64().unwrap();
+        assert!(start("execute_tool") >= start("tool_call"));
+        assert!(end("execute_tool") <= end("tool_call"));
+        assert!(end("tool_call") <= end("request"));
+    }
+}
@@ -6,10 +6,16 @@
 //!
 //! Adapted from ZeroClaw (MIT OR Apache-2.0 licensed).
 
+pub mod chrome_trace;
 pub mod log;
+pub mod prometheus;
+pub mod request_ids;
 pub mod traits;
 
+pub use chrome_trace::{ChromeTraceLayer, REQUEST_SPAN, init_request_trace};
 pub use log::LogObserver;
+pub use prometheus::PrometheusObserver;
+pub use request_ids::{ConnectionIds, RequestIds};
 pub use traits::{Observer, ObserverEvent, ObserverMetric};
 
 use std::sync::Arc;
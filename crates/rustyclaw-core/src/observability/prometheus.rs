@@ -0,0 +1,381 @@
+//! Prometheus observer: aggregates events into counters, gauges and
+//! histograms and renders them in the text exposition format.
+//!
+//! A deliberately small registry rather than a metrics framework: the
+//! gateway serves [`PrometheusObserver::render`] on its `/metrics`
+//! listener.  Labels are limited to tool and provider names, and each
+//! metric keeps at most [`MAX_LABEL_VALUES`] of them; further names are
+//! counted under `other`, so a model inventing tool names cannot grow the
+//! output without bound.
+
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Distinct label values kept per metric.
+pub const MAX_LABEL_VALUES: usize = 200;
+
+/// Label value that absorbs names past [`MAX_LABEL_VALUES`].
+const OVERFLOW_LABEL: &str = "other";
+
+/// Upper bounds, in seconds, of the latency histogram buckets.
+const LATENCY_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations per bucket (not cumulative); the last is `+Inf`.
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        let i = LATENCY_BUCKETS
+            .iter()
+            .position(|le| secs <= *le)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[i] += 1;
+        self.sum += secs;
+        self.count += 1;
+    }
+}
+
+/// Values of one labelled metric, by label value.
+#[derive(Debug)]
+struct Family<T> {
+    values: BTreeMap<String, T>,
+}
+
+impl<T: Default> Family<T> {
+    fn new() -> Self {
+        Self {
+            values: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, label: &str) -> &mut T {
+        let label = if self.values.contains_key(label) || self.values.len() < MAX_LABEL_VALUES {
+            label
+        } else {
+            OVERFLOW_LABEL
+        };
+        self.values.entry(label.to_string()).or_default()
+    }
+}
+
+#[derive(Debug)]
+struct Registry {
+    active_connections: u64,
+    queue_depth: u64,
+    vault_locked: Option<bool>,
+    tool_calls: Family<u64>,
+    tool_errors: Family<u64>,
+    tool_duration: Family<Histogram>,
+    provider_requests: Family<u64>,
+    provider_errors: Family<u64>,
+    provider_duration: Family<Histogram>,
+    input_tokens: Family<u64>,
+    output_tokens: Family<u64>,
+}
+
+/// Observer that keeps Prometheus metrics in memory.
+pub struct PrometheusObserver {
+    registry: Mutex<Registry>,
+}
+
+impl PrometheusObserver {
+    pub fn new() -> Self {
+        Self {
+            registry: Mutex::new(Registry {
+                active_connections: 0,
+                queue_depth: 0,
+                vault_locked: None,
+                tool_calls: Family::new(),
+                tool_errors: Family::new(),
+                tool_duration: Family::new(),
+                provider_requests: Family::new(),
+                provider_errors: Family::new(),
+                provider_duration: Family::new(),
+                input_tokens: Family::new(),
+                output_tokens: Family::new(),
+            }),
+        }
+    }
+
+    /// Record whether the secrets vault is locked; read at scrape time.
+    pub fn set_vault_locked(&self, locked: bool) {
+        self.registry.lock().unwrap().vault_locked = Some(locked);
+    }
+
+    /// All metrics in the Prometheus text exposition format (0.0.4).
+    pub fn render(&self) -> String {
+        let r = self.registry.lock().unwrap();
+        let mut out = String::new();
+        gauge(
+            &mut out,
+            "rustyclaw_active_connections",
+            "Client connections currently open.",
+            r.active_connections,
+        );
+        gauge(
+            &mut out,
+            "rustyclaw_queue_depth",
+            "Inbound messages waiting to be processed.",
+            r.queue_depth,
+        );
+        if let Some(locked) = r.vault_locked {
+            gauge(
+                &mut out,
+                "rustyclaw_vault_locked",
+                "1 while the secrets vault is locked.",
+                u64::from(locked),
+            );
+        }
+        counters(
+            &mut out,
+            "rustyclaw_tool_calls_total",
+            "Tool calls completed.",
+            "tool",
+            &r.tool_calls,
+        );
+        counters(
+            &mut out,
+            "rustyclaw_tool_errors_total",
+            "Tool calls that failed.",
+            "tool",
+            &r.tool_errors,
+        );
+        histograms(
+            &mut out,
+            "rustyclaw_tool_duration_seconds",
+            "Tool call duration.",
+            "tool",
+            &r.tool_duration,
+        );
+        counters(
+            &mut out,
+            "rustyclaw_provider_requests_total",
+            "Model provider requests.",
+            "provider",
+            &r.provider_requests,
+        );
+        counters(
+            &mut out,
+            "rustyclaw_provider_errors_total",
+            "Model provider requests that failed.",
+            "provider",
+            &r.provider_errors,
+        );
+        histograms(
+            &mut out,
+            "rustyclaw_provider_duration_seconds",
+            "Model provider request duration.",
+            "provider",
+            &r.provider_duration,
+        );
+        counters(
+            &mut out,
+            "rustyclaw_input_tokens_total",
+            "Prompt tokens reported by providers.",
+            "provider",
+            &r.input_tokens,
+        );
+        counters(
+            &mut out,
+            "rustyclaw_output_tokens_total",
+            "Completion tokens reported by providers.",
+            "provider",
+            &r.output_tokens,
+        );
+        out
+    }
+}
+
+impl Default for PrometheusObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Observer for PrometheusObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        let mut r = self.registry.lock().unwrap();
+        match event {
+            ObserverEvent::ToolCall {
+                tool,
+                duration,
+                success,
+            } => {
+                *r.tool_calls.get(tool) += 1;
+                if !success {
+                    *r.tool_errors.get(tool) += 1;
+                }
+                r.tool_duration.get(tool).observe(*duration);
+            }
+            ObserverEvent::LlmResponse {
+                provider,
+                duration,
+                success,
+                input_tokens,
+                output_tokens,
+                ..
+            } => {
+                *r.provider_requests.get(provider) += 1;
+                if !success {
+                    *r.provider_errors.get(provider) += 1;
+                }
+                r.provider_duration.get(provider).observe(*duration);
+                if let Some(n) = input_tokens {
+                    *r.input_tokens.get(provider) += n;
+                }
+                if let Some(n) = output_tokens {
+                    *r.output_tokens.get(provider) += n;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        let mut r = self.registry.lock().unwrap();
+        match metric {
+            ObserverMetric::ActiveSessions(n) => r.active_connections = *n,
+            ObserverMetric::QueueDepth(n) => r.queue_depth = *n,
+            // Unlabelled; the events above carry the same data per tool or
+            // provider.
+            ObserverMetric::RequestLatency(_) | ObserverMetric::TokensUsed(_) => {}
+        }
+    }
+
+    fn name(&self) -> &str {
+        "prometheus"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// ── Rendering ───────────────────────────────────────────────────────────────
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: u64) {
+    header(out, name, help, "gauge");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn counters(out: &mut String, name: &str, help: &str, label: &str, family: &Family<u64>) {
+    header(out, name, help, "counter");
+    for (value, n) in &family.values {
+        let _ = writeln!(out, "{}{{{}=\"{}\"}} {}", name, label, escape(value), n);
+    }
+}
+
+fn histograms(out: &mut String, name: &str, help: &str, label: &str, family: &Family<Histogram>) {
+    header(out, name, help, "histogram");
+    for (value, h) in &family.values {
+        let value = escape(value);
+        let mut cumulative = 0;
+        for (i, n) in h.buckets.iter().enumerate() {
+            cumulative += n;
+            let le = LATENCY_BUCKETS
+                .get(i)
+                .map_or_else(|| "+Inf".to_string(), |b| b.to_string());
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}=\"{}\",le=\"{}\"}} {}",
+                name, label, value, le, cumulative
+            );
+        }
+        let _ = writeln!(out, "{}_sum{{{}=\"{}\"}} {}", name, label, value, h.sum);
+        let _ = writeln!(out, "{}_count{{{}=\"{}\"}} {}", name, label, value, h.count);
+    }
+}
+
+/// Escape a label value (backslash, double quote and newline).
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_call(tool: &str, ms: u64, success: bool) -> ObserverEvent {
+        ObserverEvent::ToolCall {
+            tool: tool.into(),
+            duration: Duration::from_millis(ms),
+            success,
+        }
+    }
+
+    #[test]
+    fn test_render_counters_histograms_and_gauges() {
+        let obs = PrometheusObserver::new();
+        obs.record_event(&tool_call("read_file", 20, true));
+        obs.record_event(&tool_call("read_file", 700, false));
+        obs.record_event(&ObserverEvent::LlmResponse {
+            provider: "anthropic".into(),
+            model: "claude".into(),
+            duration: Duration::from_secs(3),
+            success: true,
+            error_message: None,
+            input_tokens: Some(1200),
+            output_tokens: Some(300),
+        });
+        obs.record_metric(&ObserverMetric::ActiveSessions(2));
+        obs.set_vault_locked(false);
+
+        let text = obs.render();
+        assert!(text.contains("# TYPE rustyclaw_tool_calls_total counter\n"));
+        assert!(text.contains("rustyclaw_tool_calls_total{tool=\"read_file\"} 2\n"));
+        assert!(text.contains("rustyclaw_tool_errors_total{tool=\"read_file\"} 1\n"));
+        assert!(text.contains(
+            "rustyclaw_tool_duration_seconds_bucket{tool=\"read_file\",le=\"0.05\"} 1\n"
+        ));
+        assert!(
+            text.contains(
+                "rustyclaw_tool_duration_seconds_bucket{tool=\"read_file\",le=\"1\"} 2\n"
+            )
+        );
+        assert!(text.contains(
+            "rustyclaw_tool_duration_seconds_bucket{tool=\"read_file\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(text.contains("rustyclaw_tool_duration_seconds_count{tool=\"read_file\"} 2\n"));
+        assert!(text.contains("rustyclaw_provider_requests_total{provider=\"anthropic\"} 1\n"));
+        assert!(text.contains("rustyclaw_input_tokens_total{provider=\"anthropic\"} 1200\n"));
+        assert!(text.contains("rustyclaw_active_connections 2\n"));
+        assert!(text.contains("rustyclaw_vault_locked 0\n"));
+    }
+
+    #[test]
+    fn test_label_values_are_capped_and_escaped() {
+        let obs = PrometheusObserver::new();
+        for i in 0..MAX_LABEL_VALUES + 10 {
+            obs.record_event(&tool_call(&format!("tool_{}", i), 1, true));
+        }
+        obs.record_event(&tool_call("tool_0", 1, true));
+        let text = obs.render();
+        assert!(text.contains("rustyclaw_tool_calls_total{tool=\"tool_0\"} 2\n"));
+        assert!(text.contains("rustyclaw_tool_calls_total{tool=\"other\"} 10\n"));
+        let series = text
+            .lines()
+            .filter(|l| l.starts_with("rustyclaw_tool_calls_total{"))
+            .count();
+        assert_eq!(series, MAX_LABEL_VALUES + 1);
+
+        assert_eq!(escape("a\"b\\c\nd"), r#"a\"b\\c\nd"#);
+    }
+}
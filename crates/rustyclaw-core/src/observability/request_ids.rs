@@ -0,0 +1,86 @@
+//! Connection and request IDs for attributing logs and audit records.
+//!
+//! Each gateway connection gets a random 8-hex-digit ID and numbers the
+//! requests it handles, so request `3f9a1c07-12` is the twelfth on
+//! connection `3f9a1c07`.  The IDs say nothing about the peer or its
+//! credentials; they only tell interleaved log lines apart.
+//!
+//! The gateway runs each request inside [`scope`], which makes the IDs
+//! visible to code that records them — the secret access log, for one —
+//! without threading them through every call.  Tracing spans carry the
+//! same IDs for log output.
+
+use std::fmt;
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: RequestIds;
+}
+
+/// The IDs of the request being handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestIds {
+    pub connection: String,
+    pub request: String,
+}
+
+impl fmt::Display for RequestIds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.request)
+    }
+}
+
+/// Hands out the request IDs of one connection.
+#[derive(Debug)]
+pub struct ConnectionIds {
+    connection: String,
+    requests: u64,
+}
+
+impl ConnectionIds {
+    /// IDs for a new connection.
+    pub fn new() -> Self {
+        Self {
+            connection: format!("{:08x}", rand::random::<u32>()),
+            requests: 0,
+        }
+    }
+
+    pub fn connection(&self) -> &str {
+        &self.connection
+    }
+
+    /// The IDs for the connection's next request.
+    pub fn next_request(&mut self) -> RequestIds {
+        self.requests += 1;
+        RequestIds {
+            connection: self.connection.clone(),
+            request: format!("{}-{}", self.connection, self.requests),
+        }
+    }
+}
+
+impl Default for ConnectionIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run `f` as the request `ids`.
+pub async fn scope<F: Future>(ids: RequestIds, f: F) -> F::Output {
+    CURRENT.scope(ids, f).await
+}
+
+/// Run the blocking `f` as the request `ids`, if any.  For work moved off
+/// the request's task, which doesn't inherit [`scope`].
+pub fn sync_scope<R>(ids: Option<RequestIds>, f: impl FnOnce() -> R) -> R {
+    match ids {
+        Some(ids) => CURRENT.sync_scope(ids, f),
+        None => f(),
+    }
+}
+
+/// The request being handled on this task, if any.
+pub fn current() -> Option<RequestIds> {
+    CURRENT.try_with(RequestIds::clone).ok()
+}
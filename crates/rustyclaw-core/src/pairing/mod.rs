@@ -45,7 +45,10 @@ pub use authorized::{
     load_authorized_clients, remove_authorized_client,
 };
 
-pub use qr::{PairingData, generate_pairing_qr, generate_pairing_qr_ascii, parse_pairing_qr};
+pub use qr::{
+    PairingData, decode_qr_image, generate_pairing_qr, generate_pairing_qr_ascii, parse_pairing_qr,
+    render_qr_png, render_qr_unicode,
+};
 
 pub use fingerprint::{format_fingerprint_art, key_fingerprint, key_fingerprint_short};
 
@@ -87,12 +87,16 @@ impl PairingData {
 /// Returns the QR code as a PNG image in bytes.
 #[cfg(feature = "qr")]
 pub fn generate_pairing_qr(data: &PairingData) -> Result<Vec<u8>> {
+    render_qr_png(&data.to_json()?)
+}
+
+/// Render `text` as a QR code PNG (200–400 px square).
+#[cfg(feature = "qr")]
+pub fn render_qr_png(text: &str) -> Result<Vec<u8>> {
     use image::{ImageEncoder, Luma, codecs::png::PngEncoder};
     use qrcode::QrCode;
 
-    let json = data.to_json()?;
-
-    let code = QrCode::new(json.as_bytes()).context("Failed to generate QR code")?;
+    let code = QrCode::new(text.as_bytes()).context("Failed to generate QR code")?;
 
     // Render to image
     let image = code
@@ -119,11 +123,15 @@ pub fn generate_pairing_qr(data: &PairingData) -> Result<Vec<u8>> {
 /// Generate a QR code as ASCII art (for terminal display).
 #[cfg(feature = "qr")]
 pub fn generate_pairing_qr_ascii(data: &PairingData) -> Result<String> {
-    use qrcode::QrCode;
+    render_qr_unicode(&data.to_json()?)
+}
 
-    let json = data.to_json()?;
+/// Render `text` as a QR code drawn with Unicode half blocks, two module
+/// rows per line.
+pub fn render_qr_unicode(text: &str) -> Result<String> {
+    use qrcode::QrCode;
 
-    let code = QrCode::new(json.as_bytes()).context("Failed to generate QR code")?;
+    let code = QrCode::new(text.as_bytes()).context("Failed to generate QR code")?;
 
     // Render to ASCII using Unicode block characters
     let mut output = String::new();
@@ -164,6 +172,41 @@ pub fn generate_pairing_qr_ascii(_data: &PairingData) -> Result<String> {
     anyhow::bail!("QR code feature not enabled")
 }
 
+#[cfg(not(feature = "qr"))]
+pub fn render_qr_png(_text: &str) -> Result<Vec<u8>> {
+    anyhow::bail!("QR code feature not enabled")
+}
+
+/// Read the QR codes in an image, in the order they are found.
+#[cfg(feature = "qr")]
+pub fn decode_qr_image(path: &std::path::Path) -> Result<Vec<String>> {
+    let image = image::ImageReader::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?
+        .with_guessed_format()
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .decode()
+        .with_context(|| format!("Failed to decode image {}", path.display()))?
+        .to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32).0[0],
+    );
+    let mut texts = Vec::new();
+    for grid in prepared.detect_grids() {
+        let (_, text) = grid
+            .decode()
+            .map_err(|e| anyhow::anyhow!("Found a QR code but could not decode it: {}", e))?;
+        texts.push(text);
+    }
+    Ok(texts)
+}
+
+#[cfg(not(feature = "qr"))]
+pub fn decode_qr_image(_path: &std::path::Path) -> Result<Vec<String>> {
+    anyhow::bail!("QR code feature not enabled")
+}
+
 /// Parse pairing data from a QR code or JSON string.
 pub fn parse_pairing_qr(input: &str) -> Result<PairingData> {
     // Try to parse as JSON directly
@@ -503,6 +503,7 @@ impl ProcessManager {
             .arg("-c")
             .arg(command)
             .current_dir(working_dir)
+            .envs(crate::tools::session_env())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -514,6 +515,7 @@ impl ProcessManager {
             .arg("/C")
             .arg(command)
             .current_dir(working_dir)
+            .envs(crate::tools::session_env())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -525,6 +527,7 @@ impl ProcessManager {
             .arg("-c")
             .arg(command)
             .current_dir(working_dir)
+            .envs(crate::tools::session_env())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -0,0 +1,226 @@
+//! Extract the human-readable part of a provider's HTTP error body.
+//!
+//! When a provider rejects a request (4xx/5xx) the useful information —
+//! "maximum context length is 128000 tokens", "model not found" — lives in
+//! the JSON response body, buried inside genai's `Debug`-style error text.
+//! [`parse_provider_error`] digs it back out so the gateway can show the
+//! user the provider's own message instead of the whole error dump.
+//!
+//! Recognised shapes:
+//!
+//! - OpenAI and compatibles: `{"error": {"message", "type", "code"}}`
+//! - Anthropic: `{"type": "error", "error": {"type", "message"}}`
+//! - Google: `{"error": {"code": 400, "message", "status"}}` (optionally
+//!   wrapped in a one-element array)
+//! - Proxies that send `{"error": "plain string"}`
+//!
+//! Providers sometimes echo the submitted key back ("Incorrect API key
+//! provided: sk-…"), so anything derived from an error body must go through
+//! [`redact_credentials`] before it leaves the gateway.
+
+use std::ops::Range;
+
+use serde_json::Value;
+
+use super::models::redact_secret;
+use crate::security::LeakDetector;
+
+/// The provider-reported part of a failed model call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderErrorBody {
+    /// HTTP status, when it could be recovered from the error text.
+    pub status: Option<u16>,
+    /// Machine-readable code (`context_length_exceeded`,
+    /// `invalid_request_error`, `INVALID_ARGUMENT`, …).
+    pub code: Option<String>,
+    /// The provider's message, verbatim.
+    pub message: String,
+}
+
+impl ProviderErrorBody {
+    /// One-line rendering: `message (HTTP 400, code)`.
+    pub fn summary(&self) -> String {
+        let mut tags = Vec::new();
+        if let Some(status) = self.status {
+            tags.push(format!("HTTP {status}"));
+        }
+        if let Some(ref code) = self.code {
+            tags.push(code.clone());
+        }
+        if tags.is_empty() {
+            self.message.clone()
+        } else {
+            format!("{} ({})", self.message, tags.join(", "))
+        }
+    }
+}
+
+/// Find and parse a provider error body inside `text`.
+///
+/// `text` is typically the alternate Display (`{:#}`) of a model-call
+/// error.  The body may appear as raw JSON or as a `Debug`-escaped string
+/// field (`body: "{\"error\": …}"`); both are handled.  Returns `None`
+/// when no recognisable error object is present.
+pub fn parse_provider_error(text: &str) -> Option<ProviderErrorBody> {
+    let status = find_status(text);
+    let escaped = debug_string_field(text, "body: ");
+    let candidates = escaped
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(text));
+
+    for candidate in candidates {
+        if let Some(mut body) = find_error_json(candidate) {
+            body.status = status.or(body.status);
+            return Some(body);
+        }
+    }
+    None
+}
+
+/// Replace credentials in `text` with a redaction marker.
+///
+/// `api_key` (the key the request was sent with) is always replaced;
+/// well-known key and token formats are caught by the leak detector's
+/// patterns even when they are not the active key.
+pub fn redact_credentials(text: &str, api_key: Option<&str>) -> String {
+    let mut out = text.to_string();
+    if let Some(key) = api_key.map(str::trim).filter(|k| k.len() >= 8) {
+        out = out.replace(key, &redact_secret(key));
+    }
+
+    let mut ranges: Vec<Range<usize>> = LeakDetector::new()
+        .scan(&out)
+        .matches
+        .into_iter()
+        .map(|m| m.location)
+        .collect();
+    if ranges.is_empty() {
+        return out;
+    }
+    ranges.sort_by_key(|r| r.start);
+
+    let mut redacted = String::with_capacity(out.len());
+    let mut last_end = 0;
+    for range in ranges {
+        if range.end <= last_end {
+            continue;
+        }
+        let start = range.start.max(last_end);
+        redacted.push_str(&out[last_end..start]);
+        redacted.push_str(&redact_secret(&out[start..range.end]));
+        last_end = range.end;
+    }
+    redacted.push_str(&out[last_end..]);
+    redacted
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Scan `text` for the first JSON object carrying an `error` member.
+fn find_error_json(text: &str) -> Option<ProviderErrorBody> {
+    for (idx, _) in text.match_indices(['{', '[']) {
+        let mut values = serde_json::Deserializer::from_str(&text[idx..]).into_iter::<Value>();
+        let value = values.next().and_then(Result::ok);
+        if let Some(body) = value.as_ref().and_then(error_from_value) {
+            return Some(body);
+        }
+    }
+    None
+}
+
+fn error_from_value(value: &Value) -> Option<ProviderErrorBody> {
+    // Google occasionally wraps the error object in a one-element array.
+    if let Value::Array(items) = value {
+        return items.first().and_then(error_from_value);
+    }
+
+    match value.get("error")? {
+        Value::String(message) if !message.trim().is_empty() => Some(ProviderErrorBody {
+            status: None,
+            code: None,
+            message: message.trim().to_string(),
+        }),
+        Value::Object(err) => {
+            let message = err.get("message").and_then(|v| v.as_str())?.trim();
+            if message.is_empty() {
+                return None;
+            }
+            // Google puts the HTTP status in `code` and the symbolic
+            // code in `status`; OpenAI uses `code` for the symbolic one.
+            let status = err
+                .get("code")
+                .and_then(|v| v.as_u64())
+                .and_then(|c| u16::try_from(c).ok());
+            let code = ["code", "status", "type"]
+                .iter()
+                .filter_map(|k| err.get(*k).and_then(|v| v.as_str()))
+                .find(|s| !s.is_empty())
+                .map(str::to_string);
+            Some(ProviderErrorBody {
+                status,
+                code,
+                message: message.to_string(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Recover the HTTP status from error text such as `status: 400`,
+/// `HTTP 429` or `returned 401 Unauthorized`.
+fn find_status(text: &str) -> Option<u16> {
+    for marker in ["status: ", "HTTP ", "returned "] {
+        for (idx, _) in text.match_indices(marker) {
+            let rest = &text[idx + marker.len()..];
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            if digits.len() != 3 {
+                continue;
+            }
+            match digits.parse::<u16>() {
+                Ok(code) if (400..600).contains(&code) => return Some(code),
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+/// Unescape the `Debug`-formatted string that follows `field` in `text`
+/// (e.g. `body: "{\"error\": …}"`).
+pub(super) fn debug_string_field(text: &str, field: &str) -> Option<String> {
+    let start = text.find(field)? + field.len();
+    let mut chars = text[start..].chars();
+    if chars.next()? != '"' {
+        return None;
+    }
+
+    let mut out = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(out),
+            '\\' => match chars.next()? {
+                'n' => out.push('\n'),
+                'r' => out.push('\r'),
+                't' => out.push('\t'),
+                '0' => out.push('\0'),
+                'u' => {
+                    // `\u{…}` escape.
+                    let hex: String = chars
+                        .by_ref()
+                        .skip_while(|c| *c == '{')
+                        .take_while(|c| *c != '}')
+                        .collect();
+                    let ch = u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)?;
+                    out.push(ch);
+                }
+                other => out.push(other),
+            },
+            c => out.push(c),
+        }
+    }
+    // Unterminated — the error text was truncated; use what we have.
+    Some(out)
+}
@@ -0,0 +1,113 @@
+//! Extra HTTP headers per provider (`[provider_headers]`).
+//!
+//! Corporate LLM gateways and proxies often want their own headers on every
+//! model request — an org id, a proxy credential.  Each provider id can
+//! list headers that are added to its chat requests and connection probes.
+//! They never replace the credential header the adapter sets: names such as
+//! `Authorization` and `x-api-key` are dropped with a warning.
+//!
+//! Header values can be credentials too, so logs show them through
+//! [`redact_extra_header`].
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use tracing::warn;
+
+use super::models::redact_secret;
+
+/// Headers that carry the provider API key; the adapter owns these.
+const AUTH_HEADERS: &[&str] = &["authorization", "x-api-key", "x-goog-api-key", "api-key"];
+
+/// Name fragments that mark a header value as a credential in logs.
+const SENSITIVE_NAME_PARTS: &[&str] = &[
+    "auth",
+    "token",
+    "key",
+    "secret",
+    "password",
+    "cookie",
+    "session",
+    "signature",
+];
+
+static EXTRA_HEADERS: OnceLock<HashMap<String, Vec<(String, String)>>> = OnceLock::new();
+
+/// Whether `name` is a credential header set by the provider adapter.
+pub fn is_auth_header(name: &str) -> bool {
+    AUTH_HEADERS.iter().any(|h| name.eq_ignore_ascii_case(h))
+}
+
+/// The usable headers of one `[provider_headers.<id>]` table, sorted by
+/// name.  Credential headers and names or values that aren't valid in HTTP
+/// are dropped with a warning.
+pub fn resolve_extra_headers(
+    provider: &str,
+    headers: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut usable: Vec<(String, String)> = headers
+        .iter()
+        .filter(|(name, value)| {
+            if is_auth_header(name) {
+                warn!(
+                    provider,
+                    header = %name,
+                    "Ignoring extra header: it would replace the provider's auth header"
+                );
+                return false;
+            }
+            let valid = reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_ok()
+                && reqwest::header::HeaderValue::from_str(value).is_ok();
+            if !valid {
+                warn!(provider, header = %name, "Ignoring invalid extra header");
+            }
+            valid
+        })
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    usable.sort();
+    usable
+}
+
+/// Apply `[provider_headers]`.  Called once at gateway startup.
+pub fn init_extra_headers(config: &HashMap<String, HashMap<String, String>>) {
+    let resolved = config
+        .iter()
+        .map(|(provider, headers)| (provider.clone(), resolve_extra_headers(provider, headers)))
+        .collect();
+    let _ = EXTRA_HEADERS.set(resolved);
+}
+
+/// The extra headers configured for `provider` (empty when none).
+pub fn extra_headers_for(provider: &str) -> &'static [(String, String)] {
+    EXTRA_HEADERS
+        .get()
+        .and_then(|m| m.get(provider))
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+/// Add `headers` to a request.  Credential headers are skipped even if a
+/// caller passes them, so the one the adapter set stays in place.
+pub fn apply_extra_headers(
+    mut builder: reqwest::RequestBuilder,
+    headers: &[(String, String)],
+) -> reqwest::RequestBuilder {
+    for (name, value) in headers {
+        if !is_auth_header(name) {
+            builder = builder.header(name, value);
+        }
+    }
+    builder
+}
+
+/// `value` as it may appear in logs: masked when the header name suggests
+/// a credential (`Proxy-Authorization`, `X-Api-Token`, …).
+pub fn redact_extra_header(name: &str, value: &str) -> String {
+    let lower = name.to_ascii_lowercase();
+    if SENSITIVE_NAME_PARTS.iter().any(|part| lower.contains(part)) {
+        redact_secret(value)
+    } else {
+        value.to_string()
+    }
+}
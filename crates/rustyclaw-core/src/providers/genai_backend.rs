@@ -31,15 +31,20 @@ use tracing::{debug, warn};
 use genai::Client;
 use genai::adapter::AdapterKind;
 use genai::chat::{
-    ChatMessage as GenChatMessage, ChatOptions, ChatRequest, ChatStreamEvent, ContentPart,
-    MessageContent, Tool, ToolCall, ToolResponse,
+    CacheControl, ChatMessage as GenChatMessage, ChatOptions, ChatRequest, ChatStreamEvent,
+    ContentPart, MessageContent, ReasoningEffort, Tool, ToolCall, ToolResponse,
 };
 use genai::resolver::{AuthData, Endpoint, ServiceTargetResolver};
 use genai::{ModelIden, ServiceTarget};
 
-use crate::gateway::protocol::server;
+use super::reasoning::{self, ReplyStream};
+use super::refusal;
+use super::timeouts::{self, TimeoutPhase, within};
+use super::wire_log;
 use crate::gateway::transport::TransportWriter;
-use crate::gateway::{ModelResponse, ParsedToolCall, ProviderRequest, ToolCallResult};
+use crate::gateway::{
+    ModelResponse, ParsedToolCall, ProviderRequest, SamplingParams, ToolCallResult,
+};
 use crate::providers;
 use crate::tools;
 
@@ -47,6 +52,10 @@ use crate::tools;
 /// previous implementation used this same ceiling across providers.
 const MAX_TOKENS: u32 = 16384;
 
+/// Stop-sequence limits of the OpenAI and Gemini APIs.
+const OPENAI_MAX_STOP: usize = 4;
+const GEMINI_MAX_STOP: usize = 5;
+
 // ── Public entry points (preserve the previous call surface) ────────────────
 
 /// Call an OpenAI-compatible provider with tools. Streams when a writer is set.
@@ -134,82 +143,106 @@ async fn genai_chat(
         "Starting genai chat request"
     );
 
-    let client = build_client(http, req);
+    let limits = timeouts::timeouts_for(&req.provider);
+    let client = build_client(&timeouts::http_client(http, limits.connect), req);
     let chat_req = to_genai_chat_request(req);
 
     let copilot = providers::needs_copilot_session(&req.provider);
     let mut options = ChatOptions::default().with_max_tokens(MAX_TOKENS);
+    let adapter = adapter_for(&req.provider);
+    options = apply_sampling(options, adapter, &req.model, &req.sampling);
+    options = apply_thinking(options, adapter, req);
     // Copilot/proxy endpoints reject the `stream_options.include_usage` field
     // that genai adds when usage capture is on, so skip usage there.
     options = options.with_capture_usage(!copilot);
-    if let Some(headers) = copilot_extra_headers(req) {
-        options = options.with_extra_headers(headers);
+    // `[provider_headers]` go after the Copilot ones; neither can carry the
+    // API key header, which genai sets from the resolver's auth data.
+    let mut headers = copilot_extra_headers(req);
+    headers.extend(providers::extra_headers_for(&req.provider).iter().cloned());
+    if !headers.is_empty() {
+        options = options.with_extra_headers(genai::Headers::from(headers));
     }
 
-    match writer {
+    wire_log::log_request(req, writer.is_some());
+    let result: Result<ModelResponse> = match writer {
         Some(w) => {
             let options = options
                 .with_capture_content(true)
                 .with_capture_tool_calls(true)
                 .with_capture_reasoning_content(true);
-            let stream = client
-                .exec_chat_stream(&req.model, chat_req, Some(&options))
-                .await?;
-            consume_stream(stream.stream, w).await
+            let started = client.exec_chat_stream(&req.model, chat_req, Some(&options));
+            match within(
+                &req.provider,
+                TimeoutPhase::Response,
+                limits.request,
+                started,
+            )
+            .await
+            {
+                Ok(Ok(stream)) => {
+                    consume_stream(stream.stream, w, &req.provider, limits.request).await
+                }
+                Ok(Err(e)) => Err(e.into()),
+                Err(timeout) => Err(timeout.into()),
+            }
         }
         None => {
-            let resp = client
-                .exec_chat(&req.model, chat_req, Some(&options))
-                .await?;
-            Ok(chat_response_to_model_response(resp))
+            let reply = client.exec_chat(&req.model, chat_req, Some(&options));
+            match within(&req.provider, TimeoutPhase::Response, limits.request, reply).await {
+                Ok(reply) => reply
+                    .map(chat_response_to_model_response)
+                    .map_err(Into::into),
+                Err(timeout) => Err(timeout.into()),
+            }
         }
+    };
+    let result = result.map_err(|e| {
+        let e = timeouts::tag_connect_timeout(&req.provider, limits.connect, e);
+        refusal::tag_refusal(&req.provider, e)
+    });
+    match &result {
+        Ok(resp) => wire_log::log_response(req, resp),
+        Err(e) => wire_log::log_error(req, e),
     }
+    result
 }
 
 /// Consume a genai stream, forwarding text/thinking chunks to the client and
-/// assembling the final [`ModelResponse`].
+/// assembling the final [`ModelResponse`].  Fails if the provider sends
+/// nothing for `idle`.
 async fn consume_stream(
     mut stream: genai::chat::ChatStream,
     writer: &mut dyn TransportWriter,
+    provider: &str,
+    idle: Option<std::time::Duration>,
 ) -> Result<ModelResponse> {
-    let mut result = ModelResponse::default();
-    let mut stream_started = false;
-    let mut thinking_started = false;
+    let mut reply = ReplyStream::new();
 
-    while let Some(event) = stream.next().await {
+    while let Some(event) = within(provider, TimeoutPhase::Stream, idle, stream.next()).await? {
         match event? {
-            ChatStreamEvent::Start => {
-                server::send_stream_start(writer).await?;
-                stream_started = true;
-            }
-            ChatStreamEvent::Chunk(chunk) => {
-                if !stream_started {
-                    server::send_stream_start(writer).await?;
-                    stream_started = true;
-                }
-                result.text.push_str(&chunk.content);
-                server::send_chunk(writer, &chunk.content).await?;
-            }
+            ChatStreamEvent::Start => reply.start(writer).await?,
+            ChatStreamEvent::Chunk(chunk) => reply.text(writer, &chunk.content).await?,
             ChatStreamEvent::ReasoningChunk(chunk) => {
-                if !thinking_started {
-                    let _ = server::send_thinking_start(writer).await;
-                    thinking_started = true;
-                }
-                let _ = server::send_thinking_delta(writer, &chunk.content).await;
+                reply.reasoning(writer, &chunk.content).await?
             }
             ChatStreamEvent::ToolCallChunk(_) => {
                 // Tool calls are assembled from the captured content in `End`.
             }
             ChatStreamEvent::ThoughtSignatureChunk(_) => {}
             ChatStreamEvent::End(end) => {
-                if thinking_started {
-                    let _ = server::send_thinking_end(writer).await;
-                }
+                reply.finish(writer).await?;
+                let result = &mut reply.response;
                 if let Some(content) = end.captured_content {
                     for part in content.into_parts() {
                         match part {
                             ContentPart::ToolCall(tc) => result.tool_calls.push(to_parsed_call(tc)),
-                            ContentPart::Text(t) if result.text.is_empty() => result.text = t,
+                            ContentPart::Text(t) if result.text.is_empty() => {
+                                let (thinking, text) = reasoning::split_think_tags(&t);
+                                if result.thinking.is_empty() {
+                                    result.thinking = thinking;
+                                }
+                                result.text = text;
+                            }
                             _ => {}
                         }
                     }
@@ -222,6 +255,7 @@ async fn consume_stream(
         }
     }
 
+    let mut result = reply.response;
     result.finish_reason = Some(finish_reason_for(&result).to_string());
     Ok(result)
 }
@@ -246,6 +280,10 @@ fn chat_response_to_model_response(resp: genai::chat::ChatResponse) -> ModelResp
             _ => {}
         }
     }
+    // Reasoning comes back on its own or inline as `<think>…</think>`.
+    let (inline, text) = reasoning::split_think_tags(&result.text);
+    result.thinking = resp.reasoning_content.unwrap_or(inline);
+    result.text = text;
 
     result.finish_reason = Some(finish_reason_for(&result).to_string());
     result
@@ -254,8 +292,8 @@ fn chat_response_to_model_response(resp: genai::chat::ChatResponse) -> ModelResp
 // ── Conversion helpers ───────────────────────────────────────────────────────
 
 /// Build a genai client configured for this request's provider, base URL, and
-/// resolved API key. The caller's `reqwest::Client` is reused so connection
-/// settings are shared.
+/// resolved API key. `http` carries the provider's connect timeout (see
+/// [`timeouts::http_client`]).
 fn build_client(http: &reqwest::Client, req: &ProviderRequest) -> Client {
     let adapter = adapter_for(&req.provider);
     let base_url = normalize_base_url(adapter, &req.base_url);
@@ -289,6 +327,121 @@ fn adapter_for(provider: &str) -> AdapterKind {
     }
 }
 
+/// Apply per-turn sampling overrides to `options`.
+///
+/// Parameters the target provider or model rejects are dropped (or clamped)
+/// with a debug note rather than failing the request.
+fn apply_sampling(
+    mut options: ChatOptions,
+    adapter: AdapterKind,
+    model: &str,
+    sampling: &SamplingParams,
+) -> ChatOptions {
+    if sampling.is_empty() {
+        return options;
+    }
+    let mut temperature = sampling.temperature;
+    let mut top_p = sampling.top_p;
+
+    if adapter == AdapterKind::OpenAI
+        && is_openai_reasoning_model(model)
+        && (temperature.is_some() || top_p.is_some())
+    {
+        debug!(
+            model,
+            "Reasoning model does not accept temperature/top_p; ignoring"
+        );
+        temperature = None;
+        top_p = None;
+    }
+    if adapter == AdapterKind::Anthropic {
+        if temperature.is_some_and(|t| t > 1.0) {
+            debug!(?temperature, "Anthropic caps temperature at 1.0; clamping");
+            temperature = Some(1.0);
+        }
+        if temperature.is_some() && top_p.is_some() {
+            debug!("Anthropic accepts temperature or top_p, not both; ignoring top_p");
+            top_p = None;
+        }
+    }
+
+    if let Some(t) = temperature {
+        options = options.with_temperature(t);
+    }
+    if let Some(p) = top_p {
+        options = options.with_top_p(p);
+    }
+    if let Some(max) = sampling.max_tokens {
+        options = options.with_max_tokens(max);
+    }
+    if !sampling.stop.is_empty() {
+        let limit = match adapter {
+            AdapterKind::OpenAI => OPENAI_MAX_STOP,
+            AdapterKind::Gemini => GEMINI_MAX_STOP,
+            _ => usize::MAX,
+        };
+        let mut stop = sampling.stop.clone();
+        if stop.len() > limit {
+            debug!(
+                requested = stop.len(),
+                limit, "Provider limits stop sequences; ignoring the extras"
+            );
+            stop.truncate(limit);
+        }
+        options = options.with_stop_sequences(stop);
+    }
+    options
+}
+
+/// Turn on extended thinking when `[thinking]` is enabled and the model
+/// supports it.  Applied after [`apply_sampling`], since thinking changes
+/// what sampling Anthropic accepts.
+fn apply_thinking(
+    mut options: ChatOptions,
+    adapter: AdapterKind,
+    req: &ProviderRequest,
+) -> ChatOptions {
+    let Some(budget) = req.thinking_budget else {
+        return options;
+    };
+    match adapter {
+        AdapterKind::Anthropic => {
+            // A tool-result round would have to replay the signed thinking
+            // blocks of the call before it, which aren't kept.
+            if req.messages.last().is_some_and(|m| m.role == "tool") {
+                return options;
+            }
+            if options.temperature.is_some() || options.top_p.is_some() {
+                debug!("Anthropic thinking runs at default sampling; ignoring temperature/top_p");
+                options.temperature = None;
+                options.top_p = None;
+            }
+            // The budget counts towards max_tokens, so leave room to answer.
+            let answer = options.max_tokens.unwrap_or(MAX_TOKENS);
+            options
+                .with_max_tokens(answer.saturating_add(budget))
+                .with_reasoning_effort(ReasoningEffort::Budget(budget))
+        }
+        AdapterKind::Gemini => options.with_reasoning_effort(ReasoningEffort::Budget(budget)),
+        AdapterKind::OpenAI if is_openai_reasoning_model(&req.model) => {
+            let effort = match budget {
+                0..4096 => ReasoningEffort::Low,
+                4096..16384 => ReasoningEffort::Medium,
+                _ => ReasoningEffort::High,
+            };
+            options.with_reasoning_effort(effort)
+        }
+        _ => options,
+    }
+}
+/// OpenAI reasoning models (o-series, gpt-5) only run at default sampling.
+fn is_openai_reasoning_model(model: &str) -> bool {
+    let name = model.rsplit('/').next().unwrap_or(model);
+    ["o1", "o3", "o4", "gpt-5"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
 /// Normalise a configured base URL into the form each genai adapter expects.
 ///
 /// genai builds request URLs by joining/concatenating onto the endpoint base,
@@ -316,6 +469,10 @@ fn normalize_base_url(adapter: AdapterKind, base: &str) -> String {
 /// reference the same `call_id`, only the first is kept. This guards against
 /// upstream bugs (HashMap-based remapping, history corruption, double-append)
 /// that can slip duplicate tool_result IDs into the conversation.
+///
+/// With `req.cache_prompt` set for Anthropic, the last message of the leading
+/// system block carries an ephemeral `cache_control` breakpoint. Anthropic
+/// orders tools before the system prompt, so the breakpoint covers both.
 fn to_genai_chat_request(req: &ProviderRequest) -> ChatRequest {
     // ── Pre-processing: deduplicate tool IDs in the ChatMessage stream ──
     // This is the final safety net before messages reach the API. It fixes:
@@ -327,9 +484,24 @@ fn to_genai_chat_request(req: &ProviderRequest) -> ChatRequest {
     // thread history or adapter bugs can still produce them.
     let messages = deduplicate_tool_ids(&req.messages);
 
+    // Later system messages (memory-flush notes, injected context) change
+    // from turn to turn, so only the leading block is a stable prefix.
+    let breakpoint = if req.cache_prompt && adapter_for(&req.provider) == AdapterKind::Anthropic {
+        messages
+            .iter()
+            .take_while(|m| m.role == "system")
+            .count()
+            .checked_sub(1)
+    } else {
+        None
+    };
+
     let mut gen_messages: Vec<GenChatMessage> = Vec::with_capacity(messages.len());
-    for msg in &messages {
+    for (idx, msg) in messages.iter().enumerate() {
         match msg.role.as_str() {
+            "system" if breakpoint == Some(idx) => gen_messages.push(
+                GenChatMessage::system(msg.content.clone()).with_options(CacheControl::Ephemeral),
+            ),
             "system" => gen_messages.push(GenChatMessage::system(msg.content.clone())),
             "assistant" => gen_messages.push(decode_assistant(&msg.content)),
             "tool" => gen_messages.push(decode_tool_result(&msg.content)),
@@ -546,10 +718,10 @@ fn finish_reason_for(resp: &ModelResponse) -> &'static str {
 }
 
 /// Build the GitHub Copilot IDE headers required by the Copilot chat API.
-/// Returns `None` for non-Copilot providers.
-fn copilot_extra_headers(req: &ProviderRequest) -> Option<genai::Headers> {
+/// Returns nothing for non-Copilot providers.
+fn copilot_extra_headers(req: &ProviderRequest) -> Vec<(String, String)> {
     if !providers::needs_copilot_session(&req.provider) {
-        return None;
+        return Vec::new();
     }
     // Agent-initiated unless the last message is from the user.
     let is_agent_call = req
@@ -559,7 +731,7 @@ fn copilot_extra_headers(req: &ProviderRequest) -> Option<genai::Headers> {
         .unwrap_or(false);
     let x_initiator = if is_agent_call { "agent" } else { "user" };
 
-    let headers: Vec<(String, String)> = vec![
+    vec![
         (
             "User-Agent".to_string(),
             providers::COPILOT_API_USER_AGENT.to_string(),
@@ -581,8 +753,7 @@ fn copilot_extra_headers(req: &ProviderRequest) -> Option<genai::Headers> {
             "conversation-edits".to_string(),
         ),
         ("X-Initiator".to_string(), x_initiator.to_string()),
-    ];
-    Some(genai::Headers::from(headers))
+    ]
 }
 
 #[cfg(test)]
@@ -690,6 +861,9 @@ mod tests {
             provider: "openai".to_string(),
             base_url: "https://api.openai.com/v1".to_string(),
             api_key: Some("sk-test".to_string()),
+            sampling: SamplingParams::default(),
+            cache_prompt: false,
+            thinking_budget: None,
         };
         // Avoid pulling the full tool registry into the assertion.
         unsafe { std::env::set_var("RUSTYCLAW_SKIP_TOOLS", "1") };
@@ -701,4 +875,181 @@ mod tests {
         assert_eq!(chat_req.messages[1].role, ChatRole::User);
         assert!(chat_req.tools.is_none());
     }
+
+    #[test]
+    fn cache_prompt_marks_leading_system_block_for_anthropic() {
+        let mut req = ProviderRequest {
+            messages: vec![
+                crate::gateway::ChatMessage::text("system", "soul"),
+                crate::gateway::ChatMessage::text("system", "workspace context"),
+                crate::gateway::ChatMessage::text("user", "hi"),
+                crate::gateway::ChatMessage::text("system", "flush note"),
+            ],
+            model: "claude-sonnet-4-20250514".to_string(),
+            provider: "anthropic".to_string(),
+            base_url: "https://api.anthropic.com".to_string(),
+            api_key: None,
+            sampling: SamplingParams::default(),
+            cache_prompt: true,
+            thinking_budget: None,
+        };
+        unsafe { std::env::set_var("RUSTYCLAW_SKIP_TOOLS", "1") };
+        let marked: Vec<bool> = to_genai_chat_request(&req)
+            .messages
+            .iter()
+            .map(|m| {
+                m.options
+                    .as_ref()
+                    .is_some_and(|o| o.cache_control.is_some())
+            })
+            .collect();
+        assert_eq!(marked, [false, true, false, false]);
+
+        req.provider = "openai".to_string();
+        let chat_req = to_genai_chat_request(&req);
+        unsafe { std::env::remove_var("RUSTYCLAW_SKIP_TOOLS") };
+        assert!(chat_req.messages.iter().all(|m| m.options.is_none()));
+    }
+
+    #[test]
+    fn sampling_validation() {
+        assert!(SamplingParams::default().validate().is_ok());
+        let ok = SamplingParams {
+            temperature: Some(0.2),
+            top_p: Some(1.0),
+            max_tokens: Some(256),
+            stop: vec!["###".to_string()],
+        };
+        assert!(ok.validate().is_ok());
+
+        let bad = |s: SamplingParams| s.validate().unwrap_err();
+        assert!(
+            bad(SamplingParams {
+                temperature: Some(2.5),
+                ..Default::default()
+            })
+            .contains("temperature")
+        );
+        assert!(
+            bad(SamplingParams {
+                top_p: Some(0.0),
+                ..Default::default()
+            })
+            .contains("top_p")
+        );
+        assert!(
+            bad(SamplingParams {
+                max_tokens: Some(0),
+                ..Default::default()
+            })
+            .contains("max_tokens")
+        );
+    }
+
+    #[test]
+    fn sampling_defaults_leave_options_untouched() {
+        let options = apply_sampling(
+            ChatOptions::default().with_max_tokens(MAX_TOKENS),
+            AdapterKind::OpenAI,
+            "gpt-4.1",
+            &SamplingParams::default(),
+        );
+        assert_eq!(options.temperature, None);
+        assert_eq!(options.top_p, None);
+        assert_eq!(options.max_tokens, Some(MAX_TOKENS));
+        assert!(options.stop_sequences.is_empty());
+    }
+
+    #[test]
+    fn sampling_drops_unsupported_per_provider() {
+        let sampling = SamplingParams {
+            temperature: Some(1.5),
+            top_p: Some(0.9),
+            max_tokens: Some(512),
+            stop: (0..6).map(|i| format!("<stop{i}>")).collect(),
+        };
+
+        let openai = apply_sampling(
+            ChatOptions::default(),
+            AdapterKind::OpenAI,
+            "gpt-4.1",
+            &sampling,
+        );
+        assert_eq!(openai.temperature, Some(1.5));
+        assert_eq!(openai.top_p, Some(0.9));
+        assert_eq!(openai.max_tokens, Some(512));
+        assert_eq!(openai.stop_sequences.len(), OPENAI_MAX_STOP);
+
+        let reasoning = apply_sampling(
+            ChatOptions::default(),
+            AdapterKind::OpenAI,
+            "o3-mini",
+            &sampling,
+        );
+        assert_eq!(reasoning.temperature, None);
+        assert_eq!(reasoning.top_p, None);
+        assert_eq!(reasoning.max_tokens, Some(512));
+
+        let anthropic = apply_sampling(
+            ChatOptions::default(),
+            AdapterKind::Anthropic,
+            "claude-sonnet-4-20250514",
+            &sampling,
+        );
+        assert_eq!(anthropic.temperature, Some(1.0));
+        assert_eq!(anthropic.top_p, None);
+        assert_eq!(anthropic.stop_sequences.len(), 6);
+    }
+
+    #[test]
+    fn thinking_budget_maps_per_provider() {
+        let mut req = ProviderRequest {
+            messages: vec![crate::gateway::ChatMessage::text("user", "hi")],
+            model: "claude-sonnet-4-20250514".to_string(),
+            provider: "anthropic".to_string(),
+            base_url: String::new(),
+            api_key: None,
+            sampling: SamplingParams::default(),
+            cache_prompt: false,
+            thinking_budget: Some(4000),
+        };
+        let sampled = ChatOptions::default()
+            .with_max_tokens(1000)
+            .with_temperature(0.3);
+
+        let anthropic = apply_thinking(sampled.clone(), AdapterKind::Anthropic, &req);
+        assert!(matches!(
+            anthropic.reasoning_effort,
+            Some(ReasoningEffort::Budget(4000))
+        ));
+        assert_eq!(anthropic.max_tokens, Some(5000));
+        assert_eq!(anthropic.temperature, None);
+
+        // Not on a tool-result round, and not at all when it's off.
+        req.messages
+            .push(crate::gateway::ChatMessage::text("tool", "{}"));
+        let tool_round = apply_thinking(sampled.clone(), AdapterKind::Anthropic, &req);
+        assert!(tool_round.reasoning_effort.is_none());
+        assert_eq!(tool_round.max_tokens, Some(1000));
+        req.thinking_budget = None;
+        let off = apply_thinking(sampled.clone(), AdapterKind::Gemini, &req);
+        assert!(off.reasoning_effort.is_none());
+
+        req.thinking_budget = Some(20_000);
+        let gemini = apply_thinking(ChatOptions::default(), AdapterKind::Gemini, &req);
+        assert!(matches!(
+            gemini.reasoning_effort,
+            Some(ReasoningEffort::Budget(20_000))
+        ));
+
+        req.model = "o4-mini".to_string();
+        let o_series = apply_thinking(ChatOptions::default(), AdapterKind::OpenAI, &req);
+        assert!(matches!(
+            o_series.reasoning_effort,
+            Some(ReasoningEffort::High)
+        ));
+        req.model = "gpt-4.1".to_string();
+        let plain = apply_thinking(ChatOptions::default(), AdapterKind::OpenAI, &req);
+        assert!(plain.reasoning_effort.is_none());
+    }
 }
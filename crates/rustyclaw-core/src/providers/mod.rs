@@ -370,6 +370,31 @@ pub fn base_url_for_provider(id: &str) -> Option<&'static str> {
     provider_by_id(id).and_then(|p| p.base_url)
 }
 
+/// Split a `provider/model` spec (as given to `--model`) into its parts.
+///
+/// Only the first `/` separates the two, so OpenRouter-style models keep
+/// theirs (`openrouter/anthropic/claude-sonnet-4`).  The provider must be
+/// one RustyClaw knows.
+pub fn parse_model_spec(spec: &str) -> Result<(String, String), String> {
+    let Some((provider, model)) = spec.trim().split_once('/') else {
+        return Err(format!(
+            "Invalid model '{}': use provider/model, e.g. anthropic/claude-haiku-4-5",
+            spec
+        ));
+    };
+    if model.is_empty() {
+        return Err(format!("Invalid model '{}': missing model name", spec));
+    }
+    if provider_by_id(provider).is_none() {
+        return Err(format!(
+            "Unknown provider '{}'. Known providers: {}",
+            provider,
+            provider_ids().join(", ")
+        ));
+    }
+    Ok((provider.to_string(), model.to_string()))
+}
+
 // ── Dynamic model fetching ──────────────────────────────────────────────────
 
 /// Rich model metadata returned by [`fetch_models_detailed`].
@@ -414,14 +439,33 @@ impl ModelInfo {
 /// Returns `Err` with a human-readable message on any failure — no silent
 /// fallbacks.  Callers should display the error to the user.
 mod device_flow;
+mod error_body;
+mod extra_headers;
 mod genai_backend;
 mod models;
+mod reasoning;
+mod refusal;
+mod response_cache;
+mod timeouts;
+mod wire_log;
 pub use device_flow::*;
+pub use error_body::{ProviderErrorBody, parse_provider_error, redact_credentials};
+pub use extra_headers::{
+    apply_extra_headers, extra_headers_for, init_extra_headers, is_auth_header,
+    redact_extra_header, resolve_extra_headers,
+};
 pub use genai_backend::{
     call_anthropic_with_tools, call_google_with_tools, call_openai_with_tools,
     encode_assistant_message, encode_tool_result,
 };
 pub use models::*;
+pub use refusal::{SafetyRefusal, detect_refusal, find_refusal, is_refusal_finish, parse_refusal};
+pub use response_cache::{ResponseCache, is_cacheable, request_key};
+pub use timeouts::{
+    ProviderTimeout, ProviderTimeouts, TimeoutPhase, default_timeouts, find_timeout,
+    init_provider_timeouts, is_local_provider, resolve_timeouts, timeouts_for,
+};
+pub use wire_log::{SENSITIVE_BANNER, init_wire_log, redact_record, redact_text};
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,351 @@
+//! Reasoning ("thinking") that models produce alongside their answer.
+//!
+//! Anthropic extended thinking and Gemini thoughts arrive as their own
+//! stream events; DeepSeek-R1, QwQ and other open models served through
+//! OpenAI-compatible endpoints write it into the reply text as
+//! `<think>…</think>`.  Either way it is forwarded as `Thinking*` frames and
+//! kept in [`ModelResponse::thinking`], apart from the answer text that
+//! goes back into the conversation.
+
+use anyhow::Result;
+
+use crate::gateway::ModelResponse;
+use crate::gateway::protocol::server;
+use crate::gateway::transport::TransportWriter;
+
+const OPEN_TAG: &str = "<think>";
+const CLOSE_TAG: &str = "</think>";
+
+/// A piece of streamed reply text, after inline thinking is split out.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    Text(String),
+    Thinking(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    /// Nothing but whitespace yet; a `<think>` here opens a block.
+    Start,
+    Thinking,
+    /// Just past `</think>`; the whitespace before the answer is dropped.
+    AfterThinking,
+    Text,
+}
+
+/// Splits a leading `<think>…</think>` block out of reply text that
+/// arrives in chunks, however the tags fall across them.
+///
+/// Only a block at the very start counts, so an answer that talks about
+/// the tag keeps it.
+#[derive(Debug)]
+pub(crate) struct ThinkSplitter {
+    state: State,
+    held: String,
+}
+
+impl ThinkSplitter {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: State::Start,
+            held: String::new(),
+        }
+    }
+
+    /// Feed the next chunk; returns what can be passed on so far.
+    pub(crate) fn push(&mut self, chunk: &str) -> Vec<Segment> {
+        if self.state == State::Text {
+            return non_empty(Segment::Text(chunk.to_string()));
+        }
+        let mut buf = std::mem::take(&mut self.held);
+        buf.push_str(chunk);
+        let mut out = Vec::new();
+        loop {
+            match self.state {
+                State::Start => {
+                    let trimmed = buf.trim_start();
+                    if let Some(rest) = trimmed.strip_prefix(OPEN_TAG) {
+                        buf = rest.to_string();
+                        self.state = State::Thinking;
+                    } else if OPEN_TAG.starts_with(trimmed) {
+                        self.held = buf;
+                        return out;
+                    } else {
+                        self.state = State::Text;
+                    }
+                }
+                State::Thinking => match buf.find(CLOSE_TAG) {
+                    Some(end) => {
+                        out.extend(non_empty(Segment::Thinking(buf[..end].to_string())));
+                        buf = buf[end + CLOSE_TAG.len()..].to_string();
+                        self.state = State::AfterThinking;
+                    }
+                    None => {
+                        let keep = partial_tag_len(&buf, CLOSE_TAG);
+                        self.held = buf.split_off(buf.len() - keep);
+                        out.extend(non_empty(Segment::Thinking(buf)));
+                        return out;
+                    }
+                },
+                State::AfterThinking => {
+                    buf = buf.trim_start().to_string();
+                    if !buf.is_empty() {
+                        self.state = State::Text;
+                    }
+                }
+                State::Text => {
+                    out.extend(non_empty(Segment::Text(buf)));
+                    return out;
+                }
+            }
+            if buf.is_empty() && self.state != State::Start {
+                return out;
+            }
+        }
+    }
+
+    /// The stream ended: pass on whatever was held back.  An unclosed
+    /// block is all thinking.
+    pub(crate) fn finish(&mut self) -> Vec<Segment> {
+        let held = std::mem::take(&mut self.held);
+        match self.state {
+            State::Start => non_empty(Segment::Text(held)),
+            State::Thinking => non_empty(Segment::Thinking(held)),
+            State::AfterThinking | State::Text => Vec::new(),
+        }
+    }
+}
+
+fn non_empty(segment: Segment) -> Vec<Segment> {
+    match &segment {
+        Segment::Text(s) | Segment::Thinking(s) if s.is_empty() => Vec::new(),
+        _ => vec![segment],
+    }
+}
+
+/// Length of the longest suffix of `buf` that could begin `tag`.
+fn partial_tag_len(buf: &str, tag: &str) -> usize {
+    (1..tag.len())
+        .rev()
+        .find(|&n| buf.ends_with(&tag[..n]))
+        .unwrap_or(0)
+}
+
+/// Split complete reply text into `(thinking, answer)`.
+pub(crate) fn split_think_tags(text: &str) -> (String, String) {
+    let mut splitter = ThinkSplitter::new();
+    let mut segments = splitter.push(text);
+    segments.extend(splitter.finish());
+    let (mut thinking, mut answer) = (String::new(), String::new());
+    for segment in segments {
+        match segment {
+            Segment::Thinking(s) => thinking.push_str(&s),
+            Segment::Text(s) => answer.push_str(&s),
+        }
+    }
+    (thinking, answer)
+}
+
+/// Builds a [`ModelResponse`] from a streamed reply while forwarding it to
+/// the client: answer text as `Chunk` frames, thinking — streamed as such
+/// or inline — as `ThinkingStart` / `ThinkingDelta` / `ThinkingEnd`.
+#[derive(Debug)]
+pub(crate) struct ReplyStream {
+    pub(crate) response: ModelResponse,
+    splitter: ThinkSplitter,
+    stream_started: bool,
+    thinking_open: bool,
+}
+
+impl ReplyStream {
+    pub(crate) fn new() -> Self {
+        Self {
+            response: ModelResponse::default(),
+            splitter: ThinkSplitter::new(),
+            stream_started: false,
+            thinking_open: false,
+        }
+    }
+
+    pub(crate) async fn start(&mut self, writer: &mut dyn TransportWriter) -> Result<()> {
+        if !self.stream_started {
+            server::send_stream_start(writer).await?;
+            self.stream_started = true;
+        }
+        Ok(())
+    }
+
+    /// A chunk of reply text, which may carry inline thinking.
+    pub(crate) async fn text(
+        &mut self,
+        writer: &mut dyn TransportWriter,
+        chunk: &str,
+    ) -> Result<()> {
+        for segment in self.splitter.push(chunk) {
+            self.emit(writer, segment).await?;
+        }
+        Ok(())
+    }
+
+    /// A chunk the provider marked as reasoning.
+    pub(crate) async fn reasoning(
+        &mut self,
+        writer: &mut dyn TransportWriter,
+        chunk: &str,
+    ) -> Result<()> {
+        self.emit(writer, Segment::Thinking(chunk.to_string()))
+            .await
+    }
+
+    /// Flush what the splitter held back and close an open thinking block.
+    pub(crate) async fn finish(&mut self, writer: &mut dyn TransportWriter) -> Result<()> {
+        for segment in self.splitter.finish() {
+            self.emit(writer, segment).await?;
+        }
+        self.end_thinking(writer).await;
+        Ok(())
+    }
+
+    async fn emit(&mut self, writer: &mut dyn TransportWriter, segment: Segment) -> Result<()> {
+        match segment {
+            Segment::Thinking(delta) => {
+                // A client that misses thinking still gets the answer.
+                if !self.thinking_open {
+                    let _ = server::send_thinking_start(writer).await;
+                    self.thinking_open = true;
+                }
+                let _ = server::send_thinking_delta(writer, &delta).await;
+                self.response.thinking.push_str(&delta);
+            }
+            Segment::Text(delta) => {
+                self.end_thinking(writer).await;
+                self.start(writer).await?;
+                server::send_chunk(writer, &delta).await?;
+                self.response.text.push_str(&delta);
+            }
+        }
+        Ok(())
+    }
+
+    async fn end_thinking(&mut self, writer: &mut dyn TransportWriter) {
+        if self.thinking_open {
+            let _ = server::send_thinking_end(writer).await;
+            self.thinking_open = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::ServerPayload;
+    use crate::gateway::transport::CollectWriter;
+
+    fn split_chunks(chunks: &[&str]) -> Vec<Segment> {
+        let mut splitter = ThinkSplitter::new();
+        let mut out: Vec<Segment> = chunks.iter().flat_map(|c| splitter.push(c)).collect();
+        out.extend(splitter.finish());
+        // Merge neighbours of the same kind, as a reader would see them.
+        out.into_iter().fold(Vec::new(), |mut acc, seg| {
+            match (acc.last_mut(), &seg) {
+                (Some(Segment::Text(a)), Segment::Text(b))
+                | (Some(Segment::Thinking(a)), Segment::Thinking(b)) => a.push_str(b),
+                _ => acc.push(seg),
+            }
+            acc
+        })
+    }
+
+    #[test]
+    fn test_inline_thinking_split_across_chunks() {
+        let expected = vec![
+            Segment::Thinking("The user wants 2+2.".into()),
+            Segment::Text("It's 4.".into()),
+        ];
+        let whole = "<think>The user wants 2+2.</think>\n\nIt's 4.";
+        assert_eq!(split_chunks(&[whole]), expected);
+        // Tags cut at every position still split the same way.
+        for at in 1..whole.len() {
+            let (a, b) = whole.split_at(at);
+            assert_eq!(split_chunks(&[a, b]), expected, "split at {at}");
+        }
+        let chars: Vec<String> = whole.chars().map(String::from).collect();
+        let chars: Vec<&str> = chars.iter().map(String::as_str).collect();
+        assert_eq!(split_chunks(&chars), expected);
+    }
+
+    #[test]
+    fn test_text_without_leading_think_is_left_alone() {
+        assert_eq!(
+            split_think_tags("Use a <think> tag.</think>"),
+            (String::new(), "Use a <think> tag.</think>".to_string())
+        );
+        assert_eq!(
+            split_think_tags("<thin"),
+            (String::new(), "<thin".to_string())
+        );
+        assert_eq!(
+            split_think_tags("  <b>hi</b>"),
+            (String::new(), "  <b>hi</b>".to_string())
+        );
+        // Cut off mid-thought: all of it was thinking.
+        assert_eq!(
+            split_think_tags("<think>still going</thi"),
+            ("still going</thi".to_string(), String::new())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reply_stream_labels_reasoning_and_answer() {
+        let mut sink = CollectWriter(Vec::new());
+        let mut reply = ReplyStream::new();
+        // A provider reasoning event, then an answer with inline thinking
+        // (as a proxy that flattens both might send).
+        reply.start(&mut sink).await.unwrap();
+        reply.reasoning(&mut sink, "Check units. ").await.unwrap();
+        reply.text(&mut sink, "<think>Metres, so").await.unwrap();
+        reply.text(&mut sink, " 1000.</th").await.unwrap();
+        reply.text(&mut sink, "ink>A km is ").await.unwrap();
+        reply.text(&mut sink, "1000 m.").await.unwrap();
+        reply.finish(&mut sink).await.unwrap();
+
+        assert_eq!(reply.response.thinking, "Check units. Metres, so 1000.");
+        assert_eq!(reply.response.text, "A km is 1000 m.");
+
+        let kinds: Vec<String> = sink
+            .0
+            .iter()
+            .map(|p| match p {
+                ServerPayload::StreamStart => "start".into(),
+                ServerPayload::ThinkingStart => "think{".into(),
+                ServerPayload::ThinkingDelta { delta } => format!("think:{delta}"),
+                ServerPayload::ThinkingEnd => "}think".into(),
+                ServerPayload::Chunk { delta } => format!("text:{delta}"),
+                other => panic!("unexpected frame {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "start",
+                "think{",
+                "think:Check units. ",
+                "think:Metres, so",
+                "think: 1000.",
+                "}think",
+                "text:A km is ",
+                "text:1000 m.",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reply_stream_closes_unfinished_thinking() {
+        let mut sink = CollectWriter(Vec::new());
+        let mut reply = ReplyStream::new();
+        reply.reasoning(&mut sink, "hmm").await.unwrap();
+        reply.finish(&mut sink).await.unwrap();
+        assert!(matches!(sink.0.last(), Some(ServerPayload::ThinkingEnd)));
+        assert!(reply.response.text.is_empty());
+    }
+}
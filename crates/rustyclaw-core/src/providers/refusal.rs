@@ -0,0 +1,212 @@
+//! Recognise a model declining to answer on safety grounds.
+//!
+//! Each provider flags a safety refusal its own way, and none of them as an
+//! ordinary error:
+//!
+//! - Anthropic: `"stop_reason": "refusal"` (top level, or in a streamed
+//!   `message_delta`)
+//! - OpenAI and Azure OpenAI: `"finish_reason": "content_filter"` on a
+//!   choice, a `refusal` message, or an error with code `content_filter` /
+//!   `content_policy_violation`; Azure names the filtered categories in
+//!   `content_filter_results`
+//! - Gemini: `promptFeedback.blockReason` when the prompt is blocked, or a
+//!   candidate `finishReason` of `SAFETY`, `BLOCKLIST`,
+//!   `PROHIBITED_CONTENT`, `SPII` or `IMAGE_SAFETY`; the blocked
+//!   `safetyRatings` name the category
+//!
+//! A refusal found in a failed call is carried as a [`SafetyRefusal`] in the
+//! error chain (see [`find_refusal`]), so the gateway can tell the user the
+//! model declined rather than report a provider failure — and knows not to
+//! retry it.
+
+use std::fmt;
+
+use serde_json::Value;
+
+use super::error_body::debug_string_field;
+
+/// Finish reasons, across providers, that mean the reply was withheld.
+const REFUSAL_FINISH_REASONS: &[&str] = &[
+    "refusal",
+    "content_filter",
+    "safety",
+    "blocklist",
+    "prohibited_content",
+    "spii",
+    "image_safety",
+];
+
+/// OpenAI error codes for a request rejected by the content filter.
+const REFUSAL_ERROR_CODES: &[&str] = &["content_filter", "content_policy_violation"];
+
+/// A model call the provider declined on safety grounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafetyRefusal {
+    pub provider: String,
+    /// The provider's own signal, e.g. `finish_reason=content_filter`.
+    pub signal: String,
+    /// What was flagged (`hate`, `dangerous_content`, …), when the
+    /// provider says.
+    pub category: Option<String>,
+}
+
+impl fmt::Display for SafetyRefusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} declined to respond on safety grounds", self.provider)?;
+        match &self.category {
+            Some(category) => write!(f, " (category: {}; {})", category, self.signal),
+            None => write!(f, " ({})", self.signal),
+        }
+    }
+}
+
+impl std::error::Error for SafetyRefusal {}
+
+/// Whether a finish reason means the provider withheld the reply.
+pub fn is_refusal_finish(reason: &str) -> bool {
+    REFUSAL_FINISH_REASONS.contains(&reason.to_ascii_lowercase().as_str())
+}
+
+/// The refusal signalled by a provider response or error body, if any.
+pub fn detect_refusal(provider: &str, body: &Value) -> Option<SafetyRefusal> {
+    let (signal, category) = anthropic_refusal(body)
+        .or_else(|| openai_refusal(body))
+        .or_else(|| gemini_refusal(body))?;
+    Some(SafetyRefusal {
+        provider: provider.to_string(),
+        signal,
+        category,
+    })
+}
+
+/// Find a refusal in the text of a failed call.  Like
+/// [`parse_provider_error`](super::parse_provider_error), the body may be
+/// raw JSON or a `Debug`-escaped `body: "…"` field.
+pub fn parse_refusal(provider: &str, text: &str) -> Option<SafetyRefusal> {
+    let escaped = debug_string_field(text, "body: ");
+    let candidates = escaped
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(text));
+    for candidate in candidates {
+        for (idx, _) in candidate.match_indices(['{', '[']) {
+            let mut values =
+                serde_json::Deserializer::from_str(&candidate[idx..]).into_iter::<Value>();
+            if let Some(Ok(value)) = values.next()
+                && let Some(refusal) = detect_refusal(provider, &value)
+            {
+                return Some(refusal);
+            }
+        }
+    }
+    None
+}
+
+/// The [`SafetyRefusal`] behind `err`, if a refusal is what failed it.
+pub fn find_refusal(err: &anyhow::Error) -> Option<&SafetyRefusal> {
+    err.downcast_ref::<SafetyRefusal>()
+        .or_else(|| err.chain().find_map(|e| e.downcast_ref::<SafetyRefusal>()))
+}
+
+/// Tag a failed call as a refusal when its error text carries one.
+pub(crate) fn tag_refusal(provider: &str, err: anyhow::Error) -> anyhow::Error {
+    if find_refusal(&err).is_some() {
+        return err;
+    }
+    match parse_refusal(provider, &format!("{err:#}")) {
+        Some(refusal) => err.context(refusal),
+        None => err,
+    }
+}
+
+// ── Provider shapes ─────────────────────────────────────────────────────────
+
+type Signal = (String, Option<String>);
+
+fn anthropic_refusal(body: &Value) -> Option<Signal> {
+    let stop = body
+        .get("stop_reason")
+        .or_else(|| body.get("delta").and_then(|d| d.get("stop_reason")))?;
+    (stop.as_str() == Some("refusal")).then(|| ("stop_reason=refusal".to_string(), None))
+}
+
+fn openai_refusal(body: &Value) -> Option<Signal> {
+    if let Some(err) = body.get("error")
+        && let Some(code) = err.get("code").and_then(|v| v.as_str())
+        && REFUSAL_ERROR_CODES.contains(&code)
+    {
+        let category = err
+            .get("innererror")
+            .and_then(|inner| inner.get("content_filter_result"))
+            .and_then(filtered_categories);
+        return Some((format!("error={}", code), category));
+    }
+
+    for choice in body.get("choices")?.as_array()? {
+        let category = choice
+            .get("content_filter_results")
+            .and_then(filtered_categories);
+        if choice.get("finish_reason").and_then(|v| v.as_str()) == Some("content_filter") {
+            return Some(("finish_reason=content_filter".to_string(), category));
+        }
+        let refusal = choice
+            .get("message")
+            .or_else(|| choice.get("delta"))
+            .and_then(|m| m.get("refusal"))
+            .and_then(|v| v.as_str());
+        if refusal.is_some_and(|r| !r.trim().is_empty()) {
+            return Some(("refusal".to_string(), category));
+        }
+    }
+    None
+}
+
+fn gemini_refusal(body: &Value) -> Option<Signal> {
+    // Streamed and batched replies can arrive as an array of chunks.
+    if let Value::Array(items) = body {
+        return items.iter().find_map(gemini_refusal);
+    }
+
+    if let Some(feedback) = body.get("promptFeedback")
+        && let Some(reason) = feedback.get("blockReason").and_then(|v| v.as_str())
+        && reason != "BLOCK_REASON_UNSPECIFIED"
+    {
+        let category = feedback.get("safetyRatings").and_then(blocked_rating);
+        return Some((format!("blockReason={}", reason), category));
+    }
+
+    for candidate in body.get("candidates")?.as_array()? {
+        let Some(reason) = candidate.get("finishReason").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if is_refusal_finish(reason) {
+            let category = candidate.get("safetyRatings").and_then(blocked_rating);
+            return Some((format!("finishReason={}", reason), category));
+        }
+    }
+    None
+}
+
+/// Azure's `content_filter_results`: the categories marked `filtered`.
+fn filtered_categories(results: &Value) -> Option<String> {
+    let names: Vec<&str> = results
+        .as_object()?
+        .iter()
+        .filter(|(_, r)| r.get("filtered").and_then(|v| v.as_bool()) == Some(true))
+        .map(|(name, _)| name.as_str())
+        .collect();
+    (!names.is_empty()).then(|| names.join(", "))
+}
+
+/// Gemini's `safetyRatings`: the categories marked `blocked`, without the
+/// `HARM_CATEGORY_` prefix.
+fn blocked_rating(ratings: &Value) -> Option<String> {
+    let names: Vec<String> = ratings
+        .as_array()?
+        .iter()
+        .filter(|r| r.get("blocked").and_then(|v| v.as_bool()) == Some(true))
+        .filter_map(|r| r.get("category").and_then(|v| v.as_str()))
+        .map(|c| c.trim_start_matches("HARM_CATEGORY_").to_ascii_lowercase())
+        .collect();
+    (!names.is_empty()).then(|| names.join(", "))
+}
@@ -0,0 +1,128 @@
+//! Reuse of model responses for exact-duplicate requests.
+//!
+//! Agent loops occasionally send the same conversation twice — a prompt
+//! re-sent after a disconnect, a regenerate in a fresh thread.  With
+//! `[prompt_cache] responses = true` the gateway keeps a small per-connection
+//! [`ResponseCache`] and answers such repeats without calling the provider.
+//!
+//! Requests are keyed by [`request_key`], a SHA-256 digest over
+//! everything that reaches the provider except the credential, so two
+//! different prompts can't share an entry.  Only deterministic requests
+//! (`temperature = 0`) are cached unless `allow_nondeterministic` is set,
+//! since replaying a sampled answer changes what the user asked for.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::gateway::{ModelResponse, ProviderRequest};
+
+/// SHA-256 of a normalized request; see [`request_key`].
+pub type RequestKey = [u8; 32];
+
+/// Whether `req` may be answered from the cache.
+pub fn is_cacheable(req: &ProviderRequest, allow_nondeterministic: bool) -> bool {
+    allow_nondeterministic || req.sampling.temperature == Some(0.0)
+}
+
+/// Digest of the normalized request.
+///
+/// Message text is compared with line endings unified and trailing
+/// whitespace removed, so cosmetic differences don't defeat the cache.
+/// The API key is left out; a rotated Copilot session token is still the
+/// same request.
+pub fn request_key(req: &ProviderRequest) -> RequestKey {
+    let s = &req.sampling;
+    let messages: Vec<_> = req
+        .messages
+        .iter()
+        .map(|msg| {
+            json!({
+                "role": msg.role,
+                "content": normalize(&msg.content),
+                "tool_calls": msg.tool_calls,
+                "tool_call_id": msg.tool_call_id,
+                "media": msg.media,
+            })
+        })
+        .collect();
+    let normalized = json!({
+        "provider": req.provider,
+        "model": req.model,
+        "base_url": req.base_url.trim_end_matches('/'),
+        "cache_prompt": req.cache_prompt,
+        "thinking_budget": req.thinking_budget,
+        "temperature": s.temperature,
+        "top_p": s.top_p,
+        "max_tokens": s.max_tokens,
+        "stop": s.stop,
+        "messages": messages,
+    });
+    let mut key = RequestKey::default();
+    key.copy_from_slice(&Sha256::digest(normalized.to_string().as_bytes()));
+    key
+}
+
+fn normalize(content: &str) -> String {
+    content
+        .replace("\r\n", "\n")
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+/// Bounded least-recently-used map from [`request_key`] to a response.
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    entries: HashMap<RequestKey, ModelResponse>,
+    /// Keys from least to most recently used.
+    order: VecDeque<RequestKey>,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached responses.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// A copy of the response cached under `key`, marking it recently used.
+    pub fn get(&mut self, key: RequestKey) -> Option<ModelResponse> {
+        let resp = self.entries.get(&key)?.clone();
+        self.touch(key);
+        Some(resp)
+    }
+
+    /// Cache `resp` under `key`, evicting the least recently used entries
+    /// beyond `capacity`.  A capacity of 0 clears the cache.
+    pub fn insert(&mut self, key: RequestKey, resp: ModelResponse, capacity: usize) {
+        if capacity == 0 {
+            self.entries.clear();
+            self.order.clear();
+            return;
+        }
+        self.entries.insert(key, resp);
+        self.touch(key);
+        while self.order.len() > capacity {
+            if let Some(old) = self.order.pop_front() {
+                self.entries.remove(&old);
+            }
+        }
+    }
+
+    fn touch(&mut self, key: RequestKey) {
+        self.order.retain(|k| *k != key);
+        self.order.push_back(key);
+    }
+}
@@ -22,6 +22,36 @@ fn test_provider_by_id() {
     assert!(provider.is_none());
 }
 
+#[test]
+fn test_parse_model_spec() {
+    assert_eq!(
+        parse_model_spec("anthropic/claude-haiku-4-5").unwrap(),
+        ("anthropic".to_string(), "claude-haiku-4-5".to_string())
+    );
+    // Only the first slash separates provider from model.
+    let (provider, model) = parse_model_spec("openrouter/anthropic/claude-sonnet-4").unwrap();
+    assert_eq!(
+        (provider.as_str(), model.as_str()),
+        ("openrouter", "anthropic/claude-sonnet-4")
+    );
+
+    assert!(
+        parse_model_spec("claude-haiku")
+            .unwrap_err()
+            .contains("provider/model")
+    );
+    assert!(
+        parse_model_spec("anthropic/")
+            .unwrap_err()
+            .contains("missing model")
+    );
+    let err = parse_model_spec("nope/some-model").unwrap_err();
+    assert!(
+        err.contains("Unknown provider 'nope'") && err.contains("anthropic"),
+        "{err}"
+    );
+}
+
 #[test]
 fn test_provider_auth_methods() {
     // API key providers
@@ -444,3 +474,316 @@ fn test_truncate_for_error_truncates_long_bodies() {
 fn test_truncate_for_error_passes_through_short_bodies() {
     assert_eq!(truncate_for_error("hello"), "hello");
 }
+
+#[test]
+fn test_parse_provider_error_openai_debug_body() {
+    // genai renders the failed response with `Debug`, escaping the body.
+    let text = r#"Web call failed for model 'gpt-4o'. Cause: ResponseFailedStatus { status: 400, body: "{\n  \"error\": {\n    \"message\": \"This model's maximum context length is 128000 tokens.\",\n    \"type\": \"invalid_request_error\",\n    \"code\": \"context_length_exceeded\"\n  }\n}", headers: {} }"#;
+    let body = parse_provider_error(text).expect("should parse");
+    assert_eq!(body.status, Some(400));
+    assert_eq!(body.code.as_deref(), Some("context_length_exceeded"));
+    assert_eq!(
+        body.message,
+        "This model's maximum context length is 128000 tokens."
+    );
+    assert_eq!(
+        body.summary(),
+        "This model's maximum context length is 128000 tokens. \
+         (HTTP 400, context_length_exceeded)"
+    );
+}
+
+#[test]
+fn test_parse_provider_error_anthropic_and_google_shapes() {
+    let anthropic = r#"HTTP 404: {"type":"error","error":{"type":"not_found_error","message":"model: claude-nope"}}"#;
+    let body = parse_provider_error(anthropic).unwrap();
+    assert_eq!(body.status, Some(404));
+    assert_eq!(body.code.as_deref(), Some("not_found_error"));
+    assert_eq!(body.message, "model: claude-nope");
+
+    let google =
+        r#"[{"error":{"code":400,"message":"API key not valid.","status":"INVALID_ARGUMENT"}}]"#;
+    let body = parse_provider_error(google).unwrap();
+    assert_eq!(body.status, Some(400));
+    assert_eq!(body.code.as_deref(), Some("INVALID_ARGUMENT"));
+}
+
+#[test]
+fn test_parse_provider_error_none_without_error_object() {
+    assert!(parse_provider_error("Connection timeout after 30s").is_none());
+    assert!(parse_provider_error(r#"status: 500, body: "{\"ok\": false}""#).is_none());
+}
+
+#[test]
+fn test_redact_credentials_masks_active_key_and_known_formats() {
+    let key = "my-custom-proxy-key-1234";
+    let text = format!(
+        "Incorrect API key provided: {key}. Also saw sk-proj-abcdefghijklmnopqrstuvwxyz0123"
+    );
+    let redacted = redact_credentials(&text, Some(key));
+    assert!(!redacted.contains(key), "got {redacted}");
+    assert!(!redacted.contains("sk-proj-abcdefghij"), "got {redacted}");
+    assert!(redacted.starts_with("Incorrect API key provided: <redacted"));
+}
+
+fn cache_test_request(temperature: Option<f64>, last: &str) -> crate::gateway::ProviderRequest {
+    use crate::gateway::{ChatMessage, ProviderRequest, SamplingParams};
+    ProviderRequest {
+        messages: vec![
+            ChatMessage::text("system", "be brief"),
+            ChatMessage::text("user", last),
+        ],
+        model: "claude-sonnet-4-20250514".to_string(),
+        provider: "anthropic".to_string(),
+        base_url: "https://api.anthropic.com".to_string(),
+        api_key: Some("sk-ant-one".to_string()),
+        sampling: SamplingParams {
+            temperature,
+            ..Default::default()
+        },
+        cache_prompt: false,
+        thinking_budget: None,
+    }
+}
+
+#[test]
+fn test_request_key_normalizes_whitespace_and_ignores_api_key() {
+    let a = cache_test_request(Some(0.0), "list files");
+    let mut b = cache_test_request(Some(0.0), "list files  \r\n");
+    b.api_key = Some("sk-ant-two".to_string());
+    assert_eq!(request_key(&a), request_key(&b));
+
+    let c = cache_test_request(Some(0.0), "list dirs");
+    assert_ne!(request_key(&a), request_key(&c));
+    let d = cache_test_request(Some(0.5), "list files");
+    assert_ne!(request_key(&a), request_key(&d));
+}
+
+#[test]
+fn test_request_key_separates_message_boundaries() {
+    // The same text split across messages differently is a different prompt.
+    let mut a = cache_test_request(Some(0.0), "b");
+    a.messages[0].content = "a".to_string();
+    let mut b = cache_test_request(Some(0.0), "");
+    b.messages[0].content = "ab".to_string();
+    assert_ne!(request_key(&a), request_key(&b));
+}
+
+#[test]
+fn test_is_cacheable_requires_deterministic_sampling() {
+    assert!(is_cacheable(&cache_test_request(Some(0.0), "hi"), false));
+    assert!(!is_cacheable(&cache_test_request(None, "hi"), false));
+    assert!(!is_cacheable(&cache_test_request(Some(0.7), "hi"), false));
+    assert!(is_cacheable(&cache_test_request(Some(0.7), "hi"), true));
+}
+
+#[test]
+fn test_response_cache_evicts_least_recently_used() {
+    let resp = |text: &str| crate::gateway::ModelResponse {
+        text: text.to_string(),
+        ..Default::default()
+    };
+    let mut cache = ResponseCache::new();
+    cache.insert([1; 32], resp("one"), 2);
+    cache.insert([2; 32], resp("two"), 2);
+    assert_eq!(cache.get([1; 32]).unwrap().text, "one");
+    cache.insert([3; 32], resp("three"), 2);
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get([2; 32]).is_none());
+    assert_eq!(cache.get([1; 32]).unwrap().text, "one");
+    assert_eq!(cache.get([3; 32]).unwrap().text, "three");
+
+    cache.insert([4; 32], resp("four"), 0);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_provider_timeouts_defaults_and_overrides() {
+    use crate::config::ProviderTimeoutConfig;
+    use std::time::Duration;
+
+    let local = default_timeouts("ollama");
+    let cloud = default_timeouts("anthropic");
+    assert!(local.request > cloud.request);
+    assert!(local.connect < cloud.connect);
+
+    let config = ProviderTimeoutConfig {
+        request_timeout_secs: Some(0),
+        connect_timeout_secs: Some(2),
+    };
+    let resolved = resolve_timeouts("ollama", Some(&config));
+    assert_eq!(resolved.request, None);
+    assert_eq!(resolved.connect, Some(Duration::from_secs(2)));
+
+    let partial = ProviderTimeoutConfig {
+        request_timeout_secs: Some(30),
+        ..Default::default()
+    };
+    let resolved = resolve_timeouts("openai", Some(&partial));
+    assert_eq!(resolved.request, Some(Duration::from_secs(30)));
+    assert_eq!(resolved.connect, cloud.connect);
+}
+
+#[test]
+fn test_provider_timeouts_follow_reinit() {
+    use crate::config::ProviderTimeoutConfig;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    let limits = |secs| {
+        HashMap::from([(
+            "exo".to_string(),
+            ProviderTimeoutConfig {
+                request_timeout_secs: Some(secs),
+                ..Default::default()
+            },
+        )])
+    };
+    init_provider_timeouts(&limits(20));
+    assert_eq!(timeouts_for("exo").request, Some(Duration::from_secs(20)));
+    // A reload applies the new values.
+    init_provider_timeouts(&limits(90));
+    assert_eq!(timeouts_for("exo").request, Some(Duration::from_secs(90)));
+    init_provider_timeouts(&HashMap::new());
+    assert_eq!(timeouts_for("exo"), default_timeouts("exo"));
+}
+
+#[tokio::test]
+async fn test_stalled_call_fails_with_provider_timeout() {
+    use std::time::Duration;
+
+    let stalled = std::future::pending::<()>();
+    let limit = Some(Duration::from_millis(20));
+    let err = timeouts::within("lmstudio", TimeoutPhase::Stream, limit, stalled)
+        .await
+        .unwrap_err();
+    assert_eq!(err.phase, TimeoutPhase::Stream);
+
+    let err = anyhow::Error::new(err).context("Model call failed");
+    let found = find_timeout(&err).expect("timeout kept in the chain");
+    assert_eq!(found.provider, "lmstudio");
+    assert!(find_timeout(&anyhow::anyhow!("Connection timeout after 30s")).is_none());
+
+    let quick = timeouts::within("lmstudio", TimeoutPhase::Response, limit, async { 7 });
+    assert_eq!(quick.await.unwrap(), 7);
+}
+#[test]
+fn test_detect_refusal_provider_shapes() {
+    let anthropic = serde_json::json!({
+        "type": "message",
+        "content": [],
+        "stop_reason": "refusal"
+    });
+    let refusal = detect_refusal("anthropic", &anthropic).expect("anthropic refusal");
+    assert_eq!(refusal.signal, "stop_reason=refusal");
+    assert_eq!(refusal.category, None);
+    let delta =
+        serde_json::json!({ "type": "message_delta", "delta": { "stop_reason": "refusal" } });
+    assert!(detect_refusal("anthropic", &delta).is_some());
+
+    let openai = serde_json::json!({
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": null },
+            "finish_reason": "content_filter",
+            "content_filter_results": {
+                "hate": { "filtered": false, "severity": "safe" },
+                "violence": { "filtered": true, "severity": "high" }
+            }
+        }]
+    });
+    let refusal = detect_refusal("azure", &openai).expect("openai refusal");
+    assert_eq!(refusal.signal, "finish_reason=content_filter");
+    assert_eq!(refusal.category.as_deref(), Some("violence"));
+
+    let gemini = serde_json::json!({
+        "candidates": [{
+            "finishReason": "SAFETY",
+            "safetyRatings": [
+                { "category": "HARM_CATEGORY_HARASSMENT", "probability": "NEGLIGIBLE" },
+                { "category": "HARM_CATEGORY_DANGEROUS_CONTENT", "probability": "HIGH", "blocked": true }
+            ]
+        }]
+    });
+    let refusal = detect_refusal("google", &gemini).expect("gemini refusal");
+    assert_eq!(refusal.signal, "finishReason=SAFETY");
+    assert_eq!(refusal.category.as_deref(), Some("dangerous_content"));
+    let blocked_prompt =
+        serde_json::json!({ "promptFeedback": { "blockReason": "PROHIBITED_CONTENT" } });
+    let refusal = detect_refusal("google", &blocked_prompt).unwrap();
+    assert_eq!(refusal.signal, "blockReason=PROHIBITED_CONTENT");
+
+    let normal = serde_json::json!({
+        "stop_reason": "end_turn",
+        "choices": [{ "finish_reason": "stop" }],
+        "candidates": [{ "finishReason": "STOP" }]
+    });
+    assert!(detect_refusal("openai", &normal).is_none());
+    assert!(is_refusal_finish("content_filter"));
+    assert!(is_refusal_finish("SAFETY"));
+    assert!(!is_refusal_finish("length"));
+}
+
+#[test]
+fn test_parse_refusal_from_failed_call() {
+    // Azure rejects a filtered prompt with a 400 whose body names the category.
+    let text = r#"Web call failed for model 'gpt-4o'. Cause: ResponseFailedStatus { status: 400, body: "{\"error\": {\"message\": \"The response was filtered\", \"code\": \"content_filter\", \"innererror\": {\"code\": \"ResponsibleAIPolicyViolation\", \"content_filter_result\": {\"self_harm\": {\"filtered\": true, \"severity\": \"medium\"}}}}}", headers: {} }"#;
+    let refusal = parse_refusal("azure", text).expect("should detect");
+    assert_eq!(refusal.signal, "error=content_filter");
+    assert_eq!(refusal.category.as_deref(), Some("self_harm"));
+    assert_eq!(
+        refusal.to_string(),
+        "azure declined to respond on safety grounds (category: self_harm; error=content_filter)"
+    );
+
+    let err = refusal::tag_refusal("azure", anyhow::anyhow!("{}", text));
+    assert_eq!(find_refusal(&err), Some(&refusal));
+
+    let other = r#"status: 400, body: "{\"error\": {\"message\": \"bad\", \"code\": \"context_length_exceeded\"}}""#;
+    assert!(parse_refusal("openai", other).is_none());
+    assert!(
+        find_refusal(&refusal::tag_refusal(
+            "openai",
+            anyhow::anyhow!("{}", other)
+        ))
+        .is_none()
+    );
+}
+
+#[test]
+fn test_extra_headers_go_on_the_request_without_replacing_auth() {
+    use std::collections::HashMap;
+
+    let config: HashMap<String, String> = [
+        ("X-Org-Id", "acme"),
+        ("Proxy-Authorization", "Basic dXNlcjpwYXNz"),
+        ("authorization", "Bearer from-config"),
+        ("Bad Header", "x"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    let headers = resolve_extra_headers("openai", &config);
+    let names: Vec<&str> = headers.iter().map(|(k, _)| k.as_str()).collect();
+    assert_eq!(names, ["Proxy-Authorization", "X-Org-Id"]);
+
+    // The adapter's credential stays; a caller-supplied one is skipped too.
+    let mut with_auth = headers.clone();
+    with_auth.push(("Authorization".into(), "Bearer sneaky".into()));
+    let http = reqwest::Client::new();
+    let builder = http
+        .get("https://llm-gateway.corp.test/v1/models")
+        .bearer_auth("sk-real");
+    let request = apply_extra_headers(builder, &with_auth).build().unwrap();
+    let sent = request.headers();
+    assert_eq!(sent["x-org-id"], "acme");
+    assert_eq!(sent["proxy-authorization"], "Basic dXNlcjpwYXNz");
+    assert_eq!(sent.get_all("authorization").iter().count(), 1);
+    assert_eq!(sent["authorization"], "Bearer sk-real");
+
+    // Credential-like values are masked for logs.
+    assert_eq!(redact_extra_header("X-Org-Id", "acme"), "acme");
+    let masked = redact_extra_header("Proxy-Authorization", "Basic dXNlcjpwYXNz");
+    assert!(!masked.contains("dXNlcjpwYXNz"), "{}", masked);
+}
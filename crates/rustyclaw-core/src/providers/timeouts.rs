@@ -0,0 +1,209 @@
+//! Per-provider request and connect timeouts (`[provider_timeouts]`).
+//!
+//! A local model can spend minutes loading before its first token, while a
+//! cloud API that hasn't answered in that time is better given up on, so
+//! each provider id has its own limits.  The request timeout bounds a whole
+//! non-streamed reply but only the gap between two events of a streamed
+//! one: a long generation that keeps producing output is never cut off.
+//!
+//! A limit that trips is reported as a [`ProviderTimeout`] in the error
+//! chain (see [`find_timeout`]), so callers can tell it from other failures.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+
+use tracing::warn;
+
+use crate::config::ProviderTimeoutConfig;
+
+/// Providers served from the user's own machine or network.
+const LOCAL_PROVIDERS: &[&str] = &["ollama", "lmstudio", "exo", "llamacpp"];
+
+/// Whether `provider` is served from the user's own machine or network.
+pub fn is_local_provider(provider: &str) -> bool {
+    LOCAL_PROVIDERS.contains(&provider)
+}
+
+/// Resolved limits for one provider.  `None` means no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProviderTimeouts {
+    pub request: Option<Duration>,
+    pub connect: Option<Duration>,
+}
+
+/// The limits used for `provider` when `[provider_timeouts]` doesn't set
+/// them: 600s per request and 5s to connect for local servers, 180s and
+/// 10s for cloud APIs.
+pub fn default_timeouts(provider: &str) -> ProviderTimeouts {
+    let (request, connect) = if is_local_provider(provider) {
+        (600, 5)
+    } else {
+        (180, 10)
+    };
+    ProviderTimeouts {
+        request: Some(Duration::from_secs(request)),
+        connect: Some(Duration::from_secs(connect)),
+    }
+}
+
+/// Merge a `[provider_timeouts.<id>]` entry over the defaults.  A value of
+/// 0 removes that limit.
+pub fn resolve_timeouts(
+    provider: &str,
+    config: Option<&ProviderTimeoutConfig>,
+) -> ProviderTimeouts {
+    let mut timeouts = default_timeouts(provider);
+    let secs = |s: u64| (s > 0).then(|| Duration::from_secs(s));
+    if let Some(config) = config {
+        if let Some(s) = config.request_timeout_secs {
+            timeouts.request = secs(s);
+        }
+        if let Some(s) = config.connect_timeout_secs {
+            timeouts.connect = secs(s);
+        }
+    }
+    timeouts
+}
+
+static OVERRIDES: RwLock<Option<HashMap<String, ProviderTimeoutConfig>>> = RwLock::new(None);
+
+/// Apply `[provider_timeouts]`.  Called at gateway startup and again on
+/// `Reload`; calls already waiting keep the limits they started with.
+pub fn init_provider_timeouts(config: &HashMap<String, ProviderTimeoutConfig>) {
+    *OVERRIDES.write().unwrap_or_else(|e| e.into_inner()) = Some(config.clone());
+}
+
+/// The limits in effect for `provider`.
+pub fn timeouts_for(provider: &str) -> ProviderTimeouts {
+    let overrides = OVERRIDES.read().unwrap_or_else(|e| e.into_inner());
+    resolve_timeouts(provider, overrides.as_ref().and_then(|m| m.get(provider)))
+}
+
+/// Which wait ran out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutPhase {
+    /// Opening the connection.
+    Connect,
+    /// Waiting for a reply, or for a stream to start.
+    Response,
+    /// Waiting for the next event of a started stream.
+    Stream,
+}
+
+/// A provider call that ran past one of its limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderTimeout {
+    pub provider: String,
+    pub phase: TimeoutPhase,
+    pub after: Duration,
+}
+
+impl fmt::Display for ProviderTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.after.as_secs();
+        let (what, setting) = match self.phase {
+            TimeoutPhase::Connect => (
+                format!("Could not connect to {} within {}s", self.provider, secs),
+                "connect_timeout_secs",
+            ),
+            TimeoutPhase::Response => (
+                format!("{} did not respond within {}s", self.provider, secs),
+                "request_timeout_secs",
+            ),
+            TimeoutPhase::Stream => (
+                format!("{} stopped streaming for {}s", self.provider, secs),
+                "request_timeout_secs",
+            ),
+        };
+        write!(
+            f,
+            "{} (raise {} under [provider_timeouts.{}] to wait longer)",
+            what, setting, self.provider
+        )
+    }
+}
+
+impl std::error::Error for ProviderTimeout {}
+
+/// The [`ProviderTimeout`] behind `err`, if a limit is what failed it.
+pub fn find_timeout(err: &anyhow::Error) -> Option<&ProviderTimeout> {
+    err.downcast_ref::<ProviderTimeout>().or_else(|| {
+        err.chain()
+            .find_map(|e| e.downcast_ref::<ProviderTimeout>())
+    })
+}
+
+/// Await `fut`, giving up after `limit`.
+pub(crate) async fn within<F: Future>(
+    provider: &str,
+    phase: TimeoutPhase,
+    limit: Option<Duration>,
+    fut: F,
+) -> Result<F::Output, ProviderTimeout> {
+    let Some(limit) = limit else {
+        return Ok(fut.await);
+    };
+    tokio::time::timeout(limit, fut)
+        .await
+        .map_err(|_| ProviderTimeout {
+            provider: provider.to_string(),
+            phase,
+            after: limit,
+        })
+}
+
+/// Tag a failed call as a connect timeout when reqwest gave up connecting.
+pub(crate) fn tag_connect_timeout(
+    provider: &str,
+    connect: Option<Duration>,
+    err: anyhow::Error,
+) -> anyhow::Error {
+    let Some(after) = connect else {
+        return err;
+    };
+    let connect_timed_out = err.chain().any(|e| {
+        e.downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_connect() && e.is_timeout())
+    });
+    if !connect_timed_out || find_timeout(&err).is_some() {
+        return err;
+    }
+    err.context(ProviderTimeout {
+        provider: provider.to_string(),
+        phase: TimeoutPhase::Connect,
+        after,
+    })
+}
+
+/// An HTTP client with the given connect timeout.  Clients are shared per
+/// timeout so connections are still pooled; `fallback` is used when no
+/// limit is set or the client can't be built.
+pub(crate) fn http_client(
+    fallback: &reqwest::Client,
+    connect: Option<Duration>,
+) -> reqwest::Client {
+    static CLIENTS: OnceLock<Mutex<HashMap<Duration, reqwest::Client>>> = OnceLock::new();
+    let Some(connect) = connect else {
+        return fallback.clone();
+    };
+    let mut clients = CLIENTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(client) = clients.get(&connect) {
+        return client.clone();
+    }
+    match reqwest::Client::builder().connect_timeout(connect).build() {
+        Ok(client) => {
+            clients.insert(connect, client.clone());
+            client
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to build provider HTTP client; using the default");
+            fallback.clone()
+        }
+    }
+}
@@ -153,6 +153,8 @@ pub struct SandboxPolicy {
     pub allow_paths: Vec<PathBuf>,
     /// Working directory for the agent
     pub workspace: PathBuf,
+    /// Variables set for the command on top of what the mode lets it inherit
+    pub env: Vec<(String, String)>,
 }
 
 impl Default for SandboxPolicy {
@@ -163,6 +165,7 @@ impl Default for SandboxPolicy {
             deny_exec: Vec::new(),
             allow_paths: Vec::new(),
             workspace: PathBuf::from("."),
+            env: Vec::new(),
         }
     }
 }
@@ -180,6 +183,7 @@ impl SandboxPolicy {
             deny_exec: vec![cred_dir],
             allow_paths: Vec::new(),
             workspace: workspace.into(),
+            env: Vec::new(),
         }
     }
 
@@ -191,6 +195,7 @@ impl SandboxPolicy {
             deny_exec: Vec::new(),
             allow_paths: allowed,
             workspace: workspace.into(),
+            env: Vec::new(),
         }
     }
 
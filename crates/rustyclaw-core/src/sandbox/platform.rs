@@ -370,7 +370,7 @@ pub fn run_sandboxed(
     }
 
     match effective_mode {
-        SandboxMode::None => run_unsandboxed(command),
+        SandboxMode::None => run_unsandboxed(command, &policy.env),
         SandboxMode::PathValidation => run_with_path_validation(command, policy),
         SandboxMode::Bubblewrap => run_with_bubblewrap(command, policy),
         SandboxMode::Docker => run_with_docker(command, policy),
@@ -384,10 +384,14 @@ pub fn run_sandboxed(
     }
 }
 
-pub(crate) fn run_unsandboxed(command: &str) -> Result<std::process::Output, String> {
+pub(crate) fn run_unsandboxed(
+    command: &str,
+    env: &[(String, String)],
+) -> Result<std::process::Output, String> {
     std::process::Command::new("sh")
         .arg("-c")
         .arg(command)
+        .envs(env.iter().cloned())
         .output()
         .map_err(|e| format!("Command failed: {}", e))
 }
@@ -503,7 +507,7 @@ pub(crate) fn run_with_path_validation(
         );
     }
 
-    run_unsandboxed(command)
+    run_unsandboxed(command, &policy.env)
 }
 
 #[cfg(target_os = "linux")]
@@ -529,6 +533,7 @@ fn run_with_bubblewrap(
             proc.env(&key, &value);
         }
     }
+    proc.envs(policy.env.iter().cloned());
 
     proc.output()
         .map_err(|e| format!("Sandboxed command failed: {}", e))
@@ -551,6 +556,7 @@ fn run_with_macos_sandbox(
 
     std::process::Command::new(&cmd)
         .args(&args)
+        .envs(policy.env.iter().cloned())
         .output()
         .map_err(|e| format!("Sandboxed command failed: {}", e))
 }
@@ -692,6 +698,7 @@ fn run_with_landlock_bwrap(
             proc.env(&key, &value);
         }
     }
+    proc.envs(policy.env.iter().cloned());
 
     info!(
         mode = "Landlock+Bubblewrap",
@@ -713,6 +720,13 @@ fn run_with_landlock_bwrap(
 
 // ── Docker Container (Cross-Platform) ──────────────────────────────────────
 
+/// Memory limit for each Docker-sandboxed command.
+const DOCKER_MEMORY_LIMIT: &str = "2g";
+/// CPU limit for each Docker-sandboxed command.
+const DOCKER_CPU_LIMIT: &str = "1.0";
+/// Size of the writable `/tmp` in the container.
+const DOCKER_TMPFS_SIZE: &str = "512M";
+
 /// Run command in an ephemeral Docker container.
 ///
 /// This provides strong isolation across platforms:
@@ -741,9 +755,9 @@ fn run_with_docker(command: &str, policy: &SandboxPolicy) -> Result<std::process
         container_name,
         // Resource limits
         "--memory".to_string(),
-        "2g".to_string(),
+        DOCKER_MEMORY_LIMIT.to_string(),
         "--cpus".to_string(),
-        "1.0".to_string(),
+        DOCKER_CPU_LIMIT.to_string(),
         // Security
         "--user".to_string(),
         "1000:1000".to_string(), // Non-root user
@@ -757,7 +771,7 @@ fn run_with_docker(command: &str, policy: &SandboxPolicy) -> Result<std::process
         "bridge".to_string(), // Allow network for web_fetch
         // Tmpfs for /tmp (writable)
         "--tmpfs".to_string(),
-        "/tmp:size=512M".to_string(),
+        format!("/tmp:size={}", DOCKER_TMPFS_SIZE),
     ];
 
     // Mount workspace based on policy
@@ -786,6 +800,12 @@ fn run_with_docker(command: &str, policy: &SandboxPolicy) -> Result<std::process
         docker_args.push("/tmp".to_string());
     }
 
+    // Pass variables by name so their values stay out of the argument list
+    for (key, _) in &policy.env {
+        docker_args.push("--env".to_string());
+        docker_args.push(key.clone());
+    }
+
     // Use Alpine Linux for minimal footprint
     docker_args.push("alpine:latest".to_string());
 
@@ -804,6 +824,7 @@ fn run_with_docker(command: &str, policy: &SandboxPolicy) -> Result<std::process
     // Execute docker command
     std::process::Command::new("docker")
         .args(&docker_args)
+        .envs(policy.env.iter().cloned())
         .output()
         .map_err(|e| format!("Docker execution failed: {}", e))
 }
@@ -867,6 +888,32 @@ impl Sandbox {
         run_sandboxed(command, &self.policy, self.mode)
     }
 
+    /// Network access a sandboxed command gets in the effective mode.  No
+    /// mode isolates the network today: `web_fetch` and friends need it.
+    pub fn network_policy(&self) -> &'static str {
+        match self.effective_mode() {
+            SandboxMode::Bubblewrap | SandboxMode::LandlockBwrap => {
+                "host network shared into the namespace"
+            }
+            SandboxMode::Docker => "container on the Docker bridge network",
+            SandboxMode::MacOSSandbox => "allowed by the seatbelt profile",
+            _ => "unrestricted",
+        }
+    }
+
+    /// Resource limits on each sandboxed command in the effective mode, as
+    /// `(limit, value)` pairs; empty when commands run without limits.
+    pub fn resource_limits(&self) -> Vec<(&'static str, &'static str)> {
+        match self.effective_mode() {
+            SandboxMode::Docker => vec![
+                ("memory", DOCKER_MEMORY_LIMIT),
+                ("cpus", DOCKER_CPU_LIMIT),
+                ("tmpfs", DOCKER_TMPFS_SIZE),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
     /// Human-readable status string.
     pub fn status(&self) -> String {
         format!(
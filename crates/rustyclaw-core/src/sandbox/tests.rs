@@ -82,7 +82,7 @@ fn test_bwrap_command_generation() {
 
 #[test]
 fn test_run_unsandboxed() {
-    let output = run_unsandboxed("echo hello").unwrap();
+    let output = run_unsandboxed("echo hello", &[]).unwrap();
     assert!(output.status.success());
     assert!(String::from_utf8_lossy(&output.stdout).contains("hello"));
 }
@@ -0,0 +1,454 @@
+//! Secret access audit trail.
+//!
+//! Each read of a secret or credential appends one JSON line to
+//! `{credentials_dir}/secrets_audit.jsonl`: when it happened, which secret,
+//! who asked (requester, skill, session) and whether the access policy
+//! allowed it.  Values are never recorded.
+//!
+//! The log lives beside the vault, so the agent's file tools refuse it just
+//! as they refuse the vault, and it is created owner-only (`0o600`).  It
+//! rolls to `.1`, `.2`, … under the `[log_rotation]` limits.
+//!
+//! ## Tamper evidence
+//!
+//! Each line carries `prev`, the SHA-256 of the line before it (across
+//! rotations), and `sig`, an HMAC-SHA256 of the line itself under a key
+//! kept in the vault.  Editing, inserting, removing or reordering lines
+//! breaks the chain at the first affected line, and a forged line can't be
+//! signed without opening the vault.  `rustyclaw audit verify` runs
+//! [`SecretsManager::verify_access_log`].  Lines written before signing
+//! began are chained but unsigned; once a signed line appears, an unsigned
+//! line after it is a break, since anyone can append one.  That includes
+//! lines written later while the vault was locked.  Lines cut from the
+//! very start or end of the log leave no gap, so the chain cannot show
+//! those.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, KeyInit, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::SecretsManager;
+use super::types::AccessContext;
+use crate::log_rotation::{self, LogRotator};
+
+/// File name of the access log inside the credentials directory.
+pub const AUDIT_FILE: &str = "secrets_audit.jsonl";
+
+/// What happened to a request for a secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessOutcome {
+    /// The value was handed out.
+    Allowed,
+    /// The access policy, a disabled credential or missing approval
+    /// refused the read.
+    Denied,
+    /// Access was allowed but no such secret exists.
+    NotFound,
+}
+
+impl std::fmt::Display for AccessOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allowed => write!(f, "allowed"),
+            Self::Denied => write!(f, "denied"),
+            Self::NotFound => write!(f, "not found"),
+        }
+    }
+}
+
+/// One line of the access log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessRecord {
+    /// Unix time in seconds.
+    pub timestamp: u64,
+    /// Credential name, or the raw key for legacy secrets.
+    pub secret: String,
+    /// What asked, e.g. `tool:ssh` or `provider:anthropic`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requester: Option<String>,
+    /// The skill running at the time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skill: Option<String>,
+    /// The session or connection the request came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    /// The gateway request that caused the read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<String>,
+    pub outcome: AccessOutcome,
+    /// Why access was denied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// SHA-256 of the previous line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev: Option<String>,
+    /// HMAC-SHA256 of this line without `sig`.  Stays last so the signed
+    /// text is the line minus this field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sig: Option<String>,
+}
+
+/// Which records [`SecretsManager::access_log`](super::SecretsManager::access_log)
+/// returns.  Text filters are case-insensitive substring matches.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub secret: Option<String>,
+    /// Matched against the requester, skill, session and request.
+    pub requester: Option<String>,
+    pub outcome: Option<AccessOutcome>,
+    /// Only records at or after this Unix time.
+    pub since: Option<u64>,
+    /// Only the most recent `limit` matches.
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    fn matches(&self, record: &AccessRecord) -> bool {
+        let contains = |haystack: Option<&str>, needle: &str| {
+            haystack.is_some_and(|h| h.to_lowercase().contains(&needle.to_lowercase()))
+        };
+        self.secret
+            .as_deref()
+            .is_none_or(|s| contains(Some(&record.secret), s))
+            && self.requester.as_deref().is_none_or(|r| {
+                contains(record.requester.as_deref(), r)
+                    || contains(record.skill.as_deref(), r)
+                    || contains(record.session.as_deref(), r)
+                    || contains(record.request.as_deref(), r)
+            })
+            && self.outcome.is_none_or(|o| o == record.outcome)
+            && self.since.is_none_or(|t| record.timestamp >= t)
+    }
+}
+
+/// Why [`SecretsManager::verify_access_log`] stopped at a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainBreak {
+    /// The line is not an access record.
+    Unreadable,
+    /// `prev` is missing or is not the hash of the line before: lines
+    /// were removed, inserted or reordered, or the previous line edited.
+    Unlinked,
+    /// `sig` does not match the line: it was edited, or signed with
+    /// another vault's key.
+    BadSignature,
+    /// `sig` is missing after signed lines: the line was appended or
+    /// replaced by someone without the vault, or written while it was
+    /// locked.
+    Unsigned,
+}
+
+impl std::fmt::Display for ChainBreak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreadable => write!(f, "not an access record"),
+            Self::Unlinked => write!(
+                f,
+                "does not follow the line before it (lines removed, inserted or \
+                 reordered, or the previous line edited)"
+            ),
+            Self::BadSignature => write!(
+                f,
+                "signature does not match (line edited, or signed by another vault)"
+            ),
+            Self::Unsigned => write!(
+                f,
+                "unsigned after signed lines (line forged, or written while the \
+                 vault was locked)"
+            ),
+        }
+    }
+}
+
+/// The first line that fails verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BrokenLink {
+    pub file: PathBuf,
+    /// 1-based line number within `file`.
+    pub line: usize,
+    /// 1-based position in the whole log, oldest first.
+    pub entry: usize,
+    /// The line's timestamp, when it parses.
+    pub timestamp: Option<u64>,
+    pub problem: ChainBreak,
+}
+
+/// Result of checking the access log's chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ChainReport {
+    /// Lines checked, up to and including a broken one.
+    pub entries: usize,
+    /// Lines without a signature from before signing began.
+    pub unsigned: usize,
+    pub broken: Option<BrokenLink>,
+}
+
+impl ChainReport {
+    pub fn is_intact(&self) -> bool {
+        self.broken.is_none()
+    }
+}
+
+fn line_hash(line: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(line.as_bytes()))
+}
+
+fn line_mac(key: &[u8], line: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(line.as_bytes());
+    mac
+}
+
+/// The log and its rotations, oldest first.
+fn log_files(path: &Path) -> impl Iterator<Item = PathBuf> {
+    log_rotation::rotated_files(path)
+        .into_iter()
+        .rev()
+        .chain(std::iter::once(path.to_path_buf()))
+}
+
+/// The newest line of the log, from the newest rotation when the live
+/// file is empty or missing.
+fn last_line(path: &Path) -> Option<String> {
+    std::iter::once(path.to_path_buf())
+        .chain(log_rotation::rotated_files(path))
+        .find_map(|file| {
+            let text = std::fs::read_to_string(file).ok()?;
+            let line = text.lines().rev().find(|l| !l.trim().is_empty())?;
+            Some(line.to_string())
+        })
+}
+
+/// Chain `record` to the end of the log at `path` and, given the vault's
+/// audit key, sign it.
+pub(super) fn seal(path: &Path, mut record: AccessRecord, key: Option<&[u8]>) -> AccessRecord {
+    record.prev = last_line(path).map(|line| line_hash(&line));
+    record.sig = None;
+    if let Some(key) = key
+        && let Ok(line) = serde_json::to_string(&record)
+    {
+        let sig = line_mac(key, &line).finalize().into_bytes();
+        record.sig = Some(URL_SAFE_NO_PAD.encode(sig));
+    }
+    record
+}
+
+/// Check one line against the line before it.  Returns the record and
+/// whether it is signed; once `signing` has begun, every line must be.
+fn check_line(
+    line: &str,
+    before: Option<&str>,
+    chained: bool,
+    signing: bool,
+    key: &[u8],
+) -> Result<(AccessRecord, bool), ChainBreak> {
+    let mut record: AccessRecord =
+        serde_json::from_str(line).map_err(|_| ChainBreak::Unreadable)?;
+    match (&record.prev, before) {
+        // The first line left, or the first since chaining began.
+        (_, None) | (None, Some(_)) if !chained => {}
+        (Some(hash), Some(before)) if *hash == line_hash(before) => {}
+        _ => return Err(ChainBreak::Unlinked),
+    }
+    let Some(sig) = record.sig.take() else {
+        if signing {
+            return Err(ChainBreak::Unsigned);
+        }
+        return Ok((record, false));
+    };
+    let unsigned = serde_json::to_string(&record).map_err(|_| ChainBreak::Unreadable)?;
+    let sig = URL_SAFE_NO_PAD
+        .decode(sig)
+        .map_err(|_| ChainBreak::BadSignature)?;
+    line_mac(key, &unsigned)
+        .verify_slice(&sig)
+        .map_err(|_| ChainBreak::BadSignature)?;
+    Ok((record, true))
+}
+
+/// Walk the log at `path` and its rotations, oldest first, up to the first
+/// line that fails.
+pub(super) fn verify(path: &Path, key: &[u8]) -> std::io::Result<ChainReport> {
+    let mut report = ChainReport::default();
+    let mut before: Option<String> = None;
+    let mut chained = false;
+    let mut signing = false;
+    for file in log_files(path) {
+        let text = match std::fs::read_to_string(&file) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for (n, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            report.entries += 1;
+            match check_line(line, before.as_deref(), chained, signing, key) {
+                Ok((record, signed)) => {
+                    chained |= record.prev.is_some();
+                    signing |= signed;
+                    if !signed {
+                        report.unsigned += 1;
+                    }
+                }
+                Err(problem) => {
+                    let timestamp = serde_json::from_str::<serde_json::Value>(line)
+                        .ok()
+                        .and_then(|v| v.get("timestamp")?.as_u64());
+                    report.broken = Some(BrokenLink {
+                        file,
+                        line: n + 1,
+                        entry: report.entries,
+                        timestamp,
+                        problem,
+                    });
+                    return Ok(report);
+                }
+            }
+            before = Some(line.to_string());
+        }
+    }
+    Ok(report)
+}
+
+/// Append `record` to the log at `path`, rotating it first if full.
+pub(super) fn append(path: &Path, record: &AccessRecord) -> std::io::Result<()> {
+    append_with(path, record, &log_rotation::global())
+}
+
+pub(super) fn append_with(
+    path: &Path,
+    record: &AccessRecord,
+    rotator: &LogRotator,
+) -> std::io::Result<()> {
+    let line = serde_json::to_string(record)?;
+    rotator.rotate_if_needed(path, line.len() as u64 + 1);
+
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Records from the log and its rotations, oldest first, that match
+/// `filter`.  Lines that do not parse are skipped.
+pub(super) fn read(path: &Path, filter: &AuditFilter) -> std::io::Result<Vec<AccessRecord>> {
+    let mut records = Vec::new();
+    for file in log_files(path) {
+        let text = match std::fs::read_to_string(&file) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        records.extend(
+            text.lines()
+                .filter_map(|line| serde_json::from_str::<AccessRecord>(line).ok())
+                .filter(|r| filter.matches(r)),
+        );
+    }
+    if let Some(limit) = filter.limit {
+        let skip = records.len().saturating_sub(limit);
+        records.drain(..skip);
+    }
+    Ok(records)
+}
+
+impl SecretsManager {
+    /// The vault key holding the access log's signing key.
+    pub(super) const AUDIT_KEY: &'static str = "__rustyclaw_audit_key";
+
+    /// The access log's signing key, created on first use.
+    fn audit_key(&mut self) -> Result<Vec<u8>> {
+        let encoded = match self.read_secret(Self::AUDIT_KEY)? {
+            Some(key) => key,
+            None => {
+                let key = URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>());
+                self.store_secret(Self::AUDIT_KEY, &key)?;
+                key
+            }
+        };
+        URL_SAFE_NO_PAD
+            .decode(encoded.trim())
+            .context("Corrupted access log key")
+    }
+
+    /// Record one access decision.  A log that cannot be written is
+    /// reported but does not fail the read.
+    ///
+    /// Reads made while the gateway handles a request are tagged with the
+    /// request, and with its connection when `ctx` names no session.  The
+    /// line is signed whenever the vault can be opened; a denied read
+    /// doesn't create a vault just to sign its log line.
+    pub(super) fn record_access(
+        &mut self,
+        secret: &str,
+        ctx: &AccessContext,
+        outcome: AccessOutcome,
+        reason: Option<String>,
+    ) {
+        let ids = crate::observability::request_ids::current();
+        let record = AccessRecord {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            secret: secret.to_string(),
+            requester: ctx.requester.clone(),
+            skill: ctx.active_skill.clone(),
+            session: ctx.session.clone().or_else(|| {
+                ids.as_ref()
+                    .map(|ids| format!("connection:{}", ids.connection))
+            }),
+            request: ids.map(|ids| ids.request),
+            outcome,
+            reason,
+            prev: None,
+            sig: None,
+        };
+        let key = if self.vault_path.exists() {
+            self.audit_key()
+                .inspect_err(|e| {
+                    tracing::warn!(error = %e, "Secret access log line left unsigned");
+                })
+                .ok()
+        } else {
+            None
+        };
+        let record = seal(&self.audit_path, record, key.as_deref());
+        if let Err(e) = append(&self.audit_path, &record) {
+            tracing::warn!(path = %self.audit_path.display(), error = %e,
+                "Failed to write secret access log");
+        }
+    }
+
+    /// Read the secret access log.
+    ///
+    /// Requires the vault to open, so the log is no easier to read than
+    /// the secrets it describes.
+    pub fn access_log(&mut self, filter: &AuditFilter) -> Result<Vec<AccessRecord>> {
+        self.ensure_vault()?;
+        read(&self.audit_path, filter).context("Failed to read secret access log")
+    }
+
+    /// Check the access log's hash chain and signatures, reporting the
+    /// first line that fails.  Needs the vault for the signing key.
+    pub fn verify_access_log(&mut self) -> Result<ChainReport> {
+        let key = self.audit_key()?;
+        verify(&self.audit_path, &key).context("Failed to read secret access log")
+    }
+}
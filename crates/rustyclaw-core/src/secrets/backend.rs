@@ -0,0 +1,157 @@
+//! Storage backends for secret values.
+//!
+//! [`SecretsManager`](super::SecretsManager) always keeps its bookkeeping —
+//! credential metadata and access policies (`cred:*`), the TOTP secret,
+//! gateway tokens — in the encrypted file vault.  Secret *values* go to the
+//! configured [`SecretsBackend`]: the same file vault by default, or the OS
+//! keychain when `secrets_backend = "keychain"`.
+//!
+//! With the keychain backend, at-rest protection of secret values is the
+//! operating system's job (login keychain, Secret Service collection,
+//! Credential Manager).  RustyClaw's access policies still apply, because
+//! every read goes through the manager, but anything else running as the
+//! same OS user may be able to read the keychain items directly.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Which backend stores secret values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretsBackendKind {
+    /// Encrypted SecureStore vault in the credentials directory.
+    #[default]
+    File,
+    /// OS keychain: macOS Keychain, Secret Service (libsecret) on Linux,
+    /// or Windows Credential Manager.  Requires the `keychain` feature.
+    Keychain,
+}
+
+impl std::fmt::Display for SecretsBackendKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::File => "file",
+            Self::Keychain => "keychain",
+        })
+    }
+}
+
+/// A key/value store for secret values.
+///
+/// Implementations persist every write immediately; there is no separate
+/// save step.  Key enumeration is not part of the trait because OS
+/// keychains cannot list their items — the manager keeps its own index.
+pub trait SecretsBackend: Send {
+    /// Short name for logs and error messages.
+    fn name(&self) -> &'static str;
+
+    /// Read a value, returning `None` if the key does not exist.
+    fn get(&self, key: &str) -> Result<Option<String>>;
+
+    /// Create or overwrite a value.
+    fn set(&mut self, key: &str, value: &str) -> Result<()>;
+
+    /// Delete a value.  Deleting a missing key is not an error.
+    fn remove(&mut self, key: &str) -> Result<()>;
+}
+
+// ── Encrypted file vault ────────────────────────────────────────────────────
+
+impl SecretsBackend for securestore::SecretsManager {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        match securestore::SecretsManager::get(self, key) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.kind() == securestore::ErrorKind::SecretNotFound => Ok(None),
+            Err(e) => Err(anyhow::anyhow!("Failed to get secret: {}", e)),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        securestore::SecretsManager::set(self, key, value);
+        self.save().context("Failed to save secrets vault")
+    }
+
+    fn remove(&mut self, key: &str) -> Result<()> {
+        securestore::SecretsManager::remove(self, key).context("Failed to remove secret")?;
+        self.save().context("Failed to save secrets vault")
+    }
+}
+
+// ── OS keychain ─────────────────────────────────────────────────────────────
+
+/// Secret values stored as generic passwords in the OS keychain.
+///
+/// Each value is one keychain item with service `rustyclaw:<credentials
+/// dir>` and the vault key as the account name, so separate RustyClaw
+/// profiles never share items.
+#[cfg(feature = "keychain")]
+pub struct KeychainBackend {
+    service: String,
+}
+
+#[cfg(feature = "keychain")]
+impl KeychainBackend {
+    /// Create a backend whose items are namespaced by `credentials_dir`.
+    pub fn new(credentials_dir: &std::path::Path) -> Self {
+        Self {
+            service: format!("rustyclaw:{}", credentials_dir.display()),
+        }
+    }
+
+    /// Check that the platform keychain is reachable by writing, reading
+    /// back, and deleting a probe item.
+    ///
+    /// Returns `false` on headless Linux without a Secret Service daemon,
+    /// in sandboxes without keychain access, and similar environments.
+    pub fn is_available(&self) -> bool {
+        const PROBE_KEY: &str = "__rustyclaw_keychain_probe";
+        let mut probe = Self {
+            service: self.service.clone(),
+        };
+        let ok = probe.set(PROBE_KEY, "ok").is_ok()
+            && matches!(probe.get(PROBE_KEY), Ok(Some(ref v)) if v == "ok");
+        let _ = probe.remove(PROBE_KEY);
+        ok
+    }
+
+    fn entry(&self, key: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(&self.service, key)
+            .with_context(|| format!("Invalid keychain entry for '{}'", key))
+    }
+}
+
+#[cfg(feature = "keychain")]
+impl SecretsBackend for KeychainBackend {
+    fn name(&self) -> &'static str {
+        "keychain"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        match self.entry(key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(anyhow::anyhow!("Keychain read failed for '{}': {}", key, e)),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        self.entry(key)?
+            .set_password(value)
+            .with_context(|| format!("Keychain write failed for '{}'", key))
+    }
+
+    fn remove(&mut self, key: &str) -> Result<()> {
+        match self.entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(anyhow::anyhow!(
+                "Keychain delete failed for '{}': {}",
+                key,
+                e
+            )),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! One-time recovery codes for TOTP two-factor authentication.
+//!
+//! A lost authenticator would otherwise lock the user out of the vault and
+//! the gateway.  At enrollment a set of backup codes is generated and shown
+//! once; each code can stand in for a TOTP code a single time and is then
+//! discarded.  Only a SHA-256 hash of each code is kept in the vault.
+//!
+//! Codes look like `k7m2p-q9xr4`: ten characters from an alphabet without
+//! look-alikes (no `0/o`, `1/l/i`), so about 50 bits each.  Dashes, spaces
+//! and case are ignored when a code is checked.
+
+use anyhow::{Context, Result};
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+
+use super::SecretsManager;
+use super::gateway_tokens::{constant_time_eq, hash_secret, unix_now};
+
+/// Codes generated per set.
+pub const BACKUP_CODE_COUNT: usize = 10;
+
+/// Characters in a code, not counting the separating dash.
+const CODE_LEN: usize = 10;
+
+const ALPHABET: &[u8] = b"23456789abcdefghjkmnpqrstuvwxyz";
+
+/// The stored set of unused backup codes.  The codes themselves are
+/// never stored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct BackupCodes {
+    /// Unix timestamp (seconds) when the set was generated.
+    created_at: u64,
+    /// URL-safe base64 SHA-256 of each unused, normalized code.
+    hashes: Vec<String>,
+}
+
+/// Lowercase `code` and drop dashes and whitespace.
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn random_code() -> String {
+    let mut rng = rand::rng();
+    let chars: String = (0..CODE_LEN)
+        .map(|_| *ALPHABET.choose(&mut rng).expect("alphabet is not empty") as char)
+        .collect();
+    format!("{}-{}", &chars[..CODE_LEN / 2], &chars[CODE_LEN / 2..])
+}
+
+impl SecretsManager {
+    /// The vault key holding the hashed TOTP backup codes.
+    pub(super) const BACKUP_CODES_KEY: &'static str = "__rustyclaw_totp_backup_codes";
+
+    fn load_backup_codes(&mut self) -> Result<Option<BackupCodes>> {
+        match self.read_secret(Self::BACKUP_CODES_KEY)? {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .context("Corrupted TOTP backup codes"),
+            None => Ok(None),
+        }
+    }
+
+    fn save_backup_codes(&mut self, codes: &BackupCodes) -> Result<()> {
+        let json = serde_json::to_string(codes).context("Failed to serialize backup codes")?;
+        self.store_secret(Self::BACKUP_CODES_KEY, &json)
+    }
+
+    /// Generate a fresh set of TOTP backup codes, replacing any earlier set.
+    ///
+    /// Returns the plaintext codes.  They cannot be recovered later —
+    /// callers must show them to the user now.  Fails when TOTP is not
+    /// set up, since the codes would have nothing to stand in for.
+    pub fn generate_backup_codes(&mut self) -> Result<Vec<String>> {
+        if !self.has_totp() {
+            anyhow::bail!("No TOTP secret configured");
+        }
+        let mut codes: Vec<String> = Vec::with_capacity(BACKUP_CODE_COUNT);
+        while codes.len() < BACKUP_CODE_COUNT {
+            let code = random_code();
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+        self.save_backup_codes(&BackupCodes {
+            created_at: unix_now()?,
+            hashes: codes.iter().map(|c| hash_secret(&normalize(c))).collect(),
+        })?;
+        Ok(codes)
+    }
+
+    /// Number of backup codes not yet used (0 when none were generated).
+    pub fn backup_codes_remaining(&mut self) -> Result<usize> {
+        Ok(self
+            .load_backup_codes()?
+            .map_or(0, |codes| codes.hashes.len()))
+    }
+
+    /// Unix timestamp when the current backup code set was generated.
+    pub fn backup_codes_created_at(&mut self) -> Result<Option<u64>> {
+        Ok(self.load_backup_codes()?.map(|codes| codes.created_at))
+    }
+
+    /// Check a backup code and, if it is valid and unused, consume it.
+    ///
+    /// Returns `Ok(false)` for unknown or already-used codes.  The hash
+    /// comparison is constant-time.
+    pub fn use_backup_code(&mut self, code: &str) -> Result<bool> {
+        let normalized = normalize(code);
+        if normalized.len() != CODE_LEN {
+            return Ok(false);
+        }
+        let Some(mut codes) = self.load_backup_codes()? else {
+            return Ok(false);
+        };
+        let presented = hash_secret(&normalized);
+        let Some(index) = codes
+            .hashes
+            .iter()
+            .position(|h| constant_time_eq(presented.as_bytes(), h.as_bytes()))
+        else {
+            return Ok(false);
+        };
+        codes.hashes.remove(index);
+        self.save_backup_codes(&codes)?;
+        Ok(true)
+    }
+
+    /// Verify a TOTP code, falling back to a one-time backup code.
+    ///
+    /// A matching backup code is consumed.  Errors, like
+    /// [`verify_totp`](Self::verify_totp), when no TOTP secret is configured.
+    pub fn verify_totp_or_backup_code(&mut self, code: &str) -> Result<bool> {
+        if self.verify_totp(code)? {
+            return Ok(true);
+        }
+        self.use_backup_code(code)
+    }
+
+    /// Delete the stored backup codes.
+    pub(super) fn remove_backup_codes(&mut self) -> Result<()> {
+        if self.read_secret(Self::BACKUP_CODES_KEY)?.is_some() {
+            self.delete_secret(Self::BACKUP_CODES_KEY)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+//! Long-lived bearer tokens for unattended gateway clients.
+//!
+//! Tokens let CI jobs and scripts answer the gateway's auth challenge
+//! without a human typing a TOTP code.  Only a SHA-256 hash of each token
+//! is kept in the vault; the plaintext is shown once at issue time.
+//!
+//! A token has the form `rcgt_<id>_<secret>` where `<id>` is a short hex
+//! identifier used for listing and revocation, and `<secret>` is 32 bytes
+//! of CSPRNG output (URL-safe base64).
+//!
+//! Each token carries a [`GatewayRole`].  Observer tokens connect
+//! read-only: they see what the agent is doing but cannot drive it.
+//! A token may also be scoped to a list of tools; the agent on such a
+//! connection can call nothing else, whatever `tool_permissions` says.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::SecretsManager;
+
+/// Prefix that marks an auth response as a bearer token rather than a
+/// TOTP code.
+pub const GATEWAY_TOKEN_PREFIX: &str = "rcgt_";
+
+/// What a connection authenticated with a token may do.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GatewayRole {
+    /// Full access: chat, commands, secrets and configuration.
+    #[default]
+    Operator,
+    /// Read-only: receives session activity, every inbound request is refused.
+    Observer,
+}
+
+impl GatewayRole {
+    pub fn is_read_only(self) -> bool {
+        self == Self::Observer
+    }
+}
+
+/// Metadata for an issued gateway token.  The token itself is never stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GatewayToken {
+    /// Short identifier embedded in the token (used for revocation).
+    pub id: String,
+    /// Human-readable label (e.g. "ci-nightly").
+    pub label: String,
+    /// URL-safe base64 SHA-256 of the secret part.
+    pub hash: String,
+    /// Unix timestamp (seconds) when the token was issued.
+    pub created_at: u64,
+    /// Unix timestamp (seconds) after which the token is rejected.
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// Access granted to connections using the token.  Tokens issued
+    /// before roles existed are operator tokens.
+    #[serde(default)]
+    pub role: GatewayRole,
+    /// The only tools connections using the token may call.  `None`
+    /// leaves the gateway's tool permissions in charge.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+}
+
+impl GatewayToken {
+    /// Whether the token has passed its expiry at `now` (Unix seconds).
+    pub fn is_expired_at(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|exp| now >= exp)
+    }
+}
+
+/// Returns `true` if `code` looks like a gateway bearer token.
+pub fn is_gateway_token(code: &str) -> bool {
+    code.trim().starts_with(GATEWAY_TOKEN_PREFIX)
+}
+
+pub(super) fn unix_now() -> Result<u64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .context("System time error")?
+        .as_secs())
+}
+
+pub(super) fn hash_secret(secret: &str) -> String {
+    let digest = Sha256::digest(secret.as_bytes());
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest)
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch.
+pub(super) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Split `rcgt_<id>_<secret>` into `(id, secret)`.
+fn split_token(token: &str) -> Option<(&str, &str)> {
+    let rest = token.trim().strip_prefix(GATEWAY_TOKEN_PREFIX)?;
+    let (id, secret) = rest.split_once('_')?;
+    if id.is_empty() || secret.is_empty() {
+        return None;
+    }
+    Some((id, secret))
+}
+
+impl SecretsManager {
+    /// The vault key holding the JSON list of [`GatewayToken`] records.
+    pub(super) const GATEWAY_TOKENS_KEY: &'static str = "__rustyclaw_gateway_tokens";
+
+    /// List all issued gateway tokens (including expired ones).
+    pub fn list_gateway_tokens(&mut self) -> Result<Vec<GatewayToken>> {
+        match self.read_secret(Self::GATEWAY_TOKENS_KEY)? {
+            Some(json) => serde_json::from_str(&json).context("Corrupted gateway token list"),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn save_gateway_tokens(&mut self, tokens: &[GatewayToken]) -> Result<()> {
+        let json =
+            serde_json::to_string(tokens).context("Failed to serialize gateway token list")?;
+        self.store_secret(Self::GATEWAY_TOKENS_KEY, &json)
+    }
+
+    /// Issue a new gateway token.
+    ///
+    /// Returns the stored metadata and the plaintext token.  The plaintext
+    /// cannot be recovered later — callers must show it to the user now.
+    pub fn issue_gateway_token(
+        &mut self,
+        label: &str,
+        ttl_secs: Option<u64>,
+        role: GatewayRole,
+        tools: Option<Vec<String>>,
+    ) -> Result<(GatewayToken, String)> {
+        let mut tokens = self.list_gateway_tokens()?;
+
+        let id = loop {
+            let candidate = format!("{:08x}", rand::random::<u32>());
+            if !tokens.iter().any(|t| t.id == candidate) {
+                break candidate;
+            }
+        };
+        let secret =
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>());
+
+        let now = unix_now()?;
+        let record = GatewayToken {
+            id: id.clone(),
+            label: label.to_string(),
+            hash: hash_secret(&secret),
+            created_at: now,
+            expires_at: ttl_secs.map(|ttl| now.saturating_add(ttl)),
+            role,
+            tools,
+        };
+        tokens.push(record.clone());
+        self.save_gateway_tokens(&tokens)?;
+
+        Ok((record, format!("{}{}_{}", GATEWAY_TOKEN_PREFIX, id, secret)))
+    }
+
+    /// Revoke a gateway token by id.  Returns `false` if no such token exists.
+    pub fn revoke_gateway_token(&mut self, id: &str) -> Result<bool> {
+        let mut tokens = self.list_gateway_tokens()?;
+        let before = tokens.len();
+        tokens.retain(|t| t.id != id);
+        if tokens.len() == before {
+            return Ok(false);
+        }
+        self.save_gateway_tokens(&tokens)?;
+        Ok(true)
+    }
+
+    /// Verify a presented bearer token.
+    ///
+    /// Returns `Ok(false)` for malformed, unknown, expired, or mismatched
+    /// tokens.  The hash comparison is constant-time.
+    pub fn verify_gateway_token(&mut self, token: &str) -> Result<bool> {
+        Ok(self.gateway_token_role(token)?.is_some())
+    }
+
+    /// Verify a presented bearer token and return the role it grants, or
+    /// `None` when [`verify_gateway_token`](Self::verify_gateway_token)
+    /// would reject it.
+    pub fn gateway_token_role(&mut self, token: &str) -> Result<Option<GatewayRole>> {
+        Ok(self.gateway_token_grant(token)?.map(|record| record.role))
+    }
+
+    /// Verify a presented bearer token and return its record — role and
+    /// tool scope — or `None` when it is rejected.
+    pub fn gateway_token_grant(&mut self, token: &str) -> Result<Option<GatewayToken>> {
+        let Some((id, secret)) = split_token(token) else {
+            return Ok(None);
+        };
+        let tokens = self.list_gateway_tokens()?;
+        let Some(record) = tokens.into_iter().find(|t| t.id == id) else {
+            return Ok(None);
+        };
+        if record.is_expired_at(unix_now()?) {
+            return Ok(None);
+        }
+        let presented = hash_secret(secret);
+        Ok(constant_time_eq(presented.as_bytes(), record.hash.as_bytes()).then_some(record))
+    }
+}
@@ -0,0 +1,159 @@
+//! Random passwords and passphrases for `secrets_generate`.
+//!
+//! Every character and word is a uniform pick from the thread-local
+//! CSPRNG.  Passphrases use the 2048-word BIP-39 English list
+//! (`wordlist.txt`, CC0), 11 bits per word.
+
+use std::sync::LazyLock;
+
+use anyhow::{Result, bail};
+use rand::seq::{IndexedRandom, SliceRandom};
+
+/// Shortest password [`generate_password`] will produce.
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+/// Longest password [`generate_password`] will produce.
+pub const MAX_PASSWORD_LENGTH: usize = 256;
+/// Fewest words [`generate_passphrase`] will produce.
+pub const MIN_PASSPHRASE_WORDS: usize = 4;
+/// Most words [`generate_passphrase`] will produce.
+pub const MAX_PASSPHRASE_WORDS: usize = 24;
+
+static WORDS: LazyLock<Vec<&'static str>> =
+    LazyLock::new(|| include_str!("wordlist.txt").lines().collect());
+
+/// Characters that are easy to misread: `0 O o 1 l I |` and friends.
+const AMBIGUOUS: &str = "0Oo1lI|`'\"";
+
+/// A set of characters a password may draw from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Lower,
+    Upper,
+    Digits,
+    Symbols,
+    /// Lowercase hexadecimal; only valid on its own.
+    Hex,
+}
+
+impl CharClass {
+    /// Parse a class name (`lower`, `upper`, `digits`, `symbols`, `hex`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "lower" | "lowercase" => Some(Self::Lower),
+            "upper" | "uppercase" => Some(Self::Upper),
+            "digits" | "digit" | "numbers" => Some(Self::Digits),
+            "symbols" | "symbol" | "special" => Some(Self::Symbols),
+            "hex" => Some(Self::Hex),
+            _ => None,
+        }
+    }
+
+    fn alphabet(self) -> &'static str {
+        match self {
+            Self::Lower => "abcdefghijklmnopqrstuvwxyz",
+            Self::Upper => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            Self::Digits => "0123456789",
+            Self::Symbols => "!#$%&()*+,-./:;<=>?@[]^_{}~",
+            Self::Hex => "0123456789abcdef",
+        }
+    }
+}
+
+/// What [`generate_password`] should produce.
+#[derive(Debug, Clone)]
+pub struct PasswordSpec {
+    pub length: usize,
+    pub classes: Vec<CharClass>,
+    /// Leave out characters that are easy to confuse when read aloud or
+    /// retyped (see `AMBIGUOUS`).
+    pub exclude_ambiguous: bool,
+}
+
+impl Default for PasswordSpec {
+    fn default() -> Self {
+        Self {
+            length: 24,
+            classes: vec![
+                CharClass::Lower,
+                CharClass::Upper,
+                CharClass::Digits,
+                CharClass::Symbols,
+            ],
+            exclude_ambiguous: false,
+        }
+    }
+}
+
+/// A freshly generated value.
+#[derive(Debug, Clone)]
+pub struct GeneratedSecret {
+    pub value: String,
+    /// Entropy of the draw, `log2(alphabet) * length`.  Slightly generous
+    /// for passwords, which are forced to contain every class.
+    pub entropy_bits: f64,
+}
+
+/// A random password with at least one character from every class in
+/// `spec`.
+pub fn generate_password(spec: &PasswordSpec) -> Result<GeneratedSecret> {
+    if !(MIN_PASSWORD_LENGTH..=MAX_PASSWORD_LENGTH).contains(&spec.length) {
+        bail!(
+            "Password length must be between {} and {}",
+            MIN_PASSWORD_LENGTH,
+            MAX_PASSWORD_LENGTH
+        );
+    }
+    if spec.classes.is_empty() {
+        bail!("At least one character class is required");
+    }
+    if spec.classes.contains(&CharClass::Hex) && spec.classes.len() > 1 {
+        bail!("The hex class can't be combined with other classes");
+    }
+
+    let mut classes: Vec<Vec<char>> = Vec::new();
+    for class in &spec.classes {
+        let chars: Vec<char> = class
+            .alphabet()
+            .chars()
+            .filter(|c| !(spec.exclude_ambiguous && AMBIGUOUS.contains(*c)))
+            .collect();
+        if !classes.contains(&chars) {
+            classes.push(chars);
+        }
+    }
+    let alphabet: Vec<char> = classes.concat();
+
+    let mut rng = rand::rng();
+    let mut password: Vec<char> = classes
+        .iter()
+        .filter_map(|chars| chars.choose(&mut rng).copied())
+        .collect();
+    while password.len() < spec.length {
+        password.extend(alphabet.choose(&mut rng));
+    }
+    password.shuffle(&mut rng);
+
+    Ok(GeneratedSecret {
+        value: password.into_iter().collect(),
+        entropy_bits: spec.length as f64 * (alphabet.len() as f64).log2(),
+    })
+}
+
+/// A passphrase of `words` random words joined by `separator`.
+pub fn generate_passphrase(words: usize, separator: &str) -> Result<GeneratedSecret> {
+    if !(MIN_PASSPHRASE_WORDS..=MAX_PASSPHRASE_WORDS).contains(&words) {
+        bail!(
+            "A passphrase must have between {} and {} words",
+            MIN_PASSPHRASE_WORDS,
+            MAX_PASSPHRASE_WORDS
+        );
+    }
+    let mut rng = rand::rng();
+    let chosen: Vec<&str> = (0..words)
+        .filter_map(|_| WORDS.choose(&mut rng).copied())
+        .collect();
+    Ok(GeneratedSecret {
+        value: chosen.join(separator),
+        entropy_bits: words as f64 * (WORDS.len() as f64).log2(),
+    })
+}
@@ -16,13 +16,45 @@
 //! | `val:<name>:card`      | JSON `{cardholder,number,expiry,cvv}`              |
 //! | `val:<name>:card_extra`| JSON map of additional payment card fields         |
 //! | `<bare key>`           | Legacy / raw secrets (API keys, TOTP, etc.)        |
+//! | `__rustyclaw_totp_backup_codes` | Hashed one-time TOTP recovery codes       |
+//! | `__rustyclaw_gateway_tokens` | JSON list of hashed gateway bearer tokens    |
+//! | `__rustyclaw_audit_key`      | Key signing the secret access log             |
+//! | `__rustyclaw_backend_index`  | Keys held by a non-file value backend         |
+//!
+//! ## Backends
+//!
+//! Secret values (`val:*` and bare keys) can live in the OS keychain instead
+//! of the vault file — see [`backend`].  Metadata, policies, TOTP and gateway
+//! tokens always stay in the vault.
+//!
+//! ## Access log
+//!
+//! Reads of secrets and credentials, allowed or not, are recorded without
+//! their values in `{credentials_dir}/secrets_audit.jsonl`, hash-chained
+//! and signed so edits show up — see [`audit`].
 
+pub mod audit;
+pub mod backend;
+mod backup_codes;
+mod gateway_tokens;
+pub mod generate;
 mod types;
 mod vault;
 mod vault_ext;
 
 use std::path::PathBuf;
 
+use anyhow::Result;
+
+pub use audit::{AccessOutcome, AccessRecord, AuditFilter, BrokenLink, ChainBreak, ChainReport};
+#[cfg(feature = "keychain")]
+pub use backend::KeychainBackend;
+pub use backend::{SecretsBackend, SecretsBackendKind};
+pub use backup_codes::BACKUP_CODE_COUNT;
+pub use gateway_tokens::{GATEWAY_TOKEN_PREFIX, GatewayRole, GatewayToken, is_gateway_token};
+pub use generate::{
+    CharClass, GeneratedSecret, PasswordSpec, generate_passphrase, generate_password,
+};
 pub use types::{
     AccessContext, AccessPolicy, BrowserStore, Cookie, CredentialValue, Secret, SecretEntry,
     SecretKind, SecretString, WebStorage,
@@ -34,10 +66,14 @@ pub struct SecretsManager {
     pub(crate) vault_path: PathBuf,
     /// Path to the key file (only used when no password is set)
     pub(crate) key_path: PathBuf,
+    /// Path to the secret access log
+    pub(crate) audit_path: PathBuf,
     /// Optional user-supplied password (used instead of the key file)
     pub(crate) password: Option<String>,
     /// In-memory vault handle (loaded lazily)
     pub(crate) vault: Option<securestore::SecretsManager>,
+    /// Backend for secret values; `None` keeps them in the vault
+    pub(crate) backend: Option<Box<dyn SecretsBackend>>,
     /// Whether the agent can access secrets without prompting
     pub(crate) agent_access_enabled: bool,
 }
@@ -52,8 +88,10 @@ impl SecretsManager {
         Self {
             vault_path: dir.join("secrets.json"),
             key_path: dir.join("secrets.key"),
+            audit_path: dir.join(audit::AUDIT_FILE),
             password: None,
             vault: None,
+            backend: None,
             agent_access_enabled: false,
         }
     }
@@ -65,12 +103,48 @@ impl SecretsManager {
         Self {
             vault_path: dir.join("secrets.json"),
             key_path: dir.join("secrets.key"),
+            audit_path: dir.join(audit::AUDIT_FILE),
             password: Some(password),
             vault: None,
+            backend: None,
             agent_access_enabled: false,
         }
     }
 
+    /// Store secret values in `backend` instead of the vault file.
+    ///
+    /// Credential metadata, policies, TOTP and gateway tokens stay in the
+    /// vault, which is still opened with the key file or password.
+    pub fn with_backend(mut self, backend: Box<dyn SecretsBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Apply the `secrets_backend` config setting.
+    ///
+    /// Fails for [`SecretsBackendKind::Keychain`] when RustyClaw was built
+    /// without the `keychain` feature.
+    pub fn with_backend_kind(self, kind: SecretsBackendKind) -> Result<Self> {
+        match kind {
+            SecretsBackendKind::File => Ok(self),
+            #[cfg(feature = "keychain")]
+            SecretsBackendKind::Keychain => {
+                let dir = self.vault_path.parent().unwrap_or(&self.vault_path);
+                let backend = KeychainBackend::new(dir);
+                Ok(self.with_backend(Box::new(backend)))
+            }
+            #[cfg(not(feature = "keychain"))]
+            SecretsBackendKind::Keychain => anyhow::bail!(
+                "secrets_backend = \"keychain\" needs a build with the `keychain` feature"
+            ),
+        }
+    }
+
+    /// Name of the backend holding secret values (`"file"` or `"keychain"`).
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.as_ref().map_or("file", |b| b.name())
+    }
+
     /// Set the password after construction (e.g. after prompting the user).
     ///
     /// **Note:** This only affects how the vault is opened on next access.
@@ -100,8 +174,10 @@ impl SecretsManager {
         Self {
             vault_path: dir.join("secrets.json"),
             key_path: dir.join("secrets.key"),
+            audit_path: dir.join(audit::AUDIT_FILE),
             password: None,
             vault: None,
+            backend: None,
             agent_access_enabled: false,
         }
     }
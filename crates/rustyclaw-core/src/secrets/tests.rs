@@ -1,6 +1,7 @@
 //! Tests for the secrets manager.
 
 use super::*;
+use crate::log_rotation::LogRotator;
 use std::path::Path;
 use std::sync::atomic::{AtomicU32, Ordering};
 use totp_rs::{Algorithm, Secret as TotpSecret, TOTP};
@@ -313,6 +314,63 @@ fn test_totp_setup_and_verify() {
     let _ = std::fs::remove_dir_all(&dir);
 }
 
+#[test]
+fn test_backup_code_authenticates_once() {
+    let dir = temp_dir();
+    let mut manager = SecretsManager::new(&dir);
+
+    // Codes only exist alongside a TOTP secret.
+    assert!(manager.generate_backup_codes().is_err());
+    manager.setup_totp("testuser").unwrap();
+    assert_eq!(manager.backup_codes_remaining().unwrap(), 0);
+
+    let codes = manager.generate_backup_codes().unwrap();
+    assert_eq!(codes.len(), BACKUP_CODE_COUNT);
+    assert_eq!(manager.backup_codes_remaining().unwrap(), BACKUP_CODE_COUNT);
+    assert!(manager.backup_codes_created_at().unwrap().is_some());
+
+    // Only hashes are stored.
+    let stored = manager
+        .read_secret(SecretsManager::BACKUP_CODES_KEY)
+        .unwrap()
+        .unwrap();
+    assert!(codes.iter().all(|c| !stored.contains(c.as_str())));
+
+    // A backup code stands in for a TOTP code exactly once.
+    assert!(manager.verify_totp_or_backup_code(&codes[0]).unwrap());
+    assert!(!manager.verify_totp_or_backup_code(&codes[0]).unwrap());
+    assert_eq!(
+        manager.backup_codes_remaining().unwrap(),
+        BACKUP_CODE_COUNT - 1
+    );
+
+    // Case, dashes and spaces are ignored.
+    let sloppy = codes[1].to_uppercase().replace('-', " ");
+    assert!(manager.use_backup_code(&sloppy).unwrap());
+    assert!(!manager.use_backup_code(&codes[1]).unwrap());
+
+    assert!(!manager.verify_totp_or_backup_code("abcde-fghjk").unwrap());
+    assert!(!manager.verify_totp_or_backup_code("000000").unwrap());
+
+    // Hidden from the credential listing.
+    assert!(
+        !manager
+            .list_all_entries()
+            .iter()
+            .any(|(n, _)| n == SecretsManager::BACKUP_CODES_KEY)
+    );
+
+    // Regenerating invalidates the old set; removing TOTP drops them all.
+    let fresh = manager.generate_backup_codes().unwrap();
+    assert!(!manager.use_backup_code(&codes[2]).unwrap());
+    manager.remove_totp().unwrap();
+    assert_eq!(manager.backup_codes_remaining().unwrap(), 0);
+    manager.setup_totp("testuser").unwrap();
+    assert!(!manager.verify_totp_or_backup_code(&fresh[0]).unwrap());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
 // ── Typed credential tests ──────────────────────────────────────
 
 #[test]
@@ -959,3 +1017,535 @@ fn test_set_policy_legacy_key_promotes_to_typed() {
 
     let _ = std::fs::remove_dir_all(&dir);
 }
+
+#[test]
+fn test_gateway_token_issue_verify_revoke() {
+    let dir = temp_dir();
+    let mut m = SecretsManager::new(&dir);
+
+    let (record, token) = m
+        .issue_gateway_token("ci", None, GatewayRole::Operator, None)
+        .unwrap();
+    assert!(is_gateway_token(&token));
+    assert!(token.starts_with(&format!("{}{}_", GATEWAY_TOKEN_PREFIX, record.id)));
+    // Only the hash is persisted.
+    assert!(!record.hash.is_empty());
+    assert!(!token.contains(&record.hash));
+
+    assert!(m.verify_gateway_token(&token).unwrap());
+    assert!(!m.verify_gateway_token(&format!("{}x", token)).unwrap());
+    assert!(!m.verify_gateway_token("rcgt_deadbeef_nope").unwrap());
+    assert!(!m.verify_gateway_token("123456").unwrap());
+
+    // Internal bookkeeping key is hidden from the credential listing.
+    assert!(
+        !m.list_all_entries()
+            .iter()
+            .any(|(n, _)| n == SecretsManager::GATEWAY_TOKENS_KEY)
+    );
+
+    assert!(m.revoke_gateway_token(&record.id).unwrap());
+    assert!(!m.revoke_gateway_token(&record.id).unwrap());
+    assert!(!m.verify_gateway_token(&token).unwrap());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_gateway_token_expiry() {
+    let dir = temp_dir();
+    let mut m = SecretsManager::new(&dir);
+
+    let (record, token) = m
+        .issue_gateway_token("short-lived", Some(0), GatewayRole::Operator, None)
+        .unwrap();
+    assert!(record.is_expired_at(record.created_at));
+    assert!(!m.verify_gateway_token(&token).unwrap());
+
+    let (record, token) = m
+        .issue_gateway_token("week", Some(7 * 86_400), GatewayRole::Operator, None)
+        .unwrap();
+    assert!(!record.is_expired_at(record.created_at));
+    assert!(m.verify_gateway_token(&token).unwrap());
+    assert_eq!(m.list_gateway_tokens().unwrap().len(), 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_gateway_token_roles() {
+    let dir = temp_dir();
+    let mut m = SecretsManager::new(&dir);
+
+    let (_, operator) = m
+        .issue_gateway_token("laptop", None, GatewayRole::Operator, None)
+        .unwrap();
+    let (record, observer) = m
+        .issue_gateway_token("phone", None, GatewayRole::Observer, None)
+        .unwrap();
+    assert_eq!(record.role, GatewayRole::Observer);
+
+    assert_eq!(
+        m.gateway_token_role(&operator).unwrap(),
+        Some(GatewayRole::Operator)
+    );
+    assert_eq!(
+        m.gateway_token_role(&observer).unwrap(),
+        Some(GatewayRole::Observer)
+    );
+    assert!(m.verify_gateway_token(&observer).unwrap());
+    let tampered = format!("{}x", observer);
+    assert_eq!(m.gateway_token_role(&tampered).unwrap(), None);
+
+    // Records written before roles existed are operator tokens.
+    let legacy: GatewayToken =
+        serde_json::from_str(r#"{"id":"a","label":"old","hash":"h","created_at":0}"#).unwrap();
+    assert_eq!(legacy.role, GatewayRole::Operator);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_gateway_token_tool_scope() {
+    let dir = temp_dir();
+    let mut m = SecretsManager::new(&dir);
+
+    let tools = vec!["read_file".to_string(), "web_fetch".to_string()];
+    let (_, scoped) = m
+        .issue_gateway_token("kiosk", None, GatewayRole::Operator, Some(tools.clone()))
+        .unwrap();
+    let (_, full) = m
+        .issue_gateway_token("laptop", None, GatewayRole::Operator, None)
+        .unwrap();
+
+    let grant = m.gateway_token_grant(&scoped).unwrap().unwrap();
+    assert_eq!(grant.tools, Some(tools));
+    assert_eq!(grant.role, GatewayRole::Operator);
+    assert_eq!(m.gateway_token_grant(&full).unwrap().unwrap().tools, None);
+    assert!(
+        m.gateway_token_grant(&format!("{}x", scoped))
+            .unwrap()
+            .is_none()
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ── Value backends ──────────────────────────────────────────────
+
+/// In-memory backend that shares its map with the test.
+#[derive(Clone, Default)]
+struct MemoryBackend(std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, String>>>);
+
+impl SecretsBackend for MemoryBackend {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn get(&self, key: &str) -> anyhow::Result<Option<String>> {
+        Ok(self.0.lock().unwrap().get(key).cloned())
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &str) -> anyhow::Result<()> {
+        self.0.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_backend_receives_values_but_not_metadata() {
+    let dir = temp_dir();
+    let store = MemoryBackend::default();
+    let mut m = SecretsManager::new(&dir).with_backend(Box::new(store.clone()));
+    assert_eq!(m.backend_name(), "memory");
+
+    let entry = SecretEntry {
+        label: "OpenAI".to_string(),
+        kind: SecretKind::ApiKey,
+        policy: AccessPolicy::WithApproval,
+        description: None,
+        disabled: false,
+    };
+    m.store_credential("openai", &entry, "sk-test-value", None)
+        .unwrap();
+    m.store_secret("ANTHROPIC_API_KEY", "sk-ant-raw").unwrap();
+    m.setup_totp("tester").unwrap();
+
+    {
+        let values = store.0.lock().unwrap();
+        assert_eq!(
+            values.get("val:openai").map(String::as_str),
+            Some("sk-test-value")
+        );
+        assert_eq!(
+            values.get("ANTHROPIC_API_KEY").map(String::as_str),
+            Some("sk-ant-raw")
+        );
+        // Policies and TOTP never leave the vault.
+        assert!(!values.contains_key("cred:openai"));
+        assert!(!values.contains_key(SecretsManager::TOTP_SECRET_KEY));
+    }
+
+    // The tool-facing API behaves exactly as with the file backend.
+    let ctx = AccessContext {
+        user_approved: true,
+        ..Default::default()
+    };
+    match m.get_credential("openai", &ctx).unwrap().unwrap().1 {
+        CredentialValue::Single(v) => assert_eq!(v, "sk-test-value"),
+        _ => panic!("Expected Single"),
+    }
+    assert_eq!(m.get_secret("ANTHROPIC_API_KEY", false).unwrap(), None);
+    let keys = m.list_secrets();
+    assert!(keys.contains(&"ANTHROPIC_API_KEY".to_string()));
+    assert!(keys.contains(&"val:openai".to_string()));
+    assert!(!keys.contains(&SecretsManager::BACKEND_INDEX_KEY.to_string()));
+
+    m.delete_secret("ANTHROPIC_API_KEY").unwrap();
+    assert!(!store.0.lock().unwrap().contains_key("ANTHROPIC_API_KEY"));
+    assert!(!m.list_secrets().contains(&"ANTHROPIC_API_KEY".to_string()));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_backend_index_survives_reload() {
+    let dir = temp_dir();
+    let store = MemoryBackend::default();
+    {
+        let mut m = SecretsManager::new(&dir).with_backend(Box::new(store.clone()));
+        m.store_secret("GITHUB_TOKEN", "ghp_value").unwrap();
+    }
+    let mut m = SecretsManager::new(&dir).with_backend(Box::new(store));
+    m.set_agent_access(true);
+    assert!(m.list_secrets().contains(&"GITHUB_TOKEN".to_string()));
+    assert_eq!(
+        m.get_secret("GITHUB_TOKEN", false).unwrap().as_deref(),
+        Some("ghp_value")
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[cfg(not(feature = "keychain"))]
+#[test]
+fn test_keychain_backend_requires_feature() {
+    let dir = temp_dir();
+    let err = SecretsManager::new(&dir)
+        .with_backend_kind(SecretsBackendKind::Keychain)
+        .err()
+        .expect("keychain should be unavailable");
+    assert!(err.to_string().contains("keychain"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Round-trip through the real OS keychain.  Skipped (with a note) when
+/// the platform keychain is unreachable, e.g. headless CI without a
+/// Secret Service daemon.
+#[cfg(feature = "keychain")]
+#[test]
+fn test_keychain_backend_round_trip() {
+    let dir = temp_dir();
+    if !KeychainBackend::new(&dir).is_available() {
+        eprintln!("skipping: OS keychain not available");
+        let _ = std::fs::remove_dir_all(&dir);
+        return;
+    }
+
+    let mut m = SecretsManager::new(&dir)
+        .with_backend_kind(SecretsBackendKind::Keychain)
+        .unwrap();
+    assert_eq!(m.backend_name(), "keychain");
+    m.set_agent_access(true);
+
+    m.store_secret("RUSTYCLAW_TEST_KEY", "keychain-value")
+        .unwrap();
+    assert_eq!(
+        m.get_secret("RUSTYCLAW_TEST_KEY", false)
+            .unwrap()
+            .as_deref(),
+        Some("keychain-value")
+    );
+    // The value is not written to the vault file.
+    let vault_file = std::fs::read_to_string(dir.join("secrets.json")).unwrap();
+    assert!(!vault_file.contains("RUSTYCLAW_TEST_KEY\":"));
+
+    m.delete_secret("RUSTYCLAW_TEST_KEY").unwrap();
+    assert_eq!(m.get_secret("RUSTYCLAW_TEST_KEY", false).unwrap(), None);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_access_log_records_decisions_without_values() {
+    let dir = temp_dir();
+    let mut m = SecretsManager::new(&dir);
+    m.store_secret("OPENAI_API_KEY", "sk-very-secret").unwrap();
+    let entry = SecretEntry {
+        label: "deploy-key".to_string(),
+        kind: SecretKind::Token,
+        policy: AccessPolicy::SkillOnly(vec!["deploy".to_string()]),
+        description: None,
+        disabled: false,
+    };
+    m.store_credential("dk", &entry, "deploy-token-value", None)
+        .unwrap();
+
+    // Agent access is off, so an unapproved read is denied.
+    assert_eq!(m.get_secret("OPENAI_API_KEY", false).unwrap(), None);
+    let ctx = AccessContext::approved("provider:openai");
+    assert!(m.get_secret_with("OPENAI_API_KEY", &ctx).unwrap().is_some());
+    assert_eq!(m.get_secret("NOPE", true).unwrap(), None);
+    let ctx = AccessContext {
+        active_skill: Some("build".to_string()),
+        session: Some("connection:7".to_string()),
+        ..AccessContext::requested_by("tool:ssh")
+    };
+    assert!(m.get_credential("dk", &ctx).is_err());
+
+    // Metadata reads inside get_credential are not logged.
+    let log = m.access_log(&AuditFilter::default()).unwrap();
+    let outcomes: Vec<_> = log.iter().map(|r| r.outcome).collect();
+    assert_eq!(
+        outcomes,
+        [
+            AccessOutcome::Denied,
+            AccessOutcome::Allowed,
+            AccessOutcome::NotFound,
+            AccessOutcome::Denied,
+        ]
+    );
+    assert_eq!(log[1].requester.as_deref(), Some("provider:openai"));
+    let denied = &log[3];
+    assert_eq!(denied.secret, "dk");
+    assert_eq!(denied.skill.as_deref(), Some("build"));
+    assert_eq!(denied.session.as_deref(), Some("connection:7"));
+    assert_eq!(denied.reason.as_deref(), Some("policy: skills: deploy"));
+
+    let raw = std::fs::read_to_string(dir.join(audit::AUDIT_FILE)).unwrap();
+    assert!(!raw.contains("sk-very-secret") && !raw.contains("deploy-token-value"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let meta = std::fs::metadata(dir.join(audit::AUDIT_FILE)).unwrap();
+        assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+    }
+
+    let filter = AuditFilter {
+        requester: Some("SSH".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(m.access_log(&filter).unwrap().len(), 1);
+    let filter = AuditFilter {
+        outcome: Some(AccessOutcome::Denied),
+        limit: Some(1),
+        ..Default::default()
+    };
+    let last_denied = m.access_log(&filter).unwrap();
+    assert_eq!(last_denied.len(), 1);
+    assert_eq!(last_denied[0].secret, "dk");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_access_log_rotates() {
+    let dir = temp_dir();
+    let path = dir.join(audit::AUDIT_FILE);
+    for i in 0..20 {
+        let record = AccessRecord {
+            timestamp: i,
+            secret: "key".to_string(),
+            requester: None,
+            skill: None,
+            session: None,
+            request: None,
+            outcome: AccessOutcome::Allowed,
+            reason: None,
+            prev: None,
+            sig: None,
+        };
+        audit::append_with(&path, &record, &LogRotator::new(120, 3, None)).unwrap();
+    }
+
+    for n in 1..=3 {
+        assert!(dir.join(format!("{}.{}", audit::AUDIT_FILE, n)).exists());
+    }
+    assert!(!dir.join(format!("{}.4", audit::AUDIT_FILE)).exists());
+    assert!(std::fs::metadata(&path).unwrap().len() <= 120);
+
+    // The oldest records were dropped; the rest read back in order.
+    let records = audit::read(&path, &AuditFilter::default()).unwrap();
+    let times: Vec<u64> = records.iter().map(|r| r.timestamp).collect();
+    assert!(times.len() < 20);
+    assert!(times.windows(2).all(|w| w[0] + 1 == w[1]));
+    assert_eq!(times.last(), Some(&19));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_access_log_chain_verifies_and_locates_tampering() {
+    let dir = temp_dir();
+    let path = dir.join(audit::AUDIT_FILE);
+    let mut m = SecretsManager::new(&dir);
+    m.store_secret("GITHUB_TOKEN", "ghp-secret").unwrap();
+    let ctx = AccessContext::approved("tool:git");
+    for _ in 0..5 {
+        m.get_secret_with("GITHUB_TOKEN", &ctx).unwrap();
+    }
+
+    let report = m.verify_access_log().unwrap();
+    assert!(report.is_intact(), "{report:?}");
+    assert_eq!((report.entries, report.unsigned), (5, 0));
+    let original = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = original.lines().collect();
+    assert!(lines[1..].iter().all(|l| l.contains("\"prev\"")));
+    assert!(lines.iter().all(|l| l.contains("\"sig\"")));
+
+    // Rewrite the third read as denied: caught at that line, not after.
+    let edited = lines[2].replace("\"allowed\"", "\"denied\"");
+    let mut tampered = lines.clone();
+    tampered[2] = &edited;
+    std::fs::write(&path, tampered.join("\n") + "\n").unwrap();
+    let broken = m.verify_access_log().unwrap().broken.unwrap();
+    assert_eq!((broken.entry, broken.line), (3, 3));
+    assert_eq!(broken.problem, ChainBreak::BadSignature);
+
+    // Dropping that line instead breaks the link from the one after it.
+    let mut removed = lines.clone();
+    removed.remove(2);
+    std::fs::write(&path, removed.join("\n") + "\n").unwrap();
+    let broken = m.verify_access_log().unwrap().broken.unwrap();
+    assert_eq!(broken.entry, 3);
+    assert_eq!(broken.problem, ChainBreak::Unlinked);
+
+    // The signing key stays out of listings.
+    std::fs::write(&path, &original).unwrap();
+    assert!(m.verify_access_log().unwrap().is_intact());
+    let names: Vec<String> = m.list_all_entries().into_iter().map(|(n, _)| n).collect();
+    assert_eq!(names, ["GITHUB_TOKEN"]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_access_log_refuses_unsigned_tail() {
+    let dir = temp_dir();
+    let path = dir.join(audit::AUDIT_FILE);
+    let mut m = SecretsManager::new(&dir);
+    m.store_secret("GITHUB_TOKEN", "ghp-secret").unwrap();
+    let ctx = AccessContext::approved("tool:git");
+    for _ in 0..5 {
+        m.get_secret_with("GITHUB_TOKEN", &ctx).unwrap();
+    }
+
+    // Replace the last two reads with lines chained correctly but written
+    // without the vault's key.
+    let original = std::fs::read_to_string(&path).unwrap();
+    let kept: Vec<&str> = original.lines().take(3).collect();
+    std::fs::write(&path, kept.join("\n") + "\n").unwrap();
+    for _ in 0..2 {
+        let record = AccessRecord {
+            timestamp: 0,
+            secret: "GITHUB_TOKEN".to_string(),
+            requester: None,
+            skill: None,
+            session: None,
+            request: None,
+            outcome: AccessOutcome::Denied,
+            reason: None,
+            prev: None,
+            sig: None,
+        };
+        let record = audit::seal(&path, record, None);
+        assert!(record.prev.is_some() && record.sig.is_none());
+        audit::append(&path, &record).unwrap();
+    }
+
+    let report = m.verify_access_log().unwrap();
+    let broken = report.broken.unwrap();
+    assert_eq!((broken.entry, broken.line), (4, 4));
+    assert_eq!(broken.problem, ChainBreak::Unsigned);
+    assert_eq!(report.unsigned, 0);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_generate_password_respects_length_and_classes() {
+    let spec = PasswordSpec {
+        length: 40,
+        classes: vec![CharClass::Upper, CharClass::Digits],
+        exclude_ambiguous: true,
+    };
+    for _ in 0..20 {
+        let password = generate_password(&spec).unwrap().value;
+        assert_eq!(password.chars().count(), 40);
+        assert!(
+            password
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        );
+        assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+        assert!(password.chars().any(|c| c.is_ascii_digit()));
+        assert!(!password.contains(['0', 'O', '1', 'I']));
+    }
+
+    let hex = PasswordSpec {
+        length: 64,
+        classes: vec![CharClass::Hex],
+        exclude_ambiguous: false,
+    };
+    let token = generate_password(&hex).unwrap();
+    assert_eq!(token.value.len(), 64);
+    assert!(token.value.chars().all(|c| "0123456789abcdef".contains(c)));
+    assert_eq!(token.entropy_bits, 256.0);
+
+    // Every default class shows up even in the shortest password.
+    let short = PasswordSpec {
+        length: generate::MIN_PASSWORD_LENGTH,
+        ..Default::default()
+    };
+    let password = generate_password(&short).unwrap().value;
+    assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+    assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+    assert!(password.chars().any(|c| c.is_ascii_digit()));
+    assert!(password.chars().any(|c| c.is_ascii_punctuation()));
+}
+
+#[test]
+fn test_generate_password_rejects_bad_specs() {
+    let too_short = PasswordSpec {
+        length: 4,
+        ..Default::default()
+    };
+    assert!(generate_password(&too_short).is_err());
+    let no_classes = PasswordSpec {
+        classes: Vec::new(),
+        ..Default::default()
+    };
+    assert!(generate_password(&no_classes).is_err());
+    let mixed_hex = PasswordSpec {
+        classes: vec![CharClass::Hex, CharClass::Upper],
+        ..Default::default()
+    };
+    assert!(generate_password(&mixed_hex).is_err());
+    assert_eq!(CharClass::parse(" Digits"), Some(CharClass::Digits));
+    assert_eq!(CharClass::parse("emoji"), None);
+}
+
+#[test]
+fn test_generate_passphrase() {
+    let phrase = generate_passphrase(6, "-").unwrap();
+    let words: Vec<&str> = phrase.value.split('-').collect();
+    assert_eq!(words.len(), 6);
+    for word in &words {
+        assert!(word.len() >= 3 && word.chars().all(|c| c.is_ascii_lowercase()));
+    }
+    assert_eq!(phrase.entropy_bits, 66.0);
+
+    assert!(generate_passphrase(2, " ").is_err());
+    assert!(generate_passphrase(100, " ").is_err());
+}
@@ -315,6 +315,29 @@ pub struct AccessContext {
     pub authenticated: bool,
     /// The name of the skill currently being executed, if any.
     pub active_skill: Option<String>,
+    /// What is asking, e.g. `tool:ssh`; recorded in the access log.
+    pub requester: Option<String>,
+    /// The session or connection the request belongs to, if known.
+    pub session: Option<String>,
+}
+
+impl AccessContext {
+    /// A context that only names the requester.
+    pub fn requested_by(requester: impl Into<String>) -> Self {
+        Self {
+            requester: Some(requester.into()),
+            ..Default::default()
+        }
+    }
+
+    /// RustyClaw itself reading a secret on the user's behalf, such as a
+    /// provider API key; approved, and logged under `requester`.
+    pub fn approved(requester: impl Into<String>) -> Self {
+        Self {
+            user_approved: true,
+            ..Self::requested_by(requester)
+        }
+    }
 }
 
 /// Kept for backward compatibility with older code that references this type.
@@ -4,6 +4,8 @@ use anyhow::{Context, Result};
 use securestore::KeySource;
 
 use super::SecretsManager;
+use super::audit::AccessOutcome;
+use super::backend::SecretsBackend;
 use super::types::{
     AccessContext, AccessPolicy, CredentialValue, SecretEntry, SecretKind, SecretString,
 };
@@ -136,46 +138,131 @@ impl SecretsManager {
 
     // ── CRUD operations ─────────────────────────────────────────────
 
-    /// Store (or overwrite) a secret in the vault and persist to disk.
+    /// Vault key holding the JSON list of keys stored in the value backend.
+    ///
+    /// OS keychains cannot enumerate their items, so the index lives in the
+    /// file vault next to the rest of the bookkeeping.
+    pub(super) const BACKEND_INDEX_KEY: &'static str = "__rustyclaw_backend_index";
+
+    /// Keys that always stay in the file vault, whatever the backend:
+    /// credential metadata/policies, TOTP and its backup codes, gateway
+    /// tokens, the access log key and the index.
+    fn is_bookkeeping_key(key: &str) -> bool {
+        key.starts_with("cred:")
+            || key == Self::TOTP_SECRET_KEY
+            || key == Self::BACKUP_CODES_KEY
+            || key == Self::GATEWAY_TOKENS_KEY
+            || key == Self::AUDIT_KEY
+            || key == Self::BACKEND_INDEX_KEY
+            || key == "__init"
+    }
+
+    /// Whether `key` is routed to the configured value backend.
+    fn uses_value_backend(&self, key: &str) -> bool {
+        self.backend.is_some() && !Self::is_bookkeeping_key(key)
+    }
+
+    /// The backend that holds `key`.
+    fn backend_for(&mut self, key: &str) -> Result<&mut dyn SecretsBackend> {
+        if !self.uses_value_backend(key) {
+            return Ok(self.ensure_vault()?);
+        }
+        self.backend
+            .as_deref_mut()
+            .context("No secrets backend configured")
+    }
+
+    fn backend_index(&mut self) -> Result<Vec<String>> {
+        match SecretsBackend::get(self.ensure_vault()?, Self::BACKEND_INDEX_KEY)? {
+            Some(json) => serde_json::from_str(&json).context("Corrupted secrets backend index"),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn update_backend_index(&mut self, key: &str, present: bool) -> Result<()> {
+        let mut index = self.backend_index()?;
+        let listed = index.iter().any(|k| k == key);
+        if listed == present {
+            return Ok(());
+        }
+        if present {
+            index.push(key.to_string());
+        } else {
+            index.retain(|k| k != key);
+        }
+        let json = serde_json::to_string(&index).context("Failed to serialize backend index")?;
+        SecretsBackend::set(self.ensure_vault()?, Self::BACKEND_INDEX_KEY, &json)
+    }
+
+    /// Store (or overwrite) a secret and persist it.
     pub fn store_secret(&mut self, key: &str, value: &str) -> Result<()> {
-        let vault = self.ensure_vault()?;
-        vault.set(key, value);
-        vault.save().context("Failed to save secrets vault")?;
+        self.backend_for(key)?.set(key, value)?;
+        if self.uses_value_backend(key) {
+            self.update_backend_index(key, true)?;
+        }
         Ok(())
     }
 
-    /// Retrieve a secret from the vault.
+    /// Retrieve a secret.
     ///
     /// Returns `None` if the secret does not exist **or** if agent
     /// access is disabled and the caller has not provided explicit
     /// user approval.
     pub fn get_secret(&mut self, key: &str, user_approved: bool) -> Result<Option<String>> {
-        if !self.agent_access_enabled && !user_approved {
+        let ctx = AccessContext {
+            user_approved,
+            ..Default::default()
+        };
+        self.get_secret_with(key, &ctx)
+    }
+
+    /// Like [`get_secret`](Self::get_secret), taking approval from `ctx`
+    /// and recording its requester, skill and session in the access log.
+    pub fn get_secret_with(&mut self, key: &str, ctx: &AccessContext) -> Result<Option<String>> {
+        if !self.agent_access_enabled && !ctx.user_approved {
+            let reason = "agent access disabled and not approved".to_string();
+            self.record_access(key, ctx, AccessOutcome::Denied, Some(reason));
             return Ok(None);
         }
 
-        let vault = self.ensure_vault()?;
-        match vault.get(key) {
-            Ok(value) => Ok(Some(value)),
-            Err(e) if e.kind() == securestore::ErrorKind::SecretNotFound => Ok(None),
-            Err(e) => Err(anyhow::anyhow!("Failed to get secret: {}", e)),
-        }
+        let value = self.read_secret(key)?;
+        let outcome = match value {
+            Some(_) => AccessOutcome::Allowed,
+            None => AccessOutcome::NotFound,
+        };
+        self.record_access(key, ctx, outcome, None);
+        Ok(value)
     }
 
-    /// Delete a secret from the vault and persist to disk.
+    /// Read a value with no access check or log entry, for metadata and
+    /// the manager's own bookkeeping.
+    pub(super) fn read_secret(&mut self, key: &str) -> Result<Option<String>> {
+        self.backend_for(key)?.get(key)
+    }
+
+    /// Delete a secret and persist the change.
     pub fn delete_secret(&mut self, key: &str) -> Result<()> {
-        let vault = self.ensure_vault()?;
-        vault.remove(key).context("Failed to remove secret")?;
-        vault.save().context("Failed to save secrets vault")?;
+        self.backend_for(key)?.remove(key)?;
+        if self.uses_value_backend(key) {
+            self.update_backend_index(key, false)?;
+        }
         Ok(())
     }
 
     /// List all stored secret keys (not values).
     pub fn list_secrets(&mut self) -> Vec<String> {
-        match self.ensure_vault() {
-            Ok(vault) => vault.keys().map(|s| s.to_string()).collect(),
-            Err(_) => Vec::new(),
+        let mut keys: Vec<String> = match self.ensure_vault() {
+            Ok(vault) => vault
+                .keys()
+                .filter(|k| *k != Self::BACKEND_INDEX_KEY)
+                .map(|s| s.to_string())
+                .collect(),
+            Err(_) => return Vec::new(),
+        };
+        if self.backend.is_some() {
+            keys.extend(self.backend_index().unwrap_or_default());
         }
+        keys
     }
 
     // ── Typed credential API ────────────────────────────────────────
@@ -293,6 +380,9 @@ impl SecretsManager {
     ///   password / TOTP (satisfies `WithAuth`).
     /// - `active_skill`: if the agent is currently executing a skill,
     ///   pass its name here (satisfies `SkillOnly` when listed).
+    ///
+    /// The outcome is recorded in the access log with `requester` and
+    /// `session`.
     pub fn get_credential(
         &mut self,
         name: &str,
@@ -302,20 +392,27 @@ impl SecretsManager {
         let val_key = format!("val:{}", name);
 
         // Load metadata.
-        let meta_json = match self.get_secret(&meta_key, true)? {
+        let meta_json = match self.read_secret(&meta_key)? {
             Some(j) => j,
-            None => return Ok(None),
+            None => {
+                self.record_access(name, ctx, AccessOutcome::NotFound, None);
+                return Ok(None);
+            }
         };
         let entry: SecretEntry =
             serde_json::from_str(&meta_json).context("Corrupted credential metadata")?;
 
         // ── Disabled check ─────────────────────────────────────────
         if entry.disabled {
+            let reason = "credential disabled".to_string();
+            self.record_access(name, ctx, AccessOutcome::Denied, Some(reason));
             anyhow::bail!("Credential '{}' is disabled", name,);
         }
 
         // ── Policy check ───────────────────────────────────────────
         if !self.check_access(&entry.policy, ctx) {
+            let reason = format!("policy: {}", entry.policy);
+            self.record_access(name, ctx, AccessOutcome::Denied, Some(reason));
             anyhow::bail!(
                 "Access denied for credential '{}' (policy: {:?})",
                 name,
@@ -326,24 +423,21 @@ impl SecretsManager {
         // ── Load value(s) ──────────────────────────────────────────
         //
         // NOTE: Values are wrapped in SecretString to zero memory on drop.
-        // The earlier `get_secret` returns raw String for backward compatibility;
+        // `read_secret` returns raw String for backward compatibility;
         // we intentionally convert to SecretString here as the single chokepoint
         // where credential values enter the process.
         let value = match entry.kind {
             SecretKind::UsernamePassword => {
-                let password =
-                    SecretString::new(self.get_secret(&val_key, true)?.unwrap_or_default());
+                let password = SecretString::new(self.read_secret(&val_key)?.unwrap_or_default());
                 let user_key = format!("val:{}:user", name);
-                let username =
-                    SecretString::new(self.get_secret(&user_key, true)?.unwrap_or_default());
+                let username = SecretString::new(self.read_secret(&user_key)?.unwrap_or_default());
                 CredentialValue::UserPass { username, password }
             }
             SecretKind::SshKey => {
                 let private_key =
-                    SecretString::new(self.get_secret(&val_key, true)?.unwrap_or_default());
+                    SecretString::new(self.read_secret(&val_key)?.unwrap_or_default());
                 let pub_key = format!("val:{}:pub", name);
-                let public_key =
-                    SecretString::new(self.get_secret(&pub_key, true)?.unwrap_or_default());
+                let public_key = SecretString::new(self.read_secret(&pub_key)?.unwrap_or_default());
                 CredentialValue::SshKeyPair {
                     private_key,
                     public_key,
@@ -352,7 +446,7 @@ impl SecretsManager {
             SecretKind::FormAutofill => {
                 let fields_key = format!("val:{}:fields", name);
                 let fields_json = self
-                    .get_secret(&fields_key, true)?
+                    .read_secret(&fields_key)?
                     .unwrap_or_else(|| "{}".to_string());
                 let fields: std::collections::BTreeMap<String, String> =
                     serde_json::from_str(&fields_json).context("Corrupted form-autofill fields")?;
@@ -363,7 +457,7 @@ impl SecretsManager {
                 let extra_key = format!("val:{}:card_extra", name);
 
                 let card_json = self
-                    .get_secret(&card_key, true)?
+                    .read_secret(&card_key)?
                     .unwrap_or_else(|| "{}".to_string());
 
                 #[derive(serde::Deserialize)]
@@ -381,7 +475,7 @@ impl SecretsManager {
                     serde_json::from_str(&card_json).context("Corrupted payment card data")?;
 
                 let extra: std::collections::BTreeMap<String, String> =
-                    match self.get_secret(&extra_key, true)? {
+                    match self.read_secret(&extra_key)? {
                         Some(j) => serde_json::from_str(&j).context("Corrupted card extras")?,
                         None => std::collections::BTreeMap::new(),
                     };
@@ -395,11 +489,12 @@ impl SecretsManager {
                 }
             }
             _ => {
-                let v = self.get_secret(&val_key, true)?.unwrap_or_default();
+                let v = self.read_secret(&val_key)?.unwrap_or_default();
                 CredentialValue::Single(SecretString::new(v))
             }
         };
 
+        self.record_access(name, ctx, AccessOutcome::Allowed, None);
         Ok(Some((entry, value)))
     }
 
@@ -409,7 +504,7 @@ impl SecretsManager {
         let mut result = Vec::new();
         for key in &keys {
             if let Some(name) = key.strip_prefix("cred:") {
-                if let Ok(Some(json)) = self.get_secret(key, true) {
+                if let Ok(Some(json)) = self.read_secret(key) {
                     if let Ok(entry) = serde_json::from_str::<SecretEntry>(&json) {
                         result.push((name.to_string(), entry));
                     }
@@ -424,8 +519,8 @@ impl SecretsManager {
     ///
     /// Legacy keys that match a known provider secret name get a
     /// synthesised [`SecretEntry`] with `kind = ApiKey` or `Token`.
-    /// Internal keys (TOTP secret, `__init`, `cred:*`, `val:*`) are
-    /// excluded.
+    /// Internal keys (TOTP secret and backup codes, gateway tokens, access log key, `__init`,
+    /// `cred:*`, `val:*`) are excluded.
     pub fn list_all_entries(&mut self) -> Vec<(String, SecretEntry)> {
         let all_keys = self.list_secrets();
 
@@ -435,7 +530,7 @@ impl SecretsManager {
         // 1. Typed credentials (cred:* prefix)
         for key in &all_keys {
             if let Some(name) = key.strip_prefix("cred:") {
-                if let Ok(Some(json)) = self.get_secret(key, true) {
+                if let Ok(Some(json)) = self.read_secret(key) {
                     if let Ok(entry) = serde_json::from_str::<SecretEntry>(&json) {
                         typed_names.insert(name.to_string());
                         result.push((name.to_string(), entry));
@@ -450,6 +545,9 @@ impl SecretsManager {
             if key.starts_with("cred:")
                 || key.starts_with("val:")
                 || key == Self::TOTP_SECRET_KEY
+                || key == Self::BACKUP_CODES_KEY
+                || key == Self::GATEWAY_TOKENS_KEY
+                || key == Self::AUDIT_KEY
                 || key == "__init"
             {
                 continue;
@@ -516,30 +614,35 @@ impl SecretsManager {
     /// This bypasses the disabled check and the access-policy check because
     /// the *user* is physically present and explicitly asked to view the
     /// secret.  For legacy bare-key secrets (no `cred:` metadata) the raw
-    /// value is returned directly.
+    /// value is returned directly.  The view is still recorded in the
+    /// access log.
     pub fn peek_credential_display(&mut self, name: &str) -> Result<Vec<(String, String)>> {
         let meta_key = format!("cred:{}", name);
         let val_key = format!("val:{}", name);
+        let ctx = AccessContext {
+            user_approved: true,
+            ..AccessContext::requested_by("user:viewer")
+        };
 
         // Check if this is a typed credential.
-        if let Some(json) = self.get_secret(&meta_key, true)? {
+        if let Some(json) = self.read_secret(&meta_key)? {
             let entry: SecretEntry =
                 serde_json::from_str(&json).context("Corrupted credential metadata")?;
 
             let pairs = match entry.kind {
                 SecretKind::UsernamePassword => {
-                    let password = self.get_secret(&val_key, true)?.unwrap_or_default();
+                    let password = self.read_secret(&val_key)?.unwrap_or_default();
                     let user_key = format!("val:{}:user", name);
-                    let username = self.get_secret(&user_key, true)?.unwrap_or_default();
+                    let username = self.read_secret(&user_key)?.unwrap_or_default();
                     vec![
                         ("Username".to_string(), username),
                         ("Password".to_string(), password),
                     ]
                 }
                 SecretKind::SshKey => {
-                    let private_key = self.get_secret(&val_key, true)?.unwrap_or_default();
+                    let private_key = self.read_secret(&val_key)?.unwrap_or_default();
                     let pub_key = format!("val:{}:pub", name);
-                    let public_key = self.get_secret(&pub_key, true)?.unwrap_or_default();
+                    let public_key = self.read_secret(&pub_key)?.unwrap_or_default();
                     vec![
                         ("Public Key".to_string(), public_key),
                         ("Private Key".to_string(), private_key),
@@ -548,7 +651,7 @@ impl SecretsManager {
                 SecretKind::FormAutofill => {
                     let fields_key = format!("val:{}:fields", name);
                     let fields_json = self
-                        .get_secret(&fields_key, true)?
+                        .read_secret(&fields_key)?
                         .unwrap_or_else(|| "{}".to_string());
                     let fields: std::collections::BTreeMap<String, String> =
                         serde_json::from_str(&fields_json).unwrap_or_default();
@@ -557,7 +660,7 @@ impl SecretsManager {
                 SecretKind::PaymentMethod => {
                     let card_key = format!("val:{}:card", name);
                     let card_json = self
-                        .get_secret(&card_key, true)?
+                        .read_secret(&card_key)?
                         .unwrap_or_else(|| "{}".to_string());
 
                     #[derive(serde::Deserialize)]
@@ -586,7 +689,7 @@ impl SecretsManager {
                     ];
 
                     let extra_key = format!("val:{}:card_extra", name);
-                    if let Some(j) = self.get_secret(&extra_key, true)? {
+                    if let Some(j) = self.read_secret(&extra_key)? {
                         let extra: std::collections::BTreeMap<String, String> =
                             serde_json::from_str(&j).unwrap_or_default();
                         for (k, v) in extra {
@@ -596,15 +699,16 @@ impl SecretsManager {
                     pairs
                 }
                 _ => {
-                    let v = self.get_secret(&val_key, true)?.unwrap_or_default();
+                    let v = self.read_secret(&val_key)?.unwrap_or_default();
                     vec![("Value".to_string(), v)]
                 }
             };
+            self.record_access(name, &ctx, AccessOutcome::Allowed, None);
             return Ok(pairs);
         }
 
         // Legacy bare-key secret — return the raw value.
-        match self.get_secret(name, true)? {
+        match self.get_secret_with(name, &ctx)? {
             Some(v) => Ok(vec![("Value".to_string(), v)]),
             None => anyhow::bail!("Secret '{}' not found", name),
         }
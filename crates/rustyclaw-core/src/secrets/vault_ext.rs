@@ -35,7 +35,7 @@ impl SecretsManager {
     pub fn set_credential_disabled(&mut self, name: &str, disabled: bool) -> Result<()> {
         let meta_key = format!("cred:{}", name);
 
-        let mut entry: SecretEntry = match self.get_secret(&meta_key, true)? {
+        let mut entry: SecretEntry = match self.read_secret(&meta_key)? {
             Some(json) => serde_json::from_str(&json).context("Corrupted credential metadata")?,
             None => {
                 // Legacy bare key — promote to typed entry.
@@ -62,7 +62,7 @@ impl SecretsManager {
     pub fn set_credential_policy(&mut self, name: &str, policy: AccessPolicy) -> Result<()> {
         let meta_key = format!("cred:{}", name);
 
-        let mut entry: SecretEntry = match self.get_secret(&meta_key, true)? {
+        let mut entry: SecretEntry = match self.read_secret(&meta_key)? {
             Some(json) => serde_json::from_str(&json).context("Corrupted credential metadata")?,
             None => {
                 // Legacy bare key — promote to typed entry.
@@ -193,6 +193,8 @@ impl SecretsManager {
         // Store the base32-encoded secret in the vault.
         let encoded = secret.to_encoded().to_string();
         self.store_secret(Self::TOTP_SECRET_KEY, &encoded)?;
+        // Backup codes belong to the old secret; the caller issues new ones.
+        self.remove_backup_codes()?;
 
         Ok(totp.get_url())
     }
@@ -202,7 +204,7 @@ impl SecretsManager {
     /// or an error if no TOTP secret is configured.
     pub fn verify_totp(&mut self, code: &str) -> Result<bool> {
         let encoded = self
-            .get_secret(Self::TOTP_SECRET_KEY, true)?
+            .read_secret(Self::TOTP_SECRET_KEY)?
             .ok_or_else(|| anyhow::anyhow!("No TOTP secret configured"))?;
 
         // Users often paste codes formatted like "123 456" or "123-456".
@@ -244,18 +246,18 @@ impl SecretsManager {
 
     /// Check whether a TOTP secret is stored in the vault.
     pub fn has_totp(&mut self) -> bool {
-        self.get_secret(Self::TOTP_SECRET_KEY, true)
+        self.read_secret(Self::TOTP_SECRET_KEY)
             .ok()
             .flatten()
             .is_some()
     }
 
-    /// Remove the stored TOTP secret (disables 2FA).
+    /// Remove the stored TOTP secret and its backup codes (disables 2FA).
     pub fn remove_totp(&mut self) -> Result<()> {
         if self.has_totp() {
             self.delete_secret(Self::TOTP_SECRET_KEY)?;
         }
-        Ok(())
+        self.remove_backup_codes()
     }
 
     /// No-op kept for API compatibility.  The securestore crate
@@ -269,7 +271,7 @@ impl SecretsManager {
 
     /// Load the browser store from the vault, or create a new empty one.
     pub fn load_browser_store(&mut self) -> Result<super::types::BrowserStore> {
-        match self.get_secret(Self::BROWSER_STORE_KEY, true)? {
+        match self.read_secret(Self::BROWSER_STORE_KEY)? {
             Some(json) => {
                 let mut store: super::types::BrowserStore =
                     serde_json::from_str(&json).context("Corrupted browser store")?;
@@ -35,3 +35,23 @@ pub use prompt_guard::{GuardAction, GuardResult, PromptGuard};
 pub use safety_layer::{DefenseCategory, DefenseResult, PolicyAction, SafetyConfig, SafetyLayer};
 pub use ssrf::SsrfValidator;
 pub use validator::{InputValidator, ValidationError, ValidationErrorCode, ValidationResult};
+
+/// Secret-shaped values searched for by the `audit_sensitive` tool and
+/// masked in the provider wire log, as `(label, regex)` pairs.
+pub const SENSITIVE_PATTERNS: &[(&str, &str)] = &[
+    ("AWS Access Key", r"AKIA[0-9A-Z]{16}"),
+    (
+        "Private Key Header",
+        r"-----BEGIN (RSA |DSA |EC |OPENSSH )?PRIVATE KEY-----",
+    ),
+    ("GitHub Token", r"gh[ps]_[A-Za-z0-9_]{36,}"),
+    (
+        "Generic API Key",
+        r#"(?i)(api[_-]?key|apikey|secret[_-]?key)\s*[=:"]\s*[A-Za-z0-9_\-]{20,}"#,
+    ),
+    (
+        "JWT Token",
+        r"eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+    ),
+    ("Slack Token", r"xox[bpras]-[A-Za-z0-9-]{10,}"),
+];
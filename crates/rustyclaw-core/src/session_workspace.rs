@@ -0,0 +1,205 @@
+//! Per-session working directories.
+//!
+//! With `[session_workspaces] isolate = true`, each session — a gateway
+//! conversation thread or a messenger chat — gets its own directory at
+//! `<workspace>/sessions/<key>/`, created when the session first runs a
+//! turn and passed to its tool calls as their `workspace_dir`.
+//! Relative paths and command working directories then land there, so
+//! sessions working in parallel don't overwrite each other's files.
+//!
+//! Isolation only moves the starting point.  Absolute paths are resolved
+//! as before and remain subject to the global sandbox policy, and the
+//! tools that keep workspace-wide state (memory, the key/value store,
+//! skills, cron jobs) keep using the workspace root — see
+//! [`workspace_root`].
+//!
+//! When isolation is off every session shares the root, as before.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tracing::debug;
+
+use crate::config::Config;
+
+/// Sessions directories seen so far, mapped to their workspace root, so
+/// [`workspace_root`] can find the root from a session directory.
+static SESSION_ROOTS: Mutex<BTreeMap<PathBuf, PathBuf>> = Mutex::new(BTreeMap::new());
+
+/// Where sessions work, per `[session_workspaces]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionWorkspaces {
+    root: PathBuf,
+    /// `<root>/<dir>`, or `None` when sessions share the root.
+    sessions_dir: Option<PathBuf>,
+}
+
+impl SessionWorkspaces {
+    pub fn from_config(config: &Config) -> Self {
+        let root = config.workspace_dir();
+        let settings = &config.session_workspaces;
+        if settings.isolate {
+            Self::isolated(root, &settings.dir)
+        } else {
+            Self::shared(root)
+        }
+    }
+
+    /// Every session works in `root`.
+    pub fn shared(root: PathBuf) -> Self {
+        Self {
+            root,
+            sessions_dir: None,
+        }
+    }
+
+    /// Each session works in `<root>/<dir>/<key>`.
+    pub fn isolated(root: PathBuf, dir: &str) -> Self {
+        let sessions_dir = root.join(dir);
+        Self {
+            root,
+            sessions_dir: Some(sessions_dir),
+        }
+    }
+
+    pub fn is_isolated(&self) -> bool {
+        self.sessions_dir.is_some()
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The directory `key`'s tools work in, without creating it.
+    pub fn dir_for(&self, key: &str) -> PathBuf {
+        match &self.sessions_dir {
+            Some(sessions_dir) => sessions_dir.join(sanitize_key(key)),
+            None => self.root.clone(),
+        }
+    }
+
+    /// The directory `key`'s tools work in, created if it doesn't exist.
+    pub fn enter(&self, key: &str) -> std::io::Result<PathBuf> {
+        let dir = self.dir_for(key);
+        if let Some(sessions_dir) = &self.sessions_dir {
+            std::fs::create_dir_all(&dir)?;
+            SESSION_ROOTS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(sessions_dir.clone(), self.root.clone());
+        }
+        Ok(dir)
+    }
+
+    /// End `key`'s session: remove its directory if nothing was left in
+    /// it.  Returns whether a directory was removed.
+    pub fn leave(&self, key: &str) -> bool {
+        if !self.is_isolated() {
+            return false;
+        }
+        let dir = self.dir_for(key);
+        // `remove_dir` refuses non-empty directories, which is the point.
+        let removed = std::fs::remove_dir(&dir).is_ok();
+        if removed {
+            debug!(dir = %dir.display(), "Removed empty session directory");
+        }
+        removed
+    }
+
+    /// Remove every empty session directory, e.g. those left by sessions
+    /// that ended while the gateway was down.  Returns how many went.
+    pub fn prune_empty(&self) -> usize {
+        let Some(sessions_dir) = &self.sessions_dir else {
+            return 0;
+        };
+        let Ok(entries) = std::fs::read_dir(sessions_dir) else {
+            return 0;
+        };
+        entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter(|entry| std::fs::remove_dir(entry.path()).is_ok())
+            .count()
+    }
+}
+
+/// The workspace root a tool's `workspace_dir` belongs to: the root when
+/// it is a session directory, otherwise the directory itself.
+pub fn workspace_root(workspace_dir: &Path) -> PathBuf {
+    let roots = SESSION_ROOTS.lock().unwrap_or_else(|e| e.into_inner());
+    workspace_dir
+        .parent()
+        .and_then(|parent| roots.get(parent))
+        .cloned()
+        .unwrap_or_else(|| workspace_dir.to_path_buf())
+}
+
+/// A session key as a single path component: anything but ASCII
+/// letters, digits, `-`, `_` and `.` becomes `_`.
+fn sanitize_key(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.chars().all(|c| c == '.') {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_sessions_use_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspaces = SessionWorkspaces::shared(dir.path().to_path_buf());
+        assert_eq!(workspaces.enter("thread-1").unwrap(), dir.path());
+        assert!(!workspaces.leave("thread-1"));
+        assert!(dir.path().exists());
+    }
+
+    #[test]
+    fn test_isolated_session_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspaces = SessionWorkspaces::isolated(dir.path().to_path_buf(), "sessions");
+
+        let a = workspaces.enter("thread-1").unwrap();
+        let b = workspaces.enter("telegram:42").unwrap();
+        assert_eq!(a, dir.path().join("sessions").join("thread-1"));
+        assert_eq!(b, dir.path().join("sessions").join("telegram_42"));
+        assert!(a.is_dir() && b.is_dir());
+        assert_eq!(workspace_root(&a), dir.path());
+        assert_eq!(workspace_root(dir.path()), dir.path());
+
+        std::fs::write(b.join("notes.txt"), "kept").unwrap();
+        assert!(workspaces.leave("thread-1"));
+        assert!(!a.exists());
+        assert!(!workspaces.leave("telegram:42"));
+        assert!(b.join("notes.txt").exists());
+
+        workspaces.enter("thread-2").unwrap();
+        assert_eq!(workspaces.prune_empty(), 1);
+        assert!(b.exists());
+    }
+
+    #[test]
+    fn test_keys_cannot_escape_sessions_dir() {
+        assert_eq!(sanitize_key("../../etc"), ".._.._etc");
+        assert_eq!(sanitize_key(".."), "_..");
+        assert_eq!(sanitize_key(""), "_");
+        assert_eq!(
+            sanitize_key("agent:main:subagent:1f"),
+            "agent_main_subagent_1f"
+        );
+    }
+}
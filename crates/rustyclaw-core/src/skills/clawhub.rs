@@ -226,8 +226,13 @@ impl SkillManager {
     /// Search the ClawHub registry for skills matching a query.
     ///
     /// If the registry is unreachable, falls back to matching against
-    /// locally-loaded skills so the user still gets useful results.
+    /// locally-loaded skills so the user still gets useful results.  A
+    /// local mirror is searched directly; its errors are returned.
     pub fn search_registry(&self, query: &str) -> Result<Vec<RegistryEntry>> {
+        if let Some(dir) = self.registry_mirror.as_deref() {
+            return self.search_mirror(dir, query);
+        }
+
         // ── Try remote registry first ───────────────────────────
         match self.search_registry_remote(query) {
             Ok(results) => return Ok(results),
@@ -297,14 +302,75 @@ impl SkillManager {
         Ok(entries)
     }
 
-    /// Install a skill from the ClawHub registry into the primary
-    /// skills directory.  Returns the installed `Skill`.
+    /// Install a skill from the ClawHub registry (or the local mirror)
+    /// into the primary skills directory.  Returns the installed `Skill`.
     pub fn install_from_registry(&mut self, name: &str, version: Option<&str>) -> Result<Skill> {
+        // Use last directory (user's writable dir) for installations, not first (bundled/read-only)
+        let skills_dir = self
+            .skills_dirs
+            .last()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No skills directory configured"))?;
+        let skill_dir = skills_dir.join(name);
+
+        let installed_version = match self.registry_mirror.clone() {
+            Some(dir) => self.fetch_from_mirror(&dir, name, version, &skill_dir)?,
+            None => {
+                self.download_from_registry(name, version, &skill_dir)?;
+                version.unwrap_or("latest").to_string()
+            }
+        };
+
+        // Write .clawhub metadata
+        let clawhub_dir = skill_dir.join(".clawhub");
+        std::fs::create_dir_all(&clawhub_dir)?;
+        let meta = serde_json::json!({
+            "version": 1,
+            "registry": self.registry_url,
+            "slug": name,
+            "installedVersion": installed_version,
+            "installedAt": std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        });
+        std::fs::write(
+            clawhub_dir.join("install.json"),
+            serde_json::to_string_pretty(&meta)?,
+        )?;
+
+        // Load the newly-installed skill.
+        let skill_md_path = skill_dir.join("SKILL.md");
+        let mut skill = self.load_skill_md(&skill_md_path)?;
+        skill.source = SkillSource::Registry {
+            registry_url: self.registry_url.clone(),
+            version: installed_version,
+        };
+
+        // Add or replace in the in-memory list.
+        if let Some(idx) = self.skills.iter().position(|s| s.name == skill.name) {
+            self.skills[idx] = skill.clone();
+        } else {
+            self.skills.push(skill.clone());
+        }
+
+        Ok(skill)
+    }
+
+    /// Internal: download a skill's zip from ClawHub and extract it into
+    /// `skill_dir`.
+    fn download_from_registry(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        skill_dir: &Path,
+    ) -> Result<()> {
         if !self.registry_reachable() {
             anyhow::bail!(
                 "ClawHub registry ({}) is not reachable. \
-                 Check your internet connection or set a custom registry URL \
-                 with `clawhub_url` in your config.",
+                 Check your internet connection, set a custom registry URL \
+                 with `clawhub_url`, or install from a local mirror with \
+                 `clawhub_mirror` in your config.",
                 self.registry_url,
             );
         }
@@ -331,15 +397,7 @@ impl SkillManager {
         // Response is a zip file
         let zip_bytes = resp.bytes().context("Failed to read zip data")?;
 
-        // Use last directory (user's writable dir) for installations, not first (bundled/read-only)
-        let skills_dir = self
-            .skills_dirs
-            .last()
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("No skills directory configured"))?;
-
-        let skill_dir = skills_dir.join(name);
-        std::fs::create_dir_all(&skill_dir)?;
+        std::fs::create_dir_all(skill_dir)?;
 
         // Extract zip to skill directory
         let cursor = std::io::Cursor::new(zip_bytes);
@@ -360,40 +418,7 @@ impl SkillManager {
             }
         }
 
-        // Write .clawhub metadata
-        let clawhub_dir = skill_dir.join(".clawhub");
-        std::fs::create_dir_all(&clawhub_dir)?;
-        let meta = serde_json::json!({
-            "version": 1,
-            "registry": self.registry_url,
-            "slug": name,
-            "installedVersion": version.unwrap_or("latest"),
-            "installedAt": std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as u64,
-        });
-        std::fs::write(
-            clawhub_dir.join("install.json"),
-            serde_json::to_string_pretty(&meta)?,
-        )?;
-
-        // Load the newly-installed skill.
-        let skill_md_path = skill_dir.join("SKILL.md");
-        let mut skill = self.load_skill_md(&skill_md_path)?;
-        skill.source = SkillSource::Registry {
-            registry_url: self.registry_url.clone(),
-            version: version.unwrap_or("latest").to_string(),
-        };
-
-        // Add or replace in the in-memory list.
-        if let Some(idx) = self.skills.iter().position(|s| s.name == skill.name) {
-            self.skills[idx] = skill.clone();
-        } else {
-            self.skills.push(skill.clone());
-        }
-
-        Ok(skill)
+        Ok(())
     }
 
     /// Publish a local skill to the ClawHub registry.
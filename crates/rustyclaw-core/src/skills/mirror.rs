@@ -0,0 +1,285 @@
+//! Local registry mirrors for installing skills without internet access.
+//!
+//! A mirror is a directory (configured with `clawhub_mirror`, as a path or a
+//! `file://` URL) laid out as:
+//!
+//! ```text
+//! index.json              skills and the versions the mirror holds
+//! <name>/<version>/...    the files of one version (SKILL.md and friends)
+//! ```
+//!
+//! `rustyclaw skills bundle <dir>` writes one from the installed skills;
+//! bundling into an existing mirror adds to it.  Search and install read it
+//! the way they would query ClawHub, and a version is resolved the same way:
+//! an exact version when one is asked for, otherwise the latest.
+
+#![allow(unused_imports)]
+use super::*;
+
+/// Name of the index file at the root of a mirror.
+pub const MIRROR_INDEX: &str = "index.json";
+
+/// Version given to a bundled skill that doesn't record one.
+const DEFAULT_BUNDLE_VERSION: &str = "0.1.0";
+
+/// The `index.json` of a mirror.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MirrorIndex {
+    #[serde(default)]
+    pub skills: Vec<MirrorSkill>,
+}
+
+/// One skill in a mirror.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorSkill {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub required_secrets: Vec<String>,
+    /// Versions held by the mirror, each in `<name>/<version>/`.
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+/// The mirror directory named by a registry setting: a `file://` URL or a
+/// path.  `None` for an `http(s)://` registry.
+pub fn mirror_dir(registry: &str) -> Option<PathBuf> {
+    let registry = registry.trim();
+    if let Some(path) = registry.strip_prefix("file://") {
+        return Some(PathBuf::from(path));
+    }
+    if registry.contains("://") {
+        return None;
+    }
+    let expanded = match registry.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()?.join(rest),
+        None => PathBuf::from(registry),
+    };
+    Some(expanded)
+}
+
+/// Sort key of a version: its numeric parts ("v1.10.0" > "1.9.2"), then
+/// whether it is a release, so "1.0.0" beats "1.0.0-beta".
+fn version_key(version: &str) -> (Vec<u64>, bool) {
+    let version = version.trim_start_matches('v');
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<u64> = core
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect();
+    (parts, !version.contains('-'))
+}
+
+/// Pick the version to install from `available`: the requested one (an
+/// exact match, ignoring a leading `v`) or, for none or `latest`, the
+/// highest.
+pub fn resolve_version(available: &[String], requested: Option<&str>) -> Option<String> {
+    match requested.map(str::trim) {
+        None | Some("") | Some("latest") => available
+            .iter()
+            .max_by(|a, b| version_key(a).cmp(&version_key(b)))
+            .cloned(),
+        Some(wanted) => {
+            let wanted = wanted.trim_start_matches('v');
+            available
+                .iter()
+                .find(|v| v.trim_start_matches('v') == wanted)
+                .cloned()
+        }
+    }
+}
+
+impl MirrorIndex {
+    /// Read the index of the mirror at `dir`.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MIRROR_INDEX);
+        let json = std::fs::read_to_string(&path).with_context(|| {
+            format!(
+                "Skill mirror index {} not found — create the mirror with \
+                 `rustyclaw skills bundle`",
+                path.display()
+            )
+        })?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse skill mirror index {}", path.display()))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&MirrorSkill> {
+        self.skills.iter().find(|s| s.name == name)
+    }
+}
+
+/// Whether `part` names a single entry inside a directory, so a hostile
+/// index can't point outside the mirror.
+fn is_plain_component(part: &str) -> bool {
+    let mut components = Path::new(part).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    )
+}
+
+/// Copy the files of a skill directory, leaving out install metadata.
+fn copy_skill_files(from: &Path, to: &Path) -> Result<()> {
+    for entry in walkdir::WalkDir::new(from).min_depth(1) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(from)?;
+        if relative.starts_with(".clawhub") {
+            continue;
+        }
+        let target = to.join(relative);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// The version recorded when a skill was installed from a registry.
+fn installed_version(skill: &Skill) -> Option<String> {
+    let meta = skill.base_dir().join(".clawhub").join("install.json");
+    let meta: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(meta).ok()?).ok()?;
+    meta.get("installedVersion")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+impl SkillManager {
+    /// The local mirror search and install use, if the registry is one.
+    pub fn registry_mirror(&self) -> Option<&Path> {
+        self.registry_mirror.as_deref()
+    }
+
+    /// Search the mirror's index by name and description.
+    pub(super) fn search_mirror(&self, dir: &Path, query: &str) -> Result<Vec<RegistryEntry>> {
+        let index = MirrorIndex::load(dir)?;
+        let q_lower = query.to_lowercase();
+        Ok(index
+            .skills
+            .iter()
+            .filter(|s| {
+                s.name.to_lowercase().contains(&q_lower)
+                    || s.description.to_lowercase().contains(&q_lower)
+            })
+            .map(|s| RegistryEntry {
+                name: s.name.clone(),
+                version: resolve_version(&s.versions, None).unwrap_or_default(),
+                description: s.description.clone(),
+                display_name: String::new(),
+                author: s.author.clone(),
+                downloads: 0,
+                required_secrets: s.required_secrets.clone(),
+            })
+            .collect())
+    }
+
+    /// Copy a skill out of the mirror into `skill_dir`.  Returns the
+    /// version that was copied.
+    pub(super) fn fetch_from_mirror(
+        &self,
+        dir: &Path,
+        name: &str,
+        version: Option<&str>,
+        skill_dir: &Path,
+    ) -> Result<String> {
+        let index = MirrorIndex::load(dir)?;
+        let entry = index.get(name).ok_or_else(|| {
+            anyhow::anyhow!("Skill '{}' is not in the mirror at {}", name, dir.display())
+        })?;
+        let resolved = resolve_version(&entry.versions, version).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Version {} of '{}' is not in the mirror (available: {})",
+                version.unwrap_or("latest"),
+                name,
+                entry.versions.join(", ")
+            )
+        })?;
+        if !is_plain_component(name) || !is_plain_component(&resolved) {
+            anyhow::bail!("Invalid mirror entry {}@{}", name, resolved);
+        }
+        let source = dir.join(name).join(&resolved);
+        if !source.join("SKILL.md").is_file() {
+            anyhow::bail!(
+                "Mirror entry {}@{} has no SKILL.md at {}",
+                name,
+                resolved,
+                source.display()
+            );
+        }
+        std::fs::create_dir_all(skill_dir)?;
+        copy_skill_files(&source, skill_dir)?;
+        Ok(resolved)
+    }
+
+    /// Package the loaded SKILL.md skills into a mirror at `out`, adding to
+    /// any index already there.  Returns the names and versions bundled.
+    pub fn bundle_skills(&self, out: &Path) -> Result<Vec<(String, String)>> {
+        let mut index = if out.join(MIRROR_INDEX).exists() {
+            MirrorIndex::load(out)?
+        } else {
+            MirrorIndex::default()
+        };
+
+        let mut bundled = Vec::new();
+        for skill in &self.skills {
+            // Legacy single-file skills have no directory to ship.
+            if skill.path.file_name().is_none_or(|f| f != "SKILL.md")
+                || !is_plain_component(&skill.name)
+            {
+                continue;
+            }
+            let version = match &skill.source {
+                SkillSource::Registry { version, .. } if version != "latest" => {
+                    Some(version.clone())
+                }
+                _ => installed_version(skill).filter(|v| v != "latest"),
+            }
+            .filter(|v| is_plain_component(v))
+            .unwrap_or_else(|| DEFAULT_BUNDLE_VERSION.to_string());
+
+            let target = out.join(&skill.name).join(&version);
+            if target.exists() {
+                std::fs::remove_dir_all(&target)?;
+            }
+            copy_skill_files(skill.base_dir(), &target)
+                .with_context(|| format!("Failed to bundle skill '{}'", skill.name))?;
+
+            let entry = match index.skills.iter().position(|s| s.name == skill.name) {
+                Some(i) => &mut index.skills[i],
+                None => {
+                    index.skills.push(MirrorSkill {
+                        name: skill.name.clone(),
+                        description: String::new(),
+                        author: String::new(),
+                        required_secrets: Vec::new(),
+                        versions: Vec::new(),
+                    });
+                    index.skills.last_mut().expect("just pushed")
+                }
+            };
+            entry.description = skill.description.clone().unwrap_or_default();
+            entry.required_secrets = skill.linked_secrets.clone();
+            if !entry.versions.contains(&version) {
+                entry.versions.push(version.clone());
+            }
+            bundled.push((skill.name.clone(), version));
+        }
+
+        index.skills.sort_by(|a, b| a.name.cmp(&b.name));
+        std::fs::create_dir_all(out)?;
+        std::fs::write(
+            out.join(MIRROR_INDEX),
+            serde_json::to_string_pretty(&index)?,
+        )?;
+        Ok(bundled)
+    }
+}
@@ -142,6 +142,9 @@ pub struct Skill {
     /// whose allowed-list includes this skill's name are accessible.
     #[serde(default)]
     pub linked_secrets: Vec<String>,
+    /// Tool and path grants applied while the skill is active.
+    #[serde(default)]
+    pub permissions: SkillPermissions,
 }
 
 /// OpenClaw-compatible skill metadata
@@ -201,6 +204,8 @@ pub struct SkillManager {
     registry_url: String,
     /// ClawHub auth token (optional; needed for publish / private skills).
     registry_token: Option<String>,
+    /// Local mirror directory, when the registry is a path or `file://` URL.
+    registry_mirror: Option<PathBuf>,
 }
 
 impl SkillManager {
@@ -211,6 +216,7 @@ impl SkillManager {
             env_vars: std::env::vars().collect(),
             registry_url: DEFAULT_REGISTRY_URL.to_string(),
             registry_token: None,
+            registry_mirror: None,
         }
     }
 
@@ -222,13 +228,17 @@ impl SkillManager {
             env_vars: std::env::vars().collect(),
             registry_url: DEFAULT_REGISTRY_URL.to_string(),
             registry_token: None,
+            registry_mirror: None,
         }
     }
 
-    /// Configure the ClawHub registry URL and optional auth token.
+    /// Configure the ClawHub registry URL and optional auth token.  A
+    /// directory path or `file://` URL selects a local mirror (see
+    /// [`mirror`]) for search and install.
     pub fn set_registry(&mut self, url: &str, token: Option<String>) {
         self.registry_url = url.to_string();
         self.registry_token = token;
+        self.registry_mirror = mirror::mirror_dir(url);
     }
 
     /// Get the primary skills directory (last in the list — user's writable dir).
@@ -338,6 +348,8 @@ impl SkillManager {
             })
             .unwrap_or_default();
 
+        let permissions = SkillPermissions::from_frontmatter(&frontmatter, base_dir);
+
         Ok(Skill {
             name,
             description,
@@ -347,6 +359,7 @@ impl SkillManager {
             metadata,
             source: SkillSource::Local,
             linked_secrets,
+            permissions,
         })
     }
 
@@ -656,16 +669,21 @@ impl SkillManager {
             anyhow::bail!("Skill already exists: {name} (at {})", skill_dir.display());
         }
 
-        std::fs::create_dir_all(&skill_dir)?;
-
-        // Build frontmatter
-        let mut fm = format!("---\nname: {name}\ndescription: {description}\n");
+        // Build frontmatter with the YAML serializer, so a description
+        // can't smuggle in keys of its own (such as `allow_tools`).
+        let mut fm = serde_yaml::Mapping::new();
+        fm.insert("name".into(), name.into());
+        fm.insert("description".into(), description.into());
         if let Some(meta) = metadata_json {
-            fm.push_str(&format!("metadata: {meta}\n"));
+            let meta: serde_yaml::Value =
+                serde_json::from_str(meta).context("Skill metadata must be valid JSON")?;
+            fm.insert("metadata".into(), meta);
         }
-        fm.push_str("---\n\n");
+        let fm = serde_yaml::to_string(&fm)?;
 
-        let content = format!("{fm}{instructions}\n");
+        std::fs::create_dir_all(&skill_dir)?;
+
+        let content = format!("---\n{fm}---\n\n{instructions}\n");
         let skill_path = skill_dir.join("SKILL.md");
         std::fs::write(&skill_path, &content)?;
 
@@ -822,5 +840,11 @@ fn parse_frontmatter(content: &str) -> Result<(serde_yaml::Value, String)> {
 mod clawhub;
 pub use clawhub::*;
 
+pub mod mirror;
+pub use mirror::{MirrorIndex, MirrorSkill};
+
+mod permissions;
+pub use permissions::SkillPermissions;
+
 #[cfg(test)]
 mod tests;
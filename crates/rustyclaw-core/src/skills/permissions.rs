@@ -0,0 +1,230 @@
+//! Per-skill permission overlay.
+//!
+//! A skill's SKILL.md front-matter may carry two keys that change what the
+//! agent can do while that skill is active:
+//!
+//! ```yaml
+//! allow_tools: [execute_command]     # allowed even if chat denies them
+//! allow_paths: [scripts, ~/deploy]   # path arguments must stay inside these
+//! ```
+//!
+//! `allow_tools` widens the chat tool policy: the listed tools run without
+//! confirmation even when `[tool_permissions]` sets them to `ask` or
+//! `skill_only`.  It never lifts a `deny`, and it only counts for skills
+//! the operator lists in `trusted_skills`, by name or pinned to a SHA-256
+//! of the SKILL.md; the agent can write skills, so a grant in a file it
+//! may have written means nothing on its own.  Registry-installed skills
+//! cannot widen the policy.  Tools set to `skill_only` that name the skill
+//! are allowed.
+//!
+//! `allow_paths` narrows it: when present, path arguments of the skill's
+//! tool calls must resolve inside one of the listed paths or the skill's
+//! own directory.  Relative entries are resolved against the skill's
+//! directory.
+//!
+//! A skill becomes active when the agent reads its SKILL.md, and stays
+//! active for the rest of that chat turn.  Reading it only applies grants
+//! the operator has already trusted.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::{Component, Path, PathBuf};
+
+use super::{Skill, SkillManager, SkillSource};
+use crate::tools::{ToolPermission, expand_tilde};
+
+/// Tool arguments that name a file or directory.
+const PATH_ARGS: &[&str] = &[
+    "path",
+    "paths",
+    "file",
+    "files",
+    "working_dir",
+    "repo_path",
+    "output_path",
+    "output_dir",
+    "destination",
+];
+
+/// Tool and path grants declared in SKILL.md front-matter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillPermissions {
+    /// Tools the skill may call whatever the chat policy says.
+    #[serde(default)]
+    pub allow_tools: Vec<String>,
+    /// When non-empty, the only places (besides the skill's directory) its
+    /// tool calls may touch.
+    #[serde(default)]
+    pub allow_paths: Vec<PathBuf>,
+}
+
+impl SkillPermissions {
+    /// Read `allow_tools` and `allow_paths` from parsed front-matter.
+    pub(crate) fn from_frontmatter(frontmatter: &serde_yaml::Value, base_dir: &Path) -> Self {
+        let strings = |key: &str| -> Vec<String> {
+            frontmatter
+                .get(key)
+                .and_then(|v| v.as_sequence())
+                .map(|seq| {
+                    seq.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let allow_paths = strings("allow_paths")
+            .iter()
+            .map(|p| {
+                let p = p.replace("{baseDir}", &base_dir.display().to_string());
+                let p = expand_tilde(&p);
+                if p.is_absolute() { p } else { base_dir.join(p) }
+            })
+            .collect();
+        Self {
+            allow_tools: strings("allow_tools"),
+            allow_paths,
+        }
+    }
+}
+
+impl Skill {
+    /// The skill's directory (the parent of its SKILL.md).
+    pub fn base_dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// Whether the skill came from a ClawHub registry (its directory keeps
+    /// the install record, which survives a reload).
+    pub fn from_registry(&self) -> bool {
+        matches!(self.source, SkillSource::Registry { .. })
+            || self.base_dir().join(".clawhub").exists()
+    }
+
+    /// Whether `trusted_skills` lists this skill, by name or as
+    /// `name@sha256:<hex>` matching its SKILL.md as it is now.
+    pub fn is_trusted(&self, trusted_skills: &[String]) -> bool {
+        if self.from_registry() {
+            return false;
+        }
+        trusted_skills
+            .iter()
+            .any(|entry| match entry.split_once("@sha256:") {
+                Some((name, pinned)) => {
+                    name == self.name
+                        && self
+                            .content_digest()
+                            .is_some_and(|d| d.eq_ignore_ascii_case(pinned.trim()))
+                }
+                None => *entry == self.name,
+            })
+    }
+
+    /// Hex SHA-256 of the skill's SKILL.md.
+    pub fn content_digest(&self) -> Option<String> {
+        let bytes = std::fs::read(&self.path).ok()?;
+        Some(
+            Sha256::digest(&bytes)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+        )
+    }
+
+    /// The permission `tool` has while this skill is active, given the
+    /// permission it has in chat and the operator's `trusted_skills`.
+    pub fn tool_permission(
+        &self,
+        tool: &str,
+        chat: &ToolPermission,
+        trusted_skills: &[String],
+    ) -> ToolPermission {
+        match chat {
+            ToolPermission::Deny => ToolPermission::Deny,
+            _ if self.permissions.allow_tools.iter().any(|t| t == tool)
+                && self.is_trusted(trusted_skills) =>
+            {
+                ToolPermission::Allow
+            }
+            ToolPermission::SkillOnly(skills) if skills.contains(&self.name) => {
+                ToolPermission::Allow
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Check the path arguments of a tool call against `allow_paths`.
+    pub fn check_tool_paths(&self, args: &Value, workspace_dir: &Path) -> Result<(), String> {
+        if self.permissions.allow_paths.is_empty() {
+            return Ok(());
+        }
+        let allowed: Vec<PathBuf> = std::iter::once(self.base_dir())
+            .chain(self.permissions.allow_paths.iter().map(PathBuf::as_path))
+            .filter_map(normalize)
+            .collect();
+        for path in path_args(args) {
+            let full = expand_tilde(path);
+            let full = if full.is_absolute() {
+                full
+            } else {
+                workspace_dir.join(full)
+            };
+            let inside = normalize(&full).is_some_and(|p| allowed.iter().any(|a| p.starts_with(a)));
+            if !inside {
+                return Err(format!(
+                    "Path '{}' is outside the paths skill '{}' may use (allow_paths)",
+                    path, self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SkillManager {
+    /// The enabled skill whose SKILL.md a `read_file` call with `args` read,
+    /// which makes it the active skill.
+    pub fn skill_activated_by(
+        &self,
+        tool: &str,
+        args: &Value,
+        workspace_dir: &Path,
+    ) -> Option<&Skill> {
+        if tool != "read_file" {
+            return None;
+        }
+        let path = args.get("path").and_then(|v| v.as_str())?;
+        let read = normalize(&workspace_dir.join(expand_tilde(path)))?;
+        self.skills
+            .iter()
+            .filter(|s| s.enabled)
+            .find(|s| normalize(&s.path).is_some_and(|p| p == read))
+    }
+}
+
+fn path_args(args: &Value) -> Vec<&str> {
+    PATH_ARGS
+        .iter()
+        .filter_map(|key| args.get(*key))
+        .flat_map(|v| match v {
+            Value::String(s) => vec![s.as_str()],
+            Value::Array(items) => items.iter().filter_map(|i| i.as_str()).collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// Canonicalise `path`, or its nearest existing ancestor for paths that do
+/// not exist yet.  `None` if a `..` could not be resolved.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    if let Ok(canonical) = path.canonicalize() {
+        return Some(canonical);
+    }
+    match path.components().next_back()? {
+        Component::Normal(name) => Some(normalize(path.parent()?)?.join(name)),
+        Component::CurDir => normalize(path.parent()?),
+        _ => None,
+    }
+}
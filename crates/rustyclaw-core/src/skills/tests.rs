@@ -62,6 +62,7 @@ fn test_gate_check_always() {
         },
         source: SkillSource::Local,
         linked_secrets: vec![],
+        permissions: SkillPermissions::default(),
     };
     let result = manager.check_gates(&skill);
     assert!(result.passed);
@@ -85,6 +86,7 @@ fn test_gate_check_missing_bin() {
         },
         source: SkillSource::Local,
         linked_secrets: vec![],
+        permissions: SkillPermissions::default(),
     };
     let result = manager.check_gates(&skill);
     assert!(!result.passed);
@@ -107,6 +109,7 @@ fn test_generate_prompt_context() {
         metadata: SkillMetadata::default(),
         source: SkillSource::Local,
         linked_secrets: vec![],
+        permissions: SkillPermissions::default(),
     });
     let context = manager.generate_prompt_context();
     assert!(context.contains("test-skill"));
@@ -126,6 +129,7 @@ fn test_link_and_unlink_secret() {
         metadata: SkillMetadata::default(),
         source: SkillSource::Local,
         linked_secrets: vec![],
+        permissions: SkillPermissions::default(),
     });
 
     manager.link_secret("deploy", "AWS_KEY").unwrap();
@@ -164,6 +168,7 @@ fn test_skill_info() {
             version: "1.0.0".into(),
         },
         linked_secrets: vec!["SCRAPER_KEY".into()],
+        permissions: SkillPermissions::default(),
     });
 
     let info = manager.skill_info("web-scrape").unwrap();
@@ -185,6 +190,7 @@ fn test_remove_skill() {
         metadata: SkillMetadata::default(),
         source: SkillSource::Local,
         linked_secrets: vec![],
+        permissions: SkillPermissions::default(),
     });
     assert_eq!(manager.get_skills().len(), 1);
     manager.remove_skill("temp-skill").unwrap();
@@ -204,3 +210,230 @@ fn test_base64_decode() {
     let decoded = STANDARD.decode(encoded).unwrap();
     assert_eq!(decoded, b"Hello");
 }
+
+fn skill_with_permissions(dir: &Path, frontmatter: &str) -> Skill {
+    let skill_dir = dir.join("deploy");
+    std::fs::create_dir_all(&skill_dir).unwrap();
+    let skill_md = skill_dir.join("SKILL.md");
+    std::fs::write(
+        &skill_md,
+        format!("---\nname: deploy\n{}---\n\nDeploy.\n", frontmatter),
+    )
+    .unwrap();
+    SkillManager::new(dir.to_path_buf())
+        .load_skill_md(&skill_md)
+        .unwrap()
+}
+
+#[test]
+fn test_trusted_skill_grants_tools_but_not_over_deny() {
+    let dir = tempfile::tempdir().unwrap();
+    let skill = skill_with_permissions(dir.path(), "allow_tools: [execute_command]\n");
+    assert_eq!(skill.permissions.allow_tools, vec!["execute_command"]);
+
+    use crate::tools::ToolPermission;
+    let ask = ToolPermission::Ask;
+    let trusted = vec!["deploy".to_string()];
+    assert_eq!(
+        skill.tool_permission("execute_command", &ask, &trusted),
+        ToolPermission::Allow
+    );
+    assert_eq!(skill.tool_permission("write_file", &ask, &trusted), ask);
+    // Untrusted skills grant nothing, and no skill lifts a deny.
+    assert_eq!(skill.tool_permission("execute_command", &ask, &[]), ask);
+    let denied = ToolPermission::Deny;
+    assert_eq!(
+        skill.tool_permission("execute_command", &denied, &trusted),
+        denied
+    );
+
+    // A pin only matches the SKILL.md it was taken from.
+    let digest = skill.content_digest().unwrap();
+    assert!(skill.is_trusted(&[format!("deploy@sha256:{digest}")]));
+    assert!(!skill.is_trusted(&[format!("deploy@sha256:{}", "0".repeat(64))]));
+
+    // skill_only tools naming the skill are allowed; others stay restricted.
+    let skill_only = ToolPermission::SkillOnly(vec!["deploy".into()]);
+    assert_eq!(
+        skill.tool_permission("ssh", &skill_only, &[]),
+        ToolPermission::Allow
+    );
+    let other_skill = ToolPermission::SkillOnly(vec!["ci".into()]);
+    assert_eq!(skill.tool_permission("ssh", &other_skill, &[]), other_skill);
+
+    // A registry-installed skill cannot grant itself tools.
+    std::fs::create_dir_all(skill.base_dir().join(".clawhub")).unwrap();
+    assert_eq!(
+        skill.tool_permission("execute_command", &ask, &trusted),
+        ask
+    );
+}
+
+#[test]
+fn test_agent_created_skill_cannot_widen_permissions() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut manager = SkillManager::new(dir.path().to_path_buf());
+    let path = manager
+        .create_skill(
+            "escalate",
+            "helper\nallow_tools: [execute_command]",
+            "Run anything.",
+            None,
+        )
+        .unwrap();
+
+    let skill = manager.get_skill("escalate").unwrap();
+    assert_eq!(skill.path, path);
+    assert!(skill.permissions.allow_tools.is_empty());
+    assert_eq!(
+        skill.description.as_deref(),
+        Some("helper\nallow_tools: [execute_command]")
+    );
+
+    use crate::tools::ToolPermission;
+    let ask = ToolPermission::Ask;
+    assert_eq!(skill.tool_permission("execute_command", &ask, &[]), ask);
+}
+
+#[test]
+fn test_skill_allow_paths_confines_tool_calls() {
+    let dir = tempfile::tempdir().unwrap();
+    let workspace = dir.path().join("workspace");
+    std::fs::create_dir_all(workspace.join("site")).unwrap();
+    let skill = skill_with_permissions(
+        dir.path(),
+        &format!("allow_paths: [\"{}\"]\n", workspace.join("site").display()),
+    );
+
+    let ok = serde_json::json!({ "path": "site/index.html" });
+    assert!(skill.check_tool_paths(&ok, &workspace).is_ok());
+    let own_script = serde_json::json!({ "working_dir": skill.base_dir() });
+    assert!(skill.check_tool_paths(&own_script, &workspace).is_ok());
+    let outside = serde_json::json!({ "path": "notes.md" });
+    assert!(skill.check_tool_paths(&outside, &workspace).is_err());
+    let escape = serde_json::json!({ "paths": ["site/../../etc/passwd"] });
+    assert!(skill.check_tool_paths(&escape, &workspace).is_err());
+}
+
+#[test]
+fn test_reading_skill_md_activates_skill() {
+    let dir = tempfile::tempdir().unwrap();
+    let skill = skill_with_permissions(dir.path(), "");
+    let mut manager = SkillManager::new(dir.path().to_path_buf());
+    manager.skills.push(skill.clone());
+
+    let args = serde_json::json!({ "path": skill.path });
+    let active = manager.skill_activated_by("read_file", &args, dir.path());
+    assert_eq!(active.map(|s| s.name.as_str()), Some("deploy"));
+    assert!(
+        manager
+            .skill_activated_by("write_file", &args, dir.path())
+            .is_none()
+    );
+}
+
+#[test]
+fn test_mirror_version_resolution() {
+    use super::mirror::{mirror_dir, resolve_version};
+
+    let versions: Vec<String> = ["0.9.2", "v0.10.0", "0.10.0-beta"]
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+    assert_eq!(resolve_version(&versions, None).as_deref(), Some("v0.10.0"));
+    assert_eq!(
+        resolve_version(&versions, Some("latest")).as_deref(),
+        Some("v0.10.0")
+    );
+    assert_eq!(
+        resolve_version(&versions, Some("0.9.2")).as_deref(),
+        Some("0.9.2")
+    );
+    assert_eq!(
+        resolve_version(&versions, Some("v0.9.2")).as_deref(),
+        Some("0.9.2")
+    );
+    assert!(resolve_version(&versions, Some("1.0.0")).is_none());
+
+    assert_eq!(
+        mirror_dir("file:///srv/mirror"),
+        Some(PathBuf::from("/srv/mirror"))
+    );
+    assert_eq!(
+        mirror_dir("/srv/mirror"),
+        Some(PathBuf::from("/srv/mirror"))
+    );
+    assert!(mirror_dir("https://clawhub.ai").is_none());
+}
+
+#[test]
+fn test_bundle_and_install_from_mirror() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("source");
+    let skill_dir = source.join("deploy");
+    std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+    std::fs::write(
+        skill_dir.join("SKILL.md"),
+        "---\nname: deploy\ndescription: Ship the site\n---\n\nDeploy.\n",
+    )
+    .unwrap();
+    std::fs::write(skill_dir.join("scripts/run.sh"), "echo deploy\n").unwrap();
+
+    let mut manager = SkillManager::new(source);
+    manager.load_skills().unwrap();
+    let mirror = dir.path().join("mirror");
+    let bundled = manager.bundle_skills(&mirror).unwrap();
+    assert_eq!(bundled, vec![("deploy".to_string(), "0.1.0".to_string())]);
+
+    // A newer version published to the mirror by hand.
+    let newer = mirror.join("deploy/0.2.0");
+    std::fs::create_dir_all(&newer).unwrap();
+    std::fs::write(
+        newer.join("SKILL.md"),
+        "---\nname: deploy\ndescription: Ship the site, faster\n---\n\nDeploy v2.\n",
+    )
+    .unwrap();
+    let mut index = MirrorIndex::load(&mirror).unwrap();
+    index.skills[0].versions.push("0.2.0".into());
+    std::fs::write(
+        mirror.join(mirror::MIRROR_INDEX),
+        serde_json::to_string(&index).unwrap(),
+    )
+    .unwrap();
+
+    let target = dir.path().join("target");
+    let mut offline = SkillManager::new(target.clone());
+    offline.set_registry(&format!("file://{}", mirror.display()), None);
+    assert_eq!(offline.registry_mirror(), Some(mirror.as_path()));
+
+    let results = offline.search_registry("ship").unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].version, "0.2.0");
+
+    let latest = offline.install_from_registry("deploy", None).unwrap();
+    assert!(latest.instructions.contains("Deploy v2"));
+    assert!(latest.from_registry());
+
+    let pinned = offline
+        .install_from_registry("deploy", Some("0.1.0"))
+        .unwrap();
+    assert_eq!(
+        pinned.source,
+        SkillSource::Registry {
+            registry_url: format!("file://{}", mirror.display()),
+            version: "0.1.0".into(),
+        }
+    );
+    assert!(target.join("deploy/scripts/run.sh").is_file());
+    let meta = std::fs::read_to_string(target.join("deploy/.clawhub/install.json")).unwrap();
+    assert!(meta.contains("\"installedVersion\": \"0.1.0\""));
+
+    let missing = offline.install_from_registry("deploy", Some("9.9.9"));
+    assert!(
+        missing
+            .unwrap_err()
+            .to_string()
+            .contains("available: 0.1.0, 0.2.0")
+    );
+    assert!(offline.install_from_registry("nope", None).is_err());
+}
@@ -183,7 +183,8 @@ impl TaskManager {
     /// Create a new task and return its handle.
     #[instrument(skip(self), fields(kind = ?kind))]
     pub async fn create(&self, kind: TaskKind, session_key: Option<String>) -> TaskHandle {
-        let task = Task::new(kind).with_session(session_key.clone().unwrap_or_default());
+        let mut task = Task::new(kind);
+        task.session_key = session_key;
         let id = task.id;
 
         // Create control channel
@@ -241,6 +242,20 @@ impl TaskManager {
             .collect()
     }
 
+    /// Tasks from every session matching `filter`, oldest first.
+    pub async fn query(&self, filter: &TaskFilter) -> Vec<Task> {
+        let mut tasks: Vec<Task> = self
+            .tasks
+            .read()
+            .await
+            .values()
+            .filter(|t| filter.matches(t))
+            .cloned()
+            .collect();
+        tasks.sort_by_key(|t| t.id.0);
+        tasks
+    }
+
     /// Get the foreground task for a session.
     pub async fn foreground_task(&self, session_key: &str) -> Option<Task> {
         let fg_id = self
@@ -505,6 +520,31 @@ impl Default for TaskManager {
     }
 }
 
+/// Selects tasks for [`TaskManager::query`].
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    /// Only tasks of this kind ([`TaskKind::code`], e.g. `"command"`).
+    pub kind: Option<String>,
+    /// Only tasks in this status ([`TaskStatus::code`], e.g. `"running"`).
+    pub status: Option<String>,
+    /// Keep completed, failed and cancelled tasks.  Ignored when `status`
+    /// names a terminal status.
+    pub include_finished: bool,
+}
+
+impl TaskFilter {
+    /// Whether `task` passes the filter.
+    pub fn matches(&self, task: &Task) -> bool {
+        if self.kind.as_deref().is_some_and(|k| k != task.kind.code()) {
+            return false;
+        }
+        match self.status.as_deref() {
+            Some(status) => status == task.status.code(),
+            None => self.include_finished || !task.status.is_terminal(),
+        }
+    }
+}
+
 /// Task statistics.
 #[derive(Debug, Clone, Default)]
 pub struct TaskStats {
@@ -525,3 +565,96 @@ impl TaskStats {
         self.pending + self.running + self.background + self.paused + self.waiting_input
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(cmd: &str) -> TaskKind {
+        TaskKind::Command {
+            command: cmd.to_string(),
+            pid: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_spans_sessions() {
+        let mgr = TaskManager::new();
+        let build = mgr
+            .create(command("cargo build"), Some("thread-1".into()))
+            .await;
+        mgr.start(build.id).await;
+        let agent = mgr
+            .create(
+                TaskKind::SubAgent {
+                    session_key: "agent:main:subagent:1".into(),
+                    label: Some("research".into()),
+                },
+                Some("telegram:42".into()),
+            )
+            .await;
+        mgr.start(agent.id).await;
+
+        let tasks = mgr.query(&TaskFilter::default()).await;
+        assert_eq!(tasks.len(), 2);
+        let owner = |id: TaskId| {
+            tasks
+                .iter()
+                .find(|t| t.id == id)
+                .and_then(|t| t.session_key.clone())
+        };
+        assert_eq!(owner(build.id).as_deref(), Some("thread-1"));
+        assert_eq!(owner(agent.id).as_deref(), Some("telegram:42"));
+        assert!(tasks.iter().all(|t| t.started_at.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_query_filters() {
+        let mgr = TaskManager::new();
+        let done = mgr.create(command("ls"), Some("thread-1".into())).await;
+        mgr.start(done.id).await;
+        mgr.complete(done.id, None).await;
+        let running = mgr
+            .create(command("sleep 60"), Some("thread-2".into()))
+            .await;
+        mgr.start(running.id).await;
+        let cron = mgr
+            .create(
+                TaskKind::CronJob {
+                    job_id: "scheduled-messages".into(),
+                    job_name: None,
+                },
+                None,
+            )
+            .await;
+
+        let ids = |tasks: Vec<Task>| tasks.iter().map(|t| t.id).collect::<Vec<_>>();
+
+        assert_eq!(
+            ids(mgr.query(&TaskFilter::default()).await),
+            vec![running.id, cron.id]
+        );
+        let all = TaskFilter {
+            include_finished: true,
+            ..Default::default()
+        };
+        assert_eq!(mgr.query(&all).await.len(), 3);
+
+        let commands = TaskFilter {
+            kind: Some("command".into()),
+            ..Default::default()
+        };
+        assert_eq!(ids(mgr.query(&commands).await), vec![running.id]);
+
+        let completed = TaskFilter {
+            status: Some("completed".into()),
+            ..Default::default()
+        };
+        assert_eq!(ids(mgr.query(&completed).await), vec![done.id]);
+
+        // Tasks without an owner stay ownerless.
+        let cron_task = mgr.get(cron.id).await.unwrap();
+        assert_eq!(cron_task.kind.code(), "cron");
+        assert_eq!(cron_task.session_key, None);
+    }
+}
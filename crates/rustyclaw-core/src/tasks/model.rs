@@ -101,6 +101,20 @@ impl TaskStatus {
         }
     }
 
+    /// Stable lowercase name used on the wire and in filters.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running { .. } => "running",
+            Self::Background { .. } => "background",
+            Self::Paused { .. } => "paused",
+            Self::Completed { .. } => "completed",
+            Self::Failed { .. } => "failed",
+            Self::Cancelled => "cancelled",
+            Self::WaitingForInput { .. } => "waiting_input",
+        }
+    }
+
     /// Get status message if available.
     pub fn message(&self) -> Option<&str> {
         match self {
@@ -166,6 +180,20 @@ impl TaskKind {
         }
     }
 
+    /// Stable lowercase name used on the wire and in filters.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Command { .. } => "command",
+            Self::SubAgent { .. } => "sub_agent",
+            Self::CronJob { .. } => "cron",
+            Self::McpTool { .. } => "mcp",
+            Self::Browser { .. } => "browser",
+            Self::FileOp { .. } => "file",
+            Self::WebRequest { .. } => "web",
+            Self::Custom { .. } => "custom",
+        }
+    }
+
     /// Get a detailed description.
     pub fn description(&self) -> String {
         match self {
@@ -1,13 +1,15 @@
 //! Thread manager — manages all agent threads.
 
-use super::{AgentThread, MessageRole, ThreadEvent, ThreadId, ThreadInfo, ThreadStatus};
+use super::{
+    AgentThread, HistoryStore, MessageRole, ThreadEvent, ThreadId, ThreadInfo, ThreadStatus,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::{RwLock, broadcast};
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Configuration for thread management.
 #[derive(Debug, Clone)]
@@ -482,11 +484,7 @@ impl ThreadManager {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        let state = PersistentState {
-            threads: self.threads.values().cloned().collect(),
-            foreground_id: self.foreground_id,
-        };
-        let json = serde_json::to_string_pretty(&state)
+        let json = serde_json::to_string_pretty(&self.persistent_state())
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
         std::fs::write(path, json)
     }
@@ -496,20 +494,7 @@ impl ThreadManager {
         let json = std::fs::read_to_string(path)?;
         let state: PersistentState = serde_json::from_str(&json)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-
-        let (events_tx, _) = broadcast::channel(256);
-        let mut mgr = Self {
-            threads: HashMap::new(),
-            foreground_id: state.foreground_id,
-            events_tx,
-            config: ThreadManagerConfig::default(),
-        };
-
-        for thread in state.threads {
-            mgr.threads.insert(thread.id, thread);
-        }
-
-        Ok(mgr)
+        Ok(Self::from_persistent_state(state))
     }
 
     /// Load from file or create with default chat thread.
@@ -534,6 +519,59 @@ impl ThreadManager {
         }
     }
 
+    /// Load from a history store, or create with a default chat thread
+    /// when it holds nothing or can't be read.
+    pub fn load_or_default_from(store: &dyn HistoryStore) -> Self {
+        match store.load() {
+            Ok(Some(mgr)) => {
+                debug!(
+                    store = store.name(),
+                    threads = mgr.threads.len(),
+                    "Loaded threads"
+                );
+                mgr
+            }
+            Ok(None) => {
+                let mut mgr = Self::new();
+                mgr.create_chat("Main");
+                if let Err(e) = store.save(&mgr) {
+                    debug!(store = store.name(), error = %e, "Failed to save default threads");
+                }
+                mgr
+            }
+            Err(e) => {
+                // Don't overwrite history that exists but failed to load.
+                warn!(store = store.name(), error = %e, "Failed to load threads");
+                let mut mgr = Self::new();
+                mgr.create_chat("Main");
+                mgr
+            }
+        }
+    }
+
+    pub(super) fn persistent_state(&self) -> PersistentState {
+        PersistentState {
+            threads: self.threads.values().cloned().collect(),
+            foreground_id: self.foreground_id,
+        }
+    }
+
+    pub(super) fn from_persistent_state(state: PersistentState) -> Self {
+        let (events_tx, _) = broadcast::channel(256);
+        let mut mgr = Self {
+            threads: HashMap::new(),
+            foreground_id: state.foreground_id,
+            events_tx,
+            config: ThreadManagerConfig::default(),
+        };
+
+        for thread in state.threads {
+            mgr.threads.insert(thread.id, thread);
+        }
+
+        mgr
+    }
+
     // ── Backwards Compatibility ─────────────────────────────────────────────
     // These methods match the old tasks::ThreadManager API for easier migration.
 
@@ -578,9 +616,9 @@ impl Default for ThreadManager {
 
 /// State for persistence.
 #[derive(Debug, Serialize, Deserialize)]
-struct PersistentState {
-    threads: Vec<AgentThread>,
-    foreground_id: Option<ThreadId>,
+pub(super) struct PersistentState {
+    pub(super) threads: Vec<AgentThread>,
+    pub(super) foreground_id: Option<ThreadId>,
 }
 
 /// Shared thread manager type.
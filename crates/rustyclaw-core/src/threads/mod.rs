@@ -16,15 +16,22 @@
 mod events;
 mod manager;
 mod model;
+mod store;
 pub mod subtask;
+mod transcript;
 
 pub use events::*;
 pub use manager::*;
 pub use model::*;
+pub use store::{
+    FileHistoryStore, HistoryBackendKind, HistoryStore, NullHistoryStore, SharedHistoryStore,
+    SqliteHistoryStore, open_history_store,
+};
 pub use subtask::{
     SpawnOptions, SubtaskHandle, SubtaskRegistry, SubtaskResult, spawn_background, spawn_subagent,
     spawn_task,
 };
+pub use transcript::{MAX_TOOL_OUTPUT_CHARS, TranscriptFormat, render_transcript};
 
 // Backwards compatibility: TaskId is now ThreadId
 pub type TaskId = ThreadId;
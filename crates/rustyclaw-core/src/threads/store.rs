@@ -0,0 +1,397 @@
+//! Where thread history is kept between gateway runs.
+//!
+//! The gateway loads its threads through a [`HistoryStore`] when a client
+//! connects and writes them back through it after each turn.  The backend
+//! is chosen with `history_backend` in the config:
+//!
+//! - `"file"` (default): `threads.json` in the sessions directory.
+//! - `"sqlite"`: `history.sqlite3` in the sessions directory, with one row
+//!   per message, so history can be searched and analysed across threads
+//!   with ordinary SQL.
+//! - `"none"`: nothing is written to disk.  History lasts as long as the
+//!   connection, and a new connection starts with an empty "Main" thread.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+
+use super::manager::PersistentState;
+use super::{AgentThread, MessageRole, ThreadId, ThreadManager, ThreadMessage};
+
+/// Which backend keeps thread history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryBackendKind {
+    /// Pretty-printed JSON file, `threads.json`.
+    #[default]
+    File,
+    /// SQLite database, `history.sqlite3`.
+    Sqlite,
+    /// Keep history in memory only.
+    None,
+}
+
+impl std::fmt::Display for HistoryBackendKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::File => "file",
+            Self::Sqlite => "sqlite",
+            Self::None => "none",
+        })
+    }
+}
+
+/// Persistent storage for a [`ThreadManager`]'s threads.
+///
+/// Every save replaces what was stored before, so a store always holds the
+/// state of the last save.
+pub trait HistoryStore: Send + Sync {
+    /// Short name for logs and error messages.
+    fn name(&self) -> &'static str;
+
+    /// Read the stored threads, or `None` if nothing has been stored yet.
+    fn load(&self) -> Result<Option<ThreadManager>>;
+
+    /// Store the threads of `mgr`.
+    fn save(&self, mgr: &ThreadManager) -> Result<()>;
+}
+
+/// Shared handle to the configured history store.
+pub type SharedHistoryStore = Arc<dyn HistoryStore>;
+
+/// Open the `kind` store kept in `sessions_dir`.
+pub fn open_history_store(
+    kind: HistoryBackendKind,
+    sessions_dir: &Path,
+) -> Result<SharedHistoryStore> {
+    Ok(match kind {
+        HistoryBackendKind::File => {
+            Arc::new(FileHistoryStore::new(sessions_dir.join("threads.json")))
+        }
+        HistoryBackendKind::Sqlite => Arc::new(SqliteHistoryStore::open(
+            &sessions_dir.join("history.sqlite3"),
+        )?),
+        HistoryBackendKind::None => Arc::new(NullHistoryStore),
+    })
+}
+
+// ── JSON file ───────────────────────────────────────────────────────────────
+
+/// Threads in one JSON file, as written by [`ThreadManager::save_to_file`].
+pub struct FileHistoryStore {
+    path: PathBuf,
+}
+
+impl FileHistoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl HistoryStore for FileHistoryStore {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn load(&self) -> Result<Option<ThreadManager>> {
+        match ThreadManager::load_from_file(&self.path) {
+            Ok(mgr) => Ok(Some(mgr)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", self.path.display())),
+        }
+    }
+
+    fn save(&self, mgr: &ThreadManager) -> Result<()> {
+        mgr.save_to_file(&self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+// ── SQLite ──────────────────────────────────────────────────────────────────
+
+/// Threads in a SQLite database.
+///
+/// `threads` holds each thread's metadata as JSON next to its id and label;
+/// `messages` holds one row per message with its role, text and time (Unix
+/// milliseconds), in order within the thread.  `meta` records which thread
+/// was in the foreground.
+pub struct SqliteHistoryStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteHistoryStore {
+    /// Open (or create) the database at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let conn =
+            Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        Self::init(&conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn init(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS threads (
+                id INTEGER PRIMARY KEY,
+                label TEXT NOT NULL,
+                data TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS messages (
+                thread_id INTEGER NOT NULL,
+                seq INTEGER NOT NULL,
+                role TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                tool_calls TEXT,
+                tool_call_id TEXT,
+                PRIMARY KEY (thread_id, seq)
+            );
+            CREATE INDEX IF NOT EXISTS messages_by_time ON messages(created_at);
+
+            CREATE TABLE IF NOT EXISTS meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+            "#,
+        )
+        .context("Failed to create history tables")
+    }
+}
+
+impl HistoryStore for SqliteHistoryStore {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn load(&self) -> Result<Option<ThreadManager>> {
+        let conn = self.conn.lock().unwrap();
+        let foreground: Option<String> = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'foreground_id'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(foreground) = foreground else {
+            // Written with every save, so an empty database has none.
+            return Ok(None);
+        };
+
+        let mut threads = Vec::new();
+        let mut select_threads = conn.prepare("SELECT data FROM threads ORDER BY id")?;
+        let mut select_messages = conn.prepare(
+            "SELECT role, content, created_at, tool_calls, tool_call_id
+                FROM messages WHERE thread_id = ?1 ORDER BY seq",
+        )?;
+        for data in select_threads.query_map([], |row| row.get::<_, String>(0))? {
+            let mut thread: AgentThread =
+                serde_json::from_str(&data?).context("Corrupt thread in history database")?;
+            let rows = select_messages.query_map(params![thread.id.0 as i64], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            })?;
+            for row in rows {
+                let (role, content, created_at, tool_calls, tool_call_id) = row?;
+                thread.messages.push_back(ThreadMessage {
+                    role: parse_role(&role)
+                        .with_context(|| format!("Unknown message role '{}'", role))?,
+                    content,
+                    timestamp: UNIX_EPOCH + Duration::from_millis(created_at.max(0) as u64),
+                    tool_calls: tool_calls
+                        .map(|json| serde_json::from_str(&json))
+                        .transpose()
+                        .context("Corrupt tool calls in history database")?,
+                    tool_call_id,
+                });
+            }
+            threads.push(thread);
+        }
+
+        let foreground_id = foreground.parse().ok().map(ThreadId);
+        Ok(Some(ThreadManager::from_persistent_state(
+            PersistentState {
+                threads,
+                foreground_id,
+            },
+        )))
+    }
+
+    fn save(&self, mgr: &ThreadManager) -> Result<()> {
+        let state = mgr.persistent_state();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute_batch("DELETE FROM messages; DELETE FROM threads;")?;
+        {
+            let mut insert_thread =
+                tx.prepare("INSERT INTO threads (id, label, data) VALUES (?1, ?2, ?3)")?;
+            let mut insert_message = tx.prepare(
+                "INSERT INTO messages
+                    (thread_id, seq, role, content, created_at, tool_calls, tool_call_id)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for mut thread in state.threads {
+                let id = thread.id.0 as i64;
+                let messages = std::mem::take(&mut thread.messages);
+                let data = serde_json::to_string(&thread)?;
+                insert_thread.execute(params![id, &thread.label, data])?;
+                for (seq, message) in messages.iter().enumerate() {
+                    insert_message.execute(params![
+                        id,
+                        seq as i64,
+                        role_name(&message.role),
+                        &message.content,
+                        unix_millis(message.timestamp),
+                        message.tool_calls.as_ref().map(|v| v.to_string()),
+                        &message.tool_call_id,
+                    ])?;
+                }
+            }
+        }
+        let foreground = state
+            .foreground_id
+            .map(|id| id.0.to_string())
+            .unwrap_or_default();
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('foreground_id', ?1)",
+            params![foreground],
+        )?;
+        tx.commit().context("Failed to save history")
+    }
+}
+
+fn role_name(role: &MessageRole) -> &'static str {
+    match role {
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::System => "system",
+        MessageRole::Tool => "tool",
+    }
+}
+
+fn parse_role(name: &str) -> Option<MessageRole> {
+    Some(match name {
+        "user" => MessageRole::User,
+        "assistant" => MessageRole::Assistant,
+        "system" => MessageRole::System,
+        "tool" => MessageRole::Tool,
+        _ => return None,
+    })
+}
+
+fn unix_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+// ── No persistence ──────────────────────────────────────────────────────────
+
+/// Keeps nothing: loads find no history and saves are dropped.
+pub struct NullHistoryStore;
+
+impl HistoryStore for NullHistoryStore {
+    fn name(&self) -> &'static str {
+        "none"
+    }
+
+    fn load(&self) -> Result<Option<ThreadManager>> {
+        Ok(None)
+    }
+
+    fn save(&self, _mgr: &ThreadManager) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_threads() -> ThreadManager {
+        let mut mgr = ThreadManager::new();
+        let main = mgr.create_chat("Main");
+        mgr.add_message(main, MessageRole::User, "What's in the logs?");
+        let thread = mgr.get_mut(main).unwrap();
+        thread.add_assistant_with_tool_calls(
+            "Let me look.",
+            serde_json::json!([{"id": "call_1", "name": "read_file", "arguments": {}}]),
+        );
+        thread.add_tool_result("call_1", "ERROR disk full");
+        mgr.add_message(main, MessageRole::Assistant, "The disk is full.");
+        let other = mgr.create_chat("Side quest");
+        mgr.add_message(other, MessageRole::User, "Unrelated");
+        mgr.switch_foreground(main);
+        mgr
+    }
+
+    #[test]
+    fn test_null_store_persists_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = dir.path().join("sessions");
+        let store = open_history_store(HistoryBackendKind::None, &sessions).unwrap();
+
+        let mgr = ThreadManager::load_or_default_from(store.as_ref());
+        assert_eq!(mgr.list().len(), 1);
+        store.save(&sample_threads()).unwrap();
+
+        assert!(store.load().unwrap().is_none());
+        assert!(!sessions.exists(), "nothing may be written to disk");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_sqlite_store_round_trips_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = open_history_store(HistoryBackendKind::Sqlite, dir.path()).unwrap();
+        assert!(store.load().unwrap().is_none());
+
+        let mgr = sample_threads();
+        store.save(&mgr).unwrap();
+        // A second save replaces the first rather than adding to it.
+        store.save(&mgr).unwrap();
+        drop(store);
+
+        let store = SqliteHistoryStore::open(&dir.path().join("history.sqlite3")).unwrap();
+        let loaded = store.load().unwrap().unwrap();
+        assert_eq!(loaded.list().len(), 2);
+        assert_eq!(loaded.foreground_id(), mgr.foreground_id());
+
+        let original = mgr.foreground().unwrap();
+        let restored = loaded.foreground().unwrap();
+        assert_eq!(restored.label, "Main");
+        assert_eq!(restored.messages.len(), original.messages.len());
+        for (a, b) in original.messages.iter().zip(&restored.messages) {
+            assert_eq!(a.role, b.role);
+            assert_eq!(a.content, b.content);
+            assert_eq!(a.tool_calls, b.tool_calls);
+            assert_eq!(a.tool_call_id, b.tool_call_id);
+            assert_eq!(unix_millis(a.timestamp), unix_millis(b.timestamp));
+        }
+
+        let conn = store.conn.lock().unwrap();
+        let hits: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM messages WHERE content LIKE '%disk%'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(hits, 2);
+    }
+}
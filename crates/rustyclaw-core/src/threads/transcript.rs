@@ -0,0 +1,278 @@
+//! Transcript export for a thread's conversation.
+//!
+//! Renders a thread as Markdown (for reading and sharing) or JSON (for
+//! archival and re-import).  Exports leave the machine, so credentials are
+//! redacted from every message and tool call, and tool outputs longer than
+//! [`MAX_TOOL_OUTPUT_CHARS`] are cut short with a note saying how much was
+//! left out.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+
+use super::{AgentThread, MessageRole, ThreadMessage};
+use crate::providers::redact_credentials;
+
+/// Characters of a single tool output kept in an export.
+pub const MAX_TOOL_OUTPUT_CHARS: usize = 8_000;
+
+/// Output format of an exported transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    Markdown,
+    Json,
+}
+
+impl TranscriptFormat {
+    /// Parse a format name as sent by clients ("markdown"/"md" or "json").
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Render `thread` as a transcript in `format`.
+pub fn render_transcript(thread: &AgentThread, format: TranscriptFormat) -> String {
+    match format {
+        TranscriptFormat::Markdown => render_markdown(thread),
+        TranscriptFormat::Json => render_json(thread),
+    }
+}
+
+fn role_name(role: &MessageRole) -> &'static str {
+    match role {
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::System => "system",
+        MessageRole::Tool => "tool",
+    }
+}
+
+fn timestamp(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Redacted message text, with tool outputs truncated.  The flag says
+/// whether anything was cut.
+fn message_text(message: &ThreadMessage) -> (String, bool) {
+    let text = redact_credentials(&message.content, None);
+    if message.role != MessageRole::Tool {
+        return (text, false);
+    }
+    let total = text.chars().count();
+    if total <= MAX_TOOL_OUTPUT_CHARS {
+        return (text, false);
+    }
+    let mut kept: String = text.chars().take(MAX_TOOL_OUTPUT_CHARS).collect();
+    kept.push_str(&format!(
+        "\n[… {} more characters of tool output omitted from this export]",
+        total - MAX_TOOL_OUTPUT_CHARS
+    ));
+    (kept, true)
+}
+
+/// Redact credentials from every string inside a JSON value.
+fn redact_value(value: &Value) -> Value {
+    match value {
+        Value::String(s) => Value::String(redact_credentials(s, None)),
+        Value::Array(items) => Value::Array(items.iter().map(redact_value).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), redact_value(v)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// A code fence longer than any run of backticks in `text`.
+fn fence(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn render_markdown(thread: &AgentThread) -> String {
+    let mut out = format!("# {}\n\n", thread.label);
+    out.push_str(&format!(
+        "_Session {} · started {} · exported {}_\n",
+        thread.id.0,
+        timestamp(thread.created_at),
+        timestamp(SystemTime::now())
+    ));
+    if let Some(summary) = &thread.compact_summary {
+        out.push_str("\n## Earlier conversation (summary)\n\n");
+        out.push_str(redact_credentials(summary, None).trim_end());
+        out.push('\n');
+    }
+
+    for message in &thread.messages {
+        let (text, _) = message_text(message);
+        let heading = match message.role {
+            MessageRole::User => "User".to_string(),
+            MessageRole::Assistant => "Assistant".to_string(),
+            MessageRole::System => "System".to_string(),
+            MessageRole::Tool => match &message.tool_call_id {
+                Some(id) => format!("Tool result (`{}`)", id),
+                None => "Tool result".to_string(),
+            },
+        };
+        out.push_str(&format!(
+            "\n## {} — {}\n\n",
+            heading,
+            timestamp(message.timestamp)
+        ));
+
+        if message.role == MessageRole::Tool {
+            let fence = fence(&text);
+            out.push_str(&format!("{}\n{}\n{}\n", fence, text.trim_end(), fence));
+        } else if !text.trim().is_empty() {
+            out.push_str(text.trim_end());
+            out.push('\n');
+        }
+
+        let calls = message.tool_calls.as_ref().and_then(Value::as_array);
+        for call in calls.into_iter().flatten() {
+            let name = call.get("name").and_then(Value::as_str).unwrap_or("tool");
+            let arguments = match call.get("arguments").map(redact_value) {
+                Some(Value::String(s)) => s,
+                Some(v) => serde_json::to_string_pretty(&v).unwrap_or_default(),
+                None => String::new(),
+            };
+            let fence = fence(&arguments);
+            out.push_str(&format!(
+                "\n**Tool call** `{}`\n\n{}json\n{}\n{}\n",
+                name, fence, arguments, fence
+            ));
+        }
+    }
+    out
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn render_json(thread: &AgentThread) -> String {
+    let messages: Vec<Value> = thread
+        .messages
+        .iter()
+        .map(|m| {
+            let (content, truncated) = message_text(m);
+            let mut entry = json!({
+                "role": role_name(&m.role),
+                "content": content,
+                "timestamp": unix_secs(m.timestamp),
+            });
+            if let Some(calls) = &m.tool_calls {
+                entry["tool_calls"] = redact_value(calls);
+            }
+            if let Some(id) = &m.tool_call_id {
+                entry["tool_call_id"] = json!(id);
+            }
+            if truncated {
+                entry["truncated"] = json!(true);
+            }
+            entry
+        })
+        .collect();
+
+    let transcript = json!({
+        "session_id": thread.id.0,
+        "label": thread.label,
+        "created_at": unix_secs(thread.created_at),
+        "exported_at": unix_secs(SystemTime::now()),
+        "compact_summary": thread
+            .compact_summary
+            .as_deref()
+            .map(|s| redact_credentials(s, None)),
+        "messages": messages,
+    });
+    serde_json::to_string_pretty(&transcript).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::threads::ThreadManager;
+
+    fn thread_with(messages: &[(MessageRole, &str)]) -> AgentThread {
+        let mut mgr = ThreadManager::new();
+        let id = mgr.create_chat("Deploy");
+        for (role, content) in messages {
+            mgr.add_message(id, role.clone(), *content);
+        }
+        mgr.get(id).unwrap().clone()
+    }
+
+    #[test]
+    fn test_format_parse() {
+        assert_eq!(
+            TranscriptFormat::parse("Markdown"),
+            Some(TranscriptFormat::Markdown)
+        );
+        assert_eq!(
+            TranscriptFormat::parse("md"),
+            Some(TranscriptFormat::Markdown)
+        );
+        assert_eq!(
+            TranscriptFormat::parse("json"),
+            Some(TranscriptFormat::Json)
+        );
+        assert_eq!(TranscriptFormat::parse("html"), None);
+    }
+
+    #[test]
+    fn test_transcript_redacts_secrets() {
+        let key = "sk-proj-abcdefghijklmnopqrstuvwxyz0123";
+        let mut thread = thread_with(&[
+            (MessageRole::User, format!("my key is {}", key).as_str()),
+            (MessageRole::Assistant, "Got it."),
+        ]);
+        thread.messages[1].tool_calls = Some(json!([{
+            "id": "call_1",
+            "name": "execute_command",
+            "arguments": { "command": format!("curl -H 'x-api-key: {}'", key) },
+        }]));
+
+        for format in [TranscriptFormat::Markdown, TranscriptFormat::Json] {
+            let out = render_transcript(&thread, format);
+            assert!(!out.contains(key), "{:?} export leaked the key", format);
+            assert!(out.contains("my key is"));
+            assert!(out.contains("execute_command"));
+        }
+    }
+
+    #[test]
+    fn test_transcript_truncates_long_tool_output() {
+        let output = "x".repeat(MAX_TOOL_OUTPUT_CHARS + 500);
+        let thread = thread_with(&[
+            (MessageRole::User, "list it"),
+            (MessageRole::Tool, output.as_str()),
+        ]);
+
+        let markdown = render_transcript(&thread, TranscriptFormat::Markdown);
+        assert!(markdown.contains("500 more characters of tool output omitted"));
+        assert!(!markdown.contains(&output));
+
+        let json: Value =
+            serde_json::from_str(&render_transcript(&thread, TranscriptFormat::Json)).unwrap();
+        let tool = &json["messages"][1];
+        assert_eq!(tool["role"], "tool");
+        assert_eq!(tool["truncated"], true);
+        assert_eq!(json["messages"][0]["content"], "list it");
+        assert!(json["messages"][0].get("truncated").is_none());
+    }
+}
@@ -0,0 +1,140 @@
+//! What the user sees before approving a tool set to `Ask`.
+//!
+//! The gateway sends an [`ApprovalContext`] with every tool approval
+//! request: the tool's [`tool_summary`](super::tool_summary), its
+//! arguments with credentials masked, and the [`ToolRisk`]s that make the
+//! call worth a second look.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::providers::{redact_record, redact_secret};
+
+/// Something a tool can do that the user should know about before
+/// allowing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolRisk {
+    WritesFiles,
+    DeletesFiles,
+    RunsCommands,
+    Network,
+    Secrets,
+    ControlsProcesses,
+    SendsMessages,
+    ReadsScreen,
+}
+
+impl ToolRisk {
+    /// Short hint shown in the approval prompt.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::WritesFiles => "writes to disk",
+            Self::DeletesFiles => "deletes files",
+            Self::RunsCommands => "runs a command",
+            Self::Network => "network access",
+            Self::Secrets => "reads or changes secrets",
+            Self::ControlsProcesses => "stops or starts processes",
+            Self::SendsMessages => "sends messages",
+            Self::ReadsScreen => "reads the screen or clipboard",
+        }
+    }
+}
+
+impl std::fmt::Display for ToolRisk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// The risks of calling `name`, most serious first.  Read-only tools have
+/// none.
+pub fn tool_risks(name: &str) -> Vec<ToolRisk> {
+    use ToolRisk::*;
+    let risks: &[ToolRisk] = match name {
+        "execute_command" | "process" => &[RunsCommands, ControlsProcesses],
+        "docker" => &[RunsCommands, ControlsProcesses, Network],
+        "ssh" => &[RunsCommands, Network],
+        "git" => &[RunsCommands, WritesFiles, Network],
+        "lint" => &[RunsCommands, WritesFiles],
+        "npm_manage" | "uv_manage" | "ollama_manage" | "exo_manage" | "agent_setup" => {
+            &[RunsCommands, WritesFiles, Network]
+        }
+        "ast_grep_manage" => &[RunsCommands, WritesFiles],
+        "secure_delete" | "browser_cache" => &[DeletesFiles],
+        "write_file"
+        | "edit_file"
+        | "move_file"
+        | "copy_file"
+        | "apply_patch"
+        | "pdf_create"
+        | "convert_document"
+        | "image_transform"
+        | "screenshot_annotate"
+        | "translate_file"
+        | "qr"
+        | "save_memory"
+        | "add_memory"
+        | "skill_create"
+        | "cron" => &[WritesFiles],
+        "skill_install" | "image_generate" => &[WritesFiles, Network],
+        "web_fetch"
+        | "web_search"
+        | "web_extract"
+        | "extract_links"
+        | "extract_structured"
+        | "summarize_url"
+        | "network_request_replay"
+        | "http_server"
+        | "weather"
+        | "transcribe"
+        | "dns"
+        | "skill_search"
+        | "mcp_connect" => &[Network],
+        "browser" => &[Network, ReadsScreen],
+        "secrets_list" | "secrets_get" | "secrets_store" | "secrets_set_policy"
+        | "secrets_generate" | "skill_link_secret" => &[Secrets],
+        "process_tree" | "service_start" | "service_stop" | "service_restart" | "gateway"
+        | "task_cancel" | "swarm_stop" => &[ControlsProcesses],
+        "message" | "sessions_send" => &[SendsMessages, Network],
+        "screenshot" | "ocr_screen" | "clipboard" => &[ReadsScreen],
+        _ => &[],
+    };
+    risks.to_vec()
+}
+
+/// Everything an approval prompt shows about one tool call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalContext {
+    /// What the tool lets the agent do, from `tool_summary`.
+    pub summary: String,
+    /// The call's arguments as pretty-printed JSON, credentials masked.
+    pub arguments: String,
+    pub risks: Vec<ToolRisk>,
+}
+
+impl ApprovalContext {
+    pub fn new(name: &str, arguments: &Value) -> Self {
+        Self {
+            summary: super::tool_summary(name).to_string(),
+            arguments: redact_arguments(name, arguments),
+            risks: tool_risks(name),
+        }
+    }
+}
+
+/// Mask credential-named keys and secret-shaped strings.  A value handed
+/// to a vault tool is a secret whatever it looks like.
+fn redact_arguments(name: &str, arguments: &Value) -> String {
+    let mut arguments = arguments.clone();
+    if super::is_secrets_tool(name)
+        && let Some(Value::String(value)) = arguments.get_mut("value")
+    {
+        *value = redact_secret(value);
+    }
+    let line = redact_record(arguments, None);
+    serde_json::from_str::<Value>(&line)
+        .ok()
+        .and_then(|v| serde_json::to_string_pretty(&v).ok())
+        .unwrap_or(line)
+}
@@ -0,0 +1,393 @@
+//! `extract_archive_member` tool: list an archive or read one member of it
+//! without extracting anything to disk.
+//!
+//! Supports zip and tar, the latter plain, gzip- or zstd-compressed; the
+//! format is sniffed from the file's first bytes, not its extension.  Zip
+//! members are found through the central directory and decompressed alone.
+//! Tar has no index, so the stream is read until the member turns up and
+//! then dropped — entries before it are skipped over, never kept.
+//!
+//! Member names are matched after normalization (`./a/b` is `a/b`), and
+//! names that are absolute or climb out with `..` are never read.  At most
+//! `max_bytes` of a member are decompressed, which also defuses archive
+//! bombs.
+
+use base64::Engine;
+use serde_json::{Value, json};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+
+/// Bytes of a member returned when the call doesn't say.
+const DEFAULT_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Upper bound on bytes read from a member regardless of the request.
+const MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Members listed when the call doesn't say.
+const DEFAULT_LIST_LIMIT: usize = 500;
+
+/// Upper bound on listed members.
+const MAX_LIST_LIMIT: usize = 5000;
+
+/// Container formats this tool reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    TarZst,
+}
+
+impl ArchiveFormat {
+    fn name(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::Tar => "tar",
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::TarZst => "tar.zst",
+        }
+    }
+
+    /// Identify the format from the file's leading bytes.
+    pub(crate) fn sniff(path: &Path) -> Result<Self, String> {
+        let mut head = [0u8; 512];
+        let mut file =
+            File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+        let mut len = 0;
+        while len < head.len() {
+            match file.read(&mut head[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) => return Err(format!("Cannot read '{}': {}", path.display(), e)),
+            }
+        }
+        let head = &head[..len];
+        if head.starts_with(b"PK\x03\x04") || head.starts_with(b"PK\x05\x06") {
+            Ok(ArchiveFormat::Zip)
+        } else if head.starts_with(&[0x1f, 0x8b]) {
+            Ok(ArchiveFormat::TarGz)
+        } else if head.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Ok(ArchiveFormat::TarZst)
+        } else if head.len() >= 262 && &head[257..262] == b"ustar" {
+            Ok(ArchiveFormat::Tar)
+        } else {
+            Err(format!(
+                "'{}' is not a zip, tar, tar.gz or tar.zst archive",
+                path.display()
+            ))
+        }
+    }
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `extract_archive_member` tool.
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_extract_archive_member(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let path_str = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: path".to_string())?;
+    let path = checked_path(workspace_dir, path_str)?;
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    let format = ArchiveFormat::sniff(&path)?;
+
+    let Some(member) = args.get("member").and_then(|v| v.as_str()) else {
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_LIST_LIMIT)
+            .clamp(1, MAX_LIST_LIMIT);
+        let (members, truncated) = list_members(&path, format, limit)?;
+        return Ok(json!({
+            "archive": path.display().to_string(),
+            "format": format.name(),
+            "members": members,
+            "truncated": truncated,
+        })
+        .to_string());
+    };
+
+    let wanted = normalize_member(member).ok_or_else(|| {
+        format!(
+            "Refusing member name '{}': it must be a relative path without '..'",
+            member
+        )
+    })?;
+    let max_bytes = args
+        .get("max_bytes")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_MAX_BYTES)
+        .clamp(1, MAX_BYTES);
+    let encoding = args
+        .get("encoding")
+        .and_then(|v| v.as_str())
+        .unwrap_or("auto");
+    if !matches!(encoding, "auto" | "text" | "base64") {
+        return Err(format!(
+            "Unknown encoding: '{}'. Use: auto, text, base64",
+            encoding
+        ));
+    }
+    debug!(
+        archive = %path.display(),
+        member = %wanted,
+        format = format.name(),
+        "Reading archive member"
+    );
+
+    let read = read_member(&path, format, &wanted, max_bytes)?;
+    let truncated = read.size > read.bytes.len() as u64;
+    let (encoding, content) = match (encoding, std::str::from_utf8(&read.bytes)) {
+        ("base64", _) => ("base64", base64_encode(&read.bytes)),
+        (_, Ok(text)) if !text.contains('\0') => ("text", text.to_string()),
+        // A cut can split a multi-byte character; keep the valid prefix.
+        (_, Err(e)) if truncated && e.error_len().is_none() && e.valid_up_to() > 0 => (
+            "text",
+            String::from_utf8_lossy(&read.bytes[..e.valid_up_to()]).into_owned(),
+        ),
+        ("text", _) => {
+            return Err(format!(
+                "Member '{}' is not UTF-8 text; use encoding='base64'",
+                wanted
+            ));
+        }
+        _ => ("base64", base64_encode(&read.bytes)),
+    };
+
+    Ok(json!({
+        "archive": path.display().to_string(),
+        "format": format.name(),
+        "member": wanted,
+        "size": read.size,
+        "bytes_read": read.bytes.len(),
+        "truncated": truncated,
+        "encoding": encoding,
+        "content": content,
+    })
+    .to_string())
+}
+
+fn checked_path(workspace_dir: &Path, p: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, p);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted archive read of protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// `name` as a clean relative path (`./a//b/` → `a/b`), or `None` when it
+/// is absolute, has a drive prefix, or contains a `..` component.
+pub(crate) fn normalize_member(name: &str) -> Option<String> {
+    let name = name.replace('\\', "/");
+    if name.starts_with('/') || name.split('/').next().is_some_and(|c| c.ends_with(':')) {
+        return None;
+    }
+    let mut parts = Vec::new();
+    for part in name.split('/') {
+        match part {
+            "" | "." => continue,
+            ".." => return None,
+            part => parts.push(part),
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+// ── Listing ─────────────────────────────────────────────────────────────────
+
+/// Up to `limit` members with their sizes, and whether more were left out.
+fn list_members(
+    path: &Path,
+    format: ArchiveFormat,
+    limit: usize,
+) -> Result<(Vec<Value>, bool), String> {
+    let describe = |name: &str, size: u64, is_dir: bool| {
+        let mut entry = json!({ "name": name, "size": size, "is_dir": is_dir });
+        if normalize_member(name).is_none() {
+            entry["unsafe_name"] = json!(true);
+        }
+        entry
+    };
+
+    let mut members = Vec::new();
+    if format == ArchiveFormat::Zip {
+        let mut zip = open_zip(path)?;
+        for i in 0..zip.len().min(limit) {
+            // Raw access reads the directory record without decompressing.
+            let file = zip.by_index_raw(i).map_err(|e| zip_error(path, e))?;
+            members.push(describe(file.name(), file.size(), file.is_dir()));
+        }
+        return Ok((members, zip.len() > limit));
+    }
+
+    let mut tar = open_tar(path, format)?;
+    let entries = tar.entries().map_err(|e| tar_error(path, e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| tar_error(path, e))?;
+        if members.len() == limit {
+            return Ok((members, true));
+        }
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+        members.push(describe(
+            &name,
+            entry.size(),
+            entry.header().entry_type().is_dir(),
+        ));
+    }
+    Ok((members, false))
+}
+
+// ── Reading ─────────────────────────────────────────────────────────────────
+
+/// The first bytes of a member and its full uncompressed size.
+pub(crate) struct MemberRead {
+    pub size: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// Read at most `max_bytes` of the member named `wanted` (already
+/// normalized).
+pub(crate) fn read_member(
+    path: &Path,
+    format: ArchiveFormat,
+    wanted: &str,
+    max_bytes: u64,
+) -> Result<MemberRead, String> {
+    let not_found = || format!("No member '{}' in {}", wanted, path.display());
+
+    if format == ArchiveFormat::Zip {
+        let mut zip = open_zip(path)?;
+        let index = (0..zip.len())
+            .find(|&i| {
+                zip.name_for_index(i)
+                    .and_then(normalize_member)
+                    .is_some_and(|name| name == wanted)
+            })
+            .ok_or_else(not_found)?;
+        let file = zip.by_index(index).map_err(|e| zip_error(path, e))?;
+        if file.is_dir() {
+            return Err(format!("'{}' is a directory", wanted));
+        }
+        let size = file.size();
+        let bytes = read_capped(file, max_bytes, wanted)?;
+        return Ok(MemberRead { size, bytes });
+    }
+
+    let mut tar = open_tar(path, format)?;
+    let entries = tar.entries().map_err(|e| tar_error(path, e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| tar_error(path, e))?;
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+        if normalize_member(&name).as_deref() != Some(wanted) {
+            continue;
+        }
+        let kind = entry.header().entry_type();
+        if kind.is_dir() {
+            return Err(format!("'{}' is a directory", wanted));
+        }
+        if !kind.is_file() {
+            return Err(format!(
+                "'{}' is a link or special file, not a regular file",
+                wanted
+            ));
+        }
+        let size = entry.size();
+        let bytes = read_capped(entry, max_bytes, wanted)?;
+        return Ok(MemberRead { size, bytes });
+    }
+    Err(not_found())
+}
+
+fn read_capped(reader: impl Read, max_bytes: u64, name: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    reader
+        .take(max_bytes)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read '{}': {}", name, e))?;
+    Ok(bytes)
+}
+
+fn open_zip(path: &Path) -> Result<zip::ZipArchive<BufReader<File>>, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+    zip::ZipArchive::new(BufReader::new(file)).map_err(|e| zip_error(path, e))
+}
+
+fn open_tar(path: &Path, format: ArchiveFormat) -> Result<tar::Archive<Box<dyn Read>>, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+    let file = BufReader::new(file);
+    let stream: Box<dyn Read> = match format {
+        ArchiveFormat::TarGz => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        ArchiveFormat::TarZst => Box::new(
+            zstd::stream::read::Decoder::with_buffer(file)
+                .map_err(|e| format!("Cannot read '{}': {}", path.display(), e))?,
+        ),
+        _ => Box::new(file),
+    };
+    Ok(tar::Archive::new(stream))
+}
+
+fn zip_error(path: &Path, e: zip::result::ZipError) -> String {
+    format!("Cannot read zip '{}': {}", path.display(), e)
+}
+
+fn tar_error(path: &Path, e: std::io::Error) -> String {
+    format!("Cannot read tar '{}': {}", path.display(), e)
+}
+
+// ── Parameter definitions ───────────────────────────────────────────────────
+
+pub fn extract_archive_member_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "path".into(),
+            description: "Path to the archive (zip, tar, tar.gz/tgz or tar.zst).".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "member".into(),
+            description: "Member to read, e.g. 'etc/app/config.toml'. Omit to list the \
+                          archive's members instead."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "encoding".into(),
+            description: "'auto' (default: text when the member is UTF-8, else base64), \
+                          'text' or 'base64'."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "max_bytes".into(),
+            description: "Most bytes of the member to return (default 1 MiB, max 16 MiB).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "limit".into(),
+            description: "Most members to list (default 500, max 5000).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,372 @@
+//! Color tool: conversion between hex, RGB and HSL, WCAG contrast ratios,
+//! and simple palettes built from one base color.
+//!
+//! Colors may be given as hex (`#336699`, `#369`, with or without the
+//! `#`), `rgb(51, 102, 153)`, `hsl(210, 50%, 40%)` or one of the CSS
+//! basic color names.  Alpha is not supported.  Every color in a result
+//! carries its hex, RGB and HSL values for programs alongside a one-line
+//! `human` form.
+
+use serde_json::{Value, json};
+use std::path::Path;
+use tracing::instrument;
+
+use super::ToolParam;
+
+/// WCAG 2 minimum contrast ratios: (AA, AAA) for normal and large text.
+const NORMAL_TEXT: (f64, f64) = (4.5, 7.0);
+const LARGE_TEXT: (f64, f64) = (3.0, 4.5);
+
+/// Shades in a palette when `count` isn't given, and the most allowed.
+const DEFAULT_SHADES: usize = 9;
+const MAX_SHADES: usize = 20;
+
+/// The CSS basic colors, plus orange.
+const NAMED: &[(&str, &str)] = &[
+    ("black", "000000"),
+    ("silver", "c0c0c0"),
+    ("gray", "808080"),
+    ("grey", "808080"),
+    ("white", "ffffff"),
+    ("maroon", "800000"),
+    ("red", "ff0000"),
+    ("purple", "800080"),
+    ("fuchsia", "ff00ff"),
+    ("magenta", "ff00ff"),
+    ("green", "008000"),
+    ("lime", "00ff00"),
+    ("olive", "808000"),
+    ("yellow", "ffff00"),
+    ("navy", "000080"),
+    ("blue", "0000ff"),
+    ("teal", "008080"),
+    ("aqua", "00ffff"),
+    ("cyan", "00ffff"),
+    ("orange", "ffa500"),
+];
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `color` tool.
+#[instrument(skip(args, _workspace_dir), fields(action))]
+pub fn exec_color(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: action".to_string())?;
+    tracing::Span::current().record("action", action);
+    let color_arg = |key: &str| {
+        args.get(key)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("Missing required parameter: {}", key))
+            .and_then(Rgb::parse)
+    };
+
+    let result = match action {
+        "convert" => Rgb::describe(color_arg("color")?),
+        "contrast" => contrast(color_arg("foreground")?, color_arg("background")?),
+        "palette" => {
+            let count = match args.get("count").and_then(|v| v.as_u64()) {
+                None => DEFAULT_SHADES,
+                Some(n) if (2..=MAX_SHADES as u64).contains(&n) => n as usize,
+                Some(n) => {
+                    return Err(format!(
+                        "count must be between 2 and {}, got {}",
+                        MAX_SHADES, n
+                    ));
+                }
+            };
+            palette(color_arg("color")?, count)
+        }
+        other => {
+            return Err(format!(
+                "Unknown action: '{}'. Use: convert, contrast, palette",
+                other
+            ));
+        }
+    };
+    serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+}
+
+// ── Colors ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Parse hex, `rgb(…)`, `hsl(…)` or a CSS basic color name.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let lower = text.trim().to_ascii_lowercase();
+        let invalid = || {
+            format!(
+                "Invalid color: '{}'. Use hex (#336699 or #369), rgb(51, 102, 153), \
+                 hsl(210, 50%, 40%) or a CSS name such as 'navy'",
+                text.trim()
+            )
+        };
+        if let Some(&(_, hex)) = NAMED.iter().find(|(name, _)| *name == lower) {
+            return Self::from_hex(hex).ok_or_else(invalid);
+        }
+        if let Some(inner) = function_args(&lower, "rgb") {
+            let [r, g, b] = three_numbers(inner).ok_or_else(invalid)?;
+            let channel = |(value, percent): (f64, bool)| {
+                let value = if percent { value * 2.55 } else { value };
+                (0.0..=255.0)
+                    .contains(&value)
+                    .then_some(value.round() as u8)
+            };
+            return match (channel(r), channel(g), channel(b)) {
+                (Some(r), Some(g), Some(b)) => Ok(Rgb { r, g, b }),
+                _ => Err(format!("{}: channels run from 0 to 255", invalid())),
+            };
+        }
+        if let Some(inner) = function_args(&lower, "hsl") {
+            let inner = inner.replace("deg", "");
+            let [(h, _), (s, _), (l, _)] = three_numbers(&inner).ok_or_else(invalid)?;
+            if !(0.0..=100.0).contains(&s) || !(0.0..=100.0).contains(&l) {
+                return Err(format!(
+                    "{}: saturation and lightness run from 0% to 100%",
+                    invalid()
+                ));
+            }
+            return Ok(Self::from_hsl(h, s, l));
+        }
+        Self::from_hex(lower.strip_prefix('#').unwrap_or(&lower)).ok_or_else(invalid)
+    }
+
+    /// Six or three hex digits, without the `#`.
+    fn from_hex(digits: &str) -> Option<Self> {
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let digits = match digits.len() {
+            6 => digits.to_string(),
+            3 => digits.chars().flat_map(|c| [c, c]).collect(),
+            _ => return None,
+        };
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+        Some(Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        })
+    }
+
+    /// Hue in degrees (any value, wrapped), saturation and lightness in
+    /// percent.
+    pub fn from_hsl(h: f64, s: f64, l: f64) -> Self {
+        let (s, l) = (s / 100.0, l / 100.0);
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = l - c / 2.0;
+        let channel = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        Rgb {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        }
+    }
+
+    /// Hue in degrees, saturation and lightness in percent.
+    pub fn to_hsl(self) -> (f64, f64, f64) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        if d == 0.0 {
+            return (0.0, 0.0, l * 100.0);
+        }
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+        (h * 60.0, s * 100.0, l * 100.0)
+    }
+
+    pub fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    /// WCAG relative luminance, 0 for black to 1 for white.
+    pub fn luminance(self) -> f64 {
+        let linear = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// Same color with the hue turned by `degrees`.
+    fn rotate(self, degrees: f64) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h + degrees, s, l)
+    }
+
+    fn describe(self) -> Value {
+        let (h, s, l) = self.to_hsl();
+        let (h, s, l) = (round1(h), round1(s), round1(l));
+        let rgb = format!("rgb({}, {}, {})", self.r, self.g, self.b);
+        let hsl = format!("hsl({}, {}%, {}%)", h, s, l);
+        json!({
+            "hex": self.hex(),
+            "rgb": { "r": self.r, "g": self.g, "b": self.b },
+            "hsl": { "h": h, "s": s, "l": l },
+            "css": { "rgb": rgb, "hsl": hsl },
+            "human": format!("{} = {} = {}", self.hex(), rgb, hsl),
+        })
+    }
+}
+
+/// WCAG 2 contrast ratio between two colors, from 1 to 21.
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (a.luminance(), b.luminance());
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+fn contrast(foreground: Rgb, background: Rgb) -> Value {
+    let ratio = contrast_ratio(foreground, background);
+    // Pass or fail on the exact ratio; only the reported number is rounded.
+    let verdict = |min: f64| if ratio >= min { "pass" } else { "fail" };
+    let human = format!(
+        "{} on {}: {:.2}:1 — AA {} (normal text), {} (large text); \
+         AAA {} (normal text), {} (large text)",
+        foreground.hex(),
+        background.hex(),
+        ratio,
+        verdict(NORMAL_TEXT.0),
+        verdict(LARGE_TEXT.0),
+        verdict(NORMAL_TEXT.1),
+        verdict(LARGE_TEXT.1),
+    );
+    json!({
+        "foreground": foreground.hex(),
+        "background": background.hex(),
+        "ratio": (ratio * 100.0).round() / 100.0,
+        "aa": { "normal": ratio >= NORMAL_TEXT.0, "large": ratio >= LARGE_TEXT.0 },
+        "aaa": { "normal": ratio >= NORMAL_TEXT.1, "large": ratio >= LARGE_TEXT.1 },
+        "human": human,
+    })
+}
+
+/// `count` shades of the base hue from dark to light, and the colors
+/// found by turning the hue: complement, analogous and triadic.
+fn palette(base: Rgb, count: usize) -> Value {
+    let (h, s, _) = base.to_hsl();
+    let shades: Vec<String> = (0..count)
+        .map(|i| {
+            let l = 10.0 + 80.0 * i as f64 / (count - 1) as f64;
+            Rgb::from_hsl(h, s, l).hex()
+        })
+        .collect();
+    let complement = base.rotate(180.0).hex();
+    let analogous = [base.rotate(-30.0).hex(), base.rotate(30.0).hex()];
+    let triadic = [base.rotate(120.0).hex(), base.rotate(240.0).hex()];
+    let human = format!(
+        "Base {}\nShades (dark → light): {}\nComplement: {}\nAnalogous: {}\nTriadic: {}",
+        base.hex(),
+        shades.join(" "),
+        complement,
+        analogous.join(" "),
+        triadic.join(" "),
+    );
+    json!({
+        "base": base.describe(),
+        "shades": shades,
+        "complement": complement,
+        "analogous": analogous,
+        "triadic": triadic,
+        "human": human,
+    })
+}
+
+// ── Parsing helpers ─────────────────────────────────────────────────────────
+
+/// The text between the parentheses of `name(…)`.
+fn function_args<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    text.strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Three numbers separated by commas or spaces, each with whether it
+/// ended in `%`.
+fn three_numbers(text: &str) -> Option<[(f64, bool); 3]> {
+    let numbers: Vec<(f64, bool)> = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.strip_suffix('%') {
+            Some(n) => n.parse().ok().map(|n| (n, true)),
+            None => part.parse().ok().map(|n| (n, false)),
+        })
+        .collect::<Option<_>>()?;
+    numbers.try_into().ok()
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn color_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "One of: convert, contrast, palette.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "color".into(),
+            description: "For 'convert' and 'palette': the color, as hex ('#336699', \
+                          '#369'), 'rgb(51, 102, 153)', 'hsl(210, 50%, 40%)' or a CSS \
+                          basic name such as 'navy'."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "foreground".into(),
+            description: "For 'contrast': text color, in any form 'color' accepts.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "background".into(),
+            description: "For 'contrast': background color.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "count".into(),
+            description: "For 'palette': number of shades from dark to light, 2 to 20. \
+                          Default: 9."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
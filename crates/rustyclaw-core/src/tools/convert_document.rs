@@ -0,0 +1,434 @@
+//! `convert_document` tool: convert between document formats.
+//!
+//! Conversions go through pandoc when it is installed.  Without it, a
+//! built-in engine covers the common text cases — markdown to HTML (with
+//! pulldown-cmark) and HTML back to markdown — and anything else fails
+//! with install instructions rather than a half-converted file.
+//!
+//! Both paths are sandbox-checked, inputs are capped at
+//! [`MAX_INPUT_BYTES`], and pandoc runs with `--sandbox` where its version
+//! supports it, so a document can't pull in other files while it is
+//! converted.
+
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+use crate::gateway::MediaRef;
+
+/// Largest input converted (20 MiB).
+pub const MAX_INPUT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// How long pandoc may run before it is killed.
+const PANDOC_TIMEOUT: Duration = Duration::from_secs(120);
+
+const PANDOC_MISSING: &str = "pandoc is not installed. Install it with 'brew install pandoc' \
+     (macOS), 'apt install pandoc' (Debian/Ubuntu) or from https://pandoc.org/installing.html. \
+     Without it only markdown -> html and html -> markdown are available.";
+
+/// A document format: its name, pandoc's name for it, file extensions and
+/// MIME type.
+struct Format {
+    name: &'static str,
+    pandoc: &'static str,
+    extensions: &'static [&'static str],
+    mime: &'static str,
+}
+
+const FORMATS: &[Format] = &[
+    Format {
+        name: "markdown",
+        pandoc: "markdown",
+        extensions: &["md", "markdown"],
+        mime: "text/markdown",
+    },
+    Format {
+        name: "html",
+        pandoc: "html",
+        extensions: &["html", "htm"],
+        mime: "text/html",
+    },
+    Format {
+        name: "docx",
+        pandoc: "docx",
+        extensions: &["docx"],
+        mime: "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    },
+    Format {
+        name: "rst",
+        pandoc: "rst",
+        extensions: &["rst"],
+        mime: "text/x-rst",
+    },
+    Format {
+        name: "latex",
+        pandoc: "latex",
+        extensions: &["tex", "latex"],
+        mime: "application/x-latex",
+    },
+    Format {
+        name: "odt",
+        pandoc: "odt",
+        extensions: &["odt"],
+        mime: "application/vnd.oasis.opendocument.text",
+    },
+    Format {
+        name: "epub",
+        pandoc: "epub",
+        extensions: &["epub"],
+        mime: "application/epub+zip",
+    },
+    Format {
+        name: "plain",
+        pandoc: "plain",
+        extensions: &["txt"],
+        mime: "text/plain",
+    },
+    // Output only, and pandoc needs a LaTeX engine for it.
+    Format {
+        name: "pdf",
+        pandoc: "pdf",
+        extensions: &["pdf"],
+        mime: "application/pdf",
+    },
+];
+
+impl Format {
+    /// By name (`md` and `tex` work too) or file extension.
+    fn named(name: &str) -> Option<&'static Format> {
+        let name = name.trim().trim_start_matches('.').to_ascii_lowercase();
+        FORMATS
+            .iter()
+            .find(|f| f.name == name || f.extensions.contains(&name.as_str()))
+    }
+
+    fn of_path(path: &Path) -> Option<&'static Format> {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(Self::named)
+    }
+}
+
+fn format_names() -> String {
+    FORMATS
+        .iter()
+        .map(|f| f.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `convert_document` tool.
+#[instrument(skip(args, workspace_dir), fields(engine))]
+pub async fn exec_convert_document_async(
+    args: &Value,
+    workspace_dir: &Path,
+) -> Result<String, String> {
+    let input_str = args
+        .get("input_path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: input_path".to_string())?;
+    let input = checked_path(workspace_dir, input_str)?;
+    let size = std::fs::metadata(&input)
+        .map_err(|_| format!("File not found: {}", input.display()))?
+        .len();
+    if size > MAX_INPUT_BYTES {
+        return Err(format!(
+            "Input is {} bytes; the limit is {}",
+            size, MAX_INPUT_BYTES
+        ));
+    }
+
+    let from = match args.get("from").and_then(|v| v.as_str()) {
+        Some(name) => Format::named(name)
+            .ok_or_else(|| format!("Unknown format: '{}'. Use: {}", name, format_names()))?,
+        None => Format::of_path(&input).ok_or_else(|| {
+            format!(
+                "Can't tell the format of '{}'; pass from ({})",
+                input.display(),
+                format_names()
+            )
+        })?,
+    };
+    if from.name == "pdf" {
+        return Err("PDF can't be converted from; use pdf_extract to get its text".to_string());
+    }
+    let output_arg = args.get("output_path").and_then(|v| v.as_str());
+    let to = match args.get("to").and_then(|v| v.as_str()) {
+        Some(name) => Format::named(name)
+            .ok_or_else(|| format!("Unknown format: '{}'. Use: {}", name, format_names()))?,
+        None => output_arg
+            .and_then(|p| Format::of_path(Path::new(p)))
+            .ok_or("Missing required parameter: to (or an output_path extension)")?,
+    };
+    let output = match output_arg {
+        Some(p) => checked_path(workspace_dir, p)?,
+        None => checked_path(
+            workspace_dir,
+            &input.with_extension(to.extensions[0]).to_string_lossy(),
+        )?,
+    };
+    if output == input {
+        return Err(format!(
+            "Output would overwrite the input '{}'; pass output_path",
+            input.display()
+        ));
+    }
+
+    let engine = args
+        .get("engine")
+        .and_then(|v| v.as_str())
+        .unwrap_or("auto");
+    tracing::Span::current().record("engine", engine);
+    let pandoc = which::which("pandoc").ok();
+    let used = match (engine, pandoc) {
+        ("pandoc" | "auto", Some(pandoc)) => {
+            prepare_output(&output)?;
+            convert_pandoc(&pandoc, &input, &output, from, to).await?;
+            "pandoc"
+        }
+        ("pandoc", None) => return Err(PANDOC_MISSING.to_string()),
+        ("auto", None) if !builtin_supports(from, to) => return Err(PANDOC_MISSING.to_string()),
+        ("builtin" | "auto", _) => {
+            convert_builtin(&input, &output, from, to)?;
+            "builtin"
+        }
+        (other, _) => {
+            return Err(format!(
+                "Unknown engine: '{}'. Use: auto, pandoc, builtin",
+                other
+            ));
+        }
+    };
+
+    let bytes = std::fs::metadata(&output).map(|m| m.len()).unwrap_or(0);
+    debug!(
+        path = %output.display(),
+        from = from.name,
+        to = to.name,
+        engine = used,
+        bytes,
+        "Converted document"
+    );
+    let mut media = MediaRef::new(to.mime.to_string());
+    media.filename = output.file_name().map(|n| n.to_string_lossy().into_owned());
+    media.size = Some(bytes as usize);
+    media.local_path = Some(output.display().to_string());
+    let result = json!({
+        "path": output.display().to_string(),
+        "from": from.name,
+        "to": to.name,
+        "engine": used,
+        "bytes": bytes,
+        "media": media,
+    });
+    serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+}
+
+/// Sync stub — execution goes through the async path.
+pub fn exec_convert_document_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("convert_document requires async execution".into())
+}
+
+// ── Engines ─────────────────────────────────────────────────────────────────
+
+async fn convert_pandoc(
+    pandoc: &Path,
+    input: &Path,
+    output: &Path,
+    from: &Format,
+    to: &Format,
+) -> Result<(), String> {
+    let mut command = tokio::process::Command::new(pandoc);
+    command
+        .arg("--from")
+        .arg(from.pandoc)
+        .arg("--to")
+        .arg(to.pandoc)
+        .arg("--output")
+        .arg(output);
+    if matches!(to.name, "html" | "latex") {
+        command.arg("--standalone");
+    }
+    if supports_sandbox(pandoc).await {
+        command.arg("--sandbox");
+    }
+    let child = command
+        .arg(input)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let out = match tokio::time::timeout(PANDOC_TIMEOUT, child).await {
+        Ok(result) => result.map_err(|e| format!("Failed to run pandoc: {}", e))?,
+        Err(_) => {
+            return Err(format!(
+                "pandoc took longer than {}s and was stopped",
+                PANDOC_TIMEOUT.as_secs()
+            ));
+        }
+    };
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        let mut message = format!("pandoc failed: {}", stderr.trim());
+        if to.name == "pdf" {
+            message.push_str(" (PDF output needs a LaTeX engine; pdf_create works without one)");
+        }
+        return Err(message);
+    }
+    Ok(())
+}
+
+/// `--sandbox` arrived in pandoc 2.15; older versions reject it.
+async fn supports_sandbox(pandoc: &Path) -> bool {
+    let Ok(out) = tokio::process::Command::new(pandoc)
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .output()
+        .await
+    else {
+        return false;
+    };
+    let text = String::from_utf8_lossy(&out.stdout);
+    let version = text
+        .lines()
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .unwrap_or("");
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    let (major, minor) = (parts.next().unwrap_or(0), parts.next().unwrap_or(0));
+    (major, minor) >= (2, 15)
+}
+
+fn builtin_supports(from: &Format, to: &Format) -> bool {
+    let pair = (from.name, to.name);
+    pair == ("markdown", "html") || (cfg!(feature = "web-tools") && pair == ("html", "markdown"))
+}
+
+/// Markdown to a standalone HTML page, or HTML to markdown.
+fn convert_builtin(input: &Path, output: &Path, from: &Format, to: &Format) -> Result<(), String> {
+    if !builtin_supports(from, to) {
+        return Err(format!(
+            "{} -> {} needs pandoc; built in are markdown -> html and html -> markdown",
+            from.name, to.name
+        ));
+    }
+    let text = std::fs::read_to_string(input)
+        .map_err(|e| format!("Failed to read '{}': {}", input.display(), e))?;
+    let converted = if from.name == "markdown" {
+        markdown_to_html(&text, input)
+    } else {
+        html_to_markdown(&text)
+    };
+    prepare_output(output)?;
+    std::fs::write(output, converted)
+        .map_err(|e| format!("Failed to write '{}': {}", output.display(), e))
+}
+
+fn markdown_to_html(markdown: &str, input: &Path) -> String {
+    let options = pulldown_cmark::Options::ENABLE_TABLES
+        | pulldown_cmark::Options::ENABLE_STRIKETHROUGH
+        | pulldown_cmark::Options::ENABLE_TASKLISTS
+        | pulldown_cmark::Options::ENABLE_FOOTNOTES;
+    let mut body = String::new();
+    pulldown_cmark::html::push_html(
+        &mut body,
+        pulldown_cmark::Parser::new_ext(markdown, options),
+    );
+    let title = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         </head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(&title),
+        body
+    )
+}
+
+#[cfg(feature = "web-tools")]
+fn html_to_markdown(html: &str) -> String {
+    html2md::parse_html(html)
+}
+
+#[cfg(not(feature = "web-tools"))]
+fn html_to_markdown(_html: &str) -> String {
+    unreachable!("builtin_supports rules this out")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn checked_path(workspace_dir: &Path, p: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, p);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted convert_document access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+fn prepare_output(output: &Path) -> Result<(), String> {
+    match output.parent() {
+        Some(parent) => std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e)),
+        None => Ok(()),
+    }
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn convert_document_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "input_path".into(),
+            description: "Document to convert.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "to".into(),
+            description: format!(
+                "Target format: {}. Default: output_path's extension.",
+                format_names()
+            ),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "from".into(),
+            description: "Source format. Default: from input_path's extension.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "output_path".into(),
+            description: "Where to write the result. Default: the input path with the target \
+                          format's extension."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "engine".into(),
+            description: "'auto' (default) uses pandoc when installed, else the built-in \
+                          markdown <-> html converter; 'pandoc' or 'builtin' forces one."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -1,14 +1,23 @@
 //! Cron tool: scheduled job management.
+//!
+//! Also home to scheduled channel messages: the `message` tool's `schedule`
+//! action stores a one-shot job here, and the gateway calls
+//! [`dispatch_due_messages`] periodically to send the ones that are due.
 
-use serde_json::Value;
-use std::path::Path;
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
 use tracing::{debug, instrument, warn};
 
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+use crate::cron::{
+    CronExport, CronJob, CronJobPatch, CronStore, ImportConflict, ImportOutcome, Payload, RunEntry,
+    RunStatus, Schedule, SessionTarget,
+};
+
 /// Cron job management.
 #[instrument(skip(args, workspace_dir), fields(action))]
 pub fn exec_cron(args: &Value, workspace_dir: &Path) -> Result<String, String> {
-    use crate::cron::*;
-
     let action = args
         .get("action")
         .and_then(|v| v.as_str())
@@ -149,12 +158,316 @@ pub fn exec_cron(args: &Value, workspace_dir: &Path) -> Result<String, String> {
             Ok(output)
         }
 
+        "export" => export_jobs(args, workspace_dir, &store),
+
+        "import" => import_jobs(args, workspace_dir, &mut store),
+
         _ => {
             warn!(action, "Unknown cron action");
             Err(format!(
-                "Unknown action: {}. Valid: status, list, add, update, remove, run, runs",
+                "Unknown action: {}. Valid: status, list, add, update, remove, run, runs, \
+                 export, import",
                 action
             ))
         }
     }
 }
+
+/// Write every job to `path` (or return it) as JSON or YAML.
+fn export_jobs(args: &Value, workspace_dir: &Path, store: &CronStore) -> Result<String, String> {
+    let export = store.export();
+    let format = args
+        .get("format")
+        .and_then(|v| v.as_str())
+        .unwrap_or("json");
+    let text = match format {
+        "json" => serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?,
+        "yaml" => serde_yaml::to_string(&export).map_err(|e| e.to_string())?,
+        other => return Err(format!("Unknown format: {}. Valid: json, yaml", other)),
+    };
+    let Some(path) = args.get("path").and_then(|v| v.as_str()) else {
+        return Ok(text);
+    };
+    let path = checked_path(workspace_dir, path)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, text)
+        .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+    debug!(count = export.jobs.len(), path = %path.display(), "Exported cron jobs");
+    Ok(format!(
+        "Exported {} job(s) to {}",
+        export.jobs.len(),
+        path.display()
+    ))
+}
+
+/// Load jobs from `path` or inline `data`, validating each schedule.
+fn import_jobs(
+    args: &Value,
+    workspace_dir: &Path,
+    store: &mut CronStore,
+) -> Result<String, String> {
+    let text = match (
+        args.get("path").and_then(|v| v.as_str()),
+        args.get("data").and_then(|v| v.as_str()),
+    ) {
+        (Some(path), None) => {
+            let path = checked_path(workspace_dir, path)?;
+            std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?
+        }
+        (None, Some(data)) => data.to_string(),
+        (Some(_), Some(_)) => return Err("Pass either path or data, not both".to_string()),
+        (None, None) => return Err("Missing path or data for import".to_string()),
+    };
+    let conflict = match args.get("onConflict").and_then(|v| v.as_str()) {
+        None | Some("skip") => ImportConflict::Skip,
+        Some("overwrite") => ImportConflict::Overwrite,
+        Some("merge") => ImportConflict::Merge,
+        Some(other) => {
+            return Err(format!(
+                "Unknown onConflict: {}. Valid: skip, overwrite, merge",
+                other
+            ));
+        }
+    };
+
+    let export = CronExport::parse(&text)?;
+    let report = store.import(export.jobs, conflict)?;
+    let count = |want: fn(&ImportOutcome) -> bool| report.iter().filter(|(_, o)| want(o)).count();
+    let mut output = format!(
+        "Imported cron jobs: {} added, {} overwritten, {} merged, {} skipped.\n",
+        count(|o| *o == ImportOutcome::Added),
+        count(|o| *o == ImportOutcome::Overwritten),
+        count(|o| *o == ImportOutcome::Merged),
+        count(|o| matches!(o, ImportOutcome::Skipped(_))),
+    );
+    for (id, outcome) in &report {
+        let line = match outcome {
+            ImportOutcome::Added => format!("+ {} added", id),
+            ImportOutcome::Overwritten => format!("~ {} overwritten", id),
+            ImportOutcome::Merged => format!("~ {} merged", id),
+            ImportOutcome::Skipped(why) => format!("○ {} skipped: {}", id, why),
+        };
+        output.push('\n');
+        output.push_str(&line);
+    }
+    debug!(jobs = report.len(), ?conflict, "Imported cron jobs");
+    Ok(output)
+}
+
+fn checked_path(workspace_dir: &Path, p: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, p);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted cron access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+/// Store a one-shot job that sends `message` to `target` on `channel`.
+///
+/// The time comes from `at` (RFC 3339, a local `YYYY-MM-DD HH:MM`, or a bare
+/// `HH:MM` meaning the next occurrence) or from `delayMinutes`.  The reply
+/// states the fire time in the gateway host's local timezone.
+pub(crate) fn schedule_message(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let message = args
+        .get("message")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing message for schedule action")?;
+    let target = args
+        .get("target")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing target for schedule action")?;
+    let channel = args
+        .get("channel")
+        .and_then(|v| v.as_str())
+        .unwrap_or("auto");
+
+    let when = fire_time(args, Local::now(), "schedule")?;
+    let job = CronJob::new(
+        Some(format!("Message to {} ({})", target, channel)),
+        Schedule::At {
+            at: when.with_timezone(&chrono::Utc).to_rfc3339(),
+        },
+        SessionTarget::Isolated,
+        Payload::SendMessage {
+            channel: channel.to_string(),
+            target: target.to_string(),
+            message: message.to_string(),
+        },
+    );
+    let mut store = CronStore::new(&workspace_dir.join(".cron"))?;
+    let id = store.add(job)?;
+    debug!(job_id = %id, at = %when, "Scheduled message");
+
+    Ok(format!(
+        "Scheduled message {} to {} via {} for {}.\n\
+         Cancel with cron(action='remove', jobId='{}').",
+        id,
+        target,
+        channel,
+        format_local(&when),
+        id
+    ))
+}
+
+/// Send every scheduled message in `cron_dir` whose time has come.
+///
+/// Each job is settled after one attempt: delivered messages are deleted,
+/// failed ones are disabled and the error is kept in the run history.
+/// Returns the number of messages attempted.  A directory without a job
+/// store is skipped rather than created.
+pub async fn dispatch_due_messages(cron_dir: &Path) -> Result<usize, String> {
+    if !cron_dir.join("jobs.json").exists() {
+        return Ok(0);
+    }
+    let started_ms = now_ms();
+    let due: Vec<(String, String, String, String)> = CronStore::new(cron_dir)?
+        .due(started_ms)
+        .into_iter()
+        .filter_map(|job| match &job.payload {
+            Payload::SendMessage {
+                channel,
+                target,
+                message,
+            } => Some((
+                job.job_id.clone(),
+                channel.clone(),
+                target.clone(),
+                message.clone(),
+            )),
+            _ => None,
+        })
+        .collect();
+
+    for (job_id, channel, target, message) in &due {
+        let args = json!({
+            "action": "send",
+            "channel": channel,
+            "target": target,
+            "message": message,
+        });
+        let result = super::gateway_tools::exec_message_async(&args, cron_dir).await;
+        match &result {
+            Ok(_) => debug!(job_id, "Scheduled message sent"),
+            Err(e) => warn!(job_id, error = %e, "Scheduled message failed"),
+        }
+        settle_dispatched(cron_dir, job_id, started_ms, &result)?;
+    }
+    Ok(due.len())
+}
+
+/// Record the run of `job_id` and settle it, against the store as it is
+/// now rather than as it was before the send: jobs scheduled, changed or
+/// cancelled while the message was going out are kept as they are.
+pub(super) fn settle_dispatched(
+    cron_dir: &Path,
+    job_id: &str,
+    started_ms: u64,
+    result: &Result<String, String>,
+) -> Result<(), String> {
+    let mut store = CronStore::new(cron_dir)?;
+    store.record_run(&RunEntry {
+        job_id: job_id.to_string(),
+        run_id: format!("run-{:x}", started_ms),
+        started_ms,
+        finished_ms: Some(now_ms()),
+        status: if result.is_ok() {
+            RunStatus::Ok
+        } else {
+            RunStatus::Error
+        },
+        error: result.as_ref().err().cloned(),
+    })?;
+    // Cancelled or moved to a later time meanwhile: leave it be.
+    let still_due = store
+        .get(job_id)
+        .is_some_and(|job| job.enabled && job.schedule.at_ms().is_some_and(|at| at <= started_ms));
+    if still_due {
+        store.finish_one_shot(job_id, started_ms, result.is_ok())?;
+    }
+    Ok(())
+}
+
+/// The future time named by `at` or `delayMinutes` in `args`.
+pub(super) fn fire_time(
+    args: &Value,
+    now: DateTime<Local>,
+    action: &str,
+) -> Result<DateTime<Local>, String> {
+    let when = match (
+        args.get("at").and_then(|v| v.as_str()),
+        args.get("delayMinutes").and_then(|v| v.as_u64()),
+    ) {
+        (Some(at), None) => parse_local_time(at, now)?,
+        (None, Some(minutes)) => i64::try_from(minutes)
+            .ok()
+            .and_then(chrono::Duration::try_minutes)
+            .and_then(|d| now.checked_add_signed(d))
+            .ok_or("delayMinutes is out of range")?,
+        (Some(_), Some(_)) => return Err("Pass either at or delayMinutes, not both".to_string()),
+        (None, None) => {
+            return Err(format!("Missing at or delayMinutes for {} action", action));
+        }
+    };
+    if when <= now {
+        return Err(format!(
+            "Scheduled time {} is in the past",
+            format_local(&when)
+        ));
+    }
+    Ok(when)
+}
+
+pub(super) fn now_ms() -> u64 {
+    u64::try_from(Local::now().timestamp_millis()).unwrap_or_default()
+}
+
+/// Parse a user-supplied time; anything without an offset is local time.
+fn parse_local_time(at: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+    let at = at.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(at) {
+        return Ok(t.with_timezone(&Local));
+    }
+
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(at, fmt).ok())
+    .or_else(|| {
+        let time = ["%H:%M", "%H:%M:%S"]
+            .iter()
+            .find_map(|fmt| NaiveTime::parse_from_str(at, fmt).ok())?;
+        let today = now.date_naive().and_time(time);
+        if today > now.naive_local() {
+            Some(today)
+        } else {
+            Some(today + chrono::Duration::days(1))
+        }
+    })
+    .ok_or_else(|| {
+        format!(
+            "Invalid time '{}': use RFC 3339, 'YYYY-MM-DD HH:MM' or 'HH:MM'",
+            at
+        )
+    })?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("{} does not exist in the local timezone", at))
+}
+
+pub(super) fn format_local(t: &DateTime<Local>) -> String {
+    t.format("%Y-%m-%d %H:%M %:z (local time)").to_string()
+}
@@ -0,0 +1,700 @@
+//! CSV tool: query delimited text files without pasting them into the model.
+//!
+//! Parses RFC 4180-style files (quoted fields, doubled quotes, embedded
+//! delimiters and newlines) with a configurable delimiter, with or without a
+//! header row, and answers `head`, `stats`, `filter`, and `select` requests
+//! as an aligned text table or JSON.
+//!
+//! Files go through the same vault-boundary and sandbox path checks as
+//! `read_file`.  At most [`MAX_ROWS`] data rows are scanned; results say
+//! when a file was cut short.
+
+use serde_json::{Map, Value, json};
+use std::io::Read;
+use std::path::Path;
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{
+    VAULT_ACCESS_DENIED, is_protected_path, open_file_read_safe, resolve_path, sandbox,
+};
+
+/// Data rows scanned per call; anything beyond is ignored.
+const MAX_ROWS: usize = 100_000;
+
+/// Bytes read from the file; anything beyond is ignored.
+const MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Rows returned by `head`, `filter`, and `select` unless `limit` is given.
+const DEFAULT_LIMIT: usize = 20;
+
+/// Upper bound on returned rows regardless of the requested `limit`.
+const MAX_LIMIT: usize = 1000;
+
+/// Table cells longer than this are cut with an ellipsis.
+const MAX_CELL_CHARS: usize = 40;
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `csv` tool.
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub fn exec_csv(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: action".to_string())?;
+    tracing::Span::current().record("action", action);
+
+    if !matches!(action, "head" | "stats" | "filter" | "select") {
+        return Err(format!(
+            "Unknown action: '{}'. Use: head, stats, filter, select",
+            action
+        ));
+    }
+
+    let path_str = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: path".to_string())?;
+    let path = resolve_path(workspace_dir, path_str);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted csv access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+
+    let delimiter = parse_delimiter(args, &path)?;
+    let has_header = args
+        .get("has_header")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let limit = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|n| (n as usize).clamp(1, MAX_LIMIT))
+        .unwrap_or(DEFAULT_LIMIT);
+    let as_json = match args.get("format").and_then(|v| v.as_str()) {
+        None | Some("table") => false,
+        Some("json") => true,
+        Some(other) => {
+            return Err(format!("Unknown format: '{}'. Use: table, json", other));
+        }
+    };
+
+    let text = read_capped(&path)?;
+    let table = Table::parse(&text, delimiter, has_header);
+    debug!(
+        path = %path.display(),
+        rows = table.rows.len(),
+        columns = table.headers.len(),
+        "Parsed CSV"
+    );
+
+    let out = match action {
+        "stats" => {
+            let stats = table.stats();
+            if as_json {
+                json!({
+                    "rows": table.rows.len(),
+                    "truncated": table.truncated,
+                    "columns": stats,
+                })
+                .to_string()
+            } else {
+                render_stats(&table, &stats)
+            }
+        }
+        _ => {
+            let columns = match args.get("columns") {
+                Some(spec) => table.resolve_columns(spec)?,
+                None if action == "select" => {
+                    return Err("Missing required parameter for select: columns".to_string());
+                }
+                None => (0..table.headers.len()).collect(),
+            };
+            let predicates = match args.get("where") {
+                Some(spec) => parse_predicates(spec, &table)?,
+                None if action == "filter" => {
+                    return Err("Missing required parameter for filter: where".to_string());
+                }
+                None => Vec::new(),
+            };
+
+            let matched: Vec<&Vec<String>> = table
+                .rows
+                .iter()
+                .filter(|row| predicates.iter().all(|p| p.matches(row)))
+                .collect();
+            let shown = &matched[..matched.len().min(limit)];
+
+            if as_json {
+                let rows: Vec<Value> = shown
+                    .iter()
+                    .map(|row| {
+                        let obj: Map<String, Value> = columns
+                            .iter()
+                            .map(|&c| (table.headers[c].clone(), cell_json(row, c)))
+                            .collect();
+                        Value::Object(obj)
+                    })
+                    .collect();
+                json!({
+                    "columns": columns.iter().map(|&c| &table.headers[c]).collect::<Vec<_>>(),
+                    "rows": rows,
+                    "matched": matched.len(),
+                    "scanned": table.rows.len(),
+                    "truncated": table.truncated,
+                })
+                .to_string()
+            } else {
+                let mut out = render_table(&table.headers, &columns, shown);
+                out.push_str(&format!(
+                    "\n{} of {} matching rows shown ({} scanned{})",
+                    shown.len(),
+                    matched.len(),
+                    table.rows.len(),
+                    if table.truncated {
+                        format!(", file truncated at {} rows", MAX_ROWS)
+                    } else {
+                        String::new()
+                    }
+                ));
+                out
+            }
+        }
+    };
+    Ok(out)
+}
+
+/// Read up to [`MAX_BYTES`] of `path` as UTF-8 (lossy).
+fn read_capped(path: &Path) -> Result<String, String> {
+    let (file, _) = open_file_read_safe(path)
+        .map_err(|e| format!("Failed to open file '{}': {}", path.display(), e))?;
+    let mut bytes = Vec::new();
+    file.take(MAX_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read file '{}': {}", path.display(), e))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// The `delimiter` argument, or tab for `.tsv` files and comma otherwise.
+fn parse_delimiter(args: &Value, path: &Path) -> Result<char, String> {
+    let Some(raw) = args.get("delimiter").and_then(|v| v.as_str()) else {
+        let is_tsv = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+        return Ok(if is_tsv { '\t' } else { ',' });
+    };
+    match raw {
+        "\\t" | "tab" => Ok('\t'),
+        _ => {
+            let mut chars = raw.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c != '"' && c != '\n' && c != '\r' => Ok(c),
+                _ => Err(format!(
+                    "Invalid delimiter '{}': use a single character or 'tab'",
+                    raw
+                )),
+            }
+        }
+    }
+}
+
+// ── Parsing ─────────────────────────────────────────────────────────────────
+
+/// A parsed file: header names and data rows, all as raw strings.
+struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    /// More than [`MAX_ROWS`] data rows were present.
+    truncated: bool,
+}
+
+impl Table {
+    fn parse(text: &str, delimiter: char, has_header: bool) -> Self {
+        let mut records = parse_records(text, delimiter, MAX_ROWS + 2);
+        let headers = if has_header && !records.is_empty() {
+            records.remove(0)
+        } else {
+            Vec::new()
+        };
+        let truncated = records.len() > MAX_ROWS;
+        records.truncate(MAX_ROWS);
+
+        let width = records
+            .iter()
+            .map(Vec::len)
+            .chain(std::iter::once(headers.len()))
+            .max()
+            .unwrap_or(0);
+        let headers = (0..width)
+            .map(|i| match headers.get(i).map(|h| h.trim()) {
+                Some(h) if !h.is_empty() => h.to_string(),
+                _ => format!("col{}", i + 1),
+            })
+            .collect();
+
+        Self {
+            headers,
+            rows: records,
+            truncated,
+        }
+    }
+
+    /// Index of a column given by header name or 1-based position.
+    fn column(&self, spec: &str) -> Result<usize, String> {
+        let spec = spec.trim();
+        if let Some(i) = self.headers.iter().position(|h| h == spec) {
+            return Ok(i);
+        }
+        if let Some(i) = self
+            .headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(spec))
+        {
+            return Ok(i);
+        }
+        match spec.parse::<usize>() {
+            Ok(n) if (1..=self.headers.len()).contains(&n) => Ok(n - 1),
+            _ => Err(format!(
+                "Unknown column '{}'. Columns: {}",
+                spec,
+                self.headers.join(", ")
+            )),
+        }
+    }
+
+    /// Resolve the `columns` argument (array or comma-separated string).
+    fn resolve_columns(&self, spec: &Value) -> Result<Vec<usize>, String> {
+        let names: Vec<String> = match spec {
+            Value::Array(items) => items
+                .iter()
+                .map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect(),
+            Value::String(s) => s.split(',').map(str::to_string).collect(),
+            _ => return Err("columns must be an array or a comma-separated string".to_string()),
+        };
+        if names.is_empty() {
+            return Err("columns must name at least one column".to_string());
+        }
+        names.iter().map(|n| self.column(n)).collect()
+    }
+
+    fn stats(&self) -> Vec<ColumnStats> {
+        (0..self.headers.len())
+            .map(|c| ColumnStats::collect(&self.headers[c], self.rows.iter().map(|r| cell(r, c))))
+            .collect()
+    }
+}
+
+/// Split `text` into records of fields, stopping after `max_records`.
+///
+/// Quoted fields may contain the delimiter, newlines, and `""` for a
+/// literal quote.  Blank lines are skipped.
+fn parse_records(text: &str, delimiter: char, max_records: usize) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                end_record(&mut records, &mut record, &mut field);
+                if records.len() >= max_records {
+                    return records;
+                }
+            }
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        end_record(&mut records, &mut record, &mut field);
+    }
+    records.truncate(max_records);
+    records
+}
+
+/// Close the current field and record, dropping records from blank lines.
+fn end_record(records: &mut Vec<Vec<String>>, record: &mut Vec<String>, field: &mut String) {
+    record.push(std::mem::take(field));
+    let done = std::mem::take(record);
+    if !(done.len() == 1 && done[0].is_empty()) {
+        records.push(done);
+    }
+}
+
+/// Cell `c` of `row`; short rows read as empty.
+fn cell(row: &[String], c: usize) -> &str {
+    row.get(c).map(String::as_str).unwrap_or("")
+}
+
+/// A cell as JSON: empty → null, numbers as numbers, everything else text.
+fn cell_json(row: &[String], c: usize) -> Value {
+    let raw = cell(row, c);
+    if raw.trim().is_empty() {
+        return Value::Null;
+    }
+    if let Ok(i) = raw.trim().parse::<i64>() {
+        return json!(i);
+    }
+    match raw.trim().parse::<f64>() {
+        Ok(f) if f.is_finite() => json!(f),
+        _ => json!(raw),
+    }
+}
+
+// ── Filtering ───────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+}
+
+/// `column op value`, e.g. `age >= 30` or `city contains York`.
+struct Predicate {
+    column: usize,
+    op: Op,
+    value: String,
+}
+
+impl Predicate {
+    fn parse(expr: &str, table: &Table) -> Result<Self, String> {
+        // Longest operators first so `>=` is not read as `>`.
+        const OPS: &[(&str, Op)] = &[
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("!=", Op::Ne),
+            ("==", Op::Eq),
+            (" contains ", Op::Contains),
+            ("=", Op::Eq),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+        ];
+        let (pos, token, op) = OPS
+            .iter()
+            .filter_map(|(token, op)| expr.find(token).map(|pos| (pos, *token, *op)))
+            .min_by_key(|(pos, token, _)| (*pos, std::cmp::Reverse(token.len())))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid filter '{}': expected 'column op value' with op one of \
+                     ==, !=, >, >=, <, <=, contains",
+                    expr
+                )
+            })?;
+
+        let column = table.column(&expr[..pos])?;
+        let value = expr[pos + token.len()..].trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        Ok(Self {
+            column,
+            op,
+            value: value.to_string(),
+        })
+    }
+
+    fn matches(&self, row: &[String]) -> bool {
+        let cell = cell(row, self.column).trim();
+        if self.op == Op::Contains {
+            return cell.to_lowercase().contains(&self.value.to_lowercase());
+        }
+        let ordering = match (cell.parse::<f64>(), self.value.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b),
+            _ => Some(cell.cmp(self.value.as_str())),
+        };
+        let Some(ordering) = ordering else {
+            return false;
+        };
+        match self.op {
+            Op::Eq => ordering.is_eq(),
+            Op::Ne => ordering.is_ne(),
+            Op::Gt => ordering.is_gt(),
+            Op::Ge => ordering.is_ge(),
+            Op::Lt => ordering.is_lt(),
+            Op::Le => ordering.is_le(),
+            Op::Contains => unreachable!("handled above"),
+        }
+    }
+}
+
+/// Parse the `where` argument (one expression or an array of them, ANDed).
+fn parse_predicates(spec: &Value, table: &Table) -> Result<Vec<Predicate>, String> {
+    match spec {
+        Value::String(s) => Ok(vec![Predicate::parse(s, table)?]),
+        Value::Array(items) => items
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .ok_or_else(|| "where entries must be strings".to_string())
+                    .and_then(|s| Predicate::parse(s, table))
+            })
+            .collect(),
+        _ => Err("where must be a string or an array of strings".to_string()),
+    }
+}
+
+// ── Statistics ──────────────────────────────────────────────────────────────
+
+/// Per-column summary for `stats`.
+#[derive(Debug, serde::Serialize)]
+struct ColumnStats {
+    name: String,
+    /// `integer`, `float`, `boolean`, `string`, or `empty`.
+    #[serde(rename = "type")]
+    kind: &'static str,
+    non_null: usize,
+    nulls: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<Value>,
+}
+
+impl ColumnStats {
+    fn collect<'a>(name: &str, cells: impl Iterator<Item = &'a str>) -> Self {
+        let values: Vec<&str> = cells.map(str::trim).collect();
+        let present: Vec<&str> = values.iter().copied().filter(|v| !v.is_empty()).collect();
+        let nulls = values.len() - present.len();
+
+        let all = |f: fn(&str) -> bool| !present.is_empty() && present.iter().all(|v| f(v));
+        let kind = if present.is_empty() {
+            "empty"
+        } else if all(|v| v.parse::<i64>().is_ok()) {
+            "integer"
+        } else if all(|v| v.parse::<f64>().is_ok_and(f64::is_finite)) {
+            "float"
+        } else if all(|v| matches!(v.to_ascii_lowercase().as_str(), "true" | "false")) {
+            "boolean"
+        } else {
+            "string"
+        };
+
+        let (min, max) = match kind {
+            "integer" => {
+                let nums = present.iter().filter_map(|v| v.parse::<i64>().ok());
+                (
+                    nums.clone().min().map(|n| json!(n)),
+                    nums.max().map(|n| json!(n)),
+                )
+            }
+            "float" => {
+                let nums: Vec<f64> = present.iter().filter_map(|v| v.parse().ok()).collect();
+                (
+                    nums.iter().copied().reduce(f64::min).map(|n| json!(n)),
+                    nums.iter().copied().reduce(f64::max).map(|n| json!(n)),
+                )
+            }
+            "string" => (
+                present.iter().min().map(|s| json!(s)),
+                present.iter().max().map(|s| json!(s)),
+            ),
+            _ => (None, None),
+        };
+
+        Self {
+            name: name.to_string(),
+            kind,
+            non_null: present.len(),
+            nulls,
+            min,
+            max,
+        }
+    }
+}
+
+// ── Rendering ───────────────────────────────────────────────────────────────
+
+/// Aligned text table of `columns` over `rows`.
+fn render_table(headers: &[String], columns: &[usize], rows: &[&Vec<String>]) -> String {
+    let grid: Vec<Vec<String>> = std::iter::once(
+        columns
+            .iter()
+            .map(|&c| clip(&headers[c]))
+            .collect::<Vec<_>>(),
+    )
+    .chain(
+        rows.iter()
+            .map(|row| columns.iter().map(|&c| clip(cell(row, c))).collect()),
+    )
+    .collect();
+    render_grid(&grid)
+}
+
+fn render_stats(table: &Table, stats: &[ColumnStats]) -> String {
+    let show = |v: &Option<Value>| match v {
+        Some(Value::String(s)) => clip(s),
+        Some(v) => v.to_string(),
+        None => String::new(),
+    };
+    let mut grid = vec![
+        ["column", "type", "non_null", "nulls", "min", "max"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>(),
+    ];
+    grid.extend(stats.iter().map(|s| {
+        vec![
+            clip(&s.name),
+            s.kind.to_string(),
+            s.non_null.to_string(),
+            s.nulls.to_string(),
+            show(&s.min),
+            show(&s.max),
+        ]
+    }));
+    let mut out = render_grid(&grid);
+    out.push_str(&format!(
+        "\n{} rows{}",
+        table.rows.len(),
+        if table.truncated {
+            format!(" (file truncated at {} rows)", MAX_ROWS)
+        } else {
+            String::new()
+        }
+    ));
+    out
+}
+
+/// Render rows of cells with a separator under the first row.
+fn render_grid(grid: &[Vec<String>]) -> String {
+    let cols = grid.first().map_or(0, Vec::len);
+    let widths: Vec<usize> = (0..cols)
+        .map(|c| {
+            grid.iter()
+                .map(|r| r.get(c).map_or(0, |s| s.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |row: &Vec<String>| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, w)| format!("{:<w$}", cell, w = *w))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = String::new();
+    for (i, row) in grid.iter().enumerate() {
+        out.push_str(&line(row));
+        out.push('\n');
+        if i == 0 {
+            let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+            out.push_str(&rule.join("-+-"));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// One-line, length-capped rendering of a cell for tables.
+fn clip(s: &str) -> String {
+    let flat = s.replace(['\n', '\r'], " ");
+    if flat.chars().count() <= MAX_CELL_CHARS {
+        flat
+    } else {
+        let cut: String = flat.chars().take(MAX_CELL_CHARS - 1).collect();
+        format!("{}…", cut)
+    }
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn csv_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "Action: 'head' (first rows), 'stats' (per-column type, \
+                          min/max, null counts), 'filter' (rows matching 'where'), or \
+                          'select' (only the given columns)."
+                .into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "path".into(),
+            description: "Path to the CSV/TSV file.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "columns".into(),
+            description: "Columns to return, by header name or 1-based index. \
+                          Required for 'select'; optional projection for head/filter."
+                .into(),
+            param_type: "array".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "where".into(),
+            description: "Filter expression(s) 'column op value', all of which must \
+                          match. Ops: ==, !=, >, >=, <, <=, contains. Numeric values \
+                          compare numerically. Required for 'filter'."
+                .into(),
+            param_type: "array".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "delimiter".into(),
+            description: "Field delimiter: one character or 'tab'. Default: tab for \
+                          .tsv files, comma otherwise."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "has_header".into(),
+            description: "Whether the first row is a header. Default: true. Without \
+                          one, columns are named col1, col2, …"
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "limit".into(),
+            description: "Maximum rows to return (default 20, max 1000).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "format".into(),
+            description: "Output format: 'table' (default) or 'json'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -68,6 +68,27 @@ pub static FIND_FILES: ToolDef = ToolDef {
     execute: exec_find_files,
 };
 
+pub static MOVE_FILE: ToolDef = ToolDef {
+    name: "move_file",
+    description: "Move or rename a file or directory. If `destination` is an \
+                  existing directory, the source is moved into it. Refuses to \
+                  replace an existing destination unless `force` is true. \
+                  Prefer this over `mv` in execute_command.",
+    parameters: vec![],
+    execute: exec_move_file,
+};
+
+pub static COPY_FILE: ToolDef = ToolDef {
+    name: "copy_file",
+    description: "Copy a file, or a directory with everything in it. If \
+                  `destination` is an existing directory, the copy is placed \
+                  inside it. Keeps permissions and modification times; symlinks \
+                  are copied as links. Refuses to replace an existing destination \
+                  unless `force` is true. Prefer this over `cp` in execute_command.",
+    parameters: vec![],
+    execute: exec_copy_file,
+};
+
 pub static EXECUTE_COMMAND: ToolDef = ToolDef {
     name: "execute_command",
     description: "Execute a shell command and return output (stdout + stderr). \
@@ -116,12 +137,24 @@ pub static PROCESS: ToolDef = ToolDef {
     execute: exec_process,
 };
 
+pub static ENV_VAR: ToolDef = ToolDef {
+    name: "env_var",
+    description: "Environment variables for this session's later execute_command calls, \
+                  so they needn't be repeated on every command. Actions: 'set', 'unset', \
+                  'get' (session value, else the gateway's), 'list'. Values that look like \
+                  credentials are applied but masked in the output; prefer the secrets vault \
+                  for those.",
+    parameters: vec![],
+    execute: exec_env_var,
+};
+
 #[cfg(feature = "semantic-memory")]
 pub static MEMORY_SEARCH: ToolDef = ToolDef {
     name: "memory_search",
     description: "Semantically search MEMORY.md and memory/*.md files for relevant information. \
                   Use before answering questions about prior work, decisions, dates, people, \
-                  preferences, or todos. Returns matching snippets with file path and line numbers.",
+                  preferences, or todos. Returns matching snippets with file path and line numbers. \
+                  Narrow recall with `after`/`before` dates and a `files` glob.",
     parameters: vec![],
     execute: exec_memory_search,
 };
@@ -147,7 +180,8 @@ pub static SAVE_MEMORY: ToolDef = ToolDef {
 pub static SEARCH_HISTORY: ToolDef = ToolDef {
     name: "search_history",
     description: "Search HISTORY.md for past entries matching a pattern. Returns timestamped entries \
-                  that match the query. Use to recall when something happened or find past events.",
+                  containing every word of the query, best match first (falling back to a \
+                  substring match). Use to recall when something happened or find past events.",
     parameters: vec![],
     execute: exec_search_history,
 };
@@ -166,11 +200,23 @@ pub static CRON: ToolDef = ToolDef {
     name: "cron",
     description: "Manage scheduled jobs. Actions: status (scheduler status), list (show jobs), \
                   add (create job), update (modify job), remove (delete job), run (trigger immediately), \
-                  runs (get run history). Use for reminders and recurring tasks.",
+                  runs (get run history), export (all jobs as JSON/YAML, to a file or \
+                  inline), import (load exported jobs, validating schedules). Use for \
+                  reminders and recurring tasks.",
     parameters: vec![],
     execute: exec_cron,
 };
 
+pub static REMINDER: ToolDef = ToolDef {
+    name: "reminder",
+    description: "Schedule a message back into this conversation after a delay or at a time. \
+                  When it fires you get a user message starting with [Reminder] and can act \
+                  on it. Actions: set (message plus at or delayMinutes), list, cancel (id). \
+                  At most 10 pending reminders per conversation.",
+    parameters: vec![],
+    execute: exec_reminder,
+};
+
 pub static SESSIONS_LIST: ToolDef = ToolDef {
     name: "sessions_list",
     description: "List active sessions with optional filters. Shows main sessions and sub-agents. \
@@ -233,6 +279,24 @@ pub static APPLY_PATCH: ToolDef = ToolDef {
     execute: exec_apply_patch,
 };
 
+pub static WATCH_FILE: ToolDef = ToolDef {
+    name: "watch_file",
+    description: "Start watching a file, directory or glob in the workspace for changes. \
+                  Returns a watch_id; call watch_poll to get created/modified/deleted \
+                  events since the last poll. Watches end when the session does, or \
+                  after 30 minutes without a poll.",
+    parameters: vec![],
+    execute: exec_watch_file,
+};
+
+pub static WATCH_POLL: ToolDef = ToolDef {
+    name: "watch_poll",
+    description: "Get the changes seen by file watches since the previous poll. Polls \
+                  one watch (watch_id) or all of them; stop=true removes the watch.",
+    parameters: vec![],
+    execute: exec_watch_poll,
+};
+
 pub static SECRETS_LIST: ToolDef = ToolDef {
     name: "secrets_list",
     description: "**CHECK THIS FIRST** before asking the user for API keys or tokens! \
@@ -273,9 +337,20 @@ pub static SECRETS_SET_POLICY: ToolDef = ToolDef {
     execute: exec_secrets_stub,
 };
 
+pub static SECRETS_GENERATE: ToolDef = ToolDef {
+    name: "secrets_generate",
+    description: "Generate a strong random password or passphrase. Give a name to store \
+                  it straight into the vault; the stored value is not returned unless \
+                  reveal=true, so it never has to pass through the conversation. \
+                  Use this instead of inventing passwords or tokens yourself.",
+    parameters: vec![],
+    execute: exec_secrets_stub,
+};
+
 pub static GATEWAY: ToolDef = ToolDef {
     name: "gateway",
     description: "Manage the gateway daemon. Actions: restart (restart gateway), \
+                  pause / resume (hold every session's tool loop until resumed), \
                   config.get (get current config), config.schema (get config schema), \
                   config.apply (replace entire config), config.patch (partial config update), \
                   update.run (update gateway).",
@@ -288,6 +363,10 @@ pub static MESSAGE: ToolDef = ToolDef {
     description: "Send messages via configured channels (telegram, discord, whatsapp, signal, matrix, etc.).\n\n\
                   **Actions:** send, poll, react, thread-create, thread-reply, search, pin, edit, delete\n\n\
                   **Example:** message(action='send', channel='telegram', target='@username', message='Hello')\n\n\
+                  **Scheduling:** message(action='schedule', ..., at='09:00') sends later — `at` takes \
+                  RFC 3339, local 'YYYY-MM-DD HH:MM' or 'HH:MM' (next occurrence); or pass delayMinutes. \
+                  Scheduled messages are cron jobs: they survive restarts, appear in cron list, and are \
+                  cancelled with cron remove. Prefer this over hand-rolled cron jobs.\n\n\
                   Use for proactive notifications, cross-channel messaging, or channel-specific features \
                   like reactions, threads, and polls. The channel parameter selects which messenger to use.",
     parameters: vec![],
@@ -302,6 +381,17 @@ pub static TTS: ToolDef = ToolDef {
     execute: exec_tts,
 };
 
+pub static TRANSCRIBE: ToolDef = ToolDef {
+    name: "transcribe",
+    description: "Transcribe an audio file (voice note, meeting recording; wav, mp3, m4a, ogg, \
+                  opus, flac, webm…) to text. Runs whisper.cpp locally when installed, \
+                  otherwise the configured cloud speech-to-text API, and reports which ran. \
+                  timestamps=true adds segments with start/end seconds. Recordings over the \
+                  configured duration cap are refused.",
+    parameters: vec![],
+    execute: exec_transcribe_stub,
+};
+
 pub static IMAGE: ToolDef = ToolDef {
     name: "image",
     description: "Analyze an image using the configured image/vision model. \
@@ -615,6 +705,283 @@ pub static LOAD_STATUS: ToolDef = ToolDef {
     execute: exec_load_status_stub,
 };
 
+pub static ENV_INFO: ToolDef = ToolDef {
+    name: "env_info",
+    description: "Return a structured snapshot of the host environment: OS and version, \
+                  architecture, CPU/memory, shell, installed toolchains (rust, node, python, \
+                  go, …) with versions, container/VM detection, and the RustyClaw version. \
+                  Call this once at the start of technical tasks instead of probing with \
+                  execute_command. Cached for ten minutes; pass refresh=true to re-probe.",
+    parameters: vec![],
+    execute: exec_env_info_stub,
+};
+
+// ── Git tool ────────────────────────────────────────────────────────────────
+
+pub static GIT: ToolDef = ToolDef {
+    name: "git",
+    description: "Run git operations with structured JSON results. Actions: 'status' \
+                  (branch, ahead/behind, parsed entries), 'diff' (per-file stats + patch; \
+                  staged=true for the index), 'log' (commits with hash, author, date), \
+                  'branch' (list, or create with name), 'add' (stage paths or all), \
+                  'commit' (requires confirm=true; first call previews), 'stash' \
+                  (push/list/pop/apply/drop). Works in the workspace or repo_path. \
+                  Prefer this over execute_command for git.",
+    parameters: vec![],
+    execute: exec_git,
+};
+
+// ── Lint tool ───────────────────────────────────────────────────────────────
+
+pub static LINT: ToolDef = ToolDef {
+    name: "lint",
+    description: "Run the project's linters and return parsed diagnostics (file, line, \
+                  column, severity, code, message) instead of raw output. Picks clippy for \
+                  Cargo.toml, eslint for package.json, ruff or flake8 for Python and \
+                  shellcheck for shell scripts, whichever are installed; `linter` runs just \
+                  one. fix=true applies autofixes where the linter has them (clippy, eslint, \
+                  ruff). Prefer this over execute_command for lint checks.",
+    parameters: vec![],
+    execute: exec_lint,
+};
+
+pub static DOCKER: ToolDef = ToolDef {
+    name: "docker",
+    description: "Manage containers with docker or podman, with JSON results. Actions: 'ps' \
+                  (containers; all=true includes stopped), 'images', 'run' (image, command, \
+                  env, ports, volumes; detached by default, returns the ID), 'stop', 'rm', \
+                  'logs' (tail, since), 'exec' (command in a running container). Containers \
+                  get the [docker] memory and CPU limits; privileged mode and host networking \
+                  are refused unless allowed there.",
+    parameters: vec![],
+    execute: exec_docker_stub,
+};
+
+// ── HTTP server tool ────────────────────────────────────────────────────────
+
+pub static HTTP_SERVER: ToolDef = ToolDef {
+    name: "http_server",
+    description: "Start a short-lived HTTP listener on loopback. 'capture' records the \
+                  first request it receives (an OAuth redirect, a webhook) and closes; \
+                  'result' waits for it and returns method, path, query, headers and body. \
+                  'serve' shares a file or directory (GET only) until lifetime_secs or \
+                  max_hits runs out. 'stop' and 'list' manage listeners. Every listener \
+                  closes within an hour.",
+    parameters: vec![],
+    execute: exec_http_server_stub,
+};
+
+// ── Request replay tool ─────────────────────────────────────────────────────
+
+pub static NETWORK_REQUEST_REPLAY: ToolDef = ToolDef {
+    name: "network_request_replay",
+    description: "Replay an HTTP request from a curl command (e.g. a browser's 'Copy as \
+                  cURL') or a HAR entry, and return status, headers and body. Write \
+                  {{secret:NAME}} in the URL, a header, the body or -u to fill in a vault \
+                  credential; its value is masked in the response. Only hosts in \
+                  [request_replay] allowed_hosts can be reached and redirects are not \
+                  followed. dry_run=true shows the parsed request without sending it.",
+    parameters: vec![],
+    execute: exec_network_request_replay_stub,
+};
+
+// ── CSV tool ────────────────────────────────────────────────────────────────
+
+pub static CSV: ToolDef = ToolDef {
+    name: "csv",
+    description: "Query a CSV/TSV file without reading it whole. Actions: 'head' (first \
+                  rows), 'stats' (per-column type, min/max, null counts), 'filter' (rows \
+                  matching 'where' expressions like 'age >= 30'), 'select' (only the given \
+                  columns). Handles quoted fields, custom delimiters and headerless files. \
+                  Output as an aligned table or format='json'.",
+    parameters: vec![],
+    execute: exec_csv,
+};
+
+// ── Parse log tool ──────────────────────────────────────────────────────────
+
+pub static PARSE_LOG: ToolDef = ToolDef {
+    name: "parse_log",
+    description: "Triage a log file of any size without reading it whole. Returns line \
+                  counts by format and level, the time span, the most repeated messages \
+                  (numbers and ids folded together) and the latest matching lines. Filter \
+                  by minimum 'level', a regex 'pattern' and a 'since'/'until' window; use \
+                  'tail' to read only the end of a big file. Understands JSON lines, \
+                  syslog and plain text logs, mixed in one file.",
+    parameters: vec![],
+    execute: exec_parse_log,
+};
+
+// ── Text stats tool ─────────────────────────────────────────────────────────
+
+pub static TEXT_STATS: ToolDef = ToolDef {
+    name: "text_stats",
+    description: "Inspect a text file in one call, like wc, head and tail together. Returns \
+                  line, word, byte and character counts, the longest line, the encoding \
+                  and line-ending style (LF, CRLF or mixed), and the first 'head' and last \
+                  'tail' lines (10 each by default, at most 200). Give a regex 'pattern' \
+                  to also count its matches. Binary files are detected and not read.",
+    parameters: vec![],
+    execute: exec_text_stats,
+};
+
+// ── Encode tool ─────────────────────────────────────────────────────────────
+
+pub static ENCODE: ToolDef = ToolDef {
+    name: "encode",
+    description: "Encode, decode or hash a string or file (max 10 MB) without shelling out. \
+                  Actions: 'base64_encode', 'base64_decode', 'hex_encode', 'hex_decode', \
+                  'url_encode', 'url_decode', 'hash' (algorithm md5, sha1, sha256 or sha512; \
+                  hex digest). Prefer this over execute_command with base64/md5sum/shasum.",
+    parameters: vec![],
+    execute: exec_encode,
+};
+
+// ── Regex tool ──────────────────────────────────────────────────────────────
+
+pub static REGEX: ToolDef = ToolDef {
+    name: "regex",
+    description: "Regular expressions over a string or a file (max 10 MB), without sed/grep \
+                  quoting. Actions: 'find_all' (matches with line, position and capture \
+                  groups), 'replace' (with $1 / ${name} in the replacement; returns the new \
+                  text, or writes it back with in_place), 'extract' (named groups as JSON \
+                  records). Matching is linear-time; overly complex patterns are refused.",
+    parameters: vec![],
+    execute: exec_regex,
+};
+
+// ── QR tool ─────────────────────────────────────────────────────────────────
+
+pub static QR: ToolDef = ToolDef {
+    name: "qr",
+    description: "Generate or read QR codes. action='encode' with text: format='png' \
+                  (default) writes an image to output_path (default qr_codes/ in the \
+                  workspace) and returns it as a media reference; format='unicode' returns \
+                  the code in block characters for a terminal. action='decode' with path \
+                  returns the text of the QR code(s) in an image.",
+    parameters: vec![],
+    execute: exec_qr,
+};
+
+// ── Image transform tool ────────────────────────────────────────────────────
+
+pub static IMAGE_TRANSFORM: ToolDef = ToolDef {
+    name: "image_transform",
+    description: "Resize, crop or convert an image without a model or ImageMagick. \
+                  action='info' returns width, height and format; 'resize' scales to width \
+                  and/or height (aspect ratio kept unless keep_aspect=false); 'crop' cuts \
+                  the width x height region at x, y; 'convert' changes the format (png, \
+                  jpeg, gif, webp, bmp). Writes to output_path (default beside the input) \
+                  and returns the file as a media reference for the image tool or a message.",
+    parameters: vec![],
+    execute: exec_image_transform,
+};
+
+// ── Screenshot annotate tool ────────────────────────────────────────────────
+
+pub static SCREENSHOT_ANNOTATE: ToolDef = ToolDef {
+    name: "screenshot_annotate",
+    description: "Mark up an image, typically a screenshot, to show the user where something \
+                  is. Draws rectangles (x, y, width, height, as returned by ocr_screen with \
+                  boxes), arrows (x1, y1 to x2, y2) and text labels; coordinates are pixels \
+                  or percentages such as \"50%\". Writes a copy (default \
+                  <name>-annotated.png) and returns it as a media reference to send.",
+    parameters: vec![],
+    execute: exec_screenshot_annotate,
+};
+
+// ── Weather tool ────────────────────────────────────────────────────────────
+
+pub static WEATHER: ToolDef = ToolDef {
+    name: "weather",
+    description: "Current conditions and a daily forecast (up to 5 days) for a place name \
+                  ('Portland, Maine') or latitude/longitude. Returns JSON with temperature, \
+                  feels-like, humidity, wind, conditions, and per-day min/max temperature and \
+                  precipitation, with units. Use this instead of web_fetch for weather.",
+    parameters: vec![],
+    execute: exec_weather_stub,
+};
+
+// ── Time tool ───────────────────────────────────────────────────────────────
+
+pub static TIME: ToolDef = ToolDef {
+    name: "time",
+    description: "Time and timezones with DST handled from the tz database. action='now' \
+                  gives the current time in a zone; 'convert' moves a time between zones; \
+                  'diff' gives the duration between two times; 'parse' turns text such as \
+                  'next friday 3pm' or 'in 2 hours' into a timestamp. Results include \
+                  RFC 3339, Unix time, UTC offset and a human-readable form.",
+    parameters: vec![],
+    execute: exec_time,
+};
+
+// ── Timezone meeting tool ───────────────────────────────────────────────────
+
+pub static TIMEZONE_MEETING: ToolDef = ToolDef {
+    name: "timezone_meeting",
+    description: "Find meeting times that fall within everyone's working hours. Give each \
+                  participant's timezone, optionally with a name and hours (default \
+                  09:00-17:00), and a date. Returns the overlapping windows with UTC times \
+                  and each participant's local time and UTC offset on that date, so DST \
+                  changes are accounted for.",
+    parameters: vec![],
+    execute: exec_timezone_meeting,
+};
+
+// ── Color tool ──────────────────────────────────────────────────────────────
+
+pub static COLOR: ToolDef = ToolDef {
+    name: "color",
+    description: "Work with colors for theming and accessibility. action='convert' gives a \
+                  color as hex, RGB and HSL; 'contrast' gives the WCAG contrast ratio of a \
+                  foreground on a background and whether it passes AA and AAA for normal \
+                  and large text; 'palette' builds shades of a base color plus its \
+                  complement, analogous and triadic colors. Accepts hex, rgb(), hsl() or \
+                  CSS basic names. Results are JSON with a 'human' summary line.",
+    parameters: vec![],
+    execute: exec_color,
+};
+
+// ── Validate tool ───────────────────────────────────────────────────────────
+
+pub static VALIDATE: ToolDef = ToolDef {
+    name: "validate",
+    description: "Check that a JSON, YAML or TOML file (or inline content) parses and, when a \
+                  JSON Schema is given as a file path or inline, that it conforms. Syntax \
+                  errors and schema violations come back in one shape: kind, JSON Pointer \
+                  path of the failing value, line, column and message. Use it to check \
+                  config files before relying on them.",
+    parameters: vec![],
+    execute: exec_validate,
+};
+
+// ── DNS tool ────────────────────────────────────────────────────────────────
+
+pub static DNS: ToolDef = ToolDef {
+    name: "dns",
+    description: "Look up DNS records for a domain: A, AAAA, MX, TXT, CNAME, NS or SOA. Pass \
+                  an IP address as the name for a reverse (PTR) lookup. Returns structured \
+                  records with their TTLs and the response status (NOERROR, NXDOMAIN, ...). \
+                  Queries the system nameserver unless 'server' names another one. Use this \
+                  instead of running dig or nslookup.",
+    parameters: vec![],
+    execute: exec_dns,
+};
+
+// ── Math tool ───────────────────────────────────────────────────────────────
+
+pub static MATH: ToolDef = ToolDef {
+    name: "math",
+    description: "Evaluate an arithmetic expression precisely, or convert a value between \
+                  units. 'eval' supports + - * / % ^, parentheses, common functions (sqrt, \
+                  ln, log, sin, round, ...) and pi/e, and keeps exact fractions where it \
+                  can. 'convert' handles length, mass, temperature, data sizes and \
+                  currencies (ECB reference rates). Use this instead of doing arithmetic \
+                  in your head or with execute_command.",
+    parameters: vec![],
+    execute: exec_math,
+};
+
 // ── Managed service tools ───────────────────────────────────────────────────
 
 pub static SERVICE_LIST: ToolDef = ToolDef {
@@ -686,6 +1053,17 @@ pub static SYSTEM_MONITOR: ToolDef = ToolDef {
     execute: exec_system_monitor,
 };
 
+pub static PROCESS_TREE: ToolDef = ToolDef {
+    name: "process_tree",
+    description: "Inspect and manage host processes by hierarchy. 'list' shows parent/child \
+                  trees (filter by `name` or `pid`), 'info' gives details for one pid, and \
+                  'kill' sends a signal (TERM by default), with tree=true to include every \
+                  descendant. Processes you didn't start need confirm=true; pid 1 and the \
+                  gateway itself are never signalled.",
+    parameters: vec![],
+    execute: exec_process_tree,
+};
+
 pub static BATTERY_HEALTH: ToolDef = ToolDef {
     name: "battery_health",
     description: "Report battery status including charge level, cycle count, capacity, \
@@ -729,10 +1107,23 @@ pub static SCREENSHOT: ToolDef = ToolDef {
     execute: exec_screenshot,
 };
 
+pub static OCR_SCREEN: ToolDef = ToolDef {
+    name: "ocr_screen",
+    description: "Read the text currently on screen: takes a screenshot (same path, \
+                  region and delay options as screenshot) and runs tesseract OCR on it. \
+                  Returns the text, and with boxes='word' or 'line' the bounding box and \
+                  confidence of each word or line in screenshot pixels. Fails with a clear \
+                  message when tesseract is not installed.",
+    parameters: vec![],
+    execute: exec_ocr_screen,
+};
+
 pub static CLIPBOARD: ToolDef = ToolDef {
     name: "clipboard",
     description: "Read from or write to the system clipboard. Uses pbcopy/pbpaste \
-                  on macOS or xclip/xsel on Linux.",
+                  on macOS or xclip/xsel on Linux. When clipboard history is enabled, \
+                  'history' lists recent copies (newest first, secrets redacted) and \
+                  'history_get' returns one in full.",
     parameters: vec![],
     execute: exec_clipboard,
 };
@@ -949,6 +1340,54 @@ pub static PDF: ToolDef = ToolDef {
     execute: exec_pdf,
 };
 
+pub static PDF_EXTRACT: ToolDef = ToolDef {
+    name: "pdf_extract",
+    description: "Extract from selected pages of a PDF. pages='12', '3-5' or '1,4,9-'. \
+                  mode='text' (default) returns per-page text, 'tables' returns rows of \
+                  cells from column-aligned layouts, 'images' exports embedded images to \
+                  files and returns them as media references. Results include page \
+                  count, title and author, and name the next page when output is \
+                  capped. Requires poppler-utils (pdfinfo, pdftotext, pdfimages).",
+    parameters: vec![],
+    execute: exec_pdf_extract,
+};
+
+pub static PDF_CREATE: ToolDef = ToolDef {
+    name: "pdf_create",
+    description: "Render markdown or HTML to a PDF file at output_path and return it as a \
+                  media reference. Options: page_size (a3, a4, a5, letter, legal), \
+                  landscape, margin_mm, title, and header/footer text where {page} and \
+                  {pages} become the page number and count. Prints through headless \
+                  Chrome when available, otherwise uses a built-in renderer that \
+                  covers headings, lists, emphasis, quotes, code blocks and tables.",
+    parameters: vec![],
+    execute: exec_pdf_create_stub,
+};
+
+pub static EXTRACT_ARCHIVE_MEMBER: ToolDef = ToolDef {
+    name: "extract_archive_member",
+    description: "Read one file from a zip, tar, tar.gz or tar.zst archive without \
+                  extracting it. Omit member to list the archive's members with sizes. \
+                  Returns the member as text, or base64 when it is binary, up to \
+                  max_bytes (default 1 MiB). Member names with '..' or an absolute \
+                  path are refused. Use it to inspect a config inside a large backup.",
+    parameters: vec![],
+    execute: exec_extract_archive_member,
+};
+
+// ── Convert document tool ───────────────────────────────────────────────────
+
+pub static CONVERT_DOCUMENT: ToolDef = ToolDef {
+    name: "convert_document",
+    description: "Convert a document file between formats: markdown, html, docx, rst, latex, \
+                  odt, epub, plain text, and pdf as output. Uses pandoc when installed; \
+                  without it, markdown -> html and html -> markdown still work and other \
+                  conversions report how to install pandoc. Formats default from the file \
+                  extensions; returns the output path and a media reference.",
+    parameters: vec![],
+    execute: exec_convert_document_stub,
+};
+
 // ── Swarm tools ─────────────────────────────────────────────────────────────
 
 pub static SWARM_CREATE: ToolDef = ToolDef {
@@ -1012,6 +1451,15 @@ pub static TODO: ToolDef = ToolDef {
     execute: exec_todo,
 };
 
+// ── Key/value store ─────────────────────────────────────────────────────────
+
+pub static KV_STORE: ToolDef = ToolDef {
+    name: "kv_store",
+    description: "Durable key/value scratch space for structured working state (counters,                   partial results, flags) that must survive between turns, instead of                   writing files. Actions: 'get', 'set' (value is any JSON), 'delete', 'list'.                   Keys belong to this session unless global=true.",
+    parameters: vec![],
+    execute: exec_kv_store,
+};
+
 // ── Skill curator ───────────────────────────────────────────────────────────
 
 pub static SKILL_CURATOR: ToolDef = ToolDef {
@@ -1038,6 +1486,94 @@ pub static WEB_EXTRACT: ToolDef = ToolDef {
     execute: exec_web_extract_stub,
 };
 
+// ── Scraping tools ──────────────────────────────────────────────────────────
+
+pub static EXTRACT_LINKS: ToolDef = ToolDef {
+    name: "extract_links",
+    description: "List every link on a web page as JSON: absolute URL, anchor text and rel, \
+                  deduplicated, in page order. Works on a URL or on HTML/markdown you already \
+                  fetched (pass base_url to resolve relative links). scope narrows to \
+                  internal or external links.",
+    parameters: vec![],
+    execute: exec_extract_links_stub,
+};
+
+pub static EXTRACT_STRUCTURED: ToolDef = ToolDef {
+    name: "extract_structured",
+    description: "Read a web page's structured metadata as JSON: title, language, canonical \
+                  URL, Open Graph and Twitter card properties, JSON-LD blocks and other meta \
+                  tags. Works on a URL or on HTML you already have. Cheaper than reading \
+                  the page when you need the article's title, author, dates or image.",
+    parameters: vec![],
+    execute: exec_extract_structured_stub,
+};
+
+// ── Translation tool ────────────────────────────────────────────────────────
+
+pub static TRANSLATE: ToolDef = ToolDef {
+    name: "translate",
+    description: "Translate text into another language using the configured model. \
+                  Returns JSON with the translated `text` and the `source_lang` (detected \
+                  when not given). Markdown structure is kept and fenced code blocks are \
+                  passed through untouched; long inputs are chunked automatically. Prefer \
+                  this over translating inline in your reply.",
+    parameters: vec![],
+    execute: exec_translate_stub,
+};
+
+pub static TRANSLATE_FILE: ToolDef = ToolDef {
+    name: "translate_file",
+    description: "Translate a subtitle (.srt, .vtt), Markdown (.md) or gettext (.po) file \
+                  with the configured model and write the result beside it (e.g. \
+                  film.fr.srt). Only the text is translated: cue indices and timings, code \
+                  blocks, links and msgids are kept as they were, and .po entries that \
+                  already have a msgstr are left alone. Returns JSON with the output `path` \
+                  and how many `segments` were translated.",
+    parameters: vec![],
+    execute: exec_translate_file_stub,
+};
+
+// ── Proofreading tool ───────────────────────────────────────────────────────
+
+pub static PROOFREAD: ToolDef = ToolDef {
+    name: "proofread",
+    description: "Proofread text (inline or from a file) with the configured model. Returns \
+                  JSON with `corrections` — a list of {original, suggestion, reason} — and \
+                  the `corrected` text. Markdown is kept and code is never changed; long \
+                  inputs are chunked. The file itself is not modified. Set engine='spell' \
+                  for an offline spelling-only check with aspell/hunspell.",
+    parameters: vec![],
+    execute: exec_proofread,
+};
+
+// ── Web page summary tool ───────────────────────────────────────────────────
+
+pub static SUMMARIZE_URL: ToolDef = ToolDef {
+    name: "summarize_url",
+    description: "Fetch a web page (same address checks as web_fetch) and summarise it with \
+                  the configured model. Navigation, headers and footers are dropped first; \
+                  long pages are summarised in parts and then combined. Returns JSON with \
+                  the `summary`, a list of `key_points` and, with include_links=true, the \
+                  page's `links`. `length` is short, medium (default) or long. Prefer this \
+                  over web_fetch when only the gist of a page is needed.",
+    parameters: vec![],
+    execute: exec_summarize_url,
+};
+
+// ── Remote command tool ─────────────────────────────────────────────────────
+
+pub static SSH: ToolDef = ToolDef {
+    name: "ssh",
+    description: "Run a shell command on a remote host over SSH and return its stdout, \
+                  stderr and exit code. `host` is an alias from the [ssh_hosts] config — \
+                  only configured hosts can be reached, and their keys or passwords come \
+                  from the vault, so never ask the user for them. Use instead of \
+                  execute_command with an inline ssh invocation. Set `sudo` for root \
+                  commands on hosts that allow it.",
+    parameters: vec![],
+    execute: exec_ssh_stub,
+};
+
 // ── Image generation tool ───────────────────────────────────────────────────
 
 #[cfg(feature = "image-gen")]
@@ -1053,5 +1589,5 @@ pub static IMAGE_GENERATE: ToolDef = ToolDef {
 // Re-export parameter functions from params module
 pub use params::*;
 
-// Re-export provider-specific tool-schema formatters.
-pub use schema::{tools_anthropic, tools_google, tools_openai};
+// Re-export provider-specific tool-schema formatters and the parameter lookup.
+pub use schema::{tool_params, tools_anthropic, tools_google, tools_openai};
@@ -0,0 +1,621 @@
+//! DNS lookups with structured results.
+//!
+//! Queries go straight to a nameserver over UDP (retried over TCP when the
+//! answer is truncated) with a small built-in resolver, so records come
+//! back the same way on every platform instead of being scraped from `dig`
+//! or `nslookup`.  The nameserver is the one named in the call, else the
+//! first `nameserver` in `/etc/resolv.conf`, else Cloudflare's `1.1.1.1`.
+//!
+//! Supported types: A, AAAA, MX, TXT, CNAME, NS, SOA and PTR.  An IP
+//! address as the name is a reverse lookup (PTR on `in-addr.arpa` /
+//! `ip6.arpa`).  Every record carries its TTL.
+
+use serde_json::{Value, json};
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket};
+use std::path::Path;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+use super::ToolParam;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 5;
+const MAX_TIMEOUT_SECS: u64 = 30;
+const FALLBACK_SERVER: &str = "1.1.1.1";
+const DNS_PORT: u16 = 53;
+/// UDP payload size advertised with EDNS(0); the usual safe value.
+const EDNS_UDP_SIZE: u16 = 1232;
+const CLASS_IN: u16 = 1;
+const TYPE_OPT: u16 = 41;
+
+/// Record types the tool can ask for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordType {
+    A,
+    Ns,
+    Cname,
+    Soa,
+    Ptr,
+    Mx,
+    Txt,
+    Aaaa,
+}
+
+impl RecordType {
+    const ALL: [RecordType; 8] = [
+        Self::A,
+        Self::Aaaa,
+        Self::Mx,
+        Self::Txt,
+        Self::Cname,
+        Self::Ns,
+        Self::Soa,
+        Self::Ptr,
+    ];
+
+    pub fn code(self) -> u16 {
+        match self {
+            Self::A => 1,
+            Self::Ns => 2,
+            Self::Cname => 5,
+            Self::Soa => 6,
+            Self::Ptr => 12,
+            Self::Mx => 15,
+            Self::Txt => 16,
+            Self::Aaaa => 28,
+        }
+    }
+
+    pub fn from_code(code: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.code() == code)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::A => "A",
+            Self::Ns => "NS",
+            Self::Cname => "CNAME",
+            Self::Soa => "SOA",
+            Self::Ptr => "PTR",
+            Self::Mx => "MX",
+            Self::Txt => "TXT",
+            Self::Aaaa => "AAAA",
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown record type: '{}'. Use: A, AAAA, MX, TXT, CNAME, NS, SOA, PTR",
+                    s
+                )
+            })
+    }
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `dns` tool.
+#[instrument(skip(args, _workspace_dir))]
+pub fn exec_dns(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| "Missing required parameter: name".to_string())?;
+
+    // An address is always a reverse lookup.
+    let (qname, qtype) = match name.parse::<IpAddr>() {
+        Ok(ip) => (reverse_name(ip), RecordType::Ptr),
+        Err(_) => {
+            let qtype = match args.get("type").and_then(|v| v.as_str()) {
+                Some(t) => RecordType::parse(t)?,
+                None => RecordType::A,
+            };
+            (name.trim_end_matches('.').to_string(), qtype)
+        }
+    };
+    validate_name(&qname)?;
+
+    let server = match args.get("server").and_then(|v| v.as_str()) {
+        Some(s) => parse_server(s)?,
+        None => system_nameserver(),
+    };
+    let timeout = Duration::from_secs(
+        args.get("timeout_secs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS),
+    );
+
+    debug!(name = %qname, qtype = qtype.name(), %server, "DNS query");
+    let response = query(&qname, qtype, server, timeout)?;
+    Ok(json!({
+        "name": qname,
+        "type": qtype.name(),
+        "server": server.to_string(),
+        "status": rcode_name(response.rcode),
+        "authoritative": response.authoritative,
+        "records": response.answers.iter().map(Record::to_json).collect::<Vec<_>>(),
+    })
+    .to_string())
+}
+
+// ── Names and servers ───────────────────────────────────────────────────────
+
+/// The PTR name for an address: `4.4.8.8.in-addr.arpa`, or nibbles under
+/// `ip6.arpa` for IPv6.
+pub fn reverse_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, d] = v4.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+        }
+        IpAddr::V6(v6) => {
+            let mut labels: Vec<String> = v6
+                .octets()
+                .iter()
+                .flat_map(|byte| [byte >> 4, byte & 0xf])
+                .map(|nibble| format!("{:x}", nibble))
+                .collect();
+            labels.reverse();
+            format!("{}.ip6.arpa", labels.join("."))
+        }
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.len() > 253 {
+        return Err(format!("Name too long: '{}'", name));
+    }
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(format!("Invalid name: '{}'", name));
+        }
+    }
+    Ok(())
+}
+
+/// `1.1.1.1`, `1.1.1.1:5353`, `2606:4700::1111` or `[2606:4700::1111]:53`.
+fn parse_server(s: &str) -> Result<SocketAddr, String> {
+    let s = s.trim();
+    if let Ok(addr) = s.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    s.parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, DNS_PORT))
+        .map_err(|_| {
+            format!(
+                "Invalid server address: '{}' (use an IP, optionally with :port)",
+                s
+            )
+        })
+}
+
+/// The first usable nameserver in `/etc/resolv.conf`, or the fallback.
+fn system_nameserver() -> SocketAddr {
+    std::fs::read_to_string("/etc/resolv.conf")
+        .ok()
+        .and_then(|conf| nameserver_from_resolv_conf(&conf))
+        .unwrap_or_else(|| SocketAddr::new(FALLBACK_SERVER.parse().unwrap(), DNS_PORT))
+}
+
+fn nameserver_from_resolv_conf(conf: &str) -> Option<SocketAddr> {
+    conf.lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver"))
+        .filter_map(|rest| {
+            // Drop a zone index (`fe80::1%eth0`); std can't parse it.
+            let addr = rest.trim().split('%').next()?;
+            addr.parse::<IpAddr>().ok()
+        })
+        .map(|ip| SocketAddr::new(ip, DNS_PORT))
+        .next()
+}
+
+fn rcode_name(rcode: u8) -> String {
+    match rcode {
+        0 => "NOERROR".into(),
+        1 => "FORMERR".into(),
+        2 => "SERVFAIL".into(),
+        3 => "NXDOMAIN".into(),
+        4 => "NOTIMP".into(),
+        5 => "REFUSED".into(),
+        other => format!("RCODE{}", other),
+    }
+}
+
+// ── Wire format ─────────────────────────────────────────────────────────────
+
+/// A decoded answer record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub name: String,
+    pub rtype: u16,
+    pub ttl: u32,
+    pub data: RecordData,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordData {
+    Address(IpAddr),
+    /// CNAME, NS and PTR.
+    Target(String),
+    Mx {
+        preference: u16,
+        exchange: String,
+    },
+    Txt(Vec<String>),
+    Soa {
+        mname: String,
+        rname: String,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    /// A type the tool doesn't decode, as hex.
+    Other(String),
+}
+
+impl Record {
+    fn to_json(&self) -> Value {
+        let rtype = RecordType::from_code(self.rtype)
+            .map(|t| t.name().to_string())
+            .unwrap_or_else(|| format!("TYPE{}", self.rtype));
+        let mut out = json!({ "name": self.name, "type": rtype, "ttl": self.ttl });
+        match &self.data {
+            RecordData::Address(ip) => out["address"] = json!(ip.to_string()),
+            RecordData::Target(target) => out["target"] = json!(target),
+            RecordData::Mx {
+                preference,
+                exchange,
+            } => {
+                out["preference"] = json!(preference);
+                out["exchange"] = json!(exchange);
+            }
+            RecordData::Txt(strings) => {
+                out["text"] = json!(strings.concat());
+                out["strings"] = json!(strings);
+            }
+            RecordData::Soa {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => {
+                out["mname"] = json!(mname);
+                out["rname"] = json!(rname);
+                out["serial"] = json!(serial);
+                out["refresh"] = json!(refresh);
+                out["retry"] = json!(retry);
+                out["expire"] = json!(expire);
+                out["minimum"] = json!(minimum);
+            }
+            RecordData::Other(hex) => out["data"] = json!(hex),
+        }
+        out
+    }
+}
+
+/// The parts of a response the tool reports.
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub rcode: u8,
+    pub authoritative: bool,
+    pub truncated: bool,
+    pub answers: Vec<Record>,
+}
+
+/// Encode a recursive query for `name` with an EDNS(0) OPT record.
+pub fn encode_query(id: u16, name: &str, qtype: RecordType) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(512);
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(&0x0100u16.to_be_bytes()); // RD
+    msg.extend_from_slice(&1u16.to_be_bytes()); // QDCOUNT
+    msg.extend_from_slice(&0u16.to_be_bytes()); // ANCOUNT
+    msg.extend_from_slice(&0u16.to_be_bytes()); // NSCOUNT
+    msg.extend_from_slice(&1u16.to_be_bytes()); // ARCOUNT
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        msg.push(label.len() as u8);
+        msg.extend_from_slice(label.as_bytes());
+    }
+    msg.push(0);
+    msg.extend_from_slice(&qtype.code().to_be_bytes());
+    msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+    // OPT: root name, type, UDP size in the class field, no flags or data.
+    msg.push(0);
+    msg.extend_from_slice(&TYPE_OPT.to_be_bytes());
+    msg.extend_from_slice(&EDNS_UDP_SIZE.to_be_bytes());
+    msg.extend_from_slice(&[0; 6]);
+    msg
+}
+
+/// Decode a response to query `id`.
+pub fn decode_response(id: u16, msg: &[u8]) -> Result<Response, String> {
+    let mut reader = Reader { msg, pos: 0 };
+    if reader.u16()? != id {
+        return Err("DNS response ID doesn't match the query".to_string());
+    }
+    let flags = reader.u16()?;
+    if flags & 0x8000 == 0 {
+        return Err("DNS message is not a response".to_string());
+    }
+    let qdcount = reader.u16()?;
+    let ancount = reader.u16()?;
+    reader.u16()?; // NSCOUNT
+    reader.u16()?; // ARCOUNT
+    for _ in 0..qdcount {
+        reader.name()?;
+        reader.take(4)?;
+    }
+    let mut answers = Vec::with_capacity(ancount as usize);
+    for _ in 0..ancount {
+        let name = reader.name()?;
+        let rtype = reader.u16()?;
+        reader.u16()?; // class
+        let ttl = reader.u32()?;
+        let len = reader.u16()? as usize;
+        let end = reader.pos + len;
+        if end > msg.len() {
+            return Err("Truncated DNS record".to_string());
+        }
+        let data = reader.rdata(rtype, end)?;
+        reader.pos = end;
+        answers.push(Record {
+            name,
+            rtype,
+            ttl,
+            data,
+        });
+    }
+    Ok(Response {
+        rcode: (flags & 0x000f) as u8,
+        authoritative: flags & 0x0400 != 0,
+        truncated: flags & 0x0200 != 0,
+        answers,
+    })
+}
+
+struct Reader<'a> {
+    msg: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .msg
+            .get(self.pos..self.pos + n)
+            .ok_or_else(|| "Truncated DNS message".to_string())?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// A possibly compressed name; the position ends up after it in the
+    /// record, not after whatever a pointer led to.
+    fn name(&mut self) -> Result<String, String> {
+        let mut labels: Vec<String> = Vec::new();
+        let mut pos = self.pos;
+        let mut resume = None;
+        // Each pointer must go backwards, which rules out loops.
+        let mut limit = pos;
+        loop {
+            let len = *self
+                .msg
+                .get(pos)
+                .ok_or_else(|| "Truncated DNS name".to_string())?;
+            match len {
+                0 => {
+                    pos += 1;
+                    break;
+                }
+                l if l & 0xc0 == 0xc0 => {
+                    let low = *self
+                        .msg
+                        .get(pos + 1)
+                        .ok_or_else(|| "Truncated DNS name".to_string())?;
+                    let target = (((l & 0x3f) as usize) << 8) | low as usize;
+                    if target >= limit {
+                        return Err("Bad compression pointer in DNS name".to_string());
+                    }
+                    resume.get_or_insert(pos + 2);
+                    limit = target;
+                    pos = target;
+                }
+                l if l & 0xc0 == 0 => {
+                    let start = pos + 1;
+                    let label = self
+                        .msg
+                        .get(start..start + l as usize)
+                        .ok_or_else(|| "Truncated DNS name".to_string())?;
+                    labels.push(String::from_utf8_lossy(label).into_owned());
+                    pos = start + l as usize;
+                }
+                _ => return Err("Unsupported DNS label type".to_string()),
+            }
+        }
+        self.pos = resume.unwrap_or(pos);
+        Ok(labels.join("."))
+    }
+
+    fn rdata(&mut self, rtype: u16, end: usize) -> Result<RecordData, String> {
+        let len = end - self.pos;
+        let data = match RecordType::from_code(rtype) {
+            Some(RecordType::A) if len == 4 => {
+                let b = self.take(4)?;
+                RecordData::Address(IpAddr::V4(Ipv4Addr::new(b[0], b[1], b[2], b[3])))
+            }
+            Some(RecordType::Aaaa) if len == 16 => {
+                let octets: [u8; 16] = self.take(16)?.try_into().unwrap();
+                RecordData::Address(IpAddr::V6(Ipv6Addr::from(octets)))
+            }
+            Some(RecordType::Cname | RecordType::Ns | RecordType::Ptr) => {
+                RecordData::Target(self.name()?)
+            }
+            Some(RecordType::Mx) => RecordData::Mx {
+                preference: self.u16()?,
+                exchange: self.name()?,
+            },
+            Some(RecordType::Txt) => {
+                let mut strings = Vec::new();
+                while self.pos < end {
+                    let n = self.u8()? as usize;
+                    strings.push(String::from_utf8_lossy(self.take(n)?).into_owned());
+                }
+                RecordData::Txt(strings)
+            }
+            Some(RecordType::Soa) => RecordData::Soa {
+                mname: self.name()?,
+                rname: self.name()?,
+                serial: self.u32()?,
+                refresh: self.u32()?,
+                retry: self.u32()?,
+                expire: self.u32()?,
+                minimum: self.u32()?,
+            },
+            _ => RecordData::Other(
+                self.take(len)?
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect(),
+            ),
+        };
+        if self.pos > end {
+            return Err("DNS record data overruns its length".to_string());
+        }
+        Ok(data)
+    }
+}
+
+// ── Transport ───────────────────────────────────────────────────────────────
+
+/// Ask `server` about `name`.  Every network step is bounded by `timeout`.
+pub fn query(
+    name: &str,
+    qtype: RecordType,
+    server: SocketAddr,
+    timeout: Duration,
+) -> Result<Response, String> {
+    let id = query_id();
+    let request = encode_query(id, name, qtype);
+    let response = decode_response(id, &query_udp(&request, server, timeout)?)?;
+    if !response.truncated {
+        return Ok(response);
+    }
+    debug!(%server, "DNS answer truncated, retrying over TCP");
+    decode_response(id, &query_tcp(&request, server, timeout)?)
+}
+
+fn query_id() -> u16 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    (nanos ^ (nanos >> 16) ^ std::process::id()) as u16
+}
+
+fn timed_out(server: SocketAddr, e: std::io::Error) -> String {
+    match e.kind() {
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => {
+            format!("DNS query to {} timed out", server)
+        }
+        _ => format!("DNS query to {} failed: {}", server, e),
+    }
+}
+
+fn query_udp(request: &[u8], server: SocketAddr, timeout: Duration) -> Result<Vec<u8>, String> {
+    let bind: SocketAddr = if server.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(bind).map_err(|e| format!("Failed to open UDP socket: {}", e))?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| socket.connect(server))
+        .and_then(|_| socket.send(request))
+        .map_err(|e| timed_out(server, e))?;
+    let mut buf = vec![0u8; 65535];
+    let n = socket.recv(&mut buf).map_err(|e| timed_out(server, e))?;
+    buf.truncate(n);
+    Ok(buf)
+}
+
+fn query_tcp(request: &[u8], server: SocketAddr, timeout: Duration) -> Result<Vec<u8>, String> {
+    let mut stream =
+        TcpStream::connect_timeout(&server, timeout).map_err(|e| timed_out(server, e))?;
+    let mut framed = (request.len() as u16).to_be_bytes().to_vec();
+    framed.extend_from_slice(request);
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .and_then(|_| stream.write_all(&framed))
+        .map_err(|e| timed_out(server, e))?;
+    let mut len = [0u8; 2];
+    stream
+        .read_exact(&mut len)
+        .map_err(|e| timed_out(server, e))?;
+    let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+    stream
+        .read_exact(&mut buf)
+        .map_err(|e| timed_out(server, e))?;
+    Ok(buf)
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn dns_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "name".into(),
+            description: "Domain to look up, e.g. 'example.com'. An IP address does a \
+                          reverse (PTR) lookup."
+                .into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "type".into(),
+            description: "Record type: A (default), AAAA, MX, TXT, CNAME, NS, SOA or PTR.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "server".into(),
+            description: "Nameserver IP to ask, optionally with a port ('9.9.9.9', \
+                          '127.0.0.1:5353'). Default: the system's first nameserver, \
+                          else 1.1.1.1."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "timeout_secs".into(),
+            description: "Seconds to wait for an answer (1-30). Default: 5.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,955 @@
+//! Docker tool: container lifecycle with structured results.
+//!
+//! Wraps the `docker` CLI — or `podman`, which accepts the same commands —
+//! so the agent gets parsed container and image lists instead of table
+//! text.  The CLI is invoked with an argument vector (no shell) and every
+//! call has a timeout.
+//!
+//! `run` is held to `[docker]`: each container gets the configured memory
+//! and CPU limits (a call may only lower them), and privileged containers,
+//! mounts of the engine socket, and host networking are refused unless the
+//! config allows them.  Bind mounts of the credentials directory are always
+//! refused.
+
+use serde_json::{Map, Value, json};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::env_var::{is_valid_name, session_env};
+use super::helpers::{VAULT_ACCESS_DENIED, expand_tilde, is_protected_tree, resolve_path, sandbox};
+use super::sysadmin::which_first_async;
+use crate::config::DockerConfig;
+
+const ACTIONS: &str = "ps, images, run, stop, rm, logs, exec";
+
+/// CLI timeout when the call does not set one.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Longest CLI timeout a call may ask for.
+pub const MAX_TIMEOUT_SECS: u64 = 600;
+
+/// Lines returned by `logs` when the call does not set `tail`.
+const DEFAULT_LOG_LINES: u64 = 200;
+
+const MAX_LOG_LINES: u64 = 5000;
+
+/// Bytes kept from each of stdout and stderr.
+const MAX_OUTPUT_BYTES: usize = 100_000;
+
+/// Exit status the CLI uses when `run` fails before the container starts.
+const RUN_ENGINE_FAILURE: i32 = 125;
+
+/// Socket files whose mount hands a container control of the engine.
+const ENGINE_SOCKETS: &[&str] = &["docker.sock", "podman.sock"];
+
+/// `[docker]` settings, set once at gateway startup.
+static CONFIG: OnceLock<DockerConfig> = OnceLock::new();
+
+/// Called once from the gateway to apply `[docker]`.
+pub fn set_docker_config(config: DockerConfig) {
+    let _ = CONFIG.set(config);
+}
+
+fn docker_config() -> DockerConfig {
+    CONFIG.get().cloned().unwrap_or_default()
+}
+
+/// The container CLI in use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Engine {
+    binary: String,
+    podman: bool,
+}
+
+impl Engine {
+    fn name(&self) -> &'static str {
+        if self.podman { "podman" } else { "docker" }
+    }
+
+    /// `--format` value that makes `ps` and `images` print JSON.
+    fn json_format(&self) -> &'static str {
+        if self.podman { "json" } else { "{{json .}}" }
+    }
+}
+
+/// Find the configured CLI, or docker then podman on `PATH`.
+async fn detect_engine(config: &DockerConfig) -> Result<Engine, String> {
+    let binary = match config.binary.as_deref() {
+        Some(binary) => which_first_async(&[binary])
+            .await
+            .ok_or_else(|| format!("'{}' ([docker] binary) is not installed.", binary))?,
+        None => which_first_async(&["docker", "podman"])
+            .await
+            .ok_or("Neither docker nor podman is installed.")?,
+    };
+    let podman = Path::new(&binary)
+        .file_name()
+        .is_some_and(|name| name == "podman");
+    Ok(Engine { binary, podman })
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Sync stub for the static ToolDef.
+pub fn exec_docker_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("docker requires async execution".into())
+}
+
+/// Execute the `docker` tool.
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub async fn exec_docker_async(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: action")?;
+    tracing::Span::current().record("action", action);
+
+    if !matches!(
+        action,
+        "ps" | "images" | "run" | "stop" | "rm" | "logs" | "exec"
+    ) {
+        return Err(format!(
+            "Unknown action: '{}'. Valid actions: {}",
+            action, ACTIONS
+        ));
+    }
+
+    let config = docker_config();
+    let timeout_secs = args
+        .get("timeout")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(1, MAX_TIMEOUT_SECS);
+    // Check the request before looking for an engine so a refused `run`
+    // says why, whether or not docker is installed.
+    let plan = match action {
+        "run" => Some(plan_run(args, &config, workspace_dir)?),
+        _ => None,
+    };
+    let engine = detect_engine(&config).await?;
+    debug!(engine = engine.name(), "Executing docker tool");
+    let cli = Cli {
+        engine: &engine,
+        workspace_dir,
+        timeout_secs,
+    };
+
+    match (action, plan) {
+        ("run", Some(plan)) => docker_run(&cli, plan).await,
+        ("ps", _) => docker_ps(&cli, args).await,
+        ("images", _) => docker_images(&cli).await,
+        ("stop", _) => docker_stop(&cli, args).await,
+        ("rm", _) => docker_rm(&cli, args).await,
+        ("logs", _) => docker_logs(&cli, args).await,
+        _ => docker_exec(&cli, args).await,
+    }
+}
+
+// ── Actions ─────────────────────────────────────────────────────────────────
+
+async fn docker_ps(cli: &Cli<'_>, args: &Value) -> Result<String, String> {
+    let mut argv = vec!["ps".to_string()];
+    if bool_arg(args, "all") {
+        argv.push("--all".into());
+    }
+    argv.extend(["--format".into(), cli.engine.json_format().into()]);
+    let output = cli.run(&argv, &[]).await?.checked(cli.engine, "ps")?;
+    let containers = parse_containers(&output.stdout);
+    Ok(json!({
+        "engine": cli.engine.name(),
+        "count": containers.len(),
+        "containers": containers,
+    })
+    .to_string())
+}
+
+async fn docker_images(cli: &Cli<'_>) -> Result<String, String> {
+    let argv = vec![
+        "images".to_string(),
+        "--format".into(),
+        cli.engine.json_format().into(),
+    ];
+    let output = cli.run(&argv, &[]).await?.checked(cli.engine, "images")?;
+    let images = parse_images(&output.stdout);
+    Ok(json!({
+        "engine": cli.engine.name(),
+        "count": images.len(),
+        "images": images,
+    })
+    .to_string())
+}
+
+async fn docker_run(cli: &Cli<'_>, plan: RunPlan) -> Result<String, String> {
+    let output = cli.run(&plan.argv, &plan.env).await?;
+    if plan.detach || output.exit_code == Some(RUN_ENGINE_FAILURE) {
+        let output = output.checked(cli.engine, "run")?;
+        let id = output.stdout.trim();
+        return Ok(json!({
+            "action": "run",
+            "container": short_id(id),
+            "image": plan.image,
+            "detached": true,
+            "limits": { "memory": plan.memory, "cpus": plan.cpus },
+        })
+        .to_string());
+    }
+    let mut result = output.to_json();
+    result["action"] = json!("run");
+    result["image"] = json!(plan.image);
+    Ok(result.to_string())
+}
+
+async fn docker_stop(cli: &Cli<'_>, args: &Value) -> Result<String, String> {
+    let container = container_arg(args)?;
+    let mut argv = vec!["stop".to_string()];
+    if let Some(grace) = args.get("grace_secs").and_then(|v| v.as_u64()) {
+        argv.extend(["--time".into(), grace.to_string()]);
+    }
+    argv.push(container.to_string());
+    cli.run(&argv, &[]).await?.checked(cli.engine, "stop")?;
+    Ok(json!({ "action": "stop", "container": container, "stopped": true }).to_string())
+}
+
+async fn docker_rm(cli: &Cli<'_>, args: &Value) -> Result<String, String> {
+    let container = container_arg(args)?;
+    let force = bool_arg(args, "force");
+    let mut argv = vec!["rm".to_string()];
+    if force {
+        argv.push("--force".into());
+    }
+    argv.push(container.to_string());
+    cli.run(&argv, &[]).await?.checked(cli.engine, "rm")?;
+    Ok(json!({
+        "action": "rm",
+        "container": container,
+        "removed": true,
+        "forced": force,
+    })
+    .to_string())
+}
+
+async fn docker_logs(cli: &Cli<'_>, args: &Value) -> Result<String, String> {
+    let container = container_arg(args)?;
+    let tail = args
+        .get("tail")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_LOG_LINES)
+        .clamp(1, MAX_LOG_LINES);
+    let mut argv = vec!["logs".to_string(), "--tail".into(), tail.to_string()];
+    if let Some(since) = args.get("since").and_then(|v| v.as_str()) {
+        argv.extend(["--since".into(), since.to_string()]);
+    }
+    if bool_arg(args, "timestamps") {
+        argv.push("--timestamps".into());
+    }
+    argv.push(container.to_string());
+    // The container's stderr arrives on the CLI's stderr, so both are kept.
+    let output = cli.run(&argv, &[]).await?.checked(cli.engine, "logs")?;
+    let mut result = json!({
+        "container": container,
+        "tail": tail,
+        "stdout": output.stdout,
+        "stderr": output.stderr,
+    });
+    if output.truncated {
+        result["truncated"] = json!(true);
+    }
+    Ok(result.to_string())
+}
+
+async fn docker_exec(cli: &Cli<'_>, args: &Value) -> Result<String, String> {
+    let container = container_arg(args)?;
+    let command = command_arg(args)?.ok_or("Missing required parameter: command")?;
+    let env = env_arg(args)?;
+    let mut argv = vec!["exec".to_string()];
+    if let Some(workdir) = args.get("workdir").and_then(|v| v.as_str()) {
+        argv.extend(["--workdir".into(), workdir.to_string()]);
+    }
+    for (key, _) in &env {
+        argv.extend(["--env".into(), key.clone()]);
+    }
+    argv.push(container.to_string());
+    argv.extend(command);
+    let mut result = cli.run(&argv, &env).await?.to_json();
+    result["container"] = json!(container);
+    Ok(result.to_string())
+}
+
+// ── run policy ──────────────────────────────────────────────────────────────
+
+/// A `run` request that passed the `[docker]` checks.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RunPlan {
+    pub argv: Vec<String>,
+    /// Variables passed by name (`--env KEY`) and set on the CLI process,
+    /// so values stay out of the argument list.
+    pub env: Vec<(String, String)>,
+    pub image: String,
+    pub detach: bool,
+    pub memory: String,
+    pub cpus: f64,
+}
+
+/// Check a `run` request against `config` and build its arguments.
+pub(crate) fn plan_run(
+    args: &Value,
+    config: &DockerConfig,
+    workspace_dir: &Path,
+) -> Result<RunPlan, String> {
+    let image = args
+        .get("image")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: image")?;
+    check_word("image", image)?;
+    let detach = args.get("detach").and_then(|v| v.as_bool()).unwrap_or(true);
+
+    let privileged = bool_arg(args, "privileged");
+    if privileged && !config.allow_privileged {
+        return Err(
+            "Privileged containers are not allowed. Set allow_privileged = true in [docker] \
+             to permit them."
+                .to_string(),
+        );
+    }
+
+    let network = args.get("network").and_then(|v| v.as_str());
+    if let Some(network) = network {
+        check_word("network", network)?;
+        if network == "host" && !config.allow_host_network {
+            return Err(
+                "Host networking is not allowed. Set allow_host_network = true in [docker] \
+                 to permit it."
+                    .to_string(),
+            );
+        }
+    }
+
+    let memory_limit = parse_memory(&config.memory)
+        .ok_or_else(|| format!("Invalid [docker] memory limit '{}'", config.memory))?;
+    let memory = match args.get("memory").and_then(|v| v.as_str()) {
+        Some(requested) => {
+            let bytes = parse_memory(requested).ok_or_else(|| {
+                format!("Invalid memory '{}': use e.g. '512m' or '2g'", requested)
+            })?;
+            if bytes > memory_limit {
+                return Err(format!(
+                    "Memory '{}' exceeds the [docker] limit of {}",
+                    requested, config.memory
+                ));
+            }
+            requested.to_string()
+        }
+        None => config.memory.clone(),
+    };
+    let cpus = match args.get("cpus").and_then(|v| v.as_f64()) {
+        Some(requested) if requested <= 0.0 => {
+            return Err(format!("Invalid cpus {}: must be positive", requested));
+        }
+        Some(requested) if requested > config.cpus => {
+            return Err(format!(
+                "cpus {} exceeds the [docker] limit of {}",
+                requested, config.cpus
+            ));
+        }
+        Some(requested) => requested,
+        None => config.cpus,
+    };
+
+    let mut argv = vec!["run".to_string()];
+    if detach {
+        argv.push("--detach".into());
+    }
+    if bool_arg(args, "remove") {
+        argv.push("--rm".into());
+    }
+    argv.extend([
+        "--memory".into(),
+        memory.clone(),
+        "--cpus".into(),
+        cpus.to_string(),
+    ]);
+    if let Some(name) = args.get("name").and_then(|v| v.as_str()) {
+        check_word("name", name)?;
+        argv.extend(["--name".into(), name.to_string()]);
+    }
+    if let Some(network) = network {
+        argv.extend(["--network".into(), network.to_string()]);
+    }
+    if privileged {
+        argv.push("--privileged".into());
+    }
+    for port in string_list(args, "ports") {
+        check_word("port", &port)?;
+        argv.extend(["--publish".into(), port]);
+    }
+    for volume in string_list(args, "volumes") {
+        let volume = check_volume(&volume, config, workspace_dir)?;
+        argv.extend(["--volume".into(), volume]);
+    }
+    let env = env_arg(args)?;
+    for (key, _) in &env {
+        argv.extend(["--env".into(), key.clone()]);
+    }
+    if let Some(workdir) = args.get("workdir").and_then(|v| v.as_str()) {
+        argv.extend(["--workdir".into(), workdir.to_string()]);
+    }
+    argv.push(image.to_string());
+    if let Some(command) = command_arg(args)? {
+        argv.extend(command);
+    }
+
+    Ok(RunPlan {
+        argv,
+        env,
+        image: image.to_string(),
+        detach,
+        memory,
+        cpus,
+    })
+}
+
+/// Resolve the host side of a `host:container[:options]` mount.
+///
+/// Named volumes pass through; bind mounts are made absolute against the
+/// workspace and refused when they contain the credentials directory, fall
+/// outside the sandbox, or name the engine socket while privileged
+/// containers are disallowed.
+fn check_volume(spec: &str, config: &DockerConfig, workspace_dir: &Path) -> Result<String, String> {
+    let Some((host, rest)) = spec.split_once(':') else {
+        return Err(format!(
+            "Invalid volume '{}': use 'host_path:container_path[:ro]' or 'name:container_path'",
+            spec
+        ));
+    };
+    check_word("volume", host)?;
+    if !(host.starts_with('/') || host.starts_with('.') || host.starts_with('~')) {
+        return Ok(spec.to_string());
+    }
+    let path = resolve_path(workspace_dir, &expand_tilde(host).to_string_lossy());
+    if is_protected_tree(&path) {
+        warn!(path = %path.display(), "Attempted docker mount of protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    let is_socket = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| ENGINE_SOCKETS.contains(&name));
+    if is_socket && !config.allow_privileged {
+        return Err(format!(
+            "Mounting {} gives the container control of the engine. Set \
+             allow_privileged = true in [docker] to permit it.",
+            path.display()
+        ));
+    }
+    Ok(format!("{}:{}", path.display(), rest))
+}
+
+/// Parse a memory size such as `512m`, `2g` or `1.5GB` into bytes.
+pub(crate) fn parse_memory(text: &str) -> Option<u64> {
+    let lower = text.trim().to_ascii_lowercase();
+    let lower = lower.strip_suffix('b').unwrap_or(&lower);
+    let (number, scale) = match lower.char_indices().last()? {
+        (i, 'k') => (&lower[..i], 1u64 << 10),
+        (i, 'm') => (&lower[..i], 1 << 20),
+        (i, 'g') => (&lower[..i], 1 << 30),
+        (i, 't') => (&lower[..i], 1 << 40),
+        _ => (lower, 1),
+    };
+    let value: f64 = number.parse().ok()?;
+    (value.is_finite() && value > 0.0).then(|| (value * scale as f64) as u64)
+}
+
+// ── CLI ─────────────────────────────────────────────────────────────────────
+
+/// How to invoke the engine for one tool call.
+struct Cli<'a> {
+    engine: &'a Engine,
+    workspace_dir: &'a Path,
+    timeout_secs: u64,
+}
+
+impl Cli<'_> {
+    /// Run `<engine> <argv>` with the session environment plus `env`.
+    async fn run(&self, argv: &[String], env: &[(String, String)]) -> Result<CliOutput, String> {
+        let subcommand = argv.first().map(String::as_str).unwrap_or("");
+        debug!(subcommand, "Running container CLI");
+        let mut command = tokio::process::Command::new(&self.engine.binary);
+        command
+            .args(argv)
+            .current_dir(self.workspace_dir)
+            .envs(session_env())
+            .envs(env.iter().cloned())
+            .stdin(Stdio::null())
+            .kill_on_drop(true);
+        let output = tokio::time::timeout(Duration::from_secs(self.timeout_secs), command.output())
+            .await
+            .map_err(|_| {
+                format!(
+                    "{} {} timed out after {}s",
+                    self.engine.name(),
+                    subcommand,
+                    self.timeout_secs
+                )
+            })?
+            .map_err(|e| format!("Failed to run {}: {}", self.engine.binary, e))?;
+
+        let (stdout, out_truncated) = capped(&output.stdout);
+        let (stderr, err_truncated) = capped(&output.stderr);
+        Ok(CliOutput {
+            stdout,
+            stderr,
+            exit_code: output.status.code(),
+            truncated: out_truncated || err_truncated,
+        })
+    }
+}
+
+/// Output of one CLI invocation.
+struct CliOutput {
+    stdout: String,
+    stderr: String,
+    exit_code: Option<i32>,
+    truncated: bool,
+}
+
+impl CliOutput {
+    /// Fail unless the CLI exited successfully.
+    fn checked(self, engine: &Engine, subcommand: &str) -> Result<Self, String> {
+        if self.exit_code == Some(0) {
+            return Ok(self);
+        }
+        let stderr = self.stderr.trim();
+        Err(if stderr.is_empty() {
+            format!(
+                "{} {} failed (exit code {:?})",
+                engine.name(),
+                subcommand,
+                self.exit_code
+            )
+        } else {
+            format!("{} {} failed: {}", engine.name(), subcommand, stderr)
+        })
+    }
+
+    /// Exit code and output of a command run in a container.
+    fn to_json(&self) -> Value {
+        let mut result = json!({
+            "exit_code": self.exit_code,
+            "stdout": self.stdout,
+            "stderr": self.stderr,
+        });
+        if self.truncated {
+            result["truncated"] = json!(true);
+        }
+        result
+    }
+}
+
+/// Decode `bytes`, keeping at most [`MAX_OUTPUT_BYTES`].
+fn capped(bytes: &[u8]) -> (String, bool) {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= MAX_OUTPUT_BYTES {
+        return (text.into_owned(), false);
+    }
+    let mut end = MAX_OUTPUT_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), true)
+}
+
+// ── Parsing ─────────────────────────────────────────────────────────────────
+
+/// Parse `ps`/`images` JSON: one object per line (docker) or one array
+/// (podman).  Lines that are not JSON objects are skipped.
+fn parse_records(raw: &str) -> Vec<Map<String, Value>> {
+    let raw = raw.trim();
+    if raw.starts_with('[') {
+        return match serde_json::from_str::<Vec<Value>>(raw) {
+            Ok(items) => items
+                .into_iter()
+                .filter_map(|item| match item {
+                    Value::Object(obj) => Some(obj),
+                    _ => None,
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+    }
+    raw.lines()
+        .filter_map(|line| match serde_json::from_str(line.trim()) {
+            Ok(Value::Object(obj)) => Some(obj),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The first of `keys` present in `obj`, as text.  Arrays are joined with
+/// `", "`.
+fn field(obj: &Map<String, Value>, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|key| obj.get(*key).filter(|v| !v.is_null()))
+        .map(text)
+        .unwrap_or_default()
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(items) => items.iter().map(text).collect::<Vec<_>>().join(", "),
+        Value::Object(obj) => port_text(obj),
+        Value::Null => String::new(),
+    }
+}
+
+/// Format a podman port mapping the way docker prints it.
+fn port_text(obj: &Map<String, Value>) -> String {
+    let host_ip = field(obj, &["host_ip"]);
+    let host_port = field(obj, &["host_port"]);
+    let container_port = field(obj, &["container_port"]);
+    let protocol = field(obj, &["protocol"]);
+    let host = if host_ip.is_empty() {
+        host_port
+    } else {
+        format!("{}:{}", host_ip, host_port)
+    };
+    format!("{}->{}/{}", host, container_port, protocol)
+}
+
+fn short_id(id: &str) -> String {
+    id.strip_prefix("sha256:")
+        .unwrap_or(id)
+        .chars()
+        .take(12)
+        .collect()
+}
+
+/// Parse `ps --format` output into container entries.
+pub(crate) fn parse_containers(raw: &str) -> Vec<Value> {
+    parse_records(raw)
+        .iter()
+        .map(|c| {
+            json!({
+                "id": short_id(&field(c, &["ID", "Id"])),
+                "name": field(c, &["Names", "Name"]),
+                "image": field(c, &["Image"]),
+                "command": field(c, &["Command"]),
+                "state": field(c, &["State"]).to_lowercase(),
+                "status": field(c, &["Status"]),
+                "created": field(c, &["CreatedAt", "Created"]),
+                "ports": field(c, &["Ports"]),
+            })
+        })
+        .collect()
+}
+
+/// Parse `images --format` output into image entries.
+pub(crate) fn parse_images(raw: &str) -> Vec<Value> {
+    parse_records(raw)
+        .iter()
+        .map(|image| {
+            let (repository, tag) = match image.get("Repository") {
+                Some(repo) => (text(repo), field(image, &["Tag"])),
+                None => {
+                    let tags = image
+                        .get("RepoTags")
+                        .or_else(|| image.get("Names"))
+                        .and_then(|v| v.as_array())
+                        .and_then(|tags| tags.first())
+                        .map(text)
+                        .unwrap_or_default();
+                    split_image_ref(&tags)
+                }
+            };
+            let size = match image.get("Size") {
+                Some(Value::Number(n)) => n.as_u64().map(human_size).unwrap_or_default(),
+                _ => field(image, &["Size"]),
+            };
+            json!({
+                "id": short_id(&field(image, &["ID", "Id"])),
+                "repository": repository,
+                "tag": tag,
+                "size": size,
+                "created": field(image, &["CreatedSince", "CreatedAt", "Created"]),
+            })
+        })
+        .collect()
+}
+
+/// Split `registry:5000/name:tag` into repository and tag.
+fn split_image_ref(reference: &str) -> (String, String) {
+    match reference.rsplit_once(':') {
+        Some((repo, tag)) if !tag.contains('/') => (repo.to_string(), tag.to_string()),
+        _ if reference.is_empty() => ("<none>".to_string(), "<none>".to_string()),
+        _ => (reference.to_string(), "latest".to_string()),
+    }
+}
+
+/// Sizes in decimal units, as the docker CLI prints them.
+fn human_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "kB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        return format!("{}B", bytes);
+    }
+    // Three significant digits: 7.8MB, 77.8MB, 778MB.
+    let decimals = match value {
+        v if v >= 100.0 => 0,
+        v if v >= 10.0 => 1,
+        _ => 2,
+    };
+    let number = format!("{:.*}", decimals, value);
+    let number = if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &number
+    };
+    format!("{}{}", number, UNITS[unit])
+}
+
+// ── Arguments ───────────────────────────────────────────────────────────────
+
+fn bool_arg(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// Accept either an array of strings or a single string.
+fn string_list(args: &Value, key: &str) -> Vec<String> {
+    match args.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        Some(Value::String(s)) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Reject values the CLI would read as an option.
+fn check_word(what: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() || value.starts_with('-') {
+        return Err(format!("Invalid {}: '{}'", what, value));
+    }
+    Ok(())
+}
+
+fn container_arg(args: &Value) -> Result<&str, String> {
+    let container = args
+        .get("container")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: container")?;
+    check_word("container", container)?;
+    Ok(container)
+}
+
+/// `command` as an argument vector: an array is used as-is, a string runs
+/// under `sh -c`.
+fn command_arg(args: &Value) -> Result<Option<Vec<String>>, String> {
+    match args.get("command") {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) if s.trim().is_empty() => Ok(None),
+        Some(Value::String(s)) => Ok(Some(vec!["sh".into(), "-c".into(), s.clone()])),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| "command array must contain only strings".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|argv| (!argv.is_empty()).then_some(argv)),
+        Some(_) => Err("command must be a string or an array of strings".into()),
+    }
+}
+
+/// `env` as name/value pairs.
+fn env_arg(args: &Value) -> Result<Vec<(String, String)>, String> {
+    let Some(env) = args.get("env") else {
+        return Ok(Vec::new());
+    };
+    let env = env
+        .as_object()
+        .ok_or("env must be an object of NAME: value pairs")?;
+    env.iter()
+        .map(|(name, value)| {
+            if !is_valid_name(name) {
+                return Err(format!("Invalid variable name '{}'", name));
+            }
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+pub fn docker_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "Action: 'ps', 'images', 'run', 'stop', 'rm', 'logs' or 'exec'.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "container".into(),
+            description: "Container name or ID (stop, rm, logs, exec).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "image".into(),
+            description: "Image to run, e.g. 'postgres:16' (run).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "command".into(),
+            description: "Command for run/exec: an array of arguments, or a string run with \
+                          'sh -c'."
+                .into(),
+            param_type: "array".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "name".into(),
+            description: "Container name (run).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "env".into(),
+            description: "Environment variables as an object of NAME: value (run, exec).".into(),
+            param_type: "object".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "ports".into(),
+            description: "Published ports, e.g. ['8080:80'] (run).".into(),
+            param_type: "array".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "volumes".into(),
+            description: "Mounts, e.g. ['./data:/data', 'cache:/root/.cache'] (run). Relative \
+                          paths are resolved against the workspace."
+                .into(),
+            param_type: "array".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "workdir".into(),
+            description: "Working directory inside the container (run, exec).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "network".into(),
+            description: "Network for run, e.g. 'none' or 'bridge'. 'host' needs \
+                          allow_host_network in [docker]."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "memory".into(),
+            description: "Memory limit for run, e.g. '512m'. Defaults to, and may not exceed, \
+                          the [docker] limit."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "cpus".into(),
+            description: "CPU limit for run. Defaults to, and may not exceed, the [docker] \
+                          limit."
+                .into(),
+            param_type: "number".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "privileged".into(),
+            description: "Run privileged. Refused unless allow_privileged is set in [docker]."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "detach".into(),
+            description: "Run in the background and return the container ID (default true). \
+                          When false, waits and returns the output."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "remove".into(),
+            description: "Remove the container when it exits (run).".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "all".into(),
+            description: "Include stopped containers (ps).".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "force".into(),
+            description: "Remove a running container (rm).".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "grace_secs".into(),
+            description: "Seconds to wait before killing the container (stop).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "tail".into(),
+            description: format!(
+                "Log lines to return (logs, default {}, max {}).",
+                DEFAULT_LOG_LINES, MAX_LOG_LINES
+            ),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "since".into(),
+            description: "Only logs newer than this, e.g. '10m' or an RFC 3339 time (logs).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "timestamps".into(),
+            description: "Prefix log lines with timestamps (logs).".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "timeout".into(),
+            description: format!(
+                "Seconds to wait for the CLI (default {}, max {}).",
+                DEFAULT_TIMEOUT_SECS, MAX_TIMEOUT_SECS
+            ),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,228 @@
+//! Encode tool: base64, hex and URL encoding, plus md5/sha1/sha256/sha512
+//! hashes, over a string or a file.
+//!
+//! Saves the agent from shelling out to `base64`, `xxd` or `md5` /
+//! `md5sum`, whose flags and output differ between macOS and Linux.  Files
+//! go through the same vault-boundary and sandbox checks as `read_file`
+//! and may be at most [`MAX_FILE_BYTES`].
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use serde_json::Value;
+use sha2::Digest;
+use std::io::Read;
+use std::path::Path;
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{
+    VAULT_ACCESS_DENIED, is_protected_path, open_file_read_safe, resolve_path, sandbox,
+};
+
+/// Largest file accepted as input.
+pub const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+const ACTIONS: &str = "base64_encode, base64_decode, hex_encode, hex_decode, url_encode, \
+                       url_decode, hash";
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `encode` tool.
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub fn exec_encode(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: action".to_string())?;
+    tracing::Span::current().record("action", action);
+    if !ACTIONS.split(", ").any(|a| a == action) {
+        return Err(format!("Unknown action: '{}'. Use: {}", action, ACTIONS));
+    }
+
+    let input = read_input(args, workspace_dir)?;
+    let url_safe = args
+        .get("url_safe")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    match action {
+        "base64_encode" => Ok(if url_safe {
+            URL_SAFE.encode(&input)
+        } else {
+            STANDARD.encode(&input)
+        }),
+        "base64_decode" => base64_decode(&input, url_safe).map(bytes_to_text),
+        "hex_encode" => Ok(to_hex(&input)),
+        "hex_decode" => hex_decode(&input).map(bytes_to_text),
+        "url_encode" => Ok(urlencoding::encode_binary(&input).into_owned()),
+        "url_decode" => Ok(bytes_to_text(
+            urlencoding::decode_binary(&input).into_owned(),
+        )),
+        "hash" => {
+            let algorithm = args
+                .get("algorithm")
+                .and_then(|v| v.as_str())
+                .unwrap_or("sha256");
+            hash(algorithm, &input)
+        }
+        other => Err(format!("Unknown action: '{}'. Use: {}", other, ACTIONS)),
+    }
+}
+
+/// The bytes to work on: the `input` string, or the contents of `path`.
+fn read_input(args: &Value, workspace_dir: &Path) -> Result<Vec<u8>, String> {
+    let input = args.get("input").and_then(|v| v.as_str());
+    let path = args.get("path").and_then(|v| v.as_str());
+    match (input, path) {
+        (Some(text), None) => Ok(text.as_bytes().to_vec()),
+        (None, Some(path)) => read_file_capped(&resolve_path(workspace_dir, path)),
+        (Some(_), Some(_)) => Err("Give either input or path, not both".to_string()),
+        (None, None) => Err("Missing required parameter: input or path".to_string()),
+    }
+}
+
+fn read_file_capped(path: &Path) -> Result<Vec<u8>, String> {
+    if is_protected_path(path) {
+        warn!(path = %path.display(), "Attempted encode access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(path)?;
+    }
+    let (file, _) = open_file_read_safe(path)
+        .map_err(|e| format!("Failed to open file '{}': {}", path.display(), e))?;
+    let mut bytes = Vec::new();
+    // One byte past the cap tells a file at the limit from a larger one.
+    file.take(MAX_FILE_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read file '{}': {}", path.display(), e))?;
+    if bytes.len() as u64 > MAX_FILE_BYTES {
+        return Err(format!(
+            "File '{}' is larger than {} MB",
+            path.display(),
+            MAX_FILE_BYTES / (1024 * 1024)
+        ));
+    }
+    debug!(path = %path.display(), bytes = bytes.len(), "Read encode input");
+    Ok(bytes)
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn hash(algorithm: &str, data: &[u8]) -> Result<String, String> {
+    match algorithm.to_ascii_lowercase().replace('-', "").as_str() {
+        "md5" => Ok(to_hex(&md5::Md5::digest(data))),
+        "sha1" => Ok(to_hex(&sha1::Sha1::digest(data))),
+        "sha256" => Ok(to_hex(&sha2::Sha256::digest(data))),
+        "sha512" => Ok(to_hex(&sha2::Sha512::digest(data))),
+        other => Err(format!(
+            "Unknown algorithm: '{}'. Use: md5, sha1, sha256, sha512",
+            other
+        )),
+    }
+}
+
+/// Decode standard or URL-safe base64, padded or not, ignoring whitespace.
+fn base64_decode(input: &[u8], url_safe: bool) -> Result<Vec<u8>, String> {
+    let compact: Vec<u8> = input
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    let trimmed = compact.strip_suffix(b"==").unwrap_or(&compact);
+    let trimmed = trimmed.strip_suffix(b"=").unwrap_or(trimmed);
+    // Characters only the URL-safe alphabet uses pick it automatically.
+    let engine = if url_safe || trimmed.iter().any(|b| matches!(b, b'-' | b'_')) {
+        &URL_SAFE_NO_PAD
+    } else {
+        &STANDARD_NO_PAD
+    };
+    engine
+        .decode(trimmed)
+        .map_err(|e| format!("Invalid base64: {}", e))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decode hex digits, ignoring whitespace and a leading `0x`.
+fn hex_decode(input: &[u8]) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = input
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    let digits = digits
+        .strip_prefix(b"0x")
+        .or_else(|| digits.strip_prefix(b"0X"))
+        .unwrap_or(&digits);
+    if digits.len() % 2 != 0 {
+        return Err("Invalid hex: odd number of digits".to_string());
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|s| u8::from_str_radix(s, 16).ok())
+                .ok_or_else(|| format!("Invalid hex digits: '{}'", String::from_utf8_lossy(pair)))
+        })
+        .collect()
+}
+
+/// Decoded output as text; binary data is shown as hex instead.
+fn bytes_to_text(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => {
+            let bytes = e.into_bytes();
+            format!(
+                "(binary data, {} bytes, shown as hex)\n{}",
+                bytes.len(),
+                to_hex(&bytes)
+            )
+        }
+    }
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn encode_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "One of: base64_encode, base64_decode, hex_encode, hex_decode, \
+                          url_encode, url_decode, hash."
+                .into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "input".into(),
+            description: "Text to encode, decode or hash. Give this or path.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "path".into(),
+            description: "File to read the input from instead (max 10 MB).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "algorithm".into(),
+            description: "Hash algorithm for 'hash': md5, sha1, sha256 or sha512. \
+                          Default: sha256."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "url_safe".into(),
+            description: "Use the URL-safe base64 alphabet ('-' and '_'). Default: false.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+    ]
+}
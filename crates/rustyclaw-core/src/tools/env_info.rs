@@ -0,0 +1,243 @@
+//! Environment snapshot tool: OS, hardware, shell, and toolchains at a glance.
+//!
+//! Saves the agent from spending turns on `uname`, `cat /etc/os-release`,
+//! `rustc --version` and friends at the start of technical tasks. The
+//! snapshot is shared by every session in the process and cached for
+//! [`CACHE_TTL`] because it rarely changes; pass `refresh=true` to
+//! re-probe sooner.
+
+use serde_json::{Value, json};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use sysinfo::System;
+use tracing::{debug, instrument};
+
+use super::ToolParam;
+
+/// Upper bound for a single `--version` probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Toolchains to detect: (display name, binary, version argument).
+const TOOLCHAINS: &[(&str, &str, &str)] = &[
+    ("rust", "rustc", "--version"),
+    ("cargo", "cargo", "--version"),
+    ("node", "node", "--version"),
+    ("npm", "npm", "--version"),
+    ("python", "python3", "--version"),
+    ("go", "go", "version"),
+    ("java", "java", "-version"),
+    ("docker", "docker", "--version"),
+    ("git", "git", "--version"),
+];
+
+/// How long a snapshot is reused before the next call re-probes.
+pub const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Last snapshot and when it was taken, shared by all sessions.
+static ENV_INFO_CACHE: Mutex<Option<(Instant, Value)>> = Mutex::new(None);
+
+fn lock_cache() -> MutexGuard<'static, Option<(Instant, Value)>> {
+    ENV_INFO_CACHE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ── Tool executor (async) ───────────────────────────────────────────────────
+
+/// Return a structured snapshot of the host environment (async).
+#[instrument(skip(args, _workspace_dir))]
+pub async fn exec_env_info_async(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let refresh = args
+        .get("refresh")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    if !refresh
+        && let Some((taken, cached)) = lock_cache().as_ref()
+        && taken.elapsed() < CACHE_TTL
+    {
+        debug!("Returning cached environment snapshot");
+        return Ok(cached.to_string());
+    }
+
+    debug!(refresh, "Probing host environment");
+    let snapshot = probe_environment().await;
+    *lock_cache() = Some((Instant::now(), snapshot.clone()));
+    Ok(snapshot.to_string())
+}
+
+/// Sync stub for the static ToolDef.
+pub fn exec_env_info_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("env_info requires async execution".into())
+}
+
+// ── Probes ──────────────────────────────────────────────────────────────────
+
+async fn probe_environment() -> Value {
+    let mut sys = System::new();
+    sys.refresh_memory();
+    sys.refresh_cpu_all();
+
+    let cpus = sys.cpus();
+    let cpu_brand = cpus
+        .first()
+        .map(|c| c.brand().trim().to_string())
+        .unwrap_or_else(|| "unknown".into());
+
+    let shell = std::env::var("SHELL").ok().filter(|s| !s.is_empty());
+
+    let probes = TOOLCHAINS
+        .iter()
+        .map(|&(name, bin, arg)| async move { (name, probe_version(bin, arg).await) });
+    let mut toolchains = serde_json::Map::new();
+    for (name, version) in futures_util::future::join_all(probes).await {
+        // Absent toolchains are omitted rather than reported as errors.
+        if let Some(version) = version {
+            toolchains.insert(name.into(), json!(version));
+        }
+    }
+
+    json!({
+        "os": {
+            "name": System::name().unwrap_or_else(|| "unknown".into()),
+            "version": System::os_version().unwrap_or_else(|| "unknown".into()),
+            "kernel": System::kernel_version().unwrap_or_else(|| "unknown".into()),
+            "family": std::env::consts::FAMILY,
+        },
+        "arch": std::env::consts::ARCH,
+        "hostname": System::host_name().unwrap_or_else(|| "unknown".into()),
+        "cpu": {
+            "brand": cpu_brand,
+            "physical_cores": System::physical_core_count(),
+            "logical_cores": cpus.len(),
+        },
+        "memory": {
+            "total_bytes": sys.total_memory(),
+            "available_bytes": sys.available_memory(),
+        },
+        "shell": shell,
+        "toolchains": toolchains,
+        "container": detect_container(),
+        "virtualization": detect_virtualization(),
+        "rustyclaw_version": env!("CARGO_PKG_VERSION"),
+    })
+}
+
+/// Run `<bin> <arg>` with a time bound and return the first non-empty line
+/// of output, or `None` when the binary is missing, fails, or times out.
+async fn probe_version(bin: &str, arg: &str) -> Option<String> {
+    let path = which::which(bin).ok()?;
+    let child = tokio::process::Command::new(path)
+        .arg(arg)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = match tokio::time::timeout(PROBE_TIMEOUT, child).await {
+        Ok(Ok(output)) if output.status.success() => output,
+        Ok(_) => return None,
+        Err(_) => {
+            debug!(bin, "Version probe timed out");
+            return None;
+        }
+    };
+
+    // Some tools (java, older python) print their version on stderr.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    first_line(&stdout).or_else(|| first_line(&stderr))
+}
+
+fn first_line(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(str::to_string)
+}
+
+/// Best-effort container runtime detection.
+fn detect_container() -> Option<String> {
+    if Path::new("/.dockerenv").exists() {
+        return Some("docker".into());
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some("podman".into());
+    }
+    if std::env::var_os("KUBERNETES_SERVICE_HOST").is_some() {
+        return Some("kubernetes".into());
+    }
+    if let Ok(kind) = std::env::var("container") {
+        if !kind.is_empty() {
+            return Some(kind);
+        }
+    }
+    std::fs::read_to_string("/proc/1/cgroup")
+        .ok()
+        .and_then(|cgroup| classify_cgroup(&cgroup))
+        .map(str::to_string)
+}
+
+/// Map the contents of `/proc/1/cgroup` to a container runtime name.
+pub(crate) fn classify_cgroup(cgroup: &str) -> Option<&'static str> {
+    let lower = cgroup.to_lowercase();
+    if lower.contains("kubepods") {
+        Some("kubernetes")
+    } else if lower.contains("docker") {
+        Some("docker")
+    } else if lower.contains("containerd") {
+        Some("containerd")
+    } else if lower.contains("lxc") {
+        Some("lxc")
+    } else {
+        None
+    }
+}
+
+/// Best-effort hypervisor detection from DMI identifiers (Linux only).
+fn detect_virtualization() -> Option<String> {
+    let read = |p: &str| std::fs::read_to_string(p).unwrap_or_default();
+    let ident = format!(
+        "{} {}",
+        read("/sys/class/dmi/id/sys_vendor"),
+        read("/sys/class/dmi/id/product_name")
+    );
+    if let Some(vm) = classify_dmi(&ident) {
+        return Some(vm.to_string());
+    }
+    let cpuinfo = read("/proc/cpuinfo");
+    cpuinfo
+        .lines()
+        .any(|l| l.starts_with("flags") && l.split_whitespace().any(|f| f == "hypervisor"))
+        .then(|| "unknown hypervisor".to_string())
+}
+
+/// Map DMI vendor/product strings to a hypervisor name.
+pub(crate) fn classify_dmi(ident: &str) -> Option<&'static str> {
+    let lower = ident.to_lowercase();
+    [
+        ("virtualbox", "virtualbox"),
+        ("vmware", "vmware"),
+        ("qemu", "qemu"),
+        ("kvm", "kvm"),
+        ("microsoft corporation virtual", "hyper-v"),
+        ("xen", "xen"),
+        ("parallels", "parallels"),
+        ("amazon ec2", "aws"),
+        ("google compute engine", "gce"),
+    ]
+    .iter()
+    .find(|(needle, _)| lower.contains(needle))
+    .map(|&(_, name)| name)
+}
+
+// ── Parameter definitions ───────────────────────────────────────────────────
+
+pub fn env_info_params() -> Vec<ToolParam> {
+    vec![ToolParam {
+        name: "refresh".into(),
+        description: "Re-probe the environment instead of returning the snapshot cached \
+                      in the last ten minutes. Default: false."
+            .into(),
+        param_type: "boolean".into(),
+        required: false,
+    }]
+}
@@ -0,0 +1,190 @@
+//! Env var tool: environment variables for the session's later commands.
+//!
+//! `set` and `unset` edit a map kept for the calling session (see
+//! [`session`](super::session)) that every subsequent `execute_command`
+//! (foreground, background or sandboxed) in that session applies on top
+//! of the gateway's own environment, so the agent can export a variable
+//! once instead of prefixing each command with it.  Other sessions and
+//! the gateway process itself are never affected.
+//!
+//! Values that look like credentials — by name (`*_TOKEN`, `*_KEY`, ...)
+//! or because the leak detector recognises them — are still applied, but
+//! the tool never echoes them back and `set` warns that the vault is the
+//! better place for them.
+
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::{debug, instrument};
+
+use super::{ToolParam, session};
+use crate::providers::redact_credentials;
+
+const ACTIONS: &str = "get, set, unset, list";
+
+/// Shown instead of a sensitive value.
+const MASK: &str = "********";
+
+/// Name fragments that mark a variable as holding a credential.
+const SENSITIVE_NAME_PARTS: &[&str] = &[
+    "KEY",
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "CREDENTIAL",
+    "AUTH",
+    "COOKIE",
+];
+
+/// Variables set for each session's commands, by session.
+static SESSION_ENV: Mutex<BTreeMap<String, BTreeMap<String, String>>> = Mutex::new(BTreeMap::new());
+
+/// The variables to apply to a command the current session is about to
+/// spawn.
+pub(crate) fn session_env() -> Vec<(String, String)> {
+    SESSION_ENV
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&session::current_or_default())
+        .map(|env| env.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default()
+}
+
+/// Whether `name` can be exported by a POSIX shell.
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether a variable should be treated as a credential.
+pub(crate) fn is_sensitive(name: &str, value: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    SENSITIVE_NAME_PARTS.iter().any(|part| upper.contains(part))
+        || redact_credentials(value, None) != value
+}
+
+/// The value as the tool reports it: masked when sensitive.
+fn shown(name: &str, value: &str) -> Value {
+    if is_sensitive(name, value) {
+        json!({ "name": name, "value": MASK, "sensitive": true })
+    } else {
+        json!({ "name": name, "value": value, "sensitive": false })
+    }
+}
+
+fn required_name(args: &Value) -> Result<&str, String> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: name")?;
+    if !is_valid_name(name) {
+        return Err(format!(
+            "Invalid variable name '{}': use letters, digits and '_', not starting with a digit",
+            name
+        ));
+    }
+    Ok(name)
+}
+
+/// Execute the `env_var` tool.
+#[instrument(skip(args, _workspace_dir), fields(action))]
+pub fn exec_env_var(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: action")?;
+    tracing::Span::current().record("action", action);
+
+    let mut sessions = SESSION_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    let env = sessions.entry(session::current_or_default()).or_default();
+    match action {
+        "get" => {
+            let name = required_name(args)?;
+            if let Some(value) = env.get(name) {
+                let mut out = shown(name, value);
+                out["source"] = json!("session");
+                return Ok(out.to_string());
+            }
+            match std::env::var(name) {
+                Ok(value) => {
+                    let mut out = shown(name, &value);
+                    out["source"] = json!("process");
+                    Ok(out.to_string())
+                }
+                Err(_) => Ok(json!({ "name": name, "set": false }).to_string()),
+            }
+        }
+
+        "set" => {
+            let name = required_name(args)?;
+            let value = args
+                .get("value")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing required parameter: value")?;
+            if value.contains('\0') {
+                return Err("Variable values cannot contain NUL bytes".to_string());
+            }
+            let replaced = env.insert(name.to_string(), value.to_string()).is_some();
+            debug!(name, replaced, "Set session environment variable");
+
+            let mut out = shown(name, value);
+            out["action"] = json!("set");
+            out["replaced"] = json!(replaced);
+            if is_sensitive(name, value) {
+                out["warning"] = json!(
+                    "This looks like a credential. It is passed to every later command \
+                     and masked here, but storing it with secrets_store keeps it out of \
+                     the conversation."
+                );
+            }
+            Ok(out.to_string())
+        }
+
+        "unset" => {
+            let name = required_name(args)?;
+            let removed = env.remove(name).is_some();
+            debug!(name, removed, "Unset session environment variable");
+            Ok(json!({ "action": "unset", "name": name, "removed": removed }).to_string())
+        }
+
+        "list" => {
+            let vars: Vec<Value> = env.iter().map(|(k, v)| shown(k, v)).collect();
+            Ok(json!({ "count": vars.len(), "variables": vars }).to_string())
+        }
+
+        _ => Err(format!(
+            "Unknown action: '{}'. Valid actions: {}",
+            action, ACTIONS
+        )),
+    }
+}
+
+pub fn env_var_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "Action: 'get' (session value, else the gateway's), 'set', \
+                          'unset' (session variables only), 'list' (session variables)."
+                .into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "name".into(),
+            description: "Variable name, e.g. 'RUST_LOG' (required except for 'list').".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "value".into(),
+            description: "Value to set (required for 'set').".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,273 @@
+//! `move_file` and `copy_file`: rename, move and copy files or directory
+//! trees without shelling out to platform-specific `mv` / `cp`.
+//!
+//! Both refuse to replace an existing destination unless `force = true`,
+//! keep permissions and modification times where the platform allows, and
+//! never follow symlinks inside a copied tree — a link is copied as a link.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+use tracing::{debug, instrument, warn};
+
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_tree, resolve_path, sandbox};
+
+/// Files and bytes carried by one move or copy.
+#[derive(Debug, Default)]
+struct Transfer {
+    files: u64,
+    bytes: u64,
+}
+
+/// Validated source and destination of a move or copy.
+struct Plan {
+    source: PathBuf,
+    destination: PathBuf,
+    replaced: bool,
+}
+
+/// Move or rename a file or directory.
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_move_file(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let plan = plan(args, workspace_dir, "move")?;
+    debug!(source = %plan.source.display(), destination = %plan.destination.display(), "Moving");
+    let moved = move_path(&plan.source, &plan.destination).map_err(|e| {
+        format!(
+            "Failed to move '{}' to '{}': {}",
+            plan.source.display(),
+            plan.destination.display(),
+            e
+        )
+    })?;
+    Ok(report(&plan, &moved))
+}
+
+/// Copy a file, or a directory recursively.
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_copy_file(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let plan = plan(args, workspace_dir, "copy")?;
+    debug!(source = %plan.source.display(), destination = %plan.destination.display(), "Copying");
+    let mut copied = Transfer::default();
+    copy_tree(&plan.source, &plan.destination, &mut copied).map_err(|e| {
+        format!(
+            "Failed to copy '{}' to '{}': {}",
+            plan.source.display(),
+            plan.destination.display(),
+            e
+        )
+    })?;
+    Ok(report(&plan, &copied))
+}
+
+fn report(plan: &Plan, transfer: &Transfer) -> String {
+    json!({
+        "source": plan.source.display().to_string(),
+        "destination": plan.destination.display().to_string(),
+        "files": transfer.files,
+        "bytes": transfer.bytes,
+        "replaced": plan.replaced,
+    })
+    .to_string()
+}
+
+/// Resolve and check both paths, and clear the way for the destination.
+///
+/// A destination that is an existing directory receives the source inside
+/// it, as with `mv` and `cp`.  An existing target is only replaced with
+/// `force`, and never by an entry of a different kind (file vs directory).
+fn plan(args: &Value, workspace_dir: &Path, verb: &str) -> Result<Plan, String> {
+    let arg = |name: &str| {
+        args.get(name)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .ok_or_else(|| format!("Missing required parameter: {}", name))
+    };
+    let source = resolve_path(workspace_dir, arg("source")?);
+    let mut destination = resolve_path(workspace_dir, arg("destination")?);
+    let force = args.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let source_meta = fs::symlink_metadata(&source)
+        .map_err(|e| format!("Cannot {} '{}': {}", verb, source.display(), e))?;
+    if destination.is_dir()
+        && let Some(name) = source.file_name()
+    {
+        destination = destination.join(name);
+    }
+
+    for path in [&source, &destination] {
+        if is_protected_tree(path) {
+            warn!(path = %path.display(), "Attempted {} of protected path", verb);
+            return Err(VAULT_ACCESS_DENIED.to_string());
+        }
+        if let Some(sb) = sandbox() {
+            sb.check_path(path)?;
+        }
+    }
+
+    let source_real = real_path(&source);
+    let destination_real = real_path(&destination);
+    if source_real == destination_real {
+        return Err(format!(
+            "Source and destination are the same: {}",
+            source.display()
+        ));
+    }
+    if source_meta.is_dir() && destination_real.starts_with(&source_real) {
+        return Err(format!(
+            "Cannot {} '{}' into itself",
+            verb,
+            source.display()
+        ));
+    }
+
+    let mut replaced = false;
+    if let Ok(existing) = fs::symlink_metadata(&destination) {
+        if !force {
+            return Err(format!(
+                "'{}' already exists; pass force=true to replace it",
+                destination.display()
+            ));
+        }
+        if existing.is_dir() != source_meta.is_dir() {
+            let kind = if existing.is_dir() {
+                "directory"
+            } else {
+                "file"
+            };
+            return Err(format!(
+                "'{}' is a {}; force only replaces an entry of the same kind",
+                destination.display(),
+                kind
+            ));
+        }
+        remove(&destination, &existing)
+            .map_err(|e| format!("Failed to replace '{}': {}", destination.display(), e))?;
+        replaced = true;
+    }
+
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            format!(
+                "Failed to create directories for '{}': {}",
+                destination.display(),
+                e
+            )
+        })?;
+    }
+
+    Ok(Plan {
+        source,
+        destination,
+        replaced,
+    })
+}
+
+/// Canonical form of `path`, resolving the deepest existing ancestor when
+/// the path itself doesn't exist yet.
+fn real_path(path: &Path) -> PathBuf {
+    let mut rest = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(real) = current.canonicalize() {
+            return rest.iter().rev().fold(real, |acc, part| acc.join(part));
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_owned());
+                current = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+fn move_path(source: &Path, destination: &Path) -> io::Result<Transfer> {
+    let mut moved = Transfer::default();
+    measure(source, &mut moved)?;
+    match fs::rename(source, destination) {
+        Ok(()) => Ok(moved),
+        // Renames can't cross filesystems; copy, then remove the original.
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            let mut copied = Transfer::default();
+            copy_tree(source, destination, &mut copied)?;
+            remove(source, &fs::symlink_metadata(source)?)?;
+            Ok(copied)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn measure(path: &Path, totals: &mut Transfer) -> io::Result<()> {
+    let meta = fs::symlink_metadata(path)?;
+    if meta.is_dir() {
+        for entry in fs::read_dir(path)? {
+            measure(&entry?.path(), totals)?;
+        }
+    } else {
+        totals.files += 1;
+        if meta.is_file() {
+            totals.bytes += meta.len();
+        }
+    }
+    Ok(())
+}
+
+fn copy_tree(source: &Path, destination: &Path, totals: &mut Transfer) -> io::Result<()> {
+    let meta = fs::symlink_metadata(source)?;
+    if meta.file_type().is_symlink() {
+        copy_symlink(source, destination)?;
+        totals.files += 1;
+    } else if meta.is_dir() {
+        fs::create_dir_all(destination)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &destination.join(entry.file_name()), totals)?;
+        }
+        // Set last so a read-only directory can still be filled.
+        fs::set_permissions(destination, meta.permissions())?;
+    } else {
+        // `fs::copy` carries the permission bits over.
+        totals.bytes += fs::copy(source, destination)?;
+        totals.files += 1;
+        if let Ok(modified) = meta.modified()
+            && let Ok(file) = fs::File::options().write(true).open(destination)
+        {
+            let _ = file.set_modified(modified);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, destination)
+}
+
+#[cfg(windows)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    use std::os::windows::fs::{symlink_dir, symlink_file};
+    let target = fs::read_link(source)?;
+    if fs::metadata(source).map(|m| m.is_dir()).unwrap_or(false) {
+        symlink_dir(target, destination)
+    } else {
+        symlink_file(target, destination)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn copy_symlink(source: &Path, _destination: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("cannot copy symlink {}", source.display()),
+    ))
+}
+
+fn remove(path: &Path, meta: &fs::Metadata) -> io::Result<()> {
+    if meta.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
@@ -5,7 +5,7 @@ use serde_json::Value;
 use std::path::Path;
 use tracing::{debug, instrument, warn};
 
-use super::{check_protected_config, merge_json};
+use super::{check_protected_config, gateway_pause, gateway_resume, gateway_status, merge_json};
 
 // ── Async implementations ───────────────────────────────────────────────────
 
@@ -26,6 +26,12 @@ pub async fn exec_gateway_async(args: &Value, workspace_dir: &Path) -> Result<St
         .join("openclaw.json");
 
     match action {
+        "status" => gateway_status(),
+
+        "pause" => gateway_pause(args),
+
+        "resume" => gateway_resume(),
+
         "restart" => {
             let reason = args
                 .get("reason")
@@ -152,7 +158,7 @@ pub async fn exec_gateway_async(args: &Value, workspace_dir: &Path) -> Result<St
         _ => {
             warn!(action, "Unknown gateway action");
             Err(format!(
-                "Unknown action: {}. Valid: restart, config.get, config.schema, config.apply, config.patch, update.run",
+                "Unknown action: {}. Valid: status, pause, resume, restart, config.get, config.schema, config.apply, config.patch, update.run",
                 action
             ))
         }
@@ -160,8 +166,8 @@ pub async fn exec_gateway_async(args: &Value, workspace_dir: &Path) -> Result<St
 }
 
 /// Send messages via channel plugins (async).
-#[instrument(skip(args, _workspace_dir), fields(action))]
-pub async fn exec_message_async(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub async fn exec_message_async(args: &Value, workspace_dir: &Path) -> Result<String, String> {
     let action = args
         .get("action")
         .and_then(|v| v.as_str())
@@ -257,8 +263,10 @@ pub async fn exec_message_async(args: &Value, _workspace_dir: &Path) -> Result<S
             Ok(format!("Broadcast results:\n{}", results.join("\n")))
         }
 
+        "schedule" => crate::tools::cron_tool::schedule_message(args, workspace_dir),
+
         _ => Err(format!(
-            "Unknown action: {}. Valid: send, broadcast",
+            "Unknown action: {}. Valid: send, broadcast, schedule",
             action
         )),
     }
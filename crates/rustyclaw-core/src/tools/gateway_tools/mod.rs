@@ -68,6 +68,47 @@ pub(crate) fn merge_json(base: Value, patch: Value) -> Value {
     }
 }
 
+/// Uptime, current model, recent model-probe results and whether the
+/// agent is paused, for the `status` action.
+pub(crate) fn gateway_status() -> Result<String, String> {
+    let mut status = serde_json::to_value(crate::health::snapshot())
+        .map_err(|e| format!("Failed to serialize gateway status: {}", e))?;
+    if let Some((provider, model, _)) = crate::runtime_ctx::get_model_info() {
+        status["provider"] = Value::String(provider);
+        status["model"] = Value::String(model);
+    }
+    status["paused"] = match crate::agent_pause::global().state() {
+        Some(pause) => serde_json::to_value(pause)
+            .map_err(|e| format!("Failed to serialize gateway status: {}", e))?,
+        None => Value::Bool(false),
+    };
+    serde_json::to_string_pretty(&status)
+        .map_err(|e| format!("Failed to serialize gateway status: {}", e))
+}
+
+/// Pause the agent loop in every session, for the `pause` action.  The
+/// calling turn is held too, at the start of its next round.
+pub(crate) fn gateway_pause(args: &Value) -> Result<String, String> {
+    let reason = args
+        .get("reason")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    if crate::agent_pause::global().pause(reason) {
+        Ok("Agent paused; sessions hold before their next tool round.".to_string())
+    } else {
+        Ok("Agent is already paused.".to_string())
+    }
+}
+
+/// Resume a paused agent loop, for the `resume` action.
+pub(crate) fn gateway_resume() -> Result<String, String> {
+    if crate::agent_pause::global().resume() {
+        Ok("Agent resumed.".to_string())
+    } else {
+        Ok("Agent is not paused.".to_string())
+    }
+}
+
 /// Gateway management (sync wrapper).
 #[instrument(skip(args, workspace_dir), fields(action))]
 pub fn exec_gateway(args: &Value, workspace_dir: &Path) -> Result<String, String> {
@@ -85,6 +126,12 @@ pub fn exec_gateway(args: &Value, workspace_dir: &Path) -> Result<String, String
         .join("openclaw.json");
 
     match action {
+        "status" => gateway_status(),
+
+        "pause" => gateway_pause(args),
+
+        "resume" => gateway_resume(),
+
         "restart" => {
             let reason = args
                 .get("reason")
@@ -201,7 +248,7 @@ pub fn exec_gateway(args: &Value, workspace_dir: &Path) -> Result<String, String
         _ => {
             warn!(action, "Unknown gateway action");
             Err(format!(
-                "Unknown action: {}. Valid: restart, config.get, config.schema, config.apply, config.patch, update.run",
+                "Unknown action: {}. Valid: status, pause, resume, restart, config.get, config.schema, config.apply, config.patch, update.run",
                 action
             ))
         }
@@ -209,8 +256,8 @@ pub fn exec_gateway(args: &Value, workspace_dir: &Path) -> Result<String, String
 }
 
 /// Send messages via channel plugins (sync wrapper).
-#[instrument(skip(args, _workspace_dir), fields(action))]
-pub fn exec_message(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub fn exec_message(args: &Value, workspace_dir: &Path) -> Result<String, String> {
     let action = args
         .get("action")
         .and_then(|v| v.as_str())
@@ -302,8 +349,10 @@ pub fn exec_message(args: &Value, _workspace_dir: &Path) -> Result<String, Strin
             Ok(format!("Broadcast results:\n{}", results.join("\n")))
         }
 
+        "schedule" => crate::tools::cron_tool::schedule_message(args, workspace_dir),
+
         _ => Err(format!(
-            "Unknown action: {}. Valid: send, broadcast",
+            "Unknown action: {}. Valid: send, broadcast, schedule",
             action
         )),
     }
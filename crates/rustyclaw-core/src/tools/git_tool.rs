@@ -0,0 +1,602 @@
+//! Git tool: structured repository operations.
+//!
+//! Wraps the `git` CLI so the agent gets parsed results (status entries,
+//! commit lists, per-file diff stats) instead of scraping porcelain text
+//! from `execute_command`.  Every invocation goes through the sandbox like
+//! any other command.
+//!
+//! Read-only actions run immediately.  `commit` and `stash drop` only
+//! preview what they would do unless called with `confirm=true`, and the
+//! tool never amends, rebases, resets, or pushes.
+
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, run_sandboxed_command};
+
+/// Default number of commits returned by `log`.
+const DEFAULT_LOG_LIMIT: u64 = 20;
+
+/// Upper bound on `log` results regardless of the requested limit.
+const MAX_LOG_LIMIT: u64 = 500;
+
+/// Maximum bytes of patch text returned by `diff`.
+const MAX_DIFF_CHARS: usize = 50_000;
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `git` tool.
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub fn exec_git(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: action".to_string())?;
+
+    tracing::Span::current().record("action", action);
+
+    let repo = resolve_repo(args, workspace_dir)?;
+    debug!(repo = %repo.display(), "Executing git tool");
+
+    match action {
+        "status" => git_status(&repo),
+        "diff" => git_diff(args, &repo),
+        "log" => git_log(args, &repo),
+        "branch" => git_branch(args, &repo),
+        "add" => git_add(args, &repo),
+        "commit" => git_commit(args, &repo),
+        "stash" => git_stash(args, &repo),
+        _ => Err(format!(
+            "Unknown action: '{}'. Use: status, diff, log, branch, add, commit, stash",
+            action
+        )),
+    }
+}
+
+/// Resolve `repo_path` (default: the workspace) to the repository root.
+fn resolve_repo(args: &Value, workspace_dir: &Path) -> Result<PathBuf, String> {
+    let dir = match args.get("repo_path").and_then(|v| v.as_str()) {
+        Some(p) => resolve_path(workspace_dir, p),
+        None => workspace_dir.to_path_buf(),
+    };
+    if is_protected_path(&dir) {
+        warn!(path = %dir.display(), "Attempted git access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
+    }
+    let top = run_git(&dir, &["rev-parse", "--show-toplevel"])
+        .map_err(|_| format!("Not a git repository: {}", dir.display()))?;
+    Ok(PathBuf::from(top.trim()))
+}
+
+// ── Actions ─────────────────────────────────────────────────────────────────
+
+fn git_status(repo: &Path) -> Result<String, String> {
+    let raw = run_git(repo, &["status", "--porcelain=v1", "--branch", "-z"])?;
+    let mut status = parse_status(&raw);
+    status["repo"] = json!(repo.display().to_string());
+    Ok(status.to_string())
+}
+
+fn git_diff(args: &Value, repo: &Path) -> Result<String, String> {
+    let staged = bool_arg(args, "staged");
+    let path = args.get("path").and_then(|v| v.as_str());
+
+    let mut base = vec!["diff", "--no-ext-diff"];
+    if staged {
+        base.push("--cached");
+    }
+    let with_path = |extra: &[&'static str]| {
+        let mut argv: Vec<&str> = base.clone();
+        argv.extend_from_slice(extra);
+        if let Some(p) = path {
+            argv.push("--");
+            argv.push(p);
+        }
+        argv
+    };
+
+    let numstat = run_git(repo, &with_path(&["--numstat"]))?;
+    let files = parse_numstat(&numstat);
+    let mut patch = run_git(repo, &with_path(&[]))?;
+    let truncated = patch.len() > MAX_DIFF_CHARS;
+    if truncated {
+        let mut cut = MAX_DIFF_CHARS;
+        while !patch.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        patch.truncate(cut);
+    }
+
+    Ok(json!({
+        "staged": staged,
+        "files": files,
+        "diff": patch,
+        "truncated": truncated,
+    })
+    .to_string())
+}
+
+fn git_log(args: &Value, repo: &Path) -> Result<String, String> {
+    let limit = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_LOG_LIMIT)
+        .clamp(1, MAX_LOG_LIMIT)
+        .to_string();
+    let mut argv = vec![
+        "log",
+        "-n",
+        limit.as_str(),
+        "--pretty=format:%H%x1f%h%x1f%an%x1f%ae%x1f%aI%x1f%s%x1e",
+    ];
+    if let Some(p) = args.get("path").and_then(|v| v.as_str()) {
+        argv.push("--");
+        argv.push(p);
+    }
+
+    // A repository without commits makes `git log` fail; report it as empty.
+    let raw = match run_git(repo, &argv) {
+        Ok(raw) => raw,
+        Err(e) if e.contains("does not have any commits") => String::new(),
+        Err(e) => return Err(e),
+    };
+    let commits = parse_log(&raw);
+    Ok(json!({ "count": commits.len(), "commits": commits }).to_string())
+}
+
+fn git_branch(args: &Value, repo: &Path) -> Result<String, String> {
+    if let Some(name) = args.get("name").and_then(|v| v.as_str()) {
+        check_ref_name(name)?;
+        run_git(repo, &["branch", "--", name])?;
+        return Ok(json!({ "action": "branch", "created": name }).to_string());
+    }
+
+    let raw = run_git(
+        repo,
+        &[
+            "branch",
+            "--list",
+            "--format=%(HEAD)%1f%(refname:short)%1f%(objectname:short)%1f%(upstream:short)",
+        ],
+    )?;
+    let branches: Vec<Value> = raw
+        .lines()
+        .filter_map(|line| {
+            let mut f = line.split('\x1f');
+            let head = f.next()?;
+            let name = f.next()?;
+            Some(json!({
+                "name": name,
+                "current": head == "*",
+                "commit": f.next().unwrap_or(""),
+                "upstream": f.next().filter(|u| !u.is_empty()),
+            }))
+        })
+        .collect();
+    Ok(json!({ "branches": branches }).to_string())
+}
+
+fn git_add(args: &Value, repo: &Path) -> Result<String, String> {
+    let paths = string_list(args, "paths");
+    if bool_arg(args, "all") {
+        run_git(repo, &["add", "--all"])?;
+    } else if paths.is_empty() {
+        return Err("Missing required parameter: paths (or set all=true)".into());
+    } else {
+        let mut argv = vec!["add", "--"];
+        argv.extend(paths.iter().map(String::as_str));
+        run_git(repo, &argv)?;
+    }
+    git_status(repo)
+}
+
+fn git_commit(args: &Value, repo: &Path) -> Result<String, String> {
+    let message = args
+        .get("message")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .ok_or_else(|| "Missing required parameter: message".to_string())?;
+    let all = bool_arg(args, "all");
+
+    // Changes that would be committed: the index, plus tracked edits with all=true.
+    let mut staged = parse_name_status(&run_git(repo, &["diff", "--cached", "--name-status"])?);
+    if all {
+        for change in parse_name_status(&run_git(repo, &["diff", "--name-status"])?) {
+            if !staged.iter().any(|c| c["path"] == change["path"]) {
+                staged.push(change);
+            }
+        }
+    }
+    if staged.is_empty() {
+        return Err(
+            "Nothing to commit. Stage changes with action='add' first (or set all=true).".into(),
+        );
+    }
+
+    if !bool_arg(args, "confirm") {
+        return Ok(json!({
+            "action": "commit",
+            "committed": false,
+            "message": message,
+            "changes": staged,
+            "hint": "Call again with confirm=true to create this commit.",
+        })
+        .to_string());
+    }
+
+    let mut argv = vec!["commit", "-m", message];
+    if all {
+        argv.push("--all");
+    }
+    run_git(repo, &argv)?;
+
+    let head = run_git(repo, &["log", "-n", "1", "--pretty=format:%H%x1f%h"])?;
+    let (hash, short) = head.trim().split_once('\x1f').unwrap_or((head.trim(), ""));
+    debug!(hash, "Created commit");
+    Ok(json!({
+        "action": "commit",
+        "committed": true,
+        "hash": hash,
+        "short": short,
+        "message": message,
+        "changes": staged,
+    })
+    .to_string())
+}
+
+fn git_stash(args: &Value, repo: &Path) -> Result<String, String> {
+    let op = args
+        .get("stash_action")
+        .and_then(|v| v.as_str())
+        .unwrap_or("push");
+
+    match op {
+        "push" => {
+            let mut argv = vec!["stash", "push", "--include-untracked"];
+            if let Some(msg) = args.get("message").and_then(|v| v.as_str()) {
+                argv.extend(["-m", msg]);
+            }
+            let out = run_git(repo, &argv)?;
+            Ok(
+                json!({ "action": "stash", "stash_action": "push", "output": out.trim() })
+                    .to_string(),
+            )
+        }
+        "list" => {
+            let raw = run_git(repo, &["stash", "list", "--format=%gd%x1f%aI%x1f%s"])?;
+            let stashes: Vec<Value> = raw
+                .lines()
+                .filter_map(|line| {
+                    let mut f = line.split('\x1f');
+                    Some(json!({
+                        "ref": f.next()?,
+                        "date": f.next()?,
+                        "message": f.next().unwrap_or(""),
+                    }))
+                })
+                .collect();
+            Ok(json!({ "stashes": stashes }).to_string())
+        }
+        "pop" | "apply" | "drop" => {
+            let stash_ref = args
+                .get("stash_ref")
+                .and_then(|v| v.as_str())
+                .unwrap_or("stash@{0}");
+            check_ref_name(stash_ref)?;
+            if op == "drop" && !bool_arg(args, "confirm") {
+                return Ok(json!({
+                    "action": "stash",
+                    "stash_action": "drop",
+                    "dropped": false,
+                    "stash_ref": stash_ref,
+                    "hint": "Dropping a stash discards its changes. Call again with confirm=true.",
+                })
+                .to_string());
+            }
+            let out = run_git(repo, &["stash", op, stash_ref])?;
+            Ok(json!({
+                "action": "stash",
+                "stash_action": op,
+                "stash_ref": stash_ref,
+                "output": out.trim(),
+            })
+            .to_string())
+        }
+        _ => Err(format!(
+            "Unknown stash_action: '{}'. Use: push, list, pop, apply, drop",
+            op
+        )),
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Run `git <argv>` in `repo` through the sandbox and return stdout.
+fn run_git(repo: &Path, argv: &[&str]) -> Result<String, String> {
+    let mut command = String::from("GIT_TERMINAL_PROMPT=0 git -c color.ui=never --no-pager");
+    for arg in argv {
+        command.push(' ');
+        command.push_str(&shell_quote(arg));
+    }
+    let output = run_sandboxed_command(&command, repo)?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim();
+        Err(if stderr.is_empty() {
+            format!(
+                "git {} failed ({})",
+                argv.first().unwrap_or(&""),
+                output.status
+            )
+        } else {
+            format!("git {} failed: {}", argv.first().unwrap_or(&""), stderr)
+        })
+    }
+}
+
+/// Quote `s` as a single POSIX shell word.
+pub(crate) fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+fn bool_arg(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// Accept either an array of strings or a single string.
+fn string_list(args: &Value, key: &str) -> Vec<String> {
+    match args.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        Some(Value::String(s)) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Reject names that git would read as an option.
+fn check_ref_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.starts_with('-') {
+        return Err(format!("Invalid ref name: '{}'", name));
+    }
+    Ok(())
+}
+
+/// Parse `git status --porcelain=v1 --branch -z` output.
+pub(crate) fn parse_status(raw: &str) -> Value {
+    let mut branch: Option<String> = None;
+    let mut upstream: Option<String> = None;
+    let (mut ahead, mut behind) = (0u64, 0u64);
+    let mut entries = Vec::new();
+
+    let mut records = raw.split('\0').filter(|r| !r.is_empty());
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("## ") {
+            let (names, counts) = match header.split_once(" [") {
+                Some((n, c)) => (n, c.trim_end_matches(']')),
+                None => (header, ""),
+            };
+            let names = names
+                .strip_prefix("No commits yet on ")
+                .or_else(|| names.strip_prefix("Initial commit on "))
+                .unwrap_or(names);
+            let (b, u) = match names.split_once("...") {
+                Some((b, u)) => (b, Some(u)),
+                None => (names, None),
+            };
+            if b != "HEAD (no branch)" {
+                branch = Some(b.to_string());
+            }
+            upstream = u.map(str::to_string);
+            for part in counts.split(", ") {
+                if let Some(n) = part.strip_prefix("ahead ") {
+                    ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix("behind ") {
+                    behind = n.parse().unwrap_or(0);
+                }
+            }
+            continue;
+        }
+
+        if record.len() < 4 {
+            continue;
+        }
+        let mut codes = record.chars();
+        let x = codes.next().unwrap_or(' ');
+        let y = codes.next().unwrap_or(' ');
+        let path = &record[3..];
+        // Renames and copies are followed by a record holding the source path.
+        let orig_path = if matches!(x, 'R' | 'C') {
+            records.next()
+        } else {
+            None
+        };
+        entries.push(json!({
+            "path": path,
+            "orig_path": orig_path,
+            "index": x.to_string(),
+            "worktree": y.to_string(),
+            "status": describe_status(x, y),
+            "staged": !matches!(x, ' ' | '?' | '!'),
+        }));
+    }
+
+    json!({
+        "branch": branch,
+        "upstream": upstream,
+        "ahead": ahead,
+        "behind": behind,
+        "clean": entries.is_empty(),
+        "entries": entries,
+    })
+}
+
+/// Human-readable label for an `XY` porcelain status code.
+fn describe_status(x: char, y: char) -> &'static str {
+    match (x, y) {
+        ('?', '?') => "untracked",
+        ('!', '!') => "ignored",
+        ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => "conflicted",
+        _ => {
+            let code = if x != ' ' { x } else { y };
+            match code {
+                'M' => "modified",
+                'A' => "added",
+                'D' => "deleted",
+                'R' => "renamed",
+                'C' => "copied",
+                'T' => "typechange",
+                _ => "unknown",
+            }
+        }
+    }
+}
+
+/// Parse `%H%x1f%h%x1f%an%x1f%ae%x1f%aI%x1f%s%x1e` log records.
+pub(crate) fn parse_log(raw: &str) -> Vec<Value> {
+    raw.split('\x1e')
+        .map(|r| r.trim_start_matches('\n'))
+        .filter(|r| !r.is_empty())
+        .filter_map(|record| {
+            let f: Vec<&str> = record.split('\x1f').collect();
+            if f.len() < 6 {
+                return None;
+            }
+            Some(json!({
+                "hash": f[0],
+                "short": f[1],
+                "author": f[2],
+                "email": f[3],
+                "date": f[4],
+                "subject": f[5],
+            }))
+        })
+        .collect()
+}
+
+/// Parse `git diff --numstat`; binary files report null counts.
+fn parse_numstat(raw: &str) -> Vec<Value> {
+    raw.lines()
+        .filter_map(|line| {
+            let mut f = line.splitn(3, '\t');
+            let added = f.next()?;
+            let deleted = f.next()?;
+            let path = f.next()?;
+            Some(json!({
+                "path": path,
+                "additions": added.parse::<u64>().ok(),
+                "deletions": deleted.parse::<u64>().ok(),
+            }))
+        })
+        .collect()
+}
+
+/// Parse `git diff --name-status` into `{status, path}` objects.
+fn parse_name_status(raw: &str) -> Vec<Value> {
+    raw.lines()
+        .filter_map(|line| {
+            let (code, path) = line.split_once('\t')?;
+            Some(json!({ "status": code, "path": path }))
+        })
+        .collect()
+}
+
+// ── Parameter definitions ───────────────────────────────────────────────────
+
+pub fn git_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "Action to perform: 'status', 'diff', 'log', 'branch', 'add', \
+                          'commit', or 'stash'."
+                .into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "repo_path".into(),
+            description: "Path to the repository (default: workspace). Any directory \
+                          inside the work tree works."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "path".into(),
+            description: "Limit 'diff' or 'log' to this file or directory.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "staged".into(),
+            description: "For 'diff': show staged changes instead of unstaged ones.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "limit".into(),
+            description: "For 'log': maximum commits to return (default: 20).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "name".into(),
+            description: "For 'branch': create a branch with this name at HEAD. \
+                          Omit to list branches."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "paths".into(),
+            description: "For 'add': files to stage.".into(),
+            param_type: "array".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "all".into(),
+            description: "For 'add': stage every change. For 'commit': also include \
+                          unstaged edits to tracked files."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "message".into(),
+            description: "Commit message (required for 'commit'); optional label for \
+                          'stash push'."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "confirm".into(),
+            description: "Must be true to create a commit or drop a stash. \
+                          First call without confirm returns a preview."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "stash_action".into(),
+            description: "For 'stash': 'push' (default), 'list', 'pop', 'apply', or 'drop'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "stash_ref".into(),
+            description: "For 'stash' pop/apply/drop: which stash (default: stash@{0}).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -1,5 +1,8 @@
 //! Helper functions and global state for the tools system.
 
+use super::env_var::session_env;
+use crate::config::{TranscribeConfig, WeatherConfig};
+use crate::memory_consolidation::ConsolidationConfig;
 use crate::process_manager::{ProcessManager, SharedProcessManager};
 use crate::sandbox::{Sandbox, SandboxMode, SandboxPolicy};
 use std::path::{Path, PathBuf};
@@ -49,6 +52,7 @@ pub fn run_sandboxed_command(command: &str, cwd: &Path) -> Result<std::process::
         // Update policy workspace to the actual cwd for this command
         let mut policy = sb.policy.clone();
         policy.workspace = cwd.to_path_buf();
+        policy.env = session_env();
         crate::sandbox::run_sandboxed(command, &policy, sb.mode)
     } else {
         debug!(cwd = %cwd.display(), "Running unsandboxed command (no sandbox configured)");
@@ -57,11 +61,57 @@ pub fn run_sandboxed_command(command: &str, cwd: &Path) -> Result<std::process::
             .arg("-c")
             .arg(command)
             .current_dir(cwd)
+            .envs(session_env())
             .output()
             .map_err(|e| format!("Command failed: {}", e))
     }
 }
 
+// ── Memory consolidation settings ───────────────────────────────────────────
+
+/// `[memory_consolidation]` settings, set once at gateway startup.
+static MEMORY_CONSOLIDATION: OnceLock<ConsolidationConfig> = OnceLock::new();
+
+/// Called once from the gateway to apply the configured memory settings.
+pub fn set_memory_consolidation_config(config: ConsolidationConfig) {
+    let _ = MEMORY_CONSOLIDATION.set(config);
+}
+
+/// The configured memory settings, or the defaults when none were set.
+pub fn memory_consolidation_config() -> ConsolidationConfig {
+    MEMORY_CONSOLIDATION.get().cloned().unwrap_or_default()
+}
+
+// ── Weather settings ────────────────────────────────────────────────────────
+
+/// `[weather]` settings, set once at gateway startup.
+static WEATHER: OnceLock<WeatherConfig> = OnceLock::new();
+
+/// Called once from the gateway to apply the configured weather provider.
+pub fn set_weather_config(config: WeatherConfig) {
+    let _ = WEATHER.set(config);
+}
+
+/// The configured weather settings, or the keyless defaults.
+pub fn weather_config() -> WeatherConfig {
+    WEATHER.get().cloned().unwrap_or_default()
+}
+
+// ── Transcription settings ──────────────────────────────────────────────────
+
+/// `[transcribe]` settings, set once at gateway startup.
+static TRANSCRIBE: OnceLock<TranscribeConfig> = OnceLock::new();
+
+/// Called once from the gateway to apply the configured speech-to-text backend.
+pub fn set_transcribe_config(config: TranscribeConfig) {
+    let _ = TRANSCRIBE.set(config);
+}
+
+/// The configured transcription settings, or the defaults.
+pub fn transcribe_config() -> TranscribeConfig {
+    TRANSCRIBE.get().cloned().unwrap_or_default()
+}
+
 // ── Credentials directory protection ────────────────────────────────────────
 
 /// Absolute path of the credentials directory, set once at gateway startup.
@@ -92,6 +142,11 @@ pub fn set_credentials_dir(path: PathBuf) {
     let _ = CREDENTIALS_DIR.set(path);
 }
 
+/// The registered credentials directory, if any.
+pub(crate) fn credentials_dir() -> Option<&'static Path> {
+    CREDENTIALS_DIR.get().map(PathBuf::as_path)
+}
+
 /// Returns `true` when a command string references the credentials directory.
 pub fn command_references_credentials(command: &str) -> bool {
     if let Some(cred_dir) = CREDENTIALS_DIR.get() {
@@ -126,6 +181,22 @@ pub fn is_protected_path(path: &Path) -> bool {
     }
 }
 
+/// Returns `true` when `path` is protected or is a directory that contains
+/// the credentials directory, so moving or copying it would take the vault
+/// along.
+pub fn is_protected_tree(path: &Path) -> bool {
+    if is_protected_path(path) {
+        return true;
+    }
+    let Some(cred_dir) = CREDENTIALS_DIR.get() else {
+        return false;
+    };
+    match (cred_dir.canonicalize(), path.canonicalize()) {
+        (Ok(cred), Ok(path)) => cred.starts_with(path),
+        _ => cred_dir.starts_with(path),
+    }
+}
+
 /// Try to resolve a path and double-canonicalize to detect symlink swaps.
 ///
 /// Returns `Ok(canonical)` if the path resolves consistently twice,
@@ -265,6 +336,9 @@ pub fn open_file_write_safe(path: &Path) -> std::io::Result<(std::fs::File, Path
 /// Standard denial message when a tool tries to touch the vault.
 pub const VAULT_ACCESS_DENIED: &str = "Access denied: the credentials directory is protected. Use the secrets_list / secrets_get / secrets_store tools instead.";
 
+/// Result text for a tool call the user cancelled while it was running.
+pub const TOOL_CANCELLED: &str = "Cancelled: the user stopped this tool call before it finished.";
+
 // ── Path helpers ────────────────────────────────────────────────────────────
 
 /// Resolve a path argument against the workspace root.
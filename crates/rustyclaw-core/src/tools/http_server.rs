@@ -0,0 +1,674 @@
+//! HTTP server tool: short-lived local listeners for callbacks and sharing.
+//!
+//! `capture` opens a listener that records the first request it receives —
+//! an OAuth redirect, a webhook delivery — answers it, and closes; `result`
+//! waits for that request and returns its method, path, query, headers and
+//! body.  `serve` shares a file or directory over plain HTTP until its
+//! lifetime runs out or it has answered `max_hits` requests.
+//!
+//! Listeners only bind to loopback, every listener is closed after at most
+//! [`MAX_LIFETIME_SECS`], and at most [`MAX_LISTENERS`] run at once.
+//! Served paths are confined to the shared file or directory, which must
+//! pass the sandbox's path check, and the credentials directory is never
+//! served.
+
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, instrument};
+
+use super::ToolParam;
+use super::helpers::{
+    VAULT_ACCESS_DENIED, expand_tilde, is_protected_path, is_protected_tree, resolve_path, sandbox,
+};
+use crate::sandbox::Sandbox;
+
+const ACTIONS: &str = "serve, capture, result, stop, list";
+
+/// Lifetime of a listener when the call does not set one.
+pub const DEFAULT_LIFETIME_SECS: u64 = 300;
+
+/// Longest a listener may stay open.
+pub const MAX_LIFETIME_SECS: u64 = 3600;
+
+/// Listeners allowed to run at once.
+pub const MAX_LISTENERS: usize = 8;
+
+/// How long `result` waits for a request when the call does not say.
+const DEFAULT_WAIT_SECS: u64 = 30;
+
+/// Longest request head accepted.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Bytes of a captured request body kept.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Time a client has to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Body sent back to the captured request when the call gives none.
+const DEFAULT_REPLY: &str = "Received. You can close this window.";
+
+/// A request recorded by a `capture` listener.
+#[derive(Debug, Clone, Serialize)]
+struct CapturedRequest {
+    method: String,
+    path: String,
+    query: BTreeMap<String, String>,
+    headers: BTreeMap<String, String>,
+    body: String,
+    body_truncated: bool,
+}
+
+enum Mode {
+    Serve {
+        root: PathBuf,
+        max_hits: Option<u64>,
+    },
+    Capture {
+        reply: String,
+    },
+}
+
+impl Mode {
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::Serve { .. } => "serve",
+            Mode::Capture { .. } => "capture",
+        }
+    }
+}
+
+/// A listener started by this tool.  `cancel` fires when it closes, for
+/// whatever reason; a `capture` listener fills `captured` first.
+struct Listener {
+    mode: &'static str,
+    url: String,
+    expires_at: Instant,
+    cancel: CancellationToken,
+    hits: Arc<AtomicU64>,
+    captured: Arc<OnceLock<CapturedRequest>>,
+}
+
+impl Listener {
+    fn is_running(&self) -> bool {
+        !self.cancel.is_cancelled()
+    }
+
+    fn summary(&self, id: &str) -> Value {
+        json!({
+            "id": id,
+            "mode": self.mode,
+            "url": self.url,
+            "running": self.is_running(),
+            "hits": self.hits.load(Ordering::Relaxed),
+            "expires_in_secs": self
+                .expires_at
+                .saturating_duration_since(Instant::now())
+                .as_secs(),
+        })
+    }
+}
+
+static LISTENERS: Mutex<BTreeMap<String, Listener>> = Mutex::new(BTreeMap::new());
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Sync stub for the static ToolDef.
+pub fn exec_http_server_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("http_server requires async execution".into())
+}
+
+/// Execute the `http_server` tool.
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub async fn exec_http_server_async(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: action")?;
+    tracing::Span::current().record("action", action);
+
+    match action {
+        "serve" => {
+            let path = args
+                .get("path")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing required parameter: path")?;
+            let root = serve_root(path, workspace_dir, sandbox())?;
+            let max_hits = args.get("max_hits").and_then(|v| v.as_u64());
+            Ok(start(args, Mode::Serve { root, max_hits })
+                .await?
+                .to_string())
+        }
+
+        "capture" => {
+            let reply = args
+                .get("reply")
+                .and_then(|v| v.as_str())
+                .unwrap_or(DEFAULT_REPLY)
+                .to_string();
+            let mut out = start(args, Mode::Capture { reply }).await?;
+            if let Some(wait) = args.get("wait_secs").and_then(|v| v.as_u64())
+                && wait > 0
+            {
+                let id = out["id"].as_str().unwrap_or_default().to_string();
+                out["request"] = json!(wait_for_request(&id, wait).await?);
+            }
+            Ok(out.to_string())
+        }
+
+        "result" => {
+            let id = required_id(args)?;
+            let wait = args
+                .get("wait_secs")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_WAIT_SECS)
+                .min(MAX_LIFETIME_SECS);
+            let request = wait_for_request(id, wait).await?;
+            let mut out = listener_summary(id)?;
+            out["captured"] = json!(request.is_some());
+            if let Some(request) = request {
+                out["request"] = json!(request);
+            }
+            Ok(out.to_string())
+        }
+
+        "stop" => {
+            let id = required_id(args)?;
+            let listener = lock_listeners()
+                .remove(id)
+                .ok_or_else(|| unknown_listener(id))?;
+            let was_running = listener.is_running();
+            listener.cancel.cancel();
+            let mut out = listener.summary(id);
+            out["running"] = json!(false);
+            out["stopped"] = json!(was_running);
+            Ok(out.to_string())
+        }
+
+        "list" => {
+            let listeners = lock_listeners();
+            let list: Vec<Value> = listeners.iter().map(|(id, l)| l.summary(id)).collect();
+            Ok(json!({ "count": list.len(), "listeners": list }).to_string())
+        }
+
+        _ => Err(format!(
+            "Unknown action: '{}'. Valid actions: {}",
+            action, ACTIONS
+        )),
+    }
+}
+
+/// Resolve the file or directory `serve` shares, refusing anything that
+/// holds the credentials directory or that `sandbox` does not allow.
+pub(crate) fn serve_root(
+    path: &str,
+    workspace_dir: &Path,
+    sandbox: Option<&Sandbox>,
+) -> Result<PathBuf, String> {
+    let root = resolve_path(workspace_dir, &expand_tilde(path).to_string_lossy());
+    if is_protected_tree(&root) {
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox {
+        sb.check_path(&root)?;
+    }
+    root.canonicalize()
+        .map_err(|e| format!("Cannot serve {}: {}", root.display(), e))
+}
+
+fn lock_listeners() -> std::sync::MutexGuard<'static, BTreeMap<String, Listener>> {
+    LISTENERS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn required_id(args: &Value) -> Result<&str, String> {
+    args.get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: id".to_string())
+}
+
+fn unknown_listener(id: &str) -> String {
+    format!("No listener '{}'. Use action 'list' to see them.", id)
+}
+
+fn listener_summary(id: &str) -> Result<Value, String> {
+    lock_listeners()
+        .get(id)
+        .map(|l| l.summary(id))
+        .ok_or_else(|| unknown_listener(id))
+}
+
+/// Bind a listener for `mode` and run it in the background.  Returns what
+/// the caller needs to use it: its id and URL.
+async fn start(args: &Value, mode: Mode) -> Result<Value, String> {
+    let requested = args
+        .get("lifetime_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_LIFETIME_SECS);
+    let lifetime_secs = requested.clamp(1, MAX_LIFETIME_SECS);
+    let port = args.get("port").and_then(|v| v.as_u64()).unwrap_or(0);
+    let port = u16::try_from(port).map_err(|_| format!("Invalid port: {}", port))?;
+
+    {
+        let mut listeners = lock_listeners();
+        // Forget closed listeners an hour after they would have expired.
+        let horizon = Duration::from_secs(MAX_LIFETIME_SECS);
+        listeners.retain(|_, l| l.is_running() || l.expires_at.elapsed() < horizon);
+        if listeners.values().filter(|l| l.is_running()).count() >= MAX_LISTENERS {
+            return Err(format!(
+                "{} listeners are already running; stop one first",
+                MAX_LISTENERS
+            ));
+        }
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to listen on 127.0.0.1:{}: {}", port, e))?;
+    let addr = listener.local_addr().map_err(|e| e.to_string())?;
+    let mut url = format!("http://127.0.0.1:{}/", addr.port());
+    if let Mode::Serve { root, .. } = &mode
+        && root.is_file()
+        && let Some(name) = root.file_name()
+    {
+        url.push_str(&urlencoding::encode(&name.to_string_lossy()));
+    }
+
+    let id = format!("http-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    let cancel = CancellationToken::new();
+    let hits = Arc::new(AtomicU64::new(0));
+    let captured = Arc::new(OnceLock::new());
+    let lifetime = Duration::from_secs(lifetime_secs);
+    let mode_name = mode.name();
+    lock_listeners().insert(
+        id.clone(),
+        Listener {
+            mode: mode_name,
+            url: url.clone(),
+            expires_at: Instant::now() + lifetime,
+            cancel: cancel.clone(),
+            hits: hits.clone(),
+            captured: captured.clone(),
+        },
+    );
+    debug!(id, %addr, mode = mode_name, lifetime_secs, "Started HTTP listener");
+    tokio::spawn(run_listener(
+        listener, mode, lifetime, cancel, hits, captured,
+    ));
+
+    let mut out = json!({
+        "id": id,
+        "mode": mode_name,
+        "url": url,
+        "listen": addr.to_string(),
+        "lifetime_secs": lifetime_secs,
+    });
+    if requested > MAX_LIFETIME_SECS {
+        out["note"] = json!(format!("lifetime_secs is capped at {}", MAX_LIFETIME_SECS));
+    }
+    Ok(out)
+}
+
+/// Accept connections until the listener is stopped, its lifetime ends,
+/// or its mode is done (first capture, or `max_hits` served).
+async fn run_listener(
+    listener: TcpListener,
+    mode: Mode,
+    lifetime: Duration,
+    cancel: CancellationToken,
+    hits: Arc<AtomicU64>,
+    captured: Arc<OnceLock<CapturedRequest>>,
+) {
+    let deadline = tokio::time::sleep(lifetime);
+    tokio::pin!(deadline);
+    loop {
+        let mut stream = tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = &mut deadline => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    debug!(error = %e, "HTTP listener accept error");
+                    continue;
+                }
+            },
+        };
+        let served = hits.fetch_add(1, Ordering::Relaxed) + 1;
+        match &mode {
+            Mode::Capture { reply } => match read_request(&mut stream).await {
+                Ok(request) => {
+                    let _ =
+                        write_response(&mut stream, "200 OK", "text/plain", reply.as_bytes()).await;
+                    let _ = captured.set(request);
+                    break;
+                }
+                Err(e) => debug!(error = %e, "Ignoring malformed request"),
+            },
+            Mode::Serve { root, max_hits } => {
+                let root = root.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_path(stream, &root).await {
+                        debug!(error = %e, "HTTP serve request failed");
+                    }
+                });
+                if max_hits.is_some_and(|max| served >= max) {
+                    break;
+                }
+            }
+        }
+    }
+    cancel.cancel();
+    debug!(hits = hits.load(Ordering::Relaxed), "HTTP listener closed");
+}
+
+/// Wait up to `wait_secs` for listener `id` to capture its request.
+async fn wait_for_request(id: &str, wait_secs: u64) -> Result<Option<CapturedRequest>, String> {
+    let (captured, cancel) = {
+        let listeners = lock_listeners();
+        let listener = listeners.get(id).ok_or_else(|| unknown_listener(id))?;
+        if listener.mode != "capture" {
+            return Err(format!(
+                "Listener '{}' serves files; it captures nothing",
+                id
+            ));
+        }
+        (listener.captured.clone(), listener.cancel.clone())
+    };
+    // A capture listener closes as soon as it has its request.
+    let wait = Duration::from_secs(wait_secs);
+    let _ = tokio::time::timeout(wait, cancel.cancelled()).await;
+    Ok(captured.get().cloned())
+}
+
+/// Read one request: head, then as much body as `Content-Length` promises
+/// (up to [`MAX_BODY_BYTES`]).
+async fn read_request(stream: &mut TcpStream) -> Result<CapturedRequest, String> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if data.len() > MAX_HEAD_BYTES {
+            return Err("Request head too large".to_string());
+        }
+        let n = read_some(stream, &mut buf).await?;
+        if n == 0 {
+            return Err("Connection closed before the request was complete".to_string());
+        }
+        data.extend_from_slice(&buf[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&data[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(format!("Malformed request line: {}", request_line));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let headers: BTreeMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let length: usize = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let mut body = data.split_off(head_end + 4);
+    while body.len() < length.min(MAX_BODY_BYTES) {
+        let n = read_some(stream, &mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&buf[..n]);
+    }
+    let body_truncated = length > MAX_BODY_BYTES || body.len() > MAX_BODY_BYTES;
+    body.truncate(MAX_BODY_BYTES);
+
+    Ok(CapturedRequest {
+        method: method.to_string(),
+        path: urlencoding::decode(path)
+            .map(|p| p.into_owned())
+            .unwrap_or_else(|_| path.to_string()),
+        query: url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect(),
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+        body_truncated,
+    })
+}
+
+async fn read_some(stream: &mut TcpStream, buf: &mut [u8]) -> Result<usize, String> {
+    tokio::time::timeout(READ_TIMEOUT, stream.read(buf))
+        .await
+        .map_err(|_| "Timed out reading request".to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Answer one `GET`/`HEAD` with the shared file, or a file inside the
+/// shared directory.
+async fn serve_path(mut stream: TcpStream, root: &Path) -> Result<(), String> {
+    let request = read_request(&mut stream).await?;
+    let head_only = request.method == "HEAD";
+    if request.method != "GET" && !head_only {
+        return write_response(&mut stream, "405 Method Not Allowed", "text/plain", b"").await;
+    }
+
+    let Some(file) = locate(root, &request.path) else {
+        return write_response(&mut stream, "404 Not Found", "text/plain", b"Not found").await;
+    };
+    if file.is_dir() {
+        let index = file.join("index.html");
+        if !index.is_file() {
+            let listing = directory_listing(&file, &request.path);
+            let body = if head_only {
+                &[][..]
+            } else {
+                listing.as_bytes()
+            };
+            return write_response(&mut stream, "200 OK", "text/html; charset=utf-8", body).await;
+        }
+        return send_file(&mut stream, &index, head_only).await;
+    }
+    send_file(&mut stream, &file, head_only).await
+}
+
+/// The file `request_path` names under `root`, if it exists and stays
+/// inside `root` once symlinks are resolved.
+fn locate(root: &Path, request_path: &str) -> Option<PathBuf> {
+    if root.is_file() {
+        let name = root.file_name()?.to_string_lossy();
+        let wanted = request_path.trim_start_matches('/');
+        return (wanted.is_empty() || wanted == name).then(|| root.to_path_buf());
+    }
+    let mut path = root.to_path_buf();
+    for component in Path::new(request_path.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    let path = path.canonicalize().ok()?;
+    (path.starts_with(root) && !is_protected_path(&path)).then_some(path)
+}
+
+fn directory_listing(dir: &Path, request_path: &str) -> String {
+    let base = request_path.trim_end_matches('/');
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| {
+                    let mut name = e.file_name().to_string_lossy().into_owned();
+                    if e.path().is_dir() {
+                        name.push('/');
+                    }
+                    name
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    let items: String = names
+        .iter()
+        .map(|name| {
+            format!(
+                "<li><a href=\"{}/{}\">{}</a></li>\n",
+                base,
+                urlencoding::encode(name.trim_end_matches('/')),
+                html_escape(name)
+            )
+        })
+        .collect();
+    format!("<!DOCTYPE html>\n<ul>\n{}</ul>\n", items)
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+async fn send_file(stream: &mut TcpStream, path: &Path, head_only: bool) -> Result<(), String> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| e.to_string())?;
+    let len = file.metadata().await.map_err(|e| e.to_string())?.len();
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        content_type(path),
+        len
+    );
+    stream
+        .write_all(head.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    if !head_only {
+        tokio::io::copy(&mut file, stream)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    stream.shutdown().await.map_err(|e| e.to_string())
+}
+
+async fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<(), String> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    stream
+        .write_all(head.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    stream.write_all(body).await.map_err(|e| e.to_string())?;
+    stream.shutdown().await.map_err(|e| e.to_string())
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "txt" | "md" | "log" | "csv" => "text/plain; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "wasm" => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+pub fn http_server_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "Action: 'capture' (record the first request, e.g. an OAuth redirect \
+                          or webhook), 'result' (wait for a capture's request), 'serve' (share \
+                          a file or directory), 'stop', 'list'."
+                .into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "id".into(),
+            description: "Listener id returned by 'capture' or 'serve' (for 'result', 'stop')."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "path".into(),
+            description: "File or directory to share (required for 'serve').".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "port".into(),
+            description: "Port to listen on, e.g. a registered OAuth redirect port. Default: \
+                          a free ephemeral port."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "lifetime_secs".into(),
+            description: format!(
+                "Close the listener after this many seconds (default: {}, max: {}).",
+                DEFAULT_LIFETIME_SECS, MAX_LIFETIME_SECS
+            ),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "wait_secs".into(),
+            description: format!(
+                "How long 'result' waits for the request (default: {}). On 'capture', wait \
+                 this long before returning.",
+                DEFAULT_WAIT_SECS
+            ),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "max_hits".into(),
+            description: "For 'serve': close after answering this many requests.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "reply".into(),
+            description: "For 'capture': text sent back to the captured request.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,395 @@
+//! Image transform tool: resize, crop, convert and inspect images in-process.
+//!
+//! Pure Rust (the `image` crate), so agents don't need ImageMagick to
+//! shrink a screenshot before sending it or turn a PNG into a JPEG.  Every
+//! action that writes returns the new file as a [`MediaRef`], ready for the
+//! `image` tool or a messenger.
+//!
+//! Outputs are capped at [`MAX_DIMENSION`] pixels a side and
+//! [`MAX_OUTPUT_BYTES`] encoded; both paths go through the same protected
+//! path and sandbox checks as the file tools.  Decoding and encoding need
+//! the `image-tools` feature.
+
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tracing::{instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+
+/// Largest width or height an output may have.
+pub const MAX_DIMENSION: u32 = 16_384;
+
+/// Largest encoded output written (50 MiB).
+pub const MAX_OUTPUT_BYTES: usize = 50 * 1024 * 1024;
+
+/// JPEG quality used when the call gives none.
+const DEFAULT_JPEG_QUALITY: u8 = 85;
+
+const FORMATS: &str = "png, jpeg, gif, webp, bmp";
+
+/// Execute the `image_transform` tool.
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub fn exec_image_transform(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: action".to_string())?;
+    tracing::Span::current().record("action", action);
+    if !matches!(action, "info" | "resize" | "crop" | "convert") {
+        return Err(format!(
+            "Unknown action: '{}'. Use: info, resize, crop, convert",
+            action
+        ));
+    }
+    let path_str = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: path".to_string())?;
+    let input = checked_path(workspace_dir, path_str)?;
+    if !input.is_file() {
+        return Err(format!("File not found: {}", input.display()));
+    }
+    imp::run(action, args, workspace_dir, &input)
+}
+
+#[cfg(feature = "image-tools")]
+mod imp {
+    use super::*;
+    use crate::gateway::MediaRef;
+    use image::codecs::jpeg::JpegEncoder;
+    use image::imageops::FilterType;
+    use image::{DynamicImage, ImageFormat, ImageReader};
+    use tracing::debug;
+
+    pub(super) fn run(
+        action: &str,
+        args: &Value,
+        workspace_dir: &Path,
+        input: &Path,
+    ) -> Result<String, String> {
+        let reader = ImageReader::open(input)
+            .and_then(|r| r.with_guessed_format())
+            .map_err(|e| format!("Failed to read '{}': {}", input.display(), e))?;
+        let source_format = reader.format();
+        let image = reader
+            .decode()
+            .map_err(|e| format!("Failed to decode '{}': {}", input.display(), e))?;
+
+        let (image, suffix) = match action {
+            "info" => {
+                let bytes = std::fs::metadata(input).map(|m| m.len()).unwrap_or(0);
+                let out = json!({
+                    "path": input.display().to_string(),
+                    "width": image.width(),
+                    "height": image.height(),
+                    "format": source_format.map(format_name),
+                    "color": format!("{:?}", image.color()),
+                    "bytes": bytes,
+                });
+                return serde_json::to_string_pretty(&out).map_err(|e| e.to_string());
+            }
+            "resize" => {
+                let resized = resize(&image, args)?;
+                let suffix = format!("{}x{}", resized.width(), resized.height());
+                (resized, suffix)
+            }
+            "crop" => (crop(&image, args)?, "crop".to_string()),
+            _ => (image, String::new()),
+        };
+
+        let format = output_format(args, source_format)?;
+        let output = match args.get("output_path").and_then(|v| v.as_str()) {
+            Some(p) => checked_path(workspace_dir, p)?,
+            None => checked_path(
+                workspace_dir,
+                &default_output(input, &suffix, format).to_string_lossy(),
+            )?,
+        };
+        let quality = args
+            .get("quality")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_JPEG_QUALITY, |q| q.clamp(1, 100) as u8);
+        let encoded = encode(&image, format, quality)?;
+        if encoded.len() > MAX_OUTPUT_BYTES {
+            return Err(format!(
+                "Output would be {} bytes, over the {} byte limit",
+                encoded.len(),
+                MAX_OUTPUT_BYTES
+            ));
+        }
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+        }
+        std::fs::write(&output, &encoded)
+            .map_err(|e| format!("Failed to write '{}': {}", output.display(), e))?;
+        debug!(
+            path = %output.display(),
+            width = image.width(),
+            height = image.height(),
+            bytes = encoded.len(),
+            "Wrote transformed image"
+        );
+
+        let mut media = MediaRef::new(format.to_mime_type().to_string());
+        media.filename = output.file_name().map(|n| n.to_string_lossy().into_owned());
+        media.size = Some(encoded.len());
+        media.local_path = Some(output.display().to_string());
+        let out = json!({
+            "path": output.display().to_string(),
+            "width": image.width(),
+            "height": image.height(),
+            "format": format_name(format),
+            "bytes": encoded.len(),
+            "media": media,
+        });
+        serde_json::to_string_pretty(&out).map_err(|e| e.to_string())
+    }
+
+    /// Scale to `width` and/or `height`.  With `keep_aspect` (the default)
+    /// the image fits inside the box, so giving one side derives the other.
+    fn resize(image: &DynamicImage, args: &Value) -> Result<DynamicImage, String> {
+        let width = dimension(args, "width")?;
+        let height = dimension(args, "height")?;
+        let keep_aspect = args
+            .get("keep_aspect")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let (w, h) = (image.width() as f64, image.height() as f64);
+        let (target_w, target_h) = match (width, height) {
+            (None, None) => return Err("resize needs width, height or both".to_string()),
+            (Some(width), None) => (width, ((h * width as f64 / w).round() as u32).max(1)),
+            (None, Some(height)) => (((w * height as f64 / h).round() as u32).max(1), height),
+            (Some(width), Some(height)) => (width, height),
+        };
+        check_size(target_w, target_h)?;
+        Ok(if keep_aspect {
+            image.resize(target_w, target_h, FilterType::Lanczos3)
+        } else {
+            image.resize_exact(target_w, target_h, FilterType::Lanczos3)
+        })
+    }
+
+    fn crop(image: &DynamicImage, args: &Value) -> Result<DynamicImage, String> {
+        let x = offset(args, "x")?;
+        let y = offset(args, "y")?;
+        let width = dimension(args, "width")?
+            .ok_or_else(|| "Missing required parameter: width".to_string())?;
+        let height = dimension(args, "height")?
+            .ok_or_else(|| "Missing required parameter: height".to_string())?;
+        if x.saturating_add(width) > image.width() || y.saturating_add(height) > image.height() {
+            return Err(format!(
+                "Crop {}x{} at ({}, {}) falls outside the {}x{} image",
+                width,
+                height,
+                x,
+                y,
+                image.width(),
+                image.height()
+            ));
+        }
+        Ok(image.crop_imm(x, y, width, height))
+    }
+
+    fn dimension(args: &Value, name: &str) -> Result<Option<u32>, String> {
+        match args.get(name).and_then(|v| v.as_u64()) {
+            None => Ok(None),
+            Some(0) => Err(format!("{} must be at least 1", name)),
+            Some(n) if n > MAX_DIMENSION as u64 => Err(format!(
+                "{} is {}; outputs are capped at {} pixels a side",
+                name, n, MAX_DIMENSION
+            )),
+            Some(n) => Ok(Some(n as u32)),
+        }
+    }
+
+    fn offset(args: &Value, name: &str) -> Result<u32, String> {
+        let n = args.get(name).and_then(|v| v.as_u64()).unwrap_or(0);
+        u32::try_from(n).map_err(|_| format!("{} is out of range", name))
+    }
+
+    fn check_size(width: u32, height: u32) -> Result<(), String> {
+        if width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return Err(format!(
+                "Output would be {}x{}; outputs are capped at {} pixels a side",
+                width, height, MAX_DIMENSION
+            ));
+        }
+        Ok(())
+    }
+
+    /// The requested `format`, else the output path's extension, else the
+    /// input's own format.
+    fn output_format(args: &Value, source: Option<ImageFormat>) -> Result<ImageFormat, String> {
+        let requested = args.get("format").and_then(|v| v.as_str()).or_else(|| {
+            args.get("output_path")
+                .and_then(|v| v.as_str())
+                .and_then(|p| Path::new(p).extension())
+                .and_then(|e| e.to_str())
+        });
+        let format = match requested {
+            Some(name) => ImageFormat::from_extension(name.to_ascii_lowercase())
+                .ok_or_else(|| format!("Unknown format: '{}'. Use: {}", name, FORMATS))?,
+            None => source.unwrap_or(ImageFormat::Png),
+        };
+        match format {
+            ImageFormat::Png
+            | ImageFormat::Jpeg
+            | ImageFormat::Gif
+            | ImageFormat::WebP
+            | ImageFormat::Bmp => Ok(format),
+            other => Err(format!(
+                "Cannot write {}. Use: {}",
+                format_name(other),
+                FORMATS
+            )),
+        }
+    }
+
+    fn encode(image: &DynamicImage, format: ImageFormat, quality: u8) -> Result<Vec<u8>, String> {
+        let mut buf = Vec::new();
+        let result = match format {
+            // JPEG has no alpha channel.
+            ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+                .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, quality)),
+            ImageFormat::Png => image.write_to(&mut std::io::Cursor::new(&mut buf), format),
+            // The GIF, WebP and BMP encoders take 8-bit RGBA.
+            _ => DynamicImage::ImageRgba8(image.to_rgba8())
+                .write_to(&mut std::io::Cursor::new(&mut buf), format),
+        };
+        result.map_err(|e| format!("Failed to encode {}: {}", format_name(format), e))?;
+        Ok(buf)
+    }
+
+    fn format_name(format: ImageFormat) -> &'static str {
+        format
+            .extensions_str()
+            .first()
+            .copied()
+            .unwrap_or("unknown")
+    }
+
+    /// `<stem>-<suffix>.<ext>` beside the input, or `<stem>.<ext>` for a
+    /// plain conversion unless that would overwrite the input.
+    fn default_output(input: &Path, suffix: &str, format: ImageFormat) -> PathBuf {
+        let stem = input
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "image".to_string());
+        let ext = format_name(format);
+        let mut output = input.with_file_name(if suffix.is_empty() {
+            format!("{}.{}", stem, ext)
+        } else {
+            format!("{}-{}.{}", stem, suffix, ext)
+        });
+        if output == input {
+            output = input.with_file_name(format!("{}-converted.{}", stem, ext));
+        }
+        output
+    }
+}
+
+#[cfg(not(feature = "image-tools"))]
+mod imp {
+    use super::*;
+
+    pub(super) fn run(
+        _action: &str,
+        _args: &Value,
+        _workspace_dir: &Path,
+        _input: &Path,
+    ) -> Result<String, String> {
+        Err("image_transform needs the image-tools feature, which this build lacks".to_string())
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn checked_path(workspace_dir: &Path, p: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, p);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted image_transform access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn image_transform_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "One of: info (dimensions and format), resize, crop, convert.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "path".into(),
+            description: "Image to read.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "width".into(),
+            description: "For 'resize': target width (give width, height or both). For \
+                          'crop': width of the region."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "height".into(),
+            description: "For 'resize': target height. For 'crop': height of the region.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "keep_aspect".into(),
+            description: "For 'resize': fit inside width x height keeping the aspect ratio \
+                          (default: true); false stretches to exactly that size."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "x".into(),
+            description: "For 'crop': left edge of the region (default: 0).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "y".into(),
+            description: "For 'crop': top edge of the region (default: 0).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "format".into(),
+            description: format!(
+                "Output format: {}. Default: output_path's extension, else the input's format.",
+                FORMATS
+            ),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "quality".into(),
+            description: format!("JPEG quality, 1-100 (default: {}).", DEFAULT_JPEG_QUALITY),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "output_path".into(),
+            description: "Where to write the result. Default: beside the input, e.g. \
+                          photo-800x600.jpg or photo.png."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,202 @@
+//! Key/value tool: durable scratch space for structured working state.
+//!
+//! Values are arbitrary JSON, kept in `.rustyclaw/kv_store.json` under the
+//! workspace so they survive between turns and gateway restarts.  Keys are
+//! scoped to the calling session (see [`session`](super::session); the
+//! conversation thread the gateway is serving) unless the call passes
+//! `global: true`, in which case they are shared by every session.
+//!
+//! The whole store is capped at [`MAX_STORE_BYTES`]; a `set` that would
+//! grow it past that is refused and nothing is written.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, instrument};
+
+use super::{ToolParam, session};
+
+const ACTIONS: &str = "get, set, delete, list";
+
+/// Largest the store file may grow to (1 MiB).
+pub(crate) const MAX_STORE_BYTES: usize = 1024 * 1024;
+
+/// Longest key accepted.
+const MAX_KEY_LEN: usize = 256;
+
+/// Serialises read-modify-write cycles on the store file.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Store {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    global: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    sessions: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+fn store_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join(".rustyclaw").join("kv_store.json")
+}
+
+fn load(path: &Path) -> Result<Store, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map_err(|e| format!("Key/value store {} is corrupt: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Store::default()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+fn save(path: &Path, store: &Store) -> Result<(), String> {
+    let text = serde_json::to_string(store).map_err(|e| e.to_string())?;
+    if text.len() > MAX_STORE_BYTES {
+        return Err(format!(
+            "Key/value store would grow to {} bytes, over the {} byte limit. \
+             Delete keys you no longer need first.",
+            text.len(),
+            MAX_STORE_BYTES
+        ));
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, text).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn required_key(args: &Value) -> Result<&str, String> {
+    let key = args
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: key")?;
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(format!("Keys must be 1 to {} bytes long", MAX_KEY_LEN));
+    }
+    Ok(key)
+}
+
+/// The value to store.  Strings holding JSON are stored as that JSON, so
+/// `'{"n": 1}'` becomes an object; any other string is stored as-is.
+fn parse_value(args: &Value) -> Result<Value, String> {
+    match args.get("value") {
+        Some(Value::String(s)) => Ok(serde_json::from_str(s).unwrap_or_else(|_| json!(s))),
+        Some(value) => Ok(value.clone()),
+        None => Err("Missing required parameter: value".to_string()),
+    }
+}
+
+/// Execute the `kv_store` tool in the calling session.
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub fn exec_kv_store(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    exec_kv_store_in(args, workspace_dir, &session::current_or_default())
+}
+
+/// Execute the `kv_store` tool with unscoped keys in `session`.
+pub(crate) fn exec_kv_store_in(
+    args: &Value,
+    workspace_dir: &Path,
+    session: &str,
+) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: action")?;
+    tracing::Span::current().record("action", action);
+    let global = args
+        .get("global")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let scope = if global { "global" } else { "session" };
+
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = store_path(workspace_dir);
+    let mut store = load(&path)?;
+    let entries = if global {
+        &mut store.global
+    } else {
+        store.sessions.entry(session.to_string()).or_default()
+    };
+
+    match action {
+        "get" => {
+            let key = required_key(args)?;
+            Ok(match entries.get(key) {
+                Some(value) => json!({ "key": key, "scope": scope, "found": true, "value": value }),
+                None => json!({ "key": key, "scope": scope, "found": false }),
+            }
+            .to_string())
+        }
+
+        "set" => {
+            let key = required_key(args)?;
+            let value = parse_value(args)?;
+            let replaced = entries.insert(key.to_string(), value).is_some();
+            save(&path, &store)?;
+            debug!(key, scope, replaced, "Stored key");
+            let out = json!({ "action": "set", "key": key, "scope": scope, "replaced": replaced });
+            Ok(out.to_string())
+        }
+
+        "delete" => {
+            let key = required_key(args)?;
+            let removed = entries.remove(key).is_some();
+            if entries.is_empty() && !global {
+                store.sessions.remove(session);
+            }
+            if removed {
+                save(&path, &store)?;
+            }
+            debug!(key, scope, removed, "Deleted key");
+            let out = json!({ "action": "delete", "key": key, "scope": scope, "removed": removed });
+            Ok(out.to_string())
+        }
+
+        "list" => {
+            let keys: Vec<&String> = entries.keys().collect();
+            Ok(json!({ "scope": scope, "count": keys.len(), "keys": keys }).to_string())
+        }
+
+        _ => Err(format!(
+            "Unknown action: '{}'. Valid actions: {}",
+            action, ACTIONS
+        )),
+    }
+}
+
+pub fn kv_store_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "Action: 'get', 'set', 'delete', or 'list' (keys in the scope).".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "key".into(),
+            description: "Key to read, write or delete (required except for 'list').".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "value".into(),
+            description: "Value for 'set'. JSON text (e.g. '{\"done\": 3}', '[1, 2]', '42') \
+                          is stored as that JSON; anything else as a plain string."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "global".into(),
+            description: "Use keys shared by every session instead of this session's \
+                          (default: false)."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,600 @@
+//! Lint tool: run the project's linters and return parsed diagnostics.
+//!
+//! Looks at the project directory to decide what applies — `Cargo.toml`
+//! means clippy, `package.json` or an ESLint config means eslint, Python
+//! project files mean ruff (or flake8 when ruff isn't installed), and shell
+//! scripts mean shellcheck — then runs each installed linter in its
+//! machine-readable output mode and turns the result into one list of
+//! `{file, line, column, severity, code, message}` diagnostics.
+//!
+//! Linters run through the sandbox like any other command, under
+//! coreutils `timeout` where it is available.  `fix=true` applies the
+//! linter's own autofixes (clippy, eslint and ruff); flake8 and shellcheck
+//! have none and only report.
+
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::git_tool::shell_quote;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, run_sandboxed_command};
+
+/// Seconds a linter may run when the call doesn't say.
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Longest timeout a call may ask for.
+const MAX_TIMEOUT_SECS: u64 = 600;
+
+/// Diagnostics returned when the call doesn't say.
+const DEFAULT_MAX_DIAGNOSTICS: usize = 200;
+
+/// Upper bound on returned diagnostics regardless of the request.
+const MAX_DIAGNOSTICS: usize = 1000;
+
+/// Shell scripts passed to one shellcheck run.
+const MAX_SHELL_FILES: usize = 200;
+
+/// Directories never searched for scripts or Python files.
+const SKIP_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    ".venv",
+    "venv",
+    "__pycache__",
+    "dist",
+    "build",
+];
+
+/// Exit status of `timeout` when it stopped the command.
+const TIMED_OUT: i32 = 124;
+
+/// A linter this tool knows how to run and parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Linter {
+    Clippy,
+    Eslint,
+    Ruff,
+    Flake8,
+    Shellcheck,
+}
+
+impl Linter {
+    const ALL: [Linter; 5] = [
+        Linter::Clippy,
+        Linter::Eslint,
+        Linter::Ruff,
+        Linter::Flake8,
+        Linter::Shellcheck,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Linter::Clippy => "clippy",
+            Linter::Eslint => "eslint",
+            Linter::Ruff => "ruff",
+            Linter::Flake8 => "flake8",
+            Linter::Shellcheck => "shellcheck",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.name() == name)
+    }
+
+    fn supports_fix(self) -> bool {
+        matches!(self, Linter::Clippy | Linter::Eslint | Linter::Ruff)
+    }
+}
+
+/// One finding, in the same shape whichever linter reported it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Diagnostic {
+    pub linter: &'static str,
+    pub file: String,
+    pub line: u64,
+    pub column: u64,
+    /// `error`, `warning` or `info`.
+    pub severity: &'static str,
+    /// Rule or lint name, e.g. `clippy::needless_return`, `no-unused-vars`.
+    pub code: Option<String>,
+    pub message: String,
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `lint` tool.
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_lint(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let dir = match args.get("path").and_then(|v| v.as_str()) {
+        Some(p) => resolve_path(workspace_dir, p),
+        None => workspace_dir.to_path_buf(),
+    };
+    if is_protected_path(&dir) {
+        warn!(path = %dir.display(), "Attempted lint of protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
+    }
+
+    let fix = args.get("fix").and_then(|v| v.as_bool()).unwrap_or(false);
+    let timeout_secs = args
+        .get("timeout_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(1, MAX_TIMEOUT_SECS);
+    let max_diagnostics = args
+        .get("max_diagnostics")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_MAX_DIAGNOSTICS)
+        .clamp(1, MAX_DIAGNOSTICS);
+
+    let mut skipped = Vec::new();
+    let linters = match args.get("linter").and_then(|v| v.as_str()) {
+        Some(name) => {
+            let linter = Linter::from_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown linter: '{}'. Use: clippy, eslint, ruff, flake8, shellcheck",
+                    name
+                )
+            })?;
+            if !is_installed(linter, &dir) {
+                return Err(format!("{} is not installed", name));
+            }
+            vec![linter]
+        }
+        None => choose_linters(&dir, &mut skipped)?,
+    };
+    debug!(dir = %dir.display(), ?linters, fix, "Running linters");
+
+    let mut diagnostics = Vec::new();
+    let mut runs = Vec::new();
+    for linter in linters {
+        let fixing = fix && linter.supports_fix();
+        match run_linter(linter, &dir, fixing, timeout_secs) {
+            Ok(found) => {
+                runs.push(json!({
+                    "linter": linter.name(),
+                    "diagnostics": found.len(),
+                    "fixed": fixing,
+                    "fix_supported": linter.supports_fix(),
+                }));
+                diagnostics.extend(found);
+            }
+            Err(e) => {
+                warn!(linter = linter.name(), error = %e, "Linter failed");
+                runs.push(json!({ "linter": linter.name(), "error": e }));
+            }
+        }
+    }
+    if runs.iter().all(|r| r.get("error").is_some()) {
+        let errors: Vec<String> = runs
+            .iter()
+            .map(|r| {
+                format!(
+                    "{}: {}",
+                    r["linter"].as_str().unwrap_or(""),
+                    r["error"].as_str().unwrap_or("")
+                )
+            })
+            .collect();
+        return Err(format!("Every linter failed. {}", errors.join("; ")));
+    }
+
+    let count = |severity: &str| {
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    };
+    let counts = json!({
+        "error": count("error"),
+        "warning": count("warning"),
+        "info": count("info"),
+    });
+    let total = diagnostics.len();
+    diagnostics.truncate(max_diagnostics);
+
+    Ok(json!({
+        "path": dir.display().to_string(),
+        "linters": runs,
+        "skipped": skipped,
+        "counts": counts,
+        "total": total,
+        "truncated": total > diagnostics.len(),
+        "diagnostics": diagnostics,
+    })
+    .to_string())
+}
+
+// ── Detection ───────────────────────────────────────────────────────────────
+
+/// The installed linters that apply to `dir`.  Ones that apply but aren't
+/// installed are listed in `skipped`; an error says why nothing can run.
+fn choose_linters(dir: &Path, skipped: &mut Vec<Value>) -> Result<Vec<Linter>, String> {
+    let has = |name: &str| dir.join(name).exists();
+    let has_any = |names: &[&str]| names.iter().any(|n| has(n));
+
+    let mut wanted: Vec<Vec<Linter>> = Vec::new();
+    if has("Cargo.toml") {
+        wanted.push(vec![Linter::Clippy]);
+    }
+    if has_any(&[
+        "package.json",
+        "eslint.config.js",
+        "eslint.config.mjs",
+        "eslint.config.cjs",
+        ".eslintrc",
+        ".eslintrc.js",
+        ".eslintrc.cjs",
+        ".eslintrc.json",
+        ".eslintrc.yml",
+    ]) {
+        wanted.push(vec![Linter::Eslint]);
+    }
+    if has_any(&[
+        "pyproject.toml",
+        "setup.py",
+        "setup.cfg",
+        "requirements.txt",
+    ]) || !find_files(dir, "py", 1).is_empty()
+    {
+        // Either one will do; ruff is faster and can fix.
+        wanted.push(vec![Linter::Ruff, Linter::Flake8]);
+    }
+    if !find_files(dir, "sh", 1).is_empty() {
+        wanted.push(vec![Linter::Shellcheck]);
+    }
+
+    if wanted.is_empty() {
+        return Err(format!(
+            "No linter applies to {}: found no Cargo.toml, package.json or ESLint \
+             config, Python project files, or shell scripts",
+            dir.display()
+        ));
+    }
+
+    let mut chosen = Vec::new();
+    for choices in wanted {
+        match choices.iter().find(|l| is_installed(**l, dir)) {
+            Some(linter) => chosen.push(*linter),
+            None => {
+                let names: Vec<&str> = choices.iter().map(|l| l.name()).collect();
+                skipped.push(json!({
+                    "linter": names.join(" or "),
+                    "reason": "not installed",
+                }));
+            }
+        }
+    }
+    if chosen.is_empty() {
+        let missing: Vec<&str> = skipped
+            .iter()
+            .filter_map(|s| s["linter"].as_str())
+            .collect();
+        return Err(format!(
+            "This project wants {}, but none is installed",
+            missing.join(", ")
+        ));
+    }
+    Ok(chosen)
+}
+
+fn is_installed(linter: Linter, dir: &Path) -> bool {
+    match linter {
+        Linter::Clippy => which::which("cargo-clippy").is_ok(),
+        Linter::Eslint => local_eslint(dir).is_some() || which::which("eslint").is_ok(),
+        other => which::which(other.name()).is_ok(),
+    }
+}
+
+/// The project's own eslint, which knows its plugins and config.
+fn local_eslint(dir: &Path) -> Option<PathBuf> {
+    let bin = dir.join("node_modules").join(".bin").join("eslint");
+    bin.is_file().then_some(bin)
+}
+
+/// Files under `dir` with extension `ext`, relative to `dir`, up to `limit`.
+fn find_files(dir: &Path, ext: &str, limit: usize) -> Vec<String> {
+    walkdir::WalkDir::new(dir)
+        .max_depth(6)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !(e.file_type().is_dir()
+                    && SKIP_DIRS.contains(&e.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|x| x == ext))
+        .filter_map(|e| {
+            e.path()
+                .strip_prefix(dir)
+                .ok()
+                .map(|p| p.to_string_lossy().into_owned())
+        })
+        .take(limit)
+        .collect()
+}
+
+// ── Running ─────────────────────────────────────────────────────────────────
+
+fn run_linter(
+    linter: Linter,
+    dir: &Path,
+    fix: bool,
+    timeout_secs: u64,
+) -> Result<Vec<Diagnostic>, String> {
+    let command = match linter {
+        Linter::Clippy if fix => {
+            "cargo clippy --fix --allow-dirty --allow-staged --message-format=json --quiet"
+                .to_string()
+        }
+        Linter::Clippy => "cargo clippy --message-format=json --quiet".to_string(),
+        Linter::Eslint => {
+            let bin = local_eslint(dir)
+                .map(|p| shell_quote(&p.to_string_lossy()))
+                .unwrap_or_else(|| "eslint".into());
+            format!("{} --format json{} .", bin, if fix { " --fix" } else { "" })
+        }
+        Linter::Ruff => format!(
+            "ruff check --output-format json --no-cache{} .",
+            if fix { " --fix" } else { "" }
+        ),
+        Linter::Flake8 => "flake8 .".to_string(),
+        Linter::Shellcheck => {
+            let files = find_files(dir, "sh", MAX_SHELL_FILES);
+            let quoted: Vec<String> = files.iter().map(|f| shell_quote(f)).collect();
+            format!("shellcheck --format json1 -- {}", quoted.join(" "))
+        }
+    };
+    let command = match timeout_prefix(timeout_secs) {
+        Some(prefix) => format!("{} {}", prefix, command),
+        None => command,
+    };
+
+    let output = run_sandboxed_command(&command, dir)?;
+    if output.status.code() == Some(TIMED_OUT) {
+        return Err(format!(
+            "{} took longer than {}s and was stopped",
+            linter.name(),
+            timeout_secs
+        ));
+    }
+    // Linters exit non-zero when they find something, so the status alone
+    // says little: a run failed when it printed nothing or nothing readable.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let failed = |detail: String| {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match stderr.trim() {
+            "" => format!("{} failed: {}", linter.name(), detail),
+            stderr => format!("{} failed: {}", linter.name(), clip(stderr, 2000)),
+        }
+    };
+    if stdout.trim().is_empty() && !output.status.success() {
+        return Err(failed(output.status.to_string()));
+    }
+    match linter {
+        Linter::Clippy => Ok(parse_clippy(&stdout)),
+        Linter::Eslint => parse_eslint(&stdout, dir),
+        Linter::Ruff => parse_ruff(&stdout, dir),
+        Linter::Flake8 => Ok(parse_flake8(&stdout)),
+        Linter::Shellcheck => parse_shellcheck(&stdout),
+    }
+    .map_err(failed)
+}
+
+/// `timeout` (or Homebrew's `gtimeout`) bounding a command to `secs`.
+fn timeout_prefix(secs: u64) -> Option<String> {
+    ["timeout", "gtimeout"]
+        .into_iter()
+        .find(|bin| which::which(bin).is_ok())
+        .map(|bin| format!("{} -k 5 {}", bin, secs))
+}
+
+fn clip(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+// ── Parsers ─────────────────────────────────────────────────────────────────
+
+/// Parse `cargo clippy --message-format=json`: one JSON object per line,
+/// of which the `compiler-message` ones with a primary span are findings.
+/// The same finding reported for several targets is kept once.
+pub(crate) fn parse_clippy(raw: &str) -> Vec<Diagnostic> {
+    let mut found: Vec<Diagnostic> = Vec::new();
+    for line in raw.lines() {
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if record["reason"] != "compiler-message" {
+            continue;
+        }
+        let message = &record["message"];
+        let Some(span) = message["spans"]
+            .as_array()
+            .and_then(|spans| spans.iter().find(|s| s["is_primary"] == true))
+        else {
+            // Summaries such as "3 warnings emitted" have no location.
+            continue;
+        };
+        let severity = match message["level"].as_str().unwrap_or("") {
+            "error" | "error: internal compiler error" => "error",
+            "warning" => "warning",
+            _ => "info",
+        };
+        let diagnostic = Diagnostic {
+            linter: Linter::Clippy.name(),
+            file: span["file_name"].as_str().unwrap_or("").to_string(),
+            line: span["line_start"].as_u64().unwrap_or(0),
+            column: span["column_start"].as_u64().unwrap_or(0),
+            severity,
+            code: message["code"]["code"].as_str().map(str::to_string),
+            message: message["message"].as_str().unwrap_or("").to_string(),
+        };
+        if !found.contains(&diagnostic) {
+            found.push(diagnostic);
+        }
+    }
+    found
+}
+
+/// Parse `eslint --format json`: one entry per file, each with its
+/// messages.  Severity 2 is an error, 1 a warning.
+pub(crate) fn parse_eslint(raw: &str, root: &Path) -> Result<Vec<Diagnostic>, String> {
+    let files: Vec<Value> = serde_json::from_str(raw.trim())
+        .map_err(|e| format!("Could not read eslint output: {}", e))?;
+    let mut found = Vec::new();
+    for file in &files {
+        let path = relative(file["filePath"].as_str().unwrap_or(""), root);
+        for message in file["messages"].as_array().into_iter().flatten() {
+            found.push(Diagnostic {
+                linter: Linter::Eslint.name(),
+                file: path.clone(),
+                line: message["line"].as_u64().unwrap_or(0),
+                column: message["column"].as_u64().unwrap_or(0),
+                severity: if message["severity"].as_u64() == Some(2) {
+                    "error"
+                } else {
+                    "warning"
+                },
+                code: message["ruleId"].as_str().map(str::to_string),
+                message: message["message"].as_str().unwrap_or("").to_string(),
+            });
+        }
+    }
+    Ok(found)
+}
+
+/// Parse `ruff check --output-format json`.  Ruff has no severities;
+/// syntax errors (no rule code, or `E9xx`) count as errors.
+pub(crate) fn parse_ruff(raw: &str, root: &Path) -> Result<Vec<Diagnostic>, String> {
+    let items: Vec<Value> = serde_json::from_str(raw.trim())
+        .map_err(|e| format!("Could not read ruff output: {}", e))?;
+    Ok(items
+        .iter()
+        .map(|item| {
+            let code = item["code"].as_str().map(str::to_string);
+            Diagnostic {
+                linter: Linter::Ruff.name(),
+                file: relative(item["filename"].as_str().unwrap_or(""), root),
+                line: item["location"]["row"].as_u64().unwrap_or(0),
+                column: item["location"]["column"].as_u64().unwrap_or(0),
+                severity: python_severity(code.as_deref()),
+                code,
+                message: item["message"].as_str().unwrap_or("").to_string(),
+            }
+        })
+        .collect())
+}
+
+/// Parse flake8's default `path:row:col: CODE message` lines.
+pub(crate) fn parse_flake8(raw: &str) -> Vec<Diagnostic> {
+    raw.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, ':');
+            let file = parts.next()?;
+            let row = parts.next()?.parse().ok()?;
+            let col = parts.next()?.parse().ok()?;
+            let rest = parts.next()?.trim();
+            let (code, message) = rest.split_once(' ').unwrap_or((rest, ""));
+            Some(Diagnostic {
+                linter: Linter::Flake8.name(),
+                file: file.trim_start_matches("./").to_string(),
+                line: row,
+                column: col,
+                severity: python_severity(Some(code)),
+                code: Some(code.to_string()),
+                message: message.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parse `shellcheck --format json1`.  `style` notes count as `info`.
+pub(crate) fn parse_shellcheck(raw: &str) -> Result<Vec<Diagnostic>, String> {
+    let report: Value = serde_json::from_str(raw.trim())
+        .map_err(|e| format!("Could not read shellcheck output: {}", e))?;
+    Ok(report["comments"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|comment| Diagnostic {
+            linter: Linter::Shellcheck.name(),
+            file: comment["file"].as_str().unwrap_or("").to_string(),
+            line: comment["line"].as_u64().unwrap_or(0),
+            column: comment["column"].as_u64().unwrap_or(0),
+            severity: match comment["level"].as_str() {
+                Some("error") => "error",
+                Some("warning") => "warning",
+                _ => "info",
+            },
+            code: comment["code"].as_u64().map(|c| format!("SC{}", c)),
+            message: comment["message"].as_str().unwrap_or("").to_string(),
+        })
+        .collect())
+}
+
+fn python_severity(code: Option<&str>) -> &'static str {
+    match code {
+        None => "error",
+        Some(code) if code.starts_with("E9") => "error",
+        Some(_) => "warning",
+    }
+}
+
+/// `path` relative to `root` when it is inside it.
+fn relative(path: &str, root: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(root)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+// ── Parameter definitions ───────────────────────────────────────────────────
+
+pub fn lint_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "path".into(),
+            description: "Project directory to lint (default: workspace).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "linter".into(),
+            description: "Run only this linter: 'clippy', 'eslint', 'ruff', 'flake8' or \
+                          'shellcheck'. Default: every installed linter that fits the project."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "fix".into(),
+            description: "Apply the linter's automatic fixes (clippy, eslint and ruff only); \
+                          the remaining diagnostics are returned. Default: false."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "timeout_secs".into(),
+            description: "Seconds each linter may run (default 120, max 600).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "max_diagnostics".into(),
+            description: "Most diagnostics to return (default 200, max 1000).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
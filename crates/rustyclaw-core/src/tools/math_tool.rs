@@ -0,0 +1,1139 @@
+//! Math tool: exact arithmetic and unit conversion.
+//!
+//! `eval` parses an expression — `+ - * / % ^`, parentheses, functions
+//! such as `sqrt` and `ln`, the constants `pi`, `e` and `tau` — and keeps
+//! the value as an exact fraction for as long as the operations allow, so
+//! `0.1 + 0.2` is `3/10` rather than `0.30000000000000004`.  Functions
+//! with irrational results switch to floating point, and the result says
+//! which it is.
+//!
+//! `convert` handles length, mass, temperature and data sizes from a
+//! built-in table, and currencies with the European Central Bank's
+//! reference rates (through frankfurter.dev), fetched once an hour.
+
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+
+/// Longest expression accepted, in characters.
+const MAX_EXPRESSION_LEN: usize = 1000;
+
+/// Deepest nesting of parentheses and unary operators.
+const MAX_DEPTH: usize = 64;
+
+/// Largest exponent magnitude; `10^10^10` is an error, not a hang.
+const MAX_EXPONENT: f64 = 10_000.0;
+
+/// Fraction digits in the `decimal` form of an exact result.
+const DECIMAL_PLACES: usize = 20;
+
+/// Significant digits kept in floating-point results, which hides the
+/// last-bit noise of unit factors (`212.00000000000003`).
+const FLOAT_DIGITS: usize = 15;
+const CONVERT_DIGITS: usize = 12;
+
+const RATES_URL: &str = "https://api.frankfurter.dev/v1/latest";
+const RATES_TTL_SECS: u64 = 3600;
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+/// Base currencies whose rates are kept at once.
+const MAX_CACHE_ENTRIES: usize = 32;
+
+/// Rate tables by base currency, with the time they were fetched.
+static RATES: LazyLock<Mutex<HashMap<String, (Instant, Value)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `math` tool.
+#[instrument(skip(args, _workspace_dir), fields(action))]
+pub fn exec_math(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let action = match args.get("action").and_then(|v| v.as_str()) {
+        Some(action) => action,
+        None if args.get("expression").is_some() => "eval",
+        None if args.get("from").is_some() => "convert",
+        None => return Err("Missing required parameter: action".to_string()),
+    };
+    tracing::Span::current().record("action", action);
+    let out = match action {
+        "eval" => {
+            let expression = args
+                .get("expression")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Missing required parameter: expression".to_string())?;
+            eval_json(expression)?
+        }
+        "convert" => {
+            let value = args
+                .get("value")
+                .and_then(number_arg)
+                .ok_or_else(|| "Missing required parameter: value (a number)".to_string())?;
+            let unit = |key: &str| {
+                args.get(key)
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .ok_or_else(|| format!("Missing required parameter: {}", key))
+            };
+            convert_json(value, unit("from")?, unit("to")?)?
+        }
+        other => {
+            return Err(format!(
+                "Unknown action: '{}'. Use 'eval' or 'convert'",
+                other
+            ));
+        }
+    };
+    Ok(out.to_string())
+}
+
+/// A number, or a string holding an expression such as `"5/8"`.
+fn number_arg(v: &Value) -> Option<f64> {
+    v.as_f64().or_else(|| {
+        v.as_str()
+            .and_then(|s| evaluate(s).ok())
+            .map(Number::to_f64)
+    })
+}
+
+// ── Numbers ─────────────────────────────────────────────────────────────────
+
+/// A value during evaluation: an exact fraction (numerator, positive
+/// denominator, in lowest terms) or a float once exactness is lost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Exact(i128, i128),
+    Float(f64),
+}
+
+impl Number {
+    fn int(n: i128) -> Self {
+        Number::Exact(n, 1)
+    }
+
+    fn ratio(n: i128, d: i128) -> Result<Self, String> {
+        if d == 0 {
+            return Err("Division by zero".to_string());
+        }
+        let g = gcd(n, d);
+        let (n, d) = (n / g, d / g);
+        if d > 0 {
+            return Ok(Number::Exact(n, d));
+        }
+        // i128::MIN can't be negated; such values go to floating point.
+        match (n.checked_neg(), d.checked_neg()) {
+            (Some(n), Some(d)) => Ok(Number::Exact(n, d)),
+            _ => float(n as f64 / d as f64),
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Number::Exact(n, d) => n as f64 / d as f64,
+            Number::Float(x) => x,
+        }
+    }
+
+    pub fn is_exact(self) -> bool {
+        matches!(self, Number::Exact(..))
+    }
+
+    /// The integer value, if this is an exact integer.
+    fn as_int(self) -> Option<i128> {
+        match self {
+            Number::Exact(n, 1) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+/// A float result, or an error for infinities and NaN.
+fn float(x: f64) -> Result<Number, String> {
+    if x.is_nan() {
+        Err("Result is not a real number".to_string())
+    } else if x.is_infinite() {
+        Err("Result is too large".to_string())
+    } else {
+        Ok(Number::Float(x))
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // Only 0/0 gives 0, and that is rejected before dividing.
+    i128::try_from(a).unwrap_or(1).max(1)
+}
+
+/// Combine two numbers exactly when `exact` can, else with `approx`.
+fn combine(
+    a: Number,
+    b: Number,
+    exact: impl FnOnce(i128, i128, i128, i128) -> Option<(i128, i128)>,
+    approx: impl FnOnce(f64, f64) -> f64,
+) -> Result<Number, String> {
+    if let (Number::Exact(an, ad), Number::Exact(bn, bd)) = (a, b)
+        && let Some((n, d)) = exact(an, ad, bn, bd)
+    {
+        return Number::ratio(n, d);
+    }
+    float(approx(a.to_f64(), b.to_f64()))
+}
+
+fn add(a: Number, b: Number) -> Result<Number, String> {
+    combine(
+        a,
+        b,
+        |an, ad, bn, bd| {
+            let n = an.checked_mul(bd)?.checked_add(bn.checked_mul(ad)?)?;
+            Some((n, ad.checked_mul(bd)?))
+        },
+        |x, y| x + y,
+    )
+}
+
+fn sub(a: Number, b: Number) -> Result<Number, String> {
+    combine(
+        a,
+        b,
+        |an, ad, bn, bd| {
+            let n = an.checked_mul(bd)?.checked_sub(bn.checked_mul(ad)?)?;
+            Some((n, ad.checked_mul(bd)?))
+        },
+        |x, y| x - y,
+    )
+}
+
+fn mul(a: Number, b: Number) -> Result<Number, String> {
+    combine(
+        a,
+        b,
+        |an, ad, bn, bd| Some((an.checked_mul(bn)?, ad.checked_mul(bd)?)),
+        |x, y| x * y,
+    )
+}
+
+fn div(a: Number, b: Number) -> Result<Number, String> {
+    if b.to_f64() == 0.0 {
+        return Err("Division by zero".to_string());
+    }
+    combine(
+        a,
+        b,
+        |an, ad, bn, bd| Some((an.checked_mul(bd)?, ad.checked_mul(bn)?)),
+        |x, y| x / y,
+    )
+}
+
+/// Remainder with the sign of the divisor, like Python's `%`.
+fn rem(a: Number, b: Number) -> Result<Number, String> {
+    let quotient = div(a, b)?;
+    let floored = match quotient {
+        Number::Exact(n, d) => Number::int(n.div_euclid(d)),
+        Number::Float(x) => Number::Float(x.floor()),
+    };
+    sub(a, mul(b, floored)?)
+}
+
+fn pow(base: Number, exponent: Number) -> Result<Number, String> {
+    let e = exponent.to_f64();
+    if e.abs() > MAX_EXPONENT {
+        return Err(format!(
+            "Exponent {} is too large (the limit is ±{})",
+            e, MAX_EXPONENT
+        ));
+    }
+    if base.to_f64() == 0.0 && e < 0.0 {
+        return Err("Division by zero".to_string());
+    }
+    if let (Number::Exact(n, d), Some(k)) = (base, exponent.as_int()) {
+        let power = u32::try_from(k.unsigned_abs()).ok();
+        let exact = power.and_then(|p| Some((n.checked_pow(p)?, d.checked_pow(p)?)));
+        if let Some((n, d)) = exact {
+            return if k < 0 {
+                Number::ratio(d, n)
+            } else {
+                Number::ratio(n, d)
+            };
+        }
+    }
+    float(base.to_f64().powf(e))
+}
+
+// ── Parser ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(Number),
+    Ident(String),
+    Op(char),
+    Open,
+    Close,
+    Comma,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Num(n) => write!(f, "number {}", decimal_string(*n)),
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Op(op) => write!(f, "'{}'", op),
+            Token::Open => f.write_str("'('"),
+            Token::Close => f.write_str("')'"),
+            Token::Comma => f.write_str("','"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit() || matches!(chars[i], '.' | '_'))
+                {
+                    i += 1;
+                }
+                // Scientific notation, but not the constant e: `2e3`, `1.5E-7`.
+                if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                    let sign = usize::from(matches!(chars.get(i + 1), Some('+' | '-')));
+                    if chars.get(i + 1 + sign).is_some_and(char::is_ascii_digit) {
+                        i += 1 + sign;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let literal: String = chars[start..i].iter().collect();
+                tokens.push(Token::Num(parse_literal(&literal)?));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let name: String = chars[start..i].iter().collect();
+                tokens.push(Token::Ident(name.to_lowercase()));
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                tokens.push(Token::Op('^'));
+                i += 2;
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '−' | '–' => {
+                tokens.push(Token::Op('-'));
+                i += 1;
+            }
+            '×' | '·' => {
+                tokens.push(Token::Op('*'));
+                i += 1;
+            }
+            '÷' => {
+                tokens.push(Token::Op('/'));
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            other => return Err(format!("Unexpected character '{}'", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// A decimal literal as an exact fraction: `0.1` is 1/10, `2.5e3` is 2500.
+fn parse_literal(literal: &str) -> Result<Number, String> {
+    let invalid = || format!("Invalid number '{}'", literal);
+    let cleaned = literal.replace('_', "");
+    let (mantissa, exponent) = match cleaned.find(['e', 'E']) {
+        Some(at) => (
+            &cleaned[..at],
+            cleaned[at + 1..].parse::<i32>().map_err(|_| invalid())?,
+        ),
+        None => (cleaned.as_str(), 0),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+        return Err(invalid());
+    }
+    let exact = exact_literal(whole, fraction, exponent);
+    match exact {
+        Some((n, d)) => Number::ratio(n, d),
+        None => float(cleaned.parse::<f64>().map_err(|_| invalid())?),
+    }
+}
+
+/// A literal's digits as a fraction, or `None` when it overflows.
+fn exact_literal(whole: &str, fraction: &str, exponent: i32) -> Option<(i128, i128)> {
+    let mut n = format!("{}{}", whole, fraction).parse::<i128>().ok()?;
+    let mut d = 10i128.checked_pow(u32::try_from(fraction.len()).ok()?)?;
+    let scale = 10i128.checked_pow(exponent.unsigned_abs())?;
+    if exponent >= 0 {
+        n = n.checked_mul(scale)?;
+    } else {
+        d = d.checked_mul(scale)?;
+    }
+    Some((n, d))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_op(&mut self, ops: &[char]) -> Option<char> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn descend(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("Expression is nested too deeply".to_string());
+        }
+        Ok(())
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<Number, String> {
+        let mut value = self.term()?;
+        while let Some(op) = self.eat_op(&['+', '-']) {
+            let rhs = self.term()?;
+            value = if op == '+' {
+                add(value, rhs)?
+            } else {
+                sub(value, rhs)?
+            };
+        }
+        Ok(value)
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<Number, String> {
+        let mut value = self.unary()?;
+        while let Some(op) = self.eat_op(&['*', '/', '%']) {
+            let rhs = self.unary()?;
+            value = match op {
+                '*' => mul(value, rhs)?,
+                '/' => div(value, rhs)?,
+                _ => rem(value, rhs)?,
+            };
+        }
+        Ok(value)
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Result<Number, String> {
+        let Some(op) = self.eat_op(&['-', '+']) else {
+            return self.power();
+        };
+        self.descend()?;
+        let value = self.unary()?;
+        self.depth -= 1;
+        if op == '-' {
+            sub(Number::int(0), value)
+        } else {
+            Ok(value)
+        }
+    }
+
+    // power := primary ('^' unary)?   — right-associative, so -2^2 is -4
+    // and 2^3^2 is 2^9.
+    fn power(&mut self) -> Result<Number, String> {
+        let base = self.primary()?;
+        if self.eat_op(&['^']).is_none() {
+            return Ok(base);
+        }
+        self.descend()?;
+        let exponent = self.unary()?;
+        self.depth -= 1;
+        pow(base, exponent)
+    }
+
+    // primary := number | constant | name '(' args ')' | '(' expr ')'
+    fn primary(&mut self) -> Result<Number, String> {
+        match self.advance() {
+            Some(Token::Num(n)) => Ok(n),
+            Some(Token::Open) => {
+                self.descend()?;
+                let value = self.expr()?;
+                self.depth -= 1;
+                match self.advance() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err("Missing closing parenthesis".to_string()),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                if self.peek() != Some(&Token::Open) {
+                    return constant(&name);
+                }
+                self.pos += 1;
+                self.descend()?;
+                let mut args = vec![self.expr()?];
+                loop {
+                    match self.advance() {
+                        Some(Token::Comma) => args.push(self.expr()?),
+                        Some(Token::Close) => break,
+                        _ => return Err(format!("Missing closing parenthesis after {}(", name)),
+                    }
+                }
+                self.depth -= 1;
+                function(&name, &args)
+            }
+            Some(Token::Op(op)) => Err(format!("Unexpected operator '{}'", op)),
+            Some(Token::Close) => Err("Unexpected ')'".to_string()),
+            Some(Token::Comma) => Err("Unexpected ','".to_string()),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+fn constant(name: &str) -> Result<Number, String> {
+    match name {
+        "pi" | "π" => Ok(Number::Float(std::f64::consts::PI)),
+        "tau" | "τ" => Ok(Number::Float(std::f64::consts::TAU)),
+        "e" => Ok(Number::Float(std::f64::consts::E)),
+        _ => Err(format!(
+            "Unknown name '{}'. Constants: pi, tau, e; functions need parentheses",
+            name
+        )),
+    }
+}
+
+const FUNCTIONS: &str = "sqrt, cbrt, abs, exp, ln, log, log2, log10, sin, cos, tan, asin, \
+                         acos, atan, sinh, cosh, tanh, floor, ceil, round, min, max";
+
+fn function(name: &str, args: &[Number]) -> Result<Number, String> {
+    let arity = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(format!(
+                "{}() takes {} argument(s), got {}",
+                name,
+                n,
+                args.len()
+            ))
+        }
+    };
+    let x = args[0];
+    let f = x.to_f64();
+    match name {
+        "abs" => {
+            arity(1)?;
+            match x {
+                Number::Exact(n, d) => Number::ratio(n.checked_abs().ok_or("Overflow")?, d),
+                Number::Float(v) => Ok(Number::Float(v.abs())),
+            }
+        }
+        "floor" | "ceil" | "round" => {
+            arity(1)?;
+            match x {
+                Number::Exact(n, d) => {
+                    let (q, r) = (n.div_euclid(d), n.rem_euclid(d));
+                    let up = match name {
+                        "floor" => false,
+                        "ceil" => r != 0,
+                        // Half away from zero, like f64::round.
+                        _ => r > d - r || (r == d - r && n > 0),
+                    };
+                    Ok(Number::int(q + i128::from(up)))
+                }
+                Number::Float(v) => float(match name {
+                    "floor" => v.floor(),
+                    "ceil" => v.ceil(),
+                    _ => v.round(),
+                }),
+            }
+        }
+        "min" | "max" => {
+            let mut best = x;
+            for &candidate in &args[1..] {
+                let better = if name == "min" {
+                    candidate.to_f64() < best.to_f64()
+                } else {
+                    candidate.to_f64() > best.to_f64()
+                };
+                if better {
+                    best = candidate;
+                }
+            }
+            Ok(best)
+        }
+        "sqrt" => {
+            arity(1)?;
+            if f < 0.0 {
+                return Err("sqrt() of a negative number".to_string());
+            }
+            // Perfect squares stay exact: sqrt(9/4) is 3/2.
+            if let Number::Exact(n, d) = x
+                && let (Some(rn), Some(rd)) = (exact_sqrt(n), exact_sqrt(d))
+            {
+                return Number::ratio(rn, rd);
+            }
+            float(f.sqrt())
+        }
+        "log" if args.len() == 2 => {
+            let base = args[1].to_f64();
+            if f <= 0.0 || base <= 0.0 || base == 1.0 {
+                return Err("log() needs a positive value and a positive base other than 1".into());
+            }
+            float(f.ln() / base.ln())
+        }
+        "ln" | "log" | "log2" | "log10" => {
+            arity(1)?;
+            if f <= 0.0 {
+                return Err(format!("{}() of a number that isn't positive", name));
+            }
+            float(match name {
+                "ln" => f.ln(),
+                "log2" => f.log2(),
+                _ => f.log10(),
+            })
+        }
+        "cbrt" | "exp" | "sin" | "cos" | "tan" | "asin" | "acos" | "atan" | "sinh" | "cosh"
+        | "tanh" => {
+            arity(1)?;
+            float(match name {
+                "cbrt" => f.cbrt(),
+                "exp" => f.exp(),
+                "sin" => f.sin(),
+                "cos" => f.cos(),
+                "tan" => f.tan(),
+                "asin" => f.asin(),
+                "acos" => f.acos(),
+                "atan" => f.atan(),
+                "sinh" => f.sinh(),
+                "cosh" => f.cosh(),
+                _ => f.tanh(),
+            })
+        }
+        _ => Err(format!(
+            "Unknown function '{}'. Functions: {}",
+            name, FUNCTIONS
+        )),
+    }
+}
+
+/// The integer square root of `n`, if `n` is a perfect square.
+fn exact_sqrt(n: i128) -> Option<i128> {
+    if n < 0 {
+        return None;
+    }
+    let root = (n as f64).sqrt().round() as i128;
+    (root.saturating_sub(1)..=root.saturating_add(1))
+        .find(|r| *r >= 0 && r.checked_mul(*r) == Some(n))
+}
+
+/// Evaluate an arithmetic expression.
+pub fn evaluate(expression: &str) -> Result<Number, String> {
+    if expression.chars().count() > MAX_EXPRESSION_LEN {
+        return Err(format!(
+            "Expression is too long (the limit is {} characters)",
+            MAX_EXPRESSION_LEN
+        ));
+    }
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return Err("Empty expression".to_string());
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.expr()?;
+    match parser.peek() {
+        None => Ok(value),
+        Some(Token::Close) => Err("Unmatched ')'".to_string()),
+        Some(token) => Err(format!("Unexpected {} after a complete expression", token)),
+    }
+}
+
+fn eval_json(expression: &str) -> Result<Value, String> {
+    let value = evaluate(expression)?;
+    debug!(exact = value.is_exact(), "Evaluated expression");
+    let mut out = json!({
+        "expression": expression,
+        "result": result_json(value),
+        "decimal": decimal_string(value),
+        "exact": value.is_exact(),
+    });
+    if let Number::Exact(n, d) = value
+        && d != 1
+    {
+        out["fraction"] = json!(format!("{}/{}", n, d));
+    }
+    Ok(out)
+}
+
+fn result_json(value: Number) -> Value {
+    match value.as_int().map(i64::try_from) {
+        Some(Ok(n)) => json!(n),
+        _ => json!(round_sig(value.to_f64(), FLOAT_DIGITS)),
+    }
+}
+
+/// The value in decimal: exact results to [`DECIMAL_PLACES`] places,
+/// rounded half up, without trailing zeros.
+fn decimal_string(value: Number) -> String {
+    let Number::Exact(n, d) = value else {
+        return float_string(value.to_f64());
+    };
+    let (whole, mut remainder) = ((n / d).unsigned_abs(), (n % d).unsigned_abs());
+    let d = d.unsigned_abs();
+    let mut digits = Vec::with_capacity(DECIMAL_PLACES + 1);
+    for _ in 0..=DECIMAL_PLACES {
+        let Some(scaled) = remainder.checked_mul(10) else {
+            return float_string(value.to_f64());
+        };
+        digits.push((scaled / d) as u8);
+        remainder = scaled % d;
+    }
+    let round_up = digits.pop().is_some_and(|last| last >= 5);
+    let mut whole = whole;
+    if round_up {
+        let mut carry = true;
+        for digit in digits.iter_mut().rev() {
+            if *digit == 9 {
+                *digit = 0;
+            } else {
+                *digit += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            whole += 1;
+        }
+    }
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    let sign = if n < 0 { "-" } else { "" };
+    if digits.is_empty() {
+        return format!("{}{}", if whole == 0 { "" } else { sign }, whole);
+    }
+    let fraction: String = digits.iter().map(|d| char::from(b'0' + d)).collect();
+    format!("{}{}.{}", sign, whole, fraction)
+}
+
+/// A float result in plain notation, or scientific when very large or
+/// very small.
+fn float_string(x: f64) -> String {
+    let x = round_sig(x, FLOAT_DIGITS);
+    if x != 0.0 && !(1e-6..1e16).contains(&x.abs()) {
+        format!("{:e}", x)
+    } else {
+        x.to_string()
+    }
+}
+
+/// `x` rounded to `digits` significant digits.
+fn round_sig(x: f64, digits: usize) -> f64 {
+    format!("{:.*e}", digits.saturating_sub(1), x)
+        .parse()
+        .unwrap_or(x)
+}
+
+// ── Unit conversion ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Length,
+    Mass,
+    Temperature,
+    Data,
+}
+
+impl Category {
+    fn name(self) -> &'static str {
+        match self {
+            Category::Length => "length",
+            Category::Mass => "mass",
+            Category::Temperature => "temperature",
+            Category::Data => "data",
+        }
+    }
+}
+
+struct Unit {
+    symbol: &'static str,
+    names: &'static [&'static str],
+    category: Category,
+    /// Size in the category's base unit (metre, kilogram, byte).  Unused
+    /// for temperatures, which aren't proportional.
+    factor: f64,
+}
+
+const fn unit(
+    symbol: &'static str,
+    names: &'static [&'static str],
+    category: Category,
+    factor: f64,
+) -> Unit {
+    Unit {
+        symbol,
+        names,
+        category,
+        factor,
+    }
+}
+
+use Category::{Data, Length, Mass, Temperature};
+
+const UNITS: &[Unit] = &[
+    unit("m", &["meter", "meters", "metre", "metres"], Length, 1.0),
+    unit(
+        "km",
+        &["kilometer", "kilometers", "kilometre", "kilometres"],
+        Length,
+        1e3,
+    ),
+    unit(
+        "cm",
+        &["centimeter", "centimeters", "centimetre", "centimetres"],
+        Length,
+        1e-2,
+    ),
+    unit(
+        "mm",
+        &["millimeter", "millimeters", "millimetre", "millimetres"],
+        Length,
+        1e-3,
+    ),
+    unit(
+        "µm",
+        &["um", "micrometer", "micrometers", "micron", "microns"],
+        Length,
+        1e-6,
+    ),
+    unit(
+        "nm",
+        &["nanometer", "nanometers", "nanometre", "nanometres"],
+        Length,
+        1e-9,
+    ),
+    unit("in", &["inch", "inches"], Length, 0.0254),
+    unit("ft", &["foot", "feet"], Length, 0.3048),
+    unit("yd", &["yard", "yards"], Length, 0.9144),
+    unit("mi", &["mile", "miles"], Length, 1609.344),
+    unit("nmi", &["nautical mile", "nautical miles"], Length, 1852.0),
+    unit("mg", &["milligram", "milligrams"], Mass, 1e-6),
+    unit("g", &["gram", "grams"], Mass, 1e-3),
+    unit("kg", &["kilogram", "kilograms", "kilo", "kilos"], Mass, 1.0),
+    unit(
+        "t",
+        &["tonne", "tonnes", "metric ton", "metric tons"],
+        Mass,
+        1e3,
+    ),
+    unit("oz", &["ounce", "ounces"], Mass, 0.028_349_523_125),
+    unit("lb", &["lbs", "pound", "pounds"], Mass, 0.453_592_37),
+    unit("st", &["stone", "stones"], Mass, 6.350_293_18),
+    unit("°C", &["C", "celsius", "degC"], Temperature, 1.0),
+    unit("°F", &["F", "fahrenheit", "degF"], Temperature, 1.0),
+    unit("K", &["kelvin", "kelvins"], Temperature, 1.0),
+    unit("bit", &["b", "bits"], Data, 0.125),
+    unit("B", &["byte", "bytes"], Data, 1.0),
+    unit("kB", &["kilobyte", "kilobytes"], Data, 1e3),
+    unit("MB", &["megabyte", "megabytes"], Data, 1e6),
+    unit("GB", &["gigabyte", "gigabytes"], Data, 1e9),
+    unit("TB", &["terabyte", "terabytes"], Data, 1e12),
+    unit("PB", &["petabyte", "petabytes"], Data, 1e15),
+    unit("KiB", &["kibibyte", "kibibytes"], Data, 1024.0),
+    unit("MiB", &["mebibyte", "mebibytes"], Data, 1_048_576.0),
+    unit("GiB", &["gibibyte", "gibibytes"], Data, 1_073_741_824.0),
+    unit("TiB", &["tebibyte", "tebibytes"], Data, 1_099_511_627_776.0),
+    unit(
+        "PiB",
+        &["pebibyte", "pebibytes"],
+        Data,
+        1_125_899_906_842_624.0,
+    ),
+    unit(
+        "kbit",
+        &["kb/s", "kbps", "kilobit", "kilobits"],
+        Data,
+        125.0,
+    ),
+    unit(
+        "Mbit",
+        &["Mb/s", "Mbps", "megabit", "megabits"],
+        Data,
+        125e3,
+    ),
+    unit(
+        "Gbit",
+        &["Gb/s", "Gbps", "gigabit", "gigabits"],
+        Data,
+        125e6,
+    ),
+];
+
+/// Look up a unit by symbol or name.  Case matters first (`b` is a bit,
+/// `B` a byte); otherwise any case is accepted.
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    let names = |u: &Unit| std::iter::once(u.symbol).chain(u.names.iter().copied());
+    UNITS
+        .iter()
+        .find(|u| names(u).any(|s| s == name))
+        .or_else(|| {
+            UNITS
+                .iter()
+                .find(|u| names(u).any(|s| s.eq_ignore_ascii_case(name)))
+        })
+}
+
+fn to_celsius(symbol: &str, value: f64) -> f64 {
+    match symbol {
+        "°F" => (value - 32.0) * 5.0 / 9.0,
+        "K" => value - 273.15,
+        _ => value,
+    }
+}
+
+fn from_celsius(symbol: &str, celsius: f64) -> f64 {
+    match symbol {
+        "°F" => celsius * 9.0 / 5.0 + 32.0,
+        "K" => celsius + 273.15,
+        _ => celsius,
+    }
+}
+
+/// Convert `value` between units of the same category, returning the
+/// result and the two units.
+fn convert_units(
+    value: f64,
+    from: &str,
+    to: &str,
+) -> Result<(f64, &'static Unit, &'static Unit), String> {
+    let (Some(source), Some(target)) = (find_unit(from), find_unit(to)) else {
+        let unknown = if find_unit(from).is_none() { from } else { to };
+        return Err(format!("Unknown unit: '{}'", unknown));
+    };
+    if source.category != target.category {
+        return Err(format!(
+            "Cannot convert {} ({}) to {} ({})",
+            source.symbol,
+            source.category.name(),
+            target.symbol,
+            target.category.name()
+        ));
+    }
+    let result = if source.category == Category::Temperature {
+        let celsius = to_celsius(source.symbol, value);
+        if celsius < -273.15 {
+            return Err(format!(
+                "{} {} is below absolute zero",
+                value, source.symbol
+            ));
+        }
+        from_celsius(target.symbol, celsius)
+    } else {
+        value * source.factor / target.factor
+    };
+    Ok((round_sig(result, CONVERT_DIGITS), source, target))
+}
+
+fn is_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+fn convert_json(value: f64, from: &str, to: &str) -> Result<Value, String> {
+    if find_unit(from).is_none()
+        && find_unit(to).is_none()
+        && is_currency_code(from)
+        && is_currency_code(to)
+    {
+        return convert_currency(value, &from.to_uppercase(), &to.to_uppercase());
+    }
+    let (result, source, target) = convert_units(value, from, to)?;
+    Ok(json!({
+        "value": value,
+        "from": source.symbol,
+        "to": target.symbol,
+        "category": source.category.name(),
+        "result": result,
+        "human": format!("{} {} = {} {}", value, source.symbol, result, target.symbol),
+    }))
+}
+
+// ── Currency ────────────────────────────────────────────────────────────────
+
+fn convert_currency(value: f64, from: &str, to: &str) -> Result<Value, String> {
+    let (rate, date) = if from == to {
+        (1.0, None)
+    } else {
+        let rates = rates_for(from)?;
+        let rate = rates
+            .get("rates")
+            .and_then(|r| r.get(to))
+            .and_then(|r| r.as_f64())
+            .ok_or_else(|| format!("No exchange rate from {} to {}", from, to))?;
+        let date = rates.get("date").and_then(|d| d.as_str()).map(String::from);
+        (rate, date)
+    };
+    let result = (value * rate * 100.0).round() / 100.0;
+    Ok(json!({
+        "value": value,
+        "from": from,
+        "to": to,
+        "category": "currency",
+        "result": result,
+        "rate": rate,
+        "date": date,
+        "source": "European Central Bank reference rates via frankfurter.dev",
+        "human": format!("{} {} = {:.2} {}", value, from, result, to),
+    }))
+}
+
+/// The rate table for `base`, from the cache or the rate service.
+fn rates_for(base: &str) -> Result<Value, String> {
+    if let Some(hit) = cached_rates(base) {
+        debug!(base, "Using cached exchange rates");
+        return Ok(hit);
+    }
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .user_agent("RustyClaw/0.1 (math tool)")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(format!("{}?base={}", RATES_URL, base))
+        .send()
+        .map_err(|e| format!("Exchange rate request failed: {}", e.without_url()))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND
+        || status == reqwest::StatusCode::UNPROCESSABLE_ENTITY
+    {
+        return Err(format!("Unknown currency: '{}'", base));
+    }
+    if !status.is_success() {
+        warn!(status = status.as_u16(), "Exchange rate API error");
+        return Err(format!("Exchange rate service error {}", status.as_u16()));
+    }
+    let rates: Value = response.json().map_err(|e| {
+        format!(
+            "Exchange rate service returned invalid JSON: {}",
+            e.without_url()
+        )
+    })?;
+    if !rates.get("rates").is_some_and(Value::is_object) {
+        return Err("Exchange rate service returned no rates".to_string());
+    }
+    store_rates(base.to_string(), rates.clone());
+    Ok(rates)
+}
+
+fn cached_rates(base: &str) -> Option<Value> {
+    let cache = RATES.lock().unwrap();
+    let (at, rates) = cache.get(base)?;
+    (at.elapsed() < Duration::from_secs(RATES_TTL_SECS)).then(|| rates.clone())
+}
+
+fn store_rates(base: String, rates: Value) {
+    let ttl = Duration::from_secs(RATES_TTL_SECS);
+    let mut cache = RATES.lock().unwrap();
+    cache.retain(|_, (at, _)| at.elapsed() < ttl);
+    if cache.len() >= MAX_CACHE_ENTRIES
+        && let Some(oldest) = cache
+            .iter()
+            .min_by_key(|(_, (at, _))| *at)
+            .map(|(k, _)| k.clone())
+    {
+        cache.remove(&oldest);
+    }
+    cache.insert(base, (Instant::now(), rates));
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn math_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "'eval' to evaluate an expression, 'convert' to convert a value \
+                          between units or currencies. Inferred from the other parameters \
+                          when omitted."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "expression".into(),
+            description: "For eval: an expression using + - * / % ^ (or ** × ÷), \
+                          parentheses, the constants pi, tau and e, and the functions sqrt, \
+                          cbrt, abs, exp, ln, log (base 10, or log(x, base)), log2, sin, cos, \
+                          tan, asin, acos, atan, sinh, cosh, tanh, floor, ceil, round, min, \
+                          max. Angles are in radians. Example: '(1.5 + 2) * 3^2 / 7'."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "value".into(),
+            description: "For convert: the amount to convert.".into(),
+            param_type: "number".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "from".into(),
+            description: "For convert: the source unit — length (m, km, cm, mm, in, ft, yd, \
+                          mi, nmi), mass (mg, g, kg, t, oz, lb, st), temperature (C, F, K), \
+                          data (bit, B, kB, MB, GB, TB, KiB, MiB, GiB, TiB, Mbit, ...) or a \
+                          three-letter currency code (USD, EUR, JPY, ...)."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "to".into(),
+            description: "For convert: the target unit, of the same kind as 'from'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -20,6 +20,13 @@ pub fn exec_memory_search(args: &Value, workspace_dir: &Path) -> Result<String,
     let max_results = args.get("maxResults").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
     let min_score = args.get("minScore").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
 
+    let filter = parse_memory_filter(args)?;
+    if !filter.is_empty() {
+        // The vector store cannot be pre-filtered, so scoped searches run
+        // keyword scoring over only the files and entries that pass.
+        return search_memory_filtered(query, &filter, args, max_results, workspace_dir);
+    }
+
     debug!(max_results, min_score, "Searching memory with steel-memory");
 
     // Use steel-memory semantic search via blocking runtime
@@ -75,6 +82,114 @@ pub fn exec_memory_search(args: &Value, workspace_dir: &Path) -> Result<String,
     })
 }
 
+/// Build a [`MemoryFilter`](crate::memory::MemoryFilter) from the optional
+/// `after`, `before`, and `files` arguments.
+#[cfg(feature = "semantic-memory")]
+fn parse_memory_filter(args: &Value) -> Result<crate::memory::MemoryFilter, String> {
+    let date_arg = |name: &str| -> Result<_, String> {
+        match args.get(name).and_then(|v| v.as_str()).map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(raw) => crate::memory::parse_date_lenient(raw)
+                .map(Some)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid '{}' date: '{}'. Use YYYY-MM-DD or YYYY-MM-DD HH:MM[:SS].",
+                        name, raw
+                    )
+                }),
+        }
+    };
+
+    let files = match args.get("files").and_then(|v| v.as_str()).map(str::trim) {
+        None | Some("") => None,
+        Some(raw) => Some(
+            glob::Pattern::new(raw)
+                .map_err(|e| format!("Invalid 'files' glob '{}': {}", raw, e))?,
+        ),
+    };
+
+    Ok(crate::memory::MemoryFilter {
+        after: date_arg("after")?,
+        before: date_arg("before")?,
+        files,
+    })
+}
+
+/// Keyword (BM25) search restricted by `filter`, honouring the recency options.
+#[cfg(feature = "semantic-memory")]
+fn search_memory_filtered(
+    query: &str,
+    filter: &crate::memory::MemoryFilter,
+    args: &Value,
+    max_results: usize,
+    workspace_dir: &Path,
+) -> Result<String, String> {
+    let recency_boost = args
+        .get("recencyBoost")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let half_life_days = args
+        .get("halfLifeDays")
+        .and_then(|v| v.as_f64())
+        .filter(|d| *d > 0.0)
+        .unwrap_or(30.0);
+
+    debug!(filters = %filter.describe(), max_results, "Searching memory with filters");
+
+    let index = crate::memory::MemoryIndex::index_workspace_filtered(workspace_dir, filter)?;
+    let results = if recency_boost {
+        index.search_with_decay(query, max_results, half_life_days)
+    } else {
+        index.search(query, max_results)
+    };
+
+    if results.is_empty() {
+        return Ok(format!(
+            "No matching memories found (filters: {}).",
+            filter.describe()
+        ));
+    }
+
+    let mut output = format!(
+        "Memory search results for: {} (keyword; filters: {})\n\n",
+        query,
+        filter.describe()
+    );
+    for (i, result) in results.iter().enumerate() {
+        let chunk = &result.chunk;
+        let when = chunk
+            .timestamp
+            .map(|ts| format!(" — {}", ts.format("%Y-%m-%d %H:%M")))
+            .unwrap_or_default();
+        let snippet = if chunk.text.len() > 700 {
+            let mut end = 700;
+            while !chunk.text.is_char_boundary(end) {
+                end -= 1;
+            }
+            format!("{}...", &chunk.text[..end])
+        } else {
+            chunk.text.clone()
+        };
+
+        output.push_str(&format!(
+            "{}. **{}** lines {}-{}{} (score: {:.2})\n",
+            i + 1,
+            chunk.path,
+            chunk.start_line,
+            chunk.end_line,
+            when,
+            result.score
+        ));
+        output.push_str(&format!("{}\n\n", snippet));
+    }
+
+    debug!(
+        result_count = results.len(),
+        "Filtered memory search complete"
+    );
+    Ok(output)
+}
+
 /// Read content from a memory file.
 #[instrument(skip(args, workspace_dir))]
 pub fn exec_memory_get(args: &Value, workspace_dir: &Path) -> Result<String, String> {
@@ -139,39 +254,60 @@ pub fn exec_add_memory(args: &Value, workspace_dir: &Path) -> Result<String, Str
 ///
 /// This tool allows the LLM to:
 /// 1. Append a timestamped entry to HISTORY.md (searchable log)
-/// 2. Optionally update MEMORY.md with curated long-term facts
+/// 2. Optionally update MEMORY.md with curated long-term facts, either as a
+///    full replacement or as individual facts that are deduplicated against
+///    what the file already holds
 ///
 /// The LLM decides what's important enough to persist.
 #[instrument(skip(args, workspace_dir))]
 pub fn exec_save_memory(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    use crate::memory_consolidation::{FactOutcome, MemoryConsolidation};
+
     let history_entry = args
         .get("history_entry")
         .and_then(|v| v.as_str())
         .ok_or_else(|| "Missing required parameter: history_entry".to_string())?;
 
     let memory_update = args.get("memory_update").and_then(|v| v.as_str());
+    let memory_facts: Vec<String> = match args.get("memory_facts") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::String(fact)) => vec![fact.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| "memory_facts must be a list of strings".to_string())
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err("memory_facts must be a list of strings".to_string()),
+    };
 
     debug!(
         history_entry_len = history_entry.len(),
         has_memory_update = memory_update.is_some(),
+        memory_facts = memory_facts.len(),
         "Saving memory"
     );
 
-    let config = crate::memory_consolidation::ConsolidationConfig::default();
-    let consolidation = crate::memory_consolidation::MemoryConsolidation::new(config);
+    let consolidation = MemoryConsolidation::new(super::memory_consolidation_config());
 
-    // Append to HISTORY.md
+    // HISTORY.md stays append-only; a repeat is only flagged.
+    let similar = consolidation.similar_history(workspace_dir, history_entry)?;
     let history_size = consolidation.append_history(workspace_dir, history_entry)?;
 
     // Update MEMORY.md if provided
-    let memory_size = if let Some(content) = memory_update {
-        consolidation.update_memory(workspace_dir, content)?
+    let mut memory_size = if let Some(content) = memory_update {
+        Some(consolidation.update_memory(workspace_dir, content)?)
     } else {
-        consolidation
-            .read_memory(workspace_dir)
-            .map(|s| s.len())
-            .unwrap_or(0)
+        None
     };
+    let mut outcomes = Vec::new();
+    if !memory_facts.is_empty() {
+        let (facts_outcomes, size) = consolidation.add_facts(workspace_dir, &memory_facts)?;
+        outcomes = facts_outcomes;
+        memory_size = Some(size);
+    }
 
     let mut output = String::new();
     output.push_str("Memory saved successfully.\n\n");
@@ -179,10 +315,43 @@ pub fn exec_save_memory(args: &Value, workspace_dir: &Path) -> Result<String, St
         "- HISTORY.md: {} bytes (entry appended)\n",
         history_size
     ));
-    if memory_update.is_some() {
-        output.push_str(&format!("- MEMORY.md: {} bytes (updated)\n", memory_size));
-    } else {
-        output.push_str("- MEMORY.md: unchanged\n");
+    if let Some((entry, score)) = similar {
+        output.push_str(&format!(
+            "  note: {:.0}% similar to the entry from {}: \"{}\"\n",
+            score * 100.0,
+            entry.timestamp,
+            entry.text
+        ));
+    }
+    match memory_size {
+        Some(size) if memory_update.is_some() || outcomes.is_empty() => {
+            output.push_str(&format!("- MEMORY.md: {} bytes (updated)\n", size));
+        }
+        Some(size) => {
+            let count = |f: fn(&FactOutcome) -> bool| outcomes.iter().filter(|o| f(o)).count();
+            output.push_str(&format!(
+                "- MEMORY.md: {} bytes ({} added, {} updated, {} skipped as duplicates)\n",
+                size,
+                count(|o| matches!(o, FactOutcome::Added { .. })),
+                count(|o| matches!(o, FactOutcome::Updated { .. })),
+                count(|o| matches!(o, FactOutcome::Skipped { .. })),
+            ));
+        }
+        None => output.push_str("- MEMORY.md: unchanged\n"),
+    }
+    for outcome in &outcomes {
+        match outcome {
+            FactOutcome::Added { .. } => {}
+            FactOutcome::Updated { fact, previous, .. } => {
+                output.push_str(&format!("  updated: \"{}\" -> \"{}\"\n", previous, fact));
+            }
+            FactOutcome::Skipped { fact, existing, .. } => {
+                output.push_str(&format!(
+                    "  skipped: \"{}\" (already recorded as \"{}\")\n",
+                    fact, existing
+                ));
+            }
+        }
     }
 
     Ok(output)
@@ -203,7 +372,7 @@ pub fn exec_search_history(args: &Value, workspace_dir: &Path) -> Result<String,
 
     debug!(pattern, max_results, "Searching history");
 
-    let config = crate::memory_consolidation::ConsolidationConfig::default();
+    let config = super::memory_consolidation_config();
     let consolidation = crate::memory_consolidation::MemoryConsolidation::new(config);
 
     let results = consolidation.search_history(workspace_dir, pattern, max_results)?;
@@ -8,35 +8,76 @@ pub mod agent_setup;
 
 use tracing::{debug, instrument, warn};
 
+mod approval;
+mod archive_member;
 mod ast_grep;
 mod browser;
+mod color;
+mod convert_document;
 mod cron_tool;
+mod csv_tool;
 mod devices;
+mod dns_tool;
+mod docker_tool;
+mod encode;
+mod env_info;
+mod env_var;
 pub mod exo_ai;
 mod file;
+mod file_ops;
 mod gateway_tools;
+mod git_tool;
 pub(crate) mod helpers;
+mod http_server;
 #[cfg(feature = "image-gen")]
 mod image_gen;
+mod image_transform;
 mod kernel_tools;
+mod kv_store;
+mod lint;
+mod math_tool;
 mod memory_tools;
 pub mod npm;
 pub mod ollama;
+mod parse_log;
 mod patch;
 mod pdf;
+mod pdf_create;
+mod pdf_extract;
+mod process_tree;
+pub mod proofread;
+mod qr;
+mod regex_tool;
+mod reminder;
+mod request_replay;
 mod runtime;
 mod schema;
+mod screenshot_annotate;
 mod secrets_tools;
+pub mod session;
 mod sessions_tools;
 mod skill_curator;
 mod skills_tools;
+pub mod ssh;
+pub mod summarize_url;
 mod swarm_tools;
 mod sysadmin;
 mod system_tools;
+mod text_stats;
+mod time_tool;
+mod timezone_meeting;
 mod todo_tool;
+mod tool_defaults;
+mod transcribe;
+pub mod translate;
+pub mod translate_file;
 pub mod uv;
+mod validate;
+mod watch;
+mod weather;
 mod web;
 mod web_extract;
+mod web_scrape;
 // ast-grep structural code tool
 use ast_grep::exec_ast_grep;
 
@@ -58,28 +99,123 @@ use swarm_tools::{
 
 // Re-export helpers for external use
 pub use helpers::{
-    SharedVault, VAULT_ACCESS_DENIED, command_references_credentials, expand_tilde, init_sandbox,
-    is_protected_path, process_manager, run_sandboxed_command, sandbox, sanitize_tool_output,
-    set_credentials_dir, set_vault, vault,
+    SharedVault, TOOL_CANCELLED, VAULT_ACCESS_DENIED, command_references_credentials, expand_tilde,
+    init_sandbox, is_protected_path, memory_consolidation_config, process_manager,
+    run_sandboxed_command, sandbox, sanitize_tool_output, set_credentials_dir,
+    set_memory_consolidation_config, set_transcribe_config, set_vault, set_weather_config,
+    transcribe_config, vault, weather_config,
 };
 
+// Opt-in clipboard history for the clipboard tool
+pub use system_tools::{ClipboardHistory, set_clipboard_history_config, spawn_clipboard_watcher};
+
+// Context shown in tool approval prompts
+pub use approval::{ApprovalContext, ToolRisk, tool_risks};
+
 // File operations
 use file::{
     exec_edit_file, exec_find_files, exec_list_directory, exec_read_file, exec_search_files,
     exec_write_file,
 };
+use file_ops::{exec_copy_file, exec_move_file};
 
 // Runtime operations
 use runtime::{exec_execute_command, exec_process};
 
+// Session environment for commands
+use env_var::exec_env_var;
+pub(crate) use env_var::session_env;
+
 // Web operations
 use web::{exec_web_fetch, exec_web_search};
 use web_extract::exec_web_extract_stub;
+use web_scrape::{exec_extract_links_stub, exec_extract_structured_stub};
+
+// Translation (executed by the gateway)
+use translate::exec_translate_stub;
+use translate_file::exec_translate_file_stub;
+
+// Proofreading (model-backed calls are executed by the gateway)
+use proofread::exec_proofread;
+
+// Web page summaries (executed by the gateway)
+use summarize_url::exec_summarize_url;
+
+// Remote commands (executed by the gateway)
+use ssh::exec_ssh_stub;
+
+// Git operations
+use git_tool::exec_git;
+
+// Project linters
+use lint::exec_lint;
+
+// Containers (async, limits from [docker])
+use docker_tool::exec_docker_stub;
+pub use docker_tool::set_docker_config;
+
+// Short-lived HTTP listeners (async)
+use http_server::exec_http_server_stub;
+
+// Replaying curl commands and HAR entries (async, hosts from [request_replay])
+use request_replay::exec_network_request_replay_stub;
+pub use request_replay::set_request_replay_config;
+
+// Default arguments filled in before dispatch ([tool_defaults.<tool>])
+pub use tool_defaults::{ToolDefaults, check_tool_defaults, merge_defaults, set_tool_defaults};
+
+// CSV querying
+use csv_tool::exec_csv;
+
+// Log triage
+use parse_log::exec_parse_log;
+
+// Text file statistics
+use text_stats::exec_text_stats;
+
+// Encoding and hashing
+use encode::exec_encode;
+
+// Regular expressions
+use regex_tool::exec_regex;
+
+// QR codes
+use qr::exec_qr;
+
+// Image resize, crop and conversion
+use image_transform::exec_image_transform;
+
+// Time and timezones
+use time_tool::exec_time;
+
+// Meeting times across timezones
+use timezone_meeting::exec_timezone_meeting;
+
+// Color conversion, contrast and palettes
+use color::exec_color;
+
+// JSON/YAML/TOML syntax and JSON Schema checks
+use validate::exec_validate;
+
+// DNS lookups with a built-in resolver
+use dns_tool::exec_dns;
+
+// Exact arithmetic and unit conversion
+use math_tool::exec_math;
+
+// Boxes, arrows and labels drawn onto screenshots
+use screenshot_annotate::exec_screenshot_annotate;
 
 // Todo planning tool
 use todo_tool::exec_todo;
 pub use todo_tool::{TodoItem, TodoStatus, get_todo_snapshot};
 
+// Host process hierarchy
+use process_tree::exec_process_tree;
+
+// Key/value scratch space (scoped to the calling session)
+use kv_store::exec_kv_store;
+
 // Skill curator
 use skill_curator::exec_skill_curator;
 
@@ -93,8 +229,13 @@ use memory_tools::{exec_add_memory, exec_memory_search};
 use memory_tools::{exec_memory_get, exec_save_memory, exec_search_history};
 
 // Cron operations
+pub use cron_tool::dispatch_due_messages;
 use cron_tool::exec_cron;
 
+// Reminders (stored by the gateway for the foreground thread)
+use reminder::exec_reminder;
+pub use reminder::{DueReminder, MAX_PENDING_REMINDERS, apply_reminder, take_due_reminders};
+
 // Session operations
 use sessions_tools::{
     exec_agents_list, exec_session_status, exec_sessions_history, exec_sessions_list,
@@ -104,6 +245,10 @@ use sessions_tools::{
 // Patch operations
 use patch::exec_apply_patch;
 
+// File watches
+pub use watch::clear_watches;
+use watch::{exec_watch_file, exec_watch_poll};
+
 // Gateway operations
 use gateway_tools::{exec_gateway, exec_image, exec_message, exec_tts};
 
@@ -139,6 +284,15 @@ use model_tools::{
 // Kernel awareness (host hardware + load)
 use kernel_tools::{exec_host_info_stub, exec_load_status_stub};
 
+// Environment snapshot
+use env_info::exec_env_info_stub;
+
+// Weather (async, provider from [weather])
+use weather::exec_weather_stub;
+
+// Speech to text (async, whisper.cpp or cloud from [transcribe])
+use transcribe::exec_transcribe_stub;
+
 // Managed service tools
 mod service_tools;
 use service_tools::{
@@ -152,8 +306,8 @@ use secrets_tools::exec_secrets_stub;
 // System tools
 use system_tools::{
     exec_app_index, exec_audit_sensitive, exec_battery_health, exec_browser_cache,
-    exec_classify_files, exec_clipboard, exec_cloud_browse, exec_disk_usage, exec_screenshot,
-    exec_secure_delete, exec_summarize_file, exec_system_monitor,
+    exec_classify_files, exec_clipboard, exec_cloud_browse, exec_disk_usage, exec_ocr_screen,
+    exec_screenshot, exec_secure_delete, exec_summarize_file, exec_system_monitor,
 };
 
 // System administration tools
@@ -162,8 +316,16 @@ use sysadmin::{
     exec_user_manage,
 };
 
-// PDF tool
+// PDF tools
 use pdf::exec_pdf;
+use pdf_create::exec_pdf_create_stub;
+use pdf_extract::exec_pdf_extract;
+
+// Reading single archive members without extracting
+use archive_member::exec_extract_archive_member;
+
+// Document format conversion (pandoc, or built in for markdown <-> html)
+use convert_document::exec_convert_document_stub;
 
 // Exo AI tools
 use exo_ai::exec_exo_manage;
@@ -188,6 +350,7 @@ fn exec_client_dom_query_stub(_args: &Value, _workspace_dir: &Path) -> Result<St
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::path::Path;
+use tokio_util::sync::CancellationToken;
 
 // ── Tool permissions ────────────────────────────────────────────────────────
 
@@ -245,6 +408,43 @@ impl ToolPermission {
         }
     }
 
+    /// Wire name of the permission level (`allow`, `deny`, `ask`,
+    /// `skill_only`), as used in config and gateway frames.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+            Self::Ask => "ask",
+            Self::SkillOnly(_) => "skill_only",
+        }
+    }
+
+    /// Build a permission from its [`kind`](Self::kind) name.  `skills` is
+    /// only used for `skill_only`; blank names are dropped.
+    pub fn from_kind(kind: &str, skills: Vec<String>) -> Option<Self> {
+        match kind {
+            "allow" => Some(Self::Allow),
+            "deny" => Some(Self::Deny),
+            "ask" => Some(Self::Ask),
+            "skill_only" => Some(Self::SkillOnly(
+                skills
+                    .into_iter()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Skills allowed to invoke the tool (empty unless `SkillOnly`).
+    pub fn skills(&self) -> &[String] {
+        match self {
+            Self::SkillOnly(skills) => skills,
+            _ => &[],
+        }
+    }
+
     /// Human-readable description of what this permission level does.
     pub fn description(&self) -> &'static str {
         match self {
@@ -272,16 +472,20 @@ pub fn tool_summary(name: &str) -> &'static str {
         "list_directory" => "List folder contents",
         "search_files" => "Search inside file contents",
         "find_files" => "Find files by name",
+        "move_file" => "Move or rename files and folders",
+        "copy_file" => "Copy files and folders",
         "execute_command" => "Run shell commands",
         "web_fetch" => "Fetch content from URLs",
         "web_search" => "Search the web",
         "process" => "Manage background processes",
+        "env_var" => "Set environment variables for later commands",
         "memory_search" => "Search agent memory files",
         "memory_get" => "Read agent memory files",
         "save_memory" => "Save memories (two-layer consolidation)",
         "search_history" => "Search HISTORY.md for past entries",
         "add_memory" => "Add memory to semantic index",
         "cron" => "Manage scheduled jobs",
+        "reminder" => "Remind yourself later in this conversation",
         "sessions_list" => "List active sessions",
         "sessions_spawn" => "Spawn async sub-agents (use cheaper models for simple tasks)",
         "sessions_send" => "Send messages to sessions",
@@ -289,13 +493,17 @@ pub fn tool_summary(name: &str) -> &'static str {
         "session_status" => "Check session status & usage",
         "agents_list" => "List available agent types",
         "apply_patch" => "Apply diff patches to files",
+        "watch_file" => "Watch a file, directory or glob for changes",
+        "watch_poll" => "Get file changes seen since the last poll",
         "secrets_list" => "List vault secret names",
         "secrets_get" => "Read secrets from the vault",
         "secrets_store" => "Store secrets in the vault",
         "secrets_set_policy" => "Change credential access policy",
+        "secrets_generate" => "Generate a strong password or passphrase",
         "gateway" => "Control the gateway daemon",
         "message" => "Send messages via channels",
         "tts" => "Convert text to speech",
+        "transcribe" => "Transcribe an audio file to text",
         "image" => "Analyze images with vision AI",
         "nodes" => "Control paired companion devices",
         "browser" => "Automate a web browser",
@@ -327,6 +535,27 @@ pub fn tool_summary(name: &str) -> &'static str {
         "model_recommend" => "Get model recommendation for task complexity",
         "host_info" => "View gateway host hardware capabilities",
         "load_status" => "View current system load and resource usage",
+        "env_info" => "Summarize OS, hardware, shell & installed toolchains",
+        "git" => "Git status, diff, log, branch, add, commit & stash",
+        "lint" => "Run the project's linters and return parsed diagnostics",
+        "docker" => "Containers: ps, images, run, stop, rm, logs & exec",
+        "http_server" => "Briefly serve files or capture a callback request",
+        "network_request_replay" => "Replay a curl command or HAR entry to an allowed host",
+        "csv" => "Query CSV files: head, stats, filter & select",
+        "parse_log" => "Summarize and filter log files by level, time & pattern",
+        "text_stats" => "Line/word/byte counts, head & tail, encoding of a file",
+        "encode" => "Base64/hex/URL encode & decode, md5/sha hashes",
+        "regex" => "Regex find, replace & named-group extraction",
+        "qr" => "Generate QR codes (PNG or terminal) and read them from images",
+        "image_transform" => "Resize, crop or convert images, or read their dimensions",
+        "screenshot_annotate" => "Draw boxes, arrows & labels on a screenshot",
+        "weather" => "Current weather and a short forecast for a place",
+        "time" => "Current time, timezone conversion, durations & date parsing",
+        "timezone_meeting" => "Find working hours several timezones have in common",
+        "color" => "Convert colors, check WCAG contrast & build palettes",
+        "validate" => "Check JSON/YAML/TOML syntax, optionally against a JSON Schema",
+        "dns" => "DNS lookups (A, AAAA, MX, TXT, CNAME, NS, SOA, reverse) with TTLs",
+        "math" => "Evaluate arithmetic exactly; convert units & currencies",
         "service_list" => "List managed backend services and their status",
         "service_start" => "Start a managed backend service",
         "service_stop" => "Stop a managed backend service",
@@ -335,12 +564,14 @@ pub fn tool_summary(name: &str) -> &'static str {
         "disk_usage" => "Scan disk usage by folder",
         "classify_files" => "Categorize files as docs, caches, etc.",
         "system_monitor" => "View CPU, memory & process info",
+        "process_tree" => "List, inspect or kill host processes and their children",
         "battery_health" => "Check battery status & health",
         "app_index" => "List installed apps by size",
         "cloud_browse" => "Browse local cloud storage folders",
         "browser_cache" => "Audit or clean browser caches",
         "screenshot" => "Capture a screenshot",
-        "clipboard" => "Read or write the clipboard",
+        "ocr_screen" => "Read the text on screen (screenshot + OCR)",
+        "clipboard" => "Read or write the clipboard, or recall recent copies",
         "audit_sensitive" => "Scan files for exposed secrets",
         "secure_delete" => "Securely overwrite & delete files",
         "summarize_file" => "Preview-summarize any file type",
@@ -353,6 +584,10 @@ pub fn tool_summary(name: &str) -> &'static str {
         "npm_manage" => "Manage Node.js packages & scripts via npm",
         "agent_setup" => "Set up local model infrastructure",
         "pdf" => "Analyze PDF files (extract text, metadata, page counts)",
+        "pdf_extract" => "Extract text, tables or images from chosen PDF pages",
+        "pdf_create" => "Render markdown or HTML to a PDF file",
+        "extract_archive_member" => "List an archive or read one member without extracting",
+        "convert_document" => "Convert documents between md, docx, html, rst, latex…",
         "swarm_create" => "Create and start a multi-agent swarm",
         "swarm_list" => "List all swarms and their status",
         "swarm_status" => "Get detailed status for a swarm",
@@ -360,8 +595,16 @@ pub fn tool_summary(name: &str) -> &'static str {
         "swarm_stop" => "Stop a running swarm",
         "swarm_templates" => "List available swarm templates",
         "todo" => "Plan and track multi-step tasks with a checklist",
+        "kv_store" => "Stash JSON working state between turns",
         "skill_curator" => "Auto-propose, grade, merge, and prune skills",
         "web_extract" => "Extract clean readable content from web pages",
+        "extract_links" => "List a page's links with their anchor text",
+        "extract_structured" => "Read a page's Open Graph, JSON-LD and meta tags",
+        "translate" => "Translate text, preserving markdown & code blocks",
+        "translate_file" => "Translate subtitles, Markdown or .po files, keeping their structure",
+        "proofread" => "Proofread text or a file, returning structured corrections",
+        "summarize_url" => "Summarise a web page with key points",
+        "ssh" => "Run commands on configured remote hosts",
         "image_generate" => "Generate images from text prompts",
         _ => "Unknown tool",
     }
@@ -415,10 +658,13 @@ pub fn all_tools() -> Vec<&'static ToolDef> {
         &LIST_DIRECTORY,
         &SEARCH_FILES,
         &FIND_FILES,
+        &MOVE_FILE,
+        &COPY_FILE,
         &EXECUTE_COMMAND,
         &WEB_FETCH,
         &WEB_SEARCH,
         &PROCESS,
+        &ENV_VAR,
         #[cfg(feature = "semantic-memory")]
         &MEMORY_SEARCH,
         &MEMORY_GET,
@@ -427,6 +673,7 @@ pub fn all_tools() -> Vec<&'static ToolDef> {
         #[cfg(feature = "semantic-memory")]
         &ADD_MEMORY,
         &CRON,
+        &REMINDER,
         &SESSIONS_LIST,
         &SESSIONS_SPAWN,
         &SESSIONS_SEND,
@@ -434,13 +681,17 @@ pub fn all_tools() -> Vec<&'static ToolDef> {
         &SESSION_STATUS,
         &AGENTS_LIST,
         &APPLY_PATCH,
+        &WATCH_FILE,
+        &WATCH_POLL,
         &SECRETS_LIST,
         &SECRETS_GET,
         &SECRETS_STORE,
         &SECRETS_SET_POLICY,
+        &SECRETS_GENERATE,
         &GATEWAY,
         &MESSAGE,
         &TTS,
+        &TRANSCRIBE,
         &IMAGE,
         &NODES,
         &BROWSER,
@@ -473,6 +724,27 @@ pub fn all_tools() -> Vec<&'static ToolDef> {
         &MODEL_RECOMMEND,
         &HOST_INFO,
         &LOAD_STATUS,
+        &ENV_INFO,
+        &GIT,
+        &LINT,
+        &DOCKER,
+        &HTTP_SERVER,
+        &NETWORK_REQUEST_REPLAY,
+        &CSV,
+        &PARSE_LOG,
+        &TEXT_STATS,
+        &ENCODE,
+        &REGEX,
+        &QR,
+        &IMAGE_TRANSFORM,
+        &SCREENSHOT_ANNOTATE,
+        &WEATHER,
+        &TIME,
+        &TIMEZONE_MEETING,
+        &COLOR,
+        &VALIDATE,
+        &DNS,
+        &MATH,
         &SERVICE_LIST,
         &SERVICE_START,
         &SERVICE_STOP,
@@ -481,11 +753,13 @@ pub fn all_tools() -> Vec<&'static ToolDef> {
         &DISK_USAGE,
         &CLASSIFY_FILES,
         &SYSTEM_MONITOR,
+        &PROCESS_TREE,
         &BATTERY_HEALTH,
         &APP_INDEX,
         &CLOUD_BROWSE,
         &BROWSER_CACHE,
         &SCREENSHOT,
+        &OCR_SCREEN,
         &CLIPBOARD,
         &AUDIT_SENSITIVE,
         &SECURE_DELETE,
@@ -505,6 +779,10 @@ pub fn all_tools() -> Vec<&'static ToolDef> {
         &ASK_USER,
         &CLIENT_DOM_QUERY,
         &PDF,
+        &PDF_EXTRACT,
+        &PDF_CREATE,
+        &EXTRACT_ARCHIVE_MEMBER,
+        &CONVERT_DOCUMENT,
         &SWARM_CREATE,
         &SWARM_LIST,
         &SWARM_STATUS,
@@ -512,8 +790,16 @@ pub fn all_tools() -> Vec<&'static ToolDef> {
         &SWARM_STOP,
         &SWARM_TEMPLATES,
         &TODO,
+        &KV_STORE,
         &SKILL_CURATOR,
         &WEB_EXTRACT,
+        &EXTRACT_LINKS,
+        &EXTRACT_STRUCTURED,
+        &TRANSLATE,
+        &TRANSLATE_FILE,
+        &PROOFREAD,
+        &SUMMARIZE_URL,
+        &SSH,
         #[cfg(feature = "image-gen")]
         &IMAGE_GENERATE,
     ]
@@ -529,7 +815,11 @@ pub use definitions::*;
 pub fn is_secrets_tool(name: &str) -> bool {
     matches!(
         name,
-        "secrets_list" | "secrets_get" | "secrets_store" | "secrets_set_policy"
+        "secrets_list"
+            | "secrets_get"
+            | "secrets_store"
+            | "secrets_set_policy"
+            | "secrets_generate"
     )
 }
 
@@ -561,6 +851,55 @@ pub fn is_dom_query_tool(name: &str) -> bool {
     name == "client_dom_query"
 }
 
+/// Returns `true` for tools without side effects, which the gateway may
+/// run concurrently when one model response asks for several of them.
+/// Anything that writes, prompts, or needs the gateway's model or vault
+/// stays sequential.
+pub fn is_parallel_safe_tool(name: &str) -> bool {
+    matches!(
+        name,
+        "read_file"
+            | "list_directory"
+            | "search_files"
+            | "find_files"
+            | "web_fetch"
+            | "web_search"
+            | "web_extract"
+            | "extract_links"
+            | "extract_structured"
+            | "memory_search"
+            | "memory_get"
+            | "search_history"
+            | "env_info"
+            | "disk_usage"
+            | "net_info"
+            | "weather"
+            | "time"
+            | "timezone_meeting"
+            | "color"
+            | "validate"
+            | "dns"
+            | "math"
+    )
+}
+
+/// Returns `true` for tools whose state belongs to the whole workspace
+/// rather than one session (memory, stored keys, skills, cron jobs).  With
+/// per-session directories these still run against the workspace root.
+pub fn uses_workspace_root(name: &str) -> bool {
+    is_skill_tool(name)
+        || matches!(
+            name,
+            "memory_search"
+                | "memory_get"
+                | "save_memory"
+                | "search_history"
+                | "kv_store"
+                | "cron"
+                | "agents_list"
+        )
+}
+
 /// Tools that have native async implementations.
 const ASYNC_NATIVE_TOOLS: &[&str] = &[
     "execute_command",
@@ -576,6 +915,7 @@ const ASYNC_NATIVE_TOOLS: &[&str] = &[
     "gateway",
     "message",
     "tts",
+    "transcribe",
     "image",
     "ollama_manage",
     "exo_manage",
@@ -595,6 +935,7 @@ const ASYNC_NATIVE_TOOLS: &[&str] = &[
     "cloud_browse",
     "browser_cache",
     "screenshot",
+    "ocr_screen",
     "clipboard",
     "audit_sensitive",
     "secure_delete",
@@ -602,6 +943,15 @@ const ASYNC_NATIVE_TOOLS: &[&str] = &[
     "nodes",
     "canvas",
     "web_extract",
+    "extract_links",
+    "extract_structured",
+    "env_info",
+    "weather",
+    "pdf_create",
+    "convert_document",
+    "docker",
+    "http_server",
+    "network_request_replay",
     #[cfg(feature = "image-gen")]
     "image_generate",
 ];
@@ -610,6 +960,7 @@ const ASYNC_NATIVE_TOOLS: &[&str] = &[
 ///
 /// Tools with async implementations are called directly.
 /// Other tools run on a blocking thread pool to avoid blocking the async runtime.
+/// Arguments the model left out are first filled in from `[tool_defaults]`.
 #[instrument(skip(args, workspace_dir), fields(tool = name))]
 pub async fn execute_tool(
     name: &str,
@@ -618,6 +969,17 @@ pub async fn execute_tool(
 ) -> Result<String, String> {
     debug!("Executing tool");
 
+    let args = tool_defaults::with_defaults(name, args);
+    let args = args.as_ref();
+
+    let root;
+    let workspace_dir = if uses_workspace_root(name) {
+        root = crate::session_workspace::workspace_root(workspace_dir);
+        root.as_path()
+    } else {
+        workspace_dir
+    };
+
     // Handle async-native tools directly
     if ASYNC_NATIVE_TOOLS.contains(&name) {
         let result = match name {
@@ -634,6 +996,7 @@ pub async fn execute_tool(
             "gateway" => gateway_tools::exec_gateway_async(args, workspace_dir).await,
             "message" => gateway_tools::exec_message_async(args, workspace_dir).await,
             "tts" => gateway_tools::exec_tts_async(args, workspace_dir).await,
+            "transcribe" => transcribe::exec_transcribe_async(args, workspace_dir).await,
             "image" => gateway_tools::exec_image_async(args, workspace_dir).await,
             "ollama_manage" => ollama::exec_ollama_manage_async(args, workspace_dir).await,
             "exo_manage" => exo_ai::exec_exo_manage_async(args, workspace_dir).await,
@@ -653,6 +1016,7 @@ pub async fn execute_tool(
             "cloud_browse" => system_tools::exec_cloud_browse_async(args, workspace_dir).await,
             "browser_cache" => system_tools::exec_browser_cache_async(args, workspace_dir).await,
             "screenshot" => system_tools::exec_screenshot_async(args, workspace_dir).await,
+            "ocr_screen" => system_tools::exec_ocr_screen_async(args, workspace_dir).await,
             "clipboard" => system_tools::exec_clipboard_async(args, workspace_dir).await,
             "audit_sensitive" => {
                 system_tools::exec_audit_sensitive_async(args, workspace_dir).await
@@ -662,6 +1026,21 @@ pub async fn execute_tool(
             "nodes" => devices::exec_nodes_async(args, workspace_dir).await,
             "canvas" => devices::exec_canvas_async(args, workspace_dir).await,
             "web_extract" => web_extract::exec_web_extract_async(args, workspace_dir).await,
+            "extract_links" => web_scrape::exec_extract_links_async(args, workspace_dir).await,
+            "extract_structured" => {
+                web_scrape::exec_extract_structured_async(args, workspace_dir).await
+            }
+            "env_info" => env_info::exec_env_info_async(args, workspace_dir).await,
+            "weather" => weather::exec_weather_async(args, workspace_dir).await,
+            "pdf_create" => pdf_create::exec_pdf_create_async(args, workspace_dir).await,
+            "convert_document" => {
+                convert_document::exec_convert_document_async(args, workspace_dir).await
+            }
+            "docker" => docker_tool::exec_docker_async(args, workspace_dir).await,
+            "http_server" => http_server::exec_http_server_async(args, workspace_dir).await,
+            "network_request_replay" => {
+                request_replay::exec_network_request_replay_async(args, workspace_dir).await
+            }
             #[cfg(feature = "image-gen")]
             "image_generate" => image_gen::exec_image_generate_async(args, workspace_dir).await,
             _ => unreachable!(),
@@ -686,10 +1065,20 @@ pub async fn execute_tool(
     let args = args.clone();
     let workspace_dir = workspace_dir.to_path_buf();
 
-    // Run sync tools on blocking thread pool
-    let result = tokio::task::spawn_blocking(move || execute_fn(&args, &workspace_dir))
-        .await
-        .map_err(|e| format!("Task join error: {}", e))?;
+    // Run sync tools on blocking thread pool, still inside the caller's
+    // span, request and session so their logs and secret reads are
+    // attributed and their session state is the caller's.
+    let span = tracing::Span::current();
+    let ids = crate::observability::request_ids::current();
+    let tool_session = session::current();
+    let result = tokio::task::spawn_blocking(move || {
+        let _entered = span.enter();
+        crate::observability::request_ids::sync_scope(ids, || {
+            session::sync_scope(tool_session, || execute_fn(&args, &workspace_dir))
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
 
     if result.is_err() {
         warn!(error = ?result.as_ref().err(), "Tool execution failed");
@@ -698,6 +1087,31 @@ pub async fn execute_tool(
     result.map(|s| crate::tool_pipeline::apply_global(name, &args_for_pipeline, s))
 }
 
+/// Execute a tool, abandoning it as soon as `cancel` fires.
+///
+/// The in-flight call is dropped: open HTTP requests are closed and
+/// `execute_command` kills its process group.  A sync tool already running
+/// on the blocking pool finishes in the background and its output is
+/// discarded.  A cancelled call returns `Err(TOOL_CANCELLED)`.
+pub async fn execute_tool_cancellable(
+    name: &str,
+    args: &Value,
+    workspace_dir: &Path,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    if cancel.is_cancelled() {
+        return Err(TOOL_CANCELLED.to_string());
+    }
+    tokio::select! {
+        biased;
+        _ = cancel.cancelled() => {
+            debug!(tool = name, "Tool call cancelled");
+            Err(TOOL_CANCELLED.to_string())
+        }
+        result = execute_tool(name, args, workspace_dir) => result,
+    }
+}
+
 // ── Wire types for WebSocket protocol ───────────────────────────────────────
 
 /// A tool call requested by the model (sent gateway → client for display).
@@ -722,3 +1136,5 @@ pub struct ToolResult {
 mod tests_a;
 #[cfg(test)]
 mod tests_b;
+#[cfg(test)]
+mod tests_c;
@@ -285,11 +285,34 @@ pub fn screenshot_params() -> Vec<ToolParam> {
     ]
 }
 
+pub fn ocr_screen_params() -> Vec<ToolParam> {
+    let mut params = screenshot_params();
+    params[0].description =
+        "Where to keep the screenshot. Omit to OCR a temporary capture that is deleted.".into();
+    params.extend([
+        ToolParam {
+            name: "boxes".into(),
+            description: "Bounding boxes to return: 'none' (default), 'word' or 'line'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "lang".into(),
+            description: "Tesseract language(s), e.g. 'eng' (default) or 'eng+deu'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]);
+    params
+}
+
 pub fn clipboard_params() -> Vec<ToolParam> {
     vec![
         ToolParam {
             name: "action".into(),
-            description: "Action: 'read' to get clipboard contents, 'write' to set them.".into(),
+            description: "Action: 'read' to get clipboard contents, 'write' to set them, \
+                          'history' to list recent copies, 'history_get' to fetch one."
+                .into(),
             param_type: "string".into(),
             required: true,
         },
@@ -299,6 +322,25 @@ pub fn clipboard_params() -> Vec<ToolParam> {
             param_type: "string".into(),
             required: false,
         },
+        ToolParam {
+            name: "query".into(),
+            description: "Only list history entries containing this text (action='history')."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "limit".into(),
+            description: "Most history entries to list (action='history', default 10).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "index".into(),
+            description: "History entry to return, 0 = most recent (action='history_get').".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
     ]
 }
 
@@ -969,8 +1011,45 @@ pub fn client_dom_query_params() -> Vec<ToolParam> {
 
 // ── Re-exported parameter functions from new tool modules ───────────────────
 
+pub use crate::tools::archive_member::extract_archive_member_params;
+pub use crate::tools::color::color_params;
+pub use crate::tools::convert_document::convert_document_params;
+pub use crate::tools::csv_tool::csv_params;
+pub use crate::tools::dns_tool::dns_params;
+pub use crate::tools::docker_tool::docker_params;
+pub use crate::tools::encode::encode_params;
+pub use crate::tools::env_info::env_info_params;
+pub use crate::tools::env_var::env_var_params;
+pub use crate::tools::git_tool::git_params;
+pub use crate::tools::http_server::http_server_params;
 #[cfg(feature = "image-gen")]
 pub use crate::tools::image_gen::image_generate_params;
+pub use crate::tools::image_transform::image_transform_params;
+pub use crate::tools::kv_store::kv_store_params;
+pub use crate::tools::lint::lint_params;
+pub use crate::tools::math_tool::math_params;
+pub use crate::tools::parse_log::parse_log_params;
+pub use crate::tools::pdf_create::pdf_create_params;
+pub use crate::tools::pdf_extract::pdf_extract_params;
+pub use crate::tools::process_tree::process_tree_params;
+pub use crate::tools::proofread::proofread_params;
+pub use crate::tools::qr::qr_params;
+pub use crate::tools::regex_tool::regex_params;
+pub use crate::tools::reminder::reminder_params;
+pub use crate::tools::request_replay::network_request_replay_params;
+pub use crate::tools::screenshot_annotate::screenshot_annotate_params;
 pub use crate::tools::skill_curator::skill_curator_params;
+pub use crate::tools::ssh::ssh_params;
+pub use crate::tools::summarize_url::summarize_url_params;
+pub use crate::tools::text_stats::text_stats_params;
+pub use crate::tools::time_tool::time_params;
+pub use crate::tools::timezone_meeting::timezone_meeting_params;
 pub use crate::tools::todo_tool::todo_params;
+pub use crate::tools::transcribe::transcribe_params;
+pub use crate::tools::translate::translate_params;
+pub use crate::tools::translate_file::translate_file_params;
+pub use crate::tools::validate::validate_params;
+pub use crate::tools::watch::{watch_file_params, watch_poll_params};
+pub use crate::tools::weather::weather_params;
 pub use crate::tools::web_extract::web_extract_params;
+pub use crate::tools::web_scrape::{extract_links_params, extract_structured_params};
@@ -135,6 +135,43 @@ pub fn find_files_params() -> Vec<ToolParam> {
     ]
 }
 
+pub fn move_file_params() -> Vec<ToolParam> {
+    transfer_params("move", "Path to move or rename.")
+}
+
+pub fn copy_file_params() -> Vec<ToolParam> {
+    transfer_params(
+        "copy",
+        "File or directory to copy; directories are copied recursively.",
+    )
+}
+
+/// Parameters shared by `move_file` and `copy_file`.
+fn transfer_params(verb: &str, source: &str) -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "source".into(),
+            description: source.into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "destination".into(),
+            description: format!(
+                "Where to {verb} it. An existing directory receives the source inside it."
+            ),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "force".into(),
+            description: "Replace an existing destination of the same kind. Default: false.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+    ]
+}
+
 pub fn execute_command_params() -> Vec<ToolParam> {
     vec![
         ToolParam {
@@ -342,6 +379,24 @@ pub fn memory_search_params() -> Vec<ToolParam> {
             param_type: "number".into(),
             required: false,
         },
+        ToolParam {
+            name: "after".into(),
+            description: "Only search entries written on/after this date or datetime (e.g. '2026-02-01' or '2026-02-01 14:00'). Uses HISTORY.md entry timestamps and file modification times.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "before".into(),
+            description: "Only search entries written before this date or datetime.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "files".into(),
+            description: "Glob restricting which memory files are searched (e.g. 'memory/2026-02-*.md', 'HISTORY.md'). Any filter switches to keyword scoring over the matching entries.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
     ]
 }
 
@@ -382,6 +437,12 @@ pub fn save_memory_params() -> Vec<ToolParam> {
             param_type: "string".into(),
             required: false,
         },
+        ToolParam {
+            name: "memory_facts".into(),
+            description: "Optional: individual facts to add to MEMORY.md. Facts already recorded are skipped and near-duplicates are updated in place.".into(),
+            param_type: "array".into(),
+            required: false,
+        },
     ]
 }
 
@@ -389,7 +450,7 @@ pub fn search_history_params() -> Vec<ToolParam> {
     vec![
         ToolParam {
             name: "pattern".into(),
-            description: "Text pattern to search for in HISTORY.md entries.".into(),
+            description: "Words or text to search for in HISTORY.md entries.".into(),
             param_type: "string".into(),
             required: true,
         },
@@ -501,11 +562,94 @@ pub fn secrets_set_policy_params() -> Vec<ToolParam> {
     ]
 }
 
+pub fn secrets_generate_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "style".into(),
+            description: "What to generate: 'password' (default) or 'passphrase' (random words).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "length".into(),
+            description: "Password length in characters, 8-256. Default: 24.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "charset".into(),
+            description: "Comma-separated character classes for a password: lower, upper, digits, symbols, or hex on its own. Default: 'lower,upper,digits,symbols'. Each class appears at least once.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "exclude_ambiguous".into(),
+            description: "Leave out look-alike characters such as 0/O and 1/l/I. Default: false.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "words".into(),
+            description: "Number of words in a passphrase, 4-24. Default: 6.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "separator".into(),
+            description: "String placed between passphrase words. Default: '-'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "name".into(),
+            description: "Store the value in the vault under this credential name. Without it the value is only returned.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "kind".into(),
+            description: "Credential kind when storing (see secrets_store). Default: token, or username_password when a username is given.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "policy".into(),
+            description: "Access policy when storing: always, approval (default), auth, skill:<name>.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "description".into(),
+            description: "Description of the stored credential.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "username".into(),
+            description: "Username to store alongside a generated password.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "reveal".into(),
+            description: "Also return the value when storing it. Default: false.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "overwrite".into(),
+            description: "Replace an existing credential with the same name. Default: false.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+    ]
+}
+
 pub fn gateway_params() -> Vec<ToolParam> {
     vec![
         ToolParam {
             name: "action".into(),
-            description: "Action: 'restart', 'config.get', 'config.schema', 'config.apply', 'config.patch', 'update.run'.".into(),
+            description: "Action: 'status' (uptime, model health, pause state), 'pause' (hold every session's tool loop before its next round), 'resume', 'restart', 'config.get', 'config.schema', 'config.apply', 'config.patch', 'update.run'.".into(),
             param_type: "string".into(),
             required: true,
         },
@@ -523,7 +667,7 @@ pub fn gateway_params() -> Vec<ToolParam> {
         },
         ToolParam {
             name: "reason".into(),
-            description: "Reason for restart or config change.".into(),
+            description: "Reason for restart, pause or config change.".into(),
             param_type: "string".into(),
             required: false,
         },
@@ -540,7 +684,7 @@ pub fn message_params() -> Vec<ToolParam> {
     vec![
         ToolParam {
             name: "action".into(),
-            description: "Action: 'send' or 'broadcast'.".into(),
+            description: "Action: 'send', 'broadcast' or 'schedule'.".into(),
             param_type: "string".into(),
             required: true,
         },
@@ -568,6 +712,20 @@ pub fn message_params() -> Vec<ToolParam> {
             param_type: "array".into(),
             required: false,
         },
+        ToolParam {
+            name: "at".into(),
+            description: "For 'schedule': when to send. RFC 3339, or local time as \
+                          'YYYY-MM-DD HH:MM' or 'HH:MM' (next occurrence)."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "delayMinutes".into(),
+            description: "For 'schedule': send this many minutes from now instead of 'at'.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
         ToolParam {
             name: "replyTo".into(),
             description: "Message ID to reply to.".into(),
@@ -763,7 +921,8 @@ pub fn cron_params() -> Vec<ToolParam> {
     vec![
         ToolParam {
             name: "action".into(),
-            description: "Action: 'status', 'list', 'add', 'update', 'remove', 'run', 'runs'."
+            description: "Action: 'status', 'list', 'add', 'update', 'remove', 'run', 'runs', \
+                          'export', 'import'."
                 .into(),
             param_type: "string".into(),
             required: true,
@@ -792,6 +951,37 @@ pub fn cron_params() -> Vec<ToolParam> {
             param_type: "boolean".into(),
             required: false,
         },
+        ToolParam {
+            name: "format".into(),
+            description: "Format for 'export': 'json' (default) or 'yaml'. 'import' reads \
+                          either."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "path".into(),
+            description: "File to export to or import from. Without it, 'export' returns \
+                          the jobs in its reply."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "data".into(),
+            description: "Exported jobs to 'import', instead of a path.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "onConflict".into(),
+            description: "For 'import', when a job ID already exists: 'skip' (default), \
+                          'overwrite', or 'merge' (take the imported definition, keep the \
+                          existing run times)."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
     ]
 }
 
@@ -0,0 +1,769 @@
+//! Log triage tool: filter and summarize log files of any size.
+//!
+//! `parse_log` reads a log once and answers in a bounded amount of text:
+//! counts by level, the most repeated messages, and the matching lines
+//! themselves, so the agent can triage a huge log without paging through
+//! it.  Each line is parsed on its own as a JSON object (tracing, bunyan,
+//! pino, logrus …), a syslog record (RFC 3164 or 5424), or plain text with
+//! an optional leading timestamp and level word, so mixed files work.  A
+//! plain line with neither, such as a stack-trace continuation, takes the
+//! level and time of the entry before it.
+//!
+//! Files go through the same vault-boundary and sandbox path checks as
+//! `read_file`.  At most [`MAX_SCAN_LINES`] lines are read per call; `tail`
+//! starts that many lines from the end instead of at the top.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::LazyLock;
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{
+    VAULT_ACCESS_DENIED, is_protected_path, open_file_read_safe, resolve_path, sandbox,
+};
+
+/// Lines read per call; the summary says when the file went on.
+const MAX_SCAN_LINES: usize = 1_000_000;
+
+/// Matching lines returned unless `limit` is given.
+const DEFAULT_LIMIT: usize = 50;
+
+/// Upper bound on returned lines regardless of the requested `limit`.
+const MAX_LIMIT: usize = 500;
+
+/// Repeated messages listed unless `top` is given.
+const DEFAULT_TOP: usize = 10;
+
+/// Upper bound on listed messages regardless of the requested `top`.
+const MAX_TOP: usize = 50;
+
+/// Distinct messages counted for `top`; later new ones are not tracked.
+const MAX_DISTINCT_MESSAGES: usize = 50_000;
+
+/// Returned lines and messages longer than this are cut with an ellipsis.
+const MAX_LINE_CHARS: usize = 300;
+
+/// Bytes read per step when looking backwards for the `tail` start.
+const TAIL_BLOCK: u64 = 64 * 1024;
+
+// ── Records ─────────────────────────────────────────────────────────────────
+
+/// Severity of a log entry, lowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    /// A level name as loggers write it (`WARNING`, `err`, `fatal`, …).
+    pub fn parse(word: &str) -> Option<Level> {
+        match word.to_ascii_lowercase().as_str() {
+            "trace" | "trc" | "finest" | "finer" => Some(Level::Trace),
+            "debug" | "dbg" | "fine" => Some(Level::Debug),
+            "info" | "inf" | "information" | "notice" => Some(Level::Info),
+            "warn" | "warning" | "wrn" => Some(Level::Warn),
+            "error" | "err" | "fatal" | "critical" | "crit" | "panic" | "severe" | "alert"
+            | "emerg" | "emergency" => Some(Level::Error),
+            _ => None,
+        }
+    }
+
+    /// Bunyan and pino numeric levels (10 trace … 50 error, 60 fatal).
+    fn from_number(n: i64) -> Level {
+        match n {
+            n if n <= 10 => Level::Trace,
+            n if n <= 20 => Level::Debug,
+            n if n <= 30 => Level::Info,
+            n if n <= 40 => Level::Warn,
+            _ => Level::Error,
+        }
+    }
+
+    /// Syslog severity (0 emergency … 7 debug).
+    fn from_syslog(severity: u32) -> Level {
+        match severity {
+            0..=3 => Level::Error,
+            4 => Level::Warn,
+            5 | 6 => Level::Info,
+            _ => Level::Debug,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Trace => "trace",
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a line was recognised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Json,
+    Syslog,
+    Plain,
+}
+
+/// One parsed log line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub format: LogFormat,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub level: Option<Level>,
+    pub message: String,
+}
+
+/// Parse one line, trying JSON, then syslog, then plain text.
+pub fn parse_line(line: &str) -> LogRecord {
+    parse_json(line)
+        .or_else(|| parse_syslog(line))
+        .unwrap_or_else(|| parse_plain(line))
+}
+
+const LEVEL_KEYS: &[&str] = &["level", "lvl", "severity", "log.level", "levelname"];
+const TIME_KEYS: &[&str] = &["timestamp", "time", "ts", "@timestamp", "datetime", "t"];
+const MESSAGE_KEYS: &[&str] = &["message", "msg", "fields.message", "@message", "event"];
+
+fn parse_json(line: &str) -> Option<LogRecord> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return None;
+    }
+    let obj: Map<String, Value> = serde_json::from_str(line).ok()?;
+    let field = |keys: &[&str]| keys.iter().find_map(|key| json_field(&obj, key));
+
+    let level = field(LEVEL_KEYS).and_then(|v| match v {
+        Value::String(s) => Level::parse(s),
+        Value::Number(n) => n.as_i64().map(Level::from_number),
+        _ => None,
+    });
+    let timestamp = field(TIME_KEYS).and_then(|v| match v {
+        Value::String(s) => parse_timestamp(s),
+        Value::Number(n) => n.as_f64().and_then(epoch_timestamp),
+        _ => None,
+    });
+    let message = match field(MESSAGE_KEYS) {
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+        None => line.to_string(),
+    };
+    Some(LogRecord {
+        format: LogFormat::Json,
+        timestamp,
+        level,
+        message,
+    })
+}
+
+/// `key` itself, or a dotted path into nested objects (`fields.message`).
+fn json_field<'a>(obj: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    if let Some(value) = obj.get(key) {
+        return Some(value);
+    }
+    let (head, rest) = key.split_once('.')?;
+    json_field(obj.get(head)?.as_object()?, rest)
+}
+
+/// Seconds, or milliseconds for values too large to be seconds.
+fn epoch_timestamp(n: f64) -> Option<DateTime<Utc>> {
+    let millis = if n > 1e11 { n } else { n * 1000.0 };
+    DateTime::from_timestamp_millis(millis as i64)
+}
+
+static SYSLOG_5424: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^<(\d{1,3})>1 (\S+) (\S+) (\S+) (\S+) (\S+) (?:-|(?:\[[^\]]*\])+) ?(.*)$").unwrap()
+});
+
+static SYSLOG_3164: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:<(\d{1,3})>)?([A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}) (\S+) ([^:\[\s]+)(?:\[\d+\])?: ?(.*)$",
+    )
+    .unwrap()
+});
+
+fn parse_syslog(line: &str) -> Option<LogRecord> {
+    if let Some(caps) = SYSLOG_5424.captures(line) {
+        let severity = caps[1].parse::<u32>().ok().map(|pri| pri % 8);
+        let message = format!("{}: {}", &caps[4], &caps[7]);
+        return Some(LogRecord {
+            format: LogFormat::Syslog,
+            timestamp: parse_timestamp(&caps[2]),
+            level: severity
+                .map(Level::from_syslog)
+                .or_else(|| level_word(&caps[7])),
+            message,
+        });
+    }
+    let caps = SYSLOG_3164.captures(line)?;
+    let severity = caps
+        .get(1)
+        .and_then(|m| m.as_str().parse::<u32>().ok())
+        .map(|pri| pri % 8);
+    Some(LogRecord {
+        format: LogFormat::Syslog,
+        timestamp: bsd_timestamp(&caps[2], Local::now()),
+        level: severity
+            .map(Level::from_syslog)
+            .or_else(|| level_word(&caps[5])),
+        message: format!("{}: {}", &caps[4], &caps[5]),
+    })
+}
+
+/// An RFC 3164 `Mmm dd hh:mm:ss` stamp, which has no year: the most recent
+/// such time that isn't more than a day ahead of `now`.
+fn bsd_timestamp(stamp: &str, now: DateTime<Local>) -> Option<DateTime<Utc>> {
+    let stamp = stamp.split_whitespace().collect::<Vec<_>>().join(" ");
+    let at = |year: i32| {
+        NaiveDateTime::parse_from_str(&format!("{} {}", year, stamp), "%Y %b %d %H:%M:%S")
+            .ok()
+            .and_then(local_to_utc)
+    };
+    let this_year = at(now.year())?;
+    if this_year > now.with_timezone(&Utc) + Duration::days(1) {
+        at(now.year() - 1)
+    } else {
+        Some(this_year)
+    }
+}
+
+static PLAIN_TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\[?(\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\]?\s*",
+    )
+    .unwrap()
+});
+
+static LEADING_LEVEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^[\[<(]?(trace|debug|info|notice|warn(?:ing)?|error|err|fatal|crit(?:ical)?|panic|severe)[\]>)]?:?(?:\s+|$)",
+    )
+    .unwrap()
+});
+
+static LEVEL_WORD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(TRACE|DEBUG|INFO|NOTICE|WARN|WARNING|ERROR|FATAL|CRITICAL|PANIC|SEVERE)\b")
+        .unwrap()
+});
+
+/// An upper-case level word near the start of `text` (`[main] ERROR …`).
+fn level_word(text: &str) -> Option<Level> {
+    let head: String = text.chars().take(80).collect();
+    LEVEL_WORD
+        .captures(&head)
+        .and_then(|caps| Level::parse(&caps[1]))
+}
+
+fn parse_plain(line: &str) -> LogRecord {
+    let mut rest = line.trim_end();
+    let mut timestamp = None;
+    if let Some(caps) = PLAIN_TIMESTAMP.captures(rest) {
+        timestamp = parse_timestamp(&caps[1]);
+        rest = &rest[caps[0].len()..];
+    }
+    let level = match LEADING_LEVEL.captures(rest) {
+        Some(caps) => {
+            let level = Level::parse(&caps[1]);
+            rest = &rest[caps[0].len()..];
+            level
+        }
+        None => level_word(rest),
+    };
+    LogRecord {
+        format: LogFormat::Plain,
+        timestamp,
+        level,
+        message: rest.trim().to_string(),
+    }
+}
+
+/// RFC 3339 and the common variants: a space instead of `T`, a comma
+/// before the fraction, `+0000` offsets, or no zone (local time).
+pub fn parse_timestamp(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.len() < 19 || text.as_bytes()[10] != b' ' && text.as_bytes()[10] != b'T' {
+        return None;
+    }
+    let normalized = format!("{}T{}", &text[..10], text[11..].replace(',', "."));
+    if let Ok(t) = DateTime::parse_from_rfc3339(&normalized) {
+        return Some(t.with_timezone(&Utc));
+    }
+    if let Ok(t) = DateTime::parse_from_str(&normalized, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(t.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(&normalized, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .and_then(local_to_utc)
+}
+
+fn local_to_utc(naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// A `since`/`until` argument: a timestamp, a date (midnight local time),
+/// or an age such as `30m`, `2h`, `1d` counted back from `now`.
+pub fn parse_time_arg(text: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let text = text.trim();
+    let invalid = || {
+        format!(
+            "Invalid time '{}': use a timestamp (2024-03-01T10:00:00Z), a date \
+             (2024-03-01) or an age such as 30m, 2h, 1d",
+            text
+        )
+    };
+    if let Some(t) = parse_timestamp(text) {
+        return Ok(t);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return date
+            .and_hms_opt(0, 0, 0)
+            .and_then(local_to_utc)
+            .ok_or_else(invalid);
+    }
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let amount: i64 = text[..split].parse().map_err(|_| invalid())?;
+    let age = match &text[split..] {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => return Err(invalid()),
+    };
+    age.and_then(|age| now.checked_sub_signed(age))
+        .ok_or_else(invalid)
+}
+
+static NUMBERS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b|\b0x[0-9a-f]+\b|\b[0-9a-f]{12,}\b|\d+",
+    )
+    .unwrap()
+});
+
+/// `message` with ids and numbers replaced, so entries that differ only
+/// in a duration, pid or request id count as one.
+pub fn message_template(message: &str) -> String {
+    let template = NUMBERS.replace_all(message.trim(), "<n>");
+    truncate_chars(&template, MAX_LINE_CHARS)
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max).collect();
+    cut.push('…');
+    cut
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// What to keep.
+struct Filter {
+    min_level: Option<Level>,
+    pattern: Option<Regex>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+}
+
+impl Filter {
+    fn matches(&self, line: &str, level: Option<Level>, timestamp: Option<DateTime<Utc>>) -> bool {
+        if let Some(min) = self.min_level
+            && level.is_none_or(|level| level < min)
+        {
+            return false;
+        }
+        if let Some(since) = self.since
+            && timestamp.is_none_or(|t| t < since)
+        {
+            return false;
+        }
+        if let Some(until) = self.until
+            && timestamp.is_none_or(|t| t > until)
+        {
+            return false;
+        }
+        self.pattern.as_ref().is_none_or(|re| re.is_match(line))
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut parts = Vec::new();
+        if let Some(level) = self.min_level {
+            parts.push(format!("level ≥ {}", level));
+        }
+        if let Some(re) = &self.pattern {
+            parts.push(format!("pattern /{}/", re.as_str()));
+        }
+        if let Some(since) = self.since {
+            parts.push(format!("since {}", since.to_rfc3339()));
+        }
+        if let Some(until) = self.until {
+            parts.push(format!("until {}", until.to_rfc3339()));
+        }
+        parts
+    }
+}
+
+/// Everything gathered in one pass over the file.
+#[derive(Default)]
+struct Scan {
+    lines: usize,
+    /// Stopped at [`MAX_SCAN_LINES`] before the end of the file.
+    truncated: bool,
+    json: usize,
+    syslog: usize,
+    plain: usize,
+    /// Entries per level; index 5 is entries without one.
+    levels: [usize; 6],
+    first: Option<DateTime<Utc>>,
+    last: Option<DateTime<Utc>>,
+    matched: usize,
+    /// The latest matching lines, with their line numbers.
+    shown: VecDeque<(usize, String)>,
+    /// Matching entries per (level, message template).
+    messages: HashMap<(Option<Level>, String), usize>,
+}
+
+/// Execute the `parse_log` tool.
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_parse_log(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let path_str = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: path")?;
+    let path = resolve_path(workspace_dir, path_str);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted parse_log access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+
+    let now = Utc::now();
+    let time_arg = |key: &str| {
+        args.get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| parse_time_arg(s, now))
+            .transpose()
+    };
+    let filter = Filter {
+        min_level: match args.get("level").and_then(|v| v.as_str()) {
+            Some(word) => Some(Level::parse(word).ok_or_else(|| {
+                format!(
+                    "Unknown level '{}'. Use: error, warn, info, debug, trace",
+                    word
+                )
+            })?),
+            None => None,
+        },
+        pattern: args
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .map(|p| Regex::new(p).map_err(|e| format!("Invalid pattern: {}", e)))
+            .transpose()?,
+        since: time_arg("since")?,
+        until: time_arg("until")?,
+    };
+    let tail = args
+        .get("tail")
+        .and_then(|v| v.as_u64())
+        .map(|n| (n as usize).clamp(1, MAX_SCAN_LINES));
+    let limit = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|n| (n as usize).min(MAX_LIMIT))
+        .unwrap_or(DEFAULT_LIMIT);
+    let top = args
+        .get("top")
+        .and_then(|v| v.as_u64())
+        .map(|n| (n as usize).min(MAX_TOP))
+        .unwrap_or(DEFAULT_TOP);
+
+    let (mut file, _) = open_file_read_safe(&path)
+        .map_err(|e| format!("Failed to open file '{}': {}", path.display(), e))?;
+    let read_err = |e: std::io::Error| format!("Failed to read file '{}': {}", path.display(), e);
+    if let Some(lines) = tail {
+        let start = tail_offset(&mut file, lines).map_err(read_err)?;
+        file.seek(SeekFrom::Start(start)).map_err(read_err)?;
+    }
+    let scan = scan_lines(BufReader::new(file), &filter, limit).map_err(read_err)?;
+    debug!(
+        path = %path.display(),
+        lines = scan.lines,
+        matched = scan.matched,
+        "Scanned log"
+    );
+    Ok(render(path_str, &scan, &filter, tail, top))
+}
+
+/// Byte offset where the last `lines` lines of `file` begin.
+fn tail_offset(file: &mut File, lines: usize) -> std::io::Result<u64> {
+    let len = file.seek(SeekFrom::End(0))?;
+    let mut buf = vec![0u8; TAIL_BLOCK as usize];
+    let mut pos = len;
+    let mut seen = 0;
+    while pos > 0 {
+        let start = pos.saturating_sub(TAIL_BLOCK);
+        let chunk = &mut buf[..(pos - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+        for (i, &byte) in chunk.iter().enumerate().rev() {
+            let after = start + i as u64 + 1;
+            // The newline ending the last line doesn't start another.
+            if byte == b'\n' && after < len {
+                seen += 1;
+                if seen == lines {
+                    return Ok(after);
+                }
+            }
+        }
+        pos = start;
+    }
+    Ok(0)
+}
+
+fn scan_lines(mut reader: impl BufRead, filter: &Filter, limit: usize) -> std::io::Result<Scan> {
+    let mut scan = Scan::default();
+    let mut buf = Vec::new();
+    // Level and time of the last plain-text entry, which continuation
+    // lines (stack traces, wrapped messages) belong to.
+    let mut context: Option<(Option<Level>, Option<DateTime<Utc>>)> = None;
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        if scan.lines == MAX_SCAN_LINES {
+            scan.truncated = true;
+            break;
+        }
+        scan.lines += 1;
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches(['\n', '\r']);
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = parse_line(line);
+        let continuation = record.format == LogFormat::Plain
+            && record.timestamp.is_none()
+            && record.level.is_none()
+            && context.is_some();
+        let (level, timestamp) = match (continuation, context) {
+            (true, Some(inherited)) => inherited,
+            _ => {
+                match record.format {
+                    LogFormat::Json => scan.json += 1,
+                    LogFormat::Syslog => scan.syslog += 1,
+                    LogFormat::Plain => scan.plain += 1,
+                }
+                scan.levels[record.level.map_or(5, |l| l as usize)] += 1;
+                if let Some(t) = record.timestamp {
+                    scan.first = Some(scan.first.map_or(t, |f| f.min(t)));
+                    scan.last = Some(scan.last.map_or(t, |l| l.max(t)));
+                }
+                context =
+                    (record.format == LogFormat::Plain).then_some((record.level, record.timestamp));
+                (record.level, record.timestamp)
+            }
+        };
+
+        if !filter.matches(line, level, timestamp) {
+            continue;
+        }
+        scan.matched += 1;
+        if !continuation {
+            let key = (level, message_template(&record.message));
+            if let Some(count) = scan.messages.get_mut(&key) {
+                *count += 1;
+            } else if scan.messages.len() < MAX_DISTINCT_MESSAGES {
+                scan.messages.insert(key, 1);
+            }
+        }
+        if limit > 0 {
+            if scan.shown.len() == limit {
+                scan.shown.pop_front();
+            }
+            scan.shown
+                .push_back((scan.lines, truncate_chars(line, MAX_LINE_CHARS)));
+        }
+    }
+    Ok(scan)
+}
+
+fn render(path: &str, scan: &Scan, filter: &Filter, tail: Option<usize>, top: usize) -> String {
+    let mut out = format!(
+        "{}: {} lines{} (json {}, syslog {}, plain {})\n",
+        path,
+        scan.lines,
+        if tail.is_some() { " from the end" } else { "" },
+        scan.json,
+        scan.syslog,
+        scan.plain
+    );
+    if scan.truncated {
+        out.push_str(&format!(
+            "Stopped after {} lines; the file goes on. Use tail to read the end instead, \
+             or since/until to narrow the window.\n",
+            MAX_SCAN_LINES
+        ));
+    }
+    if let (Some(first), Some(last)) = (scan.first, scan.last) {
+        out.push_str(&format!(
+            "Time: {} → {}\n",
+            first.to_rfc3339(),
+            last.to_rfc3339()
+        ));
+    }
+    let levels: Vec<String> = [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ]
+    .iter()
+    .map(|&l| (l.as_str(), scan.levels[l as usize]))
+    .chain(std::iter::once(("none", scan.levels[5])))
+    .filter(|&(_, n)| n > 0)
+    .map(|(name, n)| format!("{} {}", name, n))
+    .collect();
+    if !levels.is_empty() {
+        out.push_str(&format!("Levels: {}\n", levels.join(", ")));
+    }
+    let described = filter.describe();
+    out.push_str(&format!(
+        "Matched: {} of {} lines",
+        scan.matched, scan.lines
+    ));
+    if !described.is_empty() {
+        out.push_str(&format!(" ({})", described.join(", ")));
+    }
+    out.push('\n');
+
+    let mut messages: Vec<(&(Option<Level>, String), &usize)> = scan.messages.iter().collect();
+    messages.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let repeated: Vec<_> = messages
+        .into_iter()
+        .filter(|&(_, &n)| n > 1)
+        .take(top)
+        .collect();
+    if !repeated.is_empty() {
+        out.push_str("\nTop messages:\n");
+        for ((level, template), count) in repeated {
+            let level = level.map_or("-", Level::as_str);
+            out.push_str(&format!("  {:>6}× {:<5} {}\n", count, level, template));
+        }
+    }
+
+    if !scan.shown.is_empty() {
+        if scan.shown.len() < scan.matched {
+            out.push_str(&format!(
+                "\nLast {} of {} matching lines:\n",
+                scan.shown.len(),
+                scan.matched
+            ));
+        } else {
+            out.push_str("\nMatching lines:\n");
+        }
+        for (number, line) in &scan.shown {
+            // Line numbers only mean something when counted from the top.
+            if tail.is_some() {
+                out.push_str(&format!("  {}\n", line));
+            } else {
+                out.push_str(&format!("  L{}: {}\n", number, line));
+            }
+        }
+    }
+    out.trim_end().to_string()
+}
+
+pub fn parse_log_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "path".into(),
+            description: "Log file to read.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "level".into(),
+            description: "Keep entries at this level or above: error, warn, info, debug or \
+                          trace. Entries without a level are dropped."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "pattern".into(),
+            description: "Regex the raw line must match; prefix with (?i) to ignore case.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "since".into(),
+            description: "Keep entries at or after this time: a timestamp \
+                          (2024-03-01T10:00:00Z), a date, or an age such as 30m, 2h, 1d."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "until".into(),
+            description: "Keep entries at or before this time, in the same forms as since.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "tail".into(),
+            description: "Read only the last N lines of the file. Use it for big logs, \
+                          where a scan from the top stops after 1,000,000 lines."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "limit".into(),
+            description: "Matching lines to return, latest last (default 50, max 500; \
+                          0 for the summary only)."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "top".into(),
+            description: "Most repeated matching messages to list, with ids and numbers \
+                          folded together (default 10, max 50)."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
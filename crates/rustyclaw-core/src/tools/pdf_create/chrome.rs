@@ -0,0 +1,74 @@
+//! HTML to PDF through headless Chrome (`Page.printToPDF`).  Compiled only
+//! with the `browser` feature.
+//!
+//! The browser tool keeps a headed window for interactive use, and Chrome
+//! only prints from headless mode, so each call launches its own headless
+//! instance and closes it afterwards.
+
+use chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams;
+use chromiumoxide::{Browser, BrowserConfig};
+use futures_util::StreamExt;
+use tracing::debug;
+
+use super::layout::PageSetup;
+
+/// Render `html` to PDF bytes with the page geometry in `setup`.
+pub(super) async fn print(html: &str, setup: &PageSetup) -> Result<Vec<u8>, String> {
+    let config = BrowserConfig::builder()
+        .build()
+        .map_err(|e| format!("Failed to build browser config: {}", e))?;
+    let (mut browser, mut handler) = Browser::launch(config)
+        .await
+        .map_err(|e| format!("Failed to launch browser: {}", e))?;
+    let events = tokio::spawn(async move { while handler.next().await.is_some() {} });
+
+    let inches = |points: f32| f64::from(points) / 72.0;
+    let params = PrintToPdfParams {
+        paper_width: Some(inches(setup.width)),
+        paper_height: Some(inches(setup.height)),
+        margin_top: Some(inches(setup.margin)),
+        margin_bottom: Some(inches(setup.margin)),
+        margin_left: Some(inches(setup.margin)),
+        margin_right: Some(inches(setup.margin)),
+        print_background: Some(true),
+        display_header_footer: Some(setup.header.is_some() || setup.footer.is_some()),
+        header_template: Some(template(setup.header.as_deref())),
+        footer_template: Some(template(setup.footer.as_deref())),
+        ..Default::default()
+    };
+
+    let result = async {
+        let page = browser
+            .new_page("about:blank")
+            .await
+            .map_err(|e| format!("Failed to open page: {}", e))?;
+        page.set_content(html)
+            .await
+            .map_err(|e| format!("Failed to load content: {}", e))?;
+        page.pdf(params)
+            .await
+            .map_err(|e| format!("Failed to print PDF: {}", e))
+    }
+    .await;
+
+    if let Err(e) = browser.close().await {
+        debug!(error = %e, "Failed to close print browser");
+    }
+    events.abort();
+    result
+}
+
+/// A Chrome header/footer template showing `text` centred, with `{page}`
+/// and `{pages}` mapped to Chrome's page number fields.
+fn template(text: Option<&str>) -> String {
+    let Some(text) = text else {
+        return "<span></span>".to_string();
+    };
+    let text = super::escape_html(text)
+        .replace("{page}", "<span class=\"pageNumber\"></span>")
+        .replace("{pages}", "<span class=\"totalPages\"></span>");
+    format!(
+        "<div style=\"font-size:9px;color:#666;width:100%;text-align:center;\">{}</div>",
+        text
+    )
+}
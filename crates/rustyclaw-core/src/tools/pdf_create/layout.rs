@@ -0,0 +1,526 @@
+//! Markdown to laid-out pages for the built-in PDF engine.
+//!
+//! Markdown is reduced to a flat list of [`Block`]s (paragraphs, headings,
+//! list items, code) which are then word-wrapped onto pages with Helvetica
+//! metrics.  Tables come out as rows of cells separated by `|`; images are
+//! replaced by their alt text.
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+
+use super::writer::{Canvas, Font};
+
+const BODY_SIZE: f32 = 11.0;
+const CODE_SIZE: f32 = 9.5;
+const MARGIN_TEXT_SIZE: f32 = 9.0;
+const LEADING: f32 = 1.4;
+/// Indent per list level or block quote, in points.
+const INDENT: f32 = 18.0;
+
+/// Page geometry and running header/footer, all in points.
+#[derive(Debug, Clone)]
+pub(super) struct PageSetup {
+    pub width: f32,
+    pub height: f32,
+    pub margin: f32,
+    /// Text centred above the body; `{page}` and `{pages}` are replaced.
+    pub header: Option<String>,
+    /// Text centred below the body, with the same placeholders.
+    pub footer: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Paragraph,
+    Heading(u8),
+    Item(String),
+    Code,
+    Rule,
+}
+
+#[derive(Debug)]
+struct Block {
+    kind: Kind,
+    indent: f32,
+    runs: Vec<(Font, String)>,
+}
+
+/// What an open tag was, so its end event can be handled without matching
+/// on every end variant.
+enum Open {
+    Block,
+    Quote,
+    List,
+    Bold,
+    Italic,
+    TableHead,
+    Other,
+}
+
+fn font(bold: bool, italic: bool) -> Font {
+    match (bold, italic) {
+        (false, false) => Font::Regular,
+        (true, false) => Font::Bold,
+        (false, true) => Font::Italic,
+        (true, true) => Font::BoldItalic,
+    }
+}
+
+fn parse(markdown: &str) -> Vec<Block> {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut blocks = Vec::new();
+    let mut current: Option<Block> = None;
+    let mut stack = Vec::new();
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    let (mut bold, mut italic, mut quotes) = (0u32, 0u32, 0u32);
+
+    fn flush(current: &mut Option<Block>, blocks: &mut Vec<Block>) {
+        if let Some(block) = current.take()
+            && (!block.runs.is_empty() || block.kind == Kind::Rule)
+        {
+            blocks.push(block);
+        }
+    }
+
+    for event in Parser::new_ext(markdown, options) {
+        let indent = (quotes as usize + lists.len()) as f32 * INDENT;
+        let new_block = |kind| {
+            Some(Block {
+                kind,
+                indent,
+                runs: Vec::new(),
+            })
+        };
+        match event {
+            Event::Start(tag) => {
+                let open = match tag {
+                    Tag::Paragraph => {
+                        // The first paragraph of a loose list item fills the
+                        // item itself.
+                        let empty_item = matches!(
+                            &current,
+                            Some(b) if b.runs.is_empty() && matches!(b.kind, Kind::Item(_))
+                        );
+                        if !empty_item {
+                            flush(&mut current, &mut blocks);
+                            current = new_block(Kind::Paragraph);
+                        }
+                        Open::Block
+                    }
+                    Tag::Heading { level, .. } => {
+                        flush(&mut current, &mut blocks);
+                        current = new_block(Kind::Heading(level as u8));
+                        Open::Block
+                    }
+                    Tag::CodeBlock(_) => {
+                        flush(&mut current, &mut blocks);
+                        current = new_block(Kind::Code);
+                        Open::Block
+                    }
+                    Tag::Item => {
+                        flush(&mut current, &mut blocks);
+                        let marker = match lists.last_mut() {
+                            Some(Some(n)) => {
+                                *n += 1;
+                                format!("{}.", *n - 1)
+                            }
+                            _ => "•".to_string(),
+                        };
+                        current = new_block(Kind::Item(marker));
+                        Open::Block
+                    }
+                    Tag::List(start) => {
+                        flush(&mut current, &mut blocks);
+                        lists.push(start);
+                        Open::List
+                    }
+                    Tag::BlockQuote(_) => {
+                        flush(&mut current, &mut blocks);
+                        quotes += 1;
+                        Open::Quote
+                    }
+                    Tag::TableHead => {
+                        flush(&mut current, &mut blocks);
+                        current = new_block(Kind::Paragraph);
+                        bold += 1;
+                        Open::TableHead
+                    }
+                    Tag::TableRow => {
+                        flush(&mut current, &mut blocks);
+                        current = new_block(Kind::Paragraph);
+                        Open::Block
+                    }
+                    Tag::TableCell => {
+                        if let Some(b) = current.as_mut()
+                            && !b.runs.is_empty()
+                        {
+                            b.runs.push((Font::Regular, " | ".to_string()));
+                        }
+                        Open::Other
+                    }
+                    Tag::Strong => {
+                        bold += 1;
+                        Open::Bold
+                    }
+                    Tag::Emphasis => {
+                        italic += 1;
+                        Open::Italic
+                    }
+                    _ => Open::Other,
+                };
+                stack.push(open);
+            }
+            Event::End(_) => match stack.pop() {
+                Some(Open::Block) => flush(&mut current, &mut blocks),
+                Some(Open::Quote) => {
+                    flush(&mut current, &mut blocks);
+                    quotes -= 1;
+                }
+                Some(Open::List) => {
+                    flush(&mut current, &mut blocks);
+                    lists.pop();
+                }
+                Some(Open::Bold) => bold -= 1,
+                Some(Open::Italic) => italic -= 1,
+                Some(Open::TableHead) => {
+                    flush(&mut current, &mut blocks);
+                    bold -= 1;
+                }
+                Some(Open::Other) | None => {}
+            },
+            Event::Text(text) => {
+                let block = current.get_or_insert_with(|| Block {
+                    kind: Kind::Paragraph,
+                    indent,
+                    runs: Vec::new(),
+                });
+                let f = if block.kind == Kind::Code {
+                    Font::Mono
+                } else {
+                    font(bold > 0, italic > 0 || quotes > 0)
+                };
+                block.runs.push((f, text.into_string()));
+            }
+            Event::Code(text) => {
+                current
+                    .get_or_insert_with(|| Block {
+                        kind: Kind::Paragraph,
+                        indent,
+                        runs: Vec::new(),
+                    })
+                    .runs
+                    .push((Font::Mono, text.into_string()));
+            }
+            Event::SoftBreak => {
+                if let Some(b) = current.as_mut() {
+                    b.runs.push((Font::Regular, " ".to_string()));
+                }
+            }
+            Event::HardBreak => {
+                if let Some(b) = current.as_mut() {
+                    b.runs.push((Font::Regular, "\n".to_string()));
+                }
+            }
+            Event::TaskListMarker(done) => {
+                if let Some(b) = current.as_mut() {
+                    let mark = if done { "[x] " } else { "[ ] " };
+                    b.runs.push((Font::Mono, mark.to_string()));
+                }
+            }
+            Event::Rule => {
+                flush(&mut current, &mut blocks);
+                blocks.push(Block {
+                    kind: Kind::Rule,
+                    indent,
+                    runs: Vec::new(),
+                });
+            }
+            _ => {}
+        }
+    }
+    flush(&mut current, &mut blocks);
+    blocks
+}
+
+/// A piece of a line: words glued together with no space between them,
+/// such as `**bold**,` which is two fonts but one word.
+#[derive(Debug, Default)]
+struct Word {
+    parts: Vec<(Font, String)>,
+    width: f32,
+}
+
+enum Token {
+    Word(Word),
+    Break,
+}
+
+/// Split runs into words, keeping track of where spaces were.
+fn tokenize(runs: &[(Font, String)], size: f32) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = Word::default();
+    let end_word = |word: &mut Word, tokens: &mut Vec<Token>| {
+        if !word.parts.is_empty() {
+            tokens.push(Token::Word(std::mem::take(word)));
+        }
+    };
+    for (font, text) in runs {
+        if text == "\n" {
+            end_word(&mut word, &mut tokens);
+            tokens.push(Token::Break);
+            continue;
+        }
+        for (i, piece) in text.split(char::is_whitespace).enumerate() {
+            if i > 0 {
+                end_word(&mut word, &mut tokens);
+            }
+            if !piece.is_empty() {
+                word.width += font.width(piece, size);
+                word.parts.push((*font, piece.to_string()));
+            }
+        }
+    }
+    end_word(&mut word, &mut tokens);
+    tokens
+}
+
+/// Break a word wider than `max` into pieces that fit.
+fn split_word(word: Word, max: f32, size: f32) -> Vec<Word> {
+    let mut pieces = Vec::new();
+    let mut piece = Word::default();
+    for (font, text) in word.parts {
+        for c in text.chars() {
+            let w = font.width(c.encode_utf8(&mut [0; 4]), size);
+            if piece.width + w > max && !piece.parts.is_empty() {
+                pieces.push(std::mem::take(&mut piece));
+            }
+            match piece.parts.last_mut() {
+                Some((f, s)) if *f == font => s.push(c),
+                _ => piece.parts.push((font, c.to_string())),
+            }
+            piece.width += w;
+        }
+    }
+    if !piece.parts.is_empty() {
+        pieces.push(piece);
+    }
+    pieces
+}
+
+struct Layout<'a> {
+    setup: &'a PageSetup,
+    pages: Vec<Canvas>,
+    page: Canvas,
+    /// Top of the next line, from the bottom of the page.
+    y: f32,
+}
+
+impl Layout<'_> {
+    fn top(&self) -> f32 {
+        self.setup.height - self.setup.margin
+    }
+
+    /// Start a new page unless `height` still fits on this one.
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < self.setup.margin && self.y < self.top() {
+            self.pages.push(std::mem::take(&mut self.page));
+            self.y = self.top();
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        if self.y < self.top() {
+            self.y -= height;
+        }
+    }
+
+    fn line(&mut self, x: f32, size: f32, words: &[Word]) {
+        let leading = size * LEADING;
+        self.reserve(leading);
+        let baseline = self.y - size;
+        // Consecutive text in one font goes out as a single string.
+        let mut x = x;
+        let mut run: Option<(Font, String)> = None;
+        for (i, word) in words.iter().enumerate() {
+            for (j, (font, text)) in word.parts.iter().enumerate() {
+                let sep = if i > 0 && j == 0 { " " } else { "" };
+                match &mut run {
+                    Some((f, s)) if f == font => {
+                        s.push_str(sep);
+                        s.push_str(text);
+                    }
+                    _ => {
+                        if let Some((f, s)) = run.take() {
+                            self.page.text(x, baseline, f, size, 0.0, &s);
+                            x += f.width(&s, size);
+                        }
+                        if !sep.is_empty() {
+                            x += Font::Regular.width(sep, size);
+                        }
+                        run = Some((*font, text.clone()));
+                    }
+                }
+            }
+        }
+        if let Some((f, s)) = run {
+            self.page.text(x, baseline, f, size, 0.0, &s);
+        }
+        self.y -= leading;
+    }
+
+    fn text_block(&mut self, block: &Block, size: f32, marker: Option<&str>) {
+        let x = self.setup.margin + block.indent;
+        let max = (self.setup.width - self.setup.margin - x).max(size * 4.0);
+        let space = Font::Regular.width(" ", size);
+        let mut line: Vec<Word> = Vec::new();
+        let mut width = 0.0;
+        let mut first = true;
+        let emit = |layout: &mut Self, line: &mut Vec<Word>, first: &mut bool| {
+            if *first && let Some(marker) = marker {
+                layout.reserve(size * LEADING);
+                let w = Font::Regular.width(marker, size);
+                layout.page.text(
+                    x - w - 5.0,
+                    layout.y - size,
+                    Font::Regular,
+                    size,
+                    0.0,
+                    marker,
+                );
+            }
+            layout.line(x, size, line);
+            line.clear();
+            *first = false;
+        };
+
+        for token in tokenize(&block.runs, size) {
+            let word = match token {
+                Token::Break => {
+                    emit(self, &mut line, &mut first);
+                    width = 0.0;
+                    continue;
+                }
+                Token::Word(word) => word,
+            };
+            let pieces = if word.width > max {
+                split_word(word, max, size)
+            } else {
+                vec![word]
+            };
+            for word in pieces {
+                if !line.is_empty() && width + space + word.width > max {
+                    emit(self, &mut line, &mut first);
+                    width = 0.0;
+                }
+                if !line.is_empty() {
+                    width += space;
+                }
+                width += word.width;
+                line.push(word);
+            }
+        }
+        if !line.is_empty() || first {
+            emit(self, &mut line, &mut first);
+        }
+    }
+
+    fn code_block(&mut self, block: &Block) {
+        let x = self.setup.margin + block.indent + 8.0;
+        let max_chars = ((self.setup.width - self.setup.margin - x) / (CODE_SIZE * 0.6))
+            .floor()
+            .max(8.0) as usize;
+        let text: String = block.runs.iter().map(|(_, t)| t.as_str()).collect();
+        for line in text.trim_end_matches('\n').split('\n') {
+            let chars: Vec<char> = line.replace('\t', "    ").chars().collect();
+            if chars.is_empty() {
+                self.reserve(CODE_SIZE * LEADING);
+                self.y -= CODE_SIZE * LEADING;
+            }
+            for chunk in chars.chunks(max_chars) {
+                let chunk: String = chunk.iter().collect();
+                self.reserve(CODE_SIZE * LEADING);
+                self.page
+                    .text(x, self.y - CODE_SIZE, Font::Mono, CODE_SIZE, 0.0, &chunk);
+                self.y -= CODE_SIZE * LEADING;
+            }
+        }
+    }
+}
+
+/// Lay `markdown` out onto pages.
+pub(super) fn render(markdown: &str, setup: &PageSetup) -> Vec<Canvas> {
+    let mut layout = Layout {
+        setup,
+        pages: Vec::new(),
+        page: Canvas::default(),
+        y: setup.height - setup.margin,
+    };
+
+    for block in parse(markdown) {
+        match &block.kind {
+            Kind::Heading(level) => {
+                let size = match level {
+                    1 => 20.0,
+                    2 => 16.0,
+                    3 => 13.5,
+                    _ => 12.0,
+                };
+                layout.gap(size * 0.6);
+                // Keep a heading with at least two lines of what follows.
+                layout.reserve(size * LEADING + BODY_SIZE * LEADING * 2.0);
+                let runs = block
+                    .runs
+                    .iter()
+                    .map(|(f, t)| (font(true, *f == Font::Italic), t.clone()))
+                    .collect();
+                let heading = Block {
+                    kind: Kind::Paragraph,
+                    indent: block.indent,
+                    runs,
+                };
+                layout.text_block(&heading, size, None);
+                layout.y -= size * 0.3;
+            }
+            Kind::Paragraph => {
+                layout.text_block(&block, BODY_SIZE, None);
+                layout.y -= BODY_SIZE * 0.6;
+            }
+            Kind::Item(marker) => {
+                layout.text_block(&block, BODY_SIZE, Some(marker));
+                layout.y -= BODY_SIZE * 0.2;
+            }
+            Kind::Code => {
+                layout.code_block(&block);
+                layout.y -= BODY_SIZE * 0.6;
+            }
+            Kind::Rule => {
+                layout.reserve(12.0);
+                let y = layout.y - 6.0;
+                let x = setup.margin + block.indent;
+                layout.page.line(x, y, setup.width - setup.margin, y, 0.6);
+                layout.y -= 12.0;
+            }
+        }
+    }
+    layout.pages.push(layout.page);
+
+    let total = layout.pages.len();
+    for (i, page) in layout.pages.iter_mut().enumerate() {
+        let fill = |text: &str| {
+            text.replace("{page}", &(i + 1).to_string())
+                .replace("{pages}", &total.to_string())
+        };
+        let mut centred = |text: &str, baseline: f32| {
+            let w = Font::Regular.width(text, MARGIN_TEXT_SIZE);
+            let x = ((setup.width - w) / 2.0).max(0.0);
+            page.text(x, baseline, Font::Regular, MARGIN_TEXT_SIZE, 0.4, text);
+        };
+        if let Some(header) = &setup.header {
+            centred(&fill(header), setup.height - setup.margin / 2.0);
+        }
+        if let Some(footer) = &setup.footer {
+            centred(&fill(footer), setup.margin / 2.0 - MARGIN_TEXT_SIZE / 2.0);
+        }
+    }
+    layout.pages
+}
@@ -0,0 +1,376 @@
+//! `pdf_create` tool: render markdown or HTML to a PDF file.
+//!
+//! Two engines:
+//!
+//! - `browser` prints through headless Chrome, so HTML and CSS render as
+//!   they would in a browser.  Needs the `browser` feature and a Chrome or
+//!   Chromium install.
+//! - `builtin` lays the text out itself with the standard PDF fonts
+//!   (see [`layout`] and [`writer`]).  It handles headings, paragraphs with
+//!   bold and italic, lists, quotes, code blocks, rules and simple tables;
+//!   HTML input is converted to markdown first.  No dependencies, so it
+//!   always works.
+//!
+//! `engine = "auto"` (the default) tries the browser and falls back to the
+//! built-in engine.  The PDF is written inside the workspace (subject to the
+//! sandbox) and returned as a [`MediaRef`].
+
+#[cfg(feature = "browser")]
+mod chrome;
+mod layout;
+mod writer;
+
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+use crate::gateway::MediaRef;
+use layout::PageSetup;
+
+const POINTS_PER_MM: f32 = 72.0 / 25.4;
+
+const DEFAULT_MARGIN_MM: f64 = 20.0;
+
+/// Largest markdown or HTML input accepted.
+const MAX_INPUT_BYTES: usize = 2 * 1024 * 1024;
+
+/// Page sizes by name, portrait, in millimetres.
+const PAGE_SIZES: &[(&str, f32, f32)] = &[
+    ("a3", 297.0, 420.0),
+    ("a4", 210.0, 297.0),
+    ("a5", 148.0, 210.0),
+    ("letter", 215.9, 279.4),
+    ("legal", 215.9, 355.6),
+];
+
+enum Source<'a> {
+    Markdown(&'a str),
+    Html(&'a str),
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `pdf_create` tool.
+#[instrument(skip(args, workspace_dir), fields(engine))]
+pub async fn exec_pdf_create_async(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let source = match (
+        args.get("markdown").and_then(|v| v.as_str()),
+        args.get("html").and_then(|v| v.as_str()),
+    ) {
+        (Some(md), None) => Source::Markdown(md),
+        (None, Some(html)) => Source::Html(html),
+        (Some(_), Some(_)) => return Err("Pass either markdown or html, not both".to_string()),
+        (None, None) => return Err("Missing required parameter: markdown or html".to_string()),
+    };
+    let input_len = match source {
+        Source::Markdown(s) | Source::Html(s) => s.len(),
+    };
+    if input_len > MAX_INPUT_BYTES {
+        return Err(format!(
+            "Input is {} bytes; the limit is {}",
+            input_len, MAX_INPUT_BYTES
+        ));
+    }
+
+    let output = args
+        .get("output_path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: output_path".to_string())?;
+    let path = checked_path(workspace_dir, output)?;
+    let setup = page_setup(args)?;
+    let title = args.get("title").and_then(|v| v.as_str());
+
+    let engine = args
+        .get("engine")
+        .and_then(|v| v.as_str())
+        .unwrap_or("auto");
+    tracing::Span::current().record("engine", engine);
+    let (pdf, used, pages) = match engine {
+        "builtin" => {
+            let (pdf, pages) = render_builtin(&source, &setup, title);
+            (pdf, "builtin", Some(pages))
+        }
+        "browser" => (
+            print_browser(&source, &setup, title).await?,
+            "browser",
+            None,
+        ),
+        "auto" => match print_browser(&source, &setup, title).await {
+            Ok(pdf) => (pdf, "browser", None),
+            Err(e) => {
+                debug!(error = %e, "Browser engine unavailable, using built-in");
+                let (pdf, pages) = render_builtin(&source, &setup, title);
+                (pdf, "builtin", Some(pages))
+            }
+        },
+        other => {
+            return Err(format!(
+                "Unknown engine: '{}'. Use: auto, browser, builtin",
+                other
+            ));
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, &pdf)
+        .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+    debug!(path = %path.display(), bytes = pdf.len(), engine = used, "Wrote PDF");
+
+    let mut media = MediaRef::new("application/pdf".to_string());
+    media.filename = path.file_name().map(|n| n.to_string_lossy().into_owned());
+    media.size = Some(pdf.len());
+    media.local_path = Some(path.display().to_string());
+    let mut result = json!({
+        "path": path.display().to_string(),
+        "engine": used,
+        "bytes": pdf.len(),
+        "media": media,
+    });
+    if let Some(pages) = pages {
+        result["pages"] = json!(pages);
+    }
+    serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+}
+
+/// Sync stub — execution goes through the async path.
+pub fn exec_pdf_create_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("pdf_create requires async execution".into())
+}
+
+// ── Engines ─────────────────────────────────────────────────────────────────
+
+/// PDF bytes and page count from the built-in engine.
+fn render_builtin(source: &Source<'_>, setup: &PageSetup, title: Option<&str>) -> (Vec<u8>, usize) {
+    let markdown = match source {
+        Source::Markdown(md) => md.to_string(),
+        Source::Html(html) => html_to_markdown(html),
+    };
+    let pages = layout::render(&markdown, setup);
+    let pdf = writer::write_pdf(&pages, setup.width, setup.height, title);
+    (pdf, pages.len())
+}
+
+#[cfg(feature = "browser")]
+async fn print_browser(
+    source: &Source<'_>,
+    setup: &PageSetup,
+    title: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    let html = match source {
+        Source::Html(html) if html.to_ascii_lowercase().contains("<html") => html.to_string(),
+        Source::Html(html) => html_document(html, title),
+        Source::Markdown(md) => {
+            let mut body = String::new();
+            let options = pulldown_cmark::Options::ENABLE_TABLES
+                | pulldown_cmark::Options::ENABLE_STRIKETHROUGH
+                | pulldown_cmark::Options::ENABLE_TASKLISTS;
+            pulldown_cmark::html::push_html(
+                &mut body,
+                pulldown_cmark::Parser::new_ext(md, options),
+            );
+            html_document(&body, title)
+        }
+    };
+    chrome::print(&html, setup).await
+}
+
+#[cfg(not(feature = "browser"))]
+async fn print_browser(
+    _source: &Source<'_>,
+    _setup: &PageSetup,
+    _title: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    Err("The browser engine requires the 'browser' feature".to_string())
+}
+
+/// Wrap an HTML fragment in a page with plain print styling.
+#[cfg(feature = "browser")]
+fn html_document(body: &str, title: Option<&str>) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title><style>\
+         body{{font-family:Helvetica,Arial,sans-serif;font-size:11pt;line-height:1.4}}\
+         pre,code{{font-family:Menlo,Consolas,monospace;font-size:9.5pt}}\
+         pre{{background:#f5f5f5;padding:8px;white-space:pre-wrap}}\
+         table{{border-collapse:collapse}}th,td{{border:1px solid #ccc;padding:4px 8px}}\
+         blockquote{{color:#555;border-left:3px solid #ccc;margin-left:0;padding-left:12px}}\
+         </style></head><body>{}</body></html>",
+        escape_html(title.unwrap_or_default()),
+        body
+    )
+}
+
+#[cfg(feature = "web-tools")]
+fn html_to_markdown(html: &str) -> String {
+    html2md::parse_html(html)
+}
+
+/// Without html2md, keep the text and drop the markup.
+#[cfg(not(feature = "web-tools"))]
+fn html_to_markdown(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn checked_path(workspace_dir: &Path, p: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, p);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted pdf_create write to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+fn page_setup(args: &Value) -> Result<PageSetup, String> {
+    let size = args
+        .get("page_size")
+        .and_then(|v| v.as_str())
+        .unwrap_or("a4");
+    let (_, w, h) = PAGE_SIZES
+        .iter()
+        .find(|(name, _, _)| name.eq_ignore_ascii_case(size))
+        .copied()
+        .ok_or_else(|| {
+            let names: Vec<&str> = PAGE_SIZES.iter().map(|(n, _, _)| *n).collect();
+            format!("Unknown page_size: '{}'. Use: {}", size, names.join(", "))
+        })?;
+    let landscape = args
+        .get("landscape")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let (w, h) = if landscape { (h, w) } else { (w, h) };
+
+    let margin_mm = args
+        .get("margin_mm")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(DEFAULT_MARGIN_MM);
+    if !(0.0..=f64::from(w.min(h)) / 3.0).contains(&margin_mm) {
+        return Err(format!(
+            "margin_mm must be between 0 and {:.0} for this page size",
+            w.min(h) / 3.0
+        ));
+    }
+
+    let text = |key: &str| {
+        args.get(key)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    Ok(PageSetup {
+        width: w * POINTS_PER_MM,
+        height: h * POINTS_PER_MM,
+        margin: margin_mm as f32 * POINTS_PER_MM,
+        header: text("header"),
+        footer: text("footer"),
+    })
+}
+
+#[cfg_attr(not(feature = "browser"), allow(dead_code))]
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn pdf_create_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "markdown".into(),
+            description: "Markdown to render. Pass this or html.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "html".into(),
+            description: "HTML to render, a fragment or a full document. Pass this or \
+                          markdown."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "output_path".into(),
+            description: "Where to write the PDF, e.g. 'reports/summary.pdf'.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "title".into(),
+            description: "Document title stored in the PDF metadata.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "page_size".into(),
+            description: "One of: a3, a4 (default), a5, letter, legal.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "landscape".into(),
+            description: "Landscape orientation. Default: false.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "margin_mm".into(),
+            description: "Margin on every side, in millimetres. Default: 20.".into(),
+            param_type: "number".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "header".into(),
+            description: "Text centred at the top of each page. {page} and {pages} are \
+                          replaced by the page number and page count."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "footer".into(),
+            description: "Text centred at the bottom of each page, with the same \
+                          placeholders, e.g. 'Page {page} of {pages}'."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "engine".into(),
+            description: "'auto' (default) prints through headless Chrome when available \
+                          and otherwise uses the built-in renderer; 'browser' or 'builtin' \
+                          forces one."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,240 @@
+//! Minimal PDF 1.4 writer.
+//!
+//! Uses only the standard 14 fonts (Helvetica and Courier), which every PDF
+//! reader provides, so nothing is embedded and the output stays small.
+//! Text is WinAnsi-encoded; characters outside that set print as `?`.
+
+use std::fmt::Write;
+
+/// Fonts available to page content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Font {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Mono,
+}
+
+const FONTS: [Font; 5] = [
+    Font::Regular,
+    Font::Bold,
+    Font::Italic,
+    Font::BoldItalic,
+    Font::Mono,
+];
+
+/// Helvetica advance widths for ASCII 32..=126, in 1/1000 em.
+const HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Helvetica-Bold advance widths for ASCII 32..=126, in 1/1000 em.
+const HELVETICA_BOLD: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Italic => "F3",
+            Font::BoldItalic => "F4",
+            Font::Mono => "F5",
+        }
+    }
+
+    fn base_font(self) -> &'static str {
+        match self {
+            Font::Regular => "Helvetica",
+            Font::Bold => "Helvetica-Bold",
+            Font::Italic => "Helvetica-Oblique",
+            Font::BoldItalic => "Helvetica-BoldOblique",
+            Font::Mono => "Courier",
+        }
+    }
+
+    /// Width of `text` set at `size` points.
+    pub(super) fn width(self, text: &str, size: f32) -> f32 {
+        let units: u32 = text.chars().map(|c| self.glyph_width(c)).sum();
+        units as f32 * size / 1000.0
+    }
+
+    fn glyph_width(self, c: char) -> u32 {
+        let table = match self {
+            Font::Mono => return 600,
+            Font::Regular | Font::Italic => &HELVETICA,
+            Font::Bold | Font::BoldItalic => &HELVETICA_BOLD,
+        };
+        match win_ansi(c) {
+            Some(b @ 32..=126) => u32::from(table[usize::from(b - 32)]),
+            Some(0x91 | 0x92) => 222,
+            Some(0x95) => 350,
+            Some(0x97 | 0x85 | 0x89) => 1000,
+            _ => 556,
+        }
+    }
+}
+
+/// The WinAnsiEncoding byte for `c`, if it has one.
+fn win_ansi(c: char) -> Option<u8> {
+    let b = match c {
+        ' '..='~' | '\u{a0}'..='ÿ' => c as u32 as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        '‰' => 0x89,
+        'Š' => 0x8a,
+        '‹' => 0x8b,
+        'Œ' => 0x8c,
+        'Ž' => 0x8e,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '™' => 0x99,
+        'š' => 0x9a,
+        '›' => 0x9b,
+        'œ' => 0x9c,
+        'ž' => 0x9e,
+        'Ÿ' => 0x9f,
+        _ => return None,
+    };
+    Some(b)
+}
+
+/// `text` as the body of a PDF literal string.
+fn literal(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match win_ansi(c).unwrap_or(b'?') {
+            b @ (b'(' | b')' | b'\\') => {
+                out.push('\\');
+                out.push(b as char);
+            }
+            b if b >= 0x80 => {
+                let _ = write!(out, "\\{:03o}", b);
+            }
+            b => out.push(b as char),
+        }
+    }
+    out
+}
+
+/// Drawing operations for one page.
+#[derive(Debug, Default)]
+pub(super) struct Canvas {
+    ops: String,
+}
+
+impl Canvas {
+    /// Draw `text` with its baseline starting at (`x`, `y`).  `gray` is the
+    /// fill level, 0 for black.
+    pub(super) fn text(&mut self, x: f32, y: f32, font: Font, size: f32, gray: f32, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let _ = writeln!(
+            self.ops,
+            "BT {:.2} g /{} {:.2} Tf {:.2} {:.2} Td ({}) Tj ET",
+            gray,
+            font.resource(),
+            size,
+            x,
+            y,
+            literal(text)
+        );
+    }
+
+    pub(super) fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, gray: f32) {
+        let _ = writeln!(
+            self.ops,
+            "{:.2} G 0.5 w {:.2} {:.2} m {:.2} {:.2} l S",
+            gray, x1, y1, x2, y2
+        );
+    }
+}
+
+/// Serialize `pages` of `width` × `height` points into a PDF file.
+pub(super) fn write_pdf(pages: &[Canvas], width: f32, height: f32, title: Option<&str>) -> Vec<u8> {
+    // 1 catalog, 2 page tree, 3 info, then the fonts, then a page object and
+    // its content stream for each page.
+    let first_font = 4;
+    let first_page = first_font + FONTS.len();
+    let mut objects: Vec<String> = Vec::new();
+
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", first_page + 2 * i))
+        .collect();
+    objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+    objects.push(format!(
+        "<< /Type /Pages /Kids [{}] /Count {} >>",
+        kids.join(" "),
+        pages.len()
+    ));
+    let mut info = "<< /Producer (RustyClaw)".to_string();
+    if let Some(title) = title {
+        let _ = write!(info, " /Title ({})", literal(title));
+    }
+    info.push_str(" >>");
+    objects.push(info);
+
+    let mut font_refs = String::new();
+    for (i, font) in FONTS.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+            font.base_font()
+        ));
+        let _ = write!(font_refs, " /{} {} 0 R", font.resource(), first_font + i);
+    }
+
+    for (i, page) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+             /Resources << /Font <<{} >> >> /Contents {} 0 R >>",
+            width,
+            height,
+            font_refs,
+            first_page + 2 * i + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            page.ops.len(),
+            page.ops
+        ));
+    }
+
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        let _ = write!(out, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+    let xref = out.len();
+    let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(out, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        out,
+        "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+    out.into_bytes()
+}
@@ -0,0 +1,457 @@
+//! `pdf_extract` tool: page-addressed PDF extraction with structure.
+//!
+//! Where the `pdf` tool dumps a document's text, this one answers "the
+//! table on page 12": callers pick pages (`"12"`, `"3-5"`, `"1,4,9-"`) and a
+//! mode — `text` (per-page text), `tables` (rows split from the layout-
+//! preserving text where columns are visibly separated), or `images`
+//! (embedded images exported to files and returned as [`MediaRef`]s).
+//! Every result carries the document's page count, title and author.
+//!
+//! Built on poppler-utils (`pdfinfo`, `pdftotext`, `pdfimages`), like the
+//! `pdf` tool.  Output is capped at [`MAX_OUTPUT_CHARS`] characters of page
+//! text and [`MAX_IMAGES`] images; the result names the next page to ask
+//! for when it stops early.
+
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+use crate::gateway::MediaRef;
+
+/// Characters of page text returned per call.
+const MAX_OUTPUT_CHARS: usize = 100_000;
+
+/// Images exported per call.
+const MAX_IMAGES: usize = 50;
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `pdf_extract` tool.
+#[instrument(skip(args, workspace_dir), fields(mode))]
+pub fn exec_pdf_extract(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let mode = args.get("mode").and_then(|v| v.as_str()).unwrap_or("text");
+    tracing::Span::current().record("mode", mode);
+    if !matches!(mode, "text" | "tables" | "images") {
+        return Err(format!(
+            "Unknown mode: '{}'. Use: text, tables, images",
+            mode
+        ));
+    }
+
+    let path_str = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: path".to_string())?;
+    let path = checked_path(workspace_dir, path_str)?;
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+
+    let metadata = pdf_metadata(&path)?;
+    let page_count = metadata
+        .get("pages")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+        .ok_or_else(|| format!("Could not read the page count of '{}'", path.display()))?;
+
+    let pages = match args.get("pages") {
+        None | Some(Value::Null) => (1..=page_count).collect(),
+        Some(Value::Number(n)) => parse_page_ranges(&n.to_string(), page_count)?,
+        Some(Value::String(s)) => parse_page_ranges(s, page_count)?,
+        Some(_) => return Err("pages must be a string like '3-5' or a number".to_string()),
+    };
+    let max_chars = args
+        .get("max_chars")
+        .and_then(|v| v.as_u64())
+        .map(|n| (n as usize).min(MAX_OUTPUT_CHARS))
+        .unwrap_or(MAX_OUTPUT_CHARS);
+
+    debug!(path = %path.display(), pages = pages.len(), "Extracting from PDF");
+
+    let mut result = match mode {
+        "images" => {
+            let out_dir = match args.get("output_dir").and_then(|v| v.as_str()) {
+                Some(dir) => checked_path(workspace_dir, dir)?,
+                None => {
+                    let stem = path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| "pdf".to_string());
+                    checked_path(workspace_dir, &format!("pdf_images/{}", stem))?
+                }
+            };
+            extract_images(&path, &pages, &out_dir)?
+        }
+        _ => extract_pages(&path, &pages, mode == "tables", max_chars)?,
+    };
+    result["path"] = json!(path.display().to_string());
+    result["mode"] = json!(mode);
+    result["metadata"] = metadata;
+    Ok(result.to_string())
+}
+
+/// Resolve `p` against the workspace and apply the vault and sandbox checks.
+fn checked_path(workspace_dir: &Path, p: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, p);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted pdf_extract access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+// ── Page ranges ─────────────────────────────────────────────────────────────
+
+/// Parse a 1-based page selection such as `"12"`, `"3-5"` or `"1,4,9-"`
+/// into sorted, de-duplicated page numbers.  An open end runs to
+/// `page_count`.
+pub(crate) fn parse_page_ranges(spec: &str, page_count: usize) -> Result<Vec<usize>, String> {
+    let invalid = |part: &str| {
+        format!(
+            "Invalid page range '{}': use e.g. '12', '3-5' or '1,4,9-' (document has {} pages)",
+            part, page_count
+        )
+    };
+
+    let mut pages = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((a, b)) => {
+                let a = a.trim();
+                let b = b.trim();
+                let start = if a.is_empty() { Ok(1) } else { a.parse() };
+                let end = if b.is_empty() {
+                    Ok(page_count)
+                } else {
+                    b.parse()
+                };
+                match (start, end) {
+                    (Ok(s), Ok(e)) => (s, e),
+                    _ => return Err(invalid(part)),
+                }
+            }
+            None => {
+                let n = part.parse().map_err(|_| invalid(part))?;
+                (n, n)
+            }
+        };
+        if start == 0 || start > end || end > page_count {
+            return Err(invalid(part));
+        }
+        pages.extend(start..=end);
+    }
+    if pages.is_empty() {
+        return Err(invalid(spec));
+    }
+    pages.sort_unstable();
+    pages.dedup();
+    Ok(pages)
+}
+
+/// Group sorted page numbers into inclusive `(first, last)` runs.
+fn page_runs(pages: &[usize]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for &p in pages {
+        match runs.last_mut() {
+            Some((_, last)) if *last + 1 == p => *last = p,
+            _ => runs.push((p, p)),
+        }
+    }
+    runs
+}
+
+// ── Metadata ────────────────────────────────────────────────────────────────
+
+/// Page count, title, author and the other `pdfinfo` fields worth showing.
+fn pdf_metadata(path: &Path) -> Result<Value, String> {
+    let output = run_poppler(Command::new("pdfinfo").arg(path), "pdfinfo")?;
+    Ok(parse_pdfinfo(&output))
+}
+
+pub(crate) fn parse_pdfinfo(output: &str) -> Value {
+    let mut meta = json!({ "pages": null, "title": null, "author": null });
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let field = match key.trim() {
+            "Pages" => {
+                meta["pages"] = value.parse::<u64>().map(Value::from).unwrap_or(Value::Null);
+                continue;
+            }
+            "Title" => "title",
+            "Author" => "author",
+            "Subject" => "subject",
+            "Creator" => "creator",
+            "Producer" => "producer",
+            "CreationDate" => "created",
+            "ModDate" => "modified",
+            "Encrypted" => "encrypted",
+            _ => continue,
+        };
+        meta[field] = json!(value);
+    }
+    meta
+}
+
+// ── Text and tables ─────────────────────────────────────────────────────────
+
+/// Per-page text (or tables), stopping once `max_chars` of text is used.
+fn extract_pages(
+    path: &Path,
+    pages: &[usize],
+    tables: bool,
+    max_chars: usize,
+) -> Result<Value, String> {
+    let mut out = Vec::new();
+    let mut used = 0usize;
+    let mut next_page = None;
+
+    'runs: for (first, last) in page_runs(pages) {
+        let mut cmd = Command::new("pdftotext");
+        if tables {
+            cmd.arg("-layout");
+        }
+        cmd.arg("-f")
+            .arg(first.to_string())
+            .arg("-l")
+            .arg(last.to_string())
+            .arg("-enc")
+            .arg("UTF-8")
+            .arg(path)
+            .arg("-");
+        let text = run_poppler(&mut cmd, "pdftotext")?;
+
+        // pdftotext ends every page with a form feed.
+        for (page, page_text) in (first..=last).zip(text.split('\u{c}')) {
+            if used >= max_chars {
+                next_page = Some(page);
+                break 'runs;
+            }
+            let remaining = max_chars - used;
+            let (page_text, cut) = match page_text.char_indices().nth(remaining) {
+                Some((idx, _)) => (&page_text[..idx], true),
+                None => (page_text, false),
+            };
+            used += page_text.chars().count();
+
+            let mut entry = if tables {
+                let tables: Vec<Value> = detect_tables(page_text)
+                    .into_iter()
+                    .map(|rows| json!({ "rows": rows }))
+                    .collect();
+                json!({ "page": page, "tables": tables })
+            } else {
+                json!({ "page": page, "text": page_text.trim_end() })
+            };
+            if cut {
+                entry["truncated"] = json!(true);
+                out.push(entry);
+                next_page = pages.iter().copied().find(|p| *p > page);
+                break 'runs;
+            }
+            out.push(entry);
+        }
+    }
+
+    let mut result = json!({ "pages": out, "truncated": next_page.is_some() });
+    if let Some(page) = next_page {
+        result["next_page"] = json!(page);
+    }
+    Ok(result)
+}
+
+/// Find tables in `pdftotext -layout` output.
+///
+/// A table is a run of at least two lines that each split into two or more
+/// cells on gaps of two or more spaces.  Prose lines and single-column
+/// text end the run.  This recovers simple grid layouts; merged cells and
+/// wrapped cell text come back as they appear on the page.
+pub(crate) fn detect_tables(layout_text: &str) -> Vec<Vec<Vec<String>>> {
+    let mut tables = Vec::new();
+    let mut current: Vec<Vec<String>> = Vec::new();
+    for line in layout_text.lines() {
+        let cells = split_cells(line);
+        if cells.len() >= 2 {
+            current.push(cells);
+        } else if !current.is_empty() {
+            if current.len() >= 2 {
+                tables.push(std::mem::take(&mut current));
+            }
+            current.clear();
+        }
+    }
+    if current.len() >= 2 {
+        tables.push(current);
+    }
+    tables
+}
+
+fn split_cells(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut spaces = 0;
+    for c in line.trim().chars() {
+        if c == ' ' {
+            spaces += 1;
+            continue;
+        }
+        if spaces >= 2 {
+            cells.push(std::mem::take(&mut cell));
+        } else if spaces == 1 {
+            cell.push(' ');
+        }
+        spaces = 0;
+        cell.push(c);
+    }
+    if !cell.is_empty() {
+        cells.push(cell);
+    }
+    cells
+}
+
+// ── Images ──────────────────────────────────────────────────────────────────
+
+/// Export embedded images on `pages` as PNG files in `out_dir`.
+fn extract_images(path: &Path, pages: &[usize], out_dir: &Path) -> Result<Value, String> {
+    std::fs::create_dir_all(out_dir)
+        .map_err(|e| format!("Failed to create '{}': {}", out_dir.display(), e))?;
+
+    let mut images = Vec::new();
+    let mut truncated = false;
+    for (first, last) in page_runs(pages) {
+        // `-p` puts the page number in each file name: img-012-003.png.
+        let prefix = out_dir.join(format!("p{}", first));
+        run_poppler(
+            Command::new("pdfimages")
+                .arg("-png")
+                .arg("-p")
+                .arg("-f")
+                .arg(first.to_string())
+                .arg("-l")
+                .arg(last.to_string())
+                .arg(path)
+                .arg(&prefix),
+            "pdfimages",
+        )?;
+
+        let stem = format!("p{}-", first);
+        let mut files: Vec<PathBuf> = std::fs::read_dir(out_dir)
+            .map_err(|e| format!("Failed to list '{}': {}", out_dir.display(), e))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(&stem))
+            })
+            .collect();
+        files.sort();
+
+        for file in files {
+            if images.len() >= MAX_IMAGES {
+                truncated = true;
+                let _ = std::fs::remove_file(&file);
+                continue;
+            }
+            images.push(image_ref(&file));
+        }
+    }
+
+    Ok(json!({
+        "output_dir": out_dir.display().to_string(),
+        "images": images,
+        "truncated": truncated,
+    }))
+}
+
+fn image_ref(file: &Path) -> MediaRef {
+    let mime = match file.extension().and_then(|e| e.to_str()) {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("tif") | Some("tiff") => "image/tiff",
+        _ => "image/png",
+    };
+    let mut media = MediaRef::new(mime.to_string());
+    media.filename = file.file_name().map(|n| n.to_string_lossy().into_owned());
+    media.size = std::fs::metadata(file).ok().map(|m| m.len() as usize);
+    media.local_path = Some(file.display().to_string());
+    media
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Run a poppler-utils command and return its stdout.
+fn run_poppler(cmd: &mut Command, name: &str) -> Result<String, String> {
+    let output = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                format!("{} not found. Install poppler-utils for pdf_extract.", name)
+            } else {
+                format!("Failed to run {}: {}", name, e)
+            }
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", name, stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn pdf_extract_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "path".into(),
+            description: "Path to the PDF file.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "pages".into(),
+            description: "Pages to read, 1-based: '12', '3-5', '1,4,9-' (9 to the end). \
+                          Default: all pages."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "mode".into(),
+            description: "'text' (default) for per-page text, 'tables' for rows of \
+                          cells from column-aligned layouts, 'images' to export \
+                          embedded images as files."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "output_dir".into(),
+            description: "Directory for exported images (mode 'images'). Default: \
+                          pdf_images/<file name> in the workspace."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "max_chars".into(),
+            description: "Maximum characters of page text to return (default and \
+                          maximum 100000)."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
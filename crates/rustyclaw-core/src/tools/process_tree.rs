@@ -0,0 +1,700 @@
+//! Process tree tool: find, inspect and signal host processes.
+//!
+//! `system_monitor` shows the busiest processes; `process_tree` shows how
+//! processes relate, so the agent can find a runaway build and stop it
+//! together with everything it spawned.
+//!
+//! The process table is read from `/proc` on Linux and from `ps` elsewhere.
+//! `kill` never signals pid 1 or the gateway itself.  Processes the agent
+//! started (descendants of the gateway) are signalled straight away; any
+//! other target needs `confirm: true`, and the first call only previews
+//! what would be signalled.
+
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+
+const ACTIONS: &str = "list, info, kill";
+
+/// Most processes `list` returns in one tree.
+const MAX_LIST_NODES: usize = 500;
+
+/// Longest command line reported for one process.
+const MAX_COMMAND_CHARS: usize = 500;
+
+/// One process in a snapshot of the host's process table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub ppid: u32,
+    /// Executable name (`comm`).
+    pub name: String,
+    /// Full command line; empty for kernel threads.
+    pub command: String,
+    /// One-letter scheduler state (`R`, `S`, `Z`, …).
+    pub state: String,
+    pub rss_kb: Option<u64>,
+}
+
+/// A process table with parent → child links.
+#[derive(Debug, Default)]
+pub struct ProcessTree {
+    entries: HashMap<u32, ProcessEntry>,
+    children: HashMap<u32, Vec<u32>>,
+}
+
+impl ProcessTree {
+    pub fn new(entries: Vec<ProcessEntry>) -> Self {
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for entry in &entries {
+            // A process can't be its own parent (the kernel's swapper
+            // reports ppid 0 for pid 0 on some systems).
+            if entry.ppid != entry.pid {
+                children.entry(entry.ppid).or_default().push(entry.pid);
+            }
+        }
+        for kids in children.values_mut() {
+            kids.sort_unstable();
+        }
+        let entries = entries.into_iter().map(|e| (e.pid, e)).collect();
+        Self { entries, children }
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&ProcessEntry> {
+        self.entries.get(&pid)
+    }
+
+    /// Direct children of `pid`, in pid order.
+    pub fn children(&self, pid: u32) -> &[u32] {
+        self.children
+            .get(&pid)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Every descendant of `pid`, parents before their children.
+    pub fn descendants(&self, pid: u32) -> Vec<u32> {
+        let mut out = Vec::new();
+        let mut seen = HashSet::from([pid]);
+        let mut queue = std::collections::VecDeque::from([pid]);
+        while let Some(next) = queue.pop_front() {
+            for &child in self.children(next) {
+                // Guard against a cycle in a snapshot taken mid-reparent.
+                if seen.insert(child) {
+                    out.push(child);
+                    queue.push_back(child);
+                }
+            }
+        }
+        out
+    }
+
+    /// `pid`'s parent, grandparent and so on up to the root.
+    pub fn ancestors(&self, pid: u32) -> Vec<u32> {
+        let mut out = Vec::new();
+        let mut seen = HashSet::from([pid]);
+        let mut current = pid;
+        while let Some(entry) = self.entries.get(&current) {
+            if !self.entries.contains_key(&entry.ppid) || !seen.insert(entry.ppid) {
+                break;
+            }
+            out.push(entry.ppid);
+            current = entry.ppid;
+        }
+        out
+    }
+
+    pub fn is_descendant_of(&self, pid: u32, ancestor: u32) -> bool {
+        self.ancestors(pid).contains(&ancestor)
+    }
+
+    /// Processes whose parent isn't in the table, in pid order.
+    pub fn roots(&self) -> Vec<u32> {
+        let mut roots: Vec<u32> = self
+            .entries
+            .values()
+            .filter(|e| e.ppid == e.pid || !self.entries.contains_key(&e.ppid))
+            .map(|e| e.pid)
+            .collect();
+        roots.sort_unstable();
+        roots
+    }
+
+    /// `pid` and its descendants as nested JSON, stopping once `budget`
+    /// nodes have been emitted.
+    fn subtree_json(&self, pid: u32, budget: &mut usize) -> Option<Value> {
+        let entry = self.entries.get(&pid)?;
+        if *budget == 0 {
+            return None;
+        }
+        *budget -= 1;
+        let children: Vec<Value> = self
+            .children(pid)
+            .iter()
+            .filter_map(|&child| self.subtree_json(child, budget))
+            .collect();
+        let mut node = summary_json(entry);
+        if !children.is_empty() {
+            node["children"] = json!(children);
+        }
+        Some(node)
+    }
+}
+
+fn summary_json(entry: &ProcessEntry) -> Value {
+    json!({
+        "pid": entry.pid,
+        "ppid": entry.ppid,
+        "name": entry.name,
+        "state": entry.state,
+        "rss_kb": entry.rss_kb,
+        "command": truncate(&entry.command, MAX_COMMAND_CHARS),
+    })
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+#[instrument(skip(args, _workspace_dir))]
+pub fn exec_process_tree(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| format!("Missing required parameter: action ({})", ACTIONS))?;
+    debug!(action, "process_tree request");
+
+    let tree = ProcessTree::new(snapshot()?);
+    match action {
+        "list" => list(&tree, args),
+        "info" => info(&tree, pid_arg(args)?),
+        "kill" => kill(&tree, args),
+        other => Err(format!("Unknown action '{}'. Use: {}", other, ACTIONS)),
+    }
+}
+
+fn pid_arg(args: &Value) -> Result<u32, String> {
+    let pid = args
+        .get("pid")
+        .and_then(|v| v.as_u64())
+        .ok_or("Missing required parameter: pid")?;
+    u32::try_from(pid).map_err(|_| format!("Invalid pid {}", pid))
+}
+
+fn list(tree: &ProcessTree, args: &Value) -> Result<String, String> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    let roots: Vec<u32> = if args.get("pid").is_some() {
+        let pid = pid_arg(args)?;
+        if tree.get(pid).is_none() {
+            return Err(format!("No process with pid {}", pid));
+        }
+        vec![pid]
+    } else if let Some(name) = name {
+        let needle = name.to_lowercase();
+        let matches: HashSet<u32> = tree
+            .entries
+            .values()
+            .filter(|e| {
+                e.name.to_lowercase().contains(&needle)
+                    || e.command.to_lowercase().contains(&needle)
+            })
+            .map(|e| e.pid)
+            .collect();
+        // A match inside another match's tree is already shown there.
+        let mut roots: Vec<u32> = matches
+            .iter()
+            .copied()
+            .filter(|&pid| !tree.ancestors(pid).iter().any(|a| matches.contains(a)))
+            .collect();
+        roots.sort_unstable();
+        roots
+    } else {
+        tree.roots()
+    };
+
+    let mut budget = MAX_LIST_NODES;
+    let processes: Vec<Value> = roots
+        .iter()
+        .filter_map(|&pid| tree.subtree_json(pid, &mut budget))
+        .collect();
+    Ok(json!({
+        "processes": processes,
+        "count": MAX_LIST_NODES - budget,
+        "truncated": budget == 0,
+    })
+    .to_string())
+}
+
+fn info(tree: &ProcessTree, pid: u32) -> Result<String, String> {
+    let entry = tree
+        .get(pid)
+        .ok_or_else(|| format!("No process with pid {}", pid))?;
+    let mut out = summary_json(entry);
+    out["command"] = json!(entry.command);
+    out["children"] = json!(tree.children(pid));
+    out["descendant_count"] = json!(tree.descendants(pid).len());
+    out["ancestors"] = json!(
+        tree.ancestors(pid)
+            .iter()
+            .filter_map(|&a| tree.get(a))
+            .map(|a| json!({ "pid": a.pid, "name": a.name }))
+            .collect::<Vec<_>>()
+    );
+    out["started_by_agent"] = json!(tree.is_descendant_of(pid, std::process::id()));
+    if let Value::Object(details) = platform::details(pid) {
+        for (key, value) in details {
+            out[key] = value;
+        }
+    }
+    Ok(out.to_string())
+}
+
+fn kill(tree: &ProcessTree, args: &Value) -> Result<String, String> {
+    let pid = pid_arg(args)?;
+    let signal = args
+        .get("signal")
+        .and_then(|v| v.as_str())
+        .unwrap_or("TERM");
+    let signum = parse_signal(signal)?;
+    let with_tree = args.get("tree").and_then(|v| v.as_bool()).unwrap_or(false);
+    let confirm = args
+        .get("confirm")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let plan = kill_plan(tree, pid, with_tree, std::process::id())?;
+    let targets: Vec<Value> = plan
+        .targets
+        .iter()
+        .map(|&p| {
+            let name = tree.get(p).map(|e| e.name.as_str()).unwrap_or("?");
+            json!({ "pid": p, "name": name })
+        })
+        .collect();
+    if plan.needs_confirmation && !confirm {
+        return Ok(json!({
+            "status": "confirm_required",
+            "signal": signal_name(signum),
+            "targets": targets,
+            "hint": "These processes were not started by the agent. \
+                     Call again with confirm=true to signal them.",
+        })
+        .to_string());
+    }
+
+    let mut signalled = Vec::new();
+    let mut failed = Vec::new();
+    for (&target, summary) in plan.targets.iter().zip(targets) {
+        match platform::send_signal(target, signum) {
+            Ok(()) => signalled.push(summary),
+            Err(err) => {
+                warn!(pid = target, error = %err, "Failed to signal process");
+                failed.push(json!({ "pid": target, "error": err }));
+            }
+        }
+    }
+    if signalled.is_empty() {
+        return Err(format!(
+            "Failed to signal pid {}: {}",
+            pid,
+            failed
+                .first()
+                .and_then(|f| f["error"].as_str())
+                .unwrap_or("unknown error")
+        ));
+    }
+    Ok(json!({
+        "status": "signalled",
+        "signal": signal_name(signum),
+        "signalled": signalled,
+        "failed": failed,
+    })
+    .to_string())
+}
+
+/// The processes a `kill` call would signal, and whether it needs
+/// `confirm`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct KillPlan {
+    /// Children before parents, so a parent can't respawn them.
+    pub targets: Vec<u32>,
+    /// Some target wasn't started by the agent.
+    pub needs_confirmation: bool,
+}
+
+/// Work out what killing `pid` (and with `with_tree`, its descendants)
+/// would signal.  Refuses pid 1 and any set that includes `self_pid`.
+pub fn kill_plan(
+    tree: &ProcessTree,
+    pid: u32,
+    with_tree: bool,
+    self_pid: u32,
+) -> Result<KillPlan, String> {
+    match pid {
+        0 => return Err("Refusing to signal pid 0 (the caller's process group)".into()),
+        1 => return Err("Refusing to signal pid 1 (init)".into()),
+        _ => {}
+    }
+    if tree.get(pid).is_none() {
+        return Err(format!("No process with pid {}", pid));
+    }
+    let mut targets = if with_tree {
+        tree.descendants(pid)
+    } else {
+        Vec::new()
+    };
+    targets.reverse();
+    targets.push(pid);
+    if targets.contains(&self_pid) {
+        return Err(format!(
+            "Refusing to signal the gateway itself (pid {})",
+            self_pid
+        ));
+    }
+    let needs_confirmation = targets.iter().any(|&p| !tree.is_descendant_of(p, self_pid));
+    Ok(KillPlan {
+        targets,
+        needs_confirmation,
+    })
+}
+
+/// Signals `kill` accepts, by name.
+const SIGNALS: &[(&str, i32)] = &[
+    ("HUP", 1),
+    ("INT", 2),
+    ("QUIT", 3),
+    ("KILL", 9),
+    ("USR1", platform::SIGUSR1),
+    ("USR2", platform::SIGUSR2),
+    ("TERM", 15),
+    ("CONT", platform::SIGCONT),
+    ("STOP", platform::SIGSTOP),
+];
+
+/// `TERM`, `SIGTERM`, `term` or `15` → 15.
+pub fn parse_signal(signal: &str) -> Result<i32, String> {
+    let upper = signal.trim().to_ascii_uppercase();
+    let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+    if let Some(&(_, num)) = SIGNALS.iter().find(|(n, _)| *n == name) {
+        return Ok(num);
+    }
+    match name.parse::<i32>() {
+        Ok(num) if SIGNALS.iter().any(|&(_, n)| n == num) => Ok(num),
+        _ => Err(format!(
+            "Unsupported signal '{}'. Use one of: {}",
+            signal,
+            SIGNALS
+                .iter()
+                .map(|(n, _)| *n)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+fn signal_name(signum: i32) -> String {
+    SIGNALS
+        .iter()
+        .find(|&&(_, n)| n == signum)
+        .map(|(n, _)| format!("SIG{}", n))
+        .unwrap_or_else(|| signum.to_string())
+}
+
+// ── Process table ───────────────────────────────────────────────────────────
+
+/// Read the host's process table.
+pub fn snapshot() -> Result<Vec<ProcessEntry>, String> {
+    platform::snapshot()
+}
+
+/// Parse the contents of `/proc/<pid>/stat`.
+///
+/// Returns the entry (without its command line) plus the raw fields after
+/// the executable name, which [`platform::details`] reads further.
+pub fn parse_proc_stat(stat: &str, page_kb: u64) -> Option<(ProcessEntry, Vec<&str>)> {
+    // The name is in parentheses and may itself contain spaces or ')'.
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let pid = stat[..open].trim().parse().ok()?;
+    let name = stat.get(open + 1..close)?.to_string();
+    let fields: Vec<&str> = stat[close + 1..].split_whitespace().collect();
+    let state = fields.first()?.to_string();
+    let ppid = fields.get(1)?.parse().ok()?;
+    let rss_kb = fields
+        .get(21)
+        .and_then(|pages| pages.parse::<u64>().ok())
+        .map(|pages| pages * page_kb);
+    Some((
+        ProcessEntry {
+            pid,
+            ppid,
+            name,
+            command: String::new(),
+            state,
+            rss_kb,
+        },
+        fields,
+    ))
+}
+
+/// Parse one line of `ps -axww -o pid=,ppid=,rss=,state=,args=`.
+pub fn parse_ps_line(line: &str) -> Option<ProcessEntry> {
+    let mut parts = line.split_whitespace();
+    let pid = parts.next()?.parse().ok()?;
+    let ppid = parts.next()?.parse().ok()?;
+    let rss_kb = parts.next()?.parse().ok();
+    let state = parts.next()?.chars().next()?.to_string();
+    let command = parts.collect::<Vec<_>>().join(" ");
+    let name = command
+        .split_whitespace()
+        .next()
+        .and_then(|exe| exe.rsplit('/').next())
+        .unwrap_or_default()
+        .to_string();
+    Some(ProcessEntry {
+        pid,
+        ppid,
+        name,
+        command,
+        state,
+        rss_kb,
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{ProcessEntry, parse_proc_stat};
+    use serde_json::{Value, json};
+
+    pub(super) const SIGUSR1: i32 = libc::SIGUSR1;
+    pub(super) const SIGUSR2: i32 = libc::SIGUSR2;
+    pub(super) const SIGCONT: i32 = libc::SIGCONT;
+    pub(super) const SIGSTOP: i32 = libc::SIGSTOP;
+
+    fn page_kb() -> u64 {
+        // SAFETY: sysconf has no preconditions.
+        let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if size > 0 { size as u64 / 1024 } else { 4 }
+    }
+
+    fn clock_ticks() -> f64 {
+        // SAFETY: sysconf has no preconditions.
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks > 0 { ticks as f64 } else { 100.0 }
+    }
+
+    fn read_entry(pid: u32, page_kb: u64) -> Option<ProcessEntry> {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        let (mut entry, _) = parse_proc_stat(&stat, page_kb)?;
+        let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+        entry.command = String::from_utf8_lossy(&cmdline)
+            .split('\0')
+            .filter(|arg| !arg.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(entry)
+    }
+
+    pub(super) fn snapshot() -> Result<Vec<ProcessEntry>, String> {
+        let page_kb = page_kb();
+        let dir = std::fs::read_dir("/proc").map_err(|e| format!("Failed to read /proc: {}", e))?;
+        Ok(dir
+            .flatten()
+            .filter_map(|e| e.file_name().to_str()?.parse::<u32>().ok())
+            // Processes can exit between listing and reading.
+            .filter_map(|pid| read_entry(pid, page_kb))
+            .collect())
+    }
+
+    pub(super) fn details(pid: u32) -> Value {
+        let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) else {
+            return Value::Null;
+        };
+        let Some((_, fields)) = parse_proc_stat(&stat, page_kb()) else {
+            return Value::Null;
+        };
+        let num = |idx: usize| fields.get(idx).and_then(|f| f.parse::<u64>().ok());
+        let ticks = clock_ticks();
+        let uptime = std::fs::read_to_string("/proc/uptime")
+            .ok()
+            .and_then(|u| u.split_whitespace().next()?.parse::<f64>().ok());
+        let cpu_secs = match (num(11), num(12)) {
+            (Some(user), Some(system)) => Some((user + system) as f64 / ticks),
+            _ => None,
+        };
+        let elapsed_secs = match (num(19), uptime) {
+            (Some(start), Some(uptime)) => Some((uptime - start as f64 / ticks).max(0.0).round()),
+            _ => None,
+        };
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+        let uid = status
+            .lines()
+            .find_map(|l| l.strip_prefix("Uid:"))
+            .and_then(|l| l.split_whitespace().next()?.parse::<u32>().ok());
+        let link = |name: &str| {
+            std::fs::read_link(format!("/proc/{}/{}", pid, name))
+                .ok()
+                .map(|p| p.display().to_string())
+        };
+        json!({
+            "uid": uid,
+            "threads": num(17),
+            "virtual_kb": num(20).map(|bytes| bytes / 1024),
+            "cpu_time_secs": cpu_secs,
+            "elapsed_secs": elapsed_secs,
+            "exe": link("exe"),
+            "cwd": link("cwd"),
+        })
+    }
+
+    pub(super) fn send_signal(pid: u32, signum: i32) -> Result<(), String> {
+        let pid = libc::pid_t::try_from(pid).map_err(|_| format!("Invalid pid {}", pid))?;
+        // SAFETY: kill has no memory-safety preconditions.
+        if unsafe { libc::kill(pid, signum) } == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error().to_string())
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+mod platform {
+    use super::{ProcessEntry, parse_ps_line};
+    use serde_json::{Value, json};
+
+    pub(super) const SIGUSR1: i32 = libc::SIGUSR1;
+    pub(super) const SIGUSR2: i32 = libc::SIGUSR2;
+    pub(super) const SIGCONT: i32 = libc::SIGCONT;
+    pub(super) const SIGSTOP: i32 = libc::SIGSTOP;
+
+    fn ps(args: &[&str]) -> Result<String, String> {
+        let out = std::process::Command::new("ps")
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run ps: {}", e))?;
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+
+    pub(super) fn snapshot() -> Result<Vec<ProcessEntry>, String> {
+        let out = ps(&["-axww", "-o", "pid=,ppid=,rss=,state=,args="])?;
+        Ok(out.lines().filter_map(parse_ps_line).collect())
+    }
+
+    pub(super) fn details(pid: u32) -> Value {
+        let pid = pid.to_string();
+        let Ok(out) = ps(&["-o", "uid=,vsz=,time=,etime=,%cpu=", "-p", &pid]) else {
+            return Value::Null;
+        };
+        let fields: Vec<&str> = out.split_whitespace().collect();
+        if fields.len() < 5 {
+            return Value::Null;
+        }
+        json!({
+            "uid": fields[0].parse::<u32>().ok(),
+            "virtual_kb": fields[1].parse::<u64>().ok(),
+            "cpu_time": fields[2],
+            "elapsed": fields[3],
+            "cpu_percent": fields[4].parse::<f64>().ok(),
+        })
+    }
+
+    pub(super) fn send_signal(pid: u32, signum: i32) -> Result<(), String> {
+        let pid = libc::pid_t::try_from(pid).map_err(|_| format!("Invalid pid {}", pid))?;
+        // SAFETY: kill has no memory-safety preconditions.
+        if unsafe { libc::kill(pid, signum) } == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error().to_string())
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use super::ProcessEntry;
+    use serde_json::Value;
+
+    pub(super) const SIGUSR1: i32 = 10;
+    pub(super) const SIGUSR2: i32 = 12;
+    pub(super) const SIGCONT: i32 = 18;
+    pub(super) const SIGSTOP: i32 = 19;
+
+    pub(super) fn snapshot() -> Result<Vec<ProcessEntry>, String> {
+        Err("process_tree is only available on Linux and macOS".into())
+    }
+
+    pub(super) fn details(_pid: u32) -> Value {
+        Value::Null
+    }
+
+    pub(super) fn send_signal(_pid: u32, _signum: i32) -> Result<(), String> {
+        Err("process_tree is only available on Linux and macOS".into())
+    }
+}
+
+// ── Parameter definitions ───────────────────────────────────────────────────
+
+pub fn process_tree_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: format!("What to do: {}.", ACTIONS),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "pid".into(),
+            description: "Process id. Required for 'info' and 'kill'; for 'list', show only \
+                          this process and its descendants."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "name".into(),
+            description: "For 'list': show processes whose name or command line contains \
+                          this (case-insensitive), with their descendants."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "signal".into(),
+            description: "For 'kill': TERM (default), INT, HUP, QUIT, KILL, USR1, USR2, STOP \
+                          or CONT."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "tree".into(),
+            description: "For 'kill': also signal every descendant, children first. \
+                          Default: false."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "confirm".into(),
+            description: "For 'kill': required to signal processes the agent didn't start. \
+                          Without it the call returns the processes it would signal."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,595 @@
+//! Proofreading tool: spelling and grammar corrections for text or a file.
+//!
+//! As with [`translate`](super::translate), the gateway makes the model
+//! call and this module does the rest: reading the input, splitting off
+//! fenced code, chunking, building the prompt, and merging each chunk's
+//! reply into one list of `(original, suggestion, reason)` corrections
+//! plus the corrected text.  The gateway supplies the model call to
+//! [`proofread_with`].
+//!
+//! Fenced code blocks never reach the model.  Inline `code` spans are
+//! swapped for `⟦n⟧` placeholders before the prompt is built and restored
+//! afterwards; if a reply loses one, that chunk is kept as written.
+//!
+//! `engine = "spell"` checks spelling offline with `aspell` or `hunspell`
+//! instead, and runs without the gateway.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+use super::translate::{Segment, chunk_segments, split_fenced};
+
+/// Maximum characters of prose sent to the model in one request.  Lower
+/// than translation's because the reply carries both the corrected text
+/// and the list of corrections.
+pub const MAX_CHUNK_CHARS: usize = 3000;
+
+/// Largest input accepted, inline or from a file.
+const MAX_INPUT_BYTES: usize = 512 * 1024;
+
+/// One suggested change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Correction {
+    /// The text as written, exactly.
+    pub original: String,
+    /// What it should be replaced with.
+    pub suggestion: String,
+    /// A few words on why, e.g. "spelling" or "subject–verb agreement".
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// The JSON object the model is asked to return for each chunk.
+#[derive(Debug, Deserialize)]
+struct ChunkReply {
+    #[serde(default)]
+    corrections: Vec<Correction>,
+    corrected: String,
+}
+
+/// Which checker runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// The configured model (spelling, grammar and punctuation).
+    Model,
+    /// `aspell` or `hunspell` (spelling only, offline).
+    Spell,
+}
+
+impl Engine {
+    pub fn from_args(args: &Value) -> Result<Self, String> {
+        match args
+            .get("engine")
+            .and_then(|v| v.as_str())
+            .unwrap_or("model")
+        {
+            "model" => Ok(Self::Model),
+            "spell" => Ok(Self::Spell),
+            other => Err(format!("Unknown engine: '{}'. Use: model, spell", other)),
+        }
+    }
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Sync entry point for the static ToolDef.
+///
+/// Only the offline spell checker runs here; the gateway intercepts
+/// model-backed calls because it owns the provider connection.
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_proofread(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    match Engine::from_args(args)? {
+        Engine::Spell => spellcheck(args, workspace_dir),
+        Engine::Model => Err("proofread with the model is executed by the gateway; \
+                              pass engine='spell' for an offline spelling check"
+            .into()),
+    }
+}
+
+/// Proofread `args.text` (or the file at `args.path`) using `call_model`
+/// for each prose chunk.
+///
+/// `call_model` receives a complete prompt and returns the model's reply.
+/// Returns a JSON object with the merged `corrections`, the `corrected`
+/// text, and whether anything `changed`.
+#[instrument(skip(args, workspace_dir, call_model))]
+pub async fn proofread_with<F, Fut>(
+    args: &Value,
+    workspace_dir: &Path,
+    mut call_model: F,
+) -> Result<String, String>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    let (text, path) = read_input(args, workspace_dir)?;
+    let spelling_only = spelling_only(args)?;
+    let language = language(args);
+
+    let segments = chunk_segments(split_fenced(&text), MAX_CHUNK_CHARS);
+    debug!(segments = segments.len(), spelling_only, "Proofreading");
+
+    let mut corrected = String::with_capacity(text.len());
+    let mut corrections: Vec<Correction> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut chunks = 0;
+    for segment in segments {
+        let prose = match segment {
+            Segment::Code(code) => {
+                corrected.push_str(&code);
+                continue;
+            }
+            Segment::Prose(prose) => prose,
+        };
+
+        let body = prose.trim();
+        if body.is_empty() {
+            corrected.push_str(&prose);
+            continue;
+        }
+        let start = prose.len() - prose.trim_start().len();
+        let (leading, trailing) = (&prose[..start], &prose[start + body.len()..]);
+
+        let (masked, spans) = mask_inline_code(body);
+        let prompt = proofread_prompt(&masked, language, spelling_only);
+        let reply = parse_reply(&call_model(prompt).await?)?;
+        chunks += 1;
+
+        corrected.push_str(leading);
+        match unmask(&reply.corrected, &spans) {
+            Some(fixed) => {
+                corrected.push_str(fixed.trim());
+                corrections.extend(reply.corrections.into_iter().filter_map(|c| {
+                    let original = unmask_lossy(&c.original, &spans);
+                    let suggestion = unmask_lossy(&c.suggestion, &spans);
+                    (original != suggestion).then_some(Correction {
+                        original,
+                        suggestion,
+                        reason: c.reason,
+                    })
+                }));
+            }
+            None => {
+                warn!(chunk = chunks, "Proofread reply lost an inline code span");
+                warnings.push(format!(
+                    "Chunk {} was left unchanged: the model altered inline code.",
+                    chunks
+                ));
+                corrected.push_str(body);
+            }
+        }
+        corrected.push_str(trailing);
+    }
+
+    let mut out = json!({
+        "engine": "model",
+        "corrections": corrections,
+        "corrected": corrected,
+        "changed": corrected != text,
+        "chunks": chunks,
+    });
+    if let Some(path) = path {
+        out["path"] = json!(path.display().to_string());
+    }
+    if !warnings.is_empty() {
+        out["warnings"] = json!(warnings);
+    }
+    Ok(out.to_string())
+}
+
+// ── Input ───────────────────────────────────────────────────────────────────
+
+/// The text to check, from `text` or the file at `path` (returned too).
+fn read_input(args: &Value, workspace_dir: &Path) -> Result<(String, Option<PathBuf>), String> {
+    let text = args.get("text").and_then(|v| v.as_str());
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .filter(|p| !p.trim().is_empty());
+    let (text, path) = match (text, path) {
+        (Some(_), Some(_)) => return Err("Pass either text or path, not both".into()),
+        (Some(text), None) => (text.to_string(), None),
+        (None, Some(p)) => {
+            let path = resolve_path(workspace_dir, p);
+            if is_protected_path(&path) {
+                warn!(path = %path.display(), "Attempted proofread of protected path");
+                return Err(VAULT_ACCESS_DENIED.to_string());
+            }
+            if let Some(sb) = sandbox() {
+                sb.check_path(&path)?;
+            }
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            (text, Some(path))
+        }
+        (None, None) => return Err("Missing required parameter: text or path".into()),
+    };
+    if text.len() > MAX_INPUT_BYTES {
+        return Err(format!(
+            "Input is {} KB; proofread accepts up to {} KB",
+            text.len() / 1024,
+            MAX_INPUT_BYTES / 1024
+        ));
+    }
+    Ok((text, path))
+}
+
+fn spelling_only(args: &Value) -> Result<bool, String> {
+    match args.get("checks").and_then(|v| v.as_str()).unwrap_or("all") {
+        "all" => Ok(false),
+        "spelling" => Ok(true),
+        other => Err(format!("Unknown checks: '{}'. Use: all, spelling", other)),
+    }
+}
+
+fn language(args: &Value) -> Option<&str> {
+    args.get("language")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+// ── Model prompt and reply ──────────────────────────────────────────────────
+
+/// Build the prompt for one prose chunk.
+pub fn proofread_prompt(text: &str, language: Option<&str>, spelling_only: bool) -> String {
+    let lang = language
+        .map(|l| format!(" (written in {})", l))
+        .unwrap_or_default();
+    let what = if spelling_only {
+        "spelling mistakes only"
+    } else {
+        "spelling, grammar and punctuation mistakes"
+    };
+    format!(
+        "Proofread the text between the <text> tags{lang}. Fix {what}; do not reword \
+         for style or change the meaning.\n\
+         Rules:\n\
+         - Reply with only a JSON object, no commentary: \
+         {{\"corrections\": [{{\"original\": \"...\", \"suggestion\": \"...\", \
+         \"reason\": \"...\"}}], \"corrected\": \"...\"}}\n\
+         - `original` is the shortest phrase containing the mistake, copied exactly; \
+         `suggestion` replaces it; `reason` is a few words.\n\
+         - `corrected` is the whole text with every correction applied.\n\
+         - Preserve Markdown formatting, URLs and line breaks exactly. Placeholders \
+         like ⟦0⟧ stand for code: copy them unchanged.\n\
+         - If nothing needs fixing, return an empty list and the text unchanged.\n\n\
+         <text>\n{text}\n</text>"
+    )
+}
+
+/// Parse the model's JSON reply, tolerating a Markdown fence or stray
+/// text around the object.
+fn parse_reply(reply: &str) -> Result<ChunkReply, String> {
+    let start = reply.find('{');
+    let end = reply.rfind('}');
+    let object = match (start, end) {
+        (Some(s), Some(e)) if s < e => &reply[s..=e],
+        _ => return Err("Model did not return the corrections as JSON".into()),
+    };
+    serde_json::from_str(object)
+        .map_err(|e| format!("Model returned malformed corrections JSON: {}", e))
+}
+
+fn placeholder(n: usize) -> String {
+    format!("⟦{}⟧", n)
+}
+
+/// Replace inline code spans with numbered placeholders.
+///
+/// Returns the masked text and the spans, backticks included, in order.
+fn mask_inline_code(text: &str) -> (String, Vec<String>) {
+    let mut masked = String::with_capacity(text.len());
+    let mut spans = Vec::new();
+    for piece in inline_code_pieces(text) {
+        match piece {
+            Ok(prose) => masked.push_str(prose),
+            Err(code) => {
+                masked.push_str(&placeholder(spans.len()));
+                spans.push(code.to_string());
+            }
+        }
+    }
+    (masked, spans)
+}
+
+/// Split `text` into prose (`Ok`) and inline code spans (`Err`).
+///
+/// A run of backticks opens a span that closes at the next run of the
+/// same length; an unmatched run is ordinary text.
+fn inline_code_pieces(text: &str) -> Vec<Result<&str, &str>> {
+    let mut pieces = Vec::new();
+    let mut prose_start = 0;
+    let mut i = 0;
+    while let Some(offset) = text[i..].find('`') {
+        let open = i + offset;
+        let ticks = text[open..].len() - text[open..].trim_start_matches('`').len();
+        let body = open + ticks;
+        let close = find_tick_run(&text[body..], ticks).map(|c| body + c);
+        match close {
+            Some(close) => {
+                if open > prose_start {
+                    pieces.push(Ok(&text[prose_start..open]));
+                }
+                pieces.push(Err(&text[open..close + ticks]));
+                prose_start = close + ticks;
+                i = prose_start;
+            }
+            None => i = body,
+        }
+    }
+    if prose_start < text.len() {
+        pieces.push(Ok(&text[prose_start..]));
+    }
+    pieces
+}
+
+/// Byte offset of the next run of exactly `len` backticks.
+fn find_tick_run(text: &str, len: usize) -> Option<usize> {
+    let mut i = 0;
+    while let Some(offset) = text[i..].find('`') {
+        let start = i + offset;
+        let run = text[start..].len() - text[start..].trim_start_matches('`').len();
+        if run == len {
+            return Some(start);
+        }
+        i = start + run;
+    }
+    None
+}
+
+/// Restore every placeholder, or `None` if any is missing or duplicated.
+fn unmask(text: &str, spans: &[String]) -> Option<String> {
+    let mut out = text.to_string();
+    for (n, span) in spans.iter().enumerate() {
+        let marker = placeholder(n);
+        if out.matches(&marker).count() != 1 {
+            return None;
+        }
+        out = out.replacen(&marker, span, 1);
+    }
+    Some(out)
+}
+
+/// Restore whichever placeholders appear (for correction snippets).
+fn unmask_lossy(text: &str, spans: &[String]) -> String {
+    spans
+        .iter()
+        .enumerate()
+        .fold(text.to_string(), |acc, (n, span)| {
+            acc.replace(&placeholder(n), span)
+        })
+}
+
+// ── Offline spell check ─────────────────────────────────────────────────────
+
+/// A word the spell checker did not recognise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+    pub word: String,
+    /// Suggestions, best first (may be empty).
+    pub suggestions: Vec<String>,
+}
+
+fn spellcheck(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let (text, path) = read_input(args, workspace_dir)?;
+    let (program, program_args) = spell_command(language(args))?;
+    let lines = prose_lines(&text);
+    if lines.is_empty() {
+        return Ok(json!({ "engine": "spell", "corrections": [], "corrected": null }).to_string());
+    }
+
+    // `^` stops a line being read as an ispell pipe command.
+    let mut input = String::new();
+    for (_, line) in &lines {
+        input.push('^');
+        input.push_str(line);
+        input.push('\n');
+    }
+
+    let mut child = Command::new(&program)
+        .args(&program_args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program.display(), e))?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or("Failed to open spell checker stdin")?;
+    // Write from a thread so a long input cannot deadlock against a full
+    // stdout pipe.
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Spell checker failed: {}", e))?;
+    let _ = writer.join();
+    if !output.status.success() {
+        return Err(format!(
+            "{} exited with {}: {}",
+            program.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let results = parse_ispell(&String::from_utf8_lossy(&output.stdout));
+    let corrections: Vec<Value> = lines
+        .iter()
+        .zip(results)
+        .flat_map(|((line_no, _), misspellings)| {
+            misspellings.into_iter().map(move |m| {
+                json!({
+                    "original": m.word,
+                    "suggestion": m.suggestions.first().cloned().unwrap_or_default(),
+                    "reason": "spelling",
+                    "line": line_no,
+                    "alternatives": m.suggestions.iter().skip(1).take(4).collect::<Vec<_>>(),
+                })
+            })
+        })
+        .collect();
+
+    let name = program
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut out = json!({
+        "engine": "spell",
+        "checker": name,
+        "corrections": corrections,
+        "corrected": null,
+    });
+    if let Some(path) = path {
+        out["path"] = json!(path.display().to_string());
+    }
+    Ok(out.to_string())
+}
+
+/// Locate aspell (preferred) or hunspell and its pipe-mode arguments.
+fn spell_command(language: Option<&str>) -> Result<(PathBuf, Vec<String>), String> {
+    if let Ok(aspell) = which::which("aspell") {
+        let mut args = vec!["-a".to_string()];
+        if let Some(lang) = language {
+            args.push(format!("--lang={}", lang));
+        }
+        return Ok((aspell, args));
+    }
+    if let Ok(hunspell) = which::which("hunspell") {
+        let mut args = vec!["-a".to_string()];
+        if let Some(lang) = language {
+            args.push("-d".to_string());
+            args.push(lang.to_string());
+        }
+        return Ok((hunspell, args));
+    }
+    Err("engine='spell' needs aspell or hunspell on PATH".into())
+}
+
+/// Non-blank prose lines with their 1-based line numbers, with fenced
+/// code, inline code and URLs blanked out.
+fn prose_lines(text: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut line_no = 0;
+    for segment in split_fenced(text) {
+        let prose = match segment {
+            Segment::Code(code) => {
+                line_no += code.split_inclusive('\n').count();
+                continue;
+            }
+            Segment::Prose(prose) => prose,
+        };
+        for line in prose.split_inclusive('\n') {
+            line_no += 1;
+            let cleaned: String = inline_code_pieces(line.trim_end())
+                .into_iter()
+                .map(|piece| match piece {
+                    Ok(prose) => prose
+                        .split(' ')
+                        .map(|word| if is_url(word) { "" } else { word })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    Err(_) => " ".to_string(),
+                })
+                .collect();
+            if !cleaned.trim().is_empty() {
+                lines.push((line_no, cleaned));
+            }
+        }
+    }
+    lines
+}
+
+fn is_url(word: &str) -> bool {
+    let word = word.trim_start_matches(['(', '<', '[']);
+    word.contains("://") || word.starts_with("www.") || word.contains("](")
+}
+
+/// Parse ispell pipe-mode (`-a`) output into one list per input line.
+///
+/// Handles `& word count offset: s1, s2`, `? word count offset: …`
+/// (guesses) and `# word offset` (no suggestions); `*`, `+` and `-`
+/// mean the word was fine.
+pub fn parse_ispell(output: &str) -> Vec<Vec<Misspelling>> {
+    let mut lines = Vec::new();
+    let mut current = Vec::new();
+    for line in output.lines() {
+        if line.starts_with("@(#)") {
+            continue;
+        }
+        if line.is_empty() {
+            lines.push(std::mem::take(&mut current));
+            continue;
+        }
+        let (head, suggestions) = match line.split_once(": ") {
+            Some((head, rest)) => (head, rest.split(", ").map(str::to_string).collect()),
+            None => (line, Vec::new()),
+        };
+        let mut parts = head.split_whitespace();
+        let (Some(kind), Some(word)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if matches!(kind, "&" | "?" | "#") {
+            current.push(Misspelling {
+                word: word.to_string(),
+                suggestions,
+            });
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+// ── Parameter definitions ───────────────────────────────────────────────────
+
+pub fn proofread_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "text".into(),
+            description: "Text to proofread. Give this or 'path'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "path".into(),
+            description: "File to proofread, relative to the workspace. The file is not \
+                          modified."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "engine".into(),
+            description: "'model' (default): spelling, grammar and punctuation via the \
+                          configured model. 'spell': offline spelling check with aspell or \
+                          hunspell, no corrected text."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "checks".into(),
+            description: "For the model engine: 'all' (default) or 'spelling'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "language".into(),
+            description: "Language of the text (e.g. 'British English', or 'en_GB' for the \
+                          spell engine). Defaults to the checker's default."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
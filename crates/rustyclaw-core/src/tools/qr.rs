@@ -0,0 +1,164 @@
+//! QR tool: encode text as a QR code, or read the codes in an image.
+//!
+//! `encode` renders through the same code as the pairing QR: a PNG written
+//! into the workspace and returned as a [`MediaRef`], or Unicode half
+//! blocks for showing in a terminal.  `decode` reads any image format the
+//! `image` crate knows and returns every QR code it finds.  PNG output and
+//! decoding need the `qr` feature; the Unicode rendering is always there.
+
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+use crate::gateway::MediaRef;
+use crate::pairing::{decode_qr_image, render_qr_png, render_qr_unicode};
+
+/// Longest text accepted for encoding; a version 40 code at the lowest
+/// error correction holds 2953 bytes.
+const MAX_TEXT_BYTES: usize = 2953;
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `qr` tool.
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub fn exec_qr(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: action".to_string())?;
+    tracing::Span::current().record("action", action);
+    match action {
+        "encode" => encode(args, workspace_dir),
+        "decode" => decode(args, workspace_dir),
+        other => Err(format!("Unknown action: '{}'. Use: encode, decode", other)),
+    }
+}
+
+fn encode(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let text = args
+        .get("text")
+        .and_then(|v| v.as_str())
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| "Missing required parameter: text".to_string())?;
+    if text.len() > MAX_TEXT_BYTES {
+        return Err(format!(
+            "Text is {} bytes; a QR code holds at most {}",
+            text.len(),
+            MAX_TEXT_BYTES
+        ));
+    }
+
+    match args.get("format").and_then(|v| v.as_str()).unwrap_or("png") {
+        "unicode" => render_qr_unicode(text).map_err(|e| e.to_string()),
+        "png" => {
+            let png = render_qr_png(text).map_err(|e| e.to_string())?;
+            let path = match args.get("output_path").and_then(|v| v.as_str()) {
+                Some(p) => checked_path(workspace_dir, p)?,
+                None => checked_path(workspace_dir, &default_output())?,
+            };
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+            }
+            std::fs::write(&path, &png)
+                .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+            debug!(path = %path.display(), bytes = png.len(), "Wrote QR code");
+
+            let mut media = MediaRef::new("image/png".to_string());
+            media.filename = path.file_name().map(|n| n.to_string_lossy().into_owned());
+            media.size = Some(png.len());
+            media.local_path = Some(path.display().to_string());
+            let result = json!({
+                "path": path.display().to_string(),
+                "media": media,
+            });
+            serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+        }
+        other => Err(format!("Unknown format: '{}'. Use: png, unicode", other)),
+    }
+}
+
+fn decode(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let path_str = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: path".to_string())?;
+    let path = checked_path(workspace_dir, path_str)?;
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+
+    let texts = decode_qr_image(&path).map_err(|e| format!("{:#}", e))?;
+    match texts.as_slice() {
+        [] => Err(format!("No QR code found in '{}'", path.display())),
+        [text] => Ok(text.clone()),
+        _ => serde_json::to_string_pretty(&json!({ "codes": texts })).map_err(|e| e.to_string()),
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn checked_path(workspace_dir: &Path, p: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, p);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted qr access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+/// `qr_codes/qr-<unix ms>.png`, relative to the workspace.
+fn default_output() -> String {
+    let ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    format!("qr_codes/qr-{}.png", ms)
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn qr_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "One of: encode, decode.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "text".into(),
+            description: "For 'encode': the text or URL to put in the code.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "format".into(),
+            description: "For 'encode': 'png' (default) writes an image file; 'unicode' \
+                          returns the code drawn in block characters for a terminal."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "output_path".into(),
+            description: "For 'encode' as png: where to write the image. \
+                          Default: qr_codes/qr-<timestamp>.png in the workspace."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "path".into(),
+            description: "For 'decode': the image (PNG, JPEG, …) to read.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,365 @@
+//! Regex tool: find, replace and extract with regular expressions over a
+//! string or a file.
+//!
+//! Spares the agent sed/grep quoting and the differences between their GNU
+//! and BSD flavours.  Patterns use the `regex` crate's syntax, which has no
+//! look-around or backreferences in the pattern and matches in time linear
+//! in the input, so no pattern can backtrack catastrophically.  What is
+//! left is guarded here: compiled patterns are capped at
+//! [`MAX_PATTERN_BYTES`], input at [`MAX_INPUT_BYTES`], matches at
+//! [`MAX_LIMIT`] and each scan at [`MAX_SCAN`].
+
+use regex::{Captures, Regex, RegexBuilder};
+use serde_json::{Map, Value, json};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{
+    VAULT_ACCESS_DENIED, is_protected_path, open_file_read_safe, open_file_write_safe,
+    resolve_path, sandbox,
+};
+
+/// Largest input accepted, inline or from a file.
+pub const MAX_INPUT_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Compiled size a pattern may reach; larger ones are refused as too complex.
+const MAX_PATTERN_BYTES: usize = 1024 * 1024;
+
+/// Wall-clock budget for one pass over the input.
+const MAX_SCAN: Duration = Duration::from_secs(2);
+
+/// Matches returned unless `limit` is given.
+const DEFAULT_LIMIT: usize = 100;
+
+/// Upper bound on returned matches regardless of the requested `limit`.
+const MAX_LIMIT: usize = 1000;
+
+const ACTIONS: &str = "find_all, replace, extract";
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `regex` tool.
+#[instrument(skip(args, workspace_dir), fields(action))]
+pub fn exec_regex(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: action".to_string())?;
+    tracing::Span::current().record("action", action);
+    if !ACTIONS.split(", ").any(|a| a == action) {
+        return Err(format!("Unknown action: '{}'. Use: {}", action, ACTIONS));
+    }
+
+    let pattern = args
+        .get("pattern")
+        .and_then(|v| v.as_str())
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| "Missing required parameter: pattern".to_string())?;
+    let case_insensitive = args
+        .get("case_insensitive")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let re = compile(pattern, case_insensitive)?;
+    let (text, path) = read_input(args, workspace_dir)?;
+
+    match action {
+        "find_all" => Ok(find_all(&re, &text, limit(args))),
+        "extract" => extract(&re, &text, limit(args)),
+        "replace" => replace(&re, args, &text, path.as_deref()),
+        other => Err(format!("Unknown action: '{}'. Use: {}", other, ACTIONS)),
+    }
+}
+
+fn limit(args: &Value) -> usize {
+    args.get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|n| (n as usize).clamp(1, MAX_LIMIT))
+        .unwrap_or(DEFAULT_LIMIT)
+}
+
+fn compile(pattern: &str, case_insensitive: bool) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .size_limit(MAX_PATTERN_BYTES)
+        .dfa_size_limit(MAX_PATTERN_BYTES)
+        .build()
+        .map_err(|e| match e {
+            regex::Error::CompiledTooBig(limit) => format!(
+                "Pattern is too complex: it compiles to more than {} KB. Use smaller \
+                 repetition counts or split it into simpler patterns.",
+                limit / 1024
+            ),
+            other => format!("Invalid pattern: {}", other),
+        })
+}
+
+/// The text to search: the `input` string, or the contents of `path` (which
+/// is returned too, for in-place replacement).
+fn read_input(args: &Value, workspace_dir: &Path) -> Result<(String, Option<PathBuf>), String> {
+    let input = args.get("input").and_then(|v| v.as_str());
+    let path = args.get("path").and_then(|v| v.as_str());
+    match (input, path) {
+        (Some(text), None) => {
+            if text.len() as u64 > MAX_INPUT_BYTES {
+                return Err(format!(
+                    "Input is larger than {} MB",
+                    MAX_INPUT_BYTES / (1024 * 1024)
+                ));
+            }
+            Ok((text.to_string(), None))
+        }
+        (None, Some(path)) => {
+            let path = resolve_path(workspace_dir, path);
+            Ok((read_file_capped(&path)?, Some(path)))
+        }
+        (Some(_), Some(_)) => Err("Give either input or path, not both".to_string()),
+        (None, None) => Err("Missing required parameter: input or path".to_string()),
+    }
+}
+
+fn read_file_capped(path: &Path) -> Result<String, String> {
+    if is_protected_path(path) {
+        warn!(path = %path.display(), "Attempted regex access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(path)?;
+    }
+    let (file, _) = open_file_read_safe(path)
+        .map_err(|e| format!("Failed to open file '{}': {}", path.display(), e))?;
+    let mut bytes = Vec::new();
+    // One byte past the cap tells a file at the limit from a larger one.
+    file.take(MAX_INPUT_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read file '{}': {}", path.display(), e))?;
+    if bytes.len() as u64 > MAX_INPUT_BYTES {
+        return Err(format!(
+            "File '{}' is larger than {} MB",
+            path.display(),
+            MAX_INPUT_BYTES / (1024 * 1024)
+        ));
+    }
+    debug!(path = %path.display(), bytes = bytes.len(), "Read regex input");
+    String::from_utf8(bytes).map_err(|_| format!("File '{}' is not UTF-8 text", path.display()))
+}
+
+// ── Actions ─────────────────────────────────────────────────────────────────
+
+/// Up to `limit` matches, each with its position, line and capture groups.
+fn find_all(re: &Regex, text: &str, limit: usize) -> String {
+    let (found, stopped) = scan(re, text, limit);
+    let mut line = 1;
+    let mut counted_to = 0;
+    let matches: Vec<Value> = found
+        .iter()
+        .map(|caps| {
+            let m = caps.get(0).expect("group 0 is the whole match");
+            line += text[counted_to..m.start()].matches('\n').count();
+            counted_to = m.start();
+            let mut entry = json!({
+                "match": m.as_str(),
+                "start": m.start(),
+                "end": m.end(),
+                "line": line,
+            });
+            if caps.len() > 1 {
+                let groups: Vec<Value> = (1..caps.len())
+                    .map(|i| caps.get(i).map_or(Value::Null, |g| json!(g.as_str())))
+                    .collect();
+                entry["groups"] = Value::Array(groups);
+            }
+            let named = named_groups(re, caps);
+            if !named.is_empty() {
+                entry["named"] = Value::Object(named);
+            }
+            entry
+        })
+        .collect();
+    json!({
+        "count": matches.len(),
+        "truncated": stopped.is_some(),
+        "stopped_by": stopped,
+        "matches": matches,
+    })
+    .to_string()
+}
+
+/// One JSON object per match, keyed by the pattern's named groups.
+fn extract(re: &Regex, text: &str, limit: usize) -> Result<String, String> {
+    if re.capture_names().flatten().next().is_none() {
+        return Err(
+            "extract needs named groups, e.g. (?P<key>\\w+)=(?P<value>\\S+); \
+             use find_all for numbered groups"
+                .to_string(),
+        );
+    }
+    let (found, stopped) = scan(re, text, limit);
+    let records: Vec<Value> = found
+        .iter()
+        .map(|caps| Value::Object(named_groups(re, caps)))
+        .collect();
+    Ok(json!({
+        "count": records.len(),
+        "truncated": stopped.is_some(),
+        "stopped_by": stopped,
+        "records": records,
+    })
+    .to_string())
+}
+
+/// Replace matches, expanding `$1` / `${name}` in the replacement.  Returns
+/// the new text, or writes it back to `path` with `in_place`.  Nothing is
+/// changed if the scan runs out of time.
+fn replace(re: &Regex, args: &Value, text: &str, path: Option<&Path>) -> Result<String, String> {
+    let replacement = args
+        .get("replacement")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter for replace: replacement".to_string())?;
+    let in_place = args
+        .get("in_place")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if in_place && path.is_none() {
+        return Err("in_place needs a path".to_string());
+    }
+    // Unlike find_all, replace changes every match unless limited.
+    let max = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize);
+
+    let started = Instant::now();
+    let mut out = String::with_capacity(text.len());
+    let mut copied_to = 0;
+    let mut replaced = 0;
+    for caps in re.captures_iter(text) {
+        if max.is_some_and(|max| replaced >= max) {
+            break;
+        }
+        if started.elapsed() > MAX_SCAN {
+            return Err(format!(
+                "Replacement did not finish within {}s; nothing was changed. Narrow the \
+                 pattern or the input.",
+                MAX_SCAN.as_secs()
+            ));
+        }
+        let m = caps.get(0).expect("group 0 is the whole match");
+        out.push_str(&text[copied_to..m.start()]);
+        caps.expand(replacement, &mut out);
+        copied_to = m.end();
+        replaced += 1;
+    }
+    out.push_str(&text[copied_to..]);
+
+    match path.filter(|_| in_place) {
+        Some(path) => {
+            if replaced > 0 {
+                let (mut file, _) = open_file_write_safe(path)
+                    .map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+                file.write_all(out.as_bytes())
+                    .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+            }
+            debug!(path = %path.display(), replaced, "Replaced in place");
+            Ok(json!({ "path": path.display().to_string(), "replacements": replaced }).to_string())
+        }
+        None => Ok(json!({ "replacements": replaced, "text": out }).to_string()),
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Up to `limit` matches in order, and what stopped the scan early, if
+/// anything: `"limit"` when more matches remain, `"time"` when the scan ran
+/// past [`MAX_SCAN`].
+fn scan<'t>(re: &Regex, text: &'t str, limit: usize) -> (Vec<Captures<'t>>, Option<&'static str>) {
+    let started = Instant::now();
+    let mut found = Vec::new();
+    for caps in re.captures_iter(text) {
+        if found.len() == limit {
+            return (found, Some("limit"));
+        }
+        if started.elapsed() > MAX_SCAN {
+            warn!(matches = found.len(), "Regex scan ran out of time");
+            return (found, Some("time"));
+        }
+        found.push(caps);
+    }
+    (found, None)
+}
+
+fn named_groups(re: &Regex, caps: &Captures) -> Map<String, Value> {
+    re.capture_names()
+        .flatten()
+        .map(|name| {
+            let value = caps.name(name).map_or(Value::Null, |g| json!(g.as_str()));
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn regex_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "One of: find_all, replace, extract.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "pattern".into(),
+            description: "Regular expression (Rust regex syntax: no look-around). Inline \
+                          flags work, e.g. (?m) for per-line ^/$ or (?s) for '.' matching \
+                          newlines. Name groups with (?P<name>...)."
+                .into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "input".into(),
+            description: "Text to search. Give this or path.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "path".into(),
+            description: "File to search instead (UTF-8 text, max 10 MB).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "replacement".into(),
+            description: "For 'replace': the replacement text. $1 or ${name} inserts a \
+                          group; $$ is a literal $."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "in_place".into(),
+            description: "For 'replace' with path: write the result back to the file \
+                          instead of returning it. Default: false."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "case_insensitive".into(),
+            description: "Ignore case. Default: false.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "limit".into(),
+            description: "Most matches to return (default 100, max 1000), or for 'replace' \
+                          the most to replace (default: all)."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,260 @@
+//! Reminder tool: schedule a message back into the current conversation.
+//!
+//! Only the gateway knows which thread a tool call belongs to, so the tool
+//! itself just checks its arguments and returns them in a
+//! [`THREAD_UPDATE_MARKER`]; the gateway then calls [`apply_reminder`] with
+//! the foreground thread.  Reminders are one-shot cron jobs with a
+//! [`Payload::Reminder`] in the store at [`Config::reminders_dir`].  Each
+//! connection collects the ones due for its threads with
+//! [`take_due_reminders`] and runs them as user turns, so a reminder that
+//! comes due while no client is connected fires on the next connection.
+//!
+//! [`Config::reminders_dir`]: crate::config::Config::reminders_dir
+
+use chrono::Local;
+use serde_json::{Value, json};
+use std::path::Path;
+use tracing::{debug, instrument};
+
+use super::cron_tool::{fire_time, format_local, now_ms};
+use super::{THREAD_UPDATE_MARKER, ToolParam};
+use crate::cron::{CronJob, CronStore, Payload, RunEntry, RunStatus, Schedule, SessionTarget};
+
+/// Most reminders a thread may have pending at once.
+pub const MAX_PENDING_REMINDERS: usize = 10;
+
+const ACTIONS: &str = "set, list, cancel";
+
+/// A reminder whose time has come, already removed from the store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueReminder {
+    pub job_id: String,
+    pub thread_id: u64,
+    pub message: String,
+}
+
+impl DueReminder {
+    /// The user turn injected into the thread.
+    pub fn prompt(&self) -> String {
+        format!("[Reminder] {}", self.message)
+    }
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `reminder` tool: validate, then defer to the gateway.
+#[instrument(skip(args, _workspace_dir), fields(action))]
+pub fn exec_reminder(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let action = action(args)?;
+    tracing::Span::current().record("action", action);
+    match action {
+        "set" => {
+            message(args)?;
+            fire_time(args, Local::now(), "set")?;
+        }
+        "cancel" => {
+            job_id(args)?;
+        }
+        _ => {}
+    }
+    let update = json!({
+        "action": "reminder",
+        "args": args,
+    });
+    Ok(format!("{}{}", THREAD_UPDATE_MARKER, update))
+}
+
+/// Set, list or cancel reminders for `thread_id` in the store at
+/// `store_dir`.  Called by the gateway for the marker [`exec_reminder`]
+/// returns.
+pub fn apply_reminder(args: &Value, store_dir: &Path, thread_id: u64) -> Result<String, String> {
+    let mut store = CronStore::new(store_dir)?;
+    match action(args)? {
+        "set" => {
+            let message = message(args)?;
+            let when = fire_time(args, Local::now(), "set")?;
+            if pending(&store, thread_id).len() >= MAX_PENDING_REMINDERS {
+                return Err(format!(
+                    "This conversation already has {} pending reminders; cancel one first",
+                    MAX_PENDING_REMINDERS
+                ));
+            }
+            let job = CronJob::new(
+                Some(format!("Reminder for thread {}", thread_id)),
+                Schedule::At {
+                    at: when.with_timezone(&chrono::Utc).to_rfc3339(),
+                },
+                SessionTarget::Main,
+                Payload::Reminder {
+                    thread_id,
+                    message: message.to_string(),
+                },
+            );
+            let id = store.add(job)?;
+            debug!(job_id = %id, thread_id, at = %when, "Reminder set");
+            Ok(format!(
+                "Reminder {} set for {}. It will arrive in this conversation as a \
+                 message starting with [Reminder].",
+                id,
+                format_local(&when)
+            ))
+        }
+        "list" => {
+            let reminders = pending(&store, thread_id);
+            if reminders.is_empty() {
+                return Ok("No pending reminders in this conversation.".to_string());
+            }
+            let mut out = format!("{} pending reminder(s):\n", reminders.len());
+            for (job, message) in reminders {
+                let when = job
+                    .schedule
+                    .at_ms()
+                    .and_then(|ms| i64::try_from(ms).ok())
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .map(|t| format_local(&t.with_timezone(&Local)))
+                    .unwrap_or_else(|| "unknown time".to_string());
+                out.push_str(&format!("- {} at {}: {}\n", job.job_id, when, message));
+            }
+            Ok(out)
+        }
+        "cancel" => {
+            let id = job_id(args)?;
+            if !pending(&store, thread_id)
+                .iter()
+                .any(|(job, _)| job.job_id == id)
+            {
+                return Err(format!("No pending reminder '{}' in this conversation", id));
+            }
+            store.remove(id)?;
+            Ok(format!("Cancelled reminder {}.", id))
+        }
+        other => Err(format!("Unknown action: '{}'. Use: {}", other, ACTIONS)),
+    }
+}
+
+/// Remove and return the reminders for `thread_ids` that are due, oldest
+/// first.  A directory without a job store is skipped rather than created.
+pub fn take_due_reminders(
+    store_dir: &Path,
+    thread_ids: &[u64],
+) -> Result<Vec<DueReminder>, String> {
+    if !store_dir.join("jobs.json").exists() {
+        return Ok(Vec::new());
+    }
+    let ran_ms = now_ms();
+    let mut store = CronStore::new(store_dir)?;
+    let mut due: Vec<(u64, DueReminder)> = store
+        .due(ran_ms)
+        .into_iter()
+        .filter_map(|job| match &job.payload {
+            Payload::Reminder { thread_id, message } if thread_ids.contains(thread_id) => Some((
+                job.schedule.at_ms().unwrap_or_default(),
+                DueReminder {
+                    job_id: job.job_id.clone(),
+                    thread_id: *thread_id,
+                    message: message.clone(),
+                },
+            )),
+            _ => None,
+        })
+        .collect();
+    due.sort_by_key(|(at_ms, _)| *at_ms);
+
+    for (_, reminder) in &due {
+        store.record_run(&RunEntry {
+            job_id: reminder.job_id.clone(),
+            run_id: format!("run-{:x}", ran_ms),
+            started_ms: ran_ms,
+            finished_ms: Some(ran_ms),
+            status: RunStatus::Ok,
+            error: None,
+        })?;
+        store.finish_one_shot(&reminder.job_id, ran_ms, true)?;
+    }
+    Ok(due.into_iter().map(|(_, reminder)| reminder).collect())
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn action(args: &Value) -> Result<&str, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: action".to_string())?;
+    if !ACTIONS.split(", ").any(|a| a == action) {
+        return Err(format!("Unknown action: '{}'. Use: {}", action, ACTIONS));
+    }
+    Ok(action)
+}
+
+fn message(args: &Value) -> Result<&str, String> {
+    args.get("message")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .ok_or_else(|| "Missing message for set action".to_string())
+}
+
+fn job_id(args: &Value) -> Result<&str, String> {
+    args.get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing id for cancel action".to_string())
+}
+
+/// Enabled reminders for `thread_id` with their messages, soonest first.
+fn pending(store: &CronStore, thread_id: u64) -> Vec<(&CronJob, &str)> {
+    let mut reminders: Vec<(&CronJob, &str)> = store
+        .list(false)
+        .into_iter()
+        .filter_map(|job| match &job.payload {
+            Payload::Reminder {
+                thread_id: t,
+                message,
+            } if *t == thread_id => Some((job, message.as_str())),
+            _ => None,
+        })
+        .collect();
+    reminders.sort_by_key(|(job, _)| job.schedule.at_ms());
+    reminders
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn reminder_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "One of: set, list, cancel.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "message".into(),
+            description: "For 'set': what to remind yourself of. It comes back as a user \
+                          message starting with [Reminder]."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "at".into(),
+            description: "For 'set': when. RFC 3339, or local time as 'YYYY-MM-DD HH:MM' \
+                          or 'HH:MM' (next occurrence)."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "delayMinutes".into(),
+            description: "For 'set': fire this many minutes from now instead of 'at'.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "id".into(),
+            description: "For 'cancel': the reminder ID from 'set' or 'list'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
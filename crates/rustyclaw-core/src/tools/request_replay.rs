@@ -0,0 +1,826 @@
+//! Request replay tool: send a request copied from a browser or terminal.
+//!
+//! Takes a curl command line ("Copy as cURL") or a HAR entry (a request
+//! from "Save all as HAR"), turns it into one HTTP request, and returns
+//! the response status, headers and body.  `{{secret:NAME}}` in the URL,
+//! a header, the body or `-u` is replaced with the vault credential `NAME`
+//! just before sending, and the value is masked again in the response.
+//!
+//! Only hosts in `[request_replay] allowed_hosts` can be reached — none
+//! are by default — and web_fetch's SSRF checks apply as well.  Redirects
+//! are not followed: a 3xx is returned as is, with its `Location`.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use regex::Regex;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{LazyLock, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox, vault};
+use super::web::ssrf_check_blocking;
+use crate::config::RequestReplayConfig;
+use crate::secrets::{AccessContext, CredentialValue};
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Characters of response body returned unless `max_chars` says otherwise.
+const DEFAULT_MAX_CHARS: usize = 50_000;
+
+/// Shortest secret value masked in responses; masking every "1" or "ok"
+/// would garble the body without hiding anything.
+const MIN_MASKED_LEN: usize = 4;
+
+/// Headers dropped from an imported request.  The client sets the framing
+/// ones itself, and a copied `accept-encoding` can ask for a compression
+/// the client won't decode.
+const DROPPED_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "connection",
+    "transfer-encoding",
+    "accept-encoding",
+];
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*secret:([A-Za-z0-9_.-]+)\s*\}\}").unwrap());
+
+/// `[request_replay]` settings, set once at gateway startup.
+static CONFIG: OnceLock<RequestReplayConfig> = OnceLock::new();
+
+/// Called once from the gateway to apply `[request_replay]`.
+pub fn set_request_replay_config(config: RequestReplayConfig) {
+    let _ = CONFIG.set(config);
+}
+
+fn replay_config() -> RequestReplayConfig {
+    CONFIG.get().cloned().unwrap_or_default()
+}
+
+/// A request imported from a curl command or a HAR entry, placeholders
+/// still in place.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayRequest {
+    pub method: String,
+    pub url: String,
+    /// In the order given, names as written.
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    /// `user:password` from curl's `-u`, encoded only when the request is
+    /// sent so placeholders in it still work.
+    pub basic_auth: Option<String>,
+    /// curl options that were accepted but have no effect here.
+    pub ignored: Vec<String>,
+}
+
+impl ReplayRequest {
+    fn has_header(&self, name: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|(n, _)| n.eq_ignore_ascii_case(name))
+    }
+
+    fn default_header(&mut self, name: &str, value: &str) {
+        if !self.has_header(name) {
+            self.headers.push((name.to_string(), value.to_string()));
+        }
+    }
+}
+
+// ── curl ────────────────────────────────────────────────────────────────────
+
+/// curl options that take a value, by long name.
+const VALUE_OPTIONS: &[&str] = &[
+    "--request",
+    "--header",
+    "--data",
+    "--data-raw",
+    "--data-binary",
+    "--data-ascii",
+    "--data-urlencode",
+    "--json",
+    "--user",
+    "--user-agent",
+    "--referer",
+    "--cookie",
+    "--url",
+    "--output",
+    "--max-time",
+    "--connect-timeout",
+    "--write-out",
+    "--form",
+    "--upload-file",
+    "--proxy",
+];
+
+/// Options that only change how curl itself behaves: what it prints, where
+/// it writes, how long it waits.  `--location` and `--insecure` are here
+/// too — redirects are never followed and certificates are always checked.
+const IGNORED_OPTIONS: &[&str] = &[
+    "--silent",
+    "--show-error",
+    "--location",
+    "--insecure",
+    "--verbose",
+    "--include",
+    "--fail",
+    "--globoff",
+    "--compressed",
+    "--no-buffer",
+    "--http1.1",
+    "--http2",
+    "--output",
+    "--max-time",
+    "--connect-timeout",
+    "--write-out",
+];
+
+fn long_option(short: char) -> Option<&'static str> {
+    Some(match short {
+        'X' => "--request",
+        'H' => "--header",
+        'd' => "--data",
+        'u' => "--user",
+        'A' => "--user-agent",
+        'e' => "--referer",
+        'b' => "--cookie",
+        'G' => "--get",
+        'I' => "--head",
+        'F' => "--form",
+        'T' => "--upload-file",
+        'x' => "--proxy",
+        'o' => "--output",
+        'm' => "--max-time",
+        'w' => "--write-out",
+        's' => "--silent",
+        'S' => "--show-error",
+        'L' => "--location",
+        'k' => "--insecure",
+        'v' => "--verbose",
+        'i' => "--include",
+        'f' => "--fail",
+        'g' => "--globoff",
+        _ => return None,
+    })
+}
+
+/// Expand one command-line word into `(long option, attached value)`
+/// pairs: `-sSL` is three options and `-XPOST` is `--request POST`.
+fn expand_option(word: &str) -> Result<Vec<(&'static str, Option<String>)>, String> {
+    let unsupported = |option: &str| format!("Unsupported curl option '{}'", option);
+    if word.starts_with("--") {
+        let long = VALUE_OPTIONS
+            .iter()
+            .chain(IGNORED_OPTIONS)
+            .chain(&["--get", "--head"])
+            .copied()
+            .find(|o| *o == word)
+            .ok_or_else(|| unsupported(word))?;
+        return Ok(vec![(long, None)]);
+    }
+    let mut options = Vec::new();
+    for (i, c) in word[1..].char_indices() {
+        let long = long_option(c).ok_or_else(|| unsupported(&format!("-{}", c)))?;
+        if VALUE_OPTIONS.contains(&long) {
+            let rest = &word[1 + i + c.len_utf8()..];
+            options.push((long, (!rest.is_empty()).then(|| rest.to_string())));
+            break;
+        }
+        options.push((long, None));
+    }
+    Ok(options)
+}
+
+/// Parse a curl command line into a request.
+///
+/// Understands the options browsers put in "Copy as cURL" and the common
+/// ones people type: `-X`, `-H`, `-d`/`--data`/`--data-raw`/
+/// `--data-binary`, `--data-urlencode`, `--json`, `-u`, `-A`, `-e`, `-b`,
+/// `-G` and `-I`.  Output and verbosity options are ignored; anything else
+/// (forms, uploads, proxies, reading data from a file) is refused rather
+/// than sent differently from what curl would do.
+pub fn parse_curl(command: &str) -> Result<ReplayRequest, String> {
+    let mut words = shell_words(command)?.into_iter();
+    let mut request = ReplayRequest::default();
+    let mut method = None;
+    let mut data: Vec<String> = Vec::new();
+    let (mut get, mut head, mut json_body) = (false, false, false);
+
+    let mut first = true;
+    while let Some(word) = words.next() {
+        if std::mem::take(&mut first) && (word == "curl" || word.ends_with("/curl")) {
+            continue;
+        }
+        if !word.starts_with('-') {
+            if !request.url.is_empty() {
+                return Err("Only one URL can be replayed at a time".to_string());
+            }
+            request.url = word;
+            continue;
+        }
+        for (option, attached) in expand_option(&word)? {
+            let value = if VALUE_OPTIONS.contains(&option) {
+                match attached {
+                    Some(v) => v,
+                    None => words
+                        .next()
+                        .ok_or_else(|| format!("curl option '{}' needs a value", word))?,
+                }
+            } else {
+                String::new()
+            };
+            match option {
+                "--request" => method = Some(value.to_uppercase()),
+                "--header" => {
+                    let (name, value) = value
+                        .split_once(':')
+                        .ok_or_else(|| format!("Header '{}' has no ':'", value))?;
+                    request
+                        .headers
+                        .push((name.trim().to_string(), value.trim().to_string()));
+                }
+                "--data" | "--data-binary" | "--data-ascii" if value.starts_with('@') => {
+                    return Err(format!(
+                        "Reading request data from a file ('{}') is not supported; \
+                         put the body in the command instead",
+                        value
+                    ));
+                }
+                "--data" | "--data-raw" | "--data-binary" | "--data-ascii" => data.push(value),
+                "--data-urlencode" => data.push(urlencode_data(&value)),
+                "--json" => {
+                    data.push(value);
+                    json_body = true;
+                }
+                "--user" => request.basic_auth = Some(value),
+                "--user-agent" => request.headers.push(("User-Agent".to_string(), value)),
+                "--referer" => request.headers.push(("Referer".to_string(), value)),
+                "--cookie" if !value.contains('=') => {
+                    return Err(format!(
+                        "Reading cookies from a file ('{}') is not supported; \
+                         pass them as -b 'name=value'",
+                        value
+                    ));
+                }
+                "--cookie" => request.headers.push(("Cookie".to_string(), value)),
+                "--url" => {
+                    if !request.url.is_empty() {
+                        return Err("Only one URL can be replayed at a time".to_string());
+                    }
+                    request.url = value;
+                }
+                "--get" => get = true,
+                "--head" => head = true,
+                "--form" | "--upload-file" | "--proxy" => {
+                    return Err(format!("Unsupported curl option '{}'", option));
+                }
+                _ => request.ignored.push(option.to_string()),
+            }
+        }
+    }
+
+    if request.url.is_empty() {
+        return Err("The curl command has no URL".to_string());
+    }
+    // Like curl, a URL without a scheme is plain HTTP.
+    if !request.url.contains("://") {
+        request.url = format!("http://{}", request.url);
+    }
+    if get && !data.is_empty() {
+        let separator = if request.url.contains('?') { '&' } else { '?' };
+        request.url = format!("{}{}{}", request.url, separator, data.join("&"));
+        data.clear();
+    }
+    if !data.is_empty() {
+        if json_body {
+            request.default_header("Content-Type", "application/json");
+            request.default_header("Accept", "application/json");
+        } else {
+            request.default_header("Content-Type", "application/x-www-form-urlencoded");
+        }
+        request.body = Some(data.join("&"));
+    }
+    request.method = match method {
+        Some(m) => m,
+        None if head => "HEAD".to_string(),
+        None if request.body.is_some() => "POST".to_string(),
+        None => "GET".to_string(),
+    };
+    Ok(request)
+}
+
+/// `--data-urlencode` value: `name=content` encodes only the content.
+fn urlencode_data(value: &str) -> String {
+    match value.split_once('=') {
+        Some((name, content)) if !name.is_empty() => {
+            format!("{}={}", name, urlencoding::encode(content))
+        }
+        Some((_, content)) => urlencoding::encode(content).into_owned(),
+        None => urlencoding::encode(value).into_owned(),
+    }
+}
+
+/// Split a command line into words the way a POSIX shell would, for the
+/// quoting that copied curl commands use: single quotes, double quotes,
+/// `$'…'` strings, backslash escapes, and backslash-newline continuations.
+fn shell_words(input: &str) -> Result<Vec<String>, String> {
+    let unterminated = || "Unterminated quote in the curl command".to_string();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(escaped) => {
+                    word.push(escaped);
+                    in_word = true;
+                }
+                None => {}
+            },
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        '\'' => break,
+                        ch => word.push(ch),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        '"' => break,
+                        '\\' => match chars.next().ok_or_else(unterminated)? {
+                            '\n' => {}
+                            ch @ ('"' | '\\' | '$' | '`') => word.push(ch),
+                            ch => {
+                                word.push('\\');
+                                word.push(ch);
+                            }
+                        },
+                        ch => word.push(ch),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_word = true;
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        '\'' => break,
+                        '\\' => match chars.next().ok_or_else(unterminated)? {
+                            'n' => word.push('\n'),
+                            't' => word.push('\t'),
+                            'r' => word.push('\r'),
+                            ch @ ('\\' | '\'' | '"') => word.push(ch),
+                            ch => {
+                                word.push('\\');
+                                word.push(ch);
+                            }
+                        },
+                        ch => word.push(ch),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+// ── HAR ─────────────────────────────────────────────────────────────────────
+
+/// Take a request from HAR JSON: a whole HAR file (`log.entries[entry]`),
+/// one entry (`{"request": …}`), or a bare request object.
+pub fn parse_har(har: &Value, entry: usize) -> Result<ReplayRequest, String> {
+    let request = if let Some(entries) = har.pointer("/log/entries").and_then(|v| v.as_array()) {
+        entries
+            .get(entry)
+            .ok_or_else(|| {
+                format!(
+                    "The HAR has {} entries; there is no entry {}",
+                    entries.len(),
+                    entry
+                )
+            })?
+            .get("request")
+    } else if har.get("request").is_some() {
+        har.get("request")
+    } else {
+        Some(har)
+    };
+    let request = request
+        .and_then(|v| v.as_object())
+        .ok_or("The HAR entry has no request object")?;
+    let field = |name: &str| {
+        request
+            .get(name)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("The HAR request has no {}", name))
+    };
+
+    let mut replay = ReplayRequest {
+        method: field("method")?.to_uppercase(),
+        url: field("url")?.to_string(),
+        ..Default::default()
+    };
+    for header in request
+        .get("headers")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let (Some(name), Some(value)) = (
+            header.get("name").and_then(|v| v.as_str()),
+            header.get("value").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+        // HTTP/2 pseudo-headers (`:authority`, `:path`) are part of the URL.
+        if !name.starts_with(':') {
+            replay.headers.push((name.to_string(), value.to_string()));
+        }
+    }
+
+    if let Some(post) = request.get("postData") {
+        let text = post.get("text").and_then(|v| v.as_str());
+        let params = post.get("params").and_then(|v| v.as_array());
+        replay.body = match (text, params) {
+            (Some(text), _) => Some(text.to_string()),
+            (None, Some(params)) => Some(
+                params
+                    .iter()
+                    .map(|p| {
+                        let get = |k: &str| p.get(k).and_then(|v| v.as_str()).unwrap_or("");
+                        format!(
+                            "{}={}",
+                            urlencoding::encode(get("name")),
+                            urlencoding::encode(get("value"))
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("&"),
+            ),
+            (None, None) => None,
+        };
+        if let Some(mime) = post.get("mimeType").and_then(|v| v.as_str())
+            && !mime.is_empty()
+        {
+            replay.default_header("Content-Type", mime);
+        }
+    }
+    Ok(replay)
+}
+
+// ── Host allowlist ──────────────────────────────────────────────────────────
+
+/// Whether `host` matches an `allowed_hosts` entry: the exact name, or
+/// `*.example.com` for any subdomain of example.com (but not example.com
+/// itself).
+pub fn host_allowed(host: &str, allowed: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed.iter().any(|entry| {
+        let entry = entry.trim().trim_end_matches('.').to_ascii_lowercase();
+        match entry.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+            None => host == entry,
+        }
+    })
+}
+
+fn check_url(url: &str, config: &RequestReplayConfig) -> Result<url::Url, String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only http and https requests can be replayed".to_string());
+    }
+    let host = parsed.host_str().ok_or("URL has no host")?;
+    if config.allowed_hosts.is_empty() {
+        return Err(format!(
+            "Host '{}' is not allowed: no hosts are configured for network_request_replay. \
+             Add it to [request_replay] allowed_hosts in config.toml.",
+            host
+        ));
+    }
+    if !host_allowed(host, &config.allowed_hosts) {
+        return Err(format!(
+            "Host '{}' is not in the request_replay host allowlist. Allowed hosts: {}",
+            host,
+            config.allowed_hosts.join(", ")
+        ));
+    }
+    Ok(parsed)
+}
+
+// ── Secrets ─────────────────────────────────────────────────────────────────
+
+/// Credential names used by placeholders anywhere in the request, each once.
+fn secret_names(request: &ReplayRequest) -> Vec<String> {
+    let texts = [
+        Some(&request.url),
+        request.body.as_ref(),
+        request.basic_auth.as_ref(),
+    ];
+    let header_texts = request.headers.iter().flat_map(|(n, v)| [n, v]);
+    let mut names: Vec<String> = Vec::new();
+    for text in texts.into_iter().flatten().chain(header_texts) {
+        for cap in PLACEHOLDER.captures_iter(text) {
+            if !names.iter().any(|n| n == &cap[1]) {
+                names.push(cap[1].to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Read each named credential from the vault.  A username/password
+/// credential stands in as `username:password`, which is what `-u` wants.
+async fn load_secrets(names: &[String]) -> Result<Vec<(String, String)>, String> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    let vault = vault().ok_or("The vault is not available to fill {{secret:…}} placeholders")?;
+    let ctx = AccessContext::requested_by("tool:network_request_replay");
+    let mut mgr = vault.lock().await;
+    let mut secrets = Vec::with_capacity(names.len());
+    for name in names {
+        let (_, value) = mgr
+            .get_credential(name, &ctx)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("No credential named '{}' in the vault", name))?;
+        let value = match value {
+            CredentialValue::Single(secret) => secret.into_inner(),
+            CredentialValue::UserPass { username, password } => {
+                format!("{}:{}", username.as_str(), password.as_str())
+            }
+            _ => {
+                return Err(format!(
+                    "Credential '{}' is not a key, token or username/password credential",
+                    name
+                ));
+            }
+        };
+        secrets.push((name.clone(), value));
+    }
+    Ok(secrets)
+}
+
+fn substitute(text: &str, secrets: &[(String, String)]) -> String {
+    PLACEHOLDER
+        .replace_all(text, |cap: &regex::Captures| {
+            secrets
+                .iter()
+                .find(|(name, _)| name == &cap[1])
+                .map_or_else(|| cap[0].to_string(), |(_, value)| value.clone())
+        })
+        .into_owned()
+}
+
+/// Put placeholders back wherever a secret value shows up in the response.
+fn mask(text: &str, secrets: &[(String, String)]) -> String {
+    secrets
+        .iter()
+        .filter(|(_, value)| value.len() >= MIN_MASKED_LEN)
+        .fold(text.to_string(), |text, (name, value)| {
+            text.replace(value.as_str(), &format!("{{{{secret:{}}}}}", name))
+        })
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `network_request_replay` tool.
+#[instrument(skip(args, workspace_dir), fields(method, url))]
+pub async fn exec_network_request_replay_async(
+    args: &Value,
+    workspace_dir: &Path,
+) -> Result<String, String> {
+    let request = match (
+        args.get("curl").and_then(|v| v.as_str()),
+        args.get("har").and_then(|v| v.as_str()),
+    ) {
+        (Some(curl), None) => parse_curl(curl)?,
+        (None, Some(har)) => {
+            let entry = args.get("entry").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            parse_har(&read_har(har, workspace_dir).await?, entry)?
+        }
+        _ => return Err("Give exactly one of 'curl' or 'har'".to_string()),
+    };
+    let span = tracing::Span::current();
+    span.record("method", request.method.as_str());
+    span.record("url", request.url.as_str());
+
+    let config = replay_config();
+    // Refuse a disallowed host before anything is read from the vault.
+    check_url(&request.url, &config)?;
+    let names = secret_names(&request);
+
+    if args
+        .get("dry_run")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        let mut out = Map::new();
+        out.insert("dry_run".into(), json!(true));
+        out.insert("method".into(), json!(request.method));
+        out.insert("url".into(), json!(request.url));
+        let headers: Vec<String> = request
+            .headers
+            .iter()
+            .map(|(n, v)| format!("{}: {}", n, v))
+            .collect();
+        out.insert("headers".into(), json!(headers));
+        out.insert("body".into(), json!(request.body));
+        if let Some(auth) = &request.basic_auth {
+            out.insert("basic_auth".into(), json!(auth));
+        }
+        out.insert("secrets".into(), json!(names));
+        if !request.ignored.is_empty() {
+            out.insert("ignored_options".into(), json!(request.ignored));
+        }
+        return serde_json::to_string_pretty(&out).map_err(|e| e.to_string());
+    }
+
+    let secrets = load_secrets(&names).await?;
+    let url = substitute(&request.url, &secrets);
+    let parsed = check_url(&url, &config)?;
+    {
+        let url = url.clone();
+        tokio::task::spawn_blocking(move || ssrf_check_blocking(&url))
+            .await
+            .map_err(|e| format!("SSRF validation task failed: {e}"))??;
+    }
+
+    let method = reqwest::Method::from_bytes(request.method.as_bytes())
+        .map_err(|_| format!("Invalid HTTP method '{}'", request.method))?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .user_agent("RustyClaw/0.1 (network_request_replay tool)")
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut builder = client.request(method, parsed);
+    for (name, value) in &request.headers {
+        if DROPPED_HEADERS.iter().any(|d| name.eq_ignore_ascii_case(d)) {
+            continue;
+        }
+        builder = builder.header(name.as_str(), substitute(value, &secrets));
+    }
+    if let Some(auth) = &request.basic_auth {
+        let encoded = STANDARD.encode(substitute(auth, &secrets));
+        builder = builder.header("Authorization", format!("Basic {}", encoded));
+    }
+    if let Some(body) = &request.body {
+        builder = builder.body(substitute(body, &secrets));
+    }
+
+    debug!(secrets = secrets.len(), "Replaying request");
+    let started = Instant::now();
+    let response = builder.send().await.map_err(|e| {
+        warn!(error = %e, "Replayed request failed");
+        format!("HTTP request failed: {}", mask(&e.to_string(), &secrets))
+    })?;
+    let status = response.status();
+    let mut headers: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in response.headers() {
+        let value = mask(&String::from_utf8_lossy(value.as_bytes()), &secrets);
+        headers
+            .entry(name.as_str().to_string())
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read response body: {}", e))?;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let max_chars = args
+        .get("max_chars")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_MAX_CHARS, |n| n as usize);
+    let (body, truncated) = match std::str::from_utf8(&bytes) {
+        Ok(text) => {
+            let text = mask(text, &secrets);
+            let truncated = text.chars().count() > max_chars;
+            (
+                json!(text.chars().take(max_chars).collect::<String>()),
+                truncated,
+            )
+        }
+        Err(_) => (Value::Null, false),
+    };
+
+    let mut out = Map::new();
+    out.insert("method".into(), json!(request.method));
+    out.insert("url".into(), json!(request.url));
+    out.insert("status".into(), json!(status.as_u16()));
+    out.insert("reason".into(), json!(status.canonical_reason()));
+    out.insert("headers".into(), json!(headers));
+    out.insert("body".into(), body);
+    out.insert("body_bytes".into(), json!(bytes.len()));
+    out.insert("truncated".into(), json!(truncated));
+    out.insert("elapsed_ms".into(), json!(elapsed_ms));
+    if !request.ignored.is_empty() {
+        out.insert("ignored_options".into(), json!(request.ignored));
+    }
+    serde_json::to_string_pretty(&out).map_err(|e| e.to_string())
+}
+
+/// HAR JSON given inline, or the path of a `.har` file.
+async fn read_har(har: &str, workspace_dir: &Path) -> Result<Value, String> {
+    let text = if har.trim_start().starts_with('{') {
+        har.to_string()
+    } else {
+        let path = resolve_path(workspace_dir, har.trim());
+        if is_protected_path(&path) {
+            warn!(path = %path.display(), "Attempted request replay from protected path");
+            return Err(VAULT_ACCESS_DENIED.to_string());
+        }
+        if let Some(sb) = sandbox() {
+            sb.check_path(&path)?;
+        }
+        tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| format!("Failed to read HAR file '{}': {}", path.display(), e))?
+    };
+    serde_json::from_str(&text).map_err(|e| format!("Invalid HAR JSON: {}", e))
+}
+
+/// Sync stub for the static ToolDef.
+pub fn exec_network_request_replay_stub(
+    _args: &Value,
+    _workspace_dir: &Path,
+) -> Result<String, String> {
+    Err("network_request_replay requires async execution".into())
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn network_request_replay_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "curl".into(),
+            description: "A curl command line, e.g. from a browser's 'Copy as cURL'. Give this \
+                          or har."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "har".into(),
+            description: "HAR JSON (a whole HAR file, one entry, or a request object), or the \
+                          path of a .har file. Give this or curl."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "entry".into(),
+            description: "Index of the entry to replay from a HAR file's log.entries. \
+                          Default: 0."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "dry_run".into(),
+            description: "Show the parsed request and the vault secrets it needs without \
+                          sending it. Default: false."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "max_chars".into(),
+            description: "Most characters of response body to return. Default: 50000.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
@@ -2,6 +2,7 @@
 //!
 //! These tools use async I/O for process spawning and management.
 
+use super::env_var::session_env;
 use super::helpers::{
     VAULT_ACCESS_DENIED, command_references_credentials, is_protected_path, process_manager,
     resolve_path, run_sandboxed_command, validate_command_safe,
@@ -96,11 +97,16 @@ pub async fn exec_execute_command_async(
     }
 
     // For commands with yield support, use tokio::process
+    // Own process group on Unix so the whole tree can be killed if the
+    // call is cancelled (see `ChildGuard`).
     #[cfg(unix)]
     let mut child = Command::new("sh")
         .arg("-c")
         .arg(command)
         .current_dir(&cwd)
+        .envs(session_env())
+        .process_group(0)
+        .kill_on_drop(true)
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
@@ -112,6 +118,8 @@ pub async fn exec_execute_command_async(
         .arg("/C")
         .arg(command)
         .current_dir(&cwd)
+        .envs(session_env())
+        .kill_on_drop(true)
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
@@ -123,12 +131,15 @@ pub async fn exec_execute_command_async(
         .arg("-c")
         .arg(command)
         .current_dir(&cwd)
+        .envs(session_env())
+        .kill_on_drop(true)
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .spawn()
         .map_err(|e| format!("Failed to execute command: {}", e))?;
 
+    let mut guard = ChildGuard::new(&child);
     let yield_deadline = Instant::now() + Duration::from_millis(yield_ms);
     let timeout_deadline = Instant::now() + Duration::from_secs(timeout_secs);
 
@@ -144,6 +155,7 @@ pub async fn exec_execute_command_async(
                 match result {
                     Ok(Some(_status)) => {
                         // Process finished - collect output
+                        guard.disarm();
                         let output = child.wait_with_output().await
                             .map_err(|e| format!("Failed to get command output: {}", e))?;
                         return format_output_async(output, timeout_secs);
@@ -174,6 +186,48 @@ pub async fn exec_execute_command_async(
     }
 }
 
+/// Kills a foreground command's process group when dropped.
+///
+/// `kill_on_drop` only reaches the `sh` itself; anything it started
+/// (pipelines, `sleep`, build tools) would otherwise keep running after a
+/// timeout or after the tool future is dropped by cancellation.
+struct ChildGuard {
+    #[cfg(unix)]
+    pgid: Option<u32>,
+}
+
+impl ChildGuard {
+    fn new(child: &tokio::process::Child) -> Self {
+        #[cfg(not(unix))]
+        let _ = child;
+        Self {
+            #[cfg(unix)]
+            pgid: child.id(),
+        }
+    }
+
+    /// The process exited on its own; leave its (reapable) group alone.
+    fn disarm(&mut self) {
+        #[cfg(unix)]
+        {
+            self.pgid = None;
+        }
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.pgid.and_then(|p| i32::try_from(p).ok()) {
+            // SAFETY: plain syscall; a negative pid targets the process group
+            // created by `process_group(0)`, whose id is the child's pid.
+            unsafe {
+                libc::kill(-pgid, libc::SIGKILL);
+            }
+        }
+    }
+}
+
 /// Move a tokio child process to the sync ProcessManager for background execution.
 async fn background_child(
     child: tokio::process::Child,
@@ -349,6 +403,7 @@ fn exec_execute_command_sync(args: &Value, workspace_dir: &Path) -> Result<Strin
         .arg("-c")
         .arg(command)
         .current_dir(&cwd)
+        .envs(session_env())
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
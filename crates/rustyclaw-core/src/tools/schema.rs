@@ -49,16 +49,23 @@ fn resolve_params(tool: &ToolDef) -> Vec<ToolParam> {
         "list_directory" => list_directory_params(),
         "search_files" => search_files_params(),
         "find_files" => find_files_params(),
+        "move_file" => move_file_params(),
+        "copy_file" => copy_file_params(),
         "execute_command" => execute_command_params(),
         "web_fetch" => web_fetch_params(),
         "web_search" => web_search_params(),
+        "extract_links" => extract_links_params(),
+        "extract_structured" => extract_structured_params(),
         "process" => process_params(),
+        "env_var" => env_var_params(),
+        "kv_store" => kv_store_params(),
         "memory_search" => memory_search_params(),
         "memory_get" => memory_get_params(),
         "save_memory" => save_memory_params(),
         "search_history" => search_history_params(),
         "add_memory" => add_memory_params(),
         "cron" => cron_params(),
+        "reminder" => reminder_params(),
         "sessions_list" => sessions_list_params(),
         "sessions_spawn" => sessions_spawn_params(),
         "sessions_send" => sessions_send_params(),
@@ -66,13 +73,17 @@ fn resolve_params(tool: &ToolDef) -> Vec<ToolParam> {
         "session_status" => session_status_params(),
         "agents_list" => agents_list_params(),
         "apply_patch" => apply_patch_params(),
+        "watch_file" => watch_file_params(),
+        "watch_poll" => watch_poll_params(),
         "secrets_list" => secrets_list_params(),
         "secrets_get" => secrets_get_params(),
         "secrets_store" => secrets_store_params(),
         "secrets_set_policy" => secrets_set_policy_params(),
+        "secrets_generate" => secrets_generate_params(),
         "gateway" => gateway_params(),
         "message" => message_params(),
         "tts" => tts_params(),
+        "transcribe" => transcribe_params(),
         "image" => image_params(),
         "nodes" => nodes_params(),
         "browser" => browser_params(),
@@ -106,11 +117,13 @@ fn resolve_params(tool: &ToolDef) -> Vec<ToolParam> {
         "disk_usage" => disk_usage_params(),
         "classify_files" => classify_files_params(),
         "system_monitor" => system_monitor_params(),
+        "process_tree" => process_tree_params(),
         "battery_health" => battery_health_params(),
         "app_index" => app_index_params(),
         "cloud_browse" => cloud_browse_params(),
         "browser_cache" => browser_cache_params(),
         "screenshot" => screenshot_params(),
+        "ocr_screen" => ocr_screen_params(),
         "clipboard" => clipboard_params(),
         "audit_sensitive" => audit_sensitive_params(),
         "secure_delete" => secure_delete_params(),
@@ -129,6 +142,10 @@ fn resolve_params(tool: &ToolDef) -> Vec<ToolParam> {
         "npm_manage" => npm_manage_params(),
         "agent_setup" => agent_setup_params(),
         "pdf" => pdf_params(),
+        "pdf_extract" => pdf_extract_params(),
+        "pdf_create" => pdf_create_params(),
+        "extract_archive_member" => extract_archive_member_params(),
+        "convert_document" => convert_document_params(),
         "swarm_create" => swarm_create_params(),
         "swarm_list" => swarm_list_params(),
         "swarm_status" => swarm_status_params(),
@@ -137,6 +154,32 @@ fn resolve_params(tool: &ToolDef) -> Vec<ToolParam> {
         "swarm_templates" => swarm_templates_params(),
         "host_info" => kernel_tools::host_info_params(),
         "load_status" => kernel_tools::load_status_params(),
+        "env_info" => env_info_params(),
+        "git" => git_params(),
+        "lint" => lint_params(),
+        "docker" => docker_params(),
+        "http_server" => http_server_params(),
+        "network_request_replay" => network_request_replay_params(),
+        "csv" => csv_params(),
+        "parse_log" => parse_log_params(),
+        "text_stats" => text_stats_params(),
+        "encode" => encode_params(),
+        "regex" => regex_params(),
+        "qr" => qr_params(),
+        "image_transform" => image_transform_params(),
+        "screenshot_annotate" => screenshot_annotate_params(),
+        "weather" => weather_params(),
+        "time" => time_params(),
+        "timezone_meeting" => timezone_meeting_params(),
+        "color" => color_params(),
+        "validate" => validate_params(),
+        "dns" => dns_params(),
+        "math" => math_params(),
+        "translate" => translate_params(),
+        "translate_file" => translate_file_params(),
+        "proofread" => proofread_params(),
+        "summarize_url" => summarize_url_params(),
+        "ssh" => ssh_params(),
         "service_list" => service_tools::service_list_params(),
         "service_start" => service_tools::service_start_params(),
         "service_stop" => service_tools::service_stop_params(),
@@ -146,6 +189,16 @@ fn resolve_params(tool: &ToolDef) -> Vec<ToolParam> {
     }
 }
 
+/// Parameters of the tool called `name`, or an empty list for an unknown
+/// tool.  Used by clients that describe tools to the user.
+pub fn tool_params(name: &str) -> Vec<ToolParam> {
+    all_tools()
+        .into_iter()
+        .find(|t| t.name == name)
+        .map(resolve_params)
+        .unwrap_or_default()
+}
+
 /// OpenAI / OpenAI-compatible function-calling format.
 ///
 /// ```json
@@ -0,0 +1,559 @@
+//! Screenshot annotation: draw boxes, arrows and text labels onto an image.
+//!
+//! Meant for "click here" answers: capture the screen (or find the spot
+//! with `ocr_screen`, whose boxes use the same `x` / `y` / `width` /
+//! `height`), mark it up and send the copy.  Coordinates are pixels or
+//! percentages of the image's size, such as `"50%"`.
+//!
+//! Drawing is pure Rust — `embedded-graphics` over the `image` crate — and
+//! labels use a built-in bitmap font, so neither ImageMagick nor system
+//! fonts are needed.  The copy goes beside the input (or to `output_path`)
+//! through the same protected path and sandbox checks as the file tools,
+//! and comes back as a [`MediaRef`](crate::gateway::MediaRef).  Needs the
+//! `image-tools` feature.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tracing::{instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+
+/// Most annotations drawn in one call.
+pub const MAX_ANNOTATIONS: usize = 100;
+
+const DEFAULT_COLOR: &str = "red";
+
+/// Execute the `screenshot_annotate` tool.
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_screenshot_annotate(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let path_str = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: path".to_string())?;
+    let annotations = annotation_list(args)?;
+    let input = checked_path(workspace_dir, path_str)?;
+    if !input.is_file() {
+        return Err(format!("File not found: {}", input.display()));
+    }
+    imp::run(args, &annotations, workspace_dir, &input)
+}
+
+/// The `annotations` array.  Models sometimes send it, or its items, as
+/// JSON text, so strings are parsed.
+fn annotation_list(args: &Value) -> Result<Vec<Value>, String> {
+    let parse = |text: &str| {
+        serde_json::from_str::<Value>(text).map_err(|e| format!("Invalid annotations JSON: {}", e))
+    };
+    let list = match args.get("annotations") {
+        Some(Value::Array(items)) => items.clone(),
+        Some(Value::String(text)) => match parse(text)? {
+            Value::Array(items) => items,
+            item @ Value::Object(_) => vec![item],
+            _ => return Err("annotations must be an array of objects".to_string()),
+        },
+        _ => return Err("Missing required parameter: annotations".to_string()),
+    };
+    if list.is_empty() {
+        return Err("annotations is empty; give at least one rect, arrow or text".to_string());
+    }
+    if list.len() > MAX_ANNOTATIONS {
+        return Err(format!(
+            "{} annotations given; at most {} per image",
+            list.len(),
+            MAX_ANNOTATIONS
+        ));
+    }
+    list.into_iter()
+        .map(|item| match item {
+            Value::String(text) => parse(&text),
+            item => Ok(item),
+        })
+        .collect()
+}
+
+#[cfg(feature = "image-tools")]
+mod imp {
+    use super::*;
+    use crate::gateway::MediaRef;
+    use crate::tools::color::Rgb;
+    use embedded_graphics::mono_font::MonoTextStyleBuilder;
+    use embedded_graphics::mono_font::iso_8859_1::FONT_10X20;
+    use embedded_graphics::pixelcolor::{Rgb888, RgbColor};
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::{
+        Line, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, StrokeAlignment, Triangle,
+    };
+    use embedded_graphics::text::{Baseline, Text};
+    use image::{DynamicImage, ImageFormat, ImageReader, RgbaImage};
+    use serde_json::json;
+    use std::convert::Infallible;
+    use tracing::debug;
+
+    /// Pixel height of the built-in font at scale 1.
+    const FONT_HEIGHT: u32 = 20;
+
+    /// Longest label, in characters.
+    const MAX_LABEL_CHARS: usize = 200;
+
+    pub(super) fn run(
+        args: &Value,
+        annotations: &[Value],
+        workspace_dir: &Path,
+        input: &Path,
+    ) -> Result<String, String> {
+        let image = ImageReader::open(input)
+            .and_then(|r| r.with_guessed_format())
+            .map_err(|e| format!("Failed to read '{}': {}", input.display(), e))?
+            .decode()
+            .map_err(|e| format!("Failed to decode '{}': {}", input.display(), e))?;
+        let mut image = image.to_rgba8();
+        let (width, height) = image.dimensions();
+
+        let color = match args.get("color").and_then(|v| v.as_str()) {
+            Some(c) => Rgb::parse(c)?,
+            None => Rgb::parse(DEFAULT_COLOR)?,
+        };
+        // Defaults that stay visible on a 4K capture without swamping a
+        // small one.
+        let line_width = match args.get("line_width").and_then(|v| v.as_u64()) {
+            Some(w) => w.clamp(1, 50) as u32,
+            None => (width.min(height) / 250).max(3),
+        };
+        let text_scale = match args.get("font_size").and_then(|v| v.as_u64()) {
+            Some(size) => (size as f64 / FONT_HEIGHT as f64).round().clamp(1.0, 10.0) as u32,
+            None => (height / 40 / FONT_HEIGHT).max(1),
+        };
+        let pen = Pen {
+            color,
+            line_width,
+            text_scale,
+        };
+
+        for (i, annotation) in annotations.iter().enumerate() {
+            let shape = Shape::parse(annotation, width, height)
+                .map_err(|e| format!("Annotation {}: {}", i + 1, e))?;
+            shape.draw(&mut image, &pen, annotation)?;
+        }
+
+        let output = match args.get("output_path").and_then(|v| v.as_str()) {
+            Some(p) => checked_path(workspace_dir, p)?,
+            None => checked_path(workspace_dir, &default_output(input).to_string_lossy())?,
+        };
+        let format = ImageFormat::from_path(&output)
+            .ok()
+            .filter(|f| matches!(f, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP))
+            .ok_or_else(|| {
+                format!(
+                    "Cannot write '{}'; use a .png, .jpg or .webp output_path",
+                    output.display()
+                )
+            })?;
+        let mut encoded = Vec::new();
+        let image = match format {
+            // JPEG has no alpha channel.
+            ImageFormat::Jpeg => DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(image).to_rgb8()),
+            _ => DynamicImage::ImageRgba8(image),
+        };
+        image
+            .write_to(&mut std::io::Cursor::new(&mut encoded), format)
+            .map_err(|e| format!("Failed to encode '{}': {}", output.display(), e))?;
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+        }
+        std::fs::write(&output, &encoded)
+            .map_err(|e| format!("Failed to write '{}': {}", output.display(), e))?;
+        debug!(
+            path = %output.display(),
+            annotations = annotations.len(),
+            bytes = encoded.len(),
+            "Wrote annotated image"
+        );
+
+        let mut media = MediaRef::new(format.to_mime_type().to_string());
+        media.filename = output.file_name().map(|n| n.to_string_lossy().into_owned());
+        media.size = Some(encoded.len());
+        media.local_path = Some(output.display().to_string());
+        let out = json!({
+            "path": output.display().to_string(),
+            "width": width,
+            "height": height,
+            "annotations": annotations.len(),
+            "bytes": encoded.len(),
+            "media": media,
+        });
+        serde_json::to_string_pretty(&out).map_err(|e| e.to_string())
+    }
+
+    /// Defaults for every annotation in a call.
+    struct Pen {
+        color: Rgb,
+        line_width: u32,
+        text_scale: u32,
+    }
+
+    enum Shape {
+        Rect {
+            top_left: Point,
+            size: Size,
+            label: Option<String>,
+        },
+        Arrow {
+            from: Point,
+            to: Point,
+            label: Option<String>,
+        },
+        Text {
+            at: Point,
+            text: String,
+        },
+    }
+
+    impl Shape {
+        fn parse(annotation: &Value, width: u32, height: u32) -> Result<Self, String> {
+            let kind = annotation
+                .get("type")
+                .and_then(|v| v.as_str())
+                .ok_or("missing type (rect, arrow or text)")?;
+            let x = |name: &str| coordinate(annotation, name, width);
+            let y = |name: &str| coordinate(annotation, name, height);
+            let label = text_arg(annotation, "label")?;
+            match kind {
+                "rect" | "box" => {
+                    let (left, top) = (x("x")?, y("y")?);
+                    let (w, h) = (x("width")?, y("height")?);
+                    if w == 0 || h == 0 {
+                        return Err("width and height must be at least 1".to_string());
+                    }
+                    if left + w > width as i32 || top + h > height as i32 {
+                        return Err(format!(
+                            "{}x{} at ({}, {}) falls outside the {}x{} image",
+                            w, h, left, top, width, height
+                        ));
+                    }
+                    Ok(Shape::Rect {
+                        top_left: Point::new(left, top),
+                        size: Size::new(w as u32, h as u32),
+                        label,
+                    })
+                }
+                "arrow" => {
+                    let from = Point::new(x("x1")?, y("y1")?);
+                    let to = Point::new(x("x2")?, y("y2")?);
+                    if from == to {
+                        return Err("arrow starts and ends at the same point".to_string());
+                    }
+                    Ok(Shape::Arrow { from, to, label })
+                }
+                "text" | "label" => Ok(Shape::Text {
+                    at: Point::new(x("x")?, y("y")?),
+                    text: text_arg(annotation, "text")?.ok_or("text annotations need 'text'")?,
+                }),
+                other => Err(format!("unknown type '{}'; use rect, arrow or text", other)),
+            }
+        }
+
+        fn draw(&self, image: &mut RgbaImage, pen: &Pen, annotation: &Value) -> Result<(), String> {
+            let color = match annotation.get("color").and_then(|v| v.as_str()) {
+                Some(c) => Rgb::parse(c)?,
+                None => pen.color,
+            };
+            let stroke = Rgb888::new(color.r, color.g, color.b);
+            let line_width = pen.line_width;
+            let mut canvas = Canvas::new(image);
+            match self {
+                Shape::Rect {
+                    top_left,
+                    size,
+                    label,
+                } => {
+                    // Outside the box, so it doesn't cover what it marks.
+                    let style = PrimitiveStyleBuilder::new()
+                        .stroke_color(stroke)
+                        .stroke_width(line_width)
+                        .stroke_alignment(StrokeAlignment::Outside)
+                        .build();
+                    let _ = Rectangle::new(*top_left, *size)
+                        .into_styled(style)
+                        .draw(&mut canvas);
+                    if let Some(label) = label {
+                        let gap = line_width as i32;
+                        let above =
+                            top_left.y - gap - text_size(label, pen.text_scale).height as i32;
+                        let at = if above >= 0 {
+                            Point::new(top_left.x - gap, above)
+                        } else {
+                            Point::new(top_left.x, top_left.y + size.height as i32 + gap)
+                        };
+                        draw_label(image, label, at, color, pen.text_scale);
+                    }
+                }
+                Shape::Arrow { from, to, label } => {
+                    let (dx, dy) = ((to.x - from.x) as f64, (to.y - from.y) as f64);
+                    let length = dx.hypot(dy);
+                    let (ux, uy) = (dx / length, dy / length);
+                    let head = (line_width as f64 * 4.0).max(12.0).min(length);
+                    // The shaft stops where the head starts, so a thick
+                    // line doesn't poke past the tip.
+                    let base = (to.x as f64 - ux * head, to.y as f64 - uy * head);
+                    let side = |sign: f64| {
+                        Point::new(
+                            (base.0 - uy * head * 0.5 * sign).round() as i32,
+                            (base.1 + ux * head * 0.5 * sign).round() as i32,
+                        )
+                    };
+                    let base = Point::new(base.0.round() as i32, base.1.round() as i32);
+                    let _ = Line::new(*from, base)
+                        .into_styled(PrimitiveStyle::with_stroke(stroke, line_width))
+                        .draw(&mut canvas);
+                    let _ = Triangle::new(*to, side(1.0), side(-1.0))
+                        .into_styled(PrimitiveStyle::with_fill(stroke))
+                        .draw(&mut canvas);
+                    if let Some(label) = label {
+                        // At the tail, away from what the arrow points at.
+                        let size = text_size(label, pen.text_scale);
+                        let at = Point::new(
+                            if dx >= 0.0 {
+                                from.x - size.width as i32
+                            } else {
+                                from.x
+                            },
+                            if dy >= 0.0 {
+                                from.y - size.height as i32
+                            } else {
+                                from.y
+                            },
+                        );
+                        draw_label(image, label, at, color, pen.text_scale);
+                    }
+                }
+                Shape::Text { at, text } => draw_label(image, text, *at, color, pen.text_scale),
+            }
+            Ok(())
+        }
+    }
+
+    /// A pixel value, or a percentage of `extent` such as `"25%"`, that has
+    /// to land on the image.
+    fn coordinate(annotation: &Value, name: &str, extent: u32) -> Result<i32, String> {
+        let value = match annotation.get(name) {
+            Some(Value::Number(n)) => n.as_f64().unwrap_or(f64::NAN),
+            Some(Value::String(s)) => match s.trim().strip_suffix('%') {
+                Some(percent) => percent
+                    .trim()
+                    .parse::<f64>()
+                    .map(|p| p * extent as f64 / 100.0)
+                    .map_err(|_| format!("{} is '{}', not a number or percentage", name, s))?,
+                None => s
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("{} is '{}', not a number or percentage", name, s))?,
+            },
+            _ => return Err(format!("missing {}", name)),
+        };
+        if !(0.0..=extent as f64).contains(&value) {
+            return Err(format!(
+                "{} is {}, outside 0..{} for this image",
+                name, value, extent
+            ));
+        }
+        Ok(value.round() as i32)
+    }
+
+    fn text_arg(annotation: &Value, name: &str) -> Result<Option<String>, String> {
+        match annotation.get(name).and_then(|v| v.as_str()).map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(text) if text.chars().count() > MAX_LABEL_CHARS => Err(format!(
+                "{} is longer than {} characters",
+                name, MAX_LABEL_CHARS
+            )),
+            Some(text) => Ok(Some(text.to_string())),
+        }
+    }
+
+    /// Size of a label's tag, padding included.
+    fn text_size(text: &str, scale: u32) -> Size {
+        let columns = text.lines().map(|l| l.chars().count()).max().unwrap_or(0) as u32;
+        let rows = text.lines().count().max(1) as u32;
+        Size::new((columns * 10 + 4) * scale, (rows * FONT_HEIGHT + 4) * scale)
+    }
+
+    /// Text on a tag of `color`, kept inside the image.  The text is black
+    /// or white, whichever reads better on the tag.
+    fn draw_label(image: &mut RgbaImage, text: &str, at: Point, color: Rgb, scale: u32) {
+        let size = text_size(text, scale);
+        let (width, height) = image.dimensions();
+        let x = at.x.min(width as i32 - size.width as i32).max(0);
+        let y = at.y.min(height as i32 - size.height as i32).max(0);
+        let tag = Rgb888::new(color.r, color.g, color.b);
+        let ink = if color.luminance() > 0.4 {
+            Rgb888::BLACK
+        } else {
+            Rgb888::WHITE
+        };
+
+        // Drawn in font pixels, each painted as a scale x scale block.
+        let mut canvas = Canvas {
+            image,
+            origin: Point::new(x, y),
+            scale,
+        };
+        let font_size = Size::new(size.width / scale, size.height / scale);
+        let _ = Rectangle::new(Point::zero(), font_size)
+            .into_styled(PrimitiveStyle::with_fill(tag))
+            .draw(&mut canvas);
+        let style = MonoTextStyleBuilder::new()
+            .font(&FONT_10X20)
+            .text_color(ink)
+            .build();
+        let _ = Text::with_baseline(text, Point::new(2, 2), style, Baseline::Top).draw(&mut canvas);
+    }
+
+    /// An `embedded-graphics` target over an RGBA image.  Pixels outside
+    /// the image are dropped.
+    struct Canvas<'a> {
+        image: &'a mut RgbaImage,
+        origin: Point,
+        scale: u32,
+    }
+
+    impl<'a> Canvas<'a> {
+        fn new(image: &'a mut RgbaImage) -> Self {
+            Self {
+                image,
+                origin: Point::zero(),
+                scale: 1,
+            }
+        }
+    }
+
+    impl OriginDimensions for Canvas<'_> {
+        fn size(&self) -> Size {
+            let (width, height) = self.image.dimensions();
+            Size::new(width / self.scale, height / self.scale)
+        }
+    }
+
+    impl DrawTarget for Canvas<'_> {
+        type Color = Rgb888;
+        type Error = Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            let (width, height) = self.image.dimensions();
+            let scale = self.scale as i32;
+            for Pixel(point, color) in pixels {
+                let rgba = image::Rgba([color.r(), color.g(), color.b(), 255]);
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let x = self.origin.x + point.x * scale + dx;
+                        let y = self.origin.y + point.y * scale + dy;
+                        if (0..width as i32).contains(&x) && (0..height as i32).contains(&y) {
+                            self.image.put_pixel(x as u32, y as u32, rgba);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// `<stem>-annotated.png` beside the input.
+    fn default_output(input: &Path) -> PathBuf {
+        let stem = input
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "image".to_string());
+        input.with_file_name(format!("{}-annotated.png", stem))
+    }
+}
+
+#[cfg(not(feature = "image-tools"))]
+mod imp {
+    use super::*;
+
+    pub(super) fn run(
+        _args: &Value,
+        _annotations: &[Value],
+        _workspace_dir: &Path,
+        _input: &Path,
+    ) -> Result<String, String> {
+        Err("screenshot_annotate needs the image-tools feature, which this build lacks".to_string())
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn checked_path(workspace_dir: &Path, p: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, p);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted screenshot_annotate access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn screenshot_annotate_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "path".into(),
+            description: "Image to annotate, e.g. a screenshot.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "annotations".into(),
+            description: format!(
+                "Up to {} objects (or JSON strings of them), drawn in order. \
+                 {{\"type\": \"rect\", \"x\", \"y\", \"width\", \"height\", \"label\"?}} boxes a \
+                 region (ocr_screen boxes fit as-is); {{\"type\": \"arrow\", \"x1\", \"y1\", \
+                 \"x2\", \"y2\", \"label\"?}} points from (x1, y1) to (x2, y2); \
+                 {{\"type\": \"text\", \"x\", \"y\", \"text\"}} places a label. Coordinates \
+                 are pixels or percentages of the image such as \"50%\". Each may set its \
+                 own \"color\".",
+                MAX_ANNOTATIONS
+            ),
+            param_type: "array".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "color".into(),
+            description: format!(
+                "Default color: a CSS name, #hex or rgb(...) (default: {}).",
+                DEFAULT_COLOR
+            ),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "line_width".into(),
+            description: "Stroke width in pixels (default: scaled to the image).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "font_size".into(),
+            description: "Label height in pixels, rounded to a multiple of 20 (default: \
+                          scaled to the image)."
+                .into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "output_path".into(),
+            description: "Where to write the result (.png, .jpg or .webp). Default: \
+                          <name>-annotated.png beside the input."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,41 @@
+//! The session a tool call runs for.
+//!
+//! Tools that keep state per session, such as `env_var`, look the session
+//! up here instead of in a process-wide setting, so two connections
+//! running turns at once each see their own.  The gateway runs every chat
+//! turn inside [`scope`] with the conversation thread it serves, much as
+//! requests carry their [`request_ids`](crate::observability::request_ids).
+//! Calls made outside a scope share [`DEFAULT_SESSION`].
+
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: String;
+}
+
+/// Session of tool calls made outside [`scope`].
+pub const DEFAULT_SESSION: &str = "default";
+
+/// Run `f` with its tool calls belonging to `session`.
+pub async fn scope<F: Future>(session: impl Into<String>, f: F) -> F::Output {
+    CURRENT.scope(session.into(), f).await
+}
+
+/// Run the blocking `f` in `session`, if any.  For work moved off the
+/// turn's task, which doesn't inherit [`scope`].
+pub(crate) fn sync_scope<R>(session: Option<String>, f: impl FnOnce() -> R) -> R {
+    match session {
+        Some(session) => CURRENT.sync_scope(session, f),
+        None => f(),
+    }
+}
+
+/// The session of the running tool call, if it was given one.
+pub fn current() -> Option<String> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// The session of the running tool call, or [`DEFAULT_SESSION`].
+pub(crate) fn current_or_default() -> String {
+    current().unwrap_or_else(|| DEFAULT_SESSION.to_string())
+}
@@ -0,0 +1,431 @@
+//! `ssh` tool: run a command on a configured remote host.
+//!
+//! Hosts come from `[ssh_hosts.<alias>]` in config and the agent names them
+//! by alias — an alias that is not configured cannot be reached, so the
+//! config doubles as the host allowlist.  Keys and passwords are vault
+//! credentials named in the host entry and read through the normal access
+//! policy; they never pass through the model.
+//!
+//! The gateway intercepts the tool because it holds the live config, and
+//! calls [`run_ssh`] with the [`RusshTransport`].  Tests substitute their
+//! own [`SshTransport`].
+
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, instrument, warn};
+
+use super::{SharedVault, ToolParam};
+use crate::config::SshHostConfig;
+use crate::secrets::{AccessContext, CredentialValue, SecretString};
+
+/// Command timeout when the call does not set one.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Longest command timeout a call may ask for.
+pub const MAX_TIMEOUT_SECS: u64 = 600;
+
+/// Time allowed to connect and authenticate.
+const CONNECT_TIMEOUT_SECS: u64 = 15;
+
+/// Bytes kept from each of stdout and stderr.
+const MAX_OUTPUT_BYTES: usize = 100_000;
+
+/// How to authenticate to a host.
+pub enum SshAuth {
+    /// Private key in OpenSSH format.
+    Key(SecretString),
+    Password(SecretString),
+}
+
+/// Result of a remote command.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SshOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the server closed the channel without an exit status.
+    pub exit_code: Option<u32>,
+    /// Output beyond [`MAX_OUTPUT_BYTES`] per stream was dropped.
+    pub truncated: bool,
+    /// SHA-256 fingerprint of the key the host presented.
+    pub host_key: Option<String>,
+}
+
+/// Connects to a host and runs one command.
+pub trait SshTransport {
+    /// Run `command` on `host`, writing `stdin` (if any) to it first.
+    fn run(
+        &self,
+        host: &SshHostConfig,
+        auth: &SshAuth,
+        command: &str,
+        stdin: Option<&str>,
+    ) -> impl Future<Output = Result<SshOutput, String>> + Send;
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Sync stub for the static ToolDef — the gateway intercepts `ssh` because
+/// it needs the configured hosts.
+pub fn exec_ssh_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("ssh is executed by the gateway (it needs the configured hosts)".into())
+}
+
+/// Run `args.command` on the host aliased by `args.host`.
+///
+/// Returns JSON with `stdout`, `stderr` and `exit_code`.  A non-zero exit
+/// code is a successful tool call; connection, authentication and timeout
+/// failures are errors.
+#[instrument(skip(args, hosts, vault, transport), fields(host))]
+pub async fn run_ssh<T: SshTransport>(
+    args: &Value,
+    hosts: &HashMap<String, SshHostConfig>,
+    vault: &SharedVault,
+    transport: &T,
+) -> Result<String, String> {
+    let alias = args
+        .get("host")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: host".to_string())?;
+    tracing::Span::current().record("host", alias);
+    let command = args
+        .get("command")
+        .and_then(|v| v.as_str())
+        .filter(|c| !c.trim().is_empty())
+        .ok_or_else(|| "Missing required parameter: command".to_string())?;
+    let sudo = args.get("sudo").and_then(|v| v.as_bool()).unwrap_or(false);
+    let timeout_secs = args
+        .get("timeout")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(1, MAX_TIMEOUT_SECS);
+
+    let host = lookup_host(hosts, alias)?;
+    if sudo && !host.allow_sudo {
+        return Err(format!(
+            "sudo is not allowed on '{}'. Set allow_sudo = true in [ssh_hosts.{}] to permit it.",
+            alias, alias
+        ));
+    }
+
+    // Without a pinned key, the host we reach may not be the one configured;
+    // don't hand it a password or run anything as root there.
+    if host.fingerprint.is_none() && (sudo || host.key.is_none()) {
+        return Err(format!(
+            "'{}' has no pinned host key, so it is not sent passwords or sudo commands. \
+             Set fingerprint = \"SHA256:...\" in [ssh_hosts.{}] \
+             (`ssh-keyscan {} | ssh-keygen -lf -` shows it).",
+            alias, alias, host.host
+        ));
+    }
+
+    let auth = host_auth(host, alias, vault).await?;
+    let sudo_password = match (&auth, sudo) {
+        (SshAuth::Password(pw), true) => Some(format!("{}\n", pw.as_str())),
+        _ => None,
+    };
+    let remote = remote_command(command, sudo, sudo_password.is_some());
+
+    debug!(user = %host.user, port = host.port, sudo, "Running remote command");
+    let output = tokio::time::timeout(
+        Duration::from_secs(timeout_secs),
+        transport.run(host, &auth, &remote, sudo_password.as_deref()),
+    )
+    .await
+    .map_err(|_| format!("Command on '{}' timed out after {}s", alias, timeout_secs))??;
+
+    let mut result = json!({
+        "host": alias,
+        "exit_code": output.exit_code,
+        "stdout": output.stdout,
+        "stderr": output.stderr,
+    });
+    if output.truncated {
+        result["truncated"] = json!(true);
+    }
+    // Unpinned hosts: show the key so the user can pin it.
+    if host.fingerprint.is_none()
+        && let Some(fp) = output.host_key
+    {
+        result["host_key"] = json!(fp);
+    }
+    Ok(result.to_string())
+}
+
+/// The configured host for `alias`, or an error naming the allowed aliases.
+pub(crate) fn lookup_host<'a>(
+    hosts: &'a HashMap<String, SshHostConfig>,
+    alias: &str,
+) -> Result<&'a SshHostConfig, String> {
+    if let Some(host) = hosts.get(alias) {
+        return Ok(host);
+    }
+    warn!(host = alias, "ssh to a host that is not configured");
+    if hosts.is_empty() {
+        return Err(
+            "No SSH hosts are configured. Add an [ssh_hosts.<alias>] entry to config.toml."
+                .to_string(),
+        );
+    }
+    let mut aliases: Vec<&str> = hosts.keys().map(String::as_str).collect();
+    aliases.sort_unstable();
+    Err(format!(
+        "Host '{}' is not in the SSH host allowlist. Configured hosts: {}",
+        alias,
+        aliases.join(", ")
+    ))
+}
+
+/// Read the host's key (preferred) or password from the vault.
+async fn host_auth(
+    host: &SshHostConfig,
+    alias: &str,
+    vault: &SharedVault,
+) -> Result<SshAuth, String> {
+    // Same context as `secrets_get`: the tool call is not user approval.
+    let ctx = AccessContext::requested_by("tool:ssh");
+    let (name, want_key) = match (&host.key, &host.password) {
+        (Some(key), _) => (key, true),
+        (None, Some(password)) => (password, false),
+        (None, None) => {
+            return Err(format!(
+                "Host '{}' has no key or password credential configured",
+                alias
+            ));
+        }
+    };
+
+    let mut mgr = vault.lock().await;
+    let (_, value) = mgr
+        .get_credential(name, &ctx)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| {
+            format!(
+                "Credential '{}' for host '{}' not found in the vault",
+                name, alias
+            )
+        })?;
+    match (value, want_key) {
+        (CredentialValue::SshKeyPair { private_key, .. }, true)
+        | (CredentialValue::Single(private_key), true) => Ok(SshAuth::Key(private_key)),
+        (CredentialValue::UserPass { password, .. }, false)
+        | (CredentialValue::Single(password), false) => Ok(SshAuth::Password(password)),
+        _ => Err(format!(
+            "Credential '{}' is not {} credential",
+            name,
+            if want_key { "an SSH key" } else { "a password" }
+        )),
+    }
+}
+
+/// The command line sent to the host.
+///
+/// With `sudo`, the command runs under `sh -c` so pipes and redirects are
+/// elevated too.  Key-authenticated hosts use `sudo -n` (passwordless sudo
+/// or a clear failure); password hosts read the password from stdin.
+pub(crate) fn remote_command(command: &str, sudo: bool, password_on_stdin: bool) -> String {
+    if !sudo {
+        return command.to_string();
+    }
+    let flags = if password_on_stdin { "-S -p ''" } else { "-n" };
+    format!("sudo {} -- sh -c {}", flags, shell_quote(command))
+}
+
+/// Single-quote `s` for a POSIX shell.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+// ── russh transport ─────────────────────────────────────────────────────────
+
+/// [`SshTransport`] over a fresh `russh` connection per command.
+pub struct RusshTransport;
+
+impl SshTransport for RusshTransport {
+    fn run(
+        &self,
+        host: &SshHostConfig,
+        auth: &SshAuth,
+        command: &str,
+        stdin: Option<&str>,
+    ) -> impl Future<Output = Result<SshOutput, String>> + Send {
+        russh_run(host, auth, command, stdin)
+    }
+}
+
+/// Whether a host presenting `fingerprint` may be used, given the pinned
+/// one.  Unpinned hosts are accepted here; [`run_ssh`] keeps passwords and
+/// sudo away from them.
+pub(crate) fn host_key_matches(pinned: Option<&str>, fingerprint: &str) -> bool {
+    pinned.is_none_or(|pinned| pinned.trim() == fingerprint)
+}
+
+/// Checks the server key against the pinned fingerprint, if any, and
+/// records the key it saw.
+struct HostKeyCheck {
+    expected: Option<String>,
+    seen: Arc<Mutex<Option<String>>>,
+}
+
+impl russh::client::Handler for HostKeyCheck {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &russh::keys::PublicKey,
+    ) -> Result<bool, Self::Error> {
+        let fp = server_public_key
+            .fingerprint(russh::keys::HashAlg::Sha256)
+            .to_string();
+        let ok = host_key_matches(self.expected.as_deref(), &fp);
+        if !ok {
+            warn!(fingerprint = %fp, "SSH host key does not match the pinned fingerprint");
+        }
+        if let Ok(mut seen) = self.seen.lock() {
+            *seen = Some(fp);
+        }
+        Ok(ok)
+    }
+}
+
+async fn russh_run(
+    host: &SshHostConfig,
+    auth: &SshAuth,
+    command: &str,
+    stdin: Option<&str>,
+) -> Result<SshOutput, String> {
+    use russh::ChannelMsg;
+    use russh::client::{self, AuthResult};
+    use russh::keys::PrivateKeyWithHashAlg;
+
+    let target = format!("{}@{}:{}", host.user, host.host, host.port);
+    let seen = Arc::new(Mutex::new(None));
+    let handler = HostKeyCheck {
+        expected: host.fingerprint.clone(),
+        seen: seen.clone(),
+    };
+    let config = Arc::new(client::Config::default());
+
+    let connect = async {
+        let mut session = client::connect(config, (host.host.as_str(), host.port), handler)
+            .await
+            .map_err(|e| match e {
+                russh::Error::UnknownKey => format!(
+                    "Host key for {} does not match the configured fingerprint",
+                    target
+                ),
+                e => format!("Failed to connect to {}: {}", target, e),
+            })?;
+        let result = match auth {
+            SshAuth::Key(pem) => {
+                let key = russh::keys::decode_secret_key(pem.as_str(), None)
+                    .map_err(|e| format!("Invalid private key: {}", e))?;
+                let hash = session
+                    .best_supported_rsa_hash()
+                    .await
+                    .ok()
+                    .flatten()
+                    .flatten();
+                session
+                    .authenticate_publickey(
+                        &host.user,
+                        PrivateKeyWithHashAlg::new(Arc::new(key), hash),
+                    )
+                    .await
+            }
+            SshAuth::Password(pw) => session.authenticate_password(&host.user, pw.as_str()).await,
+        }
+        .map_err(|e| format!("Authentication to {} failed: {}", target, e))?;
+        if !matches!(result, AuthResult::Success) {
+            return Err(format!("Authentication to {} was rejected", target));
+        }
+        Ok(session)
+    };
+    let session = tokio::time::timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS), connect)
+        .await
+        .map_err(|_| format!("Timed out connecting to {}", target))??;
+
+    let mut channel = session
+        .channel_open_session()
+        .await
+        .map_err(|e| format!("Failed to open a session on {}: {}", target, e))?;
+    channel
+        .exec(true, command)
+        .await
+        .map_err(|e| format!("Failed to start the command on {}: {}", target, e))?;
+    if let Some(input) = stdin {
+        channel
+            .data(input.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to the command on {}: {}", target, e))?;
+    }
+    let _ = channel.eof().await;
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let mut exit_code = None;
+    let mut truncated = false;
+    while let Some(msg) = channel.wait().await {
+        match msg {
+            ChannelMsg::Data { data } => truncated |= push_capped(&mut stdout, &data),
+            ChannelMsg::ExtendedData { data, ext: 1 } => {
+                truncated |= push_capped(&mut stderr, &data)
+            }
+            ChannelMsg::ExitStatus { exit_status } => exit_code = Some(exit_status),
+            _ => {}
+        }
+    }
+    let _ = session
+        .disconnect(russh::Disconnect::ByApplication, "", "en")
+        .await;
+
+    let host_key = seen.lock().ok().and_then(|s| s.clone());
+    Ok(SshOutput {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        exit_code,
+        truncated,
+        host_key,
+    })
+}
+
+/// Append `data` to `buf` up to [`MAX_OUTPUT_BYTES`]; true if any was dropped.
+fn push_capped(buf: &mut Vec<u8>, data: &[u8]) -> bool {
+    let room = MAX_OUTPUT_BYTES.saturating_sub(buf.len());
+    buf.extend_from_slice(&data[..data.len().min(room)]);
+    data.len() > room
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn ssh_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "host".into(),
+            description: "Alias of a host configured under [ssh_hosts] (not a hostname).".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "command".into(),
+            description: "Shell command to run on the host.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "timeout".into(),
+            description: "Seconds to wait for the command (default 60, max 600).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "sudo".into(),
+            description: "Run the command with sudo. Only on hosts with allow_sudo.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,335 @@
+//! Web page summaries: fetch a page, keep the readable part, and have the
+//! model boil it down to a summary and a handful of key points.
+//!
+//! Fetching goes through the same path as `web_fetch` (and `web_scrape`),
+//! so private and loopback addresses are refused, redirects included.
+//! Navigation, headers, footers and scripts are dropped before anything
+//! reaches the model.  A page too long for one prompt is split into
+//! chunks; each chunk is reduced to notes and the notes are summarised
+//! together.
+//!
+//! As with [`proofread`](super::proofread), the gateway makes the model
+//! calls and passes them in to [`summarize_url_with`].
+
+use serde_json::Value;
+use std::future::Future;
+use std::path::Path;
+
+use super::ToolParam;
+
+/// Characters of page text sent to the model in one request.
+pub const MAX_CHUNK_CHARS: usize = 12_000;
+
+/// Chunks read from one page; anything past this is left out and the
+/// result is marked `truncated`.
+#[cfg(feature = "web-tools")]
+const MAX_CHUNKS: usize = 16;
+
+#[cfg(feature = "web-tools")]
+const DEFAULT_MAX_LINKS: usize = 20;
+
+/// How long the summary should be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Length {
+    Short,
+    Medium,
+    Long,
+}
+
+impl Length {
+    pub fn from_args(args: &Value) -> Result<Self, String> {
+        match args
+            .get("length")
+            .and_then(|v| v.as_str())
+            .unwrap_or("medium")
+        {
+            "short" => Ok(Self::Short),
+            "medium" => Ok(Self::Medium),
+            "long" => Ok(Self::Long),
+            other => Err(format!(
+                "Unknown length: '{}'. Use: short, medium, long",
+                other
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Short => "short",
+            Self::Medium => "medium",
+            Self::Long => "long",
+        }
+    }
+
+    /// Most words allowed in the summary.
+    pub fn max_words(self) -> usize {
+        match self {
+            Self::Short => 80,
+            Self::Medium => 200,
+            Self::Long => 400,
+        }
+    }
+
+    /// Most key points returned.
+    pub fn max_points(self) -> usize {
+        match self {
+            Self::Short => 3,
+            Self::Medium => 5,
+            Self::Long => 8,
+        }
+    }
+}
+
+/// The JSON object the model is asked to return for the final summary.
+#[cfg(feature = "web-tools")]
+#[derive(Debug, serde::Deserialize)]
+struct SummaryReply {
+    summary: String,
+    #[serde(default)]
+    key_points: Vec<String>,
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Sync entry point for the static ToolDef; the gateway intercepts
+/// `summarize_url` because it owns the provider connection.
+pub fn exec_summarize_url(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("summarize_url is executed by the gateway".into())
+}
+
+/// Summarise the page at `args.url` (or the HTML in `args.content`),
+/// using `call_model` for every model request.
+///
+/// `call_model` receives a complete prompt and returns the model's reply.
+/// Returns a JSON object with the `summary`, its `key_points`, and the
+/// page's `links` when `include_links` is set.
+#[cfg(feature = "web-tools")]
+#[tracing::instrument(skip(args, call_model))]
+pub async fn summarize_url_with<F, Fut>(args: &Value, mut call_model: F) -> Result<String, String>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    use serde_json::json;
+
+    use super::translate::{Segment, chunk_segments};
+    use super::web::readable_content;
+    use super::web_scrape::{Scope, extract_links, load_page};
+
+    let length = Length::from_args(args)?;
+    let include_links = args
+        .get("include_links")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let max_links = args
+        .get("max_links")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_MAX_LINKS);
+
+    let page = load_page(args).await?;
+    let title = page_title(&page.html);
+    let text = readable_content(&page.html, "text");
+    if text.trim().is_empty() {
+        return Err("The page has no readable text to summarise".into());
+    }
+
+    let mut chunks: Vec<String> = chunk_segments(vec![Segment::Prose(text)], MAX_CHUNK_CHARS)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Prose(p) if !p.trim().is_empty() => Some(p.trim().to_string()),
+            _ => None,
+        })
+        .collect();
+    let truncated = chunks.len() > MAX_CHUNKS;
+    chunks.truncate(MAX_CHUNKS);
+    let chunk_count = chunks.len();
+    tracing::debug!(chunks = chunk_count, truncated, "Summarising page");
+
+    // Map: reduce each chunk to notes.  A page that fits in one chunk goes
+    // straight to the final prompt.
+    let source = if chunk_count == 1 {
+        chunks.remove(0)
+    } else {
+        let mut notes = Vec::with_capacity(chunk_count);
+        for (i, chunk) in chunks.iter().enumerate() {
+            let prompt = notes_prompt(chunk, title.as_deref(), i + 1, chunk_count);
+            notes.push(call_model(prompt).await?.trim().to_string());
+        }
+        notes.join("\n\n")
+    };
+
+    // Reduce: one summary of the page (or of the notes).
+    let reply = call_model(summary_prompt(
+        &source,
+        title.as_deref(),
+        length,
+        chunk_count > 1,
+    ))
+    .await?;
+    let reply = parse_reply(&reply)?;
+
+    let mut out = json!({
+        "url": page.url.as_ref().map(|u| u.to_string()),
+        "title": title,
+        "length": length.name(),
+        "summary": clip_words(&reply.summary, length.max_words()),
+        "key_points": reply
+            .key_points
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .take(length.max_points())
+            .collect::<Vec<_>>(),
+        "chunks": chunk_count,
+        "truncated": truncated,
+    });
+    if include_links {
+        out["links"] = extract_links(&page, Scope::All, max_links)?["links"].take();
+    }
+    Ok(out.to_string())
+}
+
+#[cfg(not(feature = "web-tools"))]
+pub async fn summarize_url_with<F, Fut>(_args: &Value, _call_model: F) -> Result<String, String>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    Err("summarize_url requires the 'web-tools' feature".into())
+}
+
+#[cfg(feature = "web-tools")]
+fn page_title(html: &str) -> Option<String> {
+    let doc = scraper::Html::parse_document(html);
+    let selector = scraper::Selector::parse("title").expect("static selector");
+    let title = doc
+        .select(&selector)
+        .next()?
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+// ── Model prompts and reply ─────────────────────────────────────────────────
+
+/// Prompt for the map step: notes on one chunk of a long page.
+pub fn notes_prompt(chunk: &str, title: Option<&str>, part: usize, total: usize) -> String {
+    let title = title
+        .map(|t| format!(" of the page \"{}\"", t))
+        .unwrap_or_default();
+    format!(
+        "The text between the <page> tags is part {part} of {total}{title}. Write concise \
+         notes on it: the main facts, claims, names and figures, as a plain bulleted list. \
+         Leave out anything that is navigation or advertising. Reply with only the notes.\n\n\
+         <page>\n{chunk}\n</page>"
+    )
+}
+
+/// Prompt for the reduce step.  `from_notes` says the input is notes from
+/// the map step rather than the page itself.
+pub fn summary_prompt(
+    source: &str,
+    title: Option<&str>,
+    length: Length,
+    from_notes: bool,
+) -> String {
+    let title = title.map(|t| format!(" \"{}\"", t)).unwrap_or_default();
+    let what = if from_notes {
+        format!("notes taken on the web page{title}, part by part")
+    } else {
+        format!("the text of the web page{title}")
+    };
+    format!(
+        "The text between the <page> tags is {what}. Summarise the page.\n\
+         Rules:\n\
+         - Reply with only a JSON object, no commentary: \
+         {{\"summary\": \"...\", \"key_points\": [\"...\"]}}\n\
+         - `summary` is at most {words} words of plain prose.\n\
+         - `key_points` has at most {points} short items, most important first.\n\
+         - Use only what the page says; do not add outside knowledge.\n\n\
+         <page>\n{source}\n</page>",
+        words = length.max_words(),
+        points = length.max_points(),
+    )
+}
+
+/// Parse the model's JSON reply, tolerating a Markdown fence or stray
+/// text around the object.  A reply with no object is taken as the
+/// summary itself.
+#[cfg(feature = "web-tools")]
+fn parse_reply(reply: &str) -> Result<SummaryReply, String> {
+    let start = reply.find('{');
+    let end = reply.rfind('}');
+    if let (Some(s), Some(e)) = (start, end)
+        && s < e
+    {
+        return serde_json::from_str(&reply[s..=e])
+            .map_err(|e| format!("Model returned malformed summary JSON: {}", e));
+    }
+    let summary = reply.trim();
+    if summary.is_empty() {
+        return Err("Model returned an empty summary".into());
+    }
+    Ok(SummaryReply {
+        summary: summary.to_string(),
+        key_points: Vec::new(),
+    })
+}
+
+/// `text` cut to at most `max` words, with an ellipsis if anything went.
+#[cfg(feature = "web-tools")]
+fn clip_words(text: &str, max: usize) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() <= max {
+        return words.join(" ");
+    }
+    format!("{}…", words[..max].join(" "))
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn summarize_url_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "url".into(),
+            description: "HTTP or HTTPS URL of the page to summarise.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "content".into(),
+            description: "HTML to summarise instead of fetching a URL.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "base_url".into(),
+            description: "URL that relative links in 'content' resolve against.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "length".into(),
+            description: "Summary length: 'short' (~80 words, 3 key points), 'medium' \
+                          (~200 words, 5 points, default) or 'long' (~400 words, 8 points)."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "include_links".into(),
+            description: "Also return the links on the page. Default: false.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "max_links".into(),
+            description: "Most links to return with include_links (default 20).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
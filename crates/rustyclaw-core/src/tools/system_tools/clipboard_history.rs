@@ -0,0 +1,199 @@
+//! Opt-in clipboard history for the `clipboard` tool.
+//!
+//! With `[clipboard_history] enabled = true`, text copied while the gateway
+//! runs — sampled by [`spawn_clipboard_watcher`], or read and written by the
+//! tool itself — is kept in a bounded in-memory ring so the agent can answer
+//! "what did I copy a minute ago?".  Credentials are redacted before an
+//! entry is stored, entries expire by count and by age, and nothing is
+//! written to disk.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+use tracing::debug;
+
+use super::media::PASTE_CMD;
+use super::sh_async;
+use crate::config::ClipboardHistoryConfig;
+use crate::providers::redact_credentials;
+
+/// Characters of an entry shown by the `history` action.
+const PREVIEW_CHARS: usize = 80;
+
+/// One remembered copy.
+#[derive(Debug, Clone)]
+pub struct ClipEntry {
+    /// The copied text, with credentials redacted.
+    pub text: String,
+    pub copied_at: Instant,
+    /// Whether anything was redacted from the original.
+    pub redacted: bool,
+}
+
+/// Recent clipboard contents, newest first.
+#[derive(Debug)]
+pub struct ClipboardHistory {
+    entries: VecDeque<ClipEntry>,
+    max_entries: usize,
+    max_age: Duration,
+}
+
+impl ClipboardHistory {
+    pub fn new(config: &ClipboardHistoryConfig) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            max_entries: config.max_entries.max(1),
+            max_age: Duration::from_secs(config.max_age_secs),
+        }
+    }
+
+    /// Remember `text` as copied at `now`.  Blank text and a repeat of the
+    /// newest entry are ignored.  Returns whether an entry was added.
+    pub fn record(&mut self, text: &str, now: Instant) -> bool {
+        if text.trim().is_empty() {
+            return false;
+        }
+        let stored = redact_credentials(text, None);
+        if self.entries.front().is_some_and(|e| e.text == stored) {
+            return false;
+        }
+        self.entries.push_front(ClipEntry {
+            redacted: stored != text,
+            text: stored,
+            copied_at: now,
+        });
+        self.entries.truncate(self.max_entries);
+        self.prune(now);
+        true
+    }
+
+    /// Entries still within the age limit at `now`, newest first.
+    pub fn entries(&mut self, now: Instant) -> &VecDeque<ClipEntry> {
+        self.prune(now);
+        &self.entries
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while self
+            .entries
+            .back()
+            .is_some_and(|e| now.duration_since(e.copied_at) > self.max_age)
+        {
+            self.entries.pop_back();
+        }
+    }
+}
+
+static CONFIG: OnceLock<ClipboardHistoryConfig> = OnceLock::new();
+static HISTORY: OnceLock<Mutex<ClipboardHistory>> = OnceLock::new();
+
+/// Called once from the gateway to apply `[clipboard_history]`.
+pub fn set_clipboard_history_config(config: ClipboardHistoryConfig) {
+    if config.enabled {
+        let _ = HISTORY.set(Mutex::new(ClipboardHistory::new(&config)));
+    }
+    let _ = CONFIG.set(config);
+}
+
+fn history() -> Option<&'static Mutex<ClipboardHistory>> {
+    HISTORY.get()
+}
+
+/// Remember text seen on the clipboard; does nothing when history is off.
+pub(crate) fn record_clipboard(text: &str) {
+    if let Some(history) = history() {
+        let mut history = history.lock().unwrap_or_else(|e| e.into_inner());
+        history.record(text, Instant::now());
+    }
+}
+
+/// Sample the clipboard in the background so copies made outside the
+/// agent are remembered.  `None` when history or polling is off.
+pub fn spawn_clipboard_watcher() -> Option<tokio::task::JoinHandle<()>> {
+    let config = CONFIG.get()?;
+    if !config.enabled || config.poll_secs == 0 {
+        return None;
+    }
+    let interval = Duration::from_secs(config.poll_secs);
+    Some(tokio::spawn(async move {
+        // Only a change is a copy; the same text sitting on the clipboard
+        // is not recorded again after its entry expires.
+        let mut last = String::new();
+        loop {
+            if let Ok(text) = sh_async(PASTE_CMD).await
+                && text != last
+            {
+                debug!(length = text.len(), "Clipboard changed");
+                record_clipboard(&text);
+                last = text;
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }))
+}
+
+/// The `history` and `history_get` actions of the `clipboard` tool.
+pub(crate) fn exec_history_action(action: &str, args: &Value) -> Result<String, String> {
+    let history = history().ok_or(
+        "Clipboard history is off. Enable it with `[clipboard_history] enabled = true` \
+         in config.toml.",
+    )?;
+    let mut history = history.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    let entries = history.entries(now);
+    let age = |e: &ClipEntry| now.duration_since(e.copied_at).as_secs();
+
+    if action == "history_get" {
+        let index = args.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        let entry = entries.get(index).ok_or_else(|| {
+            format!(
+                "No clipboard history entry {} ({} remembered)",
+                index,
+                entries.len()
+            )
+        })?;
+        return Ok(json!({
+            "index": index,
+            "copied_secs_ago": age(entry),
+            "content": entry.text,
+            "length": entry.text.len(),
+            "redacted": entry.redacted,
+        })
+        .to_string());
+    }
+
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .map(str::to_lowercase);
+    let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+    let listed: Vec<Value> = entries
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| {
+            query
+                .as_ref()
+                .is_none_or(|q| e.text.to_lowercase().contains(q))
+        })
+        .take(limit)
+        .map(|(index, e)| {
+            let mut preview: String = e.text.chars().take(PREVIEW_CHARS).collect();
+            if preview.len() < e.text.len() {
+                preview.push('…');
+            }
+            json!({
+                "index": index,
+                "copied_secs_ago": age(e),
+                "preview": preview,
+                "length": e.text.len(),
+                "redacted": e.redacted,
+            })
+        })
+        .collect();
+    Ok(
+        json!({ "count": listed.len(), "remembered": entries.len(), "entries": listed })
+            .to_string(),
+    )
+}
@@ -1,10 +1,15 @@
 //! Media tools: screenshot capture and clipboard access.
 
+use super::clipboard_history::{exec_history_action, record_clipboard};
 use super::{expand_tilde, resolve_path, sh, sh_async};
 use serde_json::{Value, json};
 use std::path::Path;
 use tracing::{debug, instrument};
 
+/// Prints the clipboard's text with whichever tool the platform has.
+pub(super) const PASTE_CMD: &str = "pbpaste 2>/dev/null || xclip -selection clipboard -o 2>/dev/null \
+     || xsel --clipboard --output 2>/dev/null";
+
 fn human_size(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     let mut val = bytes as f64;
@@ -83,7 +88,8 @@ pub async fn exec_clipboard_async(args: &Value, _workspace_dir: &Path) -> Result
 
     match action {
         "read" => {
-            let text = sh_async("pbpaste 2>/dev/null || xclip -selection clipboard -o 2>/dev/null || xsel --clipboard --output 2>/dev/null").await.unwrap_or_default();
+            let text = sh_async(PASTE_CMD).await.unwrap_or_default();
+            record_clipboard(&text);
             Ok(json!({ "content": text.trim(), "length": text.len() }).to_string())
         }
         "write" => {
@@ -96,8 +102,10 @@ pub async fn exec_clipboard_async(args: &Value, _workspace_dir: &Path) -> Result
                 content.replace('\'', "'\\''")
             );
             sh_async(&cmd).await?;
+            record_clipboard(content);
             Ok(json!({ "status": "ok", "length": content.len() }).to_string())
         }
+        "history" | "history_get" => exec_history_action(action, args),
         _ => Err(format!("Unknown action: {}", action)),
     }
 }
@@ -172,8 +180,8 @@ pub fn exec_clipboard(args: &Value, _workspace_dir: &Path) -> Result<String, Str
 
     match action {
         "read" => {
-            let text = sh("pbpaste 2>/dev/null || xclip -selection clipboard -o 2>/dev/null")
-                .unwrap_or_default();
+            let text = sh(PASTE_CMD).unwrap_or_default();
+            record_clipboard(&text);
             Ok(json!({ "content": text.trim(), "length": text.len() }).to_string())
         }
         "write" => {
@@ -196,11 +204,13 @@ pub fn exec_clipboard(args: &Value, _workspace_dir: &Path) -> Result<String, Str
             }
             let status = child.wait().map_err(|e| format!("Wait failed: {}", e))?;
             if status.success() {
+                record_clipboard(content);
                 Ok(json!({ "status": "ok", "length": content.len() }).to_string())
             } else {
                 Err("Clipboard write failed".to_string())
             }
         }
+        "history" | "history_get" => exec_history_action(action, args),
         _ => Err(format!("Unknown action: {}", action)),
     }
 }
@@ -4,17 +4,24 @@
 //! Split into submodules for maintainability.
 
 mod apps;
+mod clipboard_history;
 mod disk;
 mod media;
 mod monitor;
+mod ocr;
 mod security;
 mod text;
 
+pub use clipboard_history::{
+    ClipEntry, ClipboardHistory, set_clipboard_history_config, spawn_clipboard_watcher,
+};
+
 // Re-export sync functions
 pub use apps::{exec_app_index, exec_browser_cache, exec_cloud_browse};
 pub use disk::{exec_classify_files, exec_disk_usage};
 pub use media::{exec_clipboard, exec_screenshot};
 pub use monitor::{exec_battery_health, exec_system_monitor};
+pub use ocr::{OcrWord, exec_ocr_screen, ocr_image, parse_tesseract_tsv, words_to_text};
 pub use security::{exec_audit_sensitive, exec_secure_delete};
 pub use text::exec_summarize_file;
 
@@ -23,6 +30,7 @@ pub use apps::{exec_app_index_async, exec_browser_cache_async, exec_cloud_browse
 pub use disk::{exec_classify_files_async, exec_disk_usage_async};
 pub use media::{exec_clipboard_async, exec_screenshot_async};
 pub use monitor::{exec_battery_health_async, exec_system_monitor_async};
+pub use ocr::exec_ocr_screen_async;
 pub use security::{exec_audit_sensitive_async, exec_secure_delete_async};
 pub use text::exec_summarize_file_async;
 
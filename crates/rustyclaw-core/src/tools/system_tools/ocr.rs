@@ -0,0 +1,297 @@
+//! Screen OCR: capture a screenshot and read the text on it with tesseract.
+
+use super::media::{exec_screenshot, exec_screenshot_async};
+use super::{has_command, has_command_async};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument};
+
+const UNAVAILABLE: &str = "OCR unavailable: tesseract is not installed. Install it with \
+     'brew install tesseract' (macOS) or 'apt install tesseract-ocr' (Debian/Ubuntu).";
+
+/// Tesseract's TSV level for a single word.
+const WORD_LEVEL: &str = "5";
+
+/// A recognized word and its box in image pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrWord {
+    pub text: String,
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+    pub confidence: f32,
+    /// (block, paragraph, line) numbers, which group words into lines.
+    line: (u32, u32, u32),
+}
+
+/// How much box detail to return.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Boxes {
+    None,
+    Word,
+    Line,
+}
+
+struct Request {
+    boxes: Boxes,
+    lang: String,
+    /// Where the screenshot goes, and whether to delete it afterwards.
+    image: PathBuf,
+    keep: bool,
+    /// Arguments for the screenshot tool.
+    capture: Value,
+}
+
+fn parse_request(args: &Value) -> Result<Request, String> {
+    let boxes = match args.get("boxes").and_then(|v| v.as_str()).unwrap_or("none") {
+        "none" => Boxes::None,
+        "word" | "words" => Boxes::Word,
+        "line" | "lines" => Boxes::Line,
+        other => {
+            return Err(format!(
+                "Unknown boxes value: '{}'. Use 'none', 'word' or 'line'",
+                other
+            ));
+        }
+    };
+    let lang = args
+        .get("lang")
+        .and_then(|v| v.as_str())
+        .unwrap_or("eng")
+        .to_string();
+    if lang.is_empty()
+        || !lang
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '+')
+    {
+        return Err(format!(
+            "Invalid lang: '{}' (e.g. 'eng' or 'eng+deu')",
+            lang
+        ));
+    }
+
+    // Capture with the screenshot tool's own options; without a path the
+    // image is a temporary file.
+    let mut capture = args.clone();
+    let (image, keep) = match args.get("path").and_then(|v| v.as_str()) {
+        Some(path) => (PathBuf::from(path), true),
+        None => {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or_default();
+            let name = format!("rustyclaw-ocr-{}-{}.png", std::process::id(), nanos);
+            (std::env::temp_dir().join(name), false)
+        }
+    };
+    if !keep && let Some(map) = capture.as_object_mut() {
+        map.insert("path".into(), json!(image.display().to_string()));
+    }
+    Ok(Request {
+        boxes,
+        lang,
+        image,
+        keep,
+        capture,
+    })
+}
+
+/// Where the screenshot tool saved the image.
+fn captured_path(screenshot_result: &str, fallback: &Path) -> PathBuf {
+    serde_json::from_str::<Value>(screenshot_result)
+        .ok()
+        .and_then(|v| v.get("path").and_then(|p| p.as_str()).map(PathBuf::from))
+        .unwrap_or_else(|| fallback.to_path_buf())
+}
+
+fn tesseract_args(image: &Path, lang: &str) -> Vec<String> {
+    vec![
+        image.display().to_string(),
+        "stdout".to_string(),
+        "-l".to_string(),
+        lang.to_string(),
+        "tsv".to_string(),
+    ]
+}
+
+fn tesseract_result(output: std::process::Output) -> Result<String, String> {
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+// ── Async implementation ────────────────────────────────────────────────────
+
+#[instrument(skip(args, workspace_dir))]
+pub async fn exec_ocr_screen_async(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    if !has_command_async("tesseract").await {
+        return Err(UNAVAILABLE.to_string());
+    }
+    let request = parse_request(args)?;
+    debug!(lang = %request.lang, boxes = ?request.boxes, "OCR screen");
+    let shot = exec_screenshot_async(&request.capture, workspace_dir).await?;
+    let image = captured_path(&shot, &request.image);
+    let output = tokio::process::Command::new("tesseract")
+        .args(tesseract_args(&image, &request.lang))
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run tesseract: {}", e));
+    if !request.keep {
+        let _ = tokio::fs::remove_file(&image).await;
+    }
+    let tsv = tesseract_result(output?)?;
+    Ok(render(&request, &image, &tsv))
+}
+
+// ── Sync implementation ─────────────────────────────────────────────────────
+
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_ocr_screen(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    if !has_command("tesseract") {
+        return Err(UNAVAILABLE.to_string());
+    }
+    let request = parse_request(args)?;
+    debug!(lang = %request.lang, boxes = ?request.boxes, "OCR screen");
+    let shot = exec_screenshot(&request.capture, workspace_dir)?;
+    let image = captured_path(&shot, &request.image);
+    let tsv = ocr_image(&image, &request.lang);
+    if !request.keep {
+        let _ = std::fs::remove_file(&image);
+    }
+    Ok(render(&request, &image, &tsv?))
+}
+
+/// Run tesseract on an image file, returning its TSV output.
+pub fn ocr_image(image: &Path, lang: &str) -> Result<String, String> {
+    let output = std::process::Command::new("tesseract")
+        .args(tesseract_args(image, lang))
+        .output()
+        .map_err(|e| format!("Failed to run tesseract: {}", e))?;
+    tesseract_result(output)
+}
+
+// ── Output ──────────────────────────────────────────────────────────────────
+
+fn render(request: &Request, image: &Path, tsv: &str) -> String {
+    let words = parse_tesseract_tsv(tsv);
+    let mut out = json!({
+        "text": words_to_text(&words),
+        "words": words.len(),
+        "engine": "tesseract",
+        "lang": request.lang,
+    });
+    if request.keep {
+        out["screenshot"] = json!(image.display().to_string());
+    }
+    match request.boxes {
+        Boxes::None => {}
+        Boxes::Word => {
+            out["boxes"] = words
+                .iter()
+                .map(|w| {
+                    json!({
+                        "text": w.text,
+                        "x": w.left,
+                        "y": w.top,
+                        "width": w.width,
+                        "height": w.height,
+                        "confidence": w.confidence,
+                    })
+                })
+                .collect();
+        }
+        Boxes::Line => {
+            out["boxes"] = lines(&words)
+                .into_iter()
+                .map(|line| {
+                    let left = line.iter().map(|w| w.left).min().unwrap_or(0);
+                    let top = line.iter().map(|w| w.top).min().unwrap_or(0);
+                    let right = line.iter().map(|w| w.left + w.width).max().unwrap_or(0);
+                    let bottom = line.iter().map(|w| w.top + w.height).max().unwrap_or(0);
+                    let confidence =
+                        line.iter().map(|w| w.confidence).sum::<f32>() / line.len() as f32;
+                    json!({
+                        "text": line_text(&line),
+                        "x": left,
+                        "y": top,
+                        "width": right - left,
+                        "height": bottom - top,
+                        "confidence": confidence,
+                    })
+                })
+                .collect();
+        }
+    }
+    out.to_string()
+}
+
+/// The recognized words in tesseract's `tsv` output, in reading order.
+/// Rows with no text (page, block and line markers) are dropped.
+pub fn parse_tesseract_tsv(tsv: &str) -> Vec<OcrWord> {
+    tsv.lines()
+        .skip(1) // header
+        .filter_map(|row| {
+            let cols: Vec<&str> = row.split('\t').collect();
+            if cols.len() < 12 || cols[0] != WORD_LEVEL {
+                return None;
+            }
+            let text = cols[11].trim();
+            if text.is_empty() {
+                return None;
+            }
+            let num = |i: usize| cols[i].trim().parse::<u32>().ok();
+            Some(OcrWord {
+                text: text.to_string(),
+                left: num(6)?,
+                top: num(7)?,
+                width: num(8)?,
+                height: num(9)?,
+                confidence: cols[10].trim().parse().unwrap_or(-1.0),
+                line: (num(2)?, num(3)?, num(4)?),
+            })
+        })
+        .collect()
+}
+
+/// Words grouped into lines, in order.
+fn lines(words: &[OcrWord]) -> Vec<Vec<&OcrWord>> {
+    let mut grouped: BTreeMap<(u32, u32, u32), Vec<&OcrWord>> = BTreeMap::new();
+    for word in words {
+        grouped.entry(word.line).or_default().push(word);
+    }
+    grouped.into_values().collect()
+}
+
+fn line_text(line: &[&OcrWord]) -> String {
+    line.iter()
+        .map(|w| w.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Plain text: a line per recognized line, a blank line between blocks and
+/// paragraphs.
+pub fn words_to_text(words: &[OcrWord]) -> String {
+    let mut text = String::new();
+    let mut previous: Option<(u32, u32)> = None;
+    for line in lines(words) {
+        let (block, paragraph, _) = line[0].line;
+        if let Some(prev) = previous {
+            text.push_str(if prev == (block, paragraph) {
+                "\n"
+            } else {
+                "\n\n"
+            });
+        }
+        text.push_str(&line_text(&line));
+        previous = Some((block, paragraph));
+    }
+    text
+}
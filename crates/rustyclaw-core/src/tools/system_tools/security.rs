@@ -96,23 +96,7 @@ pub async fn exec_audit_sensitive_async(
         resolve_path(workspace_dir, path_str)
     };
 
-    let patterns: Vec<(&str, &str)> = vec![
-        ("AWS Access Key", r"AKIA[0-9A-Z]{16}"),
-        (
-            "Private Key Header",
-            r"-----BEGIN (RSA |DSA |EC |OPENSSH )?PRIVATE KEY-----",
-        ),
-        ("GitHub Token", r"gh[ps]_[A-Za-z0-9_]{36,}"),
-        (
-            "Generic API Key",
-            r#"(?i)(api[_-]?key|apikey|secret[_-]?key)\s*[=:"]\s*[A-Za-z0-9_\-]{20,}"#,
-        ),
-        (
-            "JWT Token",
-            r"eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
-        ),
-        ("Slack Token", r"xox[bpras]-[A-Za-z0-9-]{10,}"),
-    ];
+    let patterns = crate::security::SENSITIVE_PATTERNS;
 
     // Use spawn_blocking for filesystem walk
     let target_clone = target.clone();
@@ -122,7 +106,7 @@ pub async fn exec_audit_sensitive_async(
 
     let mut findings: Vec<Value> = Vec::new();
     for file_path in &files {
-        for (label, pattern) in &patterns {
+        for (label, pattern) in patterns {
             let script = format!(
                 "grep -nE '{}' '{}' 2>/dev/null | head -3",
                 pattern,
@@ -253,7 +237,7 @@ pub fn exec_audit_sensitive(args: &Value, workspace_dir: &Path) -> Result<String
 
     let mut findings: Vec<Value> = Vec::new();
     for file_path in &files {
-        for (label, pattern) in &patterns {
+        for (label, pattern) in patterns {
             let script = format!(
                 "grep -nE '{}' '{}' 2>/dev/null | head -3",
                 pattern,
@@ -220,6 +220,60 @@ fn test_execute_command_failure() {
     assert!(result.unwrap().contains("exit code"));
 }
 
+#[tokio::test]
+async fn test_execute_command_cancel_returns_promptly() {
+    let cancel = CancellationToken::new();
+    let trigger = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        trigger.cancel();
+    });
+
+    let started = std::time::Instant::now();
+    let args = json!({ "command": "sleep 30", "timeout_secs": 60, "yieldMs": 60000 });
+    let result = execute_tool_cancellable("execute_command", &args, ws(), &cancel).await;
+    assert_eq!(result.unwrap_err(), TOOL_CANCELLED);
+    assert!(
+        started.elapsed() < std::time::Duration::from_secs(5),
+        "cancel took {:?}",
+        started.elapsed()
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_execute_command_cancel_kills_process_group() {
+    let dir = tempfile::tempdir().unwrap();
+    let marker = dir.path().join("survived");
+    // The backgrounded subshell would outlive a plain kill of `sh`.
+    let command = format!("(sleep 1; touch '{}') & sleep 30", marker.display());
+    let args = json!({ "command": command, "timeout_secs": 60, "yieldMs": 60000 });
+
+    let cancel = CancellationToken::new();
+    let trigger = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        trigger.cancel();
+    });
+    let result = execute_tool_cancellable("execute_command", &args, dir.path(), &cancel).await;
+    assert_eq!(result.unwrap_err(), TOOL_CANCELLED);
+
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+    assert!(
+        !marker.exists(),
+        "child of the cancelled command kept running"
+    );
+}
+
+#[tokio::test]
+async fn test_execute_tool_cancellable_already_cancelled() {
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let args = json!({ "command": "echo should-not-run" });
+    let result = execute_tool_cancellable("execute_command", &args, ws(), &cancel).await;
+    assert_eq!(result.unwrap_err(), TOOL_CANCELLED);
+}
+
 // ── execute_tool dispatch ───────────────────────────────────────
 
 #[tokio::test]
@@ -459,7 +513,7 @@ fn test_execute_command_params_with_background() {
 #[test]
 fn test_memory_search_params_defined() {
     let params = memory_search_params();
-    assert_eq!(params.len(), 5);
+    assert_eq!(params.len(), 8);
     assert!(params.iter().any(|p| p.name == "query" && p.required));
     assert!(params.iter().any(|p| p.name == "maxResults" && !p.required));
     assert!(params.iter().any(|p| p.name == "minScore" && !p.required));
@@ -473,6 +527,9 @@ fn test_memory_search_params_defined() {
             .iter()
             .any(|p| p.name == "halfLifeDays" && !p.required)
     );
+    for name in ["after", "before", "files"] {
+        assert!(params.iter().any(|p| p.name == name && !p.required));
+    }
 }
 
 #[cfg(feature = "semantic-memory")]
@@ -484,6 +541,37 @@ fn test_memory_search_missing_query() {
     assert!(result.unwrap_err().contains("Missing required parameter"));
 }
 
+#[cfg(feature = "semantic-memory")]
+#[test]
+fn test_memory_search_invalid_date_filter() {
+    let args = json!({ "query": "auth", "after": "last tuesday" });
+    let result = exec_memory_search(&args, ws());
+    assert!(result.unwrap_err().contains("Invalid 'after' date"));
+}
+
+#[cfg(feature = "semantic-memory")]
+#[test]
+fn test_memory_search_filtered_header() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("HISTORY.md"),
+        "\n[2026-02-10 09:00 UTC] Auth decision: JWT.\n\n\
+         [2026-02-18 16:45 UTC] Auth decision: session cookies.\n",
+    )
+    .unwrap();
+
+    let args = json!({
+        "query": "auth decision",
+        "after": "2026-02-15",
+        "before": "2026-02-20T00:00:00",
+        "files": "HISTORY.md",
+    });
+    let output = exec_memory_search(&args, dir.path()).unwrap();
+    assert!(output.contains("filters: after 2026-02-15, before 2026-02-20, files HISTORY.md"));
+    assert!(output.contains("session cookies"));
+    assert!(!output.contains("JWT"));
+}
+
 // ── memory_get ──────────────────────────────────────────────────
 
 #[test]
@@ -516,7 +604,7 @@ fn test_memory_get_invalid_path() {
 #[test]
 fn test_cron_params_defined() {
     let params = cron_params();
-    assert_eq!(params.len(), 5);
+    assert_eq!(params.len(), 9);
     assert!(params.iter().any(|p| p.name == "action" && p.required));
     assert!(params.iter().any(|p| p.name == "jobId" && !p.required));
 }
@@ -537,6 +625,40 @@ fn test_cron_invalid_action() {
     assert!(result.unwrap_err().contains("Unknown action"));
 }
 
+#[test]
+fn test_cron_export_import_between_workspaces() {
+    let old = tempfile::tempdir().unwrap();
+    let job = json!({
+        "jobId": "job-digest",
+        "name": "Digest",
+        "schedule": { "kind": "cron", "expr": "0 8 * * 1", "tz": "America/Chicago" },
+        "sessionTarget": "isolated",
+        "payload": { "kind": "agentTurn", "message": "Weekly digest" },
+        "enabled": false,
+        "createdMs": 1,
+    });
+    exec_cron(&json!({ "action": "add", "job": job }), old.path()).unwrap();
+    let reply = exec_cron(
+        &json!({ "action": "export", "format": "yaml", "path": "backup/cron.yaml" }),
+        old.path(),
+    )
+    .unwrap();
+    assert!(reply.starts_with("Exported 1 job(s)"), "{reply}");
+
+    let new = tempfile::tempdir().unwrap();
+    let backup = old.path().join("backup/cron.yaml");
+    let args = json!({ "action": "import", "path": backup });
+    let reply = exec_cron(&args, new.path()).unwrap();
+    assert!(reply.contains("1 added"), "{reply}");
+    assert!(reply.contains("+ job-digest added"), "{reply}");
+    let again = exec_cron(&args, new.path()).unwrap();
+    assert!(again.contains("○ job-digest skipped"), "{again}");
+
+    let listed = exec_cron(&json!({ "action": "export" }), new.path()).unwrap();
+    assert!(listed.contains("\"America/Chicago\""), "{listed}");
+    assert!(listed.contains("\"enabled\": false"), "{listed}");
+}
+
 // ── sessions_list ───────────────────────────────────────────────
 
 #[test]
@@ -30,6 +30,7 @@ fn test_is_secrets_tool() {
     assert!(is_secrets_tool("secrets_list"));
     assert!(is_secrets_tool("secrets_get"));
     assert!(is_secrets_tool("secrets_store"));
+    assert!(is_secrets_tool("secrets_generate"));
     assert!(!is_secrets_tool("read_file"));
     assert!(!is_secrets_tool("memory_get"));
 }
@@ -64,6 +65,18 @@ fn test_secrets_store_params_defined() {
     assert!(params.iter().any(|p| p.name == "username" && !p.required));
 }
 
+#[test]
+fn test_secrets_generate_params_defined() {
+    let params = secrets_generate_params();
+    assert_eq!(params.len(), 13);
+    assert!(params.iter().all(|p| !p.required));
+    assert!(
+        params
+            .iter()
+            .any(|p| p.name == "reveal" && p.param_type == "boolean")
+    );
+}
+
 #[test]
 fn test_protected_path_without_init() {
     // Before set_credentials_dir is called, nothing is protected.
@@ -95,12 +108,22 @@ fn test_gateway_config_schema() {
     assert!(result.unwrap().contains("properties"));
 }
 
+#[test]
+fn test_gateway_status() {
+    let args = json!({ "action": "status" });
+    let status: serde_json::Value =
+        serde_json::from_str(&exec_gateway(&args, ws()).unwrap()).unwrap();
+    assert!(status["uptimeSecs"].is_u64());
+    assert!(status["summary"].is_string());
+    assert!(status["probes"].is_array());
+}
+
 // ── message ─────────────────────────────────────────────────────
 
 #[test]
 fn test_message_params_defined() {
     let params = message_params();
-    assert_eq!(params.len(), 7);
+    assert_eq!(params.len(), 9);
     assert!(params.iter().any(|p| p.name == "action" && p.required));
 }
 
@@ -112,6 +135,113 @@ fn test_message_missing_action() {
     assert!(result.unwrap_err().contains("Missing required parameter"));
 }
 
+#[test]
+fn test_message_schedule_lands_in_cron_list() {
+    let dir = tempfile::tempdir().unwrap();
+    let args = json!({
+        "action": "schedule",
+        "channel": "discord",
+        "target": "1234",
+        "message": "Standup in 5",
+        "delayMinutes": 30,
+    });
+    let reply = exec_message(&args, dir.path()).unwrap();
+    assert!(reply.contains("local time"), "{reply}");
+
+    let list = exec_cron(&json!({ "action": "list" }), dir.path()).unwrap();
+    assert!(list.contains("Message to 1234 (discord)"), "{list}");
+
+    let job_id = reply.split_whitespace().nth(2).unwrap();
+    exec_cron(&json!({ "action": "remove", "jobId": job_id }), dir.path()).unwrap();
+    let list = exec_cron(&json!({ "action": "list" }), dir.path()).unwrap();
+    assert_eq!(list, "No cron jobs configured.");
+}
+
+#[test]
+fn test_message_schedule_rejects_past_time() {
+    let dir = tempfile::tempdir().unwrap();
+    let args = json!({
+        "action": "schedule",
+        "target": "1234",
+        "message": "too late",
+        "at": "2001-01-01T00:00:00Z",
+    });
+    let err = exec_message(&args, dir.path()).unwrap_err();
+    assert!(err.contains("in the past"), "{err}");
+}
+
+#[tokio::test]
+async fn test_dispatch_due_messages_settles_job() {
+    use crate::cron::{CronJob, CronStore, Payload, Schedule, SessionTarget};
+
+    let dir = tempfile::tempdir().unwrap();
+    let cron_dir = dir.path().join(".cron");
+    let mut store = CronStore::new(&cron_dir).unwrap();
+    let id = store
+        .add(CronJob::new(
+            None,
+            Schedule::At {
+                at: "2001-01-01T00:00:00Z".to_string(),
+            },
+            SessionTarget::Isolated,
+            Payload::SendMessage {
+                channel: "webhook".to_string(),
+                target: "ops".to_string(),
+                message: "hello".to_string(),
+            },
+        ))
+        .unwrap();
+
+    // No webhookUrl: the send fails, so the job is disabled, not retried.
+    let sent = dispatch_due_messages(&cron_dir).await.unwrap();
+    assert_eq!(sent, 1);
+    let store = CronStore::new(&cron_dir).unwrap();
+    assert!(!store.get(&id).unwrap().enabled);
+    assert_eq!(store.get_runs(&id, 10).unwrap().len(), 1);
+    assert_eq!(dispatch_due_messages(&cron_dir).await.unwrap(), 0);
+}
+
+#[test]
+fn test_settling_a_dispatch_keeps_jobs_changed_meanwhile() {
+    use crate::cron::{CronJob, CronStore, Payload, Schedule, SessionTarget};
+
+    let dir = tempfile::tempdir().unwrap();
+    let cron_dir = dir.path().join(".cron");
+    let job = |at: &str| {
+        CronJob::new(
+            None,
+            Schedule::At { at: at.to_string() },
+            SessionTarget::Isolated,
+            Payload::SendMessage {
+                channel: "webhook".to_string(),
+                target: "ops".to_string(),
+                message: "hello".to_string(),
+            },
+        )
+    };
+    let due = CronStore::new(&cron_dir)
+        .unwrap()
+        .add(job("2001-01-01T00:00:00Z"))
+        .unwrap();
+    let started_ms = cron_tool::now_ms();
+
+    // While the message is being sent, another job is scheduled.
+    let later = CronStore::new(&cron_dir)
+        .unwrap()
+        .add(job("2999-01-01T00:00:00Z"))
+        .unwrap();
+    cron_tool::settle_dispatched(&cron_dir, &due, started_ms, &Err("down".into())).unwrap();
+
+    let store = CronStore::new(&cron_dir).unwrap();
+    assert!(!store.get(&due).unwrap().enabled);
+    assert!(store.get(&later).unwrap().enabled);
+
+    // A job cancelled meanwhile stays cancelled.
+    CronStore::new(&cron_dir).unwrap().remove(&later).unwrap();
+    cron_tool::settle_dispatched(&cron_dir, &later, started_ms, &Ok(String::new())).unwrap();
+    assert!(CronStore::new(&cron_dir).unwrap().get(&later).is_none());
+}
+
 // ── tts ─────────────────────────────────────────────────────────
 
 #[test]
@@ -501,12 +631,61 @@ fn test_screenshot_params_defined() {
     assert!(params.iter().all(|p| !p.required));
 }
 
+// ── ocr_screen ──────────────────────────────────────────────────
+
+#[test]
+fn test_ocr_screen_params_include_screenshot_target() {
+    let names: Vec<String> = ocr_screen_params().into_iter().map(|p| p.name).collect();
+    assert_eq!(names, ["path", "region", "delay", "boxes", "lang"]);
+}
+
+#[test]
+fn test_ocr_tsv_to_words_and_text() {
+    let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+               1\t1\t0\t0\t0\t0\t0\t0\t800\t600\t-1\t\n\
+               4\t1\t1\t1\t1\t0\t10\t10\t120\t20\t-1\t\n\
+               5\t1\t1\t1\t1\t1\t10\t10\t50\t20\t96.5\tSave\n\
+               5\t1\t1\t1\t1\t2\t70\t10\t60\t20\t91\tchanges\n\
+               5\t1\t1\t1\t2\t1\t10\t40\t40\t20\t88\tOK\n\
+               5\t1\t2\t1\t1\t1\t10\t90\t70\t20\t-1\t \n\
+               5\t1\t2\t1\t1\t2\t90\t90\t70\t20\t90\tCancel\n";
+    let words = system_tools::parse_tesseract_tsv(tsv);
+    assert_eq!(words.len(), 4);
+    assert_eq!(words[1].text, "changes");
+    assert_eq!((words[1].left, words[1].top, words[1].width), (70, 10, 60));
+    assert_eq!(words[1].confidence, 91.0);
+    assert_eq!(
+        system_tools::words_to_text(&words),
+        "Save changes\nOK\n\nCancel"
+    );
+}
+
+#[test]
+fn test_ocr_screen_requires_tesseract() {
+    if !system_tools::has_command("tesseract") {
+        let err = exec_ocr_screen(&json!({}), ws()).unwrap_err();
+        assert!(err.contains("tesseract is not installed"), "{}", err);
+        return;
+    }
+    // A blank 32x32 PNG: tesseract runs and finds no words.
+    let png = base64::Engine::decode(
+        &base64::engine::general_purpose::STANDARD,
+        "iVBORw0KGgoAAAANSUhEUgAAACAAAAAgCAAAAABWESUoAAAAFklEQVR42mP4TwAwjCoYVTCqYKQqAAA/aPwuqUTQyAAAAABJRU5ErkJggg==",
+    )
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let image = dir.path().join("blank.png");
+    std::fs::write(&image, png).unwrap();
+    let tsv = system_tools::ocr_image(&image, "eng").unwrap();
+    assert!(system_tools::parse_tesseract_tsv(&tsv).is_empty());
+}
+
 // ── clipboard ───────────────────────────────────────────────────
 
 #[test]
 fn test_clipboard_params_defined() {
     let params = clipboard_params();
-    assert_eq!(params.len(), 2);
+    assert_eq!(params.len(), 5);
     assert!(params.iter().any(|p| p.name == "action" && p.required));
 }
 
@@ -525,6 +704,57 @@ fn test_clipboard_invalid_action() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_clipboard_history_evicts_by_count_and_age() {
+    use std::time::{Duration, Instant};
+    let config = crate::config::ClipboardHistoryConfig {
+        enabled: true,
+        max_entries: 3,
+        max_age_secs: 60,
+        ..Default::default()
+    };
+    let mut history = ClipboardHistory::new(&config);
+    let start = Instant::now();
+    for (i, text) in ["one", "two", "three", "four"].iter().enumerate() {
+        assert!(history.record(text, start + Duration::from_secs(i as u64)));
+    }
+    // Blank text and an unchanged clipboard are not new copies.
+    assert!(!history.record("  ", start));
+    assert!(!history.record("four", start + Duration::from_secs(5)));
+
+    let texts: Vec<_> = history
+        .entries(start + Duration::from_secs(5))
+        .iter()
+        .map(|e| e.text.clone())
+        .collect();
+    assert_eq!(texts, ["four", "three", "two"]);
+
+    // "two" was copied at 1s and "three" at 2s.
+    let later = start + Duration::from_secs(62);
+    assert_eq!(history.entries(later).len(), 2);
+    assert_eq!(history.entries(start + Duration::from_secs(70)).len(), 0);
+}
+
+#[test]
+fn test_clipboard_history_redacts_secrets() {
+    let config = crate::config::ClipboardHistoryConfig {
+        enabled: true,
+        ..Default::default()
+    };
+    let mut history = ClipboardHistory::new(&config);
+    let now = std::time::Instant::now();
+    let key = "sk-proj-abcdefghijklmnopqrstuvwxyz0123";
+    history.record(&format!("OPENAI_API_KEY={}", key), now);
+    history.record("plain note", now);
+
+    let entries = history.entries(now);
+    assert_eq!(entries[0].text, "plain note");
+    assert!(!entries[0].redacted);
+    assert!(entries[1].redacted);
+    assert!(!entries[1].text.contains(key));
+    assert!(entries[1].text.contains("<redacted"));
+}
+
 // ── audit_sensitive ─────────────────────────────────────────────
 
 #[test]
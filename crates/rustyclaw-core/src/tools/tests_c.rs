@@ -0,0 +1,4297 @@
+//! Tests for the tools module.
+
+#![allow(unused_imports, dead_code)]
+use super::*;
+use crate::config::SshHostConfig;
+use crate::secrets::{AccessPolicy, SecretEntry, SecretKind, SecretsManager};
+use ssh::{SshAuth, SshOutput, SshTransport};
+use std::path::Path;
+
+/// Helper: return the project root as workspace dir for tests.
+fn ws() -> &'static Path {
+    // In the workspace, CARGO_MANIFEST_DIR is crates/rustyclaw-core.
+    // The workspace root is two levels up.
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+}
+
+// ── env_info ────────────────────────────────────────────────────
+
+#[test]
+fn test_env_info_params_defined() {
+    let params = env_info_params();
+    assert_eq!(params.len(), 1);
+    assert!(params.iter().any(|p| p.name == "refresh" && !p.required));
+}
+
+#[test]
+fn test_env_info_classify_cgroup() {
+    assert_eq!(
+        env_info::classify_cgroup("0::/system.slice/docker-abc123.scope"),
+        Some("docker")
+    );
+    assert_eq!(
+        env_info::classify_cgroup("12:pids:/kubepods/burstable/pod1"),
+        Some("kubernetes")
+    );
+    assert_eq!(env_info::classify_cgroup("0::/init.scope"), None);
+}
+
+#[test]
+fn test_env_info_classify_dmi() {
+    assert_eq!(
+        env_info::classify_dmi("innotek GmbH VirtualBox"),
+        Some("virtualbox")
+    );
+    assert_eq!(
+        env_info::classify_dmi("QEMU Standard PC (Q35)"),
+        Some("qemu")
+    );
+    assert_eq!(env_info::classify_dmi("Dell Inc. XPS 13"), None);
+}
+
+#[tokio::test]
+async fn test_env_info_snapshot() {
+    let result = execute_tool("env_info", &json!({ "refresh": true }), ws()).await;
+    assert!(result.is_ok());
+    let snapshot: Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(snapshot["arch"], std::env::consts::ARCH);
+    assert_eq!(snapshot["rustyclaw_version"], env!("CARGO_PKG_VERSION"));
+    assert!(snapshot["toolchains"].is_object());
+}
+
+// ── translate ───────────────────────────────────────────────────
+
+/// Fake model: "translates" by upper-casing the text inside the prompt's
+/// `<text>` tags, and reports a source language when asked.
+async fn fake_translate(prompt: String) -> Result<String, String> {
+    let body = prompt
+        .split_once("<text>\n")
+        .and_then(|(_, rest)| rest.rsplit_once("\n</text>"))
+        .map(|(body, _)| body)
+        .ok_or("prompt is missing <text> tags")?;
+    if prompt.contains("[source:") {
+        Ok(format!("[source: English]\n{}", body.to_uppercase()))
+    } else {
+        Ok(body.to_uppercase())
+    }
+}
+
+#[test]
+fn test_translate_params_defined() {
+    let params = translate_params();
+    assert_eq!(params.len(), 3);
+    assert!(params.iter().any(|p| p.name == "text" && p.required));
+    assert!(params.iter().any(|p| p.name == "target_lang" && p.required));
+    assert!(
+        params
+            .iter()
+            .any(|p| p.name == "source_lang" && !p.required)
+    );
+}
+
+#[test]
+fn test_translate_split_fenced_roundtrip() {
+    let text = "Intro\n\n```rust\nlet x = 1;\n```\nMiddle\n~~~~\n```\nstill code\n~~~~\nOutro";
+    let segments = translate::split_fenced(text);
+    let rebuilt: String = segments
+        .iter()
+        .map(|s| match s {
+            translate::Segment::Prose(p) | translate::Segment::Code(p) => p.as_str(),
+        })
+        .collect();
+    assert_eq!(rebuilt, text);
+    assert_eq!(
+        segments[1],
+        translate::Segment::Code("```rust\nlet x = 1;\n```\n".into())
+    );
+    // A shorter/different fence inside a block does not close it.
+    assert_eq!(
+        segments[3],
+        translate::Segment::Code("~~~~\n```\nstill code\n~~~~\n".into())
+    );
+}
+
+#[tokio::test]
+async fn test_translate_preserves_code_fences() {
+    let code = "```python\ndef greet():\n    print(\"hello world\")\n```\n";
+    let text = format!("Say hello.\n\n{code}\nThen say goodbye.\n");
+    let args = json!({ "text": text, "target_lang": "Shouting" });
+
+    let result = translate::translate_with(&args, fake_translate)
+        .await
+        .unwrap();
+    let out: Value = serde_json::from_str(&result).unwrap();
+    let translated = out["text"].as_str().unwrap();
+
+    assert!(
+        translated.contains(code),
+        "code block changed: {translated}"
+    );
+    assert!(translated.starts_with("SAY HELLO.\n\n```python"));
+    assert!(translated.ends_with("```\n\nTHEN SAY GOODBYE.\n"));
+    assert_eq!(out["source_lang"], "English");
+    assert_eq!(out["target_lang"], "Shouting");
+}
+
+#[tokio::test]
+async fn test_translate_chunks_long_input() {
+    let para = "word ".repeat(300);
+    let text = vec![para.trim_end(); 40].join("\n\n");
+    let args = json!({ "text": text, "target_lang": "fr", "source_lang": "en" });
+
+    let mut calls = 0usize;
+    let result = translate::translate_with(&args, |prompt| {
+        calls += 1;
+        assert!(!prompt.contains("[source:"));
+        fake_translate(prompt)
+    })
+    .await
+    .unwrap();
+
+    assert!(calls > 1);
+    let out: Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(out["text"], text.to_uppercase());
+    assert_eq!(out["source_lang"], "en");
+}
+
+#[tokio::test]
+async fn test_translate_requires_target_lang() {
+    let result = translate::translate_with(&json!({ "text": "hi" }), fake_translate).await;
+    assert!(result.unwrap_err().contains("target_lang"));
+}
+
+// ── git ─────────────────────────────────────────────────────────
+
+/// Create a throwaway repository with a committer identity configured.
+fn init_git_repo() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for args in [
+        &["init", "-q"][..],
+        &["config", "user.name", "Test User"],
+        &["config", "user.email", "test@example.com"],
+        &["config", "commit.gpgsign", "false"],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+    dir
+}
+
+#[test]
+fn test_git_params_defined() {
+    let params = git_params();
+    assert!(params.iter().any(|p| p.name == "action" && p.required));
+    assert!(params.iter().any(|p| p.name == "repo_path" && !p.required));
+    assert!(params.iter().any(|p| p.name == "confirm" && !p.required));
+    assert!(
+        params
+            .iter()
+            .any(|p| p.name == "paths" && p.param_type == "array")
+    );
+}
+
+#[test]
+fn test_git_parse_status_rename_and_branch() {
+    let raw =
+        "## main...origin/main [ahead 2, behind 1]\0R  new.rs\0old.rs\0 M lib.rs\0?? notes.txt\0";
+    let status = git_tool::parse_status(raw);
+    assert_eq!(status["branch"], "main");
+    assert_eq!(status["upstream"], "origin/main");
+    assert_eq!(status["ahead"], 2);
+    assert_eq!(status["behind"], 1);
+
+    let entries = status["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0]["status"], "renamed");
+    assert_eq!(entries[0]["orig_path"], "old.rs");
+    assert_eq!(entries[0]["staged"], true);
+    assert_eq!(entries[1]["status"], "modified");
+    assert_eq!(entries[1]["staged"], false);
+    assert_eq!(entries[2]["status"], "untracked");
+}
+
+#[test]
+fn test_git_status_commit_roundtrip() {
+    let repo = init_git_repo();
+    std::fs::write(repo.path().join("hello.txt"), "hello\n").unwrap();
+
+    let status: Value =
+        serde_json::from_str(&exec_git(&json!({ "action": "status" }), repo.path()).unwrap())
+            .unwrap();
+    assert_eq!(status["clean"], false);
+    assert_eq!(status["entries"][0]["path"], "hello.txt");
+    assert_eq!(status["entries"][0]["status"], "untracked");
+
+    exec_git(
+        &json!({ "action": "add", "paths": ["hello.txt"] }),
+        repo.path(),
+    )
+    .unwrap();
+
+    // Without confirm, commit only previews.
+    let preview: Value = serde_json::from_str(
+        &exec_git(
+            &json!({ "action": "commit", "message": "Add greeting" }),
+            repo.path(),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(preview["committed"], false);
+    assert_eq!(preview["changes"][0]["path"], "hello.txt");
+
+    let commit: Value = serde_json::from_str(
+        &exec_git(
+            &json!({ "action": "commit", "message": "Add greeting", "confirm": true }),
+            repo.path(),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(commit["committed"], true);
+    let hash = commit["hash"].as_str().unwrap();
+    assert_eq!(hash.len(), 40);
+
+    let log: Value =
+        serde_json::from_str(&exec_git(&json!({ "action": "log" }), repo.path()).unwrap()).unwrap();
+    assert_eq!(log["count"], 1);
+    assert_eq!(log["commits"][0]["hash"], hash);
+    assert_eq!(log["commits"][0]["subject"], "Add greeting");
+    assert_eq!(log["commits"][0]["author"], "Test User");
+
+    let status: Value =
+        serde_json::from_str(&exec_git(&json!({ "action": "status" }), repo.path()).unwrap())
+            .unwrap();
+    assert_eq!(status["clean"], true);
+}
+
+#[test]
+fn test_git_commit_nothing_staged() {
+    let repo = init_git_repo();
+    let result = exec_git(
+        &json!({ "action": "commit", "message": "empty", "confirm": true }),
+        repo.path(),
+    );
+    assert!(result.unwrap_err().contains("Nothing to commit"));
+}
+
+#[test]
+fn test_git_not_a_repository() {
+    let dir = tempfile::tempdir().unwrap();
+    let result = exec_git(&json!({ "action": "status" }), dir.path());
+    assert!(result.unwrap_err().contains("Not a git repository"));
+}
+
+// ── csv ─────────────────────────────────────────────────────────
+
+const SAMPLE_CSV: &str = "name,city,age,score\n\
+                          Alice,\"Portland, OR\",34,91.5\n\
+                          Bob,\"New York, NY\",,78\n\
+                          \"Carol \"\"CJ\"\" Jones\",Boston,29,\n\
+                          Dave,\"Austin, TX\",41,85.25\n";
+
+fn sample_csv() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("people.csv"), SAMPLE_CSV).unwrap();
+    dir
+}
+
+#[test]
+fn test_csv_params_defined() {
+    let params = csv_params();
+    assert_eq!(params.len(), 8);
+    assert!(params.iter().any(|p| p.name == "action" && p.required));
+    assert!(params.iter().any(|p| p.name == "path" && p.required));
+}
+
+#[test]
+fn test_csv_head_table_keeps_quoted_commas() {
+    let dir = sample_csv();
+    let out = exec_csv(
+        &json!({ "action": "head", "path": "people.csv", "limit": 2 }),
+        dir.path(),
+    )
+    .unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].starts_with("name"));
+    assert!(lines[2].contains("Portland, OR"));
+    assert!(lines[3].contains("New York, NY"));
+    assert!(out.contains("2 of 4 matching rows shown"));
+}
+
+#[test]
+fn test_csv_head_json() {
+    let dir = sample_csv();
+    let out = exec_csv(
+        &json!({ "action": "head", "path": "people.csv", "format": "json" }),
+        dir.path(),
+    )
+    .unwrap();
+    let v: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(v["matched"], 4);
+    assert_eq!(v["rows"][1]["city"], "New York, NY");
+    assert!(v["rows"][1]["age"].is_null());
+    assert_eq!(v["rows"][2]["name"], "Carol \"CJ\" Jones");
+    assert_eq!(v["rows"][3]["score"], 85.25);
+}
+
+#[test]
+fn test_csv_stats() {
+    let dir = sample_csv();
+    let out = exec_csv(
+        &json!({ "action": "stats", "path": "people.csv", "format": "json" }),
+        dir.path(),
+    )
+    .unwrap();
+    let v: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(v["rows"], 4);
+    let age = &v["columns"][2];
+    assert_eq!(age["name"], "age");
+    assert_eq!(age["type"], "integer");
+    assert_eq!(age["nulls"], 1);
+    assert_eq!(age["min"], 29);
+    assert_eq!(age["max"], 41);
+    let score = &v["columns"][3];
+    assert_eq!(score["type"], "float");
+    assert_eq!(score["max"], 91.5);
+    assert_eq!(v["columns"][1]["type"], "string");
+}
+
+#[test]
+fn test_csv_filter_and_select() {
+    let dir = sample_csv();
+    let out = exec_csv(
+        &json!({
+            "action": "filter",
+            "path": "people.csv",
+            "where": ["age >= 30", "city contains , "],
+            "columns": ["name", 3],
+            "format": "json",
+        }),
+        dir.path(),
+    )
+    .unwrap();
+    let v: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(v["matched"], 2);
+    assert_eq!(v["columns"], json!(["name", "age"]));
+    assert_eq!(v["rows"][0], json!({ "name": "Alice", "age": 34 }));
+    assert_eq!(v["rows"][1]["name"], "Dave");
+
+    let out = exec_csv(
+        &json!({ "action": "select", "path": "people.csv", "columns": ["city"] }),
+        dir.path(),
+    )
+    .unwrap();
+    assert!(out.contains("Austin, TX"));
+    assert!(!out.contains("Alice"));
+}
+
+#[test]
+fn test_csv_headerless_custom_delimiter() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("data.txt"), "a;1\nb;\"2;3\"\n").unwrap();
+    let out = exec_csv(
+        &json!({
+            "action": "filter",
+            "path": "data.txt",
+            "delimiter": ";",
+            "has_header": false,
+            "where": "col1 == b",
+            "format": "json",
+        }),
+        dir.path(),
+    )
+    .unwrap();
+    let v: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(v["rows"], json!([{ "col1": "b", "col2": "2;3" }]));
+}
+
+#[test]
+fn test_csv_errors() {
+    let dir = sample_csv();
+    let err = exec_csv(
+        &json!({ "action": "select", "path": "people.csv", "columns": ["nope"] }),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("Unknown column 'nope'"));
+
+    let err = exec_csv(
+        &json!({ "action": "filter", "path": "people.csv" }),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("where"));
+
+    let err = exec_csv(
+        &json!({ "action": "head", "path": "missing.csv" }),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("Failed to open"));
+}
+
+// ── pdf_extract ─────────────────────────────────────────────────
+
+#[test]
+fn test_pdf_extract_params_defined() {
+    let params = pdf_extract_params();
+    assert_eq!(params.len(), 5);
+    assert!(params.iter().any(|p| p.name == "path" && p.required));
+    assert!(params.iter().any(|p| p.name == "pages" && !p.required));
+}
+
+#[test]
+fn test_pdf_extract_parse_page_ranges() {
+    use pdf_extract::parse_page_ranges;
+    assert_eq!(parse_page_ranges("12", 20).unwrap(), vec![12]);
+    assert_eq!(parse_page_ranges("3-5", 20).unwrap(), vec![3, 4, 5]);
+    assert_eq!(
+        parse_page_ranges("9-, 1,4,4", 10).unwrap(),
+        vec![1, 4, 9, 10]
+    );
+    assert_eq!(parse_page_ranges("-2", 10).unwrap(), vec![1, 2]);
+    assert!(parse_page_ranges("0", 10).is_err());
+    assert!(parse_page_ranges("5-3", 10).is_err());
+    assert!(
+        parse_page_ranges("11", 10)
+            .unwrap_err()
+            .contains("10 pages")
+    );
+    assert!(parse_page_ranges("abc", 10).is_err());
+}
+
+#[test]
+fn test_pdf_extract_parse_pdfinfo() {
+    let meta = pdf_extract::parse_pdfinfo(
+        "Title:          Q3 Report\nAuthor:         Finance\n\
+         Producer:       LibreOffice\nPages:          14\nEncrypted:      no\n",
+    );
+    assert_eq!(meta["pages"], 14);
+    assert_eq!(meta["title"], "Q3 Report");
+    assert_eq!(meta["author"], "Finance");
+    assert_eq!(meta["producer"], "LibreOffice");
+}
+
+#[test]
+fn test_pdf_extract_detect_tables() {
+    let layout = "Quarterly summary for the region\n\
+                  \n\
+                  Region      Revenue     Units\n\
+                  North       1,200       30\n\
+                  South West  950         22\n\
+                  \n\
+                  Figures are unaudited.\n";
+    let tables = pdf_extract::detect_tables(layout);
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0][0], ["Region", "Revenue", "Units"]);
+    assert_eq!(tables[0][2], ["South West", "950", "22"]);
+}
+
+/// Build a minimal PDF with one page per entry; each page draws its rows
+/// with cells 150pt apart so layout extraction sees columns.
+fn sample_pdf(pages: &[&[&[&str]]]) -> Vec<u8> {
+    let font_id = 3;
+    let first_page_id = 4;
+    let info_id = first_page_id + 2 * pages.len();
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", first_page_id + 2 * i))
+        .collect();
+
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+    for (i, rows) in pages.iter().enumerate() {
+        let mut content = String::new();
+        for (r, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                content.push_str(&format!(
+                    "BT /F1 12 Tf {} {} Td ({}) Tj ET\n",
+                    72 + 150 * c,
+                    720 - 20 * r,
+                    cell
+                ));
+            }
+        }
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 {} 0 R >> >> /Contents {} 0 R >>",
+            font_id,
+            first_page_id + 2 * i + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+    objects.push("<< /Title (Sample Report) /Author (Test Suite) >>".to_string());
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, obj) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, obj).as_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for off in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", off).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            info_id,
+            xref
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+fn poppler_available() -> bool {
+    std::process::Command::new("pdftotext")
+        .arg("-v")
+        .output()
+        .is_ok()
+}
+
+#[test]
+fn test_pdf_extract_page_range_text_and_tables() {
+    if !poppler_available() {
+        eprintln!("skipping: poppler-utils not installed");
+        return;
+    }
+    let dir = tempfile::tempdir().unwrap();
+    let pdf = sample_pdf(&[
+        &[&["Introduction page"]],
+        &[&["Second page body"]],
+        &[&["Item", "Qty"], &["Bolts", "40"], &["Nuts", "75"]],
+    ]);
+    std::fs::write(dir.path().join("report.pdf"), pdf).unwrap();
+
+    let out =
+        exec_pdf_extract(&json!({ "path": "report.pdf", "pages": "2-3" }), dir.path()).unwrap();
+    let v: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(v["metadata"]["pages"], 3);
+    assert_eq!(v["metadata"]["title"], "Sample Report");
+    assert_eq!(v["metadata"]["author"], "Test Suite");
+    let pages = v["pages"].as_array().unwrap();
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0]["page"], 2);
+    assert!(
+        pages[0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Second page body")
+    );
+    assert!(!out.contains("Introduction page"));
+
+    let out = exec_pdf_extract(
+        &json!({ "path": "report.pdf", "pages": 3, "mode": "tables" }),
+        dir.path(),
+    )
+    .unwrap();
+    let v: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(
+        v["pages"][0]["tables"][0]["rows"][1],
+        json!(["Bolts", "40"])
+    );
+
+    let out = exec_pdf_extract(
+        &json!({ "path": "report.pdf", "max_chars": 10 }),
+        dir.path(),
+    )
+    .unwrap();
+    let v: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(v["truncated"], true);
+    assert_eq!(v["next_page"], 2);
+}
+
+#[test]
+fn test_pdf_extract_rejects_bad_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = exec_pdf_extract(&json!({ "path": "missing.pdf" }), dir.path()).unwrap_err();
+    assert!(err.contains("File not found"));
+    let err =
+        exec_pdf_extract(&json!({ "path": "missing.pdf", "mode": "ocr" }), dir.path()).unwrap_err();
+    assert!(err.contains("Unknown mode"));
+}
+
+// ── ssh ─────────────────────────────────────────────────────────
+
+/// Records what it was asked to run and replies with canned output.
+#[derive(Default)]
+struct MockSsh {
+    calls: std::sync::Mutex<Vec<(String, &'static str, Option<String>)>>,
+    delay: Option<std::time::Duration>,
+}
+
+impl SshTransport for MockSsh {
+    async fn run(
+        &self,
+        _host: &SshHostConfig,
+        auth: &SshAuth,
+        command: &str,
+        stdin: Option<&str>,
+    ) -> Result<SshOutput, String> {
+        let kind = match auth {
+            SshAuth::Key(_) => "key",
+            SshAuth::Password(_) => "password",
+        };
+        self.calls
+            .lock()
+            .unwrap()
+            .push((command.to_string(), kind, stdin.map(str::to_string)));
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+        Ok(SshOutput {
+            stdout: "ok\n".into(),
+            stderr: String::new(),
+            exit_code: Some(0),
+            truncated: false,
+            host_key: Some("SHA256:abc".into()),
+        })
+    }
+}
+
+fn ssh_host(key: Option<&str>, password: Option<&str>) -> SshHostConfig {
+    SshHostConfig {
+        host: "10.0.0.5".into(),
+        port: 22,
+        user: "deploy".into(),
+        key: key.map(str::to_string),
+        password: password.map(str::to_string),
+        fingerprint: None,
+        allow_sudo: false,
+    }
+}
+
+fn ssh_vault(dir: &Path) -> SharedVault {
+    let mut mgr = SecretsManager::new(dir);
+    mgr.generate_ssh_key("web_key", "test", AccessPolicy::Always)
+        .unwrap();
+    let entry = SecretEntry {
+        label: "db password".into(),
+        kind: SecretKind::UsernamePassword,
+        policy: AccessPolicy::Always,
+        description: None,
+        disabled: false,
+    };
+    mgr.store_credential("db_pw", &entry, "hunter2", Some("deploy"))
+        .unwrap();
+    std::sync::Arc::new(tokio::sync::Mutex::new(mgr))
+}
+
+#[test]
+fn test_ssh_params() {
+    let params = ssh::ssh_params();
+    let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["host", "command", "timeout", "sudo"]);
+    assert!(params.iter().filter(|p| p.required).count() == 2);
+}
+
+#[test]
+fn test_ssh_lookup_host_allowlist() {
+    let empty = std::collections::HashMap::new();
+    let err = ssh::lookup_host(&empty, "web").unwrap_err();
+    assert!(err.contains("No SSH hosts are configured"));
+
+    let hosts = std::collections::HashMap::from([
+        ("web".to_string(), ssh_host(Some("web_key"), None)),
+        ("db".to_string(), ssh_host(None, Some("db_pw"))),
+    ]);
+    assert!(ssh::lookup_host(&hosts, "web").is_ok());
+    let err = ssh::lookup_host(&hosts, "10.0.0.5").unwrap_err();
+    assert!(err.contains("not in the SSH host allowlist"));
+    assert!(err.ends_with("Configured hosts: db, web"));
+}
+
+#[test]
+fn test_ssh_remote_command() {
+    assert_eq!(ssh::remote_command("uptime", false, false), "uptime");
+    assert_eq!(
+        ssh::remote_command("cat /etc/shadow | wc -l", true, false),
+        "sudo -n -- sh -c 'cat /etc/shadow | wc -l'"
+    );
+    assert_eq!(
+        ssh::remote_command("echo 'hi'", true, true),
+        r"sudo -S -p '' -- sh -c 'echo '\''hi'\'''"
+    );
+}
+
+#[tokio::test]
+async fn test_ssh_key_auth_runs_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = ssh_vault(dir.path());
+    let hosts =
+        std::collections::HashMap::from([("web".to_string(), ssh_host(Some("web_key"), None))]);
+    let mock = MockSsh::default();
+
+    let out = ssh::run_ssh(
+        &json!({"host": "web", "command": "uptime"}),
+        &hosts,
+        &vault,
+        &mock,
+    )
+    .await
+    .unwrap();
+    let out: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(out["host"], "web");
+    assert_eq!(out["exit_code"], 0);
+    assert_eq!(out["stdout"], "ok\n");
+    // Unpinned host: the key is reported so it can be pinned.
+    assert_eq!(out["host_key"], "SHA256:abc");
+
+    let calls = mock.calls.lock().unwrap();
+    assert_eq!(calls.as_slice(), [("uptime".to_string(), "key", None)]);
+}
+
+#[tokio::test]
+async fn test_ssh_sudo_requires_allow_sudo() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = ssh_vault(dir.path());
+    let mut host = ssh_host(None, Some("db_pw"));
+    let mut hosts = std::collections::HashMap::from([("db".to_string(), host.clone())]);
+    let mock = MockSsh::default();
+    let args = json!({"host": "db", "command": "systemctl restart postgresql", "sudo": true});
+
+    let err = ssh::run_ssh(&args, &hosts, &vault, &mock)
+        .await
+        .unwrap_err();
+    assert!(err.contains("sudo is not allowed on 'db'"));
+    assert!(mock.calls.lock().unwrap().is_empty());
+
+    host.allow_sudo = true;
+    host.fingerprint = Some("SHA256:abc".into());
+    hosts.insert("db".to_string(), host);
+    ssh::run_ssh(&args, &hosts, &vault, &mock).await.unwrap();
+    let calls = mock.calls.lock().unwrap();
+    assert_eq!(calls[0].1, "password");
+    assert!(calls[0].0.starts_with("sudo -S -p ''"));
+    assert_eq!(calls[0].2.as_deref(), Some("hunter2\n"));
+}
+
+#[tokio::test]
+async fn test_ssh_unpinned_host_gets_no_password_or_sudo() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = ssh_vault(dir.path());
+    let mut web = ssh_host(Some("web_key"), None);
+    web.allow_sudo = true;
+    let hosts = std::collections::HashMap::from([
+        ("web".to_string(), web),
+        ("db".to_string(), ssh_host(None, Some("db_pw"))),
+    ]);
+    let mock = MockSsh::default();
+
+    for args in [
+        json!({"host": "db", "command": "uptime"}),
+        json!({"host": "web", "command": "uptime", "sudo": true}),
+    ] {
+        let err = ssh::run_ssh(&args, &hosts, &vault, &mock)
+            .await
+            .unwrap_err();
+        assert!(err.contains("no pinned host key"), "{err}");
+    }
+    assert!(mock.calls.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_ssh_host_key_matches_pin() {
+    assert!(ssh::host_key_matches(Some("SHA256:abc"), "SHA256:abc"));
+    assert!(ssh::host_key_matches(Some(" SHA256:abc\n"), "SHA256:abc"));
+    assert!(!ssh::host_key_matches(Some("SHA256:abc"), "SHA256:evil"));
+    // Unpinned: accepted for key auth; run_ssh refuses passwords and sudo.
+    assert!(ssh::host_key_matches(None, "SHA256:evil"));
+}
+
+#[tokio::test]
+async fn test_ssh_missing_credential() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = ssh_vault(dir.path());
+    let hosts =
+        std::collections::HashMap::from([("web".to_string(), ssh_host(Some("no_such_key"), None))]);
+    let err = ssh::run_ssh(
+        &json!({"host": "web", "command": "uptime"}),
+        &hosts,
+        &vault,
+        &MockSsh::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("'no_such_key'"), "{err}");
+}
+
+#[tokio::test]
+async fn test_ssh_timeout() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = ssh_vault(dir.path());
+    let hosts =
+        std::collections::HashMap::from([("web".to_string(), ssh_host(Some("web_key"), None))]);
+    let mock = MockSsh {
+        delay: Some(std::time::Duration::from_secs(30)),
+        ..Default::default()
+    };
+    let err = ssh::run_ssh(
+        &json!({"host": "web", "command": "sleep 30", "timeout": 1}),
+        &hosts,
+        &vault,
+        &mock,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("timed out after 1s"));
+}
+
+// ── watch_file / watch_poll ─────────────────────────────────────
+
+/// Poll `watch_id` until an event passes `want` or a few seconds pass.
+fn poll_watch_until(
+    watch_id: &str,
+    workspace: &Path,
+    want: impl Fn(&serde_json::Value) -> bool,
+) -> Vec<serde_json::Value> {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    let mut seen = Vec::new();
+    while std::time::Instant::now() < deadline {
+        let out = exec_watch_poll(&json!({ "watch_id": watch_id }), workspace).unwrap();
+        let out: serde_json::Value = serde_json::from_str(&out).unwrap();
+        seen.extend(
+            out["watches"][0]["events"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .cloned(),
+        );
+        if seen.iter().any(&want) {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    seen
+}
+
+#[test]
+fn test_watch_params() {
+    assert!(
+        watch_file_params()
+            .iter()
+            .any(|p| p.name == "path" && p.required)
+    );
+    assert!(watch_poll_params().iter().all(|p| !p.required));
+}
+
+#[test]
+fn test_watch_file_write_produces_modify_event() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "one").unwrap();
+    let out = exec_watch_file(&json!({ "path": "notes.txt" }), dir.path()).unwrap();
+    let out: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let id = out["watch_id"].as_str().unwrap().to_string();
+
+    std::fs::write(dir.path().join("notes.txt"), "two").unwrap();
+    let events = poll_watch_until(&id, dir.path(), |e| e["kind"] == "modified");
+    assert!(
+        events
+            .iter()
+            .any(|e| e["kind"] == "modified" && e["path"] == "notes.txt"),
+        "{events:?}"
+    );
+
+    let out = exec_watch_poll(&json!({ "watch_id": id, "stop": true }), dir.path()).unwrap();
+    assert!(out.contains("\"stopped\":true"));
+    assert!(exec_watch_poll(&json!({ "watch_id": id }), dir.path()).is_err());
+}
+
+#[test]
+fn test_watch_glob_filters_events() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    let out = exec_watch_file(&json!({ "path": "src/*.rs" }), dir.path()).unwrap();
+    let out: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let id = out["watch_id"].as_str().unwrap().to_string();
+
+    std::fs::write(dir.path().join("src/notes.txt"), "skip").unwrap();
+    std::fs::write(dir.path().join("src/lib.rs"), "fn main() {}").unwrap();
+    let events = poll_watch_until(&id, dir.path(), |e| e["kind"] == "created");
+    assert!(
+        events
+            .iter()
+            .any(|e| e["kind"] == "created" && e["path"] == "src/lib.rs"),
+        "{events:?}"
+    );
+    assert!(events.iter().all(|e| e["path"] != "src/notes.txt"));
+    exec_watch_poll(&json!({ "watch_id": id, "stop": true }), dir.path()).unwrap();
+}
+
+#[test]
+fn test_watch_file_rejects_paths_outside_workspace() {
+    let dir = tempfile::tempdir().unwrap();
+    let other = tempfile::tempdir().unwrap();
+    let err = exec_watch_file(
+        &json!({ "path": other.path().to_string_lossy() }),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("inside the workspace"), "{err}");
+
+    let err = exec_watch_file(&json!({ "path": "../elsewhere" }), dir.path()).unwrap_err();
+    assert!(err.contains(".."), "{err}");
+}
+
+// ── encode ──────────────────────────────────────────────────────
+
+#[test]
+fn test_encode_params() {
+    let params = encode_params();
+    assert!(params.iter().any(|p| p.name == "action" && p.required));
+    assert!(params.iter().any(|p| p.name == "path" && !p.required));
+}
+
+#[test]
+fn test_encode_base64_round_trip() {
+    let text = "héllo, wörld?\n";
+    let encoded = exec_encode(&json!({ "action": "base64_encode", "input": text }), ws()).unwrap();
+    assert_eq!(encoded, "aMOpbGxvLCB3w7ZybGQ/Cg==");
+    let decoded = exec_encode(
+        &json!({ "action": "base64_decode", "input": encoded }),
+        ws(),
+    )
+    .unwrap();
+    assert_eq!(decoded, text);
+
+    let url_safe = exec_encode(
+        &json!({ "action": "base64_encode", "input": text, "url_safe": true }),
+        ws(),
+    )
+    .unwrap();
+    assert_eq!(url_safe, "aMOpbGxvLCB3w7ZybGQ_Cg==");
+    let decoded = exec_encode(
+        &json!({ "action": "base64_decode", "input": url_safe }),
+        ws(),
+    )
+    .unwrap();
+    assert_eq!(decoded, text);
+}
+
+#[test]
+fn test_encode_sha256_known_vector() {
+    let out = exec_encode(&json!({ "action": "hash", "input": "abc" }), ws()).unwrap();
+    assert_eq!(
+        out,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    let out = exec_encode(
+        &json!({ "action": "hash", "input": "abc", "algorithm": "md5" }),
+        ws(),
+    )
+    .unwrap();
+    assert_eq!(out, "900150983cd24fb0d6963f7d28e17f72");
+    let err = exec_encode(
+        &json!({ "action": "hash", "input": "abc", "algorithm": "crc32" }),
+        ws(),
+    )
+    .unwrap_err();
+    assert!(err.contains("sha512"), "{err}");
+}
+
+#[test]
+fn test_encode_hex_and_url() {
+    let hex = exec_encode(&json!({ "action": "hex_encode", "input": "Hi!" }), ws()).unwrap();
+    assert_eq!(hex, "486921");
+    let text = exec_encode(
+        &json!({ "action": "hex_decode", "input": "0x48 69 21" }),
+        ws(),
+    )
+    .unwrap();
+    assert_eq!(text, "Hi!");
+    let binary = exec_encode(&json!({ "action": "hex_decode", "input": "ff00" }), ws()).unwrap();
+    assert!(binary.starts_with("(binary data, 2 bytes"), "{binary}");
+
+    let url = exec_encode(&json!({ "action": "url_encode", "input": "a b&c=d" }), ws()).unwrap();
+    assert_eq!(url, "a%20b%26c%3Dd");
+    let text = exec_encode(&json!({ "action": "url_decode", "input": url }), ws()).unwrap();
+    assert_eq!(text, "a b&c=d");
+}
+
+#[test]
+fn test_encode_file_input() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("data.txt"), "abc").unwrap();
+    let out = exec_encode(
+        &json!({ "action": "hash", "path": "data.txt", "algorithm": "sha1" }),
+        dir.path(),
+    )
+    .unwrap();
+    assert_eq!(out, "a9993e364706816aba3e25717850c26c9cd0d89d");
+
+    let err = exec_encode(
+        &json!({ "action": "hash", "path": "data.txt", "input": "abc" }),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("not both"), "{err}");
+
+    let big = std::fs::File::create(dir.path().join("big.bin")).unwrap();
+    big.set_len(encode::MAX_FILE_BYTES + 1).unwrap();
+    let err = exec_encode(&json!({ "action": "hash", "path": "big.bin" }), dir.path()).unwrap_err();
+    assert!(err.contains("larger than"), "{err}");
+}
+
+// ── reminder ────────────────────────────────────────────────────
+
+#[test]
+fn test_reminder_set_list_cancel() {
+    let dir = tempfile::tempdir().unwrap();
+    let args = json!({ "action": "set", "message": "check the build", "delayMinutes": 30 });
+    let marker = exec_reminder(&args, ws()).unwrap();
+    assert!(marker.starts_with(THREAD_UPDATE_MARKER));
+    let err = exec_reminder(&json!({ "action": "set", "delayMinutes": 5 }), ws()).unwrap_err();
+    assert!(err.contains("Missing message"), "{err}");
+
+    let reply = apply_reminder(&args, dir.path(), 7).unwrap();
+    assert!(reply.contains("local time"), "{reply}");
+    let id = reply.split_whitespace().nth(1).unwrap();
+
+    let list = apply_reminder(&json!({ "action": "list" }), dir.path(), 7).unwrap();
+    assert!(
+        list.contains(id) && list.contains("check the build"),
+        "{list}"
+    );
+    let other = apply_reminder(&json!({ "action": "list" }), dir.path(), 8).unwrap();
+    assert_eq!(other, "No pending reminders in this conversation.");
+
+    // Another thread cannot cancel it.
+    let cancel = json!({ "action": "cancel", "id": id });
+    assert!(apply_reminder(&cancel, dir.path(), 8).is_err());
+    apply_reminder(&cancel, dir.path(), 7).unwrap();
+    let list = apply_reminder(&json!({ "action": "list" }), dir.path(), 7).unwrap();
+    assert_eq!(list, "No pending reminders in this conversation.");
+}
+
+#[test]
+fn test_reminder_pending_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    let args = json!({ "action": "set", "message": "ping", "delayMinutes": 10 });
+    for _ in 0..MAX_PENDING_REMINDERS {
+        apply_reminder(&args, dir.path(), 1).unwrap();
+    }
+    let err = apply_reminder(&args, dir.path(), 1).unwrap_err();
+    assert!(err.contains("pending reminders"), "{err}");
+    apply_reminder(&args, dir.path(), 2).unwrap();
+}
+
+#[test]
+fn test_take_due_reminders_only_for_given_threads() {
+    use crate::cron::{CronJob, CronStore, Payload, Schedule, SessionTarget};
+
+    let dir = tempfile::tempdir().unwrap();
+    assert!(take_due_reminders(dir.path(), &[1]).unwrap().is_empty());
+
+    let mut store = CronStore::new(dir.path()).unwrap();
+    for (thread_id, at) in [
+        (1, "2001-01-01T00:05:00Z"),
+        (1, "2001-01-01T00:00:00Z"),
+        (2, "2001-01-01T00:00:00Z"),
+    ] {
+        store
+            .add(CronJob::new(
+                None,
+                Schedule::At { at: at.to_string() },
+                SessionTarget::Main,
+                Payload::Reminder {
+                    thread_id,
+                    message: format!("due at {}", at),
+                },
+            ))
+            .unwrap();
+    }
+
+    let due = take_due_reminders(dir.path(), &[1]).unwrap();
+    assert_eq!(due.len(), 2);
+    assert_eq!(due[0].prompt(), "[Reminder] due at 2001-01-01T00:00:00Z");
+    assert!(due.iter().all(|r| r.thread_id == 1));
+    assert!(take_due_reminders(dir.path(), &[1]).unwrap().is_empty());
+    assert_eq!(take_due_reminders(dir.path(), &[2]).unwrap().len(), 1);
+}
+
+// ── qr ──────────────────────────────────────────────────────────
+
+#[cfg(feature = "qr")]
+#[test]
+fn test_qr_encode_decode_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let text = "https://example.com/pair?code=Ünïcode 42";
+    let out = exec_qr(
+        &json!({"action": "encode", "text": text, "output_path": "codes/pair.png"}),
+        dir.path(),
+    )
+    .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(result["media"]["mime_type"], "image/png");
+    assert!(dir.path().join("codes/pair.png").is_file());
+
+    let decoded = exec_qr(
+        &json!({"action": "decode", "path": "codes/pair.png"}),
+        dir.path(),
+    )
+    .unwrap();
+    assert_eq!(decoded, text);
+}
+
+#[test]
+fn test_qr_encode_unicode_and_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let art = exec_qr(
+        &json!({"action": "encode", "text": "hello", "format": "unicode"}),
+        dir.path(),
+    )
+    .unwrap();
+    assert!(art.contains('█'));
+    assert!(art.lines().count() > 5);
+
+    let long = "x".repeat(3000);
+    let err = exec_qr(&json!({"action": "encode", "text": long}), dir.path()).unwrap_err();
+    assert!(err.contains("at most"), "{err}");
+    assert!(exec_qr(&json!({"action": "decode"}), dir.path()).is_err());
+    assert!(exec_qr(&json!({"action": "scan"}), dir.path()).is_err());
+}
+
+// ── weather ─────────────────────────────────────────────────────
+
+#[test]
+fn test_weather_parses_open_meteo() {
+    use weather::{parse_open_meteo, pick_open_meteo_place};
+
+    let geocoding = json!({"results": [
+        {"name": "Portland", "admin1": "Oregon", "country": "United States",
+         "latitude": 45.52, "longitude": -122.68},
+        {"name": "Portland", "admin1": "Maine", "country": "United States",
+         "latitude": 43.66, "longitude": -70.26},
+    ]});
+    let place = pick_open_meteo_place(&geocoding, Some("Maine")).unwrap();
+    assert_eq!(place.name, "Portland, Maine, United States");
+    assert_eq!(place.latitude, 43.66);
+    assert_eq!(
+        pick_open_meteo_place(&geocoding, None).unwrap().latitude,
+        45.52
+    );
+
+    let body = json!({
+        "current": {
+            "time": "2026-03-01T14:15", "temperature_2m": 7.4,
+            "apparent_temperature": 4.9, "relative_humidity_2m": 81,
+            "weather_code": 61, "wind_speed_10m": 14.2
+        },
+        "daily": {
+            "time": ["2026-03-01", "2026-03-02"],
+            "weather_code": [61, 3],
+            "temperature_2m_max": [9.1, 11.0],
+            "temperature_2m_min": [2.3, 4.5],
+            "precipitation_sum": [4.2, 0.0],
+            "precipitation_probability_max": [90, 10]
+        }
+    });
+    let weather = parse_open_meteo(&body, place, "metric").unwrap();
+    assert_eq!(weather.current.temperature, 7.4);
+    assert_eq!(weather.current.conditions, "light rain");
+    assert_eq!(weather.units.temperature, "°C");
+    assert_eq!(weather.forecast.len(), 2);
+    assert_eq!(weather.forecast[1].date, "2026-03-02");
+    assert_eq!(weather.forecast[1].temp_max, 11.0);
+    assert_eq!(weather.forecast[1].conditions, "overcast");
+
+    let err = parse_open_meteo(&json!({"current": {}}), weather.location, "metric");
+    assert!(err.is_err());
+}
+
+#[test]
+fn test_weather_folds_openweathermap_forecast_into_days() {
+    use weather::{Place, parse_openweathermap};
+
+    // UTC+1; 2026-03-01T00:00:00Z is 1772323200.
+    let midnight = 1_772_323_200_i64;
+    let current = json!({
+        "dt": midnight + 13 * 3600, "timezone": 3600,
+        "main": {"temp": 12.5, "feels_like": 11.0, "humidity": 60},
+        "wind": {"speed": 3.4},
+        "weather": [{"description": "scattered clouds"}]
+    });
+    let slot = |hour: i64, min: f64, max: f64, description: &str, pop: f64, rain: f64| {
+        json!({
+            "dt": midnight + hour * 3600,
+            "main": {"temp_min": min, "temp_max": max},
+            "weather": [{"description": description}],
+            "pop": pop,
+            "rain": {"3h": rain}
+        })
+    };
+    let forecast = json!({"list": [
+        slot(2, 6.0, 7.0, "clear sky", 0.0, 0.0),
+        slot(11, 10.0, 13.0, "light rain", 0.6, 1.25),
+        slot(20, 8.0, 9.0, "light rain", 0.4, 0.5),
+        slot(26, 5.0, 6.0, "mist", 0.1, 0.0),
+        slot(50, 4.0, 5.0, "snow", 0.9, 0.0),
+    ]});
+    let place = Place {
+        name: "Lyon, FR".into(),
+        latitude: 45.76,
+        longitude: 4.84,
+    };
+
+    let weather = parse_openweathermap(&current, &forecast, place, "metric", 2).unwrap();
+    assert_eq!(weather.current.temperature, 12.5);
+    assert_eq!(weather.current.time.as_deref(), Some("2026-03-01T14:00"));
+    assert_eq!(weather.units.wind_speed, "m/s");
+    // Local times: 03:00, 12:00 and 21:00 on the 1st, 03:00 on the 2nd.
+    assert_eq!(weather.forecast.len(), 2);
+    let today = &weather.forecast[0];
+    assert_eq!(today.date, "2026-03-01");
+    assert_eq!((today.temp_min, today.temp_max), (6.0, 13.0));
+    assert_eq!(today.conditions, "light rain");
+    assert_eq!(today.precipitation, Some(1.8));
+    assert_eq!(today.precipitation_chance, Some(60.0));
+    assert_eq!(weather.forecast[1].conditions, "mist");
+}
+
+// ── pdf_create ──────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_pdf_create_builtin_markdown() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut markdown = String::from(
+        "# Report\n\nSome **bold** and *italic* text, plus `code`.\n\n\
+         - first\n- second\n\n1. one\n2. two\n\n```\nfn main() {}\n```\n\n---\n\n",
+    );
+    for i in 0..120 {
+        markdown.push_str(&format!(
+            "Paragraph {} (with parentheses) \\ and – dashes.\n\n",
+            i
+        ));
+    }
+    let args = json!({
+        "markdown": markdown,
+        "output_path": "out/report.pdf",
+        "engine": "builtin",
+        "title": "Report",
+        "page_size": "letter",
+        "footer": "Page {page} of {pages}",
+    });
+
+    let result = execute_tool("pdf_create", &args, dir.path()).await.unwrap();
+    let result: Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["engine"], "builtin");
+    assert_eq!(result["media"]["mime_type"], "application/pdf");
+    let pages = result["pages"].as_u64().unwrap();
+    assert!(pages > 1, "expected several pages, got {}", pages);
+
+    let pdf = std::fs::read(dir.path().join("out/report.pdf")).unwrap();
+    assert_eq!(result["bytes"].as_u64(), Some(pdf.len() as u64));
+    assert!(pdf.starts_with(b"%PDF-1.4\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains(&format!("/Count {}", pages)));
+    assert!(text.contains(&format!("(Page 2 of {})", pages)));
+    assert!(text.contains("(Paragraph 7 \\(with parentheses\\) \\\\ and \\226 dashes.)"));
+    assert!(text.contains("/MediaBox [0 0 612.00 792.00]"));
+}
+
+#[tokio::test]
+async fn test_pdf_create_rejects_bad_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let run = |args: Value| {
+        let dir = dir.path().to_path_buf();
+        async move { execute_tool("pdf_create", &args, &dir).await }
+    };
+    let err = run(json!({"output_path": "a.pdf"})).await.unwrap_err();
+    assert!(err.contains("markdown or html"));
+    let err = run(json!({"markdown": "x", "html": "<p>x</p>", "output_path": "a.pdf"}))
+        .await
+        .unwrap_err();
+    assert!(err.contains("not both"));
+    let err = run(json!({"markdown": "x", "output_path": "a.pdf", "page_size": "b7"}))
+        .await
+        .unwrap_err();
+    assert!(err.contains("Unknown page_size"));
+    let err = run(json!({"markdown": "x", "output_path": "a.pdf", "margin_mm": 500}))
+        .await
+        .unwrap_err();
+    assert!(err.contains("margin_mm"));
+
+    let args = json!({"html": "<h1>Hi</h1><p>there</p>", "output_path": "a.pdf",
+                      "engine": "builtin", "landscape": true});
+    let result: Value = serde_json::from_str(&run(args).await.unwrap()).unwrap();
+    assert_eq!(result["pages"], 1);
+    let pdf = std::fs::read(dir.path().join("a.pdf")).unwrap();
+    assert!(String::from_utf8_lossy(&pdf).contains("/MediaBox [0 0 841.89 595.28]"));
+}
+
+// ── time ──────────────────────────────────────────────────────
+
+#[test]
+fn test_time_convert_across_dst() {
+    let convert = |time: &str| {
+        let args = json!({"action": "convert", "time": time,
+                          "from": "America/New_York", "to": "Europe/London"});
+        let out: Value = serde_json::from_str(&time_tool::exec_time(&args, ws()).unwrap()).unwrap();
+        out
+    };
+    // New York is on summer time from 8 March, London only from 29 March:
+    // the gap between them is four hours for those three weeks.
+    let march = convert("2026-03-20 12:00");
+    assert_eq!(march["from"]["rfc3339"], "2026-03-20T12:00:00-04:00");
+    assert_eq!(march["from"]["dst"], true);
+    assert_eq!(march["to"]["rfc3339"], "2026-03-20T16:00:00+00:00");
+    assert_eq!(march["to"]["dst"], false);
+    let human = march["to"]["human"].as_str().unwrap();
+    assert!(human.contains("16:00:00 GMT"), "{}", human);
+
+    let april = convert("2026-04-01 12:00");
+    assert_eq!(april["to"]["rfc3339"], "2026-04-01T17:00:00+01:00");
+    assert_eq!(april["to"]["utc_offset"], "+01:00");
+    assert_eq!(april["to"]["dst"], true);
+    assert_eq!(april["to"]["unix"], april["from"]["unix"]);
+
+    // London clocks jump from 01:00 to 02:00 that night.
+    let args = json!({"action": "convert", "time": "2026-03-29 01:30",
+                      "from": "Europe/London", "to": "UTC"});
+    let err = time_tool::exec_time(&args, ws()).unwrap_err();
+    assert!(err.contains("does not exist in Europe/London"));
+    let args = json!({"action": "now", "timezone": "Mars/Olympus"});
+    let err = time_tool::exec_time(&args, ws()).unwrap_err();
+    assert!(err.contains("Unknown timezone"));
+}
+
+#[test]
+fn test_time_diff_over_dst_change() {
+    let args = json!({"action": "diff", "start": "2026-03-28 12:00",
+                      "end": "2026-03-29 12:00", "timezone": "Europe/London"});
+    let out: Value = serde_json::from_str(&time_tool::exec_time(&args, ws()).unwrap()).unwrap();
+    assert_eq!(out["seconds"], 23 * 3600);
+    assert_eq!(out["human"], "23 hours");
+    assert_eq!(out["iso8601"], "PT23H");
+    assert_eq!(out["end"], "2026-03-29T12:00:00+01:00");
+
+    let args = json!({"action": "diff", "start": "2026-01-01T00:00:00Z",
+                      "end": "2025-12-30T21:59:30Z"});
+    let out: Value = serde_json::from_str(&time_tool::exec_time(&args, ws()).unwrap()).unwrap();
+    assert_eq!(out["seconds"], -(26 * 3600 + 30));
+    assert_eq!(out["iso8601"], "-P1DT2H30S");
+}
+
+#[test]
+fn test_time_parse_natural() {
+    use time_tool::{Zone, parse_time};
+    let zone = Zone::parse(Some("europe/paris")).unwrap();
+    // Wednesday, four days before Paris moves to summer time.
+    let now = chrono::DateTime::parse_from_rfc3339("2026-03-25T10:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    let utc = |text: &str| parse_time(text, &zone, now).map(|t| t.format("%F %R").to_string());
+    assert_eq!(utc("now").unwrap(), "2026-03-25 10:00");
+    assert_eq!(utc("tomorrow 3pm").unwrap(), "2026-03-26 14:00");
+    assert_eq!(utc("next Friday at 9:30 am").unwrap(), "2026-03-27 08:30");
+    assert_eq!(utc("last wednesday").unwrap(), "2026-03-17 23:00");
+    assert_eq!(utc("March 30").unwrap(), "2026-03-29 22:00");
+    assert_eq!(utc("5th april 2027, noon").unwrap(), "2027-04-05 10:00");
+    assert_eq!(utc("3 hours ago").unwrap(), "2026-03-25 07:00");
+    // Whole days keep the wall-clock time through the change.
+    assert_eq!(utc("in 5 days").unwrap(), "2026-03-30 09:00");
+    let rfc3339 = utc("2026-04-01T09:00:00-07:00").unwrap();
+    assert_eq!(rfc3339, "2026-04-01 16:00");
+    let err = utc("when pigs fly").unwrap_err();
+    assert!(err.contains("Could not parse"));
+    assert!(utc("14").is_err());
+}
+
+#[test]
+fn test_timezone_meeting_overlap_follows_dst() {
+    let meet = |date: &str| {
+        let args = json!({"date": date, "participants": [
+            "Ana America/New_York",
+            "Ben: Europe/London 09:00-17:00",
+            {"name": "Kiran", "timezone": "Asia/Kolkata", "hours": "10am-7pm"},
+        ]});
+        let out = timezone_meeting::exec_timezone_meeting(&args, ws()).unwrap();
+        serde_json::from_str::<Value>(&out).unwrap()
+    };
+    // New York is on summer time and London isn't yet: 09:00 in New York
+    // is 13:00 in London and 18:30 in Kolkata, half an hour before Kiran
+    // stops.
+    let out = meet("2026-03-20");
+    let windows = out["windows"].as_array().unwrap();
+    assert_eq!(windows.len(), 1, "{out}");
+    assert_eq!(windows[0]["start_utc"], "2026-03-20T13:00:00Z");
+    assert_eq!(windows[0]["end_utc"], "2026-03-20T13:30:00Z");
+    assert_eq!(windows[0]["minutes"], 30);
+    let local = &windows[0]["local"];
+    assert_eq!(local[0]["start"], "Fri 2026-03-20 09:00");
+    assert_eq!(local[0]["utc_offset"], "-04:00");
+    assert_eq!(local[1]["start"], "Fri 2026-03-20 13:00");
+    assert_eq!(local[2]["end"], "Fri 2026-03-20 19:00");
+    assert_eq!(out["participants"][2]["utc_offset"], "+05:30");
+
+    // Two weeks earlier New York starts an hour later, after Kiran is done.
+    let out = meet("2026-03-06");
+    assert_eq!(out["windows"], json!([]));
+    let note = out["note"].as_str().unwrap();
+    assert!(note.contains("No common working time"), "{note}");
+}
+
+// ── proofread ───────────────────────────────────────────────────
+
+/// Fake model: fixes "teh" and "recieve" in the prompt's `<text>` and
+/// lists each fix, mangling nothing else.
+async fn fake_proofread(prompt: String) -> Result<String, String> {
+    let body = prompt
+        .split_once("<text>\n")
+        .and_then(|(_, rest)| rest.rsplit_once("\n</text>"))
+        .map(|(body, _)| body)
+        .ok_or("prompt is missing <text> tags")?;
+    let mut corrections = Vec::new();
+    for (wrong, right) in [("teh", "the"), ("recieve", "receive")] {
+        if body.contains(wrong) {
+            corrections.push(json!({"original": wrong, "suggestion": right,
+                                    "reason": "spelling"}));
+        }
+    }
+    let corrected = body.replace("teh", "the").replace("recieve", "receive");
+    let reply = json!({ "corrections": corrections, "corrected": corrected });
+    Ok(format!("```json\n{}\n```", reply))
+}
+
+#[tokio::test]
+async fn test_proofread_leaves_code_untouched() {
+    let code = "```rust\nlet teh = recieve();\n```\n";
+    let text = format!("Call `recieve()` to get teh data.\n\n{code}\nWe recieve it later.\n");
+    let args = json!({ "text": text });
+
+    let mut prompts = Vec::new();
+    let result = proofread::proofread_with(&args, ws(), |prompt| {
+        prompts.push(prompt.clone());
+        fake_proofread(prompt)
+    })
+    .await
+    .unwrap();
+
+    // Neither the fenced block nor the inline span reached the model.
+    assert!(prompts.iter().all(|p| !p.contains("let teh")));
+    assert!(prompts.iter().all(|p| !p.contains("`recieve()`")));
+    let out: Value = serde_json::from_str(&result).unwrap();
+    let corrected = out["corrected"].as_str().unwrap();
+    assert_eq!(
+        corrected,
+        format!("Call `recieve()` to get the data.\n\n{code}\nWe receive it later.\n")
+    );
+    assert_eq!(out["changed"], true);
+    assert_eq!(out["chunks"], 2);
+}
+
+#[tokio::test]
+async fn test_proofread_returns_structured_corrections() {
+    let args = json!({ "text": "I recieve teh mail.", "checks": "spelling" });
+    let result = proofread::proofread_with(&args, ws(), |prompt| {
+        assert!(prompt.contains("spelling mistakes only"));
+        fake_proofread(prompt)
+    })
+    .await
+    .unwrap();
+    let out: Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(out["engine"], "model");
+    assert_eq!(out["corrected"], "I receive the mail.");
+    let corrections: Vec<proofread::Correction> =
+        serde_json::from_value(out["corrections"].clone()).unwrap();
+    assert_eq!(corrections.len(), 2);
+    assert_eq!(corrections[0].original, "teh");
+    assert_eq!(corrections[0].suggestion, "the");
+    assert_eq!(corrections[1].reason, "spelling");
+
+    // A reply that drops an inline code placeholder keeps the chunk as is.
+    let args = json!({ "text": "Run `ls` teh end." });
+    let result = proofread::proofread_with(&args, ws(), |_| async {
+        Ok(r#"{"corrections": [], "corrected": "Run ls the end."}"#.to_string())
+    })
+    .await
+    .unwrap();
+    let out: Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(out["corrected"], "Run `ls` teh end.");
+    assert!(out["warnings"].is_array());
+}
+
+#[test]
+fn test_proofread_parses_ispell_output() {
+    let output = "@(#) International Ispell Version 3.1.20 (but really Aspell 0.60.8)\n\
+                  *\n& teh 3 4: the, tech, ten\n*\n\n\n# qzxv 0\n\n";
+    let lines = proofread::parse_ispell(output);
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0][0].word, "teh");
+    assert_eq!(lines[0][0].suggestions, ["the", "tech", "ten"]);
+    assert!(lines[1].is_empty());
+    assert_eq!(lines[2][0].word, "qzxv");
+    assert!(lines[2][0].suggestions.is_empty());
+
+    let err = proofread::exec_proofread(&json!({"text": "hi"}), ws()).unwrap_err();
+    assert!(err.contains("executed by the gateway"));
+}
+
+// ── move_file / copy_file ───────────────────────────────────────
+
+#[test]
+fn test_move_file_renames_and_moves_into_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("draft.txt"), "hello").unwrap();
+
+    let args = json!({ "source": "draft.txt", "destination": "final.txt" });
+    let out: Value = serde_json::from_str(&exec_move_file(&args, dir.path()).unwrap()).unwrap();
+    assert!(!dir.path().join("draft.txt").exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("final.txt")).unwrap(),
+        "hello"
+    );
+    assert_eq!(out["bytes"], 5);
+    assert_eq!(out["files"], 1);
+    assert_eq!(out["replaced"], false);
+
+    // An existing directory as destination receives the source.
+    std::fs::create_dir(dir.path().join("done")).unwrap();
+    let args = json!({ "source": "final.txt", "destination": "done" });
+    let out: Value = serde_json::from_str(&exec_move_file(&args, dir.path()).unwrap()).unwrap();
+    assert!(dir.path().join("done/final.txt").is_file());
+    assert!(out["destination"].as_str().unwrap().ends_with("final.txt"));
+}
+
+#[test]
+fn test_copy_file_copies_directory_recursively() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    std::fs::create_dir_all(src.join("nested/deeper")).unwrap();
+    std::fs::write(src.join("top.txt"), "top").unwrap();
+    std::fs::write(src.join("nested/deeper/leaf.txt"), "leaf!").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink("top.txt", src.join("link")).unwrap();
+    let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+    std::fs::File::options()
+        .write(true)
+        .open(src.join("top.txt"))
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+
+    let args = json!({ "source": "src", "destination": "backup" });
+    let out: Value = serde_json::from_str(&exec_copy_file(&args, dir.path()).unwrap()).unwrap();
+    let copy = dir.path().join("backup");
+    assert_eq!(
+        std::fs::read_to_string(copy.join("top.txt")).unwrap(),
+        "top"
+    );
+    assert_eq!(
+        std::fs::read_to_string(copy.join("nested/deeper/leaf.txt")).unwrap(),
+        "leaf!"
+    );
+    assert!(src.join("top.txt").exists(), "copy keeps the source");
+    assert_eq!(out["bytes"], 8);
+    let meta = std::fs::metadata(copy.join("top.txt")).unwrap();
+    assert_eq!(meta.modified().unwrap(), old);
+    #[cfg(unix)]
+    {
+        let link = copy.join("link");
+        assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_link(link).unwrap(), Path::new("top.txt"));
+        assert_eq!(out["files"], 3);
+    }
+
+    // A directory can't be copied into itself.
+    let args = json!({ "source": "src", "destination": "src/nested" });
+    let err = exec_copy_file(&args, dir.path()).unwrap_err();
+    assert!(err.contains("into itself"), "{err}");
+}
+
+#[test]
+fn test_file_ops_refuse_overwrite_without_force() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("new.txt"), "new").unwrap();
+    std::fs::write(dir.path().join("old.txt"), "old").unwrap();
+
+    let args = json!({ "source": "new.txt", "destination": "old.txt" });
+    let err = exec_copy_file(&args, dir.path()).unwrap_err();
+    assert!(err.contains("force=true"), "{err}");
+    let err = exec_move_file(&args, dir.path()).unwrap_err();
+    assert!(err.contains("already exists"), "{err}");
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("old.txt")).unwrap(),
+        "old"
+    );
+    assert!(dir.path().join("new.txt").exists());
+
+    // force replaces an entry of the same kind only.
+    std::fs::create_dir(dir.path().join("folder")).unwrap();
+    std::fs::write(dir.path().join("folder/new.txt"), "").unwrap();
+    let args = json!({ "source": "new.txt", "destination": "folder", "force": true });
+    let out: Value = serde_json::from_str(&exec_move_file(&args, dir.path()).unwrap()).unwrap();
+    assert_eq!(out["replaced"], true);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("folder/new.txt")).unwrap(),
+        "new"
+    );
+    let args = json!({ "source": "old.txt", "destination": "folder/", "force": true });
+    std::fs::create_dir(dir.path().join("folder/old.txt")).unwrap();
+    let err = exec_move_file(&args, dir.path()).unwrap_err();
+    assert!(err.contains("same kind"), "{err}");
+}
+
+// ── regex ───────────────────────────────────────────────────────
+
+#[test]
+fn test_regex_params_defined() {
+    let params = regex_params();
+    assert!(params.iter().any(|p| p.name == "pattern" && p.required));
+    assert!(params.iter().any(|p| p.name == "input" && !p.required));
+}
+
+#[test]
+fn test_regex_find_all_and_extract_capture_groups() {
+    let text = "alpha=1\nbeta=22\ngamma\ndelta=333";
+    let args = json!({ "action": "find_all", "pattern": r"(\w+)=(\d+)", "input": text });
+    let out: Value = serde_json::from_str(&exec_regex(&args, ws()).unwrap()).unwrap();
+    assert_eq!(out["count"], 3);
+    assert_eq!(out["truncated"], false);
+    assert_eq!(out["matches"][1]["match"], "beta=22");
+    assert_eq!(out["matches"][1]["groups"], json!(["beta", "22"]));
+    assert_eq!(out["matches"][2]["line"], 4);
+
+    let args = json!({
+        "action": "extract",
+        "pattern": r"(?P<key>\w+)=(?P<value>\d+)",
+        "input": text,
+        "limit": 2,
+    });
+    let out: Value = serde_json::from_str(&exec_regex(&args, ws()).unwrap()).unwrap();
+    assert_eq!(
+        out["records"],
+        json!([{ "key": "alpha", "value": "1" }, { "key": "beta", "value": "22" }])
+    );
+    assert_eq!(out["stopped_by"], "limit");
+
+    // Without named groups there is nothing to key the records by.
+    let args = json!({ "action": "extract", "pattern": r"(\w+)=", "input": text });
+    assert!(
+        exec_regex(&args, ws())
+            .unwrap_err()
+            .contains("named groups")
+    );
+}
+
+#[test]
+fn test_regex_replace_with_backreferences() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("dates.txt"),
+        "due 2024-03-09, paid 2024-04-01\n",
+    )
+    .unwrap();
+
+    let args = json!({
+        "action": "replace",
+        "pattern": r"(?P<y>\d{4})-(\d{2})-(\d{2})",
+        "replacement": "$3/$2/${y}",
+        "path": "dates.txt",
+    });
+    let out: Value = serde_json::from_str(&exec_regex(&args, dir.path()).unwrap()).unwrap();
+    assert_eq!(out["replacements"], 2);
+    assert_eq!(out["text"], "due 09/03/2024, paid 01/04/2024\n");
+
+    let mut args = args;
+    args["in_place"] = json!(true);
+    args["limit"] = json!(1);
+    exec_regex(&args, dir.path()).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("dates.txt")).unwrap(),
+        "due 09/03/2024, paid 2024-04-01\n"
+    );
+}
+
+#[test]
+fn test_regex_pathological_patterns_are_guarded() {
+    // Nested quantifiers that backtrack exponentially elsewhere finish
+    // promptly: matching is linear in the input.
+    let input = format!("{}!", "a".repeat(50_000));
+    let args = json!({ "action": "find_all", "pattern": "^(a+)+$", "input": input });
+    let started = std::time::Instant::now();
+    let out: Value = serde_json::from_str(&exec_regex(&args, ws()).unwrap()).unwrap();
+    assert_eq!(out["count"], 0);
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+    // A pattern that would compile to something enormous is refused.
+    let args = json!({ "action": "find_all", "pattern": r"(\w{100}){100}{100}", "input": "x" });
+    let err = exec_regex(&args, ws()).unwrap_err();
+    assert!(err.contains("too complex"), "{err}");
+}
+
+// ── env_var ─────────────────────────────────────────────────────
+
+#[test]
+fn test_env_var_params_defined() {
+    let params = env_var_params();
+    assert!(params.iter().any(|p| p.name == "action" && p.required));
+    assert!(params.iter().any(|p| p.name == "value" && !p.required));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_env_var_set_is_visible_to_later_commands() {
+    let set = json!({ "action": "set", "name": "RC_TEST_GREETING", "value": "hello from env" });
+    execute_tool("env_var", &set, ws()).await.unwrap();
+
+    // Both the default yielding path and the immediate (sandbox) path.
+    for yield_ms in [10_000, 0] {
+        let args = json!({ "command": "echo \"$RC_TEST_GREETING\"", "yieldMs": yield_ms });
+        let out = execute_tool("execute_command", &args, ws()).await.unwrap();
+        assert!(out.contains("hello from env"), "yieldMs={yield_ms}: {out}");
+    }
+
+    let unset = json!({ "action": "unset", "name": "RC_TEST_GREETING" });
+    let out: Value = serde_json::from_str(&exec_env_var(&unset, ws()).unwrap()).unwrap();
+    assert_eq!(out["removed"], true);
+    let args = json!({ "command": "echo \"[$RC_TEST_GREETING]\"" });
+    let out = execute_tool("execute_command", &args, ws()).await.unwrap();
+    assert!(out.contains("[]"), "{out}");
+}
+
+#[tokio::test]
+async fn test_env_var_sessions_are_isolated() {
+    use super::session;
+
+    let set = json!({ "action": "set", "name": "RC_TEST_SESSION_VAR", "value": "one" });
+    session::scope("env-test-one", execute_tool("env_var", &set, ws()))
+        .await
+        .unwrap();
+
+    let get = json!({ "action": "get", "name": "RC_TEST_SESSION_VAR" });
+    let other = session::scope("env-test-two", execute_tool("env_var", &get, ws()))
+        .await
+        .unwrap();
+    assert!(!other.contains("\"one\""), "{other}");
+    assert!(
+        session::scope("env-test-two", async { super::session_env() })
+            .await
+            .is_empty()
+    );
+
+    let own = session::scope("env-test-one", execute_tool("env_var", &get, ws()))
+        .await
+        .unwrap();
+    assert!(own.contains("\"one\""), "{own}");
+}
+
+#[test]
+fn test_env_var_masks_sensitive_values() {
+    let set = json!({ "action": "set", "name": "RC_TEST_API_TOKEN", "value": "abc123" });
+    let out: Value = serde_json::from_str(&exec_env_var(&set, ws()).unwrap()).unwrap();
+    assert_eq!(out["sensitive"], true);
+    assert!(out["warning"].is_string());
+    assert!(!out.to_string().contains("abc123"));
+
+    // A recognisable key is masked whatever the variable is called.
+    let key = "sk-proj-abcdefghijklmnopqrstuvwxyz0123";
+    let set = json!({ "action": "set", "name": "RC_TEST_PLAIN", "value": key });
+    exec_env_var(&set, ws()).unwrap();
+    let list = exec_env_var(&json!({ "action": "list" }), ws()).unwrap();
+    assert!(!list.contains(key) && !list.contains("abc123"), "{list}");
+    let get = exec_env_var(&json!({ "action": "get", "name": "RC_TEST_PLAIN" }), ws()).unwrap();
+    assert!(!get.contains(key), "{get}");
+
+    let bad = json!({ "action": "set", "name": "1BAD", "value": "x" });
+    assert!(exec_env_var(&bad, ws()).is_err());
+
+    for name in ["RC_TEST_API_TOKEN", "RC_TEST_PLAIN"] {
+        exec_env_var(&json!({ "action": "unset", "name": name }), ws()).unwrap();
+    }
+}
+
+// ── docker ──────────────────────────────────────────────────────
+
+#[test]
+fn test_docker_params_defined() {
+    let params = docker_params();
+    assert!(params.iter().any(|p| p.name == "action" && p.required));
+    assert!(params.iter().any(|p| p.name == "privileged" && !p.required));
+}
+
+#[test]
+fn test_docker_parse_containers_docker_and_podman() {
+    let docker = concat!(
+        r#"{"Command":"\"nginx -g 'daemon of…\"","CreatedAt":"2026-10-01 12:00:00 +0000 UTC","#,
+        r#""ID":"3f4e5d6c7b8a","Image":"nginx:1.27","Names":"web","#,
+        r#""Ports":"0.0.0.0:8080->80/tcp","State":"running","Status":"Up 2 hours"}"#,
+        "\n",
+        r#"{"ID":"0a1b2c3d4e5f","Image":"alpine","Names":"job","State":"exited","#,
+        r#""Status":"Exited (0) 5 minutes ago","Ports":""}"#,
+        "\n",
+    );
+    let containers = docker_tool::parse_containers(docker);
+    assert_eq!(containers.len(), 2);
+    assert_eq!(containers[0]["name"], "web");
+    assert_eq!(containers[0]["ports"], "0.0.0.0:8080->80/tcp");
+    assert_eq!(containers[1]["state"], "exited");
+
+    let podman = r#"[{
+        "Id": "9d8c7b6a5f4e3d2c1b0a9d8c7b6a5f4e3d2c1b0a9d8c7b6a5f4e3d2c1b0a9d8c",
+        "Image": "docker.io/library/redis:7", "Names": ["cache"], "State": "running",
+        "Status": "Up 3 minutes", "Command": ["redis-server"],
+        "Ports": [{"host_ip": "", "container_port": 6379, "host_port": 6380,
+                   "range": 1, "protocol": "tcp"}]
+    }]"#;
+    let containers = docker_tool::parse_containers(podman);
+    assert_eq!(containers.len(), 1);
+    assert_eq!(containers[0]["id"], "9d8c7b6a5f4e");
+    assert_eq!(containers[0]["name"], "cache");
+    assert_eq!(containers[0]["command"], "redis-server");
+    assert_eq!(containers[0]["ports"], "6380->6379/tcp");
+}
+
+#[test]
+fn test_docker_parse_images() {
+    let docker = concat!(
+        r#"{"CreatedSince":"3 weeks ago","ID":"b2aa39c304c2","#,
+        r#""Repository":"alpine","Size":"7.8MB","Tag":"3.20"}"#,
+    );
+    let images = docker_tool::parse_images(docker);
+    assert_eq!(images[0]["repository"], "alpine");
+    assert_eq!(images[0]["tag"], "3.20");
+    assert_eq!(images[0]["size"], "7.8MB");
+
+    let podman = r#"[{"Id":"sha256:b2aa39c304c27b96c1fef0c4bd8e8e6c2a7e9f0d1c2b3a4958677a6b5c4d3e2f",
+        "RepoTags":["localhost:5000/tools/builder:1.2"],"Size":77812345,"Created":1759320000}]"#;
+    let images = docker_tool::parse_images(podman);
+    assert_eq!(images[0]["id"], "b2aa39c304c2");
+    assert_eq!(images[0]["repository"], "localhost:5000/tools/builder");
+    assert_eq!(images[0]["tag"], "1.2");
+    assert_eq!(images[0]["size"], "77.8MB");
+}
+
+#[test]
+fn test_docker_parse_memory() {
+    assert_eq!(docker_tool::parse_memory("512m"), Some(512 << 20));
+    assert_eq!(docker_tool::parse_memory("2G"), Some(2 << 30));
+    assert_eq!(docker_tool::parse_memory("1.5gb"), Some(3 << 29));
+    assert_eq!(docker_tool::parse_memory("1024"), Some(1024));
+    assert_eq!(docker_tool::parse_memory("lots"), None);
+    assert_eq!(docker_tool::parse_memory("-1g"), None);
+}
+
+#[test]
+fn test_docker_run_plan_applies_limits() {
+    let config = crate::config::DockerConfig::default();
+    let workspace = tempfile::tempdir().unwrap();
+    let args = json!({
+        "action": "run",
+        "image": "alpine:3.20",
+        "command": "echo hi",
+        "env": { "GREETING": "hello" },
+        "volumes": ["./data:/data:ro", "cache:/cache"],
+    });
+    let plan = docker_tool::plan_run(&args, &config, workspace.path()).unwrap();
+    assert!(plan.detach);
+    assert_eq!(plan.memory, "2g");
+    let argv = plan.argv.join(" ");
+    assert!(
+        argv.starts_with("run --detach --memory 2g --cpus 1"),
+        "{argv}"
+    );
+    assert!(argv.ends_with("alpine:3.20 sh -c echo hi"), "{argv}");
+    // Values travel in the environment, not the argument list.
+    assert!(
+        argv.contains("--env GREETING") && !argv.contains("hello"),
+        "{argv}"
+    );
+    assert_eq!(
+        plan.env,
+        vec![("GREETING".to_string(), "hello".to_string())]
+    );
+    let bind = format!("{}:/data:ro", workspace.path().join("./data").display());
+    assert!(plan.argv.contains(&bind), "{argv}");
+    assert!(plan.argv.contains(&"cache:/cache".to_string()), "{argv}");
+
+    // A call may lower the limits but not raise them.
+    let lower = json!({ "image": "alpine", "memory": "256m", "cpus": 0.5 });
+    let plan = docker_tool::plan_run(&lower, &config, workspace.path()).unwrap();
+    assert_eq!((plan.memory.as_str(), plan.cpus), ("256m", 0.5));
+    for over in [
+        json!({ "image": "alpine", "memory": "4g" }),
+        json!({ "image": "alpine", "cpus": 2.0 }),
+    ] {
+        let err = docker_tool::plan_run(&over, &config, workspace.path()).unwrap_err();
+        assert!(err.contains("exceeds the [docker] limit"), "{err}");
+    }
+
+    let option = json!({ "image": "--privileged" });
+    assert!(docker_tool::plan_run(&option, &config, workspace.path()).is_err());
+}
+
+#[test]
+fn test_docker_run_plan_guards_privileges() {
+    let workspace = tempfile::tempdir().unwrap();
+    let refused = [
+        (
+            json!({ "image": "alpine", "privileged": true }),
+            "allow_privileged",
+        ),
+        (
+            json!({ "image": "alpine", "network": "host" }),
+            "allow_host_network",
+        ),
+        (
+            json!({ "image": "alpine", "volumes": ["/var/run/docker.sock:/var/run/docker.sock"] }),
+            "allow_privileged",
+        ),
+    ];
+    let config = crate::config::DockerConfig::default();
+    for (args, setting) in &refused {
+        let err = docker_tool::plan_run(args, &config, workspace.path()).unwrap_err();
+        assert!(err.contains(setting), "{err}");
+    }
+
+    let permissive = crate::config::DockerConfig {
+        allow_privileged: true,
+        allow_host_network: true,
+        ..Default::default()
+    };
+    for (args, _) in &refused {
+        docker_tool::plan_run(args, &permissive, workspace.path()).unwrap();
+    }
+    let args = json!({ "image": "alpine", "network": "none" });
+    let plan = docker_tool::plan_run(&args, &config, workspace.path()).unwrap();
+    assert!(plan.argv.join(" ").contains("--network none"));
+}
+
+#[test]
+fn test_docker_run_refuses_mounts_containing_the_vault() {
+    let workspace = tempfile::tempdir().unwrap();
+    let home = std::env::temp_dir().join(format!("rustyclaw-docker-{}", std::process::id()));
+    std::fs::create_dir_all(home.join("credentials")).unwrap();
+    set_credentials_dir(home.join("credentials"));
+    // The directory is set once per process, so use whichever won.
+    let cred_dir = helpers::credentials_dir().unwrap();
+    let parent = cred_dir.parent().unwrap();
+
+    let config = crate::config::DockerConfig::default();
+    for host in [cred_dir, parent] {
+        let args = json!({ "image": "alpine", "volumes": [format!("{}:/x:ro", host.display())] });
+        let err = docker_tool::plan_run(&args, &config, workspace.path()).unwrap_err();
+        assert_eq!(err, VAULT_ACCESS_DENIED);
+    }
+}
+
+#[tokio::test]
+async fn test_docker_refuses_privileged_without_engine() {
+    // The policy check runs before engine detection, so this holds
+    // whether or not docker is installed.
+    let args = json!({ "action": "run", "image": "alpine", "privileged": true });
+    let err = execute_tool("docker", &args, ws()).await.unwrap_err();
+    assert!(err.contains("allow_privileged"), "{err}");
+    let err = execute_tool("docker", &json!({ "action": "pull" }), ws())
+        .await
+        .unwrap_err();
+    assert!(err.contains("Unknown action"), "{err}");
+}
+
+/// Image used by the round-trip test; it is never pulled.
+const DOCKER_TEST_IMAGE: &str = "alpine:latest";
+
+/// Whether the engine the tool would pick is reachable and has
+/// [`DOCKER_TEST_IMAGE`] locally.
+fn docker_test_ready() -> bool {
+    let installed = |bin: &&str| {
+        std::process::Command::new("which")
+            .arg(bin)
+            .output()
+            .is_ok_and(|o| o.status.success())
+    };
+    let Some(bin) = ["docker", "podman"].into_iter().find(installed) else {
+        return false;
+    };
+    std::process::Command::new(bin)
+        .args(["image", "inspect", DOCKER_TEST_IMAGE])
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+#[tokio::test]
+async fn test_docker_run_ps_logs_round_trip() {
+    if !docker_test_ready() {
+        eprintln!("skipping: no docker/podman socket or {DOCKER_TEST_IMAGE} not present");
+        return;
+    }
+    let name = format!("rustyclaw-test-{}", std::process::id());
+    let run = json!({
+        "action": "run",
+        "image": DOCKER_TEST_IMAGE,
+        "name": &name,
+        "command": "echo rustyclaw-docker-test; sleep 30",
+        "memory": "64m",
+    });
+    let out: Value =
+        serde_json::from_str(&execute_tool("docker", &run, ws()).await.unwrap()).unwrap();
+    assert_eq!(out["detached"], true);
+
+    let ps = json!({ "action": "ps", "all": true });
+    let out: Value =
+        serde_json::from_str(&execute_tool("docker", &ps, ws()).await.unwrap()).unwrap();
+    let listed = out["containers"].as_array().unwrap();
+    assert!(listed.iter().any(|c| c["name"] == name.as_str()), "{out}");
+
+    let logs = json!({ "action": "logs", "container": &name, "tail": 10 });
+    let mut stdout = String::new();
+    for _ in 0..20 {
+        let out: Value =
+            serde_json::from_str(&execute_tool("docker", &logs, ws()).await.unwrap()).unwrap();
+        stdout = out["stdout"].as_str().unwrap_or_default().to_string();
+        if stdout.contains("rustyclaw-docker-test") {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    }
+    assert!(stdout.contains("rustyclaw-docker-test"), "{stdout}");
+
+    let exec = json!({ "action": "exec", "container": &name, "command": ["echo", "inside"] });
+    let out: Value =
+        serde_json::from_str(&execute_tool("docker", &exec, ws()).await.unwrap()).unwrap();
+    assert_eq!(out["exit_code"], 0);
+    assert_eq!(out["stdout"].as_str().unwrap().trim(), "inside");
+
+    let rm = json!({ "action": "rm", "container": &name, "force": true });
+    execute_tool("docker", &rm, ws()).await.unwrap();
+}
+
+// ── kv_store ────────────────────────────────────────────────────
+
+fn kv(ws: &Path, session: &str, args: Value) -> Value {
+    serde_json::from_str(&kv_store::exec_kv_store_in(&args, ws, session).unwrap()).unwrap()
+}
+
+#[test]
+fn test_kv_store_set_get_list_delete() {
+    let dir = tempfile::tempdir().unwrap();
+    let ws = dir.path();
+
+    let out = kv(
+        ws,
+        "s1",
+        json!({ "action": "set", "key": "progress", "value": "{\"done\": 3}" }),
+    );
+    assert_eq!(out["replaced"], false);
+    kv(
+        ws,
+        "s1",
+        json!({ "action": "set", "key": "note", "value": "plain text" }),
+    );
+    kv(
+        ws,
+        "s1",
+        json!({ "action": "set", "key": "count", "value": 7 }),
+    );
+
+    let out = kv(ws, "s1", json!({ "action": "get", "key": "progress" }));
+    assert_eq!(out["found"], true);
+    assert_eq!(out["value"]["done"], 3);
+    let out = kv(ws, "s1", json!({ "action": "get", "key": "note" }));
+    assert_eq!(out["value"], "plain text");
+    let out = kv(ws, "s1", json!({ "action": "get", "key": "count" }));
+    assert_eq!(out["value"], 7);
+
+    let out = kv(ws, "s1", json!({ "action": "list" }));
+    assert_eq!(out["count"], 3);
+    assert_eq!(out["keys"], json!(["count", "note", "progress"]));
+
+    let out = kv(ws, "s1", json!({ "action": "delete", "key": "note" }));
+    assert_eq!(out["removed"], true);
+    let out = kv(ws, "s1", json!({ "action": "get", "key": "note" }));
+    assert_eq!(out["found"], false);
+
+    // Stored on disk, so a later call (or gateway restart) sees it.
+    assert!(ws.join(".rustyclaw/kv_store.json").exists());
+}
+
+#[test]
+fn test_kv_store_sessions_are_isolated() {
+    let dir = tempfile::tempdir().unwrap();
+    let ws = dir.path();
+
+    kv(
+        ws,
+        "s1",
+        json!({ "action": "set", "key": "step", "value": "1" }),
+    );
+    let out = kv(ws, "s2", json!({ "action": "get", "key": "step" }));
+    assert_eq!(out["found"], false);
+    assert_eq!(kv(ws, "s2", json!({ "action": "list" }))["count"], 0);
+
+    // Global keys are shared, and separate from session keys of the same name.
+    kv(
+        ws,
+        "s1",
+        json!({ "action": "set", "key": "step", "value": "9", "global": true }),
+    );
+    let out = kv(
+        ws,
+        "s2",
+        json!({ "action": "get", "key": "step", "global": true }),
+    );
+    assert_eq!(out["value"], 9);
+    let out = kv(ws, "s1", json!({ "action": "get", "key": "step" }));
+    assert_eq!(out["value"], 1);
+}
+
+#[tokio::test]
+async fn test_kv_store_follows_calling_session() {
+    use super::session;
+
+    let dir = tempfile::tempdir().unwrap();
+    let ws = dir.path();
+    let set_a = json!({ "action": "set", "key": "who", "value": "a" });
+    let set_b = json!({ "action": "set", "key": "who", "value": "b" });
+    let (a, b) = tokio::join!(
+        session::scope("kv-a", execute_tool("kv_store", &set_a, ws)),
+        session::scope("kv-b", execute_tool("kv_store", &set_b, ws)),
+    );
+    a.unwrap();
+    b.unwrap();
+
+    for (name, want) in [("kv-a", "a"), ("kv-b", "b")] {
+        let out = kv(ws, name, json!({ "action": "get", "key": "who" }));
+        assert_eq!(out["value"], want);
+    }
+}
+
+#[test]
+fn test_kv_store_caps_total_size() {
+    let dir = tempfile::tempdir().unwrap();
+    let ws = dir.path();
+    let big = "x".repeat(kv_store::MAX_STORE_BYTES / 2 + 1);
+
+    kv(
+        ws,
+        "s1",
+        json!({ "action": "set", "key": "a", "value": big.clone() }),
+    );
+    let args = json!({ "action": "set", "key": "b", "value": big });
+    let err = kv_store::exec_kv_store_in(&args, ws, "s1").unwrap_err();
+    assert!(err.contains("limit"), "{err}");
+    assert_eq!(kv(ws, "s1", json!({ "action": "list" }))["count"], 1);
+}
+
+#[test]
+fn test_kv_store_rejects_bad_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let args = json!({ "action": "set", "key": "k" });
+    let err = kv_store::exec_kv_store_in(&args, dir.path(), "s1").unwrap_err();
+    assert!(err.contains("value"), "{err}");
+    let args = json!({ "action": "bump", "key": "k" });
+    let err = kv_store::exec_kv_store_in(&args, dir.path(), "s1").unwrap_err();
+    assert!(err.contains("Unknown action"), "{err}");
+}
+
+// ── http_server ─────────────────────────────────────────────────────────────
+
+async fn http_server(args: Value, ws: &Path) -> Value {
+    let out = http_server::exec_http_server_async(&args, ws)
+        .await
+        .unwrap();
+    serde_json::from_str(&out).unwrap()
+}
+
+fn local_client() -> reqwest::Client {
+    reqwest::Client::builder().no_proxy().build().unwrap()
+}
+
+#[tokio::test]
+async fn test_http_server_captures_first_request() {
+    let dir = tempfile::tempdir().unwrap();
+    let started = http_server(
+        json!({ "action": "capture", "reply": "thanks" }),
+        dir.path(),
+    )
+    .await;
+    let id = started["id"].as_str().unwrap();
+    let url = started["url"].as_str().unwrap();
+    assert!(url.starts_with("http://127.0.0.1:"), "{url}");
+    assert!(
+        started["listen"]
+            .as_str()
+            .unwrap()
+            .starts_with("127.0.0.1:")
+    );
+
+    let resp = local_client()
+        .post(format!("{}callback?code=abc%201&state=xyz", url))
+        .header("X-Signature", "sig")
+        .body("{\"event\":\"push\"}")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.text().await.unwrap(), "thanks");
+
+    let out = http_server(
+        json!({ "action": "result", "id": id, "wait_secs": 5 }),
+        dir.path(),
+    )
+    .await;
+    assert_eq!(out["captured"], true);
+    let request = &out["request"];
+    assert_eq!(request["method"], "POST");
+    assert_eq!(request["path"], "/callback");
+    assert_eq!(request["query"]["code"], "abc 1");
+    assert_eq!(request["query"]["state"], "xyz");
+    assert_eq!(request["headers"]["x-signature"], "sig");
+    assert_eq!(request["body"], "{\"event\":\"push\"}");
+    assert_eq!(out["running"], false);
+
+    http_server(json!({ "action": "stop", "id": id }), dir.path()).await;
+}
+
+#[tokio::test]
+async fn test_http_server_serves_directory_without_escaping() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let dir = tempfile::tempdir().unwrap();
+    let shared = dir.path().join("site");
+    std::fs::create_dir(&shared).unwrap();
+    std::fs::write(shared.join("report.txt"), "quarterly numbers").unwrap();
+    std::fs::write(dir.path().join("secret.txt"), "do not serve").unwrap();
+
+    let started = http_server(
+        json!({ "action": "serve", "path": "site", "lifetime_secs": 60 }),
+        dir.path(),
+    )
+    .await;
+    let id = started["id"].as_str().unwrap();
+    let url = started["url"].as_str().unwrap();
+
+    let client = local_client();
+    let resp = client
+        .get(format!("{}report.txt", url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(
+        resp.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+    assert_eq!(resp.text().await.unwrap(), "quarterly numbers");
+    let listing = client.get(url).send().await.unwrap().text().await.unwrap();
+    assert!(listing.contains("report.txt"), "{listing}");
+
+    // Clients normalise `..` away, so send the request by hand.
+    let addr = started["listen"].as_str().unwrap();
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /../secret.txt HTTP/1.1\r\nHost: x\r\n\r\n")
+        .await
+        .unwrap();
+    let mut resp = String::new();
+    stream.read_to_string(&mut resp).await.unwrap();
+    assert!(resp.starts_with("HTTP/1.1 404"), "{resp}");
+    assert!(!resp.contains("do not serve"));
+
+    let out = http_server(json!({ "action": "stop", "id": id }), dir.path()).await;
+    assert_eq!(out["stopped"], true);
+}
+
+#[tokio::test]
+async fn test_http_server_enforces_limits() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+
+    let started = http_server(
+        json!({ "action": "serve", "path": "a.txt", "lifetime_secs": 999999 }),
+        dir.path(),
+    )
+    .await;
+    assert_eq!(started["lifetime_secs"], http_server::MAX_LIFETIME_SECS);
+    assert!(started["url"].as_str().unwrap().ends_with("/a.txt"));
+    http_server(json!({ "action": "stop", "id": started["id"] }), dir.path()).await;
+
+    let started = http_server(
+        json!({ "action": "capture", "lifetime_secs": 1 }),
+        dir.path(),
+    )
+    .await;
+    let out = http_server(
+        json!({ "action": "result", "id": started["id"], "wait_secs": 5 }),
+        dir.path(),
+    )
+    .await;
+    assert_eq!(out["captured"], false);
+    assert_eq!(out["running"], false);
+}
+
+#[tokio::test]
+async fn test_http_server_rejects_bad_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let ws = dir.path();
+    let err = http_server::exec_http_server_async(&json!({ "action": "serve" }), ws)
+        .await
+        .unwrap_err();
+    assert!(err.contains("path"), "{err}");
+    let args = json!({ "action": "serve", "path": "missing.txt" });
+    let err = http_server::exec_http_server_async(&args, ws)
+        .await
+        .unwrap_err();
+    assert!(err.contains("Cannot serve"), "{err}");
+    let args = json!({ "action": "result", "id": "http-0" });
+    let err = http_server::exec_http_server_async(&args, ws)
+        .await
+        .unwrap_err();
+    assert!(err.contains("No listener"), "{err}");
+    let args = json!({ "action": "listen" });
+    let err = http_server::exec_http_server_async(&args, ws)
+        .await
+        .unwrap_err();
+    assert!(err.contains("Unknown action"), "{err}");
+}
+
+#[test]
+fn test_http_server_refuses_root_outside_sandbox() {
+    use crate::sandbox::{Sandbox, SandboxMode, SandboxPolicy};
+
+    let workspace = tempfile::tempdir().unwrap();
+    let outside = tempfile::tempdir().unwrap();
+    std::fs::write(workspace.path().join("a.txt"), "a").unwrap();
+    std::fs::write(outside.path().join("b.txt"), "b").unwrap();
+    let policy = SandboxPolicy::strict(workspace.path(), vec![workspace.path().to_path_buf()]);
+    let sb = Sandbox::with_mode(SandboxMode::PathValidation, policy);
+
+    http_server::serve_root("a.txt", workspace.path(), Some(&sb)).unwrap();
+    let path = outside.path().join("b.txt");
+    let err =
+        http_server::serve_root(&path.to_string_lossy(), workspace.path(), Some(&sb)).unwrap_err();
+    assert!(err.contains("not in allowed areas"), "{err}");
+}
+
+// ── image_transform ─────────────────────────────────────────────────────────
+
+#[cfg(feature = "image-tools")]
+fn write_test_png(path: &Path, width: u32, height: u32) {
+    let img = image::RgbaImage::from_fn(width, height, |x, y| {
+        image::Rgba([(x % 256) as u8, (y % 256) as u8, 128, 200])
+    });
+    img.save(path).unwrap();
+}
+
+#[cfg(feature = "image-tools")]
+#[test]
+fn test_image_transform_resize_keeps_aspect_ratio() {
+    let dir = tempfile::tempdir().unwrap();
+    write_test_png(&dir.path().join("wide.png"), 400, 200);
+
+    let out = exec_image_transform(
+        &json!({"action": "resize", "path": "wide.png", "width": 100}),
+        dir.path(),
+    )
+    .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(
+        (result["width"].as_u64(), result["height"].as_u64()),
+        (Some(100), Some(50))
+    );
+    assert_eq!(result["media"]["mime_type"], "image/png");
+    let written = dir.path().join("wide-100x50.png");
+    assert_eq!(result["path"], written.display().to_string());
+    assert_eq!(image::image_dimensions(&written).unwrap(), (100, 50));
+
+    // A box with the wrong shape is fitted, not stretched.
+    let out = exec_image_transform(
+        &json!({"action": "resize", "path": "wide.png", "width": 80, "height": 80,
+                "output_path": "thumbs/t.png"}),
+        dir.path(),
+    )
+    .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(
+        (result["width"].as_u64(), result["height"].as_u64()),
+        (Some(80), Some(40))
+    );
+    assert!(dir.path().join("thumbs/t.png").is_file());
+
+    let err = exec_image_transform(
+        &json!({"action": "resize", "path": "wide.png", "width": 100_000}),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("capped"), "{err}");
+}
+
+#[cfg(feature = "image-tools")]
+#[test]
+fn test_image_transform_converts_png_to_jpeg() {
+    let dir = tempfile::tempdir().unwrap();
+    write_test_png(&dir.path().join("shot.png"), 64, 48);
+
+    let out = exec_image_transform(
+        &json!({"action": "convert", "path": "shot.png", "format": "jpeg", "quality": 70}),
+        dir.path(),
+    )
+    .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(result["format"], "jpg");
+    assert_eq!(result["media"]["mime_type"], "image/jpeg");
+    assert_eq!(
+        result["path"],
+        dir.path().join("shot.jpg").display().to_string()
+    );
+
+    let info =
+        exec_image_transform(&json!({"action": "info", "path": "shot.jpg"}), dir.path()).unwrap();
+    let info: Value = serde_json::from_str(&info).unwrap();
+    assert_eq!(info["format"], "jpg");
+    assert_eq!(
+        (info["width"].as_u64(), info["height"].as_u64()),
+        (Some(64), Some(48))
+    );
+
+    let out = exec_image_transform(
+        &json!({"action": "crop", "path": "shot.png", "x": 10, "y": 8, "width": 20, "height": 16}),
+        dir.path(),
+    )
+    .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(
+        (result["width"].as_u64(), result["height"].as_u64()),
+        (Some(20), Some(16))
+    );
+    let err = exec_image_transform(
+        &json!({"action": "crop", "path": "shot.png", "x": 60, "width": 20, "height": 16}),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("outside"), "{err}");
+}
+
+#[test]
+fn test_image_transform_rejects_bad_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = exec_image_transform(&json!({"action": "rotate", "path": "a.png"}), dir.path())
+        .unwrap_err();
+    assert!(err.contains("Unknown action"), "{err}");
+    let err = exec_image_transform(&json!({"action": "info"}), dir.path()).unwrap_err();
+    assert!(err.contains("path"), "{err}");
+    let err = exec_image_transform(
+        &json!({"action": "info", "path": "missing.png"}),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("not found"), "{err}");
+}
+
+// ── screenshot_annotate ─────────────────────────────────────────────────────
+
+#[cfg(feature = "image-tools")]
+#[test]
+fn test_screenshot_annotate_draws_onto_a_copy() {
+    let dir = tempfile::tempdir().unwrap();
+    write_test_png(&dir.path().join("shot.png"), 200, 100);
+
+    let args = json!({
+        "path": "shot.png",
+        "color": "#00ff00",
+        "line_width": 2,
+        "annotations": [
+            {"type": "rect", "x": 20, "y": 40, "width": 40, "height": 30, "label": "Here"},
+            {"type": "arrow", "x1": "90%", "y1": "90%", "x2": 150, "y2": 50},
+            {"type": "text", "x": 0, "y": "75%", "text": "Step 1", "color": "black"},
+        ],
+    });
+    let out = exec_screenshot_annotate(&args, dir.path()).unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    let written = dir.path().join("shot-annotated.png");
+    assert_eq!(result["path"], written.display().to_string());
+    assert_eq!(result["media"]["mime_type"], "image/png");
+    assert_eq!(result["annotations"], 3);
+
+    let original = image::open(dir.path().join("shot.png")).unwrap().to_rgba8();
+    let annotated = image::open(&written).unwrap().to_rgba8();
+    assert_eq!(annotated.dimensions(), (200, 100));
+    let green = [0, 255, 0, 255];
+    // The outline sits just outside the box, leaving what it marks alone.
+    assert_eq!(annotated.get_pixel(19, 55).0, green);
+    assert_eq!(annotated.get_pixel(40, 55), original.get_pixel(40, 55));
+    // The label's tag above the box, the arrow's tip, the black text tag.
+    assert_eq!(annotated.get_pixel(18, 14).0, green);
+    assert_eq!(annotated.get_pixel(150, 51).0, green);
+    assert_eq!(annotated.get_pixel(0, 75).0, [0, 0, 0, 255]);
+    // The input itself is untouched.
+    assert_eq!(original.get_pixel(19, 55).0, [19, 55, 128, 200]);
+}
+
+#[test]
+fn test_screenshot_annotate_rejects_bad_annotations() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = exec_screenshot_annotate(&json!({"path": "a.png"}), dir.path()).unwrap_err();
+    assert!(err.contains("annotations"), "{err}");
+    let err = exec_screenshot_annotate(&json!({"path": "a.png", "annotations": []}), dir.path())
+        .unwrap_err();
+    assert!(err.contains("empty"), "{err}");
+    let rect = json!([{"type": "rect", "x": 0, "y": 0, "width": 5, "height": 5}]);
+    let err = exec_screenshot_annotate(
+        &json!({"path": "missing.png", "annotations": rect}),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("not found"), "{err}");
+
+    #[cfg(feature = "image-tools")]
+    {
+        write_test_png(&dir.path().join("shot.png"), 50, 50);
+        let annotate = |annotations: Value| {
+            exec_screenshot_annotate(
+                &json!({"path": "shot.png", "annotations": annotations}),
+                dir.path(),
+            )
+        };
+        let err = annotate(json!([{"type": "rect", "x": 40, "y": 0, "width": 20, "height": 5}]))
+            .unwrap_err();
+        assert!(err.contains("outside"), "{err}");
+        assert!(err.starts_with("Annotation 1"), "{err}");
+        let err = annotate(json!([{"type": "arrow", "x1": 0, "y1": 0, "x2": "120%", "y2": 0}]))
+            .unwrap_err();
+        assert!(err.contains("x2"), "{err}");
+        let err = annotate(json!([{"type": "circle", "x": 1, "y": 1}])).unwrap_err();
+        assert!(err.contains("unknown type"), "{err}");
+        // Annotations sent as JSON text are accepted.
+        let out = annotate(json!(r#"[{"type": "text", "x": 1, "y": 1, "text": "ok"}]"#)).unwrap();
+        assert!(out.contains("shot-annotated.png"), "{out}");
+    }
+}
+
+// ── translate_file ──────────────────────────────────────────────────────────
+
+/// Stand-in model for `translate_file`: "translates" every segment of a
+/// batch by upper-casing it.
+async fn fake_batch_translate(prompt: String) -> Result<String, String> {
+    let (_, segments) = prompt
+        .split_once("\n\n<seg id=\"")
+        .ok_or("prompt has no segments")?;
+    let replies = translate_file::parse_batch_reply(&format!("<seg id=\"{}", segments));
+    let mut ids: Vec<_> = replies.keys().copied().collect();
+    ids.sort_unstable();
+    Ok(ids
+        .iter()
+        .map(|id| {
+            format!(
+                "<seg id=\"{}\">\n{}\n</seg>\n",
+                id,
+                replies[id].to_uppercase()
+            )
+        })
+        .collect())
+}
+
+#[test]
+fn test_translate_file_params_defined() {
+    let params = translate_file_params();
+    assert!(params.iter().any(|p| p.name == "path" && p.required));
+    assert!(params.iter().any(|p| p.name == "target_lang" && p.required));
+    assert!(params.iter().any(|p| p.name == "output" && !p.required));
+}
+
+#[tokio::test]
+async fn test_translate_file_srt_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let srt = "1\n00:00:01,000 --> 00:00:03,500\nHello there.\n\n\
+               2\n00:00:04,000 --> 00:00:06,250\n<i>Where are you going?</i>\nHome.\n\n\
+               3\n00:00:07,000 --> 00:00:08,000\nHello there.\n";
+    std::fs::write(dir.path().join("film.srt"), srt).unwrap();
+
+    let mut calls = 0usize;
+    let result = translate_file::translate_file_with(
+        &json!({"path": "film.srt", "target_lang": "fr"}),
+        dir.path(),
+        |prompt| {
+            calls += 1;
+            assert!(!prompt.contains("-->"), "timings sent to the model");
+            fake_batch_translate(prompt)
+        },
+    )
+    .await
+    .unwrap();
+
+    let out: Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(out["format"], "srt");
+    assert_eq!(out["segments"], 3);
+    assert_eq!(calls, 1);
+    let written = dir.path().join("film.fr.srt");
+    assert_eq!(out["path"], written.display().to_string());
+
+    let translated = std::fs::read_to_string(&written).unwrap();
+    assert_eq!(
+        translated,
+        "1\n00:00:01,000 --> 00:00:03,500\nHELLO THERE.\n\n\
+         2\n00:00:04,000 --> 00:00:06,250\n<I>WHERE ARE YOU GOING?</I>\nHOME.\n\n\
+         3\n00:00:07,000 --> 00:00:08,000\nHELLO THERE.\n"
+    );
+    // The source is never touched.
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("film.srt")).unwrap(),
+        srt
+    );
+}
+
+#[test]
+fn test_translate_file_vtt_keeps_header_and_notes() {
+    let vtt = "WEBVTT\n\nNOTE written by hand\n\nintro\n00:01.000 --> 00:02.000 align:start\n\
+               Good morning\n";
+    let pieces = translate_file::parse(translate_file::Format::Vtt, vtt);
+    let sources: Vec<_> = pieces
+        .iter()
+        .filter_map(|p| match p {
+            translate_file::Piece::Text { source, .. } => Some(source.as_str()),
+            translate_file::Piece::Keep(_) => None,
+        })
+        .collect();
+    assert_eq!(sources, ["Good morning"]);
+    let rendered = translate_file::render(&pieces, &["Bonjour".to_string()]);
+    assert_eq!(rendered, vtt.replace("Good morning", "Bonjour"));
+}
+
+#[tokio::test]
+async fn test_translate_file_markdown_keeps_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let code = "```sh\ncargo run -- --help\n```\n";
+    let md = format!(
+        "---\ntitle: Guide\n---\n# Setup\n\nRun [the tool](https://x.dev).\n\n{code}\n    \
+         indented code\n"
+    );
+    std::fs::write(dir.path().join("guide.md"), &md).unwrap();
+
+    translate_file::translate_file_with(
+        &json!({"path": "guide.md", "target_lang": "de", "output": "out/guide.md"}),
+        dir.path(),
+        fake_batch_translate,
+    )
+    .await
+    .unwrap();
+    let translated = std::fs::read_to_string(dir.path().join("out/guide.md")).unwrap();
+    assert_eq!(
+        translated,
+        format!(
+            "---\ntitle: Guide\n---\n# SETUP\n\nRUN [THE TOOL](HTTPS://X.DEV).\n\n{code}\n    \
+             indented code\n"
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_translate_file_po_fills_msgstr_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let po = "msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\n\
+              #: src/main.rs:10\nmsgid \"Open file\"\nmsgstr \"\"\n\n\
+              msgid \"Save\"\nmsgstr \"Sichern\"\n\n\
+              msgid \"%d file\"\nmsgid_plural \"%d files\"\nmsgstr[0] \"\"\nmsgstr[1] \"\"\n\n\
+              msgid \"Line one\\nLine two\\n\"\nmsgstr \"\"\n";
+    std::fs::write(dir.path().join("de.po"), po).unwrap();
+
+    let result = translate_file::translate_file_with(
+        &json!({"path": "de.po", "target_lang": "de", "output": "de.new.po"}),
+        dir.path(),
+        fake_batch_translate,
+    )
+    .await
+    .unwrap();
+    let out: Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(out["segments"], 4);
+
+    let translated = std::fs::read_to_string(dir.path().join("de.new.po")).unwrap();
+    assert_eq!(
+        translated,
+        "msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\n\
+         #: src/main.rs:10\nmsgid \"Open file\"\nmsgstr \"OPEN FILE\"\n\n\
+         msgid \"Save\"\nmsgstr \"Sichern\"\n\n\
+         msgid \"%d file\"\nmsgid_plural \"%d files\"\nmsgstr[0] \"%D FILE\"\n\
+         msgstr[1] \"%D FILES\"\n\n\
+         msgid \"Line one\\nLine two\\n\"\nmsgstr \"\"\n\"LINE ONE\\n\"\n\"LINE TWO\\n\"\n"
+    );
+}
+
+#[tokio::test]
+async fn test_translate_file_rejects_bad_input() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "hi").unwrap();
+    std::fs::write(dir.path().join("a.srt"), "").unwrap();
+
+    let err = translate_file::translate_file_with(
+        &json!({"path": "notes.txt", "target_lang": "fr"}),
+        dir.path(),
+        fake_batch_translate,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("format"), "{err}");
+    let err = translate_file::translate_file_with(
+        &json!({"path": "a.srt", "target_lang": "fr", "output": "a.srt"}),
+        dir.path(),
+        fake_batch_translate,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("never overwritten"), "{err}");
+    let err = translate_file::translate_file_with(
+        &json!({"path": "a.srt"}),
+        dir.path(),
+        fake_batch_translate,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("target_lang"), "{err}");
+}
+
+// ── process_tree ────────────────────────────────────────────────────────────
+
+fn proc_entry(pid: u32, ppid: u32, name: &str) -> process_tree::ProcessEntry {
+    process_tree::ProcessEntry {
+        pid,
+        ppid,
+        name: name.into(),
+        command: format!("/usr/bin/{name} --flag"),
+        state: "S".into(),
+        rss_kb: Some(1024),
+    }
+}
+
+/// init ─┬─ sshd ── bash ── gateway ─┬─ cargo ─┬─ rustc
+///       │                            │         └─ rustc
+///       │                            └─ node
+///       └─ cron
+fn known_process_tree() -> process_tree::ProcessTree {
+    process_tree::ProcessTree::new(vec![
+        proc_entry(1, 0, "init"),
+        proc_entry(200, 1, "sshd"),
+        proc_entry(210, 200, "bash"),
+        proc_entry(300, 210, "gateway"),
+        proc_entry(410, 300, "cargo"),
+        proc_entry(420, 410, "rustc"),
+        proc_entry(421, 410, "rustc"),
+        proc_entry(500, 300, "node"),
+        proc_entry(90, 1, "cron"),
+    ])
+}
+
+#[test]
+fn test_process_tree_params_defined() {
+    let params = process_tree_params();
+    assert!(params.iter().any(|p| p.name == "action" && p.required));
+    assert!(params.iter().any(|p| p.name == "tree" && !p.required));
+    assert!(params.iter().any(|p| p.name == "confirm" && !p.required));
+}
+
+#[test]
+fn test_process_tree_structure() {
+    let tree = known_process_tree();
+    assert_eq!(tree.roots(), [1]);
+    assert_eq!(tree.children(1), [90, 200]);
+    assert_eq!(tree.children(410), [420, 421]);
+    assert!(tree.children(420).is_empty());
+    assert_eq!(tree.descendants(300), [410, 500, 420, 421]);
+    assert_eq!(tree.ancestors(421), [410, 300, 210, 200, 1]);
+    assert!(tree.is_descendant_of(420, 300));
+    assert!(!tree.is_descendant_of(90, 300));
+
+    // A process whose parent has exited is a root of its own.
+    let orphaned = process_tree::ProcessTree::new(vec![proc_entry(7, 6, "orphan")]);
+    assert_eq!(orphaned.roots(), [7]);
+}
+
+#[test]
+fn test_process_tree_kill_plan() {
+    let tree = known_process_tree();
+    let gateway = 300;
+
+    // The agent's own children need no confirmation; a tree is signalled
+    // children first.
+    let plan = process_tree::kill_plan(&tree, 410, true, gateway).unwrap();
+    assert_eq!(plan.targets, [421, 420, 410]);
+    assert!(!plan.needs_confirmation);
+    let plan = process_tree::kill_plan(&tree, 500, false, gateway).unwrap();
+    assert_eq!(plan.targets, [500]);
+
+    // Anything else does.
+    let plan = process_tree::kill_plan(&tree, 90, false, gateway).unwrap();
+    assert!(plan.needs_confirmation);
+
+    let err = process_tree::kill_plan(&tree, 1, false, gateway).unwrap_err();
+    assert!(err.contains("pid 1"), "{err}");
+    let err = process_tree::kill_plan(&tree, gateway, false, gateway).unwrap_err();
+    assert!(err.contains("gateway itself"), "{err}");
+    // Killing an ancestor's tree would take the gateway with it.
+    let err = process_tree::kill_plan(&tree, 210, true, gateway).unwrap_err();
+    assert!(err.contains("gateway itself"), "{err}");
+    assert!(process_tree::kill_plan(&tree, 999, false, gateway).is_err());
+}
+
+#[test]
+fn test_process_tree_parsers() {
+    let stat = "4242 (tokio (worker)) S 4200 4242 4242 0 -1 4194560 1 0 0 0 \
+                25 5 0 0 20 0 3 0 1000 104857600 256 18446744073709551615";
+    let (entry, fields) = process_tree::parse_proc_stat(stat, 4).unwrap();
+    assert_eq!(entry.pid, 4242);
+    assert_eq!(entry.ppid, 4200);
+    assert_eq!(entry.name, "tokio (worker)");
+    assert_eq!(entry.state, "S");
+    assert_eq!(entry.rss_kb, Some(1024));
+    assert_eq!(fields[17], "3");
+
+    let entry = process_tree::parse_ps_line("  812   1   5120 Ss   /usr/sbin/sshd -D").unwrap();
+    assert_eq!((entry.pid, entry.ppid), (812, 1));
+    assert_eq!(entry.name, "sshd");
+    assert_eq!(entry.state, "S");
+    assert_eq!(entry.command, "/usr/sbin/sshd -D");
+    assert_eq!(entry.rss_kb, Some(5120));
+
+    assert_eq!(process_tree::parse_signal("SIGKILL"), Ok(9));
+    assert_eq!(process_tree::parse_signal("term"), Ok(15));
+    assert_eq!(process_tree::parse_signal("2"), Ok(2));
+    assert!(process_tree::parse_signal("SEGV").is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_process_tree_signals_own_child() {
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let pid = child.id();
+
+    let out = exec_process_tree(&json!({"action": "info", "pid": pid}), ws()).unwrap();
+    let info: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(info["ppid"], std::process::id());
+    assert_eq!(info["started_by_agent"], true);
+
+    let out = exec_process_tree(&json!({"action": "kill", "pid": pid}), ws()).unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(result["status"], "signalled", "{out}");
+    assert_eq!(result["signal"], "SIGTERM");
+    assert!(!child.wait().unwrap().success());
+}
+
+// ── parse_log ───────────────────────────────────────────────────────────────
+
+const MIXED_LOG: &str = r#"{"timestamp":"2024-03-01T10:00:00Z","level":"INFO","fields":{"message":"server started on port 8080"}}
+{"time":1709287260000,"level":50,"msg":"db query failed after 1200ms"}
+<11>1 2024-03-01T10:01:30Z web01 nginx 812 - - upstream timed out
+Mar  1 10:02:00 web01 sshd[4242]: Accepted publickey for deploy
+2024-03-01 10:03:00,120 WARNING [worker] retrying job 17 in 5s
+2024-03-01 10:03:05,000 ERROR [worker] job 17 failed
+Traceback (most recent call last):
+  File "worker.py", line 12, in run
+ValueError: bad input
+2024-03-01 10:04:00,000 ERROR [worker] job 18 failed
+{"timestamp":"2024-03-01T10:05:00Z","level":"error","message":"db query failed after 950ms"}
+plain line without level
+"#;
+
+fn write_log(content: &str) -> (tempfile::TempDir, std::path::PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    std::fs::write(&path, content).unwrap();
+    (dir, path)
+}
+
+#[test]
+fn test_parse_log_params_defined() {
+    let params = parse_log_params();
+    assert!(params.iter().any(|p| p.name == "path" && p.required));
+    for name in ["level", "pattern", "since", "until", "tail", "limit", "top"] {
+        assert!(
+            params.iter().any(|p| p.name == name && !p.required),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_log_recognises_formats() {
+    use parse_log::{Level, LogFormat, parse_line};
+
+    let json = parse_line(r#"{"time":1709287260000,"level":50,"msg":"boom"}"#);
+    assert_eq!(json.format, LogFormat::Json);
+    assert_eq!(json.level, Some(Level::Error));
+    assert_eq!(json.message, "boom");
+    assert_eq!(
+        json.timestamp.unwrap().to_rfc3339(),
+        "2024-03-01T10:01:00+00:00"
+    );
+
+    let rfc5424 = parse_line("<12>1 2024-03-01T10:01:30Z web01 nginx 812 - - slow upstream");
+    assert_eq!(rfc5424.format, LogFormat::Syslog);
+    assert_eq!(rfc5424.level, Some(Level::Warn));
+    assert_eq!(rfc5424.message, "nginx: slow upstream");
+
+    let rfc3164 = parse_line("Mar  1 10:02:00 web01 sshd[4242]: error: auth failed");
+    assert_eq!(rfc3164.format, LogFormat::Syslog);
+    assert!(rfc3164.timestamp.is_some());
+    assert_eq!(rfc3164.message, "sshd: error: auth failed");
+
+    let plain = parse_line("2024-03-01T10:03:00+02:00 [WARN] disk at 91%");
+    assert_eq!(plain.format, LogFormat::Plain);
+    assert_eq!(plain.level, Some(Level::Warn));
+    assert_eq!(plain.message, "disk at 91%");
+    assert_eq!(
+        plain.timestamp.unwrap().to_rfc3339(),
+        "2024-03-01T08:03:00+00:00"
+    );
+
+    let java = parse_line("[main] ERROR com.example.App - connection refused");
+    assert_eq!(java.level, Some(Level::Error));
+    assert_eq!(parse_line("just some text").level, None);
+}
+
+#[test]
+fn test_parse_log_summarizes_mixed_log() {
+    let (_dir, path) = write_log(MIXED_LOG);
+    let out = exec_parse_log(&json!({"path": path.to_str().unwrap()}), ws()).unwrap();
+    assert!(
+        out.contains("12 lines (json 3, syslog 2, plain 4)"),
+        "{out}"
+    );
+    assert!(
+        out.contains("Levels: error 5, warn 1, info 1, none 2"),
+        "{out}"
+    );
+    assert!(out.contains("Matched: 12 of 12 lines"), "{out}");
+    // The two db failures differ only in their duration.
+    assert!(
+        out.contains("2× error db query failed after <n>ms"),
+        "{out}"
+    );
+    assert!(out.contains("2× error [worker] job <n> failed"), "{out}");
+    assert!(out.contains("L12: plain line without level"), "{out}");
+}
+
+#[test]
+fn test_parse_log_level_filter_keeps_stack_traces() {
+    let (_dir, path) = write_log(MIXED_LOG);
+    let out = exec_parse_log(
+        &json!({"path": path.to_str().unwrap(), "level": "error", "limit": 4}),
+        ws(),
+    )
+    .unwrap();
+    assert!(
+        out.contains("Matched: 8 of 12 lines (level ≥ error)"),
+        "{out}"
+    );
+    assert!(out.contains("Last 4 of 8 matching lines:"), "{out}");
+    assert!(out.contains("L9: ValueError: bad input"), "{out}");
+    assert!(!out.contains("retrying job"), "{out}");
+
+    let out = exec_parse_log(
+        &json!({"path": path.to_str().unwrap(), "pattern": "(?i)timed out|refused"}),
+        ws(),
+    )
+    .unwrap();
+    assert!(out.contains("Matched: 1 of 12 lines"), "{out}");
+    assert!(out.contains("L3: <11>1"), "{out}");
+}
+
+#[test]
+fn test_parse_log_time_window_and_tail() {
+    let (_dir, path) = write_log(MIXED_LOG);
+    let path = path.to_str().unwrap();
+    let out = exec_parse_log(
+        &json!({
+            "path": path,
+            "since": "2024-03-01T10:00:30Z",
+            "until": "2024-03-01T10:01:45Z",
+        }),
+        ws(),
+    )
+    .unwrap();
+    assert!(out.contains("Matched: 2 of 12 lines"), "{out}");
+    assert!(out.contains("L2:") && out.contains("L3:"), "{out}");
+
+    let out = exec_parse_log(&json!({"path": path, "tail": 2, "top": 0}), ws()).unwrap();
+    assert!(out.contains("2 lines from the end"), "{out}");
+    assert!(out.contains("  plain line without level"), "{out}");
+    assert!(!out.contains("L2:"), "{out}");
+    assert!(!out.contains("Top messages"), "{out}");
+}
+
+#[test]
+fn test_parse_log_rejects_bad_arguments() {
+    let (_dir, path) = write_log(MIXED_LOG);
+    let path = path.to_str().unwrap();
+    let err = exec_parse_log(&json!({"path": path, "level": "loud"}), ws()).unwrap_err();
+    assert!(err.contains("Unknown level"), "{err}");
+    let err = exec_parse_log(&json!({"path": path, "pattern": "("}), ws()).unwrap_err();
+    assert!(err.contains("Invalid pattern"), "{err}");
+    let err = exec_parse_log(&json!({"path": path, "since": "yesterday"}), ws()).unwrap_err();
+    assert!(err.contains("Invalid time"), "{err}");
+
+    let now = chrono::Utc::now();
+    let hour_ago = parse_log::parse_time_arg("1h", now).unwrap();
+    assert_eq!(now - hour_ago, chrono::Duration::hours(1));
+    assert!(parse_log::parse_time_arg("99999999999999w", now).is_err());
+}
+
+// ── text_stats ──────────────────────────────────────────────────────────────
+
+#[test]
+fn test_text_stats_counts_known_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    let lines: Vec<String> = (1..=30)
+        .map(|n| format!("line {} of the file", n))
+        .collect();
+    std::fs::write(
+        &path,
+        lines.join("\n") + "\nthe longest line by a good margin\n",
+    )
+    .unwrap();
+
+    let out = exec_text_stats(
+        &json!({"path": path.to_str().unwrap(), "head": 3, "tail": 2, "pattern": "line \\d+"}),
+        ws(),
+    )
+    .unwrap();
+    let size = std::fs::metadata(&path).unwrap().len();
+    assert!(
+        out.contains(&format!(
+            "31 lines, 157 words, {} bytes, {} chars",
+            size, size
+        )),
+        "{out}"
+    );
+    assert!(out.contains("Encoding: ASCII"), "{out}");
+    assert!(out.contains("Line endings: LF\n"), "{out}");
+    assert!(out.contains("Longest line: 33 chars (line 31)"), "{out}");
+    assert!(
+        out.contains("Pattern /line \\d+/: 30 matches on 30 lines"),
+        "{out}"
+    );
+    assert!(
+        out.contains("First 3 lines:\n 1  line 1 of the file"),
+        "{out}"
+    );
+    assert!(
+        out.contains("Last 2 lines:\n30  line 30 of the file"),
+        "{out}"
+    );
+    assert!(!out.contains("line 4 of"), "{out}");
+}
+
+#[test]
+fn test_text_stats_detects_line_endings() {
+    let dir = tempfile::tempdir().unwrap();
+    let crlf = dir.path().join("crlf.txt");
+    std::fs::write(&crlf, "\u{feff}one\r\ntwo\r\nthree").unwrap();
+    let out = exec_text_stats(&json!({"path": crlf.to_str().unwrap()}), ws()).unwrap();
+    assert!(out.contains("3 lines, 3 words"), "{out}");
+    assert!(out.contains("Encoding: UTF-8 (BOM)"), "{out}");
+    assert!(
+        out.contains("Line endings: CRLF; no newline at end of file"),
+        "{out}"
+    );
+    assert!(
+        out.contains("All 3 lines:\n1  one\n2  two\n3  three"),
+        "{out}"
+    );
+
+    let mixed = dir.path().join("mixed.txt");
+    std::fs::write(&mixed, "a\r\nb\nc\n").unwrap();
+    let out = exec_text_stats(&json!({"path": mixed.to_str().unwrap()}), ws()).unwrap();
+    assert!(out.contains("Line endings: mixed (1 CRLF, 2 LF)"), "{out}");
+
+    let utf16 = dir.path().join("utf16.txt");
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(
+        "héllo\r\nwörld\r\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes),
+    );
+    std::fs::write(&utf16, bytes).unwrap();
+    let out = exec_text_stats(&json!({"path": utf16.to_str().unwrap()}), ws()).unwrap();
+    assert!(out.contains("2 lines, 2 words"), "{out}");
+    assert!(out.contains("Encoding: UTF-16LE (BOM)"), "{out}");
+    assert!(out.contains("Line endings: CRLF\n"), "{out}");
+}
+
+#[test]
+fn test_text_stats_reports_binary_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("blob.bin");
+    std::fs::write(&path, [0x7F, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0]).unwrap();
+    let out = exec_text_stats(&json!({"path": path.to_str().unwrap()}), ws()).unwrap();
+    assert!(out.contains("binary file, 10 bytes"), "{out}");
+    assert!(!out.contains("lines"), "{out}");
+
+    let err = exec_text_stats(
+        &json!({"path": path.to_str().unwrap(), "pattern": "("}),
+        ws(),
+    );
+    assert!(err.unwrap_err().contains("Invalid pattern"));
+}
+
+// ── color ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_color_convert_hex_to_hsl() {
+    let run = |color: &str| {
+        let out = exec_color(&json!({"action": "convert", "color": color}), ws()).unwrap();
+        serde_json::from_str::<Value>(&out).unwrap()
+    };
+    let out = run("#336699");
+    assert_eq!(out["hsl"], json!({"h": 210.0, "s": 50.0, "l": 40.0}));
+    assert_eq!(out["rgb"], json!({"r": 51, "g": 102, "b": 153}));
+    assert_eq!(out["css"]["hsl"], "hsl(210, 50%, 40%)");
+    assert_eq!(
+        out["human"],
+        "#336699 = rgb(51, 102, 153) = hsl(210, 50%, 40%)"
+    );
+
+    // Every accepted form of the same color agrees.
+    for form in [
+        "369",
+        "#369",
+        "rgb(51, 102, 153)",
+        "rgb(20% 40% 60%)",
+        "hsl(210deg 50% 40%)",
+    ] {
+        assert_eq!(run(form)["hex"], "#336699", "{}", form);
+    }
+    assert_eq!(run("Navy")["hex"], "#000080");
+
+    let err = exec_color(&json!({"action": "convert", "color": "#12345"}), ws()).unwrap_err();
+    assert!(err.contains("Invalid color"), "{}", err);
+    let args = json!({"action": "convert", "color": "rgb(300, 0, 0)"});
+    assert!(exec_color(&args, ws()).unwrap_err().contains("0 to 255"));
+}
+
+#[test]
+fn test_color_contrast_matches_wcag_reference() {
+    let run = |fg: &str, bg: &str| {
+        let args = json!({"action": "contrast", "foreground": fg, "background": bg});
+        serde_json::from_str::<Value>(&exec_color(&args, ws()).unwrap()).unwrap()
+    };
+    let out = run("black", "#fff");
+    assert_eq!(out["ratio"], 21.0);
+
+    // #767676 is the lightest grey that passes AA for body text on white.
+    let out = run("#767676", "#ffffff");
+    assert_eq!(out["ratio"], 4.54);
+    assert_eq!(out["aa"], json!({"normal": true, "large": true}));
+    assert_eq!(out["aaa"], json!({"normal": false, "large": true}));
+    let human = out["human"].as_str().unwrap();
+    assert!(human.starts_with("#767676 on #ffffff: 4.54:1"), "{}", human);
+
+    // Order doesn't matter, and a near miss fails.
+    let out = run("#ffffff", "#777777");
+    assert_eq!(out["ratio"], 4.48);
+    assert_eq!(out["aa"]["normal"], false);
+}
+
+#[test]
+fn test_color_palette() {
+    let args = json!({"action": "palette", "color": "#336699", "count": 3});
+    let out: Value = serde_json::from_str(&exec_color(&args, ws()).unwrap()).unwrap();
+    let shades = out["shades"].as_array().unwrap();
+    assert_eq!(shades.len(), 3);
+    assert_eq!(shades[1], "#4080bf");
+    assert_eq!(out["complement"], "#996633");
+    assert_eq!(out["base"]["hex"], "#336699");
+    let human = out["human"].as_str().unwrap();
+    assert!(human.contains("Complement: #996633"), "{}", human);
+
+    let args = json!({"action": "palette", "color": "#336699", "count": 50});
+    let err = exec_color(&args, ws()).unwrap_err();
+    assert!(err.contains("between 2 and 20"), "{}", err);
+}
+
+// ── network_request_replay ──────────────────────────────────────────────────
+
+#[test]
+fn test_request_replay_parses_multi_header_curl_with_json_body() {
+    let command = r#"curl 'https://api.example.com/v1/items?page=2' \
+  -X PUT \
+  -H 'Accept: application/json' \
+  -H "Authorization: Bearer {{secret:example_token}}" \
+  -H 'X-Trace-Id:  abc-123 ' \
+  --data-raw '{"name": "widget", "tags": ["a", "b"], "note": "it'\''s here"}' \
+  --compressed -sS"#;
+    let request = request_replay::parse_curl(command).unwrap();
+    assert_eq!(request.method, "PUT");
+    assert_eq!(request.url, "https://api.example.com/v1/items?page=2");
+    assert_eq!(
+        request.headers,
+        vec![
+            ("Accept".to_string(), "application/json".to_string()),
+            (
+                "Authorization".to_string(),
+                "Bearer {{secret:example_token}}".to_string()
+            ),
+            ("X-Trace-Id".to_string(), "abc-123".to_string()),
+            (
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded".to_string()
+            ),
+        ]
+    );
+    let body: Value = serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        json!({"name": "widget", "tags": ["a", "b"], "note": "it's here"})
+    );
+    assert_eq!(
+        request.ignored,
+        vec!["--compressed", "--silent", "--show-error"]
+    );
+}
+
+#[test]
+fn test_request_replay_curl_options() {
+    // --json sets JSON headers; data without -X is a POST.
+    let request = request_replay::parse_curl(
+        "curl --json '{\"a\":1}' -u 'bot:{{secret:pw}}' -H 'Content-Type: text/json' \
+         api.example.com/x",
+    )
+    .unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.url, "http://api.example.com/x");
+    assert_eq!(request.basic_auth.as_deref(), Some("bot:{{secret:pw}}"));
+    assert_eq!(request.headers.len(), 2);
+    assert_eq!(request.headers[1].0, "Accept");
+
+    // -G moves the data into the query; -XDELETE is an attached value.
+    let request =
+        request_replay::parse_curl("curl -G -d q=rust --data-urlencode 'w=a b' https://h.test/s")
+            .unwrap();
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.url, "https://h.test/s?q=rust&w=a%20b");
+    assert!(request.body.is_none());
+    let request = request_replay::parse_curl("curl -XDELETE https://h.test/1").unwrap();
+    assert_eq!(request.method, "DELETE");
+
+    for bad in [
+        "curl -F file=@x.png https://h.test",
+        "curl -d @body.json https://h.test",
+        "curl 'https://h.test",
+        "curl -H",
+    ] {
+        assert!(request_replay::parse_curl(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn test_request_replay_parses_har_entry() {
+    let har = json!({"log": {"entries": [
+        {"request": {"method": "get", "url": "https://a.test/", "headers": []}},
+        {"request": {
+            "method": "POST",
+            "url": "https://api.example.com/login",
+            "headers": [
+                {"name": ":authority", "value": "api.example.com"},
+                {"name": "x-csrf", "value": "{{secret:csrf}}"}
+            ],
+            "postData": {"mimeType": "application/json", "text": "{\"user\":\"me\"}"}
+        }}
+    ]}});
+    let request = request_replay::parse_har(&har, 1).unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(
+        request.headers,
+        vec![
+            ("x-csrf".to_string(), "{{secret:csrf}}".to_string()),
+            ("Content-Type".to_string(), "application/json".to_string()),
+        ]
+    );
+    assert_eq!(request.body.as_deref(), Some("{\"user\":\"me\"}"));
+    assert_eq!(request_replay::parse_har(&har, 0).unwrap().method, "GET");
+    assert!(request_replay::parse_har(&har, 2).is_err());
+}
+
+#[tokio::test]
+async fn test_request_replay_enforces_host_allowlist() {
+    let allowed = vec!["api.example.com".to_string(), "*.corp.test".to_string()];
+    assert!(request_replay::host_allowed("API.example.com", &allowed));
+    assert!(request_replay::host_allowed("git.corp.test", &allowed));
+    assert!(!request_replay::host_allowed("corp.test", &allowed));
+    assert!(!request_replay::host_allowed("evilcorp.test", &allowed));
+    assert!(!request_replay::host_allowed("example.com", &allowed));
+
+    // No [request_replay] hosts are configured in tests, so nothing is sent
+    // and the vault is never asked for the placeholder.
+    let args = json!({
+        "curl": "curl -H 'Authorization: Bearer {{secret:token}}' https://api.example.com/",
+        "dry_run": true,
+    });
+    let err = request_replay::exec_network_request_replay_async(&args, ws())
+        .await
+        .unwrap_err();
+    assert!(err.contains("not allowed"), "{}", err);
+}
+
+// ── validate ────────────────────────────────────────────────────────────────
+
+fn validate(args: Value) -> Value {
+    serde_json::from_str(&exec_validate(&args, ws()).unwrap()).unwrap()
+}
+
+const SERVER_SCHEMA: &str = r#"{
+    "type": "object",
+    "required": ["server"],
+    "properties": {
+        "server": {
+            "type": "object",
+            "required": ["host", "port"],
+            "properties": {
+                "host": {"type": "string", "minLength": 1},
+                "port": {"type": "integer", "minimum": 1, "maximum": 65535}
+            },
+            "additionalProperties": false
+        }
+    }
+}"#;
+
+#[test]
+fn test_validate_accepts_valid_documents() {
+    let yaml = "server:\n  host: example.com\n  port: 8080\n";
+    let toml = "[server]\nhost = \"example.com\"\nport = 8080\n";
+    let json = r#"{"server": {"host": "example.com", "port": 8080}}"#;
+    for (format, content) in [("yaml", yaml), ("toml", toml), ("json", json)] {
+        let out = validate(json!({
+            "content": content,
+            "format": format,
+            "schema": SERVER_SCHEMA,
+        }));
+        assert_eq!(out["valid"], true, "{}: {}", format, out);
+        assert_eq!(out["format"], format);
+        assert_eq!(out["errors"], json!([]));
+    }
+}
+
+#[test]
+fn test_validate_reports_syntax_error_location() {
+    let out = validate(json!({
+        "content": "{\n  \"name\": \"claw\",\n  \"port\": 80,,\n}",
+        "format": "json",
+    }));
+    assert_eq!(out["valid"], false);
+    let error = &out["errors"][0];
+    assert_eq!(error["kind"], "syntax");
+    assert_eq!(error["line"], 3);
+    assert_eq!(error["column"], 14);
+
+    let out = validate(json!({
+        "content": "[server]\nhost = \"a\"\nport = = 1\n",
+        "format": "toml",
+    }));
+    assert_eq!(out["errors"][0]["kind"], "syntax");
+    assert_eq!(out["errors"][0]["line"], 3);
+}
+
+#[test]
+fn test_validate_reports_schema_violation_path() {
+    let out = validate(json!({
+        "content": "# gateway\nserver:\n  host: example.com\n  port: 99999\n  debug: true\n",
+        "format": "yaml",
+        "schema": serde_json::from_str::<Value>(SERVER_SCHEMA).unwrap(),
+    }));
+    assert_eq!(out["valid"], false);
+    let errors = out["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 2, "{}", out);
+    let port = errors.iter().find(|e| e["path"] == "/server/port").unwrap();
+    assert_eq!(port["kind"], "schema");
+    assert!(port["message"].as_str().unwrap().contains("65535"));
+    assert_eq!(port["line"], 4);
+    assert_eq!(port["column"], 3);
+    let debug = errors
+        .iter()
+        .find(|e| e["path"] == "/server/debug")
+        .unwrap();
+    assert_eq!(debug["line"], 5);
+
+    // A missing property is reported at the object that should hold it.
+    let out = validate(json!({
+        "content": "[server]\nhost = \"example.com\"\n",
+        "format": "toml",
+        "schema": SERVER_SCHEMA,
+    }));
+    assert_eq!(out["errors"][0]["path"], "/server");
+    assert_eq!(out["errors"][0]["line"], 1);
+    assert!(
+        out["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("'port'")
+    );
+}
+
+// ── dns ─────────────────────────────────────────────────────────────────────
+
+/// A one-shot resolver on localhost: answers the next query with `answers`
+/// (rdata per record, all TTL 300, owner name = the question) and `rcode`.
+fn mock_resolver(rtype: u16, answers: Vec<Vec<u8>>, rcode: u8) -> String {
+    let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 512];
+        let (n, peer) = socket.recv_from(&mut buf).unwrap();
+        let query = &buf[..n];
+        // The question runs from byte 12 to the end of its name plus type
+        // and class.
+        let mut end = 12;
+        while query[end] != 0 {
+            end += query[end] as usize + 1;
+        }
+        end += 5;
+        let mut reply = query[..2].to_vec();
+        reply.extend_from_slice(&[0x81, 0x80 | rcode, 0, 1]);
+        reply.extend_from_slice(&(answers.len() as u16).to_be_bytes());
+        reply.extend_from_slice(&[0, 0, 0, 0]);
+        reply.extend_from_slice(&query[12..end]);
+        for rdata in &answers {
+            reply.extend_from_slice(&[0xc0, 12]);
+            reply.extend_from_slice(&rtype.to_be_bytes());
+            reply.extend_from_slice(&[0, 1, 0, 0, 1, 44]);
+            reply.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            reply.extend_from_slice(rdata);
+        }
+        socket.send_to(&reply, peer).unwrap();
+    });
+    addr.to_string()
+}
+
+fn dns(args: Value) -> Value {
+    serde_json::from_str(&exec_dns(&args, ws()).unwrap()).unwrap()
+}
+
+#[test]
+fn test_dns_a_records() {
+    let server = mock_resolver(1, vec![vec![93, 184, 215, 14], vec![93, 184, 215, 15]], 0);
+    let out = dns(json!({"name": "example.com", "server": server}));
+    assert_eq!(out["status"], "NOERROR");
+    assert_eq!(out["type"], "A");
+    let records = out["records"].as_array().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["name"], "example.com");
+    assert_eq!(records[0]["type"], "A");
+    assert_eq!(records[0]["ttl"], 300);
+    assert_eq!(records[0]["address"], "93.184.215.14");
+    assert_eq!(records[1]["address"], "93.184.215.15");
+}
+
+#[test]
+fn test_dns_mx_records_follow_compression() {
+    // Preference 10, exchange "mail" + a pointer to the question's name.
+    let rdata = vec![0, 10, 4, b'm', b'a', b'i', b'l', 0xc0, 12];
+    let server = mock_resolver(15, vec![rdata], 0);
+    let out = dns(json!({"name": "example.com.", "type": "mx", "server": server}));
+    assert_eq!(out["type"], "MX");
+    assert_eq!(out["records"][0]["preference"], 10);
+    assert_eq!(out["records"][0]["exchange"], "mail.example.com");
+
+    let server = mock_resolver(1, vec![], 3);
+    let out = dns(json!({"name": "missing.example.com", "server": server}));
+    assert_eq!(out["status"], "NXDOMAIN");
+    assert_eq!(out["records"], json!([]));
+}
+
+#[test]
+fn test_dns_reverse_names_and_errors() {
+    use std::net::IpAddr;
+    let v4: IpAddr = "8.8.4.4".parse().unwrap();
+    assert_eq!(dns_tool::reverse_name(v4), "4.4.8.8.in-addr.arpa");
+    let v6: IpAddr = "2001:db8::1".parse().unwrap();
+    assert_eq!(
+        dns_tool::reverse_name(v6),
+        "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+    );
+
+    let err = exec_dns(&json!({"name": "example.com", "type": "SRV"}), ws()).unwrap_err();
+    assert!(err.contains("Unknown record type"), "{}", err);
+
+    // A resolver that never answers.
+    let silent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let server = silent.local_addr().unwrap().to_string();
+    let err = exec_dns(
+        &json!({"name": "example.com", "server": server, "timeout_secs": 1}),
+        ws(),
+    )
+    .unwrap_err();
+    assert!(err.contains("timed out"), "{}", err);
+}
+
+// ── math ────────────────────────────────────────────────────────────────────
+
+fn math(args: Value) -> Value {
+    serde_json::from_str(&exec_math(&args, ws()).unwrap()).unwrap()
+}
+
+#[test]
+fn test_math_eval_precedence_and_exact_results() {
+    let out = math(json!({"expression": "2 + 3 * 4 ^ 2"}));
+    assert_eq!(out["result"], 50);
+    assert_eq!(out["exact"], true);
+
+    assert_eq!(math(json!({"expression": "-2^2"}))["result"], -4);
+    assert_eq!(math(json!({"expression": "2^3^2"}))["result"], 512);
+    assert_eq!(
+        math(json!({"expression": "(2 + 3) × 4 − 10 ÷ 4"}))["decimal"],
+        "17.5"
+    );
+    assert_eq!(math(json!({"expression": "-7 % 3"}))["result"], 2);
+
+    // Decimal literals stay exact.
+    let out = math(json!({"expression": "0.1 + 0.2"}));
+    assert_eq!(out["decimal"], "0.3");
+    assert_eq!(out["fraction"], "3/10");
+    let out = math(json!({"expression": "1/3"}));
+    assert_eq!(out["decimal"], "0.33333333333333333333");
+    assert_eq!(out["fraction"], "1/3");
+    assert_eq!(math(json!({"expression": "sqrt(9/4)"}))["fraction"], "3/2");
+
+    let out = math(json!({"expression": "round(sqrt(2) * 1000) / 1000"}));
+    assert_eq!(out["decimal"], "1.414");
+    let out = math(json!({"expression": "sin(pi / 2)"}));
+    assert_eq!(out["result"], 1.0);
+    assert_eq!(out["exact"], false);
+}
+
+#[test]
+fn test_math_rejects_division_by_zero_and_huge_exponents() {
+    let err = |expression: &str| {
+        exec_math(&json!({"action": "eval", "expression": expression}), ws()).unwrap_err()
+    };
+    assert_eq!(err("1 / (2 - 2)"), "Division by zero");
+    assert_eq!(err("5 % 0"), "Division by zero");
+    assert_eq!(err("0 ^ -1"), "Division by zero");
+    assert!(err("10^10^10").contains("too large"));
+    assert!(err("2^5000.5").contains("too large"));
+    assert!(err("(-8)^0.5").contains("not a real number"));
+    assert!(err("(1 + 2").contains("parenthesis"));
+    assert!(err("foo(2)").contains("Unknown function"));
+    assert!(err(&"(".repeat(200)).contains("nested too deeply"));
+}
+
+#[test]
+fn test_math_convert_temperature_and_units() {
+    let out = math(json!({"action": "convert", "value": 100, "from": "C", "to": "F"}));
+    assert_eq!(out["result"], 212.0);
+    assert_eq!(out["category"], "temperature");
+    assert_eq!(out["human"], "100 °C = 212 °F");
+    let out = math(json!({"action": "convert", "value": -40, "from": "fahrenheit", "to": "c"}));
+    assert_eq!(out["result"], -40.0);
+    let out = math(json!({"action": "convert", "value": 0, "from": "K", "to": "°C"}));
+    assert_eq!(out["result"], -273.15);
+
+    let out = math(json!({"action": "convert", "value": 1, "from": "mi", "to": "km"}));
+    assert_eq!(out["result"], 1.609344);
+    let out = math(json!({"action": "convert", "value": 1, "from": "GiB", "to": "MB"}));
+    assert_eq!(out["result"], 1073.741824);
+    let out = math(json!({"action": "convert", "value": 8, "from": "b", "to": "B"}));
+    assert_eq!(out["result"], 1.0);
+
+    let err = exec_math(
+        &json!({"action": "convert", "value": 1, "from": "kg", "to": "m"}),
+        ws(),
+    )
+    .unwrap_err();
+    assert_eq!(err, "Cannot convert kg (mass) to m (length)");
+    let err = exec_math(
+        &json!({"action": "convert", "value": -500, "from": "C", "to": "K"}),
+        ws(),
+    )
+    .unwrap_err();
+    assert!(err.contains("below absolute zero"), "{}", err);
+}
+
+// ── convert_document ────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_convert_document_markdown_to_html_builtin() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("notes.md"),
+        "# Plan\n\nShip **today**.\n\n| a | b |\n|---|---|\n| 1 | 2 |\n",
+    )
+    .unwrap();
+
+    let args = json!({"input_path": "notes.md", "to": "html", "engine": "builtin"});
+    let out = convert_document::exec_convert_document_async(&args, dir.path())
+        .await
+        .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    let written = dir.path().join("notes.html");
+    assert_eq!(result["path"], written.display().to_string());
+    assert_eq!(result["engine"], "builtin");
+    assert_eq!(result["media"]["mime_type"], "text/html");
+    let html = std::fs::read_to_string(&written).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"), "{html}");
+    assert!(html.contains("<title>notes</title>"), "{html}");
+    assert!(html.contains("<h1>Plan</h1>"), "{html}");
+    assert!(html.contains("<strong>today</strong>"), "{html}");
+    assert!(html.contains("<table>"), "{html}");
+
+    // Anything else needs pandoc, and the input is never overwritten.
+    let args = json!({"input_path": "notes.md", "to": "docx", "engine": "builtin"});
+    let err = convert_document::exec_convert_document_async(&args, dir.path())
+        .await
+        .unwrap_err();
+    assert!(err.contains("needs pandoc"), "{err}");
+    let args = json!({"input_path": "notes.md", "output_path": "notes.md", "from": "md"});
+    let err = convert_document::exec_convert_document_async(&args, dir.path())
+        .await
+        .unwrap_err();
+    assert!(err.contains("overwrite"), "{err}");
+    let args = json!({"input_path": "notes.md", "to": "pages"});
+    let err = convert_document::exec_convert_document_async(&args, dir.path())
+        .await
+        .unwrap_err();
+    assert!(err.contains("Unknown format"), "{err}");
+}
+
+// ── extract_links / extract_structured ──────────────────────────────────────
+
+#[cfg(feature = "web-tools")]
+const SAMPLE_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <title>  Launch   day </title>
+  <meta name="description" content="We shipped it.">
+  <meta name="author" content="Ada">
+  <meta property="og:title" content="Launch day">
+  <meta property="og:type" content="article">
+  <meta property="og:image" content="https://example.com/a.png">
+  <meta property="og:image" content="https://example.com/b.png">
+  <meta property="article:published_time" content="2026-10-01T09:00:00Z">
+  <meta name="twitter:card" content="summary_large_image">
+  <link rel="canonical" href="/blog/launch">
+  <script type="application/ld+json">
+    {"@context": "https://schema.org", "@type": "Article", "headline": "Launch day"}
+  </script>
+  <script type="application/ld+json">{not json}</script>
+</head>
+<body>
+  <nav><a href="/">Home</a> <a href="/blog/">Blog</a></nav>
+  <p>Read the <a href="docs/start.html#install">install   guide</a> or
+     <a href="https://github.com/example/repo" rel="nofollow">the source</a>.</p>
+  <a href="docs/start.html"><img src="x.png" alt="Docs"></a>
+  <a href="/blog/#comments">Comments</a>
+  <a href="#top">Back to top</a>
+  <a href="javascript:void(0)">Menu</a>
+  <a href="mailto:team@example.com">Mail us</a>
+</body>
+</html>"##;
+
+#[cfg(feature = "web-tools")]
+#[tokio::test]
+async fn test_extract_links_from_html() {
+    let args = json!({"content": SAMPLE_PAGE, "base_url": "https://example.com/blog/launch"});
+    let out = web_scrape::exec_extract_links_async(&args, ws())
+        .await
+        .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    let links = result["links"].as_array().unwrap();
+    let urls: Vec<&str> = links.iter().map(|l| l["url"].as_str().unwrap()).collect();
+    // Fragment-only and javascript: links are dropped; a fragment doesn't
+    // make a new page, so the image link and "#comments" are duplicates.
+    assert_eq!(
+        urls,
+        [
+            "https://example.com/",
+            "https://example.com/blog/",
+            "https://example.com/blog/docs/start.html#install",
+            "https://github.com/example/repo",
+            "mailto:team@example.com",
+        ]
+    );
+    assert_eq!(links[2]["text"], "install guide");
+    assert_eq!(links[3]["rel"], "nofollow");
+    assert_eq!(result["total"], 5);
+    assert_eq!(result["truncated"], false);
+
+    let args = json!({
+        "content": SAMPLE_PAGE,
+        "base_url": "https://example.com/blog/launch",
+        "scope": "external",
+        "max_links": 1,
+    });
+    let out = web_scrape::exec_extract_links_async(&args, ws())
+        .await
+        .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(result["links"][0]["url"], "https://github.com/example/repo");
+    assert_eq!(result["total"], 2);
+    assert_eq!(result["truncated"], true);
+}
+
+#[cfg(feature = "web-tools")]
+#[tokio::test]
+async fn test_extract_links_from_markdown_and_image_text() {
+    let args = json!({
+        "content": "See [the docs](/docs) and [![Logo](logo.png)](https://example.org/).",
+        "base_url": "https://example.com/",
+    });
+    let out = web_scrape::exec_extract_links_async(&args, ws())
+        .await
+        .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(result["links"][0]["url"], "https://example.com/docs");
+    assert_eq!(result["links"][0]["text"], "the docs");
+    assert_eq!(result["links"][1]["url"], "https://example.org/");
+    assert_eq!(result["links"][1]["text"], "Logo");
+}
+
+#[cfg(feature = "web-tools")]
+#[tokio::test]
+async fn test_extract_structured_reads_og_and_json_ld() {
+    let args = json!({"content": SAMPLE_PAGE, "base_url": "https://example.com/blog/launch"});
+    let out = web_scrape::exec_extract_structured_async(&args, ws())
+        .await
+        .unwrap();
+    let result: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(result["title"], "Launch day");
+    assert_eq!(result["lang"], "en");
+    assert_eq!(result["canonical"], "https://example.com/blog/launch");
+    assert_eq!(result["open_graph"]["title"], "Launch day");
+    assert_eq!(result["open_graph"]["type"], "article");
+    assert_eq!(
+        result["open_graph"]["image"],
+        json!(["https://example.com/a.png", "https://example.com/b.png"])
+    );
+    assert_eq!(
+        result["open_graph"]["article:published_time"],
+        "2026-10-01T09:00:00Z"
+    );
+    assert_eq!(result["twitter"]["card"], "summary_large_image");
+    assert_eq!(result["meta"]["description"], "We shipped it.");
+    assert_eq!(result["meta"]["author"], "Ada");
+    assert_eq!(result["json_ld"][0]["@type"], "Article");
+    assert_eq!(result["json_ld"].as_array().unwrap().len(), 1);
+    assert_eq!(result["json_ld_invalid"], 1);
+}
+
+#[tokio::test]
+async fn test_extract_links_needs_one_source() {
+    let err = web_scrape::exec_extract_links_async(&json!({}), ws())
+        .await
+        .unwrap_err();
+    #[cfg(feature = "web-tools")]
+    {
+        assert!(err.contains("url or content"), "{err}");
+        let args = json!({"url": "https://example.com", "content": "<a href=x>x</a>"});
+        let err = web_scrape::exec_extract_structured_async(&args, ws())
+            .await
+            .unwrap_err();
+        assert!(err.contains("not both"), "{err}");
+        let args = json!({"content": "<a href=x>x</a>", "scope": "internal"});
+        let err = web_scrape::exec_extract_links_async(&args, ws())
+            .await
+            .unwrap_err();
+        assert!(err.contains("base_url"), "{err}");
+    }
+    #[cfg(not(feature = "web-tools"))]
+    assert!(err.contains("web-tools"), "{err}");
+}
+
+// ── summarize_url ───────────────────────────────────────────────────────────
+
+#[cfg(feature = "web-tools")]
+fn summary_page(paragraphs: usize) -> String {
+    let body: String = (0..paragraphs)
+        .map(|i| {
+            format!(
+                "<p>Section {i}: the Otter Lake footbridge reopened after a year of repairs, \
+                 and the council reports foot traffic is back to 4,000 crossings a day.</p>\n"
+            )
+        })
+        .collect();
+    format!(
+        "<html><head><title>Footbridge reopens</title></head><body>\
+         <nav><a href=\"/\">Home</a> <a href=\"/weather\">Weather</a></nav>\
+         <article><h1>Footbridge reopens</h1>{body}\
+         <p>See the <a href=\"/council/minutes\">council minutes</a>.</p></article>\
+         <footer>Subscribe to our newsletter</footer></body></html>"
+    )
+}
+
+/// Stands in for the model: notes echo the chunk, the summary runs long
+/// and brings too many key points, so the tool has to bound both.
+#[cfg(feature = "web-tools")]
+async fn fake_summary(prompt: String) -> Result<String, String> {
+    if prompt.contains("Write concise notes") {
+        return Ok("- Otter Lake footbridge reopened; 4,000 crossings a day".to_string());
+    }
+    let summary = format!(
+        "The Otter Lake footbridge has reopened. {}",
+        "More detail. ".repeat(300)
+    );
+    let points: Vec<String> = (1..=10).map(|i| format!("Point {i}")).collect();
+    Ok(format!(
+        "```json\n{}\n```",
+        json!({ "summary": summary, "key_points": points })
+    ))
+}
+
+#[cfg(feature = "web-tools")]
+#[tokio::test]
+async fn test_summarize_url_returns_bounded_summary() {
+    let args = json!({
+        "content": summary_page(3),
+        "base_url": "https://news.example.com/bridge",
+        "length": "short",
+        "include_links": true,
+    });
+    let mut prompts = Vec::new();
+    let out = summarize_url::summarize_url_with(&args, |prompt| {
+        prompts.push(prompt.clone());
+        fake_summary(prompt)
+    })
+    .await
+    .unwrap();
+    let out: Value = serde_json::from_str(&out).unwrap();
+
+    // One chunk: no map step, and the boilerplate never reached the model.
+    assert_eq!(prompts.len(), 1);
+    assert!(prompts[0].contains("4,000 crossings"));
+    assert!(!prompts[0].contains("Subscribe"));
+    assert!(!prompts[0].contains("Weather"));
+
+    assert_eq!(out["title"], "Footbridge reopens");
+    assert_eq!(out["chunks"], 1);
+    let summary = out["summary"].as_str().unwrap();
+    assert!(summary.starts_with("The Otter Lake footbridge"));
+    assert!(summary.ends_with('…'));
+    assert!(summary.split_whitespace().count() <= 80);
+    assert_eq!(out["key_points"].as_array().unwrap().len(), 3);
+    let links: Vec<&str> = out["links"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["url"].as_str().unwrap())
+        .collect();
+    assert!(links.contains(&"https://news.example.com/council/minutes"));
+
+    // A long page is summarised part by part, then from the notes.
+    let args = json!({ "content": summary_page(200) });
+    let mut prompts = Vec::new();
+    let out = summarize_url::summarize_url_with(&args, |prompt| {
+        prompts.push(prompt.clone());
+        fake_summary(prompt)
+    })
+    .await
+    .unwrap();
+    let out: Value = serde_json::from_str(&out).unwrap();
+    let chunks = out["chunks"].as_u64().unwrap() as usize;
+    assert!(chunks > 1);
+    assert_eq!(prompts.len(), chunks + 1);
+    assert!(prompts[chunks].contains("notes taken on the web page"));
+    assert!(out["summary"].as_str().unwrap().split_whitespace().count() <= 200);
+    assert_eq!(out["key_points"].as_array().unwrap().len(), 5);
+    assert!(out.get("links").is_none());
+}
+
+#[test]
+fn test_tool_risks_for_approval_prompts() {
+    assert_eq!(tool_risks("write_file"), vec![ToolRisk::WritesFiles]);
+    assert_eq!(tool_risks("execute_command")[0], ToolRisk::RunsCommands);
+    assert_eq!(tool_risks("web_fetch"), vec![ToolRisk::Network]);
+    assert_eq!(tool_risks("secrets_get"), vec![ToolRisk::Secrets]);
+    assert!(tool_risks("read_file").is_empty());
+    assert!(tool_risks("no_such_tool").is_empty());
+
+    let ctx = ApprovalContext::new(
+        "network_request_replay",
+        &json!({"curl": "curl https://api.example.com", "headers": {"api_key": "k-1234567890"}}),
+    );
+    assert_eq!(ctx.summary, tool_summary("network_request_replay"));
+    assert!(!ctx.arguments.contains("k-1234567890"), "{}", ctx.arguments);
+    assert!(ctx.arguments.contains("api.example.com"));
+    assert_eq!(ctx.risks, vec![ToolRisk::Network]);
+}
+
+#[test]
+fn test_tool_defaults_fill_omitted_arguments() {
+    let defaults: ToolDefaults = toml::from_str(
+        r#"
+        [web_search]
+        count = 8
+        results = 20
+
+        [disk_usage]
+        depth = 2
+
+        [no_such_tool]
+        depth = 1
+        "#,
+    )
+    .unwrap();
+    let (checked, problems) = check_tool_defaults(&defaults);
+    assert_eq!(
+        problems,
+        [
+            "unknown tool 'no_such_tool'",
+            "'web_search' has no parameter 'results'",
+        ]
+    );
+    assert_eq!(checked["disk_usage"]["depth"], 2);
+    assert!(!checked["web_search"].contains_key("results"));
+
+    // Applied when the model omits the argument, or sends null for it.
+    let search = &checked["web_search"];
+    let merged = merge_defaults(&json!({"query": "rust"}), search);
+    assert_eq!(merged, json!({"query": "rust", "count": 8}));
+    let merged = merge_defaults(&json!({"query": "rust", "count": null}), search);
+    assert_eq!(merged["count"], 8);
+    assert_eq!(merge_defaults(&Value::Null, search), json!({"count": 8}));
+
+    // An argument the model gives always wins.
+    let merged = merge_defaults(&json!({"query": "rust", "count": 3}), search);
+    assert_eq!(merged, json!({"query": "rust", "count": 3}));
+}
+
+// ── lint ────────────────────────────────────────────────────────
+
+#[test]
+fn test_lint_parses_clippy_json() {
+    fn message(level: &str, code: Option<&str>, text: &str, file: &str, line: u64) -> String {
+        json!({
+            "reason": "compiler-message",
+            "package_id": "demo 0.1.0 (path+file:///work/demo)",
+            "target": {"kind": ["lib"], "name": "demo"},
+            "message": {
+                "$message_type": "diagnostic",
+                "level": level,
+                "message": text,
+                "code": code.map(|c| json!({"code": c, "explanation": null})),
+                "children": [],
+                "spans": if file.is_empty() { json!([]) } else { json!([
+                    {"file_name": "src/other.rs", "line_start": 1, "column_start": 1, "is_primary": false},
+                    {"file_name": file, "line_start": line, "column_start": 5, "is_primary": true},
+                ]) },
+            },
+        })
+        .to_string()
+    }
+
+    let needless = message(
+        "warning",
+        Some("clippy::needless_return"),
+        "unneeded `return` statement",
+        "src/lib.rs",
+        3,
+    );
+    let raw = [
+        needless.clone(),
+        // The same lint seen again while checking the test target.
+        needless,
+        message(
+            "error",
+            Some("E0308"),
+            "mismatched types",
+            "src/main.rs",
+            12,
+        ),
+        message("warning", None, "1 warning emitted", "", 0),
+        json!({"reason": "build-finished", "success": false}).to_string(),
+        "not json".to_string(),
+    ]
+    .join("\n");
+
+    let found = lint::parse_clippy(&raw);
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].linter, "clippy");
+    assert_eq!(found[0].file, "src/lib.rs");
+    assert_eq!((found[0].line, found[0].column), (3, 5));
+    assert_eq!(found[0].severity, "warning");
+    assert_eq!(found[0].code.as_deref(), Some("clippy::needless_return"));
+    assert_eq!(found[0].message, "unneeded `return` statement");
+    assert_eq!(found[1].severity, "error");
+    assert_eq!(found[1].code.as_deref(), Some("E0308"));
+}
+
+#[test]
+fn test_lint_parses_eslint_json() {
+    let root = Path::new("/work/app");
+    let raw = json!([
+        {
+            "filePath": "/work/app/src/index.js",
+            "messages": [
+                {"ruleId": "no-unused-vars", "severity": 2, "message": "'x' is defined but never used.", "line": 4, "column": 7},
+                {"ruleId": "semi", "severity": 1, "message": "Missing semicolon.", "line": 9, "column": 20, "fix": {"range": [88, 88], "text": ";"}},
+            ],
+            "errorCount": 1,
+            "warningCount": 1,
+        },
+        {"filePath": "/work/app/src/clean.js", "messages": [], "errorCount": 0, "warningCount": 0},
+        {
+            "filePath": "/work/app/src/broken.js",
+            "messages": [{"ruleId": null, "fatal": true, "severity": 2, "message": "Parsing error: Unexpected token", "line": 1, "column": 1}],
+        },
+    ])
+    .to_string();
+
+    let found = lint::parse_eslint(&raw, root).unwrap();
+    assert_eq!(found.len(), 3);
+    assert_eq!(found[0].file, "src/index.js");
+    assert_eq!((found[0].line, found[0].column), (4, 7));
+    assert_eq!(found[0].severity, "error");
+    assert_eq!(found[0].code.as_deref(), Some("no-unused-vars"));
+    assert_eq!(found[1].severity, "warning");
+    assert_eq!(found[1].message, "Missing semicolon.");
+    assert_eq!(found[2].file, "src/broken.js");
+    assert_eq!(found[2].code, None);
+
+    // Diagnostics serialize in the tool's output shape.
+    assert_eq!(
+        serde_json::to_value(&found[1]).unwrap(),
+        json!({
+            "linter": "eslint",
+            "file": "src/index.js",
+            "line": 9,
+            "column": 20,
+            "severity": "warning",
+            "code": "semi",
+            "message": "Missing semicolon.",
+        })
+    );
+
+    assert!(lint::parse_eslint("Oops! Something went wrong!", root).is_err());
+}
+
+#[test]
+fn test_lint_reports_when_no_linter_applies() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "nothing to lint\n").unwrap();
+    let err = exec_lint(&json!({}), dir.path()).unwrap_err();
+    assert!(err.starts_with("No linter applies"), "{}", err);
+
+    let err = exec_lint(&json!({"linter": "pylint"}), dir.path()).unwrap_err();
+    assert!(err.contains("Unknown linter"), "{}", err);
+}
+
+// ── extract_archive_member ──────────────────────────────────────
+
+#[test]
+fn test_extract_archive_member_reads_one_tar_gz_member() {
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("backup.tar.gz");
+    {
+        let gz = flate2::write::GzEncoder::new(
+            std::fs::File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut tar = tar::Builder::new(gz);
+        let mut add = |name: &str, body: &[u8]| {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(body.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, name, body).unwrap();
+        };
+        add("data/big.bin", &vec![0u8; 200_000]);
+        add("etc/app/config.toml", b"port = 8080\n");
+        add("README.md", b"# backup\n");
+        tar.into_inner().unwrap().finish().unwrap().flush().unwrap();
+    }
+
+    let out = exec_extract_archive_member(
+        &json!({"path": "backup.tar.gz", "member": "./etc/app/config.toml"}),
+        dir.path(),
+    )
+    .unwrap();
+    let out: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(out["format"], "tar.gz");
+    assert_eq!(out["member"], "etc/app/config.toml");
+    assert_eq!(out["encoding"], "text");
+    assert_eq!(out["content"], "port = 8080\n");
+    assert_eq!(out["truncated"], false);
+
+    // Binary members come back as base64, capped at max_bytes.
+    let out = exec_extract_archive_member(
+        &json!({"path": "backup.tar.gz", "member": "data/big.bin", "max_bytes": 16}),
+        dir.path(),
+    )
+    .unwrap();
+    let out: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(out["encoding"], "base64");
+    assert_eq!(out["size"], 200_000);
+    assert_eq!(out["bytes_read"], 16);
+    assert_eq!(out["truncated"], true);
+
+    // Nothing was extracted next to the archive.
+    let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+    assert_eq!(entries.len(), 1);
+
+    let listing =
+        exec_extract_archive_member(&json!({"path": "backup.tar.gz"}), dir.path()).unwrap();
+    let listing: Value = serde_json::from_str(&listing).unwrap();
+    let names: Vec<&str> = listing["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["data/big.bin", "etc/app/config.toml", "README.md"]);
+
+    let err = exec_extract_archive_member(
+        &json!({"path": "backup.tar.gz", "member": "etc/missing.toml"}),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("No member"), "{}", err);
+}
+
+#[test]
+fn test_extract_archive_member_zip_refuses_traversal() {
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("bundle.zip");
+    {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("notes/todo.txt", options).unwrap();
+        zip.write_all(b"ship it").unwrap();
+        zip.start_file("../evil.sh", options).unwrap();
+        zip.write_all(b"rm -rf ~").unwrap();
+        zip.finish().unwrap();
+    }
+
+    let out = exec_extract_archive_member(
+        &json!({"path": "bundle.zip", "member": "notes/todo.txt"}),
+        dir.path(),
+    )
+    .unwrap();
+    let out: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(out["format"], "zip");
+    assert_eq!(out["content"], "ship it");
+
+    let listing = exec_extract_archive_member(&json!({"path": "bundle.zip"}), dir.path()).unwrap();
+    let listing: Value = serde_json::from_str(&listing).unwrap();
+    assert_eq!(listing["members"][1]["name"], "../evil.sh");
+    assert_eq!(listing["members"][1]["unsafe_name"], true);
+
+    let err = exec_extract_archive_member(
+        &json!({"path": "bundle.zip", "member": "../evil.sh"}),
+        dir.path(),
+    )
+    .unwrap_err();
+    assert!(err.contains("Refusing"), "{}", err);
+
+    use archive_member::normalize_member;
+    assert_eq!(normalize_member("./a//b/").as_deref(), Some("a/b"));
+    assert_eq!(normalize_member("/etc/passwd"), None);
+    assert_eq!(normalize_member("a/../../b"), None);
+    assert_eq!(normalize_member("C:\\Windows\\x"), None);
+}
+
+// ── transcribe ──────────────────────────────────────────────────
+
+/// A mono 16-bit 16 kHz WAV of `secs` seconds of silence.
+fn silent_wav(secs: u32) -> Vec<u8> {
+    let data_len = secs * 16_000 * 2;
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&16_000u32.to_le_bytes());
+    wav.extend_from_slice(&32_000u32.to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.resize(wav.len() + data_len as usize, 0);
+    wav
+}
+
+#[test]
+fn test_transcribe_parses_backend_output() {
+    use transcribe::{parse_cloud, parse_whisper_cpp, wav_duration_secs};
+
+    assert_eq!(wav_duration_secs(&silent_wav(3)[..64]), Some(3.0));
+    assert_eq!(wav_duration_secs(b"ID3\x04 not a wav at all"), None);
+
+    let local = json!({
+        "result": {"language": "en"},
+        "transcription": [
+            {"offsets": {"from": 0, "to": 1500}, "text": " Buy milk."},
+            {"offsets": {"from": 1500, "to": 2000}, "text": " "},
+            {"offsets": {"from": 2000, "to": 4250}, "text": " Call the dentist."}
+        ]
+    });
+    let transcript = parse_whisper_cpp(&local).unwrap();
+    assert_eq!(transcript.text, "Buy milk. Call the dentist.");
+    assert_eq!(transcript.language.as_deref(), Some("en"));
+    assert_eq!(transcript.segments.len(), 2);
+    assert_eq!(transcript.segments[1].start, 2.0);
+    assert_eq!(transcript.segments[1].end, 4.25);
+
+    let cloud = json!({
+        "text": " Buy milk. ",
+        "language": "english",
+        "segments": [{"start": 0.0, "end": 1.4, "text": " Buy milk."}]
+    });
+    let transcript = parse_cloud(&cloud).unwrap();
+    assert_eq!(transcript.text, "Buy milk.");
+    assert_eq!(transcript.segments[0].end, 1.4);
+    assert!(
+        parse_cloud(&json!({"text": "hi"}))
+            .unwrap()
+            .segments
+            .is_empty()
+    );
+    assert!(parse_cloud(&json!({"error": "bad"})).is_err());
+}
+
+#[tokio::test]
+async fn test_transcribe_local_sample_clip() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not audio").unwrap();
+    let err = transcribe::exec_transcribe_async(&json!({"path": "notes.txt"}), dir.path())
+        .await
+        .unwrap_err();
+    assert!(err.contains("Unsupported audio format"), "{}", err);
+
+    if let Err(reason) = transcribe::find_local(&crate::config::TranscribeConfig::default()) {
+        eprintln!("skipping: {}", reason);
+        return;
+    }
+    std::fs::write(dir.path().join("clip.wav"), silent_wav(2)).unwrap();
+    let out = transcribe::exec_transcribe_async(
+        &json!({"path": "clip.wav", "backend": "local", "timestamps": true}),
+        dir.path(),
+    )
+    .await
+    .unwrap();
+    let out: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(out["backend"], "whisper.cpp");
+    assert_eq!(out["duration_secs"], 2.0);
+    assert!(out["text"].is_string());
+    assert!(out["segments"].is_array());
+    assert!(out.get("local_unavailable").is_none());
+}
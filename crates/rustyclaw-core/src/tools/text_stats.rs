@@ -0,0 +1,389 @@
+//! Text file statistics: `wc`, `head`, `tail` and `grep -c` in one call.
+//!
+//! `text_stats` reads a file once and reports its line, word, byte and
+//! character counts, the longest line, the encoding and line-ending style,
+//! and the first and last few lines, optionally with the number of matches
+//! of a regex.  It behaves the same on every platform, unlike the shell
+//! tools it stands in for.
+//!
+//! Files with a NUL byte near the start are reported as binary and not
+//! counted, except UTF-16 text, which is recognised by its byte-order mark
+//! and decoded when no larger than [`MAX_UTF16_BYTES`].
+
+use regex::Regex;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{
+    VAULT_ACCESS_DENIED, is_protected_path, open_file_read_safe, resolve_path, sandbox,
+};
+
+/// Lines shown from each end unless `head`/`tail` say otherwise.
+const DEFAULT_PREVIEW: usize = 10;
+
+/// Most lines shown from either end.
+const MAX_PREVIEW: usize = 200;
+
+/// Preview lines are cut at this many characters.
+const MAX_LINE_CHARS: usize = 300;
+
+/// How much of the file is inspected for a BOM and NUL bytes.
+const SNIFF_BYTES: usize = 8192;
+
+/// Largest UTF-16 file decoded for counting.
+const MAX_UTF16_BYTES: u64 = 32 * 1024 * 1024;
+
+/// What the first bytes say about the content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sniffed {
+    /// UTF-8 or a single-byte encoding; `bom` if it starts with EF BB BF.
+    Text {
+        bom: bool,
+    },
+    Utf16 {
+        little_endian: bool,
+    },
+    Binary,
+}
+
+fn sniff(head: &[u8]) -> Sniffed {
+    if head.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        Sniffed::Text { bom: true }
+    } else if head.starts_with(&[0xFF, 0xFE]) {
+        Sniffed::Utf16 {
+            little_endian: true,
+        }
+    } else if head.starts_with(&[0xFE, 0xFF]) {
+        Sniffed::Utf16 {
+            little_endian: false,
+        }
+    } else if head.contains(&0) {
+        Sniffed::Binary
+    } else {
+        Sniffed::Text { bom: false }
+    }
+}
+
+/// Counts gathered in one pass.
+#[derive(Debug, Default)]
+struct Stats {
+    lines: usize,
+    words: usize,
+    chars: usize,
+    crlf: usize,
+    lf: usize,
+    /// A carriage return not followed by a line feed.
+    lone_cr: usize,
+    final_newline: bool,
+    ascii: bool,
+    invalid_utf8: bool,
+    longest: usize,
+    longest_at: usize,
+    match_lines: usize,
+    matches: usize,
+    head: Vec<(usize, String)>,
+    tail: VecDeque<(usize, String)>,
+}
+
+impl Stats {
+    fn line_endings(&self) -> String {
+        let kinds: Vec<String> = [("CRLF", self.crlf), ("LF", self.lf), ("CR", self.lone_cr)]
+            .iter()
+            .filter(|&&(_, n)| n > 0)
+            .map(|&(name, n)| format!("{} {}", n, name))
+            .collect();
+        match kinds.len() {
+            0 => "none".to_string(),
+            1 if self.crlf > 0 => "CRLF".to_string(),
+            1 if self.lf > 0 => "LF".to_string(),
+            1 => "CR".to_string(),
+            _ => format!("mixed ({})", kinds.join(", ")),
+        }
+    }
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_text_stats(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let path_str = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing required parameter: path")?;
+    let path = resolve_path(workspace_dir, path_str);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted text_stats access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+
+    let preview = |key: &str| {
+        args.get(key)
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_PREVIEW, |n| (n as usize).min(MAX_PREVIEW))
+    };
+    let (head, tail) = (preview("head"), preview("tail"));
+    let pattern = args
+        .get("pattern")
+        .and_then(|v| v.as_str())
+        .map(|p| Regex::new(p).map_err(|e| format!("Invalid pattern: {}", e)))
+        .transpose()?;
+
+    let (mut file, _) = open_file_read_safe(&path)
+        .map_err(|e| format!("Failed to open file '{}': {}", path.display(), e))?;
+    let read_err = |e: std::io::Error| format!("Failed to read file '{}': {}", path.display(), e);
+    let size = file.metadata().map_err(read_err)?.len();
+    let mut start = Vec::with_capacity(SNIFF_BYTES);
+    (&mut file)
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut start)
+        .map_err(read_err)?;
+
+    let sniffed = sniff(&start);
+    // Skip the byte-order mark; the UTF-16 branch skips its own.
+    let skip = if sniffed == (Sniffed::Text { bom: true }) {
+        3
+    } else {
+        0
+    };
+    file.seek(SeekFrom::Start(skip)).map_err(read_err)?;
+    let stats = match sniffed {
+        Sniffed::Binary => {
+            debug!(path = %path.display(), size, "Binary file");
+            return Ok(format!(
+                "{}: binary file, {} bytes (NUL bytes in the first {} bytes); \
+                 no text statistics.",
+                path_str,
+                size,
+                start.len()
+            ));
+        }
+        Sniffed::Text { .. } => {
+            count(BufReader::new(file), pattern.as_ref(), head, tail).map_err(read_err)?
+        }
+        Sniffed::Utf16 { little_endian } => {
+            if size > MAX_UTF16_BYTES {
+                return Ok(format!(
+                    "{}: UTF-16{} text, {} bytes; too large to count (limit {} bytes).",
+                    path_str,
+                    if little_endian { "LE" } else { "BE" },
+                    size,
+                    MAX_UTF16_BYTES
+                ));
+            }
+            let mut raw = Vec::new();
+            file.read_to_end(&mut raw).map_err(read_err)?;
+            let text = decode_utf16(&raw[2..], little_endian);
+            count(Cursor::new(text.into_bytes()), pattern.as_ref(), head, tail).map_err(read_err)?
+        }
+    };
+    debug!(path = %path.display(), lines = stats.lines, "Counted text file");
+    Ok(render(
+        path_str,
+        size,
+        sniffed,
+        &stats,
+        pattern.as_ref(),
+        head,
+    ))
+}
+
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| {
+        let pair = [pair[0], pair[1]];
+        if little_endian {
+            u16::from_le_bytes(pair)
+        } else {
+            u16::from_be_bytes(pair)
+        }
+    });
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+fn count(
+    mut reader: impl BufRead,
+    pattern: Option<&Regex>,
+    head: usize,
+    tail: usize,
+) -> std::io::Result<Stats> {
+    let mut stats = Stats {
+        ascii: true,
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        stats.lines += 1;
+        stats.final_newline = buf.ends_with(b"\n");
+        let mut content = buf.as_slice();
+        if let Some(rest) = content.strip_suffix(b"\n") {
+            content = rest;
+            match rest.strip_suffix(b"\r") {
+                Some(rest) => {
+                    content = rest;
+                    stats.crlf += 1;
+                }
+                None => stats.lf += 1,
+            }
+        }
+        stats.lone_cr += content.iter().filter(|&&b| b == b'\r').count();
+        stats.ascii &= content.is_ascii();
+        // A multi-byte sequence never contains b'\n', so lines can be
+        // checked one at a time.
+        stats.invalid_utf8 |= std::str::from_utf8(content).is_err();
+
+        let line = String::from_utf8_lossy(content);
+        let chars = line.chars().count();
+        stats.chars += chars + (buf.len() - content.len());
+        stats.words += line.split_whitespace().count();
+        if chars > stats.longest {
+            stats.longest = chars;
+            stats.longest_at = stats.lines;
+        }
+        if let Some(re) = pattern {
+            let found = re.find_iter(&line).count();
+            if found > 0 {
+                stats.match_lines += 1;
+                stats.matches += found;
+            }
+        }
+        if stats.head.len() < head {
+            stats
+                .head
+                .push((stats.lines, truncate_chars(&line, MAX_LINE_CHARS)));
+        } else if tail > 0 {
+            if stats.tail.len() == tail {
+                stats.tail.pop_front();
+            }
+            stats
+                .tail
+                .push_back((stats.lines, truncate_chars(&line, MAX_LINE_CHARS)));
+        }
+    }
+    Ok(stats)
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+fn render(
+    path: &str,
+    size: u64,
+    sniffed: Sniffed,
+    stats: &Stats,
+    pattern: Option<&Regex>,
+    head: usize,
+) -> String {
+    let encoding = match sniffed {
+        Sniffed::Utf16 { little_endian } => {
+            format!("UTF-16{} (BOM)", if little_endian { "LE" } else { "BE" })
+        }
+        Sniffed::Text { bom: true } => "UTF-8 (BOM)".to_string(),
+        _ if stats.invalid_utf8 => "not UTF-8 (likely Latin-1 or Windows-1252)".to_string(),
+        _ if stats.ascii => "ASCII".to_string(),
+        _ => "UTF-8".to_string(),
+    };
+    let mut out = format!(
+        "{}: {} lines, {} words, {} bytes, {} chars\n",
+        path, stats.lines, stats.words, size, stats.chars
+    );
+    out.push_str(&format!("Encoding: {}\n", encoding));
+    out.push_str(&format!("Line endings: {}", stats.line_endings()));
+    if stats.lines > 0 && !stats.final_newline {
+        out.push_str("; no newline at end of file");
+    }
+    out.push('\n');
+    if stats.lines > 0 {
+        out.push_str(&format!(
+            "Longest line: {} chars (line {})\n",
+            stats.longest, stats.longest_at
+        ));
+    }
+    if let Some(re) = pattern {
+        out.push_str(&format!(
+            "Pattern /{}/: {} matches on {} lines\n",
+            re.as_str(),
+            stats.matches,
+            stats.match_lines
+        ));
+    }
+
+    let width = stats.lines.to_string().len();
+    if !stats.head.is_empty() {
+        let title = if stats.head.len() == stats.lines {
+            format!("All {} lines:", stats.lines)
+        } else {
+            format!("First {} lines:", stats.head.len())
+        };
+        push_section(&mut out, &title, &stats.head, width);
+    }
+    if !stats.tail.is_empty() {
+        let title = match stats.tail.front() {
+            // The tail picks up right where the head stopped.
+            Some(&(first, _)) if first == head + 1 && head > 0 => {
+                format!("Remaining {} lines:", stats.tail.len())
+            }
+            _ => format!("Last {} lines:", stats.tail.len()),
+        };
+        push_section(&mut out, &title, &stats.tail, width);
+    }
+    out.trim_end().to_string()
+}
+
+fn push_section<'a>(
+    out: &mut String,
+    title: &str,
+    lines: impl IntoIterator<Item = &'a (usize, String)>,
+    width: usize,
+) {
+    out.push_str(&format!("\n{}\n", title));
+    for (number, line) in lines {
+        out.push_str(&format!("{:>width$}  {}\n", number, line, width = width));
+    }
+}
+
+pub fn text_stats_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "path".into(),
+            description: "File to inspect.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "head".into(),
+            description: "Lines to show from the start (0-200). Default: 10.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "tail".into(),
+            description: "Lines to show from the end (0-200). Default: 10.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "pattern".into(),
+            description: "Regex to count: total matches and matching lines. \
+                          Prefix with (?i) to ignore case."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
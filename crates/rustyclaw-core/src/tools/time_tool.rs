@@ -0,0 +1,561 @@
+//! Time tool: current time, timezone conversion, durations and date parsing.
+//!
+//! Zones are IANA names from the bundled tz database (`chrono-tz`), so
+//! daylight saving is applied for the date in question rather than the
+//! offset in effect today.  `UTC`, `local` (the gateway host's zone) and
+//! fixed offsets such as `+05:30` are accepted too.
+//!
+//! Times may be given as RFC 3339, `YYYY-MM-DD HH:MM`, or in a small
+//! English vocabulary: `now`, `today`/`tomorrow`/`yesterday`, weekdays
+//! (`friday`, `next monday`, `last tuesday`), month names (`march 5 2026`,
+//! `5 mar`), clock times (`9am`, `15:30`, `noon`) and offsets (`in 3
+//! hours`, `2 days ago`).  Every instant in a result carries RFC 3339 and
+//! Unix time for programs alongside a spelled-out form for people.
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, LocalResult, Months, NaiveDate,
+    NaiveDateTime, NaiveTime, SecondsFormat, TimeZone, Utc, Weekday,
+};
+use chrono_tz::{OffsetComponents, Tz};
+use serde_json::{Value, json};
+use std::fmt::Display;
+use std::path::Path;
+use tracing::instrument;
+
+use super::ToolParam;
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("monday", Weekday::Mon),
+    ("tuesday", Weekday::Tue),
+    ("wednesday", Weekday::Wed),
+    ("thursday", Weekday::Thu),
+    ("friday", Weekday::Fri),
+    ("saturday", Weekday::Sat),
+    ("sunday", Weekday::Sun),
+];
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `time` tool.
+#[instrument(skip(args, _workspace_dir), fields(action))]
+pub fn exec_time(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: action".to_string())?;
+    tracing::Span::current().record("action", action);
+    let str_arg = |key: &str| args.get(key).and_then(|v| v.as_str());
+    let required =
+        |key: &str| str_arg(key).ok_or_else(|| format!("Missing required parameter: {}", key));
+    let now = Utc::now();
+
+    let result = match action {
+        "now" => Zone::parse(str_arg("timezone"))?.describe(now),
+        "convert" => {
+            let from = Zone::parse(str_arg("from"))?;
+            let to = Zone::parse(Some(required("to")?))?;
+            let t = parse_time(required("time")?, &from, now)?;
+            json!({ "from": from.describe(t), "to": to.describe(t) })
+        }
+        "diff" => {
+            let zone = Zone::parse(str_arg("timezone"))?;
+            let start = parse_time(required("start")?, &zone, now)?;
+            let end = parse_time(required("end")?, &zone, now)?;
+            let seconds = (end - start).num_seconds();
+            json!({
+                "start": zone.describe(start)["rfc3339"],
+                "end": zone.describe(end)["rfc3339"],
+                "seconds": seconds,
+                "human": human_duration(seconds),
+                "iso8601": iso_duration(seconds),
+            })
+        }
+        "parse" => {
+            let zone = Zone::parse(str_arg("timezone"))?;
+            zone.describe(parse_time(required("text")?, &zone, now)?)
+        }
+        other => {
+            return Err(format!(
+                "Unknown action: '{}'. Use: now, convert, diff, parse",
+                other
+            ));
+        }
+    };
+    serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+}
+
+// ── Zones ───────────────────────────────────────────────────────────────────
+
+/// A timezone named by the caller.
+#[derive(Debug, Clone, Copy)]
+pub enum Zone {
+    /// The gateway host's zone.
+    Local,
+    Fixed(FixedOffset),
+    Named(Tz),
+}
+
+impl Zone {
+    /// `None` or empty means the local zone.
+    pub fn parse(name: Option<&str>) -> Result<Self, String> {
+        let Some(name) = name.map(str::trim).filter(|n| !n.is_empty()) else {
+            return Ok(Zone::Local);
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "local" => return Ok(Zone::Local),
+            "utc" | "gmt" | "z" => return Ok(Zone::Named(Tz::UTC)),
+            _ => {}
+        }
+        if let Some(offset) = parse_offset(name) {
+            return Ok(Zone::Fixed(offset));
+        }
+        match Tz::from_str_insensitive(name) {
+            Ok(tz) => Ok(Zone::Named(tz)),
+            Err(_) => Err(format!(
+                "Unknown timezone: '{}'. Use an IANA name such as 'Europe/Paris', \
+                 'UTC', 'local' or an offset like '+05:30'",
+                name
+            )),
+        }
+    }
+
+    pub(super) fn label(&self) -> String {
+        match self {
+            Zone::Local => "local".to_string(),
+            Zone::Fixed(offset) => offset.to_string(),
+            Zone::Named(tz) => tz.name().to_string(),
+        }
+    }
+
+    /// Wall-clock time in this zone at instant `t`.
+    pub(super) fn wall_clock(&self, t: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Local => t.with_timezone(&Local).naive_local(),
+            Zone::Fixed(offset) => t.with_timezone(offset).naive_local(),
+            Zone::Named(tz) => t.with_timezone(tz).naive_local(),
+        }
+    }
+
+    /// The instant when clocks in this zone show `wall`.
+    pub(super) fn resolve(&self, wall: NaiveDateTime) -> Result<DateTime<Utc>, String> {
+        let label = self.label();
+        match self {
+            Zone::Local => resolve_in(&Local, wall, &label),
+            Zone::Fixed(offset) => resolve_in(offset, wall, &label),
+            Zone::Named(tz) => resolve_in(tz, wall, &label),
+        }
+    }
+
+    pub(super) fn describe(&self, t: DateTime<Utc>) -> Value {
+        match self {
+            Zone::Local => describe_in(t.with_timezone(&Local), "local"),
+            Zone::Fixed(offset) => describe_in(t.with_timezone(offset), &offset.to_string()),
+            Zone::Named(tz) => {
+                let local = t.with_timezone(tz);
+                let dst = !local.offset().dst_offset().is_zero();
+                let mut value = describe_in(local, tz.name());
+                value["dst"] = json!(dst);
+                value
+            }
+        }
+    }
+}
+
+fn resolve_in<T: TimeZone>(
+    tz: &T,
+    wall: NaiveDateTime,
+    label: &str,
+) -> Result<DateTime<Utc>, String> {
+    match tz.from_local_datetime(&wall) {
+        LocalResult::Single(t) => Ok(t.with_timezone(&Utc)),
+        // The repeated hour when clocks go back: the first pass.
+        LocalResult::Ambiguous(first, _) => Ok(first.with_timezone(&Utc)),
+        LocalResult::None => Err(format!(
+            "{} does not exist in {}: the clocks skip it for daylight saving",
+            wall.format("%Y-%m-%d %H:%M"),
+            label
+        )),
+    }
+}
+
+fn describe_in<T: TimeZone>(t: DateTime<T>, zone: &str) -> Value
+where
+    T::Offset: Display,
+{
+    json!({
+        "rfc3339": t.to_rfc3339_opts(SecondsFormat::Secs, false),
+        "unix": t.timestamp(),
+        "timezone": zone,
+        "utc_offset": t.format("%:z").to_string(),
+        "human": t.format("%A, %-d %B %Y, %H:%M:%S %Z").to_string(),
+    })
+}
+
+/// `+05:30`, `-0800`, `UTC+2`, `GMT-03:00`.
+fn parse_offset(text: &str) -> Option<FixedOffset> {
+    let upper = text.to_ascii_uppercase();
+    let rest = upper
+        .strip_prefix("UTC")
+        .or_else(|| upper.strip_prefix("GMT"))
+        .unwrap_or(&upper);
+    let (sign, digits) = match rest.split_at_checked(1)? {
+        ("+", d) => (1, d),
+        ("-", d) => (-1, d),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() > 2 => digits.split_at(digits.len() - 2),
+        None => (digits, "0"),
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+// ── Parsing ─────────────────────────────────────────────────────────────────
+
+/// Parse `text` as an instant; times without an offset are wall-clock
+/// times in `zone`, and relative phrases count from `now`.
+pub fn parse_time(text: &str, zone: &Zone, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let text = text.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(text) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    if let Ok(t) = DateTime::parse_from_rfc2822(text) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    let wall = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(text, fmt).ok());
+    if let Some(wall) = wall {
+        return zone.resolve(wall);
+    }
+
+    let lower = text.to_lowercase().replace(',', " ");
+    let tokens: Vec<&str> = lower
+        .split_whitespace()
+        .filter(|t| !matches!(*t, "at" | "on" | "the" | "of"))
+        .collect();
+    let unparsed = || {
+        format!(
+            "Could not parse '{}'. Try RFC 3339, 'YYYY-MM-DD HH:MM', 'tomorrow 9am', \
+             'next friday', 'march 5 14:00', 'in 3 hours' or '2 days ago'",
+            text
+        )
+    };
+    if tokens.is_empty() {
+        return Err(unparsed());
+    }
+    if tokens == ["now"] {
+        return Ok(now);
+    }
+    if let Some(result) = parse_relative(&tokens, zone, now) {
+        return result;
+    }
+
+    // A clock time, if any, comes last: "friday 9am", "march 5 14:30".
+    let mut date_tokens = tokens.clone();
+    let mut time = None;
+    if let [.., clock, meridiem @ ("am" | "pm")] = tokens.as_slice() {
+        time = parse_clock(&format!("{}{}", clock, meridiem));
+        if time.is_some() {
+            date_tokens.truncate(tokens.len() - 2);
+        }
+    }
+    if time.is_none()
+        && let Some(t) = tokens.last().and_then(|t| parse_clock(t))
+    {
+        time = Some(t);
+        date_tokens.pop();
+    }
+
+    let today = zone.wall_clock(now).date();
+    let date = if date_tokens.is_empty() && time.is_some() {
+        today
+    } else {
+        parse_date(&date_tokens, today).ok_or_else(unparsed)?
+    };
+    zone.resolve(date.and_time(time.unwrap_or(NaiveTime::MIN)))
+}
+
+/// `in 3 hours`, `in a week`, `2 days ago`.
+fn parse_relative(
+    tokens: &[&str],
+    zone: &Zone,
+    now: DateTime<Utc>,
+) -> Option<Result<DateTime<Utc>, String>> {
+    let (amount, unit, sign) = match tokens {
+        ["in", amount, unit] => (*amount, *unit, 1),
+        [amount, unit, "ago"] => (*amount, *unit, -1),
+        _ => return None,
+    };
+    let amount: i64 = match amount {
+        "a" | "an" | "one" => 1,
+        n => n.parse().ok()?,
+    };
+    let amount = sign * amount;
+    let unit = if unit.len() > 1 {
+        unit.trim_end_matches('s')
+    } else {
+        unit
+    };
+    let out_of_range = || format!("'{}' is out of range", tokens.join(" "));
+
+    let shifted = match unit {
+        "second" | "sec" | "s" => Duration::try_seconds(amount).map(|d| now + d),
+        "minute" | "min" | "m" => Duration::try_minutes(amount).map(|d| now + d),
+        "hour" | "hr" | "h" => Duration::try_hours(amount).map(|d| now + d),
+        // Days and longer keep the wall-clock time across DST changes.
+        "day" | "d" | "week" | "w" | "month" | "year" | "yr" | "y" => {
+            let wall = zone.wall_clock(now);
+            let wall = match unit {
+                "day" | "d" => Duration::try_days(amount).and_then(|d| wall.checked_add_signed(d)),
+                "week" | "w" => {
+                    Duration::try_weeks(amount).and_then(|d| wall.checked_add_signed(d))
+                }
+                _ => {
+                    let months = if unit == "month" {
+                        amount
+                    } else {
+                        amount.checked_mul(12)?
+                    };
+                    let n = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+                    if months >= 0 {
+                        wall.checked_add_months(n)
+                    } else {
+                        wall.checked_sub_months(n)
+                    }
+                }
+            };
+            return Some(wall.ok_or_else(out_of_range).and_then(|w| zone.resolve(w)));
+        }
+        _ => return None,
+    };
+    Some(shifted.ok_or_else(out_of_range))
+}
+
+/// `15:30`, `15:30:45`, `9am`, `9:30pm`, `noon`, `midnight`.  A bare
+/// number is not a time; it is more likely a day of the month.
+pub(super) fn parse_clock(token: &str) -> Option<NaiveTime> {
+    match token {
+        "noon" | "midday" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return Some(NaiveTime::MIN),
+        _ => {}
+    }
+    let (clock, pm) = if let Some(c) = token.strip_suffix("am") {
+        (c, Some(false))
+    } else if let Some(c) = token.strip_suffix("pm") {
+        (c, Some(true))
+    } else {
+        (token, None)
+    };
+    let mut parts = clock.split(':');
+    let hour: u32 = parts.next()?.parse().ok()?;
+    let minute: u32 = parts.next().map_or(Some(0), |m| m.parse().ok())?;
+    let second: u32 = parts.next().map_or(Some(0), |s| s.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let hour = match pm {
+        None if !clock.contains(':') => return None,
+        None => hour,
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+    };
+    NaiveTime::from_hms_opt(hour, minute, second)
+}
+
+fn parse_date(tokens: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    let weekday = |name: &str| {
+        WEEKDAYS
+            .iter()
+            .find(|(full, _)| name.len() >= 3 && full.starts_with(name))
+            .map(|(_, day)| *day)
+    };
+    let month = |name: &str| {
+        MONTHS
+            .iter()
+            .position(|full| name.len() >= 3 && full.starts_with(name))
+            .map(|i| i as u32 + 1)
+    };
+    let day = |token: &str| {
+        let digits = token.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        digits.parse::<u32>().ok().filter(|d| (1..=31).contains(d))
+    };
+    let year = |token: &str| token.parse::<i32>().ok().filter(|_| token.len() == 4);
+    // Days from `today` to the next `target`, 0 when it is today.
+    let ahead = |target: Weekday| {
+        i64::from((target.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7)
+    };
+
+    match tokens {
+        ["today"] => Some(today),
+        ["tomorrow"] => today.succ_opt(),
+        ["yesterday"] => today.pred_opt(),
+        [iso] if iso.contains('-') => NaiveDate::parse_from_str(iso, "%Y-%m-%d").ok(),
+        [name] | ["this", name] => today.checked_add_signed(Duration::days(ahead(weekday(name)?))),
+        ["next", name] => {
+            let n = match ahead(weekday(name)?) {
+                0 => 7,
+                n => n,
+            };
+            today.checked_add_signed(Duration::days(n))
+        }
+        ["last", name] => {
+            let n = match ahead(weekday(name)?) {
+                0 => 7,
+                n => 7 - n,
+            };
+            today.checked_sub_signed(Duration::days(n))
+        }
+        [a, b] => {
+            let (m, d) = month(a).zip(day(b)).or_else(|| month(b).zip(day(a)))?;
+            NaiveDate::from_ymd_opt(today.year(), m, d)
+        }
+        [a, b, y] => {
+            let (m, d) = month(a).zip(day(b)).or_else(|| month(b).zip(day(a)))?;
+            NaiveDate::from_ymd_opt(year(y)?, m, d)
+        }
+        _ => None,
+    }
+}
+
+// ── Durations ───────────────────────────────────────────────────────────────
+
+/// `1 day, 23 hours, 5 minutes`; days are 24 hours of elapsed time.
+fn human_duration(seconds: i64) -> String {
+    let parts = duration_parts(seconds);
+    let names = ["day", "hour", "minute", "second"];
+    let mut out: Vec<String> = parts
+        .iter()
+        .zip(names)
+        .filter(|(n, _)| **n > 0)
+        .map(|(n, name)| format!("{} {}{}", n, name, if *n == 1 { "" } else { "s" }))
+        .collect();
+    if out.is_empty() {
+        out.push("0 seconds".to_string());
+    }
+    let text = out.join(", ");
+    if seconds < 0 {
+        format!("{} (end is before start)", text)
+    } else {
+        text
+    }
+}
+
+/// ISO 8601 duration: `P1DT23H5M`, `-PT30M`, `PT0S`.
+fn iso_duration(seconds: i64) -> String {
+    let [d, h, m, s] = duration_parts(seconds);
+    let mut out = String::from(if seconds < 0 { "-P" } else { "P" });
+    if d > 0 {
+        out.push_str(&format!("{}D", d));
+    }
+    if h > 0 || m > 0 || s > 0 || d == 0 {
+        out.push('T');
+        if h > 0 {
+            out.push_str(&format!("{}H", h));
+        }
+        if m > 0 {
+            out.push_str(&format!("{}M", m));
+        }
+        if s > 0 || (d == 0 && h == 0 && m == 0) {
+            out.push_str(&format!("{}S", s));
+        }
+    }
+    out
+}
+
+/// Days, hours, minutes and seconds of `|seconds|`.
+fn duration_parts(seconds: i64) -> [u64; 4] {
+    let s = seconds.unsigned_abs();
+    [s / 86_400, s % 86_400 / 3600, s % 3600 / 60, s % 60]
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn time_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "action".into(),
+            description: "One of: now, convert, diff, parse.".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "timezone".into(),
+            description: "For 'now', 'parse' and 'diff': IANA zone such as \
+                          'America/New_York', 'UTC', an offset like '+05:30', or 'local' \
+                          (default, the gateway host's zone)."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "time".into(),
+            description: "For 'convert': the time to convert, e.g. '2026-03-29 14:00', \
+                          'tomorrow 9am' or an RFC 3339 timestamp."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "from".into(),
+            description: "For 'convert': zone that 'time' is in when it has no offset. \
+                          Default: local."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "to".into(),
+            description: "For 'convert': zone to convert to.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "start".into(),
+            description: "For 'diff': the earlier time, in any form 'parse' accepts.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "end".into(),
+            description: "For 'diff': the later time.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "text".into(),
+            description: "For 'parse': a date or time such as 'next friday 3pm', \
+                          'march 5 2026', 'in 2 hours' or '3 days ago'."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
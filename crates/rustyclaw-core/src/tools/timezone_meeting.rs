@@ -0,0 +1,317 @@
+//! Meeting planner: the working hours several time zones have in common.
+//!
+//! Each participant's hours are wall-clock times in their own zone.  They
+//! are turned into instants date by date with the tz database, so someone
+//! whose clocks change that week is placed by the offset in force on the
+//! day, not today's.  The meeting day is a calendar date in the reference
+//! zone (the first participant's unless `timezone` names another); a
+//! participant far away may be working late the day before or early the
+//! day after, and their local dates are shown with each window.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+use serde_json::{Value, json};
+use std::path::Path;
+use tracing::instrument;
+
+use super::ToolParam;
+use super::time_tool::{Zone, parse_clock, parse_time};
+
+/// Working hours for participants that don't give their own.
+const DEFAULT_HOURS: &str = "09:00-17:00";
+const DEFAULT_MIN_MINUTES: i64 = 30;
+const MAX_PARTICIPANTS: usize = 20;
+
+/// A span of time, start inclusive and end exclusive.
+type Span = (DateTime<Utc>, DateTime<Utc>);
+
+struct Participant {
+    name: String,
+    zone: Zone,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Execute the `timezone_meeting` tool.
+#[instrument(skip(args, _workspace_dir))]
+pub fn exec_timezone_meeting(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let participants = parse_participants(args.get("participants"))?;
+    let reference = match args.get("timezone").and_then(|v| v.as_str()) {
+        Some(name) => Zone::parse(Some(name))?,
+        None => participants[0].zone,
+    };
+    let now = Utc::now();
+    let date = match args.get("date").and_then(|v| v.as_str()) {
+        Some(text) => parse_day(text, &reference, now)?,
+        None => reference.wall_clock(now).date(),
+    };
+    let min_minutes = args
+        .get("min_minutes")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(DEFAULT_MIN_MINUTES);
+    if min_minutes < 1 {
+        return Err("min_minutes must be at least 1".into());
+    }
+
+    let next = date
+        .succ_opt()
+        .ok_or_else(|| format!("{} is out of range", date))?;
+    let day = (
+        start_of_day(&reference, date)?,
+        start_of_day(&reference, next)?,
+    );
+    let mut free = vec![day];
+    for participant in &participants {
+        free = intersect(&free, &participant.working_spans(date)?);
+    }
+    let windows: Vec<Value> = free
+        .iter()
+        .filter(|(start, end)| (*end - *start).num_minutes() >= min_minutes)
+        .map(|span| describe_window(span, &participants))
+        .collect();
+
+    let midday = day.0 + (day.1 - day.0) / 2;
+    let mut result = json!({
+        "date": date.to_string(),
+        "timezone": reference.label(),
+        "min_minutes": min_minutes,
+        "participants": participants
+            .iter()
+            .map(|p| p.describe(midday))
+            .collect::<Vec<_>>(),
+    });
+    if windows.is_empty() {
+        result["note"] = json!(format!(
+            "No common working time of at least {} minutes on {}. Widen someone's hours \
+             or lower min_minutes.",
+            min_minutes, date
+        ));
+    }
+    result["windows"] = json!(windows);
+    serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+}
+
+// ── Participants ────────────────────────────────────────────────────────────
+
+fn parse_participants(value: Option<&Value>) -> Result<Vec<Participant>, String> {
+    let items = value
+        .and_then(|v| v.as_array())
+        .filter(|items| !items.is_empty())
+        .ok_or("Missing required parameter: participants (a list of time zones)")?;
+    if items.len() > MAX_PARTICIPANTS {
+        return Err(format!(
+            "Too many participants: {} (at most {})",
+            items.len(),
+            MAX_PARTICIPANTS
+        ));
+    }
+    items.iter().map(Participant::parse).collect()
+}
+
+impl Participant {
+    /// `"America/New_York"`, `"Ana America/New_York 08:00-16:00"`, or an
+    /// object with `name`, `timezone` and `hours`.
+    fn parse(item: &Value) -> Result<Self, String> {
+        let (name, zone, hours) = match item {
+            Value::String(text) => split_participant(text)?,
+            Value::Object(fields) => {
+                let field = |key: &str| fields.get(key).and_then(|v| v.as_str()).map(str::trim);
+                let zone = field("timezone")
+                    .filter(|z| !z.is_empty())
+                    .ok_or("Each participant needs a timezone")?;
+                (
+                    field("name").unwrap_or_default().to_string(),
+                    Zone::parse(Some(zone))?,
+                    field("hours").map(str::to_string),
+                )
+            }
+            other => return Err(format!("Not a participant: {}", other)),
+        };
+        let hours = hours.as_deref().unwrap_or(DEFAULT_HOURS);
+        let (start, end) = parse_hours(hours)
+            .ok_or_else(|| format!("Invalid working hours '{}': use e.g. 09:00-17:30", hours))?;
+        if start == end {
+            return Err(format!("Working hours '{}' are empty", hours));
+        }
+        let name = if name.is_empty() { zone.label() } else { name };
+        Ok(Self {
+            name,
+            zone,
+            start,
+            end,
+        })
+    }
+
+    /// This participant's working hours on the local dates that can fall
+    /// within the reference day `date`.  Hours that end before they start
+    /// run past midnight.
+    fn working_spans(&self, date: NaiveDate) -> Result<Vec<Span>, String> {
+        let mut spans = Vec::new();
+        // Two zones are at most 26 hours apart.
+        for offset in -2..=2 {
+            let Some(local) = date.checked_add_signed(Duration::days(offset)) else {
+                continue;
+            };
+            let end_date = if self.end <= self.start {
+                local.succ_opt()
+            } else {
+                Some(local)
+            };
+            let Some(end_date) = end_date else {
+                continue;
+            };
+            let start = resolve_forward(&self.zone, local.and_time(self.start))?;
+            let end = resolve_forward(&self.zone, end_date.and_time(self.end))?;
+            if start < end {
+                spans.push((start, end));
+            }
+        }
+        Ok(spans)
+    }
+
+    fn describe(&self, at: DateTime<Utc>) -> Value {
+        json!({
+            "name": self.name,
+            "timezone": self.zone.label(),
+            "hours": format!("{}-{}", self.start.format("%H:%M"), self.end.format("%H:%M")),
+            "utc_offset": self.zone.describe(at)["utc_offset"],
+        })
+    }
+}
+
+/// Split `"Ana America/New_York 08:00-16:00"` into its parts; only the
+/// zone is required.  Some zone names are also words ("Japan", "Zulu"), so
+/// the last token that names a zone is taken and the rest is the name.
+fn split_participant(text: &str) -> Result<(String, Zone, Option<String>), String> {
+    let mut tokens: Vec<&str> = text.split_whitespace().collect();
+    let hours = tokens
+        .iter()
+        .position(|t| parse_hours(t).is_some())
+        .map(|i| tokens.remove(i).to_string());
+    let zone = tokens
+        .iter()
+        .rposition(|t| Zone::parse(Some(t)).is_ok())
+        .and_then(|i| Zone::parse(Some(tokens.remove(i))).ok());
+    let name: Vec<&str> = tokens.iter().map(|t| t.trim_end_matches(':')).collect();
+    let zone = zone.ok_or_else(|| {
+        format!(
+            "No timezone in participant '{}'. Use an IANA name such as 'Europe/Paris', \
+             'UTC' or an offset like '+05:30'",
+            text
+        )
+    })?;
+    Ok((name.join(" "), zone, hours))
+}
+
+/// `09:00-17:30`, `9am-5pm`, `22:00-06:00`.
+fn parse_hours(text: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = text.split_once('-')?;
+    let clock = |t: &str| parse_clock(&t.trim().to_ascii_lowercase());
+    Some((clock(start)?, clock(end)?))
+}
+
+// ── Dates and spans ─────────────────────────────────────────────────────────
+
+/// `2026-03-09`, or anything the `time` tool parses, as a date in `zone`.
+fn parse_day(text: &str, zone: &Zone, now: DateTime<Utc>) -> Result<NaiveDate, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d") {
+        return Ok(date);
+    }
+    Ok(zone.wall_clock(parse_time(text, zone, now)?).date())
+}
+
+fn start_of_day(zone: &Zone, date: NaiveDate) -> Result<DateTime<Utc>, String> {
+    resolve_forward(zone, date.and_time(NaiveTime::MIN))
+}
+
+/// The instant clocks in `zone` show `wall`, or the first one after it
+/// when daylight saving skips that time.
+fn resolve_forward(zone: &Zone, wall: NaiveDateTime) -> Result<DateTime<Utc>, String> {
+    let mut first_error = None;
+    for minutes in (0..=180).step_by(15) {
+        match zone.resolve(wall + Duration::minutes(minutes)) {
+            Ok(t) => return Ok(t),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(first_error.unwrap_or_default())
+}
+
+/// The parts of both sorted, disjoint span lists that overlap.
+fn intersect(a: &[Span], b: &[Span]) -> Vec<Span> {
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start < end {
+            out.push((start, end));
+        }
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    out
+}
+
+fn describe_window(&(start, end): &Span, participants: &[Participant]) -> Value {
+    let local = |zone: &Zone, t: DateTime<Utc>| zone.wall_clock(t).format("%a %Y-%m-%d %H:%M");
+    json!({
+        "start_utc": start.to_rfc3339_opts(SecondsFormat::Secs, true),
+        "end_utc": end.to_rfc3339_opts(SecondsFormat::Secs, true),
+        "minutes": (end - start).num_minutes(),
+        "local": participants
+            .iter()
+            .map(|p| json!({
+                "name": p.name,
+                "start": local(&p.zone, start).to_string(),
+                "end": local(&p.zone, end).to_string(),
+                "utc_offset": p.zone.describe(start)["utc_offset"],
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn timezone_meeting_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "participants".into(),
+            description: "One entry per participant: a timezone, optionally with a name \
+                          and working hours, e.g. 'Ana America/New_York 08:00-16:00', \
+                          'Europe/Berlin' or 'Kenji Asia/Tokyo 9am-6pm'. Hours default to \
+                          09:00-17:00; hours that end before they start run past midnight."
+                .into(),
+            param_type: "array".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "date".into(),
+            description: "Meeting day, e.g. '2026-03-10', 'tomorrow' or 'next tuesday'. \
+                          Default: today in the reference zone."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "timezone".into(),
+            description: "Zone the date is a calendar day in. Default: the first \
+                          participant's."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "min_minutes".into(),
+            description: "Shortest window worth listing, in minutes (default 30).".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
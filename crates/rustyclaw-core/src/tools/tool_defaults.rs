@@ -0,0 +1,95 @@
+//! Per-tool default arguments from `[tool_defaults.<tool>]`.
+//!
+//! ```toml
+//! [tool_defaults.web_search]
+//! count = 10
+//!
+//! [tool_defaults.disk_usage]
+//! depth = 2
+//! ```
+//!
+//! [`execute_tool`](super::execute_tool) fills in any of these the model
+//! left out (or sent as `null`) before dispatching; an argument the model
+//! did give is never replaced.  Keys that aren't parameters of the tool
+//! are dropped with a warning when the table is installed, so a typo
+//! can't reach a tool as an argument it doesn't expect.
+
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use super::{all_tools, tool_params};
+
+/// Default arguments, keyed by tool name.
+pub type ToolDefaults = HashMap<String, Map<String, Value>>;
+
+/// `[tool_defaults]`, checked and set once at gateway startup.
+static DEFAULTS: OnceLock<ToolDefaults> = OnceLock::new();
+
+/// Called once from the gateway to apply `[tool_defaults]`.  Unknown tools
+/// and parameters are logged and left out.
+pub fn set_tool_defaults(defaults: &ToolDefaults) {
+    let (checked, problems) = check_tool_defaults(defaults);
+    for problem in &problems {
+        warn!("[tool_defaults] {}", problem);
+    }
+    let _ = DEFAULTS.set(checked);
+}
+
+/// Split `defaults` into the entries that name a real tool parameter and
+/// a description of each one that doesn't.
+pub fn check_tool_defaults(defaults: &ToolDefaults) -> (ToolDefaults, Vec<String>) {
+    let tools = all_tools();
+    let mut checked = ToolDefaults::new();
+    let mut problems = Vec::new();
+
+    let mut names: Vec<&String> = defaults.keys().collect();
+    names.sort();
+    for name in names {
+        if !tools.iter().any(|t| t.name == name.as_str()) {
+            problems.push(format!("unknown tool '{}'", name));
+            continue;
+        }
+        let params = tool_params(name);
+        let mut keys: Vec<(&String, &Value)> = defaults[name].iter().collect();
+        keys.sort_by_key(|(key, _)| *key);
+        for (key, value) in keys {
+            if params.iter().any(|p| p.name == *key) {
+                checked
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(key.clone(), value.clone());
+            } else {
+                problems.push(format!("'{}' has no parameter '{}'", name, key));
+            }
+        }
+    }
+    (checked, problems)
+}
+
+/// `args` for a call to `name`, with the configured defaults filled in.
+pub(crate) fn with_defaults<'a>(name: &str, args: &'a Value) -> Cow<'a, Value> {
+    match DEFAULTS.get().and_then(|all| all.get(name)) {
+        Some(defaults) => Cow::Owned(merge_defaults(args, defaults)),
+        None => Cow::Borrowed(args),
+    }
+}
+
+/// Add each of `defaults` that `args` doesn't set.  A missing argument
+/// object counts as empty; anything else that isn't an object is left
+/// for the tool to reject.
+pub fn merge_defaults(args: &Value, defaults: &Map<String, Value>) -> Value {
+    let mut merged = match args {
+        Value::Object(map) => map.clone(),
+        Value::Null => Map::new(),
+        other => return other.clone(),
+    };
+    for (key, value) in defaults {
+        if merged.get(key).is_none_or(Value::is_null) {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+    Value::Object(merged)
+}
@@ -0,0 +1,621 @@
+//! `transcribe` tool: speech to text for voice notes and recordings.
+//!
+//! whisper.cpp runs locally when its binary, a ggml model and ffmpeg are
+//! found; otherwise — or with `[transcribe] backend = "cloud"` — the audio
+//! goes to an OpenAI-compatible transcription API with a key from the
+//! vault.  The result names the backend that ran, and why the local one
+//! was passed over when `auto` fell back.
+//!
+//! ffmpeg converts whatever was recorded to the 16 kHz mono WAV whisper.cpp
+//! reads.  Recordings longer than `max_duration_secs` are refused rather
+//! than cut short, so a transcript is never silently partial.
+
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{
+    VAULT_ACCESS_DENIED, expand_tilde, is_protected_path, resolve_path, sandbox, transcribe_config,
+    vault,
+};
+use crate::config::{TranscribeBackend, TranscribeConfig};
+use crate::secrets::{AccessContext, CredentialValue};
+
+/// Audio formats accepted, by file extension.
+pub const AUDIO_EXTENSIONS: &[&str] = &[
+    "wav", "mp3", "m4a", "aac", "ogg", "oga", "opus", "flac", "webm", "mp4", "mpga", "mpeg",
+];
+
+/// Largest file accepted (200 MiB).
+pub const MAX_INPUT_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Largest file sent to the cloud API, which rejects anything over 25 MB.
+const MAX_UPLOAD_BYTES: u64 = 25 * 1024 * 1024;
+
+/// How long ffmpeg and ffprobe may run before they are killed.
+const FFMPEG_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// whisper.cpp gets a minute plus this many seconds per second of audio.
+const WHISPER_SECS_PER_AUDIO_SEC: u64 = 2;
+
+const REQUEST_TIMEOUT_SECS: u64 = 300;
+
+/// Where [`find_local`] looks for a model when none is configured,
+/// relative to the home directory.
+const MODEL_DIRS: &[&str] = &[".cache/whisper", ".local/share/whisper"];
+
+/// One stretch of speech, in seconds from the start of the recording.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Segment {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// What a backend heard.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Transcript {
+    pub text: String,
+    pub language: Option<String>,
+    pub segments: Vec<Segment>,
+}
+
+/// An installed whisper.cpp and the model it will load.
+pub(crate) struct LocalWhisper {
+    binary: PathBuf,
+    model: PathBuf,
+    ffmpeg: PathBuf,
+}
+
+// ── Tool executor (async) ───────────────────────────────────────────────────
+
+/// Execute the `transcribe` tool.
+#[instrument(skip(args, workspace_dir), fields(backend))]
+pub async fn exec_transcribe_async(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let config = transcribe_config();
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: path".to_string())?;
+    let input = checked_path(workspace_dir, path)?;
+    let extension = input
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!(
+            "Unsupported audio format '{}'. Use: {}",
+            input.display(),
+            AUDIO_EXTENSIONS.join(", ")
+        ));
+    }
+    let size = std::fs::metadata(&input)
+        .map_err(|_| format!("File not found: {}", input.display()))?
+        .len();
+    if size > MAX_INPUT_BYTES {
+        return Err(format!(
+            "Audio is {} bytes; the limit is {}",
+            size, MAX_INPUT_BYTES
+        ));
+    }
+    let language = match args.get("language").and_then(|v| v.as_str()) {
+        Some(lang) if lang.eq_ignore_ascii_case("auto") => None,
+        Some(lang) => {
+            if !(2..=3).contains(&lang.len()) || !lang.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(format!(
+                    "Invalid language: '{}' (an ISO 639-1 code such as 'en' or 'de')",
+                    lang
+                ));
+            }
+            Some(lang.to_ascii_lowercase())
+        }
+        None => None,
+    };
+    let timestamps = args
+        .get("timestamps")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let backend = match args.get("backend").and_then(|v| v.as_str()) {
+        None => config.backend,
+        Some("auto") => TranscribeBackend::Auto,
+        Some("local") => TranscribeBackend::Local,
+        Some("cloud") => TranscribeBackend::Cloud,
+        Some(other) => {
+            return Err(format!(
+                "Unknown backend: '{}'. Use: auto, local, cloud",
+                other
+            ));
+        }
+    };
+
+    let mut duration = probe_duration(&input, &extension).await;
+    check_duration(duration, config.max_duration_secs)?;
+
+    let mut fallback = None;
+    let local = match backend {
+        TranscribeBackend::Cloud => None,
+        TranscribeBackend::Local => Some(
+            find_local(&config).map_err(|e| format!("Local transcription unavailable: {}", e))?,
+        ),
+        TranscribeBackend::Auto => match find_local(&config) {
+            Ok(local) => Some(local),
+            Err(reason) => {
+                debug!(%reason, "whisper.cpp unavailable, using the cloud backend");
+                fallback = Some(reason);
+                None
+            }
+        },
+    };
+
+    let (used, model, transcript) = match local {
+        Some(local) => {
+            let (transcript, measured) = run_local(
+                &local,
+                &input,
+                language.as_deref(),
+                config.max_duration_secs,
+            )
+            .await?;
+            duration = duration.or(Some(measured));
+            let model = local
+                .model
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            ("whisper.cpp", model, transcript)
+        }
+        None => {
+            let transcript = run_cloud(&config, &input, size, language.as_deref(), timestamps)
+                .await
+                .map_err(|e| match &fallback {
+                    Some(reason) => format!(
+                        "No transcription backend available. whisper.cpp: {}. Cloud: {}",
+                        reason, e
+                    ),
+                    None => e,
+                })?;
+            ("cloud", config.api_model.clone(), transcript)
+        }
+    };
+    tracing::Span::current().record("backend", used);
+    debug!(
+        path = %input.display(),
+        backend = used,
+        chars = transcript.text.len(),
+        "Transcribed audio"
+    );
+
+    let mut result = json!({
+        "path": input.display().to_string(),
+        "backend": used,
+        "model": model,
+        "language": transcript.language.or(language),
+        "duration_secs": duration.map(|d| (d * 10.0).round() / 10.0),
+        "text": transcript.text,
+    });
+    if timestamps {
+        result["segments"] = json!(transcript.segments);
+    }
+    if let Some(reason) = fallback {
+        result["local_unavailable"] = json!(reason);
+    }
+    serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+}
+
+/// Sync stub for the static ToolDef.
+pub fn exec_transcribe_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("transcribe requires async execution".into())
+}
+
+fn check_duration(duration: Option<f64>, max_secs: u64) -> Result<(), String> {
+    match duration {
+        Some(secs) if secs > max_secs as f64 => Err(format!(
+            "Recording is {:.0}s long; the limit is {}s ([transcribe] max_duration_secs)",
+            secs, max_secs
+        )),
+        _ => Ok(()),
+    }
+}
+
+// ── whisper.cpp ─────────────────────────────────────────────────────────────
+
+/// Find whisper.cpp, a model for it and ffmpeg, or say what is missing.
+pub(crate) fn find_local(config: &TranscribeConfig) -> Result<LocalWhisper, String> {
+    let binary = match &config.whisper_binary {
+        Some(binary) => which::which(expand_tilde(binary))
+            .map_err(|_| format!("whisper binary '{}' not found", binary))?,
+        None => which::which("whisper-cli")
+            .or_else(|_| which::which("whisper-cpp"))
+            .map_err(|_| "whisper.cpp is not installed (no whisper-cli on PATH)".to_string())?,
+    };
+    let model = match &config.whisper_model {
+        Some(model) => {
+            let path = expand_tilde(model);
+            if !path.is_file() {
+                return Err(format!("whisper model '{}' not found", model));
+            }
+            path
+        }
+        None => default_model().ok_or_else(|| {
+            format!(
+                "no whisper model configured and no ggml-*.bin in ~/{}",
+                MODEL_DIRS.join(" or ~/")
+            )
+        })?,
+    };
+    let ffmpeg = which::which("ffmpeg").map_err(|_| "ffmpeg is not installed".to_string())?;
+    Ok(LocalWhisper {
+        binary,
+        model,
+        ffmpeg,
+    })
+}
+
+/// The first `ggml-*.bin`, by name, in the usual model directories.
+fn default_model() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    MODEL_DIRS.iter().find_map(|dir| {
+        let mut models: Vec<PathBuf> = std::fs::read_dir(home.join(dir))
+            .ok()?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("ggml-") && n.ends_with(".bin"))
+            })
+            .collect();
+        models.sort();
+        models.into_iter().next()
+    })
+}
+
+/// Transcribe with whisper.cpp.  Also returns the duration of the
+/// converted audio, which is exact whatever the input format.
+async fn run_local(
+    local: &LocalWhisper,
+    input: &Path,
+    language: Option<&str>,
+    max_secs: u64,
+) -> Result<(Transcript, f64), String> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let stem = std::env::temp_dir().join(format!(
+        "rustyclaw-transcribe-{}-{}",
+        std::process::id(),
+        nanos
+    ));
+    let wav = stem.with_extension("wav");
+    let out_json = stem.with_extension("json");
+    let _cleanup = TempFiles(vec![wav.clone(), out_json.clone()]);
+
+    let mut ffmpeg = tokio::process::Command::new(&local.ffmpeg);
+    ffmpeg
+        .args(["-nostdin", "-v", "error", "-y", "-i"])
+        .arg(input)
+        .args(["-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+        .arg(&wav);
+    run(ffmpeg, "ffmpeg", FFMPEG_TIMEOUT).await?;
+
+    let header = read_head(&wav, 4096)?;
+    let duration = wav_duration_secs(&header)
+        .ok_or_else(|| "ffmpeg produced an unreadable WAV file".to_string())?;
+    check_duration(Some(duration), max_secs)?;
+
+    let mut whisper = tokio::process::Command::new(&local.binary);
+    whisper
+        .arg("-m")
+        .arg(&local.model)
+        .arg("-f")
+        .arg(&wav)
+        .args(["-l", language.unwrap_or("auto"), "-oj", "-np", "-of"])
+        .arg(&stem);
+    let timeout = Duration::from_secs(60 + duration.ceil() as u64 * WHISPER_SECS_PER_AUDIO_SEC);
+    run(whisper, "whisper.cpp", timeout).await?;
+
+    let body = std::fs::read_to_string(&out_json)
+        .map_err(|e| format!("whisper.cpp wrote no transcript: {}", e))?;
+    let body: Value = serde_json::from_str(&body)
+        .map_err(|e| format!("whisper.cpp wrote an unreadable transcript: {}", e))?;
+    Ok((parse_whisper_cpp(&body)?, duration))
+}
+
+/// Map whisper.cpp's `-oj` output.
+pub(crate) fn parse_whisper_cpp(body: &Value) -> Result<Transcript, String> {
+    let items = body
+        .get("transcription")
+        .and_then(|v| v.as_array())
+        .ok_or("whisper.cpp output has no transcription")?;
+    let segments: Vec<Segment> = items
+        .iter()
+        .map(|item| {
+            let ms = |key: &str| item["offsets"][key].as_f64().unwrap_or(0.0) / 1000.0;
+            Segment {
+                start: ms("from"),
+                end: ms("to"),
+                text: item["text"].as_str().unwrap_or("").trim().to_string(),
+            }
+        })
+        .filter(|s| !s.text.is_empty())
+        .collect();
+    Ok(Transcript {
+        text: join_segments(&segments),
+        language: body["result"]["language"].as_str().map(str::to_string),
+        segments,
+    })
+}
+
+// ── Cloud ───────────────────────────────────────────────────────────────────
+
+async fn run_cloud(
+    config: &TranscribeConfig,
+    input: &Path,
+    size: u64,
+    language: Option<&str>,
+    timestamps: bool,
+) -> Result<Transcript, String> {
+    if size > MAX_UPLOAD_BYTES {
+        return Err(format!(
+            "Audio is {} bytes; the cloud backend accepts up to {}. Compress it (e.g. to mp3) \
+             or install whisper.cpp",
+            size, MAX_UPLOAD_BYTES
+        ));
+    }
+    let api_key = api_key(config).await?;
+    let bytes = tokio::fs::read(input)
+        .await
+        .map_err(|e| format!("Failed to read '{}': {}", input.display(), e))?;
+    let file_name = input
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "audio".to_string());
+
+    // Only verbose_json carries segment times, and not every model offers it.
+    let format = if timestamps { "verbose_json" } else { "json" };
+    let mut form = reqwest::multipart::Form::new()
+        .part(
+            "file",
+            reqwest::multipart::Part::bytes(bytes).file_name(file_name),
+        )
+        .text("model", config.api_model.clone())
+        .text("response_format", format);
+    if timestamps {
+        form = form.text("timestamp_granularities[]", "segment");
+    }
+    if let Some(lang) = language {
+        form = form.text("language", lang.to_string());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .post(&config.api_url)
+        .bearer_auth(api_key)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("Transcription request failed: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Transcription API error ({}): {}", status, body));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read transcription response: {}", e))?;
+    parse_cloud(&body)
+}
+
+/// Map an OpenAI-style `json` or `verbose_json` response.
+pub(crate) fn parse_cloud(body: &Value) -> Result<Transcript, String> {
+    let text = body
+        .get("text")
+        .and_then(|v| v.as_str())
+        .ok_or("Transcription response has no text")?;
+    let segments = body
+        .get("segments")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .map(|s| Segment {
+                    start: s["start"].as_f64().unwrap_or(0.0),
+                    end: s["end"].as_f64().unwrap_or(0.0),
+                    text: s["text"].as_str().unwrap_or("").trim().to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(Transcript {
+        text: text.trim().to_string(),
+        language: body["language"].as_str().map(str::to_string),
+        segments,
+    })
+}
+
+async fn api_key(config: &TranscribeConfig) -> Result<String, String> {
+    let missing = || {
+        format!(
+            "the cloud backend needs an API key in the vault credential '{}'",
+            config.api_key
+        )
+    };
+    let vault = vault().ok_or_else(missing)?;
+    let ctx = AccessContext::requested_by("tool:transcribe");
+    let mut mgr = vault.lock().await;
+    let (_, value) = mgr
+        .get_credential(&config.api_key, &ctx)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(missing)?;
+    match value {
+        CredentialValue::Single(key) => Ok(key.into_inner()),
+        _ => Err(format!(
+            "Credential '{}' is not an API key credential",
+            config.api_key
+        )),
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Length of the recording: from the header for WAV, otherwise from
+/// ffprobe when it is installed.
+async fn probe_duration(input: &Path, extension: &str) -> Option<f64> {
+    if extension == "wav" {
+        return read_head(input, 4096)
+            .ok()
+            .and_then(|head| wav_duration_secs(&head));
+    }
+    let ffprobe = which::which("ffprobe").ok()?;
+    let mut command = tokio::process::Command::new(ffprobe);
+    command
+        .args(["-v", "error", "-show_entries", "format=duration"])
+        .args(["-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(input);
+    let stdout = run(command, "ffprobe", FFMPEG_TIMEOUT).await.ok()?;
+    stdout.trim().parse().ok()
+}
+
+/// Duration of a PCM WAV file from its header: the data chunk's size over
+/// the format chunk's byte rate.
+pub(crate) fn wav_duration_secs(header: &[u8]) -> Option<f64> {
+    if header.len() < 12 || &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return None;
+    }
+    let u32_at = |at: usize| {
+        header
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let mut byte_rate = None;
+    let mut at = 12;
+    while let (Some(id), Some(len)) = (header.get(at..at + 4), u32_at(at + 4)) {
+        match id {
+            b"fmt " => byte_rate = u32_at(at + 16),
+            b"data" => {
+                let rate = byte_rate.filter(|r| *r > 0)?;
+                return Some(len as f64 / rate as f64);
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length.
+        at += 8 + len as usize + (len as usize & 1);
+    }
+    None
+}
+
+fn read_head(path: &Path, limit: u64) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+    let mut head = Vec::new();
+    file.take(limit)
+        .read_to_end(&mut head)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    Ok(head)
+}
+
+/// Run a command to completion within `timeout`, returning its stdout.
+async fn run(
+    mut command: tokio::process::Command,
+    name: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let child = command
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let out = match tokio::time::timeout(timeout, child).await {
+        Ok(result) => result.map_err(|e| format!("Failed to run {}: {}", name, e))?,
+        Err(_) => {
+            return Err(format!(
+                "{} took longer than {}s and was stopped",
+                name,
+                timeout.as_secs()
+            ));
+        }
+    };
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(format!("{} failed: {}", name, stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+fn join_segments(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .map(|s| s.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn checked_path(workspace_dir: &Path, p: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, p);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted transcribe access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+/// Scratch files removed when the transcription finishes or fails.
+struct TempFiles(Vec<PathBuf>);
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn transcribe_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "path".into(),
+            description: format!(
+                "Audio file to transcribe ({}).",
+                AUDIO_EXTENSIONS.join(", ")
+            ),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "language".into(),
+            description: "Spoken language as an ISO 639-1 code (e.g. 'en'). Default: detected."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "timestamps".into(),
+            description: "Also return segments with start and end times in seconds. \
+                          Default: false."
+                .into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "backend".into(),
+            description: "'auto' (whisper.cpp if installed, else cloud), 'local' or 'cloud'. \
+                          Default: from [transcribe] config."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
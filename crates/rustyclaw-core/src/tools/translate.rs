@@ -0,0 +1,295 @@
+//! Translation tool: translate text with the configured model.
+//!
+//! The gateway owns the provider connection, so this module only does the
+//! model-independent work — splitting the input into prose and fenced code
+//! blocks, chunking long prose, building a tight translation prompt, and
+//! stitching the result back together.  The gateway supplies the actual
+//! model call to [`translate_with`].
+//!
+//! Fenced code blocks (```` ``` ```` / `~~~`) never reach the model and are
+//! copied to the output byte-for-byte.
+
+use serde_json::{Value, json};
+use std::future::Future;
+use std::path::Path;
+use tracing::{debug, instrument};
+
+use super::ToolParam;
+
+/// Maximum characters of prose sent to the model in one request.
+pub const MAX_CHUNK_CHARS: usize = 4000;
+
+/// A piece of the input text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Prose that should be translated.
+    Prose(String),
+    /// A fenced code block, passed through unchanged (fences included).
+    Code(String),
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Sync stub for the static ToolDef — the gateway intercepts `translate`
+/// because it needs the configured model.
+pub fn exec_translate_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("translate is executed by the gateway (it uses the configured model)".into())
+}
+
+/// Translate `args.text` using `call_model` for each prose chunk.
+///
+/// `call_model` receives a complete prompt and returns the model's reply.
+/// Returns a JSON object with the translated `text`, the `source_lang`
+/// (given or detected), and the `target_lang`.
+#[instrument(skip(args, call_model))]
+pub async fn translate_with<F, Fut>(args: &Value, mut call_model: F) -> Result<String, String>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    let text = args
+        .get("text")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing required parameter: text".to_string())?;
+    let target_lang = args
+        .get("target_lang")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Missing required parameter: target_lang".to_string())?;
+    let mut source_lang = args
+        .get("source_lang")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+    let detect = source_lang.is_none();
+
+    let segments = chunk_segments(split_fenced(text), MAX_CHUNK_CHARS);
+    debug!(segments = segments.len(), target_lang, "Translating");
+
+    let mut out = String::with_capacity(text.len());
+    for segment in segments {
+        let prose = match segment {
+            Segment::Code(code) => {
+                out.push_str(&code);
+                continue;
+            }
+            Segment::Prose(prose) => prose,
+        };
+
+        let body = prose.trim();
+        if body.is_empty() {
+            out.push_str(&prose);
+            continue;
+        }
+        // Models routinely drop surrounding blank lines; keep the original
+        // whitespace so paragraphs and code fences stay separated.
+        let start = prose.len() - prose.trim_start().len();
+        let (leading, trailing) = (&prose[..start], &prose[start + body.len()..]);
+
+        let ask_source = detect && source_lang.is_none();
+        let prompt = translation_prompt(body, target_lang, source_lang.as_deref(), ask_source);
+        let reply = call_model(prompt).await?;
+        let (detected, translated) = parse_reply(&reply, ask_source);
+        if detected.is_some() {
+            source_lang = detected;
+        }
+
+        out.push_str(leading);
+        out.push_str(translated.trim());
+        out.push_str(trailing);
+    }
+
+    Ok(json!({
+        "text": out,
+        "source_lang": source_lang,
+        "target_lang": target_lang,
+    })
+    .to_string())
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Split `text` into prose and fenced code blocks.
+///
+/// Concatenating the segments yields the original text exactly.  An
+/// unterminated fence runs to the end of the input.
+pub fn split_fenced(text: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut prose = String::new();
+    let mut code = String::new();
+    // (fence char, fence length) of the currently open block.
+    let mut open: Option<(char, usize)> = None;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        match open {
+            None => {
+                if let Some(fence) = fence_marker(trimmed) {
+                    if !prose.is_empty() {
+                        segments.push(Segment::Prose(std::mem::take(&mut prose)));
+                    }
+                    open = Some(fence);
+                    code.push_str(line);
+                } else {
+                    prose.push_str(line);
+                }
+            }
+            Some((ch, len)) => {
+                code.push_str(line);
+                let closes = fence_marker(trimmed)
+                    .is_some_and(|(c, n)| c == ch && n >= len && trimmed.trim_end().len() == n);
+                if closes {
+                    segments.push(Segment::Code(std::mem::take(&mut code)));
+                    open = None;
+                }
+            }
+        }
+    }
+
+    if !code.is_empty() {
+        segments.push(Segment::Code(code));
+    }
+    if !prose.is_empty() {
+        segments.push(Segment::Prose(prose));
+    }
+    segments
+}
+
+/// Return `(char, run length)` if `line` starts with a code fence.
+fn fence_marker(line: &str) -> Option<(char, usize)> {
+    let ch = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|c| *c == ch).count();
+    (len >= 3).then_some((ch, len))
+}
+
+/// Break prose segments longer than `max_chars` into smaller prose
+/// segments, preferring paragraph, then line, boundaries.
+pub fn chunk_segments(segments: Vec<Segment>, max_chars: usize) -> Vec<Segment> {
+    let mut out = Vec::with_capacity(segments.len());
+    for segment in segments {
+        match segment {
+            Segment::Prose(p) if p.len() > max_chars => {
+                out.extend(chunk_prose(&p, max_chars).into_iter().map(Segment::Prose));
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn chunk_prose(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for para in text.split_inclusive("\n\n") {
+        if para.len() > max_chars {
+            // Oversized paragraph: fall back to line, then hard splits.
+            for line in para.split_inclusive('\n') {
+                for piece in hard_split(line, max_chars) {
+                    push_piece(&mut chunks, &mut current, piece, max_chars);
+                }
+            }
+        } else {
+            push_piece(&mut chunks, &mut current, para, max_chars);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn push_piece(chunks: &mut Vec<String>, current: &mut String, piece: &str, max_chars: usize) {
+    if !current.is_empty() && current.len() + piece.len() > max_chars {
+        chunks.push(std::mem::take(current));
+    }
+    current.push_str(piece);
+}
+
+/// Split `s` into pieces of at most `max_chars` bytes on char boundaries.
+fn hard_split(s: &str, max_chars: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = s;
+    while rest.len() > max_chars {
+        let mut cut = max_chars;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        pieces.push(&rest[..cut]);
+        rest = &rest[cut..];
+    }
+    pieces.push(rest);
+    pieces
+}
+
+/// Build the prompt for one prose chunk.
+pub fn translation_prompt(
+    text: &str,
+    target_lang: &str,
+    source_lang: Option<&str>,
+    detect_source: bool,
+) -> String {
+    let from = source_lang
+        .map(|s| format!(" from {}", s))
+        .unwrap_or_default();
+    let header = if detect_source {
+        "- First output a line `[source: <language name>]` naming the source language, \
+         then the translation.\n"
+    } else {
+        ""
+    };
+    format!(
+        "Translate the text between the <text> tags{from} into {target_lang}.\n\
+         Rules:\n\
+         - Output only the translation: no commentary, notes, or surrounding quotes.\n\
+         - Preserve Markdown formatting, inline `code`, URLs, and placeholders exactly.\n\
+         {header}\n\
+         <text>\n{text}\n</text>"
+    )
+}
+
+/// Split a model reply into `(detected source language, translation)`.
+pub fn parse_reply(reply: &str, expect_source: bool) -> (Option<String>, String) {
+    let reply = reply.trim();
+    if expect_source {
+        let (first, rest) = reply.split_once('\n').unwrap_or((reply, ""));
+        let lang = first
+            .trim()
+            .strip_prefix("[source:")
+            .and_then(|s| s.strip_suffix(']'))
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        if let Some(lang) = lang {
+            return (Some(lang.to_string()), rest.to_string());
+        }
+    }
+    (None, reply.to_string())
+}
+
+// ── Parameter definitions ───────────────────────────────────────────────────
+
+pub fn translate_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "text".into(),
+            description: "Text to translate. Markdown is kept; fenced code blocks are \
+                          passed through unchanged."
+                .into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "target_lang".into(),
+            description: "Language to translate into (e.g. 'French', 'ja', 'pt-BR').".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "source_lang".into(),
+            description: "Source language. Omit to auto-detect.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
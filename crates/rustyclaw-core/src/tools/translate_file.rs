@@ -0,0 +1,668 @@
+//! Document-aware translation of subtitle, Markdown and gettext files.
+//!
+//! `translate_file` reads a structured file, sends only its translatable
+//! text to the model, and writes a copy with everything else as it was:
+//!
+//! - `.srt` / `.vtt`: cue text is translated; indices, cue identifiers,
+//!   timings and WebVTT `NOTE`/`STYLE` blocks are copied unchanged.
+//! - `.md`: paragraphs are translated; fenced and indented code and YAML
+//!   front matter are copied, and the prompt keeps inline code and links.
+//! - `.po` / `.pot`: each untranslated entry's `msgid` (and
+//!   `msgid_plural`) is translated into its `msgstr` lines.  The header
+//!   and entries that already have a translation are copied.
+//!
+//! Segments go to the model in numbered batches, so a long file costs a
+//! handful of requests rather than one per cue.  As with
+//! [`translate`](super::translate), the gateway makes the model call and
+//! supplies it to [`translate_file_with`].
+
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, is_protected_path, resolve_path, sandbox};
+use super::translate::{self, split_fenced};
+
+/// Largest file accepted.
+const MAX_INPUT_BYTES: usize = 2 * 1024 * 1024;
+
+/// Most characters of source text in one request.
+pub const MAX_BATCH_CHARS: usize = 3000;
+
+/// Most segments in one request, however short.
+pub const MAX_BATCH_SEGMENTS: usize = 40;
+
+/// A file format `translate_file` understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Srt,
+    Vtt,
+    Markdown,
+    Po,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "srt" => Some(Self::Srt),
+            "vtt" | "webvtt" => Some(Self::Vtt),
+            "md" | "markdown" => Some(Self::Markdown),
+            "po" | "pot" | "gettext" => Some(Self::Po),
+            _ => None,
+        }
+    }
+
+    /// The format a file's extension names.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_name(path.extension()?.to_str()?)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Markdown => "md",
+            Self::Po => "po",
+        }
+    }
+}
+
+/// A piece of a parsed document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Piece {
+    /// Copied to the output unchanged.
+    Keep(String),
+    /// Text to translate, and how to write the translation back.
+    Text { source: String, render: Render },
+}
+
+/// How a translated [`Piece::Text`] is written out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Render {
+    /// As is, with line breaks as in the source.
+    Lines { crlf: bool },
+    /// As a PO string after `keyword` (`msgstr`, `msgstr[1]`, …), ending
+    /// the line.
+    Po { keyword: String, crlf: bool },
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+/// Sync stub for the static ToolDef — the gateway intercepts
+/// `translate_file` because it needs the configured model.
+pub fn exec_translate_file_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("translate_file is executed by the gateway (it uses the configured model)".into())
+}
+
+/// Translate the file at `args.path` using `call_model` for each batch,
+/// and write the result next to it (or to `args.output`).
+///
+/// Returns a JSON object with the output `path`, the `format`, and how
+/// many `segments` and model `requests` it took.
+#[instrument(skip(args, workspace_dir, call_model))]
+pub async fn translate_file_with<F, Fut>(
+    args: &Value,
+    workspace_dir: &Path,
+    mut call_model: F,
+) -> Result<String, String>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| "Missing required parameter: path".to_string())?;
+    let target_lang = args
+        .get("target_lang")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Missing required parameter: target_lang".to_string())?;
+    let source_lang = args
+        .get("source_lang")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    let input = checked_path(workspace_dir, path)?;
+    let format = match args.get("format").and_then(|v| v.as_str()) {
+        Some(name) => Format::from_name(name)
+            .ok_or_else(|| format!("Unknown format '{}': use srt, vtt, md or po", name))?,
+        None => Format::from_path(&input).ok_or_else(|| {
+            format!(
+                "Can't tell the format of {}: pass format (srt, vtt, md or po)",
+                input.display()
+            )
+        })?,
+    };
+    let output = match args.get("output").and_then(|v| v.as_str()) {
+        Some(out) if !out.trim().is_empty() => checked_path(workspace_dir, out)?,
+        _ => default_output(&input, target_lang),
+    };
+    if output == input {
+        return Err("output must differ from path; the source file is never overwritten".into());
+    }
+
+    let size = std::fs::metadata(&input)
+        .map_err(|e| format!("Failed to read {}: {}", input.display(), e))?
+        .len();
+    if size > MAX_INPUT_BYTES as u64 {
+        return Err(format!(
+            "{} is {} KB; translate_file accepts up to {} KB",
+            input.display(),
+            size / 1024,
+            MAX_INPUT_BYTES / 1024
+        ));
+    }
+    let text = std::fs::read_to_string(&input)
+        .map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+
+    let pieces = parse(format, &text);
+    let sources: Vec<&str> = pieces
+        .iter()
+        .filter_map(|p| match p {
+            Piece::Text { source, .. } => Some(source.as_str()),
+            Piece::Keep(_) => None,
+        })
+        .collect();
+
+    // Repeated lines (common in subtitles) are translated once.
+    let mut unique: Vec<&str> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for &source in &sources {
+        index.entry(source).or_insert_with(|| {
+            unique.push(source);
+            unique.len() - 1
+        });
+    }
+    debug!(
+        format = format.as_str(),
+        segments = sources.len(),
+        unique = unique.len(),
+        target_lang,
+        "Translating file"
+    );
+
+    let mut translated: Vec<Option<String>> = vec![None; unique.len()];
+    let mut requests = 0;
+    for batch in batches(&unique) {
+        let prompt = batch_prompt(&batch, target_lang, source_lang);
+        let reply = call_model(prompt).await?;
+        requests += 1;
+        let mut replies = parse_batch_reply(&reply);
+        for (id, source) in batch {
+            match replies.remove(&id) {
+                Some(text) => translated[id] = Some(text),
+                None => {
+                    // The model dropped or mangled this segment; ask again
+                    // for it alone.
+                    warn!(segment = id, "Batch reply is missing a segment");
+                    let prompt =
+                        translate::translation_prompt(source, target_lang, source_lang, false);
+                    let reply = call_model(prompt).await?;
+                    requests += 1;
+                    translated[id] = Some(translate::parse_reply(&reply, false).1);
+                }
+            }
+        }
+    }
+
+    let translations: Vec<String> = sources
+        .iter()
+        .map(|source| translated[index[source]].clone().unwrap_or_default())
+        .collect();
+    let rendered = render(&pieces, &translations);
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&output, rendered)
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+
+    Ok(json!({
+        "path": output.display().to_string(),
+        "source": input.display().to_string(),
+        "format": format.as_str(),
+        "target_lang": target_lang,
+        "segments": sources.len(),
+        "requests": requests,
+    })
+    .to_string())
+}
+
+/// `path` resolved against the workspace, if the agent may touch it.
+fn checked_path(workspace_dir: &Path, path: &str) -> Result<PathBuf, String> {
+    let path = resolve_path(workspace_dir, path);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted translate_file on protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+/// `<stem>.<lang>.<ext>` beside the input, e.g. `film.fr.srt`.
+fn default_output(input: &Path, target_lang: &str) -> PathBuf {
+    let lang: String = target_lang
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let stem = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match input.extension() {
+        Some(ext) => format!("{}.{}.{}", stem, lang, ext.to_string_lossy()),
+        None => format!("{}.{}", stem, lang),
+    };
+    input.with_file_name(name)
+}
+
+// ── Parsing ─────────────────────────────────────────────────────────────────
+
+/// Split `text` into the pieces to copy and the pieces to translate.
+pub fn parse(format: Format, text: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    match format {
+        Format::Srt | Format::Vtt => {
+            for_each_block(text, &mut pieces, cue_pieces);
+        }
+        Format::Markdown => markdown_pieces(text, &mut pieces),
+        Format::Po => for_each_block(text, &mut pieces, po_entry_pieces),
+    }
+    pieces
+}
+
+/// Call `f` with each run of non-blank lines; blank lines are kept.
+fn for_each_block(
+    text: &str,
+    pieces: &mut Vec<Piece>,
+    mut f: impl FnMut(&[&str], &mut Vec<Piece>),
+) {
+    let mut block: Vec<&str> = Vec::new();
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                f(&block, pieces);
+                block.clear();
+            }
+            keep(pieces, line);
+        } else {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        f(&block, pieces);
+    }
+}
+
+/// Append `text` to the output, merging with a preceding kept piece.
+fn keep(pieces: &mut Vec<Piece>, text: &str) {
+    if let Some(Piece::Keep(prev)) = pieces.last_mut() {
+        prev.push_str(text);
+    } else {
+        pieces.push(Piece::Keep(text.to_string()));
+    }
+}
+
+/// Lines as one translatable piece; the last line ending is kept apart
+/// so the translation doesn't have to reproduce it.
+fn text_lines(lines: &[&str], pieces: &mut Vec<Piece>) {
+    let joined: String = lines.concat();
+    let body = joined.trim_end_matches(['\r', '\n']);
+    let crlf = joined.contains("\r\n");
+    pieces.push(Piece::Text {
+        source: body.replace("\r\n", "\n"),
+        render: Render::Lines { crlf },
+    });
+    keep(pieces, &joined[body.len()..]);
+}
+
+/// A subtitle cue: everything through the timing line is kept, the rest
+/// is text.  Blocks without a timing line (`WEBVTT`, `NOTE`, `STYLE`) are
+/// kept whole.
+fn cue_pieces(block: &[&str], pieces: &mut Vec<Piece>) {
+    match block.iter().position(|line| line.contains("-->")) {
+        Some(timing) if timing + 1 < block.len() => {
+            keep(pieces, &block[..=timing].concat());
+            text_lines(&block[timing + 1..], pieces);
+        }
+        _ => keep(pieces, &block.concat()),
+    }
+}
+
+fn markdown_pieces(text: &str, pieces: &mut Vec<Piece>) {
+    let body = match front_matter_len(text) {
+        Some(len) => {
+            keep(pieces, &text[..len]);
+            &text[len..]
+        }
+        None => text,
+    };
+    for segment in split_fenced(body) {
+        match segment {
+            translate::Segment::Code(code) => keep(pieces, &code),
+            translate::Segment::Prose(prose) => {
+                for_each_block(&prose, pieces, |block, pieces| {
+                    let indented = block
+                        .iter()
+                        .all(|line| line.starts_with("    ") || line.starts_with('\t'));
+                    if indented {
+                        keep(pieces, &block.concat());
+                    } else {
+                        text_lines(block, pieces);
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Length of a leading `---` YAML front matter block, closing line included.
+fn front_matter_len(text: &str) -> Option<usize> {
+    let mut lines = text.split_inclusive('\n');
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+    let mut len = text.split_inclusive('\n').next()?.len();
+    for line in lines {
+        len += line.len();
+        if matches!(line.trim_end(), "---" | "...") {
+            return Some(len);
+        }
+    }
+    None
+}
+
+/// One field of a PO entry: its keyword and unescaped string.
+struct PoField {
+    keyword: String,
+    value: String,
+    /// Index of the field's first line in the entry.
+    line: usize,
+}
+
+/// A PO entry: untranslated ones get a text piece per `msgstr` line,
+/// everything else is kept.
+fn po_entry_pieces(block: &[&str], pieces: &mut Vec<Piece>) {
+    let fields = po_fields(block);
+    let field = |name: &str| fields.iter().find(|f| f.keyword == name);
+    let msgstrs: Vec<&PoField> = fields
+        .iter()
+        .filter(|f| f.keyword.starts_with("msgstr"))
+        .collect();
+
+    let untranslated = match field("msgid") {
+        // An empty msgid is the catalog header.
+        Some(msgid) => !msgid.value.is_empty() && msgstrs.iter().all(|f| f.value.is_empty()),
+        None => false,
+    };
+    let Some(first) = msgstrs.first().filter(|_| untranslated) else {
+        keep(pieces, &block.concat());
+        return;
+    };
+
+    let msgid = field("msgid").map(|f| f.value.clone()).unwrap_or_default();
+    let plural = field("msgid_plural").map(|f| f.value.clone());
+    let crlf = block.concat().contains("\r\n");
+    keep(pieces, &block[..first.line].concat());
+    for msgstr in msgstrs {
+        // msgstr[0] is the singular; every other form takes the plural.
+        let source = match &plural {
+            Some(plural) if msgstr.keyword != "msgstr[0]" => plural.clone(),
+            _ => msgid.clone(),
+        };
+        pieces.push(Piece::Text {
+            source,
+            render: Render::Po {
+                keyword: msgstr.keyword.clone(),
+                crlf,
+            },
+        });
+    }
+}
+
+fn po_fields(block: &[&str]) -> Vec<PoField> {
+    let mut fields: Vec<PoField> = Vec::new();
+    for (idx, line) in block.iter().enumerate() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('"') {
+            if let Some(field) = fields.last_mut() {
+                field.value.push_str(&po_unquote(line));
+            }
+            continue;
+        }
+        if let Some((keyword, rest)) = line.split_once(char::is_whitespace) {
+            fields.push(PoField {
+                keyword: keyword.to_string(),
+                value: po_unquote(rest.trim()),
+                line: idx,
+            });
+        }
+    }
+    fields
+}
+
+/// The contents of a quoted PO string, unescaped.
+fn po_unquote(quoted: &str) -> String {
+    let inner = quoted
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(quoted);
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn po_quote(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// ── Rendering ───────────────────────────────────────────────────────────────
+
+/// Reassemble the document, with `translations` in the place of each
+/// text piece, in order.
+pub fn render(pieces: &[Piece], translations: &[String]) -> String {
+    let mut out = String::new();
+    let mut translations = translations.iter();
+    for piece in pieces {
+        match piece {
+            Piece::Keep(text) => out.push_str(text),
+            Piece::Text { source, render } => {
+                let text = translations
+                    .next()
+                    .map(String::as_str)
+                    .unwrap_or(source.as_str());
+                match render {
+                    Render::Lines { crlf } => {
+                        let text = text.trim_matches(['\r', '\n']).replace("\r\n", "\n");
+                        out.push_str(&with_line_endings(&text, *crlf));
+                    }
+                    Render::Po { keyword, crlf } => {
+                        out.push_str(&with_line_endings(&po_field(keyword, source, text), *crlf));
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// A `msgstr` field for `text`, ending in a newline exactly when the
+/// `msgid` does, as gettext requires.
+fn po_field(keyword: &str, source: &str, text: &str) -> String {
+    let mut text = text.trim_matches(['\r', '\n']).to_string();
+    if source.ends_with('\n') {
+        text.push('\n');
+    }
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    if lines.len() <= 1 {
+        return format!("{} {}\n", keyword, po_quote(&text));
+    }
+    let mut out = format!("{} \"\"\n", keyword);
+    for line in lines {
+        out.push_str(&po_quote(line));
+        out.push('\n');
+    }
+    out
+}
+
+fn with_line_endings(text: &str, crlf: bool) -> String {
+    if crlf {
+        text.replace('\n', "\r\n")
+    } else {
+        text.to_string()
+    }
+}
+
+// ── Batching ────────────────────────────────────────────────────────────────
+
+/// Group segments into requests of at most [`MAX_BATCH_CHARS`] and
+/// [`MAX_BATCH_SEGMENTS`].  A longer segment goes alone.
+pub fn batches<'a>(segments: &[&'a str]) -> Vec<Vec<(usize, &'a str)>> {
+    let mut batches: Vec<Vec<(usize, &str)>> = Vec::new();
+    let mut current: Vec<(usize, &str)> = Vec::new();
+    let mut chars = 0;
+    for (id, &segment) in segments.iter().enumerate() {
+        let full = current.len() >= MAX_BATCH_SEGMENTS || chars + segment.len() > MAX_BATCH_CHARS;
+        if !current.is_empty() && full {
+            batches.push(std::mem::take(&mut current));
+            chars = 0;
+        }
+        current.push((id, segment));
+        chars += segment.len();
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+/// Build the prompt for one batch of numbered segments.
+pub fn batch_prompt(
+    batch: &[(usize, &str)],
+    target_lang: &str,
+    source_lang: Option<&str>,
+) -> String {
+    let from = source_lang
+        .map(|s| format!(" from {}", s))
+        .unwrap_or_default();
+    let mut segments = String::new();
+    for (id, text) in batch {
+        segments.push_str(&format!("<seg id=\"{}\">\n{}\n</seg>\n", id, text));
+    }
+    format!(
+        "Translate the text of each <seg> element below{from} into {target_lang}.\n\
+         Rules:\n\
+         - Reply with every <seg id=\"…\"> element, in the same order, each holding only \
+         its translation. Nothing else: no commentary or notes.\n\
+         - Keep the line breaks inside a segment; subtitle lines stay on separate lines.\n\
+         - Preserve Markdown, markup such as <i>, inline `code`, URLs, and placeholders \
+         like %s, %(name)s or {{name}} exactly.\n\
+         \n\
+         {segments}"
+    )
+}
+
+/// The translations in a batch reply, by segment id.
+pub fn parse_batch_reply(reply: &str) -> HashMap<usize, String> {
+    let mut out = HashMap::new();
+    let mut rest = reply;
+    while let Some(start) = rest.find("<seg id=\"") {
+        rest = &rest[start + "<seg id=\"".len()..];
+        let Some((id, after)) = rest.split_once("\">") else {
+            break;
+        };
+        let Some((text, after)) = after.split_once("</seg>") else {
+            break;
+        };
+        if let Ok(id) = id.trim().parse::<usize>() {
+            out.insert(id, text.trim_matches(['\r', '\n']).to_string());
+        }
+        rest = after;
+    }
+    out
+}
+
+// ── Parameter definitions ───────────────────────────────────────────────────
+
+pub fn translate_file_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "path".into(),
+            description: "File to translate: .srt or .vtt subtitles, Markdown (.md), or a \
+                          gettext catalog (.po/.pot)."
+                .into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "target_lang".into(),
+            description: "Language to translate into (e.g. 'French', 'ja', 'pt-BR').".into(),
+            param_type: "string".into(),
+            required: true,
+        },
+        ToolParam {
+            name: "source_lang".into(),
+            description: "Source language. Omit to let the model infer it.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "output".into(),
+            description: "Where to write the translation. Default: beside the source as \
+                          <name>.<target_lang>.<ext>, e.g. film.fr.srt."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "format".into(),
+            description: "'srt', 'vtt', 'md' or 'po', when the extension doesn't say.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -0,0 +1,771 @@
+//! Validate tool: check a JSON, YAML or TOML document, optionally against
+//! a JSON Schema.
+//!
+//! A syntax error is reported with the parser's line and column.  With a
+//! schema, the document is checked against it and every violation is
+//! reported with the JSON Pointer of the failing value (`/server/port`)
+//! and, where the key can be found in the text, its line and column.  All
+//! three formats produce the same error shape.
+//!
+//! The schema support covers the keywords config files lean on: `type`,
+//! `enum`, `const`, the string, number, array and object bounds,
+//! `properties`/`required`/`additionalProperties`/`patternProperties`,
+//! `items`/`prefixItems`, `allOf`/`anyOf`/`oneOf`/`not`, `if`/`then`/`else`,
+//! and local `$ref`s (`#/$defs/…`, `#/definitions/…`).  Annotations such as
+//! `format` and `description` are ignored.
+
+use regex::Regex;
+use serde_json::{Value, json};
+use std::io::Read;
+use std::path::Path;
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{
+    VAULT_ACCESS_DENIED, is_protected_path, open_file_read_safe, resolve_path, sandbox,
+};
+
+/// Bytes read from a document or schema file.
+const MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Errors returned unless `max_errors` says otherwise.
+const DEFAULT_MAX_ERRORS: usize = 20;
+
+/// Deepest chain of `$ref`s and nested subschemas followed.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+        }
+    }
+}
+
+/// One problem with a document, in the shape the tool returns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// `"syntax"` or `"schema"`.
+    pub kind: &'static str,
+    pub message: String,
+    /// JSON Pointer of the failing value; empty for the whole document.
+    pub path: String,
+    /// 1-based line and column, when known.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl Problem {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind,
+            "path": self.path,
+            "line": self.line,
+            "column": self.column,
+            "message": self.message,
+        })
+    }
+}
+
+// ── Tool executor ───────────────────────────────────────────────────────────
+
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_validate(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let path_arg = args.get("path").and_then(|v| v.as_str());
+    let (text, format) = match (path_arg, args.get("content").and_then(|v| v.as_str())) {
+        (Some(path_str), None) => {
+            let path = checked_path(workspace_dir, path_str)?;
+            let format = match args.get("format").and_then(|v| v.as_str()) {
+                Some(name) => parse_format(name)?,
+                None => path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .and_then(Format::parse)
+                    .ok_or_else(|| {
+                        format!(
+                            "Can't tell the format of '{}' from its extension; pass \
+                             format ('json', 'yaml' or 'toml')",
+                            path_str
+                        )
+                    })?,
+            };
+            (read_capped(&path)?, format)
+        }
+        (None, Some(content)) => {
+            let name = args
+                .get("format")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing required parameter: format (needed with content)")?;
+            (content.to_string(), parse_format(name)?)
+        }
+        _ => return Err("Give exactly one of 'path' or 'content'".to_string()),
+    };
+    let schema = match args.get("schema") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(s)) if s.trim_start().starts_with('{') => Some(
+            serde_json::from_str::<Value>(s)
+                .map_err(|e| format!("Invalid inline schema: {}", e))?,
+        ),
+        Some(Value::String(s)) => Some(load_schema(workspace_dir, s)?),
+        Some(inline @ (Value::Object(_) | Value::Bool(_))) => Some(inline.clone()),
+        Some(_) => return Err("schema must be a JSON Schema object or a file path".to_string()),
+    };
+    let max_errors = args
+        .get("max_errors")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_MAX_ERRORS, |n| (n as usize).max(1));
+    debug!(
+        format = format.name(),
+        schema = schema.is_some(),
+        "Validating document"
+    );
+
+    let problems = validate_text(&text, format, schema.as_ref(), max_errors + 1)?;
+    let truncated = problems.len() > max_errors;
+    let errors: Vec<Value> = problems
+        .iter()
+        .take(max_errors)
+        .map(Problem::to_json)
+        .collect();
+    let out = json!({
+        "valid": problems.is_empty(),
+        "format": format.name(),
+        "schema": schema.is_some(),
+        "errors": errors,
+        "truncated": truncated,
+    });
+    serde_json::to_string_pretty(&out).map_err(|e| e.to_string())
+}
+
+fn parse_format(name: &str) -> Result<Format, String> {
+    Format::parse(name)
+        .ok_or_else(|| format!("Unknown format: '{}'. Use 'json', 'yaml' or 'toml'", name))
+}
+
+fn checked_path(workspace_dir: &Path, path_str: &str) -> Result<std::path::PathBuf, String> {
+    let path = resolve_path(workspace_dir, path_str);
+    if is_protected_path(&path) {
+        warn!(path = %path.display(), "Attempted validate access to protected path");
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sb) = sandbox() {
+        sb.check_path(&path)?;
+    }
+    Ok(path)
+}
+
+fn read_capped(path: &Path) -> Result<String, String> {
+    let (file, _) = open_file_read_safe(path)
+        .map_err(|e| format!("Failed to open file '{}': {}", path.display(), e))?;
+    let mut bytes = Vec::new();
+    file.take(MAX_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read file '{}': {}", path.display(), e))?;
+    String::from_utf8(bytes).map_err(|_| format!("'{}' is not UTF-8 text", path.display()))
+}
+
+/// A schema file, itself JSON, YAML or TOML by extension (JSON otherwise).
+fn load_schema(workspace_dir: &Path, path_str: &str) -> Result<Value, String> {
+    let path = checked_path(workspace_dir, path_str)?;
+    let text = read_capped(&path)?;
+    let format = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(Format::parse)
+        .unwrap_or(Format::Json);
+    parse_document(&text, format)
+        .map_err(|p| format!("Invalid schema file '{}': {}", path_str, p.message))
+}
+
+// ── Parsing ─────────────────────────────────────────────────────────────────
+
+/// Check `text` and, with a schema, its content.  Returns at most `limit`
+/// problems; an empty list means the document is valid.
+pub fn validate_text(
+    text: &str,
+    format: Format,
+    schema: Option<&Value>,
+    limit: usize,
+) -> Result<Vec<Problem>, String> {
+    let document = match parse_document(text, format) {
+        Ok(document) => document,
+        Err(problem) => return Ok(vec![problem]),
+    };
+    let Some(schema) = schema else {
+        return Ok(Vec::new());
+    };
+    let mut checker = SchemaCheck {
+        root: schema,
+        violations: Vec::new(),
+        limit,
+    };
+    checker.check(schema, &document, &mut Vec::new(), 0)?;
+    Ok(checker
+        .violations
+        .into_iter()
+        .map(|(segments, message)| {
+            let (line, column) = locate(text, format, &segments).unzip();
+            Problem {
+                kind: "schema",
+                message,
+                path: pointer(&segments),
+                line,
+                column,
+            }
+        })
+        .collect())
+}
+
+/// Parse a document into JSON values, or describe the syntax error.
+fn parse_document(text: &str, format: Format) -> Result<Value, Problem> {
+    let syntax = |message: String, at: Option<(usize, usize)>| Problem {
+        kind: "syntax",
+        message,
+        path: String::new(),
+        line: at.map(|(line, _)| line),
+        column: at.map(|(_, column)| column),
+    };
+    match format {
+        Format::Json => serde_json::from_str(text)
+            .map_err(|e| syntax(strip_location(&e.to_string()), Some((e.line(), e.column())))),
+        Format::Yaml => {
+            let yaml: serde_yaml::Value = serde_yaml::from_str(text).map_err(|e| {
+                let at = e.location().map(|l| (l.line(), l.column()));
+                syntax(strip_location(&e.to_string()), at)
+            })?;
+            serde_json::to_value(&yaml)
+                .map_err(|e| syntax(format!("YAML can't be checked as JSON data: {}", e), None))
+        }
+        Format::Toml => {
+            let table: toml::Table = toml::from_str(text).map_err(|e| {
+                let at = e.span().map(|span| line_column(text, span.start));
+                syntax(e.message().to_string(), at)
+            })?;
+            Ok(toml_to_json(toml::Value::Table(table)))
+        }
+    }
+}
+
+/// Parser messages end with " at line L column C"; the location is reported
+/// in its own fields.
+fn strip_location(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(i) => message[..i].to_string(),
+        None => message.to_string(),
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => json!(i),
+        toml::Value::Float(f) => json!(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+// ── Locations ───────────────────────────────────────────────────────────────
+
+/// `segments` as a JSON Pointer.
+fn pointer(segments: &[String]) -> String {
+    segments
+        .iter()
+        .map(|s| format!("/{}", s.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// 1-based line and column of byte offset `offset`.
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Where the value at `segments` is written: the position of its key,
+/// found by looking for each key in turn after the previous one.  Array
+/// indices aren't searched for, so an item is placed at its array's key.
+/// `None` for the whole document, or if the first key isn't found.
+fn locate(text: &str, format: Format, segments: &[String]) -> Option<(usize, usize)> {
+    let mut found = None;
+    let mut from = 0;
+    for segment in segments {
+        if segment.parse::<usize>().is_ok() {
+            continue;
+        }
+        let key = regex::escape(segment);
+        let pattern = match format {
+            Format::Json => format!(r#"("{}")\s*:"#, key),
+            Format::Yaml => format!(r#"(?m)^[ \t]*(?:-[ \t]+)?(["']?{}["']?)[ \t]*:"#, key),
+            Format::Toml => format!(
+                r#"(?m)^[ \t]*(?:\[{{1,2}}(?:[^\]\n]*\.)?(["']?{k}["']?)\]|(?:[^=\n]*\.)?(["']?{k}["']?)[ \t]*=)"#,
+                k = key
+            ),
+        };
+        let Ok(re) = Regex::new(&pattern) else {
+            break;
+        };
+        let Some(key_match) = re
+            .captures(&text[from..])
+            .and_then(|caps| caps.get(1).or_else(|| caps.get(2)))
+        else {
+            break;
+        };
+        let at = from + key_match.start();
+        found = Some(at);
+        from += key_match.end();
+    }
+    found.map(|offset| line_column(text, offset))
+}
+
+// ── Schema checking ─────────────────────────────────────────────────────────
+
+struct SchemaCheck<'a> {
+    root: &'a Value,
+    /// Path segments and message of each violation found.
+    violations: Vec<(Vec<String>, String)>,
+    limit: usize,
+}
+
+impl SchemaCheck<'_> {
+    fn report(&mut self, path: &[String], message: String) {
+        if self.violations.len() < self.limit {
+            self.violations.push((path.to_vec(), message));
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.violations.len() >= self.limit
+    }
+
+    /// Whether `instance` passes `schema`, without reporting anything.
+    fn passes(&self, schema: &Value, instance: &Value, depth: usize) -> Result<bool, String> {
+        let mut probe = SchemaCheck {
+            root: self.root,
+            violations: Vec::new(),
+            limit: 1,
+        };
+        probe.check(schema, instance, &mut Vec::new(), depth)?;
+        Ok(probe.violations.is_empty())
+    }
+
+    /// Check `instance` (at `path`) against `schema`.  Errors are problems
+    /// with the schema itself.
+    fn check(
+        &mut self,
+        schema: &Value,
+        instance: &Value,
+        path: &mut Vec<String>,
+        depth: usize,
+    ) -> Result<(), String> {
+        if depth > MAX_DEPTH {
+            return Err("Schema nests too deeply (a $ref cycle?)".to_string());
+        }
+        let schema = match schema {
+            Value::Bool(true) => return Ok(()),
+            Value::Bool(false) => {
+                self.report(path, "no value is allowed here".to_string());
+                return Ok(());
+            }
+            Value::Object(map) => map,
+            _ => return Err("A schema must be an object or a boolean".to_string()),
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(|v| v.as_str()) {
+            let target = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+                .ok_or_else(|| {
+                    format!(
+                        "Unsupported or missing $ref '{}' (only local '#/…' refs are followed)",
+                        reference
+                    )
+                })?;
+            self.check(target, instance, path, depth + 1)?;
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let names: Vec<&str> = match expected {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(|v| v.as_str()).collect(),
+                _ => Vec::new(),
+            };
+            if !names.is_empty() && !names.iter().any(|name| has_type(instance, name)) {
+                self.report(
+                    path,
+                    format!(
+                        "expected {}, found {}",
+                        names.join(" or "),
+                        type_name(instance)
+                    ),
+                );
+                // The other keywords would only restate the mismatch.
+                return Ok(());
+            }
+        }
+        if let Some(allowed) = schema.get("enum").and_then(|v| v.as_array())
+            && !allowed.contains(instance)
+        {
+            let list: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+            self.report(path, format!("must be one of {}", list.join(", ")));
+        }
+        if let Some(expected) = schema.get("const")
+            && expected != instance
+        {
+            self.report(path, format!("must be {}", expected));
+        }
+
+        match instance {
+            Value::String(s) => self.check_string(schema, s, path)?,
+            Value::Number(n) => self.check_number(schema, n.as_f64().unwrap_or(0.0), path),
+            Value::Array(items) => self.check_array(schema, items, path, depth)?,
+            Value::Object(object) => self.check_object(schema, object, path, depth)?,
+            _ => {}
+        }
+
+        if let Some(all) = schema.get("allOf").and_then(|v| v.as_array()) {
+            for sub in all {
+                self.check(sub, instance, path, depth + 1)?;
+            }
+        }
+        if let Some(any) = schema.get("anyOf").and_then(|v| v.as_array()) {
+            let mut matched = false;
+            for sub in any {
+                if self.passes(sub, instance, depth + 1)? {
+                    matched = true;
+                    break;
+                }
+            }
+            if !matched {
+                self.report(
+                    path,
+                    "does not match any of the allowed schemas (anyOf)".into(),
+                );
+            }
+        }
+        if let Some(one) = schema.get("oneOf").and_then(|v| v.as_array()) {
+            let mut matches = 0;
+            for sub in one {
+                if self.passes(sub, instance, depth + 1)? {
+                    matches += 1;
+                }
+            }
+            if matches != 1 {
+                self.report(
+                    path,
+                    format!(
+                        "must match exactly one schema in oneOf, matches {}",
+                        matches
+                    ),
+                );
+            }
+        }
+        if let Some(not) = schema.get("not")
+            && self.passes(not, instance, depth + 1)?
+        {
+            self.report(path, "matches a schema it must not match (not)".into());
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.passes(condition, instance, depth + 1)? {
+                schema.get("then")
+            } else {
+                schema.get("else")
+            };
+            if let Some(branch) = branch {
+                self.check(branch, instance, path, depth + 1)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_string(
+        &mut self,
+        schema: &serde_json::Map<String, Value>,
+        s: &str,
+        path: &[String],
+    ) -> Result<(), String> {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(|v| v.as_u64())
+            && len < min
+        {
+            self.report(path, format!("must be at least {} characters long", min));
+        }
+        if let Some(max) = schema.get("maxLength").and_then(|v| v.as_u64())
+            && len > max
+        {
+            self.report(path, format!("must be at most {} characters long", max));
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(|v| v.as_str()) {
+            let re = Regex::new(pattern)
+                .map_err(|e| format!("Invalid pattern '{}' in schema: {}", pattern, e))?;
+            if !re.is_match(s) {
+                self.report(path, format!("must match the pattern '{}'", pattern));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_number(&mut self, schema: &serde_json::Map<String, Value>, n: f64, path: &[String]) {
+        let bound = |key: &str| schema.get(key).and_then(|v| v.as_f64());
+        if let Some(min) = bound("minimum")
+            && n < min
+        {
+            self.report(path, format!("must be at least {}", min));
+        }
+        if let Some(max) = bound("maximum")
+            && n > max
+        {
+            self.report(path, format!("must be at most {}", max));
+        }
+        if let Some(min) = bound("exclusiveMinimum")
+            && n <= min
+        {
+            self.report(path, format!("must be greater than {}", min));
+        }
+        if let Some(max) = bound("exclusiveMaximum")
+            && n >= max
+        {
+            self.report(path, format!("must be less than {}", max));
+        }
+        if let Some(step) = bound("multipleOf")
+            && step > 0.0
+            && ((n / step) - (n / step).round()).abs() > 1e-9
+        {
+            self.report(path, format!("must be a multiple of {}", step));
+        }
+    }
+
+    fn check_array(
+        &mut self,
+        schema: &serde_json::Map<String, Value>,
+        items: &[Value],
+        path: &mut Vec<String>,
+        depth: usize,
+    ) -> Result<(), String> {
+        let count = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64())
+            && count < min
+        {
+            self.report(path, format!("must have at least {} items", min));
+        }
+        if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64())
+            && count > max
+        {
+            self.report(path, format!("must have at most {} items", max));
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            for (i, item) in items.iter().enumerate() {
+                if items[..i].contains(item) {
+                    self.report(path, format!("items must be unique; item {} repeats", i));
+                    break;
+                }
+            }
+        }
+
+        // `prefixItems` (or the older array form of `items`) covers the
+        // first items; a schema-valued `items` covers the rest.
+        let prefix = schema
+            .get("prefixItems")
+            .or_else(|| schema.get("items").filter(|v| v.is_array()))
+            .and_then(|v| v.as_array())
+            .map_or(&[][..], |v| v.as_slice());
+        let rest = schema.get("items").filter(|v| !v.is_array());
+        for (i, item) in items.iter().enumerate() {
+            if self.full() {
+                break;
+            }
+            let sub = match prefix.get(i) {
+                Some(sub) => sub,
+                None => match rest {
+                    Some(sub) => sub,
+                    None => continue,
+                },
+            };
+            path.push(i.to_string());
+            let result = self.check(sub, item, path, depth + 1);
+            path.pop();
+            result?;
+        }
+        Ok(())
+    }
+
+    fn check_object(
+        &mut self,
+        schema: &serde_json::Map<String, Value>,
+        object: &serde_json::Map<String, Value>,
+        path: &mut Vec<String>,
+        depth: usize,
+    ) -> Result<(), String> {
+        let count = object.len() as u64;
+        if let Some(min) = schema.get("minProperties").and_then(|v| v.as_u64())
+            && count < min
+        {
+            self.report(path, format!("must have at least {} properties", min));
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(|v| v.as_u64())
+            && count > max
+        {
+            self.report(path, format!("must have at most {} properties", max));
+        }
+        for name in schema
+            .get("required")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+        {
+            if !object.contains_key(name) {
+                self.report(path, format!("missing required property '{}'", name));
+            }
+        }
+
+        let properties = schema.get("properties").and_then(|v| v.as_object());
+        let patterns: Vec<(Regex, &Value)> = schema
+            .get("patternProperties")
+            .and_then(|v| v.as_object())
+            .into_iter()
+            .flatten()
+            .map(|(pattern, sub)| {
+                Regex::new(pattern)
+                    .map(|re| (re, sub))
+                    .map_err(|e| format!("Invalid patternProperties key '{}': {}", pattern, e))
+            })
+            .collect::<Result<_, _>>()?;
+        let additional = schema.get("additionalProperties");
+
+        for (name, value) in object {
+            if self.full() {
+                break;
+            }
+            let mut subs: Vec<&Value> = Vec::new();
+            if let Some(sub) = properties.and_then(|p| p.get(name)) {
+                subs.push(sub);
+            }
+            subs.extend(
+                patterns
+                    .iter()
+                    .filter(|(re, _)| re.is_match(name))
+                    .map(|(_, sub)| *sub),
+            );
+            if subs.is_empty() {
+                match additional {
+                    Some(Value::Bool(false)) => {
+                        path.push(name.clone());
+                        self.report(path, format!("property '{}' is not allowed", name));
+                        path.pop();
+                        continue;
+                    }
+                    Some(sub) => subs.push(sub),
+                    None => continue,
+                }
+            }
+            path.push(name.clone());
+            let mut result = Ok(());
+            for sub in subs {
+                result = self.check(sub, value, path, depth + 1);
+                if result.is_err() {
+                    break;
+                }
+            }
+            path.pop();
+            result?;
+        }
+        Ok(())
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn validate_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "path".into(),
+            description: "Document to check. The format comes from the extension (.json, \
+                          .yaml/.yml, .toml) unless format is given. Give this or content."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "content".into(),
+            description: "Document text to check instead of a file; needs format.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "format".into(),
+            description: "'json', 'yaml' or 'toml'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "schema".into(),
+            description: "JSON Schema to check the document against: a path to a schema \
+                          file, or the schema itself as a JSON object or string."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "max_errors".into(),
+            description: "Most errors to report. Default: 20.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]
+}
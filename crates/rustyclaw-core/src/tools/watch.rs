@@ -0,0 +1,374 @@
+//! File watch tools: `watch_file` and `watch_poll`.
+//!
+//! `watch_file` registers a path or glob inside the workspace and returns a
+//! watch id; `watch_poll` returns the created/modified/deleted events seen
+//! since the previous poll.  Watches live in the gateway process: at most
+//! [`MAX_WATCHES`] at a time, dropped when the client disconnects or after
+//! [`WATCH_IDLE_SECS`] without a poll.
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{VAULT_ACCESS_DENIED, display_path, is_protected_path, resolve_path};
+
+/// Most watches active at once.
+pub const MAX_WATCHES: usize = 16;
+
+/// A watch that has not been polled for this long is removed.
+pub const WATCH_IDLE_SECS: u64 = 30 * 60;
+
+/// Events buffered per watch between polls; older ones are dropped.
+const MAX_EVENTS: usize = 500;
+
+/// What happened to a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl ChangeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Modified => "modified",
+            ChangeKind::Deleted => "deleted",
+        }
+    }
+}
+
+/// Events received for one watch since its last poll.
+#[derive(Default)]
+struct EventBuffer {
+    events: Vec<(ChangeKind, PathBuf)>,
+    dropped: usize,
+}
+
+/// Which paths under the watched directory are reported.
+enum Filter {
+    /// Everything (a directory was watched).
+    All,
+    /// One path (a file was watched, through its parent directory so that
+    /// editors that save by renaming are still seen).
+    Exact(PathBuf),
+    Glob(glob::Pattern),
+}
+
+impl Filter {
+    fn matches(&self, path: &Path) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Exact(target) => path == target,
+            Filter::Glob(pattern) => pattern.matches_path(path),
+        }
+    }
+}
+
+struct Watch {
+    /// What the agent asked for, as given.
+    target: String,
+    buffer: Arc<Mutex<EventBuffer>>,
+    last_polled: Instant,
+    /// Dropping the watcher stops the OS watch.
+    _watcher: RecommendedWatcher,
+}
+
+static WATCHES: Mutex<BTreeMap<String, Watch>> = Mutex::new(BTreeMap::new());
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Remove every watch.  Called by the gateway when a client disconnects.
+pub fn clear_watches() {
+    if let Ok(mut watches) = WATCHES.lock() {
+        watches.clear();
+    }
+}
+
+fn expire_idle(watches: &mut BTreeMap<String, Watch>) {
+    let idle = Duration::from_secs(WATCH_IDLE_SECS);
+    watches.retain(|id, w| {
+        let keep = w.last_polled.elapsed() < idle;
+        if !keep {
+            debug!(watch_id = %id, "Expiring idle file watch");
+        }
+        keep
+    });
+}
+
+// ── Tool executors ──────────────────────────────────────────────────────────
+
+/// Start watching a path or glob.
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_watch_file(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let target = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| "Missing required parameter: path".to_string())?;
+
+    let (root, mode, filter) = watch_plan(workspace_dir, target)?;
+
+    let mut watches = WATCHES
+        .lock()
+        .map_err(|_| "Watch registry is unavailable")?;
+    expire_idle(&mut watches);
+    if watches.len() >= MAX_WATCHES {
+        return Err(format!(
+            "Too many active watches ({}). Stop one with watch_poll and stop = true.",
+            MAX_WATCHES
+        ));
+    }
+
+    let buffer = Arc::new(Mutex::new(EventBuffer::default()));
+    let sink = buffer.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) => record_event(&sink, &filter, event),
+        Err(e) => warn!(error = %e, "File watch error"),
+    })
+    .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+    watcher
+        .watch(&root, mode)
+        .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+
+    let id = format!("watch-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    debug!(watch_id = %id, root = %root.display(), "Watching");
+    watches.insert(
+        id.clone(),
+        Watch {
+            target: target.to_string(),
+            buffer,
+            last_polled: Instant::now(),
+            _watcher: watcher,
+        },
+    );
+
+    Ok(json!({
+        "watch_id": id,
+        "path": target,
+        "expires_after_idle_secs": WATCH_IDLE_SECS,
+    })
+    .to_string())
+}
+
+/// Return (and clear) the events seen since the last poll.
+#[instrument(skip(args, workspace_dir))]
+pub fn exec_watch_poll(args: &Value, workspace_dir: &Path) -> Result<String, String> {
+    let watch_id = args.get("watch_id").and_then(|v| v.as_str());
+    let stop = args.get("stop").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let mut watches = WATCHES
+        .lock()
+        .map_err(|_| "Watch registry is unavailable")?;
+    expire_idle(&mut watches);
+    let ids: Vec<String> = match watch_id {
+        Some(id) if watches.contains_key(id) => vec![id.to_string()],
+        Some(id) => {
+            return Err(format!(
+                "Unknown watch '{}' (watches expire after {} minutes without a poll)",
+                id,
+                WATCH_IDLE_SECS / 60
+            ));
+        }
+        None => watches.keys().cloned().collect(),
+    };
+
+    let mut results = Vec::new();
+    for id in ids {
+        let Some(watch) = watches.get_mut(&id) else {
+            continue;
+        };
+        watch.last_polled = Instant::now();
+        let taken = match watch.buffer.lock() {
+            Ok(mut buf) => std::mem::take(&mut *buf),
+            Err(_) => EventBuffer::default(),
+        };
+        let events: Vec<Value> = taken
+            .events
+            .iter()
+            .map(|(kind, path)| {
+                json!({
+                    "kind": kind.as_str(),
+                    "path": display_path(path, workspace_dir),
+                })
+            })
+            .collect();
+        let mut entry = json!({
+            "watch_id": id,
+            "path": watch.target,
+            "events": events,
+        });
+        if taken.dropped > 0 {
+            entry["dropped"] = json!(taken.dropped);
+        }
+        if stop {
+            watches.remove(&id);
+            entry["stopped"] = json!(true);
+        }
+        results.push(entry);
+    }
+
+    Ok(json!({ "watches": results }).to_string())
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// The directory to watch, how, and which of its events to report.
+fn watch_plan(
+    workspace_dir: &Path,
+    target: &str,
+) -> Result<(PathBuf, RecursiveMode, Filter), String> {
+    let full = resolve_path(workspace_dir, target);
+    if full.components().any(|c| c == Component::ParentDir) {
+        return Err("Watch paths may not contain '..'".to_string());
+    }
+
+    let (root, mode, filter) = if is_glob(target) {
+        let pattern = glob::Pattern::new(&full.to_string_lossy())
+            .map_err(|e| format!("Invalid glob '{}': {}", target, e))?;
+        let root: PathBuf = full
+            .components()
+            .take_while(|c| !is_glob(&c.as_os_str().to_string_lossy()))
+            .collect();
+        (root, RecursiveMode::Recursive, Filter::Glob(pattern))
+    } else if full.is_dir() {
+        (full.clone(), RecursiveMode::Recursive, Filter::All)
+    } else {
+        // A file, or a path that does not exist yet: watch its directory.
+        let parent = full
+            .parent()
+            .ok_or_else(|| format!("Cannot watch {}", full.display()))?
+            .to_path_buf();
+        (
+            parent,
+            RecursiveMode::NonRecursive,
+            Filter::Exact(full.clone()),
+        )
+    };
+
+    if !root.is_dir() {
+        return Err(format!("Directory not found: {}", root.display()));
+    }
+    let canonical = root
+        .canonicalize()
+        .map_err(|e| format!("Cannot resolve {}: {}", root.display(), e))?;
+    let workspace = workspace_dir
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_dir.to_path_buf());
+    if !canonical.starts_with(&workspace) {
+        return Err(format!(
+            "Can only watch paths inside the workspace ({})",
+            workspace_dir.display()
+        ));
+    }
+    if is_protected_path(&full) || is_protected_path(&root) {
+        return Err(VAULT_ACCESS_DENIED.to_string());
+    }
+    if let Some(sandbox) = super::sandbox() {
+        crate::sandbox::validate_path(&root, &sandbox.policy)?;
+    }
+    Ok((root, mode, filter))
+}
+
+fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '['])
+}
+
+/// Translate a notify event and append what passes the filter.
+fn record_event(buffer: &Mutex<EventBuffer>, filter: &Filter, event: Event) {
+    let changes: Vec<(ChangeKind, PathBuf)> = match event.kind {
+        EventKind::Create(_) => with_kind(ChangeKind::Created, event.paths),
+        EventKind::Remove(_) => with_kind(ChangeKind::Deleted, event.paths),
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+            with_kind(ChangeKind::Deleted, event.paths)
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+            with_kind(ChangeKind::Created, event.paths)
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            let mut paths = event.paths.into_iter();
+            paths
+                .next()
+                .map(|from| (ChangeKind::Deleted, from))
+                .into_iter()
+                .chain(paths.map(|to| (ChangeKind::Created, to)))
+                .collect()
+        }
+        // Platforms that cannot tell which side of a rename this is.
+        EventKind::Modify(ModifyKind::Name(_)) => event
+            .paths
+            .into_iter()
+            .map(|p| {
+                let kind = if p.exists() {
+                    ChangeKind::Created
+                } else {
+                    ChangeKind::Deleted
+                };
+                (kind, p)
+            })
+            .collect(),
+        EventKind::Modify(_) | EventKind::Any | EventKind::Other => {
+            with_kind(ChangeKind::Modified, event.paths)
+        }
+        EventKind::Access(_) => return,
+    };
+
+    let Ok(mut buf) = buffer.lock() else {
+        return;
+    };
+    for (kind, path) in changes {
+        if !filter.matches(&path) {
+            continue;
+        }
+        // One save usually arrives as several writes.
+        if buf.events.last() == Some(&(kind, path.clone())) {
+            continue;
+        }
+        if buf.events.len() == MAX_EVENTS {
+            buf.events.remove(0);
+            buf.dropped += 1;
+        }
+        buf.events.push((kind, path));
+    }
+}
+
+fn with_kind(kind: ChangeKind, paths: Vec<PathBuf>) -> Vec<(ChangeKind, PathBuf)> {
+    paths.into_iter().map(|p| (kind, p)).collect()
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn watch_file_params() -> Vec<ToolParam> {
+    vec![ToolParam {
+        name: "path".into(),
+        description: "File, directory or glob (e.g. 'src/**/*.rs') inside the workspace. \
+                      Directories are watched recursively."
+            .into(),
+        param_type: "string".into(),
+        required: true,
+    }]
+}
+
+pub fn watch_poll_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "watch_id".into(),
+            description: "Watch to poll, as returned by watch_file. Default: all watches.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "stop".into(),
+            description: "Remove the watch after returning its events.".into(),
+            param_type: "boolean".into(),
+            required: false,
+        },
+    ]
+}
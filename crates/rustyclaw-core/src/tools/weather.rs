@@ -0,0 +1,608 @@
+//! Weather tool: current conditions and a short daily forecast.
+//!
+//! Data comes from the `[weather]` provider — Open-Meteo by default, which
+//! needs no key, or OpenWeatherMap with a key from the vault — and both are
+//! mapped to the same [`Weather`] shape.  A place can be named ("Lyon",
+//! "Portland, Maine") or given as coordinates.  Results are reused for
+//! `cache_secs` per place, units and forecast length, so asking again in
+//! the same conversation does not hit the API.
+
+use chrono::Timelike;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument, warn};
+
+use super::ToolParam;
+use super::helpers::{vault, weather_config};
+use crate::config::{WeatherConfig, WeatherProvider};
+use crate::secrets::{AccessContext, CredentialValue};
+
+/// Most forecast days a call may ask for (OpenWeatherMap's free forecast
+/// covers five).
+pub const MAX_FORECAST_DAYS: u64 = 5;
+
+const DEFAULT_FORECAST_DAYS: u64 = 3;
+
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+/// Cached results kept at once; expired ones are dropped first.
+const MAX_CACHE_ENTRIES: usize = 64;
+
+/// Serialized results by request, with the time they were fetched.
+static CACHE: LazyLock<Mutex<HashMap<String, (Instant, String)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Conditions at a place, as returned by the tool.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Weather {
+    pub location: Place,
+    pub provider: &'static str,
+    pub units: Units,
+    pub current: Current,
+    pub forecast: Vec<DailyForecast>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Place {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Units of the numbers in a [`Weather`], which depend on the provider.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Units {
+    pub temperature: &'static str,
+    pub wind_speed: &'static str,
+    pub precipitation: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Current {
+    /// Local time of the observation.
+    pub time: Option<String>,
+    pub temperature: f64,
+    pub feels_like: Option<f64>,
+    /// Relative humidity in percent.
+    pub humidity: Option<f64>,
+    pub wind_speed: Option<f64>,
+    pub conditions: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyForecast {
+    /// Local date, `YYYY-MM-DD`.
+    pub date: String,
+    pub temp_min: f64,
+    pub temp_max: f64,
+    pub precipitation: Option<f64>,
+    /// Highest chance of precipitation during the day, in percent.
+    pub precipitation_chance: Option<f64>,
+    pub conditions: String,
+}
+
+/// What the call asked about.
+enum Target {
+    Name(String),
+    Coords(f64, f64),
+}
+
+impl Target {
+    fn from_args(args: &Value) -> Result<Self, String> {
+        let lat = args.get("latitude").and_then(|v| v.as_f64());
+        let lon = args.get("longitude").and_then(|v| v.as_f64());
+        match (lat, lon) {
+            (Some(lat), Some(lon)) => {
+                if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+                    return Err(format!("Coordinates out of range: {}, {}", lat, lon));
+                }
+                Ok(Self::Coords(lat, lon))
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                Err("Give both latitude and longitude".to_string())
+            }
+            (None, None) => args
+                .get("location")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(|l| Self::Name(l.to_string()))
+                .ok_or_else(|| {
+                    "Missing required parameter: location (or latitude and longitude)".to_string()
+                }),
+        }
+    }
+
+    fn cache_key(&self) -> String {
+        match self {
+            Self::Name(name) => name.to_lowercase(),
+            Self::Coords(lat, lon) => format!("{:.3},{:.3}", lat, lon),
+        }
+    }
+}
+
+// ── Tool executor (async) ───────────────────────────────────────────────────
+
+/// Execute the `weather` tool.
+#[instrument(skip(args, _workspace_dir))]
+pub async fn exec_weather_async(args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    let config = weather_config();
+    let units = args
+        .get("units")
+        .and_then(|v| v.as_str())
+        .unwrap_or(config.units.as_str())
+        .to_ascii_lowercase();
+    if !matches!(units.as_str(), "metric" | "imperial") {
+        return Err(format!("Unknown units: '{}'. Use: metric, imperial", units));
+    }
+    let days = args
+        .get("days")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_FORECAST_DAYS)
+        .clamp(1, MAX_FORECAST_DAYS) as usize;
+    let target = Target::from_args(args)?;
+
+    let key = format!(
+        "{:?}|{}|{}|{}",
+        config.provider,
+        target.cache_key(),
+        units,
+        days
+    );
+    if let Some(hit) = cached(&key, config.cache_secs) {
+        debug!(key, "Returning cached weather");
+        return Ok(hit);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .user_agent("RustyClaw/0.1 (weather tool)")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let weather = match config.provider {
+        WeatherProvider::OpenMeteo => open_meteo(&client, &target, &units, days).await?,
+        WeatherProvider::OpenWeatherMap => {
+            let api_key = api_key(&config).await?;
+            openweathermap(&client, &target, &units, days, &api_key).await?
+        }
+    };
+
+    let out = serde_json::to_string_pretty(&weather).map_err(|e| e.to_string())?;
+    store(key, out.clone(), config.cache_secs);
+    Ok(out)
+}
+
+/// Sync stub for the static ToolDef.
+pub fn exec_weather_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("weather requires async execution".into())
+}
+
+// ── Open-Meteo ──────────────────────────────────────────────────────────────
+
+async fn open_meteo(
+    client: &reqwest::Client,
+    target: &Target,
+    units: &str,
+    days: usize,
+) -> Result<Weather, String> {
+    let place = match target {
+        Target::Coords(lat, lon) => coords_place(*lat, *lon),
+        Target::Name(name) => {
+            // The geocoder matches place names only, so "Portland, Maine"
+            // searches for "Portland" and picks the result in Maine.
+            let (query, qualifier) = match name.split_once(',') {
+                Some((query, rest)) => (query.trim(), Some(rest.trim())),
+                None => (name.as_str(), None),
+            };
+            let url = format!(
+                "https://geocoding-api.open-meteo.com/v1/search?name={}&count=10&format=json",
+                urlencoding::encode(query)
+            );
+            let body = get_json(client, &url, "Open-Meteo geocoding").await?;
+            pick_open_meteo_place(&body, qualifier)
+                .ok_or_else(|| format!("No place found for '{}'", name))?
+        }
+    };
+
+    let mut url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}\
+         &current=temperature_2m,apparent_temperature,relative_humidity_2m,weather_code,\
+         wind_speed_10m\
+         &daily=weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum,\
+         precipitation_probability_max\
+         &timezone=auto&forecast_days={}",
+        place.latitude, place.longitude, days
+    );
+    if units == "imperial" {
+        url.push_str("&temperature_unit=fahrenheit&wind_speed_unit=mph&precipitation_unit=inch");
+    }
+    let body = get_json(client, &url, "Open-Meteo forecast").await?;
+    parse_open_meteo(&body, place, units)
+}
+
+/// The first geocoding result, or the first whose region or country
+/// matches `qualifier`.
+pub(crate) fn pick_open_meteo_place(body: &Value, qualifier: Option<&str>) -> Option<Place> {
+    let results = body.get("results")?.as_array()?;
+    let matches = |r: &&Value| {
+        let Some(q) = qualifier.map(str::to_lowercase) else {
+            return true;
+        };
+        ["admin1", "country", "country_code"].iter().any(|k| {
+            r.get(*k)
+                .and_then(|v| v.as_str())
+                .is_some_and(|v| v.to_lowercase() == q)
+        })
+    };
+    let r = results.iter().find(matches).or_else(|| results.first())?;
+    let name = ["name", "admin1", "country"]
+        .iter()
+        .filter_map(|k| r.get(*k).and_then(|v| v.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(Place {
+        name,
+        latitude: r.get("latitude")?.as_f64()?,
+        longitude: r.get("longitude")?.as_f64()?,
+    })
+}
+
+/// Map an Open-Meteo forecast response.
+pub(crate) fn parse_open_meteo(body: &Value, place: Place, units: &str) -> Result<Weather, String> {
+    let current = &body["current"];
+    let number = |v: &Value, field: &str| {
+        v[field]
+            .as_f64()
+            .ok_or_else(|| format!("Open-Meteo response has no {}", field))
+    };
+    let current = Current {
+        time: current["time"].as_str().map(str::to_string),
+        temperature: number(current, "temperature_2m")?,
+        feels_like: current["apparent_temperature"].as_f64(),
+        humidity: current["relative_humidity_2m"].as_f64(),
+        wind_speed: current["wind_speed_10m"].as_f64(),
+        conditions: wmo_description(current["weather_code"].as_u64()).to_string(),
+    };
+
+    let daily = &body["daily"];
+    let dates = daily["time"]
+        .as_array()
+        .ok_or("Open-Meteo response has no daily forecast")?;
+    let mut forecast = Vec::with_capacity(dates.len());
+    for (i, date) in dates.iter().enumerate() {
+        let at = |field: &str| daily[field].get(i).and_then(|v| v.as_f64());
+        let (Some(date), Some(temp_min), Some(temp_max)) = (
+            date.as_str(),
+            at("temperature_2m_min"),
+            at("temperature_2m_max"),
+        ) else {
+            continue;
+        };
+        forecast.push(DailyForecast {
+            date: date.to_string(),
+            temp_min,
+            temp_max,
+            precipitation: at("precipitation_sum"),
+            precipitation_chance: at("precipitation_probability_max"),
+            conditions: wmo_description(daily["weather_code"].get(i).and_then(|v| v.as_u64()))
+                .to_string(),
+        });
+    }
+
+    let imperial = units == "imperial";
+    Ok(Weather {
+        location: place,
+        provider: "open-meteo",
+        units: Units {
+            temperature: if imperial { "°F" } else { "°C" },
+            wind_speed: if imperial { "mph" } else { "km/h" },
+            precipitation: if imperial { "in" } else { "mm" },
+        },
+        current,
+        forecast,
+    })
+}
+
+/// Text for a WMO weather interpretation code, as used by Open-Meteo.
+fn wmo_description(code: Option<u64>) -> &'static str {
+    match code {
+        Some(0) => "clear sky",
+        Some(1) => "mainly clear",
+        Some(2) => "partly cloudy",
+        Some(3) => "overcast",
+        Some(45 | 48) => "fog",
+        Some(51 | 53 | 55) => "drizzle",
+        Some(56 | 57) => "freezing drizzle",
+        Some(61) => "light rain",
+        Some(63) => "rain",
+        Some(65) => "heavy rain",
+        Some(66 | 67) => "freezing rain",
+        Some(71) => "light snow",
+        Some(73) => "snow",
+        Some(75) => "heavy snow",
+        Some(77) => "snow grains",
+        Some(80..=82) => "rain showers",
+        Some(85 | 86) => "snow showers",
+        Some(95) => "thunderstorm",
+        Some(96 | 99) => "thunderstorm with hail",
+        _ => "unknown",
+    }
+}
+
+// ── OpenWeatherMap ──────────────────────────────────────────────────────────
+
+async fn openweathermap(
+    client: &reqwest::Client,
+    target: &Target,
+    units: &str,
+    days: usize,
+    api_key: &str,
+) -> Result<Weather, String> {
+    let place = match target {
+        Target::Coords(lat, lon) => coords_place(*lat, *lon),
+        Target::Name(name) => {
+            let url = format!(
+                "https://api.openweathermap.org/geo/1.0/direct?q={}&limit=1&appid={}",
+                urlencoding::encode(name),
+                api_key
+            );
+            let body = get_json(client, &url, "OpenWeatherMap geocoding").await?;
+            let r = body
+                .get(0)
+                .ok_or_else(|| format!("No place found for '{}'", name))?;
+            Place {
+                name: ["name", "state", "country"]
+                    .iter()
+                    .filter_map(|k| r.get(*k).and_then(|v| v.as_str()))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                latitude: r["lat"].as_f64().ok_or("Geocoding result has no lat")?,
+                longitude: r["lon"].as_f64().ok_or("Geocoding result has no lon")?,
+            }
+        }
+    };
+
+    let query = format!(
+        "lat={}&lon={}&units={}&appid={}",
+        place.latitude, place.longitude, units, api_key
+    );
+    let current = get_json(
+        client,
+        &format!("https://api.openweathermap.org/data/2.5/weather?{}", query),
+        "OpenWeatherMap current weather",
+    )
+    .await?;
+    let forecast = get_json(
+        client,
+        &format!("https://api.openweathermap.org/data/2.5/forecast?{}", query),
+        "OpenWeatherMap forecast",
+    )
+    .await?;
+    parse_openweathermap(&current, &forecast, place, units, days)
+}
+
+/// Map OpenWeatherMap current-weather and 3-hourly forecast responses,
+/// folding the forecast into `days` local days.
+pub(crate) fn parse_openweathermap(
+    current: &Value,
+    forecast: &Value,
+    place: Place,
+    units: &str,
+    days: usize,
+) -> Result<Weather, String> {
+    let offset = current["timezone"].as_i64().unwrap_or(0);
+    let local = |dt: i64| chrono::DateTime::from_timestamp(dt + offset, 0).map(|t| t.naive_utc());
+    let description = |v: &Value| {
+        v["weather"][0]["description"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string()
+    };
+
+    let now = Current {
+        time: current["dt"]
+            .as_i64()
+            .and_then(local)
+            .map(|t| t.format("%Y-%m-%dT%H:%M").to_string()),
+        temperature: current["main"]["temp"]
+            .as_f64()
+            .ok_or("OpenWeatherMap response has no temperature")?,
+        feels_like: current["main"]["feels_like"].as_f64(),
+        humidity: current["main"]["humidity"].as_f64(),
+        wind_speed: current["wind"]["speed"].as_f64(),
+        conditions: description(current),
+    };
+
+    /// One day's 3-hour slots, folded as they are read.
+    struct Day {
+        min: f64,
+        max: f64,
+        precipitation: f64,
+        chance: Option<f64>,
+        /// Conditions of the slot nearest midday, with its distance in hours.
+        conditions: (u32, String),
+    }
+    let mut by_date: BTreeMap<chrono::NaiveDate, Day> = BTreeMap::new();
+    let slots = forecast["list"]
+        .as_array()
+        .ok_or("OpenWeatherMap response has no forecast")?;
+    for slot in slots {
+        let Some(at) = slot["dt"].as_i64().and_then(local) else {
+            continue;
+        };
+        let (Some(min), Some(max)) = (
+            slot["main"]["temp_min"].as_f64(),
+            slot["main"]["temp_max"].as_f64(),
+        ) else {
+            continue;
+        };
+        let precipitation =
+            slot["rain"]["3h"].as_f64().unwrap_or(0.0) + slot["snow"]["3h"].as_f64().unwrap_or(0.0);
+        let chance = slot["pop"].as_f64().map(|p| (p * 100.0).round());
+        let from_midday = at.hour().abs_diff(12);
+        let day = by_date.entry(at.date()).or_insert_with(|| Day {
+            min,
+            max,
+            precipitation: 0.0,
+            chance: None,
+            conditions: (u32::MAX, String::new()),
+        });
+        day.min = day.min.min(min);
+        day.max = day.max.max(max);
+        day.precipitation += precipitation;
+        if let Some(chance) = chance {
+            day.chance = Some(day.chance.map_or(chance, |c| c.max(chance)));
+        }
+        if from_midday < day.conditions.0 {
+            day.conditions = (from_midday, description(slot));
+        }
+    }
+
+    let imperial = units == "imperial";
+    Ok(Weather {
+        location: place,
+        provider: "openweathermap",
+        units: Units {
+            temperature: if imperial { "°F" } else { "°C" },
+            wind_speed: if imperial { "mph" } else { "m/s" },
+            precipitation: "mm",
+        },
+        current: now,
+        forecast: by_date
+            .into_iter()
+            .take(days)
+            .map(|(date, day)| DailyForecast {
+                date: date.to_string(),
+                temp_min: day.min,
+                temp_max: day.max,
+                precipitation: Some((day.precipitation * 10.0).round() / 10.0),
+                precipitation_chance: day.chance,
+                conditions: day.conditions.1,
+            })
+            .collect(),
+    })
+}
+
+/// The OpenWeatherMap key from the vault credential named in `[weather]`.
+async fn api_key(config: &WeatherConfig) -> Result<String, String> {
+    let missing = || {
+        format!(
+            "OpenWeatherMap needs an API key in the vault credential '{}', or set \
+             [weather] provider = \"open-meteo\" to use the keyless provider",
+            config.api_key
+        )
+    };
+    let vault = vault().ok_or_else(missing)?;
+    let ctx = AccessContext::requested_by("tool:weather");
+    let mut mgr = vault.lock().await;
+    let (_, value) = mgr
+        .get_credential(&config.api_key, &ctx)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(missing)?;
+    match value {
+        CredentialValue::Single(key) => Ok(key.into_inner()),
+        _ => Err(format!(
+            "Credential '{}' is not an API key credential",
+            config.api_key
+        )),
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn coords_place(latitude: f64, longitude: f64) -> Place {
+    Place {
+        name: format!("{:.4}, {:.4}", latitude, longitude),
+        latitude,
+        longitude,
+    }
+}
+
+/// GET `url` and parse the JSON body.  Errors name the request as `what`
+/// rather than echoing the URL, which may hold an API key.
+async fn get_json(client: &reqwest::Client, url: &str, what: &str) -> Result<Value, String> {
+    let response = client
+        .get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("{} request failed: {}", what, e.without_url()))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        warn!(status = status.as_u16(), what, "Weather API error");
+        let body: String = body.chars().take(300).collect();
+        return Err(format!("{} error {}: {}", what, status.as_u16(), body));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("{} returned invalid JSON: {}", what, e.without_url()))
+}
+
+fn cached(key: &str, ttl_secs: u64) -> Option<String> {
+    let cache = CACHE.lock().unwrap();
+    let (at, value) = cache.get(key)?;
+    (at.elapsed() < Duration::from_secs(ttl_secs)).then(|| value.clone())
+}
+
+fn store(key: String, value: String, ttl_secs: u64) {
+    if ttl_secs == 0 {
+        return;
+    }
+    let ttl = Duration::from_secs(ttl_secs);
+    let mut cache = CACHE.lock().unwrap();
+    cache.retain(|_, (at, _)| at.elapsed() < ttl);
+    if cache.len() >= MAX_CACHE_ENTRIES
+        && let Some(oldest) = cache
+            .iter()
+            .min_by_key(|(_, (at, _))| *at)
+            .map(|(k, _)| k.clone())
+    {
+        cache.remove(&oldest);
+    }
+    cache.insert(key, (Instant::now(), value));
+}
+
+// ── Parameters ──────────────────────────────────────────────────────────────
+
+pub fn weather_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "location".into(),
+            description: "Place name, optionally with region or country: 'Lyon', \
+                          'Portland, Maine'. Give this or latitude and longitude."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "latitude".into(),
+            description: "Latitude in degrees, with longitude.".into(),
+            param_type: "number".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "longitude".into(),
+            description: "Longitude in degrees, with latitude.".into(),
+            param_type: "number".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "days".into(),
+            description: "Forecast days including today, 1–5. Default: 3.".into(),
+            param_type: "integer".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "units".into(),
+            description: "'metric' or 'imperial'. Default: the configured units (metric).".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
@@ -38,7 +38,7 @@ fn ssrf_redirect_policy(max: usize) -> reqwest::redirect::Policy {
 ///
 /// Returns a user-facing error string prefixed so callers/tests can recognise
 /// a security rejection.
-fn ssrf_check_blocking(url: &str) -> Result<(), String> {
+pub(super) fn ssrf_check_blocking(url: &str) -> Result<(), String> {
     SsrfValidator::default().validate_url(url)
 }
 
@@ -187,23 +187,7 @@ pub async fn exec_web_fetch_async(args: &Value, _workspace_dir: &Path) -> Result
     // Parse HTML and extract content
     #[cfg(feature = "web-tools")]
     {
-        let document = scraper::Html::parse_document(&body);
-        let content = extract_readable_content(&document);
-
-        let result = match extract_mode {
-            "text" => html_to_text(&content),
-            _ => html2md::parse_html(&content),
-        };
-
-        let mut result = result
-            .lines()
-            .map(|l| l.trim_end())
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        while result.contains("\n\n\n") {
-            result = result.replace("\n\n\n", "\n\n");
-        }
+        let mut result = readable_content(&body, extract_mode);
 
         if result.len() > max_chars {
             result.truncate(max_chars);
@@ -347,6 +331,56 @@ pub async fn exec_web_search_async(args: &Value, _workspace_dir: &Path) -> Resul
     Ok(output)
 }
 
+/// A page fetched for a tool that parses it itself.
+#[cfg(feature = "web-tools")]
+pub(super) struct FetchedPage {
+    /// Where the request ended up after redirects.
+    pub url: url::Url,
+    pub body: String,
+}
+
+/// GET `url` with `web_fetch`'s SSRF checks on the URL and every redirect,
+/// without cookies or extraction.
+#[cfg(feature = "web-tools")]
+pub(super) async fn fetch_page(url: &str, user_agent: &str) -> Result<FetchedPage, String> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("URL must start with http:// or https://".to_string());
+    }
+    {
+        let url_owned = url.to_string();
+        tokio::task::spawn_blocking(move || ssrf_check_blocking(&url_owned))
+            .await
+            .map_err(|e| format!("SSRF validation task failed: {e}"))??;
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent(user_agent)
+        .redirect(ssrf_redirect_policy(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!(
+            "HTTP {} — {}",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("Unknown")
+        ));
+    }
+    let url = response.url().clone();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response body: {}", e))?;
+    Ok(FetchedPage { url, body })
+}
+
 // ── Cookie helpers (async) ──────────────────────────────────────────────────
 
 async fn get_cookie_header_async(domain: &str, path: &str, is_secure: bool) -> Option<String> {
@@ -501,23 +535,7 @@ fn exec_web_fetch_sync(args: &Value, _workspace_dir: &Path) -> Result<String, St
 
     #[cfg(feature = "web-tools")]
     {
-        let document = scraper::Html::parse_document(&body);
-        let content = extract_readable_content(&document);
-
-        let result = match extract_mode {
-            "text" => html_to_text(&content),
-            _ => html2md::parse_html(&content),
-        };
-
-        let mut result = result
-            .lines()
-            .map(|l| l.trim_end())
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        while result.contains("\n\n\n") {
-            result = result.replace("\n\n\n", "\n\n");
-        }
+        let mut result = readable_content(&body, extract_mode);
 
         if result.len() > max_chars {
             result.truncate(max_chars);
@@ -674,6 +692,31 @@ fn store_response_cookies_sync(domain: &str, headers: &[String]) {
 
 // ── HTML extraction helpers ─────────────────────────────────────────────────
 
+/// The main content of an HTML page — navigation, headers, footers and
+/// scripts stripped — as markdown, or plain text for `extract_mode =
+/// "text"`.
+#[cfg(feature = "web-tools")]
+pub(super) fn readable_content(html: &str, extract_mode: &str) -> String {
+    let document = scraper::Html::parse_document(html);
+    let content = extract_readable_content(&document);
+
+    let result = match extract_mode {
+        "text" => html_to_text(&content),
+        _ => html2md::parse_html(&content),
+    };
+
+    let mut result = result
+        .lines()
+        .map(|l| l.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    while result.contains("\n\n\n") {
+        result = result.replace("\n\n\n", "\n\n");
+    }
+    result
+}
+
 #[cfg(feature = "web-tools")]
 fn extract_readable_content(document: &scraper::Html) -> String {
     use scraper::Selector;
@@ -0,0 +1,434 @@
+//! Scraping tools: `extract_links` and `extract_structured`.
+//!
+//! Where `web_fetch` turns a page into text, these return just the parts a
+//! scraping workflow needs, as JSON: every link with its anchor text, or
+//! the page's Open Graph, Twitter card, JSON-LD and meta tags.  Both take
+//! a `url` to fetch (with `web_fetch`'s SSRF checks) or `content` the agent
+//! already has, so a page fetched once can be mined several ways.
+
+use serde_json::Value;
+use std::path::Path;
+use tracing::instrument;
+
+use super::ToolParam;
+
+/// Links returned when the call doesn't say.
+const DEFAULT_MAX_LINKS: usize = 500;
+/// Most links one call may return.
+const MAX_LINKS: usize = 5_000;
+
+/// Collect the links on a page, absolutized and deduplicated (async).
+#[instrument(skip(args, _workspace_dir))]
+pub async fn exec_extract_links_async(
+    args: &Value,
+    _workspace_dir: &Path,
+) -> Result<String, String> {
+    #[cfg(feature = "web-tools")]
+    {
+        let scope = match args.get("scope").and_then(|v| v.as_str()).unwrap_or("all") {
+            "all" => imp::Scope::All,
+            "internal" => imp::Scope::Internal,
+            "external" => imp::Scope::External,
+            other => {
+                return Err(format!(
+                    "Unknown scope '{}': use all, internal or external",
+                    other
+                ));
+            }
+        };
+        let max = args
+            .get("max_links")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_MAX_LINKS, |n| (n as usize).clamp(1, MAX_LINKS));
+
+        let page = imp::load_page(args).await?;
+        let links = imp::extract_links(&page, scope, max)?;
+        Ok(links.to_string())
+    }
+    #[cfg(not(feature = "web-tools"))]
+    {
+        let _ = args;
+        Err("extract_links requires the 'web-tools' feature".to_string())
+    }
+}
+
+/// Pull Open Graph, Twitter card, JSON-LD and meta tags from a page (async).
+#[instrument(skip(args, _workspace_dir))]
+pub async fn exec_extract_structured_async(
+    args: &Value,
+    _workspace_dir: &Path,
+) -> Result<String, String> {
+    #[cfg(feature = "web-tools")]
+    {
+        let page = imp::load_page(args).await?;
+        Ok(imp::extract_structured(&page).to_string())
+    }
+    #[cfg(not(feature = "web-tools"))]
+    {
+        let _ = args;
+        Err("extract_structured requires the 'web-tools' feature".to_string())
+    }
+}
+
+/// Sync stub for the static ToolDef.
+pub fn exec_extract_links_stub(_args: &Value, _workspace_dir: &Path) -> Result<String, String> {
+    Err("extract_links requires async execution".into())
+}
+
+/// Sync stub for the static ToolDef.
+pub fn exec_extract_structured_stub(
+    _args: &Value,
+    _workspace_dir: &Path,
+) -> Result<String, String> {
+    Err("extract_structured requires async execution".into())
+}
+
+// `summarize_url` takes its page and links the same way.
+#[cfg(feature = "web-tools")]
+pub(super) use imp::{Scope, extract_links, load_page};
+
+#[cfg(feature = "web-tools")]
+mod imp {
+    use scraper::{ElementRef, Html, Selector};
+    use serde_json::{Map, Value, json};
+    use std::collections::HashMap;
+    use url::Url;
+
+    use super::super::web;
+
+    const USER_AGENT: &str = "RustyClaw/0.1 (web scraping tools)";
+
+    /// The HTML to work on and the URL its relative links resolve against.
+    pub(in crate::tools) struct Page {
+        pub html: String,
+        pub url: Option<Url>,
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    pub(in crate::tools) enum Scope {
+        All,
+        Internal,
+        External,
+    }
+
+    pub(in crate::tools) async fn load_page(args: &Value) -> Result<Page, String> {
+        let url = args.get("url").and_then(|v| v.as_str());
+        let content = args.get("content").and_then(|v| v.as_str());
+        match (url, content) {
+            (Some(_), Some(_)) => Err("Pass either url or content, not both".to_string()),
+            (Some(url), None) => {
+                let fetched = web::fetch_page(url, USER_AGENT).await?;
+                Ok(Page {
+                    html: fetched.body,
+                    url: Some(fetched.url),
+                })
+            }
+            (None, Some(content)) => {
+                let url = args
+                    .get("base_url")
+                    .and_then(|v| v.as_str())
+                    .map(|base| {
+                        Url::parse(base).map_err(|e| format!("Invalid base_url '{}': {}", base, e))
+                    })
+                    .transpose()?;
+                Ok(Page {
+                    html: content.to_string(),
+                    url,
+                })
+            }
+            (None, None) => Err("Missing required parameter: url or content".to_string()),
+        }
+    }
+
+    fn selector(css: &str) -> Selector {
+        Selector::parse(css).expect("static selector")
+    }
+
+    /// Whitespace-collapsed text of an element.
+    fn text_of(el: ElementRef) -> String {
+        el.text()
+            .flat_map(str::split_whitespace)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Whether `content` is HTML rather than the markdown `web_fetch`
+    /// returns.
+    fn looks_like_html(content: &str) -> bool {
+        let head: String = content
+            .chars()
+            .take(65_536)
+            .collect::<String>()
+            .to_lowercase();
+        head.trim_start().starts_with('<')
+            || ["<a ", "<html", "<body", "<meta ", "<link "]
+                .iter()
+                .any(|tag| head.contains(tag))
+    }
+
+    /// What relative links resolve against: the page's `<base href>`, if
+    /// any, then the page URL.
+    fn base_url(doc: &Html, page: Option<&Url>) -> Option<Url> {
+        let href = doc
+            .select(&selector("base[href]"))
+            .next()
+            .and_then(|base| base.value().attr("href"));
+        match (href, page) {
+            (Some(href), Some(page)) => page.join(href).ok(),
+            (Some(href), None) => Url::parse(href).ok(),
+            (None, page) => page.cloned(),
+        }
+    }
+
+    pub(in crate::tools) fn extract_links(
+        page: &Page,
+        scope: Scope,
+        max: usize,
+    ) -> Result<Value, String> {
+        let host = page.url.as_ref().and_then(|u| u.host_str());
+        if scope != Scope::All && host.is_none() {
+            return Err("scope internal/external needs a url or base_url".to_string());
+        }
+
+        let html = if looks_like_html(&page.html) {
+            page.html.clone()
+        } else {
+            let mut html = String::new();
+            pulldown_cmark::html::push_html(&mut html, pulldown_cmark::Parser::new(&page.html));
+            html
+        };
+        let doc = Html::parse_document(&html);
+        let base = base_url(&doc, page.url.as_ref());
+
+        let mut links: Vec<Map<String, Value>> = Vec::new();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for anchor in doc.select(&selector("a[href], area[href]")) {
+            let href = anchor.value().attr("href").unwrap_or("").trim();
+            let scheme = href.split(':').next().unwrap_or("").to_ascii_lowercase();
+            if href.is_empty()
+                || href.starts_with('#')
+                || matches!(scheme.as_str(), "javascript" | "data" | "vbscript")
+            {
+                continue;
+            }
+            let absolute = match &base {
+                Some(base) => base.join(href).ok(),
+                None => Url::parse(href).ok(),
+            };
+            if scope != Scope::All {
+                let internal = absolute.as_ref().and_then(|u| u.host_str()) == host;
+                if internal != (scope == Scope::Internal) {
+                    continue;
+                }
+            }
+            // Links that differ only by fragment are the same page.
+            let (url, key) = match absolute {
+                Some(mut url) => {
+                    let full = url.to_string();
+                    url.set_fragment(None);
+                    (full, url.to_string())
+                }
+                None => (href.to_string(), href.to_string()),
+            };
+
+            let mut text = text_of(anchor);
+            if text.is_empty() {
+                text = ["title", "aria-label"]
+                    .iter()
+                    .find_map(|attr| anchor.value().attr(attr))
+                    .or_else(|| {
+                        anchor
+                            .select(&selector("img[alt]"))
+                            .next()
+                            .and_then(|img| img.value().attr("alt"))
+                    })
+                    .unwrap_or("")
+                    .trim()
+                    .to_string();
+            }
+
+            match seen.get(&key) {
+                Some(&i) => {
+                    // Keep the first anchor text that says something.
+                    if links[i]["text"] == "" && !text.is_empty() {
+                        links[i].insert("text".into(), text.into());
+                    }
+                }
+                None => {
+                    seen.insert(key, links.len());
+                    let mut link = Map::new();
+                    link.insert("url".into(), url.into());
+                    link.insert("text".into(), text.into());
+                    if let Some(rel) = anchor.value().attr("rel") {
+                        link.insert("rel".into(), rel.trim().into());
+                    }
+                    links.push(link);
+                }
+            }
+        }
+
+        let total = links.len();
+        links.truncate(max);
+        Ok(json!({
+            "url": page.url.as_ref().map(Url::as_str),
+            "total": total,
+            "truncated": total > links.len(),
+            "links": links,
+        }))
+    }
+
+    /// Add `value` under `key`, turning repeated keys (several `og:image`s)
+    /// into arrays.
+    fn insert_multi(map: &mut Map<String, Value>, key: &str, value: &str) {
+        let value = Value::from(value);
+        match map.get_mut(key) {
+            None => {
+                map.insert(key.to_string(), value);
+            }
+            Some(Value::Array(values)) => {
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+            Some(existing) => {
+                if *existing != value {
+                    *existing = Value::Array(vec![existing.take(), value]);
+                }
+            }
+        }
+    }
+
+    pub(super) fn extract_structured(page: &Page) -> Value {
+        let doc = Html::parse_document(&page.html);
+        let base = base_url(&doc, page.url.as_ref());
+
+        let title = doc
+            .select(&selector("title"))
+            .next()
+            .map(text_of)
+            .filter(|t| !t.is_empty());
+        let lang = doc
+            .select(&selector("html[lang]"))
+            .next()
+            .and_then(|html| html.value().attr("lang"));
+        let canonical = doc
+            .select(&selector("link[rel~=canonical][href]"))
+            .next()
+            .and_then(|link| link.value().attr("href"))
+            .map(|href| match &base {
+                Some(base) => base.join(href).map_or(href.to_string(), String::from),
+                None => href.to_string(),
+            });
+
+        let mut meta = Map::new();
+        let mut open_graph = Map::new();
+        let mut twitter = Map::new();
+        for tag in doc.select(&selector("meta[content]")) {
+            let el = tag.value();
+            let content = el.attr("content").unwrap_or("").trim();
+            let (key, is_property) = match (el.attr("property"), el.attr("name")) {
+                (Some(property), _) => (property, true),
+                (None, Some(name)) => (name, false),
+                (None, None) => continue,
+            };
+            let key = key.trim().to_ascii_lowercase();
+            if let Some(rest) = key.strip_prefix("og:") {
+                insert_multi(&mut open_graph, rest, content);
+            } else if let Some(rest) = key.strip_prefix("twitter:") {
+                insert_multi(&mut twitter, rest, content);
+            } else if is_property {
+                // Other Open Graph namespaces: article:, book:, profile:, …
+                insert_multi(&mut open_graph, &key, content);
+            } else if !key.is_empty() {
+                insert_multi(&mut meta, &key, content);
+            }
+        }
+
+        let mut json_ld = Vec::new();
+        let mut invalid = 0;
+        for script in doc.select(&selector(r#"script[type="application/ld+json"]"#)) {
+            let text: String = script.text().collect();
+            match serde_json::from_str::<Value>(text.trim()) {
+                Ok(Value::Array(items)) => json_ld.extend(items),
+                Ok(item) => json_ld.push(item),
+                Err(_) => invalid += 1,
+            }
+        }
+
+        let mut out = json!({
+            "url": page.url.as_ref().map(Url::as_str),
+            "title": title,
+            "lang": lang,
+            "canonical": canonical,
+            "open_graph": open_graph,
+            "twitter": twitter,
+            "json_ld": json_ld,
+            "meta": meta,
+        });
+        if invalid > 0 {
+            out["json_ld_invalid"] = invalid.into();
+        }
+        out
+    }
+}
+
+// ── Parameter definitions ───────────────────────────────────────────────────
+
+fn source_params() -> Vec<ToolParam> {
+    vec![
+        ToolParam {
+            name: "url".into(),
+            description: "Page to fetch (http or https). Give this or 'content'.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "content".into(),
+            description: "Page content you already have, e.g. from web_fetch or a file, \
+                          instead of fetching 'url'."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "base_url".into(),
+            description: "URL that 'content' came from, to resolve its relative links.".into(),
+            param_type: "string".into(),
+            required: false,
+        },
+    ]
+}
+
+pub fn extract_links_params() -> Vec<ToolParam> {
+    let mut params = source_params();
+    params[1].description = "HTML, or the markdown web_fetch returns, to take links from \
+                             instead of fetching 'url'."
+        .into();
+    params.extend([
+        ToolParam {
+            name: "scope".into(),
+            description: "'all' (default), 'internal' (same host as the page) or 'external'."
+                .into(),
+            param_type: "string".into(),
+            required: false,
+        },
+        ToolParam {
+            name: "max_links".into(),
+            description: format!(
+                "Most links to return. Default: {}, at most {}.",
+                DEFAULT_MAX_LINKS, MAX_LINKS
+            ),
+            param_type: "integer".into(),
+            required: false,
+        },
+    ]);
+    params
+}
+
+pub fn extract_structured_params() -> Vec<ToolParam> {
+    let mut params = source_params();
+    params[1].description = "HTML to read instead of fetching 'url'. Markdown from web_fetch \
+                             has no meta tags left; pass HTML."
+        .into();
+    params
+}
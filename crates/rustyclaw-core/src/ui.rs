@@ -184,6 +184,7 @@ impl DialogState {
                 id,
                 name,
                 arguments,
+                ..
             } => {
                 self.pending_tool_approval = Some((id.clone(), name.clone(), arguments.clone()));
                 true
@@ -305,7 +305,8 @@ impl WorkspaceContext {
         // Add tool categories overview
         guidance.push_str(
             "### Available Tool Categories
-- **Files:** read_file, write_file, edit_file, list_directory, search_files, find_files
+- **Files:** read_file, write_file, edit_file, list_directory, search_files, find_files,
+  move_file, copy_file
 - **Shell:** execute_command, process (background commands)
 - **Web:** web_fetch, web_search, browser (automation)
 - **Memory:** memory_search, memory_get, save_memory
@@ -36,9 +36,7 @@ pub(super) fn render_dialogs(sig: AppSignals) -> Element {
         mut show_settings,
         mut show_swarm,
         mut swarm_creating,
-        mut tool_approval_id,
-        mut tool_approval_name,
-        mut tool_approval_args,
+        mut tool_approval,
         mut show_tool_approval,
         mut show_vault_unlock,
         mut vault_unlock_error,
@@ -252,18 +250,13 @@ pub(super) fn render_dialogs(sig: AppSignals) -> Element {
 
             ToolApprovalDialog {
                 visible: *show_tool_approval.read(),
-                data: ToolApprovalData {
-                    id: tool_approval_id.read().clone(),
-                    name: tool_approval_name.read().clone(),
-                    arguments: tool_approval_args.read().clone(),
-                    selected_allow: true,
-                },
-                on_approve: move |id: String| {
+                data: tool_approval.read().clone(),
+                on_approve: move |(id, remember): (String, bool)| {
                     state.write().pending_tool_approval = None;
                     let gw = gateway.read().clone();
                     if let Some(client) = gw {
                         spawn(async move {
-                            let _ = client.send(GatewayCommand::ToolApprove { id, approved: true }).await;
+                            let _ = client.send(GatewayCommand::ToolApprove { id, approved: true, remember }).await;
                         });
                     }
                 },
@@ -272,7 +265,7 @@ pub(super) fn render_dialogs(sig: AppSignals) -> Element {
                     let gw = gateway.read().clone();
                     if let Some(client) = gw {
                         spawn(async move {
-                            let _ = client.send(GatewayCommand::ToolApprove { id, approved: false }).await;
+                            let _ = client.send(GatewayCommand::ToolApprove { id, approved: false, remember: false }).await;
                         });
                     }
                 },
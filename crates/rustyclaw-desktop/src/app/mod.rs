@@ -16,7 +16,8 @@ use rustyclaw_core::ui::ConnectionStatus;
 use rustyclaw_core::user_prompt_types::UserPrompt;
 
 use rustyclaw_view::{
-    BannerActionKind, HatchingDialogData, PromptAttachment, build_prompt_with_attachments,
+    BannerActionKind, HatchingDialogData, PromptAttachment, ToolApprovalData,
+    build_prompt_with_attachments,
 };
 
 mod dialogs;
@@ -53,9 +54,7 @@ pub fn App() -> Element {
     let swarm_creating = use_signal(|| false);
 
     // Tool approval state
-    let mut tool_approval_id = use_signal(String::new);
-    let mut tool_approval_name = use_signal(String::new);
-    let mut tool_approval_args = use_signal(String::new);
+    let mut tool_approval = use_signal(ToolApprovalData::default);
     let mut show_tool_approval = use_signal(|| false);
 
     // Vault unlock state
@@ -128,9 +127,7 @@ pub fn App() -> Element {
         show_settings,
         show_swarm,
         swarm_creating,
-        tool_approval_id,
-        tool_approval_name,
-        tool_approval_args,
+        tool_approval,
         show_tool_approval,
         show_vault_unlock,
         vault_unlock_error,
@@ -396,10 +393,8 @@ pub fn App() -> Element {
     // Sync pending events from state into dialog signals
     use_effect(move || {
         let s = state.read();
-        if let Some((id, name, args)) = &s.pending_tool_approval {
-            tool_approval_id.set(id.clone());
-            tool_approval_name.set(name.clone());
-            tool_approval_args.set(args.clone());
+        if let Some(pending) = &s.pending_tool_approval {
+            tool_approval.set(pending.clone());
             show_tool_approval.set(true);
         } else {
             show_tool_approval.set(false);
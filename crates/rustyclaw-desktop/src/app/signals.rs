@@ -9,7 +9,7 @@ use crate::app_support::connect_to_gateway;
 use crate::state::AppState;
 use rustyclaw_core::gateway::GatewayClient;
 use rustyclaw_core::user_prompt_types::UserPrompt;
-use rustyclaw_view::{ConnectionDialogData, HatchingDialogData};
+use rustyclaw_view::{ConnectionDialogData, HatchingDialogData, ToolApprovalData};
 
 #[derive(Clone, Copy)]
 pub(super) struct AppSignals {
@@ -23,9 +23,7 @@ pub(super) struct AppSignals {
     pub show_settings: Signal<bool>,
     pub show_swarm: Signal<bool>,
     pub swarm_creating: Signal<bool>,
-    pub tool_approval_id: Signal<String>,
-    pub tool_approval_name: Signal<String>,
-    pub tool_approval_args: Signal<String>,
+    pub tool_approval: Signal<ToolApprovalData>,
     pub show_tool_approval: Signal<bool>,
     pub show_vault_unlock: Signal<bool>,
     pub vault_unlock_error: Signal<Option<String>>,
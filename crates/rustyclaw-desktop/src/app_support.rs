@@ -7,11 +7,13 @@ use dioxus::prelude::*;
 use rustyclaw_view::{chrono, serde_json, tracing, uuid};
 
 use crate::state::AppState;
-use rustyclaw_core::gateway::GatewayClient;
 use rustyclaw_core::gateway::client_types::{GatewayCommand, GatewayEvent};
+use rustyclaw_core::gateway::{Capabilities, GatewayClient};
 use rustyclaw_core::types::MessageRole;
 use rustyclaw_core::ui::{ConnectionStatus, ThreadInfo};
-use rustyclaw_view::{SecretInfoData, SecretsDialogData, SwarmAgentData, SwarmData};
+use rustyclaw_view::{
+    SecretInfoData, SecretsDialogData, SwarmAgentData, SwarmData, ToolApprovalData,
+};
 
 // ── Shared buffer for the worker → UI bridge ───────────────────────────────
 
@@ -63,7 +65,7 @@ pub(crate) async fn connect_to_gateway(
 ) {
     state.write().connection = ConnectionStatus::Connecting;
 
-    match GatewayClient::connect(url).await {
+    match GatewayClient::connect(url, Capabilities::gateway()).await {
         Ok(client) => {
             gateway.set(Some(Arc::new(client)));
             state.write().connection = ConnectionStatus::Connected;
@@ -203,8 +205,19 @@ pub(crate) fn handle_gateway_event(event: GatewayEvent, mut state: Signal<AppSta
             id,
             name,
             arguments,
+            summary,
+            risks,
+            can_remember,
         } => {
-            state.write().pending_tool_approval = Some((id, name, arguments));
+            state.write().pending_tool_approval = Some(ToolApprovalData {
+                id,
+                name,
+                arguments,
+                description: summary,
+                risks,
+                can_remember,
+                selected_allow: true,
+            });
         }
         GatewayEvent::ThreadsUpdate {
             threads,
@@ -429,9 +442,9 @@ pub(crate) fn handle_gateway_event(event: GatewayEvent, mut state: Signal<AppSta
                 format!("Model reloaded: {provider}/{model}"),
             );
         }
-        GatewayEvent::ThinkingDelta => {
-            // Keeps the thinking clock alive server-side; the desktop tracks
-            // thinking state via ThinkingStart/ThinkingEnd, so nothing to do.
+        GatewayEvent::ThinkingDelta { .. } => {
+            // The desktop only shows that the model is thinking, tracked via
+            // ThinkingStart/ThinkingEnd; the reasoning text isn't displayed.
         }
         GatewayEvent::ThreadSwitched { .. } => {
             // Thread state syncs via ThreadsUpdate/ThreadHistory.
@@ -449,7 +462,11 @@ pub(crate) fn handle_gateway_event(event: GatewayEvent, mut state: Signal<AppSta
         | GatewayEvent::SecretsSetupTotpResult { .. }
         | GatewayEvent::SecretsVerifyTotpResult { .. }
         | GatewayEvent::SecretsRemoveTotpResult { .. } => {}
-        GatewayEvent::Error { message } => {
+        // Only sent to clients that announce `request_ids`.
+        GatewayEvent::RequestStart { .. } => {}
+        // Only sent to clients that announce `resume_turns`.
+        GatewayEvent::TurnStarted { .. } => {}
+        GatewayEvent::Error { message, .. } => {
             let mut s = state.write();
             s.push_notice(MessageRole::Error, message);
             s.is_processing = false;
@@ -600,6 +617,67 @@ pub(crate) fn handle_gateway_event(event: GatewayEvent, mut state: Signal<AppSta
                 s.push_notice(MessageRole::Error, format!("Engine error: {}", message));
             }
         }
+        GatewayEvent::ToolPermissions { ok, message, .. } => {
+            // No tool permissions panel yet; surface failed updates only.
+            if !ok {
+                let message = message.unwrap_or_else(|| "unknown error".to_string());
+                state
+                    .write()
+                    .push_notice(MessageRole::Error, format!("Tool permission: {}", message));
+            }
+        }
+        GatewayEvent::SessionExport {
+            session_id,
+            ok,
+            format,
+            content,
+            error,
+        } => {
+            // The desktop app doesn't request exports yet; report any reply.
+            let mut s = state.write();
+            if ok {
+                s.push_notice(
+                    MessageRole::Info,
+                    format!(
+                        "Exported session {} as {} ({} bytes)",
+                        session_id,
+                        format,
+                        content.len()
+                    ),
+                );
+            } else {
+                let error = error.unwrap_or_else(|| "unknown error".to_string());
+                s.push_notice(MessageRole::Error, format!("Session export: {}", error));
+            }
+        }
+        GatewayEvent::SandboxPolicy { sandbox, tools } => {
+            // No policy panel yet; summarize the reply.
+            let denied = tools.iter().filter(|t| t.permission == "deny").count();
+            state.write().push_notice(
+                MessageRole::Info,
+                format!(
+                    "Sandbox: {} (effective {}), network {}; {} of {} tools denied",
+                    sandbox.mode,
+                    sandbox.effective_mode,
+                    sandbox.network,
+                    denied,
+                    tools.len()
+                ),
+            );
+        }
+        GatewayEvent::GlobalTasks { tasks } => {
+            // No gateway-wide task panel yet; summarize the reply.
+            let sessions: std::collections::BTreeSet<_> =
+                tasks.iter().filter_map(|t| t.session.as_deref()).collect();
+            state.write().push_notice(
+                MessageRole::Info,
+                format!(
+                    "Gateway tasks: {} across {} sessions",
+                    tasks.len(),
+                    sessions.len()
+                ),
+            );
+        }
     }
 }
 
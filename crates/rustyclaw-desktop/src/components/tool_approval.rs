@@ -10,7 +10,9 @@ use super::RcModal;
 pub struct ToolApprovalDialogProps {
     pub visible: bool,
     pub data: ToolApprovalData,
-    pub on_approve: EventHandler<String>,
+    /// Called with the call id and whether to approve the tool for the
+    /// rest of the session.
+    pub on_approve: EventHandler<(String, bool)>,
     pub on_deny: EventHandler<String>,
 }
 
@@ -21,8 +23,10 @@ pub fn ToolApprovalDialog(props: ToolApprovalDialogProps) -> Element {
     }
 
     let truncated_args = props.data.arguments_preview(500, 20);
+    let risk_line = props.data.risk_line();
 
     let id_approve = props.data.id.clone();
+    let id_session = props.data.id.clone();
     let id_deny = props.data.id.clone();
     let id_close = props.data.id.clone();
 
@@ -39,9 +43,16 @@ pub fn ToolApprovalDialog(props: ToolApprovalDialogProps) -> Element {
                         onclick: move |_| props.on_deny.call(id_deny.clone()),
                         "✕ Deny"
                     }
+                    if props.data.can_remember {
+                        Button {
+                            color: BulmaColor::Light,
+                            onclick: move |_| props.on_approve.call((id_session.clone(), true)),
+                            "✓ Approve for session"
+                        }
+                    }
                     Button {
                         color: BulmaColor::Primary,
-                        onclick: move |_| props.on_approve.call(id_approve.clone()),
+                        onclick: move |_| props.on_approve.call((id_approve.clone(), false)),
                         "✓ Approve"
                     }
                 }
@@ -55,6 +66,10 @@ pub fn ToolApprovalDialog(props: ToolApprovalDialogProps) -> Element {
                     class: "tool-approval-name",
                     "{props.data.name}"
                 }
+                p { class: "help", "{props.data.description}" }
+                if let Some(risks) = risk_line {
+                    p { class: "help has-text-warning", "{risks}" }
+                }
             }
 
             if !truncated_args.is_empty() {
@@ -63,6 +63,7 @@ fn main() -> Result<()> {
 
     let mut config = Config::load(cli.common.config_path())?;
     cli.common.apply_overrides(&mut config);
+    rustyclaw_core::log_rotation::init(&config.log_rotation);
 
     // Only forward an explicit URL (from --url or config). When neither is set,
     // leave it None so the desktop client shows its connection dialog with the
@@ -10,7 +10,7 @@ use std::collections::{HashMap, VecDeque};
 use rustyclaw_core::gateway::protocol;
 use rustyclaw_core::ui::{ChatMessage, ConnectionStatus, ThreadInfo};
 use rustyclaw_core::user_prompt_types::UserPrompt;
-use rustyclaw_view::{PromptAttachment, SecretsDialogData};
+use rustyclaw_view::{PromptAttachment, SecretsDialogData, ToolApprovalData};
 use rustyclaw_view::{chrono, uuid};
 
 /// UI theme preference.
@@ -96,8 +96,8 @@ pub struct AppState {
     /// Active UI theme.
     pub theme: Theme,
 
-    /// Pending tool approval request (id, name, arguments).
-    pub pending_tool_approval: Option<(String, String, String)>,
+    /// Pending tool approval request.
+    pub pending_tool_approval: Option<ToolApprovalData>,
 
     /// Pending user prompt from the agent.
     pub pending_user_prompt: Option<UserPrompt>,
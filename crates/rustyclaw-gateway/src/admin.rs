@@ -2,8 +2,13 @@
 //!
 //! Handles the client frames that mutate gateway/runtime configuration:
 //! `Reload` (re-read config from disk), `ModelSwitch` (change active provider/
-//! model), `SetAgentName`, and `SetWorkingDirectory`. Each updates the relevant
-//! shared state and, where appropriate, streams a status frame back.
+//! model), `SetAgentName`, `SetWorkingDirectory`, and `ToolPermissionSet`.
+//! Each updates the relevant shared state and, where appropriate, streams a
+//! status frame back.  Reloads and model switches are also broadcast to the
+//! other connections.  `SessionModel` is the exception: it resolves a model
+//! for the calling connection only and touches no shared state, and
+//! `SandboxPolicyRequest` only reports the sandbox and tool permissions.
+//! Both model frames are refused for models outside `allowed_models`.
 
 use std::sync::Arc;
 
@@ -12,12 +17,18 @@ use tracing::debug;
 
 use rustyclaw_core::config::{Config, ModelProvider};
 use rustyclaw_core::gateway::protocol;
-use rustyclaw_core::gateway::protocol::server::send_reload_result;
-use rustyclaw_core::gateway::{ModelContext, StatusType, transport};
+use rustyclaw_core::gateway::protocol::frames::{SandboxPolicyDto, ToolPermissionDto};
+use rustyclaw_core::gateway::protocol::server::{send_frame, send_reload_result};
+use rustyclaw_core::gateway::{
+    CopilotSession, ErrorCode, ModelContext, ServerFrame, ServerFrameType, ServerPayload,
+    StatusType, transport,
+};
 use rustyclaw_core::providers as crate_providers;
+use rustyclaw_core::secrets::AccessContext;
 use rustyclaw_core::tools;
 
 use crate::session::init_copilot_session;
+use crate::status_broadcast;
 use crate::{SharedConfig, SharedCopilotSession, SharedModelCtx, SharedModelRegistry, SharedVault};
 
 /// Handle a `Reload`: re-read config from disk and refresh model/session state.
@@ -29,6 +40,7 @@ pub(crate) async fn handle_reload(
     shared_model_ctx: &SharedModelCtx,
     shared_copilot_session: &SharedCopilotSession,
     model_registry: &SharedModelRegistry,
+    connection_id: u64,
 ) -> Result<()> {
     let settings_dir = config.settings_dir.clone();
     let config_path = settings_dir.join("config.toml");
@@ -84,6 +96,9 @@ pub(crate) async fn handle_reload(
                 }
             }
 
+            // Limits read outside the shared config.
+            crate_providers::init_provider_timeouts(&new_config.provider_timeouts);
+
             {
                 let mut cfg = shared_config.write().await;
                 *cfg = new_config;
@@ -99,11 +114,22 @@ pub(crate) async fn handle_reload(
                 let display = crate_providers::display_name_for_provider(&ctx.provider);
                 let detail = format!("{} / {} (reloaded)", display, ctx.model);
                 protocol::server::send_status(writer, StatusType::ModelConfigured, &detail).await?;
+                status_broadcast::publish(Some(connection_id), StatusType::ModelConfigured, detail);
+            } else {
+                status_broadcast::publish(
+                    Some(connection_id),
+                    StatusType::NoModel,
+                    "Config reloaded — no model configured",
+                );
             }
         }
         Err(e) => {
-            protocol::server::send_error(writer, &format!("Failed to reload config: {}", e))
-                .await?;
+            protocol::server::send_error(
+                writer,
+                ErrorCode::ConfigError,
+                &format!("Failed to reload config: {}", e),
+            )
+            .await?;
         }
     }
     Ok(())
@@ -118,16 +144,23 @@ pub(crate) async fn handle_model_switch(
     shared_copilot_session: &SharedCopilotSession,
     provider: String,
     model: String,
+    connection_id: u64,
 ) -> Result<()> {
     debug!("Model switch request: {} / {}", provider, model);
+    let refusal = disallowed_model(&*shared_config.read().await, &provider, &model);
+    if let Some(message) = refusal {
+        send_reload_result(writer, false, &provider, &model, Some(&message)).await?;
+        return Ok(());
+    }
     let base_url = crate_providers::base_url_for_provider(&provider)
         .unwrap_or("")
         .to_string();
     let api_key = {
         let key_name = crate_providers::secret_key_for_provider(&provider);
         if let Some(name) = key_name {
+            let ctx = AccessContext::approved(format!("provider:{}", provider));
             let mut v = vault.lock().await;
-            v.get_secret(name, true)
+            v.get_secret_with(name, &ctx)
                 .ok()
                 .flatten()
                 .or_else(|| std::env::var(name).ok())
@@ -170,9 +203,145 @@ pub(crate) async fn handle_model_switch(
     send_reload_result(writer, true, &provider, &model, None).await?;
     let detail = format!("{} / {}", display, model);
     protocol::server::send_status(writer, StatusType::ModelConfigured, &detail).await?;
+    status_broadcast::publish(Some(connection_id), StatusType::ModelConfigured, detail);
     Ok(())
 }
 
+/// Why `allowed_models` keeps clients off `provider`/`model`, or `None`
+/// when they may use it.
+fn disallowed_model(config: &Config, provider: &str, model: &str) -> Option<String> {
+    if config.model_allowed(provider, model) {
+        return None;
+    }
+    Some(format!(
+        "Model '{}' from {} is not allowed on this gateway. Allowed models: {}",
+        model,
+        provider,
+        config.allowed_models.join(", ")
+    ))
+}
+
+/// A provider/model one connection uses instead of the configured model.
+pub(crate) struct SessionModel {
+    pub ctx: Arc<ModelContext>,
+    pub copilot: Option<Arc<CopilotSession>>,
+}
+
+/// Handle a `SessionModel`: resolve credentials for `provider`/`model` from
+/// the vault and return the context for this connection's chats.
+///
+/// Nothing is persisted or broadcast.  When the provider is unknown or its
+/// credentials are missing, an error frame says so and `None` is returned,
+/// leaving the connection on its previous model.
+pub(crate) async fn handle_session_model(
+    writer: &mut dyn transport::TransportWriter,
+    vault: &SharedVault,
+    config: &Config,
+    provider: String,
+    model: String,
+) -> Result<Option<SessionModel>> {
+    debug!("Session model request: {} / {}", provider, model);
+    let Some(def) = crate_providers::provider_by_id(&provider) else {
+        let message = format!(
+            "Unknown provider '{}'. Known providers: {}",
+            provider,
+            crate_providers::provider_ids().join(", ")
+        );
+        protocol::server::send_error(writer, ErrorCode::InvalidRequest, &message).await?;
+        return Ok(None);
+    };
+    if model.trim().is_empty() {
+        let message = format!("No model given for {}", def.display);
+        protocol::server::send_error(writer, ErrorCode::InvalidRequest, &message).await?;
+        return Ok(None);
+    }
+    if let Some(message) = disallowed_model(config, &provider, &model) {
+        protocol::server::send_error(writer, ErrorCode::InvalidRequest, &message).await?;
+        return Ok(None);
+    }
+
+    // A configured endpoint for the same provider (e.g. `custom`) still applies.
+    let base_url = config
+        .model
+        .as_ref()
+        .filter(|m| m.provider == provider)
+        .and_then(|m| m.base_url.clone())
+        .or_else(|| def.base_url.map(String::from));
+    let Some(base_url) = base_url else {
+        let message = format!(
+            "No endpoint for {}: set base_url in [model] to use it",
+            def.display
+        );
+        protocol::server::send_error(writer, ErrorCode::ConfigError, &message).await?;
+        return Ok(None);
+    };
+
+    let (api_key, vault_locked) = match def.secret_key {
+        Some(name) => {
+            let ctx = AccessContext::approved(format!("provider:{}", provider));
+            let mut v = vault.lock().await;
+            let key = v
+                .get_secret_with(name, &ctx)
+                .ok()
+                .flatten()
+                .or_else(|| std::env::var(name).ok());
+            (key, v.is_locked())
+        }
+        None => (None, false),
+    };
+    let key_required = def.secret_key.is_some()
+        && !matches!(
+            def.auth_method,
+            crate_providers::AuthMethod::OptionalApiKey | crate_providers::AuthMethod::None
+        );
+    if key_required && api_key.is_none() {
+        let key_name = def.secret_key.unwrap_or_default();
+        let (code, message) = if vault_locked {
+            (
+                ErrorCode::VaultLocked,
+                format!(
+                    "Cannot use {}: the vault is locked, unlock it to read {}",
+                    def.display, key_name
+                ),
+            )
+        } else {
+            (
+                ErrorCode::ConfigError,
+                format!(
+                    "Cannot use {}: no credentials found. Store the key as {} in the vault.",
+                    def.display, key_name
+                ),
+            )
+        };
+        protocol::server::send_error(writer, code, &message).await?;
+        return Ok(None);
+    }
+
+    let copilot = match init_copilot_session(&provider, api_key.as_deref(), vault).await {
+        Some(session) => Some(session),
+        None if crate_providers::needs_copilot_session(&provider) => api_key
+            .clone()
+            .map(|key| Arc::new(CopilotSession::new(key))),
+        None => None,
+    };
+
+    let detail = format!("{} / {} (this session)", def.display, model);
+    protocol::server::send_status(writer, StatusType::ModelConfigured, &detail).await?;
+    if api_key.is_some() {
+        let detail = format!("{} API key loaded", def.display);
+        protocol::server::send_status(writer, StatusType::CredentialsLoaded, &detail).await?;
+    }
+    Ok(Some(SessionModel {
+        ctx: Arc::new(ModelContext {
+            provider,
+            model,
+            base_url,
+            api_key,
+        }),
+        copilot,
+    }))
+}
+
 /// Handle a `SetAgentName`: update the agent name in config and shared state.
 pub(crate) async fn handle_set_agent_name(
     config: &mut Config,
@@ -188,6 +357,99 @@ pub(crate) async fn handle_set_agent_name(
     config.agent_name = name;
 }
 
+/// Every built-in tool with its permission from `config`.
+fn tool_permission_list(config: &Config) -> Vec<ToolPermissionDto> {
+    tools::all_tool_names()
+        .into_iter()
+        .map(|name| {
+            let perm = config
+                .tool_permissions
+                .get(name)
+                .cloned()
+                .unwrap_or_default();
+            ToolPermissionDto::new(name, &perm)
+        })
+        .collect()
+}
+
+async fn send_tool_permissions(
+    writer: &mut dyn transport::TransportWriter,
+    config: &Config,
+    ok: bool,
+    message: Option<String>,
+) -> Result<()> {
+    let frame = ServerFrame {
+        frame_type: ServerFrameType::ToolPermissionsResult,
+        payload: ServerPayload::ToolPermissionsResult {
+            ok,
+            tools: tool_permission_list(config),
+            message,
+        },
+    };
+    send_frame(writer, &frame).await
+}
+
+/// Handle a `ToolPermissionsRequest`: send every tool with its permission.
+pub(crate) async fn handle_tool_permissions_request(
+    writer: &mut dyn transport::TransportWriter,
+    shared_config: &SharedConfig,
+) -> Result<()> {
+    let cfg = shared_config.read().await;
+    send_tool_permissions(writer, &cfg, true, None).await
+}
+
+/// Handle a `SandboxPolicyRequest`: send the sandbox tools run under and
+/// every tool's permission, so a remote user can see what the agent may
+/// touch.
+pub(crate) async fn handle_sandbox_policy_request(
+    writer: &mut dyn transport::TransportWriter,
+    shared_config: &SharedConfig,
+) -> Result<()> {
+    let tools = tool_permission_list(&*shared_config.read().await);
+    let frame = ServerFrame {
+        frame_type: ServerFrameType::SandboxPolicyResult,
+        payload: ServerPayload::SandboxPolicyResult {
+            sandbox: SandboxPolicyDto::new(tools::sandbox()),
+            tools,
+        },
+    };
+    send_frame(writer, &frame).await
+}
+
+/// Handle a `ToolPermissionSet`: update the permission in shared state and
+/// on disk, then reply with the updated list.  The tool loop reads
+/// permissions from the shared config on every round, so the change applies
+/// to requests already in flight.
+pub(crate) async fn handle_tool_permission_set(
+    writer: &mut dyn transport::TransportWriter,
+    config: &mut Config,
+    shared_config: &SharedConfig,
+    tool: String,
+    permission: String,
+    skills: Vec<String>,
+) -> Result<()> {
+    debug!("Tool permission change: {} -> {}", tool, permission);
+    let error = if !tools::all_tool_names().contains(&tool.as_str()) {
+        Some(format!("Unknown tool: {}", tool))
+    } else if let Some(perm) = tools::ToolPermission::from_kind(&permission, skills) {
+        let mut cfg = shared_config.write().await;
+        cfg.tool_permissions.insert(tool.clone(), perm.clone());
+        let saved = cfg.save(None);
+        drop(cfg);
+        config.tool_permissions.insert(tool, perm);
+        saved
+            .err()
+            .map(|e| format!("Permission applied but not saved to config: {}", e))
+    } else {
+        Some(format!(
+            "Unknown permission: {} (use allow, deny, ask or skill_only)",
+            permission
+        ))
+    };
+    let cfg = shared_config.read().await;
+    send_tool_permissions(writer, &cfg, error.is_none(), error).await
+}
+
 /// Handle a `SetWorkingDirectory`: repoint the workspace and re-register the
 /// sandbox so tool access controls apply to the new location.
 pub(crate) fn handle_set_working_directory(config: &mut Config, path: String) {
@@ -204,3 +466,116 @@ pub(crate) fn handle_set_working_directory(config: &mut Config, path: String) {
         config.sandbox.deny_paths.clone(),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::secrets::SecretsManager;
+    use std::time::Duration;
+    use tokio::sync::{Mutex, RwLock};
+
+    use crate::test_support::CollectWriter;
+
+    #[tokio::test]
+    async fn test_session_model_respects_allowed_models() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault: SharedVault = Arc::new(Mutex::new(SecretsManager::new(tmp.path())));
+        let config = Config {
+            allowed_models: vec!["ollama/llama3.1".into(), "anthropic/*".into()],
+            ..Config::default()
+        };
+
+        let mut writer = CollectWriter(Vec::new());
+        let resolved = handle_session_model(
+            &mut writer,
+            &vault,
+            &config,
+            "ollama".into(),
+            "llama3.1".into(),
+        )
+        .await
+        .unwrap()
+        .expect("allowed model resolves");
+        assert_eq!(resolved.ctx.model, "llama3.1");
+        assert!(
+            !writer
+                .0
+                .iter()
+                .any(|f| matches!(f.payload, ServerPayload::Error { .. }))
+        );
+
+        let mut writer = CollectWriter(Vec::new());
+        let refused = handle_session_model(
+            &mut writer,
+            &vault,
+            &config,
+            "ollama".into(),
+            "deepseek-coder".into(),
+        )
+        .await
+        .unwrap();
+        assert!(refused.is_none());
+        let Some(ServerPayload::Error { message, code, .. }) =
+            writer.0.first().map(|f| f.payload.clone())
+        else {
+            panic!("expected an error frame, got {:?}", writer.0);
+        };
+        assert_eq!(code, ErrorCode::InvalidRequest);
+        assert!(message.contains("not allowed"), "{}", message);
+        assert!(message.contains("ollama/llama3.1"), "{}", message);
+
+        // Provider wildcards, and an empty list allows anything.
+        assert!(config.model_allowed("anthropic", "claude-sonnet-4"));
+        assert!(!config.model_allowed("openai", "gpt-4o"));
+        assert!(Config::default().model_allowed("openai", "gpt-4o"));
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_provider_timeouts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = Config {
+            settings_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        std::fs::write(
+            tmp.path().join("config.toml"),
+            format!(
+                "settings_dir = {:?}\n[provider_timeouts.lmstudio]\nrequest_timeout_secs = 42\n",
+                tmp.path().display().to_string()
+            ),
+        )
+        .unwrap();
+        let vault: SharedVault = Arc::new(Mutex::new(SecretsManager::new(
+            tmp.path().join("credentials"),
+        )));
+        let shared_config: SharedConfig = Arc::new(RwLock::new(config.clone()));
+        let shared_model_ctx: SharedModelCtx = Arc::new(RwLock::new(None));
+        let copilot: SharedCopilotSession = Arc::new(RwLock::new(None));
+        let registry = rustyclaw_core::models::create_model_registry();
+
+        let mut writer = CollectWriter(Vec::new());
+        handle_reload(
+            &mut writer,
+            &config,
+            &vault,
+            &shared_config,
+            &shared_model_ctx,
+            &copilot,
+            &registry,
+            1,
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            writer
+                .0
+                .iter()
+                .any(|f| matches!(f.payload, ServerPayload::ReloadResult { ok: true, .. }))
+        );
+        assert_eq!(
+            crate_providers::timeouts_for("lmstudio").request,
+            Some(Duration::from_secs(42))
+        );
+    }
+}
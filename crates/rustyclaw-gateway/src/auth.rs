@@ -1,14 +1,16 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::Mutex;
 use tracing::{debug, instrument, warn};
 
-use rustyclaw_core::gateway::transport::TransportReader;
+use rustyclaw_core::config::Config;
+use rustyclaw_core::gateway::transport::{PeerInfo, TransportReader};
 use rustyclaw_core::gateway::{ClientFrameType, ClientPayload, CopilotSession};
 use rustyclaw_core::providers;
+use rustyclaw_core::secrets::GatewayToken;
 
 /// Maximum consecutive TOTP failures before lockout.
 const MAX_TOTP_FAILURES: u32 = 3;
@@ -112,6 +114,62 @@ pub async fn clear_rate_limit(limiter: &RateLimiter, ip: IpAddr) {
     map.remove(&ip);
 }
 
+/// The only tools a client may call, or `None` when it isn't restricted.
+///
+/// The token's own scope and any `client_tool_allowlists` entries for its
+/// token id or SSH key fingerprint all apply: a tool must be on every list
+/// that exists, so each one can only narrow the others.
+pub fn client_tool_allowlist(
+    config: &Config,
+    peer: &PeerInfo,
+    token: Option<&GatewayToken>,
+) -> Option<BTreeSet<String>> {
+    let configured = [
+        token.map(|t| t.id.as_str()),
+        peer.key_fingerprint.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|identity| config.client_tool_allowlists.get(identity));
+    token
+        .and_then(|t| t.tools.as_ref())
+        .into_iter()
+        .chain(configured)
+        .map(|tools| tools.iter().cloned().collect::<BTreeSet<_>>())
+        .reduce(|allowed, list| allowed.intersection(&list).cloned().collect())
+}
+
+/// The error sent back when a client restricted to a tool allowlist sends
+/// `frame_type`, or `None` when the frame is allowed.
+///
+/// Such a client may chat in its own threads, answer the prompts its turns
+/// raise, and pick a model for its connection.  Every other frame is
+/// refused, including ones added later: secrets, configuration, tool
+/// permissions, administration, and anything that reaches beyond the
+/// connection (other sessions' turns, tasks, transcripts or logs).
+pub fn scoped_client_rejection(frame_type: ClientFrameType) -> Option<String> {
+    use ClientFrameType as F;
+    let allowed = matches!(
+        frame_type,
+        F::Chat
+            | F::Cancel
+            | F::ToolApprovalResponse
+            | F::UserPromptResponse
+            | F::CredentialResponse
+            | F::DomQueryResponse
+            | F::TasksRequest
+            | F::ThreadCreate
+            | F::ThreadSwitch
+            | F::ThreadList
+            | F::ThreadClose
+            | F::ThreadRename
+            | F::ThreadHistoryRequest
+            | F::Capabilities
+            | F::SessionModel
+    );
+    (!allowed).then(|| format!("{frame_type:?} is not permitted for this client."))
+}
+
 /// Resolve the effective bearer token for an API call.
 ///
 /// For Copilot providers the raw API key is an OAuth token that must be
@@ -7,32 +7,45 @@
 //! [`dispatch_text_message`](crate::dispatch::dispatch_text_message) for the
 //! model/tool loop.
 
+use std::collections::BTreeSet;
 use std::sync::Arc;
 
 use anyhow::Result;
 use tokio::sync::Mutex;
-use tracing::warn;
+use tracing::{debug, instrument, warn};
 
 use rustyclaw_core::config::Config;
 use rustyclaw_core::gateway::{
-    ChatMessage, ChatRequest, ScopedTransportWriter, ServerFrame, ServerFrameType, ServerPayload,
-    transport,
+    Capabilities, ChatMessage, ChatRequest, ErrorCode, NegotiatedWriter, SamplingParams,
+    ScopedTransportWriter, ServerFrame, ServerFrameType, ServerPayload, transport,
 };
+use rustyclaw_core::observability::request_ids;
+use rustyclaw_core::providers::ResponseCache;
+use rustyclaw_core::threads::HistoryStore;
 
+use crate::admin::SessionModel;
 use crate::dispatch::dispatch_text_message;
 use crate::thread_updates::{send_thread_messages_update, send_threads_update};
+use crate::tool_approval::SharedApprovals;
+use crate::turn_resume::TurnRecorder;
+use crate::vault_relock::UnlockRelay;
 use crate::{
     SharedConfig, SharedCopilotSession, SharedModelCtx, SharedObserver, SharedSkillManager,
-    SharedTaskManager, SharedVault, ToolCancelFlag, providers, system_prompt,
+    SharedTaskManager, SharedVault, ToolCancelFlag, helpers, providers, system_prompt,
 };
 use protocol::server::send_frame;
 use rustyclaw_core::gateway::protocol;
 
 /// Handle a client `Chat` frame: bookkeeping, context assembly, dispatch.
+///
+/// Runs in a `request` span, the root of a `--trace` request trace.
 #[allow(clippy::too_many_arguments)]
+#[instrument(name = "request", skip_all, fields(stream_id))]
 pub(crate) async fn handle_chat_frame(
     http: &reqwest::Client,
     messages: Vec<ChatMessage>,
+    sampling: SamplingParams,
+    system_prefix: Option<String>,
     stream_id: u64,
     writer: &mut dyn transport::TransportWriter,
     config: &Config,
@@ -44,7 +57,8 @@ pub(crate) async fn handle_chat_frame(
     shared_config: &SharedConfig,
     shared_model_ctx: &SharedModelCtx,
     shared_copilot_session: &SharedCopilotSession,
-    approval_rx: &Arc<Mutex<tokio::sync::mpsc::Receiver<(String, bool)>>>,
+    session_model: Option<&SessionModel>,
+    approval_rx: &SharedApprovals,
     user_prompt_rx: &Arc<
         Mutex<
             tokio::sync::mpsc::Receiver<(
@@ -56,9 +70,21 @@ pub(crate) async fn handle_chat_frame(
     >,
     credential_rx: &Arc<Mutex<tokio::sync::mpsc::Receiver<(String, bool, Option<String>)>>>,
     dom_query_rx: &Arc<Mutex<tokio::sync::mpsc::Receiver<(String, String, bool)>>>,
+    unlock_relay: &UnlockRelay,
     thread_mgr: &mut rustyclaw_core::threads::ThreadManager,
-    threads_path: &std::path::Path,
+    history: &dyn HistoryStore,
+    response_cache: &mut ResponseCache,
+    capabilities: &Capabilities,
+    tool_allowlist: Option<&BTreeSet<String>>,
 ) -> Result<()> {
+    // Open the request's stream with the IDs it is logged under.
+    if let Some(ids) = request_ids::current() {
+        let mut scoped = ScopedTransportWriter::new(writer, stream_id);
+        let mut negotiated = NegotiatedWriter::new(&mut scoped, capabilities);
+        protocol::server::send_request_start(&mut negotiated, &ids).await?;
+    }
+    debug!(messages = messages.len(), "Chat request");
+
     // Check for auto-switch: find better matching thread
     if let Some(last_user) = messages.iter().rev().find(|m| m.role == "user") {
         if let Some(better_thread_id) = thread_mgr.find_best_match(&last_user.content) {
@@ -113,8 +139,12 @@ pub(crate) async fn handle_chat_frame(
             }
         }
     }
-    if did_append_user_message && let Err(e) = thread_mgr.save_to_file(threads_path) {
-        warn!(error = %e, path = ?threads_path, "Failed to persist user message to thread history");
+    if did_append_user_message && let Err(e) = history.save(thread_mgr) {
+        warn!(
+            error = %e,
+            store = history.name(),
+            "Failed to persist user message to thread history"
+        );
     }
     if did_auto_label {
         send_threads_update(writer, thread_mgr, task_mgr, None).await?;
@@ -135,11 +165,21 @@ pub(crate) async fn handle_chat_frame(
         send_thread_messages_update(writer, thread_id, thread_mgr).await?;
     }
 
-    // Re-read model_ctx from shared state for each dispatch
-    let current_model_ctx = shared_model_ctx.read().await.clone();
-    // Re-read copilot session from shared state
-    let copilot_session = shared_copilot_session.read().await.clone();
-    let workspace_dir = config.workspace_dir();
+    // A per-connection model wins; otherwise re-read the shared state for
+    // each dispatch so model switches take effect immediately.
+    let (current_model_ctx, copilot_session) = match session_model {
+        Some(session) => (Some(session.ctx.clone()), session.copilot.clone()),
+        None => (
+            shared_model_ctx.read().await.clone(),
+            shared_copilot_session.read().await.clone(),
+        ),
+    };
+    let workspace_dir = match active_thread_id {
+        Some(thread_id) => {
+            helpers::session_workspace_dir(config, &helpers::thread_session_key(thread_id.0))
+        }
+        None => config.workspace_dir(),
+    };
 
     // Ensure a system prompt is present. The TUI
     // sends the full conversation (including a
@@ -271,6 +311,24 @@ pub(crate) async fn handle_chat_frame(
         );
     }
 
+    // Point the model at this thread's own directory
+    let workspace_root = config.workspace_dir();
+    if workspace_dir != workspace_root
+        && messages_with_context
+            .first()
+            .is_some_and(|m| m.role == "system")
+    {
+        messages_with_context[0].content = format!(
+            "{}\n\n## Session Directory\n\
+            This conversation works in `{}`: relative paths and commands start \
+            there, apart from other conversations' files. The shared workspace \
+            root is `{}`; use absolute paths to reach files there.",
+            messages_with_context[0].content,
+            workspace_dir.display(),
+            workspace_root.display()
+        );
+    }
+
     // Inject relevant memory context from Steel Memory
     #[cfg(feature = "semantic-memory")]
     if !messages_with_context.is_empty() && messages_with_context[0].role == "system" {
@@ -311,10 +369,30 @@ pub(crate) async fn handle_chat_frame(
         provider: None,
         base_url: None,
         api_key: None,
+        sampling,
+        system_prefix,
     };
 
-    let mut stream_writer = ScopedTransportWriter::new(writer, stream_id);
-    if let Err(err) = dispatch_text_message(
+    // Buffer the turn so the client can resume it after a dropped
+    // connection.
+    let mut scoped_writer = ScopedTransportWriter::new(writer, stream_id);
+    let mut recorder = TurnRecorder::new(
+        &mut scoped_writer,
+        active_thread_id.map(|id| id.0),
+        capabilities,
+    );
+    let turn = recorder.turn();
+    let mut stream_writer = NegotiatedWriter::new(&mut recorder, capabilities);
+    if let Some(turn) = turn {
+        protocol::server::send_turn_started(
+            &mut stream_writer,
+            turn.session_id,
+            turn.turn_id,
+            false,
+        )
+        .await?;
+    }
+    let turn = dispatch_text_message(
         http,
         &chat_request,
         current_model_ctx.as_deref(),
@@ -332,21 +410,33 @@ pub(crate) async fn handle_chat_frame(
         user_prompt_rx,
         credential_rx,
         dom_query_rx,
+        unlock_relay,
         thread_mgr,
-        threads_path,
-    )
-    .await
-    {
+        history,
+        response_cache,
+        tool_allowlist,
+    );
+    // Tools that keep per-session state (`kv_store`, `env_var`) see the
+    // thread as their session.
+    let dispatched = match active_thread_id {
+        Some(thread_id) => {
+            rustyclaw_core::tools::session::scope(thread_id.0.to_string(), turn).await
+        }
+        None => turn.await,
+    };
+    if let Err(err) = dispatched {
         warn!(error = %err, error_debug = ?err, "Chat dispatch failed");
         let error_frame = ServerFrame {
             frame_type: ServerFrameType::Error,
             payload: ServerPayload::Error {
                 ok: false,
                 message: format!("{err:#}"),
+                code: ErrorCode::Internal,
             },
         };
         send_frame(&mut stream_writer, &error_frame).await?;
     }
+    stream_writer.flush().await?;
 
     Ok(())
 }
@@ -120,12 +120,19 @@ pub(crate) struct RunArgs {
     /// Run as SSH subsystem (stdio mode for OpenSSH integration)
     #[arg(long)]
     pub(crate) ssh_stdio: bool,
+    /// Also listen on this Unix domain socket (owner-only) for local clients
+    #[arg(long, value_name = "PATH")]
+    pub(crate) unix_socket: Option<std::path::PathBuf>,
     /// Path to SSH host key (default: ~/.rustyclaw/ssh_host_key)
     #[arg(long, value_name = "PATH")]
     pub(crate) ssh_host_key: Option<std::path::PathBuf>,
     /// Path to authorized_clients file (default: ~/.rustyclaw/authorized_clients)
     #[arg(long, value_name = "PATH")]
     pub(crate) ssh_authorized_clients: Option<std::path::PathBuf>,
+    /// Write each request's spans as a Chrome trace under the logs dir
+    /// (same as `[request_trace] enabled = true`)
+    #[arg(long)]
+    pub(crate) trace: bool,
 }
 
 impl Default for RunArgs {
@@ -143,8 +150,10 @@ impl Default for RunArgs {
             tls_key: None,
             ssh_listen: None,
             ssh_stdio: false,
+            unix_socket: None,
             ssh_host_key: None,
             ssh_authorized_clients: None,
+            trace: false,
         }
     }
 }
@@ -0,0 +1,230 @@
+//! The gateway's cap on concurrent client connections.
+//!
+//! Every accepted transport takes a [`ConnectionSlot`] before the
+//! per-connection engine starts, and gives it back when the slot drops.
+//! At `gateway_max_connections` a new connection is either refused at once
+//! or, with `gateway_connection_overflow = "queue"`, held until a slot
+//! frees up or `gateway_connection_queue_secs` runs out.  A refused client
+//! gets a `ServerBusy` status before the connection closes.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::Notify;
+use tracing::warn;
+
+use rustyclaw_core::config::{Config, ConnectionOverflow};
+use rustyclaw_core::gateway::protocol;
+use rustyclaw_core::gateway::{StatusType, Transport};
+
+/// Counts open connections against the configured maximum.
+#[derive(Debug)]
+pub(crate) struct ConnectionLimit {
+    /// 0 for no limit.
+    max: u64,
+    overflow: ConnectionOverflow,
+    queue_timeout: Duration,
+    active: AtomicU64,
+    freed: Notify,
+}
+
+impl ConnectionLimit {
+    pub(crate) fn new(
+        max: usize,
+        overflow: ConnectionOverflow,
+        queue_timeout: Duration,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            max: max as u64,
+            overflow,
+            queue_timeout,
+            active: AtomicU64::new(0),
+            freed: Notify::new(),
+        })
+    }
+
+    pub(crate) fn from_config(config: &Config) -> Arc<Self> {
+        Self::new(
+            config.gateway_max_connections,
+            config.gateway_connection_overflow,
+            Duration::from_secs(config.gateway_connection_queue_secs),
+        )
+    }
+
+    /// Connections holding a slot right now.
+    pub(crate) fn active(&self) -> u64 {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Take a slot if one is free.
+    pub(crate) fn try_acquire(self: &Arc<Self>) -> Option<ConnectionSlot> {
+        self.active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (self.max == 0 || n < self.max).then_some(n + 1)
+            })
+            .ok()?;
+        Some(ConnectionSlot {
+            limit: self.clone(),
+        })
+    }
+
+    /// Take a slot, waiting for one in queue mode.  `None` if the
+    /// connection should be refused.
+    pub(crate) async fn acquire(self: &Arc<Self>) -> Option<ConnectionSlot> {
+        if self.overflow == ConnectionOverflow::Reject {
+            return self.try_acquire();
+        }
+        let wait = async {
+            loop {
+                // Register before checking, so a slot freed in between
+                // still wakes us.
+                let freed = self.freed.notified();
+                tokio::pin!(freed);
+                freed.as_mut().enable();
+                if let Some(slot) = self.try_acquire() {
+                    return slot;
+                }
+                freed.await;
+            }
+        };
+        tokio::time::timeout(self.queue_timeout, wait).await.ok()
+    }
+
+    /// Admit `transport`, or tell its client the gateway is busy and close
+    /// it.
+    pub(crate) async fn admit(
+        self: &Arc<Self>,
+        transport: Box<dyn Transport>,
+    ) -> Option<(Box<dyn Transport>, ConnectionSlot)> {
+        if let Some(slot) = self.acquire().await {
+            return Some((transport, slot));
+        }
+        let peer = transport.peer_info().clone();
+        warn!(
+            transport = %peer.transport_type,
+            user = ?peer.username,
+            max = self.max,
+            "Connection refused: gateway at its connection limit"
+        );
+        if let Err(e) = self.refuse(transport).await {
+            warn!(error = %e, "Failed to notify refused connection");
+        }
+        None
+    }
+
+    async fn refuse(&self, transport: Box<dyn Transport>) -> Result<()> {
+        let (_reader, mut writer) = transport.into_split();
+        let detail = format!(
+            "Gateway is at its connection limit ({}) — try again later",
+            self.max
+        );
+        protocol::server::send_status(&mut *writer, StatusType::ServerBusy, &detail).await?;
+        writer.close().await
+    }
+}
+
+/// One connection's place under the limit, released on drop.
+#[derive(Debug)]
+pub(crate) struct ConnectionSlot {
+    limit: Arc<ConnectionLimit>,
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.limit.active.fetch_sub(1, Ordering::AcqRel);
+        self.limit.freed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::gateway::{ServerPayload, SshConnection};
+
+    use crate::unix_socket::UnixSocketListener;
+
+    #[test]
+    fn test_slots_are_counted_and_released() {
+        let limit = ConnectionLimit::new(2, ConnectionOverflow::Reject, Duration::ZERO);
+        let first = limit.try_acquire().unwrap();
+        let second = limit.try_acquire().unwrap();
+        assert_eq!(limit.active(), 2);
+        assert!(limit.try_acquire().is_none());
+
+        drop(first);
+        assert_eq!(limit.active(), 1);
+        let _third = limit.try_acquire().unwrap();
+        drop(second);
+        assert_eq!(limit.active(), 1);
+    }
+
+    #[test]
+    fn test_zero_means_unlimited() {
+        let limit = ConnectionLimit::new(0, ConnectionOverflow::Reject, Duration::ZERO);
+        let slots: Vec<_> = (0..1000).map(|_| limit.try_acquire().unwrap()).collect();
+        assert_eq!(limit.active(), 1000);
+        drop(slots);
+        assert_eq!(limit.active(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_second_connection_refused_at_limit_of_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gateway.sock");
+        let listener = UnixSocketListener::bind(&path).unwrap();
+        let url = format!("unix:{}", path.display());
+        let limit = ConnectionLimit::new(1, ConnectionOverflow::Reject, Duration::ZERO);
+
+        let (_first_conn, _first_writer, _first_reader) =
+            SshConnection::connect(&url).await.unwrap();
+        let first = limit.admit(listener.accept().await.unwrap()).await;
+        assert!(first.is_some());
+
+        let (_conn, _writer, mut reader) = SshConnection::connect(&url).await.unwrap();
+        let second = limit.admit(listener.accept().await.unwrap()).await;
+        assert!(second.is_none());
+        assert_eq!(limit.active(), 1);
+
+        let frame = reader.recv_wire().await.unwrap().unwrap().frame;
+        match frame.payload {
+            ServerPayload::Status { status, detail } => {
+                assert_eq!(status, StatusType::ServerBusy);
+                assert!(detail.contains("connection limit (1)"), "{}", detail);
+            }
+            other => panic!("expected a status, got {other:?}"),
+        }
+        assert!(reader.recv_wire().await.unwrap().is_none());
+
+        // Once the first connection ends, a new one gets in.
+        drop(first);
+        let (_conn, _writer, _reader) = SshConnection::connect(&url).await.unwrap();
+        let third = limit.admit(listener.accept().await.unwrap()).await;
+        assert!(third.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_queued_connection_waits_for_a_free_slot() {
+        let limit = ConnectionLimit::new(1, ConnectionOverflow::Queue, Duration::from_secs(5));
+        let held = limit.try_acquire().unwrap();
+
+        let waiter = tokio::spawn({
+            let limit = limit.clone();
+            async move { limit.acquire().await.is_some() }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+
+        drop(held);
+        assert!(waiter.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_queued_connection_gives_up_after_timeout() {
+        let limit = ConnectionLimit::new(1, ConnectionOverflow::Queue, Duration::from_millis(50));
+        let _held = limit.try_acquire().unwrap();
+        assert!(limit.acquire().await.is_none());
+        assert_eq!(limit.active(), 1);
+    }
+}
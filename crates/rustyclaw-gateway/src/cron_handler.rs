@@ -0,0 +1,68 @@
+//! Delivery of scheduled channel messages.
+//!
+//! The `message` tool's `schedule` action stores one-shot cron jobs in the
+//! `.cron` store of whichever workspace the agent was working in.  This task
+//! polls the stores of the configured workspace and of every registered
+//! project, and sends whatever is due.  Jobs live on disk, so anything
+//! scheduled before a restart is picked up on the next poll.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use rustyclaw_core::config::Config;
+use rustyclaw_core::projects::ProjectManager;
+use rustyclaw_core::tools;
+
+use crate::{SharedTaskManager, task_registry};
+
+/// How often the job stores are checked for due messages.
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Spawn the scheduled-message poller; it stops when `cancel` fires.
+/// Runs that send anything are recorded as cron tasks in `task_mgr`.
+pub fn spawn_message_scheduler(
+    config: &Config,
+    task_mgr: SharedTaskManager,
+    cancel: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let workspace_dir = config.workspace_dir();
+    let projects_path = config.sessions_dir().join("projects.json");
+
+    tokio::spawn(async move {
+        loop {
+            // Re-read the project registry each round so projects created
+            // while the gateway runs are covered too.
+            let mut cron_dirs = BTreeSet::from([workspace_dir.join(".cron")]);
+            cron_dirs.extend(
+                ProjectManager::load_or_new(&projects_path)
+                    .list()
+                    .into_iter()
+                    .map(|p| p.path.join(".cron")),
+            );
+
+            for dir in &cron_dirs {
+                poll_store(dir, &task_mgr).await;
+            }
+
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+        }
+    })
+}
+
+async fn poll_store(cron_dir: &Path, task_mgr: &SharedTaskManager) {
+    match tools::dispatch_due_messages(cron_dir).await {
+        Ok(0) => {}
+        Ok(n) => {
+            debug!(count = n, dir = %cron_dir.display(), "Dispatched scheduled messages");
+            task_registry::record_cron_run(task_mgr, &cron_dir.display().to_string(), n).await;
+        }
+        Err(e) => warn!(error = %e, dir = %cron_dir.display(), "Scheduled message poll failed"),
+    }
+}
@@ -4,24 +4,34 @@
 //! and delegates the model/tool loop to [`dispatch_text_message`].
 
 use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashMap};
 use std::future::Future;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use tokio::sync::Mutex;
-use tracing::{debug, trace};
+use tracing::{Instrument, Span, debug, info_span, trace};
 
 use rustyclaw_core::gateway::{
-    ChatMessage, ChatRequest, CopilotSession, ModelContext, ModelResponse, ServerFrame,
-    ServerFrameType, ServerPayload, ToolCallResult, protocol, transport,
+    ChatMessage, ChatRequest, CopilotSession, ModelContext, ModelResponse, ParsedToolCall,
+    ServerFrame, ServerFrameType, ServerPayload, ToolCallResult, protocol, transport,
 };
 use rustyclaw_core::observability::ObserverEvent;
+use rustyclaw_core::providers::{
+    ResponseCache, SafetyRefusal, is_cacheable, is_refusal_finish, request_key,
+};
+use rustyclaw_core::secrets::AccessContext;
+use rustyclaw_core::threads::HistoryStore;
 use rustyclaw_core::tools;
 
 use crate::thread_updates::{send_thread_messages_update, send_threads_update};
+use crate::tool_approval::{self, SharedApprovals};
+use crate::turn_budget::TurnBudget;
+use crate::vault_relock::{self, UnlockRelay};
 use crate::{
     COMPACTION_THRESHOLD, SharedConfig, SharedCopilotSession, SharedObserver, SharedSkillManager,
-    SharedTaskManager, SharedVault, ToolCancelFlag, auth, errors, helpers, providers,
-    tool_executor,
+    SharedTaskManager, SharedVault, ToolCancelFlag, auth, command_wrapper, errors, helpers,
+    proofread_handler, providers, ssh_handler, summarize_handler, task_registry, tool_executor,
+    translate_handler,
 };
 use protocol::server::send_frame;
 
@@ -259,19 +269,17 @@ async fn execute_dom_query(
 /// Implements an agentic tool loop: the model is called, and if it
 /// requests tool calls, the gateway executes them locally and feeds
 /// the results back into the conversation, repeating until the model
-/// produces a final text response (or a safety limit is hit).
+/// produces a final text response (or a [`TurnBudget`] limit is hit).
 ///
 /// The `tool_cancel` flag can be set by another task to interrupt the
 /// tool loop gracefully.
 async fn await_model_with_cancel<F>(
     fut: F,
     tool_cancel: &ToolCancelFlag,
-    timeout: std::time::Duration,
 ) -> Result<Option<ModelResponse>>
 where
     F: Future<Output = Result<ModelResponse>>,
 {
-    let deadline = tokio::time::Instant::now() + timeout;
     tokio::pin!(fut);
 
     loop {
@@ -279,14 +287,10 @@ where
             return Ok(None);
         }
 
-        let now = tokio::time::Instant::now();
-        if now >= deadline {
-            anyhow::bail!("Model request timed out after {}s", timeout.as_secs());
-        }
-
         // Poll both the model future and a short timer so cancel requests are
         // observed quickly even while waiting on provider/network latency.
-        let tick = std::time::Duration::from_millis(200).min(deadline - now);
+        // Timeouts are the provider call's own (`[provider_timeouts]`).
+        let tick = std::time::Duration::from_millis(200);
         tokio::select! {
             res = &mut fut => return res.map(Some),
             _ = tokio::time::sleep(tick) => {}
@@ -308,7 +312,7 @@ pub(crate) async fn dispatch_text_message(
     tool_cancel: &ToolCancelFlag,
     shared_config: &SharedConfig,
     shared_copilot_session: &SharedCopilotSession,
-    approval_rx: &Arc<Mutex<tokio::sync::mpsc::Receiver<(String, bool)>>>,
+    approval_rx: &SharedApprovals,
     user_prompt_rx: &Arc<
         Mutex<
             tokio::sync::mpsc::Receiver<(
@@ -320,17 +324,21 @@ pub(crate) async fn dispatch_text_message(
     >,
     credential_rx: &Arc<Mutex<tokio::sync::mpsc::Receiver<(String, bool, Option<String>)>>>,
     dom_query_rx: &Arc<Mutex<tokio::sync::mpsc::Receiver<(String, String, bool)>>>,
+    unlock_relay: &UnlockRelay,
     thread_mgr: &mut rustyclaw_core::threads::ThreadManager,
-    threads_path: &std::path::Path,
+    history: &dyn HistoryStore,
+    response_cache: &mut ResponseCache,
+    tool_allowlist: Option<&BTreeSet<String>>,
 ) -> Result<()> {
     let mut resolved = match providers::resolve_request(req.clone(), model_ctx) {
         Ok(r) => r,
-        Err(msg) => {
+        Err((code, msg)) => {
             let error_frame = ServerFrame {
                 frame_type: ServerFrameType::Error,
                 payload: ServerPayload::Error {
                     ok: false,
                     message: msg,
+                    code,
                 },
             };
             send_frame(writer, &error_frame)
@@ -347,8 +355,18 @@ pub(crate) async fn dispatch_text_message(
         if let Some(key_name) =
             rustyclaw_core::providers::secret_key_for_provider(&resolved.provider)
         {
+            // The vault may have been locked since the session started.
+            let optional = rustyclaw_core::providers::provider_by_id(&resolved.provider)
+                .map(|p| p.auth_method)
+                == Some(rustyclaw_core::providers::AuthMethod::OptionalApiKey);
+            if !optional {
+                let purpose = format!("the {} key is kept in it", key_name);
+                vault_relock::wait_for_unlock(writer, vault, unlock_relay, tool_cancel, &purpose)
+                    .await?;
+            }
+            let ctx = AccessContext::approved(format!("provider:{}", resolved.provider));
             let mut v = vault.lock().await;
-            if let Ok(Some(key)) = v.get_secret(key_name, true) {
+            if let Ok(Some(key)) = v.get_secret_with(key_name, &ctx) {
                 resolved.api_key = Some(key);
             }
         }
@@ -376,10 +394,11 @@ pub(crate) async fn dispatch_text_message(
     let mut original_api_key = resolved.api_key.clone();
 
     // ── Agentic tool loop ───────────────────────────────────────────
-    // No hard limit — the model will stop when it's done. The user can
-    // cancel by sending a {"type": "cancel"} message (e.g., pressing Esc).
-    // We use a very high limit as a safety net against infinite loops.
-    const MAX_TOOL_ROUNDS: usize = 500;
+    // The model stops when it's done, or the user cancels by sending a
+    // {"type": "cancel"} message (e.g., pressing Esc).  As a safety net
+    // against endless loops, `max_tool_iterations` and `max_turn_secs`
+    // bound the turn; hitting either is reported as a status, not as the
+    // model's answer.
     /// Maximum consecutive auto-continuations before giving up.
     /// Prevents infinite loops when the model keeps narrating intent
     /// but never actually makes tool calls.
@@ -403,15 +422,35 @@ pub(crate) async fn dispatch_text_message(
     // adapters/proxies can re-emit the same ID across turns.
     let mut seen_tool_ids = collect_existing_tool_ids(&resolved.messages);
 
+    // The skill whose SKILL.md the agent has read this turn; its
+    // `allow_tools` / `allow_paths` overlay applies to later tool calls.
+    let mut active_skill: Option<rustyclaw_core::skills::Skill> = None;
+
     // Memory flush controller - tracks whether we've flushed this conversation
     use rustyclaw_core::memory_flush::MemoryFlush;
-    let flush_config = {
+    let (flush_config, cache_config, thinking_budget, mut budget) = {
         let cfg = shared_config.read().await;
-        cfg.memory_flush.clone()
+        (
+            cfg.memory_flush.clone(),
+            cfg.prompt_cache.clone(),
+            cfg.thinking.budget(),
+            TurnBudget::from_config(&cfg),
+        )
     };
     let mut memory_flush = MemoryFlush::new(flush_config);
+    resolved.cache_prompt = cache_config.cache_control;
+    resolved.thinking_budget = thinking_budget;
+
+    loop {
+        // ── Wait out a global pause ─────────────────────────────────
+        let held = crate::pause_gate::hold_while_paused(
+            writer,
+            rustyclaw_core::agent_pause::global(),
+            tool_cancel,
+        )
+        .await?;
+        budget.add_pause(held);
 
-    for _round in 0..MAX_TOOL_ROUNDS {
         // ── Check for cancellation ──────────────────────────────────
         if tool_cancel.load(Ordering::Relaxed) {
             protocol::server::send_info(writer, "Tool loop cancelled by user.").await?;
@@ -419,6 +458,22 @@ pub(crate) async fn dispatch_text_message(
             return Ok(());
         }
 
+        // ── Check the turn budget ───────────────────────────────────
+        if let Err(stop) = budget.start_round() {
+            errors::handle(
+                stop,
+                None,
+                writer,
+                &mut resolved,
+                &mut original_api_key,
+                vault,
+                credential_rx,
+                tool_cancel,
+            )
+            .await?;
+            return Ok(());
+        }
+
         // Refresh the bearer token before each model call.
         // For Copilot providers, this ensures the session token is still valid.
         let effective_copilot = local_copilot.as_deref().or(copilot_session);
@@ -506,7 +561,7 @@ pub(crate) async fn dispatch_text_message(
             if let Some(thread) = thread_mgr.foreground_mut() {
                 thread.memory_flushed = true;
             }
-            let _ = thread_mgr.save_to_file(threads_path);
+            let _ = history.save(thread_mgr);
             flush_pending_resume = true;
             flushed_this_round = true;
 
@@ -533,10 +588,10 @@ pub(crate) async fn dispatch_text_message(
                     if let Some(thread) = thread_mgr.foreground_mut() {
                         thread.apply_compaction_keeping(outcome.summary, outcome.kept_recent);
                     }
-                    if let Err(e) = thread_mgr.save_to_file(threads_path) {
+                    if let Err(e) = history.save(thread_mgr) {
                         tracing::warn!(
                             error = %e,
-                            path = ?threads_path,
+                            store = history.name(),
                             "Failed to persist compaction summary to thread history"
                         );
                     }
@@ -559,9 +614,29 @@ pub(crate) async fn dispatch_text_message(
             }
         }
 
+        // ── Response cache ──────────────────────────────────────────
+        // An exact repeat of an earlier request on this connection is
+        // answered from memory. Google's text is sent after the call below,
+        // so only the streaming providers need the cached text replayed.
+        let cache_key = (cache_config.responses
+            && is_cacheable(&resolved, cache_config.allow_nondeterministic))
+        .then(|| request_key(&resolved));
+        let cached = cache_key.and_then(|key| response_cache.get(key));
+        let from_cache = cached.is_some();
+        if let Some(ref resp) = cached {
+            debug!(
+                provider = %resolved.provider,
+                model = %resolved.model,
+                "Reusing cached response for identical request"
+            );
+            if !resp.text.is_empty() && resolved.provider != "google" {
+                providers::send_chunk(writer, &resp.text).await?;
+            }
+        }
+
         // Record LLM request event for observability
         let request_start = std::time::Instant::now();
-        if let Some(obs) = observer {
+        if let Some(obs) = observer.filter(|_| !from_cache) {
             obs.record_event(&ObserverEvent::LlmRequest {
                 provider: resolved.provider.clone(),
                 model: resolved.model.clone(),
@@ -569,29 +644,39 @@ pub(crate) async fn dispatch_text_message(
             });
         }
 
-        let model_timeout = std::time::Duration::from_secs(180);
-        let result = if resolved.provider == "anthropic" {
+        let call_span = if from_cache {
+            Span::none()
+        } else {
+            info_span!(
+                "provider_call",
+                provider = %resolved.provider,
+                model = %resolved.model,
+            )
+        };
+        let result = if let Some(resp) = cached {
+            Ok(Some(resp))
+        } else if resolved.provider == "anthropic" {
             // Anthropic: use streaming mode with writer for real-time chunks.
-            // Still enforce timeout/cancel around the provider future.
+            // Still watch for cancel around the provider future.
             await_model_with_cancel(
                 providers::call_anthropic_with_tools(http, &resolved, Some(writer)),
                 tool_cancel,
-                model_timeout,
             )
+            .instrument(call_span)
             .await
         } else if resolved.provider == "google" {
             await_model_with_cancel(
                 providers::call_google_with_tools(http, &resolved),
                 tool_cancel,
-                model_timeout,
             )
+            .instrument(call_span)
             .await
         } else {
             await_model_with_cancel(
                 providers::call_openai_with_tools(http, &resolved, Some(writer)),
                 tool_cancel,
-                model_timeout,
             )
+            .instrument(call_span)
             .await
         };
 
@@ -601,20 +686,27 @@ pub(crate) async fn dispatch_text_message(
             Ok(_) => (true, None),
             Err(e) => (false, Some(format!("{e:#}"))),
         };
-        if let Some(obs) = observer {
+        let usage = result.as_ref().ok().and_then(|r| r.as_ref());
+        if let Some(obs) = observer.filter(|_| !from_cache) {
             obs.record_event(&ObserverEvent::LlmResponse {
                 provider: resolved.provider.clone(),
                 model: resolved.model.clone(),
                 duration: request_duration,
                 success,
                 error_message: error_msg,
-                input_tokens: None, // TODO: extract from response if available
-                output_tokens: None,
+                input_tokens: usage.and_then(|r| r.prompt_tokens),
+                output_tokens: usage.and_then(|r| r.completion_tokens),
             });
         }
 
         let mut model_resp = match result {
-            Ok(Some(r)) => r,
+            Ok(Some(r)) => {
+                let refused = r.finish_reason.as_deref().is_some_and(is_refusal_finish);
+                if let Some(key) = cache_key.filter(|_| !from_cache && !refused) {
+                    response_cache.insert(key, r.clone(), cache_config.max_entries);
+                }
+                r
+            }
             Ok(None) => {
                 match errors::handle(
                     errors::GatewayError::Cancelled,
@@ -757,7 +849,7 @@ pub(crate) async fn dispatch_text_message(
                     }
                     // Persist the final assistant turn so reconnecting
                     // clients see it via ThreadHistoryRequest.
-                    let _ = thread_mgr.save_to_file(threads_path);
+                    let _ = history.save(thread_mgr);
                     // Auto-ingest assistant response into Steel Memory
                     #[cfg(feature = "semantic-memory")]
                     {
@@ -793,6 +885,29 @@ pub(crate) async fn dispatch_text_message(
                     std::ops::ControlFlow::Continue(()) => continue,
                     std::ops::ControlFlow::Break(()) => return Ok(()),
                 }
+            } else if is_refusal_finish(finish_reason) {
+                let refusal = SafetyRefusal {
+                    provider: resolved.provider.clone(),
+                    signal: format!("finish_reason={}", finish_reason),
+                    category: None,
+                };
+                match errors::handle(
+                    errors::GatewayError::SafetyRefusal {
+                        provider: resolved.provider.clone(),
+                    },
+                    Some(anyhow::Error::new(refusal)),
+                    writer,
+                    &mut resolved,
+                    &mut original_api_key,
+                    vault,
+                    credential_rx,
+                    tool_cancel,
+                )
+                .await?
+                {
+                    std::ops::ControlFlow::Continue(()) => continue,
+                    std::ops::ControlFlow::Break(()) => return Ok(()),
+                }
             } else {
                 match errors::handle(
                     errors::GatewayError::UnexpectedFinish {
@@ -821,12 +936,84 @@ pub(crate) async fn dispatch_text_message(
         let mut tool_results: Vec<ToolCallResult> = Vec::new();
 
         // Snapshot current tool permissions (cheap clone of a HashMap).
-        let tool_permissions = {
+        let (tool_permissions, trusted_skills, max_parallel_tools, approval_session) = {
             let cfg = shared_config.read().await;
-            cfg.tool_permissions.clone()
+            (
+                cfg.tool_permissions.clone(),
+                cfg.trusted_skills.clone(),
+                cfg.max_parallel_tools,
+                cfg.tool_approval_session,
+            )
         };
 
-        for tc in &model_resp.tool_calls {
+        // Results of read-only calls run ahead together, by call index.
+        let mut prefetched: Vec<Option<(String, bool)>> = vec![None; model_resp.tool_calls.len()];
+
+        for (idx, tc) in model_resp.tool_calls.iter().enumerate() {
+            // ── Run the next stretch of read-only calls concurrently ──
+            // When a response asks for several side-effect-free tools in a
+            // row (three file reads, say) they run together, up to
+            // `max_parallel_tools`.  The stretch ends at the first call that
+            // isn't parallel-safe, so nothing overtakes an earlier write, and
+            // at calls that would activate a skill, whose overlay applies to
+            // the calls after them.  The rest of this loop still reports and
+            // records every call in order, taking these results instead of
+            // executing again.
+            if prefetched[idx].is_none()
+                && max_parallel_tools > 1
+                && !tool_cancel.load(Ordering::Relaxed)
+            {
+                let end = {
+                    let mgr = skill_mgr.lock().await;
+                    parallel_run_end(&model_resp.tool_calls, idx, |tc| {
+                        tools::is_parallel_safe_tool(&tc.name)
+                            && mgr
+                                .skill_activated_by(&tc.name, &tc.arguments, workspace_dir)
+                                .is_none()
+                            && matches!(
+                                resolve_permission(
+                                    tc,
+                                    &tool_permissions,
+                                    &trusted_skills,
+                                    tool_allowlist,
+                                    active_skill.as_ref(),
+                                    workspace_dir
+                                ),
+                                (tools::ToolPermission::Allow, None)
+                            )
+                    })
+                };
+                if end - idx > 1 {
+                    let held = crate::pause_gate::hold_while_paused(
+                        writer,
+                        rustyclaw_core::agent_pause::global(),
+                        tool_cancel,
+                    )
+                    .await?;
+                    budget.add_pause(held);
+                    debug!(
+                        calls = end - idx,
+                        max_parallel_tools, "Running tool calls concurrently"
+                    );
+                    let batch = &model_resp.tool_calls[idx..end];
+                    let results =
+                        tool_executor::run_concurrently(batch.len(), max_parallel_tools, |n| {
+                            tool_executor::execute_tool_by_type(
+                                &batch[n].name,
+                                &batch[n].arguments,
+                                workspace_dir,
+                                vault,
+                                skill_mgr,
+                                tool_cancel,
+                            )
+                        })
+                        .await;
+                    for (slot, result) in prefetched[idx..end].iter_mut().zip(results) {
+                        *slot = Some(result);
+                    }
+                }
+            }
+
             // Record tool call start event
             let tool_start = std::time::Instant::now();
             if let Some(obs) = observer {
@@ -840,9 +1027,49 @@ pub(crate) async fn dispatch_text_message(
             let args_str = serde_json::to_string(&tc.arguments).unwrap_or_default();
 
             // ── Permission check ────────────────────────────────────
-            let permission = tool_permissions.get(&tc.name).cloned().unwrap_or_default(); // default = Allow
+            let (permission, denial) = resolve_permission(
+                tc,
+                &tool_permissions,
+                &trusted_skills,
+                tool_allowlist,
+                active_skill.as_ref(),
+                workspace_dir,
+            );
 
-            let (output, is_error) = match permission {
+            // Wait for a relocked vault instead of failing the call.
+            if denial.is_none()
+                && tools::is_secrets_tool(&tc.name)
+                && matches!(
+                    permission,
+                    tools::ToolPermission::Allow | tools::ToolPermission::Ask
+                )
+            {
+                let purpose = format!("`{}` needs it", tc.name);
+                vault_relock::wait_for_unlock(writer, vault, unlock_relay, tool_cancel, &purpose)
+                    .await?;
+            }
+
+            // Track commands in the gateway-wide task list while they run.
+            let runs = denial.is_none()
+                && matches!(
+                    permission,
+                    tools::ToolPermission::Allow | tools::ToolPermission::Ask
+                );
+            let session_key = thread_mgr
+                .foreground_id()
+                .map(|id| helpers::thread_session_key(id.0));
+            let command_task = match session_key.as_deref() {
+                Some(key) if runs && command_wrapper::should_wrap_in_task(&tc.name) => {
+                    Some(command_wrapper::start_command_task(task_mgr, &tc.arguments, key).await)
+                }
+                _ => None,
+            };
+
+            let (output, mut is_error) = match permission {
+                _ if denial.is_some() => {
+                    protocol::server::send_tool_call(writer, &tc.id, &tc.name, &args_str).await?;
+                    (denial.unwrap_or_default(), true)
+                }
                 tools::ToolPermission::Deny => {
                     // Notify the client about the denied tool call.
                     protocol::server::send_tool_call(writer, &tc.id, &tc.name, &args_str).await?;
@@ -864,25 +1091,17 @@ pub(crate) async fn dispatch_text_message(
                     (msg, true)
                 }
                 tools::ToolPermission::Ask => {
-                    // Send approval request to the TUI and wait for response.
-                    protocol::server::send_tool_approval_request(
-                        writer, &tc.id, &tc.name, &args_str,
+                    // Ask the client, unless it approved this tool for the session.
+                    let approved = tool_approval::request_approval(
+                        writer,
+                        approval_rx,
+                        &tc.id,
+                        &tc.name,
+                        &tc.arguments,
+                        approval_session,
                     )
                     .await?;
 
-                    // Wait for the user's response (with timeout).
-                    let approved = {
-                        let mut rx = approval_rx.lock().await;
-                        match tokio::time::timeout(std::time::Duration::from_secs(120), rx.recv())
-                            .await
-                        {
-                            Ok(Some((id, approved))) if id == tc.id => approved,
-                            Ok(Some(_)) => false, // Mismatched ID — treat as denied
-                            Ok(None) => false,    // Channel closed
-                            Err(_) => false,      // Timeout
-                        }
-                    };
-
                     if !approved {
                         // Notify the client about the denied tool call.
                         protocol::server::send_tool_call(writer, &tc.id, &tc.name, &args_str)
@@ -899,6 +1118,68 @@ pub(crate) async fn dispatch_text_message(
                             execute_user_prompt(writer, &tc.id, &tc.arguments, user_prompt_rx).await
                         } else if tools::is_dom_query_tool(&tc.name) {
                             execute_dom_query(writer, &tc.id, &tc.arguments, dom_query_rx).await
+                        } else if translate_handler::is_translate_tool(&tc.name) {
+                            match translate_handler::execute_translate(
+                                http,
+                                &resolved,
+                                &tc.arguments,
+                                tool_cancel,
+                            )
+                            .await
+                            {
+                                Ok(text) => (text, false),
+                                Err(err) => (err, true),
+                            }
+                        } else if translate_handler::is_translate_file_tool(&tc.name) {
+                            match translate_handler::execute_translate_file(
+                                http,
+                                &resolved,
+                                &tc.arguments,
+                                workspace_dir,
+                                tool_cancel,
+                            )
+                            .await
+                            {
+                                Ok(text) => (text, false),
+                                Err(err) => (err, true),
+                            }
+                        } else if proofread_handler::is_proofread_tool(&tc.name) {
+                            match proofread_handler::execute_proofread(
+                                http,
+                                &resolved,
+                                &tc.arguments,
+                                workspace_dir,
+                                tool_cancel,
+                            )
+                            .await
+                            {
+                                Ok(text) => (text, false),
+                                Err(err) => (err, true),
+                            }
+                        } else if summarize_handler::is_summarize_tool(&tc.name) {
+                            match summarize_handler::execute_summarize(
+                                http,
+                                &resolved,
+                                &tc.arguments,
+                                tool_cancel,
+                            )
+                            .await
+                            {
+                                Ok(text) => (text, false),
+                                Err(err) => (err, true),
+                            }
+                        } else if ssh_handler::is_ssh_tool(&tc.name) {
+                            match ssh_handler::execute_ssh(
+                                shared_config,
+                                vault,
+                                &tc.arguments,
+                                tool_cancel,
+                            )
+                            .await
+                            {
+                                Ok(text) => (text, false),
+                                Err(err) => (err, true),
+                            }
                         } else {
                             tool_executor::execute_tool_by_type(
                                 &tc.name,
@@ -906,6 +1187,7 @@ pub(crate) async fn dispatch_text_message(
                                 workspace_dir,
                                 vault,
                                 skill_mgr,
+                                tool_cancel,
                             )
                             .await
                         }
@@ -915,11 +1197,75 @@ pub(crate) async fn dispatch_text_message(
                     // Notify the client about the tool call.
                     protocol::server::send_tool_call(writer, &tc.id, &tc.name, &args_str).await?;
 
-                    // Execute the tool.
-                    if tools::is_user_prompt_tool(&tc.name) {
+                    // Execute the tool (or take its concurrent result).
+                    if let Some(result) = prefetched[idx].take() {
+                        result
+                    } else if tools::is_user_prompt_tool(&tc.name) {
                         execute_user_prompt(writer, &tc.id, &tc.arguments, user_prompt_rx).await
                     } else if tools::is_dom_query_tool(&tc.name) {
                         execute_dom_query(writer, &tc.id, &tc.arguments, dom_query_rx).await
+                    } else if translate_handler::is_translate_tool(&tc.name) {
+                        match translate_handler::execute_translate(
+                            http,
+                            &resolved,
+                            &tc.arguments,
+                            tool_cancel,
+                        )
+                        .await
+                        {
+                            Ok(text) => (text, false),
+                            Err(err) => (err, true),
+                        }
+                    } else if translate_handler::is_translate_file_tool(&tc.name) {
+                        match translate_handler::execute_translate_file(
+                            http,
+                            &resolved,
+                            &tc.arguments,
+                            workspace_dir,
+                            tool_cancel,
+                        )
+                        .await
+                        {
+                            Ok(text) => (text, false),
+                            Err(err) => (err, true),
+                        }
+                    } else if proofread_handler::is_proofread_tool(&tc.name) {
+                        match proofread_handler::execute_proofread(
+                            http,
+                            &resolved,
+                            &tc.arguments,
+                            workspace_dir,
+                            tool_cancel,
+                        )
+                        .await
+                        {
+                            Ok(text) => (text, false),
+                            Err(err) => (err, true),
+                        }
+                    } else if summarize_handler::is_summarize_tool(&tc.name) {
+                        match summarize_handler::execute_summarize(
+                            http,
+                            &resolved,
+                            &tc.arguments,
+                            tool_cancel,
+                        )
+                        .await
+                        {
+                            Ok(text) => (text, false),
+                            Err(err) => (err, true),
+                        }
+                    } else if ssh_handler::is_ssh_tool(&tc.name) {
+                        match ssh_handler::execute_ssh(
+                            shared_config,
+                            vault,
+                            &tc.arguments,
+                            tool_cancel,
+                        )
+                        .await
+                        {
+                            Ok(text) => (text, false),
+                            Err(err) => (err, true),
+                        }
                     } else {
                         tool_executor::execute_tool_by_type(
                             &tc.name,
@@ -927,12 +1273,39 @@ pub(crate) async fn dispatch_text_message(
                             workspace_dir,
                             vault,
                             skill_mgr,
+                            tool_cancel,
                         )
                         .await
                     }
                 }
             };
 
+            if let Some(task_id) = command_task {
+                if is_error {
+                    command_wrapper::fail_command_task(task_mgr, task_id, &output).await;
+                } else if let Some(process) = command_wrapper::parse_session_id(&output) {
+                    command_wrapper::update_command_task_session(task_mgr, task_id, &process).await;
+                } else {
+                    command_wrapper::complete_command_task(task_mgr, task_id, &output).await;
+                }
+            }
+            if tc.name == "sessions_spawn"
+                && !is_error
+                && let Some(key) = session_key.as_deref()
+            {
+                task_registry::record_subagent_task(task_mgr, key, &output).await;
+            }
+
+            // Reading a skill's SKILL.md makes it the active skill.
+            if !is_error {
+                let mgr = skill_mgr.lock().await;
+                if let Some(skill) = mgr.skill_activated_by(&tc.name, &tc.arguments, workspace_dir)
+                {
+                    debug!(skill = %skill.name, "Skill activated");
+                    active_skill = Some(skill.clone());
+                }
+            }
+
             // Sanitize the output (truncate large outputs, warn about garbage).
             let mut output = tools::sanitize_tool_output(output);
 
@@ -957,12 +1330,24 @@ pub(crate) async fn dispatch_text_message(
                                     thread_mgr.rename(fg_id, caption);
                                     output = format!("Thread caption set to: {}", caption);
                                     send_threads_update(writer, thread_mgr, task_mgr, None).await?;
-                                    let _ = thread_mgr.save_to_file(threads_path);
+                                    let _ = history.save(thread_mgr);
                                 } else {
                                     output = "No active thread to caption.".to_string();
                                 }
                             }
                         }
+                        "reminder" => {
+                            let args = update.get("args").cloned().unwrap_or_default();
+                            let result = match thread_mgr.foreground_id() {
+                                Some(fg_id) => {
+                                    let store_dir = shared_config.read().await.reminders_dir();
+                                    tools::apply_reminder(&args, &store_dir, fg_id.0)
+                                }
+                                None => Err("No active thread to remind.".to_string()),
+                            };
+                            is_error = result.is_err();
+                            output = result.unwrap_or_else(|e| e);
+                        }
                         _ => {}
                     }
                 }
@@ -1060,7 +1445,7 @@ pub(crate) async fn dispatch_text_message(
                 thread.add_tool_result(tr.id.clone(), tr.output.clone());
             }
         }
-        let _ = thread_mgr.save_to_file(threads_path);
+        let _ = history.save(thread_mgr);
 
         // ── Bail out if tools keep failing with no progress ─────────
         // A round where every tool call errored may still recover (the
@@ -1089,22 +1474,56 @@ pub(crate) async fn dispatch_text_message(
             return Ok(());
         }
     }
+}
 
-    // If we exhausted all rounds, send what we have and stop.
-    let _ = errors::handle(
-        errors::GatewayError::ToolLoopExhausted {
-            rounds: MAX_TOOL_ROUNDS,
-        },
-        None,
-        writer,
-        &mut resolved,
-        &mut original_api_key,
-        vault,
-        credential_rx,
-        tool_cancel,
-    )
-    .await?;
-    Ok(())
+/// The effective permission for `tc`, and why the call is refused outright
+/// (a tool outside the client's allowlist, or paths outside the active
+/// skill's), after the active skill's overlay.  Unlisted tools are allowed.
+fn resolve_permission(
+    tc: &ParsedToolCall,
+    overrides: &HashMap<String, tools::ToolPermission>,
+    trusted_skills: &[String],
+    allowlist: Option<&BTreeSet<String>>,
+    active_skill: Option<&rustyclaw_core::skills::Skill>,
+    workspace_dir: &std::path::Path,
+) -> (tools::ToolPermission, Option<String>) {
+    // The client's allowlist overrides every other permission.
+    if let Some(allowed) = allowlist
+        && !allowed.contains(&tc.name)
+    {
+        let allowed: Vec<&str> = allowed.iter().map(String::as_str).collect();
+        let msg = format!(
+            "Tool '{}' is not permitted for this client (allowed: {}).",
+            tc.name,
+            if allowed.is_empty() {
+                "none".to_string()
+            } else {
+                allowed.join(", ")
+            }
+        );
+        return (tools::ToolPermission::Deny, Some(msg));
+    }
+    let permission = overrides.get(&tc.name).cloned().unwrap_or_default();
+    match active_skill {
+        Some(skill) => (
+            skill.tool_permission(&tc.name, &permission, trusted_skills),
+            skill.check_tool_paths(&tc.arguments, workspace_dir).err(),
+        ),
+        None => (permission, None),
+    }
+}
+
+/// End (exclusive) of the run of calls from `start` that `batchable`
+/// accepts.  Equals `start` when the call at `start` is not batchable.
+fn parallel_run_end(
+    calls: &[ParsedToolCall],
+    start: usize,
+    mut batchable: impl FnMut(&ParsedToolCall) -> bool,
+) -> usize {
+    calls[start..]
+        .iter()
+        .position(|tc| !batchable(tc))
+        .map_or(calls.len(), |n| start + n)
 }
 
 /// Detect tool call IDs that are likely non-unique across turns.
@@ -1161,3 +1580,122 @@ fn collect_existing_tool_ids(
     }
     ids
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::config::Config;
+    use rustyclaw_core::gateway::transport::{PeerInfo, TransportType};
+    use rustyclaw_core::secrets::{GatewayRole, SecretsManager};
+
+    fn call(name: &str, arguments: serde_json::Value) -> ParsedToolCall {
+        serde_json::from_value(serde_json::json!({
+            "id": "c1", "name": name, "arguments": arguments
+        }))
+        .unwrap()
+    }
+
+    fn peer(fingerprint: &str) -> PeerInfo {
+        PeerInfo {
+            addr: Some("127.0.0.1:2222".parse().unwrap()),
+            username: None,
+            key_fingerprint: Some(fingerprint.to_string()),
+            transport_type: TransportType::Ssh,
+        }
+    }
+
+    #[tokio::test]
+    async fn scoped_token_is_limited_to_its_tools() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("notes.txt"), "hello").unwrap();
+        let mut secrets = SecretsManager::new(tmp.path().join("credentials"));
+        let scope = vec!["read_file".to_string(), "web_fetch".to_string()];
+        let (_, token) = secrets
+            .issue_gateway_token("kiosk", None, GatewayRole::Operator, Some(scope))
+            .unwrap();
+        let grant = secrets.gateway_token_grant(&token).unwrap().unwrap();
+
+        let config = Config::default();
+        let allowlist =
+            auth::client_tool_allowlist(&config, &peer("SHA256:kiosk"), Some(&grant)).unwrap();
+        // Global permissions allow everything; the token still wins.
+        let overrides = HashMap::new();
+
+        let read = call("read_file", serde_json::json!({ "path": "notes.txt" }));
+        let (permission, denial) =
+            resolve_permission(&read, &overrides, &[], Some(&allowlist), None, tmp.path());
+        assert_eq!((permission, denial), (tools::ToolPermission::Allow, None));
+        let vault: SharedVault = Arc::new(Mutex::new(secrets));
+        let skill_mgr: SharedSkillManager = Arc::new(Mutex::new(
+            rustyclaw_core::skills::SkillManager::new(tmp.path().join("skills")),
+        ));
+        let (output, is_error) = tool_executor::execute_tool_by_type(
+            &read.name,
+            &read.arguments,
+            tmp.path(),
+            &vault,
+            &skill_mgr,
+            &ToolCancelFlag::default(),
+        )
+        .await;
+        assert!(!is_error, "{output}");
+        assert!(output.contains("hello"));
+
+        let write = call(
+            "write_file",
+            serde_json::json!({ "path": "notes.txt", "content": "bye" }),
+        );
+        let (permission, denial) =
+            resolve_permission(&write, &overrides, &[], Some(&allowlist), None, tmp.path());
+        assert_eq!(permission, tools::ToolPermission::Deny);
+        let denial = denial.unwrap();
+        assert!(denial.contains("not permitted for this client"), "{denial}");
+        assert!(denial.contains("read_file, web_fetch"), "{denial}");
+    }
+
+    #[test]
+    fn parallel_runs_stop_at_a_write() {
+        let calls = [
+            call("write_file", serde_json::json!({ "path": "a.txt" })),
+            call("read_file", serde_json::json!({ "path": "a.txt" })),
+            call("read_file", serde_json::json!({ "path": "b.txt" })),
+            call("write_file", serde_json::json!({ "path": "b.txt" })),
+            call("read_file", serde_json::json!({ "path": "b.txt" })),
+        ];
+        let safe = |tc: &ParsedToolCall| tools::is_parallel_safe_tool(&tc.name);
+        // The first read waits for the write before it.
+        assert_eq!(parallel_run_end(&calls, 0, safe), 0);
+        // The two reads run together; the read after the second write
+        // stays behind it.
+        assert_eq!(parallel_run_end(&calls, 1, safe), 3);
+        assert_eq!(parallel_run_end(&calls, 3, safe), 3);
+        assert_eq!(parallel_run_end(&calls, 4, safe), 5);
+    }
+
+    #[test]
+    fn configured_allowlists_narrow_a_token_scope() {
+        let mut config = Config::default();
+        config
+            .client_tool_allowlists
+            .insert("SHA256:kiosk".into(), vec!["read_file".into()]);
+        let token = rustyclaw_core::secrets::GatewayToken {
+            id: "0badf00d".into(),
+            label: "kiosk".into(),
+            hash: String::new(),
+            created_at: 0,
+            expires_at: None,
+            role: GatewayRole::Operator,
+            tools: Some(vec!["read_file".into(), "web_fetch".into()]),
+        };
+
+        let allowed = auth::client_tool_allowlist(&config, &peer("SHA256:kiosk"), Some(&token));
+        assert_eq!(allowed, Some(BTreeSet::from(["read_file".to_string()])));
+        // Unlisted keys and unscoped tokens are unrestricted.
+        assert_eq!(
+            auth::client_tool_allowlist(&config, &peer("SHA256:laptop"), None),
+            None
+        );
+        let allowed = auth::client_tool_allowlist(&config, &peer("SHA256:kiosk"), None);
+        assert_eq!(allowed, Some(BTreeSet::from(["read_file".to_string()])));
+    }
+}
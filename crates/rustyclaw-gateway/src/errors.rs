@@ -29,9 +29,9 @@ use tracing::{info, warn};
 
 use super::SharedVault;
 use super::providers;
-use rustyclaw_core::gateway::ProviderRequest;
 use rustyclaw_core::gateway::protocol;
 use rustyclaw_core::gateway::transport::TransportWriter;
+use rustyclaw_core::gateway::{ErrorCode, ProviderRequest, StatusType};
 use rustyclaw_core::providers as crate_providers;
 
 // ── Error enum ──────────────────────────────────────────────────────────────
@@ -42,8 +42,10 @@ use rustyclaw_core::providers as crate_providers;
 pub enum ErrorKind {
     Auth,
     Provider,
+    Timeout,
     TokenLimit,
     ToolLoopExhausted,
+    TurnTimeExhausted,
     ContextCompaction,
     Cancelled,
     Vault,
@@ -51,6 +53,7 @@ pub enum ErrorKind {
     Config,
     TokenRefresh,
     UnexpectedFinish,
+    SafetyRefusal,
 }
 
 impl fmt::Display for ErrorKind {
@@ -64,8 +67,10 @@ impl ErrorKind {
         match self {
             Self::Auth => "auth",
             Self::Provider => "provider",
+            Self::Timeout => "timeout",
             Self::TokenLimit => "token_limit",
             Self::ToolLoopExhausted => "tool_loop_exhausted",
+            Self::TurnTimeExhausted => "turn_time_exhausted",
             Self::ContextCompaction => "context_compaction",
             Self::Cancelled => "cancelled",
             Self::Vault => "vault",
@@ -73,6 +78,7 @@ impl ErrorKind {
             Self::Config => "config",
             Self::TokenRefresh => "token_refresh",
             Self::UnexpectedFinish => "unexpected_finish",
+            Self::SafetyRefusal => "safety_refusal",
         }
     }
 }
@@ -90,12 +96,18 @@ pub enum GatewayError {
     /// Model API returned an error that is not auth-related.
     Provider,
 
+    /// The provider ran past its request or connect timeout.
+    Timeout { provider: String },
+
     /// The response was truncated because the model hit its token limit.
     TokenLimit,
 
-    /// The agentic tool loop hit the safety ceiling.
+    /// The agentic tool loop hit `max_tool_iterations`.
     ToolLoopExhausted { rounds: usize },
 
+    /// The turn ran past `max_turn_secs` while the model still wanted tools.
+    TurnTimeExhausted { secs: u64, rounds: usize },
+
     /// Context compaction failed (non-fatal — the call can proceed).
     ContextCompaction,
 
@@ -120,6 +132,10 @@ pub enum GatewayError {
     /// The model finished with an unexpected reason but no tool calls.
     /// This is informational (not an error) — logged via `send_info`.
     UnexpectedFinish { reason: String },
+
+    /// The provider declined to answer on safety grounds.  Reported as its
+    /// own error and never retried: asking again gets the same answer.
+    SafetyRefusal { provider: String },
 }
 
 impl fmt::Display for GatewayError {
@@ -129,12 +145,20 @@ impl fmt::Display for GatewayError {
                 write!(f, "Authentication failed for {}", provider)
             }
             Self::Provider => write!(f, "Provider error"),
+            Self::Timeout { provider } => write!(f, "Request to {} timed out", provider),
             Self::TokenLimit => write!(f, "Response truncated due to token limit."),
             Self::ToolLoopExhausted { rounds } => write!(
                 f,
-                "Safety limit reached ({} tool rounds) — stopping to prevent infinite loop.",
+                "Stopped after {} tool rounds: the max_tool_iterations limit was reached \
+                 before the model finished.",
                 rounds
             ),
+            Self::TurnTimeExhausted { secs, rounds } => write!(
+                f,
+                "Stopped after {} tool rounds: the turn ran past its {}s max_turn_secs \
+                 budget before the model finished.",
+                rounds, secs
+            ),
             Self::ContextCompaction => write!(f, "Context compaction failed"),
             Self::Cancelled => write!(f, "Run cancelled by user."),
             Self::Vault => write!(f, "Vault error"),
@@ -150,6 +174,9 @@ impl fmt::Display for GatewayError {
                     reason
                 )
             }
+            Self::SafetyRefusal { provider } => {
+                write!(f, "{} declined to respond on safety grounds", provider)
+            }
         }
     }
 }
@@ -162,8 +189,10 @@ impl GatewayError {
         match self {
             Self::Auth { .. } => ErrorKind::Auth,
             Self::Provider => ErrorKind::Provider,
+            Self::Timeout { .. } => ErrorKind::Timeout,
             Self::TokenLimit => ErrorKind::TokenLimit,
             Self::ToolLoopExhausted { .. } => ErrorKind::ToolLoopExhausted,
+            Self::TurnTimeExhausted { .. } => ErrorKind::TurnTimeExhausted,
             Self::ContextCompaction => ErrorKind::ContextCompaction,
             Self::Cancelled => ErrorKind::Cancelled,
             Self::Vault => ErrorKind::Vault,
@@ -171,6 +200,28 @@ impl GatewayError {
             Self::Config => ErrorKind::Config,
             Self::TokenRefresh => ErrorKind::TokenRefresh,
             Self::UnexpectedFinish { .. } => ErrorKind::UnexpectedFinish,
+            Self::SafetyRefusal { .. } => ErrorKind::SafetyRefusal,
+        }
+    }
+
+    /// The protocol code sent with this error.  Variants reported as info
+    /// or status frames rather than errors have none of their own.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Auth { .. } | Self::DeviceFlow { .. } | Self::TokenRefresh => {
+                ErrorCode::AuthFailed
+            }
+            Self::Provider => ErrorCode::ModelError,
+            Self::Timeout { .. } => ErrorCode::Timeout,
+            Self::Vault => ErrorCode::VaultLocked,
+            Self::Config => ErrorCode::ConfigError,
+            Self::SafetyRefusal { .. } => ErrorCode::SafetyRefusal,
+            Self::TokenLimit
+            | Self::ToolLoopExhausted { .. }
+            | Self::TurnTimeExhausted { .. }
+            | Self::ContextCompaction
+            | Self::Cancelled
+            | Self::UnexpectedFinish { .. } => ErrorCode::Other,
         }
     }
 
@@ -200,13 +251,69 @@ fn is_auth_error(error_msg: &str) -> bool {
     patterns.iter().any(|p| lower.contains(&p.to_lowercase()))
 }
 
+/// Check whether an error message says the provider is throttling us.
+fn is_rate_limit_error(error_msg: &str) -> bool {
+    // A parsed body says so by status or by its code (OpenAI's
+    // `rate_limit_exceeded`, Google's `RESOURCE_EXHAUSTED`).
+    if let Some(body) = crate_providers::parse_provider_error(error_msg) {
+        let code = body.code.unwrap_or_default().to_lowercase();
+        if body.status == Some(429) || code.contains("rate_limit") || code == "resource_exhausted" {
+            return true;
+        }
+    }
+    let patterns = [
+        "returned 429",
+        "http 429",
+        "too many requests",
+        "rate limit",
+    ];
+    let lower = error_msg.to_lowercase();
+    patterns.iter().any(|p| lower.contains(p))
+}
+
+/// Check whether an error message says the endpoint could not be reached
+/// at all (as opposed to answering with an error).
+fn is_unreachable_error(error_msg: &str) -> bool {
+    let patterns = [
+        "error sending request",
+        "connection refused",
+        "failed to connect",
+        "dns error",
+        "failed to lookup address",
+        "no route to host",
+        "network is unreachable",
+    ];
+    let lower = error_msg.to_lowercase();
+    patterns.iter().any(|p| lower.contains(p))
+}
+
+/// The protocol code for a failed model call that wasn't an auth failure
+/// or a timeout.
+fn provider_error_code(error_msg: &str) -> ErrorCode {
+    if is_rate_limit_error(error_msg) {
+        ErrorCode::RateLimited
+    } else if is_unreachable_error(error_msg) {
+        ErrorCode::ModelUnreachable
+    } else {
+        ErrorCode::ModelError
+    }
+}
+
 /// Inspect a raw model-call error and classify it.
 ///
 /// Returns the `(GatewayError, anyhow::Error)` pair.  The original
 /// error is returned unchanged — no wrapping, no stringification.
 pub fn classify_model_error(err: anyhow::Error, provider: &str) -> (GatewayError, anyhow::Error) {
     let full_msg = format!("{err:#}");
-    let gw = if is_auth_error(&full_msg) {
+    let gw = if crate_providers::find_refusal(&err).is_some() {
+        GatewayError::SafetyRefusal {
+            provider: provider.to_string(),
+        }
+    } else if crate_providers::find_timeout(&err).is_some() {
+        GatewayError::Timeout {
+            provider: provider.to_string(),
+        }
+    } else if is_auth_error(&full_msg) {
         GatewayError::Auth {
             provider: provider.to_string(),
         }
@@ -356,8 +463,12 @@ pub async fn handle(
                 .await
             } else {
                 let msg = user_message(&kind, &source);
-                protocol::server::send_error(writer, &format!("Token refresh failed: {msg}"))
-                    .await?;
+                protocol::server::send_error(
+                    writer,
+                    kind.code(),
+                    &format!("Token refresh failed: {msg}"),
+                )
+                .await?;
                 providers::send_response_done(writer).await?;
                 Ok(ControlFlow::Break(()))
             }
@@ -370,15 +481,12 @@ pub async fn handle(
             Ok(ControlFlow::Break(()))
         }
 
-        // ── Tool loop exhausted ─────────────────────────────────────
-        GatewayError::ToolLoopExhausted { rounds } => {
-            protocol::server::send_error(
-                writer,
-                &format!(
-                    "Safety limit reached ({rounds} tool rounds) — stopping to prevent infinite loop.",
-                ),
-            )
-            .await?;
+        // ── Tool loop stopped by a turn limit ───────────────────────
+        // A status frame rather than an error: nothing failed, but the
+        // client should show that the cutoff was ours, not the model's.
+        GatewayError::ToolLoopExhausted { .. } | GatewayError::TurnTimeExhausted { .. } => {
+            protocol::server::send_status(writer, StatusType::ToolLoopStopped, &kind.to_string())
+                .await?;
             providers::send_response_done(writer).await?;
             Ok(ControlFlow::Break(()))
         }
@@ -388,6 +496,7 @@ pub async fn handle(
             let msg = user_message(&kind, &source);
             protocol::server::send_error(
                 writer,
+                kind.code(),
                 &format!("Device flow for {provider} failed: {msg}"),
             )
             .await?;
@@ -398,7 +507,7 @@ pub async fn handle(
         // ── Config error ────────────────────────────────────────────
         GatewayError::Config => {
             let msg = user_message(&kind, &source);
-            protocol::server::send_error(writer, &msg).await?;
+            protocol::server::send_error(writer, kind.code(), &msg).await?;
             providers::send_response_done(writer).await?;
             Ok(ControlFlow::Break(()))
         }
@@ -406,7 +515,7 @@ pub async fn handle(
         // ── Vault error ─────────────────────────────────────────────
         GatewayError::Vault => {
             let msg = user_message(&kind, &source);
-            protocol::server::send_error(writer, &msg).await?;
+            protocol::server::send_error(writer, kind.code(), &msg).await?;
             Ok(ControlFlow::Break(()))
         }
 
@@ -421,16 +530,58 @@ pub async fn handle(
             Ok(ControlFlow::Break(()))
         }
 
+        // ── Safety refusal ──────────────────────────────────────────
+        // The model's decision, not a failure: shown with the provider's
+        // signal and category, and not retried.
+        GatewayError::SafetyRefusal { .. } => {
+            let msg = source
+                .as_ref()
+                .and_then(crate_providers::find_refusal)
+                .map_or_else(|| kind.to_string(), ToString::to_string);
+            protocol::server::send_error(writer, kind.code(), &msg).await?;
+            providers::send_response_done(writer).await?;
+            Ok(ControlFlow::Break(()))
+        }
+
+        // ── Provider timeout ────────────────────────────────────────
+        // The timeout's own message names the limit and the setting to
+        // raise, so it is shown instead of the whole error chain.
+        GatewayError::Timeout { .. } => {
+            let msg = source
+                .as_ref()
+                .and_then(crate_providers::find_timeout)
+                .map_or_else(|| kind.to_string(), ToString::to_string);
+            protocol::server::send_error(writer, kind.code(), &msg).await?;
+            providers::send_response_done(writer).await?;
+            Ok(ControlFlow::Break(()))
+        }
+
         // ── Generic provider error ──────────────────────────────────
         GatewayError::Provider => {
-            let msg = user_message(&kind, &source);
-            protocol::server::send_error(writer, &msg).await?;
+            let full = user_message(&kind, &source);
+            let code = provider_error_code(&full);
+            let msg = provider_message(resolved, &full);
+            protocol::server::send_error(writer, code, &msg).await?;
             providers::send_response_done(writer).await?;
             Ok(ControlFlow::Break(()))
         }
     }
 }
 
+/// Build the client-facing message for a failed model call.
+///
+/// When the provider returned a JSON error body, its message and code
+/// are shown (`gpt-4o: maximum context length is … (HTTP 400,
+/// context_length_exceeded)`) instead of the full error chain.  Either
+/// way, credentials the provider echoed back are redacted.
+fn provider_message(resolved: &ProviderRequest, full: &str) -> String {
+    let msg = match crate_providers::parse_provider_error(full) {
+        Some(body) => format!("{}: {}", resolved.model, body.summary()),
+        None => full.to_string(),
+    };
+    crate_providers::redact_credentials(&msg, resolved.api_key.as_deref())
+}
+
 // ── Device flow sub-handler ─────────────────────────────────────────────────
 
 async fn handle_device_flow(
@@ -449,6 +600,7 @@ async fn handle_device_flow(
         None => {
             protocol::server::send_error(
                 writer,
+                ErrorCode::AuthFailed,
                 &format!(
                     "Authentication failed for {} but no device flow config found.",
                     display
@@ -475,6 +627,7 @@ async fn handle_device_flow(
         Err(e) => {
             protocol::server::send_error(
                 writer,
+                ErrorCode::AuthFailed,
                 &format!("Failed to start device flow for {}: {}", display, e),
             )
             .await?;
@@ -558,6 +711,7 @@ async fn handle_device_flow(
     } else {
         protocol::server::send_error(
             writer,
+            ErrorCode::AuthFailed,
             &format!("Device flow for {} timed out or failed.", display),
         )
         .await?;
@@ -615,7 +769,12 @@ async fn handle_credential_prompt(
                 .await?;
                 Ok(ControlFlow::Continue(()))
             } else {
-                protocol::server::send_error(writer, "No credential value provided.").await?;
+                protocol::server::send_error(
+                    writer,
+                    ErrorCode::AuthFailed,
+                    "No credential value provided.",
+                )
+                .await?;
                 providers::send_response_done(writer).await?;
                 Ok(ControlFlow::Break(()))
             }
@@ -623,6 +782,7 @@ async fn handle_credential_prompt(
         _ => {
             protocol::server::send_error(
                 writer,
+                ErrorCode::AuthFailed,
                 &format!(
                     "Authentication failed for {} and no credential was provided.",
                     display
@@ -640,6 +800,7 @@ async fn handle_credential_prompt(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::CollectWriter;
 
     #[test]
     fn test_is_auth_error_positive_cases() {
@@ -665,6 +826,38 @@ mod tests {
         assert!(!is_auth_error("Some random error message"));
     }
 
+    #[test]
+    fn test_provider_error_code() {
+        assert_eq!(
+            provider_error_code("Provider returned 429 Too Many Requests"),
+            ErrorCode::RateLimited
+        );
+        assert_eq!(
+            provider_error_code(
+                r#"status: 429, body: {"error": {"message": "Slow down", "type": "requests"}}"#
+            ),
+            ErrorCode::RateLimited
+        );
+        assert_eq!(
+            provider_error_code(
+                "error sending request for url (http://localhost:11434/api/chat): \
+                 Connection refused (os error 111)"
+            ),
+            ErrorCode::ModelUnreachable
+        );
+        assert_eq!(
+            provider_error_code("returned 500 Internal Server Error"),
+            ErrorCode::ModelError
+        );
+        assert_eq!(
+            GatewayError::Auth {
+                provider: "openai".into()
+            }
+            .code(),
+            ErrorCode::AuthFailed
+        );
+    }
+
     #[test]
     fn test_classify_model_error_auth() {
         let err = anyhow::anyhow!("Provider returned 401 Unauthorized");
@@ -682,12 +875,29 @@ mod tests {
         assert!(source.to_string().contains("timeout"));
     }
 
+    #[test]
+    fn test_classify_model_error_timeout() {
+        let timeout = crate_providers::ProviderTimeout {
+            provider: "ollama".into(),
+            phase: crate_providers::TimeoutPhase::Stream,
+            after: std::time::Duration::from_secs(600),
+        };
+        let err = anyhow::Error::new(timeout).context("genai chat failed");
+        let (gw, source) = classify_model_error(err, "ollama");
+        assert!(matches!(gw, GatewayError::Timeout { .. }));
+        assert_eq!(gw.kind(), ErrorKind::Timeout);
+        let found = crate_providers::find_timeout(&source).unwrap();
+        assert!(found.to_string().contains("[provider_timeouts.ollama]"));
+    }
+
     #[test]
     fn test_error_kind_as_str() {
         assert_eq!(ErrorKind::Auth.as_str(), "auth");
         assert_eq!(ErrorKind::Provider.as_str(), "provider");
+        assert_eq!(ErrorKind::Timeout.as_str(), "timeout");
         assert_eq!(ErrorKind::TokenLimit.as_str(), "token_limit");
         assert_eq!(ErrorKind::ToolLoopExhausted.as_str(), "tool_loop_exhausted");
+        assert_eq!(ErrorKind::TurnTimeExhausted.as_str(), "turn_time_exhausted");
         assert_eq!(ErrorKind::ContextCompaction.as_str(), "context_compaction");
         assert_eq!(ErrorKind::Cancelled.as_str(), "cancelled");
         assert_eq!(ErrorKind::Vault.as_str(), "vault");
@@ -695,6 +905,7 @@ mod tests {
         assert_eq!(ErrorKind::Config.as_str(), "config");
         assert_eq!(ErrorKind::TokenRefresh.as_str(), "token_refresh");
         assert_eq!(ErrorKind::UnexpectedFinish.as_str(), "unexpected_finish");
+        assert_eq!(ErrorKind::SafetyRefusal.as_str(), "safety_refusal");
     }
 
     #[test]
@@ -743,4 +954,150 @@ mod tests {
             chain
         );
     }
+
+    #[test]
+    fn test_provider_message_passes_through_unparsed_errors() {
+        let resolved = ProviderRequest {
+            messages: Vec::new(),
+            model: "gpt-4o".into(),
+            provider: "openai".into(),
+            base_url: String::new(),
+            api_key: Some("sk-proxy-secret-value".into()),
+            sampling: Default::default(),
+            cache_prompt: false,
+            thinking_budget: None,
+        };
+        let msg = provider_message(&resolved, "Connection timeout after 30s");
+        assert_eq!(msg, "Connection timeout after 30s");
+    }
+
+    #[tokio::test]
+    async fn test_provider_400_body_reaches_error_frame() {
+        use rustyclaw_core::gateway::ServerPayload;
+
+        let key = "test-key-abcdefgh-0123456789";
+        // Shaped like genai's rendering of a rejected request.
+        let body = format!(
+            r#"{{\n  \"error\": {{\n    \"message\": \"Incorrect API key provided: {key}. Context length exceeded for this model.\",\n    \"code\": \"context_length_exceeded\"\n  }}\n}}"#
+        );
+        let source = anyhow::anyhow!(
+            "Web call failed for model 'gpt-4o'. Cause: ResponseFailedStatus {{ status: 400, \
+             body: \"{body}\", headers: {{}} }}"
+        );
+
+        let tmp = tempfile::tempdir().unwrap();
+        let vault: SharedVault = Arc::new(Mutex::new(
+            rustyclaw_core::secrets::SecretsManager::new(tmp.path()),
+        ));
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
+        let credential_rx = Arc::new(Mutex::new(rx));
+        let mut resolved = ProviderRequest {
+            messages: Vec::new(),
+            model: "gpt-4o".into(),
+            provider: "openai".into(),
+            base_url: "https://api.openai.com/v1".into(),
+            api_key: Some(key.into()),
+            sampling: Default::default(),
+            cache_prompt: false,
+            thinking_budget: None,
+        };
+        let mut original_api_key = resolved.api_key.clone();
+        let mut writer = CollectWriter(Vec::new());
+
+        let flow = handle(
+            GatewayError::Provider,
+            Some(source),
+            &mut writer,
+            &mut resolved,
+            &mut original_api_key,
+            &vault,
+            &credential_rx,
+            &Arc::new(AtomicBool::new(false)),
+        )
+        .await
+        .unwrap();
+        assert!(flow.is_break());
+
+        let (message, code) = writer
+            .0
+            .iter()
+            .find_map(|f| match &f.payload {
+                ServerPayload::Error { message, code, .. } => Some((message.clone(), *code)),
+                _ => None,
+            })
+            .expect("an error frame");
+        assert_eq!(code, ErrorCode::ModelError);
+        assert!(
+            message.starts_with("gpt-4o: Incorrect API key provided: <redacted"),
+            "got {message}"
+        );
+        assert!(message.contains("Context length exceeded for this model."));
+        assert!(message.contains("(HTTP 400, context_length_exceeded)"));
+        assert!(!message.contains(key), "key leaked: {message}");
+        assert!(!message.contains("ResponseFailedStatus"));
+    }
+
+    #[tokio::test]
+    async fn test_safety_refusal_is_reported_distinctly() {
+        use rustyclaw_core::gateway::ServerPayload;
+
+        let refusal = crate_providers::SafetyRefusal {
+            provider: "google".into(),
+            signal: "finishReason=SAFETY".into(),
+            category: Some("dangerous_content".into()),
+        };
+        let err = anyhow::anyhow!("Gemini returned no content").context(refusal);
+        let (gw, source) = classify_model_error(err, "google");
+        assert!(matches!(gw, GatewayError::SafetyRefusal { .. }));
+        assert_eq!(gw.kind(), ErrorKind::SafetyRefusal);
+        assert_eq!(gw.code(), ErrorCode::SafetyRefusal);
+
+        let tmp = tempfile::tempdir().unwrap();
+        let vault: SharedVault = Arc::new(Mutex::new(
+            rustyclaw_core::secrets::SecretsManager::new(tmp.path()),
+        ));
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
+        let credential_rx = Arc::new(Mutex::new(rx));
+        let mut resolved = ProviderRequest {
+            messages: Vec::new(),
+            model: "gemini-2.5-pro".into(),
+            provider: "google".into(),
+            base_url: String::new(),
+            api_key: None,
+            sampling: Default::default(),
+            cache_prompt: false,
+            thinking_budget: None,
+        };
+        let mut original_api_key = None;
+        let mut writer = CollectWriter(Vec::new());
+
+        let flow = handle(
+            gw,
+            Some(source),
+            &mut writer,
+            &mut resolved,
+            &mut original_api_key,
+            &vault,
+            &credential_rx,
+            &Arc::new(AtomicBool::new(false)),
+        )
+        .await
+        .unwrap();
+        assert!(flow.is_break(), "refusals are not retried");
+
+        let (message, code) = writer
+            .0
+            .iter()
+            .find_map(|f| match &f.payload {
+                ServerPayload::Error { message, code, .. } => Some((message.clone(), *code)),
+                _ => None,
+            })
+            .expect("an error frame");
+        assert_eq!(code, ErrorCode::SafetyRefusal);
+        assert_eq!(
+            message,
+            "google declined to respond on safety grounds \
+             (category: dangerous_content; finishReason=SAFETY)"
+        );
+    }
 }
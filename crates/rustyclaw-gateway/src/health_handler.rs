@@ -0,0 +1,97 @@
+//! Model probes for gateway health reporting.
+//!
+//! Each connect probes the model (see `server.rs`); this task also probes it
+//! every `health_probe_interval_secs` so an outage shows up in
+//! `rustyclaw_core::health` between connects.  The model context is re-read
+//! each round, so a model switch is picked up on the next probe.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use rustyclaw_core::gateway::{ModelContext, ProbeResult};
+use rustyclaw_core::health::{self, ProbeRecord, ProbeTrigger};
+use rustyclaw_core::providers as crate_providers;
+use rustyclaw_core::secrets::AccessContext;
+
+use crate::{SharedCopilotSession, SharedModelCtx, SharedVault, providers};
+
+/// `ctx`, with its API key filled in from the vault when it has none (the
+/// key may have been stored since startup).
+pub async fn with_vault_key(ctx: &Arc<ModelContext>, vault: &SharedVault) -> Arc<ModelContext> {
+    if ctx.api_key.is_some() {
+        return ctx.clone();
+    }
+    let Some(key_name) = crate_providers::secret_key_for_provider(&ctx.provider) else {
+        return ctx.clone();
+    };
+    let access = AccessContext::approved(format!("provider:{}", ctx.provider));
+    let mut v = vault.lock().await;
+    match v.get_secret_with(key_name, &access) {
+        Ok(Some(key)) => {
+            let mut updated = (**ctx).clone();
+            updated.api_key = Some(key);
+            Arc::new(updated)
+        }
+        _ => ctx.clone(),
+    }
+}
+
+/// Record a probe result in the health history.
+pub fn record(trigger: ProbeTrigger, ctx: &ModelContext, result: &ProbeResult) {
+    health::record_probe(ProbeRecord::new(trigger, &ctx.provider, &ctx.model, result));
+}
+
+/// Spawn the background model prober; it stops when `cancel` fires.
+pub fn spawn_model_prober(
+    interval: Duration,
+    shared_model_ctx: SharedModelCtx,
+    shared_copilot_session: SharedCopilotSession,
+    vault: SharedVault,
+    cancel: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let http = match reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(30))
+            .build()
+        {
+            Ok(http) => http,
+            Err(e) => {
+                warn!(error = %e, "Failed to build HTTP client; background model probes disabled");
+                return;
+            }
+        };
+
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+
+            let Some(ctx) = shared_model_ctx.read().await.clone() else {
+                continue;
+            };
+            let probe_ctx = with_vault_key(&ctx, &vault).await;
+            let copilot_session = shared_copilot_session.read().await.clone();
+            let result =
+                providers::validate_model_connection(&http, &probe_ctx, copilot_session.as_deref())
+                    .await;
+            match &result {
+                ProbeResult::Ready | ProbeResult::Connected { .. } => {
+                    debug!(provider = %ctx.provider, model = %ctx.model, "Model probe succeeded");
+                }
+                ProbeResult::AuthError { detail } | ProbeResult::Unreachable { detail } => {
+                    warn!(
+                        provider = %ctx.provider,
+                        model = %ctx.model,
+                        %detail,
+                        "Model probe failed"
+                    );
+                }
+            }
+            record(ProbeTrigger::Background, &ctx, &result);
+        }
+    })
+}
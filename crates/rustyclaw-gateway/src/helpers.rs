@@ -1,6 +1,10 @@
-use tracing::debug;
+use std::path::PathBuf;
 
+use tracing::{debug, warn};
+
+use rustyclaw_core::config::Config;
 use rustyclaw_core::gateway::ChatMessage;
+use rustyclaw_core::session_workspace::SessionWorkspaces;
 
 // ── Context window helpers ──────────────────────────────────────────────────
 
@@ -43,3 +47,21 @@ pub fn estimate_tokens(messages: &[ChatMessage]) -> usize {
     // ~3.5 chars/token for English; we round down to be conservative.
     total_chars / 3
 }
+
+// ── Session working directories ─────────────────────────────────────────────
+
+/// The directory `key`'s tool calls work in: its own, created on first
+/// use, when `[session_workspaces]` isolates sessions; otherwise the
+/// workspace root.
+pub fn session_workspace_dir(config: &Config, key: &str) -> PathBuf {
+    let workspaces = SessionWorkspaces::from_config(config);
+    workspaces.enter(key).unwrap_or_else(|e| {
+        warn!(error = %e, session = key, "Failed to create session directory, using workspace root");
+        workspaces.root().to_path_buf()
+    })
+}
+
+/// Session key for a gateway conversation thread.
+pub fn thread_session_key(thread_id: u64) -> String {
+    format!("thread-{}", thread_id)
+}
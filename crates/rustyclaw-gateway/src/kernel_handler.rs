@@ -3,7 +3,7 @@
 use anyhow::Result;
 use rustyclaw_core::gateway::TransportWriter;
 use rustyclaw_core::gateway::protocol::frames::{
-    GpuInfoDto, ServerFrame, ServerFrameType, ServerPayload,
+    ErrorCode, GpuInfoDto, ServerFrame, ServerFrameType, ServerPayload,
 };
 use tracing::debug;
 
@@ -36,6 +36,7 @@ pub async fn handle_host_info_request(writer: &mut dyn TransportWriter) -> Resul
         None => ServerPayload::Error {
             ok: false,
             message: "Host capabilities not yet detected".into(),
+            code: ErrorCode::Unavailable,
         },
     };
 
@@ -71,6 +72,7 @@ pub async fn handle_load_status_request(writer: &mut dyn TransportWriter) -> Res
         None => ServerPayload::Error {
             ok: false,
             message: "Load tracker not initialised".into(),
+            code: ErrorCode::Unavailable,
         },
     };
 
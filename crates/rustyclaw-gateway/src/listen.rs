@@ -2,9 +2,9 @@
 //!
 //! [`run_gateway`] is the networked entry point: it bootstraps shared state
 //! (model registry, copilot session, sandbox), optionally starts the messenger
-//! loop, then accepts SSH (or stdio) transports and hands each one to the
-//! per-connection engine in [`crate::server`]. Invoked from the binary entry
-//! point in `main.rs`.
+//! loop, then accepts SSH and Unix socket (or stdio) transports and hands each
+//! one to the per-connection engine in [`crate::server`]. Invoked from the
+//! binary entry point in `main.rs`.
 
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -18,15 +18,20 @@ use rustyclaw_core::config::Config;
 use rustyclaw_core::gateway::{
     CopilotSession, GatewayOptions, ModelContext, Transport, TransportAcceptor,
 };
+use rustyclaw_core::observability::ObserverMetric;
+use rustyclaw_core::session_workspace::SessionWorkspaces;
 use rustyclaw_core::tools;
 
+use crate::connection_limit::ConnectionLimit;
 use crate::messenger_handler::SharedMessengerManager;
 use crate::server::handle_connection;
 use crate::session::init_copilot_session;
 use crate::ssh::{SshConfig, SshServer, StdioTransport};
+use crate::unix_socket::{self, UnixSocketListener};
 use crate::{
     SharedConfig, SharedCopilotSession, SharedModelCtx, SharedModelRegistry, SharedObserver,
-    SharedSkillManager, SharedTaskManager, SharedVault, auth, messenger_handler,
+    SharedSkillManager, SharedTaskManager, SharedVault, auth, cron_handler, health_handler,
+    messenger_handler, metrics_handler,
 };
 
 /// Run the gateway WebSocket server.
@@ -54,6 +59,21 @@ pub async fn run_gateway(
     observer: Option<SharedObserver>,
     cancel: CancellationToken,
 ) -> Result<()> {
+    // Uptime reported by the `gateway` tool's status action counts from here.
+    rustyclaw_core::health::mark_started();
+
+    // Size and age limits for every log file (`[log_rotation]`).
+    rustyclaw_core::log_rotation::init(&config.log_rotation);
+
+    // Redacted provider request/response logging, when `[provider_logging]` is on.
+    rustyclaw_core::providers::init_wire_log(&config.provider_logging, &config.logs_dir());
+
+    // Per-provider request and connect timeouts (`[provider_timeouts]`).
+    rustyclaw_core::providers::init_provider_timeouts(&config.provider_timeouts);
+
+    // Extra headers for proxies and internal LLM gateways (`[provider_headers]`).
+    rustyclaw_core::providers::init_extra_headers(&config.provider_headers);
+
     // Create task manager if not provided
     let task_mgr = task_mgr.unwrap_or_else(|| Arc::new(rustyclaw_core::tasks::TaskManager::new()));
 
@@ -103,6 +123,15 @@ pub async fn run_gateway(
         }
     }
 
+    // Page a local model in now rather than on the first chat
+    // (`[model_warmup]`); runs in the background.
+    if let Some(ref ctx) = model_ctx {
+        crate::warmup::spawn(&config.model_warmup, ctx);
+    }
+
+    // Tell every client when the agent is paused or resumed.
+    crate::pause_gate::spawn_status_relay();
+
     // ── Host introspection & load tracking ─────────────────────────
     //
     // Detect hardware capabilities once, then start a background sampler
@@ -173,6 +202,28 @@ pub async fn run_gateway(
     // Register the vault so web_fetch can access the cookie jar.
     tools::set_vault(vault.clone());
 
+    // MEMORY.md / HISTORY.md locations and the duplicate threshold for save_memory.
+    tools::set_memory_consolidation_config(config.memory_consolidation.clone());
+
+    // Provider, default units and cache lifetime for the weather tool.
+    tools::set_weather_config(config.weather.clone());
+
+    // whisper.cpp or cloud backend and duration cap for the transcribe tool.
+    tools::set_transcribe_config(config.transcribe.clone());
+
+    // Opt-in clipboard history; the watcher samples copies made outside the agent.
+    tools::set_clipboard_history_config(config.clipboard_history.clone());
+    let _clipboard_watcher_handle = tools::spawn_clipboard_watcher();
+
+    // Engine, resource limits and allowances for the docker tool's `run`.
+    tools::set_docker_config(config.docker.clone());
+
+    // Hosts that network_request_replay may send to.
+    tools::set_request_replay_config(config.request_replay.clone());
+
+    // Arguments tools get when the model leaves them out; bad keys are logged.
+    tools::set_tool_defaults(&config.tool_defaults);
+
     // Initialize sandbox for command execution
     let sandbox_mode = config.sandbox.mode.parse().unwrap_or_default();
     tools::init_sandbox(
@@ -182,6 +233,13 @@ pub async fn run_gateway(
         config.sandbox.deny_paths.clone(),
     );
 
+    // Sessions that ended while the gateway was down leave their empty
+    // working directories behind.
+    let pruned = SessionWorkspaces::from_config(&config).prune_empty();
+    if pruned > 0 {
+        debug!(count = pruned, "Removed empty session directories");
+    }
+
     // SSH-only transport: websocket listen/TLS options are ignored.
 
     // Initialize Copilot session if needed (uses the new helper function)
@@ -203,6 +261,20 @@ pub async fn run_gateway(
 
     let shared_config: SharedConfig = Arc::new(RwLock::new(config.clone()));
     let shared_model_ctx: SharedModelCtx = Arc::new(RwLock::new(model_ctx.clone()));
+
+    // ── Background model probes ─────────────────────────────────────
+    //
+    // Connects probe the model too; this keeps the health history current
+    // while no client is connecting.
+    if config.health_probe_interval_secs > 0 {
+        let _prober_handle = health_handler::spawn_model_prober(
+            std::time::Duration::from_secs(config.health_probe_interval_secs),
+            shared_model_ctx.clone(),
+            shared_copilot_session.clone(),
+            vault.clone(),
+            cancel.child_token(),
+        );
+    }
     let rate_limiter = auth::new_rate_limiter();
 
     if options.ssh_stdio {
@@ -228,6 +300,37 @@ pub async fn run_gateway(
         .await;
     }
 
+    // ── Prometheus metrics ──────────────────────────────────────────
+    //
+    // Opt-in: the listener only runs with `[metrics] enabled = true`, and a
+    // bind failure leaves the gateway running without it.
+    let observer = if config.metrics.enabled {
+        match metrics_handler::start(
+            &config.metrics,
+            observer.clone(),
+            vault.clone(),
+            cancel.child_token(),
+        )
+        .await
+        {
+            Ok(observer) => Some(observer),
+            Err(e) => {
+                warn!(error = %format!("{:#}", e), "Metrics endpoint disabled");
+                observer
+            }
+        }
+    } else {
+        observer
+    };
+    let connection_limit = ConnectionLimit::from_config(&config);
+
+    // ── Scheduled messages ──────────────────────────────────────────
+    //
+    // Sends one-shot messages queued by the `message` tool's `schedule`
+    // action, including any that came due while the gateway was down.
+    let _scheduler_handle =
+        cron_handler::spawn_message_scheduler(&config, task_mgr.clone(), cancel.child_token());
+
     // ── Initialize and start messenger loop ─────────────────────────
     //
     // If messengers are configured, we poll them for incoming messages
@@ -338,60 +441,95 @@ pub async fn run_gateway(
     let mut ssh_server = SshServer::new(ssh_cfg).await?;
     ssh_server.listen(bind_addr).await?;
 
-    info!(address = %bind_addr, "Gateway listening (SSH-only)");
+    info!(address = %bind_addr, "Gateway listening (SSH)");
+
+    // Same-host clients may also connect over a Unix socket.
+    let unix_socket = options
+        .unix_socket
+        .as_ref()
+        .or(config.gateway_socket.as_ref());
+    let unix_listener = match unix_socket {
+        Some(path) => {
+            let listener = UnixSocketListener::bind(path)?;
+            info!(path = %listener.path().display(), "Gateway listening (Unix socket)");
+            Some(listener)
+        }
+        None => None,
+    };
     if messenger_mgr.is_some() {
         info!("Messenger polling enabled");
     }
 
     loop {
-        tokio::select! {
+        let accepted = tokio::select! {
             _ = cancel.cancelled() => break,
-            accepted = ssh_server.accept() => {
-                match accepted {
-                    Ok(transport) => {
-                        let peer_info = transport.peer_info().clone();
-                        info!(
+            accepted = ssh_server.accept() => accepted,
+            accepted = unix_socket::accept_next(unix_listener.as_ref()) => accepted,
+        };
+        match accepted {
+            Ok(transport) => {
+                let peer_info = transport.peer_info().clone();
+                info!(
+                    transport = %peer_info.transport_type,
+                    user = ?peer_info.username,
+                    fingerprint = ?peer_info.key_fingerprint,
+                    "Connection accepted"
+                );
+
+                let shared_cfg = shared_config.clone();
+                let shared_ctx = shared_model_ctx.clone();
+                let shared_session = shared_copilot_session.clone();
+                let vault_clone = vault.clone();
+                let skill_clone = skill_mgr.clone();
+                let task_mgr_clone = task_mgr.clone();
+                let model_reg_clone = model_registry.clone();
+                let observer_clone = observer.clone();
+                let rate_limiter_clone = rate_limiter.clone();
+                let child_cancel = cancel.child_token();
+                let limit = connection_limit.clone();
+
+                tokio::spawn(async move {
+                    let Some((transport, slot)) = limit.admit(transport).await else {
+                        return;
+                    };
+                    if let Some(obs) = &observer_clone {
+                        obs.record_metric(&ObserverMetric::ActiveSessions(limit.active()));
+                    }
+                    let gauge_observer = observer_clone.clone();
+                    if let Err(err) = handle_transport_connection(
+                        transport,
+                        shared_cfg,
+                        shared_ctx,
+                        shared_session,
+                        vault_clone,
+                        skill_clone,
+                        task_mgr_clone,
+                        model_reg_clone,
+                        observer_clone,
+                        rate_limiter_clone,
+                        child_cancel,
+                    )
+                    .await
+                    {
+                        debug!(
                             transport = %peer_info.transport_type,
-                            user = ?peer_info.username,
-                            fingerprint = ?peer_info.key_fingerprint,
-                            "SSH connection accepted"
+                            error = %err,
+                            "Connection error"
                         );
-
-                        let shared_cfg = shared_config.clone();
-                        let shared_ctx = shared_model_ctx.clone();
-                        let shared_session = shared_copilot_session.clone();
-                        let vault_clone = vault.clone();
-                        let skill_clone = skill_mgr.clone();
-                        let task_mgr_clone = task_mgr.clone();
-                        let model_reg_clone = model_registry.clone();
-                        let observer_clone = observer.clone();
-                        let rate_limiter_clone = rate_limiter.clone();
-                        let child_cancel = cancel.child_token();
-
-                        tokio::spawn(async move {
-                            if let Err(err) = handle_transport_connection(
-                                transport,
-                                shared_cfg,
-                                shared_ctx,
-                                shared_session,
-                                vault_clone,
-                                skill_clone,
-                                task_mgr_clone,
-                                model_reg_clone,
-                                observer_clone,
-                                rate_limiter_clone,
-                                child_cancel,
-                            ).await {
-                                debug!(error = %err, "SSH connection error");
-                            }
-                        });
                     }
-                    Err(e) => warn!(error = %e, "SSH accept error"),
-                }
+                    drop(slot);
+                    if let Some(obs) = &gauge_observer {
+                        obs.record_metric(&ObserverMetric::ActiveSessions(limit.active()));
+                    }
+                });
             }
+            Err(e) => warn!(error = %e, "Accept error"),
         }
     }
 
+    // Remove the socket file before the slower service shutdown.
+    drop(unix_listener);
+
     // Graceful shutdown: stop all managed services.
     if let Some(svc_mgr) = rustyclaw_core::runtime_ctx::get_service_manager() {
         info!("Stopping managed services…");
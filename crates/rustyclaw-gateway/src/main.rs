@@ -11,29 +11,51 @@ mod chat;
 mod cli;
 mod command_wrapper;
 mod concurrent;
+mod connection_limit;
+mod cron_handler;
 mod dispatch;
 mod engine_handler;
 mod errors;
+mod health_handler;
 mod helpers;
 mod kernel_handler;
 mod listen;
 mod mcp_handler;
 mod messenger_handler;
+mod metrics_handler;
 mod model_handler;
 mod panel_handler;
+mod pause_gate;
 mod project_handler;
+mod proofread_handler;
 mod providers;
+mod replay_guard;
 mod secrets_handler;
 mod server;
 mod service_handler;
 mod session;
+mod session_mirror;
 mod skills_handler;
 mod ssh;
+mod ssh_handler;
+mod status_broadcast;
+mod summarize_handler;
 mod system_prompt;
 mod task_handler;
+mod task_registry;
 mod thread_handler;
 mod thread_updates;
+mod tool_approval;
 mod tool_executor;
+mod translate_handler;
+mod turn_budget;
+mod turn_resume;
+mod unix_socket;
+mod vault_relock;
+mod warmup;
+
+#[cfg(test)]
+mod test_support;
 
 use std::io::IsTerminal;
 use std::sync::Arc;
@@ -127,6 +149,23 @@ async fn main() -> Result<()> {
 
     let protocol_stdio = args.ssh_stdio;
 
+    // Per-request Chrome traces (`--trace` / `[request_trace]`).
+    if args.trace {
+        config.request_trace.enabled = true;
+    }
+    if let Some(path) =
+        rustyclaw_core::observability::init_request_trace(&config.request_trace, &config.logs_dir())
+        && !protocol_stdio
+    {
+        println!(
+            "{}",
+            t::icon_ok(&format!(
+                "Request traces: {}",
+                t::info(&path.display().to_string())
+            ))
+        );
+    }
+
     let host = match args.bind {
         GatewayBind::Loopback => "127.0.0.1",
         GatewayBind::Lan => "0.0.0.0",
@@ -140,7 +179,6 @@ async fn main() -> Result<()> {
     // Resolve TLS paths: CLI args override config
     let tls_cert = args.tls_cert.or(config.tls_cert.clone());
     let tls_key = args.tls_key.or(config.tls_key.clone());
-    let scheme = if tls_cert.is_some() { "wss" } else { "ws" };
 
     // Determine the actual SSH listen address (CLI arg > config > default)
     let ssh_addr = args
@@ -162,9 +200,26 @@ async fn main() -> Result<()> {
             "{}",
             t::icon_ok(&format!("Gateway listening on SSH {}", t::info(&ssh_addr)))
         );
+        if let Some(socket) = args.unix_socket.as_ref().or(config.gateway_socket.as_ref()) {
+            let url = format!("unix:{}", socket.display());
+            println!(
+                "{}",
+                t::icon_ok(&format!("Gateway listening on {}", t::info(&url)))
+            );
+        }
+    }
+    // There is no WebSocket listener, so TLS (and client certificates) have
+    // nothing to apply to; SSH public-key auth against authorized_clients
+    // is what authenticates clients at the transport layer.
+    if tls_cert.is_some() || tls_key.is_some() {
+        eprintln!(
+            "{}",
+            t::icon_warn(
+                "tls_cert/tls_key are ignored: the gateway only serves SSH; \
+                 clients authenticate with keys listed in authorized_clients"
+            )
+        );
     }
-    // Keep the ws:// listen var for run_gateway options but don't surface it.
-    let _ = scheme;
 
     // ── Open the secrets vault ───────────────────────────────────────────
     //
@@ -189,7 +244,7 @@ async fn main() -> Result<()> {
             }
         }
 
-        if config.secrets_password_protected {
+        let manager = if config.secrets_password_protected {
             if let Some(pw) = env_password {
                 if !protocol_stdio {
                     println!("  {} Vault password provided by launcher", t::icon_ok(""));
@@ -213,7 +268,8 @@ async fn main() -> Result<()> {
             }
         } else {
             SecretsManager::new(&creds_dir)
-        }
+        };
+        manager.with_backend_kind(config.secrets_backend)?
     };
 
     let shared_vault: crate::SharedVault = std::sync::Arc::new(tokio::sync::Mutex::new(vault));
@@ -331,7 +387,7 @@ async fn main() -> Result<()> {
         if let Err(e) = sm.load_skills() {
             eprintln!("⚠ Could not load skills: {}", e);
         }
-        if let Some(url) = config.clawhub_url.as_deref() {
+        if let Some(url) = config.skill_registry() {
             sm.set_registry(url, config.clawhub_token.clone());
         }
         let shared_skills: crate::SharedSkillManager =
@@ -345,6 +401,7 @@ async fn main() -> Result<()> {
                 tls_key,
                 ssh_listen: args.ssh_listen.clone(),
                 ssh_stdio: args.ssh_stdio,
+                unix_socket: args.unix_socket.clone(),
                 ssh_host_key: args.ssh_host_key.clone(),
                 ssh_authorized_clients: args.ssh_authorized_clients.clone(),
             },
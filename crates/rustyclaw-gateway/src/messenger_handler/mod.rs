@@ -324,14 +324,13 @@ async fn process_incoming_message(
         "Received message"
     );
 
-    let workspace_dir = config.workspace_dir();
-
     // Build conversation key for this chat
     let conv_key = format!(
         "{}:{}",
         messenger_type,
         msg.channel.as_deref().unwrap_or(&msg.sender)
     );
+    let workspace_dir = crate::helpers::session_workspace_dir(config, &conv_key);
 
     // Get or create conversation history
     let mut messages = {
@@ -406,12 +405,18 @@ async fn process_incoming_message(
         base_url: model_ctx.base_url.clone(),
         api_key: effective_key,
         messages: messages.clone(),
+        sampling: Default::default(),
+        cache_prompt: config.prompt_cache.cache_control,
+        thinking_budget: config.thinking.budget(),
     };
 
     // Run the agentic tool loop
     let mut final_response = String::new();
 
     for _round in 0..MAX_TOOL_ROUNDS {
+        // A globally paused agent holds messenger replies too.
+        rustyclaw_core::agent_pause::global().resumed().await;
+
         let result = if resolved.provider == "anthropic" {
             providers::call_anthropic_with_tools(http, &resolved, None).await
         } else if resolved.provider == "google" {
@@ -529,6 +534,61 @@ async fn process_incoming_message(
                         (err, true)
                     }
                 }
+            } else if crate::translate_handler::is_translate_tool(&tc.name) {
+                // Messenger turns have no stop button, so the flag is never set.
+                let no_cancel = crate::ToolCancelFlag::default();
+                match crate::translate_handler::execute_translate(
+                    http,
+                    &resolved,
+                    &tc.arguments,
+                    &no_cancel,
+                )
+                .await
+                {
+                    Ok(text) => (text, false),
+                    Err(err) => (err, true),
+                }
+            } else if crate::translate_handler::is_translate_file_tool(&tc.name) {
+                let no_cancel = crate::ToolCancelFlag::default();
+                match crate::translate_handler::execute_translate_file(
+                    http,
+                    &resolved,
+                    &tc.arguments,
+                    &workspace_dir,
+                    &no_cancel,
+                )
+                .await
+                {
+                    Ok(text) => (text, false),
+                    Err(err) => (err, true),
+                }
+            } else if crate::proofread_handler::is_proofread_tool(&tc.name) {
+                let no_cancel = crate::ToolCancelFlag::default();
+                match crate::proofread_handler::execute_proofread(
+                    http,
+                    &resolved,
+                    &tc.arguments,
+                    &workspace_dir,
+                    &no_cancel,
+                )
+                .await
+                {
+                    Ok(text) => (text, false),
+                    Err(err) => (err, true),
+                }
+            } else if crate::summarize_handler::is_summarize_tool(&tc.name) {
+                let no_cancel = crate::ToolCancelFlag::default();
+                match crate::summarize_handler::execute_summarize(
+                    http,
+                    &resolved,
+                    &tc.arguments,
+                    &no_cancel,
+                )
+                .await
+                {
+                    Ok(text) => (text, false),
+                    Err(err) => (err, true),
+                }
             } else if crate::model_handler::is_model_tool(&tc.name) {
                 // Model management tools
                 match crate::model_handler::execute_model_tool(
@@ -548,6 +608,10 @@ async fn process_incoming_message(
                 }
             };
 
+            if tc.name == "sessions_spawn" && !is_error {
+                crate::task_registry::record_subagent_task(task_mgr, &conv_key, &output).await;
+            }
+
             trace!(
                 tool_name = %tc.name,
                 is_error = is_error,
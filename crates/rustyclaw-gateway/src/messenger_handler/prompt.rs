@@ -234,6 +234,8 @@ If a credential exists, use `secrets_get` to retrieve it — don't ask the user
 Before answering questions about prior work, decisions, dates, people, preferences, or todos:
 Run `memory_search` first, then use `memory_get` to pull relevant context.
 If low confidence after search, mention that you checked but didn't find a match.
+Working state you need on a later turn (counters, partial results, flags) goes in
+`kv_store(action=\"set\", key=\"...\", value=\"...\")`, not in scratch files.
 
 ### File Operations
 - `read_file` — read file contents (supports text, PDF, docx, etc.)
@@ -241,11 +243,16 @@ If low confidence after search, mention that you checked but didn't find a match
 - `edit_file` — surgical search-and-replace (include enough context for unique match)
 - `find_files` — find by name/glob pattern
 - `search_files` — search file contents (like grep)
+- `move_file` / `copy_file` — move, rename or copy files and folders (`force` to replace)
+- `regex` — find, replace or extract with a regex over text or a file (instead of sed)
 
 ### Command Execution
 - Short commands: `execute_command(command=\"...\")`
 - Long-running: `execute_command(command=\"...\", background=true)` then `process(action=\"poll\", session_id=\"...\")`
 - Interactive TTY: use `pty=true` for commands needing terminal
+- Variables for every later command: `env_var(action=\"set\", name=\"...\", value=\"...\")`
+- Containers: `docker(action=\"ps\")`, `docker(action=\"run\", image=\"...\")`, then `logs` / `exec` / `stop` (instead of `execute_command(\"docker ...\")`)
+- Callbacks and sharing: `http_server(action=\"capture\")` gives a loopback URL to use as an OAuth redirect or webhook target; `http_server(action=\"result\", id=\"...\")` returns the request it received. `http_server(action=\"serve\", path=\"...\")` shares a file or directory for a few minutes
 
 ### Sub-Agents
 Spawn sub-agents for complex or time-consuming tasks:
@@ -0,0 +1,127 @@
+//! Prometheus `/metrics` endpoint.
+//!
+//! With `[metrics] enabled = true` the gateway records telemetry in a
+//! [`PrometheusObserver`] (alongside any other observer) and serves it on a
+//! small HTTP listener of its own.  The listener answers `GET /metrics` and
+//! nothing else, one request per connection, which is all a Prometheus
+//! scraper needs.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use rustyclaw_core::config::MetricsConfig;
+use rustyclaw_core::observability::{CompositeObserver, PrometheusObserver};
+
+use crate::{SharedObserver, SharedVault};
+
+/// Longest request head read from a scraper.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Time a scraper has to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Bind the metrics listener and start serving.  Returns the observer the
+/// gateway should record into: the Prometheus one, combined with
+/// `observer` when there is one.
+pub async fn start(
+    config: &MetricsConfig,
+    observer: Option<SharedObserver>,
+    vault: SharedVault,
+    cancel: CancellationToken,
+) -> Result<SharedObserver> {
+    let listener = TcpListener::bind(&config.listen)
+        .await
+        .with_context(|| format!("Failed to bind metrics listener on {}", config.listen))?;
+    info!(address = %config.listen, "Serving Prometheus metrics on /metrics");
+
+    let prometheus = Arc::new(PrometheusObserver::new());
+    let serving = prometheus.clone();
+    tokio::spawn(async move {
+        loop {
+            let stream = tokio::select! {
+                _ = cancel.cancelled() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        warn!(error = %e, "Metrics accept error");
+                        continue;
+                    }
+                },
+            };
+            let prometheus = serving.clone();
+            let vault = vault.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve(stream, &prometheus, &vault).await {
+                    debug!(error = %e, "Metrics request failed");
+                }
+            });
+        }
+    });
+
+    Ok(match observer {
+        Some(other) => Arc::new(CompositeObserver::new(vec![other, prometheus])),
+        None => prometheus,
+    })
+}
+
+async fn serve(
+    mut stream: TcpStream,
+    prometheus: &PrometheusObserver,
+    vault: &SharedVault,
+) -> Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = tokio::time::timeout(READ_TIMEOUT, stream.read(&mut buf))
+            .await
+            .context("Timed out reading request")??;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+        if head.len() > MAX_REQUEST_BYTES {
+            anyhow::bail!("Request head too large");
+        }
+    }
+
+    let response = match request_target(&head) {
+        Some(("GET", "/metrics")) => {
+            prometheus.set_vault_locked(vault.lock().await.is_locked());
+            response(
+                "200 OK",
+                "text/plain; version=0.0.4; charset=utf-8",
+                &prometheus.render(),
+            )
+        }
+        Some((_, "/metrics")) => response("405 Method Not Allowed", "text/plain", ""),
+        _ => response("404 Not Found", "text/plain", ""),
+    };
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Method and path (without query) from the request line.
+fn request_target(head: &[u8]) -> Option<(&str, &str)> {
+    let line = std::str::from_utf8(head).ok()?.lines().next()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    Some((method, target.split('?').next().unwrap_or(target)))
+}
+
+fn response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
@@ -190,6 +190,11 @@ async fn exec_model_set(
     }
 
     registry.set_active(&model_id)?;
+    crate::status_broadcast::publish(
+        None,
+        rustyclaw_core::gateway::StatusType::ModelConfigured,
+        format!("{} (active)", model_id),
+    );
 
     Ok(json!({
         "success": true,
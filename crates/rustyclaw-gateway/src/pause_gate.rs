@@ -0,0 +1,154 @@
+//! Where the gateway's tool loops wait for a paused agent.
+//!
+//! The switch itself is [`rustyclaw_core::agent_pause`].  Each turn calls
+//! [`hold_while_paused`] before it starts a round, so a tool call that is
+//! already running finishes and the pause takes effect before the next
+//! model call.  A held turn tells its own client with an `AgentPaused`
+//! status; [`spawn_status_relay`] tells every other client.
+//!
+//! A cancel while held ends the wait, and the turn's usual cancel check
+//! stops it.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tracing::info;
+
+use rustyclaw_core::agent_pause::{self, AgentPause};
+use rustyclaw_core::gateway::StatusType;
+use rustyclaw_core::gateway::protocol;
+use rustyclaw_core::gateway::transport::TransportWriter;
+
+use crate::ToolCancelFlag;
+use crate::status_broadcast;
+use crate::tool_executor::cancel_requested;
+
+/// Hold the turn while `pause` is paused, until it is resumed or the turn
+/// is cancelled.  Returns how long the turn was held.
+pub(crate) async fn hold_while_paused(
+    writer: &mut dyn TransportWriter,
+    pause: &AgentPause,
+    tool_cancel: &ToolCancelFlag,
+) -> Result<Duration> {
+    let Some(state) = pause.state() else {
+        return Ok(Duration::ZERO);
+    };
+    let held = Instant::now();
+    info!("Turn held by agent pause");
+    protocol::server::send_status(writer, StatusType::AgentPaused, &state.describe()).await?;
+    tokio::select! {
+        _ = pause.resumed() => {
+            protocol::server::send_status(writer, StatusType::AgentResumed, "Agent resumed")
+                .await?;
+        }
+        _ = cancel_requested(tool_cancel) => {}
+    }
+    Ok(held.elapsed())
+}
+
+/// Broadcast every pause and resume to all connections, for the gateway's
+/// lifetime.
+pub(crate) fn spawn_status_relay() {
+    let mut changes = agent_pause::global().subscribe();
+    tokio::spawn(async move {
+        while changes.changed().await.is_ok() {
+            let state = changes.borrow_and_update().clone();
+            match state {
+                Some(state) => {
+                    status_broadcast::publish(None, StatusType::AgentPaused, state.describe())
+                }
+                None => status_broadcast::publish(None, StatusType::AgentResumed, "Agent resumed"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::gateway::{ServerFrame, ServerPayload};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+    use crate::test_support::ChannelWriter;
+    use crate::turn_budget::TurnBudget;
+
+    async fn next_status(frames: &mut UnboundedReceiver<ServerFrame>) -> StatusType {
+        match frames.recv().await.unwrap().payload {
+            ServerPayload::Status { status, .. } => status,
+            other => panic!("expected a status, got {other:?}"),
+        }
+    }
+
+    /// Run three rounds the way `dispatch_text_message` does; the first
+    /// round's tool call pauses the agent, like the `gateway` tool would.
+    async fn run_turn(
+        pause: Arc<AgentPause>,
+        cancel: ToolCancelFlag,
+        rounds: Arc<AtomicUsize>,
+        tx: UnboundedSender<ServerFrame>,
+    ) -> usize {
+        let mut writer = ChannelWriter(tx);
+        let mut budget = TurnBudget::new(3, 0);
+        loop {
+            let held = hold_while_paused(&mut writer, &pause, &cancel)
+                .await
+                .unwrap();
+            budget.add_pause(held);
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            let Ok(round) = budget.start_round() else {
+                break;
+            };
+            if round == 1 {
+                pause.pause(Some("test".into()));
+            }
+            rounds.fetch_add(1, Ordering::Relaxed);
+        }
+        rounds.load(Ordering::Relaxed)
+    }
+
+    #[tokio::test]
+    async fn test_paused_turn_starts_no_rounds_until_resumed() {
+        let pause = Arc::new(AgentPause::new());
+        let rounds = Arc::new(AtomicUsize::new(0));
+        let (tx, mut frames) = tokio::sync::mpsc::unbounded_channel();
+        let turn = tokio::spawn(run_turn(
+            pause.clone(),
+            ToolCancelFlag::default(),
+            rounds.clone(),
+            tx,
+        ));
+
+        assert_eq!(next_status(&mut frames).await, StatusType::AgentPaused);
+        // The round that paused finished; no other starts while paused.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(rounds.load(Ordering::Relaxed), 1);
+        assert!(!turn.is_finished());
+
+        assert!(pause.resume());
+        assert_eq!(next_status(&mut frames).await, StatusType::AgentResumed);
+        assert_eq!(turn.await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_ends_a_paused_turn() {
+        let pause = Arc::new(AgentPause::new());
+        let cancel = ToolCancelFlag::default();
+        let rounds = Arc::new(AtomicUsize::new(0));
+        let (tx, mut frames) = tokio::sync::mpsc::unbounded_channel();
+        let turn = tokio::spawn(run_turn(pause.clone(), cancel.clone(), rounds, tx));
+
+        assert_eq!(next_status(&mut frames).await, StatusType::AgentPaused);
+        cancel.store(true, Ordering::Relaxed);
+        let rounds = tokio::time::timeout(Duration::from_secs(5), turn)
+            .await
+            .expect("cancel should release the hold")
+            .unwrap();
+        assert_eq!(rounds, 1);
+        // Still paused for everyone else.
+        assert!(pause.is_paused());
+    }
+}
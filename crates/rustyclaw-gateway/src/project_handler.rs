@@ -12,7 +12,7 @@ use tracing::debug;
 
 use rustyclaw_core::config::Config;
 use rustyclaw_core::gateway::protocol::server::send_frame;
-use rustyclaw_core::gateway::{ServerFrame, ServerFrameType, ServerPayload, transport};
+use rustyclaw_core::gateway::{ErrorCode, ServerFrame, ServerFrameType, ServerPayload, transport};
 use rustyclaw_core::projects::{ProjectId, ProjectManager};
 
 use crate::admin;
@@ -62,6 +62,7 @@ pub(crate) async fn handle_project_create(
             payload: ServerPayload::Error {
                 ok: false,
                 message: format!("Could not create project directory '{path}': {e}"),
+                code: ErrorCode::InvalidRequest,
             },
         };
         return send_frame(writer, &frame).await;
@@ -105,6 +106,7 @@ pub(crate) async fn handle_project_delete(
         payload: ServerPayload::Error {
             ok: false,
             message: "Cannot delete the default or last remaining project".to_string(),
+            code: ErrorCode::InvalidRequest,
         },
     };
     send_frame(writer, &frame).await
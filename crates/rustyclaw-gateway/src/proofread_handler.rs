@@ -0,0 +1,60 @@
+//! `proofread` tool execution for the gateway.
+//!
+//! Model-backed proofreading needs the configured model, so the gateway
+//! intercepts the tool and supplies the provider call; chunking, code
+//! masking and merging live in `rustyclaw_core::tools::proofread`.  The
+//! offline `engine = "spell"` mode needs no model and runs the core
+//! executor on a blocking thread.
+
+use std::path::Path;
+
+use serde_json::Value;
+use tracing::instrument;
+
+use rustyclaw_core::gateway::ProviderRequest;
+use rustyclaw_core::tools::{self, proofread};
+
+use crate::translate_handler::ask_model;
+use crate::{ToolCancelFlag, tool_executor};
+
+/// Check if a tool name is the proofread tool.
+pub fn is_proofread_tool(name: &str) -> bool {
+    name == "proofread"
+}
+
+/// Execute a `proofread` tool call against the conversation's model.
+///
+/// Setting `tool_cancel` drops the in-flight model request or stops
+/// waiting for the spell checker.
+#[instrument(skip(http, resolved, args, workspace_dir, tool_cancel))]
+pub async fn execute_proofread(
+    http: &reqwest::Client,
+    resolved: &ProviderRequest,
+    args: &Value,
+    workspace_dir: &Path,
+    tool_cancel: &ToolCancelFlag,
+) -> Result<String, String> {
+    let run = async {
+        match proofread::Engine::from_args(args)? {
+            proofread::Engine::Spell => {
+                let args = args.clone();
+                let dir = workspace_dir.to_path_buf();
+                tokio::task::spawn_blocking(move || proofread::exec_proofread(&args, &dir))
+                    .await
+                    .map_err(|e| format!("Spell check task failed: {}", e))?
+            }
+            proofread::Engine::Model => {
+                proofread::proofread_with(args, workspace_dir, |prompt| {
+                    ask_model(http, resolved, prompt, "Proofreading")
+                })
+                .await
+            }
+        }
+    };
+    tokio::select! {
+        result = run => result,
+        _ = tool_executor::cancel_requested(tool_cancel) => {
+            Err(tools::TOOL_CANCELLED.to_string())
+        }
+    }
+}
@@ -8,8 +8,8 @@ use rustyclaw_core::error_details::{ErrorLike, RequestDetails};
 use rustyclaw_core::gateway::protocol::server;
 use rustyclaw_core::gateway::transport::TransportWriter;
 use rustyclaw_core::gateway::{
-    ChatMessage, CopilotSession, ModelContext, ModelResponse, ParsedToolCall, ProbeResult,
-    ProviderRequest, ToolCallResult,
+    ChatMessage, CopilotSession, ErrorCode, MAX_SYSTEM_PREFIX_BYTES, ModelContext, ModelResponse,
+    ParsedToolCall, ProbeResult, ProviderRequest, ToolCallResult,
 };
 use rustyclaw_core::providers;
 
@@ -112,32 +112,67 @@ pub fn apply_copilot_headers(
 /// Merge an incoming chat request with the gateway's model context.
 ///
 /// Fields present in the request take priority; missing fields fall back
-/// to the gateway defaults.  Returns an error message string if a required
-/// field cannot be resolved from either source.
+/// to the gateway defaults.  A `system_prefix` goes in front of the
+/// leading system prompt, or becomes the system prompt if there is none.
+/// Returns an error code and message if a required field cannot be
+/// resolved from either source, if a sampling override is out of range, or
+/// if the prefix is longer than [`MAX_SYSTEM_PREFIX_BYTES`].
 pub fn resolve_request(
     req: rustyclaw_core::gateway::ChatRequest,
     ctx: Option<&ModelContext>,
-) -> std::result::Result<ProviderRequest, String> {
+) -> std::result::Result<ProviderRequest, (ErrorCode, String)> {
+    let unconfigured = |field: &str| {
+        (
+            ErrorCode::ConfigError,
+            format!("No {field} specified and gateway has no model configured"),
+        )
+    };
     let provider = req
         .provider
         .or_else(|| ctx.map(|c| c.provider.clone()))
-        .ok_or_else(|| "No provider specified and gateway has no model configured".to_string())?;
+        .ok_or_else(|| unconfigured("provider"))?;
     let model = req
         .model
         .or_else(|| ctx.map(|c| c.model.clone()))
-        .ok_or_else(|| "No model specified and gateway has no model configured".to_string())?;
+        .ok_or_else(|| unconfigured("model"))?;
     let base_url = req
         .base_url
         .or_else(|| ctx.map(|c| c.base_url.clone()))
-        .ok_or_else(|| "No base_url specified and gateway has no model configured".to_string())?;
+        .ok_or_else(|| unconfigured("base_url"))?;
     let api_key = req.api_key.or_else(|| ctx.and_then(|c| c.api_key.clone()));
+    req.sampling
+        .validate()
+        .map_err(|msg| (ErrorCode::InvalidRequest, msg))?;
+
+    let mut messages = req.messages;
+    if let Some(prefix) = req.system_prefix.as_deref().map(str::trim)
+        && !prefix.is_empty()
+    {
+        if prefix.len() > MAX_SYSTEM_PREFIX_BYTES {
+            return Err((
+                ErrorCode::InvalidRequest,
+                format!(
+                    "system_prefix is {} bytes; the limit is {}",
+                    prefix.len(),
+                    MAX_SYSTEM_PREFIX_BYTES
+                ),
+            ));
+        }
+        match messages.first_mut().filter(|m| m.role == "system") {
+            Some(system) => system.content = format!("{}\n\n{}", prefix, system.content),
+            None => messages.insert(0, ChatMessage::text("system", prefix)),
+        }
+    }
 
     Ok(ProviderRequest {
-        messages: req.messages,
+        messages,
         model,
         provider,
         base_url,
         api_key,
+        sampling: req.sampling,
+        cache_prompt: false,
+        thinking_budget: None,
     })
 }
 
@@ -495,6 +530,10 @@ pub async fn compact_conversation(
         provider: resolved.provider.clone(),
         base_url: resolved.base_url.clone(),
         api_key: resolved.api_key.clone(),
+        // Summaries use provider defaults, not the turn's overrides.
+        sampling: Default::default(),
+        cache_prompt: false,
+        thinking_budget: None,
     };
 
     let summary_result = tokio::time::timeout(std::time::Duration::from_secs(60), async {
@@ -618,6 +657,19 @@ pub async fn validate_model_connection(
         }
     };
 
+    // `[provider_headers]` go on the probe as on chat requests, so a proxy
+    // that needs them lets it through.  The details show them masked.
+    let extra = providers::extra_headers_for(&ctx.provider);
+    let with_extra =
+        |base: &[(&str, &str)]| -> Vec<(String, String)> {
+            base.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .chain(extra.iter().map(|(name, value)| {
+                    (name.clone(), providers::redact_extra_header(name, value))
+                }))
+                .collect()
+        };
+
     // Per-branch probe: build the request, capture the structured
     // request snapshot (method/url/headers/bearer) before issuing it,
     // and run it.  Each branch returns the response (or send error)
@@ -637,17 +689,18 @@ pub async fn validate_model_connection(
         let api_key = ctx.api_key.as_deref().unwrap_or("");
         let details = RequestDetails::new("probe.anthropic", "POST", url.clone())
             .with_provider(&ctx.provider)
-            .with_request_headers([
+            .with_request_headers(with_extra(&[
                 ("x-api-key", "<redacted>"),
                 ("anthropic-version", "2023-06-01"),
                 ("content-type", "application/json"),
-            ])
+            ]))
             .with_bearer(Some(api_key));
         let builder = http
             .post(&url)
             .header("x-api-key", api_key)
             .header("anthropic-version", "2023-06-01")
             .json(&body);
+        let builder = providers::apply_extra_headers(builder, extra);
         (details, send_with_retry(builder).await)
     } else if ctx.provider == "google" {
         // Google: check the model metadata endpoint (no chat needed).
@@ -665,22 +718,27 @@ pub async fn validate_model_connection(
         );
         let details = RequestDetails::new("probe.google", "GET", public_url)
             .with_provider(&ctx.provider)
+            .with_request_headers(with_extra(&[]))
             .with_bearer(Some(key));
-        (details, send_with_retry(http.get(&url)).await)
+        let builder = providers::apply_extra_headers(http.get(&url), extra);
+        (details, send_with_retry(builder).await)
     } else {
         // OpenAI-compatible: GET /models — lightweight auth check.
         let url = format!("{}/models", ctx.base_url.trim_end_matches('/'));
         let mut details = RequestDetails::new("probe.openai_compatible", "GET", url.clone())
             .with_provider(&ctx.provider)
             .with_bearer(effective_key.as_deref());
-        if effective_key.is_some() {
-            details = details.with_request_headers([("Authorization", "Bearer <redacted>")]);
-        }
+        details = details.with_request_headers(if effective_key.is_some() {
+            with_extra(&[("Authorization", "Bearer <redacted>")])
+        } else {
+            with_extra(&[])
+        });
         let mut builder = http.get(&url);
         if let Some(ref key) = effective_key {
             builder = builder.bearer_auth(key);
         }
         builder = apply_copilot_headers(builder, &ctx.provider, &[]);
+        builder = providers::apply_extra_headers(builder, extra);
         (details, send_with_retry(builder).await)
     };
 
@@ -790,3 +848,83 @@ fn format_probe_error(err: &anyhow_tracing::Error) -> String {
 pub use rustyclaw_core::providers::{
     call_anthropic_with_tools, call_google_with_tools, call_openai_with_tools,
 };
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::gateway::ChatRequest;
+
+    fn ctx() -> ModelContext {
+        ModelContext {
+            provider: "openai".into(),
+            model: "gpt-4o".into(),
+            base_url: "https://api.openai.com/v1".into(),
+            api_key: None,
+        }
+    }
+
+    fn chat(messages: serde_json::Value, system_prefix: Option<&str>) -> ChatRequest {
+        serde_json::from_value(json!({
+            "type": "chat",
+            "messages": messages,
+            "system_prefix": system_prefix,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_system_prefix_is_prepended_to_the_system_prompt() {
+        let turn = json!([
+            { "role": "system", "content": "You are Claw.\n\n## SOUL.md\nBe kind." },
+            { "role": "user", "content": "List the open issues" },
+        ]);
+        let resolved = resolve_request(chat(turn, Some("Respond in JSON.")), Some(&ctx())).unwrap();
+        assert_eq!(resolved.messages.len(), 2);
+        assert_eq!(
+            resolved.messages[0].content,
+            "Respond in JSON.\n\nYou are Claw.\n\n## SOUL.md\nBe kind."
+        );
+
+        // Without a system prompt the prefix becomes one.
+        let bare = json!([{ "role": "user", "content": "hi" }]);
+        let resolved = resolve_request(chat(bare, Some("Be terse.")), Some(&ctx())).unwrap();
+        assert_eq!(resolved.messages[0].role, "system");
+        assert_eq!(resolved.messages[0].content, "Be terse.");
+        assert_eq!(resolved.messages[1].role, "user");
+    }
+
+    #[test]
+    fn test_system_prefix_does_not_carry_over_to_the_next_turn() {
+        let mut history = vec![
+            ChatMessage::text("system", "You are Claw."),
+            ChatMessage::text("user", "Summarize the log"),
+        ];
+        let first = chat(json!(history), Some("Respond in JSON."));
+        let resolved = resolve_request(first.clone(), Some(&ctx())).unwrap();
+        assert!(resolved.messages[0].content.starts_with("Respond in JSON."));
+        // The request the turn was built from is left alone.
+        assert_eq!(first.messages[0].content, "You are Claw.");
+
+        history.push(ChatMessage::text("assistant", "{\"errors\": 3}"));
+        history.push(ChatMessage::text("user", "And the warnings?"));
+        let resolved = resolve_request(chat(json!(history), None), Some(&ctx())).unwrap();
+        assert_eq!(resolved.messages[0].content, "You are Claw.");
+        assert!(
+            resolved
+                .messages
+                .iter()
+                .all(|m| !m.content.contains("Respond in JSON"))
+        );
+    }
+
+    #[test]
+    fn test_oversized_system_prefix_is_rejected() {
+        let prefix = "x".repeat(MAX_SYSTEM_PREFIX_BYTES + 1);
+        let turn = json!([{ "role": "user", "content": "hi" }]);
+        let err = resolve_request(chat(turn, Some(&prefix)), Some(&ctx()))
+            .err()
+            .unwrap();
+        assert_eq!(err.0, ErrorCode::InvalidRequest);
+        assert!(err.1.contains("system_prefix"), "{}", err.1);
+    }
+}
@@ -0,0 +1,85 @@
+//! Replay protection for config-mutating frames.
+//!
+//! Clients number their frames through [`WireFrame::sequence`], and a
+//! frame that changes configuration (`Reload`, `ModelSwitch`, …; see
+//! [`ClientFrameType::mutates_config`]) is only accepted when its number
+//! is above that of the last one accepted on the connection.  A frame
+//! captured earlier and sent again, on its own or out of order, is
+//! refused.  Other frames are not checked.
+//!
+//! The guard is per connection: a new connection has to authenticate
+//! again before it can send anything.
+//!
+//! [`WireFrame::sequence`]: rustyclaw_core::gateway::WireFrame::sequence
+
+use rustyclaw_core::gateway::ClientFrameType;
+
+/// Last accepted sequence number of a config-mutating frame.
+#[derive(Debug, Default)]
+pub(crate) struct ReplayGuard {
+    last_mutation: u64,
+}
+
+impl ReplayGuard {
+    /// Accept or refuse a frame of `frame_type` numbered `sequence`,
+    /// recording it when accepted.
+    pub(crate) fn check(
+        &mut self,
+        frame_type: ClientFrameType,
+        sequence: u64,
+    ) -> Result<(), String> {
+        if !frame_type.mutates_config() {
+            return Ok(());
+        }
+        if sequence == 0 {
+            return Err(format!(
+                "{:?} frames must carry a sequence number",
+                frame_type
+            ));
+        }
+        if sequence <= self.last_mutation {
+            return Err(format!(
+                "{:?} frame #{} is stale or replayed (last accepted #{})",
+                frame_type, sequence, self.last_mutation
+            ));
+        }
+        self.last_mutation = sequence;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_sequence_is_rejected() {
+        let mut guard = ReplayGuard::default();
+        assert!(guard.check(ClientFrameType::Reload, 3).is_ok());
+        let err = guard.check(ClientFrameType::Reload, 3).unwrap_err();
+        assert!(err.contains("replayed"), "{}", err);
+        // Still rejected as a different mutating frame, or an older number.
+        assert!(guard.check(ClientFrameType::SetAgentName, 3).is_err());
+        assert!(guard.check(ClientFrameType::ModelSwitch, 2).is_err());
+        assert!(guard.check(ClientFrameType::ModelSwitch, 4).is_ok());
+    }
+
+    #[test]
+    fn test_unsequenced_mutation_is_rejected() {
+        let mut guard = ReplayGuard::default();
+        assert!(guard.check(ClientFrameType::ToolPermissionSet, 0).is_err());
+        assert!(guard.check(ClientFrameType::ToolPermissionSet, 1).is_ok());
+    }
+
+    #[test]
+    fn test_read_only_frames_are_not_checked() {
+        let mut guard = ReplayGuard::default();
+        assert!(guard.check(ClientFrameType::Reload, 10).is_ok());
+        for _ in 0..2 {
+            assert!(guard.check(ClientFrameType::Chat, 0).is_ok());
+            assert!(guard.check(ClientFrameType::ThreadList, 5).is_ok());
+        }
+        // Gaps are fine; only order matters.
+        assert!(guard.check(ClientFrameType::Reload, 25).is_ok());
+    }
+}
@@ -8,12 +8,14 @@ use rustyclaw_core::gateway::protocol::server::{
     send_secrets_set_policy_result, send_secrets_setup_totp_result, send_secrets_store_result,
     send_secrets_verify_totp_result, send_vault_unlocked,
 };
-use rustyclaw_core::gateway::{ClientPayload, SecretEntryDto, transport};
+use rustyclaw_core::gateway::{ClientPayload, SecretEntryDto, StatusType, transport};
 use rustyclaw_core::secrets::{
-    AccessContext, AccessPolicy, CredentialValue, SecretEntry, SecretKind,
+    AccessContext, AccessPolicy, CharClass, CredentialValue, PasswordSpec, SecretEntry, SecretKind,
+    generate_passphrase, generate_password,
 };
 
 use super::SharedVault;
+use crate::status_broadcast;
 
 /// Execute a secrets-vault tool against the shared vault.
 ///
@@ -43,6 +45,7 @@ pub async fn execute_secrets_tool(
         "secrets_get" => exec_secrets_get(args, vault).await,
         "secrets_store" => exec_secrets_store(args, vault).await,
         "secrets_set_policy" => exec_secrets_set_policy(args, vault).await,
+        "secrets_generate" => exec_secrets_generate(args, vault).await,
         _ => {
             warn!("Unknown secrets tool requested");
             Err(format!("Unknown secrets tool: {}", name))
@@ -94,11 +97,7 @@ pub async fn exec_secrets_get(
 
     debug!(credential = cred_name, "Retrieving credential");
 
-    let ctx = AccessContext {
-        user_approved: false,
-        authenticated: false,
-        active_skill: None,
-    };
+    let ctx = AccessContext::requested_by("tool:secrets_get");
 
     let mut mgr = vault.lock().await;
     match mgr.get_credential(cred_name, &ctx) {
@@ -320,25 +319,155 @@ pub async fn exec_secrets_set_policy(
     Ok(format!("Policy for '{}' set to '{}'.", cred_name, policy,))
 }
 
+/// Generate a random password or passphrase, and store it in the vault
+/// when `name` is given.
+///
+/// A stored value is left out of the result unless `reveal` is set, so a
+/// credential the agent creates for the user never passes through the
+/// model.  An existing credential is only replaced with `overwrite`.
+#[instrument(skip(args, vault))]
+pub async fn exec_secrets_generate(
+    args: &serde_json::Value,
+    vault: &SharedVault,
+) -> Result<String, String> {
+    let style = args
+        .get("style")
+        .and_then(|v| v.as_str())
+        .unwrap_or("password");
+    let (generated, what) = match style {
+        "password" => {
+            let mut spec = PasswordSpec::default();
+            if let Some(length) = args.get("length").and_then(|v| v.as_u64()) {
+                spec.length = length as usize;
+            }
+            if let Some(charset) = args.get("charset").and_then(|v| v.as_str()) {
+                spec.classes = charset
+                    .split(',')
+                    .filter(|name| !name.trim().is_empty())
+                    .map(|name| {
+                        CharClass::parse(name).ok_or_else(|| {
+                            format!(
+                                "Unknown character class: '{}'. Use lower, upper, digits, \
+                                 symbols, or hex.",
+                                name.trim(),
+                            )
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            spec.exclude_ambiguous = args
+                .get("exclude_ambiguous")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let what = format!("{}-character password", spec.length);
+            (generate_password(&spec), what)
+        }
+        "passphrase" => {
+            let words = args.get("words").and_then(|v| v.as_u64()).unwrap_or(6);
+            let separator = args
+                .get("separator")
+                .and_then(|v| v.as_str())
+                .unwrap_or("-");
+            let what = format!("{}-word passphrase", words);
+            (generate_passphrase(words as usize, separator), what)
+        }
+        other => {
+            return Err(format!(
+                "Unknown style: '{}'. Use password or passphrase.",
+                other,
+            ));
+        }
+    };
+    let generated = generated.map_err(|e| e.to_string())?;
+    let summary = format!("{} (~{:.0} bits)", what, generated.entropy_bits);
+
+    let Some(cred_name) = args.get("name").and_then(|v| v.as_str()) else {
+        debug!(style, "Generated secret without storing it");
+        return Ok(format!("Generated a {}:\n{}", summary, generated.value));
+    };
+
+    let overwrite = args
+        .get("overwrite")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let exists = vault
+        .lock()
+        .await
+        .list_all_entries()
+        .iter()
+        .any(|(name, _)| name == cred_name);
+    if exists && !overwrite {
+        return Err(format!(
+            "Credential '{}' already exists. Pass overwrite=true to replace it.",
+            cred_name,
+        ));
+    }
+
+    let username = args.get("username").and_then(|v| v.as_str());
+    let default_kind = if username.is_some() {
+        "username_password"
+    } else {
+        "token"
+    };
+    let mut store_args = serde_json::json!({
+        "name": cred_name,
+        "kind": args.get("kind").and_then(|v| v.as_str()).unwrap_or(default_kind),
+        "value": generated.value,
+        "description": args
+            .get("description")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Generated {}", summary)),
+    });
+    for key in ["policy", "username"] {
+        if let Some(value) = args.get(key) {
+            store_args[key] = value.clone();
+        }
+    }
+    let stored = exec_secrets_store(&store_args, vault).await?;
+
+    let reveal = args
+        .get("reveal")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    debug!(credential = cred_name, reveal, "Generated secret stored");
+    let mut out = format!("Generated a {}. {}", summary, stored);
+    if reveal {
+        out.push_str(&format!("\nValue: {}", generated.value));
+    } else {
+        out.push_str(" The value was not returned; use secrets_get to read it.");
+    }
+    Ok(out)
+}
+
 /// Handle a vault/secrets client frame against the shared vault.
 ///
 /// Covers the `UnlockVault` and `Secrets*` protocol payloads sent by a
 /// connected client. Each arm locks the vault, performs the requested
 /// operation, and streams a typed result frame back over `writer`. Payloads
 /// outside the secrets family are ignored (the caller is expected to route
-/// only secrets variants here).
+/// only secrets variants here).  A successful unlock is also broadcast to the
+/// other connections, which were told the vault was locked when they
+/// connected.  Secret reads are logged under the frame's request ID.
 pub(crate) async fn handle_secrets_frame(
     writer: &mut dyn transport::TransportWriter,
     vault: &SharedVault,
     payload: ClientPayload,
+    connection_id: u64,
 ) -> Result<()> {
     match payload {
         ClientPayload::UnlockVault { password } => {
             let mut v = vault.lock().await;
             v.set_password(password);
-            match v.get_secret("__vault_check__", true) {
+            let ctx = AccessContext::approved("vault unlock");
+            match v.get_secret_with("__vault_check__", &ctx) {
                 Ok(_) => {
                     send_vault_unlocked(writer, true, None).await?;
+                    status_broadcast::publish(
+                        Some(connection_id),
+                        StatusType::VaultUnlocked,
+                        "Secrets vault unlocked",
+                    );
                 }
                 Err(e) => {
                     v.clear_password();
@@ -386,7 +515,8 @@ pub(crate) async fn handle_secrets_frame(
         }
         ClientPayload::SecretsGet { key } => {
             let mut v = vault.lock().await;
-            let result = v.get_secret(&key, true);
+            let ctx = AccessContext::approved("client:secrets_get");
+            let result = v.get_secret_with(&key, &ctx);
             match result {
                 Ok(Some(value)) => {
                     send_secrets_get_result(writer, true, &key, Some(&value), None).await?
@@ -500,7 +630,8 @@ pub(crate) async fn handle_secrets_frame(
         ClientPayload::SecretsDeleteCredential { name } => {
             let mut v = vault.lock().await;
             let meta_key = format!("cred:{}", name);
-            let is_legacy = v.get_secret(&meta_key, true).ok().flatten().is_none();
+            let ctx = AccessContext::approved("client:delete_credential");
+            let is_legacy = v.get_secret_with(&meta_key, &ctx).ok().flatten().is_none();
             if is_legacy {
                 let _ = v.delete_secret(&name);
             }
@@ -564,3 +695,78 @@ pub(crate) async fn handle_secrets_frame(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::secrets::SecretsManager;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    fn vault(dir: &std::path::Path) -> SharedVault {
+        Arc::new(Mutex::new(SecretsManager::new(dir.join("credentials"))))
+    }
+
+    #[tokio::test]
+    async fn test_generate_returns_value_when_not_storing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = vault(tmp.path());
+        let args = json!({ "length": 32, "charset": "lower,digits" });
+        let out = exec_secrets_generate(&args, &vault).await.unwrap();
+
+        let value = out.lines().last().unwrap();
+        assert!(out.starts_with("Generated a 32-character password"));
+        assert_eq!(value.len(), 32);
+        assert!(
+            value
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        );
+        assert!(vault.lock().await.list_all_entries().is_empty());
+
+        let bad = json!({ "charset": "lower,emoji" });
+        let err = exec_secrets_generate(&bad, &vault).await.unwrap_err();
+        assert!(err.contains("emoji"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_stores_without_returning_value() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = vault(tmp.path());
+        let args = json!({
+            "style": "passphrase",
+            "words": 5,
+            "separator": " ",
+            "name": "backup_disk",
+            "policy": "auth",
+        });
+        let out = exec_secrets_generate(&args, &vault).await.unwrap();
+        assert!(out.contains("5-word passphrase"));
+        assert!(out.contains("'backup_disk'"));
+        assert!(out.contains("policy: auth"));
+
+        let (entry, value) = vault
+            .lock()
+            .await
+            .get_credential("backup_disk", &AccessContext::approved("test"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.kind, SecretKind::Token);
+        assert_eq!(entry.policy, AccessPolicy::WithAuth);
+        let CredentialValue::Single(phrase) = value else {
+            panic!("expected a single value");
+        };
+        assert_eq!(phrase.split(' ').count(), 5);
+        assert!(!out.contains(&*phrase));
+
+        // The name is taken now; replacing it has to be asked for.
+        let err = exec_secrets_generate(&args, &vault).await.unwrap_err();
+        assert!(err.contains("already exists"));
+        let again = json!({ "name": "backup_disk", "overwrite": true, "reveal": true });
+        let out = exec_secrets_generate(&again, &vault).await.unwrap();
+        let revealed = out.lines().last().unwrap().strip_prefix("Value: ").unwrap();
+        assert_eq!(revealed.chars().count(), 24);
+        assert_ne!(revealed, phrase);
+    }
+}
@@ -9,27 +9,44 @@
 use anyhow::{Context, Result};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
-use tracing::{trace, warn};
+use tracing::{Instrument, Span, debug, info, info_span, instrument, trace, warn};
 
 use rustyclaw_core::gateway::{
-    ClientFrame, ClientFrameType, ClientPayload, ProbeResult, ServerFrame, ServerFrameType,
-    ServerPayload, StatusType, WireFrame, deserialize_frame, protocol, transport,
+    Capabilities, ChatMessage, ClientFrame, ClientFrameType, ClientPayload, ErrorCode, ProbeResult,
+    SamplingParams, ServerFrame, ServerFrameType, ServerPayload, StatusType, WireFrame,
+    deserialize_frame, protocol, transport,
 };
+use rustyclaw_core::health::ProbeTrigger;
+use rustyclaw_core::observability::{ConnectionIds, request_ids};
 use rustyclaw_core::providers as crate_providers;
+use rustyclaw_core::secrets::GatewayRole;
+use rustyclaw_core::threads::{NullHistoryStore, ThreadManager, open_history_store};
+use rustyclaw_core::tools;
 
 use protocol::server::send_frame;
 
 use crate::thread_updates::{
     send_projects_update, send_thread_messages_update, send_threads_update,
 };
+use crate::tool_approval::{self, ApprovalReply};
 use crate::{
     SharedConfig, SharedCopilotSession, SharedModelCtx, SharedModelRegistry, SharedObserver,
     SharedSkillManager, SharedTaskManager, SharedVault, TOTP_LOCKOUT_SECS, ToolCancelFlag, admin,
-    auth, concurrent, project_handler, providers, thread_handler,
+    auth, concurrent, health_handler, project_handler, providers, session_mirror, status_broadcast,
+    task_registry, thread_handler,
 };
 
+/// How often a connection checks for due reminders.
+const REMINDER_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Serve one client connection until it closes or `cancel` fires.
+///
+/// Runs in a `connection` span carrying the connection ID; each client frame
+/// is handled in a `frame` span under its own request ID.
+#[instrument(name = "connection", skip_all, fields(conn))]
 pub(crate) async fn handle_connection(
     conn: Box<dyn transport::Transport>,
     shared_config: SharedConfig,
@@ -46,6 +63,9 @@ pub(crate) async fn handle_connection(
     let peer_info = conn.peer_info().clone();
     let (mut reader, mut writer) = conn.into_split();
     let peer_ip = peer_info.addr.map(|a| a.ip());
+    let mut conn_ids = ConnectionIds::new();
+    Span::current().record("conn", conn_ids.connection());
+    debug!(peer = ?peer_info.addr, transport = %peer_info.transport_type, "Connection opened");
 
     // Snapshot config and model context for this connection.
     // Reload updates the shared state; new connections pick up changes.
@@ -53,9 +73,17 @@ pub(crate) async fn handle_connection(
     let model_ctx = shared_model_ctx.read().await.clone();
 
     // Thread manager for multi-task conversations.
-    // Load from persistent storage or create new with default "Main" thread.
-    let threads_path = config.sessions_dir().join("threads.json");
-    let mut thread_mgr = rustyclaw_core::threads::ThreadManager::load_or_default(&threads_path);
+    // Load from the history store or create new with default "Main" thread.
+    let history = open_history_store(config.history_backend, &config.sessions_dir())
+        .unwrap_or_else(|e| {
+            // Keep the session usable; its history just won't outlive it.
+            warn!(error = %e, backend = %config.history_backend, "Failed to open history store");
+            Arc::new(NullHistoryStore)
+        });
+    let mut thread_mgr = ThreadManager::load_or_default_from(history.as_ref());
+
+    // Responses kept for exact-repeat requests (`[prompt_cache] responses`).
+    let mut response_cache = rustyclaw_core::providers::ResponseCache::new();
 
     // Project registry (each project is a working directory grouping threads).
     // Migration: ensure a "Default" project exists pointing at the current
@@ -76,10 +104,18 @@ pub(crate) async fn handle_connection(
     // Subscribe to thread events for push-based sidebar updates
     let mut thread_events_rx = thread_mgr.subscribe();
 
+    // Status changes made through other connections (reload, model switch,
+    // vault unlock, skill install).
+    let mut status_broadcasts = status_broadcast::subscribe();
+    let connection_id = status_broadcasts.id;
+
     // ── TOTP authentication challenge ───────────────────────────────
     //
     // If TOTP 2FA is enabled, require it for every transport.
-    // SSH public-key auth is necessary but not sufficient.
+    // SSH public-key auth is necessary but not sufficient.  Without it,
+    // every connection is an operator.
+    let mut role = GatewayRole::Operator;
+    let mut token_grant = None;
     if config.totp_enabled {
         // Rate limiting requires a peer IP.
         let rate_ip = match peer_ip {
@@ -127,17 +163,32 @@ pub(crate) async fn handle_connection(
 
             match auth_result {
                 Ok(Ok(code)) => {
-                    let valid = {
+                    // Headless clients may answer the challenge with a
+                    // bearer token instead of a TOTP code, and a user who
+                    // lost their authenticator with a one-time backup code;
+                    // failures count against the same per-IP rate limit.
+                    // The token also decides whether the connection may
+                    // drive the agent, and which tools it may use.
+                    let granted = {
                         let mut v = vault.lock().await;
-                        match v.verify_totp(code.trim()) {
+                        let result = if rustyclaw_core::secrets::is_gateway_token(&code) {
+                            v.gateway_token_grant(code.trim())
+                                .map(|grant| grant.map(|t| (t.role, Some(t))))
+                        } else {
+                            v.verify_totp_or_backup_code(code.trim())
+                                .map(|ok| ok.then_some((GatewayRole::Operator, None)))
+                        };
+                        match result {
                             Ok(result) => result,
                             Err(e) => {
-                                warn!(error = %e, "TOTP verification error (vault issue?)");
-                                false
+                                warn!(error = %e, "Auth verification error (vault issue?)");
+                                None
                             }
                         }
                     };
-                    if valid {
+                    if let Some((granted, token)) = granted {
+                        role = granted;
+                        token_grant = token;
                         auth::clear_rate_limit(&rate_limiter, rate_ip).await;
                         protocol::server::send_auth_result(&mut *writer, true, None, None).await?;
                         break; // Authentication successful, continue to main loop
@@ -214,6 +265,12 @@ pub(crate) async fn handle_connection(
     };
 
     // ── Send hello ──────────────────────────────────────────────────
+    // Until the client announces what it can render, assume what every
+    // client could before capabilities were negotiated.
+    let gateway_capabilities = Capabilities::gateway();
+    let mut capabilities = gateway_capabilities.negotiate(&Capabilities::legacy());
+    // A model this connection asked for instead of the configured one.
+    let mut session_model: Option<admin::SessionModel> = None;
     protocol::server::send_hello(
         &mut *writer,
         &config.agent_name,
@@ -221,10 +278,30 @@ pub(crate) async fn handle_connection(
         vault_is_locked,
         model_ctx.as_ref().map(|c| c.provider.as_str()),
         model_ctx.as_ref().map(|c| c.model.as_str()),
+        gateway_capabilities.clone(),
     )
     .await
     .context("Failed to send hello message")?;
 
+    // Observers only watch other connections' sessions; they never get a
+    // session, tool loop or vault access of their own.
+    if role.is_read_only() {
+        info!(peer = ?peer_info.addr, "Observer connected");
+        drop(status_broadcasts);
+        return session_mirror::run_observer(reader, writer, history.as_ref(), cancel).await;
+    }
+
+    // A scoped token or a `client_tool_allowlists` entry limits the tools
+    // this connection's agent may call.
+    let tool_allowlist = auth::client_tool_allowlist(&config, &peer_info, token_grant.as_ref());
+    if let Some(tools) = &tool_allowlist {
+        info!(peer = ?peer_info.addr, ?tools, "Connection restricted to a tool allowlist");
+    }
+
+    // Copy this session's activity to any connected observers.
+    let mut writer: Box<dyn transport::TransportWriter> =
+        Box::new(session_mirror::MirrorWriter::new(writer, connection_id));
+
     if vault_is_locked {
         protocol::server::send_status(
             &mut *writer,
@@ -280,22 +357,7 @@ pub(crate) async fn handle_connection(
             //
             // If the cached model context has no API key, try fetching it
             // from the vault (it may have been stored since startup).
-            let probe_ctx = if ctx.api_key.is_none() {
-                if let Some(key_name) = crate_providers::secret_key_for_provider(&ctx.provider) {
-                    let mut v = vault.lock().await;
-                    if let Ok(Some(key)) = v.get_secret(key_name, true) {
-                        let mut updated = (**ctx).clone();
-                        updated.api_key = Some(key);
-                        std::sync::Arc::new(updated)
-                    } else {
-                        ctx.clone()
-                    }
-                } else {
-                    ctx.clone()
-                }
-            } else {
-                ctx.clone()
-            };
+            let probe_ctx = health_handler::with_vault_key(ctx, &vault).await;
 
             protocol::server::send_status(
                 &mut *writer,
@@ -308,13 +370,11 @@ pub(crate) async fn handle_connection(
             // Read current copilot session from shared state
             let copilot_session = shared_copilot_session.read().await.clone();
 
-            match providers::validate_model_connection(
-                &http,
-                &probe_ctx,
-                copilot_session.as_deref(),
-            )
-            .await
-            {
+            let result =
+                providers::validate_model_connection(&http, &probe_ctx, copilot_session.as_deref())
+                    .await;
+            health_handler::record(ProbeTrigger::Connect, ctx, &result);
+            match result {
                 ProbeResult::Ready => {
                     protocol::server::send_status(
                         &mut *writer,
@@ -355,6 +415,17 @@ pub(crate) async fn handle_connection(
                     .context("Failed to send model_error status")?;
                 }
             }
+
+            // The startup warm-up's result arrives as a status broadcast.
+            if crate::warmup::in_progress() {
+                protocol::server::send_status(
+                    &mut *writer,
+                    StatusType::ModelConnecting,
+                    &format!("Warming up {} / {} …", display, ctx.model),
+                )
+                .await
+                .context("Failed to send model_connecting status")?;
+            }
         }
         None => {
             protocol::server::send_status(
@@ -367,6 +438,13 @@ pub(crate) async fn handle_connection(
         }
     }
 
+    // A client joining a paused agent sees why nothing is moving.
+    if let Some(pause) = rustyclaw_core::agent_pause::global().state() {
+        protocol::server::send_status(&mut *writer, StatusType::AgentPaused, &pause.describe())
+            .await
+            .context("Failed to send agent_paused status")?;
+    }
+
     // ── Spawn reader task with cancel flag ─────────────────────────
     //
     // The reader runs in a separate task so it can receive cancel messages
@@ -376,8 +454,7 @@ pub(crate) async fn handle_connection(
     let (frame_tx, mut frame_rx) = tokio::sync::mpsc::channel::<WireFrame<ClientFrame>>(32);
 
     // Channel for tool-approval responses (used by the Ask permission flow).
-    let (approval_tx, approval_rx) = tokio::sync::mpsc::channel::<(String, bool)>(4);
-    let approval_rx = Arc::new(Mutex::new(approval_rx));
+    let (approval_tx, approval_rx) = tool_approval::channel();
 
     // Channel for user-prompt responses (used by the ask_user tool).
     let (user_prompt_tx, user_prompt_rx) = tokio::sync::mpsc::channel::<(
@@ -396,6 +473,9 @@ pub(crate) async fn handle_connection(
     let (dom_query_tx, dom_query_rx) = tokio::sync::mpsc::channel::<(String, String, bool)>(4);
     let dom_query_rx = Arc::new(Mutex::new(dom_query_rx));
 
+    // Unlock passwords for a turn waiting on a relocked vault.
+    let unlock_relay = Arc::new(crate::vault_relock::UnlockRelay::new(connection_id));
+
     // Channel for model task responses (concurrent execution).
     let (_model_task_tx, mut model_task_rx) = concurrent::channel();
 
@@ -411,15 +491,43 @@ pub(crate) async fn handle_connection(
         warn!(error = %e, "Failed to send initial project list");
     }
 
+    // ── Idle timeout ───────────────────────────────────────────────
+    //
+    // Every inbound frame, and the end of every request, counts as
+    // activity.  A connection with nothing of either for the configured
+    // period is told why and closed.
+    let idle_timeout = match config.gateway_idle_timeout_secs {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    };
+    let last_activity = Arc::new(std::sync::Mutex::new(Instant::now()));
+
+    // Config-mutating frames must arrive in increasing sequence order.
+    let mut replay_guard = crate::replay_guard::ReplayGuard::default();
+
+    // ── Reminders ──────────────────────────────────────────────────
+    //
+    // Reminders set with the `reminder` tool are run here as user turns
+    // in the thread that set them, between requests.
+    let reminders_dir = config.reminders_dir();
+    let mut reminder_tick = tokio::time::interval(REMINDER_POLL_INTERVAL);
+    reminder_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
     let reader_cancel = cancel.clone();
     let reader_tool_cancel = tool_cancel.clone();
-    let reader_handle = tokio::spawn(async move {
+    let reader_activity = last_activity.clone();
+    let reader_unlock = unlock_relay.clone();
+    // Scoped clients may not pause other sessions or unlock the vault; the
+    // main loop refuses those frames.
+    let reader_scoped = tool_allowlist.is_some();
+    let reader_task = async move {
         loop {
             tokio::select! {
                 _ = reader_cancel.cancelled() => break,
                 result = reader.recv() => {
                     match result {
                         Ok(Some(envelope)) => {
+                            touch(&reader_activity);
                             let stream_id = envelope.stream_id;
                             let frame = envelope.frame.clone();
                             trace!(stream_id, frame_type = ?frame.frame_type, "Received client frame");
@@ -428,9 +536,25 @@ pub(crate) async fn handle_connection(
                                 reader_tool_cancel.store(true, Ordering::Relaxed);
                                 continue;
                             }
+                            // Pause and resume act on every session, including
+                            // one holding this connection's main loop.
+                            if frame.frame_type == ClientFrameType::PauseAgent && !reader_scoped {
+                                if let ClientPayload::PauseAgent { reason } = frame.payload {
+                                    if rustyclaw_core::agent_pause::global().pause(reason) {
+                                        info!(connection_id, "Agent paused");
+                                    }
+                                    continue;
+                                }
+                            }
+                            if frame.frame_type == ClientFrameType::ResumeAgent && !reader_scoped {
+                                if rustyclaw_core::agent_pause::global().resume() {
+                                    info!(connection_id, "Agent resumed");
+                                }
+                                continue;
+                            }
                             if frame.frame_type == ClientFrameType::ToolApprovalResponse {
-                                if let ClientPayload::ToolApprovalResponse { id, approved } = frame.payload {
-                                    let _ = approval_tx.send((id, approved)).await;
+                                if let ClientPayload::ToolApprovalResponse { id, approved, remember } = frame.payload {
+                                    let _ = approval_tx.send(ApprovalReply { id, approved, remember }).await;
                                     continue;
                                 }
                             }
@@ -446,6 +570,12 @@ pub(crate) async fn handle_connection(
                                     continue;
                                 }
                             }
+                            if frame.frame_type == ClientFrameType::UnlockVault && !reader_scoped && reader_unlock.is_waiting() {
+                                if let ClientPayload::UnlockVault { password } = frame.payload {
+                                    reader_unlock.deliver(password).await;
+                                    continue;
+                                }
+                            }
                             if frame.frame_type == ClientFrameType::DomQueryResponse {
                                 if let ClientPayload::DomQueryResponse { id, result, is_error } = frame.payload {
                                     let _ = dom_query_tx.send((id, result, is_error)).await;
@@ -466,7 +596,9 @@ pub(crate) async fn handle_connection(
                 }
             }
         }
-    });
+    };
+    // The reader logs under this connection too.
+    let reader_handle = tokio::spawn(reader_task.in_current_span());
 
     // Main message handling loop — receives from channel
     loop {
@@ -475,18 +607,77 @@ pub(crate) async fn handle_connection(
                 let _ = writer.close().await;
                 break;
             }
+            _ = idle_expired(&last_activity, idle_timeout) => {
+                // Background model tasks still stream to this client.
+                if !active_tasks.running_threads().is_empty() {
+                    touch(&last_activity);
+                    continue;
+                }
+                let idle = elapsed(&last_activity);
+                info!(
+                    peer = ?peer_ip,
+                    idle_secs = idle.as_secs(),
+                    "Closing idle gateway connection"
+                );
+                let _ = protocol::server::send_status(
+                    &mut *writer,
+                    StatusType::IdleTimeout,
+                    &format!(
+                        "Closing connection after {} minutes without activity",
+                        idle.as_secs() / 60
+                    ),
+                )
+                .await;
+                let _ = writer.close().await;
+                break;
+            }
             msg = frame_rx.recv() => {
                 let envelope = match msg {
                     Some(f) => f,
                     None => break, // Channel closed (reader exited)
                 };
                 let stream_id = envelope.stream_id;
+                let sequence = envelope.sequence;
                 let frame = envelope.frame;
 
                 trace!(stream_id, frame_type = ?frame.frame_type, "Handling client frame");
+                if tool_allowlist.is_some()
+                    && let Some(message) = auth::scoped_client_rejection(frame.frame_type)
+                {
+                    warn!(
+                        peer = ?peer_ip,
+                        connection_id,
+                        frame_type = ?frame.frame_type,
+                        "Refused frame from a tool-scoped client"
+                    );
+                    protocol::server::send_error(&mut *writer, ErrorCode::ReadOnly, &message)
+                        .await?;
+                    continue;
+                }
+                if let Err(reason) = replay_guard.check(frame.frame_type, sequence) {
+                    warn!(
+                        peer = ?peer_ip,
+                        connection_id,
+                        frame_type = ?frame.frame_type,
+                        sequence,
+                        %reason,
+                        "Rejected config-mutating frame"
+                    );
+                    protocol::server::send_error(&mut *writer, ErrorCode::InvalidFrame, &reason)
+                        .await?;
+                    continue;
+                }
                 // Reset cancel flag for new request
                 tool_cancel.store(false, Ordering::Relaxed);
 
+                let ids = conn_ids.next_request();
+                let frame_span = info_span!(
+                    "frame",
+                    request_id = %ids.request,
+                    stream_id,
+                    frame_type = ?frame.frame_type,
+                );
+                request_ids::scope(ids, async {
                         // Handle the frame based on type
                         match frame.payload {
                             payload @ (ClientPayload::UnlockVault { .. }
@@ -506,6 +697,7 @@ pub(crate) async fn handle_connection(
                                     &mut *writer,
                                     &vault,
                                     payload,
+                                    connection_id,
                                 )
                                 .await?;
                             }
@@ -518,13 +710,20 @@ pub(crate) async fn handle_connection(
                                     &shared_model_ctx,
                                     &shared_copilot_session,
                                     &model_registry,
+                                    connection_id,
                                 )
                                 .await?;
                             }
-                            ClientPayload::Chat { messages } => {
+                            ClientPayload::Chat {
+                                messages,
+                                sampling,
+                                system_prefix,
+                            } => {
                                 crate::chat::handle_chat_frame(
                                     &http,
                                     messages,
+                                    sampling,
+                                    system_prefix,
                                     stream_id,
                                     &mut *writer,
                                     &config,
@@ -536,18 +735,36 @@ pub(crate) async fn handle_connection(
                                     &shared_config,
                                     &shared_model_ctx,
                                     &shared_copilot_session,
+                                    session_model.as_ref(),
                                     &approval_rx,
                                     &user_prompt_rx,
                                     &credential_rx,
                                     &dom_query_rx,
+                                    &unlock_relay,
                                     &mut thread_mgr,
-                                    &threads_path,
+                                    history.as_ref(),
+                                    &mut response_cache,
+                                    &capabilities,
+                                    tool_allowlist.as_ref(),
                                 )
                                 .await?;
                             }
                             ClientPayload::TasksRequest { session } => {
                                 thread_handler::handle_tasks_request(&mut *writer, &task_mgr, session).await?;
                             }
+                            ClientPayload::GlobalTasksRequest { kind, status, include_finished } => {
+                                let filter = rustyclaw_core::tasks::TaskFilter {
+                                    kind,
+                                    status,
+                                    include_finished,
+                                };
+                                task_registry::handle_global_tasks_request(
+                                    &mut *writer,
+                                    &task_mgr,
+                                    filter,
+                                )
+                                .await?;
+                            }
                             ClientPayload::ThreadCreate { label, project_id } => {
                                 // 0 means "the active project".
                                 let pid = if project_id == 0 {
@@ -571,7 +788,7 @@ pub(crate) async fn handle_connection(
                                     &mut *writer,
                                     &mut thread_mgr,
                                     &task_mgr,
-                                    &threads_path,
+                                    history.as_ref(),
                                     pid,
                                     label,
                                 )
@@ -582,7 +799,7 @@ pub(crate) async fn handle_connection(
                                     &mut *writer,
                                     &mut thread_mgr,
                                     &task_mgr,
-                                    &threads_path,
+                                    history.as_ref(),
                                     &shared_model_ctx,
                                     &http,
                                     thread_id,
@@ -611,12 +828,16 @@ pub(crate) async fn handle_connection(
                             ClientPayload::ThreadHistoryRequest { thread_id } => {
                                 thread_handler::handle_thread_history(&mut *writer, &thread_mgr, thread_id).await?;
                             }
+                            ClientPayload::ExportSession { session_id, format } => {
+                                thread_handler::handle_export_session(&mut *writer, &thread_mgr, session_id, format).await?;
+                            }
                             ClientPayload::ThreadClose { thread_id } => {
                                 thread_handler::handle_thread_close(
                                     &mut *writer,
+                                    &config,
                                     &mut thread_mgr,
                                     &task_mgr,
-                                    &threads_path,
+                                    history.as_ref(),
                                     thread_id,
                                 )
                                 .await?;
@@ -626,7 +847,7 @@ pub(crate) async fn handle_connection(
                                     &mut *writer,
                                     &mut thread_mgr,
                                     &task_mgr,
-                                    &threads_path,
+                                    history.as_ref(),
                                     thread_id,
                                     new_label,
                                 )
@@ -641,9 +862,23 @@ pub(crate) async fn handle_connection(
                                     &shared_copilot_session,
                                     provider,
                                     model,
+                                    connection_id,
                                 )
                                 .await?;
                             }
+                            ClientPayload::SessionModel { provider, model } => {
+                                if let Some(resolved) = admin::handle_session_model(
+                                    &mut *writer,
+                                    &vault,
+                                    &config,
+                                    provider,
+                                    model,
+                                )
+                                .await?
+                                {
+                                    session_model = Some(resolved);
+                                }
+                            }
                             ClientPayload::SetAgentName { name } => {
                                 admin::handle_set_agent_name(&mut config, &shared_config, name).await;
                             }
@@ -694,7 +929,7 @@ pub(crate) async fn handle_connection(
                                     project_id,
                                 )
                                 .await?;
-                                let _ = thread_mgr.save_to_file(&threads_path);
+                                let _ = history.save(&thread_mgr);
                                 send_threads_update(&mut *writer, &thread_mgr, &task_mgr, None).await?;
                             }
                             ClientPayload::ProjectSwitch { project_id } => {
@@ -777,14 +1012,58 @@ pub(crate) async fn handle_connection(
                                     &config.engines,
                                 ).await?;
                             }
-                            ClientPayload::Empty | ClientPayload::AuthChallenge { .. } | ClientPayload::AuthResponse { .. } | ClientPayload::ToolApprovalResponse { .. } | ClientPayload::UserPromptResponse { .. } | ClientPayload::CredentialResponse { .. } | ClientPayload::DomQueryResponse { .. } => {
+                            ClientPayload::SandboxPolicyRequest => {
+                                admin::handle_sandbox_policy_request(&mut *writer, &shared_config)
+                                    .await?;
+                            }
+                            ClientPayload::ToolPermissionsRequest => {
+                                admin::handle_tool_permissions_request(&mut *writer, &shared_config)
+                                    .await?;
+                            }
+                            ClientPayload::ToolPermissionSet { tool, permission, skills } => {
+                                admin::handle_tool_permission_set(
+                                    &mut *writer,
+                                    &mut config,
+                                    &shared_config,
+                                    tool,
+                                    permission,
+                                    skills,
+                                )
+                                .await?;
+                            }
+                            ClientPayload::ResumeTurn { session_id, turn_id } => {
+                                crate::turn_resume::handle_resume_turn(
+                                    &mut *writer,
+                                    &capabilities,
+                                    stream_id,
+                                    session_id,
+                                    turn_id,
+                                )
+                                .await?;
+                            }
+                            ClientPayload::Capabilities { capabilities: announced } => {
+                                capabilities = gateway_capabilities.negotiate(&announced);
+                                debug!(
+                                    version = capabilities.version,
+                                    features = ?capabilities.features,
+                                    "Negotiated client capabilities"
+                                );
+                            }
+                            ClientPayload::Empty | ClientPayload::AuthChallenge { .. } | ClientPayload::AuthResponse { .. } | ClientPayload::ToolApprovalResponse { .. } | ClientPayload::UserPromptResponse { .. } | ClientPayload::CredentialResponse { .. } | ClientPayload::DomQueryResponse { .. } | ClientPayload::PauseAgent { .. } | ClientPayload::ResumeAgent => {
                                 // AuthChallenge/AuthResponse handled in auth phase.
                                 // ToolApprovalResponse handled by the reader task.
                                 // UserPromptResponse handled by the reader task.
                                 // CredentialResponse handled by the reader task.
                                 // DomQueryResponse handled by the reader task.
+                                // PauseAgent/ResumeAgent handled by the reader task.
                             }
                         }
+                        anyhow::Ok(())
+                }
+                .instrument(frame_span))
+                .await?;
+                // A long request is not idleness; start counting now.
+                touch(&last_activity);
             }
             // Handle messages from spawned model tasks
             model_msg = model_task_rx.recv() => {
@@ -812,7 +1091,7 @@ pub(crate) async fn handle_connection(
                             send_threads_update(&mut *writer, &thread_mgr, &task_mgr, None).await?;
 
                             // Persist thread state
-                            let _ = thread_mgr.save_to_file(&threads_path);
+                            let _ = history.save(&thread_mgr);
                         }
                         concurrent::ModelTaskMessage::Error { thread_id, message } => {
                             // Task failed - remove from active tasks
@@ -824,6 +1103,7 @@ pub(crate) async fn handle_connection(
                                 payload: ServerPayload::Error {
                                     ok: false,
                                     message,
+                                    code: ErrorCode::ModelError,
                                 },
                             };
                             send_frame(&mut *writer, &error_frame).await?;
@@ -834,6 +1114,22 @@ pub(crate) async fn handle_connection(
                     }
                 }
             }
+            // Forward status changes made by other connections.  Lagging
+            // only loses old statuses; later ones still arrive.
+            event = status_broadcasts.rx.recv() => {
+                match event {
+                    Ok(event) if event.origin != Some(connection_id) => {
+                        protocol::server::send_status(&mut *writer, event.status, &event.detail)
+                            .await?;
+                    }
+                    Ok(_) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!(skipped, "Connection fell behind on status broadcasts");
+                    }
+                    // The sender is never dropped.
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {}
+                }
+            }
             // Handle thread events for push-based sidebar updates
             thread_event = thread_events_rx.recv() => {
                 if let Ok(event) = thread_event {
@@ -843,18 +1139,125 @@ pub(crate) async fn handle_connection(
                     }
                 }
             }
+            _ = reminder_tick.tick() => {
+                let thread_ids: Vec<u64> = thread_mgr.list().iter().map(|t| t.id.0).collect();
+                let due = match tools::take_due_reminders(&reminders_dir, &thread_ids) {
+                    Ok(due) => due,
+                    Err(e) => {
+                        warn!(error = %e, "Reminder poll failed");
+                        continue;
+                    }
+                };
+                for reminder in due {
+                    debug!(
+                        job_id = %reminder.job_id,
+                        thread_id = reminder.thread_id,
+                        "Running reminder"
+                    );
+                    if thread_mgr.foreground_id().map(|id| id.0) != Some(reminder.thread_id) {
+                        thread_handler::handle_thread_switch(
+                            &mut *writer,
+                            &mut thread_mgr,
+                            &task_mgr,
+                            history.as_ref(),
+                            &shared_model_ctx,
+                            &http,
+                            reminder.thread_id,
+                        )
+                        .await?;
+                        if let Some(pid) = thread_mgr.foreground().map(|t| t.project_id)
+                            && pid != project_mgr.active_id()
+                        {
+                            project_handler::activate_project(
+                                &mut *writer,
+                                &mut config,
+                                &mut project_mgr,
+                                &projects_path,
+                                pid,
+                            )
+                            .await?;
+                        }
+                    }
+                    tool_cancel.store(false, Ordering::Relaxed);
+                    let ids = conn_ids.next_request();
+                    let reminder_span = info_span!(
+                        "reminder",
+                        request_id = %ids.request,
+                        job_id = %reminder.job_id,
+                    );
+                    let turn = crate::chat::handle_chat_frame(
+                        &http,
+                        vec![ChatMessage::text("user", &reminder.prompt())],
+                        SamplingParams::default(),
+                        None,
+                        protocol::CONTROL_STREAM_ID,
+                        &mut *writer,
+                        &config,
+                        &vault,
+                        &skill_mgr,
+                        &task_mgr,
+                        observer.as_ref(),
+                        &tool_cancel,
+                        &shared_config,
+                        &shared_model_ctx,
+                        &shared_copilot_session,
+                        session_model.as_ref(),
+                        &approval_rx,
+                        &user_prompt_rx,
+                        &credential_rx,
+                        &dom_query_rx,
+                        &unlock_relay,
+                        &mut thread_mgr,
+                        history.as_ref(),
+                        &mut response_cache,
+                        &capabilities,
+                        tool_allowlist.as_ref(),
+                    );
+                    request_ids::scope(ids, turn.instrument(reminder_span)).await?;
+                    touch(&last_activity);
+                }
+            }
         }
     }
 
     // Clean up reader task
     reader_handle.abort();
 
+    // File watches registered with watch_file last for the session.
+    rustyclaw_core::tools::clear_watches();
+
     // Persist thread state on disconnect
-    let _ = thread_mgr.save_to_file(&threads_path);
+    let _ = history.save(&thread_mgr);
 
     Ok(())
 }
 
+fn touch(last_activity: &std::sync::Mutex<Instant>) {
+    *last_activity.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+}
+
+fn elapsed(last_activity: &std::sync::Mutex<Instant>) -> Duration {
+    last_activity
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .elapsed()
+}
+
+/// Resolve once `timeout` has passed without activity; never resolves when
+/// the timeout is disabled.
+async fn idle_expired(last_activity: &std::sync::Mutex<Instant>, timeout: Option<Duration>) {
+    let Some(timeout) = timeout else {
+        return std::future::pending().await;
+    };
+    loop {
+        let idle = elapsed(last_activity);
+        if idle >= timeout {
+            return;
+        }
+        tokio::time::sleep(timeout - idle).await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -872,13 +1275,28 @@ mod tests {
 
     struct MockTransport {
         peer: PeerInfo,
-        incoming: Arc<Mutex<VecDeque<Option<ClientFrame>>>>,
+        incoming: Arc<Mutex<VecDeque<Option<WireFrame<ClientFrame>>>>>,
         outgoing: Arc<Mutex<Vec<ServerFrame>>>,
+        /// Block instead of disconnecting once `incoming` is drained.
+        hang_when_empty: bool,
     }
 
     struct MockReader {
         peer: PeerInfo,
-        incoming: Arc<Mutex<VecDeque<Option<ClientFrame>>>>,
+        incoming: Arc<Mutex<VecDeque<Option<WireFrame<ClientFrame>>>>>,
+        hang_when_empty: bool,
+    }
+
+    async fn next_mock_frame(
+        incoming: &Mutex<VecDeque<Option<WireFrame<ClientFrame>>>>,
+        hang_when_empty: bool,
+    ) -> Result<Option<WireFrame<ClientFrame>>> {
+        let next = incoming.lock().await.pop_front();
+        match next {
+            Some(frame) => Ok(frame),
+            None if hang_when_empty => std::future::pending().await,
+            None => Ok(None),
+        }
     }
 
     struct MockWriter {
@@ -889,6 +1307,17 @@ mod tests {
         fn with_frames(
             peer: PeerInfo,
             frames: Vec<Option<ClientFrame>>,
+        ) -> (Self, Arc<Mutex<Vec<ServerFrame>>>) {
+            let frames = frames
+                .into_iter()
+                .map(|frame| frame.map(WireFrame::control))
+                .collect();
+            Self::with_wire_frames(peer, frames)
+        }
+
+        fn with_wire_frames(
+            peer: PeerInfo,
+            frames: Vec<Option<WireFrame<ClientFrame>>>,
         ) -> (Self, Arc<Mutex<Vec<ServerFrame>>>) {
             let outgoing = Arc::new(Mutex::new(Vec::new()));
             (
@@ -896,6 +1325,7 @@ mod tests {
                     peer,
                     incoming: Arc::new(Mutex::new(VecDeque::from(frames))),
                     outgoing: outgoing.clone(),
+                    hang_when_empty: false,
                 },
                 outgoing,
             )
@@ -909,13 +1339,7 @@ mod tests {
         }
 
         async fn recv(&mut self) -> Result<Option<WireFrame<ClientFrame>>> {
-            Ok(self
-                .incoming
-                .lock()
-                .await
-                .pop_front()
-                .unwrap_or(None)
-                .map(WireFrame::control))
+            next_mock_frame(&self.incoming, self.hang_when_empty).await
         }
 
         async fn send_on_stream(&mut self, _stream_id: u64, frame: &ServerFrame) -> Result<()> {
@@ -932,6 +1356,7 @@ mod tests {
                 Box::new(MockReader {
                     peer: self.peer.clone(),
                     incoming: self.incoming.clone(),
+                    hang_when_empty: self.hang_when_empty,
                 }),
                 Box::new(MockWriter {
                     outgoing: self.outgoing.clone(),
@@ -943,13 +1368,7 @@ mod tests {
     #[async_trait]
     impl TransportReader for MockReader {
         async fn recv(&mut self) -> Result<Option<WireFrame<ClientFrame>>> {
-            Ok(self
-                .incoming
-                .lock()
-                .await
-                .pop_front()
-                .unwrap_or(None)
-                .map(WireFrame::control))
+            next_mock_frame(&self.incoming, self.hang_when_empty).await
         }
 
         fn peer_info(&self) -> &PeerInfo {
@@ -1041,6 +1460,8 @@ mod tests {
             frame_type: ClientFrameType::Chat,
             payload: ClientPayload::Chat {
                 messages: vec![ChatMessage::text("user", "Hello?")],
+                sampling: Default::default(),
+                system_prefix: None,
             },
         };
 
@@ -1090,4 +1511,381 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn replayed_config_frame_is_rejected() -> Result<()> {
+        let (_tmp, mut cfg) = test_config_with_temp_state()?;
+        cfg.totp_enabled = false;
+
+        let rename = |name: &str, sequence: u64| {
+            let mut wire = WireFrame::control(ClientFrame {
+                frame_type: ClientFrameType::SetAgentName,
+                payload: ClientPayload::SetAgentName { name: name.into() },
+            });
+            wire.sequence = sequence;
+            Some(wire)
+        };
+        let peer = PeerInfo {
+            addr: Some("127.0.0.1:2222".parse().unwrap()),
+            username: Some("tester".to_string()),
+            key_fingerprint: Some("SHA256:test".to_string()),
+            transport_type: TransportType::Ssh,
+        };
+        // The second frame reuses the first one's sequence number.
+        let (mock_transport, outgoing) = MockTransport::with_wire_frames(
+            peer,
+            vec![rename("Ada", 4), rename("Mallory", 4), None],
+        );
+
+        let shared_config = Arc::new(RwLock::new(cfg.clone()));
+        let vault: SharedVault = Arc::new(Mutex::new(SecretsManager::new(cfg.credentials_dir())));
+        let skill_mgr: SharedSkillManager =
+            Arc::new(Mutex::new(SkillManager::new(cfg.skills_dir())));
+        let task_mgr: SharedTaskManager = Arc::new(rustyclaw_core::tasks::TaskManager::new());
+        let model_registry = rustyclaw_core::models::create_model_registry();
+
+        handle_transport_connection(
+            Box::new(mock_transport),
+            shared_config.clone(),
+            Arc::new(RwLock::new(None)),
+            Arc::new(RwLock::new(None)),
+            vault,
+            skill_mgr,
+            task_mgr,
+            model_registry,
+            None,
+            auth::new_rate_limiter(),
+            CancellationToken::new(),
+        )
+        .await?;
+
+        assert_eq!(shared_config.read().await.agent_name, "Ada");
+        let frames = outgoing.lock().await;
+        let rejections: Vec<_> = frames
+            .iter()
+            .filter_map(|f| match &f.payload {
+                ServerPayload::Error { code, message, .. } => Some((*code, message.clone())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rejections.len(), 1, "{:?}", rejections);
+        assert_eq!(rejections[0].0, ErrorCode::InvalidFrame);
+        assert!(rejections[0].1.contains("replayed"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scoped_client_cannot_send_admin_frames() -> Result<()> {
+        let (_tmp, mut cfg) = test_config_with_temp_state()?;
+        cfg.totp_enabled = false;
+        cfg.client_tool_allowlists.insert(
+            "SHA256:kiosk".into(),
+            vec!["read_file".into(), "web_fetch".into()],
+        );
+
+        let sequenced = |frame_type, payload, sequence| {
+            let mut wire = WireFrame::control(ClientFrame {
+                frame_type,
+                payload,
+            });
+            wire.sequence = sequence;
+            Some(wire)
+        };
+        let frames = vec![
+            sequenced(
+                ClientFrameType::ToolPermissionSet,
+                ClientPayload::ToolPermissionSet {
+                    tool: "execute_command".into(),
+                    permission: "allow".into(),
+                    skills: Vec::new(),
+                },
+                1,
+            ),
+            sequenced(
+                ClientFrameType::SetAgentName,
+                ClientPayload::SetAgentName {
+                    name: "Mallory".into(),
+                },
+                2,
+            ),
+            sequenced(ClientFrameType::SecretsList, ClientPayload::SecretsList, 0),
+            sequenced(ClientFrameType::Reload, ClientPayload::Reload, 3),
+            sequenced(
+                ClientFrameType::PauseAgent,
+                ClientPayload::PauseAgent { reason: None },
+                0,
+            ),
+            // Not on the allowlist, though they change nothing.
+            sequenced(
+                ClientFrameType::ResumeTurn,
+                ClientPayload::ResumeTurn {
+                    session_id: 1,
+                    turn_id: 1,
+                },
+                0,
+            ),
+            sequenced(
+                ClientFrameType::GlobalTasksRequest,
+                ClientPayload::GlobalTasksRequest {
+                    kind: None,
+                    status: None,
+                    include_finished: true,
+                },
+                0,
+            ),
+            sequenced(
+                ClientFrameType::ExportSession,
+                ClientPayload::ExportSession {
+                    session_id: 1,
+                    format: "markdown".into(),
+                },
+                0,
+            ),
+            None,
+        ];
+        let peer = PeerInfo {
+            addr: Some("127.0.0.1:2222".parse().unwrap()),
+            username: Some("kiosk".to_string()),
+            key_fingerprint: Some("SHA256:kiosk".to_string()),
+            transport_type: TransportType::Ssh,
+        };
+        let (mock_transport, outgoing) = MockTransport::with_wire_frames(peer, frames);
+
+        let shared_config = Arc::new(RwLock::new(cfg.clone()));
+        let vault: SharedVault = Arc::new(Mutex::new(SecretsManager::new(cfg.credentials_dir())));
+        let skill_mgr: SharedSkillManager =
+            Arc::new(Mutex::new(SkillManager::new(cfg.skills_dir())));
+        let task_mgr: SharedTaskManager = Arc::new(rustyclaw_core::tasks::TaskManager::new());
+        let model_registry = rustyclaw_core::models::create_model_registry();
+
+        handle_transport_connection(
+            Box::new(mock_transport),
+            shared_config.clone(),
+            Arc::new(RwLock::new(None)),
+            Arc::new(RwLock::new(None)),
+            vault,
+            skill_mgr,
+            task_mgr,
+            model_registry,
+            None,
+            auth::new_rate_limiter(),
+            CancellationToken::new(),
+        )
+        .await?;
+
+        let config = shared_config.read().await;
+        assert!(config.tool_permissions.is_empty());
+        assert_ne!(config.agent_name, "Mallory");
+
+        let frames = outgoing.lock().await;
+        assert!(
+            !frames
+                .iter()
+                .any(|f| f.frame_type == ServerFrameType::SecretsListResult)
+        );
+        let refusals = frames
+            .iter()
+            .filter(|f| {
+                matches!(
+                    &f.payload,
+                    ServerPayload::Error { code: ErrorCode::ReadOnly, message, .. }
+                        if message.contains("not permitted for this client")
+                )
+            })
+            .count();
+        assert_eq!(refusals, 8);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn idle_connection_is_warned_and_closed() -> Result<()> {
+        let (_tmp, mut cfg) = test_config_with_temp_state()?;
+        cfg.totp_enabled = false;
+        cfg.gateway_idle_timeout_secs = 1;
+
+        let peer = PeerInfo {
+            addr: Some("127.0.0.1:2222".parse().unwrap()),
+            username: Some("tester".to_string()),
+            key_fingerprint: Some("SHA256:test".to_string()),
+            transport_type: TransportType::Ssh,
+        };
+
+        // The client connects and then goes quiet without disconnecting.
+        let (mut mock_transport, outgoing) = MockTransport::with_frames(peer, vec![]);
+        mock_transport.hang_when_empty = true;
+
+        let vault: SharedVault = Arc::new(Mutex::new(SecretsManager::new(cfg.credentials_dir())));
+        let skill_mgr: SharedSkillManager =
+            Arc::new(Mutex::new(SkillManager::new(cfg.skills_dir())));
+        let task_mgr: SharedTaskManager = Arc::new(rustyclaw_core::tasks::TaskManager::new());
+        let model_registry = rustyclaw_core::models::create_model_registry();
+
+        tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            handle_transport_connection(
+                Box::new(mock_transport),
+                Arc::new(RwLock::new(cfg)),
+                Arc::new(RwLock::new(None)),
+                Arc::new(RwLock::new(None)),
+                vault,
+                skill_mgr,
+                task_mgr,
+                model_registry,
+                None,
+                auth::new_rate_limiter(),
+                CancellationToken::new(),
+            ),
+        )
+        .await
+        .expect("idle connection should be closed by the gateway")?;
+
+        let frames = outgoing.lock().await;
+        let last = frames.last().expect("Expected frames");
+        assert!(
+            matches!(
+                last.payload,
+                ServerPayload::Status {
+                    status: StatusType::IdleTimeout,
+                    ..
+                }
+            ),
+            "Expected idle-timeout status as the final frame, got {:?}",
+            last.payload
+        );
+
+        Ok(())
+    }
+
+    /// Collects formatted log output.
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Run a connection that asks for request IDs and sends two chats.
+    async fn run_chats_with_request_ids() -> Result<Vec<ServerFrame>> {
+        use rustyclaw_core::gateway::protocol::capabilities::feature;
+
+        let (_tmp, mut cfg) = test_config_with_temp_state()?;
+        cfg.totp_enabled = false;
+        let announce = ClientFrame {
+            frame_type: ClientFrameType::Capabilities,
+            payload: ClientPayload::Capabilities {
+                capabilities: Capabilities::new(&[feature::STREAM_DELTAS, feature::REQUEST_IDS]),
+            },
+        };
+        let chat = |stream_id: u64| {
+            Some(WireFrame::new(
+                stream_id,
+                ClientFrame {
+                    frame_type: ClientFrameType::Chat,
+                    payload: ClientPayload::Chat {
+                        messages: vec![ChatMessage::text("user", "Hello?")],
+                        sampling: Default::default(),
+                        system_prefix: None,
+                    },
+                },
+            ))
+        };
+        let peer = PeerInfo {
+            addr: Some("127.0.0.1:2222".parse().unwrap()),
+            username: Some("tester".to_string()),
+            key_fingerprint: Some("SHA256:test".to_string()),
+            transport_type: TransportType::Ssh,
+        };
+        let frames = vec![Some(WireFrame::control(announce)), chat(1), chat(2), None];
+        let (mock_transport, outgoing) = MockTransport::with_wire_frames(peer, frames);
+
+        let vault: SharedVault = Arc::new(Mutex::new(SecretsManager::new(cfg.credentials_dir())));
+        let skill_mgr: SharedSkillManager =
+            Arc::new(Mutex::new(SkillManager::new(cfg.skills_dir())));
+        let task_mgr: SharedTaskManager = Arc::new(rustyclaw_core::tasks::TaskManager::new());
+        handle_transport_connection(
+            Box::new(mock_transport),
+            Arc::new(RwLock::new(cfg)),
+            Arc::new(RwLock::new(None)),
+            Arc::new(RwLock::new(None)),
+            vault,
+            skill_mgr,
+            task_mgr,
+            rustyclaw_core::models::create_model_registry(),
+            None,
+            auth::new_rate_limiter(),
+            CancellationToken::new(),
+        )
+        .await?;
+        Ok(outgoing.lock().await.clone())
+    }
+
+    /// (connection ID, request ID) of each `RequestStart` frame.
+    fn request_starts(frames: &[ServerFrame]) -> Vec<(String, String)> {
+        frames
+            .iter()
+            .filter_map(|f| match &f.payload {
+                ServerPayload::RequestStart {
+                    connection_id,
+                    request_id,
+                } => Some((connection_id.clone(), request_id.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn concurrent_connections_get_distinct_request_ids() -> Result<()> {
+        let logs = LogBuffer::default();
+        let subscriber = {
+            let logs = logs.clone();
+            tracing_subscriber::fmt()
+                .with_writer(move || logs.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::DEBUG)
+                .finish()
+        };
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let (a, b) = tokio::join!(run_chats_with_request_ids(), run_chats_with_request_ids());
+        let (a, b) = (request_starts(&a?), request_starts(&b?));
+        assert_eq!(a.len(), 2, "one RequestStart per chat: {:?}", a);
+        assert_eq!(b.len(), 2, "one RequestStart per chat: {:?}", b);
+        let (conn_a, conn_b) = (a[0].0.clone(), b[0].0.clone());
+        assert_ne!(conn_a, conn_b);
+        assert!(a.iter().all(|(conn, _)| *conn == conn_a));
+        assert!(b.iter().all(|(conn, _)| *conn == conn_b));
+        assert_ne!(a[0].1, a[1].1);
+        assert_ne!(b[0].1, b[1].1);
+        for (conn, request) in a.iter().chain(&b) {
+            assert!(request.starts_with(&format!("{}-", conn)), "{}", request);
+        }
+
+        // Every chat logged under its own connection and request.
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        for (conn, request) in a.iter().chain(&b) {
+            let logged = logs.lines().any(|line| {
+                line.contains(&format!("conn={}", conn))
+                    && line.contains(&format!("request_id={}", request))
+                    && line.contains("Chat request")
+            });
+            assert!(logged, "no log line for {}:\n{}", request, logs);
+        }
+        assert!(
+            !logs
+                .lines()
+                .any(|line| line.contains(&conn_a) && line.contains(&conn_b)),
+            "a log line mixes connections"
+        );
+
+        Ok(())
+    }
 }
@@ -12,7 +12,7 @@ use tracing::{debug, info, warn};
 
 use rustyclaw_core::gateway::CopilotSession;
 use rustyclaw_core::providers as crate_providers;
-use rustyclaw_core::secrets::SecretsManager;
+use rustyclaw_core::secrets::{AccessContext, SecretsManager};
 
 use crate::SharedVault;
 
@@ -82,7 +82,8 @@ pub(crate) async fn init_copilot_session(
     }
 
     let mut vault_guard = vault.lock().await;
-    let session_result = vault_guard.get_secret("GITHUB_COPILOT_SESSION", true);
+    let ctx = AccessContext::approved("provider:github-copilot");
+    let session_result = vault_guard.get_secret_with("GITHUB_COPILOT_SESSION", &ctx);
 
     let mut session_from_import = match &session_result {
         Ok(Some(json_str)) => {
@@ -0,0 +1,403 @@
+//! Read-only observer connections.
+//!
+//! A client that authenticates with an observer token (see
+//! [`GatewayRole`](rustyclaw_core::secrets::GatewayRole)) does not get a
+//! session of its own.  Instead it watches the sessions of every other
+//! connection: each operator connection writes through a [`MirrorWriter`],
+//! which publishes a copy of the session's activity — statuses, streamed
+//! text, thinking, tool calls and tool results — and [`run_observer`]
+//! forwards those copies to the observer.
+//!
+//! An observer sees every session rather than one it picks.  The gateway
+//! runs a single agent, and sessions are per connection: an observer
+//! connecting from a second device has no way to name a connection that
+//! may not exist yet, and watching "the agent" is the point.  Scope comes
+//! from the token instead — issue observer tokens only to devices that may
+//! see everything the agent does.
+//!
+//! Observers may also export the transcript of any persisted session with
+//! `ExportSession`; the export is redacted like every other copy.
+//!
+//! Only activity frames are mirrored.  Secrets results, auth frames,
+//! approval and credential prompts never leave the operator's connection;
+//! calls to the secrets tools are mirrored with their arguments and output
+//! withheld, and every other tool call and result has credentials redacted
+//! as in exports.
+//! Publishing never waits: the channel is bounded, and an observer that
+//! falls behind skips frames instead of slowing down the tool loop.
+
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, trace};
+
+use rustyclaw_core::gateway::{
+    ClientFrame, ClientFrameType, ClientPayload, ErrorCode, ServerFrame, ServerFrameType,
+    ServerPayload, TransportReader, TransportWriter, protocol,
+};
+use rustyclaw_core::providers::redact_credentials;
+use rustyclaw_core::threads::HistoryStore;
+use rustyclaw_core::tools;
+
+use crate::thread_handler;
+
+/// Stands in for what a secrets tool was given or returned.
+const WITHHELD: &str = "[withheld from observers]";
+
+/// Frames buffered per observer before the oldest are dropped.  Streaming
+/// produces many small chunks, so this is larger than the status channel.
+const CAPACITY: usize = 1024;
+
+/// A frame sent to an operator connection, copied for observers.
+#[derive(Debug, Clone)]
+pub(crate) struct MirroredFrame {
+    /// Connection id (from `status_broadcast::subscribe`) of the session.
+    pub origin: u64,
+    pub frame: ServerFrame,
+}
+
+fn sender() -> &'static broadcast::Sender<MirroredFrame> {
+    static SENDER: OnceLock<broadcast::Sender<MirroredFrame>> = OnceLock::new();
+    SENDER.get_or_init(|| broadcast::channel(CAPACITY).0)
+}
+
+/// Start receiving mirrored session activity.
+pub(crate) fn subscribe() -> broadcast::Receiver<MirroredFrame> {
+    sender().subscribe()
+}
+
+/// Whether observers may see frames of this type.
+pub(crate) fn is_mirrored(frame_type: ServerFrameType) -> bool {
+    matches!(
+        frame_type,
+        ServerFrameType::Status
+            | ServerFrameType::Error
+            | ServerFrameType::Info
+            | ServerFrameType::StreamStart
+            | ServerFrameType::Chunk
+            | ServerFrameType::ThinkingStart
+            | ServerFrameType::ThinkingDelta
+            | ServerFrameType::ThinkingEnd
+            | ServerFrameType::ToolCall
+            | ServerFrameType::ToolResult
+            | ServerFrameType::ResponseDone
+    )
+}
+
+/// The copy of `frame` observers get, or `None` when they get nothing.
+///
+/// Secrets tool calls keep only the tool name; other tool arguments and
+/// results have credentials redacted.
+pub(crate) fn observer_copy(frame: &ServerFrame) -> Option<ServerFrame> {
+    if !is_mirrored(frame.frame_type) {
+        return None;
+    }
+    let payload = match &frame.payload {
+        ServerPayload::ToolCall {
+            id,
+            name,
+            arguments,
+        } => ServerPayload::ToolCall {
+            id: id.clone(),
+            name: name.clone(),
+            arguments: if tools::is_secrets_tool(name) {
+                WITHHELD.to_string()
+            } else {
+                redact_credentials(arguments, None)
+            },
+        },
+        ServerPayload::ToolResult {
+            id,
+            name,
+            result,
+            is_error,
+        } => ServerPayload::ToolResult {
+            id: id.clone(),
+            name: name.clone(),
+            result: if tools::is_secrets_tool(name) {
+                WITHHELD.to_string()
+            } else {
+                redact_credentials(result, None)
+            },
+            is_error: *is_error,
+        },
+        payload => payload.clone(),
+    };
+    Some(ServerFrame {
+        frame_type: frame.frame_type,
+        payload,
+    })
+}
+
+/// Copy `frame` to every observer.  Never waits, and does nothing when no
+/// observer is connected.
+pub(crate) fn publish(origin: u64, frame: &ServerFrame) {
+    let tx = sender();
+    if tx.receiver_count() == 0 {
+        return;
+    }
+    if let Some(frame) = observer_copy(frame) {
+        let _ = tx.send(MirroredFrame { origin, frame });
+    }
+}
+
+/// Writer adapter that publishes a session's activity as it is sent.
+pub(crate) struct MirrorWriter {
+    inner: Box<dyn TransportWriter>,
+    origin: u64,
+}
+
+impl MirrorWriter {
+    pub(crate) fn new(inner: Box<dyn TransportWriter>, origin: u64) -> Self {
+        Self { inner, origin }
+    }
+}
+
+#[async_trait]
+impl TransportWriter for MirrorWriter {
+    async fn send_on_stream(&mut self, stream_id: u64, frame: &ServerFrame) -> Result<()> {
+        publish(self.origin, frame);
+        self.inner.send_on_stream(stream_id, frame).await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await
+    }
+}
+
+/// The error sent back for a frame an observer is not allowed to send, or
+/// `None` for frames that are accepted.
+pub(crate) fn rejection(frame: &ClientFrame) -> Option<String> {
+    // Announcing what the client can render drives nothing, and an export
+    // only reads what the observer is already watching.
+    if matches!(
+        frame.frame_type,
+        ClientFrameType::Capabilities | ClientFrameType::ExportSession
+    ) {
+        return None;
+    }
+    Some(format!(
+        "This connection is read-only (observer token); {:?} was refused.",
+        frame.frame_type
+    ))
+}
+
+/// Serve an authenticated observer until it disconnects or the gateway
+/// shuts down.
+///
+/// Mirrored frames go out on the control stream.  Apart from session
+/// exports, read from the `history` store, every request the observer sends is
+/// answered with an error frame and otherwise dropped; nothing reaches a
+/// session, the tool loop or the vault.
+pub(crate) async fn run_observer(
+    mut reader: Box<dyn TransportReader>,
+    mut writer: Box<dyn TransportWriter>,
+    history: &dyn HistoryStore,
+    cancel: CancellationToken,
+) -> Result<()> {
+    let mut mirror = subscribe();
+    protocol::server::send_info(
+        &mut *writer,
+        "Connected as an observer — you can watch the agent but not send messages or commands.",
+    )
+    .await?;
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            inbound = reader.recv() => {
+                let envelope = match inbound {
+                    Ok(Some(envelope)) => envelope,
+                    Ok(None) => break,
+                    Err(e) => {
+                        trace!(error = %e, "Error reading from observer transport");
+                        break;
+                    }
+                };
+                if let ClientPayload::ExportSession { session_id, format } = envelope.frame.payload {
+                    // Read the sessions as last saved; observers hold none.
+                    let threads = history.load().ok().flatten().unwrap_or_default();
+                    thread_handler::handle_export_session(
+                        &mut *writer,
+                        &threads,
+                        session_id,
+                        format,
+                    )
+                    .await?;
+                } else if let Some(message) = rejection(&envelope.frame) {
+                    debug!(frame_type = ?envelope.frame.frame_type, "Refused observer frame");
+                    protocol::server::send_error(&mut *writer, ErrorCode::ReadOnly, &message)
+                        .await?;
+                }
+            }
+            mirrored = mirror.recv() => match mirrored {
+                Ok(mirrored) => {
+                    protocol::server::send_frame(&mut *writer, &mirrored.frame).await?;
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!(skipped, "Observer fell behind on session activity");
+                }
+                // The sender is never dropped.
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        }
+    }
+
+    let _ = writer.close().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::gateway::{Capabilities, ServerPayload, StatusType};
+
+    use crate::test_support::ChannelWriter;
+
+    fn frame(frame_type: ServerFrameType, payload: ServerPayload) -> ServerFrame {
+        ServerFrame {
+            frame_type,
+            payload,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mirror_copies_activity_but_not_secrets() {
+        let (own, mut sent) = ChannelWriter::new();
+        let mut writer = MirrorWriter::new(Box::new(own), u64::MAX);
+        let mut observer = subscribe();
+
+        let chunk = frame(
+            ServerFrameType::Chunk,
+            ServerPayload::Chunk {
+                delta: "hello".into(),
+            },
+        );
+        let secret = frame(
+            ServerFrameType::SecretsListResult,
+            ServerPayload::SecretsListResult {
+                ok: true,
+                entries: Vec::new(),
+            },
+        );
+        writer.send(&chunk).await.unwrap();
+        writer.send(&secret).await.unwrap();
+        protocol::server::send_status(&mut writer, StatusType::ModelReady, "ready")
+            .await
+            .unwrap();
+
+        // The operator's own client gets everything.
+        assert_eq!(std::iter::from_fn(|| sent.try_recv().ok()).count(), 3);
+
+        // Other tests may publish concurrently; only look at this origin.
+        let mut seen = Vec::new();
+        while let Ok(mirrored) = observer.try_recv() {
+            if mirrored.origin == u64::MAX {
+                seen.push(mirrored.frame.frame_type);
+            }
+        }
+        assert_eq!(seen, vec![ServerFrameType::Chunk, ServerFrameType::Status]);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_withholds_secrets_tool_output() {
+        let (own, mut sent) = ChannelWriter::new();
+        let origin = u64::MAX - 1;
+        let mut writer = MirrorWriter::new(Box::new(own), origin);
+        let mut observer = subscribe();
+
+        let value = "hunter2-correct-horse";
+        protocol::server::send_tool_call(
+            &mut writer,
+            "call_1",
+            "secrets_get",
+            r#"{"name":"db_password"}"#,
+        )
+        .await
+        .unwrap();
+        protocol::server::send_tool_result(
+            &mut writer,
+            "call_1",
+            "secrets_get",
+            &format!("db_password = {value}"),
+            false,
+        )
+        .await
+        .unwrap();
+        let key = "sk-proj-abcdefghijklmnopqrstuvwxyz0123";
+        protocol::server::send_tool_result(
+            &mut writer,
+            "call_2",
+            "read_file",
+            &format!("OPENAI_API_KEY={key}"),
+            false,
+        )
+        .await
+        .unwrap();
+
+        // The operator still sees the value it asked for.
+        assert!(
+            std::iter::from_fn(|| sent.try_recv().ok())
+                .any(|f| format!("{:?}", f.payload).contains(value))
+        );
+
+        let mut seen = Vec::new();
+        while let Ok(mirrored) = observer.try_recv() {
+            if mirrored.origin == origin {
+                seen.push(mirrored.frame);
+            }
+        }
+        assert_eq!(seen.len(), 3);
+        for frame in &seen {
+            let text = format!("{:?}", frame.payload);
+            assert!(!text.contains(value), "secret leaked: {text}");
+            assert!(!text.contains("db_password"), "secret name leaked: {text}");
+            assert!(!text.contains(key), "API key leaked: {text}");
+        }
+        assert!(matches!(
+            &seen[1].payload,
+            ServerPayload::ToolResult { name, result, .. }
+                if name == "secrets_get" && result == WITHHELD
+        ));
+    }
+
+    #[test]
+    fn test_observer_requests_are_refused() {
+        let chat = ClientFrame {
+            frame_type: ClientFrameType::Chat,
+            payload: ClientPayload::Chat {
+                messages: Vec::new(),
+                sampling: Default::default(),
+                system_prefix: None,
+            },
+        };
+        assert!(rejection(&chat).unwrap().contains("read-only"));
+
+        // Cancelling would stop the operator's turn.
+        let cancel = ClientFrame {
+            frame_type: ClientFrameType::Cancel,
+            payload: ClientPayload::Empty,
+        };
+        assert!(rejection(&cancel).is_some());
+
+        let capabilities = ClientFrame {
+            frame_type: ClientFrameType::Capabilities,
+            payload: ClientPayload::Capabilities {
+                capabilities: Capabilities::legacy(),
+            },
+        };
+        assert!(rejection(&capabilities).is_none());
+
+        let export = ClientFrame {
+            frame_type: ClientFrameType::ExportSession,
+            payload: ClientPayload::ExportSession {
+                session_id: 1,
+                format: "markdown".into(),
+            },
+        };
+        assert!(rejection(&export).is_none());
+    }
+}
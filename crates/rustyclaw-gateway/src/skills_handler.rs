@@ -1,7 +1,10 @@
 use anyhow_tracing::{Result, anyhow};
 use tracing::{debug, instrument, warn};
 
+use rustyclaw_core::gateway::StatusType;
+
 use super::SharedSkillManager;
+use crate::status_broadcast;
 
 /// Dispatch a skill management tool call.
 ///
@@ -209,6 +212,14 @@ pub async fn exec_gw_skill_install(
         .map(|v| format!(" v{}", v))
         .unwrap_or_else(|| " (latest)".into());
     debug!(skill = name, "Skill installed and loaded");
+    status_broadcast::publish(
+        None,
+        StatusType::SkillsReloaded,
+        format!(
+            "Skill '{}'{} installed; skills reloaded",
+            name, version_note
+        ),
+    );
     Ok(format!(
         "Skill '{}'{} installed from ClawHub and loaded.",
         name, version_note,
@@ -89,9 +89,9 @@ use russh::server::{Auth, Handler, Msg, Server, Session};
 use russh::{Channel, ChannelId};
 
 /// Maximum frame size (16 MB should be plenty).
-const MAX_FRAME_SIZE: u32 = 16 * 1024 * 1024;
+pub(crate) const MAX_FRAME_SIZE: u32 = 16 * 1024 * 1024;
 
-fn decode_client_wire_frame(data: &[u8]) -> Result<WireFrame<ClientFrame>> {
+pub(crate) fn decode_client_wire_frame(data: &[u8]) -> Result<WireFrame<ClientFrame>> {
     match deserialize_wire_frame::<ClientFrame>(data) {
         Ok(frame) => Ok(frame),
         Err(wire_err) => deserialize_frame::<ClientFrame>(data)
@@ -106,7 +106,7 @@ fn decode_client_wire_frame(data: &[u8]) -> Result<WireFrame<ClientFrame>> {
     }
 }
 
-fn encode_server_wire_frame(stream_id: u64, frame: &ServerFrame) -> Result<Vec<u8>> {
+pub(crate) fn encode_server_wire_frame(stream_id: u64, frame: &ServerFrame) -> Result<Vec<u8>> {
     serialize_wire_frame(&WireFrame::new(stream_id, frame.clone())).map_err(|e| anyhow::anyhow!(e))
 }
 
@@ -0,0 +1,38 @@
+//! `ssh` tool execution for the gateway.
+//!
+//! The tool's host allowlist is the `[ssh_hosts]` section of the live
+//! config, which only the gateway holds, so it is intercepted here instead
+//! of going through `tools::execute_tool`.  Host lookup, vault access and
+//! the SSH session itself live in `rustyclaw_core::tools::ssh`.
+
+use serde_json::Value;
+use tracing::instrument;
+
+use rustyclaw_core::tools::{self, ssh};
+
+use crate::{SharedConfig, SharedVault, ToolCancelFlag, tool_executor};
+
+/// Check if a tool name is the ssh tool.
+pub fn is_ssh_tool(name: &str) -> bool {
+    name == "ssh"
+}
+
+/// Execute an `ssh` tool call against the configured hosts.
+///
+/// Setting `tool_cancel` drops the connection.
+#[instrument(skip(shared_config, vault, args, tool_cancel))]
+pub async fn execute_ssh(
+    shared_config: &SharedConfig,
+    vault: &SharedVault,
+    args: &Value,
+    tool_cancel: &ToolCancelFlag,
+) -> Result<String, String> {
+    tool_executor::check_rate_limit("ssh")?;
+    let hosts = shared_config.read().await.ssh_hosts.clone();
+    tokio::select! {
+        result = ssh::run_ssh(args, &hosts, vault, &ssh::RusshTransport) => result,
+        _ = tool_executor::cancel_requested(tool_cancel) => {
+            Err(tools::TOOL_CANCELLED.to_string())
+        }
+    }
+}
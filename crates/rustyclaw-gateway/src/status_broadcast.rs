@@ -0,0 +1,95 @@
+//! Gateway-wide status broadcasts.
+//!
+//! Some changes affect every client, not just the one that caused them: a
+//! config reload, a model switch, the vault being unlocked, skills being
+//! reloaded.  They are published here and each connection forwards them to
+//! its client as ordinary status frames, so a second client does not keep
+//! showing a stale model until its next request.
+//!
+//! Connections forward broadcasts from their main loop, between requests,
+//! so a broadcast never lands in the middle of a streamed response.  The
+//! channel is bounded and a connection that falls behind skips the oldest
+//! events rather than holding up [`publish`].
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::sync::broadcast;
+use tracing::trace;
+
+use rustyclaw_core::gateway::StatusType;
+
+/// Events buffered per connection before the oldest are dropped.
+const CAPACITY: usize = 64;
+
+/// A status change for every connected client.
+#[derive(Debug, Clone)]
+pub(crate) struct StatusEvent {
+    /// The connection that caused the change; it has already been told.
+    pub origin: Option<u64>,
+    pub status: StatusType,
+    pub detail: String,
+}
+
+/// A connection's view of the broadcasts.
+pub(crate) struct Subscription {
+    /// Identifies the connection as an event origin.
+    pub id: u64,
+    pub rx: broadcast::Receiver<StatusEvent>,
+}
+
+fn sender() -> &'static broadcast::Sender<StatusEvent> {
+    static SENDER: OnceLock<broadcast::Sender<StatusEvent>> = OnceLock::new();
+    SENDER.get_or_init(|| broadcast::channel(CAPACITY).0)
+}
+
+/// Start receiving broadcasts for a new connection.
+pub(crate) fn subscribe() -> Subscription {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    Subscription {
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        rx: sender().subscribe(),
+    }
+}
+
+/// Send a status to every connection except `origin`.  Never waits.
+pub(crate) fn publish(origin: Option<u64>, status: StatusType, detail: impl Into<String>) {
+    let event = StatusEvent {
+        origin,
+        status,
+        detail: detail.into(),
+    };
+    // An error only means nobody is connected.
+    if let Err(broadcast::error::SendError(event)) = sender().send(event) {
+        trace!(status = ?event.status, "No connections for status broadcast");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use broadcast::error::TryRecvError;
+
+    #[test]
+    fn test_broadcast_reaches_every_subscriber_without_blocking() {
+        let mut a = subscribe();
+        let mut b = subscribe();
+        assert_ne!(a.id, b.id);
+
+        publish(Some(a.id), StatusType::ModelConfigured, "OpenAI / gpt-4o");
+        let event = b.rx.try_recv().unwrap();
+        assert_eq!(event.status, StatusType::ModelConfigured);
+        assert_eq!(event.detail, "OpenAI / gpt-4o");
+        assert_eq!(event.origin, Some(a.id));
+        // The origin still receives it; the connection loop skips it.
+        assert_eq!(a.rx.try_recv().unwrap().origin, Some(a.id));
+
+        // A subscriber that never reads does not hold up publishing; it
+        // loses the oldest events instead.
+        for i in 0..CAPACITY + 5 {
+            publish(None, StatusType::SkillsReloaded, format!("{}", i));
+        }
+        assert!(matches!(b.rx.try_recv(), Err(TryRecvError::Lagged(n)) if n >= 5));
+        assert!(b.rx.try_recv().is_ok());
+    }
+}
@@ -0,0 +1,41 @@
+//! `summarize_url` tool execution for the gateway.
+//!
+//! Fetching, boilerplate stripping and chunking happen in
+//! `rustyclaw_core::tools::summarize_url`; the gateway only supplies the
+//! model call for each chunk and for the final summary.
+
+use serde_json::Value;
+use tracing::instrument;
+
+use rustyclaw_core::gateway::ProviderRequest;
+use rustyclaw_core::tools::{self, summarize_url};
+
+use crate::translate_handler::ask_model;
+use crate::{ToolCancelFlag, tool_executor};
+
+/// Check if a tool name is the summarize_url tool.
+pub fn is_summarize_tool(name: &str) -> bool {
+    name == "summarize_url"
+}
+
+/// Execute a `summarize_url` tool call against the conversation's model.
+///
+/// Setting `tool_cancel` abandons the fetch or whichever model request is
+/// in flight.
+#[instrument(skip(http, resolved, args, tool_cancel))]
+pub async fn execute_summarize(
+    http: &reqwest::Client,
+    resolved: &ProviderRequest,
+    args: &Value,
+    tool_cancel: &ToolCancelFlag,
+) -> Result<String, String> {
+    let run = summarize_url::summarize_url_with(args, |prompt| {
+        ask_model(http, resolved, prompt, "Summary")
+    });
+    tokio::select! {
+        result = run => result,
+        _ = tool_executor::cancel_requested(tool_cancel) => {
+            Err(tools::TOOL_CANCELLED.to_string())
+        }
+    }
+}
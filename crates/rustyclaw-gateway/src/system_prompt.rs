@@ -173,6 +173,8 @@ fn build_tool_usage_section() -> String {
 ### Credentials & API Access (IMPORTANT)
 **Before asking for API keys or tokens:** Run `secrets_list` to check the vault first.
 If a credential exists, use `secrets_get` to retrieve it — don't ask the user again.
+When a new password or token is needed, use `secrets_generate` rather than making one up;
+pass `name` to store it in the vault without the value entering the conversation.
 
 **Authenticated API workflow:**
 1. `secrets_list()` → discover available credentials
@@ -188,6 +190,8 @@ If a credential exists, use `secrets_get` to retrieve it — don't ask the user
 Before answering questions about prior work, decisions, dates, people, preferences, or todos:
 Run `memory_search` first, then use `memory_get` to pull relevant context.
 If low confidence after search, mention that you checked but didn't find a match.
+Working state you need on a later turn (counters, partial results, flags) goes in
+`kv_store(action=\"set\", key=\"...\", value=\"...\")`, not in scratch files.
 
 ### File Operations
 - `read_file` — read file contents (supports text, PDF, docx, etc.)
@@ -195,11 +199,19 @@ If low confidence after search, mention that you checked but didn't find a match
 - `edit_file` — surgical search-and-replace (include enough context for unique match)
 - `find_files` — find by name/glob pattern
 - `search_files` — search file contents (like grep)
+- `move_file` / `copy_file` — move, rename or copy files and folders (`force` to replace)
+- `regex` — find, replace or extract with a regex over text or a file (instead of sed)
+- `parse_log` — triage a log file: counts by level, most repeated messages and the latest matching lines, filtered by `level`, `pattern` and `since`/`until` (`tail` for huge files). Use it instead of reading or grepping large logs
+- `text_stats` — line/word/byte counts, first and last lines, encoding and CRLF/LF style of a file in one call (`pattern` to count matches). Use it instead of `wc`, `head` or `tail`
+- `extract_archive_member` — list a zip/tar(.gz/.zst) or read one file from it without extracting (e.g. a config inside a large backup)
 
 ### Command Execution
 - Short commands: `execute_command(command=\"...\")`
 - Long-running: `execute_command(command=\"...\", background=true)` then `process(action=\"poll\", session_id=\"...\")`
 - Interactive TTY: use `pty=true` for commands needing terminal
+- Variables for every later command: `env_var(action=\"set\", name=\"...\", value=\"...\")`
+- Containers: `docker(action=\"ps\")`, `docker(action=\"run\", image=\"...\")`, then `logs` / `exec` / `stop` (instead of `execute_command(\"docker ...\")`)
+- Callbacks and sharing: `http_server(action=\"capture\")` gives a loopback URL to use as an OAuth redirect or webhook target; `http_server(action=\"result\", id=\"...\")` returns the request it received. `http_server(action=\"serve\", path=\"...\")` shares a file or directory for a few minutes
 
 ### Sub-Agents
 Spawn sub-agents for complex or time-consuming tasks:
@@ -236,6 +248,46 @@ patterns are more precise and don't break on formatting differences.
 - `load_status` — query current system load (0.0–1.0 composite score, CPU%,
   memory%, active models). Use this before launching heavy local inference to
   decide whether to use a local model or fall back to an external provider.
+- `env_info` — one-call snapshot of OS, shell, installed toolchains and their
+  versions, and container/VM status. Prefer it over `uname`/`--version` probes.
+- `process_tree` — find processes by name or pid with their parent/child
+  relationships, and stop a runaway process together with its children
+  (`action=\"kill\", tree=true`). Use it instead of `ps`/`pkill` pipelines.
+
+### Translation
+- `translate(text=\"...\", target_lang=\"...\")` — translate text with the configured
+  model. Markdown and fenced code blocks are preserved; long input is chunked.
+  Use it instead of translating large passages inline.
+- `translate_file(path=\"...\", target_lang=\"...\")` — translate .srt/.vtt subtitles,
+  Markdown or .po catalogs into a new file beside the original (e.g. film.fr.srt),
+  keeping timings, code blocks and msgids intact.
+
+### Proofreading
+- `proofread(text=\"...\")` or `proofread(path=\"...\")` — returns a list of
+  corrections (original, suggestion, reason) and the corrected text; code is left
+  alone. `engine=\"spell\"` does an offline spelling check with aspell/hunspell.
+  It never edits the file; apply the corrections yourself if the user wants them.
+
+### Web summaries
+- `summarize_url(url=\"...\")` — the gist of a page as a summary plus key points,
+  without pulling the whole page into the conversation. `length` is short, medium
+  or long; `include_links=true` adds the page's links. Use `web_fetch` when you
+  need the exact text.
+
+### Git
+- `git` — status, diff, log, branch, add, commit and stash with structured
+  results. Prefer it over `execute_command(\"git ...\")`. `commit` previews
+  first; only pass `confirm=true` when the user asked for a commit.
+
+### Linting
+- `lint` — runs clippy, eslint, ruff/flake8 or shellcheck as the project calls
+  for and returns file/line/severity diagnostics. Prefer it over running linters
+  with `execute_command`. Only pass `fix=true` when the user wants edits applied.
+
+### Audio
+- `transcribe(path=\"...\")` — speech to text for voice notes and meeting
+  recordings (wav, mp3, m4a, ogg, flac, webm…). `timestamps=true` adds timed
+  segments. The result says whether local whisper.cpp or the cloud backend ran.
 
 ### Tool Call Style
 - Default: don't narrate routine tool calls (just call them)
@@ -89,7 +89,7 @@ async fn exec_task_list(
                 "id": t.id.0,
                 "kind": t.kind.display_name(),
                 "label": t.display_label(),
-                "status": format_status_short(&t.status),
+                "status": t.status.code(),
                 "foreground": t.status.is_foreground(),
                 "elapsed": t.elapsed().map(|d| d.as_secs()),
                 "progress": t.status.progress(),
@@ -123,7 +123,7 @@ async fn exec_task_status(args: &Value, task_mgr: &SharedTaskManager) -> Result<
         "kindDetails": task.kind.description(),
         "label": task.display_label(),
         "status": format_task_status(&task),
-        "statusCode": format_status_short(&task.status),
+        "statusCode": task.status.code(),
         "foreground": task.status.is_foreground(),
         "progress": task.status.progress(),
         "message": task.status.message(),
@@ -230,7 +230,7 @@ async fn exec_task_resume(args: &Value, task_mgr: &SharedTaskManager) -> Result<
         return Err(format!(
             "Task {} is not paused (status: {})",
             task_id,
-            format_status_short(&task.status)
+            task.status.code()
         ));
     }
 
@@ -270,7 +270,7 @@ async fn exec_task_input(args: &Value, task_mgr: &SharedTaskManager) -> Result<S
         return Err(format!(
             "Task {} is not waiting for input (status: {})",
             task_id,
-            format_status_short(&task.status)
+            task.status.code()
         ));
     }
 
@@ -336,19 +336,6 @@ fn parse_task_id(args: &Value) -> Result<TaskId, String> {
     Ok(TaskId(id))
 }
 
-fn format_status_short(status: &TaskStatus) -> &'static str {
-    match status {
-        TaskStatus::Pending => "pending",
-        TaskStatus::Running { .. } => "running",
-        TaskStatus::Background { .. } => "background",
-        TaskStatus::Paused { .. } => "paused",
-        TaskStatus::Completed { .. } => "completed",
-        TaskStatus::Failed { .. } => "failed",
-        TaskStatus::Cancelled => "cancelled",
-        TaskStatus::WaitingForInput { .. } => "waiting_input",
-    }
-}
-
 /// Generate a system prompt section describing active tasks.
 pub async fn generate_task_prompt_section(
     task_mgr: &SharedTaskManager,
@@ -0,0 +1,260 @@
+//! Gateway-wide task registry.
+//!
+//! The shared [`TaskManager`](rustyclaw_core::tasks::TaskManager) already
+//! holds every connection's and messenger's tasks; this module feeds it the
+//! work that would otherwise run untracked — sub-agents started with
+//! `sessions_spawn` and scheduled-message runs — and answers
+//! `GlobalTasksRequest` with the list across all sessions.
+//!
+//! Command tasks are recorded by [`crate::command_wrapper`].
+
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::debug;
+
+use rustyclaw_core::gateway::protocol::frames::GlobalTaskDto;
+use rustyclaw_core::gateway::protocol::server::send_frame;
+use rustyclaw_core::gateway::{ServerFrame, ServerFrameType, ServerPayload, transport};
+use rustyclaw_core::sessions::{SessionStatus, SpawnResult, session_manager};
+use rustyclaw_core::tasks::{TaskFilter, TaskId, TaskKind};
+
+use crate::SharedTaskManager;
+
+/// Finished tasks older than this are dropped before listing.
+const FINISHED_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Record a sub-agent started by a successful `sessions_spawn` call made
+/// from `owner_session`.  Returns `None` when `output` is not a spawn result.
+pub async fn record_subagent_task(
+    task_mgr: &SharedTaskManager,
+    owner_session: &str,
+    output: &str,
+) -> Option<TaskId> {
+    let spawned: SpawnResult = serde_json::from_str(output).ok()?;
+    let (label, task) = session_manager()
+        .lock()
+        .ok()
+        .and_then(|mgr| {
+            mgr.get(&spawned.session_key)
+                .map(|s| (s.label.clone(), s.task.clone()))
+        })
+        .unwrap_or_default();
+
+    let kind = TaskKind::SubAgent {
+        session_key: spawned.session_key.clone(),
+        label,
+    };
+    let id = task_mgr
+        .create(kind, Some(owner_session.to_string()))
+        .await
+        .id;
+    if let Some(task) = task {
+        let _ = task_mgr.set_description(id, &task).await;
+    }
+    task_mgr.start(id).await;
+    // Sub-agents run alongside the conversation that spawned them.
+    let _ = task_mgr.set_background(id).await;
+
+    debug!(task_id = %id, session_key = %spawned.session_key, "Sub-agent task started");
+    Some(id)
+}
+
+/// Record one scheduled-message run that sent `count` messages.
+///
+/// The cron store is only known to have had work once it has been sent,
+/// so the run is recorded already finished.
+pub async fn record_cron_run(task_mgr: &SharedTaskManager, cron_dir: &str, count: usize) {
+    let kind = TaskKind::CronJob {
+        job_id: cron_dir.to_string(),
+        job_name: Some("scheduled messages".to_string()),
+    };
+    let id = task_mgr.create(kind, None).await.id;
+    task_mgr.start(id).await;
+    task_mgr
+        .complete(id, Some(format!("Sent {count} scheduled message(s)")))
+        .await;
+}
+
+/// Finish sub-agent tasks whose session has ended.
+async fn sync_subagent_tasks(task_mgr: &SharedTaskManager) {
+    let running = task_mgr
+        .query(&TaskFilter {
+            kind: Some("sub_agent".to_string()),
+            ..Default::default()
+        })
+        .await;
+    if running.is_empty() {
+        return;
+    }
+
+    // Read every status first: the session manager lock is not async.
+    let ended: Vec<(TaskId, SessionStatus)> = {
+        let Ok(mgr) = session_manager().lock() else {
+            return;
+        };
+        running
+            .iter()
+            .filter_map(|t| match &t.kind {
+                TaskKind::SubAgent { session_key, .. } => mgr
+                    .get(session_key)
+                    .filter(|s| s.status != SessionStatus::Active)
+                    .map(|s| (t.id, s.status.clone())),
+                _ => None,
+            })
+            .collect()
+    };
+
+    for (id, status) in ended {
+        match status {
+            SessionStatus::Completed => task_mgr.complete(id, None).await,
+            SessionStatus::Error => {
+                task_mgr
+                    .fail(id, "Sub-agent failed".to_string(), false)
+                    .await
+            }
+            SessionStatus::Timeout => {
+                task_mgr
+                    .fail(id, "Sub-agent timed out".to_string(), true)
+                    .await
+            }
+            SessionStatus::Stopped | SessionStatus::Active => {
+                let _ = task_mgr.cancel(id).await;
+            }
+        }
+    }
+}
+
+/// Tasks from every session matching `filter`, oldest first.
+pub async fn global_tasks(task_mgr: &SharedTaskManager, filter: &TaskFilter) -> Vec<GlobalTaskDto> {
+    task_mgr.cleanup_old(FINISHED_RETENTION).await;
+    sync_subagent_tasks(task_mgr).await;
+    task_mgr
+        .query(filter)
+        .await
+        .iter()
+        .map(GlobalTaskDto::new)
+        .collect()
+}
+
+/// Handle a `GlobalTasksRequest`: send the gateway-wide task list.
+pub(crate) async fn handle_global_tasks_request(
+    writer: &mut dyn transport::TransportWriter,
+    task_mgr: &SharedTaskManager,
+    filter: TaskFilter,
+) -> Result<()> {
+    let tasks = global_tasks(task_mgr, &filter).await;
+    let frame = ServerFrame {
+        frame_type: ServerFrameType::GlobalTasksResult,
+        payload: ServerPayload::GlobalTasksResult { tasks },
+    };
+    send_frame(writer, &frame).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use rustyclaw_core::tasks::TaskManager;
+
+    fn spawn_subagent(label: &str) -> String {
+        let session_key = session_manager().lock().unwrap().spawn_subagent(
+            "main",
+            "summarize the logs",
+            Some(label.to_string()),
+            None,
+        );
+        serde_json::to_string(&SpawnResult {
+            status: "accepted".to_string(),
+            run_id: String::new(),
+            session_key,
+            message: String::new(),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_tasks_from_two_sessions_listed_with_owner() {
+        let task_mgr: SharedTaskManager = Arc::new(TaskManager::new());
+        let args = serde_json::json!({ "command": "cargo test" });
+        let command =
+            crate::command_wrapper::start_command_task(&task_mgr, &args, "thread-1").await;
+        let output = spawn_subagent("registry-test");
+        let agent = record_subagent_task(&task_mgr, "telegram:42", &output)
+            .await
+            .unwrap();
+
+        let tasks = global_tasks(&task_mgr, &TaskFilter::default()).await;
+        assert_eq!(tasks.len(), 2);
+
+        let command_dto = tasks.iter().find(|t| t.id == command.0).unwrap();
+        assert_eq!(command_dto.kind, "command");
+        assert_eq!(command_dto.session.as_deref(), Some("thread-1"));
+        assert_eq!(command_dto.status, "running");
+        assert!(command_dto.started_at.is_some());
+
+        let agent_dto = tasks.iter().find(|t| t.id == agent.0).unwrap();
+        assert_eq!(agent_dto.kind, "sub_agent");
+        assert_eq!(agent_dto.session.as_deref(), Some("telegram:42"));
+        assert_eq!(agent_dto.label, "registry-test");
+        assert_eq!(agent_dto.description.as_deref(), Some("summarize the logs"));
+        assert!(agent_dto.started_at.is_some());
+
+        let by_kind = TaskFilter {
+            kind: Some("sub_agent".to_string()),
+            ..Default::default()
+        };
+        let only_agent = global_tasks(&task_mgr, &by_kind).await;
+        assert_eq!(only_agent.len(), 1);
+        assert_eq!(only_agent[0].id, agent.0);
+    }
+
+    #[tokio::test]
+    async fn test_finished_subagent_and_cron_runs() {
+        let task_mgr: SharedTaskManager = Arc::new(TaskManager::new());
+        let output = spawn_subagent("registry-done");
+        let agent = record_subagent_task(&task_mgr, "thread-2", &output)
+            .await
+            .unwrap();
+        let spawned: SpawnResult = serde_json::from_str(&output).unwrap();
+        session_manager()
+            .lock()
+            .unwrap()
+            .complete_session(&spawned.session_key)
+            .unwrap();
+        record_cron_run(&task_mgr, "/tmp/.cron", 2).await;
+
+        // Both are finished, so the default listing leaves them out.
+        assert!(
+            global_tasks(&task_mgr, &TaskFilter::default())
+                .await
+                .is_empty()
+        );
+
+        let completed = TaskFilter {
+            status: Some("completed".to_string()),
+            ..Default::default()
+        };
+        let tasks = global_tasks(&task_mgr, &completed).await;
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].id, agent.0);
+        assert_eq!(tasks[1].kind, "cron");
+        assert_eq!(tasks[1].session, None);
+        assert_eq!(
+            tasks[1].message.as_deref(),
+            Some("Sent 2 scheduled message(s)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_subagent_ignores_other_output() {
+        let task_mgr: SharedTaskManager = Arc::new(TaskManager::new());
+        assert!(
+            record_subagent_task(&task_mgr, "thread-1", "not json")
+                .await
+                .is_none()
+        );
+        assert!(task_mgr.all().await.is_empty());
+    }
+}
@@ -0,0 +1,47 @@
+//! Transport writers shared by the gateway's unit tests.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rustyclaw_core::gateway::ServerFrame;
+use rustyclaw_core::gateway::transport::TransportWriter;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+/// Keeps every frame sent to it, in order.
+#[derive(Default)]
+pub(crate) struct CollectWriter(pub(crate) Vec<ServerFrame>);
+
+#[async_trait]
+impl TransportWriter for CollectWriter {
+    async fn send_on_stream(&mut self, _stream_id: u64, frame: &ServerFrame) -> Result<()> {
+        self.0.push(frame.clone());
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Forwards every frame to a channel, so a test can read and answer them
+/// while the code under test is still waiting.
+pub(crate) struct ChannelWriter(pub(crate) UnboundedSender<ServerFrame>);
+
+impl ChannelWriter {
+    /// A writer and the receiving end of its channel.
+    pub(crate) fn new() -> (Self, UnboundedReceiver<ServerFrame>) {
+        let (tx, rx) = unbounded_channel();
+        (Self(tx), rx)
+    }
+}
+
+#[async_trait]
+impl TransportWriter for ChannelWriter {
+    async fn send_on_stream(&mut self, _stream_id: u64, frame: &ServerFrame) -> Result<()> {
+        let _ = self.0.send(frame.clone());
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -1,21 +1,24 @@
 //! Thread/task client-frame handlers.
 //!
 //! Each function handles one `ClientPayload` variant in the thread family
-//! (create / switch / list / history / close / rename) plus `TasksRequest`,
+//! (create / switch / list / history / export / close / rename) plus `TasksRequest`,
 //! operating on the connection's [`ThreadManager`](rustyclaw_core::threads::ThreadManager)
 //! and streaming the resulting frames back to the client.
 
 use anyhow::Result;
 use tracing::{debug, info};
 
+use rustyclaw_core::config::Config;
 use rustyclaw_core::gateway::protocol::server::{send_frame, send_info};
 use rustyclaw_core::gateway::{
-    ChatMessage, ProviderRequest, ServerFrame, ServerFrameType, ServerPayload, protocol, transport,
+    ChatMessage, ErrorCode, ProviderRequest, ServerFrame, ServerFrameType, ServerPayload, protocol,
+    transport,
 };
-use rustyclaw_core::threads::ThreadId;
+use rustyclaw_core::session_workspace::SessionWorkspaces;
+use rustyclaw_core::threads::{HistoryStore, ThreadId, TranscriptFormat, render_transcript};
 
 use crate::thread_updates::{send_thread_messages_update, send_threads_update};
-use crate::{SharedModelCtx, SharedTaskManager, providers};
+use crate::{SharedModelCtx, SharedTaskManager, helpers, providers};
 
 /// Handle a `TasksRequest`: send the current task list.
 pub(crate) async fn handle_tasks_request(
@@ -56,7 +59,7 @@ pub(crate) async fn handle_thread_create(
     writer: &mut dyn transport::TransportWriter,
     thread_mgr: &mut rustyclaw_core::threads::ThreadManager,
     task_mgr: &SharedTaskManager,
-    threads_path: &std::path::Path,
+    history: &dyn HistoryStore,
     project_id: rustyclaw_core::projects::ProjectId,
     label: String,
 ) -> Result<()> {
@@ -78,7 +81,7 @@ pub(crate) async fn handle_thread_create(
     // Send updated thread list
     send_threads_update(writer, thread_mgr, task_mgr, None).await?;
     // Persist thread state
-    let _ = thread_mgr.save_to_file(threads_path);
+    let _ = history.save(thread_mgr);
     Ok(())
 }
 
@@ -89,7 +92,7 @@ pub(crate) async fn handle_thread_switch(
     writer: &mut dyn transport::TransportWriter,
     thread_mgr: &mut rustyclaw_core::threads::ThreadManager,
     task_mgr: &SharedTaskManager,
-    threads_path: &std::path::Path,
+    history: &dyn HistoryStore,
     shared_model_ctx: &SharedModelCtx,
     http: &reqwest::Client,
     thread_id: u64,
@@ -117,7 +120,7 @@ pub(crate) async fn handle_thread_switch(
             },
         };
         send_frame(writer, &frame).await?;
-        let _ = thread_mgr.save_to_file(threads_path);
+        let _ = history.save(thread_mgr);
         return Ok(());
     }
 
@@ -146,6 +149,9 @@ pub(crate) async fn handle_thread_switch(
                             provider: ctx.provider.clone(),
                             base_url: ctx.base_url.clone(),
                             api_key: ctx.api_key.clone(),
+                            sampling: Default::default(),
+                            cache_prompt: false,
+                            thinking_budget: None,
                         };
 
                         let summary_result = if ctx.provider == "anthropic" {
@@ -195,13 +201,14 @@ pub(crate) async fn handle_thread_switch(
         send_threads_update(writer, thread_mgr, task_mgr, None).await?;
         send_thread_messages_update(writer, target_id, thread_mgr).await?;
         // Persist thread state (includes compaction summary)
-        let _ = thread_mgr.save_to_file(threads_path);
+        let _ = history.save(thread_mgr);
     } else {
         let frame = ServerFrame {
             frame_type: ServerFrameType::Error,
             payload: ServerPayload::Error {
                 ok: false,
                 message: format!("Thread {} not found", thread_id),
+                code: ErrorCode::NotFound,
             },
         };
         send_frame(writer, &frame).await?;
@@ -280,21 +287,77 @@ pub(crate) async fn handle_thread_history(
     send_frame(writer, &frame).await
 }
 
+/// Handle an `ExportSession`: send a thread's transcript.
+///
+/// Only threads in `thread_mgr` can be exported, so a connection never
+/// reaches a session it could not already switch to.  An unknown id and a
+/// thread the connection doesn't have get the same "not found" reply.
+pub(crate) async fn handle_export_session(
+    writer: &mut dyn transport::TransportWriter,
+    thread_mgr: &rustyclaw_core::threads::ThreadManager,
+    session_id: u64,
+    format: String,
+) -> Result<()> {
+    debug!(session_id, %format, "Session export request");
+    let result = match TranscriptFormat::parse(&format) {
+        None => Err(format!(
+            "Unknown export format '{}' (use \"markdown\" or \"json\")",
+            format
+        )),
+        Some(parsed) => match thread_mgr.get(ThreadId(session_id)) {
+            Some(thread) => Ok((parsed, render_transcript(thread, parsed))),
+            None => Err(format!("Session {} not found", session_id)),
+        },
+    };
+    let payload = match result {
+        Ok((parsed, content)) => {
+            info!(
+                session_id,
+                format = parsed.as_str(),
+                bytes = content.len(),
+                "Exported session"
+            );
+            ServerPayload::SessionExportResult {
+                session_id,
+                ok: true,
+                format: parsed.as_str().to_string(),
+                content,
+                error: None,
+            }
+        }
+        Err(error) => ServerPayload::SessionExportResult {
+            session_id,
+            ok: false,
+            format,
+            content: String::new(),
+            error: Some(error),
+        },
+    };
+    let frame = ServerFrame {
+        frame_type: ServerFrameType::SessionExportResult,
+        payload,
+    };
+    send_frame(writer, &frame).await
+}
+
 /// Handle a `ThreadClose`: remove a thread and broadcast the new list.
+/// The thread's session directory goes too if it was left empty.
 pub(crate) async fn handle_thread_close(
     writer: &mut dyn transport::TransportWriter,
+    config: &Config,
     thread_mgr: &mut rustyclaw_core::threads::ThreadManager,
     task_mgr: &SharedTaskManager,
-    threads_path: &std::path::Path,
+    history: &dyn HistoryStore,
     thread_id: u64,
 ) -> Result<()> {
     debug!("Thread close request: {}", thread_id);
     let task_id = ThreadId(thread_id);
     thread_mgr.remove(task_id);
+    SessionWorkspaces::from_config(config).leave(&helpers::thread_session_key(thread_id));
     // Send updated thread list
     send_threads_update(writer, thread_mgr, task_mgr, None).await?;
     // Persist thread state
-    let _ = thread_mgr.save_to_file(threads_path);
+    let _ = history.save(thread_mgr);
     Ok(())
 }
 
@@ -303,7 +366,7 @@ pub(crate) async fn handle_thread_rename(
     writer: &mut dyn transport::TransportWriter,
     thread_mgr: &mut rustyclaw_core::threads::ThreadManager,
     task_mgr: &SharedTaskManager,
-    threads_path: &std::path::Path,
+    history: &dyn HistoryStore,
     thread_id: u64,
     new_label: String,
 ) -> Result<()> {
@@ -313,13 +376,14 @@ pub(crate) async fn handle_thread_rename(
         // Send updated thread list
         send_threads_update(writer, thread_mgr, task_mgr, None).await?;
         // Persist thread state
-        let _ = thread_mgr.save_to_file(threads_path);
+        let _ = history.save(thread_mgr);
     } else {
         let frame = ServerFrame {
             frame_type: ServerFrameType::Error,
             payload: ServerPayload::Error {
                 ok: false,
                 message: format!("Thread {} not found", thread_id),
+                code: ErrorCode::NotFound,
             },
         };
         send_frame(writer, &frame).await?;
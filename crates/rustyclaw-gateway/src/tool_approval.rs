@@ -0,0 +1,266 @@
+//! The `Ask` tool permission: prompt the client and wait for its answer.
+//!
+//! Each connection has one [`Approvals`]: the channel its reader task
+//! forwards `ToolApprovalResponse` frames into, and the tools the user
+//! allowed "for this session".  Those skip the prompt until the connection
+//! closes, as long as `tool_approval_session` stays on.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::{Receiver, Sender};
+use tracing::debug;
+
+use rustyclaw_core::gateway::protocol;
+use rustyclaw_core::gateway::transport::TransportWriter;
+use rustyclaw_core::tools::ApprovalContext;
+
+/// How long a prompt waits before the call counts as denied.
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A client's answer to one approval request.
+#[derive(Debug)]
+pub(crate) struct ApprovalReply {
+    pub(crate) id: String,
+    pub(crate) approved: bool,
+    pub(crate) remember: bool,
+}
+
+/// One connection's approval state.
+#[derive(Debug)]
+pub(crate) struct Approvals {
+    replies: Receiver<ApprovalReply>,
+    session: HashSet<String>,
+}
+
+pub(crate) type SharedApprovals = Arc<Mutex<Approvals>>;
+
+/// A connection's reply sender and approval state.
+pub(crate) fn channel() -> (Sender<ApprovalReply>, SharedApprovals) {
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let approvals = Approvals {
+        replies: rx,
+        session: HashSet::new(),
+    };
+    (tx, Arc::new(Mutex::new(approvals)))
+}
+
+/// Ask the client whether tool call `id` may run.  `false` if it says no,
+/// answers a different call, goes away or doesn't answer in time.
+/// `allow_session` is the `tool_approval_session` setting.
+pub(crate) async fn request_approval(
+    writer: &mut dyn TransportWriter,
+    approvals: &SharedApprovals,
+    id: &str,
+    name: &str,
+    arguments: &Value,
+    allow_session: bool,
+) -> Result<bool> {
+    let mut approvals = approvals.lock().await;
+    if allow_session && approvals.session.contains(name) {
+        debug!(tool = name, "Tool already approved for this session");
+        return Ok(true);
+    }
+
+    let context = ApprovalContext::new(name, arguments);
+    protocol::server::send_tool_approval_request(writer, id, name, &context, allow_session).await?;
+
+    match tokio::time::timeout(APPROVAL_TIMEOUT, approvals.replies.recv()).await {
+        Ok(Some(reply)) if reply.id == id => {
+            if reply.approved && reply.remember && allow_session {
+                approvals.session.insert(name.to_string());
+            }
+            Ok(reply.approved)
+        }
+        // Mismatched ID, closed channel or timeout — treat as denied.
+        _ => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::gateway::ServerPayload;
+    use rustyclaw_core::tools::ToolRisk;
+    use serde_json::json;
+
+    use crate::test_support::ChannelWriter;
+
+    fn reply(id: &str, approved: bool, remember: bool) -> ApprovalReply {
+        ApprovalReply {
+            id: id.into(),
+            approved,
+            remember,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_carries_redacted_args_and_risks() {
+        let (mut writer, mut frames) = ChannelWriter::new();
+        let (tx, approvals) = channel();
+        tx.send(reply("call-1", true, false)).await.unwrap();
+
+        let args = json!({
+            "command": "./deploy.sh",
+            "env": { "password": "hunter2-correct-horse" },
+        });
+        let approved = request_approval(
+            &mut writer,
+            &approvals,
+            "call-1",
+            "execute_command",
+            &args,
+            true,
+        )
+        .await
+        .unwrap();
+        assert!(approved);
+
+        match frames.try_recv().unwrap().payload {
+            ServerPayload::ToolApprovalRequest {
+                id,
+                name,
+                arguments,
+                summary,
+                risks,
+                can_remember,
+            } => {
+                assert_eq!(id, "call-1");
+                assert_eq!(name, "execute_command");
+                assert_eq!(summary, "Run shell commands");
+                assert!(arguments.contains("./deploy.sh"), "{arguments}");
+                assert!(!arguments.contains("hunter2"), "{arguments}");
+                assert!(arguments.contains("<redacted len=21>"), "{arguments}");
+                assert_eq!(
+                    risks,
+                    vec![ToolRisk::RunsCommands, ToolRisk::ControlsProcesses]
+                );
+                assert!(can_remember);
+            }
+            other => panic!("expected an approval request, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vault_values_are_masked() {
+        let (mut writer, mut frames) = ChannelWriter::new();
+        let (tx, approvals) = channel();
+        tx.send(reply("call-1", false, false)).await.unwrap();
+
+        let args = json!({ "name": "deploy_key", "value": "plain words" });
+        let approved = request_approval(
+            &mut writer,
+            &approvals,
+            "call-1",
+            "secrets_store",
+            &args,
+            true,
+        )
+        .await
+        .unwrap();
+        assert!(!approved);
+
+        let ServerPayload::ToolApprovalRequest {
+            arguments, risks, ..
+        } = frames.try_recv().unwrap().payload
+        else {
+            panic!("expected an approval request");
+        };
+        assert!(arguments.contains("deploy_key"), "{arguments}");
+        assert!(!arguments.contains("plain words"), "{arguments}");
+        assert_eq!(risks, vec![ToolRisk::Secrets]);
+    }
+
+    #[tokio::test]
+    async fn test_session_approval_skips_later_prompts() {
+        let (mut writer, mut frames) = ChannelWriter::new();
+        let (tx, approvals) = channel();
+        let args = json!({ "path": "notes.md", "content": "hi" });
+
+        tx.send(reply("call-1", true, true)).await.unwrap();
+        assert!(
+            request_approval(&mut writer, &approvals, "call-1", "write_file", &args, true)
+                .await
+                .unwrap()
+        );
+        assert!(frames.try_recv().is_ok());
+
+        // No reply queued: a second prompt would wait, so none is sent.
+        assert!(
+            request_approval(&mut writer, &approvals, "call-2", "write_file", &args, true)
+                .await
+                .unwrap()
+        );
+        assert!(frames.try_recv().is_err());
+
+        // Other tools still ask, and so does this one once the setting is off.
+        tx.send(reply("call-3", false, false)).await.unwrap();
+        assert!(
+            !request_approval(&mut writer, &approvals, "call-3", "edit_file", &args, true)
+                .await
+                .unwrap()
+        );
+        tx.send(reply("call-4", false, false)).await.unwrap();
+        assert!(
+            !request_approval(
+                &mut writer,
+                &approvals,
+                "call-4",
+                "write_file",
+                &args,
+                false
+            )
+            .await
+            .unwrap()
+        );
+        assert!(frames.try_recv().is_ok());
+        assert!(frames.try_recv().is_ok());
+        assert!(frames.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_remember_is_ignored_when_session_approval_is_off() {
+        let (mut writer, mut frames) = ChannelWriter::new();
+        let (tx, approvals) = channel();
+        let args = json!({ "command": "ls" });
+
+        tx.send(reply("call-1", true, true)).await.unwrap();
+        assert!(
+            request_approval(
+                &mut writer,
+                &approvals,
+                "call-1",
+                "execute_command",
+                &args,
+                false
+            )
+            .await
+            .unwrap()
+        );
+        tx.send(reply("call-2", false, false)).await.unwrap();
+        assert!(
+            !request_approval(
+                &mut writer,
+                &approvals,
+                "call-2",
+                "execute_command",
+                &args,
+                true
+            )
+            .await
+            .unwrap()
+        );
+        // Both calls prompted; the first didn't offer a session approval.
+        let ServerPayload::ToolApprovalRequest { can_remember, .. } =
+            frames.try_recv().unwrap().payload
+        else {
+            panic!("expected an approval request");
+        };
+        assert!(!can_remember);
+        assert!(frames.try_recv().is_ok());
+    }
+}
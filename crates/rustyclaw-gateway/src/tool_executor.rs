@@ -3,14 +3,18 @@
 //! This module provides a unified entry point for executing tools, handling
 //! the different tool types (user prompts, secrets, skills, standard tools).
 
+use futures_util::StreamExt;
 use rustyclaw_core::tools;
 use serde_json::Value;
 use std::collections::VecDeque;
+use std::future::Future;
 use std::path::Path;
 use std::sync::Mutex;
-use std::time::Instant;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
-use super::{SharedSkillManager, SharedVault};
+use super::{SharedSkillManager, SharedVault, ToolCancelFlag};
 use crate::secrets_handler;
 use crate::skills_handler;
 
@@ -23,8 +27,8 @@ use crate::skills_handler;
 /// runaway tool loops or abuse through repeated expensive calls.
 ///
 /// The limiter is **global** (all sessions share one instance) and uses
-/// a coarse-grained mutex — contention is negligible given tool calls
-/// are serialised by the model anyway.
+/// a coarse-grained mutex — contention is negligible, as at most
+/// `max_parallel_tools` calls run at once.
 pub struct ToolRateLimiter {
     window_ms: u64,
     max_calls: usize,
@@ -95,16 +99,40 @@ pub fn check_rate_limit(name: &str) -> Result<(), String> {
         .and_then(|mut limiter| limiter.check(name))
 }
 
+// ── Cancellation ────────────────────────────────────────────────────────────
+
+/// How often a running tool checks whether the user cancelled.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Resolve once `tool_cancel` is set.
+///
+/// The flag is flipped by the connection's reader task; polling it keeps
+/// the rest of the tool loop free of extra plumbing.
+pub async fn cancel_requested(tool_cancel: &ToolCancelFlag) {
+    while !tool_cancel.load(Ordering::Relaxed) {
+        tokio::time::sleep(CANCEL_POLL_INTERVAL).await;
+    }
+}
+
 /// Execute a tool by name, routing to the appropriate handler.
 ///
+/// Standard tools are stopped mid-flight when `tool_cancel` is set and
+/// report [`tools::TOOL_CANCELLED`] as an error result.
+///
 /// Returns `(output_text, is_error)`.
+#[tracing::instrument(name = "tool_call", skip_all, fields(tool = name))]
 pub async fn execute_tool_by_type(
     name: &str,
     arguments: &Value,
     workspace_dir: &Path,
     vault: &SharedVault,
     skill_mgr: &SharedSkillManager,
+    tool_cancel: &ToolCancelFlag,
 ) -> (String, bool) {
+    if tool_cancel.load(Ordering::Relaxed) {
+        return (tools::TOOL_CANCELLED.to_string(), true);
+    }
+
     // Apply rate limiting before executing any tool.
     if let Err(err) = check_rate_limit(name) {
         tracing::warn!(tool = name, "Rate limit hit");
@@ -122,13 +150,43 @@ pub async fn execute_tool_by_type(
             Err(err) => (err.to_string(), true),
         }
     } else {
-        match tools::execute_tool(name, arguments, workspace_dir).await {
+        let cancel = CancellationToken::new();
+        let run = tools::execute_tool_cancellable(name, arguments, workspace_dir, &cancel);
+        tokio::pin!(run);
+        let result = tokio::select! {
+            result = &mut run => result,
+            _ = cancel_requested(tool_cancel) => {
+                // Let the tool clean up (kill its child, drop its request)
+                // and report the cancellation itself.
+                cancel.cancel();
+                run.await
+            }
+        };
+        match result {
             Ok(text) => (text, false),
             Err(err) => (err, true),
         }
     }
 }
 
+// ── Concurrent execution ────────────────────────────────────────────────────
+
+/// Run `count` independent jobs, at most `max_parallel` at a time.
+///
+/// `run(i)` starts job `i`.  Results come back in index order however the
+/// jobs interleave, so each can be matched to its tool call; a job that
+/// fails only produces its own error result.
+pub async fn run_concurrently<T, F, Fut>(count: usize, max_parallel: usize, run: F) -> Vec<T>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = T>,
+{
+    futures_util::stream::iter((0..count).map(run))
+        .buffered(max_parallel.max(1))
+        .collect()
+        .await
+}
+
 /// Check if a short response suggests incomplete intent that should be continued.
 ///
 /// Returns true if the model appears to have stated intent without making a tool call.
@@ -248,4 +306,116 @@ mod tests {
             "The banner is rendered in banner.rs; here's the fix you asked for."
         ));
     }
+
+    #[tokio::test]
+    async fn test_run_concurrently_overlaps_jobs_and_keeps_order() {
+        use std::sync::atomic::AtomicUsize;
+
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let job = |i: usize| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                // Later jobs finish first.
+                tokio::time::sleep(Duration::from_millis(300 - 100 * i as u64)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i * 10
+            }
+        };
+
+        let started = Instant::now();
+        let results = run_concurrently(3, 4, job).await;
+        assert_eq!(results, [0, 10, 20]);
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert!(started.elapsed() < Duration::from_millis(550));
+
+        peak.store(0, Ordering::SeqCst);
+        assert_eq!(run_concurrently(3, 2, job).await, [0, 10, 20]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_tool_calls_aggregate_results_in_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "alpha").unwrap();
+        std::fs::write(tmp.path().join("b.txt"), "bravo").unwrap();
+        let vault: SharedVault = std::sync::Arc::new(tokio::sync::Mutex::new(
+            rustyclaw_core::secrets::SecretsManager::new(tmp.path().join("credentials")),
+        ));
+        let skill_mgr: SharedSkillManager = std::sync::Arc::new(tokio::sync::Mutex::new(
+            rustyclaw_core::skills::SkillManager::new(tmp.path().join("skills")),
+        ));
+        let tool_cancel = ToolCancelFlag::default();
+
+        // One model response asking for three reads and a write.
+        let response: Vec<rustyclaw_core::gateway::ParsedToolCall> =
+            serde_json::from_value(serde_json::json!([
+                { "id": "c1", "name": "read_file", "arguments": { "path": "a.txt" } },
+                { "id": "c2", "name": "read_file", "arguments": { "path": "missing.txt" } },
+                { "id": "c3", "name": "write_file",
+                  "arguments": { "path": "c.txt", "content": "charlie" } },
+                { "id": "c4", "name": "read_file", "arguments": { "path": "b.txt" } },
+            ]))
+            .unwrap();
+        let batch: Vec<_> = response
+            .iter()
+            .filter(|tc| tools::is_parallel_safe_tool(&tc.name))
+            .collect();
+        assert_eq!(batch.len(), 3, "write_file must stay sequential");
+
+        let results = run_concurrently(batch.len(), 4, |i| {
+            execute_tool_by_type(
+                &batch[i].name,
+                &batch[i].arguments,
+                tmp.path(),
+                &vault,
+                &skill_mgr,
+                &tool_cancel,
+            )
+        })
+        .await;
+
+        // A failed call yields its own error and doesn't disturb the rest.
+        let errors: Vec<bool> = results.iter().map(|(_, is_error)| *is_error).collect();
+        assert_eq!(errors, [false, true, false], "{:?}", results);
+        assert!(results[0].0.contains("alpha"));
+        assert!(results[2].0.contains("bravo"));
+        assert!(!tmp.path().join("c.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_flag_stops_running_command() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault: SharedVault = std::sync::Arc::new(tokio::sync::Mutex::new(
+            rustyclaw_core::secrets::SecretsManager::new(tmp.path().join("credentials")),
+        ));
+        let skill_mgr: SharedSkillManager = std::sync::Arc::new(tokio::sync::Mutex::new(
+            rustyclaw_core::skills::SkillManager::new(tmp.path().join("skills")),
+        ));
+        let tool_cancel: ToolCancelFlag =
+            std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let flag = tool_cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            flag.store(true, Ordering::Relaxed);
+        });
+
+        let started = Instant::now();
+        let args = serde_json::json!({ "command": "sleep 30", "yieldMs": 60000 });
+        let (output, is_error) = execute_tool_by_type(
+            "execute_command",
+            &args,
+            tmp.path(),
+            &vault,
+            &skill_mgr,
+            &tool_cancel,
+        )
+        .await;
+        assert!(is_error);
+        assert_eq!(output, tools::TOOL_CANCELLED);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
 }
@@ -0,0 +1,120 @@
+//! `translate` and `translate_file` tool execution for the gateway.
+//!
+//! The tools need the configured model, which only the gateway can reach,
+//! so they are intercepted here instead of going through `tools::execute_tool`.
+//! Chunking and code-fence handling live in `rustyclaw_core::tools::translate`,
+//! and file parsing in `rustyclaw_core::tools::translate_file`; this module
+//! only supplies the provider call.
+
+use serde_json::Value;
+use std::path::Path;
+use tracing::instrument;
+
+use rustyclaw_core::gateway::{ChatMessage, ProviderRequest};
+use rustyclaw_core::tools::{self, translate, translate_file};
+
+use crate::{ToolCancelFlag, providers, tool_executor};
+
+/// Per-request model timeout.
+const MODEL_TIMEOUT_SECS: u64 = 60;
+
+/// Check if a tool name is the translate tool.
+pub fn is_translate_tool(name: &str) -> bool {
+    name == "translate"
+}
+
+/// Execute a `translate` tool call against the conversation's model.
+///
+/// Setting `tool_cancel` drops the in-flight model request.
+#[instrument(skip(http, resolved, args, tool_cancel))]
+pub async fn execute_translate(
+    http: &reqwest::Client,
+    resolved: &ProviderRequest,
+    args: &Value,
+    tool_cancel: &ToolCancelFlag,
+) -> Result<String, String> {
+    tokio::select! {
+        result = translate_chunks(http, resolved, args) => result,
+        _ = tool_executor::cancel_requested(tool_cancel) => {
+            Err(tools::TOOL_CANCELLED.to_string())
+        }
+    }
+}
+
+/// Check if a tool name is the translate_file tool.
+pub fn is_translate_file_tool(name: &str) -> bool {
+    name == "translate_file"
+}
+
+/// Execute a `translate_file` tool call, one model request per batch of
+/// segments.
+///
+/// Setting `tool_cancel` drops the in-flight model request; nothing is
+/// written unless every batch completes.
+#[instrument(skip(http, resolved, args, workspace_dir, tool_cancel))]
+pub async fn execute_translate_file(
+    http: &reqwest::Client,
+    resolved: &ProviderRequest,
+    args: &Value,
+    workspace_dir: &Path,
+    tool_cancel: &ToolCancelFlag,
+) -> Result<String, String> {
+    let translate = translate_file::translate_file_with(args, workspace_dir, |prompt| {
+        ask_model(http, resolved, prompt, "Translation")
+    });
+    tokio::select! {
+        result = translate => result,
+        _ = tool_executor::cancel_requested(tool_cancel) => {
+            Err(tools::TOOL_CANCELLED.to_string())
+        }
+    }
+}
+
+async fn translate_chunks(
+    http: &reqwest::Client,
+    resolved: &ProviderRequest,
+    args: &Value,
+) -> Result<String, String> {
+    translate::translate_with(args, |prompt| {
+        ask_model(http, resolved, prompt, "Translation")
+    })
+    .await
+}
+
+/// Send `prompt` as a single-turn request, no tools — the same shape as
+/// compaction — and return the reply text.  `label` names the task in
+/// errors.
+pub(crate) async fn ask_model(
+    http: &reqwest::Client,
+    resolved: &ProviderRequest,
+    prompt: String,
+    label: &str,
+) -> Result<String, String> {
+    let req = ProviderRequest {
+        messages: vec![ChatMessage::text("user", &prompt)],
+        model: resolved.model.clone(),
+        provider: resolved.provider.clone(),
+        base_url: resolved.base_url.clone(),
+        api_key: resolved.api_key.clone(),
+        sampling: Default::default(),
+        cache_prompt: false,
+        thinking_budget: None,
+    };
+    let call = async {
+        if req.provider == "anthropic" {
+            providers::call_anthropic_with_tools(http, &req, None).await
+        } else if req.provider == "google" {
+            providers::call_google_with_tools(http, &req).await
+        } else {
+            providers::call_openai_with_tools(http, &req, None).await
+        }
+    };
+    let resp = tokio::time::timeout(std::time::Duration::from_secs(MODEL_TIMEOUT_SECS), call)
+        .await
+        .map_err(|_| format!("{} timed out after {}s", label, MODEL_TIMEOUT_SECS))?
+        .map_err(|e| format!("{} request failed: {:#}", label, e))?;
+    if resp.text.trim().is_empty() {
+        return Err(format!("{}: the model returned an empty reply", label));
+    }
+    Ok(resp.text)
+}
@@ -0,0 +1,121 @@
+//! Per-turn limits on the agentic tool loop.
+//!
+//! A turn may run at most `max_tool_iterations` model rounds and, when
+//! `max_turn_secs` is non-zero, for at most that long.  Either limit
+//! stops the loop between rounds with a
+//! [`GatewayError`](crate::errors::GatewayError) whose handler sends a
+//! `ToolLoopStopped` status, so the client can tell a cutoff apart from
+//! the model choosing to stop.
+
+use std::time::{Duration, Instant};
+
+use rustyclaw_core::config::Config;
+
+use crate::errors::GatewayError;
+
+/// Tool-round and wall-clock budget for one turn.
+#[derive(Debug, Clone)]
+pub(crate) struct TurnBudget {
+    max_rounds: usize,
+    max_duration: Option<Duration>,
+    started: Instant,
+    rounds: usize,
+}
+
+impl TurnBudget {
+    /// Start a budget now.  `max_rounds` is at least 1; `max_secs = 0`
+    /// means no time limit.
+    pub(crate) fn new(max_rounds: usize, max_secs: u64) -> Self {
+        Self {
+            max_rounds: max_rounds.max(1),
+            max_duration: (max_secs > 0).then(|| Duration::from_secs(max_secs)),
+            started: Instant::now(),
+            rounds: 0,
+        }
+    }
+
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self::new(config.max_tool_iterations, config.max_turn_secs)
+    }
+
+    /// Leave time the agent spent paused out of the time limit.
+    pub(crate) fn add_pause(&mut self, held: Duration) {
+        self.started += held;
+    }
+
+    /// Claim the next round, or say why the loop has to stop.
+    pub(crate) fn start_round(&mut self) -> Result<usize, GatewayError> {
+        if self.rounds >= self.max_rounds {
+            return Err(GatewayError::ToolLoopExhausted {
+                rounds: self.rounds,
+            });
+        }
+        if let Some(limit) = self.max_duration
+            && self.started.elapsed() >= limit
+        {
+            return Err(GatewayError::TurnTimeExhausted {
+                secs: limit.as_secs(),
+                rounds: self.rounds,
+            });
+        }
+        self.rounds += 1;
+        Ok(self.rounds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a tool loop whose model never stops asking for tools, the way
+    /// `dispatch_text_message` drives its budget.
+    fn run_endless_loop(budget: &mut TurnBudget) -> (usize, GatewayError) {
+        let mut model_calls = 0;
+        loop {
+            match budget.start_round() {
+                Ok(_) => model_calls += 1,
+                Err(stop) => return (model_calls, stop),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tool_loop_stops_at_iteration_cap() {
+        let mut budget = TurnBudget::new(7, 0);
+        let (calls, stop) = run_endless_loop(&mut budget);
+        assert_eq!(calls, 7);
+        assert!(matches!(
+            stop,
+            GatewayError::ToolLoopExhausted { rounds: 7 }
+        ));
+        assert!(stop.to_string().contains("after 7 tool rounds"));
+
+        // The cap can't be configured away entirely.
+        let (calls, _) = run_endless_loop(&mut TurnBudget::new(0, 0));
+        assert_eq!(calls, 1);
+
+        let config = Config {
+            max_tool_iterations: 3,
+            ..Config::default()
+        };
+        let (calls, _) = run_endless_loop(&mut TurnBudget::from_config(&config));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_tool_loop_stops_when_turn_runs_out_of_time() {
+        let mut budget = TurnBudget::new(100, 60);
+        assert_eq!(budget.start_round().unwrap(), 1);
+        assert_eq!(budget.start_round().unwrap(), 2);
+        budget.started -= Duration::from_secs(61);
+        let stop = budget.start_round().unwrap_err();
+        assert!(matches!(
+            stop,
+            GatewayError::TurnTimeExhausted {
+                secs: 60,
+                rounds: 2
+            }
+        ));
+        assert!(stop.to_string().contains("60s"));
+    }
+}
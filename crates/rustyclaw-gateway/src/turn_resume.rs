@@ -0,0 +1,522 @@
+//! Resuming a chat turn after its connection drops.
+//!
+//! A client that announces `resume_turns` is told, with a `TurnStarted`
+//! frame, the session and turn id of each chat turn it starts.  While the
+//! turn runs, the reply frames sent for it (the same activity frames
+//! observers see) are kept here, one turn per session.  If the connection
+//! drops, the turn keeps running and recording instead of failing on its
+//! next write, and a client that reconnects within [`RESUME_WINDOW`] can
+//! send `ResumeTurn` to get everything produced so far followed by the rest
+//! of the turn as it is generated.
+//!
+//! Turn ids are random, so the id from `TurnStarted` is what lets a client
+//! resume: another connection can't guess it from its own turns.
+//!
+//! A buffer is discarded as soon as a client has received the whole turn,
+//! or [`RESUME_WINDOW`] after the last client stopped receiving it.  A turn
+//! whose frames pass [`MAX_TURN_BYTES`] stops being buffered and can only
+//! be asked again.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::Notify;
+use tracing::debug;
+
+use rustyclaw_core::gateway::protocol::capabilities::feature;
+use rustyclaw_core::gateway::{
+    Capabilities, ErrorCode, NegotiatedWriter, ScopedTransportWriter, ServerFrame, TransportWriter,
+    protocol, serialize_frame,
+};
+
+use crate::session_mirror;
+
+/// How long a turn stays resumable once no client is receiving it.
+pub(crate) const RESUME_WINDOW: Duration = Duration::from_secs(120);
+
+/// Serialized frames kept for one turn.
+pub(crate) const MAX_TURN_BYTES: usize = 1024 * 1024;
+
+/// A session and one turn in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TurnKey {
+    pub session_id: u64,
+    pub turn_id: u64,
+}
+
+/// The recorded part of a turn.
+struct BufferedTurn {
+    turn_id: u64,
+    frames: Vec<ServerFrame>,
+    bytes: usize,
+    /// Passed [`MAX_TURN_BYTES`]; `frames` has been dropped.
+    overflowed: bool,
+    finished: bool,
+    /// A resuming client is receiving the turn.
+    following: bool,
+    /// When the buffer may be discarded, once no client is receiving it.
+    expires: Option<Instant>,
+    /// Woken for every change a follower needs to see.
+    changed: Arc<Notify>,
+}
+
+/// Buffered turns by session id.
+fn turns() -> MutexGuard<'static, HashMap<u64, BufferedTurn>> {
+    static TURNS: OnceLock<Mutex<HashMap<u64, BufferedTurn>>> = OnceLock::new();
+    TURNS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Run `f` on the buffer for `key`, if it is still there.
+fn with_turn<T>(key: TurnKey, f: impl FnOnce(&mut BufferedTurn) -> T) -> Option<T> {
+    let mut turns = turns();
+    turns
+        .get_mut(&key.session_id)
+        .filter(|turn| turn.turn_id == key.turn_id)
+        .map(f)
+}
+
+/// Drop every buffer whose window has passed.
+fn purge_expired() {
+    let now = Instant::now();
+    turns().retain(|_, turn| turn.following || turn.expires.is_none_or(|at| at > now));
+}
+
+/// Start the resume window for `turn` and purge once it has passed.
+fn start_window(turn: &mut BufferedTurn) {
+    turn.expires = Some(Instant::now() + RESUME_WINDOW);
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(async {
+            tokio::time::sleep(RESUME_WINDOW).await;
+            purge_expired();
+        });
+    }
+}
+
+/// Start buffering a new turn in `session_id`, replacing any earlier one.
+fn begin(session_id: u64) -> TurnKey {
+    purge_expired();
+    let key = TurnKey {
+        session_id,
+        turn_id: rand::random(),
+    };
+    turns().insert(
+        session_id,
+        BufferedTurn {
+            turn_id: key.turn_id,
+            frames: Vec::new(),
+            bytes: 0,
+            overflowed: false,
+            finished: false,
+            following: false,
+            expires: None,
+            changed: Arc::new(Notify::new()),
+        },
+    );
+    key
+}
+
+fn record(key: TurnKey, frame: &ServerFrame) {
+    let size = serialize_frame(frame).map(|bytes| bytes.len()).unwrap_or(0);
+    with_turn(key, |turn| {
+        if turn.overflowed {
+            return;
+        }
+        if turn.bytes + size > MAX_TURN_BYTES {
+            debug!(?key, "Turn too long to buffer for resuming");
+            turn.overflowed = true;
+            turn.frames = Vec::new();
+        } else {
+            turn.bytes += size;
+            turn.frames.push(frame.clone());
+        }
+        turn.changed.notify_waiters();
+    });
+}
+
+/// Writer adapter that buffers a turn for [`handle_resume_turn`].
+///
+/// Wraps the connection's writer below the [`NegotiatedWriter`], so what is
+/// buffered is exactly what the client was sent.  Once a send fails the
+/// client is taken to be gone: later frames are only buffered, and the turn
+/// runs to the end instead of failing with the connection.
+///
+/// Without `resume_turns` (or a session to key the turn by) it passes
+/// frames straight through.  Dropping the recorder ends the turn.
+pub(crate) struct TurnRecorder<'a> {
+    inner: &'a mut dyn TransportWriter,
+    turn: Option<TurnKey>,
+    detached: bool,
+}
+
+impl<'a> TurnRecorder<'a> {
+    pub(crate) fn new(
+        inner: &'a mut dyn TransportWriter,
+        session_id: Option<u64>,
+        capabilities: &Capabilities,
+    ) -> Self {
+        let turn = session_id
+            .filter(|_| capabilities.supports(feature::RESUME_TURNS))
+            .map(begin);
+        Self {
+            inner,
+            turn,
+            detached: false,
+        }
+    }
+
+    /// The turn being buffered, if any.
+    pub(crate) fn turn(&self) -> Option<TurnKey> {
+        self.turn
+    }
+}
+
+#[async_trait]
+impl TransportWriter for TurnRecorder<'_> {
+    async fn send_on_stream(&mut self, stream_id: u64, frame: &ServerFrame) -> Result<()> {
+        let Some(key) = self.turn else {
+            return self.inner.send_on_stream(stream_id, frame).await;
+        };
+        if session_mirror::is_mirrored(frame.frame_type) {
+            record(key, frame);
+        }
+        if self.detached {
+            return Ok(());
+        }
+        if let Err(e) = self.inner.send_on_stream(stream_id, frame).await {
+            debug!(?key, error = %e, "Client gone mid-turn; buffering the rest");
+            self.detached = true;
+            with_turn(key, |turn| {
+                if !turn.following {
+                    start_window(turn);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await
+    }
+}
+
+impl Drop for TurnRecorder<'_> {
+    fn drop(&mut self) {
+        let Some(key) = self.turn else { return };
+        let mut turns = turns();
+        let Some(turn) = turns
+            .get_mut(&key.session_id)
+            .filter(|turn| turn.turn_id == key.turn_id)
+        else {
+            return;
+        };
+        if !self.detached && !turn.following {
+            // The client saw the whole turn; nothing to resume.
+            turns.remove(&key.session_id);
+            return;
+        }
+        turn.finished = true;
+        if !turn.following {
+            start_window(turn);
+        }
+        turn.changed.notify_waiters();
+    }
+}
+
+/// Handle a `ResumeTurn` request: replay the buffered part of the turn on
+/// `stream_id`, then send the rest as it arrives until the turn ends.
+///
+/// A turn that finished with its client connected, expired, or outgrew
+/// its buffer is answered with a `NotFound` error.
+pub(crate) async fn handle_resume_turn(
+    writer: &mut dyn TransportWriter,
+    capabilities: &Capabilities,
+    stream_id: u64,
+    session_id: u64,
+    turn_id: u64,
+) -> Result<()> {
+    let mut scoped = ScopedTransportWriter::new(writer, stream_id);
+    let mut negotiated = NegotiatedWriter::new(&mut scoped, capabilities);
+    let key = TurnKey {
+        session_id,
+        turn_id,
+    };
+
+    purge_expired();
+    let changed = with_turn(key, |turn| {
+        if turn.overflowed {
+            return None;
+        }
+        turn.following = true;
+        turn.expires = None;
+        Some(turn.changed.clone())
+    })
+    .flatten();
+    let Some(changed) = changed else {
+        let message = format!(
+            "Turn {turn_id} of session {session_id} can no longer be resumed; send the \
+             message again."
+        );
+        protocol::server::send_error(&mut negotiated, ErrorCode::NotFound, &message).await?;
+        return Ok(());
+    };
+
+    debug!(?key, "Resuming turn");
+    let followed = follow(&mut negotiated, key, &changed).await;
+    if !matches!(followed, Ok(true)) {
+        // This client is gone too, or the turn stalled; keep what there is
+        // for another attempt.
+        with_turn(key, |turn| {
+            turn.following = false;
+            start_window(turn);
+        });
+    }
+    if !followed? {
+        let message = format!("Turn {turn_id} stopped producing output.");
+        protocol::server::send_error(&mut negotiated, ErrorCode::Timeout, &message).await?;
+    }
+    negotiated.flush().await
+}
+
+/// Send the turn's frames from the start, waiting for new ones, until it
+/// finishes.  Returns `false` if nothing new arrives for a whole
+/// [`RESUME_WINDOW`] or the buffer overflows.
+async fn follow(writer: &mut dyn TransportWriter, key: TurnKey, changed: &Notify) -> Result<bool> {
+    protocol::server::send_turn_started(writer, key.session_id, key.turn_id, true).await?;
+    let mut sent = 0;
+    loop {
+        // Register for the next change before looking, so none is missed.
+        let notified = changed.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+
+        let Some((frames, finished, overflowed)) = with_turn(key, |turn| {
+            (
+                turn.frames.get(sent..).unwrap_or_default().to_vec(),
+                turn.finished,
+                turn.overflowed,
+            )
+        }) else {
+            return Ok(false);
+        };
+        if overflowed {
+            return Ok(false);
+        }
+        for frame in &frames {
+            protocol::server::send_frame(writer, frame).await?;
+        }
+        sent += frames.len();
+        if finished {
+            turns().remove(&key.session_id);
+            return Ok(true);
+        }
+        if tokio::time::timeout(RESUME_WINDOW, notified).await.is_err() {
+            return Ok(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::gateway::{ServerFrameType, ServerPayload};
+
+    /// Collects frames, failing every send after the first `limit`.
+    struct FlakyWriter {
+        frames: Vec<ServerFrame>,
+        limit: usize,
+    }
+
+    impl FlakyWriter {
+        fn new(limit: usize) -> Self {
+            Self {
+                frames: Vec::new(),
+                limit,
+            }
+        }
+
+        /// The streamed text, and whether the turn was seen to complete.
+        fn reply(&self) -> (String, bool) {
+            let mut text = String::new();
+            let mut done = false;
+            for frame in &self.frames {
+                match &frame.payload {
+                    ServerPayload::Chunk { delta } => text.push_str(delta),
+                    ServerPayload::ResponseDone { .. } => done = true,
+                    _ => {}
+                }
+            }
+            (text, done)
+        }
+    }
+
+    #[async_trait]
+    impl TransportWriter for FlakyWriter {
+        async fn send_on_stream(&mut self, _stream_id: u64, frame: &ServerFrame) -> Result<()> {
+            if self.frames.len() >= self.limit {
+                anyhow::bail!("connection reset");
+            }
+            self.frames.push(frame.clone());
+            Ok(())
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn send_chunk(writer: &mut dyn TransportWriter, delta: &str) {
+        let frame = ServerFrame {
+            frame_type: ServerFrameType::Chunk,
+            payload: ServerPayload::Chunk {
+                delta: delta.into(),
+            },
+        };
+        protocol::server::send_frame(writer, &frame).await.unwrap();
+    }
+
+    fn error_code(frames: &[ServerFrame]) -> Option<ErrorCode> {
+        frames.iter().find_map(|frame| match &frame.payload {
+            ServerPayload::Error { code, .. } => Some(*code),
+            _ => None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_resume_replays_turn_cut_off_by_disconnect() {
+        let caps = Capabilities::gateway();
+        let session_id = 9_001;
+
+        // The connection dies after the stream start and the first chunk.
+        let mut dropped = FlakyWriter::new(2);
+        let key = {
+            let mut writer = TurnRecorder::new(&mut dropped, Some(session_id), &caps);
+            let key = writer.turn().unwrap();
+            protocol::server::send_stream_start(&mut writer)
+                .await
+                .unwrap();
+            send_chunk(&mut writer, "The answer ").await;
+            // Every send from here fails underneath, but the turn goes on.
+            send_chunk(&mut writer, "is ").await;
+            send_chunk(&mut writer, "42.").await;
+            protocol::server::send_response_done(&mut writer, true)
+                .await
+                .unwrap();
+            key
+        };
+        assert_eq!(dropped.reply(), ("The answer ".to_string(), false));
+
+        let mut resumed = FlakyWriter::new(usize::MAX);
+        handle_resume_turn(&mut resumed, &caps, 1, session_id, key.turn_id)
+            .await
+            .unwrap();
+        assert!(matches!(
+            resumed.frames[0].payload,
+            ServerPayload::TurnStarted { resumed: true, turn_id, .. } if turn_id == key.turn_id
+        ));
+        assert_eq!(resumed.reply(), ("The answer is 42.".to_string(), true));
+
+        // The whole turn was delivered, so the buffer is gone.
+        let mut again = FlakyWriter::new(usize::MAX);
+        handle_resume_turn(&mut again, &caps, 1, session_id, key.turn_id)
+            .await
+            .unwrap();
+        assert_eq!(error_code(&again.frames), Some(ErrorCode::NotFound));
+    }
+
+    #[tokio::test]
+    async fn test_resume_follows_turn_still_running() {
+        let caps = Capabilities::gateway();
+        let session_id = 9_002;
+
+        let mut dropped = FlakyWriter::new(1);
+        let mut writer = TurnRecorder::new(&mut dropped, Some(session_id), &caps);
+        let key = writer.turn().unwrap();
+        protocol::server::send_stream_start(&mut writer)
+            .await
+            .unwrap();
+        send_chunk(&mut writer, "partial ").await;
+
+        let mut resumed = FlakyWriter::new(usize::MAX);
+        let resume = handle_resume_turn(&mut resumed, &caps, 1, session_id, key.turn_id);
+        let rest = async move {
+            tokio::task::yield_now().await;
+            send_chunk(&mut writer, "and live").await;
+            protocol::server::send_response_done(&mut writer, true)
+                .await
+                .unwrap();
+        };
+        let (result, ()) = tokio::join!(resume, rest);
+        result.unwrap();
+        assert_eq!(resumed.reply(), ("partial and live".to_string(), true));
+    }
+
+    #[tokio::test]
+    async fn test_other_connection_cannot_resume_without_turn_id() {
+        let caps = Capabilities::gateway();
+        let session_id = 9_004;
+
+        let mut dropped = FlakyWriter::new(0);
+        let key = {
+            let mut writer = TurnRecorder::new(&mut dropped, Some(session_id), &caps);
+            send_chunk(&mut writer, "private").await;
+            writer.turn().unwrap()
+        };
+
+        // Another connection's own turn tells it nothing about this one.
+        let mut other = FlakyWriter::new(usize::MAX);
+        let other_key = TurnRecorder::new(&mut other, Some(9_005), &caps)
+            .turn()
+            .unwrap();
+        for guess in [
+            other_key.turn_id.wrapping_add(1),
+            key.turn_id.wrapping_add(1),
+        ] {
+            let mut intruder = FlakyWriter::new(usize::MAX);
+            handle_resume_turn(&mut intruder, &caps, 1, session_id, guess)
+                .await
+                .unwrap();
+            assert_eq!(error_code(&intruder.frames), Some(ErrorCode::NotFound));
+            assert_eq!(intruder.reply(), (String::new(), false));
+        }
+
+        // The client that was told the id still can.
+        let mut owner = FlakyWriter::new(usize::MAX);
+        handle_resume_turn(&mut owner, &caps, 1, session_id, key.turn_id)
+            .await
+            .unwrap();
+        assert_eq!(owner.reply().0, "private");
+    }
+
+    #[tokio::test]
+    async fn test_completed_turn_is_not_kept() {
+        let session_id = 9_003;
+
+        // Without the capability nothing is buffered.
+        let mut client = FlakyWriter::new(usize::MAX);
+        let legacy = Capabilities::legacy();
+        assert!(
+            TurnRecorder::new(&mut client, Some(session_id), &legacy)
+                .turn()
+                .is_none()
+        );
+
+        // A turn the client received in full is discarded when it ends.
+        let caps = Capabilities::gateway();
+        let key = {
+            let mut writer = TurnRecorder::new(&mut client, Some(session_id), &caps);
+            send_chunk(&mut writer, "all of it").await;
+            writer.turn().unwrap()
+        };
+        let mut late = FlakyWriter::new(usize::MAX);
+        handle_resume_turn(&mut late, &caps, 1, session_id, key.turn_id)
+            .await
+            .unwrap();
+        assert_eq!(error_code(&late.frames), Some(ErrorCode::NotFound));
+    }
+}
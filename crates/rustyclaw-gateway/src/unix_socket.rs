@@ -0,0 +1,363 @@
+//! Unix domain socket transport.
+//!
+//! With `--unix-socket <path>` (or `gateway_socket` in the config) the
+//! gateway also accepts connections on a socket file, for clients on the
+//! same host:
+//!
+//! ```bash
+//! rustyclaw-gateway run --unix-socket ~/.rustyclaw/gateway.sock
+//! rustyclaw-tui --url unix:$HOME/.rustyclaw/gateway.sock
+//! ```
+//!
+//! Frames are length-prefixed exactly as on an SSH channel, but there is no
+//! SSH layer and no TCP port: who may connect is decided by the socket's
+//! file mode, which is `0600`.  The socket is bound under a temporary name
+//! and only moved into place once that mode is set, so it is never
+//! reachable at its advertised path with looser permissions.  TOTP, when
+//! enabled, still applies; such peers count as loopback for rate limiting.
+
+#[cfg(unix)]
+pub use imp::*;
+
+#[cfg(not(unix))]
+pub use stub::*;
+
+#[cfg(unix)]
+mod imp {
+    use std::net::{Ipv4Addr, SocketAddr};
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use std::path::{Path, PathBuf};
+
+    use anyhow::{Context, Result};
+    use async_trait::async_trait;
+    use rustyclaw_core::gateway::protocol::{ClientFrame, ServerFrame, WireFrame};
+    use rustyclaw_core::gateway::transport::{
+        PeerInfo, Transport, TransportReader, TransportType, TransportWriter,
+    };
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::net::UnixListener;
+    use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+    use tracing::debug;
+
+    use crate::ssh::{MAX_FRAME_SIZE, decode_client_wire_frame, encode_server_wire_frame};
+
+    /// File mode of the socket: read/write for the owner only.
+    pub const SOCKET_MODE: u32 = 0o600;
+
+    /// A gateway listener on a Unix domain socket.  The socket file is
+    /// removed when the listener is dropped.
+    pub struct UnixSocketListener {
+        listener: UnixListener,
+        path: PathBuf,
+    }
+
+    impl UnixSocketListener {
+        /// Bind `path`, replacing a socket left behind by a gateway that is
+        /// no longer running.
+        pub fn bind(path: &Path) -> Result<Self> {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty())
+                && !parent.exists()
+            {
+                std::fs::DirBuilder::new()
+                    .recursive(true)
+                    .mode(0o700)
+                    .create(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            remove_stale_socket(path)?;
+
+            let mut staging = path.as_os_str().to_owned();
+            staging.push(format!(".{}.tmp", std::process::id()));
+            let staging = PathBuf::from(staging);
+            let _ = std::fs::remove_file(&staging);
+
+            let listener = UnixListener::bind(&staging)
+                .with_context(|| format!("Failed to bind Unix socket {}", path.display()))?;
+            let placed =
+                std::fs::set_permissions(&staging, std::fs::Permissions::from_mode(SOCKET_MODE))
+                    .and_then(|()| std::fs::rename(&staging, path));
+            if let Err(e) = placed {
+                let _ = std::fs::remove_file(&staging);
+                return Err(e)
+                    .with_context(|| format!("Failed to set up Unix socket {}", path.display()));
+            }
+
+            Ok(Self {
+                listener,
+                path: path.to_path_buf(),
+            })
+        }
+
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        /// Accept the next connection.
+        pub async fn accept(&self) -> Result<Box<dyn Transport>> {
+            let (stream, _) = self.listener.accept().await?;
+            if let Ok(cred) = stream.peer_cred() {
+                debug!(uid = cred.uid(), pid = ?cred.pid(), "Unix socket peer");
+            }
+            let (read, write) = stream.into_split();
+            Ok(Box::new(UnixSocketTransport {
+                reader: UnixSocketReader {
+                    read,
+                    recv_buffer: Vec::new(),
+                    peer_info: unix_peer_info(),
+                },
+                writer: UnixSocketWriter { write },
+            }))
+        }
+    }
+
+    impl Drop for UnixSocketListener {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    /// Accept from `listener`, or never when there is none.
+    pub async fn accept_next(listener: Option<&UnixSocketListener>) -> Result<Box<dyn Transport>> {
+        match listener {
+            Some(listener) => listener.accept().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Clear `path` for binding: refuse if a gateway is still answering
+    /// there or it is not a socket, otherwise remove it.
+    fn remove_stale_socket(path: &Path) -> Result<()> {
+        let Ok(meta) = std::fs::symlink_metadata(path) else {
+            return Ok(());
+        };
+        if !meta.file_type().is_socket() {
+            anyhow::bail!("{} exists and is not a socket", path.display());
+        }
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            anyhow::bail!("Another gateway is already listening on {}", path.display());
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale socket {}", path.display()))
+    }
+
+    fn unix_peer_info() -> PeerInfo {
+        PeerInfo {
+            // Only local processes can reach the socket; TOTP rate limiting
+            // needs an address, so they all count as loopback.
+            addr: Some(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))),
+            username: None,
+            key_fingerprint: None,
+            transport_type: TransportType::Unix,
+        }
+    }
+
+    struct UnixSocketTransport {
+        reader: UnixSocketReader,
+        writer: UnixSocketWriter,
+    }
+
+    #[async_trait]
+    impl Transport for UnixSocketTransport {
+        fn peer_info(&self) -> &PeerInfo {
+            &self.reader.peer_info
+        }
+
+        async fn recv(&mut self) -> Result<Option<WireFrame<ClientFrame>>> {
+            self.reader.recv().await
+        }
+
+        async fn send_on_stream(&mut self, stream_id: u64, frame: &ServerFrame) -> Result<()> {
+            self.writer.send_on_stream(stream_id, frame).await
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            self.writer.close().await
+        }
+
+        fn into_split(self: Box<Self>) -> (Box<dyn TransportReader>, Box<dyn TransportWriter>) {
+            (Box::new(self.reader), Box::new(self.writer))
+        }
+    }
+
+    struct UnixSocketReader {
+        read: OwnedReadHalf,
+        recv_buffer: Vec<u8>,
+        peer_info: PeerInfo,
+    }
+
+    #[async_trait]
+    impl TransportReader for UnixSocketReader {
+        async fn recv(&mut self) -> Result<Option<WireFrame<ClientFrame>>> {
+            read_frame(&mut self.read, &mut self.recv_buffer).await
+        }
+
+        fn peer_info(&self) -> &PeerInfo {
+            &self.peer_info
+        }
+    }
+
+    struct UnixSocketWriter {
+        write: OwnedWriteHalf,
+    }
+
+    #[async_trait]
+    impl TransportWriter for UnixSocketWriter {
+        async fn send_on_stream(&mut self, stream_id: u64, frame: &ServerFrame) -> Result<()> {
+            write_frame(&mut self.write, stream_id, frame).await
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            self.write.shutdown().await?;
+            Ok(())
+        }
+    }
+
+    /// Read one length-prefixed frame.  Partial reads stay in `buffer`, so
+    /// a cancelled call loses nothing.
+    async fn read_frame(
+        read: &mut (impl AsyncRead + Unpin),
+        buffer: &mut Vec<u8>,
+    ) -> Result<Option<WireFrame<ClientFrame>>> {
+        loop {
+            if let Some(prefix) = buffer.first_chunk::<4>() {
+                let len = u32::from_be_bytes(*prefix) as usize;
+                if len > MAX_FRAME_SIZE as usize {
+                    anyhow::bail!("Frame too large: {} bytes", len);
+                }
+                if buffer.len() >= 4 + len {
+                    let frame_data: Vec<u8> = buffer.drain(..4 + len).skip(4).collect();
+                    return decode_client_wire_frame(&frame_data).map(Some);
+                }
+            }
+
+            let mut buf = [0u8; 8192];
+            let n = read.read(&mut buf).await?;
+            if n == 0 {
+                return Ok(None);
+            }
+            buffer.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    async fn write_frame(
+        write: &mut (impl AsyncWrite + Unpin),
+        stream_id: u64,
+        frame: &ServerFrame,
+    ) -> Result<()> {
+        let data = encode_server_wire_frame(stream_id, frame)?;
+        write.write_all(&(data.len() as u32).to_be_bytes()).await?;
+        write.write_all(&data).await?;
+        write.flush().await?;
+        Ok(())
+    }
+}
+
+#[cfg(not(unix))]
+mod stub {
+    use std::path::Path;
+
+    use anyhow::Result;
+    use rustyclaw_core::gateway::transport::Transport;
+
+    pub struct UnixSocketListener;
+
+    impl UnixSocketListener {
+        pub fn bind(path: &Path) -> Result<Self> {
+            anyhow::bail!(
+                "Cannot listen on {}: Unix sockets are not supported on this platform",
+                path.display()
+            )
+        }
+
+        pub fn path(&self) -> &Path {
+            Path::new("")
+        }
+    }
+
+    pub async fn accept_next(_listener: Option<&UnixSocketListener>) -> Result<Box<dyn Transport>> {
+        std::future::pending().await
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    use rustyclaw_core::gateway::protocol::{
+        ClientFrame, ClientFrameType, ClientPayload, ServerFrame, ServerFrameType, ServerPayload,
+    };
+    use rustyclaw_core::gateway::{SshConnection, TransportType};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_client_connects_over_unix_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gateway.sock");
+        let listener = UnixSocketListener::bind(&path).unwrap();
+
+        let url = format!("unix:{}", path.display());
+        let (_conn, mut writer, mut reader) = SshConnection::connect(&url).await.unwrap();
+        let mut transport = listener.accept().await.unwrap();
+        assert_eq!(transport.peer_info().transport_type, TransportType::Unix);
+
+        let reload = ClientFrame {
+            frame_type: ClientFrameType::Reload,
+            payload: ClientPayload::Reload,
+        };
+        writer.send_frame(3, &reload).await.unwrap();
+        let received = transport.recv().await.unwrap().unwrap();
+        assert_eq!(received.stream_id, 3);
+        assert_eq!(received.sequence, 1);
+        assert!(matches!(received.frame.payload, ClientPayload::Reload));
+
+        let start = ServerFrame {
+            frame_type: ServerFrameType::StreamStart,
+            payload: ServerPayload::StreamStart,
+        };
+        transport.send_on_stream(3, &start).await.unwrap();
+        let reply = reader.recv_wire().await.unwrap().unwrap();
+        assert_eq!(reply.stream_id, 3);
+        assert!(matches!(reply.frame.payload, ServerPayload::StreamStart));
+
+        transport.close().await.unwrap();
+        assert!(reader.recv_wire().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_socket_is_owner_only_and_removed_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run").join("gateway.sock");
+        let listener = UnixSocketListener::bind(&path).unwrap();
+
+        let meta = std::fs::symlink_metadata(&path).unwrap();
+        assert!(meta.file_type().is_socket());
+        assert_eq!(meta.permissions().mode() & 0o777, SOCKET_MODE);
+        let parent = std::fs::metadata(path.parent().unwrap()).unwrap();
+        assert_eq!(parent.permissions().mode() & 0o777, 0o700);
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+
+        drop(listener);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_bind_replaces_stale_socket_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gateway.sock");
+
+        // A socket nobody listens on any more.
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        let listener = UnixSocketListener::bind(&path).unwrap();
+        let err = UnixSocketListener::bind(&path).err().unwrap();
+        assert!(err.to_string().contains("already listening"), "{}", err);
+        drop(listener);
+
+        std::fs::write(&path, "not a socket").unwrap();
+        assert!(UnixSocketListener::bind(&path).is_err());
+        assert!(path.exists());
+    }
+}
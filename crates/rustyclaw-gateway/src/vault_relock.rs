@@ -0,0 +1,245 @@
+//! Recovery from a vault that is locked again while a session is live.
+//!
+//! A turn that needs the vault — a `secrets_*` tool call, or a provider
+//! key that has to be read from it — checks it first with
+//! [`wait_for_unlock`].  If the vault is locked, the client gets the same
+//! `VaultLocked` status it gets on connect, and the turn waits for the
+//! password instead of failing the call.
+//!
+//! The turn itself is running in the connection's main loop, so the
+//! client's `UnlockVault` frame would queue behind it.  While a turn waits,
+//! the reader task hands that frame to the [`UnlockRelay`] instead.  An
+//! unlock from another connection also ends the wait.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::{Mutex, mpsc};
+use tracing::{info, warn};
+
+use rustyclaw_core::gateway::protocol;
+use rustyclaw_core::gateway::transport::TransportWriter;
+use rustyclaw_core::gateway::{ClientPayload, StatusType};
+
+use crate::tool_executor::cancel_requested;
+use crate::{SharedVault, ToolCancelFlag, secrets_handler};
+
+/// How long a turn waits for the vault before giving up.
+const UNLOCK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often the vault is re-checked for an unlock by another connection.
+const RELOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Passwords from `UnlockVault` frames, passed from the reader task to a
+/// turn that is waiting for the vault.
+pub(crate) struct UnlockRelay {
+    connection_id: u64,
+    waiting: AtomicBool,
+    tx: mpsc::Sender<String>,
+    rx: Mutex<mpsc::Receiver<String>>,
+}
+
+impl UnlockRelay {
+    pub(crate) fn new(connection_id: u64) -> Self {
+        let (tx, rx) = mpsc::channel(4);
+        Self {
+            connection_id,
+            waiting: AtomicBool::new(false),
+            tx,
+            rx: Mutex::new(rx),
+        }
+    }
+
+    /// Whether a turn is waiting for an unlock.  Only then does the reader
+    /// task divert `UnlockVault` frames here.
+    pub(crate) fn is_waiting(&self) -> bool {
+        self.waiting.load(Ordering::Relaxed)
+    }
+
+    /// Hand a password to the waiting turn.
+    pub(crate) async fn deliver(&self, password: String) {
+        let _ = self.tx.send(password).await;
+    }
+}
+
+/// Hold the current operation until the vault is unlocked.
+///
+/// Returns `Ok(true)` straight away when the vault isn't locked.
+/// Otherwise the client is sent a `VaultLocked` status naming `purpose`,
+/// and each password it sends is tried and answered like an unlock on
+/// connect, until one works, another connection unlocks the vault, the
+/// turn is cancelled, or [`UNLOCK_TIMEOUT`] passes.  `Ok(false)` means the
+/// vault is still locked; the caller carries on and the operation fails
+/// as it would have.
+pub(crate) async fn wait_for_unlock(
+    writer: &mut dyn TransportWriter,
+    vault: &SharedVault,
+    relay: &UnlockRelay,
+    tool_cancel: &ToolCancelFlag,
+    purpose: &str,
+) -> Result<bool> {
+    if !vault.lock().await.is_locked() {
+        return Ok(true);
+    }
+    // Divert before prompting, so the answer can't slip into the queue.
+    relay.waiting.store(true, Ordering::Relaxed);
+    let result = wait(writer, vault, relay, tool_cancel, purpose).await;
+    relay.waiting.store(false, Ordering::Relaxed);
+    result
+}
+
+async fn wait(
+    writer: &mut dyn TransportWriter,
+    vault: &SharedVault,
+    relay: &UnlockRelay,
+    tool_cancel: &ToolCancelFlag,
+    purpose: &str,
+) -> Result<bool> {
+    let connection_id = relay.connection_id;
+    info!(connection_id, purpose, "Waiting for vault unlock");
+    protocol::server::send_status(
+        writer,
+        StatusType::VaultLocked,
+        &format!(
+            "Secrets vault is locked — unlock it to continue ({})",
+            purpose
+        ),
+    )
+    .await?;
+
+    let mut passwords = relay.rx.lock().await;
+    let deadline = tokio::time::sleep(UNLOCK_TIMEOUT);
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            Some(password) = passwords.recv() => {
+                secrets_handler::handle_secrets_frame(
+                    writer,
+                    vault,
+                    ClientPayload::UnlockVault { password },
+                    connection_id,
+                )
+                .await?;
+            }
+            _ = tokio::time::sleep(RELOCK_POLL_INTERVAL) => {}
+            _ = cancel_requested(tool_cancel) => return Ok(false),
+            _ = &mut deadline => {
+                warn!(connection_id, purpose, "Vault still locked; giving up");
+                return Ok(false);
+            }
+        }
+        if !vault.lock().await.is_locked() {
+            protocol::server::send_info(writer, "Vault unlocked — resuming…").await?;
+            return Ok(true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyclaw_core::gateway::ServerPayload;
+    use rustyclaw_core::secrets::SecretsManager;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    use crate::test_support::ChannelWriter;
+    use crate::tool_executor::execute_tool_by_type;
+
+    #[tokio::test]
+    async fn test_relocked_vault_prompts_and_resumes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault: SharedVault = Arc::new(Mutex::new(SecretsManager::with_password(
+            tmp.path().join("credentials"),
+            "hunter2".into(),
+        )));
+        let skills: crate::SharedSkillManager = Arc::new(Mutex::new(
+            rustyclaw_core::skills::SkillManager::new(tmp.path().join("skills")),
+        ));
+        let cancel = ToolCancelFlag::default();
+        let relay = Arc::new(UnlockRelay::new(7));
+        let (mut writer, mut frames) = ChannelWriter::new();
+
+        let store = json!({
+            "name": "deploy_token", "kind": "token", "value": "tok-123", "policy": "always",
+        });
+        let (out, is_error) = execute_tool_by_type(
+            "secrets_store",
+            &store,
+            tmp.path(),
+            &vault,
+            &skills,
+            &cancel,
+        )
+        .await;
+        assert!(!is_error, "{out}");
+
+        // Locked again between the two calls.
+        vault.lock().await.clear_password();
+        assert!(vault.lock().await.is_locked());
+
+        // The client answers the prompt: first wrongly, then correctly.
+        let client = {
+            let relay = relay.clone();
+            tokio::spawn(async move {
+                let mut unlock_results = Vec::new();
+                let mut passwords = vec!["hunter2", "wrong"];
+                while let Some(frame) = frames.recv().await {
+                    match frame.payload {
+                        ServerPayload::Status { status, .. } => {
+                            assert_eq!(status, StatusType::VaultLocked);
+                            assert!(relay.is_waiting());
+                            relay.deliver(passwords.pop().unwrap().into()).await;
+                        }
+                        ServerPayload::VaultUnlocked { ok, .. } => {
+                            unlock_results.push(ok);
+                            if let Some(password) = passwords.pop() {
+                                relay.deliver(password.into()).await;
+                            }
+                        }
+                        ServerPayload::Info { message } => {
+                            assert!(message.contains("resuming"), "{message}");
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                unlock_results
+            })
+        };
+
+        let purpose = "`secrets_get` needs it";
+        let unlocked = wait_for_unlock(&mut writer, &vault, &relay, &cancel, purpose)
+            .await
+            .unwrap();
+        assert!(unlocked);
+        assert!(!relay.is_waiting());
+        assert_eq!(client.await.unwrap(), vec![false, true]);
+
+        let get = json!({ "name": "deploy_token" });
+        let (out, is_error) =
+            execute_tool_by_type("secrets_get", &get, tmp.path(), &vault, &skills, &cancel).await;
+        assert!(!is_error, "{out}");
+        assert!(out.contains("tok-123"), "{out}");
+    }
+
+    #[tokio::test]
+    async fn test_unlocked_vault_does_not_wait() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault: SharedVault = Arc::new(Mutex::new(SecretsManager::new(tmp.path())));
+        let relay = UnlockRelay::new(1);
+        let (mut writer, mut frames) = ChannelWriter::new();
+        let unlocked = wait_for_unlock(
+            &mut writer,
+            &vault,
+            &relay,
+            &ToolCancelFlag::default(),
+            "test",
+        )
+        .await
+        .unwrap();
+        assert!(unlocked);
+        assert!(frames.try_recv().is_err(), "no prompt expected");
+    }
+}
@@ -0,0 +1,179 @@
+//! Model warm-up at gateway startup (`[model_warmup]`).
+//!
+//! A local server loads the model on its first request, which can take
+//! long enough that the first chat looks hung.  When enabled, the gateway
+//! sends that first request itself while it starts: an explicit load for
+//! Ollama (`/api/generate` with an empty prompt, like `ollama_manage load`)
+//! and a one-token completion for the other OpenAI-compatible local
+//! servers.  Cloud providers are never warmed.
+//!
+//! The warm-up runs in the background and connections are accepted
+//! meanwhile.  Progress goes out as status broadcasts, and a client that
+//! connects mid-warm-up is told on connect (see [`in_progress`]).  A
+//! failure is logged and reported, never fatal.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+use tracing::{debug, info, warn};
+
+use rustyclaw_core::config::ModelWarmupConfig;
+use rustyclaw_core::gateway::{ModelContext, StatusType};
+use rustyclaw_core::providers as crate_providers;
+
+use crate::status_broadcast;
+
+static WARMING: AtomicBool = AtomicBool::new(false);
+
+/// Whether the startup warm-up is still running.
+pub(crate) fn in_progress() -> bool {
+    WARMING.load(Ordering::Relaxed)
+}
+
+/// Start the warm-up in the background, if `config` enables it and `ctx`
+/// names a local model.
+pub(crate) fn spawn(config: &ModelWarmupConfig, ctx: &ModelContext) {
+    if !config.enabled {
+        return;
+    }
+    if !crate_providers::is_local_provider(&ctx.provider) {
+        debug!(provider = %ctx.provider, "Skipping model warm-up for a cloud provider");
+        return;
+    }
+    if ctx.model.is_empty() || ctx.base_url.is_empty() {
+        warn!(provider = %ctx.provider, "Model warm-up enabled but no model or base URL is set");
+        return;
+    }
+
+    let (url, body) = warmup_request(ctx, config.keep_alive.as_deref());
+    let label = format!(
+        "{} / {}",
+        crate_providers::display_name_for_provider(&ctx.provider),
+        ctx.model
+    );
+    let api_key = ctx.api_key.clone();
+    let timeout = Duration::from_secs(config.timeout_secs.max(1));
+
+    WARMING.store(true, Ordering::Relaxed);
+    info!(model = %label, url = %url, "Warming up model");
+    status_broadcast::publish(
+        None,
+        StatusType::ModelConnecting,
+        format!("Warming up {} …", label),
+    );
+
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let result = match tokio::time::timeout(timeout, send(&url, &body, api_key)).await {
+            Ok(result) => result,
+            Err(_) => Err(format!("timed out after {}s", timeout.as_secs())),
+        };
+        WARMING.store(false, Ordering::Relaxed);
+        match result {
+            Ok(()) => {
+                let secs = started.elapsed().as_secs_f64();
+                info!(model = %label, secs, "Model warm-up finished");
+                status_broadcast::publish(
+                    None,
+                    StatusType::ModelReady,
+                    format!("{} warmed up in {:.1}s", label, secs),
+                );
+            }
+            Err(e) => {
+                warn!(model = %label, error = %e, "Model warm-up failed");
+                status_broadcast::publish(
+                    None,
+                    StatusType::ModelError,
+                    format!(
+                        "Warm-up of {} failed: {} (the first request may be slow)",
+                        label, e
+                    ),
+                );
+            }
+        }
+    });
+}
+
+/// The URL and JSON body that load `ctx`'s model.
+fn warmup_request(ctx: &ModelContext, keep_alive: Option<&str>) -> (String, Value) {
+    let base = ctx.base_url.trim_end_matches('/');
+    if ctx.provider == "ollama" {
+        // The native API sits beside the OpenAI-compatible `/v1`.
+        let root = base.strip_suffix("/v1").unwrap_or(base);
+        let mut body = json!({ "model": ctx.model, "prompt": "" });
+        if let Some(keep_alive) = keep_alive {
+            body["keep_alive"] = json!(keep_alive);
+        }
+        (format!("{}/api/generate", root), body)
+    } else {
+        let body = json!({
+            "model": ctx.model,
+            "messages": [{ "role": "user", "content": "hi" }],
+            "max_tokens": 1,
+            "stream": false,
+        });
+        (format!("{}/chat/completions", base), body)
+    }
+}
+
+async fn send(url: &str, body: &Value, api_key: Option<String>) -> Result<(), String> {
+    let mut req = reqwest::Client::new().post(url).json(body);
+    if let Some(key) = api_key {
+        req = req.bearer_auth(key);
+    }
+    let resp = req.send().await.map_err(|e| e.to_string())?;
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let text = resp.text().await.unwrap_or_default();
+    let detail = crate_providers::parse_provider_error(&text)
+        .map(|e| e.message)
+        .unwrap_or_else(|| text.trim().chars().take(200).collect());
+    Err(format!("HTTP {}: {}", status.as_u16(), detail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(provider: &str, base_url: &str) -> ModelContext {
+        ModelContext {
+            provider: provider.into(),
+            model: "llama3.1".into(),
+            base_url: base_url.into(),
+            api_key: None,
+        }
+    }
+
+    #[test]
+    fn test_ollama_warmup_loads_through_native_api() {
+        let (url, body) = warmup_request(&ctx("ollama", "http://localhost:11434/v1/"), Some("30m"));
+        assert_eq!(url, "http://localhost:11434/api/generate");
+        assert_eq!(body["model"], "llama3.1");
+        assert_eq!(body["prompt"], "");
+        assert_eq!(body["keep_alive"], "30m");
+
+        let (_, body) = warmup_request(&ctx("ollama", "http://gpu-box:11434"), None);
+        assert!(body.get("keep_alive").is_none());
+    }
+
+    #[test]
+    fn test_other_local_servers_get_a_one_token_completion() {
+        let (url, body) = warmup_request(&ctx("exo", "http://localhost:52415/v1"), None);
+        assert_eq!(url, "http://localhost:52415/v1/chat/completions");
+        assert_eq!(body["max_tokens"], 1);
+        assert_eq!(body["stream"], false);
+    }
+
+    #[test]
+    fn test_cloud_providers_are_not_warmed() {
+        let config = ModelWarmupConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        spawn(&config, &ctx("openai", "https://api.openai.com/v1"));
+        assert!(!in_progress());
+    }
+}
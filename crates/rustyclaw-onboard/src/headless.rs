@@ -0,0 +1,597 @@
+//! Headless onboarding: provisions a RustyClaw install from flags and an
+//! optional JSON blob, without a terminal.
+//!
+//! Everything the wizard would ask for has to be supplied up front — the
+//! provider and model, its API key (or a key already in the vault), the
+//! gateway listener and the workspace.  Anything missing or contradictory
+//! is reported as an error naming the flag that fixes it; nothing here ever
+//! prompts.
+//!
+//! The JSON blob is deep-merged into the config before the flags are
+//! applied, so flags win.  An optional top-level `"secrets"` object maps
+//! vault key names to values:
+//!
+//! ```json
+//! {
+//!   "agent_name": "ci-bot",
+//!   "model": { "provider": "custom", "base_url": "http://llm.internal/v1" },
+//!   "secrets": { "CUSTOM_API_KEY": "sk-..." }
+//! }
+//! ```
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use serde_json::Value;
+
+use rustyclaw_core::config::{Config, ModelProvider, SshGatewayConfig};
+use rustyclaw_core::providers::{AuthMethod, ProviderDef, parse_model_spec, provider_by_id};
+use rustyclaw_core::secrets::{GatewayRole, SecretsManager};
+use rustyclaw_core::soul::SoulManager;
+use rustyclaw_core::theme as t;
+
+/// Label given to the gateway token issued by `--gateway-auth token`.
+const GATEWAY_TOKEN_LABEL: &str = "onboard";
+
+/// Inputs for [`run_headless_onboard`], gathered from CLI flags.
+#[derive(Debug, Default)]
+pub struct HeadlessArgs {
+    /// Discard the existing config and vault first.
+    pub reset: bool,
+    /// Configure a client of a remote gateway rather than a local one.
+    pub remote: bool,
+    pub workspace: Option<PathBuf>,
+    pub agent_name: Option<String>,
+    /// `provider/model`, e.g. `anthropic/claude-sonnet-4-20250514`.
+    pub model: Option<String>,
+    /// `<provider>-api-key` or a provider id (openclaw's `--auth-choice`).
+    pub auth_choice: Option<String>,
+    /// API keys by provider id.
+    pub api_keys: Vec<(String, String)>,
+    /// Gateway listen port.
+    pub gateway_port: Option<u16>,
+    /// `loopback`, `lan`, `auto`, `tailnet` or `custom`.
+    pub gateway_bind: Option<String>,
+    /// `token` or `password`.
+    pub gateway_auth: Option<String>,
+    pub gateway_token: Option<String>,
+    pub gateway_password: Option<String>,
+    pub remote_url: Option<String>,
+    pub remote_token: Option<String>,
+    /// Config overrides and secrets (see the module docs).
+    pub config_json: Option<Value>,
+    /// Password for a password-protected vault, or to protect a new one.
+    pub vault_password: Option<String>,
+}
+
+/// What headless onboarding configured, as printed by `onboard --json`.
+#[derive(Debug, Serialize)]
+pub struct OnboardSummary {
+    pub config_path: PathBuf,
+    pub workspace_dir: PathBuf,
+    pub agent_name: String,
+    pub mode: &'static str,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+    /// Names (never values) of the secrets written to the vault.
+    pub secrets_stored: Vec<String>,
+    pub vault_password_protected: bool,
+    pub gateway_listen: Option<String>,
+    pub gateway_url: Option<String>,
+    /// Plaintext of the token issued by `--gateway-auth token`.  It is not
+    /// stored anywhere and cannot be recovered later.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway_token: Option<String>,
+}
+
+impl OnboardSummary {
+    /// Print the summary for a human reader.
+    pub fn print(&self) {
+        t::print_header("Onboarding complete! 🎉");
+        let config_path = self.config_path.display().to_string();
+        println!(
+            "  {}",
+            t::icon_ok(&format!("Config saved to {}", t::info(&config_path)))
+        );
+        let workspace = self.workspace_dir.display().to_string();
+        println!("  {}", t::label_value("Workspace   ", &workspace));
+        println!("  {}", t::label_value("Agent       ", &self.agent_name));
+        println!("  {}", t::label_value("Mode        ", self.mode));
+        if let Some(ref provider) = self.provider {
+            let model = self.model.as_deref().unwrap_or("(provider default)");
+            println!(
+                "  {}",
+                t::label_value("Model       ", &format!("{}/{}", provider, model))
+            );
+        }
+        if let Some(ref url) = self.gateway_url {
+            println!("  {}", t::label_value("Gateway URL ", url));
+        }
+        if let Some(ref listen) = self.gateway_listen {
+            println!("  {}", t::label_value("Gateway     ", listen));
+        }
+        if !self.secrets_stored.is_empty() {
+            println!(
+                "  {}",
+                t::label_value("Secrets     ", &self.secrets_stored.join(", "))
+            );
+        }
+        if let Some(ref token) = self.gateway_token {
+            println!();
+            println!(
+                "  {}",
+                t::warn("Gateway token (shown once — store it now):")
+            );
+            println!("  {}", token);
+        }
+        println!();
+    }
+}
+
+/// Provision config and vault from `args` without prompting.
+///
+/// Validates every input before writing anything it can, and fails with an
+/// error naming the missing or conflicting flag instead of falling back to
+/// the interactive wizard.
+pub fn run_headless_onboard(config: &mut Config, args: HeadlessArgs) -> Result<OnboardSummary> {
+    let mut next = if args.reset {
+        Config {
+            settings_dir: config.settings_dir.clone(),
+            ..Config::default()
+        }
+    } else {
+        config.clone()
+    };
+
+    // ── JSON overrides ─────────────────────────────────────────────
+    let mut json_secrets = Vec::new();
+    if let Some(mut blob) = args.config_json {
+        let Some(fields) = blob.as_object_mut() else {
+            bail!("The JSON config must be an object, e.g. {{\"agent_name\": \"ci-bot\"}}");
+        };
+        if let Some(secrets) = fields.remove("secrets") {
+            json_secrets = parse_json_secrets(secrets)?;
+        }
+        next = merge_config(&next, blob)?;
+    }
+
+    // ── Flags ──────────────────────────────────────────────────────
+    if let Some(workspace) = args.workspace {
+        next.workspace_dir = Some(workspace);
+    }
+    if let Some(name) = args.agent_name {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("--agent-name cannot be empty");
+        }
+        next.agent_name = name.to_string();
+    }
+    if args.remote {
+        let Some(url) = args.remote_url.or_else(|| next.gateway_url.clone()) else {
+            bail!("Remote mode needs the gateway address: pass --remote-url ws://host:9001");
+        };
+        next.gateway_url = Some(url);
+    } else if let Some(url) = args.remote_url {
+        bail!(
+            "--remote-url {} only applies with --mode remote; add --mode remote or drop it",
+            url
+        );
+    }
+    if args.remote_token.is_some() {
+        bail!(
+            "Remote gateway tokens are not stored in the config: export \
+             RUSTYCLAW_GATEWAY_TOKEN on the client instead of passing --remote-token"
+        );
+    }
+
+    // ── Provider and model ─────────────────────────────────────────
+    let mut api_keys = Vec::with_capacity(args.api_keys.len());
+    for (id, key) in args.api_keys {
+        let key = key.trim().to_string();
+        if key.is_empty() {
+            continue;
+        }
+        let provider = provider_by_id(&id).with_context(|| format!("Unknown provider '{}'", id))?;
+        let Some(secret_key) = provider.secret_key else {
+            bail!("{} does not take an API key", provider.display);
+        };
+        api_keys.push((provider, secret_key, key));
+    }
+
+    let provider = select_provider(
+        args.model.as_deref(),
+        args.auth_choice.as_deref(),
+        &api_keys,
+        next.model.as_ref(),
+    )?;
+    if provider.is_none() && !args.remote {
+        bail!(
+            "No model provider selected: pass --model provider/model (e.g. \
+             anthropic/claude-sonnet-4-20250514), --auth-choice, or one provider API key"
+        );
+    }
+    if let Some(provider) = provider {
+        next.model = Some(resolve_model(
+            provider,
+            args.model.as_deref(),
+            next.model.as_ref(),
+        )?);
+    }
+
+    // ── Gateway listener ───────────────────────────────────────────
+    if let Some(auth) = args.gateway_auth.as_deref()
+        && auth != "token"
+    {
+        bail!(
+            "--gateway-auth {} is not supported: the gateway authenticates with issued \
+             tokens, so use --gateway-auth token",
+            auth
+        );
+    }
+    if args.gateway_password.is_some() {
+        bail!("--gateway-password is not supported: use --gateway-auth token");
+    }
+    if args.gateway_token.is_some() {
+        bail!(
+            "Gateway tokens are generated, not chosen: drop --gateway-token and pass \
+             --gateway-auth token to have one issued"
+        );
+    }
+    if args.gateway_port.is_some() || args.gateway_bind.is_some() {
+        let ssh = next.ssh.get_or_insert_with(|| SshGatewayConfig {
+            mode: "standalone".to_string(),
+            bind: "0.0.0.0:2222".to_string(),
+            ..SshGatewayConfig::default()
+        });
+        ssh.bind = gateway_listen(&ssh.bind, args.gateway_bind.as_deref(), args.gateway_port)?;
+        ssh.enabled = true;
+    }
+
+    // ── Secrets vault ──────────────────────────────────────────────
+    let vault_path = next.credentials_dir().join("secrets.json");
+    let key_path = next.credentials_dir().join("secrets.key");
+    if args.reset {
+        let _ = std::fs::remove_file(&vault_path);
+        let _ = std::fs::remove_file(&key_path);
+        next.secrets_password_protected = false;
+        next.totp_enabled = false;
+    }
+    if next.totp_enabled {
+        bail!(
+            "The vault is protected by 2FA, which needs a code typed at the terminal: \
+             run `rustyclaw onboard` interactively, or add --reset to start over"
+        );
+    }
+    let vault_exists = vault_path.exists();
+    if vault_exists && !key_path.exists() {
+        // A vault without a key file can only be password-protected.
+        next.secrets_password_protected = true;
+    }
+    let mut secrets = match args.vault_password {
+        Some(password) => {
+            if vault_exists && !next.secrets_password_protected {
+                bail!(
+                    "The existing vault is not password-protected; unset \
+                     RUSTYCLAW_VAULT_PASSWORD or add --reset to recreate it with one"
+                );
+            }
+            next.secrets_password_protected = true;
+            SecretsManager::with_password(next.credentials_dir(), password)
+        }
+        None if next.secrets_password_protected => {
+            bail!("The vault is password-protected: set RUSTYCLAW_VAULT_PASSWORD to unlock it")
+        }
+        None => SecretsManager::new(next.credentials_dir()),
+    }
+    .with_backend_kind(next.secrets_backend)?;
+
+    if let Some(provider) = provider {
+        let provided = api_keys.iter().any(|(p, _, _)| p.id == provider.id)
+            || provider
+                .secret_key
+                .is_some_and(|sk| json_secrets.iter().any(|(name, _)| name == sk));
+        check_credentials(provider, provided, &mut secrets)?;
+    }
+
+    let mut secrets_stored = Vec::new();
+    for (_, secret_key, key) in &api_keys {
+        secrets.store_secret(secret_key, key)?;
+        secrets_stored.push(secret_key.to_string());
+    }
+    for (name, value) in &json_secrets {
+        secrets.store_secret(name, value)?;
+        secrets_stored.push(name.clone());
+    }
+    secrets_stored.sort();
+    secrets_stored.dedup();
+
+    let gateway_token = if args.gateway_auth.is_some() {
+        let (_, token) = secrets
+            .issue_gateway_token(GATEWAY_TOKEN_LABEL, None, GatewayRole::Operator, None)
+            .context("Failed to issue gateway token")?;
+        Some(token)
+    } else {
+        None
+    };
+
+    // ── Write everything out ───────────────────────────────────────
+    next.ensure_dirs()
+        .context("Failed to create directory structure")?;
+    SoulManager::new(next.soul_path()).load()?;
+    next.save(None)?;
+    *config = next;
+
+    let model = config.model.as_ref();
+    Ok(OnboardSummary {
+        config_path: config.settings_dir.join("config.toml"),
+        workspace_dir: config.workspace_dir(),
+        agent_name: config.agent_name.clone(),
+        mode: if args.remote { "remote" } else { "local" },
+        provider: model.map(|m| m.provider.clone()),
+        model: model.and_then(|m| m.model.clone()),
+        base_url: model.and_then(|m| m.base_url.clone()),
+        secrets_stored,
+        vault_password_protected: config.secrets_password_protected,
+        gateway_listen: config
+            .ssh
+            .as_ref()
+            .filter(|s| s.enabled)
+            .map(|s| s.bind.clone()),
+        gateway_url: config.gateway_url.clone(),
+        gateway_token,
+    })
+}
+
+/// The `"secrets"` object of the JSON blob as `(name, value)` pairs.
+fn parse_json_secrets(secrets: Value) -> Result<Vec<(String, String)>> {
+    let Value::Object(entries) = secrets else {
+        bail!("\"secrets\" must be an object mapping secret names to string values");
+    };
+    entries
+        .into_iter()
+        .map(|(name, value)| match value {
+            Value::String(value) => Ok((name, value)),
+            _ => bail!("Secret \"{}\" must be a string", name),
+        })
+        .collect()
+}
+
+/// Deep-merge `overrides` into `config`.  Objects merge key by key; any
+/// other value replaces what was there.  `settings_dir` cannot be moved
+/// this way — use `--settings-dir`.
+fn merge_config(config: &Config, overrides: Value) -> Result<Config> {
+    let mut merged = serde_json::to_value(config).context("Failed to serialize config")?;
+    merge_json(&mut merged, overrides);
+    let mut merged: Config =
+        serde_json::from_value(merged).context("The JSON config does not match config.toml")?;
+    merged.settings_dir = config.settings_dir.clone();
+    Ok(merged)
+}
+
+fn merge_json(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(slot) => merge_json(slot, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// Pick the provider from, in order: `--model`, `--auth-choice`, the one
+/// API key given, or the provider already configured.
+fn select_provider(
+    model: Option<&str>,
+    auth_choice: Option<&str>,
+    api_keys: &[(&'static ProviderDef, &'static str, String)],
+    current: Option<&ModelProvider>,
+) -> Result<Option<&'static ProviderDef>> {
+    if let Some(spec) = model {
+        let (id, _) = parse_model_spec(spec).map_err(anyhow::Error::msg)?;
+        return Ok(provider_by_id(&id));
+    }
+    if let Some(choice) = auth_choice.filter(|c| !c.eq_ignore_ascii_case("apiKey")) {
+        let id = choice.strip_suffix("-api-key").unwrap_or(choice);
+        let id = if id == "gemini" { "google" } else { id };
+        return match provider_by_id(id) {
+            Some(provider) => Ok(Some(provider)),
+            None => bail!(
+                "Unknown --auth-choice '{}': use <provider>-api-key, e.g. anthropic-api-key",
+                choice
+            ),
+        };
+    }
+    match api_keys {
+        [] => {}
+        [(provider, _, _)] => return Ok(Some(*provider)),
+        _ => {
+            let ids: Vec<&str> = api_keys.iter().map(|(p, _, _)| p.id).collect();
+            bail!(
+                "API keys were given for several providers ({}): choose one with --model \
+                 or --auth-choice",
+                ids.join(", ")
+            );
+        }
+    }
+    Ok(current.and_then(|m| provider_by_id(&m.provider)))
+}
+
+/// The `[model]` section for `provider`: the `--model` name, else the one
+/// already configured for this provider, else the provider's first model.
+fn resolve_model(
+    provider: &'static ProviderDef,
+    spec: Option<&str>,
+    current: Option<&ModelProvider>,
+) -> Result<ModelProvider> {
+    let current = current.filter(|m| m.provider == provider.id);
+    let model = match spec {
+        Some(spec) => Some(parse_model_spec(spec).map_err(anyhow::Error::msg)?.1),
+        None => current
+            .and_then(|m| m.model.clone())
+            .or_else(|| provider.models.first().map(|m| m.to_string())),
+    };
+    let Some(model) = model else {
+        bail!(
+            "{} has no default model: pass --model {}/<model>",
+            provider.display,
+            provider.id
+        );
+    };
+    let base_url = current
+        .and_then(|m| m.base_url.clone())
+        .or_else(|| provider.base_url.map(str::to_string));
+    if base_url.is_none() && (provider.id == "custom" || provider.id == "copilot-proxy") {
+        bail!(
+            "{} needs a base URL: add {{\"model\": {{\"base_url\": \"http://host/v1\"}}}} \
+             to the JSON config",
+            provider.display
+        );
+    }
+    Ok(ModelProvider {
+        provider: provider.id.to_string(),
+        model: Some(model),
+        base_url,
+    })
+}
+
+/// Fail unless `provider` can authenticate: with a key given now, one
+/// already in the vault, or (for optional keys and keyless providers)
+/// without one.
+fn check_credentials(
+    provider: &'static ProviderDef,
+    provided: bool,
+    secrets: &mut SecretsManager,
+) -> Result<()> {
+    let Some(secret_key) = provider.secret_key else {
+        return Ok(());
+    };
+    if provided || secrets.get_secret(secret_key, true)?.is_some() {
+        return Ok(());
+    }
+    match provider.auth_method {
+        AuthMethod::None | AuthMethod::OptionalApiKey => Ok(()),
+        AuthMethod::ApiKey => {
+            let hint = provider
+                .help_url
+                .map(|url| format!(" (get one at {})", url))
+                .unwrap_or_default();
+            bail!(
+                "No API key for {}{}: set {} or add it under \"secrets\" in the JSON config",
+                provider.display,
+                hint,
+                secret_key
+            )
+        }
+        AuthMethod::DeviceFlow => bail!(
+            "{} signs in through a browser: run `rustyclaw onboard` interactively once, \
+             or add a {} token under \"secrets\" in the JSON config",
+            provider.display,
+            secret_key
+        ),
+    }
+}
+
+/// The `host:port` the gateway should listen on.  `current` is the
+/// configured address, whose host `custom` keeps and whose port is the
+/// default.
+fn gateway_listen(current: &str, bind: Option<&str>, port: Option<u16>) -> Result<String> {
+    let (current_host, current_port) = current.rsplit_once(':').unwrap_or((current, "2222"));
+    let host = match bind.unwrap_or("loopback") {
+        "loopback" => "127.0.0.1",
+        "lan" | "auto" => "0.0.0.0",
+        "custom" => current_host,
+        "tailnet" => bail!(
+            "--gateway-bind tailnet needs the tailnet address, which can't be detected \
+             headlessly: use --gateway-bind custom with {{\"ssh\": {{\"bind\": \"<ip>:<port>\"}}}} \
+             in the JSON config"
+        ),
+        other => bail!("Unknown --gateway-bind '{}'", other),
+    };
+    let port = match port {
+        Some(port) => port.to_string(),
+        None => current_port.to_string(),
+    };
+    Ok(format!("{}:{}", host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_json_is_deep() {
+        let mut base = json!({ "model": { "provider": "openai", "model": "gpt-4.1" }, "a": 1 });
+        merge_json(
+            &mut base,
+            json!({ "model": { "model": "gpt-5" }, "b": [1] }),
+        );
+        assert_eq!(
+            base,
+            json!({ "model": { "provider": "openai", "model": "gpt-5" }, "a": 1, "b": [1] })
+        );
+    }
+
+    #[test]
+    fn test_select_provider_order() {
+        let anthropic = provider_by_id("anthropic").unwrap();
+        let openai = provider_by_id("openai").unwrap();
+        let keys = [(anthropic, "ANTHROPIC_API_KEY", "sk-a".to_string())];
+
+        let picked = select_provider(Some("openai/gpt-4.1"), None, &keys, None).unwrap();
+        assert_eq!(picked.map(|p| p.id), Some("openai"));
+        let picked = select_provider(None, Some("gemini-api-key"), &keys, None).unwrap();
+        assert_eq!(picked.map(|p| p.id), Some("google"));
+        let picked = select_provider(None, Some("apiKey"), &keys, None).unwrap();
+        assert_eq!(picked.map(|p| p.id), Some("anthropic"));
+
+        let both = [
+            (anthropic, "ANTHROPIC_API_KEY", "sk-a".to_string()),
+            (openai, "OPENAI_API_KEY", "sk-o".to_string()),
+        ];
+        let err = select_provider(None, None, &both, None).unwrap_err();
+        assert!(err.to_string().contains("--auth-choice"));
+        assert!(select_provider(None, Some("nope-api-key"), &[], None).is_err());
+    }
+
+    #[test]
+    fn test_resolve_model_requires_custom_base_url() {
+        let custom = provider_by_id("custom").unwrap();
+        let err = resolve_model(custom, Some("custom/llama"), None).unwrap_err();
+        assert!(err.to_string().contains("base_url"));
+
+        let current = ModelProvider {
+            provider: "custom".into(),
+            model: None,
+            base_url: Some("http://llm.internal/v1".into()),
+        };
+        let model = resolve_model(custom, Some("custom/llama"), Some(&current)).unwrap();
+        assert_eq!(model.model.as_deref(), Some("llama"));
+        assert_eq!(model.base_url.as_deref(), Some("http://llm.internal/v1"));
+    }
+
+    #[test]
+    fn test_gateway_listen() {
+        assert_eq!(
+            gateway_listen("0.0.0.0:2222", None, None).unwrap(),
+            "127.0.0.1:2222"
+        );
+        assert_eq!(
+            gateway_listen("0.0.0.0:2222", Some("lan"), Some(9001)).unwrap(),
+            "0.0.0.0:9001"
+        );
+        assert_eq!(
+            gateway_listen("10.0.0.5:2222", Some("custom"), Some(2200)).unwrap(),
+            "10.0.0.5:2200"
+        );
+        assert!(gateway_listen("0.0.0.0:2222", Some("tailnet"), None).is_err());
+    }
+}
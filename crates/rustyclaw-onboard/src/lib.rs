@@ -2,7 +2,8 @@
 //!
 //! Mirrors the openclaw `onboard` command: walks the user through selecting a
 //! model provider, storing an API key, picking a default model, and
-//! initialising the SOUL.
+//! initialising the SOUL.  [`run_headless_onboard`] provisions the same
+//! from flags and a JSON blob when there is no one to answer prompts.
 
 use std::io::{self, Write};
 
@@ -14,15 +15,18 @@ use rustyclaw_core::secrets::SecretsManager;
 use rustyclaw_core::soul::{DEFAULT_SOUL_CONTENT, SoulManager};
 use rustyclaw_core::theme as t;
 
+mod headless;
 mod messaging;
 mod prompts;
 mod security;
 mod skills;
 
+pub use headless::{HeadlessArgs, OnboardSummary, run_headless_onboard};
 use messaging::setup_messaging;
 use prompts::{arrow_select, fuzzy_select, prompt_line, prompt_secret};
 use security::{
-    perform_device_flow_auth, setup_agent_ssh_key, setup_totp_enrollment, verify_totp_loop,
+    perform_device_flow_auth, print_backup_codes, setup_agent_ssh_key, setup_totp_enrollment,
+    verify_totp_loop,
 };
 use skills::setup_recommended_skills;
 
@@ -335,6 +339,20 @@ pub fn run_onboard_wizard(
                     println!("  {}", t::icon_ok("2FA disabled."));
                 } else {
                     println!("  {}", t::muted("Keeping 2FA enabled."));
+                    let remaining = secrets.backup_codes_remaining()?;
+                    let regenerate = prompt_line(
+                        &mut reader,
+                        &format!(
+                            "{} ",
+                            t::accent(&format!(
+                                "Regenerate backup codes ({} unused)? [y/N]:",
+                                remaining
+                            ))
+                        ),
+                    )?;
+                    if regenerate.trim().eq_ignore_ascii_case("y") {
+                        print_backup_codes(&secrets.generate_backup_codes()?);
+                    }
                 }
             } else {
                 setup_totp_enrollment(&mut reader, config, secrets, &config.agent_name.clone())?;
@@ -197,6 +197,7 @@ pub(crate) fn setup_totp_enrollment(
                         "  {}",
                         t::icon_ok("2FA enabled — authenticator verified successfully.")
                     );
+                    print_backup_codes(&secrets.generate_backup_codes()?);
                     break;
                 }
                 Ok(false) => {
@@ -222,14 +223,36 @@ pub(crate) fn setup_totp_enrollment(
     Ok(())
 }
 
-/// Prompt the user for a TOTP code in a retry loop.
+/// Show freshly generated TOTP backup codes.  They are not stored in
+/// plaintext, so this is the only time the user sees them.
+pub(crate) fn print_backup_codes(codes: &[String]) {
+    println!();
+    println!(
+        "  {}",
+        t::heading("Backup codes — each works once if you lose your authenticator:")
+    );
+    println!();
+    for code in codes {
+        println!("    {}", t::accent_bright(code));
+    }
+    println!();
+    println!(
+        "  {}",
+        t::muted("Store these somewhere safe. They will not be shown again.")
+    );
+}
+
+/// Prompt the user for a TOTP code (or a backup code) in a retry loop.
 pub(crate) fn verify_totp_loop(
     reader: &mut impl BufRead,
     secrets: &mut SecretsManager,
 ) -> Result<()> {
     loop {
-        let code = prompt_line(reader, &format!("{} ", t::accent("Enter your 2FA code:")))?;
-        match secrets.verify_totp(code.trim()) {
+        let code = prompt_line(
+            reader,
+            &format!("{} ", t::accent("Enter your 2FA code (or a backup code):")),
+        )?;
+        match secrets.verify_totp_or_backup_code(code.trim()) {
             Ok(true) => {
                 println!("  {}", t::icon_ok("2FA verified."));
                 return Ok(());
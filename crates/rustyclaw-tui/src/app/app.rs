@@ -17,7 +17,7 @@ use std::sync::mpsc as sync_mpsc;
 
 use rustyclaw_core::commands::{CommandContext, CommandResponse, handle_command};
 use rustyclaw_core::config::Config;
-use rustyclaw_core::gateway::{GatewayClient, GatewayCommand};
+use rustyclaw_core::gateway::{Capabilities, GatewayClient, GatewayCommand};
 use rustyclaw_core::secrets::SecretsManager;
 use rustyclaw_core::skills::SkillManager;
 use rustyclaw_core::soul::SoulManager;
@@ -36,10 +36,12 @@ pub(crate) enum UserInput {
     Chat(String),
     Command(String),
     AuthResponse(String),
-    /// User approved or denied a tool call
+    /// User approved or denied a tool call; `remember` approves the tool
+    /// for the rest of the session
     ToolApprovalResponse {
         id: String,
         approved: bool,
+        remember: bool,
     },
     /// User submitted vault password
     VaultUnlock(String),
@@ -59,6 +61,8 @@ pub(crate) enum UserInput {
     CancelCurrentRequest,
     /// Feed back the completed assistant response for conversation history tracking.
     AssistantResponse(String),
+    /// Open the command palette (needs the locally loaded skills)
+    OpenCommandPalette,
     /// Toggle a skill's enabled state
     ToggleSkill {
         name: String,
@@ -66,6 +70,7 @@ pub(crate) enum UserInput {
     /// Cycle a tool's permission level (Allow → Ask → Deny → SkillOnly → Allow)
     CycleToolPermission {
         name: String,
+        current_permission: String,
     },
     /// Cycle a secret's access policy (OPEN → ASK → AUTH → SKILL)
     CycleSecretPolicy {
@@ -129,11 +134,13 @@ pub struct App {
     soul_manager: SoulManager,
     deferred_vault_password: Option<String>,
     skip_connection_dialog: bool,
+    session_model: Option<(String, String)>,
 }
 
 impl App {
     pub fn new(config: Config) -> Result<Self> {
-        let secrets_manager = SecretsManager::locked(config.credentials_dir());
+        let secrets_manager = SecretsManager::locked(config.credentials_dir())
+            .with_backend_kind(config.secrets_backend)?;
         Self::build(config, secrets_manager)
     }
 
@@ -147,6 +154,12 @@ impl App {
         self.skip_connection_dialog = skip;
     }
 
+    /// Use `provider`/`model` for this session instead of the gateway's
+    /// configured model.  Nothing is written to the config.
+    pub fn set_session_model(&mut self, provider: String, model: String) {
+        self.session_model = Some((provider, model));
+    }
+
     fn build(config: Config, mut secrets_manager: SecretsManager) -> Result<Self> {
         if !config.use_secrets {
             secrets_manager.set_agent_access(false);
@@ -157,6 +170,9 @@ impl App {
         let skills_dirs = config.skills_dirs();
         let mut skill_manager = SkillManager::with_dirs(skills_dirs);
         let _ = skill_manager.load_skills();
+        if let Some(url) = config.skill_registry() {
+            skill_manager.set_registry(url, config.clawhub_token.clone());
+        }
 
         let soul_path = config.soul_path();
         let mut soul_manager = SoulManager::new(soul_path);
@@ -169,6 +185,7 @@ impl App {
             soul_manager,
             deferred_vault_password: None,
             skip_connection_dialog: false,
+            session_model: None,
         })
     }
 
@@ -191,19 +208,16 @@ impl App {
         // Check if soul needs hatching (first run or default content)
         let needs_hatching = self.soul_manager.needs_hatching();
 
-        let provider = self
-            .config
-            .model
-            .as_ref()
-            .map(|m| m.provider.clone())
-            .unwrap_or_default();
-
-        let model = self
-            .config
-            .model
-            .as_ref()
-            .and_then(|m| m.model.clone())
-            .unwrap_or_default();
+        let (provider, model) = match &self.session_model {
+            Some((provider, model)) => (provider.clone(), model.clone()),
+            None => {
+                let configured = self.config.model.as_ref();
+                (
+                    configured.map(|m| m.provider.clone()).unwrap_or_default(),
+                    configured.and_then(|m| m.model.clone()).unwrap_or_default(),
+                )
+            }
+        };
 
         let model_label = if provider.is_empty() {
             String::new()
@@ -238,7 +252,9 @@ impl App {
 
         let gateway_url = conn_result.url.clone();
 
-        let hint = "Ctrl+C quit · Esc cancel run · /help commands · ↑↓ scroll".to_string();
+        let hint = "Ctrl+C quit · Esc cancel run · Ctrl+K palette · /help commands · ↑↓ scroll"
+            .to_string();
+        let tool_display = self.config.tool_display.clone();
 
         // Extract host/port from gateway_url for pre-filling the pairing dialog.
         let (pairing_default_host, pairing_default_port) =
@@ -261,6 +277,10 @@ impl App {
             conn_result.writer,
             conn_result.reader,
             Some(gateway_url.as_str()),
+            // Streamed text and thinking; the terminal has no webview for
+            // DOM queries.
+            Capabilities::legacy(),
+            self.session_model.clone(),
         ));
 
         // Reader task: drain shared GatewayEvents from the client and adapt
@@ -296,6 +316,7 @@ impl App {
                     needs_hatching: needs_hatching,
                     gateway_host: pairing_default_host,
                     gateway_port: pairing_default_port,
+                    tool_display: tool_display,
                 ))
                 .fullscreen()
                 .disable_mouse_capture(),
@@ -324,9 +345,17 @@ impl App {
                 Ok(UserInput::AuthResponse(code)) => {
                     let _ = client.send(GatewayCommand::Auth { code }).await;
                 }
-                Ok(UserInput::ToolApprovalResponse { id, approved }) => {
+                Ok(UserInput::ToolApprovalResponse {
+                    id,
+                    approved,
+                    remember,
+                }) => {
                     let _ = client
-                        .send(GatewayCommand::ToolApprove { id, approved })
+                        .send(GatewayCommand::ToolApprove {
+                            id,
+                            approved,
+                            remember,
+                        })
                         .await;
                 }
                 Ok(UserInput::VaultUnlock(password)) => {
@@ -425,6 +454,20 @@ impl App {
                         break;
                     }
                 }
+                Ok(UserInput::OpenCommandPalette) => {
+                    let skills: Vec<_> = skill_manager
+                        .get_skills()
+                        .iter()
+                        .map(|s| rustyclaw_view::SkillInfoData {
+                            name: s.name.clone(),
+                            description: s.description.clone().unwrap_or_default(),
+                            enabled: s.enabled,
+                        })
+                        .collect();
+                    let _ = gw_tx.send(GwEvent::ShowCommandPalette {
+                        entries: rustyclaw_view::build_palette_entries(&skills),
+                    });
+                }
                 Ok(UserInput::ToggleSkill { name }) => {
                     if let Some(skill) = skill_manager.get_skills().iter().find(|s| s.name == name)
                     {
@@ -445,33 +488,25 @@ impl App {
                         });
                     }
                 }
-                Ok(UserInput::CycleToolPermission { name }) => {
-                    let current = config
-                        .tool_permissions
-                        .get(&name)
-                        .cloned()
-                        .unwrap_or_default();
-                    let next = current.cycle();
-                    config.tool_permissions.insert(name.clone(), next);
-                    let _ = config.save(None);
-                    // Re-send updated tool perms list
-                    let tool_names = rustyclaw_core::tools::all_tool_names();
-                    let tools: Vec<_> = tool_names
-                        .iter()
-                        .map(|tn| {
-                            let perm = config
-                                .tool_permissions
-                                .get(*tn)
-                                .cloned()
-                                .unwrap_or_default();
-                            rustyclaw_view::ToolPermInfoData {
-                                name: tn.to_string(),
-                                permission: perm.badge().to_string(),
-                                summary: rustyclaw_core::tools::tool_summary(tn).to_string(),
-                            }
+                Ok(UserInput::CycleToolPermission {
+                    name,
+                    current_permission,
+                }) => {
+                    // Cycle ALLOW → ASK → DENY → SKILL → ALLOW; the gateway
+                    // persists it and replies with the updated list.
+                    let next_permission = match current_permission.as_str() {
+                        "ALLOW" => "ask",
+                        "ASK" => "deny",
+                        "DENY" => "skill_only",
+                        _ => "allow",
+                    };
+                    let _ = client
+                        .send(GatewayCommand::ToolPermissionSet {
+                            tool: name,
+                            permission: next_permission.to_string(),
+                            skills: vec![],
                         })
-                        .collect();
-                    let _ = gw_tx.send(GwEvent::ShowToolPerms { tools });
+                        .await;
                 }
                 Ok(UserInput::CycleSecretPolicy {
                     name,
@@ -68,23 +68,8 @@ pub(super) async fn handle_command_action(
             });
         }
         CommandAction::ShowToolPermissions => {
-            let tool_names = rustyclaw_core::tools::all_tool_names();
-            let tools: Vec<_> = tool_names
-                .iter()
-                .map(|name| {
-                    let perm = config
-                        .tool_permissions
-                        .get(*name)
-                        .cloned()
-                        .unwrap_or_default();
-                    rustyclaw_view::ToolPermInfoData {
-                        name: name.to_string(),
-                        permission: perm.badge().to_string(),
-                        summary: rustyclaw_core::tools::tool_summary(name).to_string(),
-                    }
-                })
-                .collect();
-            let _ = gw_tx.send(GwEvent::ShowToolPerms { tools });
+            // The gateway owns the permissions it enforces.
+            let _ = client.send(GatewayCommand::ToolPermissionsRequest).await;
         }
         CommandAction::ThreadNew(label) => {
             // Send thread create to gateway
@@ -35,7 +35,7 @@ pub(crate) enum GwEvent {
     Chunk(String),
     ResponseDone,
     ThinkingStart,
-    ThinkingDelta,
+    ThinkingDelta(String),
     ThinkingEnd,
     ToolCall {
         id: String,
@@ -53,6 +53,9 @@ pub(crate) enum GwEvent {
         id: String,
         name: String,
         arguments: String,
+        summary: String,
+        risks: Vec<rustyclaw_core::tools::ToolRisk>,
+        can_remember: bool,
     },
     /// Gateway requests structured user input (ask_user tool).
     UserPromptRequest(rustyclaw_core::user_prompt_types::UserPrompt),
@@ -77,6 +80,10 @@ pub(crate) enum GwEvent {
     ShowSkills {
         skills: Vec<rustyclaw_view::SkillInfoData>,
     },
+    /// Open the command palette over these entries.
+    ShowCommandPalette {
+        entries: Vec<rustyclaw_view::PaletteEntry>,
+    },
     /// Show tool permissions info dialog.
     ShowToolPerms {
         tools: Vec<rustyclaw_view::ToolPermInfoData>,
@@ -4,6 +4,7 @@ use std::sync::mpsc as sync_mpsc;
 use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Instant;
 
+use rustyclaw_core::config::ToolDisplayConfig;
 use rustyclaw_view::tracing;
 
 use super::display_message_from_gateway;
@@ -13,11 +14,15 @@ use crate::types::DisplayMessage;
 
 type UserTx = Arc<StdMutex<Option<sync_mpsc::Sender<UserInput>>>>;
 
+/// Shown in a thinking bubble until the first reasoning text arrives.
+const THINKING_PLACEHOLDER: &str = "Thinking…";
+
 /// Apply a single gateway event to the UI state bundle.
 pub(super) fn apply_gw_event(
     ev: GwEvent,
     ui: state::Ui,
     needs_hatching: bool,
+    tool_display: &ToolDisplayConfig,
     tx_for_history: &UserTx,
 ) {
     #[allow(unused_variables, unused_mut)]
@@ -43,6 +48,9 @@ pub(super) fn apply_gw_event(
         mut tool_approval_id,
         mut tool_approval_name,
         mut tool_approval_args,
+        mut tool_approval_summary,
+        mut tool_approval_risks,
+        mut tool_approval_can_remember,
         mut tool_approval_selected,
         mut show_vault_unlock,
         mut vault_password,
@@ -133,6 +141,7 @@ pub(super) fn apply_gw_event(
         mut show_system_info,
         show_services_dialog: _,
         mut services_data,
+        mut command_palette,
     } = ui;
     match ev {
         GwEvent::AuthChallenge => {
@@ -309,12 +318,25 @@ pub(super) fn apply_gw_event(
             if stream_start.get().is_none() {
                 stream_start.set(Some(Instant::now()));
             }
+            // Reasoning is shown greyed out and collapsed; Ctrl+E expands it.
+            let mut thinking = DisplayMessage::thinking(THINKING_PLACEHOLDER);
+            thinking.collapsed = true;
             let mut m = messages.read().clone();
-            m.push(DisplayMessage::thinking("Thinking…"));
+            m.push(thinking);
             messages.set(m);
         }
-        GwEvent::ThinkingDelta => {
-            // Thinking is ongoing — keep spinner alive
+        GwEvent::ThinkingDelta(delta) => {
+            let mut m = messages.read().clone();
+            if let Some(last) = m
+                .last_mut()
+                .filter(|l| l.role == rustyclaw_core::types::MessageRole::Thinking)
+            {
+                if last.content == THINKING_PLACEHOLDER {
+                    last.content.clear();
+                }
+                last.append(&delta);
+            }
+            messages.set(m);
         }
         GwEvent::ThinkingEnd => {
             // Thinking done, but streaming may continue
@@ -382,6 +404,9 @@ pub(super) fn apply_gw_event(
                     .iter()
                     .any(|tc| tc.id == id && tc.result.is_some());
                 if before > 0 && after_match {
+                    if let Some(tc) = msg.tool_calls.iter_mut().find(|tc| tc.id == id) {
+                        tc.apply_display_policy(tool_display);
+                    }
                     matched = true;
                     break;
                 }
@@ -390,6 +415,7 @@ pub(super) fn apply_gw_event(
                 let mut fallback = DisplayMessage::assistant("");
                 fallback.add_tool_call(id, name, "{}".to_string());
                 fallback.set_tool_result(&fallback.tool_calls[0].id.clone(), result, is_error);
+                fallback.apply_tool_display(tool_display);
                 m.push(fallback);
             }
             messages.set(m);
@@ -398,11 +424,17 @@ pub(super) fn apply_gw_event(
             id,
             name,
             arguments,
+            summary,
+            risks,
+            can_remember,
         } => {
             // Show tool approval dialog
             tool_approval_id.set(id);
             tool_approval_name.set(name.clone());
             tool_approval_args.set(arguments.clone());
+            tool_approval_summary.set(summary);
+            tool_approval_risks.set(risks);
+            tool_approval_can_remember.set(can_remember);
             tool_approval_selected.set(true);
             show_tool_approval.set(true);
             let mut m = messages.read().clone();
@@ -522,6 +554,9 @@ pub(super) fn apply_gw_event(
             }
             show_skills_dialog.set(true);
         }
+        GwEvent::ShowCommandPalette { entries } => {
+            command_palette.set(rustyclaw_view::CommandPaletteData::open_with(entries));
+        }
         GwEvent::ShowToolPerms { tools } => {
             tool_perms_dialog_data.set(tools);
             if !show_tool_perms_dialog.get() {
@@ -710,7 +745,10 @@ pub(super) fn apply_gw_event(
                     foreground = ?foreground_thread_id.get(),
                     "TUI thread history reply received"
                 );
-                let converted: Vec<DisplayMessage> = rustyclaw_view::convert_history(&history);
+                let mut converted: Vec<DisplayMessage> = rustyclaw_view::convert_history(&history);
+                for msg in &mut converted {
+                    msg.apply_tool_display(tool_display);
+                }
                 tracing::debug!(
                     thread_id,
                     converted_messages = converted.len(),
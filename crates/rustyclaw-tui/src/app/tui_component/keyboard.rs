@@ -45,6 +45,9 @@ pub(super) fn apply_key_event(
         mut tool_approval_id,
         mut tool_approval_name,
         mut tool_approval_args,
+        mut tool_approval_summary,
+        mut tool_approval_risks,
+        mut tool_approval_can_remember,
         mut tool_approval_selected,
         mut show_vault_unlock,
         mut vault_password,
@@ -135,6 +138,7 @@ pub(super) fn apply_key_event(
         mut show_system_info,
         mut show_services_dialog,
         services_data: _,
+        command_palette: _,
     } = ui;
     match event {
         TerminalEvent::Key(KeyEvent {
@@ -223,8 +227,31 @@ pub(super) fn apply_key_event(
                         messages.set(m);
                         if let Ok(guard) = tx_for_keys.lock() {
                             if let Some(ref tx) = *guard {
-                                let _ =
-                                    tx.send(UserInput::ToolApprovalResponse { id, approved: true });
+                                let _ = tx.send(UserInput::ToolApprovalResponse {
+                                    id,
+                                    approved: true,
+                                    remember: false,
+                                });
+                            }
+                        }
+                    }
+                    KeyCode::Char('a') | KeyCode::Char('A') if tool_approval_can_remember.get() => {
+                        // Approve this tool until the session ends
+                        let id = tool_approval_id.read().clone();
+                        show_tool_approval.set(false);
+                        let mut m = messages.read().clone();
+                        m.push(DisplayMessage::success(format!(
+                            "✓ Approved for this session: {}",
+                            &*tool_approval_name.read()
+                        )));
+                        messages.set(m);
+                        if let Ok(guard) = tx_for_keys.lock() {
+                            if let Some(ref tx) = *guard {
+                                let _ = tx.send(UserInput::ToolApprovalResponse {
+                                    id,
+                                    approved: true,
+                                    remember: true,
+                                });
                             }
                         }
                     }
@@ -243,6 +270,7 @@ pub(super) fn apply_key_event(
                                 let _ = tx.send(UserInput::ToolApprovalResponse {
                                     id,
                                     approved: false,
+                                    remember: false,
                                 });
                             }
                         }
@@ -266,7 +294,11 @@ pub(super) fn apply_key_event(
                         messages.set(m);
                         if let Ok(guard) = tx_for_keys.lock() {
                             if let Some(ref tx) = *guard {
-                                let _ = tx.send(UserInput::ToolApprovalResponse { id, approved });
+                                let _ = tx.send(UserInput::ToolApprovalResponse {
+                                    id,
+                                    approved,
+                                    remember: false,
+                                });
                             }
                         }
                     }
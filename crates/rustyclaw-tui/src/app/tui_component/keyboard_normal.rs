@@ -49,6 +49,9 @@ pub(super) fn handle_normal_key(
         mut tool_approval_id,
         mut tool_approval_name,
         mut tool_approval_args,
+        mut tool_approval_summary,
+        mut tool_approval_risks,
+        mut tool_approval_can_remember,
         mut tool_approval_selected,
         mut show_vault_unlock,
         mut vault_password,
@@ -139,6 +142,7 @@ pub(super) fn handle_normal_key(
         mut show_system_info,
         mut show_services_dialog,
         services_data: _,
+        mut command_palette,
     } = ui;
     // ── Normal mode keyboard ────────────────────────
     // System info dialog: Esc to close
@@ -149,6 +153,52 @@ pub(super) fn handle_normal_key(
         return;
     }
 
+    // Command palette: type to filter, Enter runs or inserts, Tab inserts
+    if command_palette.read().open {
+        let mut palette = command_palette.read().clone();
+        match code {
+            KeyCode::Esc => palette.open = false,
+            KeyCode::Char('k') if modifiers.contains(KeyModifiers::CONTROL) => {
+                palette.open = false;
+            }
+            KeyCode::Up => palette.select_prev(),
+            KeyCode::Down => palette.select_next(),
+            KeyCode::Backspace => palette.pop_char(),
+            KeyCode::Char(c)
+                if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                palette.push_char(c);
+            }
+            KeyCode::Enter | KeyCode::Tab => {
+                let action = palette
+                    .selected_entry()
+                    .map(|entry| entry.action(code == KeyCode::Enter));
+                match action {
+                    Some(rustyclaw_view::PaletteAction::Run(cmd)) => {
+                        palette.open = false;
+                        scroll_offset.set(0);
+                        if let Ok(guard) = tx_for_keys.lock() {
+                            if let Some(ref tx) = *guard {
+                                let _ = tx.send(UserInput::Command(cmd));
+                            }
+                        }
+                    }
+                    Some(rustyclaw_view::PaletteAction::Insert(text)) => {
+                        palette.open = false;
+                        input_cursor_offset.set(text.len());
+                        input_value.set(text);
+                        command_completions.set(Vec::new());
+                        command_selected.set(None);
+                    }
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+        command_palette.set(palette);
+        return;
+    }
+
     // Services dialog: Esc to close
     if show_services_dialog.get() {
         if code == KeyCode::Esc {
@@ -271,10 +321,14 @@ pub(super) fn handle_normal_key(
                 let data = tool_perms_dialog_data.read();
                 if let Some(tool) = data.get(idx) {
                     let name = tool.name.clone();
+                    let permission = tool.permission.clone();
                     drop(data);
                     if let Ok(guard) = tx_for_keys.lock() {
                         if let Some(ref tx) = *guard {
-                            let _ = tx.send(UserInput::CycleToolPermission { name });
+                            let _ = tx.send(UserInput::CycleToolPermission {
+                                name,
+                                current_permission: permission,
+                            });
                         }
                     }
                 }
@@ -861,6 +915,18 @@ pub(super) fn handle_normal_key(
             }
             messages.set(m);
         }
+        KeyCode::Char('o') if modifiers.contains(KeyModifiers::CONTROL) => {
+            // Toggle the tool blocks of the selected turn, or of the most
+            // recent turn that ran tools when nothing is selected.
+            let mut m = messages.read().clone();
+            let idx = selected_message_idx
+                .get()
+                .or_else(|| m.iter().rposition(|msg| !msg.tool_calls.is_empty()));
+            if let Some(msg) = idx.and_then(|i| m.get_mut(i)) {
+                msg.toggle_tool_calls();
+            }
+            messages.set(m);
+        }
         KeyCode::Char('y') if modifiers.contains(KeyModifiers::CONTROL) => {
             let m = messages.read();
             let idx = selected_message_idx
@@ -933,6 +999,15 @@ pub(super) fn handle_normal_key(
                 messages.set(m2);
             }
         }
+        // Ctrl+K opens the command palette; the app side fills in the
+        // locally loaded skills before it appears.
+        KeyCode::Char('k') if modifiers.contains(KeyModifiers::CONTROL) => {
+            if let Ok(guard) = tx_for_keys.lock() {
+                if let Some(ref tx) = *guard {
+                    let _ = tx.send(UserInput::OpenCommandPalette);
+                }
+            }
+        }
         KeyCode::Char('h') if modifiers.contains(KeyModifiers::CONTROL) => {
             show_system_info.set(!show_system_info.get());
         }
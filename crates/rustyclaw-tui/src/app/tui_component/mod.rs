@@ -40,6 +40,8 @@ pub struct TuiRootProps {
     pub gateway_host: String,
     /// Gateway port extracted from config gateway_url (pre-fills pairing dialog).
     pub gateway_port: String,
+    /// When tool-call blocks start collapsed (from `[tool_display]` config).
+    pub tool_display: rustyclaw_core::config::ToolDisplayConfig,
 }
 
 // ── Static channels ─────────────────────────────────────────────────
@@ -78,6 +80,9 @@ pub fn TuiRoot(props: &TuiRootProps, mut hooks: Hooks) -> impl Into<AnyElement<'
     let tool_approval_id = hooks.use_state(String::new);
     let tool_approval_name = hooks.use_state(String::new);
     let tool_approval_args = hooks.use_state(String::new);
+    let tool_approval_summary = hooks.use_state(String::new);
+    let tool_approval_risks = hooks.use_state(Vec::new);
+    let tool_approval_can_remember = hooks.use_state(|| false);
     let tool_approval_selected = hooks.use_state(|| true); // true = Allow
 
     // ── Vault unlock dialog state ───────────────────────────────────
@@ -89,6 +94,7 @@ pub fn TuiRoot(props: &TuiRootProps, mut hooks: Hooks) -> impl Into<AnyElement<'
     // Start hidden; the shared view model reveals it after auth succeeds so it
     // never competes with the TOTP dialog for screen space.
     let needs_hatching = props.needs_hatching;
+    let tool_display = props.tool_display.clone();
     let hatching_dialog: State<rustyclaw_view::HatchingDialogData> =
         hooks.use_state(rustyclaw_view::HatchingDialogData::default);
 
@@ -214,6 +220,10 @@ pub fn TuiRoot(props: &TuiRootProps, mut hooks: Hooks) -> impl Into<AnyElement<'
     let show_services_dialog = hooks.use_state(|| false);
     let services_data: State<Option<rustyclaw_view::ServiceListData>> = hooks.use_state(|| None);
 
+    // ── Command palette (Ctrl+K) ────────────────────────────────────
+    let command_palette: State<rustyclaw_view::CommandPaletteData> =
+        hooks.use_state(rustyclaw_view::CommandPaletteData::default);
+
     // ── Channel access ──────────────────────────────────────────────
     let gw_rx: Arc<StdMutex<Option<sync_mpsc::Receiver<GwEvent>>>> =
         hooks.use_const(|| Arc::new(StdMutex::new(CHANNEL_RX.lock().unwrap().take())));
@@ -244,6 +254,9 @@ pub fn TuiRoot(props: &TuiRootProps, mut hooks: Hooks) -> impl Into<AnyElement<'
         tool_approval_id,
         tool_approval_name,
         tool_approval_args,
+        tool_approval_summary,
+        tool_approval_risks,
+        tool_approval_can_remember,
         tool_approval_selected,
         show_vault_unlock,
         vault_password,
@@ -334,6 +347,7 @@ pub fn TuiRoot(props: &TuiRootProps, mut hooks: Hooks) -> impl Into<AnyElement<'
         show_system_info,
         show_services_dialog,
         services_data,
+        command_palette,
     };
 
     // ── Poll gateway channel on a timer ─────────────────────────────
@@ -347,7 +361,13 @@ pub fn TuiRoot(props: &TuiRootProps, mut hooks: Hooks) -> impl Into<AnyElement<'
                 if let Ok(guard) = rx_handle.lock() {
                     if let Some(ref rx) = *guard {
                         while let Ok(ev) = rx.try_recv() {
-                            events::apply_gw_event(ev, ui, needs_hatching, &tx_for_history);
+                            events::apply_gw_event(
+                                ev,
+                                ui,
+                                needs_hatching,
+                                &tool_display,
+                                &tx_for_history,
+                            );
                         }
                     }
                 }
@@ -459,6 +479,7 @@ pub fn TuiRoot(props: &TuiRootProps, mut hooks: Hooks) -> impl Into<AnyElement<'
                 && !show_pairing.get()
                 && !show_system_info.get()
                 && !show_services_dialog.get()
+                && !command_palette.read().open
                 && !tab_focused.get(),
             on_change: move |_new_val: String| {},
             on_submit: move |_val: String| {
@@ -489,6 +510,9 @@ pub fn TuiRoot(props: &TuiRootProps, mut hooks: Hooks) -> impl Into<AnyElement<'
                 id: tool_approval_id.read().clone(),
                 name: tool_approval_name.read().clone(),
                 arguments: tool_approval_args.read().clone(),
+                description: tool_approval_summary.read().clone(),
+                risks: tool_approval_risks.read().clone(),
+                can_remember: tool_approval_can_remember.get(),
                 selected_allow: tool_approval_selected.get(),
             },
             show_vault_unlock: show_vault_unlock.get(),
@@ -579,6 +603,7 @@ pub fn TuiRoot(props: &TuiRootProps, mut hooks: Hooks) -> impl Into<AnyElement<'
             load_status: load_status.read().clone(),
             show_services_dialog: show_services_dialog.get(),
             services_data: services_data.read().clone(),
+            command_palette: command_palette.read().clone(),
             show_pairing: show_pairing.get(),
             pairing: rustyclaw_view::PairingDialogData {
                 step: *pairing_step.read(),
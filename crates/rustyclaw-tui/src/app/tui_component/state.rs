@@ -36,6 +36,9 @@ pub(super) struct Ui {
     pub tool_approval_id: State<String>,
     pub tool_approval_name: State<String>,
     pub tool_approval_args: State<String>,
+    pub tool_approval_summary: State<String>,
+    pub tool_approval_risks: State<Vec<rustyclaw_core::tools::ToolRisk>>,
+    pub tool_approval_can_remember: State<bool>,
     pub tool_approval_selected: State<bool>,
     pub show_vault_unlock: State<bool>,
     pub vault_password: State<String>,
@@ -126,4 +129,5 @@ pub(super) struct Ui {
     pub show_system_info: State<bool>,
     pub show_services_dialog: State<bool>,
     pub services_data: State<Option<rustyclaw_view::ServiceListData>>,
+    pub command_palette: State<rustyclaw_view::CommandPaletteData>,
 }
@@ -0,0 +1,183 @@
+// ── Command palette — fuzzy finder for commands, tools and skills ───────────
+
+use crate::theme;
+use iocraft::prelude::*;
+use rustyclaw_view::{CommandPaletteData, PaletteKind};
+
+#[derive(Default, Props)]
+pub struct CommandPaletteDialogProps {
+    /// Shared palette data from `rustyclaw-view`.
+    pub data: CommandPaletteData,
+}
+
+#[component]
+pub fn CommandPaletteDialog(props: &CommandPaletteDialogProps) -> impl Into<AnyElement<'static>> {
+    let max_visible = 12usize;
+    let data = &props.data;
+    let window = data.visible_window(max_visible);
+    let sel = data.selected.unwrap_or(0);
+
+    let rows: Vec<AnyElement> = if data.filtered.is_empty() {
+        vec![
+            element! {
+                Text(content: "  Nothing matches.", color: theme::MUTED)
+            }
+            .into_any(),
+        ]
+    } else {
+        data.filtered[window.clone()]
+            .iter()
+            .enumerate()
+            .map(|(i, &idx)| {
+                let entry = &data.entries[idx];
+                let real_i = window.start + i;
+                let is_selected = real_i == sel;
+                let pointer = if is_selected { "▸ " } else { "  " };
+                let (fg, bg) = if is_selected {
+                    (theme::BG_MAIN, theme::ACCENT_BRIGHT)
+                } else {
+                    (theme::TEXT, Color::Reset)
+                };
+                let tag_color = if is_selected {
+                    theme::BG_MAIN
+                } else {
+                    match entry.kind {
+                        PaletteKind::Command => theme::ACCENT,
+                        PaletteKind::Tool => theme::INFO,
+                        PaletteKind::Skill => theme::SUCCESS,
+                    }
+                };
+                let desc = entry
+                    .description
+                    .as_deref()
+                    .map(|d| format!(" — {}", d))
+                    .unwrap_or_default();
+                element! {
+                    View(
+                        key: real_i as u64,
+                        width: 100pct,
+                        flex_direction: FlexDirection::Row,
+                        background_color: bg,
+                    ) {
+                        Text(
+                            content: format!("{}{:<8}", pointer, entry.category),
+                            color: tag_color,
+                            wrap: TextWrap::NoWrap,
+                        )
+                        Text(content: entry.label.clone(), color: fg, wrap: TextWrap::NoWrap)
+                        Text(
+                            content: desc,
+                            color: if is_selected { theme::BG_MAIN } else { theme::MUTED },
+                            wrap: TextWrap::NoWrap,
+                        )
+                    }
+                }
+                .into_any()
+            })
+            .collect()
+    };
+
+    // Detail pane: what the selection does, and a tool's parameters.
+    let mut detail: Vec<AnyElement> = Vec::new();
+    if let Some(entry) = data.selected_entry() {
+        let usage = match entry.kind {
+            PaletteKind::Command => format!(
+                "Enter runs {}  ·  Tab inserts it to add arguments",
+                entry.label
+            ),
+            PaletteKind::Tool => "Enter drafts a prompt asking for this tool".to_string(),
+            PaletteKind::Skill => "Enter drafts a prompt asking for this skill".to_string(),
+        };
+        detail.push(element! { View(height: 1) }.into_any());
+        detail.push(element! { Text(content: usage, color: theme::TEXT_DIM) }.into_any());
+        if entry.kind == PaletteKind::Tool {
+            if entry.params.is_empty() {
+                detail.push(
+                    element! { Text(content: "No parameters.", color: theme::MUTED) }.into_any(),
+                );
+            } else {
+                detail.push(
+                    element! {
+                        Text(
+                            content: "Parameters (* required):",
+                            color: theme::ACCENT_BRIGHT,
+                            weight: Weight::Bold,
+                        )
+                    }
+                    .into_any(),
+                );
+                for p in &entry.params {
+                    let name = format!("  {}{} ", p.name, if p.required { "*" } else { "" });
+                    detail.push(
+                        element! {
+                            View(flex_direction: FlexDirection::Row) {
+                                Text(content: name, color: theme::ACCENT, wrap: TextWrap::NoWrap)
+                                Text(
+                                    content: format!("({}) ", p.param_type),
+                                    color: theme::MUTED,
+                                    wrap: TextWrap::NoWrap,
+                                )
+                                Text(content: p.description.clone(), color: theme::TEXT_DIM)
+                            }
+                        }
+                        .into_any(),
+                    );
+                }
+            }
+        }
+    }
+
+    element! {
+        View(
+            width: 100pct,
+            height: 100pct,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+        ) {
+            View(
+                width: 80pct,
+                max_height: 90pct,
+                flex_direction: FlexDirection::Column,
+                border_style: BorderStyle::Round,
+                border_color: theme::ACCENT_BRIGHT,
+                background_color: theme::BG_SURFACE,
+                padding_left: 2,
+                padding_right: 2,
+                padding_top: 1,
+                padding_bottom: 1,
+                overflow: Overflow::Hidden,
+            ) {
+                View(flex_direction: FlexDirection::Row) {
+                    Text(
+                        content: "🔍 Command palette  ",
+                        color: theme::ACCENT_BRIGHT,
+                        weight: Weight::Bold,
+                    )
+                    Text(
+                        content: format!("{} of {}", data.filtered.len(), data.entries.len()),
+                        color: theme::MUTED,
+                    )
+                }
+                View(height: 1)
+                View(flex_direction: FlexDirection::Row) {
+                    Text(content: "› ", color: theme::ACCENT_BRIGHT)
+                    Text(content: format!("{}▏", data.query), color: theme::TEXT)
+                }
+                View(height: 1)
+                #(rows)
+                #(detail)
+                View(height: 1)
+                View(flex_direction: FlexDirection::Row) {
+                    Text(content: "↑↓ ", color: theme::ACCENT_BRIGHT)
+                    Text(content: "navigate  ", color: theme::MUTED)
+                    Text(content: "Enter ", color: theme::ACCENT_BRIGHT)
+                    Text(content: "run/insert  ", color: theme::MUTED)
+                    Text(content: "Tab ", color: theme::ACCENT_BRIGHT)
+                    Text(content: "insert  ", color: theme::MUTED)
+                    Text(content: "Esc ", color: theme::ACCENT_BRIGHT)
+                    Text(content: "close", color: theme::MUTED)
+                }
+            }
+        }
+    }
+}
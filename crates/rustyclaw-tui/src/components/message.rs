@@ -27,8 +27,11 @@ pub fn MessageBubble(props: &MessageBubbleProps) -> impl Into<AnyElement<'static
     } else {
         // display_content() handles thinking-message truncation;
         // for uncollapsed non-markdown messages fall back to it so
-        // that behaviour is preserved.
-        if props.data.collapsed && props.data.is_collapsible() {
+        // that behaviour is preserved.  Thinking bubbles expand with
+        // Ctrl+E, so content_for_render() decides for them.
+        if props.data.role == MessageRole::Thinking
+            || (props.data.collapsed && props.data.is_collapsible())
+        {
             render_content.into_owned()
         } else {
             props.data.display_content().into_owned()
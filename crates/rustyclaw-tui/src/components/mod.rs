@@ -4,6 +4,7 @@ pub mod approvals_dialog;
 pub mod auth_dialog;
 pub mod channels_dialog;
 pub mod command_menu;
+pub mod command_palette_dialog;
 pub mod credential_request_dialog;
 pub mod cron_dialog;
 pub mod details_dialog;
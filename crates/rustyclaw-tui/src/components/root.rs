@@ -8,6 +8,7 @@ use iocraft::prelude::*;
 use crate::components::api_key_dialog::ApiKeyDialog;
 use crate::components::auth_dialog::AuthDialog;
 use crate::components::command_menu::CommandMenu;
+use crate::components::command_palette_dialog::CommandPaletteDialog;
 use crate::components::credential_request_dialog::CredentialRequestDialog;
 use crate::components::details_dialog::DetailsDialog;
 use crate::components::device_flow_dialog::DeviceFlowDialog;
@@ -164,6 +165,9 @@ pub struct RootProps {
     // services dialog overlay (Ctrl-J)
     pub show_services_dialog: bool,
     pub services_data: Option<rustyclaw_view::ServiceListData>,
+
+    // command palette overlay (Ctrl-K)
+    pub command_palette: rustyclaw_view::CommandPaletteData,
 }
 
 #[component]
@@ -232,6 +236,10 @@ pub fn Root(props: &mut RootProps) -> impl Into<AnyElement<'static>> {
     let show_services = props.show_services_dialog;
     let services = props.services_data.clone();
 
+    // Command palette state
+    let palette = std::mem::take(&mut props.command_palette);
+    let show_palette = palette.open;
+
     element! {
         View(
             width: props.width,
@@ -644,6 +652,23 @@ pub fn Root(props: &mut RootProps) -> impl Into<AnyElement<'static>> {
             } else {
                 element! { View() }.into_any()
             })
+
+            // ── Command palette overlay (Ctrl-K) ────────────────────────
+            #(if show_palette {
+                element! {
+                    View(
+                        width: props.width,
+                        height: props.height,
+                        position: Position::Absolute,
+                        top: 0,
+                        left: 0,
+                    ) {
+                        CommandPaletteDialog(data: palette)
+                    }
+                }.into_any()
+            } else {
+                element! { View() }.into_any()
+            })
         }
     }
 }
@@ -33,6 +33,12 @@ pub fn ToolApprovalDialog(props: &ToolApprovalDialogProps) -> impl Into<AnyEleme
         "▸ "
     };
     let args_display = props.data.arguments_preview(300, 12);
+    let risk_line = props.data.risk_line();
+    let hint = if props.data.can_remember {
+        "y allow · a for session · n deny · Enter confirm"
+    } else {
+        "y allow · n/Esc deny · Tab toggle · Enter confirm"
+    };
 
     element! {
         View(
@@ -61,12 +67,23 @@ pub fn ToolApprovalDialog(props: &ToolApprovalDialogProps) -> impl Into<AnyEleme
 
                 View(height: 1)
 
-                // Tool name
+                // Tool name and what it does
                 Text(
                     content: format!("Tool: {}", props.data.name),
                     color: theme::TEXT,
                     weight: Weight::Bold,
                 )
+                Text(
+                    content: props.data.description.clone(),
+                    color: theme::MUTED,
+                )
+                #(if let Some(line) = risk_line {
+                    element! {
+                        Text(content: line, color: theme::WARN)
+                    }.into_any()
+                } else {
+                    element! { View() }.into_any()
+                })
 
                 View(height: 1)
 
@@ -104,7 +121,7 @@ pub fn ToolApprovalDialog(props: &ToolApprovalDialogProps) -> impl Into<AnyEleme
 
                 // Hint
                 Text(
-                    content: "y allow · n/Esc deny · Tab toggle · Enter confirm",
+                    content: hint,
                     color: theme::MUTED,
                 )
             }
@@ -15,15 +15,27 @@ pub fn ToolCallPanel(props: &ToolCallPanelProps) -> impl Into<AnyElement<'static
         theme::INFO
     };
 
-    // Collapsed (default) → single dim line: header + status + arg/result peek.
+    // Collapsed → single dim line: header + status + size + arg/result peek.
     // Expanded → header line plus truncated args and result beneath.
+    // Ctrl+O toggles the blocks of the selected (or latest) turn.
     let collapsed = props.data.collapsed;
 
+    let size = props
+        .data
+        .result_size_label()
+        .map(|s| format!(" · {s}"))
+        .unwrap_or_default();
+    let toggle_hint = if collapsed && props.data.result.is_some() {
+        "  [Ctrl+O]"
+    } else {
+        ""
+    };
     let header = format!(
-        "{} · {} {}",
+        "{} · {} {}{}",
         props.data.summary(),
         status_icon,
-        status_label
+        status_label,
+        size
     );
 
     // Short inline peek shown when collapsed so the row is one line but still
@@ -62,7 +74,7 @@ pub fn ToolCallPanel(props: &ToolCallPanelProps) -> impl Into<AnyElement<'static
             flex_direction: FlexDirection::Column,
         ) {
             Text(
-                content: format!("{header}{peek}"),
+                content: format!("{header}{peek}{toggle_hint}"),
                 color,
                 weight: if collapsed { Weight::Normal } else { Weight::Bold },
             )
@@ -105,6 +105,10 @@ pub async fn prompt_and_connect(
         "  {}",
         t::muted("RustyClaw connects to your gateway over SSH (default port 2222).")
     );
+    println!(
+        "  {}",
+        t::muted("On the gateway's host, unix:/path/to/gateway.sock skips SSH.")
+    );
     println!(
         "  {}",
         t::muted("Press Enter to accept the default, or type a new URL.")
@@ -52,7 +52,7 @@ pub(crate) fn gateway_event_to_gw_event(event: GatewayEvent) -> Option<GwEvent>
         // ── Streaming ───────────────────────────────────────────────────
         E::StreamStart => GwEvent::StreamStart,
         E::ThinkingStart => GwEvent::ThinkingStart,
-        E::ThinkingDelta => GwEvent::ThinkingDelta,
+        E::ThinkingDelta { delta } => GwEvent::ThinkingDelta(delta),
         E::ThinkingEnd => GwEvent::ThinkingEnd,
         E::Chunk { delta } => GwEvent::Chunk(delta),
         E::ResponseDone => GwEvent::ResponseDone,
@@ -82,10 +82,16 @@ pub(crate) fn gateway_event_to_gw_event(event: GatewayEvent) -> Option<GwEvent>
             id,
             name,
             arguments,
+            summary,
+            risks,
+            can_remember,
         } => GwEvent::ToolApprovalRequest {
             id,
             name,
             arguments,
+            summary,
+            risks,
+            can_remember,
         },
 
         // ── Interactive prompts ─────────────────────────────────────────
@@ -178,13 +184,18 @@ pub(crate) fn gateway_event_to_gw_event(event: GatewayEvent) -> Option<GwEvent>
         },
 
         // ── Generic messages ────────────────────────────────────────────
-        E::Error { message } => GwEvent::error(message),
+        E::Error { message, .. } => GwEvent::error(message),
         E::Info { message } => GwEvent::Info(message),
         E::Warning { message } => GwEvent::warning(message),
 
         // DOM queries require a webview; the TUI cannot evaluate JS.
         E::DomQuery { .. } => return None,
 
+        // Only sent to clients that announce `request_ids`; the TUI doesn't.
+        E::RequestStart { .. } => return None,
+        // Likewise `resume_turns`.
+        E::TurnStarted { .. } => return None,
+
         // ── Kernel awareness ────────────────────────────────────────────
         E::HostInfo {
             hostname,
@@ -444,6 +455,71 @@ pub(crate) fn gateway_event_to_gw_event(event: GatewayEvent) -> Option<GwEvent>
             ok,
             message,
         },
+
+        E::ToolPermissions { ok, tools, message } => {
+            if !ok {
+                return Some(GwEvent::error(
+                    message.unwrap_or_else(|| "Failed to update tool permission".to_string()),
+                ));
+            }
+            let tools = tools
+                .into_iter()
+                .map(|t| {
+                    let badge =
+                        rustyclaw_core::tools::ToolPermission::from_kind(&t.permission, t.skills)
+                            .map(|p| p.badge())
+                            .unwrap_or("ALLOW");
+                    rustyclaw_view::ToolPermInfoData {
+                        name: t.name,
+                        permission: badge.to_string(),
+                        summary: t.summary,
+                    }
+                })
+                .collect();
+            GwEvent::ShowToolPerms { tools }
+        }
+
+        E::SessionExport {
+            session_id,
+            ok,
+            format,
+            content,
+            error,
+        } => {
+            if !ok {
+                return Some(GwEvent::error(
+                    error.unwrap_or_else(|| "Failed to export session".to_string()),
+                ));
+            }
+            GwEvent::Info(format!(
+                "Exported session {} as {} ({} bytes)",
+                session_id,
+                format,
+                content.len()
+            ))
+        }
+
+        E::SandboxPolicy { sandbox, tools } => {
+            let denied = tools.iter().filter(|t| t.permission == "deny").count();
+            GwEvent::Info(format!(
+                "Sandbox: {} (effective {}), network {}, {} denied paths; {}/{} tools denied",
+                sandbox.mode,
+                sandbox.effective_mode,
+                sandbox.network,
+                sandbox.deny_read.len(),
+                denied,
+                tools.len()
+            ))
+        }
+        E::GlobalTasks { tasks } => {
+            let sessions: std::collections::BTreeSet<_> =
+                tasks.iter().filter_map(|t| t.session.as_deref()).collect();
+            GwEvent::Info(format!(
+                "Gateway tasks: {} across {} sessions",
+                tasks.len(),
+                sessions.len()
+            ))
+        }
     };
 
     Some(ev)
@@ -453,7 +529,7 @@ pub(crate) fn gateway_event_to_gw_event(event: GatewayEvent) -> Option<GwEvent>
 mod tests {
     use super::*;
     use rustyclaw_core::gateway::{
-        SecretEntryDto, ServerFrame, ServerFrameType, ServerPayload, StatusType,
+        ErrorCode, SecretEntryDto, ServerFrame, ServerFrameType, ServerPayload, StatusType,
     };
 
     /// Run a server frame through the shared parser and the TUI adapter, the
@@ -472,6 +548,7 @@ mod tests {
                 vault_locked: false,
                 provider: None,
                 model: None,
+                capabilities: Default::default(),
             },
         };
         assert!(matches!(adapt(frame), Some(GwEvent::Connected)));
@@ -537,6 +614,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn tool_approval_frame_keeps_context() {
+        use rustyclaw_core::tools::ToolRisk;
+
+        let frame = ServerFrame {
+            frame_type: ServerFrameType::ToolApprovalRequest,
+            payload: ServerPayload::ToolApprovalRequest {
+                id: "call_002".into(),
+                name: "web_fetch".into(),
+                arguments: r#"{"token":"<redacted len=12>"}"#.into(),
+                summary: "Fetch content from URLs".into(),
+                risks: vec![ToolRisk::Network],
+                can_remember: true,
+            },
+        };
+        match adapt(frame) {
+            Some(GwEvent::ToolApprovalRequest {
+                arguments,
+                summary,
+                risks,
+                can_remember,
+                ..
+            }) => {
+                assert!(arguments.contains("<redacted len=12>"), "{arguments}");
+                assert_eq!(summary, "Fetch content from URLs");
+                assert_eq!(risks, vec![ToolRisk::Network]);
+                assert!(can_remember);
+            }
+            other => panic!("expected ToolApprovalRequest, got {other:?}"),
+        }
+    }
+
     #[test]
     fn error_frame_maps_to_error() {
         let frame = ServerFrame {
@@ -544,6 +653,7 @@ mod tests {
             payload: ServerPayload::Error {
                 ok: false,
                 message: "Connection failed".into(),
+                code: ErrorCode::ModelUnreachable,
             },
         };
         match adapt(frame) {
@@ -598,10 +708,60 @@ mod tests {
         };
         assert!(matches!(adapt(thinking), Some(GwEvent::ThinkingStart)));
 
+        let reasoning = ServerFrame {
+            frame_type: ServerFrameType::ThinkingDelta,
+            payload: ServerPayload::ThinkingDelta {
+                delta: "Check units.".into(),
+            },
+        };
+        assert!(matches!(
+            adapt(reasoning),
+            Some(GwEvent::ThinkingDelta(d)) if d == "Check units."
+        ));
+
         let done = ServerFrame {
             frame_type: ServerFrameType::ResponseDone,
             payload: ServerPayload::ResponseDone { ok: true },
         };
         assert!(matches!(adapt(done), Some(GwEvent::ResponseDone)));
     }
+
+    #[test]
+    fn tool_permissions_frame_maps_to_dialog_badges() {
+        use rustyclaw_core::gateway::protocol::frames::ToolPermissionDto;
+
+        let frame = ServerFrame {
+            frame_type: ServerFrameType::ToolPermissionsResult,
+            payload: ServerPayload::ToolPermissionsResult {
+                ok: true,
+                tools: vec![ToolPermissionDto {
+                    name: "execute_command".into(),
+                    permission: "skill_only".into(),
+                    skills: vec!["deploy".into()],
+                    summary: "Run shell commands".into(),
+                }],
+                message: None,
+            },
+        };
+        match adapt(frame) {
+            Some(GwEvent::ShowToolPerms { tools }) => {
+                assert_eq!(tools.len(), 1);
+                assert_eq!(tools[0].permission, "SKILL");
+            }
+            other => panic!("expected ShowToolPerms, got {other:?}"),
+        }
+
+        let failed = ServerFrame {
+            frame_type: ServerFrameType::ToolPermissionsResult,
+            payload: ServerPayload::ToolPermissionsResult {
+                ok: false,
+                tools: vec![],
+                message: Some("Unknown tool: nope".into()),
+            },
+        };
+        assert!(!matches!(
+            adapt(failed),
+            Some(GwEvent::ShowToolPerms { .. })
+        ));
+    }
 }
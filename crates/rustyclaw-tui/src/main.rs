@@ -35,7 +35,7 @@ use app::App;
 struct Cli {
     #[command(flatten)]
     common: CommonArgs,
-    /// Gateway WebSocket URL (overrides config)
+    /// Gateway URL, ssh://host:port or unix:/path (overrides config)
     #[arg(long = "url", value_name = "URL")]
     url: Option<String>,
     /// Vault password (forwarded to the gateway after connect if the vault is locked)
@@ -44,6 +44,14 @@ struct Cli {
     /// Skip the interactive connection dialog and use the saved/default gateway URL.
     #[arg(long = "no-dialog", alias = "auto-connect")]
     no_dialog: bool,
+    /// Use this provider/model for the session instead of the configured one
+    /// (e.g. anthropic/claude-haiku-4-5). Not saved.
+    #[arg(long, value_name = "PROVIDER/MODEL", value_parser = parse_model)]
+    model: Option<(String, String)>,
+}
+
+fn parse_model(spec: &str) -> Result<(String, String), String> {
+    rustyclaw_core::providers::parse_model_spec(spec)
 }
 
 #[tokio::main]
@@ -66,6 +74,7 @@ async fn main() -> Result<()> {
     let config_path = cli.common.config_path();
     let mut config = Config::load(config_path)?;
     cli.common.apply_overrides(&mut config);
+    rustyclaw_core::log_rotation::init(&config.log_rotation);
 
     if let Some(url) = &cli.url {
         config.gateway_url = Some(url.clone());
@@ -79,6 +88,9 @@ async fn main() -> Result<()> {
         app.set_deferred_vault_password(pw);
     }
     app.set_skip_connection_dialog(cli.no_dialog);
+    if let Some((provider, model)) = cli.model {
+        app.set_session_model(provider, model);
+    }
     app.run().await?;
 
     Ok(())
@@ -1,12 +1,47 @@
-//! Component data for the command palette (Ctrl+Shift+P / Cmd+K style).
+//! Component data for the command palette (Ctrl+K).
 //!
 //! Generalizes the slash-command menu into a full command palette with
-//! fuzzy matching, categories, and shortcut display.
+//! fuzzy matching, categories, and shortcut display.  Entries cover the
+//! slash-commands, the agent's tools (with their parameters) and the
+//! loaded skills.
+
+use crate::SkillInfoData;
+
+/// What a palette entry refers to, which decides what selecting it does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteKind {
+    /// A slash-command; Enter runs it.
+    Command,
+    /// An agent tool; selecting it drafts a prompt that asks for it.
+    Tool,
+    /// A skill; selecting it drafts a prompt that asks for it.
+    Skill,
+}
+
+impl PaletteKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Command => "Command",
+            Self::Tool => "Tool",
+            Self::Skill => "Skill",
+        }
+    }
+}
+
+/// One parameter of a tool, as shown in the palette's detail pane.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaletteParam {
+    pub name: String,
+    /// JSON Schema type, e.g. `"string"`.
+    pub param_type: String,
+    pub required: bool,
+    pub description: String,
+}
 
 /// A single palette action/entry.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PaletteEntry {
-    /// Unique action identifier (e.g. "open.cron", "toggle.dark-mode").
+    /// Unique action identifier (e.g. "command:help", "tool:read_file").
     pub id: String,
     /// Display label.
     pub label: String,
@@ -16,6 +51,140 @@ pub struct PaletteEntry {
     pub shortcut: Option<String>,
     /// Optional description.
     pub description: Option<String>,
+    pub kind: PaletteKind,
+    /// Tool parameters; empty for commands and skills.
+    pub params: Vec<PaletteParam>,
+}
+
+/// What the client should do with the selected entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PaletteAction {
+    /// Run this slash-command (without the leading `/`).
+    Run(String),
+    /// Put this text in the input box for the user to finish.
+    Insert(String),
+}
+
+impl PaletteEntry {
+    /// A slash-command, e.g. `"thread new"`.
+    pub fn command(name: &str) -> Self {
+        Self {
+            id: format!("command:{name}"),
+            label: format!("/{name}"),
+            category: PaletteKind::Command.label().to_string(),
+            shortcut: None,
+            description: None,
+            kind: PaletteKind::Command,
+            params: Vec::new(),
+        }
+    }
+
+    /// An agent tool with its summary and parameters.
+    pub fn tool(name: &str) -> Self {
+        let params = rustyclaw_core::tools::tool_params(name)
+            .into_iter()
+            .map(|p| PaletteParam {
+                name: p.name,
+                param_type: p.param_type,
+                required: p.required,
+                description: p.description,
+            })
+            .collect();
+        Self {
+            id: format!("tool:{name}"),
+            label: name.to_string(),
+            category: PaletteKind::Tool.label().to_string(),
+            shortcut: None,
+            description: Some(rustyclaw_core::tools::tool_summary(name).to_string()),
+            kind: PaletteKind::Tool,
+            params,
+        }
+    }
+
+    pub fn skill(skill: &SkillInfoData) -> Self {
+        let mut description = skill.description.clone();
+        if !skill.enabled {
+            description = format!("(disabled) {description}").trim_end().to_string();
+        }
+        Self {
+            id: format!("skill:{}", skill.name),
+            label: skill.name.clone(),
+            category: PaletteKind::Skill.label().to_string(),
+            shortcut: None,
+            description: (!description.is_empty()).then_some(description),
+            kind: PaletteKind::Skill,
+            params: Vec::new(),
+        }
+    }
+
+    /// The action for Enter (`run = true`) or Tab (`run = false`).
+    ///
+    /// Only commands run; Tab puts a command in the input so arguments
+    /// can be added.  Tools and skills always become a draft prompt.
+    pub fn action(&self, run: bool) -> PaletteAction {
+        match self.kind {
+            PaletteKind::Command => {
+                let name = self.label.trim_start_matches('/');
+                if run {
+                    PaletteAction::Run(name.to_string())
+                } else {
+                    PaletteAction::Insert(format!("/{name} "))
+                }
+            }
+            PaletteKind::Tool => {
+                PaletteAction::Insert(format!("Use the `{}` tool to ", self.label))
+            }
+            PaletteKind::Skill => {
+                PaletteAction::Insert(format!("Use the `{}` skill to ", self.label))
+            }
+        }
+    }
+}
+
+/// Build the palette from the slash-commands, every registered tool and
+/// the given skills.
+///
+/// `/model <id>` completions are left out: there are hundreds of them and
+/// the slash menu already offers them once `/model` is typed.
+pub fn build_palette_entries(skills: &[SkillInfoData]) -> Vec<PaletteEntry> {
+    let commands = rustyclaw_core::commands::command_names()
+        .into_iter()
+        .filter(|c| !c.starts_with("model "))
+        .map(|c| PaletteEntry::command(&c));
+    let tools = rustyclaw_core::tools::all_tool_names()
+        .into_iter()
+        .map(PaletteEntry::tool);
+    let skills = skills.iter().map(PaletteEntry::skill);
+    commands.chain(tools).chain(skills).collect()
+}
+
+/// Score `text` against `query` as a case-insensitive subsequence match.
+///
+/// Returns `None` when the characters of `query` don't all appear in
+/// order.  Consecutive matches and matches at the start of a word score
+/// higher, so `rf` ranks `read_file` above `search_files`.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut prev: Option<char> = None;
+    let mut prev_matched = false;
+    for c in text.chars().flat_map(char::to_lowercase) {
+        let Some(&want) = query.peek() else { break };
+        let matched = c == want;
+        if matched {
+            query.next();
+            score += 1;
+            if prev_matched {
+                score += 5;
+            }
+            if prev.is_none_or(|p| matches!(p, ' ' | '_' | '-' | '/' | '.' | ':')) {
+                score += 8;
+            }
+        }
+        prev_matched = matched;
+        prev = Some(c);
+    }
+    query.peek().is_none().then_some(score)
 }
 
 /// Full state for the command palette.
@@ -29,19 +198,41 @@ pub struct CommandPaletteData {
 }
 
 impl CommandPaletteData {
-    /// Filter entries by the current query (fuzzy match on label).
+    /// Open the palette over `entries` with an empty query.
+    pub fn open_with(entries: Vec<PaletteEntry>) -> Self {
+        let mut palette = Self {
+            open: true,
+            entries,
+            ..Self::default()
+        };
+        palette.update_filter();
+        palette
+    }
+
+    /// Filter entries by the current query.
+    ///
+    /// Entries are fuzzy-matched on their label and description; label
+    /// matches rank ahead of description-only matches, then by score.
     pub fn update_filter(&mut self) {
         if self.query.is_empty() {
             self.filtered = (0..self.entries.len()).collect();
         } else {
-            let q = self.query.to_lowercase();
-            self.filtered = self
+            let q = self.query.as_str();
+            let mut ranked: Vec<((bool, u32), usize)> = self
                 .entries
                 .iter()
                 .enumerate()
-                .filter(|(_, e)| e.label.to_lowercase().contains(&q))
-                .map(|(i, _)| i)
+                .filter_map(|(i, e)| {
+                    let label = fuzzy_score(q, e.label.trim_start_matches('/'));
+                    let rank = match label {
+                        Some(score) => (true, score),
+                        None => (false, fuzzy_score(q, e.description.as_deref()?)?),
+                    };
+                    Some((rank, i))
+                })
                 .collect();
+            ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            self.filtered = ranked.into_iter().map(|(_, i)| i).collect();
         }
         self.selected = if self.filtered.is_empty() {
             None
@@ -50,6 +241,18 @@ impl CommandPaletteData {
         };
     }
 
+    /// Append a character to the query and re-filter.
+    pub fn push_char(&mut self, c: char) {
+        self.query.push(c);
+        self.update_filter();
+    }
+
+    /// Remove the last query character and re-filter.
+    pub fn pop_char(&mut self) {
+        self.query.pop();
+        self.update_filter();
+    }
+
     /// Get the selected entry.
     pub fn selected_entry(&self) -> Option<&PaletteEntry> {
         self.selected
@@ -75,6 +278,19 @@ impl CommandPaletteData {
         self.selected = Some(if cur == 0 { max } else { cur - 1 });
     }
 
+    /// Range of `filtered` to show so the selected row stays visible.
+    pub fn visible_window(&self, max_visible: usize) -> std::ops::Range<usize> {
+        let total = self.filtered.len();
+        if total <= max_visible {
+            return 0..total;
+        }
+        let selected = self.selected.unwrap_or(0).min(total - 1);
+        let start = selected
+            .saturating_sub(max_visible / 2)
+            .min(total - max_visible);
+        start..start + max_visible
+    }
+
     pub fn is_open(&self) -> bool {
         self.open
     }
@@ -141,3 +357,75 @@ impl ZoomState {
         format!("{}%", (self.factor * 100.0).round() as u32)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(palette: &CommandPaletteData) -> Vec<&str> {
+        palette
+            .filtered
+            .iter()
+            .map(|&i| palette.entries[i].label.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn fuzzy_filter_ranks_word_starts_and_falls_back_to_descriptions() {
+        assert!(fuzzy_score("rdfl", "read_file").is_some());
+        assert!(fuzzy_score("fr", "read_file").is_none());
+        assert!(fuzzy_score("rf", "read_file") > fuzzy_score("rf", "search_files"));
+
+        let mut palette = CommandPaletteData::open_with(vec![
+            PaletteEntry::command("clear"),
+            PaletteEntry::tool("search_files"),
+            PaletteEntry::tool("read_file"),
+            PaletteEntry::tool("web_fetch"),
+        ]);
+        assert_eq!(labels(&palette).len(), 4);
+        for c in "rf".chars() {
+            palette.push_char(c);
+        }
+        assert_eq!(labels(&palette), ["read_file", "search_files"]);
+
+        // "url" only appears in web_fetch's summary.
+        palette.query = "url".into();
+        palette.update_filter();
+        assert_eq!(labels(&palette), ["web_fetch"]);
+
+        palette.query = "zzz".into();
+        palette.update_filter();
+        assert!(palette.selected_entry().is_none());
+    }
+
+    #[test]
+    fn palette_lists_capabilities_and_describes_selection() {
+        let skills = [SkillInfoData {
+            name: "weather-report".into(),
+            description: "Daily forecast".into(),
+            enabled: true,
+        }];
+        let entries = build_palette_entries(&skills);
+        let find = |id: &str| entries.iter().find(|e| e.id == id).unwrap();
+
+        let help = find("command:help");
+        assert_eq!(help.action(true), PaletteAction::Run("help".into()));
+        assert_eq!(help.action(false), PaletteAction::Insert("/help ".into()));
+        assert!(!entries.iter().any(|e| e.label.starts_with("/model ")));
+
+        let read = find("tool:read_file");
+        assert_eq!(
+            read.description.as_deref(),
+            Some("Read files on your computer")
+        );
+        assert!(read.params.iter().any(|p| p.name == "path" && p.required));
+        assert_eq!(
+            read.action(true),
+            PaletteAction::Insert("Use the `read_file` tool to ".into())
+        );
+
+        let skill = find("skill:weather-report");
+        assert_eq!(skill.kind, PaletteKind::Skill);
+        assert_eq!(skill.description.as_deref(), Some("Daily forecast"));
+    }
+}
@@ -1,5 +1,6 @@
 //! Shared conversation display models used by UI renderers.
 
+use rustyclaw_core::config::ToolDisplayConfig;
 use rustyclaw_core::types::MessageRole;
 use rustyclaw_core::ui::{StreamingState, ThreadInfo};
 
@@ -103,6 +104,27 @@ impl DisplayMessageData {
         }
     }
 
+    /// Apply the tool-block display policy to every finished tool call.
+    pub fn apply_tool_display(&mut self, policy: &ToolDisplayConfig) {
+        for tc in &mut self.tool_calls {
+            tc.apply_display_policy(policy);
+        }
+    }
+
+    /// Expand every tool block if any is collapsed, otherwise collapse
+    /// them all. Returns the new collapsed state, or `None` when the
+    /// message has no tool calls.
+    pub fn toggle_tool_calls(&mut self) -> Option<bool> {
+        if self.tool_calls.is_empty() {
+            return None;
+        }
+        let collapse = self.tool_calls.iter().all(|tc| !tc.collapsed);
+        for tc in &mut self.tool_calls {
+            tc.collapsed = collapse;
+        }
+        Some(collapse)
+    }
+
     pub const AUTO_COLLAPSE_LINES: usize = 40;
     pub const AUTO_COLLAPSE_CHARS: usize = 2000;
 
@@ -372,3 +394,62 @@ impl TopBarData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn_with_results(results: &[&str]) -> DisplayMessageData {
+        let mut msg = DisplayMessageData::assistant("");
+        for (i, result) in results.iter().enumerate() {
+            let id = format!("call_{i}");
+            msg.add_tool_call(id.clone(), "read_file".into(), "{}".into());
+            msg.set_tool_result(&id, result.to_string(), false);
+        }
+        msg
+    }
+
+    #[test]
+    fn long_tool_results_start_collapsed() {
+        let long = "line\n".repeat(50);
+        let mut msg = turn_with_results(&["ok", long.as_str()]);
+        msg.apply_tool_display(&ToolDisplayConfig::default());
+        assert!(!msg.tool_calls[0].collapsed);
+        assert!(msg.tool_calls[1].collapsed);
+
+        let expanded = ToolDisplayConfig {
+            collapse_long_results: false,
+            ..Default::default()
+        };
+        msg.apply_tool_display(&expanded);
+        assert!(msg.tool_calls.iter().all(|tc| !tc.collapsed));
+    }
+
+    #[test]
+    fn toggle_tool_calls_expands_then_collapses_all() {
+        let mut msg = turn_with_results(&["a", "b"]);
+        msg.tool_calls[0].collapsed = false;
+        assert_eq!(msg.toggle_tool_calls(), Some(false));
+        assert!(msg.tool_calls.iter().all(|tc| !tc.collapsed));
+        assert_eq!(msg.toggle_tool_calls(), Some(true));
+        assert!(msg.tool_calls.iter().all(|tc| tc.collapsed));
+        assert_eq!(DisplayMessageData::user("hi").toggle_tool_calls(), None);
+    }
+
+    #[test]
+    fn tool_result_size_label() {
+        let msg = turn_with_results(&["tiny", "x".repeat(2048).as_str(), "a\nb\nc"]);
+        assert_eq!(
+            msg.tool_calls[0].result_size_label().as_deref(),
+            Some("4 B")
+        );
+        assert_eq!(
+            msg.tool_calls[1].result_size_label().as_deref(),
+            Some("2.0 KB")
+        );
+        assert_eq!(
+            msg.tool_calls[2].result_size_label().as_deref(),
+            Some("5 B · 3 lines")
+        );
+    }
+}
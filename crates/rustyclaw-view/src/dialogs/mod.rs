@@ -9,6 +9,7 @@
 //! both the desktop and TUI derive the same labels, summaries, and
 //! preview text without duplicating logic.
 
+use rustyclaw_core::tools::ToolRisk;
 use rustyclaw_core::user_prompt_types::{PromptType, UserPrompt};
 
 mod connection;
@@ -29,9 +30,18 @@ pub struct ToolApprovalData {
     /// Tool name (e.g. "write_file", "web_search").
     pub name: String,
 
-    /// Pretty-printed JSON arguments.
+    /// Pretty-printed JSON arguments, credentials masked by the gateway.
     pub arguments: String,
 
+    /// What the tool lets the agent do (e.g. "Create or overwrite files").
+    pub description: String,
+
+    /// What the call could touch, most serious first.
+    pub risks: Vec<ToolRisk>,
+
+    /// Whether the gateway accepts "allow for this session".
+    pub can_remember: bool,
+
     /// Whether "Allow" is currently selected (vs "Deny").
     pub selected_allow: bool,
 }
@@ -49,6 +59,16 @@ impl ToolApprovalData {
     pub fn arguments_preview(&self, max_chars: usize, max_lines: usize) -> String {
         rustyclaw_core::ui::truncate_content(&self.arguments, max_chars, max_lines)
     }
+
+    /// The risks as one hint line, e.g. `"⚠ runs a command · network
+    /// access"`, or `None` for a read-only tool.
+    pub fn risk_line(&self) -> Option<String> {
+        if self.risks.is_empty() {
+            return None;
+        }
+        let labels: Vec<&str> = self.risks.iter().map(ToolRisk::label).collect();
+        Some(format!("⚠ {}", labels.join(" · ")))
+    }
 }
 
 // ── TOTP authentication ─────────────────────────────────────────────────────
@@ -120,7 +120,10 @@ pub use tone::Tone;
 pub use analytics::{AnalyticsPanelData, ModelUsageData, SessionUsageData, UsageTotalsData};
 pub use approvals::{ApprovalsPanelData, PendingApprovalData};
 pub use channels::{ChannelStatusData, ChannelsPanelData};
-pub use command_palette::{CommandPaletteData, PaletteEntry, ShortcutMapping, ZoomState};
+pub use command_palette::{
+    CommandPaletteData, PaletteAction, PaletteEntry, PaletteKind, PaletteParam, ShortcutMapping,
+    ZoomState, build_palette_entries, fuzzy_score,
+};
 pub use cron::{CronJobData, CronPanelData};
 pub use engines::{
     EngineCapsData, EnginesPanelData, LocalEngineData, LocalModelData, PullProgressData,
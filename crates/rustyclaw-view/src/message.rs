@@ -13,6 +13,7 @@
 use std::borrow::Cow;
 
 use chrono::{DateTime, Utc};
+use rustyclaw_core::config::ToolDisplayConfig;
 use rustyclaw_core::types::MessageRole;
 
 use crate::tone::Tone;
@@ -172,10 +173,12 @@ impl MessageBubbleData {
     /// Like [`display_content`](Self::display_content) but with a
     /// custom truncation limit for thinking messages.
     pub fn display_content_truncated(&self, thinking_max_chars: usize) -> Cow<'_, str> {
-        if self.role == MessageRole::Thinking && self.content.len() > thinking_max_chars {
-            format!("{}…", &self.content[..thinking_max_chars]).into()
-        } else {
-            self.content.as_str().into()
+        if self.role != MessageRole::Thinking {
+            return self.content.as_str().into();
+        }
+        match self.content.char_indices().nth(thinking_max_chars) {
+            Some((end, _)) => format!("{}…", &self.content[..end]).into(),
+            None => self.content.as_str().into(),
         }
     }
 
@@ -196,6 +199,14 @@ impl MessageBubbleData {
     ///
     /// Returns a borrow in the common (uncollapsed) case to avoid allocation.
     pub fn content_for_render(&self) -> Cow<'_, str> {
+        if self.role == MessageRole::Thinking {
+            // Collapsed reasoning shows only its first 120 characters.
+            return if self.collapsed {
+                self.display_content()
+            } else {
+                Cow::Borrowed(&self.content)
+            };
+        }
         if self.collapsed && self.is_collapsible() {
             let lines: Vec<&str> = self
                 .content
@@ -315,6 +326,32 @@ impl ToolCallData {
             .as_deref()
             .map(|r| rustyclaw_core::ui::truncate_content(r, max_chars, max_lines))
     }
+
+    /// Compact size of the result for the block header, e.g. `"3.2 KB · 87 lines"`.
+    pub fn result_size_label(&self) -> Option<String> {
+        let result = self.result.as_deref()?;
+        let bytes = result.len();
+        let size = if bytes < 1024 {
+            format!("{bytes} B")
+        } else if bytes < 1024 * 1024 {
+            format!("{:.1} KB", bytes as f64 / 1024.0)
+        } else {
+            format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+        };
+        let lines = result.lines().count();
+        Some(match lines {
+            0 | 1 => size,
+            n => format!("{size} · {n} lines"),
+        })
+    }
+
+    /// Fold or unfold this block according to the configured policy.
+    /// Blocks still waiting for a result are left as they are.
+    pub fn apply_display_policy(&mut self, policy: &ToolDisplayConfig) {
+        if let Some(result) = self.result.as_deref() {
+            self.collapsed = policy.should_collapse(result);
+        }
+    }
 }
 
 impl From<&rustyclaw_core::ui::ToolCallInfo> for ToolCallData {
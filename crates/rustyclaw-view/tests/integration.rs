@@ -351,6 +351,20 @@ fn display_content_passes_other_roles_through() {
     assert_eq!(data.display_content(), "# long markdown content");
 }
 
+#[test]
+fn thinking_expands_to_full_reasoning() {
+    let mut data = MessageBubbleData {
+        role: MessageRole::Thinking,
+        content: "é".repeat(200),
+        collapsed: true,
+        ..Default::default()
+    };
+    // Truncation counts characters, so multi-byte reasoning is safe.
+    assert_eq!(data.content_for_render(), format!("{}…", "é".repeat(120)));
+    data.collapsed = false;
+    assert_eq!(data.content_for_render(), data.content);
+}
+
 // ── SidebarItemData shared display methods ──────────────────────────
 
 #[test]
@@ -402,6 +416,7 @@ fn tool_approval_summary() {
         name: "web_search".into(),
         arguments: r#"{"q":"hello"}"#.into(),
         selected_allow: true,
+        ..Default::default()
     };
     assert_eq!(ta.summary(), "🔧 web_search");
 }
@@ -413,11 +428,27 @@ fn tool_approval_arguments_preview_truncates() {
         name: "test".into(),
         arguments: "a".repeat(500),
         selected_allow: true,
+        ..Default::default()
     };
     let preview = ta.arguments_preview(50, 5);
     assert!(preview.len() <= 55);
 }
 
+#[test]
+fn tool_approval_risk_line() {
+    let mut ta = ToolApprovalData {
+        name: "docker".into(),
+        risks: rustyclaw_core::tools::tool_risks("docker"),
+        ..Default::default()
+    };
+    assert_eq!(
+        ta.risk_line().as_deref(),
+        Some("⚠ runs a command · stops or starts processes · network access")
+    );
+    ta.risks.clear();
+    assert_eq!(ta.risk_line(), None);
+}
+
 // ── AuthDialogData shared display methods ───────────────────────────
 
 #[test]
@@ -25,6 +25,7 @@ fn test_path_validation_blocks_deny_read() {
         deny_exec: vec![],
         allow_paths: vec![],
         workspace: temp_workspace.path().to_path_buf(),
+        env: vec![],
     };
 
     // Try to read the secret file - should be blocked
@@ -61,6 +62,7 @@ fn test_path_validation_allows_workspace() {
         deny_exec: vec![],
         allow_paths: vec![],
         workspace: temp_workspace.path().to_path_buf(),
+        env: vec![],
     };
 
     // Try to read the workspace file - should be allowed
@@ -102,6 +104,7 @@ fn test_path_validation_blocks_deny_exec() {
         deny_exec: vec![temp_scripts.path().to_path_buf()],
         allow_paths: vec![],
         workspace: temp_workspace.path().to_path_buf(),
+        env: vec![],
     };
 
     // Try to execute the script - should be blocked
@@ -159,6 +162,7 @@ fn test_bubblewrap_respects_deny_read() {
         deny_exec: vec![],
         allow_paths: vec![],
         workspace: temp_workspace.path().to_path_buf(),
+        env: vec![],
     };
 
     let (_cmd, args) = wrap_with_bwrap("echo test", &policy);
@@ -193,6 +197,7 @@ fn test_bubblewrap_respects_deny_write() {
         deny_exec: vec![],
         allow_paths: vec![],
         workspace: temp_workspace.path().to_path_buf(),
+        env: vec![],
     };
 
     let (_cmd, args) = wrap_with_bwrap("echo test", &policy);
@@ -228,6 +233,7 @@ fn test_bubblewrap_respects_deny_exec() {
         deny_exec: vec![PathBuf::from("/usr/bin")],
         allow_paths: vec![],
         workspace: temp_workspace.path().to_path_buf(),
+        env: vec![],
     };
 
     let (_cmd, args) = wrap_with_bwrap("echo test", &policy);
@@ -255,6 +261,7 @@ fn test_macos_sandbox_deny_exec() {
         deny_exec: vec![PathBuf::from("/private/scripts")],
         allow_paths: vec![],
         workspace: temp_workspace.path().to_path_buf(),
+        env: vec![],
     };
 
     let (cmd, args) = wrap_with_macos_sandbox("echo test", &policy);
@@ -292,6 +299,7 @@ fn test_fail_closed_behavior() {
         deny_exec: vec![],
         allow_paths: vec![],
         workspace: PathBuf::from("/workspace"),
+        env: vec![],
     };
 
     // Try to validate a protected path
@@ -338,6 +346,7 @@ fn test_command_wrapping_preserves_args() {
         deny_exec: vec![],
         allow_paths: vec![],
         workspace: temp_workspace.path().to_path_buf(),
+        env: vec![],
     };
 
     let test_command = "echo 'hello world' | grep hello";
@@ -371,6 +380,7 @@ fn test_sandbox_performance() {
         deny_exec: vec![],
         allow_paths: vec![],
         workspace: temp_workspace.path().to_path_buf(),
+        env: vec![],
     };
 
     // Measure time to validate a simple command